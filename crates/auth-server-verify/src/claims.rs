@@ -0,0 +1,272 @@
+//! Claims of the tokens issued by the auth server
+//!
+//! These mirror the server's `utils::jwt` types field for field; the helper
+//! methods mirror `utils::auth` (permissions) and `OAuth2Context` (scopes).
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Roles and permissions of a user in one app
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppClaims {
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+}
+
+/// Claims of a user access token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserClaims {
+    /// User ID
+    pub sub: String,
+    /// Roles and permissions per app code
+    pub apps: HashMap<String, AppClaims>,
+    /// Expiration timestamp (Unix timestamp)
+    pub exp: i64,
+    /// Issued at timestamp (Unix timestamp)
+    pub iat: i64,
+    /// Set on refresh tokens only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    /// When the user signed in (Unix timestamp)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_time: Option<i64>,
+    /// App code the token is limited to, for tokens requested with `app=code`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    /// Assurance level of the sign-in: `pwd`, `mfa` or `phr`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acr: Option<String>,
+    /// Authentication methods of the sign-in (RFC 8176), e.g. `["pwd", "otp", "mfa"]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amr: Option<Vec<String>>,
+    /// Digest of the full app claims when `apps` was reduced by the server's
+    /// `JWT_CLAIMS_MODE`; resolve them with `GET /auth/claims`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apps_ref: Option<String>,
+    /// Session the token was issued for, kept across refreshes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+}
+
+impl UserClaims {
+    /// Whether the user has a permission in an app
+    ///
+    /// Like the server's `can`: false for apps missing from the token, so one
+    /// app's permissions never leak into another.
+    pub fn has_permission(&self, app_code: &str, permission: &str) -> bool {
+        self.apps
+            .get(app_code)
+            .is_some_and(|app| app.permissions.iter().any(|p| p == permission))
+    }
+
+    /// Whether the user has any of the permissions in an app
+    pub fn has_any_permission(&self, app_code: &str, permissions: &[&str]) -> bool {
+        permissions.iter().any(|p| self.has_permission(app_code, p))
+    }
+
+    /// Whether the user has all of the permissions in an app
+    pub fn has_all_permissions(&self, app_code: &str, permissions: &[&str]) -> bool {
+        self.apps.contains_key(app_code) && permissions.iter().all(|p| self.has_permission(app_code, p))
+    }
+
+    /// Whether the user has a role in an app
+    pub fn has_role(&self, app_code: &str, role: &str) -> bool {
+        self.apps
+            .get(app_code)
+            .is_some_and(|app| app.roles.iter().any(|r| r == role))
+    }
+
+    /// Whether the server left permissions (or whole apps) out of the token
+    ///
+    /// The helpers above only see what is in the token; resolve the full
+    /// claims with `GET /auth/claims` when this is true.
+    pub fn is_reduced(&self) -> bool {
+        self.apps_ref.is_some()
+    }
+
+    /// When the user signed in, for tokens issued before auth_time was recorded
+    pub fn auth_time(&self) -> i64 {
+        self.auth_time.unwrap_or(self.iat)
+    }
+
+    /// Whether the sign-in reached the assurance level `required` (`pwd`, `mfa` or `phr`)
+    ///
+    /// Like the server's `acr_guard`: tokens without a known `acr` count as
+    /// `pwd`, and an unknown `required` level is no requirement. When this is
+    /// false, answer like the server does (`insufficient_user_authentication`
+    /// with `acr_values`) so the client signs in again with a second factor.
+    pub fn meets_acr(&self, required: &str) -> bool {
+        let Some(required) = acr_rank(required) else {
+            return true;
+        };
+        self.acr.as_deref().and_then(acr_rank).unwrap_or(0) >= required
+    }
+}
+
+/// Position of an assurance level, weakest first
+fn acr_rank(acr: &str) -> Option<usize> {
+    ["pwd", "mfa", "phr"].iter().position(|level| *level == acr)
+}
+
+/// Confirmation (`cnf`) claim binding an app token to its caller (RFC 7800)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenConfirmation {
+    /// Network (CIDR) the caller's IP must belong to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip_range: Option<String>,
+    /// SHA-256 thumbprint of the caller's client certificate (RFC 8705)
+    #[serde(rename = "x5t#S256", default, skip_serializing_if = "Option::is_none")]
+    pub x5t_s256: Option<String>,
+}
+
+/// Claims of an app (machine-to-machine) token
+///
+/// An app token grants every scope of its own app. When `cnf` is present
+/// the caller must also be checked against it, as `app_auth_middleware` does.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppTokenClaims {
+    /// App ID
+    pub sub: String,
+    /// App ID
+    pub app_id: String,
+    /// Always "app"
+    pub token_type: String,
+    /// Expiration timestamp (Unix timestamp)
+    pub exp: i64,
+    /// Issued at timestamp (Unix timestamp)
+    pub iat: i64,
+    /// Caller binding, for apps that opted in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cnf: Option<TokenConfirmation>,
+}
+
+/// Posture of the sign-in (`azp_ctx`), for OAuth clients that opted in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostureClaims {
+    /// `trusted` when the browser was a device trusted after MFA, else `unknown`
+    pub device_trust: String,
+    /// Assurance level of the sign-in: `pwd`, `mfa` or `phr`
+    pub mfa_level: String,
+    /// Network zone of the user's address, `external` outside every zone
+    pub network_zone: String,
+}
+
+/// Claims of an OAuth2 access token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthClaims {
+    /// User ID, or the client ID for client credentials tokens
+    pub sub: String,
+    /// Client ID the token was issued to
+    pub aud: String,
+    /// Granted scopes
+    pub scope: Vec<String>,
+    /// Expiration timestamp (Unix timestamp)
+    pub exp: i64,
+    /// Issued at timestamp (Unix timestamp)
+    pub iat: i64,
+    /// Always "oauth2"
+    pub token_type: String,
+    /// Sign-in posture, for clients that opted in to posture claims
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub azp_ctx: Option<PostureClaims>,
+}
+
+impl OAuthClaims {
+    /// The user the token acts for; `None` for client credentials tokens
+    pub fn user_id(&self) -> Option<&str> {
+        (self.sub != self.aud).then_some(self.sub.as_str())
+    }
+
+    /// The client the token was issued to
+    pub fn client_id(&self) -> &str {
+        &self.aud
+    }
+
+    /// Whether the token was granted a scope (exact match)
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scope.iter().any(|s| s == scope)
+    }
+
+    /// Whether the token was granted all of the scopes
+    pub fn has_all_scopes(&self, scopes: &[&str]) -> bool {
+        scopes.iter().all(|s| self.has_scope(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_claims() -> UserClaims {
+        let mut apps = HashMap::new();
+        apps.insert(
+            "app_a".to_string(),
+            AppClaims {
+                roles: vec!["admin".to_string()],
+                permissions: vec!["read".to_string(), "write".to_string()],
+            },
+        );
+        UserClaims {
+            sub: "user".to_string(),
+            apps,
+            exp: 0,
+            iat: 0,
+            jti: None,
+            auth_time: None,
+            app: None,
+            acr: None,
+            amr: None,
+            apps_ref: None,
+            sid: None,
+        }
+    }
+
+    #[test]
+    fn test_permissions_are_scoped_to_their_app() {
+        let claims = user_claims();
+
+        assert!(claims.has_permission("app_a", "read"));
+        assert!(!claims.has_permission("app_a", "delete"));
+        assert!(!claims.has_permission("app_b", "read"));
+        assert!(claims.has_any_permission("app_a", &["delete", "write"]));
+        assert!(claims.has_all_permissions("app_a", &["read", "write"]));
+        assert!(!claims.has_all_permissions("app_a", &["read", "delete"]));
+        assert!(!claims.has_all_permissions("app_b", &[]));
+        assert!(claims.has_role("app_a", "admin"));
+        assert!(!claims.has_role("app_b", "admin"));
+    }
+
+    #[test]
+    fn test_meets_acr() {
+        let mut claims = user_claims();
+        assert!(!claims.meets_acr("mfa"));
+        assert!(claims.meets_acr("pwd"));
+
+        claims.acr = Some("mfa".to_string());
+        assert!(claims.meets_acr("mfa"));
+        assert!(!claims.meets_acr("phr"));
+        assert!(claims.meets_acr("loa3"));
+    }
+
+    #[test]
+    fn test_oauth_scopes_and_subject() {
+        let user_token = OAuthClaims {
+            sub: "user".to_string(),
+            aud: "client".to_string(),
+            scope: vec!["openid".to_string(), "profile".to_string()],
+            exp: 0,
+            iat: 0,
+            token_type: "oauth2".to_string(),
+            azp_ctx: None,
+        };
+        assert!(user_token.has_scope("profile"));
+        assert!(!user_token.has_scope("prof"));
+        assert!(user_token.has_all_scopes(&["openid", "profile"]));
+        assert_eq!(user_token.user_id(), Some("user"));
+
+        let client_token = OAuthClaims { sub: "client".to_string(), ..user_token };
+        assert_eq!(client_token.user_id(), None);
+        assert_eq!(client_token.client_id(), "client");
+    }
+}
@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+/// Why a token was not accepted
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum VerifyError {
+    /// Signature is valid but `exp` has passed
+    #[error("Token has expired")]
+    Expired,
+
+    /// Malformed, badly signed, or not the expected kind of token
+    #[error("Invalid token")]
+    Invalid,
+
+    /// No key in the JWKS matches the token's `kid`, even after a refresh
+    #[error("No signing key with kid '{0}'")]
+    UnknownKey(String),
+
+    /// The JWKS could not be fetched and no keys are cached
+    #[error("Failed to fetch JWKS: {0}")]
+    Jwks(String),
+}
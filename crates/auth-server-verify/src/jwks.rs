@@ -0,0 +1,220 @@
+//! Cached JSON Web Key Set of the auth server
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use jsonwebtoken::DecodingKey;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+use crate::error::VerifyError;
+
+/// How long keys are cached when the server sends no `max-age`
+const DEFAULT_TTL: Duration = Duration::from_secs(300);
+
+/// Minimum time between two fetches, so tokens with an unknown `kid`
+/// cannot make every request hit the auth server
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Deserialize)]
+struct Jwk {
+    kty: String,
+    kid: String,
+    #[serde(default)]
+    n: String,
+    #[serde(default)]
+    e: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+#[derive(Default)]
+struct CachedKeys {
+    keys: HashMap<String, DecodingKey>,
+    fetched_at: Option<Instant>,
+    ttl: Duration,
+}
+
+impl CachedKeys {
+    fn is_fresh(&self) -> bool {
+        self.fetched_at.is_some_and(|at| at.elapsed() < self.ttl)
+    }
+
+    fn recently_fetched(&self) -> bool {
+        self.fetched_at.is_some_and(|at| at.elapsed() < MIN_REFRESH_INTERVAL)
+    }
+
+    fn candidates(&self, kid: Option<&str>) -> Vec<DecodingKey> {
+        match kid {
+            Some(kid) => self.keys.get(kid).cloned().into_iter().collect(),
+            None => self.keys.values().cloned().collect(),
+        }
+    }
+}
+
+/// The auth server's signing keys, fetched from its JWKS endpoint
+///
+/// Keys are cached for the `max-age` the server sends
+/// (`JWKS_CACHE_MAX_AGE_SECS`). A token signed with a key not in the cache
+/// triggers a refetch, so a newly promoted signing key is picked up
+/// without waiting for the cache to expire. If the server is unreachable,
+/// the last keys fetched keep being used.
+pub struct JwksCache {
+    source: Option<(reqwest::Client, String)>,
+    cache: RwLock<CachedKeys>,
+}
+
+impl JwksCache {
+    /// Keys fetched from a JWKS URL, e.g. `https://auth.example.com/.well-known/jwks.json`
+    pub fn new(jwks_url: impl Into<String>) -> Self {
+        Self {
+            source: Some((reqwest::Client::new(), jwks_url.into())),
+            cache: RwLock::new(CachedKeys::default()),
+        }
+    }
+
+    /// A fixed set of keys by kid, never refreshed (tests, air-gapped setups)
+    pub fn from_keys(keys: impl IntoIterator<Item = (String, DecodingKey)>) -> Self {
+        Self {
+            source: None,
+            cache: RwLock::new(CachedKeys {
+                keys: keys.into_iter().collect(),
+                fetched_at: None,
+                ttl: Duration::ZERO,
+            }),
+        }
+    }
+
+    /// Keys that may have signed a token with this `kid`
+    ///
+    /// Tokens without a `kid` are tried against every key, as on the server.
+    pub async fn keys_for(&self, kid: Option<&str>) -> Result<Vec<DecodingKey>, VerifyError> {
+        {
+            let cache = self.cache.read().await;
+            let candidates = cache.candidates(kid);
+            let stale = self.source.is_some() && !cache.is_fresh();
+            if !candidates.is_empty() && !stale {
+                return Ok(candidates);
+            }
+            if self.source.is_none() || (!stale && cache.recently_fetched()) {
+                return Err(unknown_key(kid));
+            }
+        }
+
+        let mut cache = self.cache.write().await;
+        // Another task may have refreshed while this one waited for the lock
+        if !cache.recently_fetched() {
+            match self.fetch().await {
+                Ok((keys, ttl)) => {
+                    *cache = CachedKeys {
+                        keys,
+                        fetched_at: Some(Instant::now()),
+                        ttl,
+                    };
+                }
+                Err(e) if cache.keys.is_empty() => return Err(e),
+                Err(_) => {}
+            }
+        }
+
+        let candidates = cache.candidates(kid);
+        if candidates.is_empty() {
+            return Err(unknown_key(kid));
+        }
+        Ok(candidates)
+    }
+
+    async fn fetch(&self) -> Result<(HashMap<String, DecodingKey>, Duration), VerifyError> {
+        let (client, url) = self
+            .source
+            .as_ref()
+            .ok_or_else(|| VerifyError::Jwks("no JWKS URL configured".to_string()))?;
+
+        let response = client
+            .get(url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| VerifyError::Jwks(e.to_string()))?;
+
+        let ttl = response
+            .headers()
+            .get(reqwest::header::CACHE_CONTROL)
+            .and_then(|v| v.to_str().ok())
+            .and_then(max_age)
+            .unwrap_or(DEFAULT_TTL);
+
+        let jwks: JwkSet = response
+            .json()
+            .await
+            .map_err(|e| VerifyError::Jwks(e.to_string()))?;
+
+        Ok((decoding_keys(jwks), ttl))
+    }
+}
+
+fn unknown_key(kid: Option<&str>) -> VerifyError {
+    VerifyError::UnknownKey(kid.unwrap_or_default().to_string())
+}
+
+/// RSA keys of a JWKS by kid; other key types are skipped
+fn decoding_keys(jwks: JwkSet) -> HashMap<String, DecodingKey> {
+    jwks.keys
+        .into_iter()
+        .filter(|jwk| jwk.kty == "RSA")
+        .filter_map(|jwk| {
+            DecodingKey::from_rsa_components(&jwk.n, &jwk.e)
+                .ok()
+                .map(|key| (jwk.kid, key))
+        })
+        .collect()
+}
+
+/// `max-age` of a Cache-Control header value
+fn max_age(cache_control: &str) -> Option<Duration> {
+    cache_control
+        .split(',')
+        .filter_map(|directive| directive.trim().strip_prefix("max-age="))
+        .find_map(|secs| secs.trim().parse().ok())
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_age_from_cache_control() {
+        assert_eq!(max_age("public, max-age=600"), Some(Duration::from_secs(600)));
+        assert_eq!(max_age("max-age=0"), Some(Duration::ZERO));
+        assert_eq!(max_age("no-store"), None);
+    }
+
+    #[test]
+    fn test_jwks_keeps_rsa_keys_by_kid() {
+        let jwks: JwkSet = serde_json::from_str(
+            r#"{"keys": [
+                {"kty": "RSA", "use": "sig", "alg": "RS256", "kid": "k1", "n": "sXchDaQebHnPiGvyDOAT4saGEUetSyo9MKLOoWFsueri23bOdgWp4Dy1WlUzewbgBHod5pcM9H95GQRV3JDXboIRROSBigeC5yjU1hGzHHyXss8UDprecbAYxknTcQkhslANGRUZmdTOQ5qTRsLAt6BTYuyvVRdhS8exSZEy_c4gs_7svlJJQ4H9_NxsiIoLwAEk7-Q3UXERGYw_75IDrGA84-lA_-Ct4eTlXHBIY2EaV7t7LjJaynVJCpkv4LKjTTAumiGUIuQhrNhZLuF_RJLqHpM2kgWFLU7-VTdL1VbC2tejvcI2BlMkEpk1BzBZI0KQB0GaDWFLN-aEAw3vRw", "e": "AQAB"},
+                {"kty": "EC", "kid": "k2", "crv": "P-256", "x": "f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU", "y": "x_FEzRu9m36HLN_tue659LNpXW6pCyStikYjKIWI5a0"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let keys = decoding_keys(jwks);
+        assert_eq!(keys.len(), 1);
+        assert!(keys.contains_key("k1"));
+    }
+
+    #[tokio::test]
+    async fn test_static_keys_report_unknown_kid() {
+        let cache = JwksCache::from_keys(Vec::new());
+
+        assert_eq!(
+            cache.keys_for(Some("missing")).await.err(),
+            Some(VerifyError::UnknownKey("missing".to_string()))
+        );
+    }
+}
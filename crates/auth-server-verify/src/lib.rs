@@ -0,0 +1,42 @@
+//! Offline verification of auth-server tokens for resource servers
+//!
+//! Verifies the three kinds of tokens the auth server issues against its
+//! published JWKS (`/.well-known/jwks.json`), without a call to the server
+//! per request:
+//! - user access tokens from `/auth/login` and `/auth/refresh`
+//! - app tokens from `/apps/auth`
+//! - OAuth2 access tokens from `/oauth/token`
+//!
+//! The permission and scope helpers have the same semantics as the server's
+//! own middleware, so every service answers "may this caller do X" the same
+//! way.
+//!
+//! Verification is offline: a token revoked before it expires (logout,
+//! password change, admin revocation) keeps verifying until its `exp`.
+//! Where that matters, ask `POST /oauth/introspect` instead.
+//!
+//! ```rust,ignore
+//! use auth_server_verify::Verifier;
+//!
+//! let verifier = Verifier::new("https://auth.example.com/.well-known/jwks.json");
+//!
+//! let claims = verifier.verify_user_token(token).await?;
+//! if claims.has_permission("billing", "invoices.read") {
+//!     // ...
+//! }
+//!
+//! let oauth = verifier.verify_oauth_token(token).await?;
+//! if oauth.has_scope("profile") {
+//!     // ...
+//! }
+//! ```
+
+mod claims;
+mod error;
+mod jwks;
+mod verifier;
+
+pub use claims::{AppClaims, AppTokenClaims, OAuthClaims, PostureClaims, TokenConfirmation, UserClaims};
+pub use error::VerifyError;
+pub use jwks::JwksCache;
+pub use verifier::{VerifiedToken, Verifier};
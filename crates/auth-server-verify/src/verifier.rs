@@ -0,0 +1,175 @@
+//! Token verification against the auth server's signing keys
+
+use std::sync::Arc;
+
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{decode, decode_header, Algorithm, Validation};
+use serde::de::DeserializeOwned;
+
+use crate::claims::{AppTokenClaims, OAuthClaims, UserClaims};
+use crate::error::VerifyError;
+use crate::jwks::JwksCache;
+
+/// A verified token of any kind
+#[derive(Debug, Clone)]
+pub enum VerifiedToken {
+    User(UserClaims),
+    App(AppTokenClaims),
+    OAuth(OAuthClaims),
+}
+
+/// Verifies auth-server tokens; cheap to clone and share between requests
+#[derive(Clone)]
+pub struct Verifier {
+    jwks: Arc<JwksCache>,
+}
+
+impl Verifier {
+    /// Verifier using the keys published at a JWKS URL
+    pub fn new(jwks_url: impl Into<String>) -> Self {
+        Self::with_jwks(JwksCache::new(jwks_url))
+    }
+
+    /// Verifier using an existing key cache
+    pub fn with_jwks(jwks: JwksCache) -> Self {
+        Self { jwks: Arc::new(jwks) }
+    }
+
+    /// Verify a user access token, as `jwt_auth_middleware` does
+    pub async fn verify_user_token(&self, token: &str) -> Result<UserClaims, VerifyError> {
+        self.decode(token, &Validation::new(Algorithm::RS256)).await
+    }
+
+    /// Verify an app token, as `app_auth_middleware` does (except `cnf`, which
+    /// needs the caller's IP or certificate)
+    pub async fn verify_app_token(&self, token: &str) -> Result<AppTokenClaims, VerifyError> {
+        let claims: AppTokenClaims = self.decode(token, &Validation::new(Algorithm::RS256)).await?;
+        if claims.token_type != "app" {
+            return Err(VerifyError::Invalid);
+        }
+        Ok(claims)
+    }
+
+    /// Verify an OAuth2 access token, as `oauth_auth_middleware` does
+    pub async fn verify_oauth_token(&self, token: &str) -> Result<OAuthClaims, VerifyError> {
+        let claims: OAuthClaims = self.decode(token, &oauth_validation()).await?;
+        if claims.token_type != "oauth2" {
+            return Err(VerifyError::Invalid);
+        }
+        Ok(claims)
+    }
+
+    /// Verify a token of any kind, telling them apart by `token_type`
+    pub async fn verify(&self, token: &str) -> Result<VerifiedToken, VerifyError> {
+        let claims: serde_json::Value = self.decode(token, &oauth_validation()).await?;
+
+        let token_type = claims.get("token_type").and_then(|t| t.as_str()).map(str::to_string);
+        let parsed = match token_type.as_deref() {
+            None if claims.get("aud").is_none() => serde_json::from_value(claims).map(VerifiedToken::User),
+            Some("app") => serde_json::from_value(claims).map(VerifiedToken::App),
+            Some("oauth2") => serde_json::from_value(claims).map(VerifiedToken::OAuth),
+            _ => return Err(VerifyError::Invalid),
+        };
+        parsed.map_err(|_| VerifyError::Invalid)
+    }
+
+    async fn decode<T: DeserializeOwned>(&self, token: &str, validation: &Validation) -> Result<T, VerifyError> {
+        let kid = decode_header(token).map_err(|_| VerifyError::Invalid)?.kid;
+
+        let mut result = Err(VerifyError::Invalid);
+        for key in self.jwks.keys_for(kid.as_deref()).await? {
+            match decode::<T>(token, &key, validation) {
+                Ok(data) => return Ok(data.claims),
+                Err(e) => {
+                    result = Err(match e.kind() {
+                        ErrorKind::ExpiredSignature => VerifyError::Expired,
+                        _ => VerifyError::Invalid,
+                    })
+                }
+            }
+        }
+
+        result
+    }
+}
+
+/// OAuth2 tokens carry the client as `aud`; resource servers check it themselves
+fn oauth_validation() -> Validation {
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.validate_aud = false;
+    validation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, DecodingKey, EncodingKey, Header};
+    use serde_json::json;
+
+    const PRIVATE_KEY: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../../keys/private.pem"));
+    const PUBLIC_KEY: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../../keys/public.pem"));
+
+    fn verifier() -> Verifier {
+        let key = DecodingKey::from_rsa_pem(PUBLIC_KEY.as_bytes()).unwrap();
+        Verifier::with_jwks(JwksCache::from_keys([("test".to_string(), key)]))
+    }
+
+    fn sign(kid: &str, claims: serde_json::Value) -> String {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(kid.to_string());
+        encode(&header, &claims, &EncodingKey::from_rsa_pem(PRIVATE_KEY.as_bytes()).unwrap()).unwrap()
+    }
+
+    fn in_one_hour() -> i64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64
+            + 3600
+    }
+
+    #[tokio::test]
+    async fn test_verifies_user_token_and_checks_permissions() {
+        let token = sign("test", json!({
+            "sub": "user-1",
+            "apps": { "billing": { "roles": ["viewer"], "permissions": ["invoices.read"] } },
+            "exp": in_one_hour(),
+            "iat": 0,
+        }));
+
+        let claims = verifier().verify_user_token(&token).await.unwrap();
+        assert!(claims.has_permission("billing", "invoices.read"));
+        assert!(!claims.has_permission("billing", "invoices.write"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_tokens_of_the_wrong_kind() {
+        let verifier = verifier();
+        let app_token = sign("test", json!({
+            "sub": "app-1", "app_id": "app-1", "token_type": "app", "exp": in_one_hour(), "iat": 0,
+        }));
+        let oauth_token = sign("test", json!({
+            "sub": "user-1", "aud": "client-1", "scope": ["profile"], "token_type": "oauth2",
+            "exp": in_one_hour(), "iat": 0,
+        }));
+
+        assert_eq!(verifier.verify_user_token(&app_token).await.err(), Some(VerifyError::Invalid));
+        assert_eq!(verifier.verify_app_token(&oauth_token).await.err(), Some(VerifyError::Invalid));
+        assert!(verifier.verify_oauth_token(&oauth_token).await.unwrap().has_scope("profile"));
+        assert!(matches!(verifier.verify(&app_token).await, Ok(VerifiedToken::App(_))));
+        assert!(matches!(verifier.verify(&oauth_token).await, Ok(VerifiedToken::OAuth(_))));
+    }
+
+    #[tokio::test]
+    async fn test_reports_expired_and_unknown_keys() {
+        let verifier = verifier();
+        let expired = sign("test", json!({ "sub": "user-1", "apps": {}, "exp": 1, "iat": 0 }));
+        let unknown = sign("other", json!({ "sub": "user-1", "apps": {}, "exp": in_one_hour(), "iat": 0 }));
+
+        assert_eq!(verifier.verify_user_token(&expired).await.err(), Some(VerifyError::Expired));
+        assert_eq!(
+            verifier.verify_user_token(&unknown).await.err(),
+            Some(VerifyError::UnknownKey("other".to_string()))
+        );
+    }
+}
@@ -2,7 +2,7 @@ import { useState } from 'react';
 import { useForm } from 'react-hook-form';
 import { zodResolver } from '@hookform/resolvers/zod';
 import { z } from 'zod';
-import { Link, useNavigate } from 'react-router-dom';
+import { Link, useNavigate, useSearchParams } from 'react-router-dom';
 import { useAuthStore } from '@/stores/authStore';
 import { useWebAuthn } from '@/hooks/useWebAuthn';
 import { AuthServerError } from '@/lib/auth-client';
@@ -27,6 +27,7 @@ type LoginFormValues = z.infer<typeof loginSchema>;
 
 export function LoginForm() {
   const navigate = useNavigate();
+  const [searchParams] = useSearchParams();
   const { login, loginWithPasskey } = useAuthStore();
   const { isSupported: isPasskeySupported, authenticateWithPasskey, isLoading: isPasskeyLoading, error: passkeyError, clearError } = useWebAuthn();
   const [isLoading, setIsLoading] = useState(false);
@@ -34,7 +35,8 @@ export function LoginForm() {
   const form = useForm<LoginFormValues>({
     resolver: zodResolver(loginSchema),
     defaultValues: {
-      email: '',
+      // An OAuth client may pass the account to sign in with as login_hint
+      email: searchParams.get('login_hint') || '',
       password: '',
     },
   });
@@ -3,11 +3,15 @@ import { useSearchParams, useNavigate } from 'react-router-dom';
 import { Card, CardContent, CardDescription, CardFooter, CardHeader, CardTitle } from '@/components/ui/card';
 import { Button } from '@/components/ui/button';
 import { Badge } from '@/components/ui/badge';
+import { Checkbox } from '@/components/ui/checkbox';
 import { useAuthStore } from '@/stores/authStore';
 import { useOAuthClientsStore, type ConsentRequiredResponse } from '@/stores/oauthClientsStore';
 import { toast } from 'sonner';
 import { Shield, CheckCircle2, XCircle, Loader2, AlertTriangle, ExternalLink } from 'lucide-react';
 
+// Scopes the user cannot untick: without them the sign-in itself fails
+const REQUIRED_SCOPES = ['openid'];
+
 // Scope descriptions for display
 const SCOPE_DESCRIPTIONS: Record<string, { name: string; description: string }> = {
   'openid': { name: 'OpenID', description: 'Verify your identity' },
@@ -24,6 +28,7 @@ export function OAuthAuthorizePage() {
   const { initiateAuthorization, submitConsent, isLoading, error } = useOAuthClientsStore();
   
   const [consentData, setConsentData] = useState<ConsentRequiredResponse | null>(null);
+  const [approvedScopes, setApprovedScopes] = useState<string[]>([]);
   const [isSubmitting, setIsSubmitting] = useState(false);
   const [authError, setAuthError] = useState<string | null>(null);
 
@@ -35,12 +40,23 @@ export function OAuthAuthorizePage() {
   const state = searchParams.get('state') || undefined;
   const codeChallenge = searchParams.get('code_challenge') || undefined;
   const codeChallengeMethod = searchParams.get('code_challenge_method') || undefined;
+  const nonce = searchParams.get('nonce') || undefined;
+  const prompt = searchParams.get('prompt') || undefined;
+  const maxAge = searchParams.get('max_age') || undefined;
+  const loginHint = searchParams.get('login_hint') || undefined;
+  const acrValues = searchParams.get('acr_values') || undefined;
 
   useEffect(() => {
+    // Sign in (again), pre-filling the login hint, then come back here
+    const redirectToLogin = (hint?: string) => {
+      const returnUrl = window.location.pathname + window.location.search;
+      const hintParam = hint ? `&login_hint=${encodeURIComponent(hint)}` : '';
+      navigate(`/login?returnUrl=${encodeURIComponent(returnUrl)}${hintParam}`);
+    };
+
     // If not authenticated, redirect to login with return URL
     if (!isAuthenticated) {
-      const returnUrl = window.location.pathname + window.location.search;
-      navigate(`/login?returnUrl=${encodeURIComponent(returnUrl)}`);
+      redirectToLogin(loginHint);
       return;
     }
 
@@ -66,15 +82,29 @@ export function OAuthAuthorizePage() {
           state,
           code_challenge: codeChallenge,
           code_challenge_method: codeChallengeMethod,
+          nonce,
+          prompt,
+          max_age: maxAge,
+          login_hint: loginHint,
+          acr_values: acrValues,
         });
+        if (data.status === 'login_required') {
+          redirectToLogin(data.login_hint);
+          return;
+        }
+        if (data.status === 'success') {
+          window.location.href = data.redirect_url;
+          return;
+        }
         setConsentData(data);
+        setApprovedScopes(data.scopes);
       } catch (err) {
         setAuthError(err instanceof Error ? err.message : 'Failed to initiate authorization');
       }
     };
 
     initAuth();
-  }, [isAuthenticated, clientId, responseType, redirectUri, scope, state, codeChallenge, codeChallengeMethod, navigate, initiateAuthorization]);
+  }, [isAuthenticated, clientId, responseType, redirectUri, scope, state, codeChallenge, codeChallengeMethod, nonce, prompt, maxAge, loginHint, acrValues, navigate, initiateAuthorization]);
 
   const handleConsent = async (approved: boolean) => {
     if (!consentData || !user) return;
@@ -87,9 +117,11 @@ export function OAuthAuthorizePage() {
         user_id: user.id,
         redirect_uri: consentData.redirect_uri,
         scopes: consentData.scopes.join(','),
+        approved_scopes: approved ? approvedScopes.join(',') : undefined,
         state: consentData.state,
         code_challenge: consentData.code_challenge,
         code_challenge_method: consentData.code_challenge_method,
+        nonce: consentData.nonce,
       });
 
       if (redirectUrl) {
@@ -107,6 +139,16 @@ export function OAuthAuthorizePage() {
     }
   };
 
+  // First-party apps, and grants that already cover every requested scope,
+  // skip the consent screen - approve straight away
+  const autoApprove = !!consentData && (consentData.skip_consent || consentData.prompt_consent === false);
+  useEffect(() => {
+    if (autoApprove && user) {
+      handleConsent(true);
+    }
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [consentData, user]);
+
   // Error state
   if (authError || error) {
     return (
@@ -132,7 +174,7 @@ export function OAuthAuthorizePage() {
   }
 
   // Loading state
-  if (isLoading || !consentData) {
+  if (isLoading || !consentData || autoApprove) {
     return (
       <div className="min-h-screen flex items-center justify-center">
         <div className="text-center">
@@ -145,6 +187,12 @@ export function OAuthAuthorizePage() {
 
   const scopes = consentData.scopes;
 
+  const toggleScope = (scopeCode: string, checked: boolean) => {
+    setApprovedScopes((current) =>
+      checked ? [...current, scopeCode] : current.filter((s) => s !== scopeCode)
+    );
+  };
+
   return (
     <div className="min-h-screen flex items-center justify-center p-4 bg-muted/30">
       <Card className="w-full max-w-md">
@@ -166,26 +214,40 @@ export function OAuthAuthorizePage() {
             <span className="font-medium">{user?.email}</span>
           </div>
 
+          {consentData.consent_expired && (
+            <p className="text-xs text-muted-foreground">
+              Your earlier approval for this application has expired. Please review it again.
+            </p>
+          )}
+
           {/* Requested permissions */}
           <div>
-            <h4 className="text-sm font-medium mb-3">This application will be able to:</h4>
+            <h4 className="text-sm font-medium mb-3">Choose what this application will be able to do:</h4>
             <div className="space-y-2">
               {scopes.map((scopeCode) => {
                 const scopeInfo = SCOPE_DESCRIPTIONS[scopeCode] || {
                   name: scopeCode,
                   description: `Access ${scopeCode}`,
                 };
+                const required = REQUIRED_SCOPES.includes(scopeCode);
                 return (
-                  <div
+                  <label
                     key={scopeCode}
-                    className="flex items-start gap-3 p-3 rounded-lg bg-muted/50"
+                    htmlFor={`scope-${scopeCode}`}
+                    className="flex items-start gap-3 p-3 rounded-lg bg-muted/50 cursor-pointer"
                   >
-                    <CheckCircle2 className="h-5 w-5 text-green-500 mt-0.5 flex-shrink-0" />
+                    <Checkbox
+                      id={`scope-${scopeCode}`}
+                      className="mt-0.5"
+                      checked={approvedScopes.includes(scopeCode)}
+                      onCheckedChange={(checked) => toggleScope(scopeCode, checked === true)}
+                      disabled={required || isSubmitting}
+                    />
                     <div>
                       <p className="font-medium text-sm">{scopeInfo.name}</p>
                       <p className="text-xs text-muted-foreground">{scopeInfo.description}</p>
                     </div>
-                  </div>
+                  </label>
                 );
               })}
             </div>
@@ -218,7 +280,7 @@ export function OAuthAuthorizePage() {
           <Button
             className="flex-1"
             onClick={() => handleConsent(true)}
-            disabled={isSubmitting}
+            disabled={isSubmitting || approvedScopes.length === 0}
           >
             {isSubmitting ? (
               <Loader2 className="h-4 w-4 mr-2 animate-spin" />
@@ -78,6 +78,7 @@ export function OAuthClientsPage() {
   const [name, setName] = useState("");
   const [redirectUris, setRedirectUris] = useState("");
   const [isInternal, setIsInternal] = useState(false);
+  const [isNative, setIsNative] = useState(false);
   const [isActive, setIsActive] = useState(true);
   const [isSubmitting, setIsSubmitting] = useState(false);
 
@@ -106,8 +107,8 @@ export function OAuthClientsPage() {
       return;
     }
 
-    // Validate HTTPS for external apps
-    if (!isInternal) {
+    // Validate HTTPS for external web apps (native apps use loopback or custom schemes)
+    if (!isInternal && !isNative) {
       const invalidUris = uris.filter((uri) => !uri.startsWith("http://"));
       if (invalidUris.length > 0) {
         toast.error("External apps require HTTPS redirect URIs");
@@ -120,7 +121,8 @@ export function OAuthClientsPage() {
       const client = await createClient({
         name: name.trim(),
         redirect_uris: uris,
-        is_internal: isInternal,
+        is_internal: isNative ? false : isInternal,
+        client_type: isNative ? "native" : "web",
       });
       setNewClient(client);
       setCreateDialogOpen(false);
@@ -140,6 +142,7 @@ export function OAuthClientsPage() {
     setName("");
     setRedirectUris("");
     setIsInternal(false);
+    setIsNative(false);
     setIsActive(true);
   };
 
@@ -560,6 +563,20 @@ export function OAuthClientsPage() {
                 </p>
               </div>
             </div>
+            <div className="flex items-center space-x-2">
+              <Checkbox
+                id="is_native"
+                checked={isNative}
+                onCheckedChange={(checked) => setIsNative(checked === true)}
+              />
+              <div className="space-y-0.5">
+                <Label htmlFor="is_native">Native Application</Label>
+                <p className="text-xs text-muted-foreground">
+                  Desktop, mobile or CLI apps using http://127.0.0.1 (any port)
+                  or a custom scheme like com.example.app:/callback
+                </p>
+              </div>
+            </div>
           </div>
           <DialogFooter className="flex-col sm:flex-row gap-2">
             <Button
@@ -25,6 +25,11 @@ export interface AuthorizationParams {
   state?: string;
   code_challenge?: string;
   code_challenge_method?: string;
+  nonce?: string;
+  prompt?: string;
+  max_age?: string;
+  login_hint?: string;
+  acr_values?: string;
 }
 
 // Consent response from authorize endpoint
@@ -37,8 +42,32 @@ export interface ConsentRequiredResponse {
   state?: string;
   code_challenge?: string;
   code_challenge_method?: string;
+  nonce?: string;
+  skip_consent?: boolean;
+  /** False when every requested scope is already granted and still valid */
+  prompt_consent?: boolean;
+  /** The user's earlier grant expired and must be given again */
+  consent_expired?: boolean;
+  login_hint?: string;
 }
 
+// The client asked for a fresh (or stronger) sign-in: prompt=login, max_age or acr_values
+export interface LoginRequiredResponse {
+  status: 'login_required';
+  reason: 'prompt_login' | 'max_age' | 'acr';
+  client_id: string;
+  client_name: string;
+  login_hint?: string;
+}
+
+// prompt=none with nothing left to ask: the code was issued straight away
+export interface AuthorizationIssuedResponse {
+  status: 'success';
+  redirect_url: string;
+}
+
+export type AuthorizeResponse = ConsentRequiredResponse | LoginRequiredResponse | AuthorizationIssuedResponse;
+
 interface OAuthClientsState {
   clients: OAuthClientInfo[];
   scopes: PublicScopeInfo[];
@@ -53,16 +82,18 @@ interface OAuthClientsActions {
   deleteClient: (id: string) => Promise<void>;
   regenerateSecret: (id: string) => Promise<string>;
   fetchScopes: () => Promise<void>;
-  initiateAuthorization: (params: AuthorizationParams) => Promise<ConsentRequiredResponse>;
+  initiateAuthorization: (params: AuthorizationParams) => Promise<AuthorizeResponse>;
   submitConsent: (params: {
     approved: boolean;
     client_id: string;
     user_id: string;
     redirect_uri: string;
     scopes: string;
+    approved_scopes?: string;
     state?: string;
     code_challenge?: string;
     code_challenge_method?: string;
+    nonce?: string;
   }) => Promise<string>;
   clearError: () => void;
 }
@@ -175,8 +206,17 @@ export const useOAuthClientsStore = create<OAuthClientsStore>()((set) => ({
       if (params.state) queryParams.set('state', params.state);
       if (params.code_challenge) queryParams.set('code_challenge', params.code_challenge);
       if (params.code_challenge_method) queryParams.set('code_challenge_method', params.code_challenge_method);
+      if (params.nonce) queryParams.set('nonce', params.nonce);
+      if (params.prompt) queryParams.set('prompt', params.prompt);
+      if (params.max_age) queryParams.set('max_age', params.max_age);
+      if (params.login_hint) queryParams.set('login_hint', params.login_hint);
+      if (params.acr_values) queryParams.set('acr_values', params.acr_values);
 
-      const response = await fetch(`${API_URL}/oauth/authorize?${queryParams}`);
+      // The session lets the server check max_age and previously granted scopes
+      const token = authClient.getAccessToken();
+      const response = await fetch(`${API_URL}/oauth/authorize?${queryParams}`, {
+        headers: token ? { 'Authorization': `Bearer ${token}` } : undefined,
+      });
       if (!response.ok) {
         const errorData = await response.json().catch(() => ({}));
         throw new Error(errorData.error_description || errorData.message || 'Authorization failed');
@@ -0,0 +1,21 @@
+-- Migration: User Devices
+-- Registered mobile/desktop devices; refresh-token sessions can be bound to a device
+
+CREATE TABLE user_devices (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    user_id CHAR(36) NOT NULL,
+    platform VARCHAR(20) NOT NULL, -- ios, android, web, desktop
+    device_name VARCHAR(255) NULL,
+    push_token TEXT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    last_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    revoked_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_user_devices_user_id (user_id)
+);
+
+ALTER TABLE user_sessions
+    ADD COLUMN device_id CHAR(36) NULL AFTER user_id,
+    ADD CONSTRAINT fk_user_sessions_device FOREIGN KEY (device_id) REFERENCES user_devices(id) ON DELETE SET NULL,
+    ADD INDEX idx_user_sessions_device_id (device_id);
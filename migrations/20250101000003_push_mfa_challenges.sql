@@ -0,0 +1,23 @@
+-- Migration: Push MFA Challenges
+-- Login approval pushed to a registered device as a second factor
+
+-- Per-device secret used to sign approve/deny responses (returned once at registration)
+ALTER TABLE user_devices
+    ADD COLUMN signing_secret VARCHAR(255) NULL AFTER push_token;
+
+CREATE TABLE push_mfa_challenges (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    user_id CHAR(36) NOT NULL,
+    mfa_token_hash VARCHAR(255) NOT NULL,
+    status ENUM('pending', 'approved', 'denied', 'consumed') NOT NULL DEFAULT 'pending',
+    device_id CHAR(36) NULL, -- device that responded
+    ip_address VARCHAR(45) NULL,
+    user_agent TEXT NULL,
+    responded_at TIMESTAMP NULL,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (device_id) REFERENCES user_devices(id) ON DELETE SET NULL,
+    INDEX idx_push_mfa_mfa_token_hash (mfa_token_hash),
+    INDEX idx_push_mfa_expires_at (expires_at)
+);
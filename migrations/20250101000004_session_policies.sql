@@ -0,0 +1,19 @@
+-- Migration: Per-app session policies
+-- Idle timeout and absolute lifetime overrides (NULL = server default)
+
+ALTER TABLE apps
+    ADD COLUMN session_idle_timeout_secs INT NULL,
+    ADD COLUMN session_absolute_lifetime_secs INT NULL;
+
+ALTER TABLE oauth_clients
+    ADD COLUMN session_idle_timeout_secs INT NULL,
+    ADD COLUMN session_absolute_lifetime_secs INT NULL;
+
+-- App context a session was opened in
+ALTER TABLE user_sessions
+    ADD COLUMN app_id CHAR(36) NULL AFTER device_id,
+    ADD CONSTRAINT fk_user_sessions_app FOREIGN KEY (app_id) REFERENCES apps(id) ON DELETE SET NULL;
+
+-- Start of the grant a rotated OAuth token descends from
+ALTER TABLE oauth_tokens
+    ADD COLUMN session_started_at TIMESTAMP NULL;
@@ -0,0 +1,10 @@
+-- Migration: Authorization code session binding and reuse tracking
+
+-- Hash of the access token of the session that approved the code
+ALTER TABLE oauth_authorization_codes
+    ADD COLUMN session_binding_hash VARCHAR(255) NULL;
+
+-- Code a token chain was issued from, so a replayed code can revoke it
+ALTER TABLE oauth_tokens
+    ADD COLUMN authorization_code_id CHAR(36) NULL,
+    ADD INDEX idx_oauth_tokens_authorization_code_id (authorization_code_id);
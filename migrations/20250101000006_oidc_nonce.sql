@@ -0,0 +1,6 @@
+-- Migration: OpenID Connect nonce on authorization codes
+
+-- Nonce from the authorization request, echoed in the id_token
+ALTER TABLE oauth_authorization_codes
+    ADD COLUMN nonce VARCHAR(255) NULL,
+    ADD INDEX idx_oauth_codes_client_nonce (client_id, nonce);
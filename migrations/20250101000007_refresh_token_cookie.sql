@@ -0,0 +1,6 @@
+-- Migration: Per-client refresh token cookie delivery for browser SPAs
+
+-- When enabled, /oauth/token sets the refresh token in an HttpOnly cookie
+-- instead of returning it in the response body
+ALTER TABLE oauth_clients
+    ADD COLUMN refresh_token_cookie BOOLEAN NOT NULL DEFAULT FALSE;
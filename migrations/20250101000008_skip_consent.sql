@@ -0,0 +1,9 @@
+-- Migration: First-party OAuth clients that skip the consent screen
+
+-- Admin-settable; only honoured for internal clients
+ALTER TABLE oauth_clients
+    ADD COLUMN skip_consent BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Consents recorded automatically for skip_consent clients
+ALTER TABLE user_consents
+    ADD COLUMN is_implicit BOOLEAN NOT NULL DEFAULT FALSE;
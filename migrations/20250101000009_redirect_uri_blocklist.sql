@@ -0,0 +1,12 @@
+-- Migration: Admin blocklist for disallowed OAuth redirect URI patterns
+
+-- Patterns are a host (`evil.example`), a subdomain wildcard (`*.evil.example`)
+-- or a URI prefix (`https://example.com/open-redirect`)
+CREATE TABLE redirect_uri_blocklist (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    pattern VARCHAR(255) UNIQUE NOT NULL,
+    reason VARCHAR(255) NULL,
+    created_by CHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+);
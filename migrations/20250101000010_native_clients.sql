@@ -0,0 +1,6 @@
+-- Migration: Native app OAuth clients (RFC 8252)
+
+-- 'web' (default) or 'native'; native clients may use loopback redirects on
+-- any port and private-use URI schemes, and always require PKCE
+ALTER TABLE oauth_clients
+    ADD COLUMN client_type VARCHAR(20) NOT NULL DEFAULT 'web';
@@ -0,0 +1,6 @@
+-- Migration: Bind app tokens to the caller's IP range or client certificate
+
+-- 'none' (default), 'ip' or 'mtls'
+ALTER TABLE apps
+    ADD COLUMN token_binding VARCHAR(10) NOT NULL DEFAULT 'none',
+    ADD COLUMN token_binding_ip_prefix INT NULL;
@@ -0,0 +1,17 @@
+-- Migration: Normalized match keys for duplicate-account detection
+
+-- Filled in batches by the background duplicate scan; a row is recomputed
+-- whenever the user's updated_at moves past computed_at
+CREATE TABLE user_match_keys (
+    user_id CHAR(36) PRIMARY KEY,
+    normalized_email VARCHAR(255) NOT NULL,
+    normalized_phone VARCHAR(20) NULL,
+    computed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Index for grouping by normalized email
+CREATE INDEX idx_user_match_keys_email ON user_match_keys(normalized_email);
+
+-- Index for grouping by normalized phone
+CREATE INDEX idx_user_match_keys_phone ON user_match_keys(normalized_phone);
@@ -0,0 +1,36 @@
+-- Migration: Canonical email column for case-insensitive, alias-aware uniqueness
+
+-- Canonical form of the email: trimmed, lowercased and with provider rules applied
+ALTER TABLE users ADD COLUMN email_canonical VARCHAR(255) NULL AFTER email;
+
+-- Backfill: case folding for every address
+UPDATE users SET email_canonical = LOWER(TRIM(email));
+
+-- Backfill: default provider rules (gmail ignores dots and +tags, googlemail is an alias)
+UPDATE users
+SET email_canonical = CONCAT(
+    REPLACE(SUBSTRING_INDEX(SUBSTRING_INDEX(email_canonical, '@', 1), '+', 1), '.', ''),
+    '@gmail.com'
+)
+WHERE SUBSTRING_INDEX(email_canonical, '@', -1) IN ('gmail.com', 'googlemail.com');
+
+-- Backfill: default provider rules (+tags ignored)
+UPDATE users
+SET email_canonical = CONCAT(
+    SUBSTRING_INDEX(SUBSTRING_INDEX(email_canonical, '@', 1), '+', 1),
+    '@',
+    SUBSTRING_INDEX(email_canonical, '@', -1)
+)
+WHERE SUBSTRING_INDEX(email_canonical, '@', -1) IN ('outlook.com', 'hotmail.com', 'icloud.com');
+
+-- Existing accounts that collide keep the oldest as owner of the canonical
+-- address; the others are left NULL (still able to log in with their exact
+-- email) and show up in the admin duplicate-account report for merging
+UPDATE users u
+JOIN users older
+    ON older.email_canonical = u.email_canonical
+    AND (older.created_at < u.created_at OR (older.created_at = u.created_at AND older.id < u.id))
+SET u.email_canonical = NULL;
+
+-- Enforce uniqueness on the canonical form
+CREATE UNIQUE INDEX idx_users_email_canonical ON users(email_canonical);
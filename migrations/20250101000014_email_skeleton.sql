@@ -0,0 +1,11 @@
+-- Migration: Homoglyph skeleton of user emails for confusable-duplicate checks
+
+-- Skeleton of the email (NFKC, confusables mapped to ASCII, canonical rules);
+-- not unique, since it is only used to refuse look-alike registrations
+ALTER TABLE users ADD COLUMN email_skeleton VARCHAR(255) NULL AFTER email_canonical;
+
+-- Backfill: existing addresses are ASCII, so the skeleton is the canonical form
+UPDATE users SET email_skeleton = COALESCE(email_canonical, LOWER(TRIM(email)));
+
+-- Index for confusable lookups at registration
+CREATE INDEX idx_users_email_skeleton ON users(email_skeleton);
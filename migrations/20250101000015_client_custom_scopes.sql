@@ -0,0 +1,13 @@
+-- Migration: Per-client custom OAuth scopes
+
+-- Owning client for namespaced custom scopes (NULL = standard scope)
+-- 'private' (owner only), 'pending' (global visibility awaiting admin approval) or 'global'
+ALTER TABLE oauth_scopes
+    ADD COLUMN owner_client_id CHAR(36) NULL,
+    ADD COLUMN visibility VARCHAR(10) NOT NULL DEFAULT 'global',
+    ADD CONSTRAINT fk_oauth_scopes_owner_client
+        FOREIGN KEY (owner_client_id) REFERENCES oauth_clients(id) ON DELETE CASCADE;
+
+-- Listing a client's scopes and the admin approval queue
+CREATE INDEX idx_oauth_scopes_owner_client ON oauth_scopes(owner_client_id);
+CREATE INDEX idx_oauth_scopes_visibility ON oauth_scopes(visibility);
@@ -0,0 +1,33 @@
+-- Migration: Time-bounded role assignments and temporary elevation requests
+
+-- NULL = permanent assignment; expiry_notified_at marks the near-expiry webhook as sent
+ALTER TABLE user_app_roles
+    ADD COLUMN expires_at TIMESTAMP NULL,
+    ADD COLUMN expiry_notified_at TIMESTAMP NULL;
+
+-- The expiry worker scans by expiry time
+CREATE INDEX idx_user_app_roles_expires_at ON user_app_roles(expires_at);
+
+-- Requests from app members for temporary elevated access, decided by the app owner
+CREATE TABLE role_elevation_requests (
+    id CHAR(36) PRIMARY KEY,
+    app_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    role_id CHAR(36) NOT NULL,
+    reason TEXT NULL,
+    duration_secs INT NOT NULL,
+    -- 'pending', 'approved' or 'denied'
+    status VARCHAR(10) NOT NULL DEFAULT 'pending',
+    decided_by CHAR(36) NULL,
+    decided_at TIMESTAMP NULL,
+    -- When the granted role expires (approved requests only)
+    grant_expires_at TIMESTAMP NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (app_id) REFERENCES apps(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (role_id) REFERENCES roles(id) ON DELETE CASCADE,
+    FOREIGN KEY (decided_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- Owners list requests per app and status
+CREATE INDEX idx_role_elevation_requests_app_status ON role_elevation_requests(app_id, status);
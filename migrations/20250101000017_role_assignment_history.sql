@@ -0,0 +1,25 @@
+-- Migration: Role assignment history
+
+-- One row per grant/removal; role_id has no foreign key so history outlives
+-- deleted roles, and role_name keeps the name the role had at the time
+CREATE TABLE role_assignment_history (
+    id CHAR(36) PRIMARY KEY,
+    app_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    role_id CHAR(36) NOT NULL,
+    role_name VARCHAR(100) NULL,
+    -- 'granted', 'removed' or 'expired'
+    action VARCHAR(10) NOT NULL,
+    -- 'user', 'api_key' or 'system'
+    actor_type VARCHAR(10) NOT NULL,
+    actor_id CHAR(36) NULL,
+    -- Expiry of the granted assignment (NULL = permanent)
+    expires_at TIMESTAMP NULL,
+    -- Microsecond precision keeps changes made in the same second in order
+    created_at TIMESTAMP(6) DEFAULT CURRENT_TIMESTAMP(6),
+    FOREIGN KEY (app_id) REFERENCES apps(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- History is read per user within an app, newest first
+CREATE INDEX idx_role_assignment_history_app_user ON role_assignment_history(app_id, user_id, created_at);
@@ -0,0 +1,10 @@
+-- Migration: Indexes for filtered app user listing
+
+-- Status filter and registration date range/sort within an app
+CREATE INDEX idx_user_apps_app_status_created ON user_apps(app_id, status, created_at);
+
+-- Registration date sort within an app without a status filter
+CREATE INDEX idx_user_apps_app_created ON user_apps(app_id, created_at);
+
+-- Role filter: users holding a given role in an app
+CREATE INDEX idx_user_app_roles_app_role ON user_app_roles(app_id, role_id);
@@ -0,0 +1,11 @@
+-- Migration: Leadership of periodic background workers
+
+-- Last instance that won each worker's lock (MySQL GET_LOCK); the lock itself
+-- is authoritative, this table records who holds it for admin visibility
+CREATE TABLE worker_leaders (
+    worker VARCHAR(64) PRIMARY KEY,
+    instance_id VARCHAR(255) NOT NULL,
+    acquired_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- Refreshed on every tick the leader runs
+    heartbeat_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,27 @@
+-- Migration: Inbound provisioning events
+
+-- Events pushed by upstream systems (HR, IdP) and applied asynchronously by
+-- the provisioning worker; (app_id, event_id) makes redelivery idempotent
+CREATE TABLE provisioning_events (
+    id CHAR(36) PRIMARY KEY,
+    app_id CHAR(36) NOT NULL,
+    -- Producer-assigned ID, unique per app
+    event_id VARCHAR(255) NOT NULL,
+    -- 'user.deprovision' or 'role.sync'
+    event_type VARCHAR(64) NOT NULL,
+    payload JSON NOT NULL,
+    -- API key that submitted the event (recorded as the actor of the change)
+    source_api_key_id CHAR(36) NULL,
+    -- 'pending', 'processed' or 'dead_letter'
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    attempts INT NOT NULL DEFAULT 0,
+    last_error TEXT NULL,
+    next_attempt_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    processed_at TIMESTAMP NULL,
+    created_at TIMESTAMP(6) DEFAULT CURRENT_TIMESTAMP(6),
+    FOREIGN KEY (app_id) REFERENCES apps(id) ON DELETE CASCADE,
+    UNIQUE KEY uq_provisioning_events_app_event (app_id, event_id)
+);
+
+-- Worker picks due pending events in arrival order
+CREATE INDEX idx_provisioning_events_due ON provisioning_events(status, next_attempt_at, created_at);
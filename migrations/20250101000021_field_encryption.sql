@@ -0,0 +1,17 @@
+-- Migration: Widen sensitive columns for envelope-encrypted values
+
+-- Encrypted values carry a key ID, a wrapped data key, a nonce and a tag
+-- (`enc:v1:<kid>:<wrapped key>:<ciphertext>`); existing plaintext values
+-- stay readable and are re-encrypted on access or by the rotate endpoint
+ALTER TABLE users MODIFY COLUMN phone VARCHAR(512) NULL;
+
+-- MFA TOTP secrets and SMS numbers
+ALTER TABLE user_mfa_methods
+    MODIFY COLUMN secret_encrypted VARCHAR(512) NULL,
+    MODIFY COLUMN phone_number VARCHAR(512) NULL;
+
+-- Passkey display names
+ALTER TABLE webauthn_credentials MODIFY COLUMN device_name VARCHAR(512) NULL;
+
+-- Webhook signing secrets
+ALTER TABLE webhooks MODIFY COLUMN secret VARCHAR(512) NOT NULL;
@@ -0,0 +1,48 @@
+-- Migration: Abuse telemetry and suggested IP rules
+
+-- Rejected (4xx) responses per caller IP, route template and time window,
+-- flushed from each instance's in-memory counters by the abuse telemetry worker
+CREATE TABLE abuse_telemetry (
+    ip_address VARCHAR(45) NOT NULL,
+    -- Route template, e.g. /users/:user_id, or <unmatched>
+    route VARCHAR(255) NOT NULL,
+    window_start TIMESTAMP NOT NULL,
+    -- 4xx responses other than 429
+    client_errors INT NOT NULL DEFAULT 0,
+    -- 429 responses
+    rate_limited INT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (ip_address, route, window_start)
+);
+
+-- Worker scans recent windows for bursts and purges old ones
+CREATE INDEX idx_abuse_telemetry_window ON abuse_telemetry(window_start);
+
+-- Global deny rules proposed from bursts, waiting for an admin decision;
+-- approving one creates the blacklist entry in ip_rules
+CREATE TABLE ip_rule_suggestions (
+    id CHAR(36) PRIMARY KEY,
+    ip_address VARCHAR(45) NOT NULL,
+    -- Route and window of the largest burst seen while pending
+    route VARCHAR(255) NOT NULL,
+    window_start TIMESTAMP NOT NULL,
+    client_errors INT NOT NULL DEFAULT 0,
+    rate_limited INT NOT NULL DEFAULT 0,
+    reason VARCHAR(500) NOT NULL,
+    -- 'pending', 'approved' or 'rejected'
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    -- Rule created on approval
+    ip_rule_id CHAR(36) NULL,
+    reviewed_by CHAR(36) NULL,
+    reviewed_at TIMESTAMP NULL,
+    last_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (ip_rule_id) REFERENCES ip_rules(id) ON DELETE SET NULL,
+    FOREIGN KEY (reviewed_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- Lookups of the open suggestion or latest rejection for an IP
+CREATE INDEX idx_ip_rule_suggestions_ip ON ip_rule_suggestions(ip_address, status);
+
+-- Admin listing by status, newest first
+CREATE INDEX idx_ip_rule_suggestions_status ON ip_rule_suggestions(status, created_at);
@@ -0,0 +1,24 @@
+-- Migration: Userinfo address/phone claims and custom scope claim mapping
+
+-- Standard OpenID Connect scopes for the phone number and postal address
+INSERT INTO oauth_scopes (id, code, description, is_active) VALUES
+(UUID(), 'phone', 'Access your phone number', true),
+(UUID(), 'address', 'Access your postal address', true)
+ON DUPLICATE KEY UPDATE description = VALUES(description);
+
+-- Userinfo claims released by a client's custom scope (JSON array, NULL = none)
+ALTER TABLE oauth_scopes
+    ADD COLUMN claims JSON NULL;
+
+-- Postal address returned as the OpenID Connect `address` claim
+CREATE TABLE user_addresses (
+    user_id CHAR(36) NOT NULL PRIMARY KEY,
+    formatted VARCHAR(500) NULL,
+    street_address VARCHAR(255) NULL,
+    locality VARCHAR(100) NULL,
+    region VARCHAR(100) NULL,
+    postal_code VARCHAR(20) NULL,
+    country VARCHAR(100) NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    CONSTRAINT fk_user_addresses_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,32 @@
+-- Migration: OAuth client secret expiry and rotation history
+
+-- When the current secret was issued, the admin-set maximum age overriding
+-- CLIENT_SECRET_MAX_AGE_DAYS (NULL = server default, 0 = never expires) and
+-- when the owner was warned about the current secret expiring
+ALTER TABLE oauth_clients
+    ADD COLUMN secret_created_at TIMESTAMP NULL,
+    ADD COLUMN secret_max_age_days INT NULL,
+    ADD COLUMN secret_expiry_warned_at TIMESTAMP NULL;
+
+-- Existing secrets date from client registration
+UPDATE oauth_clients SET secret_created_at = created_at;
+
+-- New clients get their secret at registration
+ALTER TABLE oauth_clients
+    MODIFY COLUMN secret_created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP;
+
+-- One row per secret rotation, kept as evidence for credential-rotation controls
+CREATE TABLE oauth_client_secret_rotations (
+    id CHAR(36) NOT NULL PRIMARY KEY,
+    client_id CHAR(36) NOT NULL,
+    -- User who rotated the secret
+    rotated_by CHAR(36) NULL,
+    -- Age of the replaced secret and whether it had already expired
+    previous_secret_created_at TIMESTAMP NOT NULL,
+    previous_secret_expired BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_secret_rotations_client FOREIGN KEY (client_id) REFERENCES oauth_clients(id) ON DELETE CASCADE
+);
+
+-- Rotation history of a client, newest first
+CREATE INDEX idx_secret_rotations_client ON oauth_client_secret_rotations(client_id, created_at);
@@ -0,0 +1,34 @@
+-- Migration: Token signing keys for key ceremonies
+
+-- RSA keys that sign tokens. A key is imported, staged as 'next' (published
+-- in the JWKS but not signing), promoted to 'active' (signing) and finally
+-- 'retired' (still published and verifying until its tokens have expired).
+-- At most one key is 'next' and one is 'active'.
+CREATE TABLE signing_keys (
+    id CHAR(36) PRIMARY KEY,
+    -- RFC 7638 JWK thumbprint, used as the JWT kid
+    kid VARCHAR(64) NOT NULL,
+    -- SHA-256 (hex) of the DER public key
+    fingerprint CHAR(64) NOT NULL,
+    public_key_pem TEXT NOT NULL,
+    -- Base64url modulus and exponent, published as the JWK
+    jwk_n TEXT NOT NULL,
+    jwk_e VARCHAR(32) NOT NULL,
+    -- PEM private key sealed with field encryption; NULL for the key
+    -- configured in JWT_PRIVATE_KEY, which never leaves the environment
+    private_key TEXT NULL,
+    -- 'config' or 'imported'
+    source VARCHAR(20) NOT NULL,
+    -- 'imported', 'next', 'active' or 'retired'
+    status VARCHAR(20) NOT NULL DEFAULT 'imported',
+    imported_by CHAR(36) NULL,
+    staged_at TIMESTAMP NULL,
+    activated_at TIMESTAMP NULL,
+    retired_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_signing_keys_kid (kid),
+    FOREIGN KEY (imported_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- Every instance loads the published keys on each refresh
+CREATE INDEX idx_signing_keys_status ON signing_keys(status);
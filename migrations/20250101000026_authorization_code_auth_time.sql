@@ -0,0 +1,5 @@
+-- Migration: Authorization code auth_time for OpenID Connect ID tokens
+
+-- When the user who approved the code signed in
+ALTER TABLE oauth_authorization_codes
+    ADD COLUMN auth_time TIMESTAMP NULL;
@@ -0,0 +1,5 @@
+-- Migration: App-scoped user tokens
+
+-- App code requested at login, kept until the MFA step completes
+ALTER TABLE mfa_pending_tokens
+    ADD COLUMN app_scope VARCHAR(50) NULL;
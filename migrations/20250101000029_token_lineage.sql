@@ -0,0 +1,31 @@
+-- Migration: Token lineage
+-- Every refresh token (and a sample of access tokens) is recorded by jti with
+-- the refresh token it was issued from, so a token can be traced from the
+-- original login through its refreshes to its revocation
+
+-- One row per recorded token; family_id is the jti of the refresh token issued
+-- at login and is shared by every token refreshed from it
+CREATE TABLE token_lineage (
+    jti VARCHAR(64) PRIMARY KEY,
+    family_id VARCHAR(64) NOT NULL,
+    -- Refresh token presented to obtain this token (NULL = issued at login)
+    parent_jti VARCHAR(64) NULL,
+    token_type ENUM('access', 'refresh') NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    session_id CHAR(36) NULL,
+    -- App code the token is scoped to
+    app VARCHAR(100) NULL,
+    -- Microsecond precision keeps a login and an immediate refresh in order
+    issued_at TIMESTAMP(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    expires_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP NULL,
+    -- 'rotated', 'logout' or 'session_revoked'
+    revoked_reason VARCHAR(50) NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Lineage is read per family in issue order
+CREATE INDEX idx_token_lineage_family ON token_lineage(family_id, issued_at);
+
+-- Session revocation marks every token of the session
+CREATE INDEX idx_token_lineage_session ON token_lineage(session_id);
@@ -0,0 +1,11 @@
+-- Migration: Client public keys for request objects (JAR, RFC 9101)
+-- Clients that sign their authorization requests register the public keys
+-- the server verifies the `request` parameter with
+
+-- Public JWK Set of a client, one per client
+CREATE TABLE oauth_client_jwks (
+    client_id CHAR(36) PRIMARY KEY,
+    jwks JSON NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (client_id) REFERENCES oauth_clients(id) ON DELETE CASCADE
+);
@@ -0,0 +1,6 @@
+-- Migration: Automatic promotion of generated signing keys
+
+-- Keys generated by the server (source 'generated') are staged right away
+-- and promoted by the signing key worker once their staging delay has
+-- passed, instead of waiting for an admin to promote them
+ALTER TABLE signing_keys ADD COLUMN auto_promote BOOLEAN NOT NULL DEFAULT FALSE;
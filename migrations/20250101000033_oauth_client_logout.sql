@@ -0,0 +1,9 @@
+-- Migration: Logout URIs for OAuth clients (RP-initiated and back-channel logout)
+
+-- Where /oauth/logout may send the user after signing out; exact match,
+-- like redirect_uris
+ALTER TABLE oauth_clients ADD COLUMN post_logout_redirect_uris JSON NULL;
+
+-- Endpoint receiving a logout token when the user signs out
+-- (OpenID Connect Back-Channel Logout 1.0)
+ALTER TABLE oauth_clients ADD COLUMN backchannel_logout_uri VARCHAR(2048) NULL;
@@ -0,0 +1,8 @@
+-- Migration: Webhook circuit breaker
+
+-- Failed deliveries in a row; reset by the next successful delivery
+ALTER TABLE webhooks ADD COLUMN consecutive_failures INT NOT NULL DEFAULT 0;
+
+-- While in the future, the webhook's deliveries are held back instead of
+-- attempted; afterwards a single probe delivery decides whether it closes
+ALTER TABLE webhooks ADD COLUMN circuit_open_until TIMESTAMP NULL;
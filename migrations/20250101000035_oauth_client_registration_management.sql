@@ -0,0 +1,12 @@
+-- Migration: Dynamic client management (RFC 7592)
+
+-- SHA-256 of the registration access token that authorizes reading,
+-- updating and deleting the client at its registration_client_uri
+ALTER TABLE oauth_clients ADD COLUMN registration_access_token_hash VARCHAR(64) NULL;
+
+-- Token lookups go through the hash
+CREATE UNIQUE INDEX idx_oauth_clients_registration_token ON oauth_clients(registration_access_token_hash);
+
+-- Scopes the client may request (JSON array); NULL allows every scope
+-- available to the client
+ALTER TABLE oauth_clients ADD COLUMN allowed_scopes JSON NULL;
@@ -0,0 +1,22 @@
+-- Migration: Webhook event outbox and payload versions
+
+-- Every webhook event raised for an app, whether or not anything was
+-- subscribed at the time, so deliveries can be replayed later
+CREATE TABLE IF NOT EXISTS webhook_events (
+    id CHAR(36) PRIMARY KEY,
+    app_id CHAR(36) NOT NULL,
+    event_type VARCHAR(100) NOT NULL,
+    payload JSON NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_webhook_events_app_created (app_id, created_at),
+    FOREIGN KEY (app_id) REFERENCES apps(id) ON DELETE CASCADE
+);
+
+-- Payload schema a webhook receives; existing webhooks stay on version 1
+ALTER TABLE webhooks ADD COLUMN payload_version INT NOT NULL DEFAULT 1;
+
+-- Outbox event a delivery was rendered from
+ALTER TABLE webhook_deliveries ADD COLUMN event_id CHAR(36) NULL;
+
+-- Deliveries re-enqueued through the replay API
+ALTER TABLE webhook_deliveries ADD COLUMN is_replay BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,19 @@
+-- Migration: Request correlation ids
+
+-- Request that wrote the audit row
+ALTER TABLE audit_logs ADD COLUMN correlation_id VARCHAR(128) NULL;
+
+-- Look up everything one request did
+CREATE INDEX idx_audit_logs_correlation ON audit_logs (correlation_id);
+
+-- Request that wrote the OAuth audit row
+ALTER TABLE oauth_audit_logs ADD COLUMN correlation_id VARCHAR(128) NULL;
+
+-- Look up everything one request did
+CREATE INDEX idx_oauth_audit_logs_correlation ON oauth_audit_logs (correlation_id);
+
+-- Request that raised the webhook event
+ALTER TABLE webhook_events ADD COLUMN correlation_id VARCHAR(128) NULL;
+
+-- Copied from the event; sent to the receiver as X-Correlation-Id
+ALTER TABLE webhook_deliveries ADD COLUMN correlation_id VARCHAR(128) NULL;
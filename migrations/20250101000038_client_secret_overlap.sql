@@ -0,0 +1,18 @@
+-- Migration: Client secret rotation with overlap window
+
+-- Replaced secrets that keep authenticating until expires_at, so a client
+-- can roll out a new secret without downtime
+CREATE TABLE oauth_client_previous_secrets (
+    id CHAR(36) NOT NULL PRIMARY KEY,
+    client_id CHAR(36) NOT NULL,
+    secret_hash VARCHAR(255) NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_previous_secrets_client FOREIGN KEY (client_id) REFERENCES oauth_clients(id) ON DELETE CASCADE
+);
+
+-- Still-valid previous secrets of a client
+CREATE INDEX idx_previous_secrets_client_expires ON oauth_client_previous_secrets (client_id, expires_at);
+
+-- Until when the replaced secret stayed valid (NULL = invalidated immediately)
+ALTER TABLE oauth_client_secret_rotations ADD COLUMN previous_secret_valid_until TIMESTAMP NULL;
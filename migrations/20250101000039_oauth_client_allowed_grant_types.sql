@@ -0,0 +1,5 @@
+-- Migration: Per-client allowed grant types
+
+-- Grants the client may use at the token and authorization endpoints
+-- (NULL = every supported grant)
+ALTER TABLE oauth_clients ADD COLUMN allowed_grant_types JSON NULL;
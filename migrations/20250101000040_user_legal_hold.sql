@@ -0,0 +1,8 @@
+-- Migration: Legal hold on user accounts
+
+-- Held accounts cannot be deleted or anonymized and their audit rows are
+-- kept past retention until the hold is released
+ALTER TABLE users ADD COLUMN legal_hold BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Why the account is held (case or ticket reference)
+ALTER TABLE users ADD COLUMN legal_hold_reason VARCHAR(500) NULL;
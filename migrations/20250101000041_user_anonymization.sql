@@ -0,0 +1,8 @@
+-- Migration: Anonymize-on-delete
+
+-- What deleting a member of this app does: `delete` removes the account,
+-- `anonymize` scrubs its PII and keeps the row for audit references
+ALTER TABLE apps ADD COLUMN deletion_policy VARCHAR(16) NOT NULL DEFAULT 'delete';
+
+-- When the account's PII was scrubbed (NULL = never)
+ALTER TABLE users ADD COLUMN anonymized_at TIMESTAMP NULL;
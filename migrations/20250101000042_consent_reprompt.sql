@@ -0,0 +1,6 @@
+-- Migration: Per-client always-prompt consent
+
+-- Show the consent screen on every authorization, even when the user has
+-- already granted every requested scope
+ALTER TABLE oauth_clients
+    ADD COLUMN always_prompt_consent BOOLEAN NOT NULL DEFAULT FALSE;
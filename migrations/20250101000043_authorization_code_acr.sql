@@ -0,0 +1,5 @@
+-- Migration: Authorization code acr for OpenID Connect ID tokens
+
+-- Authentication context class the approving user signed in with
+ALTER TABLE oauth_authorization_codes
+    ADD COLUMN acr VARCHAR(32) NULL;
@@ -0,0 +1,18 @@
+-- Migration: Shared short-lived challenge store
+-- WebAuthn challenges move from their own table into one key-value table read
+-- through the challenge store, which also keeps pushed authorization requests,
+-- device codes and QR login channels
+
+-- One-time values by kind and key, unreadable once expires_at has passed
+CREATE TABLE challenge_store (
+    kind VARCHAR(32) NOT NULL,
+    challenge_key VARCHAR(255) NOT NULL,
+    value JSON NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (kind, challenge_key),
+    INDEX idx_challenge_store_expires_at (expires_at)
+);
+
+-- Outstanding challenges live at most a few minutes and are not carried over
+DROP TABLE IF EXISTS webauthn_challenges;
@@ -0,0 +1,48 @@
+-- Migration: Admin email broadcasts to user segments
+-- Broadcasts are queued per recipient and sent by the broadcast worker at a
+-- throttled rate; users on the suppression list are never emailed
+
+-- One row per broadcast; status is scheduled, sending, sent or cancelled
+CREATE TABLE email_broadcasts (
+    id CHAR(36) NOT NULL PRIMARY KEY,
+    subject VARCHAR(255) NOT NULL,
+    -- Body template with {{email}}, {{name}} and {{app_name}} placeholders
+    body TEXT NOT NULL,
+    -- Segment filter the recipients are selected with when sending starts
+    segment JSON NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'scheduled',
+    scheduled_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_by CHAR(36) NULL,
+    recipient_count INT NOT NULL DEFAULT 0,
+    sent_count INT NOT NULL DEFAULT 0,
+    failed_count INT NOT NULL DEFAULT 0,
+    suppressed_count INT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    started_at TIMESTAMP NULL,
+    completed_at TIMESTAMP NULL,
+    INDEX idx_email_broadcasts_status_scheduled (status, scheduled_at),
+    CONSTRAINT chk_email_broadcasts_status CHECK (status IN ('scheduled', 'sending', 'sent', 'cancelled'))
+);
+
+-- Email queue of a broadcast; status is pending, sent, failed or suppressed
+CREATE TABLE email_broadcast_recipients (
+    broadcast_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    error VARCHAR(500) NULL,
+    sent_at TIMESTAMP NULL,
+    PRIMARY KEY (broadcast_id, user_id),
+    INDEX idx_email_broadcast_recipients_status (broadcast_id, status),
+    CONSTRAINT fk_broadcast_recipients_broadcast FOREIGN KEY (broadcast_id) REFERENCES email_broadcasts(id) ON DELETE CASCADE,
+    CONSTRAINT fk_broadcast_recipients_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Users who must not receive broadcasts
+CREATE TABLE email_suppressions (
+    user_id CHAR(36) NOT NULL PRIMARY KEY,
+    reason VARCHAR(255) NULL,
+    created_by CHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_email_suppressions_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,7 @@
+-- Migration: Signed userinfo responses
+
+-- JWS algorithm the client wants /oauth/userinfo responses signed with
+-- (OpenID Connect Dynamic Client Registration, userinfo_signed_response_alg);
+-- NULL = plain JSON
+ALTER TABLE oauth_clients
+    ADD COLUMN userinfo_signed_response_alg VARCHAR(16) NULL;
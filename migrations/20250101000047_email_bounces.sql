@@ -0,0 +1,19 @@
+-- Migration: Email bounce and complaint suppression
+-- Hard bounces and spam complaints reported by the email provider's webhooks
+-- stop every email to the address until an admin clears it
+
+-- One row per undeliverable address; kind is hard_bounce or complaint
+CREATE TABLE email_bounces (
+    -- Lowercased address the provider reported
+    email VARCHAR(255) NOT NULL PRIMARY KEY,
+    kind VARCHAR(16) NOT NULL,
+    -- Webhook the event came from (sendgrid, mailgun, ses or generic)
+    provider VARCHAR(32) NOT NULL,
+    -- Provider's diagnostic, e.g. the SMTP response of the bounce
+    detail VARCHAR(500) NULL,
+    event_count INT NOT NULL DEFAULT 1,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_event_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_email_bounces_last_event (last_event_at),
+    CONSTRAINT chk_email_bounces_kind CHECK (kind IN ('hard_bounce', 'complaint'))
+);
@@ -0,0 +1,6 @@
+-- Migration: User locale
+-- Preferred language of the user, released as the OpenID Connect `locale`
+-- claim to clients granted the profile scope
+
+-- BCP 47 language tag, e.g. en-US; NULL when the user never set one
+ALTER TABLE users ADD COLUMN locale VARCHAR(35) NULL;
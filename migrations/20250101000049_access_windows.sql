@@ -0,0 +1,10 @@
+-- Migration: Recurring access windows for role assignments and API keys
+
+-- NULL = usable at any time; otherwise a weekly schedule such as
+-- 'mon-fri 09:00-17:00 +07:00' outside which the role is left out of tokens
+ALTER TABLE user_app_roles
+    ADD COLUMN access_window VARCHAR(255) NULL;
+
+-- Same schedule format; requests with the key outside it are rejected
+ALTER TABLE api_keys
+    ADD COLUMN access_window VARCHAR(255) NULL;
@@ -0,0 +1,30 @@
+-- Migration: Second-admin approval for sensitive admin actions
+-- Actions listed in ADMIN_APPROVAL_ACTIONS are held here until another
+-- system admin approves them, and only then executed
+
+-- One row per requested action; status is pending, approved, rejected or failed
+CREATE TABLE admin_approvals (
+    id CHAR(36) PRIMARY KEY,
+    -- delete_user, grant_system_admin or delete_app
+    action VARCHAR(32) NOT NULL,
+    -- User or app the action applies to
+    target_id CHAR(36) NOT NULL,
+    -- Extra arguments of the action, e.g. {"mode": "anonymize"} for delete_user
+    params JSON NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    requested_by CHAR(36) NOT NULL,
+    decided_by CHAR(36) NULL,
+    decided_at TIMESTAMP NULL,
+    -- Why an approved action could not be executed
+    error VARCHAR(500) NULL,
+    -- Pending requests past this time can no longer be approved
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (requested_by) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (decided_by) REFERENCES users(id) ON DELETE SET NULL,
+    CONSTRAINT chk_admin_approvals_status CHECK (status IN ('pending', 'approved', 'rejected', 'failed'))
+);
+
+-- Admins list requests by status; duplicates are looked up by action and target
+CREATE INDEX idx_admin_approvals_status ON admin_approvals(status, created_at);
+CREATE INDEX idx_admin_approvals_target ON admin_approvals(action, target_id);
@@ -0,0 +1,6 @@
+-- Migration: Per-client access token lifetime
+-- Lets an OAuth client ask for shorter or longer access tokens than the
+-- server default, bounded by OAUTH_MAX_ACCESS_TOKEN_TTL_SECS
+
+-- Access token lifetime override in seconds; NULL uses the server default
+ALTER TABLE oauth_clients ADD COLUMN access_token_ttl_secs INT NULL;
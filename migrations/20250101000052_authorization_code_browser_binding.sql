@@ -0,0 +1,7 @@
+-- Migration: Authorization code browser binding
+-- Ties each code to the browser that approved it, so a code injected into
+-- another browser's callback is rejected at the token endpoint
+
+-- Hash of the approving browser's oauth_browser cookie
+ALTER TABLE oauth_authorization_codes
+    ADD COLUMN browser_binding_hash VARCHAR(255) NULL;
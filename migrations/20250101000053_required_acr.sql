@@ -0,0 +1,9 @@
+-- Migration: Required authentication assurance levels
+-- Apps and OAuth clients may require a minimum acr (pwd, mfa or phr) from
+-- the sign-ins they accept
+
+-- Minimum acr for tokens scoped to the app; NULL accepts any sign-in
+ALTER TABLE apps ADD COLUMN required_acr VARCHAR(16) NULL;
+
+-- Minimum acr for authorization codes issued to the client; NULL accepts any sign-in
+ALTER TABLE oauth_clients ADD COLUMN required_acr VARCHAR(16) NULL;
@@ -0,0 +1,5 @@
+-- Migration: Hosted request objects (JAR request_uri, RFC 9101)
+-- Clients register the URLs the server may fetch signed request objects from
+
+-- JSON array of registered request object URLs; NULL allows none
+ALTER TABLE oauth_clients ADD COLUMN request_uris JSON NULL;
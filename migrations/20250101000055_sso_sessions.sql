@@ -0,0 +1,28 @@
+-- Migration: SSO sessions shared by first-party apps
+-- The hosted login page hands its session to an SSO cookie; apps on sibling
+-- subdomains continue it into app sessions of their own
+
+-- Browser SSO session, identified by the hash of the cookie value
+CREATE TABLE sso_sessions (
+    id CHAR(36) PRIMARY KEY,
+    user_id CHAR(36) NOT NULL,
+    -- Hosted login session the SSO session continues; revoking it ends SSO
+    session_id CHAR(36) NOT NULL,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    -- Strength and time of the sign-in, carried into continued sessions
+    acr VARCHAR(16) NULL,
+    auth_time TIMESTAMP NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (session_id) REFERENCES user_sessions(id) ON DELETE CASCADE
+);
+
+-- SSO session an app session was continued from
+ALTER TABLE user_sessions
+    ADD COLUMN sso_session_id CHAR(36) NULL,
+    ADD INDEX idx_user_sessions_sso (sso_session_id);
+
+-- Apps may refuse SSO continuation and always ask for a sign-in
+ALTER TABLE apps ADD COLUMN sso_enabled BOOLEAN NOT NULL DEFAULT TRUE;
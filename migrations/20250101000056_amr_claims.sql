@@ -0,0 +1,8 @@
+-- Migration: authentication methods (amr) of a sign-in
+-- Carried from the sign-in into authorization codes (for the id_token amr)
+-- and into SSO sessions (for continued app sessions), as a JSON array of
+-- RFC 8176 values
+
+ALTER TABLE oauth_authorization_codes ADD COLUMN amr JSON NULL;
+
+ALTER TABLE sso_sessions ADD COLUMN amr JSON NULL;
@@ -0,0 +1,6 @@
+-- Migration: User metadata
+-- Values of the deployment's extra registration fields (REGISTRATION_FIELDS),
+-- e.g. company or country, keyed by field name
+
+-- JSON object; NULL when the user submitted none
+ALTER TABLE users ADD COLUMN metadata JSON NULL;
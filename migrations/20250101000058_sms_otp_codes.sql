@@ -0,0 +1,23 @@
+-- Migration: SMS one-time passwords for MFA
+-- A code is sent to a phone either to confirm its enrollment as an MFA
+-- method or to complete a pending login; only its hash is stored
+
+CREATE TABLE mfa_sms_codes (
+    id CHAR(36) PRIMARY KEY,
+    user_id CHAR(36) NOT NULL,
+    method_id CHAR(36) NOT NULL,
+    purpose ENUM('enroll', 'login') NOT NULL,
+    code_hash VARCHAR(64) NOT NULL,
+    -- Pending login the code completes (login codes only)
+    mfa_token_hash VARCHAR(64) NULL,
+    -- Wrong guesses; the code stops working after too many
+    attempts INT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP NOT NULL,
+    used_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (method_id) REFERENCES user_mfa_methods(id) ON DELETE CASCADE,
+    INDEX idx_mfa_sms_codes_method (method_id, purpose),
+    INDEX idx_mfa_sms_codes_mfa_token (mfa_token_hash),
+    INDEX idx_mfa_sms_codes_expires_at (expires_at)
+);
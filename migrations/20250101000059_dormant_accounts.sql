@@ -0,0 +1,34 @@
+-- Migration: Dormant account policy
+-- Accounts without a sign-in for DORMANT_ACCOUNT_DAYS are flagged by the
+-- dormant account worker, which may also deactivate them or require the
+-- email address to be verified again on the next login
+
+-- Last sign-in (session activity is also taken into account)
+ALTER TABLE users ADD COLUMN last_activity_at TIMESTAMP NULL;
+
+-- When the account was flagged as dormant; cleared by the next sign-in
+ALTER TABLE users ADD COLUMN dormant_at TIMESTAMP NULL;
+
+-- Password login is refused until the email address is verified again
+ALTER TABLE users ADD COLUMN reverify_required BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Backfill: latest session activity, or the registration time
+UPDATE users u
+SET last_activity_at = GREATEST(
+    u.created_at,
+    COALESCE((SELECT MAX(s.last_active_at) FROM user_sessions s WHERE s.user_id = u.id), u.created_at)
+);
+
+CREATE INDEX idx_users_last_activity_at ON users(last_activity_at);
+CREATE INDEX idx_users_dormant_at ON users(dormant_at);
+
+-- Warnings sent before the dormancy action, one per window per period of
+-- inactivity (a new sign-in starts a new period and re-arms them)
+CREATE TABLE dormant_account_notices (
+    user_id CHAR(36) NOT NULL,
+    activity_at TIMESTAMP NOT NULL,
+    days_before INT NOT NULL,
+    sent_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, activity_at, days_before),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,27 @@
+-- Migration: Weekly app owner digests
+-- Owners who opt in are emailed a summary of the previous week (Monday to
+-- Monday, UTC). The digest worker computes each digest once, queues it here
+-- and sends the queue at a throttled rate
+
+-- Opt-in per app, set by the owner with PUT /apps/{app_id}/digest
+ALTER TABLE apps ADD COLUMN digest_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Digest queue; status is pending, sent, failed or suppressed
+CREATE TABLE app_digests (
+    id CHAR(36) NOT NULL PRIMARY KEY,
+    app_id CHAR(36) NOT NULL,
+    owner_id CHAR(36) NOT NULL,
+    period_start TIMESTAMP NOT NULL,
+    period_end TIMESTAMP NOT NULL,
+    -- Figures for the week, rendered into the email at send time
+    stats JSON NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    error VARCHAR(500) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    sent_at TIMESTAMP NULL,
+    UNIQUE KEY uq_app_digests_period (app_id, period_start),
+    INDEX idx_app_digests_status (status, created_at),
+    CONSTRAINT fk_app_digests_app FOREIGN KEY (app_id) REFERENCES apps(id) ON DELETE CASCADE,
+    CONSTRAINT fk_app_digests_owner FOREIGN KEY (owner_id) REFERENCES users(id) ON DELETE CASCADE,
+    CONSTRAINT chk_app_digests_status CHECK (status IN ('pending', 'sent', 'failed', 'suppressed'))
+);
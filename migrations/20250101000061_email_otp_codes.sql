@@ -0,0 +1,32 @@
+-- Migration: Email one-time codes
+-- A code is emailed to the account address either to complete a pending
+-- MFA login or to sign in to an app without a password; only its hash is
+-- stored
+
+-- Opt-in per app, set by the owner with PUT /apps/{app_id}/email-login
+ALTER TABLE apps ADD COLUMN email_login_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- How the user proved who they are before the MFA step: password or email_code
+ALTER TABLE mfa_pending_tokens
+    ADD COLUMN first_factor VARCHAR(16) NOT NULL DEFAULT 'password';
+
+CREATE TABLE email_otp_codes (
+    id CHAR(36) PRIMARY KEY,
+    user_id CHAR(36) NOT NULL,
+    purpose ENUM('mfa', 'login') NOT NULL,
+    code_hash VARCHAR(64) NOT NULL,
+    -- Pending login the code completes (mfa codes only)
+    mfa_token_hash VARCHAR(64) NULL,
+    -- App the code signs in to (login codes only)
+    app_id CHAR(36) NULL,
+    -- Wrong guesses; the code stops working after too many
+    attempts INT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP NOT NULL,
+    used_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (app_id) REFERENCES apps(id) ON DELETE CASCADE,
+    INDEX idx_email_otp_codes_user (user_id, purpose),
+    INDEX idx_email_otp_codes_mfa_token (mfa_token_hash),
+    INDEX idx_email_otp_codes_expires_at (expires_at)
+);
@@ -0,0 +1,22 @@
+-- Migration: Magic link tokens
+-- Passwordless sign-in to an app by clicking a single-use link emailed to
+-- the account address; only the hash of the link token is stored
+
+CREATE TABLE magic_link_tokens (
+    id CHAR(36) PRIMARY KEY,
+    user_id CHAR(36) NOT NULL,
+    -- App the link signs in to
+    app_id CHAR(36) NOT NULL,
+    token_hash VARCHAR(64) NOT NULL,
+    -- Where the link was requested from
+    ip_address VARCHAR(45) NULL,
+    user_agent TEXT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    used_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (app_id) REFERENCES apps(id) ON DELETE CASCADE,
+    UNIQUE INDEX idx_magic_link_tokens_token_hash (token_hash),
+    INDEX idx_magic_link_tokens_user (user_id, app_id),
+    INDEX idx_magic_link_tokens_expires_at (expires_at)
+);
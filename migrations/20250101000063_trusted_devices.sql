@@ -0,0 +1,20 @@
+-- Migration: Trusted devices
+-- A browser that completed an MFA challenge with "remember this device"
+-- gets an HttpOnly cookie; later sign-ins of the same user presenting it
+-- skip MFA until the device expires. Only the hash of the cookie secret is
+-- stored
+
+CREATE TABLE trusted_devices (
+    id CHAR(36) PRIMARY KEY,
+    user_id CHAR(36) NOT NULL,
+    token_hash VARCHAR(64) NOT NULL,
+    -- Where the device was trusted from, to recognise it in the list
+    ip_address VARCHAR(45) NULL,
+    user_agent TEXT NULL,
+    last_used_at TIMESTAMP NULL,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_trusted_devices_user (user_id),
+    INDEX idx_trusted_devices_expires_at (expires_at)
+);
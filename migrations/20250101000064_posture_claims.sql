@@ -0,0 +1,12 @@
+-- Migration: Posture attributes in OAuth access tokens
+-- Clients that opt in receive the device trust, MFA level and network zone
+-- of the sign-in as the azp_ctx claim of their access tokens
+
+-- Whether access tokens issued to the client carry azp_ctx
+ALTER TABLE oauth_clients ADD COLUMN posture_claims BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Posture taken when the user approved the authorization (JSON object)
+ALTER TABLE oauth_authorization_codes ADD COLUMN posture JSON NULL;
+
+-- Posture of the grant, kept across refresh token rotation
+ALTER TABLE oauth_tokens ADD COLUMN posture JSON NULL;
@@ -0,0 +1,18 @@
+-- Migration: Login history
+-- Located sign-ins of each user, compared with the next one to detect
+-- impossible travel. Only sign-ins whose address was found in the GeoIP
+-- database are kept, and only the most recent ones per user
+
+CREATE TABLE login_history (
+    id CHAR(36) PRIMARY KEY,
+    user_id CHAR(36) NOT NULL,
+    ip_address VARCHAR(45) NOT NULL,
+    country VARCHAR(2) NULL,
+    latitude DOUBLE NOT NULL,
+    longitude DOUBLE NOT NULL,
+    -- Reached faster than IMPOSSIBLE_TRAVEL_MAX_KMH from the previous sign-in
+    impossible_travel BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_login_history_user_created (user_id, created_at)
+);
@@ -0,0 +1,14 @@
+-- Migration: Cooling-off after credential changes
+-- An email change, password reset or MFA reset starts a period during which
+-- high-risk actions of the user (API key creation, consent to new clients,
+-- COOLING_OFF_SCOPES) are refused. A later change extends the period
+
+CREATE TABLE credential_cooling_off (
+    user_id CHAR(36) PRIMARY KEY,
+    -- Latest change: email_changed, password_reset or mfa_reset
+    reason VARCHAR(32) NOT NULL,
+    started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    ends_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_credential_cooling_off_ends_at (ends_at)
+);
@@ -35,6 +35,12 @@ describe('AuthServerClient', () => {
       const ready = await client.ready();
       expect(ready.status).toBe('ready');
     });
+
+    it('should return server capabilities', async () => {
+      const capabilities = await client.capabilities();
+      expect(capabilities.schema_version).toBe(1);
+      expect(capabilities.flows.sign_in).toContain('password');
+    });
   });
 
   describe('Authentication', () => {
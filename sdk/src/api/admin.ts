@@ -12,6 +12,9 @@ import {
   CreateIpRuleRequest,
   IpRuleResponse,
   IpCheckResponse,
+  IpRuleSuggestion,
+  ListIpRuleSuggestionsParams,
+  ApproveIpRuleSuggestionResponse,
   OAuthScope,
   CreateScopeRequest,
   UpdateScopeRequest,
@@ -121,6 +124,21 @@ export class AdminApi extends BaseApi {
     return this.delete(`/admin/ip-rules/${ruleId}`);
   }
 
+  async listIpRuleSuggestions(params?: ListIpRuleSuggestionsParams): Promise<IpRuleSuggestion[]> {
+    return this.get("/admin/ip-rules/suggestions", params);
+  }
+
+  async approveIpRuleSuggestion(
+    suggestionId: string,
+    expiresAt?: string
+  ): Promise<ApproveIpRuleSuggestionResponse> {
+    return this.post(`/admin/ip-rules/suggestions/${suggestionId}/approve`, { expires_at: expiresAt });
+  }
+
+  async rejectIpRuleSuggestion(suggestionId: string): Promise<IpRuleSuggestion> {
+    return this.post(`/admin/ip-rules/suggestions/${suggestionId}/reject`);
+  }
+
   // ============ OAuth Scopes ============
 
   async listScopes(params?: PaginationParams): Promise<ListScopesResponse> {
@@ -6,6 +6,14 @@ import {
   LoginResponse,
   MfaRequiredResponse,
   MfaVerifyRequest,
+  SmsMfaSendRequest,
+  SmsCodeSentResponse,
+  EmailMfaSendRequest,
+  EmailMfaVerifyRequest,
+  EmailCodeSentResponse,
+  EmailLoginSendRequest,
+  EmailLoginSendResponse,
+  EmailLoginRequest,
   RefreshRequest,
   RefreshResponse,
   ForgotPasswordRequest,
@@ -14,6 +22,7 @@ import {
   LogoutRequest,
   SessionsResponse,
   RevokeSessionRequest,
+  TrustedDevicesResponse,
   AuditLogsResponse,
   PaginationParams,
 } from "../types";
@@ -53,6 +62,64 @@ export class AuthApi extends BaseApi {
     return response;
   }
 
+  async sendSmsMfaCode(data: SmsMfaSendRequest): Promise<SmsCodeSentResponse> {
+    return this.request("POST", "/auth/mfa/sms/send", { body: data, auth: false });
+  }
+
+  async sendEmailMfaCode(data: EmailMfaSendRequest): Promise<EmailCodeSentResponse> {
+    return this.request("POST", "/auth/mfa/email/send", { body: data, auth: false });
+  }
+
+  async completeEmailMfaLogin(data: EmailMfaVerifyRequest): Promise<LoginResponse> {
+    const response = await this.request<LoginResponse>(
+      "POST",
+      "/auth/mfa/email/verify",
+      { body: data, auth: false }
+    );
+    this.tokenManager.setTokens(response.access_token, response.refresh_token);
+    return response;
+  }
+
+  async sendEmailLoginCode(data: EmailLoginSendRequest): Promise<EmailLoginSendResponse> {
+    return this.request("POST", "/auth/email-login/send", { body: data, auth: false });
+  }
+
+  async loginWithEmailCode(
+    data: EmailLoginRequest
+  ): Promise<LoginResponse | MfaRequiredResponse> {
+    const response = await this.request<LoginResponse | MfaRequiredResponse>(
+      "POST",
+      "/auth/email-login/verify",
+      { body: data, auth: false }
+    );
+
+    if ("access_token" in response) {
+      this.tokenManager.setTokens(response.access_token, response.refresh_token);
+    }
+
+    return response;
+  }
+
+  async sendMagicLink(data: EmailLoginSendRequest): Promise<EmailLoginSendResponse> {
+    return this.request("POST", "/auth/magic-link", { body: data, auth: false });
+  }
+
+  async loginWithMagicLink(
+    token: string
+  ): Promise<LoginResponse | MfaRequiredResponse> {
+    const response = await this.request<LoginResponse | MfaRequiredResponse>(
+      "GET",
+      `/auth/magic-link/verify?token=${encodeURIComponent(token)}`,
+      { auth: false }
+    );
+
+    if ("access_token" in response) {
+      this.tokenManager.setTokens(response.access_token, response.refresh_token);
+    }
+
+    return response;
+  }
+
   async refresh(data?: RefreshRequest): Promise<RefreshResponse> {
     const token = data?.refresh_token || this.tokenManager.getRefreshToken();
     if (!token) {
@@ -119,6 +186,18 @@ export class AuthApi extends BaseApi {
     return this.delete("/auth/sessions");
   }
 
+  async getTrustedDevices(): Promise<TrustedDevicesResponse> {
+    return this.get("/auth/trusted-devices");
+  }
+
+  async revokeTrustedDevice(deviceId: string): Promise<{ message: string }> {
+    return this.delete(`/auth/trusted-devices/${deviceId}`);
+  }
+
+  async revokeAllTrustedDevices(): Promise<{ message: string }> {
+    return this.delete("/auth/trusted-devices");
+  }
+
   async getAuditLogs(params?: PaginationParams): Promise<AuditLogsResponse> {
     return this.get("/auth/audit-logs", params);
   }
@@ -1,4 +1,4 @@
-import { ApiError } from "../types";
+import { ApiError, ApiErrorDetails } from "../types";
 
 export interface AuthServerConfig {
   baseUrl: string;
@@ -9,7 +9,8 @@ export class AuthServerError extends Error {
   constructor(
     public error: string,
     public statusCode: number,
-    message: string
+    message: string,
+    public details: ApiErrorDetails = {}
   ) {
     super(message);
     this.name = "AuthServerError";
@@ -149,7 +150,8 @@ export class BaseApi {
           }
         }
 
-        throw new AuthServerError(error.error, response.status, error.message);
+        const { error: code, message, status_code: _status, ...details } = error;
+        throw new AuthServerError(code, response.status, message, details);
       }
 
       if (response.status === 204) {
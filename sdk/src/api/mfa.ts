@@ -4,6 +4,10 @@ import {
   TotpVerifyRequest,
   BackupCodesResponse,
   MfaMethodsResponse,
+  SmsSetupRequest,
+  SmsCodeSentResponse,
+  SmsVerifyRequest,
+  EmailMfaSetupResponse,
 } from "../types";
 
 export class MfaApi extends BaseApi {
@@ -19,6 +23,18 @@ export class MfaApi extends BaseApi {
     return this.post("/auth/mfa/totp/verify", data);
   }
 
+  async setupSms(data: SmsSetupRequest): Promise<SmsCodeSentResponse> {
+    return this.post("/auth/mfa/sms/setup", data);
+  }
+
+  async verifySmsSetup(data: SmsVerifyRequest): Promise<BackupCodesResponse> {
+    return this.post("/auth/mfa/sms/verify", data);
+  }
+
+  async setupEmail(): Promise<EmailMfaSetupResponse> {
+    return this.post("/auth/mfa/email/setup");
+  }
+
   async getMethods(): Promise<MfaMethodsResponse> {
     return this.get("/auth/mfa/methods");
   }
@@ -11,6 +11,7 @@ import {
   OAuthApi,
   AdminApi,
 } from "./api";
+import { ServerCapabilities } from "./types";
 
 // Re-export for backward compatibility
 export { AuthServerConfig, AuthServerError };
@@ -119,6 +120,14 @@ export class AuthServerClient implements TokenManager {
     return response.json();
   }
 
+  /**
+   * Version, supported flows, token formats and feature flags of the server
+   */
+  async capabilities(): Promise<ServerCapabilities> {
+    const response = await fetch(`${this.baseUrl}/.well-known/auth-server`);
+    return response.json();
+  }
+
   // ============ Backward Compatibility Methods ============
   // These methods delegate to the appropriate API module for backward compatibility
 
@@ -135,6 +144,9 @@ export class AuthServerClient implements TokenManager {
   getSessions: AuthApi["getSessions"] = (...args) => this.auth.getSessions(...args);
   revokeSession: AuthApi["revokeSession"] = (...args) => this.auth.revokeSession(...args);
   revokeOtherSessions: AuthApi["revokeOtherSessions"] = (...args) => this.auth.revokeOtherSessions(...args);
+  getTrustedDevices: AuthApi["getTrustedDevices"] = (...args) => this.auth.getTrustedDevices(...args);
+  revokeTrustedDevice: AuthApi["revokeTrustedDevice"] = (...args) => this.auth.revokeTrustedDevice(...args);
+  revokeAllTrustedDevices: AuthApi["revokeAllTrustedDevices"] = (...args) => this.auth.revokeAllTrustedDevices(...args);
   getAuditLogs: AuthApi["getAuditLogs"] = (...args) => this.auth.getAuditLogs(...args);
 
   // MFA
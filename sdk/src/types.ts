@@ -1,11 +1,31 @@
 // ============ Common Types ============
 
-export interface ApiError {
+export interface ApiError extends ApiErrorDetails {
   error: string;
   message: string;
   status_code: number;
 }
 
+/** Machine-readable metadata sent with account-state errors */
+export interface ApiErrorDetails {
+  /** Seconds to wait before retrying (account_locked, rate_limit_exceeded) */
+  retry_after?: number;
+  /** account_locked */
+  locked_until?: string;
+  remaining_seconds?: number;
+  /** user_banned; null until the app owner lifts the ban */
+  banned_until?: string | null;
+  ban_reason?: string | null;
+  /** email_not_verified */
+  verification_required?: boolean;
+  /** user_inactive */
+  contact_support?: boolean;
+  /** rate_limit_exceeded */
+  limit?: number;
+  remaining?: number;
+  [key: string]: unknown;
+}
+
 export interface PaginationParams {
   page?: number;
   limit?: number;
@@ -19,6 +39,44 @@ export interface PaginatedResponse<T> {
   total: number;
 }
 
+/** Format of a token; `opaque` tokens must not be parsed */
+export interface TokenFormat {
+  format: 'jwt' | 'opaque' | string;
+  signing_alg_values?: string[];
+}
+
+/**
+ * GET /.well-known/auth-server
+ *
+ * Fields, list values and feature flags are only added within a
+ * `schema_version`; ignore unknown ones and treat a missing flag as disabled.
+ */
+export interface ServerCapabilities {
+  schema_version: number;
+  server: string;
+  version: string;
+  issuer: string;
+  openid_configuration: string;
+  jwks_uri: string;
+  flows: {
+    sign_in: string[];
+    mfa: string[];
+    oauth_grant_types: string[];
+  };
+  token_formats: {
+    access_token: TokenFormat;
+    access_token_claims_mode: 'full' | 'roles_only' | 'compressed' | string;
+    /** First-party refresh tokens from /auth/login and /auth/refresh */
+    refresh_token: TokenFormat;
+    /** Refresh tokens issued by /oauth/token */
+    oauth_refresh_token: TokenFormat;
+    id_token: TokenFormat;
+    api_key: TokenFormat;
+    [key: string]: unknown;
+  };
+  features: Record<string, boolean>;
+}
+
 // ============ Auth Types ============
 
 export interface RegisterRequest {
@@ -78,6 +136,50 @@ export interface VerifyEmailRequest {
 export interface MfaVerifyRequest {
   mfa_token: string;
   code: string;
+  /** The code was texted by `auth.sendSmsMfaCode` */
+  is_sms_code?: boolean;
+  /** Trust this browser so its next sign-ins skip MFA */
+  remember_device?: boolean;
+}
+
+export interface SmsMfaSendRequest {
+  mfa_token: string;
+  /** Phone to text when several are enrolled */
+  method_id?: string;
+}
+
+export interface EmailMfaSendRequest {
+  mfa_token: string;
+}
+
+export interface EmailMfaVerifyRequest {
+  mfa_token: string;
+  code: string;
+  /** Trust this browser so its next sign-ins skip MFA */
+  remember_device?: boolean;
+}
+
+export interface EmailCodeSentResponse {
+  /** e.g. a***@example.com */
+  email_hint: string;
+  expires_in: number;
+}
+
+export interface EmailLoginSendRequest {
+  email: string;
+  /** Code of the app to sign in to */
+  app: string;
+}
+
+export interface EmailLoginSendResponse {
+  message: string;
+  expires_in: number;
+}
+
+export interface EmailLoginRequest {
+  email: string;
+  app: string;
+  code: string;
 }
 
 // ============ User Profile Types ============
@@ -184,6 +286,20 @@ export interface RevokeSessionRequest {
   session_id: string;
 }
 
+export interface TrustedDevice {
+  id: string;
+  ip_address?: string;
+  user_agent?: string;
+  last_used_at?: string;
+  expires_at: string;
+  created_at: string;
+}
+
+export interface TrustedDevicesResponse {
+  trusted_devices: TrustedDevice[];
+  total: number;
+}
+
 export interface TotpSetupResponse {
   method_id: string;
   secret: string;
@@ -195,9 +311,33 @@ export interface TotpVerifyRequest {
   code: string;
 }
 
+export interface SmsSetupRequest {
+  /** International format, e.g. +84912345678 */
+  phone_number: string;
+}
+
+export interface SmsCodeSentResponse {
+  method_id: string;
+  /** e.g. +********678 */
+  phone_hint: string;
+  expires_in: number;
+}
+
+export interface SmsVerifyRequest {
+  method_id: string;
+  code: string;
+}
+
+export interface EmailMfaSetupResponse {
+  message: string;
+  /** Only when the user had no backup codes yet */
+  backup_codes: string[];
+}
+
 export interface MfaMethod {
   id: string;
   method_type: string;
+  phone_hint?: string;
   is_verified: boolean;
   created_at: string;
 }
@@ -393,6 +533,35 @@ export interface IpCheckResponse {
   rule_type?: string;
 }
 
+export type IpRuleSuggestionStatus = "pending" | "approved" | "rejected";
+
+export interface IpRuleSuggestion {
+  id: string;
+  ip_address: string;
+  route: string;
+  window_start: string;
+  client_errors: number;
+  rate_limited: number;
+  reason: string;
+  status: IpRuleSuggestionStatus;
+  ip_rule_id?: string;
+  reviewed_by?: string;
+  reviewed_at?: string;
+  last_seen_at: string;
+  created_at: string;
+}
+
+export interface ListIpRuleSuggestionsParams {
+  status?: IpRuleSuggestionStatus | "all";
+  limit?: number;
+  [key: string]: string | number | boolean | undefined;
+}
+
+export interface ApproveIpRuleSuggestionResponse {
+  suggestion: IpRuleSuggestion;
+  rule: IpRuleResponse;
+}
+
 // ============ WebAuthn/Passkey Types ============
 
 export interface StartRegistrationRequest {
@@ -515,6 +684,7 @@ export interface OAuthClientInfo {
   name: string;
   redirect_uris: string[];
   is_internal: boolean;
+  client_type?: 'web' | 'native';
   is_active: boolean;
   created_at: string;
 }
@@ -527,6 +697,8 @@ export interface CreateOAuthClientRequest {
   name: string;
   redirect_uris: string[];
   is_internal?: boolean;
+  /** `native` for desktop/mobile/CLI apps using loopback or custom-scheme redirects */
+  client_type?: 'web' | 'native';
 }
 
 export interface UpdateOAuthClientRequest {
@@ -563,6 +735,15 @@ export interface OpenIdConfiguration {
   claims_supported: string[];
 }
 
+export interface UserInfoAddress {
+  formatted?: string;
+  street_address?: string;
+  locality?: string;
+  region?: string;
+  postal_code?: string;
+  country?: string;
+}
+
 export interface UserInfo {
   sub: string;
   name?: string;
@@ -582,7 +763,7 @@ export interface UserInfo {
   locale?: string;
   phone_number?: string;
   phone_number_verified?: boolean;
-  address?: unknown;
+  address?: UserInfoAddress;
   updated_at?: number;
   [key: string]: unknown;
 }
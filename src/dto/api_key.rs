@@ -8,6 +8,8 @@ pub struct CreateApiKeyRequest {
     #[serde(default)]
     pub scopes: Vec<String>,
     pub expires_at: Option<DateTime<Utc>>,
+    /// Weekly schedule the key is usable in, e.g. `mon-fri 09:00-17:00 +07:00`
+    pub access_window: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -15,6 +17,8 @@ pub struct UpdateApiKeyRequest {
     pub name: Option<String>,
     pub scopes: Option<Vec<String>>,
     pub is_active: Option<bool>,
+    /// New schedule; an empty string removes the window
+    pub access_window: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -25,6 +29,7 @@ pub struct ApiKeyResponse {
     pub key_prefix: String,
     pub scopes: Vec<String>,
     pub expires_at: Option<DateTime<Utc>>,
+    pub access_window: Option<String>,
     pub last_used_at: Option<DateTime<Utc>>,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
@@ -39,6 +44,7 @@ pub struct ApiKeyWithSecretResponse {
     pub key_prefix: String,
     pub scopes: Vec<String>,
     pub expires_at: Option<DateTime<Utc>>,
+    pub access_window: Option<String>,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
 }
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::models::App;
+
 /// Create app request
 #[derive(Debug, Deserialize)]
 pub struct CreateAppRequest {
@@ -14,6 +16,98 @@ pub struct AppResponse {
     pub id: Uuid,
     pub code: String,
     pub name: String,
+    /// Session idle timeout override in seconds (null = server default)
+    pub session_idle_timeout_secs: Option<i64>,
+    /// Session absolute lifetime override in seconds (null = server default)
+    pub session_absolute_lifetime_secs: Option<i64>,
+    /// How app tokens are bound to the caller: none, ip or mtls
+    pub token_binding: String,
+    /// IPv4 prefix length for ip binding (null = exact address)
+    pub token_binding_ip_prefix: Option<i64>,
+    /// What deleting a member does: delete or anonymize
+    pub deletion_policy: String,
+    /// Minimum acr of sign-ins for the app: pwd, mfa or phr (null = any)
+    pub required_acr: Option<String>,
+    /// Whether a browser's SSO session may sign users in to the app
+    pub sso_enabled: bool,
+    /// Whether the owner is emailed a weekly digest of the app's activity
+    pub digest_enabled: bool,
+    /// Whether users may sign in with a code emailed to them instead of a password
+    pub email_login_enabled: bool,
+}
+
+impl From<App> for AppResponse {
+    fn from(app: App) -> Self {
+        Self {
+            id: app.id,
+            code: app.code,
+            name: app.name,
+            session_idle_timeout_secs: app.session_idle_timeout_secs,
+            session_absolute_lifetime_secs: app.session_absolute_lifetime_secs,
+            token_binding: app.token_binding,
+            token_binding_ip_prefix: app.token_binding_ip_prefix,
+            deletion_policy: app.deletion_policy,
+            required_acr: app.required_acr,
+            sso_enabled: app.sso_enabled,
+            digest_enabled: app.digest_enabled,
+            email_login_enabled: app.email_login_enabled,
+        }
+    }
+}
+
+/// Update session policy request
+///
+/// Omitted or null limits fall back to the server default.
+#[derive(Debug, Deserialize)]
+pub struct UpdateSessionPolicyRequest {
+    /// Maximum seconds between refreshes
+    pub idle_timeout_secs: Option<i64>,
+    /// Maximum seconds since login
+    pub absolute_lifetime_secs: Option<i64>,
+}
+
+/// Update token binding request
+#[derive(Debug, Deserialize)]
+pub struct UpdateTokenBindingRequest {
+    /// `none`, `ip` or `mtls`
+    pub mode: String,
+    /// IPv4 prefix length for `ip` binding (omit to bind to the exact address)
+    pub ip_prefix: Option<i64>,
+}
+
+/// Update deletion policy request
+#[derive(Debug, Deserialize)]
+pub struct UpdateDeletionPolicyRequest {
+    /// `delete` or `anonymize`
+    pub policy: String,
+}
+
+/// Update required acr request
+#[derive(Debug, Deserialize)]
+pub struct UpdateRequiredAcrRequest {
+    /// `pwd`, `mfa` or `phr` (null accepts any sign-in)
+    pub required_acr: Option<String>,
+}
+
+/// Update SSO participation request
+#[derive(Debug, Deserialize)]
+pub struct UpdateSsoRequest {
+    /// Whether sign-ins may continue from the browser's SSO session
+    pub enabled: bool,
+}
+
+/// Update passwordless email sign-in request
+#[derive(Debug, Deserialize)]
+pub struct UpdateEmailLoginRequest {
+    /// Whether users may sign in with a code emailed to them
+    pub enabled: bool,
+}
+
+/// Update weekly digest subscription request
+#[derive(Debug, Deserialize)]
+pub struct UpdateDigestRequest {
+    /// Whether the owner is emailed the weekly digest
+    pub enabled: bool,
 }
 
 /// App authentication request (app_id + secret)
@@ -51,3 +145,46 @@ pub struct RegenerateSecretResponse {
     /// Plain-text secret, returned only once
     pub secret: String,
 }
+
+/// Login options of an app, for frontends deciding which to render
+#[derive(Debug, Serialize)]
+pub struct AppAuthMethodsResponse {
+    pub app_code: String,
+    pub app_name: String,
+    pub password: PasswordAuthMethod,
+    pub passkeys: AuthMethodStatus,
+    pub magic_link: AuthMethodStatus,
+    /// Passwordless sign-in with a code emailed to the user (`POST /auth/email-login/send`)
+    pub email_code: AuthMethodStatus,
+    pub qr_login: AuthMethodStatus,
+    /// Enabled social login providers (empty when none are configured)
+    pub social_providers: Vec<String>,
+    pub mfa: MfaRequirements,
+    /// Minimum acr of sign-ins for the app: pwd, mfa or phr (null = any)
+    pub required_acr: Option<String>,
+    /// Whether the browser's SSO session can sign the user in (`POST /auth/sso/continue`)
+    pub sso: AuthMethodStatus,
+}
+
+/// Whether a login method can be used
+#[derive(Debug, Serialize)]
+pub struct AuthMethodStatus {
+    pub enabled: bool,
+}
+
+/// Email and password login
+#[derive(Debug, Serialize)]
+pub struct PasswordAuthMethod {
+    pub enabled: bool,
+    /// Login is refused until the email address is verified
+    pub requires_verified_email: bool,
+}
+
+/// Second factor requirements
+#[derive(Debug, Serialize)]
+pub struct MfaRequirements {
+    /// Every user must complete a second factor (otherwise only users who enabled MFA)
+    pub required: bool,
+    /// Second factors accepted at `POST /auth/mfa/verify`
+    pub methods: Vec<String>,
+}
@@ -1,12 +1,20 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use crate::models::UserAddress;
+use crate::utils::jwt::AppClaims;
+use crate::utils::registration_fields::RegistrationField;
 
 /// Registration request
 #[derive(Debug, Deserialize)]
 pub struct RegisterRequest {
     pub email: String,
     pub password: String,
+    /// Values of the deployment's extra registration fields (`REGISTRATION_FIELDS`)
+    #[serde(default)]
+    pub fields: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Registration response
@@ -16,6 +24,12 @@ pub struct RegisterResponse {
     pub email: String,
 }
 
+/// Extra registration fields a sign-up form should collect
+#[derive(Debug, Serialize)]
+pub struct RegistrationFieldsResponse {
+    pub fields: Vec<RegistrationField>,
+}
+
 /// Login request
 #[derive(Debug, Deserialize)]
 pub struct LoginRequest {
@@ -23,23 +37,106 @@ pub struct LoginRequest {
     pub password: String,
     /// Optional app_id to check if user is banned from specific app
     pub app_id: Option<uuid::Uuid>,
+    /// Optional app code: the tokens only carry claims for this app
+    #[serde(default)]
+    pub app: Option<String>,
+}
+
+/// Request a passwordless sign-in code by email
+#[derive(Debug, Deserialize)]
+pub struct SendEmailLoginCodeRequest {
+    pub email: String,
+    /// Code of the app to sign in to; it must accept email sign-in
+    pub app: String,
+}
+
+/// Same answer whether or not the address has an account
+#[derive(Debug, Serialize)]
+pub struct EmailLoginCodeResponse {
+    pub message: String,
+    pub expires_in: i64,
+}
+
+/// Sign in with an emailed code
+#[derive(Debug, Deserialize)]
+pub struct EmailLoginRequest {
+    pub email: String,
+    pub app: String,
+    pub code: String,
+}
+
+/// Request a single-use sign-in link by email
+#[derive(Debug, Deserialize)]
+pub struct SendMagicLinkRequest {
+    pub email: String,
+    /// Code of the app to sign in to; it must accept email sign-in
+    pub app: String,
+}
+
+/// Token from the emailed sign-in link
+#[derive(Debug, Deserialize)]
+pub struct MagicLinkVerifyQuery {
+    pub token: String,
 }
 
 /// Login/Refresh response with tokens
 #[derive(Debug, Serialize)]
 pub struct TokenResponse {
     pub access_token: String,
-    pub refresh_token: String,
+    /// Omitted when the refresh token is delivered in an HttpOnly cookie
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
     pub token_type: String,
     pub expires_in: i64,
 }
 
+/// Full app claims of a token, for tokens issued with reduced claims
+#[derive(Debug, Serialize)]
+pub struct ResolvedClaimsResponse {
+    pub sub: String,
+    /// Roles and permissions per app code, as they are now
+    pub apps: HashMap<String, AppClaims>,
+    /// Digest of `apps`
+    pub apps_ref: String,
+    /// Whether `apps` is still what the token was issued with
+    pub matches_token: bool,
+}
+
 /// Refresh token request
 #[derive(Debug, Deserialize)]
 pub struct RefreshRequest {
+    /// Refresh token; when omitted it is read from the refresh cookie
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// Deliver the rotated refresh token in an HttpOnly cookie instead of the body
+    #[serde(default)]
+    pub use_cookie: bool,
+    /// Optional app code to narrow the new tokens to (scoped refresh tokens keep their app)
+    #[serde(default)]
+    pub app: Option<String>,
+}
+
+/// Request to hand a hosted login session to the SSO cookie
+#[derive(Debug, Deserialize)]
+pub struct StartSsoSessionRequest {
+    /// Current refresh token of an unscoped sign-in
     pub refresh_token: String,
 }
 
+/// SSO session established in the SSO cookie
+#[derive(Debug, Serialize)]
+pub struct SsoSessionResponse {
+    /// When the SSO session ends at the latest
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Request to sign in to an app from the SSO cookie
+#[derive(Debug, Deserialize)]
+pub struct ContinueSsoSessionRequest {
+    /// Code of the app to sign in to
+    pub app: String,
+}
+
 /// Forgot password request
 #[derive(Debug, Deserialize)]
 pub struct ForgotPasswordRequest {
@@ -69,8 +166,15 @@ pub struct UserProfileResponse {
     pub name: Option<String>,
     pub avatar_url: Option<String>,
     pub phone: Option<String>,
+    /// Preferred language (BCP 47), released as `locale` with the `profile` scope
+    pub locale: Option<String>,
+    /// Postal address, released to OAuth clients granted the `address` scope
+    pub address: Option<UserAddress>,
     pub is_active: bool,
     pub email_verified: bool,
+    /// Mail to the address hard bounced or was reported as spam, so no email
+    /// is sent to it; the user should change their address
+    pub email_undeliverable: bool,
     pub is_system_admin: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
@@ -82,6 +186,10 @@ pub struct UpdateProfileRequest {
     pub name: Option<String>,
     pub avatar_url: Option<String>,
     pub phone: Option<String>,
+    /// BCP 47 language tag such as `en-US`
+    pub locale: Option<String>,
+    /// Replaces the whole address; an empty object removes it
+    pub address: Option<UserAddress>,
 }
 
 /// Change password request (when logged in)
@@ -102,3 +210,58 @@ pub struct VerifyEmailRequest {
 pub struct ResendVerificationRequest {
     pub email: String,
 }
+
+/// Start QR login request (sent by the device that displays the QR code)
+#[derive(Debug, Deserialize)]
+pub struct QrLoginStartRequest {
+    /// Optional app_id the resulting session is for
+    pub app_id: Option<Uuid>,
+}
+
+/// Start QR login response
+#[derive(Debug, Serialize)]
+pub struct QrLoginStartResponse {
+    /// Value to encode in the QR code
+    pub channel_code: String,
+    /// Secret kept by the displaying device to collect the tokens
+    pub poll_secret: String,
+    pub expires_in: i64,
+    /// Minimum seconds between polls
+    pub interval: i64,
+}
+
+/// Approve QR login request (sent by the already logged-in phone)
+#[derive(Debug, Deserialize)]
+pub struct QrLoginApproveRequest {
+    pub channel_code: String,
+    /// false to deny the login
+    #[serde(default = "default_true")]
+    pub approve: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Approve QR login response
+#[derive(Debug, Serialize)]
+pub struct QrLoginApproveResponse {
+    pub status: String,
+    /// The device that is being logged in
+    pub device_ip_address: Option<String>,
+    pub device_user_agent: Option<String>,
+}
+
+/// Poll QR login request (sent by the device that displays the QR code)
+#[derive(Debug, Deserialize)]
+pub struct QrLoginTokenRequest {
+    pub channel_code: String,
+    pub poll_secret: String,
+}
+
+/// Poll response while the channel is still waiting for approval
+#[derive(Debug, Serialize)]
+pub struct QrLoginPendingResponse {
+    pub status: String,
+    pub interval: i64,
+}
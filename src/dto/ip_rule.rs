@@ -30,3 +30,31 @@ pub struct IpCheckResponse {
     pub allowed: bool,
     pub rule_type: Option<String>,
 }
+
+#[derive(Debug, Serialize)]
+pub struct IpRuleSuggestionResponse {
+    pub id: Uuid,
+    pub ip_address: String,
+    pub route: String,
+    pub window_start: DateTime<Utc>,
+    pub client_errors: i32,
+    pub rate_limited: i32,
+    pub reason: String,
+    pub status: String, // "pending", "approved" or "rejected"
+    pub ip_rule_id: Option<String>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub last_seen_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ApproveIpRuleSuggestionRequest {
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApproveIpRuleSuggestionResponse {
+    pub suggestion: IpRuleSuggestionResponse,
+    pub rule: IpRuleResponse,
+}
@@ -23,13 +23,20 @@ use serde::{Deserialize, Serialize};
 /// # Requirements
 /// - 3.1: Require response_type=code, client_id, redirect_uri, scope, code_challenge
 /// - 3.2: code_challenge required for external apps
-#[derive(Debug, Clone, Deserialize)]
+///
+/// With `request_uri` the other parameters come from a pushed authorization
+/// request (RFC 9126) and only `client_id` is read from the query. With
+/// `request` they come from a signed request object (RFC 9101), which a
+/// non-PAR `request_uri` may also point at.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthorizationRequest {
     /// Must be "code" for Authorization Code Flow
+    #[serde(default)]
     pub response_type: String,
     /// The client's public identifier
     pub client_id: String,
     /// The redirect URI for the callback
+    #[serde(default)]
     pub redirect_uri: String,
     /// Space-separated list of requested scopes
     #[serde(default)]
@@ -41,6 +48,30 @@ pub struct AuthorizationRequest {
     pub code_challenge_method: Option<String>,
     /// Opaque value to maintain state between request and callback
     pub state: Option<String>,
+    /// OpenID Connect nonce, echoed in the id_token
+    #[serde(default)]
+    pub nonce: Option<String>,
+    /// Also issue the scopes the user granted this client before (incremental authorization)
+    #[serde(default)]
+    pub include_granted_scopes: bool,
+    /// Space-separated OpenID Connect prompt values: none, login, consent, select_account
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Maximum seconds since the user last signed in before they must sign in again
+    #[serde(default)]
+    pub max_age: Option<u64>,
+    /// Login identifier (email) to pre-fill on the login screen
+    #[serde(default)]
+    pub login_hint: Option<String>,
+    /// Space-separated authentication context classes, most preferred first
+    #[serde(default)]
+    pub acr_values: Option<String>,
+    /// Reference to parameters pushed to POST /oauth/par, or URL of a hosted request object
+    #[serde(default, skip_serializing)]
+    pub request_uri: Option<String>,
+    /// Signed request object carrying the parameters (JAR)
+    #[serde(default, skip_serializing)]
+    pub request: Option<String>,
 }
 
 fn default_code_challenge_method() -> Option<String> {
@@ -55,6 +86,63 @@ impl AuthorizationRequest {
             .map(|s| s.split_whitespace().map(String::from).collect())
             .unwrap_or_default()
     }
+
+    /// Parse the requested authentication context classes
+    pub fn acr_values(&self) -> Vec<String> {
+        self.acr_values
+            .as_ref()
+            .map(|s| s.split_whitespace().map(String::from).collect())
+            .unwrap_or_default()
+    }
+
+    /// Parse the prompt parameter
+    ///
+    /// `none` cannot be combined with any other value.
+    pub fn prompt(&self) -> Result<Prompt, String> {
+        let mut prompt = Prompt::default();
+        for value in self.prompt.as_deref().unwrap_or("").split_whitespace() {
+            match value {
+                "none" => prompt.none = true,
+                "login" => prompt.login = true,
+                "consent" => prompt.consent = true,
+                "select_account" => prompt.select_account = true,
+                other => return Err(format!("Unsupported prompt value '{}'", other)),
+            }
+        }
+        if prompt.none && (prompt.login || prompt.consent || prompt.select_account) {
+            return Err("prompt=none cannot be combined with other values".to_string());
+        }
+        Ok(prompt)
+    }
+
+    /// The request with the verified claims of its request object applied
+    pub fn with_request_object(
+        &self,
+        claims: serde_json::Map<String, serde_json::Value>,
+        strict: bool,
+    ) -> Result<Self, String> {
+        let query = match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(query)) => query,
+            _ => return Err("request parameters could not be read".to_string()),
+        };
+        let merged = crate::utils::request_object::merge_request_parameters(&query, claims, strict);
+
+        serde_json::from_value(serde_json::Value::Object(merged))
+            .map_err(|e| format!("request object parameters are invalid: {}", e))
+    }
+}
+
+/// OpenID Connect prompt values of an authorization request
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Prompt {
+    /// Fail instead of showing any login or consent screen
+    pub none: bool,
+    /// Make the user sign in again even with a valid session
+    pub login: bool,
+    /// Show the consent screen even when every scope is already granted
+    pub consent: bool,
+    /// Let the user pick the account; handled like `login`
+    pub select_account: bool,
 }
 
 /// Authorization Response - redirect with code
@@ -79,6 +167,7 @@ pub struct AuthorizationResponse {
 /// - authorization_code: Exchange code for tokens
 /// - client_credentials: Machine-to-machine authentication
 /// - refresh_token: Refresh access token
+/// - urn:ietf:params:oauth:grant-type:device_code: Poll for a device authorization
 ///
 /// # Requirements
 /// - 5.1: Exchange authorization code for tokens
@@ -100,6 +189,8 @@ pub struct TokenRequest {
     pub code_verifier: Option<String>,
     /// Refresh token (for refresh_token grant)
     pub refresh_token: Option<String>,
+    /// Device code (for device_code grant)
+    pub device_code: Option<String>,
     /// Requested scopes (for client_credentials grant)
     pub scope: Option<String>,
 }
@@ -135,6 +226,9 @@ pub struct OAuthTokenResponseDto {
     pub expires_in: i64,
     /// Space-separated list of granted scopes
     pub scope: String,
+    /// OpenID Connect ID token (only when the openid scope was granted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
 }
 
 impl OAuthTokenResponseDto {
@@ -151,6 +245,7 @@ impl OAuthTokenResponseDto {
             token_type: "Bearer".to_string(),
             expires_in,
             scope: scopes.join(" "),
+            id_token: None,
         }
     }
 }
@@ -164,6 +259,7 @@ impl From<crate::services::OAuthTokenResponse> for OAuthTokenResponseDto {
             token_type: response.token_type,
             expires_in: response.expires_in,
             scope: response.scope,
+            id_token: response.id_token,
         }
     }
 }
@@ -189,6 +285,147 @@ pub struct RevokeRequest {
     pub client_secret: Option<String>,
 }
 
+// ============================================================================
+// Device Authorization DTOs (RFC 8628)
+// ============================================================================
+
+/// Device Authorization Request - POST /oauth/device_authorization
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceAuthorizationRequest {
+    /// Client ID (may instead be sent with HTTP Basic authentication)
+    #[serde(default)]
+    pub client_id: Option<String>,
+    /// Client secret (for confidential clients)
+    pub client_secret: Option<String>,
+    /// Requested scopes (space-separated)
+    pub scope: Option<String>,
+}
+
+impl DeviceAuthorizationRequest {
+    /// Parse scopes from space-separated string to Vec
+    pub fn scopes(&self) -> Vec<String> {
+        self.scope
+            .as_ref()
+            .map(|s| s.split_whitespace().map(String::from).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Device Verification Query - GET /oauth/device
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceVerificationQuery {
+    /// The code shown on the device, if the user followed verification_uri_complete
+    pub user_code: Option<String>,
+}
+
+/// End Session Request - GET /oauth/logout (OpenID Connect RP-Initiated Logout 1.0)
+#[derive(Debug, Clone, Deserialize)]
+pub struct EndSessionRequest {
+    /// ID token previously issued to the client; identifies the user and client
+    pub id_token_hint: Option<String>,
+    /// Where to send the user afterwards; must be registered by the client
+    pub post_logout_redirect_uri: Option<String>,
+    /// Client requesting the logout, when no id_token_hint is given
+    pub client_id: Option<String>,
+    /// Opaque value passed back with the post-logout redirect
+    pub state: Option<String>,
+}
+
+/// Device Decision - POST /oauth/device/verify
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceDecisionRequest {
+    /// The code shown on the device
+    pub user_code: String,
+    /// Whether the user approved the device
+    pub approved: bool,
+}
+
+// ============================================================================
+// Introspection Request DTOs (RFC 7662)
+// ============================================================================
+
+/// Introspection Request - POST /oauth/introspect
+#[derive(Debug, Clone, Deserialize)]
+pub struct IntrospectRequest {
+    /// The token to introspect (access_token or refresh_token)
+    pub token: String,
+    /// Optional hint about the token type
+    #[serde(default)]
+    pub token_type_hint: Option<String>,
+    /// Client ID of the resource server
+    pub client_id: Option<String>,
+    /// Client secret of the resource server
+    pub client_secret: Option<String>,
+}
+
+// ============================================================================
+// Pushed Authorization Request DTOs (RFC 9126)
+// ============================================================================
+
+/// Pushed Authorization Request - POST /oauth/par
+///
+/// The authorization request parameters plus client authentication. The
+/// parameters may instead be sent as a signed request object in `request`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushedAuthorizationRequest {
+    #[serde(default)]
+    pub response_type: String,
+    /// Client ID (may instead be sent with HTTP Basic authentication)
+    #[serde(default)]
+    pub client_id: Option<String>,
+    /// Client secret (required for confidential clients)
+    pub client_secret: Option<String>,
+    #[serde(default)]
+    pub redirect_uri: String,
+    #[serde(default)]
+    pub scope: Option<String>,
+    pub code_challenge: Option<String>,
+    #[serde(default = "default_code_challenge_method")]
+    pub code_challenge_method: Option<String>,
+    pub state: Option<String>,
+    #[serde(default)]
+    pub nonce: Option<String>,
+    #[serde(default)]
+    pub include_granted_scopes: bool,
+    #[serde(default)]
+    pub prompt: Option<String>,
+    #[serde(default)]
+    pub max_age: Option<u64>,
+    #[serde(default)]
+    pub login_hint: Option<String>,
+    #[serde(default)]
+    pub acr_values: Option<String>,
+    /// Not allowed in a pushed request (RFC 9126 Section 2.1)
+    #[serde(default)]
+    pub request_uri: Option<String>,
+    /// Signed request object carrying the parameters (JAR)
+    #[serde(default)]
+    pub request: Option<String>,
+}
+
+impl PushedAuthorizationRequest {
+    /// The pushed parameters as an authorization request
+    pub fn authorization_request(&self) -> AuthorizationRequest {
+        AuthorizationRequest {
+            response_type: self.response_type.clone(),
+            client_id: self.client_id.clone().unwrap_or_default(),
+            redirect_uri: self.redirect_uri.clone(),
+            scope: self.scope.clone(),
+            code_challenge: self.code_challenge.clone(),
+            code_challenge_method: self.code_challenge_method.clone(),
+            state: self.state.clone(),
+            nonce: self.nonce.clone(),
+            include_granted_scopes: self.include_granted_scopes,
+            prompt: self.prompt.clone(),
+            max_age: self.max_age,
+            login_hint: self.login_hint.clone(),
+            acr_values: self.acr_values.clone(),
+            request_uri: self.request_uri.clone(),
+            request: self.request.clone(),
+        }
+    }
+}
+
 // ============================================================================
 // UserInfo Response DTOs (Requirement 11.4)
 // ============================================================================
@@ -206,9 +443,24 @@ pub struct UserInfoResponse {
     /// Whether email is verified (requires email scope)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub email_verified: Option<bool>,
-    /// User's name (requires profile scope)
+    /// User's name (requires profile scope; omitted when the user has none)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    /// Avatar URL (requires profile scope)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub picture: Option<String>,
+    /// Preferred language as a BCP 47 tag (requires profile scope)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// When the profile was last changed, in seconds since the epoch (requires profile scope)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+    /// Phone number (requires phone scope)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone_number: Option<String>,
+    /// Postal address (requires address scope)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<crate::models::UserAddress>,
 }
 
 // ============================================================================
@@ -228,6 +480,18 @@ pub struct OpenIdConfiguration {
     pub userinfo_endpoint: String,
     /// URL of the authorization server's revocation endpoint
     pub revocation_endpoint: String,
+    /// URL of the authorization server's introspection endpoint
+    pub introspection_endpoint: String,
+    /// URL of the authorization server's device authorization endpoint
+    pub device_authorization_endpoint: String,
+    /// URL of the authorization server's pushed authorization request endpoint
+    pub pushed_authorization_request_endpoint: String,
+    /// URL the client sends the user to for logout (RP-Initiated Logout)
+    pub end_session_endpoint: String,
+    /// Whether clients can register a back-channel logout URI
+    pub backchannel_logout_supported: bool,
+    /// Whether logout tokens carry a `sid` claim
+    pub backchannel_logout_session_supported: bool,
     /// URL of the authorization server's issuer identifier
     pub issuer: String,
     /// JSON array of supported response types
@@ -240,6 +504,28 @@ pub struct OpenIdConfiguration {
     pub token_endpoint_auth_methods_supported: Vec<String>,
     /// JSON array of supported code challenge methods
     pub code_challenge_methods_supported: Vec<String>,
+    /// URL of the JWK Set with the token signing keys
+    pub jwks_uri: String,
+    /// JSON array of supported ID token signing algorithms
+    pub id_token_signing_alg_values_supported: Vec<String>,
+    /// JSON array of algorithms userinfo responses can be signed with
+    pub userinfo_signing_alg_values_supported: Vec<String>,
+    /// JSON array of supported subject identifier types
+    pub subject_types_supported: Vec<String>,
+    /// JSON array of claims the userinfo endpoint can return
+    pub claims_supported: Vec<String>,
+    /// Whether signed request objects are accepted in `request` (RFC 9101)
+    pub request_parameter_supported: bool,
+    /// Whether `request_uri` may reference a hosted request object
+    pub request_uri_parameter_supported: bool,
+    /// Whether hosted request objects must be at a URL the client registered
+    pub require_request_uri_registration: bool,
+    /// JSON array of algorithms request objects may be signed with
+    pub request_object_signing_alg_values_supported: Vec<String>,
+    /// JSON array of authentication context classes reported in `acr`
+    pub acr_values_supported: Vec<String>,
+    /// JSON array of accepted `prompt` values
+    pub prompt_values_supported: Vec<String>,
 }
 
 impl OpenIdConfiguration {
@@ -251,18 +537,51 @@ impl OpenIdConfiguration {
             token_endpoint: format!("{}/oauth/token", base_url),
             userinfo_endpoint: format!("{}/oauth/userinfo", base_url),
             revocation_endpoint: format!("{}/oauth/revoke", base_url),
+            introspection_endpoint: format!("{}/oauth/introspect", base_url),
+            device_authorization_endpoint: format!("{}/oauth/device_authorization", base_url),
+            pushed_authorization_request_endpoint: format!("{}/oauth/par", base_url),
+            end_session_endpoint: format!("{}/oauth/logout", base_url),
+            backchannel_logout_supported: true,
+            // Sessions are not tracked per client, so logout tokens only carry sub
+            backchannel_logout_session_supported: false,
             response_types_supported: vec!["code".to_string()],
-            grant_types_supported: vec![
-                "authorization_code".to_string(),
-                "client_credentials".to_string(),
-                "refresh_token".to_string(),
-            ],
+            grant_types_supported: crate::services::oauth::SUPPORTED_GRANT_TYPES
+                .iter()
+                .map(|grant| grant.to_string())
+                .collect(),
             scopes_supported: scopes,
             token_endpoint_auth_methods_supported: vec![
                 "client_secret_post".to_string(),
                 "client_secret_basic".to_string(),
             ],
             code_challenge_methods_supported: vec!["S256".to_string()],
+            jwks_uri: format!("{}/.well-known/jwks.json", base_url),
+            id_token_signing_alg_values_supported: vec!["RS256".to_string()],
+            userinfo_signing_alg_values_supported: crate::utils::jwt::USERINFO_SIGNING_ALG_VALUES_SUPPORTED
+                .iter()
+                .map(|alg| alg.to_string())
+                .collect(),
+            subject_types_supported: vec!["public".to_string()],
+            claims_supported: std::iter::once("sub")
+                .chain(crate::utils::userinfo_claims::SUPPORTED_CLAIMS.iter().copied())
+                .map(String::from)
+                .collect(),
+            request_parameter_supported: true,
+            request_uri_parameter_supported: true,
+            require_request_uri_registration: true,
+            request_object_signing_alg_values_supported:
+                crate::utils::request_object::REQUEST_OBJECT_SIGNING_ALGS
+                    .iter()
+                    .map(|alg| format!("{:?}", alg))
+                    .collect(),
+            acr_values_supported: crate::utils::jwt::ACR_VALUES_SUPPORTED
+                .iter()
+                .map(|acr| acr.to_string())
+                .collect(),
+            prompt_values_supported: ["none", "login", "consent", "select_account"]
+                .iter()
+                .map(|prompt| prompt.to_string())
+                .collect(),
         }
     }
 }
@@ -309,6 +628,14 @@ impl OAuthErrorResponse {
         Self::new("invalid_client", Some("Client authentication failed"))
     }
 
+    /// Create a client_secret_expired error
+    pub fn client_secret_expired() -> Self {
+        Self::new(
+            "client_secret_expired",
+            Some("Client secret is past its maximum age; rotate it to continue"),
+        )
+    }
+
     /// Create an invalid_grant error
     pub fn invalid_grant(description: &str) -> Self {
         Self::new("invalid_grant", Some(description))
@@ -334,6 +661,31 @@ impl OAuthErrorResponse {
         Self::new("access_denied", Some("User denied consent"))
     }
 
+    /// Create an authorization_pending error
+    pub fn authorization_pending() -> Self {
+        Self::new("authorization_pending", Some("The user has not yet approved the device"))
+    }
+
+    /// Create a slow_down error
+    pub fn slow_down() -> Self {
+        Self::new("slow_down", Some("Polling too fast; increase the interval by 5 seconds"))
+    }
+
+    /// Create an expired_token error
+    pub fn expired_token() -> Self {
+        Self::new("expired_token", Some("The device code has expired"))
+    }
+
+    /// Create an invalid_request_object error
+    pub fn invalid_request_object(description: &str) -> Self {
+        Self::new("invalid_request_object", Some(description))
+    }
+
+    /// Create an invalid_request_uri error
+    pub fn invalid_request_uri(description: &str) -> Self {
+        Self::new("invalid_request_uri", Some(description))
+    }
+
     /// Create a server_error
     pub fn server_error() -> Self {
         Self::new("server_error", Some("Internal server error"))
@@ -356,6 +708,24 @@ pub struct ClientRegistrationRequest {
     /// Whether this is an internal app
     #[serde(default)]
     pub is_internal: bool,
+    /// Client type: `web` (default) or `native` for desktop/mobile/CLI apps
+    #[serde(default)]
+    pub client_type: Option<String>,
+    /// Where `GET /oauth/logout` may send the user afterwards
+    #[serde(default)]
+    pub post_logout_redirect_uris: Vec<String>,
+    /// Back-channel logout endpoint that receives logout tokens
+    #[serde(default)]
+    pub backchannel_logout_uri: Option<String>,
+    /// Space-separated scopes the client may request (omitted = every available scope)
+    #[serde(default)]
+    pub scope: Option<String>,
+    /// Grant types the client may use (omitted = every supported grant)
+    #[serde(default)]
+    pub grant_types: Option<Vec<String>>,
+    /// Sign /oauth/userinfo responses with this algorithm (omitted = plain JSON)
+    #[serde(default)]
+    pub userinfo_signed_response_alg: Option<String>,
 }
 
 /// Client Registration Response
@@ -373,6 +743,25 @@ pub struct ClientRegistrationResponse {
     pub redirect_uris: Vec<String>,
     /// Whether this is an internal app
     pub is_internal: bool,
+    /// Client type: `web` or `native`
+    pub client_type: String,
+    /// Where `GET /oauth/logout` may send the user afterwards
+    pub post_logout_redirect_uris: Vec<String>,
+    /// Back-channel logout endpoint that receives logout tokens
+    pub backchannel_logout_uri: Option<String>,
+    /// Space-separated scopes the client may request (omitted = every available scope)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// Grant types the client may use (omitted = every supported grant)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grant_types: Option<Vec<String>>,
+    /// Algorithm /oauth/userinfo responses are signed with (omitted = plain JSON)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub userinfo_signed_response_alg: Option<String>,
+    /// Token for managing the client at `registration_client_uri` (only returned once)
+    pub registration_access_token: String,
+    /// Where the client reads, updates and deletes its registration (RFC 7592)
+    pub registration_client_uri: String,
 }
 
 /// OAuth Client Info (without secret)
@@ -390,8 +779,42 @@ pub struct OAuthClientInfo {
     pub redirect_uris: Vec<String>,
     /// Whether this is an internal app
     pub is_internal: bool,
+    /// Client type: `web` or `native`
+    pub client_type: String,
     /// Whether the client is active
     pub is_active: bool,
+    /// Session idle timeout override in seconds (null = server default)
+    pub session_idle_timeout_secs: Option<i64>,
+    /// Session absolute lifetime override in seconds (null = server default)
+    pub session_absolute_lifetime_secs: Option<i64>,
+    /// Access token lifetime override in seconds (null = server default)
+    pub access_token_ttl_secs: Option<i64>,
+    /// Whether refresh tokens are delivered in an HttpOnly cookie
+    pub refresh_token_cookie: bool,
+    /// Whether the consent screen is skipped (first-party internal clients)
+    pub skip_consent: bool,
+    /// Whether the consent screen is shown on every authorization
+    pub always_prompt_consent: bool,
+    /// Whether access tokens carry the sign-in posture (`azp_ctx`)
+    pub posture_claims: bool,
+    /// Minimum acr of sign-ins: pwd, mfa or phr (null = any)
+    pub required_acr: Option<String>,
+    /// Algorithm /oauth/userinfo responses are signed with (null = plain JSON)
+    pub userinfo_signed_response_alg: Option<String>,
+    /// Where `GET /oauth/logout` may send the user afterwards
+    pub post_logout_redirect_uris: Vec<String>,
+    /// Back-channel logout endpoint that receives logout tokens
+    pub backchannel_logout_uri: Option<String>,
+    /// URLs hosting request objects the client may pass as request_uri
+    pub request_uris: Vec<String>,
+    /// Space-separated scopes the client may request (null = every available scope)
+    pub scope: Option<String>,
+    /// Grant types the client may use (null = every supported grant)
+    pub grant_types: Option<Vec<String>>,
+    /// When the current secret was issued
+    pub secret_created_at: chrono::DateTime<chrono::Utc>,
+    /// When the current secret expires (null = never)
+    pub secret_expires_at: Option<chrono::DateTime<chrono::Utc>>,
     /// When the client was created
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
@@ -412,6 +835,90 @@ pub struct UpdateOAuthClientRequest {
     pub redirect_uris: Option<Vec<String>>,
     /// Whether the client is active
     pub is_active: Option<bool>,
+    /// Session idle timeout override in seconds (0 restores the server default)
+    pub session_idle_timeout_secs: Option<i64>,
+    /// Session absolute lifetime override in seconds (0 restores the server default)
+    pub session_absolute_lifetime_secs: Option<i64>,
+    /// Access token lifetime in seconds, at most OAUTH_MAX_ACCESS_TOKEN_TTL_SECS (0 restores the server default)
+    pub access_token_ttl_secs: Option<i64>,
+    /// Deliver refresh tokens in an HttpOnly cookie (for browser SPAs)
+    pub refresh_token_cookie: Option<bool>,
+    /// Post-logout redirect URIs (replaces the current list)
+    pub post_logout_redirect_uris: Option<Vec<String>>,
+    /// Back-channel logout endpoint (an empty string removes it)
+    pub backchannel_logout_uri: Option<String>,
+    /// URLs hosting request objects the client may pass as request_uri (replaces the current list)
+    pub request_uris: Option<Vec<String>>,
+    /// Grant types the client may use (an empty list allows every supported grant)
+    pub grant_types: Option<Vec<String>>,
+    /// Show the consent screen on every authorization
+    pub always_prompt_consent: Option<bool>,
+    /// Put the sign-in posture (`azp_ctx`) in access tokens
+    pub posture_claims: Option<bool>,
+    /// Minimum acr of sign-ins: pwd, mfa or phr (an empty string accepts any sign-in)
+    pub required_acr: Option<String>,
+    /// Sign /oauth/userinfo responses with this algorithm (an empty string returns plain JSON)
+    pub userinfo_signed_response_alg: Option<String>,
+}
+
+/// Client Configuration Response (RFC 7592 Section 3)
+///
+/// Returned by `GET` and `PUT` on the client's `registration_client_uri`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientConfigurationResponse {
+    /// The client's public identifier
+    pub client_id: String,
+    /// Client name
+    pub client_name: String,
+    /// Redirect URIs
+    pub redirect_uris: Vec<String>,
+    /// Space-separated scopes the client may request (omitted = every available scope)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// Grant types the client may use (omitted = every supported grant)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grant_types: Option<Vec<String>>,
+    /// Client type: `web` or `native`
+    pub client_type: String,
+    /// Whether the client is active
+    pub is_active: bool,
+    /// Where `GET /oauth/logout` may send the user afterwards
+    pub post_logout_redirect_uris: Vec<String>,
+    /// Back-channel logout endpoint that receives logout tokens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backchannel_logout_uri: Option<String>,
+    /// Algorithm /oauth/userinfo responses are signed with (omitted = plain JSON)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub userinfo_signed_response_alg: Option<String>,
+    /// When the client was registered (Unix timestamp)
+    pub client_id_issued_at: i64,
+    /// When the client secret expires (Unix timestamp, 0 = never)
+    pub client_secret_expires_at: i64,
+    /// Where the client reads, updates and deletes its registration
+    pub registration_client_uri: String,
+}
+
+/// Client Update Request (RFC 7592 Section 2.2)
+///
+/// Replaces the client's metadata: omitting `scope` or `grant_types` lifts
+/// that restriction, omitting `userinfo_signed_response_alg` returns plain
+/// JSON from userinfo.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClientConfigurationUpdateRequest {
+    /// Must match the client being updated
+    pub client_id: String,
+    /// Client name (omitted keeps the current name)
+    pub client_name: Option<String>,
+    /// Redirect URIs
+    pub redirect_uris: Vec<String>,
+    /// Space-separated scopes the client may request
+    pub scope: Option<String>,
+    /// Grant types the client may use
+    pub grant_types: Option<Vec<String>>,
+    /// Algorithm /oauth/userinfo responses are signed with
+    pub userinfo_signed_response_alg: Option<String>,
+    /// Deactivate (false) or reactivate (true) the client
+    pub is_active: Option<bool>,
 }
 
 /// Regenerate Secret Response
@@ -419,6 +926,37 @@ pub struct UpdateOAuthClientRequest {
 pub struct RegenerateClientSecretResponse {
     /// The new client secret (only returned once)
     pub client_secret: String,
+    /// When the new secret expires (null = never)
+    pub secret_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Secret rotation with overlap request
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RotateClientSecretRequest {
+    /// How long the old secret keeps working (defaults to CLIENT_SECRET_ROTATION_GRACE_SECS)
+    pub grace_period_secs: Option<i64>,
+}
+
+/// Secret rotation with overlap response
+#[derive(Debug, Clone, Serialize)]
+pub struct RotateClientSecretResponse {
+    /// The new client secret (only returned once)
+    pub client_secret: String,
+    /// When the new secret expires (null = never)
+    pub secret_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Until when the old secret is still accepted
+    pub previous_secret_expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Secret Rotation History Response
+#[derive(Debug, Clone, Serialize)]
+pub struct ListClientSecretRotationsResponse {
+    /// When the current secret was issued
+    pub secret_created_at: chrono::DateTime<chrono::Utc>,
+    /// When the current secret expires (null = never)
+    pub secret_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Past rotations, newest first
+    pub rotations: Vec<crate::models::ClientSecretRotation>,
 }
 
 // ============================================================================
@@ -436,6 +974,8 @@ pub struct ConnectedAppInfo {
     pub name: String,
     /// Granted scopes
     pub scopes: Vec<String>,
+    /// Whether the grant was recorded automatically for a first-party app
+    pub is_implicit: bool,
     /// When consent was granted
     pub granted_at: chrono::DateTime<chrono::Utc>,
 }
@@ -486,6 +1026,76 @@ pub struct ListScopesResponse {
     pub scopes: Vec<ScopeInfo>,
 }
 
+/// Create Client Scope Request
+///
+/// Defines a namespaced custom scope (`myapp:orders.read`) for an OAuth client.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateClientScopeRequest {
+    /// Scope code in the form `namespace:name`
+    pub code: String,
+    /// Human-readable description shown on consent screens
+    pub description: String,
+    /// Request global visibility (requires admin approval)
+    #[serde(default)]
+    pub global: bool,
+    /// Userinfo claims released by the scope (e.g. `name`, `phone_number`)
+    #[serde(default)]
+    pub claims: Vec<String>,
+}
+
+/// Update Client Scope Request
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateClientScopeRequest {
+    /// New description (a global scope goes back to pending approval)
+    pub description: Option<String>,
+    /// Request (true) or withdraw (false) global visibility
+    pub global: Option<bool>,
+    /// Replace the userinfo claims released by the scope
+    pub claims: Option<Vec<String>>,
+}
+
+/// Client Scope Info
+///
+/// A custom scope defined by an OAuth client.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientScopeInfo {
+    /// Scope UUID
+    pub id: String,
+    /// Scope code
+    pub code: String,
+    /// Human-readable description
+    pub description: String,
+    /// `private`, `pending` or `global`
+    pub visibility: String,
+    /// Whether the scope is active
+    pub is_active: bool,
+    /// Userinfo claims released by the scope
+    pub claims: Vec<String>,
+    /// When the scope was created
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<crate::models::OAuthScope> for ClientScopeInfo {
+    fn from(scope: crate::models::OAuthScope) -> Self {
+        Self {
+            id: scope.id.to_string(),
+            code: scope.code,
+            description: scope.description,
+            visibility: scope.visibility,
+            is_active: scope.is_active,
+            claims: scope.claims,
+            created_at: scope.created_at,
+        }
+    }
+}
+
+/// List Client Scopes Response
+#[derive(Debug, Clone, Serialize)]
+pub struct ListClientScopesResponse {
+    /// Custom scopes defined by the client
+    pub scopes: Vec<ClientScopeInfo>,
+}
+
 /// Consent Decision
 ///
 /// User's decision on the consent screen.
@@ -536,6 +1146,24 @@ impl From<&crate::error::OAuthError> for OAuthErrorResponse {
             crate::error::OAuthError::AccessDenied => {
                 OAuthErrorResponse::access_denied()
             }
+            crate::error::OAuthError::ClientSecretExpired => {
+                OAuthErrorResponse::client_secret_expired()
+            }
+            crate::error::OAuthError::AuthorizationPending => {
+                OAuthErrorResponse::authorization_pending()
+            }
+            crate::error::OAuthError::SlowDown { .. } => {
+                OAuthErrorResponse::slow_down()
+            }
+            crate::error::OAuthError::ExpiredToken => {
+                OAuthErrorResponse::expired_token()
+            }
+            crate::error::OAuthError::InvalidRequestObject(desc) => {
+                OAuthErrorResponse::invalid_request_object(desc)
+            }
+            crate::error::OAuthError::InvalidRequestUri(desc) => {
+                OAuthErrorResponse::invalid_request_uri(desc)
+            }
             crate::error::OAuthError::ServerError(_) => {
                 OAuthErrorResponse::server_error()
             }
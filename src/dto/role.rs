@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -19,4 +20,96 @@ pub struct RoleResponse {
 #[derive(Debug, Deserialize)]
 pub struct AssignRoleRequest {
     pub role_id: Uuid,
+    /// Time-bounded assignment; omitted for a permanent one
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Weekly schedule the role is usable in, e.g. `mon-fri 09:00-17:00 +07:00`;
+    /// omitted for a role usable at any time
+    #[serde(default)]
+    pub access_window: Option<String>,
+}
+
+/// Desired role in an RBAC catalog sync
+#[derive(Debug, Deserialize)]
+pub struct SyncRoleSpec {
+    pub name: String,
+    /// Permission codes granted by the role (must be listed in `permissions`)
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+/// Full desired RBAC catalog of an app
+#[derive(Debug, Deserialize)]
+pub struct SyncRbacRequest {
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    #[serde(default)]
+    pub roles: Vec<SyncRoleSpec>,
+}
+
+/// Query parameters for an RBAC catalog sync
+#[derive(Debug, Deserialize)]
+pub struct SyncRbacQuery {
+    /// Compute the changes without applying them
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// RBAC catalog sync response
+#[derive(Debug, Serialize)]
+pub struct SyncRbacResponse {
+    pub app_id: Uuid,
+    pub dry_run: bool,
+    pub changed: bool,
+    pub changes: crate::models::RbacChangeSummary,
+}
+
+/// Temporary role elevation request
+#[derive(Debug, Deserialize)]
+pub struct CreateElevationRequest {
+    pub role_id: Uuid,
+    /// How long the role is needed once approved
+    pub duration_secs: i64,
+    pub reason: Option<String>,
+}
+
+/// Query parameters for listing elevation requests
+#[derive(Debug, Deserialize)]
+pub struct ListElevationRequestsQuery {
+    /// `pending`, `approved` or `denied`; all when omitted
+    pub status: Option<String>,
+}
+
+/// Elevation request response
+#[derive(Debug, Serialize)]
+pub struct ElevationRequestResponse {
+    pub id: Uuid,
+    pub app_id: Uuid,
+    pub user_id: Uuid,
+    pub role_id: Uuid,
+    pub reason: Option<String>,
+    pub duration_secs: i64,
+    pub status: String,
+    pub decided_by: Option<Uuid>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub grant_expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<crate::models::RoleElevationRequest> for ElevationRequestResponse {
+    fn from(r: crate::models::RoleElevationRequest) -> Self {
+        Self {
+            id: r.id,
+            app_id: r.app_id,
+            user_id: r.user_id,
+            role_id: r.role_id,
+            reason: r.reason,
+            duration_secs: r.duration_secs,
+            status: r.status,
+            decided_by: r.decided_by,
+            decided_at: r.decided_at,
+            grant_expires_at: r.grant_expires_at,
+            created_at: r.created_at,
+        }
+    }
 }
@@ -12,6 +12,9 @@ pub struct LogoutRequest {
     /// Optional: revoke all sessions (logout everywhere)
     #[serde(default)]
     pub all_sessions: bool,
+    /// Optional: end the browser's SSO session and every app session continued from it
+    #[serde(default)]
+    pub sso: bool,
 }
 
 /// Logout response
@@ -21,6 +24,20 @@ pub struct LogoutResponse {
     pub sessions_revoked: u64,
 }
 
+/// What `POST /account/revoke-all` revoked
+#[derive(Debug, Serialize)]
+pub struct RevokeAllResponse {
+    pub message: String,
+    pub sessions_revoked: u64,
+    pub sso_sessions_revoked: u64,
+    pub oauth_tokens_revoked: u64,
+    /// Clients sent a back-channel logout token
+    pub clients_notified: usize,
+    pub authorization_codes_revoked: u64,
+    pub devices_revoked: u64,
+    pub trusted_devices_revoked: u64,
+}
+
 // ============================================================================
 // Session Management DTOs
 // ============================================================================
@@ -29,12 +46,15 @@ pub struct LogoutResponse {
 #[derive(Debug, Serialize)]
 pub struct SessionResponse {
     pub id: Uuid,
+    /// Registered device this session is bound to, if any
+    pub device_id: Option<Uuid>,
     pub device_name: Option<String>,
     pub device_type: Option<String>,
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
     pub last_used_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
+    /// Whether this is the session the request was made from
     pub is_current: bool,
 }
 
@@ -58,6 +78,61 @@ pub struct RevokeSessionsResponse {
     pub revoked_count: u64,
 }
 
+// ============================================================================
+// Device DTOs
+// ============================================================================
+
+/// Register device request
+#[derive(Debug, Deserialize)]
+pub struct RegisterDeviceRequest {
+    /// ios, android, web or desktop
+    pub platform: String,
+    pub device_name: Option<String>,
+    /// FCM/APNs push token
+    pub push_token: Option<String>,
+    /// Refresh token of the session to bind to this device
+    pub refresh_token: String,
+}
+
+/// Device info response
+#[derive(Debug, Serialize)]
+pub struct DeviceResponse {
+    pub id: Uuid,
+    pub platform: String,
+    pub device_name: Option<String>,
+    pub push_enabled: bool,
+    /// Secret for signing push login approvals - only returned at registration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_secret: Option<String>,
+    pub last_seen_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// List devices response
+#[derive(Debug, Serialize)]
+pub struct ListDevicesResponse {
+    pub devices: Vec<DeviceResponse>,
+    pub total: usize,
+}
+
+/// Device trusted to skip MFA
+#[derive(Debug, Serialize)]
+pub struct TrustedDeviceResponse {
+    pub id: Uuid,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// List trusted devices response
+#[derive(Debug, Serialize)]
+pub struct ListTrustedDevicesResponse {
+    pub trusted_devices: Vec<TrustedDeviceResponse>,
+    pub total: usize,
+}
+
 // ============================================================================
 // MFA DTOs
 // ============================================================================
@@ -91,6 +166,45 @@ pub struct VerifyTotpSetupResponse {
     pub backup_codes: Vec<String>,
 }
 
+/// Setup SMS request
+#[derive(Debug, Deserialize)]
+pub struct SetupSmsRequest {
+    /// Phone number in international format, e.g. +84912345678
+    pub phone_number: String,
+}
+
+/// An SMS code was sent
+#[derive(Debug, Serialize)]
+pub struct SmsCodeSentResponse {
+    pub method_id: Uuid,
+    /// Phone number with all but the last three digits hidden
+    pub phone_hint: String,
+    pub expires_in: i64,
+}
+
+/// Verify SMS setup request
+#[derive(Debug, Deserialize)]
+pub struct VerifySmsSetupRequest {
+    pub method_id: Uuid,
+    pub code: String,
+}
+
+/// Verify SMS setup response
+#[derive(Debug, Serialize)]
+pub struct VerifySmsSetupResponse {
+    pub message: String,
+    /// Only returned when the user had no backup codes yet
+    pub backup_codes: Vec<String>,
+}
+
+/// Email MFA setup response
+#[derive(Debug, Serialize)]
+pub struct SetupEmailMfaResponse {
+    pub message: String,
+    /// Only returned when the user had no backup codes yet
+    pub backup_codes: Vec<String>,
+}
+
 /// Verify MFA request (during login)
 #[derive(Debug, Deserialize)]
 pub struct VerifyMfaRequest {
@@ -114,6 +228,9 @@ pub struct VerifyMfaResponse {
 pub struct MfaMethodResponse {
     pub id: Uuid,
     pub method_type: String,
+    /// Masked phone number (SMS methods)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone_hint: Option<String>,
     pub is_primary: bool,
     pub is_verified: bool,
     pub last_used_at: Option<DateTime<Utc>>,
@@ -167,6 +284,8 @@ pub struct AuditLogResponse {
     pub status: String,
     pub created_at: DateTime<Utc>,
     pub details: Option<serde_json::Value>,
+    /// Request id (`X-Request-Id`) of the request that wrote the row
+    pub correlation_id: Option<String>,
 }
 
 /// List audit logs response
@@ -176,6 +295,11 @@ pub struct ListAuditLogsResponse {
     pub page: u32,
     pub limit: u32,
     pub total: u64,
+    pub has_more: bool,
+    /// Always newest first
+    pub sort: crate::dto::AppliedSort,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filters: Option<serde_json::Value>,
 }
 
 /// Audit log query parameters
@@ -183,12 +307,21 @@ pub struct ListAuditLogsResponse {
 pub struct AuditLogQuery {
     pub action: Option<String>,
     pub resource_type: Option<String>,
+    /// Only rows written by this request (admin listing)
+    pub correlation_id: Option<String>,
     #[serde(default = "default_page")]
     pub page: u32,
     #[serde(default = "default_limit")]
     pub limit: u32,
 }
 
+impl AuditLogQuery {
+    /// Requested page and limit
+    pub fn pagination(&self) -> crate::dto::PaginationQuery {
+        crate::dto::PaginationQuery { page: self.page, limit: self.limit }
+    }
+}
+
 fn default_page() -> u32 { 1 }
 fn default_limit() -> u32 { 20 }
 
@@ -241,7 +374,81 @@ pub struct MfaRequiredResponse {
 #[derive(Debug, Deserialize)]
 pub struct CompleteMfaLoginRequest {
     pub mfa_token: String,
+    #[serde(default)]
     pub code: String,
     #[serde(default)]
     pub is_backup_code: bool,
+    /// Treat code as the code texted by POST /auth/mfa/sms/send
+    #[serde(default)]
+    pub is_sms_code: bool,
+    /// Approved push challenge used instead of a code
+    #[serde(default)]
+    pub push_challenge_id: Option<Uuid>,
+    /// Trust this browser so its next sign-ins skip MFA
+    #[serde(default)]
+    pub remember_device: bool,
+}
+
+/// Start push MFA request
+#[derive(Debug, Deserialize)]
+pub struct StartPushMfaRequest {
+    pub mfa_token: String,
+}
+
+/// Start push MFA response
+#[derive(Debug, Serialize)]
+pub struct StartPushMfaResponse {
+    pub challenge_id: Uuid,
+    pub expires_in: i64,
+    pub devices_notified: usize,
+}
+
+/// Send SMS MFA code request
+#[derive(Debug, Deserialize)]
+pub struct SendSmsMfaRequest {
+    pub mfa_token: String,
+    /// Phone to text when several are enrolled (defaults to the first)
+    #[serde(default)]
+    pub method_id: Option<Uuid>,
+}
+
+/// Send email MFA code request
+#[derive(Debug, Deserialize)]
+pub struct SendEmailMfaRequest {
+    pub mfa_token: String,
+}
+
+/// An email code was sent
+#[derive(Debug, Serialize)]
+pub struct EmailCodeSentResponse {
+    /// Address with the local part hidden but its first character
+    pub email_hint: String,
+    pub expires_in: i64,
+}
+
+/// Verify email MFA code request
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailMfaRequest {
+    pub mfa_token: String,
+    pub code: String,
+    /// Trust this browser so its next sign-ins skip MFA
+    #[serde(default)]
+    pub remember_device: bool,
+}
+
+/// Push MFA response sent by the device
+#[derive(Debug, Deserialize)]
+pub struct PushMfaRespondRequest {
+    pub challenge_id: Uuid,
+    pub device_id: Uuid,
+    pub approve: bool,
+    /// Hex HMAC-SHA256 of "{challenge_id}:approve" or "{challenge_id}:deny"
+    /// keyed with the device signing secret
+    pub signature: String,
+}
+
+/// Push MFA respond response
+#[derive(Debug, Serialize)]
+pub struct PushMfaRespondResponse {
+    pub status: String,
 }
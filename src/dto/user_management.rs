@@ -41,13 +41,47 @@ pub struct UserAppResponse {
     pub created_at: DateTime<Utc>,
 }
 
+/// Largest page size accepted by list endpoints
+pub const MAX_PAGE_LIMIT: u32 = 100;
+
+/// Whether items exist after `page` given the total count
+pub fn has_more(page: u32, limit: u32, total: u64) -> bool {
+    u64::from(page) * u64::from(limit) < total
+}
+
+/// Keep only the filters that were set; `None` when no filter applied
+pub fn applied_filters(filters: serde_json::Value) -> Option<serde_json::Value> {
+    match filters {
+        serde_json::Value::Object(map) => {
+            let set: serde_json::Map<_, _> = map.into_iter().filter(|(_, v)| !v.is_null()).collect();
+            (!set.is_empty()).then_some(serde_json::Value::Object(set))
+        }
+        _ => None,
+    }
+}
+
+/// Sort applied to a list
+#[derive(Debug, Clone, Serialize)]
+pub struct AppliedSort {
+    pub by: String,
+    /// `asc` or `desc`
+    pub order: String,
+}
+
 /// Generic paginated response wrapper
+///
+/// `total` counts every item matching the filters, not just this page.
 #[derive(Debug, Serialize)]
 pub struct PaginatedResponse<T> {
     pub data: Vec<T>,
     pub page: u32,
     pub limit: u32,
     pub total: u64,
+    pub has_more: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<AppliedSort>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filters: Option<serde_json::Value>,
 }
 
 impl<T> PaginatedResponse<T> {
@@ -57,6 +91,34 @@ impl<T> PaginatedResponse<T> {
             page,
             limit,
             total,
+            has_more: has_more(page, limit, total),
+            sort: None,
+            filters: None,
+        }
+    }
+
+    /// Echo the sort that was applied
+    pub fn with_sort(mut self, by: &str, order: &str) -> Self {
+        self.sort = Some(AppliedSort { by: by.to_string(), order: order.to_lowercase() });
+        self
+    }
+
+    /// Echo the filters that were applied (null entries are dropped)
+    pub fn with_filters(mut self, filters: serde_json::Value) -> Self {
+        self.filters = applied_filters(filters);
+        self
+    }
+
+    /// Convert the items while keeping the pagination metadata
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> PaginatedResponse<U> {
+        PaginatedResponse {
+            data: self.data.into_iter().map(f).collect(),
+            page: self.page,
+            limit: self.limit,
+            total: self.total,
+            has_more: self.has_more,
+            sort: self.sort,
+            filters: self.filters,
         }
     }
 }
@@ -78,6 +140,13 @@ fn default_limit() -> u32 {
     20
 }
 
+impl PaginationQuery {
+    /// Page (at least 1) and limit (1..=MAX_PAGE_LIMIT) actually used
+    pub fn normalized(&self) -> (u32, u32) {
+        (self.page.max(1), self.limit.clamp(1, MAX_PAGE_LIMIT))
+    }
+}
+
 impl Default for PaginationQuery {
     fn default() -> Self {
         Self {
@@ -87,6 +156,58 @@ impl Default for PaginationQuery {
     }
 }
 
+/// Query parameters for listing the users of an app
+#[derive(Debug, Deserialize)]
+pub struct AppUserListQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    /// Filter by membership status (active, banned)
+    pub status: Option<String>,
+    /// Only users currently holding this role
+    pub role_id: Option<Uuid>,
+    /// Search by email (partial match)
+    pub email: Option<String>,
+    /// Registered at or after this time
+    pub registered_from: Option<DateTime<Utc>>,
+    /// Registered before this time
+    pub registered_to: Option<DateTime<Utc>>,
+    /// Sort field (created_at, email)
+    #[serde(default = "default_sort_field")]
+    pub sort_by: String,
+    /// Sort order (asc, desc)
+    #[serde(default = "default_sort_order")]
+    pub sort_order: String,
+}
+
+impl AppUserListQuery {
+    /// Requested page and limit
+    pub fn pagination(&self) -> PaginationQuery {
+        PaginationQuery { page: self.page, limit: self.limit }
+    }
+}
+
+/// Query parameters for the dormant account report
+#[derive(Debug, Deserialize)]
+pub struct DormantUserReportQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    /// Minimum days without activity (default: `DORMANT_ACCOUNT_DAYS`)
+    pub days: Option<i64>,
+    /// Only accounts the worker has (true) or has not yet (false) flagged
+    pub flagged: Option<bool>,
+}
+
+impl DormantUserReportQuery {
+    /// Requested page and limit
+    pub fn pagination(&self) -> PaginationQuery {
+        PaginationQuery { page: self.page, limit: self.limit }
+    }
+}
+
 /// Query parameters for user search/filter
 #[derive(Debug, Deserialize)]
 pub struct UserSearchQuery {
@@ -100,6 +221,10 @@ pub struct UserSearchQuery {
     pub email_verified: Option<bool>,
     /// Filter by system admin status
     pub is_system_admin: Option<bool>,
+    /// Registration field to filter on (see `REGISTRATION_FIELDS`)
+    pub metadata_field: Option<String>,
+    /// Exact value `metadata_field` must have
+    pub metadata_value: Option<String>,
     /// Sort field (email, name, created_at)
     #[serde(default = "default_sort_field")]
     pub sort_by: String,
@@ -128,6 +253,8 @@ impl Default for UserSearchQuery {
             is_active: None,
             email_verified: None,
             is_system_admin: None,
+            metadata_field: None,
+            metadata_value: None,
             sort_by: default_sort_field(),
             sort_order: default_sort_order(),
             page: default_page(),
@@ -145,6 +272,8 @@ pub struct UserSearchResult {
     pub is_active: bool,
     pub email_verified: bool,
     pub is_system_admin: bool,
+    /// Values of the extra registration fields
+    pub metadata: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -180,6 +309,8 @@ pub struct UserExportData {
     pub is_active: bool,
     pub email_verified: bool,
     pub is_system_admin: bool,
+    /// Values of the extra registration fields
+    pub metadata: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -220,6 +351,14 @@ pub struct AdminUpdateUserRequest {
     pub email_verified: Option<bool>,
 }
 
+/// Request to place a user under legal hold or release it
+#[derive(Debug, Deserialize)]
+pub struct LegalHoldRequest {
+    pub legal_hold: bool,
+    /// Case or ticket reference, required when setting the hold
+    pub reason: Option<String>,
+}
+
 /// Request to update app by admin
 #[derive(Debug, Deserialize)]
 pub struct AdminUpdateAppRequest {
@@ -239,6 +378,8 @@ pub struct AdminUserDetailResponse {
     pub email_verified: bool,
     pub is_system_admin: bool,
     pub mfa_enabled: bool,
+    pub legal_hold: bool,
+    pub legal_hold_reason: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
 }
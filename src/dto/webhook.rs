@@ -6,6 +6,8 @@ use chrono::{DateTime, Utc};
 pub struct CreateWebhookRequest {
     pub url: String,
     pub events: Vec<String>,
+    /// Payload schema to pin; defaults to the latest
+    pub payload_version: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -13,6 +15,7 @@ pub struct UpdateWebhookRequest {
     pub url: Option<String>,
     pub events: Option<Vec<String>>,
     pub is_active: Option<bool>,
+    pub payload_version: Option<i32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -22,6 +25,9 @@ pub struct WebhookResponse {
     pub url: String,
     pub events: Vec<String>,
     pub is_active: bool,
+    pub payload_version: i32,
+    /// Set while deliveries are paused after repeated failures
+    pub circuit_open_until: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -33,9 +39,26 @@ pub struct WebhookWithSecretResponse {
     pub secret: String,
     pub events: Vec<String>,
     pub is_active: bool,
+    pub payload_version: i32,
     pub created_at: DateTime<Utc>,
 }
 
+/// Window of events to replay; `to` defaults to now
+#[derive(Debug, Deserialize)]
+pub struct ReplayWebhookQuery {
+    pub from: DateTime<Utc>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplayWebhookResponse {
+    /// Deliveries enqueued
+    pub queued: usize,
+    /// More events remain in the window; call again with `from` = `next_from`
+    pub truncated: bool,
+    pub next_from: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct WebhookDeliveryResponse {
     pub id: Uuid,
@@ -22,7 +22,25 @@ pub enum AuthError {
     #[error("User is banned from this app")]
     UserBanned { reason: Option<String> },
 
-    #[error("Email already exists")]
+    #[error("Email address is not verified")]
+    EmailNotVerified,
+
+    #[error("Access from this IP address is blocked")]
+    IpBlocked,
+
+    #[error("Outside the access window of this credential")]
+    OutsideAccessWindow,
+
+    #[error("Sign-in from an unlikely location")]
+    ImpossibleTravel,
+
+    #[error("High-risk actions are paused after a recent credential change")]
+    CoolingOff {
+        reason: &'static str,
+        ends_at: chrono::DateTime<chrono::Utc>,
+    },
+
+    #[error("Email address is not available")]
     EmailAlreadyExists,
 
     #[error("Invalid email format")]
@@ -43,15 +61,11 @@ pub enum AuthError {
     #[error("Account is locked")]
     AccountLocked {
         locked_until: chrono::DateTime<chrono::Utc>,
-        remaining_seconds: i64,
+        throttle: Throttle,
     },
 
     #[error("Rate limit exceeded")]
-    RateLimitExceeded {
-        retry_after_seconds: i64,
-        limit: i32,
-        remaining: i32,
-    },
+    RateLimitExceeded(Throttle),
 
     #[error("MFA required")]
     MfaRequired {
@@ -68,6 +82,33 @@ pub enum AuthError {
     #[error("Session not found")]
     SessionNotFound,
 
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("Authorization pending")]
+    AuthorizationPending,
+
+    #[error("CSRF token missing or invalid")]
+    CsrfTokenInvalid,
+
+    #[error("Token is bound to a different caller")]
+    TokenBindingMismatch,
+
+    #[error("User is not a member of the requested app")]
+    NotAppMember,
+
+    #[error("The app does not accept sign-ins from the SSO session")]
+    SsoNotAllowed,
+
+    #[error("The app does not accept sign-ins with an emailed code")]
+    EmailLoginNotAllowed,
+
+    #[error("A stronger sign-in is required ({required_acr})")]
+    InsufficientUserAuthentication { required_acr: String },
+
+    #[error("Access token would be too large ({size} bytes, limit {limit})")]
+    TokenTooLarge { size: usize, limit: usize },
+
     #[error("Internal server error")]
     InternalError(#[from] anyhow::Error),
 }
@@ -115,6 +156,12 @@ pub enum RoleError {
     #[error("User not found")]
     UserNotFound,
 
+    #[error("Role assignment expiry must be in the future")]
+    InvalidExpiry,
+
+    #[error("Invalid access window: {0}")]
+    InvalidAccessWindow(String),
+
     #[error("Internal server error")]
     InternalError(#[from] anyhow::Error),
 }
@@ -138,12 +185,147 @@ pub enum PermissionError {
     InternalError(#[from] anyhow::Error),
 }
 
+/// Why and for how long a caller is held back
+///
+/// Every rate limit, lockout and quota renders it the same way: the body
+/// gets `retry_after_seconds`, `retry_at`, `limit`, `remaining`,
+/// `window_seconds` and `policy`, and the response gets `Retry-After` and
+/// `RateLimit-*` headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Throttle {
+    /// Limit that was hit, e.g. `login`, `mfa_verify`, `account_lockout`
+    pub policy: &'static str,
+    pub retry_after_seconds: i64,
+    /// Requests (or failed attempts) allowed per window
+    pub limit: i32,
+    pub remaining: i32,
+    pub window_seconds: i64,
+}
+
+impl Throttle {
+    /// Body fields; `retry_after` is kept for clients written against the old envelope
+    fn details(&self) -> serde_json::Map<String, serde_json::Value> {
+        let retry_after = self.retry_after_seconds.max(0);
+        let retry_at = chrono::Utc::now() + chrono::Duration::seconds(retry_after);
+        let details = serde_json::json!({
+            "retry_after": retry_after,
+            "retry_after_seconds": retry_after,
+            "retry_at": retry_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "limit": self.limit,
+            "remaining": self.remaining.max(0),
+            "window_seconds": self.window_seconds,
+            "policy": self.policy,
+        });
+        match details {
+            serde_json::Value::Object(map) => map,
+            _ => serde_json::Map::new(),
+        }
+    }
+
+    /// `Retry-After` plus the IETF `RateLimit-*` fields
+    fn apply_headers(&self, headers: &mut axum::http::HeaderMap) {
+        let retry_after = self.retry_after_seconds.max(0);
+        let values = [
+            (axum::http::header::RETRY_AFTER.as_str(), retry_after.to_string()),
+            ("ratelimit-limit", self.limit.to_string()),
+            ("ratelimit-remaining", self.remaining.max(0).to_string()),
+            ("ratelimit-reset", retry_after.to_string()),
+            ("ratelimit-policy", format!("{};w={}", self.limit, self.window_seconds)),
+        ];
+        for (name, value) in values {
+            if let (Ok(name), Ok(value)) = (
+                axum::http::HeaderName::from_bytes(name.as_bytes()),
+                axum::http::HeaderValue::from_str(&value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Serialize)]
 pub struct ErrorResponse {
     pub error: String,
     pub message: String,
     pub status_code: u16,
+    /// Machine-readable metadata for the error code (e.g. `retry_after`)
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+impl AuthError {
+    /// Whether the error describes the state of the account or caller
+    /// (lockout, ban, deactivation, ...) rather than a failed request
+    pub fn is_account_state(&self) -> bool {
+        matches!(
+            self,
+            AuthError::UserInactive
+                | AuthError::UserBanned { .. }
+                | AuthError::EmailNotVerified
+                | AuthError::IpBlocked
+                | AuthError::OutsideAccessWindow
+                | AuthError::ImpossibleTravel
+                | AuthError::CoolingOff { .. }
+                | AuthError::AccountLocked { .. }
+                | AuthError::RateLimitExceeded(_)
+        )
+    }
+
+    /// Metadata returned alongside the error code
+    ///
+    /// The keys are part of the API contract: the [`Throttle`] fields,
+    /// `locked_until`, `banned_until` (null = until lifted by the app owner),
+    /// `ban_reason`, `verification_required` and `required_acr`.
+    pub fn details(&self) -> Option<serde_json::Map<String, serde_json::Value>> {
+        let details = match self {
+            AuthError::AccountLocked { locked_until, throttle } => {
+                let mut details = throttle.details();
+                details.insert("locked_until".to_string(), serde_json::json!(locked_until));
+                details.insert("remaining_seconds".to_string(), serde_json::json!(throttle.retry_after_seconds));
+                return Some(details);
+            }
+            AuthError::RateLimitExceeded(throttle) => return Some(throttle.details()),
+            AuthError::UserBanned { reason } => serde_json::json!({
+                "banned_until": null,
+                "ban_reason": reason,
+            }),
+            AuthError::UserInactive => serde_json::json!({ "contact_support": true }),
+            AuthError::EmailNotVerified => serde_json::json!({ "verification_required": true }),
+            AuthError::CoolingOff { reason, ends_at } => serde_json::json!({
+                "cooling_off_reason": reason,
+                "cooling_off_until": ends_at,
+            }),
+            AuthError::InsufficientUserAuthentication { required_acr } => serde_json::json!({
+                "required_acr": required_acr,
+            }),
+            _ => return None,
+        };
+
+        match details {
+            serde_json::Value::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Throttling behind the error, if any
+    pub fn throttle(&self) -> Option<&Throttle> {
+        match self {
+            AuthError::AccountLocked { throttle, .. } | AuthError::RateLimitExceeded(throttle) => Some(throttle),
+            _ => None,
+        }
+    }
+
+    /// Step-up challenge (RFC 9470) asking the client to sign in again at a level
+    fn www_authenticate(&self) -> Option<String> {
+        match self {
+            AuthError::InsufficientUserAuthentication { required_acr } => Some(format!(
+                "Bearer error=\"insufficient_user_authentication\", error_description=\"A stronger sign-in is required\", acr_values=\"{}\"",
+                required_acr
+            )),
+            _ => None,
+        }
+    }
 }
 
 impl IntoResponse for AuthError {
@@ -154,6 +336,11 @@ impl IntoResponse for AuthError {
             AuthError::UserNotFound => (StatusCode::NOT_FOUND, "user_not_found"),
             AuthError::UserInactive => (StatusCode::FORBIDDEN, "user_inactive"),
             AuthError::UserBanned { .. } => (StatusCode::FORBIDDEN, "user_banned"),
+            AuthError::EmailNotVerified => (StatusCode::FORBIDDEN, "email_not_verified"),
+            AuthError::IpBlocked => (StatusCode::FORBIDDEN, "ip_blocked"),
+            AuthError::OutsideAccessWindow => (StatusCode::FORBIDDEN, "outside_access_window"),
+            AuthError::ImpossibleTravel => (StatusCode::FORBIDDEN, "impossible_travel"),
+            AuthError::CoolingOff { .. } => (StatusCode::FORBIDDEN, "cooling_off"),
             AuthError::EmailAlreadyExists => (StatusCode::CONFLICT, "email_exists"),
             AuthError::InvalidEmailFormat => (StatusCode::BAD_REQUEST, "invalid_email"),
             AuthError::WeakPassword => (StatusCode::BAD_REQUEST, "weak_password"),
@@ -161,11 +348,22 @@ impl IntoResponse for AuthError {
             AuthError::TokenExpired => (StatusCode::UNAUTHORIZED, "token_expired"),
             AuthError::InsufficientScope => (StatusCode::FORBIDDEN, "insufficient_scope"),
             AuthError::AccountLocked { .. } => (StatusCode::FORBIDDEN, "account_locked"),
-            AuthError::RateLimitExceeded { .. } => (StatusCode::TOO_MANY_REQUESTS, "rate_limit_exceeded"),
+            AuthError::RateLimitExceeded(_) => (StatusCode::TOO_MANY_REQUESTS, "rate_limit_exceeded"),
             AuthError::MfaRequired { .. } => (StatusCode::FORBIDDEN, "mfa_required"),
             AuthError::InvalidMfaCode => (StatusCode::UNAUTHORIZED, "invalid_mfa_code"),
             AuthError::MfaNotEnabled => (StatusCode::BAD_REQUEST, "mfa_not_enabled"),
             AuthError::SessionNotFound => (StatusCode::NOT_FOUND, "session_not_found"),
+            AuthError::InvalidRequest(_) => (StatusCode::BAD_REQUEST, "invalid_request"),
+            AuthError::AuthorizationPending => (StatusCode::BAD_REQUEST, "authorization_pending"),
+            AuthError::CsrfTokenInvalid => (StatusCode::FORBIDDEN, "csrf_token_invalid"),
+            AuthError::TokenBindingMismatch => (StatusCode::UNAUTHORIZED, "token_binding_mismatch"),
+            AuthError::NotAppMember => (StatusCode::FORBIDDEN, "not_app_member"),
+            AuthError::SsoNotAllowed => (StatusCode::FORBIDDEN, "sso_disabled"),
+            AuthError::EmailLoginNotAllowed => (StatusCode::FORBIDDEN, "email_login_disabled"),
+            AuthError::InsufficientUserAuthentication { .. } => {
+                (StatusCode::FORBIDDEN, "insufficient_user_authentication")
+            }
+            AuthError::TokenTooLarge { .. } => (StatusCode::INTERNAL_SERVER_ERROR, "token_too_large"),
             AuthError::InternalError(ref e) => {
                 tracing::error!("Internal error: {:?}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, "internal_error")
@@ -176,15 +374,29 @@ impl IntoResponse for AuthError {
             error: error_type.to_string(),
             message: self.to_string(),
             status_code: status.as_u16(),
+            details: self.details(),
         });
 
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        if let Some(throttle) = self.throttle() {
+            throttle.apply_headers(response.headers_mut());
+        }
+        if let Some(Ok(value)) = self.www_authenticate().map(|v| v.parse()) {
+            response.headers_mut().insert(axum::http::header::WWW_AUTHENTICATE, value);
+        }
+        response
     }
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let (status, error_type) = match &self {
+        // Account-state errors keep their specific code and metadata
+        let error = match self {
+            AppError::Auth(e) if e.is_account_state() => return e.into_response(),
+            error => error,
+        };
+
+        let (status, error_type) = match &error {
             AppError::NotFound(_) => (StatusCode::NOT_FOUND, "not_found"),
             AppError::CodeAlreadyExists => (StatusCode::CONFLICT, "app_code_exists"),
             AppError::InvalidCredentials => (StatusCode::UNAUTHORIZED, "invalid_credentials"),
@@ -200,8 +412,9 @@ impl IntoResponse for AppError {
 
         let body = Json(ErrorResponse {
             error: error_type.to_string(),
-            message: self.to_string(),
+            message: error.to_string(),
             status_code: status.as_u16(),
+            details: None,
         });
 
         (status, body).into_response()
@@ -215,6 +428,8 @@ impl IntoResponse for RoleError {
             RoleError::NameAlreadyExists => (StatusCode::CONFLICT, "role_name_exists"),
             RoleError::AppNotFound => (StatusCode::NOT_FOUND, "app_not_found"),
             RoleError::UserNotFound => (StatusCode::NOT_FOUND, "user_not_found"),
+            RoleError::InvalidExpiry => (StatusCode::BAD_REQUEST, "invalid_expiry"),
+            RoleError::InvalidAccessWindow(_) => (StatusCode::BAD_REQUEST, "invalid_access_window"),
             RoleError::InternalError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error"),
         };
 
@@ -222,6 +437,7 @@ impl IntoResponse for RoleError {
             error: error_type.to_string(),
             message: self.to_string(),
             status_code: status.as_u16(),
+            details: None,
         });
 
         (status, body).into_response()
@@ -242,6 +458,7 @@ impl IntoResponse for PermissionError {
             error: error_type.to_string(),
             message: self.to_string(),
             status_code: status.as_u16(),
+            details: None,
         });
 
         (status, body).into_response()
@@ -272,6 +489,21 @@ pub enum UserManagementError {
     #[error("App not found")]
     AppNotFound,
 
+    #[error("Validation error: {0}")]
+    ValidationError(String),
+
+    #[error("User is under legal hold")]
+    LegalHold,
+
+    #[error("Approval request not found")]
+    ApprovalNotFound,
+
+    #[error("Approval request is no longer pending")]
+    ApprovalNotPending,
+
+    #[error("An admin cannot approve their own request")]
+    SelfApproval,
+
     #[error("Internal server error")]
     InternalError(#[from] anyhow::Error),
 }
@@ -286,6 +518,11 @@ impl IntoResponse for UserManagementError {
             UserManagementError::UserNotRegistered => (StatusCode::NOT_FOUND, "user_not_registered"),
             UserManagementError::UserNotFound => (StatusCode::NOT_FOUND, "user_not_found"),
             UserManagementError::AppNotFound => (StatusCode::NOT_FOUND, "app_not_found"),
+            UserManagementError::ValidationError(_) => (StatusCode::BAD_REQUEST, "validation_error"),
+            UserManagementError::LegalHold => (StatusCode::CONFLICT, "legal_hold"),
+            UserManagementError::ApprovalNotFound => (StatusCode::NOT_FOUND, "approval_not_found"),
+            UserManagementError::ApprovalNotPending => (StatusCode::CONFLICT, "approval_not_pending"),
+            UserManagementError::SelfApproval => (StatusCode::FORBIDDEN, "self_approval"),
             UserManagementError::InternalError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error"),
         };
 
@@ -293,6 +530,12 @@ impl IntoResponse for UserManagementError {
             error: error_type.to_string(),
             message: self.to_string(),
             status_code: status.as_u16(),
+            details: match &self {
+                UserManagementError::UserBanned { reason } => {
+                    AuthError::UserBanned { reason: reason.clone() }.details()
+                }
+                _ => None,
+            },
         });
 
         (status, body).into_response()
@@ -317,6 +560,14 @@ pub enum AppAuthError {
     #[error("Cross-app access denied")]
     CrossAppAccess,
 
+    /// API key lacks the scope required by an `/app-api` endpoint
+    #[error("Insufficient scope")]
+    InsufficientScope,
+
+    /// Request body is well-formed JSON but semantically invalid
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
     /// User account has been deactivated (Requirement 8.6)
     #[error("User inactive")]
     UserInactive,
@@ -331,6 +582,8 @@ impl IntoResponse for AppAuthError {
             AppAuthError::InvalidCredentials => (StatusCode::UNAUTHORIZED, "invalid_credentials"),
             AppAuthError::NotAppOwner => (StatusCode::FORBIDDEN, "not_app_owner"),
             AppAuthError::CrossAppAccess => (StatusCode::FORBIDDEN, "cross_app_access"),
+            AppAuthError::InsufficientScope => (StatusCode::FORBIDDEN, "insufficient_scope"),
+            AppAuthError::InvalidRequest(_) => (StatusCode::BAD_REQUEST, "invalid_request"),
             AppAuthError::UserInactive => (StatusCode::FORBIDDEN, "user_inactive"),
             AppAuthError::InternalError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error"),
         };
@@ -339,12 +592,33 @@ impl IntoResponse for AppAuthError {
             error: error_type.to_string(),
             message: self.to_string(),
             status_code: status.as_u16(),
+            details: None,
         });
 
         (status, body).into_response()
     }
 }
 
+/// Error types for syncing an app's role/permission catalog
+#[derive(Debug, thiserror::Error)]
+pub enum RbacSyncError {
+    /// Desired catalog is inconsistent (duplicates, unknown permissions, ...)
+    #[error("{0}")]
+    InvalidCatalog(String),
+
+    #[error("Internal server error")]
+    InternalError(#[from] anyhow::Error),
+}
+
+impl From<RbacSyncError> for AppAuthError {
+    fn from(err: RbacSyncError) -> Self {
+        match err {
+            RbacSyncError::InvalidCatalog(msg) => AppAuthError::InvalidRequest(msg),
+            RbacSyncError::InternalError(e) => AppAuthError::InternalError(e),
+        }
+    }
+}
+
 /// Error types for OAuth2 operations
 /// RFC 6749 compliant error codes
 #[allow(dead_code)]
@@ -378,6 +652,31 @@ pub enum OAuthError {
     #[error("Access denied")]
     AccessDenied,
 
+    /// Client secret is correct but past its maximum age and must be rotated
+    #[error("Client secret expired, rotate it to continue")]
+    ClientSecretExpired,
+
+    /// Device authorization not yet approved by the user (RFC 8628 Section 3.5)
+    #[error("Authorization pending")]
+    AuthorizationPending,
+
+    /// Device is polling faster than its interval (RFC 8628 Section 3.5);
+    /// `interval_secs` is the interval to use from now on
+    #[error("Slow down")]
+    SlowDown { interval_secs: i64 },
+
+    /// Device code has expired (RFC 8628 Section 3.5)
+    #[error("Device code has expired")]
+    ExpiredToken,
+
+    /// Request object is invalid or fails verification (RFC 9101 Section 6.3)
+    #[error("Invalid request object: {0}")]
+    InvalidRequestObject(String),
+
+    /// request_uri cannot be used or does not point at a request object (RFC 9101 Section 6.2)
+    #[error("Invalid request_uri: {0}")]
+    InvalidRequestUri(String),
+
     /// Internal server error
     #[error("Server error: {0}")]
     ServerError(String),
@@ -393,15 +692,99 @@ impl IntoResponse for OAuthError {
             OAuthError::UnsupportedGrantType => (StatusCode::BAD_REQUEST, "unsupported_grant_type"),
             OAuthError::InvalidScope(_) => (StatusCode::BAD_REQUEST, "invalid_scope"),
             OAuthError::AccessDenied => (StatusCode::FORBIDDEN, "access_denied"),
+            OAuthError::ClientSecretExpired => (StatusCode::UNAUTHORIZED, "client_secret_expired"),
+            OAuthError::AuthorizationPending => (StatusCode::BAD_REQUEST, "authorization_pending"),
+            OAuthError::SlowDown { .. } => (StatusCode::BAD_REQUEST, "slow_down"),
+            OAuthError::ExpiredToken => (StatusCode::BAD_REQUEST, "expired_token"),
+            OAuthError::InvalidRequestObject(_) => (StatusCode::BAD_REQUEST, "invalid_request_object"),
+            OAuthError::InvalidRequestUri(_) => (StatusCode::BAD_REQUEST, "invalid_request_uri"),
             OAuthError::ServerError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "server_error"),
         };
 
+        // A device polling too fast is throttled to one poll per interval
+        let throttle = match &self {
+            OAuthError::SlowDown { interval_secs } => Some(Throttle {
+                policy: "device_polling",
+                retry_after_seconds: *interval_secs,
+                limit: 1,
+                remaining: 0,
+                window_seconds: *interval_secs,
+            }),
+            _ => None,
+        };
+
         let body = Json(ErrorResponse {
             error: error_code.to_string(),
             message: self.to_string(),
             status_code: status.as_u16(),
+            details: throttle.as_ref().map(Throttle::details),
         });
 
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        if let Some(throttle) = &throttle {
+            throttle.apply_headers(response.headers_mut());
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn throttle(policy: &'static str) -> Throttle {
+        Throttle {
+            policy,
+            retry_after_seconds: 42,
+            limit: 5,
+            remaining: 0,
+            window_seconds: 60,
+        }
+    }
+
+    async fn body_json(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_throttled_responses_share_one_envelope() {
+        let rate_limited = AuthError::RateLimitExceeded(throttle("login")).into_response();
+        assert_eq!(rate_limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        let headers = rate_limited.headers();
+        assert_eq!(headers["retry-after"], "42");
+        assert_eq!(headers["ratelimit-limit"], "5");
+        assert_eq!(headers["ratelimit-remaining"], "0");
+        assert_eq!(headers["ratelimit-reset"], "42");
+        assert_eq!(headers["ratelimit-policy"], "5;w=60");
+        let body = body_json(rate_limited).await;
+        assert_eq!(body["error"], "rate_limit_exceeded");
+        assert_eq!(body["retry_after_seconds"], 42);
+        assert_eq!(body["retry_after"], 42);
+        assert_eq!(body["window_seconds"], 60);
+        assert_eq!(body["policy"], "login");
+        assert!(body["retry_at"].is_string());
+
+        // Lockouts keep their code and status but carry the same fields
+        let locked = AuthError::AccountLocked {
+            locked_until: chrono::Utc::now(),
+            throttle: throttle("account_lockout"),
+        }
+        .into_response();
+        assert_eq!(locked.status(), StatusCode::FORBIDDEN);
+        assert_eq!(locked.headers()["retry-after"], "42");
+        let body = body_json(locked).await;
+        assert_eq!(body["error"], "account_locked");
+        assert_eq!(body["retry_after_seconds"], 42);
+        assert_eq!(body["remaining_seconds"], 42);
+        assert_eq!(body["policy"], "account_lockout");
+
+        let slow_down = OAuthError::SlowDown { interval_secs: 10 }.into_response();
+        assert_eq!(slow_down.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(slow_down.headers()["retry-after"], "10");
+        let body = body_json(slow_down).await;
+        assert_eq!(body["error"], "slow_down");
+        assert_eq!(body["retry_after_seconds"], 10);
+        assert_eq!(body["policy"], "device_polling");
     }
 }
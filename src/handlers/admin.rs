@@ -1,23 +1,47 @@
 use axum::{
     extract::{Extension, Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::config::AppState;
 use crate::dto::user_management::{
     AdminAppDetailResponse, AdminUpdateAppRequest, AdminUpdateUserRequest,
-    AdminUserDetailResponse, PaginatedResponse, PaginationQuery,
+    AdminUserDetailResponse, DormantUserReportQuery, LegalHoldRequest, PaginatedResponse,
+    PaginationQuery,
 };
-use crate::error::UserManagementError;
-use crate::models::{App, User};
-use crate::services::{AdminService, AuditService};
+use crate::error::{AppError, AuthError, UserManagementError};
+use crate::handlers::admin_approval::{approval_pending, approval_service};
+use crate::models::{
+    App, DormantAccount, DuplicateMatchType, User, ADMIN_ACTION_DELETE_APP, ADMIN_ACTION_DELETE_USER,
+    ADMIN_ACTION_GRANT_SYSTEM_ADMIN, DELETION_POLICY_ANONYMIZE, DELETION_POLICY_DELETE,
+};
+use crate::repositories::UserRepository;
+use crate::services::{AdminService, AuditService, CoolingOffService, DuplicateAccountService, PrivacyLedgerService};
 use crate::services::admin::{UserRolesInfo};
+use crate::services::duplicate_account::DuplicateAccountReport;
 use crate::models::AuditAction;
+use crate::utils::cooling_off::{CoolingOffPolicy, CoolingOffReason};
 use crate::utils::jwt::Claims;
 
+/// Reject callers that are not system admins, returning the admin's id
+pub(crate) async fn require_system_admin(state: &AppState, claims: &Claims) -> Result<Uuid, AppError> {
+    let user_id = claims.user_id()?;
+
+    let user_repo = UserRepository::new(state.pool.clone());
+    let user = user_repo.find_by_id(user_id).await?
+        .ok_or(AuthError::UserNotFound)?;
+
+    if !user.is_system_admin {
+        return Err(AppError::Auth(AuthError::NotSystemAdmin));
+    }
+
+    Ok(user_id)
+}
+
 /// Response DTO for user info (excludes password_hash)
 #[derive(Debug, Serialize)]
 pub struct UserResponse {
@@ -76,18 +100,12 @@ pub async fn list_all_users_handler(
     let actor_id = claims.user_id()
         .map_err(|_| UserManagementError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
     
+    let (page, limit) = pagination.normalized();
     let service = AdminService::new(state.pool.clone());
-    let response = service.list_all_users(actor_id, pagination.page, pagination.limit).await?;
+    let response = service.list_all_users(actor_id, page, limit).await?;
     
     // Convert User to UserResponse (excludes password_hash)
-    let user_responses: Vec<UserResponse> = response.data.into_iter().map(UserResponse::from).collect();
-    
-    Ok(Json(PaginatedResponse::new(
-        user_responses,
-        response.page,
-        response.limit,
-        response.total,
-    )))
+    Ok(Json(response.map(UserResponse::from).with_sort("created_at", "desc")))
 }
 
 /// GET /admin/apps - List all apps (admin only)
@@ -103,18 +121,12 @@ pub async fn list_all_apps_handler(
     let actor_id = claims.user_id()
         .map_err(|_| UserManagementError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
     
+    let (page, limit) = pagination.normalized();
     let service = AdminService::new(state.pool.clone());
-    let response = service.list_all_apps(actor_id, pagination.page, pagination.limit).await?;
+    let response = service.list_all_apps(actor_id, page, limit).await?;
     
     // Convert App to AppResponse
-    let app_responses: Vec<AppResponse> = response.data.into_iter().map(AppResponse::from).collect();
-    
-    Ok(Json(PaginatedResponse::new(
-        app_responses,
-        response.page,
-        response.limit,
-        response.total,
-    )))
+    Ok(Json(response.map(AppResponse::from).with_sort("code", "asc")))
 }
 
 /// POST /admin/users/{user_id}/deactivate - Deactivate a user globally (admin only)
@@ -162,23 +174,83 @@ pub async fn get_user_handler(
         email_verified: user.email_verified,
         is_system_admin: user.is_system_admin,
         mfa_enabled: user.mfa_enabled,
+        legal_hold: user.legal_hold,
+        legal_hold_reason: user.legal_hold_reason,
         created_at: user.created_at,
         updated_at: user.updated_at,
     }))
 }
 
 /// PUT /admin/users/{user_id} - Update user (admin only)
+///
+/// When `grant_system_admin` needs approval, making a user a system admin
+/// answers `202` with the pending approval request instead; the grant must
+/// then be sent without other changes.
 pub async fn update_user_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
     Path(user_id): Path<Uuid>,
     Json(req): Json<AdminUpdateUserRequest>,
-) -> Result<Json<AdminUserDetailResponse>, UserManagementError> {
+) -> Result<Response, UserManagementError> {
     let actor_id = claims.user_id()
         .map_err(|_| UserManagementError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
-    
+
+    let approvals = approval_service(&state);
+    if req.is_system_admin == Some(true) && approvals.requires_approval(ADMIN_ACTION_GRANT_SYSTEM_ADMIN) {
+        let service = AdminService::new(state.pool.clone());
+        service.verify_admin(actor_id).await?;
+        if !service.get_user(actor_id, user_id).await?.is_system_admin {
+            if req.email.is_some() || req.is_active.is_some() || req.email_verified.is_some() {
+                return Err(UserManagementError::ValidationError(
+                    "Granting system admin needs approval; send is_system_admin without other changes".into(),
+                ));
+            }
+            let approval = approvals
+                .request(actor_id, ADMIN_ACTION_GRANT_SYSTEM_ADMIN, user_id, None)
+                .await?;
+            return Ok(approval_pending(approval));
+        }
+    }
+
+    let user = update_user(&state, actor_id, user_id, &req, None).await?;
+    Ok(Json(AdminUserDetailResponse {
+        id: user.id,
+        email: user.email,
+        name: user.name,
+        phone: user.phone,
+        avatar_url: user.avatar_url,
+        is_active: user.is_active,
+        email_verified: user.email_verified,
+        is_system_admin: user.is_system_admin,
+        mfa_enabled: user.mfa_enabled,
+        legal_hold: user.legal_hold,
+        legal_hold_reason: user.legal_hold_reason,
+        created_at: user.created_at,
+        updated_at: user.updated_at,
+    }).into_response())
+}
+
+/// Apply an admin's user update and audit it
+///
+/// `approval_id` is the approval request the update executes, if any.
+pub(crate) async fn update_user(
+    state: &AppState,
+    actor_id: Uuid,
+    user_id: Uuid,
+    req: &AdminUpdateUserRequest,
+    approval_id: Option<Uuid>,
+) -> Result<User, UserManagementError> {
     let service = AdminService::new(state.pool.clone());
     let audit_service = AuditService::new(state.pool.clone());
+
+    let previous_email = match req.email {
+        Some(_) => UserRepository::new(state.pool.clone())
+            .find_by_id(user_id)
+            .await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?
+            .map(|user| user.email),
+        None => None,
+    };
     
     let user = service.update_user(
         actor_id,
@@ -202,50 +274,158 @@ pub async fn update_user_handler(
                 "is_active": req.is_active.is_some(),
                 "is_system_admin": req.is_system_admin.is_some(),
                 "email_verified": req.email_verified.is_some(),
-            }
+            },
+            "approval_id": approval_id,
         })),
     ).await;
-    
-    Ok(Json(AdminUserDetailResponse {
-        id: user.id,
-        email: user.email,
-        name: user.name,
-        phone: user.phone,
-        avatar_url: user.avatar_url,
-        is_active: user.is_active,
-        email_verified: user.email_verified,
-        is_system_admin: user.is_system_admin,
-        mfa_enabled: user.mfa_enabled,
-        created_at: user.created_at,
-        updated_at: user.updated_at,
-    }))
+
+    // An email change hands the account to whoever owns the new address;
+    // pause high-risk actions and tell the old address too
+    if let Some(previous_email) = previous_email.filter(|email| *email != user.email) {
+        let cooling_off_service =
+            CoolingOffService::new(state.pool.clone(), CoolingOffPolicy::from_config(&state.config));
+        let cooling_off = cooling_off_service
+            .start(user_id, CoolingOffReason::EmailChanged, None, None)
+            .await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?;
+        if let Some(cooling_off) = cooling_off {
+            cooling_off_service.notify_address(&previous_email, &cooling_off);
+        }
+    }
+
+    Ok(user)
 }
 
-/// DELETE /admin/users/{user_id} - Delete user permanently (admin only)
+/// Query parameters for user deletion
+#[derive(Debug, Deserialize)]
+pub struct DeleteUserQuery {
+    /// "delete" (default) or "anonymize"
+    pub mode: Option<String>,
+}
+
+/// DELETE /admin/users/{user_id} - Delete or anonymize a user (admin only)
+///
+/// `?mode=anonymize` scrubs the account's PII and keeps the row. Members of
+/// an app whose deletion policy is `anonymize` are always anonymized. When
+/// `delete_user` needs approval, answers `202` with the pending request.
 pub async fn delete_user_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
     Path(user_id): Path<Uuid>,
-) -> Result<StatusCode, UserManagementError> {
+    Query(query): Query<DeleteUserQuery>,
+) -> Result<Response, UserManagementError> {
     let actor_id = claims.user_id()
         .map_err(|_| UserManagementError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
-    
+
+    let approvals = approval_service(&state);
+    if approvals.requires_approval(ADMIN_ACTION_DELETE_USER) {
+        let params = query.mode.as_ref().map(|mode| serde_json::json!({ "mode": mode }));
+        let approval = approvals
+            .request(actor_id, ADMIN_ACTION_DELETE_USER, user_id, params)
+            .await?;
+        return Ok(approval_pending(approval));
+    }
+
+    delete_user(&state, actor_id, user_id, query.mode.as_deref(), None).await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// Delete or anonymize a user and audit it, including a legal hold refusal
+///
+/// `approval_id` is the approval request the deletion executes, if any.
+pub(crate) async fn delete_user(
+    state: &AppState,
+    actor_id: Uuid,
+    user_id: Uuid,
+    mode: Option<&str>,
+    approval_id: Option<Uuid>,
+) -> Result<(), UserManagementError> {
     let service = AdminService::new(state.pool.clone());
     let audit_service = AuditService::new(state.pool.clone());
     
-    service.delete_user(actor_id, user_id).await?;
+    let applied = match service.delete_user(actor_id, user_id, mode).await {
+        Ok(applied) => applied,
+        Err(e) => {
+            if matches!(e, UserManagementError::LegalHold) {
+                let _ = audit_service.log_user_event(
+                    actor_id,
+                    AuditAction::LegalHoldBlocked,
+                    user_id,
+                    None,
+                    None,
+                    Some(serde_json::json!({
+                        "attempted": mode.unwrap_or(DELETION_POLICY_DELETE),
+                        "approval_id": approval_id,
+                    })),
+                ).await;
+            }
+            return Err(e);
+        }
+    };
 
     // Log the deletion
+    let action = if applied == DELETION_POLICY_ANONYMIZE {
+        AuditAction::UserAnonymized
+    } else {
+        AuditAction::UserDeleted
+    };
     let _ = audit_service.log_user_event(
         actor_id,
-        AuditAction::UserDeleted,
+        action,
         user_id,
         None,
         None,
+        Some(serde_json::json!({ "mode": applied, "approval_id": approval_id })),
+    ).await;
+
+    Ok(())
+}
+
+/// PUT /admin/users/{user_id}/legal-hold - Place a user under legal hold or release it (admin only)
+///
+/// Held accounts cannot be deleted or anonymized, and their audit rows
+/// survive retention purges, until the hold is released.
+pub async fn set_legal_hold_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(user_id): Path<Uuid>,
+    Json(req): Json<LegalHoldRequest>,
+) -> Result<Json<AdminUserDetailResponse>, UserManagementError> {
+    let actor_id = claims.user_id()
+        .map_err(|_| UserManagementError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
+
+    let service = AdminService::new(state.pool.clone());
+    let audit_service = AuditService::new(state.pool.clone());
+
+    let user = service
+        .set_legal_hold(actor_id, user_id, req.legal_hold, req.reason.as_deref())
+        .await?;
+
+    let action = if req.legal_hold { AuditAction::LegalHoldSet } else { AuditAction::LegalHoldReleased };
+    let _ = audit_service.log_user_event(
+        actor_id,
+        action,
+        user_id,
         None,
+        None,
+        Some(serde_json::json!({ "reason": user.legal_hold_reason })),
     ).await;
-    
-    Ok(StatusCode::NO_CONTENT)
+
+    Ok(Json(AdminUserDetailResponse {
+        id: user.id,
+        email: user.email,
+        name: user.name,
+        phone: user.phone,
+        avatar_url: user.avatar_url,
+        is_active: user.is_active,
+        email_verified: user.email_verified,
+        is_system_admin: user.is_system_admin,
+        mfa_enabled: user.mfa_enabled,
+        legal_hold: user.legal_hold,
+        legal_hold_reason: user.legal_hold_reason,
+        created_at: user.created_at,
+        updated_at: user.updated_at,
+    }))
 }
 
 /// POST /admin/users/{user_id}/activate - Activate a user (admin only)
@@ -278,6 +458,134 @@ pub async fn get_user_roles_handler(
     Ok(Json(roles_info))
 }
 
+/// Query parameters for the privacy ledger export
+#[derive(Debug, Deserialize)]
+pub struct PrivacyLedgerQuery {
+    /// Output format: "json" (default) or "csv"
+    pub format: Option<String>,
+}
+
+/// GET /admin/users/{user_id}/privacy-ledger - Export a user's privacy ledger (admin only)
+///
+/// Compiles consents granted/denied/revoked, data exports, deletion requests,
+/// ToS acceptances and email preference changes in chronological order.
+/// Use `?format=csv` to download the ledger as CSV.
+pub async fn privacy_ledger_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<PrivacyLedgerQuery>,
+) -> Result<Response, UserManagementError> {
+    let actor_id = claims.user_id()
+        .map_err(|_| UserManagementError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
+
+    // Verifies admin and that the target user exists
+    let service = AdminService::new(state.pool.clone());
+    service.get_user(actor_id, user_id).await?;
+
+    let ledger_service = PrivacyLedgerService::new(state.pool.clone());
+    let ledger = ledger_service.build_ledger(user_id).await?;
+
+    let as_csv = query.format.as_deref().map(|f| f.eq_ignore_ascii_case("csv")).unwrap_or(false);
+
+    // Exporting the ledger is itself a data export for the subject
+    let audit_service = AuditService::new(state.pool.clone());
+    let _ = audit_service.log_user_event(
+        actor_id,
+        AuditAction::DataExportRequested,
+        user_id,
+        None,
+        None,
+        Some(serde_json::json!({
+            "export": "privacy_ledger",
+            "format": if as_csv { "csv" } else { "json" },
+        })),
+    ).await;
+
+    if as_csv {
+        let filename = format!("attachment; filename=\"privacy-ledger-{}.csv\"", user_id);
+        return Ok((
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, filename),
+            ],
+            ledger.to_csv(),
+        ).into_response());
+    }
+
+    Ok(Json(ledger).into_response())
+}
+
+/// Default and maximum number of duplicate groups returned per match type
+const DEFAULT_DUPLICATE_GROUP_LIMIT: i64 = 100;
+const MAX_DUPLICATE_GROUP_LIMIT: i64 = 1000;
+
+/// Query parameters for the duplicate-account report
+#[derive(Debug, Deserialize)]
+pub struct DuplicateReportQuery {
+    /// Only report one signal: "email", "phone" or "webauthn"
+    pub match_type: Option<DuplicateMatchType>,
+    /// Maximum number of groups per match type (default 100, max 1000)
+    pub limit: Option<i64>,
+}
+
+/// GET /admin/users/duplicates - Report probable duplicate accounts (admin only)
+///
+/// Groups accounts sharing a normalized email (dots and `+tags` removed),
+/// a phone number or a WebAuthn public key. Email and phone keys are refreshed
+/// in batches by the background duplicate scan, so accounts created or changed
+/// since its last run may not be included yet.
+pub async fn duplicate_users_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<DuplicateReportQuery>,
+) -> Result<Json<DuplicateAccountReport>, UserManagementError> {
+    let actor_id = claims.user_id()
+        .map_err(|_| UserManagementError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
+
+    let admin_service = AdminService::new(state.pool.clone());
+    admin_service.verify_admin(actor_id).await?;
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DUPLICATE_GROUP_LIMIT)
+        .clamp(1, MAX_DUPLICATE_GROUP_LIMIT);
+
+    let service = DuplicateAccountService::new(state.pool.clone());
+    let report = service.build_report(query.match_type, limit).await?;
+
+    Ok(Json(report))
+}
+
+/// GET /admin/users/dormant - Report accounts without recent activity (admin only)
+///
+/// Activity is the latest sign-in or session use (the registration time if
+/// there was none). Accounts are listed least recently active first, whether
+/// or not the dormant account worker has flagged them yet.
+pub async fn dormant_users_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<DormantUserReportQuery>,
+) -> Result<Json<PaginatedResponse<DormantAccount>>, UserManagementError> {
+    let actor_id = claims.user_id()
+        .map_err(|_| UserManagementError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
+
+    let days = query.days.unwrap_or(state.config.dormant_account_days);
+    let (page, limit) = query.pagination().normalized();
+
+    let service = AdminService::new(state.pool.clone());
+    let response = service
+        .list_dormant_users(actor_id, days, query.flagged, page, limit)
+        .await?;
+
+    Ok(Json(
+        response
+            .with_sort("last_activity_at", "asc")
+            .with_filters(serde_json::json!({ "days": days, "flagged": query.flagged })),
+    ))
+}
+
 // ============================================================================
 // App CRUD Handlers
 // ============================================================================
@@ -326,16 +634,45 @@ pub async fn update_app_handler(
 }
 
 /// DELETE /admin/apps/{app_id} - Delete app permanently (admin only)
+///
+/// When `delete_app` needs approval, answers `202` with the pending request.
 pub async fn delete_app_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
     Path(app_id): Path<Uuid>,
-) -> Result<StatusCode, UserManagementError> {
+) -> Result<Response, UserManagementError> {
     let actor_id = claims.user_id()
         .map_err(|_| UserManagementError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
-    
-    let service = AdminService::new(state.pool.clone());
-    service.delete_app(actor_id, app_id).await?;
-    
-    Ok(StatusCode::NO_CONTENT)
+
+    let approvals = approval_service(&state);
+    if approvals.requires_approval(ADMIN_ACTION_DELETE_APP) {
+        let approval = approvals.request(actor_id, ADMIN_ACTION_DELETE_APP, app_id, None).await?;
+        return Ok(approval_pending(approval));
+    }
+
+    delete_app(&state, actor_id, app_id, None).await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// Delete an app and audit it
+///
+/// `approval_id` is the approval request the deletion executes, if any.
+pub(crate) async fn delete_app(
+    state: &AppState,
+    actor_id: Uuid,
+    app_id: Uuid,
+    approval_id: Option<Uuid>,
+) -> Result<(), UserManagementError> {
+    AdminService::new(state.pool.clone()).delete_app(actor_id, app_id).await?;
+
+    let _ = AuditService::new(state.pool.clone())
+        .log_app_event(
+            actor_id,
+            AuditAction::AppDeleted,
+            app_id,
+            Some(serde_json::json!({ "approval_id": approval_id })),
+        )
+        .await;
+
+    Ok(())
 }
@@ -0,0 +1,142 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::AppState;
+use crate::dto::user_management::AdminUpdateUserRequest;
+use crate::error::UserManagementError;
+use crate::handlers::admin;
+use crate::models::{
+    AdminApproval, ADMIN_ACTION_DELETE_APP, ADMIN_ACTION_DELETE_USER,
+    ADMIN_ACTION_GRANT_SYSTEM_ADMIN,
+};
+use crate::services::AdminApprovalService;
+use crate::utils::jwt::Claims;
+
+#[derive(Debug, Deserialize)]
+pub struct ApprovalListQuery {
+    /// pending, approved, rejected, failed or expired (default: all)
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApprovalListResponse {
+    pub approvals: Vec<AdminApproval>,
+}
+
+pub(crate) fn approval_service(state: &AppState) -> AdminApprovalService {
+    AdminApprovalService::new(
+        state.pool.clone(),
+        &state.config.admin_approval_actions,
+        state.config.admin_approval_ttl_secs,
+    )
+}
+
+/// `202 Accepted` carrying the approval request that now holds the action
+pub(crate) fn approval_pending(approval: AdminApproval) -> Response {
+    (StatusCode::ACCEPTED, Json(approval)).into_response()
+}
+
+fn actor_id(claims: &Claims) -> Result<Uuid, UserManagementError> {
+    claims.user_id()
+        .map_err(|_| UserManagementError::InternalError(anyhow::anyhow!("Invalid user ID in token")))
+}
+
+/// GET /admin/approvals - List approval requests, newest first
+pub async fn list_approvals_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<ApprovalListQuery>,
+) -> Result<Json<ApprovalListResponse>, UserManagementError> {
+    let actor_id = actor_id(&claims)?;
+
+    let approvals = approval_service(&state)
+        .list(actor_id, query.status.as_deref())
+        .await?;
+
+    Ok(Json(ApprovalListResponse { approvals }))
+}
+
+/// GET /admin/approvals/:approval_id - Get one approval request
+pub async fn get_approval_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(approval_id): Path<Uuid>,
+) -> Result<Json<AdminApproval>, UserManagementError> {
+    let actor_id = actor_id(&claims)?;
+
+    Ok(Json(approval_service(&state).get(actor_id, approval_id).await?))
+}
+
+/// POST /admin/approvals/:approval_id/approve - Approve and execute a pending action
+///
+/// The approver must be a different admin than the requester. The action runs
+/// as the approver; if it fails the request is marked `failed` and the error
+/// is returned.
+pub async fn approve_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(approval_id): Path<Uuid>,
+) -> Result<Json<AdminApproval>, UserManagementError> {
+    let actor_id = actor_id(&claims)?;
+
+    let service = approval_service(&state);
+    let approval = service.approve(actor_id, approval_id).await?;
+
+    if let Err(err) = execute(&state, actor_id, &approval).await {
+        let _ = service.record_failure(actor_id, &approval, &err).await;
+        return Err(err);
+    }
+
+    Ok(Json(approval))
+}
+
+/// POST /admin/approvals/:approval_id/reject - Reject a pending action
+pub async fn reject_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(approval_id): Path<Uuid>,
+) -> Result<Json<AdminApproval>, UserManagementError> {
+    let actor_id = actor_id(&claims)?;
+
+    Ok(Json(approval_service(&state).reject(actor_id, approval_id).await?))
+}
+
+/// Run the action an approval request was holding
+async fn execute(
+    state: &AppState,
+    actor_id: Uuid,
+    approval: &AdminApproval,
+) -> Result<(), UserManagementError> {
+    let approval_id = Some(approval.id);
+
+    match approval.action.as_str() {
+        ADMIN_ACTION_DELETE_USER => {
+            admin::delete_user(state, actor_id, approval.target_id, approval.param("mode"), approval_id)
+                .await
+        }
+        ADMIN_ACTION_GRANT_SYSTEM_ADMIN => {
+            let req = AdminUpdateUserRequest {
+                email: None,
+                is_active: None,
+                email_verified: None,
+                is_system_admin: Some(true),
+            };
+            admin::update_user(state, actor_id, approval.target_id, &req, approval_id)
+                .await
+                .map(|_| ())
+        }
+        ADMIN_ACTION_DELETE_APP => {
+            admin::delete_app(state, actor_id, approval.target_id, approval_id).await
+        }
+        other => Err(UserManagementError::InternalError(anyhow::anyhow!(
+            "Unknown approval action: {}",
+            other
+        ))),
+    }
+}
@@ -0,0 +1,196 @@
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::AppState;
+use crate::error::AppError;
+use crate::handlers::admin::require_system_admin;
+use crate::models::{AuditAction, BroadcastSegment, EmailBroadcast, EmailSuppression};
+use crate::services::{AuditService, BroadcastService};
+use crate::utils::jwt::Claims;
+
+#[derive(Debug, Deserialize)]
+pub struct BroadcastRequest {
+    /// Subject template
+    pub subject: String,
+    /// Body template (plain text, `{{email}}`, `{{name}}` and `{{app_name}}` placeholders)
+    pub body: String,
+    #[serde(default)]
+    pub segment: BroadcastSegment,
+    /// Only count the recipients and render a sample; nothing is sent
+    #[serde(default)]
+    pub preview: bool,
+    /// When to start sending (default: now)
+    pub scheduled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BroadcastListResponse {
+    pub broadcasts: Vec<EmailBroadcast>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuppressUserRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SuppressionListResponse {
+    pub suppressions: Vec<EmailSuppression>,
+}
+
+/// POST /admin/notifications/broadcast - Email a segment of users (admin only)
+///
+/// With `preview: true` returns the recipient count and the email rendered
+/// for the first recipient. Otherwise schedules the broadcast (`202`); the
+/// broadcast worker sends it at `BROADCAST_MAX_PER_MINUTE`, skipping users
+/// on the suppression list.
+pub async fn broadcast_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<BroadcastRequest>,
+) -> Result<Response, AppError> {
+    let actor_id = require_system_admin(&state, &claims).await?;
+    let service = BroadcastService::new(state.pool.clone());
+
+    if req.preview {
+        let preview = service.preview(&req.subject, &req.body, &req.segment).await?;
+        return Ok(Json(preview).into_response());
+    }
+
+    let broadcast = service
+        .schedule(&req.subject, &req.body, &req.segment, req.scheduled_at, actor_id)
+        .await?;
+
+    let _ = AuditService::new(state.pool.clone())
+        .log_broadcast_event(
+            actor_id,
+            AuditAction::BroadcastScheduled,
+            broadcast.id,
+            Some(serde_json::json!({
+                "subject": broadcast.subject,
+                "segment": broadcast.segment,
+                "scheduled_at": broadcast.scheduled_at,
+            })),
+        )
+        .await;
+
+    Ok((StatusCode::ACCEPTED, Json(broadcast)).into_response())
+}
+
+/// GET /admin/notifications/broadcasts - Recent broadcasts with delivery counts (admin only)
+pub async fn list_broadcasts_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<BroadcastListResponse>, AppError> {
+    require_system_admin(&state, &claims).await?;
+
+    let broadcasts = BroadcastService::new(state.pool.clone()).list().await?;
+    Ok(Json(BroadcastListResponse { broadcasts }))
+}
+
+/// GET /admin/notifications/broadcasts/:broadcast_id - One broadcast with delivery counts (admin only)
+pub async fn get_broadcast_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(broadcast_id): Path<Uuid>,
+) -> Result<Json<EmailBroadcast>, AppError> {
+    require_system_admin(&state, &claims).await?;
+
+    let broadcast = BroadcastService::new(state.pool.clone()).get(broadcast_id).await?;
+    Ok(Json(broadcast))
+}
+
+/// POST /admin/notifications/broadcasts/:broadcast_id/cancel - Stop a broadcast (admin only)
+///
+/// Emails already sent stay sent; queued recipients are skipped.
+pub async fn cancel_broadcast_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(broadcast_id): Path<Uuid>,
+) -> Result<Json<EmailBroadcast>, AppError> {
+    let actor_id = require_system_admin(&state, &claims).await?;
+
+    let broadcast = BroadcastService::new(state.pool.clone()).cancel(broadcast_id).await?;
+
+    let _ = AuditService::new(state.pool.clone())
+        .log_broadcast_event(
+            actor_id,
+            AuditAction::BroadcastCancelled,
+            broadcast.id,
+            Some(serde_json::json!({ "sent_count": broadcast.sent_count })),
+        )
+        .await;
+
+    Ok(Json(broadcast))
+}
+
+/// GET /admin/notifications/suppressions - Users who receive no broadcasts (admin only)
+pub async fn list_suppressions_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<SuppressionListResponse>, AppError> {
+    require_system_admin(&state, &claims).await?;
+
+    let suppressions = BroadcastService::new(state.pool.clone()).list_suppressions().await?;
+    Ok(Json(SuppressionListResponse { suppressions }))
+}
+
+/// PUT /admin/notifications/suppressions/:user_id - Stop sending broadcasts to a user (admin only)
+///
+/// Also applies to broadcasts already being sent.
+pub async fn suppress_user_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(user_id): Path<Uuid>,
+    Json(req): Json<SuppressUserRequest>,
+) -> Result<StatusCode, AppError> {
+    let actor_id = require_system_admin(&state, &claims).await?;
+
+    BroadcastService::new(state.pool.clone())
+        .suppress(user_id, req.reason.as_deref(), actor_id)
+        .await?;
+
+    let _ = AuditService::new(state.pool.clone())
+        .log_user_event(
+            actor_id,
+            AuditAction::EmailPreferencesChanged,
+            user_id,
+            None,
+            None,
+            Some(serde_json::json!({ "broadcasts": false, "reason": req.reason })),
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// DELETE /admin/notifications/suppressions/:user_id - Send broadcasts to a user again (admin only)
+pub async fn unsuppress_user_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(user_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let actor_id = require_system_admin(&state, &claims).await?;
+
+    BroadcastService::new(state.pool.clone()).unsuppress(user_id).await?;
+
+    let _ = AuditService::new(state.pool.clone())
+        .log_user_event(
+            actor_id,
+            AuditAction::EmailPreferencesChanged,
+            user_id,
+            None,
+            None,
+            Some(serde_json::json!({ "broadcasts": true })),
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,100 @@
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppState;
+use crate::error::AppError;
+use crate::handlers::admin::require_system_admin;
+use crate::utils::chaos::{self, ActiveFault, FaultKind};
+use crate::utils::jwt::Claims;
+
+#[derive(Debug, Serialize)]
+pub struct ChaosFaultsResponse {
+    /// Faults running on the instance that served this request
+    pub faults: Vec<ActiveFault>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InjectFaultRequest {
+    /// How long the fault runs (at most one hour)
+    pub duration_seconds: i64,
+    /// Delay added to each database connection checkout (db_latency)
+    pub delay_ms: Option<u64>,
+    /// Share of webhook deliveries or emails that fail, default 1 (webhook_failure, email_error)
+    pub failure_rate: Option<f64>,
+}
+
+/// The endpoints only exist in builds with the `chaos` feature
+fn require_chaos_build() -> Result<(), AppError> {
+    if !chaos::ENABLED {
+        return Err(AppError::NotFound("Fault injection is not built in".into()));
+    }
+
+    Ok(())
+}
+
+fn parse_fault(fault: &str) -> Result<FaultKind, AppError> {
+    FaultKind::from_str(fault).ok_or_else(|| AppError::NotFound(format!("Unknown fault '{}'", fault)))
+}
+
+/// GET /admin/chaos - Faults running on this instance (admin only, `chaos` builds)
+pub async fn list_chaos_faults_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ChaosFaultsResponse>, AppError> {
+    require_chaos_build()?;
+    require_system_admin(&state, &claims).await?;
+
+    Ok(Json(ChaosFaultsResponse { faults: chaos::active() }))
+}
+
+/// PUT /admin/chaos/:fault - Inject db_latency, webhook_failure or email_error for a bounded time (admin only, `chaos` builds)
+///
+/// Replaces a running fault of the same kind. Only the instance serving the
+/// request is affected.
+pub async fn inject_chaos_fault_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(fault): Path<String>,
+    Json(req): Json<InjectFaultRequest>,
+) -> Result<Json<ActiveFault>, AppError> {
+    require_chaos_build()?;
+    require_system_admin(&state, &claims).await?;
+
+    let fault = parse_fault(&fault)?;
+    let active = chaos::inject(fault, req.delay_ms, req.failure_rate, req.duration_seconds)
+        .map_err(AppError::ValidationError)?;
+
+    tracing::warn!(
+        "Chaos fault {} injected by {} until {} (delay_ms={:?}, failure_rate={:?})",
+        fault.as_str(),
+        claims.sub,
+        active.expires_at,
+        active.delay_ms,
+        active.failure_rate
+    );
+
+    Ok(Json(active))
+}
+
+/// DELETE /admin/chaos/:fault - Stop a fault before it expires (admin only, `chaos` builds)
+pub async fn clear_chaos_fault_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(fault): Path<String>,
+) -> Result<StatusCode, AppError> {
+    require_chaos_build()?;
+    require_system_admin(&state, &claims).await?;
+
+    let fault = parse_fault(&fault)?;
+    if !chaos::clear(fault) {
+        return Err(AppError::NotFound(format!("Fault '{}' is not running", fault.as_str())));
+    }
+
+    tracing::warn!("Chaos fault {} cleared by {}", fault.as_str(), claims.sub);
+
+    Ok(StatusCode::NO_CONTENT)
+}
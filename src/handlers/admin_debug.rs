@@ -0,0 +1,138 @@
+use axum::{
+    extract::{Extension, State},
+    Json,
+};
+use serde::Serialize;
+
+use crate::config::AppState;
+use crate::error::AppError;
+use crate::handlers::admin::require_system_admin;
+use crate::models::WorkerLeader;
+use crate::repositories::WorkerLeaderRepository;
+use crate::services::{AccessDecision, AccessSimulation, AccessSimulatorService, InstanceReport, InstanceService};
+use crate::utils::jwt::Claims;
+use crate::utils::route_table::{RouteInfo, GLOBAL_LAYERS, ROUTES};
+
+#[derive(Debug, Serialize)]
+pub struct DebugConfigResponse {
+    pub version: &'static str,
+    pub config: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DebugRoutesResponse {
+    pub global_layers: &'static [&'static str],
+    pub routes: &'static [RouteInfo],
+}
+
+#[derive(Debug, Serialize)]
+pub struct DebugWorkersResponse {
+    /// Identity of the instance serving this request
+    pub instance_id: String,
+    pub workers: Vec<WorkerLeaderStatus>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkerLeaderStatus {
+    #[serde(flatten)]
+    pub leader: WorkerLeader,
+    /// Whether the instance serving this request is the active leader
+    pub is_this_instance: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InstanceResponse {
+    /// Report the heartbeat sends, exactly as sent
+    #[serde(flatten)]
+    pub report: InstanceReport,
+    /// Whether this instance sends it (HEARTBEAT_URL is set)
+    pub heartbeat_enabled: bool,
+    pub heartbeat_interval_secs: u64,
+}
+
+/// GET /admin/debug/config - Running configuration with secrets redacted (admin only)
+pub async fn debug_config_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<DebugConfigResponse>, AppError> {
+    require_system_admin(&state, &claims).await?;
+
+    let config = serde_json::to_value(state.config.as_ref())
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+    Ok(Json(DebugConfigResponse {
+        version: env!("CARGO_PKG_VERSION"),
+        config,
+    }))
+}
+
+/// GET /admin/debug/routes - Mounted route table with auth requirements (admin only)
+pub async fn debug_routes_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<DebugRoutesResponse>, AppError> {
+    require_system_admin(&state, &claims).await?;
+
+    Ok(Json(DebugRoutesResponse {
+        global_layers: GLOBAL_LAYERS,
+        routes: ROUTES,
+    }))
+}
+
+/// GET /admin/debug/workers - Which instance leads each background worker (admin only)
+pub async fn debug_workers_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<DebugWorkersResponse>, AppError> {
+    require_system_admin(&state, &claims).await?;
+
+    let instance_id = state.config.instance_id.clone();
+    let workers = WorkerLeaderRepository::new(state.pool.clone())
+        .list()
+        .await?
+        .into_iter()
+        .map(|leader| WorkerLeaderStatus {
+            is_this_instance: leader.active && leader.instance_id == instance_id,
+            leader,
+        })
+        .collect();
+
+    Ok(Json(DebugWorkersResponse { instance_id, workers }))
+}
+
+/// POST /admin/access-simulator - What a sign-in to an app would decide, and why (admin only)
+///
+/// Read-only: replays login and RBAC checks for the given user, app,
+/// permission, IP, device and acr without recording attempts or issuing tokens.
+pub async fn access_simulator_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<AccessSimulation>,
+) -> Result<Json<AccessDecision>, AppError> {
+    require_system_admin(&state, &claims).await?;
+
+    let decision = AccessSimulatorService::new(state.pool.clone())
+        .with_verified_email_required(state.config.login_require_verified_email)
+        .simulate(&req)
+        .await?;
+
+    Ok(Json(decision))
+}
+
+/// GET /admin/instance - Version, enabled features and coarse counts, as sent by the usage heartbeat (admin only)
+pub async fn instance_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<InstanceResponse>, AppError> {
+    require_system_admin(&state, &claims).await?;
+
+    let report = InstanceService::new(state.pool.clone(), state.config.clone())
+        .report()
+        .await?;
+
+    Ok(Json(InstanceResponse {
+        report,
+        heartbeat_enabled: !state.config.heartbeat_url.is_empty(),
+        heartbeat_interval_secs: state.config.heartbeat_interval_secs,
+    }))
+}
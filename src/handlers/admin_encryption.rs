@@ -0,0 +1,99 @@
+use axum::{
+    extract::{Extension, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppState;
+use crate::error::AppError;
+use crate::handlers::admin::require_system_admin;
+use crate::repositories::field_encryption::{ColumnEncryptionStatus, RotationBatch};
+use crate::repositories::FieldEncryptionRepository;
+use crate::utils::field_crypto::{field_cipher, EncryptedColumn};
+use crate::utils::jwt::Claims;
+
+/// Default rows re-encrypted per rotation call
+const DEFAULT_ROTATION_BATCH: i64 = 500;
+
+/// Largest rotation batch accepted
+const MAX_ROTATION_BATCH: i64 = 5000;
+
+#[derive(Debug, Serialize)]
+pub struct EncryptionStatusResponse {
+    pub enabled: bool,
+    /// Key used for new values (null when encryption is disabled)
+    pub active_key_id: Option<String>,
+    pub columns: Vec<ColumnEncryptionStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RotateEncryptionRequest {
+    /// Column to re-encrypt, e.g. `user_phone` or `webhook_secret`
+    pub column: EncryptedColumn,
+    /// Resume after this row ID (`next_after` of the previous call)
+    pub after: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotateEncryptionResponse {
+    pub column: &'static str,
+    pub active_key_id: String,
+    #[serde(flatten)]
+    pub batch: RotationBatch,
+}
+
+/// GET /admin/encryption/status - Plaintext, current and retired-key values per column (admin only)
+pub async fn encryption_status_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<EncryptionStatusResponse>, AppError> {
+    require_system_admin(&state, &claims).await?;
+
+    let active_key_id = field_cipher().map(|c| c.active_key_id().to_string());
+    let repo = FieldEncryptionRepository::new(state.pool.clone());
+
+    let mut columns = Vec::with_capacity(EncryptedColumn::ALL.len());
+    for column in EncryptedColumn::ALL {
+        columns.push(repo.status(column, active_key_id.as_deref()).await?);
+    }
+
+    Ok(Json(EncryptionStatusResponse {
+        enabled: active_key_id.is_some(),
+        active_key_id,
+        columns,
+    }))
+}
+
+/// POST /admin/encryption/rotate - Re-encrypt one batch of a column under the active key (admin only)
+///
+/// Call repeatedly with `after` set to the returned `next_after` until it is null.
+pub async fn rotate_encryption_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<RotateEncryptionRequest>,
+) -> Result<Json<RotateEncryptionResponse>, AppError> {
+    require_system_admin(&state, &claims).await?;
+
+    let cipher = field_cipher()
+        .ok_or_else(|| AppError::ValidationError("Field encryption keys are not configured".into()))?;
+    let limit = req.limit.unwrap_or(DEFAULT_ROTATION_BATCH).clamp(1, MAX_ROTATION_BATCH);
+
+    let batch = FieldEncryptionRepository::new(state.pool.clone())
+        .rotate_batch(cipher, req.column, req.after.as_deref(), limit)
+        .await?;
+
+    tracing::info!(
+        "Rotated {} of {} values in {} (failed: {})",
+        batch.rewritten,
+        batch.scanned,
+        req.column.name(),
+        batch.failed
+    );
+
+    Ok(Json(RotateEncryptionResponse {
+        column: req.column.name(),
+        active_key_id: cipher.active_key_id().to_string(),
+        batch,
+    }))
+}
@@ -0,0 +1,358 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::AppState;
+use crate::error::{AppError, AuthError};
+use crate::models::OAuthEventType;
+use crate::repositories::{
+    OAuthAuditLogRepository, OAuthClientRepository, RedirectUriBlockRepository, UserRepository,
+};
+use crate::services::{OAuthStats, OAuthStatsService, StatsWindow};
+use crate::utils::jwt::Claims;
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSkipConsentRequest {
+    pub skip_consent: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClientTrustResponse {
+    pub client_id: String,
+    pub is_internal: bool,
+    pub skip_consent: bool,
+}
+
+/// Longest maximum secret age an admin can set (10 years)
+const MAX_SECRET_MAX_AGE_DAYS: i64 = 3650;
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSecretPolicyRequest {
+    /// Maximum secret age in days; 0 = never expires, null = server default
+    pub max_age_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClientSecretPolicyResponse {
+    pub client_id: String,
+    /// Client's own maximum age (null = server default)
+    pub secret_max_age_days: Option<i64>,
+    /// Maximum age in force for the client (0 = never expires)
+    pub effective_max_age_days: i64,
+    pub secret_created_at: chrono::DateTime<chrono::Utc>,
+    pub secret_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OAuthStatsQuery {
+    /// 1h, 24h (default), 7d or 30d
+    pub window: Option<String>,
+    /// Restrict the statistics to one client
+    pub client_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRedirectUriBlockRequest {
+    pub pattern: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RedirectUriBlockResponse {
+    pub id: String,
+    pub pattern: String,
+    pub reason: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: String,
+}
+
+/// PUT /admin/oauth-clients/:client_id/skip-consent - Mark an internal client as first-party (admin only)
+///
+/// First-party clients skip the consent screen; an implicit grant is still
+/// recorded for every authorization so it stays auditable and revocable.
+/// Only internal clients can be trusted this way.
+pub async fn update_skip_consent_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(client_id): Path<String>,
+    Json(req): Json<UpdateSkipConsentRequest>,
+) -> Result<Json<ClientTrustResponse>, AppError> {
+    let user_id = claims.user_id()?;
+
+    let user_repo = UserRepository::new(state.pool.clone());
+    let user = user_repo.find_by_id(user_id).await?
+        .ok_or(AuthError::UserNotFound)?;
+
+    if !user.is_system_admin {
+        return Err(AppError::Auth(AuthError::NotSystemAdmin));
+    }
+
+    let client_repo = OAuthClientRepository::new(state.pool.clone());
+    let client = client_repo
+        .find_by_client_id(&client_id)
+        .await
+        .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?
+        .ok_or_else(|| AppError::NotFound("OAuth client not found".into()))?;
+
+    if req.skip_consent && !client.is_internal {
+        return Err(AppError::ValidationError(
+            "Only internal clients can skip consent".into(),
+        ));
+    }
+
+    client_repo
+        .update_skip_consent(client.id, req.skip_consent)
+        .await
+        .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?;
+
+    let audit_repo = OAuthAuditLogRepository::new(state.pool.clone());
+    audit_repo
+        .create(
+            OAuthEventType::ClientTrustUpdated,
+            Some(client.id),
+            Some(user_id),
+            None,
+            Some(serde_json::json!({
+                "skip_consent": req.skip_consent,
+                "previous": client.skip_consent,
+            })),
+        )
+        .await
+        .ok();
+
+    Ok(Json(ClientTrustResponse {
+        client_id: client.client_id,
+        is_internal: client.is_internal,
+        skip_consent: req.skip_consent,
+    }))
+}
+
+/// PUT /admin/oauth-clients/:client_id/secret-policy - Set a client's maximum secret age (admin only)
+///
+/// Secrets older than the maximum age are rejected at the token endpoint
+/// with `client_secret_expired` until the owner rotates them. The owner is
+/// emailed `CLIENT_SECRET_EXPIRY_WARNING_DAYS` before expiry.
+pub async fn update_secret_policy_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(client_id): Path<String>,
+    Json(req): Json<UpdateSecretPolicyRequest>,
+) -> Result<Json<ClientSecretPolicyResponse>, AppError> {
+    let user_id = claims.user_id()?;
+
+    let user_repo = UserRepository::new(state.pool.clone());
+    let user = user_repo.find_by_id(user_id).await?
+        .ok_or(AuthError::UserNotFound)?;
+
+    if !user.is_system_admin {
+        return Err(AppError::Auth(AuthError::NotSystemAdmin));
+    }
+
+    if let Some(days) = req.max_age_days {
+        if !(0..=MAX_SECRET_MAX_AGE_DAYS).contains(&days) {
+            return Err(AppError::ValidationError(format!(
+                "max_age_days must be between 0 and {}",
+                MAX_SECRET_MAX_AGE_DAYS
+            )));
+        }
+    }
+
+    let client_repo = OAuthClientRepository::new(state.pool.clone());
+    let client = client_repo
+        .find_by_client_id(&client_id)
+        .await
+        .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?
+        .ok_or_else(|| AppError::NotFound("OAuth client not found".into()))?;
+
+    client_repo
+        .update_secret_max_age(client.id, req.max_age_days)
+        .await
+        .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?;
+
+    let audit_repo = OAuthAuditLogRepository::new(state.pool.clone());
+    audit_repo
+        .create(
+            OAuthEventType::ClientSecretPolicyUpdated,
+            Some(client.id),
+            Some(user_id),
+            None,
+            Some(serde_json::json!({
+                "max_age_days": req.max_age_days,
+                "previous": client.secret_max_age_days,
+            })),
+        )
+        .await
+        .ok();
+
+    let default_max_age_days = state.config.client_secret_max_age_days;
+    let client = crate::models::OAuthClient {
+        secret_max_age_days: req.max_age_days,
+        ..client
+    };
+
+    Ok(Json(ClientSecretPolicyResponse {
+        client_id: client.client_id.clone(),
+        secret_max_age_days: client.secret_max_age_days,
+        effective_max_age_days: client.secret_max_age_days.unwrap_or(default_max_age_days),
+        secret_created_at: client.secret_created_at,
+        secret_expires_at: client.secret_expires_at(default_max_age_days),
+    }))
+}
+
+/// GET /admin/redirect-uri-blocklist - List blocked redirect URI patterns (admin only)
+pub async fn list_redirect_uri_blocks_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<RedirectUriBlockResponse>>, AppError> {
+    let user_id = claims.user_id()?;
+
+    let user_repo = UserRepository::new(state.pool.clone());
+    let user = user_repo.find_by_id(user_id).await?
+        .ok_or(AuthError::UserNotFound)?;
+
+    if !user.is_system_admin {
+        return Err(AppError::Auth(AuthError::NotSystemAdmin));
+    }
+
+    let block_repo = RedirectUriBlockRepository::new(state.pool.clone());
+    let blocks = block_repo.list_all().await
+        .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?;
+
+    let response = blocks
+        .into_iter()
+        .map(|b| RedirectUriBlockResponse {
+            id: b.id.to_string(),
+            pattern: b.pattern,
+            reason: b.reason,
+            created_by: b.created_by.map(|u| u.to_string()),
+            created_at: b.created_at.to_rfc3339(),
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+/// POST /admin/redirect-uri-blocklist - Block a redirect URI pattern (admin only)
+///
+/// Applies to new registrations and to authorization requests for clients
+/// that already registered a matching URI.
+pub async fn create_redirect_uri_block_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<CreateRedirectUriBlockRequest>,
+) -> Result<(StatusCode, Json<RedirectUriBlockResponse>), AppError> {
+    let user_id = claims.user_id()?;
+
+    let user_repo = UserRepository::new(state.pool.clone());
+    let user = user_repo.find_by_id(user_id).await?
+        .ok_or(AuthError::UserNotFound)?;
+
+    if !user.is_system_admin {
+        return Err(AppError::Auth(AuthError::NotSystemAdmin));
+    }
+
+    let pattern = req.pattern.trim();
+    if pattern.is_empty() || pattern.len() > 255 || pattern.contains(char::is_whitespace) {
+        return Err(AppError::ValidationError(
+            "Pattern must be 1-255 characters without whitespace".into(),
+        ));
+    }
+
+    let block_repo = RedirectUriBlockRepository::new(state.pool.clone());
+    let block = block_repo
+        .create(pattern, req.reason.as_deref(), Some(user_id))
+        .await
+        .map_err(|e| match e {
+            crate::error::OAuthError::InvalidRequest(msg) => AppError::ValidationError(msg),
+            e => AppError::InternalError(anyhow::anyhow!("{}", e)),
+        })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(RedirectUriBlockResponse {
+            id: block.id.to_string(),
+            pattern: block.pattern,
+            reason: block.reason,
+            created_by: block.created_by.map(|u| u.to_string()),
+            created_at: block.created_at.to_rfc3339(),
+        }),
+    ))
+}
+
+/// DELETE /admin/redirect-uri-blocklist/:id - Remove a blocked pattern (admin only)
+pub async fn delete_redirect_uri_block_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let user_id = claims.user_id()?;
+
+    let user_repo = UserRepository::new(state.pool.clone());
+    let user = user_repo.find_by_id(user_id).await?
+        .ok_or(AuthError::UserNotFound)?;
+
+    if !user.is_system_admin {
+        return Err(AppError::Auth(AuthError::NotSystemAdmin));
+    }
+
+    let block_id = Uuid::parse_str(&id)
+        .map_err(|_| AppError::ValidationError("Invalid blocklist entry ID".into()))?;
+
+    let block_repo = RedirectUriBlockRepository::new(state.pool.clone());
+    block_repo.delete(block_id).await.map_err(|e| match e {
+        crate::error::OAuthError::InvalidRequest(msg) => AppError::NotFound(msg),
+        e => AppError::InternalError(anyhow::anyhow!("{}", e)),
+    })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /admin/oauth/stats - Grant, error and consent statistics from the OAuth audit log (admin only)
+///
+/// Totals, per-client breakdowns (grants per grant type, token endpoint
+/// errors per code, consent denial rate) and a time series over the window.
+pub async fn oauth_stats_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<OAuthStatsQuery>,
+) -> Result<Json<OAuthStats>, AppError> {
+    let user_id = claims.user_id()?;
+
+    let user_repo = UserRepository::new(state.pool.clone());
+    let user = user_repo.find_by_id(user_id).await?
+        .ok_or(AuthError::UserNotFound)?;
+
+    if !user.is_system_admin {
+        return Err(AppError::Auth(AuthError::NotSystemAdmin));
+    }
+
+    let window = match query.window.as_deref() {
+        None => StatsWindow::Day,
+        Some(window) => StatsWindow::parse(window).ok_or_else(|| {
+            AppError::ValidationError("window must be one of 1h, 24h, 7d, 30d".into())
+        })?,
+    };
+
+    let client_id = match query.client_id.as_deref() {
+        None => None,
+        Some(client_id) => {
+            let client = OAuthClientRepository::new(state.pool.clone())
+                .find_by_client_id(client_id)
+                .await
+                .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?
+                .ok_or_else(|| AppError::NotFound("OAuth client not found".into()))?;
+            Some(client.id)
+        }
+    };
+
+    let stats = OAuthStatsService::new(state.pool.clone())
+        .stats(window, client_id)
+        .await
+        .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?;
+
+    Ok(Json(stats))
+}
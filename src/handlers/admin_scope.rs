@@ -7,7 +7,9 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::config::AppState;
+use crate::dto::{has_more, AppliedSort, MAX_PAGE_LIMIT};
 use crate::error::{AppError, AuthError};
+use crate::models::{SCOPE_VISIBILITY_GLOBAL, SCOPE_VISIBILITY_PENDING, SCOPE_VISIBILITY_PRIVATE};
 use crate::repositories::{OAuthScopeRepository, UserRepository};
 use crate::utils::jwt::Claims;
 
@@ -28,6 +30,10 @@ pub struct ScopeResponse {
     pub code: String,
     pub description: String,
     pub is_active: bool,
+    /// Client owning this custom scope (null for standard scopes)
+    pub owner_client_id: Option<String>,
+    /// `private`, `pending` or `global`
+    pub visibility: String,
     pub created_at: String,
 }
 
@@ -37,6 +43,8 @@ pub struct ListScopesAdminResponse {
     pub total: u64,
     pub page: u32,
     pub limit: u32,
+    pub has_more: bool,
+    pub sort: AppliedSort,
 }
 
 #[derive(Debug, Deserialize)]
@@ -61,8 +69,8 @@ pub async fn list_all_scopes_handler(
         return Err(AppError::Auth(AuthError::NotSystemAdmin));
     }
 
-    let page = params.page.unwrap_or(1);
-    let limit = params.limit.unwrap_or(20).min(100);
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = params.limit.unwrap_or(20).clamp(1, MAX_PAGE_LIMIT);
 
     let scope_repo = OAuthScopeRepository::new(state.pool.clone());
     let scopes = scope_repo.list_all(page, limit).await
@@ -77,6 +85,8 @@ pub async fn list_all_scopes_handler(
             code: s.code,
             description: s.description,
             is_active: s.is_active,
+            owner_client_id: s.owner_client_id.map(|id| id.to_string()),
+            visibility: s.visibility,
             created_at: s.created_at.to_rfc3339(),
         })
         .collect();
@@ -86,6 +96,8 @@ pub async fn list_all_scopes_handler(
         total,
         page,
         limit,
+        has_more: has_more(page, limit, total),
+        sort: AppliedSort { by: "code".to_string(), order: "asc".to_string() },
     }))
 }
 
@@ -124,6 +136,8 @@ pub async fn create_scope_handler(
             code: scope.code,
             description: scope.description,
             is_active: scope.is_active,
+            owner_client_id: scope.owner_client_id.map(|id| id.to_string()),
+            visibility: scope.visibility,
             created_at: scope.created_at.to_rfc3339(),
         }),
     ))
@@ -160,6 +174,8 @@ pub async fn get_scope_handler(
         code: scope.code,
         description: scope.description,
         is_active: scope.is_active,
+        owner_client_id: scope.owner_client_id.map(|id| id.to_string()),
+        visibility: scope.visibility,
         created_at: scope.created_at.to_rfc3339(),
     }))
 }
@@ -197,6 +213,8 @@ pub async fn update_scope_handler(
         code: scope.code,
         description: scope.description,
         is_active: scope.is_active,
+        owner_client_id: scope.owner_client_id.map(|id| id.to_string()),
+        visibility: scope.visibility,
         created_at: scope.created_at.to_rfc3339(),
     }))
 }
@@ -278,3 +296,101 @@ pub async fn delete_scope_handler(
 
     Ok(Json(serde_json::json!({ "message": "Scope deleted" })))
 }
+
+/// GET /admin/scopes/pending - Custom scopes awaiting global approval (admin only)
+pub async fn list_pending_scopes_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<ScopeResponse>>, AppError> {
+    let user_id = claims.user_id()?;
+    
+    let user_repo = UserRepository::new(state.pool.clone());
+    let user = user_repo.find_by_id(user_id).await?
+        .ok_or(AuthError::UserNotFound)?;
+    
+    if !user.is_system_admin {
+        return Err(AppError::Auth(AuthError::NotSystemAdmin));
+    }
+
+    let scope_repo = OAuthScopeRepository::new(state.pool.clone());
+    let scopes = scope_repo.list_by_visibility(SCOPE_VISIBILITY_PENDING).await
+        .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?;
+
+    Ok(Json(
+        scopes
+            .into_iter()
+            .map(|s| ScopeResponse {
+                id: s.id.to_string(),
+                code: s.code,
+                description: s.description,
+                is_active: s.is_active,
+                owner_client_id: s.owner_client_id.map(|id| id.to_string()),
+                visibility: s.visibility,
+                created_at: s.created_at.to_rfc3339(),
+            })
+            .collect(),
+    ))
+}
+
+/// POST /admin/scopes/:id/approve - Make a pending custom scope global (admin only)
+pub async fn approve_scope_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<Json<ScopeResponse>, AppError> {
+    review_pending_scope(&state, &claims, &id, SCOPE_VISIBILITY_GLOBAL).await
+}
+
+/// POST /admin/scopes/:id/reject - Keep a pending custom scope private to its client (admin only)
+pub async fn reject_scope_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<Json<ScopeResponse>, AppError> {
+    review_pending_scope(&state, &claims, &id, SCOPE_VISIBILITY_PRIVATE).await
+}
+
+/// Resolve a pending global-visibility request
+async fn review_pending_scope(
+    state: &AppState,
+    claims: &Claims,
+    id: &str,
+    visibility: &str,
+) -> Result<Json<ScopeResponse>, AppError> {
+    let user_id = claims.user_id()?;
+    
+    let user_repo = UserRepository::new(state.pool.clone());
+    let user = user_repo.find_by_id(user_id).await?
+        .ok_or(AuthError::UserNotFound)?;
+    
+    if !user.is_system_admin {
+        return Err(AppError::Auth(AuthError::NotSystemAdmin));
+    }
+
+    let scope_id = Uuid::parse_str(id)
+        .map_err(|_| AppError::ValidationError("Invalid scope ID".into()))?;
+
+    let scope_repo = OAuthScopeRepository::new(state.pool.clone());
+    let scope = scope_repo
+        .find_by_id(scope_id)
+        .await
+        .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?
+        .ok_or_else(|| AppError::NotFound("Scope not found".into()))?;
+
+    if scope.visibility != SCOPE_VISIBILITY_PENDING {
+        return Err(AppError::ValidationError("Scope is not pending approval".into()));
+    }
+
+    let scope = scope_repo.set_visibility(scope_id, visibility).await
+        .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?;
+
+    Ok(Json(ScopeResponse {
+        id: scope.id.to_string(),
+        code: scope.code,
+        description: scope.description,
+        is_active: scope.is_active,
+        owner_client_id: scope.owner_client_id.map(|id| id.to_string()),
+        visibility: scope.visibility,
+        created_at: scope.created_at.to_rfc3339(),
+    }))
+}
@@ -0,0 +1,164 @@
+use axum::{
+    extract::{Extension, Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppState;
+use crate::error::AppError;
+use crate::handlers::admin::require_system_admin;
+use crate::models::SigningKeyRecord;
+use crate::services::signing_key::SigningKeyInfo;
+use crate::services::{SigningKeyPolicy, SigningKeyService};
+use crate::utils::jwt::Claims;
+use crate::utils::signing_key::Jwk;
+
+#[derive(Debug, Serialize)]
+pub struct ListSigningKeysResponse {
+    /// Key new tokens are signed with on this instance
+    pub signing_kid: String,
+    pub keys: Vec<SigningKeyInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportSigningKeyRequest {
+    /// RSA private key, PEM (PKCS#8 or PKCS#1)
+    pub private_key_pem: String,
+    /// Matching public key, PEM (SubjectPublicKeyInfo)
+    pub public_key_pem: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PromoteSigningKeyRequest {
+    /// Promote even if cached JWKS copies may not contain the key yet
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SigningKeyPublicResponse {
+    #[serde(flatten)]
+    pub key: SigningKeyRecord,
+    pub jwk: Jwk,
+}
+
+fn signing_key_service(state: &AppState) -> SigningKeyService {
+    SigningKeyService::new(
+        state.pool.clone(),
+        state.jwt_manager.clone(),
+        SigningKeyPolicy::from_config(&state.config),
+    )
+}
+
+/// GET /admin/signing-keys - Signing keys with their state and fingerprints (admin only)
+pub async fn list_signing_keys_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ListSigningKeysResponse>, AppError> {
+    require_system_admin(&state, &claims).await?;
+
+    let keys = signing_key_service(&state).list().await?;
+
+    Ok(Json(ListSigningKeysResponse {
+        signing_kid: state.jwt_manager.signing_kid(),
+        keys,
+    }))
+}
+
+/// POST /admin/signing-keys - Import an externally generated key pair (admin only)
+///
+/// The key is stored but neither published nor used until staged and promoted.
+pub async fn import_signing_key_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<ImportSigningKeyRequest>,
+) -> Result<Json<SigningKeyRecord>, AppError> {
+    require_system_admin(&state, &claims).await?;
+    let admin_id = claims.user_id()?;
+
+    let key = signing_key_service(&state)
+        .import(&req.private_key_pem, &req.public_key_pem, Some(admin_id))
+        .await?;
+
+    tracing::info!("Signing key {} (SHA-256 {}) imported by {}", key.kid, key.fingerprint, admin_id);
+
+    Ok(Json(key))
+}
+
+/// GET /admin/signing-keys/:kid - Export a key's public PEM and JWK (admin only)
+pub async fn get_signing_key_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(kid): Path<String>,
+) -> Result<Json<SigningKeyPublicResponse>, AppError> {
+    require_system_admin(&state, &claims).await?;
+
+    let key = signing_key_service(&state).find(&kid).await?;
+
+    Ok(Json(SigningKeyPublicResponse {
+        jwk: Jwk::rsa(&key.jwk_n, &key.jwk_e),
+        key,
+    }))
+}
+
+/// POST /admin/signing-keys/:kid/stage - Publish an imported key in the JWKS as next (admin only)
+pub async fn stage_signing_key_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(kid): Path<String>,
+) -> Result<Json<SigningKeyRecord>, AppError> {
+    require_system_admin(&state, &claims).await?;
+
+    let key = signing_key_service(&state).stage(&kid).await?;
+
+    tracing::info!("Signing key {} staged as next by {}", kid, claims.sub);
+
+    Ok(Json(key))
+}
+
+/// POST /admin/signing-keys/:kid/promote - Sign with the staged next key (admin only)
+///
+/// The previously active key is retired but keeps verifying for
+/// SIGNING_KEY_RETENTION_SECS.
+pub async fn promote_signing_key_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(kid): Path<String>,
+    req: Option<Json<PromoteSigningKeyRequest>>,
+) -> Result<Json<SigningKeyRecord>, AppError> {
+    require_system_admin(&state, &claims).await?;
+    let force = req.map(|Json(r)| r.force).unwrap_or(false);
+
+    let key = signing_key_service(&state).promote(&kid, force).await?;
+
+    tracing::warn!("Signing key {} promoted to active by {} (force: {})", kid, claims.sub, force);
+
+    Ok(Json(key))
+}
+
+/// POST /admin/keys/rotate - Generate a new key and stage it as next (admin only)
+///
+/// The signing key worker promotes it once cached JWKS copies include it;
+/// `{"force": true}` promotes it right away. The retired key keeps
+/// verifying for SIGNING_KEY_RETENTION_SECS.
+pub async fn rotate_signing_key_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    req: Option<Json<PromoteSigningKeyRequest>>,
+) -> Result<Json<SigningKeyRecord>, AppError> {
+    require_system_admin(&state, &claims).await?;
+    let admin_id = claims.user_id()?;
+    let force = req.map(|Json(r)| r.force).unwrap_or(false);
+
+    let key = signing_key_service(&state).rotate(Some(admin_id), force).await?;
+
+    tracing::warn!(
+        "Signing key {} (SHA-256 {}) generated by {} and {}",
+        key.kid,
+        key.fingerprint,
+        admin_id,
+        if force { "promoted to active" } else { "staged as next" }
+    );
+
+    Ok(Json(key))
+}
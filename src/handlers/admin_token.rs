@@ -0,0 +1,28 @@
+use axum::{
+    extract::{Extension, Path, State},
+    Json,
+};
+
+use crate::config::AppState;
+use crate::error::AppError;
+use crate::handlers::admin::require_system_admin;
+use crate::services::{TokenLineage, TokenLineageService};
+use crate::utils::jwt::Claims;
+
+/// GET /admin/tokens/:jti/lineage - Trace a token from its login through refreshes to revocation (admin only)
+///
+/// Returns every recorded token of the family the token belongs to, in issue order.
+pub async fn token_lineage_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(jti): Path<String>,
+) -> Result<Json<TokenLineage>, AppError> {
+    require_system_admin(&state, &claims).await?;
+
+    let lineage = TokenLineageService::new(state.pool.clone())
+        .lineage(&jti)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Token not found in the lineage".to_string()))?;
+
+    Ok(Json(lineage))
+}
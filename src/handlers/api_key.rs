@@ -1,6 +1,6 @@
 use axum::{
     extract::{Extension, Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     Json,
 };
 use uuid::Uuid;
@@ -8,22 +8,35 @@ use uuid::Uuid;
 use crate::config::AppState;
 use crate::dto::{CreateApiKeyRequest, UpdateApiKeyRequest, ApiKeyResponse, ApiKeyWithSecretResponse};
 use crate::error::AppError;
-use crate::services::ApiKeyService;
+use crate::handlers::auth::{extract_ip_address, extract_user_agent};
+use crate::services::{ApiKeyService, CoolingOffService};
+use crate::utils::cooling_off::{CoolingOffPolicy, HighRiskAction};
 use crate::utils::jwt::Claims;
 
 /// POST /apps/:app_id/api-keys - Create API key
 pub async fn create_api_key_handler(
     State(state): State<AppState>,
-    Extension(_claims): Extension<Claims>,
+    Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
     Path(app_id): Path<Uuid>,
     Json(req): Json<CreateApiKeyRequest>,
 ) -> Result<(StatusCode, Json<ApiKeyWithSecretResponse>), AppError> {
+    CoolingOffService::new(state.pool.clone(), CoolingOffPolicy::from_config(&state.config))
+        .check(
+            claims.user_id()?,
+            HighRiskAction::ApiKeyCreation,
+            extract_ip_address(&headers).as_deref(),
+            extract_user_agent(&headers).as_deref(),
+        )
+        .await?;
+
     let service = ApiKeyService::new(state.pool.clone());
     let (api_key, key) = service.create_api_key(
         app_id,
         &req.name,
         req.scopes,
         req.expires_at,
+        req.access_window.as_deref(),
     ).await?;
 
     Ok((
@@ -36,6 +49,7 @@ pub async fn create_api_key_handler(
             key_prefix: api_key.key_prefix,
             scopes: api_key.scopes.0,
             expires_at: api_key.expires_at,
+            access_window: api_key.access_window,
             is_active: api_key.is_active,
             created_at: api_key.created_at,
         }),
@@ -60,6 +74,7 @@ pub async fn list_api_keys_handler(
             key_prefix: k.key_prefix,
             scopes: k.scopes.0,
             expires_at: k.expires_at,
+            access_window: k.access_window,
             last_used_at: k.last_used_at,
             is_active: k.is_active,
             created_at: k.created_at,
@@ -86,6 +101,7 @@ pub async fn get_api_key_handler(
         key_prefix: key.key_prefix,
         scopes: key.scopes.0,
         expires_at: key.expires_at,
+        access_window: key.access_window,
         last_used_at: key.last_used_at,
         is_active: key.is_active,
         created_at: key.created_at,
@@ -105,6 +121,7 @@ pub async fn update_api_key_handler(
         req.name.as_deref(),
         req.scopes,
         req.is_active,
+        req.access_window.as_deref(),
     ).await?;
 
     Ok(Json(ApiKeyResponse {
@@ -114,6 +131,7 @@ pub async fn update_api_key_handler(
         key_prefix: key.key_prefix,
         scopes: key.scopes.0,
         expires_at: key.expires_at,
+        access_window: key.access_window,
         last_used_at: key.last_used_at,
         is_active: key.is_active,
         created_at: key.created_at,
@@ -12,35 +12,33 @@ use uuid::Uuid;
 use serde::{Deserialize, Serialize};
 
 use crate::config::AppState;
-use crate::dto::{PaginationQuery, UserAppResponse};
-use crate::error::AppError;
+use crate::dto::{AppUserListQuery, PaginatedResponse, UserAppResponse};
+use crate::error::{AppError, RoleError, UserManagementError};
 use crate::middleware::ApiKeyContext;
-use crate::services::{UserManagementService, RoleService, api_key_scopes};
+use crate::models::{
+    ProvisioningEvent, RoleChangeActor, PROVISIONING_EVENT_ROLE_SYNC, PROVISIONING_EVENT_USER_DEPROVISION,
+};
+use crate::services::{ProvisioningService, UserManagementService, RoleService, api_key_scopes};
 
 /// GET /api/v1/users - List users in app (requires read:users scope)
 pub async fn list_users_api_key_handler(
     State(state): State<AppState>,
     api_key: ApiKeyContext,
-    Query(pagination): Query<PaginationQuery>,
-) -> Result<Json<ListUsersResponse>, AppError> {
+    Query(query): Query<AppUserListQuery>,
+) -> Result<Json<PaginatedResponse<UserAppResponse>>, AppError> {
     // Check scope
     if !api_key.has_scope(api_key_scopes::READ_USERS) {
         return Err(AppError::Auth(crate::error::AuthError::InsufficientScope));
     }
 
     let service = UserManagementService::new(state.pool.clone());
-    let page = pagination.page;
-    let limit = pagination.limit.min(100);
-
-    let (users, total) = service.list_app_users_by_api_key(api_key.app_id, page, limit).await
-        .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?;
+    let response = service.list_app_users_by_api_key(api_key.app_id, &query).await
+        .map_err(|e| match e {
+            UserManagementError::ValidationError(msg) => AppError::ValidationError(msg),
+            e => AppError::InternalError(anyhow::anyhow!("{}", e)),
+        })?;
 
-    Ok(Json(ListUsersResponse {
-        data: users,
-        page,
-        limit,
-        total,
-    }))
+    Ok(Json(response))
 }
 
 /// GET /api/v1/users/:user_id - Get user details (requires read:users scope)
@@ -158,8 +156,11 @@ pub async fn assign_role_api_key_handler(
     }
 
     let service = RoleService::new(state.pool.clone());
-    service.assign_role_to_user(req.role_id, user_id, api_key.app_id).await
-        .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?;
+    service.assign_role_to_user(user_id, api_key.app_id, req.role_id, req.expires_at, req.access_window.as_deref(), RoleChangeActor::api_key(api_key.api_key_id)).await
+        .map_err(|e| match e {
+            RoleError::InvalidExpiry | RoleError::InvalidAccessWindow(_) => AppError::ValidationError(e.to_string()),
+            e => AppError::InternalError(anyhow::anyhow!("{}", e)),
+        })?;
 
     Ok(StatusCode::CREATED)
 }
@@ -176,22 +177,84 @@ pub async fn remove_role_api_key_handler(
     }
 
     let service = RoleService::new(state.pool.clone());
-    service.remove_role_from_user(role_id, user_id, api_key.app_id).await
+    service.remove_role_from_user(user_id, api_key.app_id, role_id, RoleChangeActor::api_key(api_key.api_key_id)).await
         .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?;
 
     Ok(StatusCode::NO_CONTENT)
 }
 
-// ============ DTOs ============
+/// POST /api/v1/provisioning/events - Queue an inbound provisioning event
+/// (user.deprovision requires write:users, role.sync requires write:roles)
+///
+/// Returns 202 for a new event and 200 with the stored event when the
+/// `event_id` was already submitted.
+pub async fn submit_provisioning_event_handler(
+    State(state): State<AppState>,
+    api_key: ApiKeyContext,
+    Json(req): Json<SubmitProvisioningEventRequest>,
+) -> Result<(StatusCode, Json<ProvisioningEvent>), AppError> {
+    // Check scope for the event type (unknown types are rejected by the service)
+    let required_scope = match req.event_type.as_str() {
+        PROVISIONING_EVENT_USER_DEPROVISION => Some(api_key_scopes::WRITE_USERS),
+        PROVISIONING_EVENT_ROLE_SYNC => Some(api_key_scopes::WRITE_ROLES),
+        _ => None,
+    };
+    if required_scope.is_some_and(|scope| !api_key.has_scope(scope)) {
+        return Err(AppError::Auth(crate::error::AuthError::InsufficientScope));
+    }
 
-#[derive(Debug, Serialize)]
-pub struct ListUsersResponse {
-    pub data: Vec<UserAppResponse>,
-    pub page: u32,
-    pub limit: u32,
-    pub total: i64,
+    let service = ProvisioningService::new(state.pool.clone());
+    let (event, created) = service
+        .submit(api_key.app_id, Some(api_key.api_key_id), &req.event_id, &req.event_type, &req.payload)
+        .await?;
+
+    let status = if created { StatusCode::ACCEPTED } else { StatusCode::OK };
+    Ok((status, Json(event)))
 }
 
+/// GET /api/v1/provisioning/events - List provisioning events (requires read:users scope)
+pub async fn list_provisioning_events_handler(
+    State(state): State<AppState>,
+    api_key: ApiKeyContext,
+    Query(query): Query<ListProvisioningEventsQuery>,
+) -> Result<Json<PaginatedResponse<ProvisioningEvent>>, AppError> {
+    // Check scope
+    if !api_key.has_scope(api_key_scopes::READ_USERS) {
+        return Err(AppError::Auth(crate::error::AuthError::InsufficientScope));
+    }
+
+    let service = ProvisioningService::new(state.pool.clone());
+    let response = service
+        .list_events(
+            api_key.app_id,
+            query.status.as_deref(),
+            query.page.unwrap_or(1),
+            query.limit.unwrap_or(20),
+        )
+        .await?;
+
+    Ok(Json(response))
+}
+
+/// POST /api/v1/provisioning/events/:event_id/retry - Requeue a dead-lettered event (requires write:users scope)
+pub async fn retry_provisioning_event_handler(
+    State(state): State<AppState>,
+    api_key: ApiKeyContext,
+    Path(event_id): Path<String>,
+) -> Result<Json<ProvisioningEvent>, AppError> {
+    // Check scope
+    if !api_key.has_scope(api_key_scopes::WRITE_USERS) {
+        return Err(AppError::Auth(crate::error::AuthError::InsufficientScope));
+    }
+
+    let service = ProvisioningService::new(state.pool.clone());
+    let event = service.retry_dead_letter(api_key.app_id, &event_id).await?;
+
+    Ok(Json(event))
+}
+
+// ============ DTOs ============
+
 #[derive(Debug, Deserialize)]
 pub struct BanUserRequest {
     pub reason: Option<String>,
@@ -200,6 +263,10 @@ pub struct BanUserRequest {
 #[derive(Debug, Deserialize)]
 pub struct AssignRoleRequest {
     pub role_id: Uuid,
+    #[serde(default)]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub access_window: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -208,3 +275,18 @@ pub struct RoleResponse {
     pub name: String,
     pub app_id: Uuid,
 }
+
+#[derive(Debug, Deserialize)]
+pub struct SubmitProvisioningEventRequest {
+    /// Producer-assigned ID; resubmitting the same ID is a no-op
+    pub event_id: String,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListProvisioningEventsQuery {
+    pub status: Option<String>,
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
@@ -1,19 +1,24 @@
 use axum::{
     extract::{Extension, Path, Query, State},
-    http::StatusCode,
+    http::{header::CACHE_CONTROL, HeaderMap, StatusCode},
+    response::IntoResponse,
     Json,
 };
 use uuid::Uuid;
 
 use crate::config::AppState;
 use crate::dto::{
-    AppAuthRequest, AppAuthResponse, AppResponse, CreateAppRequest, CreateAppWithSecretResponse,
-    PaginatedResponse, PaginationQuery, RegenerateSecretResponse,
+    AppAuthMethodsResponse, AppAuthRequest, AppAuthResponse, AppResponse, AuthMethodStatus,
+    CreateAppRequest, CreateAppWithSecretResponse, MfaRequirements, PaginatedResponse,
+    PaginationQuery, PasswordAuthMethod, RegenerateSecretResponse, UpdateDeletionPolicyRequest,
+    UpdateDigestRequest, UpdateEmailLoginRequest, UpdateRequiredAcrRequest, UpdateSessionPolicyRequest, UpdateSsoRequest, UpdateTokenBindingRequest,
 };
 use crate::error::{AppError, AuthError};
 use crate::repositories::{AppRepository, UserRepository};
 use crate::services::AppService;
+use crate::utils::acr::{self, AcrLevel};
 use crate::utils::jwt::Claims;
+use crate::utils::token_binding::{client_cert_thumbprint, client_ip};
 
 /// POST /apps - Create a new app with generated secret
 ///
@@ -60,27 +65,17 @@ pub async fn list_my_apps_handler(
 
     let app_repo = AppRepository::new(state.pool.clone());
 
-    let page = pagination.page;
-    let limit = pagination.limit.min(100);
+    let (page, limit) = pagination.normalized();
 
     let apps = app_repo.list_by_owner(owner_id, page, limit).await?;
     let total = app_repo.count_by_owner(owner_id).await?;
 
     let data = apps
         .into_iter()
-        .map(|app| AppResponse {
-            id: app.id,
-            code: app.code,
-            name: app.name,
-        })
+        .map(AppResponse::from)
         .collect();
 
-    Ok(Json(PaginatedResponse {
-        data,
-        page,
-        limit,
-        total,
-    }))
+    Ok(Json(PaginatedResponse::new(data, page, limit, total).with_sort("code", "asc")))
 }
 
 /// GET /apps/{id} - Get app details (owner only)
@@ -109,11 +104,163 @@ pub async fn get_my_app_handler(
         return Err(AppError::NotAppOwner);
     }
 
-    Ok(Json(AppResponse {
-        id: app.id,
-        code: app.code,
-        name: app.name,
-    }))
+    Ok(Json(AppResponse::from(app)))
+}
+
+/// PUT /apps/{id}/session-policy - Set session idle timeout and absolute lifetime (owner only)
+///
+/// Applies to refresh of sessions opened in this app's context.
+pub async fn update_app_session_policy_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(app_id): Path<Uuid>,
+    Json(req): Json<UpdateSessionPolicyRequest>,
+) -> Result<Json<AppResponse>, AppError> {
+    let requester_id = claims
+        .user_id()
+        .map_err(|_| AppError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
+
+    let app_service = AppService::new(state.pool.clone(), state.jwt_manager.clone());
+
+    let app = app_service
+        .update_session_policy(app_id, requester_id, req.idle_timeout_secs, req.absolute_lifetime_secs)
+        .await?;
+
+    Ok(Json(AppResponse::from(app)))
+}
+
+/// PUT /apps/{id}/token-binding - Bind app tokens to the caller's IP range or client certificate (owner only)
+///
+/// Applies to tokens issued by `/apps/auth` from now on.
+pub async fn update_app_token_binding_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(app_id): Path<Uuid>,
+    Json(req): Json<UpdateTokenBindingRequest>,
+) -> Result<Json<AppResponse>, AppError> {
+    let requester_id = claims
+        .user_id()
+        .map_err(|_| AppError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
+
+    let app_service = AppService::new(state.pool.clone(), state.jwt_manager.clone());
+
+    let app = app_service
+        .update_token_binding(app_id, requester_id, &req.mode, req.ip_prefix)
+        .await?;
+
+    Ok(Json(AppResponse::from(app)))
+}
+
+/// PUT /apps/{id}/deletion-policy - Choose whether deleted members are removed or anonymized (owner only)
+pub async fn update_app_deletion_policy_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(app_id): Path<Uuid>,
+    Json(req): Json<UpdateDeletionPolicyRequest>,
+) -> Result<Json<AppResponse>, AppError> {
+    let requester_id = claims
+        .user_id()
+        .map_err(|_| AppError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
+
+    let app_service = AppService::new(state.pool.clone(), state.jwt_manager.clone());
+
+    let app = app_service
+        .update_deletion_policy(app_id, requester_id, &req.policy)
+        .await?;
+
+    Ok(Json(AppResponse::from(app)))
+}
+
+/// PUT /apps/{id}/required-acr - Require a minimum sign-in assurance level for the app (owner only)
+///
+/// Applies to logins from now on; existing sessions keep their tokens.
+pub async fn update_app_required_acr_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(app_id): Path<Uuid>,
+    Json(req): Json<UpdateRequiredAcrRequest>,
+) -> Result<Json<AppResponse>, AppError> {
+    let requester_id = claims
+        .user_id()
+        .map_err(|_| AppError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
+
+    let app_service = AppService::new(state.pool.clone(), state.jwt_manager.clone());
+
+    let app = app_service
+        .update_required_acr(app_id, requester_id, req.required_acr.as_deref())
+        .await?;
+
+    Ok(Json(AppResponse::from(app)))
+}
+
+/// PUT /apps/{id}/sso - Allow or refuse sign-ins continued from the SSO session (owner only)
+///
+/// With SSO disabled, users signed in to another app on the same domain
+/// still have to sign in to this one.
+pub async fn update_app_sso_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(app_id): Path<Uuid>,
+    Json(req): Json<UpdateSsoRequest>,
+) -> Result<Json<AppResponse>, AppError> {
+    let requester_id = claims
+        .user_id()
+        .map_err(|_| AppError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
+
+    let app_service = AppService::new(state.pool.clone(), state.jwt_manager.clone());
+
+    let app = app_service
+        .update_sso_enabled(app_id, requester_id, req.enabled)
+        .await?;
+
+    Ok(Json(AppResponse::from(app)))
+}
+
+/// PUT /apps/{id}/digest - Subscribe to the weekly app digest or unsubscribe (owner only)
+///
+/// The digest is emailed to the owner once a week and summarizes the previous
+/// week: new users, bans, failed webhook deliveries, API keys about to expire
+/// and spikes in failed sign-ins.
+pub async fn update_app_digest_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(app_id): Path<Uuid>,
+    Json(req): Json<UpdateDigestRequest>,
+) -> Result<Json<AppResponse>, AppError> {
+    let requester_id = claims
+        .user_id()
+        .map_err(|_| AppError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
+
+    let app_service = AppService::new(state.pool.clone(), state.jwt_manager.clone());
+
+    let app = app_service
+        .update_digest_enabled(app_id, requester_id, req.enabled)
+        .await?;
+
+    Ok(Json(AppResponse::from(app)))
+}
+
+/// PUT /apps/{id}/email-login - Accept or refuse passwordless sign-in with emailed codes (owner only)
+///
+/// Users then sign in with a code sent by `POST /auth/email-login/send`;
+/// those who enabled MFA still complete a second factor.
+pub async fn update_app_email_login_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(app_id): Path<Uuid>,
+    Json(req): Json<UpdateEmailLoginRequest>,
+) -> Result<Json<AppResponse>, AppError> {
+    let requester_id = claims
+        .user_id()
+        .map_err(|_| AppError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
+
+    let app_service = AppService::new(state.pool.clone(), state.jwt_manager.clone());
+
+    let app = app_service
+        .update_email_login_enabled(app_id, requester_id, req.enabled)
+        .await?;
+
+    Ok(Json(AppResponse::from(app)))
 }
 
 /// POST /apps/auth - Authenticate app using App ID and Secret
@@ -124,13 +271,19 @@ pub async fn get_my_app_handler(
 /// - 7.1: Expose POST /apps/auth endpoint for App credential authentication
 pub async fn app_auth_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<AppAuthRequest>,
 ) -> Result<Json<AppAuthResponse>, AppError> {
     let app_service = AppService::new(state.pool.clone(), state.jwt_manager.clone());
 
     // Authenticate app and get access token (Requirements: 3.1, 3.2, 3.3, 3.4, 9.3)
     let access_token = app_service
-        .authenticate_app(req.app_id, &req.secret)
+        .authenticate_app(
+            req.app_id,
+            &req.secret,
+            client_ip(&headers).as_deref(),
+            client_cert_thumbprint(&headers).as_deref(),
+        )
         .await?;
 
     Ok(Json(AppAuthResponse {
@@ -140,6 +293,58 @@ pub async fn app_auth_handler(
     }))
 }
 
+/// How long clients and proxies may cache an app's login options
+const AUTH_METHODS_CACHE_MAX_AGE_SECS: u32 = 300;
+
+/// GET /apps/{code}/auth-methods - Login options enabled for an app (public)
+///
+/// Lets a login page decide which options to render before the user is
+/// known. MFA stays per user: those who enabled it are challenged with one
+/// of `mfa.methods`, unless the app's required acr demands it of everyone.
+/// An app requiring `phr` only accepts passkeys.
+pub async fn app_auth_methods_handler(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let app_service = AppService::new(state.pool.clone(), state.jwt_manager.clone());
+    let app = app_service
+        .get_app_by_code(&code)
+        .await?
+        .ok_or_else(|| AppError::NotFound("App not found".to_string()))?;
+
+    let required = acr::parse_required(app.required_acr.as_deref());
+    let password_allowed = AcrLevel::Mfa.satisfies(required);
+    let methods = AppAuthMethodsResponse {
+        app_code: app.code,
+        app_name: app.name,
+        password: PasswordAuthMethod {
+            enabled: password_allowed,
+            requires_verified_email: state.config.login_require_verified_email,
+        },
+        passkeys: AuthMethodStatus { enabled: true },
+        magic_link: AuthMethodStatus { enabled: false },
+        email_code: AuthMethodStatus { enabled: app.email_login_enabled && password_allowed },
+        qr_login: AuthMethodStatus { enabled: AcrLevel::Password.satisfies(required) },
+        social_providers: Vec::new(),
+        mfa: MfaRequirements {
+            required: !AcrLevel::Password.satisfies(required),
+            methods: vec![
+                "totp".to_string(),
+                "push".to_string(),
+                "email".to_string(),
+                "backup_code".to_string(),
+            ],
+        },
+        required_acr: required.map(|level| level.to_string()),
+        sso: AuthMethodStatus { enabled: app.sso_enabled },
+    };
+
+    Ok((
+        [(CACHE_CONTROL, format!("public, max-age={}", AUTH_METHODS_CACHE_MAX_AGE_SECS))],
+        Json(methods),
+    ))
+}
+
 /// POST /apps/{id}/secret/regenerate - Regenerate app secret (owner only)
 ///
 /// # Requirements
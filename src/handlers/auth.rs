@@ -1,18 +1,39 @@
 use axum::{
-    extract::State,
-    http::{HeaderMap, StatusCode},
+    extract::{Extension, Query, State},
+    http::{header::SET_COOKIE, HeaderMap, StatusCode},
+    response::{AppendHeaders, IntoResponse, Response},
     Json,
 };
 use serde::Serialize;
 
 use crate::config::AppState;
 use crate::dto::{
-    CompleteMfaLoginRequest, ForgotPasswordRequest, LoginRequest, MessageResponse, RefreshRequest,
-    RegisterRequest, RegisterResponse, ResetPasswordRequest, TokenResponse,
+    CompleteMfaLoginRequest, ContinueSsoSessionRequest, EmailCodeSentResponse, EmailLoginCodeResponse,
+    EmailLoginRequest, ForgotPasswordRequest, LoginRequest, MagicLinkVerifyQuery, MessageResponse, PushMfaRespondRequest, PushMfaRespondResponse, QrLoginApproveRequest,
+    QrLoginApproveResponse, QrLoginPendingResponse, QrLoginStartRequest, QrLoginStartResponse,
+    QrLoginTokenRequest, RefreshRequest, RegisterRequest, RegisterResponse, RegistrationFieldsResponse,
+    ResetPasswordRequest,
+    ResolvedClaimsResponse, SendEmailLoginCodeRequest, SendEmailMfaRequest, SendMagicLinkRequest, SendSmsMfaRequest,
+    SmsCodeSentResponse, VerifyEmailMfaRequest, SsoSessionResponse,
+    StartPushMfaRequest, StartPushMfaResponse, StartSsoSessionRequest, TokenResponse,
 };
 use crate::error::AuthError;
-use crate::services::{AuthService, LoginContext, LoginResult};
-use crate::utils::jwt::JwtManager;
+use crate::services::{
+    AuthService, CoolingOffService, EmailConfig, EmailService, LoginContext, LoginResult, MockEmailService,
+    MfaFactor, PushMfaService, QrLoginService, QrPollResult, RegistrationOutcome, SessionPolicy,
+};
+use crate::utils::claims_size::apps_digest;
+use crate::utils::client_fingerprint::FingerprintPolicy;
+use crate::utils::cooling_off::{CoolingOffPolicy, CoolingOffReason};
+use crate::utils::cookie::{generate_csrf_token, get_cookie, RefreshCookieSettings, TRUSTED_DEVICE_COOKIE_NAME};
+use crate::utils::jwt::{Claims, TokenPair};
+use crate::utils::metrics::{self, Outcome};
+use crate::utils::request_id::spawn_in_request;
+use crate::utils::travel::TravelPolicy;
+
+/// Response to every registration in enumeration-safe mode
+const REGISTRATION_ACCEPTED_MESSAGE: &str =
+    "Registration received. If the email can be used, check your inbox for the next steps.";
 
 /// Login response - can be either tokens or MFA required
 #[derive(Debug, Serialize)]
@@ -32,9 +53,19 @@ pub struct MfaRequiredResponse {
     pub available_methods: Vec<String>,
 }
 
+/// QR login poll response - either still pending or tokens
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum QrLoginTokenResponse {
+    /// Approved - tokens returned
+    Success(TokenResponse),
+    /// Waiting for approval on the phone
+    Pending(QrLoginPendingResponse),
+}
+
 /// Extract client IP address from headers
 /// Checks X-Forwarded-For, X-Real-IP, then falls back to direct connection
-fn extract_ip_address(headers: &HeaderMap) -> Option<String> {
+pub(crate) fn extract_ip_address(headers: &HeaderMap) -> Option<String> {
     // Check X-Forwarded-For first (for proxied requests)
     if let Some(forwarded) = headers.get("x-forwarded-for") {
         if let Ok(value) = forwarded.to_str() {
@@ -54,7 +85,7 @@ fn extract_ip_address(headers: &HeaderMap) -> Option<String> {
 }
 
 /// Extract User-Agent from headers
-fn extract_user_agent(headers: &HeaderMap) -> Option<String> {
+pub(crate) fn extract_user_agent(headers: &HeaderMap) -> Option<String> {
     headers
         .get("user-agent")
         .and_then(|v| v.to_str().ok())
@@ -69,11 +100,31 @@ fn extract_user_agent(headers: &HeaderMap) -> Option<String> {
 pub async fn register_handler(
     State(state): State<AppState>,
     Json(req): Json<RegisterRequest>,
-) -> Result<(StatusCode, Json<RegisterResponse>), AuthError> {
-    let jwt_manager = create_jwt_manager(&state)?;
-    let auth_service = AuthService::new(state.pool.clone(), jwt_manager);
-    
-    let user = auth_service.register(&req.email, &req.password).await?;
+) -> Result<Response, AuthError> {
+    let jwt_manager = state.jwt_manager.clone();
+    let auth_service = AuthService::new(state.pool.clone(), jwt_manager)
+        .with_registration_fields(state.config.registration_fields.clone());
+
+    // Enumeration-safe mode answers the same for new and taken emails; the
+    // owner of a taken one is told by email instead
+    if state.config.enumeration_safe_auth {
+        let outcome = auth_service
+            .register_enumeration_safe(&req.email, &req.password, &req.fields)
+            .await?;
+        if let RegistrationOutcome::AlreadyRegistered(Some(owner)) = outcome {
+            spawn_in_request(notify_registration_attempt(state.pool.clone(), owner.email));
+        }
+
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(MessageResponse {
+                message: REGISTRATION_ACCEPTED_MESSAGE.to_string(),
+            }),
+        )
+            .into_response());
+    }
+
+    let user = auth_service.register(&req.email, &req.password, &req.fields).await?;
     
     Ok((
         StatusCode::CREATED,
@@ -81,7 +132,30 @@ pub async fn register_handler(
             id: user.id,
             email: user.email,
         }),
-    ))
+    )
+        .into_response())
+}
+
+/// GET /auth/register/fields - Extra fields accepted by registration
+///
+/// Lets sign-up forms render the deployment's `REGISTRATION_FIELDS`.
+pub async fn registration_fields_handler(State(state): State<AppState>) -> Json<RegistrationFieldsResponse> {
+    Json(RegistrationFieldsResponse {
+        fields: state.config.registration_fields.fields().to_vec(),
+    })
+}
+
+/// Email the owner of an existing account about a sign-up with their address
+async fn notify_registration_attempt(pool: sqlx::MySqlPool, to: String) {
+    let sent = match EmailConfig::from_env().map(EmailService::new) {
+        Some(Ok(mailer)) => mailer.with_bounce_list(pool).send_registration_attempt(&to).await,
+        Some(Err(e)) => Err(e),
+        None => MockEmailService::new().send_registration_attempt(&to).await,
+    };
+
+    if let Err(e) = sent {
+        tracing::warn!("Failed to send registration attempt notice: {:?}", e);
+    }
 }
 
 /// POST /auth/login - Authenticate user and return tokens
@@ -101,8 +175,13 @@ pub async fn login_handler(
     headers: HeaderMap,
     Json(req): Json<LoginRequest>,
 ) -> Result<Json<LoginResponse>, AuthError> {
-    let jwt_manager = create_jwt_manager(&state)?;
-    let auth_service = AuthService::new(state.pool.clone(), jwt_manager);
+    let jwt_manager = state.jwt_manager.clone();
+    let auth_service = AuthService::new(state.pool.clone(), jwt_manager)
+        .with_verified_email_required(state.config.login_require_verified_email)
+        .with_token_audit_sample_rate(state.config.token_audit_sample_rate)
+        .with_trusted_device_days(state.config.mfa_trusted_device_days)
+        .with_travel_policy(TravelPolicy::from_config(&state.config))
+        .with_trusted_device(get_cookie(&headers, TRUSTED_DEVICE_COOKIE_NAME));
 
     // Extract request context for rate limiting and audit logging
     let context = LoginContext {
@@ -111,28 +190,166 @@ pub async fn login_handler(
     };
 
     let result = auth_service
-        .login(&req.email, &req.password, req.app_id, context)
-        .await?;
+        .login(&req.email, &req.password, req.app_id, req.app.as_deref(), context)
+        .await;
+    let app = req.app.clone().or_else(|| req.app_id.map(|id| id.to_string()));
+    record_sign_in_metric(app.as_deref(), &result);
 
+    Ok(Json(login_response(result?)))
+}
+
+/// Count a sign-in in the per-app SLO metrics
+fn record_sign_in_metric(app: Option<&str>, result: &Result<LoginResult, AuthError>) {
+    let outcome = match result {
+        Ok(LoginResult::Success { .. }) => Outcome::Success,
+        Ok(LoginResult::MfaRequired { .. }) => Outcome::MfaRequired,
+        Err(AuthError::InternalError(_)) => Outcome::Error,
+        Err(_) => Outcome::Failure,
+    };
+    metrics::record_sign_in(app, outcome);
+}
+
+/// Tokens, or the MFA token and methods of the second step
+fn login_response(result: LoginResult) -> LoginResponse {
     match result {
-        LoginResult::Success { tokens, .. } => Ok(Json(LoginResponse::Success(TokenResponse {
+        LoginResult::Success { tokens, .. } => LoginResponse::Success(TokenResponse {
             access_token: tokens.access_token,
-            refresh_token: tokens.refresh_token,
+            refresh_token: Some(tokens.refresh_token),
             token_type: tokens.token_type,
             expires_in: tokens.expires_in,
-        }))),
+        }),
         LoginResult::MfaRequired {
             mfa_token,
             available_methods,
             ..
-        } => Ok(Json(LoginResponse::MfaRequired(MfaRequiredResponse {
+        } => LoginResponse::MfaRequired(MfaRequiredResponse {
             mfa_required: true,
             mfa_token,
             available_methods,
-        }))),
+        }),
     }
 }
 
+/// POST /auth/email-login/send - Email a passwordless sign-in code
+///
+/// # Description
+/// Only for apps whose owner enabled email sign-in (`403 email_login_disabled`
+/// otherwise). The answer is the same whether or not the address has an
+/// account. Requests are limited to 3 per 10 minutes per client and address.
+pub async fn send_email_login_code_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SendEmailLoginCodeRequest>,
+) -> Result<Json<EmailLoginCodeResponse>, AuthError> {
+    let jwt_manager = state.jwt_manager.clone();
+    let auth_service = AuthService::new(state.pool.clone(), jwt_manager);
+
+    let context = LoginContext {
+        ip_address: extract_ip_address(&headers),
+        user_agent: extract_user_agent(&headers),
+    };
+
+    let expires_in = auth_service
+        .send_email_login_code(&req.email, &req.app, context)
+        .await?;
+
+    Ok(Json(EmailLoginCodeResponse {
+        message: "If the address has an account, a sign-in code was sent to it.".to_string(),
+        expires_in,
+    }))
+}
+
+/// POST /auth/email-login/verify - Sign in with an emailed code
+///
+/// # Description
+/// Returns tokens scoped to the app, or mfa_required for users who enabled
+/// MFA (email codes are then not offered as the second factor). A wrong
+/// code counts against the login rate limit.
+pub async fn email_login_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<EmailLoginRequest>,
+) -> Result<Json<LoginResponse>, AuthError> {
+    let jwt_manager = state.jwt_manager.clone();
+    let auth_service = AuthService::new(state.pool.clone(), jwt_manager)
+        .with_verified_email_required(state.config.login_require_verified_email)
+        .with_token_audit_sample_rate(state.config.token_audit_sample_rate)
+        .with_trusted_device_days(state.config.mfa_trusted_device_days)
+        .with_travel_policy(TravelPolicy::from_config(&state.config))
+        .with_trusted_device(get_cookie(&headers, TRUSTED_DEVICE_COOKIE_NAME));
+
+    let context = LoginContext {
+        ip_address: extract_ip_address(&headers),
+        user_agent: extract_user_agent(&headers),
+    };
+
+    let result = auth_service
+        .login_with_email_code(&req.email, &req.code, &req.app, context)
+        .await;
+    record_sign_in_metric(Some(&req.app), &result);
+
+    Ok(Json(login_response(result?)))
+}
+
+/// POST /auth/magic-link - Email a single-use sign-in link
+///
+/// # Description
+/// Only for apps whose owner enabled email sign-in (`403 email_login_disabled`
+/// otherwise). The link points to `{APP_URL}/magic-link?token=...` and is
+/// valid for 15 minutes; a new link replaces the previous unused one. The
+/// answer is the same whether or not the address has an account.
+pub async fn send_magic_link_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SendMagicLinkRequest>,
+) -> Result<Json<EmailLoginCodeResponse>, AuthError> {
+    let jwt_manager = state.jwt_manager.clone();
+    let auth_service = AuthService::new(state.pool.clone(), jwt_manager);
+
+    let context = LoginContext {
+        ip_address: extract_ip_address(&headers),
+        user_agent: extract_user_agent(&headers),
+    };
+
+    let expires_in = auth_service.send_magic_link(&req.email, &req.app, context).await?;
+
+    Ok(Json(EmailLoginCodeResponse {
+        message: "If the address has an account, a sign-in link was sent to it.".to_string(),
+        expires_in,
+    }))
+}
+
+/// GET /auth/magic-link/verify - Sign in with an emailed link
+///
+/// # Description
+/// Called by the page the link opens, with the token from the link. Returns
+/// tokens scoped to the app the link was requested for, or mfa_required for
+/// users who enabled MFA. A link signs in only once.
+pub async fn magic_link_verify_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<MagicLinkVerifyQuery>,
+) -> Result<Json<LoginResponse>, AuthError> {
+    let jwt_manager = state.jwt_manager.clone();
+    let auth_service = AuthService::new(state.pool.clone(), jwt_manager)
+        .with_verified_email_required(state.config.login_require_verified_email)
+        .with_token_audit_sample_rate(state.config.token_audit_sample_rate)
+        .with_trusted_device_days(state.config.mfa_trusted_device_days)
+        .with_travel_policy(TravelPolicy::from_config(&state.config))
+        .with_trusted_device(get_cookie(&headers, TRUSTED_DEVICE_COOKIE_NAME));
+
+    let context = LoginContext {
+        ip_address: extract_ip_address(&headers),
+        user_agent: extract_user_agent(&headers),
+    };
+
+    let result = auth_service.login_with_magic_link(&query.token, context).await;
+    // The app is only known from the link, so these count without one
+    record_sign_in_metric(None, &result);
+
+    Ok(Json(login_response(result?)))
+}
+
 /// POST /auth/mfa/verify - Complete MFA login
 /// 
 /// # Description
@@ -141,55 +358,343 @@ pub async fn login_handler(
 /// 
 /// # Security Features
 /// - Rate limiting: 5 attempts per 5 minutes
-/// - Supports TOTP and backup codes
+/// - Supports TOTP, backup codes and SMS codes (`is_sms_code`)
+/// - Supports approved push challenges (`push_challenge_id`); returns
+///   authorization_pending until the device answers
+/// - `remember_device` sets the `trusted_device` cookie when trusted
+///   devices are enabled, so sign-ins from this browser skip MFA
 pub async fn complete_mfa_login_handler(
     State(state): State<AppState>,
     headers: HeaderMap,
     Json(req): Json<CompleteMfaLoginRequest>,
-) -> Result<Json<TokenResponse>, AuthError> {
-    let jwt_manager = create_jwt_manager(&state)?;
-    let auth_service = AuthService::new(state.pool.clone(), jwt_manager);
+) -> Result<Response, AuthError> {
+    let jwt_manager = state.jwt_manager.clone();
+    let auth_service = AuthService::new(state.pool.clone(), jwt_manager)
+        .with_token_audit_sample_rate(state.config.token_audit_sample_rate)
+        .with_trusted_device_days(state.config.mfa_trusted_device_days)
+        .with_travel_policy(TravelPolicy::from_config(&state.config))
+        .with_remember_device(req.remember_device);
 
     let context = LoginContext {
         ip_address: extract_ip_address(&headers),
         user_agent: extract_user_agent(&headers),
     };
 
-    let token_pair = auth_service
-        .complete_mfa_login(&req.mfa_token, &req.code, req.is_backup_code, context)
+    let factor = match req.push_challenge_id {
+        Some(challenge_id) => MfaFactor::Push(challenge_id),
+        None if req.is_backup_code => MfaFactor::BackupCode,
+        None if req.is_sms_code => MfaFactor::Sms,
+        None => MfaFactor::Totp,
+    };
+    let (token_pair, trusted_device) = auth_service
+        .complete_mfa_login(&req.mfa_token, &req.code, factor, context)
         .await?;
 
-    Ok(Json(TokenResponse {
+    Ok(mfa_login_response(&state, token_pair, trusted_device))
+}
+
+/// Tokens after MFA, setting the trusted device cookie if one was issued
+fn mfa_login_response(state: &AppState, token_pair: TokenPair, trusted_device: Option<String>) -> Response {
+    let body = Json(TokenResponse {
         access_token: token_pair.access_token,
-        refresh_token: token_pair.refresh_token,
+        refresh_token: Some(token_pair.refresh_token),
         token_type: token_pair.token_type,
         expires_in: token_pair.expires_in,
+    });
+
+    match trusted_device {
+        Some(value) => {
+            let cookie = RefreshCookieSettings::from_config(&state.config)
+                .trusted_device_cookie(&value, state.config.mfa_trusted_device_days * 86400);
+            (AppendHeaders([(SET_COOKIE, cookie)]), body).into_response()
+        }
+        None => body.into_response(),
+    }
+}
+
+
+/// POST /auth/mfa/push - Send a push approval request to the user's devices
+/// 
+/// # Description
+/// Called after login returns mfa_required with "push" among the methods.
+/// Then poll POST /auth/mfa/verify with `push_challenge_id` until the
+/// device approves.
+pub async fn start_push_mfa_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<StartPushMfaRequest>,
+) -> Result<Json<StartPushMfaResponse>, AuthError> {
+    let jwt_manager = state.jwt_manager.clone();
+    let auth_service = AuthService::new(state.pool.clone(), jwt_manager);
+
+    let context = LoginContext {
+        ip_address: extract_ip_address(&headers),
+        user_agent: extract_user_agent(&headers),
+    };
+
+    let (challenge, devices_notified) = auth_service
+        .start_push_mfa(&req.mfa_token, context)
+        .await?;
+
+    Ok(Json(StartPushMfaResponse {
+        challenge_id: challenge.id,
+        expires_in: (challenge.expires_at - chrono::Utc::now()).num_seconds().max(0),
+        devices_notified,
+    }))
+}
+
+/// POST /auth/mfa/sms/send - Text a login code to the user's verified phone
+/// 
+/// # Description
+/// Called after login returns mfa_required with "sms" among the methods.
+/// Then complete the login with POST /auth/mfa/verify and `is_sms_code`.
+/// A new code replaces the previous one; sends are limited to 3 per
+/// 10 minutes per account.
+pub async fn send_sms_mfa_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SendSmsMfaRequest>,
+) -> Result<Json<SmsCodeSentResponse>, AuthError> {
+    let jwt_manager = state.jwt_manager.clone();
+    let auth_service = AuthService::new(state.pool.clone(), jwt_manager);
+
+    let context = LoginContext {
+        ip_address: extract_ip_address(&headers),
+        user_agent: extract_user_agent(&headers),
+    };
+
+    let sent = auth_service
+        .send_sms_mfa(&req.mfa_token, req.method_id, context)
+        .await?;
+
+    Ok(Json(SmsCodeSentResponse {
+        method_id: sent.method_id,
+        phone_hint: sent.phone_hint,
+        expires_in: sent.expires_in,
+    }))
+}
+
+/// POST /auth/mfa/email/send - Email a login code to the account address
+/// 
+/// # Description
+/// Called after login returns mfa_required with "email" among the methods.
+/// Then complete the login with POST /auth/mfa/email/verify. A new code
+/// replaces the previous one; sends are limited to 3 per 10 minutes per
+/// account.
+pub async fn send_email_mfa_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SendEmailMfaRequest>,
+) -> Result<Json<EmailCodeSentResponse>, AuthError> {
+    let jwt_manager = state.jwt_manager.clone();
+    let auth_service = AuthService::new(state.pool.clone(), jwt_manager);
+
+    let context = LoginContext {
+        ip_address: extract_ip_address(&headers),
+        user_agent: extract_user_agent(&headers),
+    };
+
+    let sent = auth_service.send_email_mfa(&req.mfa_token, context).await?;
+
+    Ok(Json(EmailCodeSentResponse {
+        email_hint: sent.email_hint,
+        expires_in: sent.expires_in,
     }))
 }
 
+/// POST /auth/mfa/email/verify - Complete MFA login with the emailed code
+/// 
+/// # Description
+/// Same rate limit and response as POST /auth/mfa/verify.
+pub async fn verify_email_mfa_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<VerifyEmailMfaRequest>,
+) -> Result<Response, AuthError> {
+    let jwt_manager = state.jwt_manager.clone();
+    let auth_service = AuthService::new(state.pool.clone(), jwt_manager)
+        .with_token_audit_sample_rate(state.config.token_audit_sample_rate)
+        .with_trusted_device_days(state.config.mfa_trusted_device_days)
+        .with_travel_policy(TravelPolicy::from_config(&state.config))
+        .with_remember_device(req.remember_device);
+
+    let context = LoginContext {
+        ip_address: extract_ip_address(&headers),
+        user_agent: extract_user_agent(&headers),
+    };
+
+    let (token_pair, trusted_device) = auth_service
+        .complete_mfa_login(&req.mfa_token, &req.code, MfaFactor::Email, context)
+        .await?;
+
+    Ok(mfa_login_response(&state, token_pair, trusted_device))
+}
+
+/// POST /auth/mfa/push/respond - Approve or deny a push challenge from a device
+/// 
+/// # Description
+/// Called by the mobile app. Authenticated by the signature made with the
+/// device signing secret returned at device registration.
+pub async fn push_mfa_respond_handler(
+    State(state): State<AppState>,
+    Json(req): Json<PushMfaRespondRequest>,
+) -> Result<Json<PushMfaRespondResponse>, AuthError> {
+    let push_mfa_service = PushMfaService::new(state.pool.clone());
+
+    let challenge = push_mfa_service
+        .respond(req.challenge_id, req.device_id, req.approve, &req.signature)
+        .await?;
+
+    Ok(Json(PushMfaRespondResponse {
+        status: challenge.status.as_str().to_string(),
+    }))
+}
 
 /// POST /auth/refresh - Refresh access token using refresh token
 /// 
+/// Browser SPAs can keep the refresh token in an HttpOnly cookie: send
+/// `use_cookie: true` once with the token in the body, then omit it and the
+/// cookie is used. Cookie-based refreshes are covered by the CSRF middleware
+/// (`X-CSRF-Token` header matching the `csrf_token` cookie).
+/// 
+/// The caller's user-agent family and IP prefix are compared with the
+/// client that logged in (see `REFRESH_FINGERPRINT_MODE`).
+/// 
 /// # Requirements
 /// - 14.3: Expose POST /auth/refresh for token refresh
 /// - 3.1-3.3: Token refresh requirements
 pub async fn refresh_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<RefreshRequest>,
+) -> Result<Response, AuthError> {
+    let jwt_manager = state.jwt_manager.clone();
+    let auth_service = AuthService::new(state.pool.clone(), jwt_manager)
+        .with_session_defaults(SessionPolicy::from_config(&state.config))
+        .with_fingerprint_policy(FingerprintPolicy::from_config(&state.config))
+        .with_token_audit_sample_rate(state.config.token_audit_sample_rate);
+    let cookie_settings = RefreshCookieSettings::from_config(&state.config);
+
+    let (refresh_token, from_cookie) = match req.refresh_token {
+        Some(token) => (token, false),
+        None => {
+            let token = get_cookie(&headers, &cookie_settings.name)
+                .ok_or_else(|| AuthError::InvalidRequest("refresh_token is required".to_string()))?;
+            (token, true)
+        }
+    };
+
+    let context = LoginContext {
+        ip_address: extract_ip_address(&headers),
+        user_agent: extract_user_agent(&headers),
+    };
+
+    let token_pair = auth_service.refresh(&refresh_token, req.app.as_deref(), &context).await?;
+
+    if !(from_cookie || req.use_cookie) {
+        return Ok(Json(TokenResponse {
+            access_token: token_pair.access_token,
+            refresh_token: Some(token_pair.refresh_token),
+            token_type: token_pair.token_type,
+            expires_in: token_pair.expires_in,
+        })
+        .into_response());
+    }
+
+    let max_age = state.config.refresh_token_expiry_secs;
+    let refresh_cookie = cookie_settings.refresh_cookie(&cookie_settings.name, &token_pair.refresh_token, max_age);
+    let csrf_cookie = cookie_settings.csrf_cookie(&generate_csrf_token(), max_age);
+
+    Ok((
+        AppendHeaders([(SET_COOKIE, refresh_cookie), (SET_COOKIE, csrf_cookie)]),
+        Json(TokenResponse {
+            access_token: token_pair.access_token,
+            refresh_token: None,
+            token_type: token_pair.token_type,
+            expires_in: token_pair.expires_in,
+        }),
+    )
+        .into_response())
+}
+
+/// POST /auth/sso/session - Hand the hosted login session to the SSO cookie
+///
+/// Sets the HttpOnly SSO cookie (`SSO_COOKIE_NAME`, scoped to
+/// `SSO_COOKIE_DOMAIN`) and a CSRF cookie on the same domain, so first-party
+/// apps on sibling subdomains can continue the session with
+/// `POST /auth/sso/continue`.
+pub async fn start_sso_session_handler(
+    State(state): State<AppState>,
+    Json(req): Json<StartSsoSessionRequest>,
+) -> Result<Response, AuthError> {
+    let jwt_manager = state.jwt_manager.clone();
+    let auth_service = AuthService::new(state.pool.clone(), jwt_manager)
+        .with_session_defaults(SessionPolicy::from_config(&state.config));
+
+    let (token, sso) = auth_service.start_sso_session(&req.refresh_token).await?;
+
+    let cookie_settings = RefreshCookieSettings::sso_from_config(&state.config);
+    let max_age = (sso.expires_at - chrono::Utc::now()).num_seconds();
+    let sso_cookie = cookie_settings.refresh_cookie(&cookie_settings.name, &token, max_age);
+    let csrf_cookie = cookie_settings.csrf_cookie(&generate_csrf_token(), max_age);
+
+    Ok((
+        AppendHeaders([(SET_COOKIE, sso_cookie), (SET_COOKIE, csrf_cookie)]),
+        Json(SsoSessionResponse { expires_at: sso.expires_at }),
+    )
+        .into_response())
+}
+
+/// POST /auth/sso/continue - Sign in to an app from the SSO cookie
+///
+/// Opens an app session carrying the original sign-in's time and acr. Fails
+/// with `401 invalid_token` when there is no usable SSO session and `403
+/// sso_disabled` when the app opted out; the app then shows its login page.
+/// Cookie-authenticated, so covered by the CSRF middleware.
+pub async fn continue_sso_session_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<ContinueSsoSessionRequest>,
 ) -> Result<Json<TokenResponse>, AuthError> {
-    let jwt_manager = create_jwt_manager(&state)?;
-    let auth_service = AuthService::new(state.pool.clone(), jwt_manager);
-    
-    let token_pair = auth_service.refresh(&req.refresh_token).await?;
-    
+    let cookie_settings = RefreshCookieSettings::sso_from_config(&state.config);
+    let token = get_cookie(&headers, &cookie_settings.name).ok_or(AuthError::InvalidToken)?;
+
+    let jwt_manager = state.jwt_manager.clone();
+    let auth_service = AuthService::new(state.pool.clone(), jwt_manager)
+        .with_session_defaults(SessionPolicy::from_config(&state.config));
+    let context = LoginContext {
+        ip_address: extract_ip_address(&headers),
+        user_agent: extract_user_agent(&headers),
+    };
+
+    let (token_pair, _) = auth_service.continue_sso_session(&token, &req.app, &context).await?;
+
     Ok(Json(TokenResponse {
         access_token: token_pair.access_token,
-        refresh_token: token_pair.refresh_token,
+        refresh_token: Some(token_pair.refresh_token),
         token_type: token_pair.token_type,
         expires_in: token_pair.expires_in,
     }))
 }
 
+/// Response for GET /auth/csrf
+#[derive(Debug, Serialize)]
+pub struct CsrfTokenResponse {
+    pub csrf_token: String,
+}
+
+/// GET /auth/csrf - Issue a CSRF token for cookie-authenticated requests
+/// 
+/// Sets the readable `csrf_token` cookie and returns the same value; the
+/// client echoes it in the `X-CSRF-Token` header on state-changing requests.
+pub async fn csrf_token_handler(State(state): State<AppState>) -> Response {
+    let cookie_settings = RefreshCookieSettings::from_config(&state.config);
+    let csrf_token = generate_csrf_token();
+    let cookie = cookie_settings.csrf_cookie(&csrf_token, state.config.refresh_token_expiry_secs);
+
+    (AppendHeaders([(SET_COOKIE, cookie)]), Json(CsrfTokenResponse { csrf_token })).into_response()
+}
+
 /// POST /auth/forgot-password - Initiate password reset
 /// 
 /// # Requirements
@@ -199,8 +704,9 @@ pub async fn forgot_password_handler(
     State(state): State<AppState>,
     Json(req): Json<ForgotPasswordRequest>,
 ) -> Result<Json<MessageResponse>, AuthError> {
-    let jwt_manager = create_jwt_manager(&state)?;
-    let auth_service = AuthService::new(state.pool.clone(), jwt_manager);
+    let jwt_manager = state.jwt_manager.clone();
+    let auth_service = AuthService::new(state.pool.clone(), jwt_manager)
+        .with_enumeration_safe(state.config.enumeration_safe_auth);
     
     // Always return success to prevent email enumeration (Requirement 4.2)
     let _ = auth_service.forgot_password(&req.email).await?;
@@ -217,24 +723,155 @@ pub async fn forgot_password_handler(
 /// - 4.3-4.4: Password reset completion requirements
 pub async fn reset_password_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<ResetPasswordRequest>,
 ) -> Result<Json<MessageResponse>, AuthError> {
-    let jwt_manager = create_jwt_manager(&state)?;
+    let jwt_manager = state.jwt_manager.clone();
     let auth_service = AuthService::new(state.pool.clone(), jwt_manager);
     
-    auth_service.reset_password(&req.token, &req.new_password).await?;
+    let user_id = auth_service.reset_password(&req.token, &req.new_password).await?;
+
+    // A reset is how a taken-over account is usually locked down, so pause high-risk actions
+    CoolingOffService::new(state.pool.clone(), CoolingOffPolicy::from_config(&state.config))
+        .start(
+            user_id,
+            CoolingOffReason::PasswordReset,
+            extract_ip_address(&headers).as_deref(),
+            extract_user_agent(&headers).as_deref(),
+        )
+        .await?;
     
     Ok(Json(MessageResponse {
         message: "Password has been reset successfully.".to_string(),
     }))
 }
 
-/// Helper function to create JwtManager from AppState
-fn create_jwt_manager(state: &AppState) -> Result<JwtManager, AuthError> {
-    JwtManager::new(
-        &state.config.jwt_private_key,
-        &state.config.jwt_public_key,
-        state.config.access_token_expiry_secs,
-        state.config.refresh_token_expiry_secs,
-    )
+/// POST /auth/qr/start - Open a QR login channel
+/// 
+/// # Description
+/// Called by the TV/desktop that wants to log in. The returned channel_code is
+/// shown as a QR code; the poll_secret stays on this device and is required
+/// to collect the tokens from POST /auth/qr/token.
+pub async fn qr_login_start_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<QrLoginStartRequest>,
+) -> Result<(StatusCode, Json<QrLoginStartResponse>), AuthError> {
+    let jwt_manager = state.jwt_manager.clone();
+    let qr_service = QrLoginService::new(state.pool.clone(), state.challenge_store.clone(), jwt_manager);
+
+    let context = LoginContext {
+        ip_address: extract_ip_address(&headers),
+        user_agent: extract_user_agent(&headers),
+    };
+
+    let start = qr_service.start(req.app_id, &context).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(QrLoginStartResponse {
+            channel_code: start.channel_code,
+            poll_secret: start.poll_secret,
+            expires_in: start.expires_in,
+            interval: start.interval,
+        }),
+    ))
+}
+
+/// POST /auth/qr/approve - Approve or deny a QR login from a logged-in device
+/// 
+/// # Description
+/// Called by the user's phone after scanning the QR code. The channel is bound
+/// to the authenticated user; the response describes the device being logged in.
+pub async fn qr_login_approve_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
+    Json(req): Json<QrLoginApproveRequest>,
+) -> Result<Json<QrLoginApproveResponse>, AuthError> {
+    let user_id = claims.user_id()?;
+    let jwt_manager = state.jwt_manager.clone();
+    let qr_service = QrLoginService::new(state.pool.clone(), state.challenge_store.clone(), jwt_manager);
+
+    let context = LoginContext {
+        ip_address: extract_ip_address(&headers),
+        user_agent: extract_user_agent(&headers),
+    };
+
+    let channel = qr_service
+        .respond(&req.channel_code, user_id, req.approve, &context)
+        .await?;
+
+    Ok(Json(QrLoginApproveResponse {
+        status: channel.status.as_str().to_string(),
+        device_ip_address: channel.ip_address,
+        device_user_agent: channel.user_agent,
+    }))
+}
+
+/// POST /auth/qr/token - Poll a QR login channel for tokens
+/// 
+/// # Description
+/// Returns `{"status": "pending"}` until the channel is approved, then the
+/// token pair exactly once. Denied channels return invalid_credentials,
+/// expired channels return token_expired.
+pub async fn qr_login_token_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<QrLoginTokenRequest>,
+) -> Result<Json<QrLoginTokenResponse>, AuthError> {
+    let jwt_manager = state.jwt_manager.clone();
+    let qr_service = QrLoginService::new(state.pool.clone(), state.challenge_store.clone(), jwt_manager)
+        .with_travel_policy(TravelPolicy::from_config(&state.config));
+
+    let context = LoginContext {
+        ip_address: extract_ip_address(&headers),
+        user_agent: extract_user_agent(&headers),
+    };
+
+    match qr_service
+        .poll(&req.channel_code, &req.poll_secret, &context)
+        .await?
+    {
+        QrPollResult::Pending { interval } => {
+            Ok(Json(QrLoginTokenResponse::Pending(QrLoginPendingResponse {
+                status: "pending".to_string(),
+                interval,
+            })))
+        }
+        QrPollResult::Approved { tokens } => {
+            Ok(Json(QrLoginTokenResponse::Success(TokenResponse {
+                access_token: tokens.access_token,
+                refresh_token: Some(tokens.refresh_token),
+                token_type: tokens.token_type,
+                expires_in: tokens.expires_in,
+            })))
+        }
+    }
+}
+
+/// GET /auth/claims - Resolve the full app claims of the caller's access token
+/// 
+/// # Description
+/// Tokens issued with JWT_CLAIMS_MODE=roles_only or compressed carry an
+/// `apps_ref` instead of the full roles and permissions. This returns the
+/// user's current app claims; `matches_token` is false when they changed
+/// since the token was issued.
+pub async fn resolve_claims_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ResolvedClaimsResponse>, AuthError> {
+    let user_id = claims.user_id()?;
+    let auth_service = AuthService::new(state.pool.clone(), state.jwt_manager.clone());
+
+    let apps = auth_service.get_user_app_claims(user_id).await?;
+    let apps_ref = apps_digest(&apps);
+    let token_ref = claims.apps_ref.clone().unwrap_or_else(|| apps_digest(&claims.apps));
+
+    Ok(Json(ResolvedClaimsResponse {
+        sub: claims.sub,
+        matches_token: token_ref == apps_ref,
+        apps,
+        apps_ref,
+    }))
 }
@@ -0,0 +1,102 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppState;
+use crate::error::{AppError, AuthError};
+use crate::handlers::admin::require_system_admin;
+use crate::models::EmailBounce;
+use crate::repositories::EmailBounceRepository;
+use crate::services::AuditService;
+use crate::utils::email_events::{self, EmailProvider};
+use crate::utils::jwt::Claims;
+use crate::utils::secret::constant_time_compare;
+
+/// Most bounced addresses returned by the list endpoint
+const LIST_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct EmailWebhookQuery {
+    /// Must match `EMAIL_WEBHOOK_SECRET`
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmailWebhookResponse {
+    /// Hard bounces and complaints recorded from the payload
+    pub recorded: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BounceListResponse {
+    pub bounces: Vec<EmailBounce>,
+}
+
+/// POST /webhooks/email/:provider - Bounce and complaint events from the email provider
+///
+/// `provider` is `sendgrid`, `mailgun`, `ses` or `generic`. The provider is
+/// configured to call the URL with `?token=<EMAIL_WEBHOOK_SECRET>`; without
+/// a secret configured the webhook does not exist. Hard bounces and spam
+/// complaints put the address on the bounce list, other events are ignored.
+pub async fn email_webhook_handler(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    Query(query): Query<EmailWebhookQuery>,
+    body: String,
+) -> Result<Json<EmailWebhookResponse>, AppError> {
+    let secret = &state.config.email_webhook_secret;
+    if secret.is_empty() {
+        return Err(AppError::NotFound("Email webhook is not enabled".into()));
+    }
+    if !query.token.as_deref().is_some_and(|token| constant_time_compare(token, secret)) {
+        return Err(AppError::Auth(AuthError::InvalidToken));
+    }
+
+    let provider: EmailProvider = provider.parse().map_err(AppError::ValidationError)?;
+    let events = email_events::parse(provider, &body).map_err(AppError::ValidationError)?;
+
+    let repo = EmailBounceRepository::new(state.pool.clone());
+    for event in &events {
+        repo.record(&event.email, event.kind, provider.as_str(), event.detail.as_deref())
+            .await?;
+        tracing::info!("Email to {} suppressed after {} ({})", event.email, event.kind, provider.as_str());
+    }
+
+    Ok(Json(EmailWebhookResponse { recorded: events.len() }))
+}
+
+/// GET /admin/notifications/bounces - Addresses no email is sent to (admin only)
+pub async fn list_bounces_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<BounceListResponse>, AppError> {
+    require_system_admin(&state, &claims).await?;
+
+    let bounces = EmailBounceRepository::new(state.pool.clone()).list(LIST_LIMIT).await?;
+    Ok(Json(BounceListResponse { bounces }))
+}
+
+/// DELETE /admin/notifications/bounces/:email - Send email to a bounced address again (admin only)
+///
+/// For when the mailbox was fixed or the complaint was a mistake; the next
+/// bounce or complaint reported for it suppresses it again.
+pub async fn clear_bounce_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(email): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let actor_id = require_system_admin(&state, &claims).await?;
+
+    if !EmailBounceRepository::new(state.pool.clone()).remove(&email).await? {
+        return Err(AppError::NotFound("Address is not on the bounce list".into()));
+    }
+
+    let _ = AuditService::new(state.pool.clone())
+        .log_email_bounce_cleared(actor_id, &email.trim().to_lowercase())
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -7,9 +7,12 @@ use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::config::AppState;
-use crate::dto::{CreateIpRuleRequest, IpRuleResponse, IpCheckResponse};
+use crate::dto::{
+    ApproveIpRuleSuggestionRequest, ApproveIpRuleSuggestionResponse, CreateIpRuleRequest,
+    IpCheckResponse, IpRuleResponse, IpRuleSuggestionResponse,
+};
 use crate::error::{AppError, AuthError};
-use crate::models::IpRuleType;
+use crate::models::{IpRuleSuggestion, IpRuleType, SUGGESTION_APPROVED, SUGGESTION_PENDING, SUGGESTION_REJECTED};
 use crate::services::{IpRuleService, IpAccessResult};
 use crate::utils::jwt::Claims;
 use crate::repositories::UserRepository;
@@ -20,6 +23,13 @@ pub struct IpCheckQuery {
     pub app_id: Option<Uuid>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct IpRuleSuggestionQuery {
+    /// "pending" (default), "approved", "rejected" or "all"
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
 /// POST /admin/ip-rules - Create IP rule (admin only)
 pub async fn create_ip_rule_handler(
     State(state): State<AppState>,
@@ -218,3 +228,113 @@ pub async fn delete_ip_rule_handler(
     service.delete_rule(rule_id).await?;
     Ok(StatusCode::NO_CONTENT)
 }
+
+/// GET /admin/ip-rules/suggestions - List deny rules suggested by abuse telemetry (admin only)
+pub async fn list_ip_rule_suggestions_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<IpRuleSuggestionQuery>,
+) -> Result<Json<Vec<IpRuleSuggestionResponse>>, AppError> {
+    let user_id = claims.user_id()?;
+
+    // Check admin
+    let user_repo = UserRepository::new(state.pool.clone());
+    let user = user_repo.find_by_id(user_id).await?
+        .ok_or(AuthError::UserNotFound)?;
+
+    if !user.is_system_admin {
+        return Err(AppError::Auth(AuthError::NotSystemAdmin));
+    }
+
+    let status = match query.status.as_deref().unwrap_or(SUGGESTION_PENDING) {
+        "all" => None,
+        s @ (SUGGESTION_PENDING | SUGGESTION_APPROVED | SUGGESTION_REJECTED) => Some(s),
+        _ => return Err(AppError::ValidationError("Invalid suggestion status".into())),
+    };
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+
+    let service = IpRuleService::new(state.pool.clone());
+    let suggestions = service.list_suggestions(status, limit).await?;
+
+    Ok(Json(suggestions.into_iter().map(suggestion_response).collect()))
+}
+
+/// POST /admin/ip-rules/suggestions/:suggestion_id/approve - Create the suggested global deny rule
+pub async fn approve_ip_rule_suggestion_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(suggestion_id): Path<Uuid>,
+    req: Option<Json<ApproveIpRuleSuggestionRequest>>,
+) -> Result<Json<ApproveIpRuleSuggestionResponse>, AppError> {
+    let user_id = claims.user_id()?;
+
+    // Check admin
+    let user_repo = UserRepository::new(state.pool.clone());
+    let user = user_repo.find_by_id(user_id).await?
+        .ok_or(AuthError::UserNotFound)?;
+
+    if !user.is_system_admin {
+        return Err(AppError::Auth(AuthError::NotSystemAdmin));
+    }
+
+    let req = req.map(|Json(r)| r).unwrap_or_default();
+
+    let service = IpRuleService::new(state.pool.clone());
+    let (suggestion, rule) = service.approve_suggestion(suggestion_id, req.expires_at, user_id).await?;
+
+    Ok(Json(ApproveIpRuleSuggestionResponse {
+        suggestion: suggestion_response(suggestion),
+        rule: IpRuleResponse {
+            id: rule.id_uuid(),
+            app_id: rule.app_id,
+            ip_address: rule.ip_address,
+            ip_range: rule.ip_range,
+            rule_type: rule.rule_type,
+            reason: rule.reason,
+            expires_at: rule.expires_at,
+            created_by: rule.created_by,
+            created_at: rule.created_at,
+        },
+    }))
+}
+
+/// POST /admin/ip-rules/suggestions/:suggestion_id/reject - Dismiss a suggested deny rule
+pub async fn reject_ip_rule_suggestion_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(suggestion_id): Path<Uuid>,
+) -> Result<Json<IpRuleSuggestionResponse>, AppError> {
+    let user_id = claims.user_id()?;
+
+    // Check admin
+    let user_repo = UserRepository::new(state.pool.clone());
+    let user = user_repo.find_by_id(user_id).await?
+        .ok_or(AuthError::UserNotFound)?;
+
+    if !user.is_system_admin {
+        return Err(AppError::Auth(AuthError::NotSystemAdmin));
+    }
+
+    let service = IpRuleService::new(state.pool.clone());
+    let suggestion = service.reject_suggestion(suggestion_id, user_id).await?;
+
+    Ok(Json(suggestion_response(suggestion)))
+}
+
+fn suggestion_response(s: IpRuleSuggestion) -> IpRuleSuggestionResponse {
+    IpRuleSuggestionResponse {
+        id: s.id_uuid(),
+        ip_address: s.ip_address,
+        route: s.route,
+        window_start: s.window_start,
+        client_errors: s.client_errors,
+        rate_limited: s.rate_limited,
+        reason: s.reason,
+        status: s.status,
+        ip_rule_id: s.ip_rule_id,
+        reviewed_by: s.reviewed_by,
+        reviewed_at: s.reviewed_at,
+        last_seen_at: s.last_seen_at,
+        created_at: s.created_at,
+    }
+}
@@ -0,0 +1,39 @@
+use axum::{
+    extract::State,
+    http::{header::{AUTHORIZATION, CONTENT_TYPE}, HeaderMap},
+    response::IntoResponse,
+};
+
+use crate::config::AppState;
+use crate::error::{AppError, AuthError};
+use crate::utils::metrics::{self, OPENMETRICS_CONTENT_TYPE};
+use crate::utils::secret::constant_time_compare;
+
+/// GET /metrics - Per-app and per-client SLO series (OpenMetrics)
+///
+/// Scrapers authenticate with `Authorization: Bearer <METRICS_TOKEN>`;
+/// without a token configured the endpoint does not exist. Counts are kept
+/// per instance since it started, so scrape every replica.
+pub async fn metrics_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    let token = &state.config.metrics_token;
+    if token.is_empty() {
+        return Err(AppError::NotFound("Metrics are not enabled".into()));
+    }
+
+    let presented = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+    if !presented.is_some_and(|presented| constant_time_compare(presented, token)) {
+        return Err(AppError::Auth(AuthError::InvalidToken));
+    }
+
+    Ok((
+        [(CONTENT_TYPE, OPENMETRICS_CONTENT_TYPE)],
+        metrics::render(state.config.metrics_top_n),
+    ))
+}
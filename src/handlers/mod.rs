@@ -5,6 +5,16 @@ pub mod permission;
 pub mod user_management;
 pub mod admin;
 pub mod admin_scope;
+pub mod admin_oauth_client;
+pub mod admin_approval;
+pub mod admin_broadcast;
+pub mod email_bounce;
+pub mod metrics;
+pub mod admin_chaos;
+pub mod admin_debug;
+pub mod admin_encryption;
+pub mod admin_signing_key;
+pub mod admin_token;
 pub mod oauth;
 pub mod user_profile;
 pub mod security;
@@ -4,34 +4,75 @@
 //! - GET /oauth/authorize - Authorization endpoint (Requirement 11.1)
 //! - POST /oauth/token - Token endpoint (Requirement 11.2)
 //! - POST /oauth/revoke - Revocation endpoint (Requirement 11.3)
+//! - POST /oauth/introspect - Introspection endpoint (RFC 7662)
+//! - POST /oauth/par - Pushed authorization request endpoint (RFC 9126)
+//! - POST /oauth/device_authorization - Device authorization endpoint (RFC 8628)
+//! - GET /oauth/device - Device verification (RFC 8628)
+//! - POST /oauth/device/verify - Approve or deny a device (RFC 8628)
+//! - GET /oauth/logout - End session endpoint (OpenID Connect RP-Initiated Logout)
 //! - GET /oauth/userinfo - UserInfo endpoint (Requirement 11.4)
 //! - GET /.well-known/openid-configuration - Discovery endpoint (Requirement 11.5)
+//! - GET /.well-known/auth-server - Server capabilities
 //! - POST /oauth/clients - Client registration endpoint (Requirement 1.1, 1.4)
+//! - POST /oauth/clients/{id}/secret/rotate - Rotate the client secret with an overlap window
+//! - GET/PUT/DELETE /oauth/clients/{id}/jwks - Keys for signed request objects (RFC 9101)
+//! - GET/PUT/DELETE /oauth/register/{client_id} - Client configuration endpoint (RFC 7592)
 //! - GET /account/connected-apps - List connected apps (Requirement 9.1)
 //! - DELETE /account/connected-apps/{client_id} - Revoke consent (Requirement 9.2, 9.3)
 
+use std::time::{Duration, Instant};
+
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::{IntoResponse, Redirect, Response},
+    http::{header::{CACHE_CONTROL, CONTENT_TYPE, SET_COOKIE}, StatusCode},
+    response::{AppendHeaders, IntoResponse, Redirect, Response},
     Extension, Json,
 };
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::config::AppState;
 use crate::dto::oauth::{
-    AuthorizationRequest, ClientRegistrationRequest, ClientRegistrationResponse,
-    ConnectedAppInfo, ConnectedAppsResponse, OAuthTokenResponseDto, OpenIdConfiguration,
-    RegenerateClientSecretResponse, RevokeRequest, TokenRequest, UpdateOAuthClientRequest,
-    UserInfoResponse,
+    AuthorizationRequest, ClientRegistrationRequest, Prompt, ClientRegistrationResponse, ClientScopeInfo,
+    ClientConfigurationResponse, ClientConfigurationUpdateRequest, ConnectedAppInfo, ConnectedAppsResponse, CreateClientScopeRequest, DeviceAuthorizationRequest,
+    DeviceDecisionRequest, DeviceVerificationQuery, EndSessionRequest, ListClientScopesResponse, ScopeInfo, OAuthTokenResponseDto, OpenIdConfiguration,
+    IntrospectRequest, ListClientSecretRotationsResponse, PushedAuthorizationRequest, RegenerateClientSecretResponse, RevokeRequest, RotateClientSecretRequest, RotateClientSecretResponse, TokenRequest, UpdateClientScopeRequest,
+    UpdateOAuthClientRequest, UserInfoResponse,
+};
+use crate::error::{AuthError, OAuthError};
+use crate::models::{
+    ClientJwks, OAuthClient, OAuthEventType, CLIENT_TYPE_NATIVE, CLIENT_TYPE_WEB, SCOPE_VISIBILITY_GLOBAL,
+    SCOPE_VISIBILITY_PENDING, SCOPE_VISIBILITY_PRIVATE,
+};
+use crate::repositories::{
+    ClientJwksRepository, OAuthAuditLogRepository, OAuthClientRepository, OAuthScopeRepository, UserAddressRepository,
+    UserRepository, WebAuthnRepository,
+};
+use crate::handlers::auth::extract_user_agent;
+use crate::services::{
+    ConsentService, CoolingOffService, DeviceAuthorizationResponse, IntrospectionResponse, OAuthService,
+    PushedAuthorizationResponse, SessionPolicy, SessionService, TokenRevocationService, TrustedDeviceService,
 };
-use crate::error::OAuthError;
-use crate::models::OAuthEventType;
-use crate::repositories::{OAuthAuditLogRepository, OAuthClientRepository, OAuthScopeRepository, UserRepository};
-use crate::services::{ConsentService, OAuthService};
-use crate::utils::jwt::{Claims, OAuth2Claims};
-use crate::utils::secret::{generate_secret, hash_secret};
+use crate::services::instance::ServerCapabilities;
+use crate::services::oauth::{DEVICE_CODE_GRANT_TYPE, PAR_REQUEST_URI_PREFIX, SUPPORTED_GRANT_TYPES};
+use crate::utils::client_auth::{client_credentials, OAuthBody};
+use crate::utils::cooling_off::CoolingOffPolicy;
+use crate::utils::cookie::{
+    browser_binding, generate_csrf_token, get_cookie, RefreshCookieSettings, BROWSER_BINDING_COOKIE_NAME,
+    TRUSTED_DEVICE_COOKIE_NAME,
+};
+use crate::utils::acr::{self, AcrLevel};
+use crate::utils::jwt::{Claims, OAuth2Claims, USERINFO_SIGNING_ALG_VALUES_SUPPORTED};
+use crate::utils::metrics::{self, Outcome};
+use crate::utils::password::hash_token;
+use crate::utils::posture::{DeviceTrust, PostureContext};
+use crate::utils::redirect_uri::{RedirectUriPolicy, RedirectUriProfile};
+use crate::utils::request_object::validate_client_jwks;
+use crate::utils::scope_code::validate_custom_scope_code;
+use crate::utils::secret::{generate_oauth_token, generate_secret, hash_oauth_token, hash_secret, weak_state_reason};
+use crate::utils::token_binding::client_ip;
+use crate::utils::userinfo_claims::{released_claims, validate_scope_claims};
 
 // ============================================================================
 // Authorization Endpoint (Task 11.1)
@@ -51,12 +92,22 @@ pub struct ConsentCallbackParams {
     pub redirect_uri: String,
     /// Scopes (comma-separated)
     pub scopes: String,
+    /// Scopes the user ticked on the consent screen (comma-separated, subset
+    /// of `scopes`; omitted approves all of them)
+    #[serde(default)]
+    pub approved_scopes: Option<String>,
     /// State parameter
     pub state: Option<String>,
     /// Code challenge for PKCE
     pub code_challenge: Option<String>,
     /// Code challenge method
     pub code_challenge_method: Option<String>,
+    /// OpenID Connect nonce
+    #[serde(default)]
+    pub nonce: Option<String>,
+    /// Also issue the scopes granted to the client before
+    #[serde(default)]
+    pub include_granted_scopes: bool,
 }
 
 /// GET /oauth/authorize - Authorization endpoint
@@ -84,11 +135,61 @@ pub struct ConsentCallbackParams {
 /// - Handle the consent form submission
 pub async fn authorize_handler(
     State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
     Query(req): Query<AuthorizationRequest>,
 ) -> Response {
-    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone());
+    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone())
+        .with_redirect_policy(RedirectUriPolicy::from_config(&state.config))
+        .with_strict(state.config.oauth_strict)
+        .with_issuer(issuer_url(&state))
+        .with_challenge_store(state.challenge_store.clone());
     let audit_repo = OAuthAuditLogRepository::new(state.pool.clone());
 
+    // A pushed request (RFC 9126) carries its parameters by reference and a
+    // request object (RFC 9101) signs them, passed by value or hosted at a
+    // registered request_uri; until they resolve there is no trusted
+    // redirect_uri, so errors are returned directly
+    let req = match (req.request_uri.as_deref(), req.request.is_some()) {
+        (Some(_), true) => {
+            return OAuthError::InvalidRequest(
+                "request and request_uri cannot be used together".to_string(),
+            )
+            .into_response();
+        }
+        (Some(request_uri), false) if request_uri.starts_with(PAR_REQUEST_URI_PREFIX) => match oauth_service
+            .resolve_pushed_authorization_request(request_uri, &req.client_id)
+            .await
+        {
+            Ok(pushed) => pushed,
+            Err(e) => return e.into_response(),
+        },
+        (Some(_), false) => match oauth_service.resolve_request_uri(&req).await {
+            Ok(resolved) => resolved,
+            Err(e) => return e.into_response(),
+        },
+        (None, true) => match oauth_service.resolve_request_object(&req).await {
+            Ok(resolved) => resolved,
+            Err(e) => return e.into_response(),
+        },
+        (None, false) if req.response_type.is_empty() || req.redirect_uri.is_empty() => {
+            return OAuthError::InvalidRequest(
+                "response_type and redirect_uri are required".to_string(),
+            )
+            .into_response();
+        }
+        (None, false) => req,
+    };
+
+    // Until client_id and redirect_uri are validated, errors are returned
+    // directly: redirecting them would make this an open redirector
+    let client = match oauth_service
+        .validate_client_redirect_uri(&req.client_id, &req.redirect_uri)
+        .await
+    {
+        Ok(client) => client,
+        Err(e) => return e.into_response(),
+    };
+
     // Validate response_type
     if req.response_type != "code" {
         return build_error_redirect(
@@ -99,23 +200,34 @@ pub async fn authorize_handler(
         );
     }
 
-    // Validate authorization request
-    let client = match oauth_service
-        .validate_authorization_request(
-            &req.client_id,
-            &req.redirect_uri,
+    // Validate the remaining authorization request parameters
+    let validation = match oauth_service
+        .validate_authorization_parameters(
+            &client,
             &req.scopes(),
             req.code_challenge.as_deref(),
             req.code_challenge_method.as_deref(),
         )
         .await
     {
-        Ok(client) => client,
-        Err(e) => {
+        Ok(()) => oauth_service.check_strict_authorization_request(&client, req.state.as_deref()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = validation {
+        return build_error_redirect(
+            &req.redirect_uri,
+            &error_code(&e),
+            &e.to_string(),
+            req.state.as_deref(),
+        );
+    }
+    let prompt = match req.prompt() {
+        Ok(prompt) => prompt,
+        Err(description) => {
             return build_error_redirect(
                 &req.redirect_uri,
-                &error_code(&e),
-                &e.to_string(),
+                "invalid_request",
+                &description,
                 req.state.as_deref(),
             );
         }
@@ -137,6 +249,24 @@ pub async fn authorize_handler(
         .await
         .ok(); // Don't fail if audit logging fails
 
+    // Warn about clients whose state parameter is too weak to protect
+    // against CSRF on their callback
+    if let Some(reason) = weak_state_reason(req.state.as_deref()) {
+        audit_repo
+            .create(
+                OAuthEventType::WeakStateParameter,
+                Some(client.id),
+                None,
+                None,
+                Some(serde_json::json!({
+                    "reason": reason,
+                    "state_length": req.state.as_deref().map(str::len).unwrap_or(0),
+                })),
+            )
+            .await
+            .ok();
+    }
+
     // In a real implementation, we would:
     // 1. Check if user is authenticated via session
     // 2. If not, redirect to login page with return URL
@@ -148,6 +278,154 @@ pub async fn authorize_handler(
     // that the client needs to handle user authentication and consent
     // through a separate flow, then call the consent callback endpoint.
 
+    // A signed-in user must sign in again when the client asks for it, when
+    // the sign-in is older than max_age, or when the client asks for a
+    // stronger sign-in than the session has. Without a session the user
+    // signs in before the consent callback anyway.
+    let session = signed_in_session(&state, &headers).await;
+    let login_reason = match &session {
+        Some(session) => login_required_reason(&state, &client, session, &req, prompt).await,
+        None if prompt.none => Some("no_session"),
+        None => None,
+    };
+    if let Some(reason) = login_reason {
+        if prompt.none {
+            return build_error_redirect(
+                &req.redirect_uri,
+                "login_required",
+                "The user must sign in",
+                req.state.as_deref(),
+            );
+        }
+
+        let response = serde_json::json!({
+            "status": "login_required",
+            "reason": reason,
+            "client_id": client.client_id,
+            "client_name": client.name,
+            "login_hint": req.login_hint,
+            "max_age": req.max_age,
+            "acr_values": req.acr_values(),
+            "required_acr": acr::parse_required(client.required_acr.as_deref())
+                .max(AcrLevel::requested(&req.acr_values()))
+                .map(|level| level.as_str()),
+            "message": "User must sign in again, then repeat the authorization request"
+        });
+        return (StatusCode::OK, Json(response)).into_response();
+    }
+
+    // Incremental authorization: when the signed-in user already granted
+    // this client some scopes, the consent screen only asks for the rest.
+    // An expired grant counts as no grant at all.
+    let requested_scopes = req.scopes();
+    let consent_service = ConsentService::new(state.pool.clone())
+        .with_consent_ttl_days(state.config.consent_ttl_days);
+    let (previously_granted, consent_expired) = match session.as_ref().map(|s| s.user_id) {
+        Some(user_id) => (
+            consent_service
+                .granted_scopes(user_id, client.id)
+                .await
+                .unwrap_or_default(),
+            consent_service
+                .consent_expired(user_id, client.id)
+                .await
+                .unwrap_or(false),
+        ),
+        None => (Vec::new(), false),
+    };
+    let (granted_scopes, new_scopes) = split_granted_scopes(
+        &requested_scopes,
+        &previously_granted,
+        req.include_granted_scopes,
+    );
+
+    // The consent screen can be skipped only when every requested scope is
+    // already granted and neither the client nor the request asks to prompt
+    let prompt_consent = !client.skips_consent()
+        && (client.always_prompt_consent
+            || prompt.consent
+            || previously_granted.is_empty()
+            || !new_scopes.is_empty());
+
+    // prompt=none: issue the code straight away or fail, never show a screen
+    if prompt.none {
+        if prompt_consent {
+            return build_error_redirect(
+                &req.redirect_uri,
+                "consent_required",
+                "The user must approve the requested scopes",
+                req.state.as_deref(),
+            );
+        }
+        let Some(session) = session else {
+            return build_error_redirect(
+                &req.redirect_uri,
+                "login_required",
+                "The user must sign in",
+                req.state.as_deref(),
+            );
+        };
+
+        let mut scopes = granted_scopes;
+        scopes.extend(new_scopes);
+        let browser = browser_binding(&headers);
+        if client.skips_consent() {
+            consent_service
+                .grant_implicit_consent(session.user_id, client.id, &scopes)
+                .await
+                .ok();
+        }
+        let code = oauth_service
+            .create_authorization_code(
+                client.id,
+                session.user_id,
+                &req.redirect_uri,
+                &scopes,
+                req.code_challenge.as_deref().unwrap_or(""),
+                req.code_challenge_method.as_deref(),
+                req.nonce.as_deref(),
+                Some(&session.binding_hash),
+                Some(&browser),
+                Some(session.auth_time),
+                session.acr.as_deref(),
+                session.amr.as_deref(),
+                sign_in_posture(&state, &client, &session, &headers).await.as_ref(),
+            )
+            .await;
+        return match code {
+            Ok(code) => {
+                let response = serde_json::json!({
+                    "status": "success",
+                    "redirect_url": code_redirect_url(&req.redirect_uri, &code, req.state.as_deref()),
+                });
+                let cookie = RefreshCookieSettings::from_config(&state.config)
+                    .browser_binding_cookie(&browser, state.config.session_absolute_lifetime_secs);
+                (StatusCode::OK, AppendHeaders([(SET_COOKIE, cookie)]), Json(response)).into_response()
+            }
+            Err(e) => build_error_redirect(
+                &req.redirect_uri,
+                &error_code(&e),
+                &e.to_string(),
+                req.state.as_deref(),
+            ),
+        };
+    }
+
+    // Descriptions for the consent screen, in the order the scopes were requested
+    let known_scopes = oauth_service
+        .scope_repo()
+        .find_by_codes(&new_scopes)
+        .await
+        .unwrap_or_default();
+    let scope_details: Vec<ScopeInfo> = new_scopes
+        .iter()
+        .filter_map(|code| known_scopes.iter().find(|s| &s.code == code))
+        .map(|s| ScopeInfo {
+            code: s.code.clone(),
+            description: s.description.clone(),
+        })
+        .collect();
+
     // Return information about what's needed for the authorization
     // In production, this would be a redirect to login/consent page
     let response = serde_json::json!({
@@ -155,10 +433,19 @@ pub async fn authorize_handler(
         "client_id": client.client_id,
         "client_name": client.name,
         "redirect_uri": req.redirect_uri,
-        "scopes": req.scopes(),
+        "scopes": requested_scopes,
+        "new_scopes": new_scopes,
+        "granted_scopes": granted_scopes,
+        "include_granted_scopes": req.include_granted_scopes,
+        "scope_details": scope_details,
         "state": req.state,
         "code_challenge": req.code_challenge,
         "code_challenge_method": req.code_challenge_method,
+        "nonce": req.nonce,
+        "login_hint": req.login_hint,
+        "skip_consent": client.skips_consent(),
+        "prompt_consent": prompt_consent,
+        "consent_expired": consent_expired,
         "message": "User authentication and consent required. Submit consent decision to POST /oauth/authorize/callback"
     });
 
@@ -177,10 +464,23 @@ pub async fn authorize_handler(
 /// - 9.5, 10.6: Log consent events for audit
 pub async fn authorize_callback_handler(
     State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
     Json(params): Json<ConsentCallbackParams>,
 ) -> Response {
-    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone());
-    let consent_service = ConsentService::new(state.pool.clone());
+    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone())
+        .with_redirect_policy(RedirectUriPolicy::from_config(&state.config));
+    let consent_service = ConsentService::new(state.pool.clone())
+        .with_consent_ttl_days(state.config.consent_ttl_days);
+
+    // The redirect_uri comes back from the browser, so it must be checked
+    // again exactly as at /oauth/authorize before any error is redirected to it
+    let client = match oauth_service
+        .validate_client_redirect_uri(&params.client_id, &params.redirect_uri)
+        .await
+    {
+        Ok(client) => client,
+        Err(e) => return e.into_response(),
+    };
 
     // Parse user_id
     let user_id = match uuid::Uuid::parse_str(&params.user_id) {
@@ -195,36 +495,33 @@ pub async fn authorize_callback_handler(
         }
     };
 
-    // Get client first for logging
-    let client = match oauth_service
-        .client_repo()
-        .find_active_by_client_id(&params.client_id)
-        .await
-    {
-        Ok(Some(c)) => c,
-        Ok(None) => {
-            return build_error_redirect(
-                &params.redirect_uri,
-                "invalid_client",
-                "Client not found",
-                params.state.as_deref(),
-            );
-        }
-        Err(e) => {
+    // The consent must come from the signed-in user's own session; the code
+    // is bound to that session so logging out invalidates it
+    let session = match verify_consent_session(&state, &headers, user_id).await {
+        Some(session) => session,
+        None => {
             return build_error_redirect(
                 &params.redirect_uri,
-                "server_error",
-                &e.to_string(),
+                "login_required",
+                "A valid session for this user is required",
                 params.state.as_deref(),
             );
         }
     };
+    if !meets_client_acr(&client, &session) {
+        return build_error_redirect(
+            &params.redirect_uri,
+            "login_required",
+            "The client requires a stronger sign-in",
+            params.state.as_deref(),
+        );
+    }
 
-    let scopes: Vec<String> = params.scopes.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+    let scopes = split_consent_scopes(&params.scopes);
 
     // Validate that all requested scopes exist
     // Requirement 2.4
-    if let Err(e) = oauth_service.validate_scopes(&scopes).await {
+    if let Err(e) = oauth_service.validate_scopes(&scopes, &client).await {
         return build_error_redirect(
             &params.redirect_uri,
             "invalid_scope",
@@ -233,8 +530,27 @@ pub async fn authorize_callback_handler(
         );
     }
 
-    // If user denied consent
-    if !params.approved {
+    // The user may approve only some of the requested scopes
+    let approved_scopes = match params.approved_scopes.as_deref() {
+        Some(approved) => split_consent_scopes(approved),
+        None => scopes.clone(),
+    };
+    if approved_scopes.iter().any(|scope| !scopes.contains(scope)) {
+        return build_error_redirect(
+            &params.redirect_uri,
+            "invalid_scope",
+            "approved_scopes must be a subset of the requested scopes",
+            params.state.as_deref(),
+        );
+    }
+    let declined_scopes: Vec<String> = scopes
+        .iter()
+        .filter(|scope| !approved_scopes.contains(scope))
+        .cloned()
+        .collect();
+
+    // If user denied consent (or approved none of the scopes)
+    if !params.approved || (!scopes.is_empty() && approved_scopes.is_empty()) {
         // Log consent denied event
         // Requirements: 9.5, 10.6
         consent_service
@@ -250,12 +566,49 @@ pub async fn authorize_callback_handler(
         );
     }
 
-    // Store consent if this is an external app
-    if client.is_external() {
-        if let Err(e) = consent_service
-            .grant_consent(user_id, client.id, &scopes)
+    // During a cooling-off period, a consent to a new client or one granting
+    // a high-risk scope is refused
+    if client.is_external() && !client.skips_consent() {
+        let checked = match consent_service.granted_scopes(user_id, client.id).await {
+            Ok(granted) => cooling_off_service(&state)
+                .check_consent(
+                    user_id,
+                    &granted,
+                    &approved_scopes,
+                    client_ip(&headers).as_deref(),
+                    extract_user_agent(&headers).as_deref(),
+                )
+                .await
+                .map_err(|e| match e {
+                    AuthError::CoolingOff { .. } => ("access_denied", e.to_string()),
+                    e => ("server_error", e.to_string()),
+                }),
+            Err(e) => Err(("server_error", e.to_string())),
+        };
+        if let Err((error, description)) = checked {
+            return build_error_redirect(&params.redirect_uri, error, &description, params.state.as_deref());
+        }
+    }
+
+    // First-party clients skip the consent screen but still record an
+    // implicit grant so it is audited and can be revoked
+    let consent = if client.skips_consent() {
+        consent_service
+            .grant_implicit_consent(user_id, client.id, &approved_scopes)
             .await
-        {
+            .map(Some)
+    } else if client.is_external() {
+        // Store consent if this is an external app
+        consent_service
+            .grant_partial_consent(user_id, client.id, &approved_scopes, &declined_scopes)
+            .await
+            .map(Some)
+    } else {
+        Ok(None)
+    };
+    let consent = match consent {
+        Ok(consent) => consent,
+        Err(e) => {
             return build_error_redirect(
                 &params.redirect_uri,
                 "server_error",
@@ -263,10 +616,33 @@ pub async fn authorize_callback_handler(
                 params.state.as_deref(),
             );
         }
+    };
+
+    // The code carries only the approved scopes. With include_granted_scopes
+    // it also carries everything granted so far, minus scopes that were
+    // deactivated or withdrawn from the client since
+    let mut scopes = approved_scopes;
+    if params.include_granted_scopes {
+        if let Some(consent) = &consent {
+            let still_valid = oauth_service
+                .scope_repo()
+                .find_by_codes(&consent.scopes)
+                .await
+                .unwrap_or_default();
+            for scope in &consent.scopes {
+                let valid = still_valid
+                    .iter()
+                    .any(|s| &s.code == scope && s.is_available_to(client.id));
+                if valid && !scopes.contains(scope) {
+                    scopes.push(scope.clone());
+                }
+            }
+        }
     }
 
-    // Generate authorization code
+    // Generate authorization code, bound to this session and browser
     let code_challenge = params.code_challenge.as_deref().unwrap_or("");
+    let browser = browser_binding(&headers);
     let code = match oauth_service
         .create_authorization_code(
             client.id,
@@ -275,6 +651,13 @@ pub async fn authorize_callback_handler(
             &scopes,
             code_challenge,
             params.code_challenge_method.as_deref(),
+            params.nonce.as_deref(),
+            Some(&session.binding_hash),
+            Some(&browser),
+            Some(session.auth_time),
+            session.acr.as_deref(),
+            session.amr.as_deref(),
+            sign_in_posture(&state, &client, &session, &headers).await.as_ref(),
         )
         .await
     {
@@ -289,21 +672,15 @@ pub async fn authorize_callback_handler(
         }
     };
 
-    // Build redirect URL with authorization code
-    let mut redirect_url = params.redirect_uri.clone();
-    redirect_url.push_str(if redirect_url.contains('?') { "&" } else { "?" });
-    redirect_url.push_str(&format!("code={}", urlencoding::encode(&code)));
-    if let Some(state) = &params.state {
-        redirect_url.push_str(&format!("&state={}", urlencoding::encode(state)));
-    }
-
     // Return JSON response for frontend to handle redirect
     let response = serde_json::json!({
         "status": "success",
-        "redirect_url": redirect_url
+        "redirect_url": code_redirect_url(&params.redirect_uri, &code, params.state.as_deref())
     });
+    let cookie = RefreshCookieSettings::from_config(&state.config)
+        .browser_binding_cookie(&browser, state.config.session_absolute_lifetime_secs);
 
-    (StatusCode::OK, Json(response)).into_response()
+    (StatusCode::OK, AppendHeaders([(SET_COOKIE, cookie)]), Json(response)).into_response()
 }
 
 // ============================================================================
@@ -320,37 +697,145 @@ pub async fn authorize_callback_handler(
 /// - authorization_code: Exchange code for tokens (Requirement 5.1)
 /// - client_credentials: Machine-to-machine auth (Requirement 6.1)
 /// - refresh_token: Refresh access token (Requirement 7.1)
+/// - urn:ietf:params:oauth:grant-type:device_code: Device polling (RFC 8628)
 ///
 /// # Requirements
 /// - 11.2: Expose POST /oauth/token for token requests
 pub async fn token_handler(
     State(state): State<AppState>,
-    axum::Form(req): axum::Form<TokenRequest>,
-) -> Result<Json<OAuthTokenResponseDto>, OAuthError> {
-    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone());
+    headers: axum::http::HeaderMap,
+    OAuthBody(mut req): OAuthBody<TokenRequest>,
+) -> Result<Response, OAuthError> {
+    let started = Instant::now();
+    (req.client_id, req.client_secret) =
+        client_credentials(&headers, req.client_id.take(), req.client_secret.take())?;
+
+    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone())
+        .with_session_defaults(SessionPolicy::from_config(&state.config))
+        .with_secret_max_age_days(state.config.client_secret_max_age_days)
+        .with_max_access_token_ttl_secs(state.config.oauth_max_access_token_ttl_secs)
+        .with_issuer(issuer_url(&state))
+        .with_challenge_store(state.challenge_store.clone());
+    let cookie_settings = RefreshCookieSettings::from_config(&state.config);
+
+    // Clients configured for cookie delivery keep refresh tokens out of the body
+    let cookie_client_id = match req.client_id.as_deref() {
+        Some(client_id) if req.grant_type != "client_credentials" => oauth_service
+            .client_repo()
+            .find_active_by_client_id(client_id)
+            .await?
+            .filter(|c| c.refresh_token_cookie)
+            .map(|c| c.client_id),
+        _ => None,
+    };
 
-    let response = match req.grant_type.as_str() {
+    if let Some(client_id) = &cookie_client_id {
+        if req.grant_type == "refresh_token" && req.refresh_token.is_none() {
+            // The CSRF middleware has already checked the double-submit token
+            req.refresh_token = get_cookie(&headers, &cookie_settings.oauth_cookie_name(client_id));
+        }
+    }
+
+    let result = match req.grant_type.as_str() {
         "authorization_code" => {
-            handle_authorization_code_grant(&oauth_service, &req).await?
+            let browser = get_cookie(&headers, BROWSER_BINDING_COOKIE_NAME);
+            handle_authorization_code_grant(&oauth_service, &req, browser.as_deref()).await
         }
         "client_credentials" => {
-            handle_client_credentials_grant(&oauth_service, &req).await?
+            handle_client_credentials_grant(&oauth_service, &req).await
         }
         "refresh_token" => {
-            handle_refresh_token_grant(&oauth_service, &req).await?
+            handle_refresh_token_grant(&oauth_service, &req).await
         }
-        _ => {
-            return Err(OAuthError::UnsupportedGrantType);
+        DEVICE_CODE_GRANT_TYPE => {
+            handle_device_code_grant(&oauth_service, &req).await
         }
+        _ => Err(OAuthError::UnsupportedGrantType),
     };
+    record_token_metric(&req, &result, started.elapsed());
 
-    Ok(Json(response))
+    let mut response = match result {
+        Ok(response) => response,
+        Err(e) => {
+            record_token_failure(&oauth_service, &state, &req, &e).await;
+            return Err(e);
+        }
+    };
+
+    let (client_id, refresh_token) = match (cookie_client_id, response.refresh_token.clone()) {
+        (Some(client_id), Some(token)) => (client_id, token),
+        _ => return Ok(Json(response).into_response()),
+    };
+    response.refresh_token = None;
+
+    let max_age = state.config.refresh_token_expiry_secs;
+    let cookie_name = cookie_settings.oauth_cookie_name(&client_id);
+    let refresh_cookie = cookie_settings.refresh_cookie(&cookie_name, &refresh_token, max_age);
+    let csrf_cookie = cookie_settings.csrf_cookie(&generate_csrf_token(), max_age);
+
+    Ok((
+        AppendHeaders([(SET_COOKIE, refresh_cookie), (SET_COOKIE, csrf_cookie)]),
+        Json(response),
+    )
+        .into_response())
+}
+
+/// Count a token request in the per-client SLO metrics
+///
+/// Device flow polling answers are not counted, and requests from clients
+/// that failed authentication are counted as `unknown` so made-up client ids
+/// do not create series.
+fn record_token_metric(req: &TokenRequest, result: &Result<OAuthTokenResponseDto, OAuthError>, elapsed: Duration) {
+    let (client_id, grant_type, outcome) = match result {
+        Ok(_) => (req.client_id.as_deref(), req.grant_type.as_str(), Outcome::Success),
+        Err(OAuthError::AuthorizationPending | OAuthError::SlowDown { .. }) => return,
+        Err(OAuthError::UnsupportedGrantType) => (req.client_id.as_deref(), "unsupported", Outcome::Failure),
+        Err(OAuthError::InvalidClient) => (None, req.grant_type.as_str(), Outcome::Failure),
+        Err(OAuthError::ServerError(_)) => (req.client_id.as_deref(), req.grant_type.as_str(), Outcome::Error),
+        Err(_) => (req.client_id.as_deref(), req.grant_type.as_str(), Outcome::Failure),
+    };
+    metrics::record_token_request(client_id, grant_type, outcome, elapsed);
+}
+
+/// Record a rejected token request in the OAuth audit log for the error statistics
+///
+/// Device flow polling answers (authorization_pending, slow_down) are not failures.
+async fn record_token_failure(oauth_service: &OAuthService, state: &AppState, req: &TokenRequest, error: &OAuthError) {
+    if matches!(error, OAuthError::AuthorizationPending | OAuthError::SlowDown { .. }) {
+        return;
+    }
+
+    let client_id = match req.client_id.as_deref() {
+        Some(client_id) => oauth_service
+            .client_repo()
+            .find_by_client_id(client_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|c| c.id),
+        None => None,
+    };
+
+    OAuthAuditLogRepository::new(state.pool.clone())
+        .create(
+            OAuthEventType::TokenRequestFailed,
+            client_id,
+            None,
+            None,
+            Some(serde_json::json!({
+                "grant_type": req.grant_type,
+                "error": error_code(error),
+            })),
+        )
+        .await
+        .ok();
 }
 
 /// Handle authorization_code grant type
 async fn handle_authorization_code_grant(
     oauth_service: &OAuthService,
     req: &TokenRequest,
+    browser_binding: Option<&str>,
 ) -> Result<OAuthTokenResponseDto, OAuthError> {
     let code = req.code.as_ref().ok_or_else(|| {
         OAuthError::InvalidRequest("code is required".to_string())
@@ -375,6 +860,7 @@ async fn handle_authorization_code_grant(
             req.client_secret.as_deref(),
             redirect_uri,
             code_verifier,
+            browser_binding,
         )
         .await?;
 
@@ -419,6 +905,198 @@ async fn handle_refresh_token_grant(
     Ok(response.into())
 }
 
+/// Handle device_code grant type
+async fn handle_device_code_grant(
+    oauth_service: &OAuthService,
+    req: &TokenRequest,
+) -> Result<OAuthTokenResponseDto, OAuthError> {
+    let device_code = req.device_code.as_ref().ok_or_else(|| {
+        OAuthError::InvalidRequest("device_code is required".to_string())
+    })?;
+
+    let client_id = req.client_id.as_ref().ok_or_else(|| {
+        OAuthError::InvalidRequest("client_id is required".to_string())
+    })?;
+
+    let response = oauth_service
+        .device_code_grant(device_code, client_id, req.client_secret.as_deref())
+        .await?;
+
+    Ok(response.into())
+}
+
+// ============================================================================
+// Pushed Authorization Request Endpoint (RFC 9126)
+// ============================================================================
+
+/// POST /oauth/par - Pushed authorization request endpoint
+///
+/// The client pushes its authorization parameters over the back channel and
+/// sends the user to `GET /oauth/authorize?client_id=...&request_uri=...`,
+/// so the parameters are neither exposed nor alterable in the browser.
+pub async fn par_handler(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    OAuthBody(mut req): OAuthBody<PushedAuthorizationRequest>,
+) -> Result<(StatusCode, Json<PushedAuthorizationResponse>), OAuthError> {
+    let (client_id, client_secret) =
+        client_credentials(&headers, req.client_id.take(), req.client_secret.take())?;
+    req.client_id = Some(client_id.ok_or_else(|| {
+        OAuthError::InvalidRequest("client_id is required".to_string())
+    })?);
+    req.client_secret = client_secret;
+
+    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone())
+        .with_redirect_policy(RedirectUriPolicy::from_config(&state.config))
+        .with_secret_max_age_days(state.config.client_secret_max_age_days)
+        .with_strict(state.config.oauth_strict)
+        .with_issuer(issuer_url(&state))
+        .with_challenge_store(state.challenge_store.clone());
+
+    let response = oauth_service
+        .push_authorization_request(&req.authorization_request(), req.client_secret.as_deref())
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+// ============================================================================
+// Device Authorization Endpoints (RFC 8628)
+// ============================================================================
+
+/// POST /oauth/device_authorization - Device authorization endpoint
+///
+/// Starts the device flow for clients that cannot open a browser (CLIs, TVs).
+/// The device shows `user_code` and `verification_uri`, then polls
+/// `POST /oauth/token` with `grant_type=urn:ietf:params:oauth:grant-type:device_code`.
+pub async fn device_authorization_handler(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    OAuthBody(req): OAuthBody<DeviceAuthorizationRequest>,
+) -> Result<Json<DeviceAuthorizationResponse>, OAuthError> {
+    let (client_id, client_secret) =
+        client_credentials(&headers, req.client_id.clone(), req.client_secret.clone())?;
+    let client_id = client_id.ok_or_else(|| {
+        OAuthError::InvalidRequest("client_id is required".to_string())
+    })?;
+
+    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone())
+        .with_secret_max_age_days(state.config.client_secret_max_age_days)
+        .with_challenge_store(state.challenge_store.clone());
+
+    let verification_uri = format!("{}/oauth/device", issuer_url(&state));
+    let response = oauth_service
+        .start_device_authorization(
+            &client_id,
+            client_secret.as_deref(),
+            &req.scopes(),
+            &verification_uri,
+        )
+        .await?;
+
+    Ok(Json(response))
+}
+
+/// GET /oauth/device - Device verification endpoint
+///
+/// The verification_uri shown on the device. With a `user_code` it returns
+/// what the consent screen needs; the signed-in user then submits the
+/// decision to `POST /oauth/device/verify`.
+pub async fn device_verification_handler(
+    State(state): State<AppState>,
+    Query(query): Query<DeviceVerificationQuery>,
+) -> Result<Json<serde_json::Value>, OAuthError> {
+    let user_code = match query.user_code.as_deref() {
+        Some(code) => code,
+        None => {
+            return Ok(Json(serde_json::json!({
+                "status": "user_code_required",
+                "message": "Enter the code shown on your device"
+            })));
+        }
+    };
+
+    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone())
+        .with_challenge_store(state.challenge_store.clone());
+    let (device_code, client) = oauth_service.find_pending_device_authorization(user_code).await?;
+
+    let known_scopes = oauth_service
+        .scope_repo()
+        .find_by_codes(&device_code.scopes)
+        .await
+        .unwrap_or_default();
+    let scope_details: Vec<ScopeInfo> = device_code
+        .scopes
+        .iter()
+        .filter_map(|code| known_scopes.iter().find(|s| &s.code == code))
+        .map(|s| ScopeInfo {
+            code: s.code.clone(),
+            description: s.description.clone(),
+        })
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "status": "consent_required",
+        "client_id": client.client_id,
+        "client_name": client.name,
+        "scopes": device_code.scopes,
+        "scope_details": scope_details,
+        "skip_consent": client.skips_consent(),
+        "expires_at": device_code.expires_at,
+        "message": "User authentication and consent required. Submit the decision to POST /oauth/device/verify"
+    })))
+}
+
+/// POST /oauth/device/verify - Approve or deny a device
+///
+/// Requires JWT authentication; the signed-in user becomes the token subject.
+pub async fn device_decision_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    headers: axum::http::HeaderMap,
+    Json(req): Json<DeviceDecisionRequest>,
+) -> Result<Json<serde_json::Value>, OAuthError> {
+    let user_id = claims.user_id()
+        .map_err(|_| OAuthError::InvalidGrant("Invalid user ID in token".to_string()))?;
+    let auth_time = DateTime::from_timestamp(claims.auth_time(), 0).unwrap_or_else(Utc::now);
+
+    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone())
+        .with_consent_ttl_days(state.config.consent_ttl_days)
+        .with_challenge_store(state.challenge_store.clone());
+
+    // Same cooling-off rule as the consent screen
+    if req.approved {
+        let (device_code, client) = oauth_service.find_pending_device_authorization(&req.user_code).await?;
+        if client.is_external() && !client.skips_consent() {
+            let granted = ConsentService::new(state.pool.clone())
+                .with_consent_ttl_days(state.config.consent_ttl_days)
+                .granted_scopes(user_id, client.id)
+                .await?;
+            cooling_off_service(&state)
+                .check_consent(
+                    user_id,
+                    &granted,
+                    &device_code.scopes,
+                    client_ip(&headers).as_deref(),
+                    extract_user_agent(&headers).as_deref(),
+                )
+                .await
+                .map_err(|e| match e {
+                    AuthError::CoolingOff { .. } => OAuthError::AccessDenied,
+                    e => OAuthError::ServerError(e.to_string()),
+                })?;
+        }
+    }
+
+    oauth_service
+        .decide_device_authorization(&req.user_code, user_id, req.approved, auth_time)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": if req.approved { "approved" } else { "denied" }
+    })))
+}
+
 // ============================================================================
 // Revoke Endpoint (Task 11.3)
 // Requirements: 9.4, 11.3
@@ -437,45 +1115,229 @@ async fn handle_refresh_token_grant(
 /// is invalid or already revoked.
 pub async fn revoke_handler(
     State(state): State<AppState>,
-    axum::Form(req): axum::Form<RevokeRequest>,
-) -> StatusCode {
+    headers: axum::http::HeaderMap,
+    OAuthBody(req): OAuthBody<RevokeRequest>,
+) -> Result<StatusCode, OAuthError> {
     let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone());
 
-    let client_id = match &req.client_id {
-        Some(id) => id.as_str(),
-        None => return StatusCode::OK, // Per RFC 7009, return OK even without client_id
+    let (client_id, _) = client_credentials(&headers, req.client_id, req.client_secret)?;
+    let client_id = match client_id {
+        Some(id) => id,
+        None => return Ok(StatusCode::OK), // Per RFC 7009, return OK even without client_id
     };
 
     // Attempt to revoke - ignore errors per RFC 7009
-    let _ = oauth_service.revoke_token(&req.token, client_id).await;
+    let _ = oauth_service.revoke_token(&req.token, &client_id).await;
 
-    StatusCode::OK
+    Ok(StatusCode::OK)
 }
 
 // ============================================================================
-// UserInfo Endpoint (Task 11.4)
-// Requirements: 11.4
+// Introspection Endpoint (RFC 7662)
 // ============================================================================
 
-/// Request with Authorization header for userinfo
-#[derive(Debug)]
-pub struct AuthorizationHeader(pub String);
-
-/// GET /oauth/userinfo - UserInfo endpoint
+/// POST /oauth/introspect - Token introspection endpoint
 ///
-/// Returns user profile information based on the granted scopes.
+/// Lets a resource server check whether an access or refresh token is
+/// active. The resource server authenticates with its client credentials
+/// and only sees tokens issued to itself, unless it is an internal client.
 ///
-/// # Requirements
-/// - 11.4: Expose GET /oauth/userinfo for retrieving user profile
+/// # Note
+/// Per RFC 7662, unknown, expired and revoked tokens are not an error:
+/// the response is `{"active": false}`.
+pub async fn introspect_handler(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    OAuthBody(req): OAuthBody<IntrospectRequest>,
+) -> Result<Json<IntrospectionResponse>, OAuthError> {
+    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone())
+        .with_session_defaults(SessionPolicy::from_config(&state.config))
+        .with_secret_max_age_days(state.config.client_secret_max_age_days);
+
+    let (client_id, client_secret) =
+        client_credentials(&headers, req.client_id.clone(), req.client_secret.clone())?;
+
+    let client_id = client_id.as_ref().ok_or_else(|| {
+        OAuthError::InvalidRequest("client_id is required".to_string())
+    })?;
+
+    let client_secret = client_secret.as_ref().ok_or_else(|| {
+        OAuthError::InvalidRequest("client_secret is required".to_string())
+    })?;
+
+    let response = oauth_service
+        .introspect_token(&req.token, req.token_type_hint.as_deref(), client_id, client_secret)
+        .await?;
+
+    Ok(Json(response))
+}
+
+// ============================================================================
+// Logout Endpoint (OpenID Connect RP-Initiated Logout 1.0)
+// ============================================================================
+
+/// GET /oauth/logout - End session endpoint
+///
+/// Signs the user out: the SPA session in the refresh cookie is revoked,
+/// all of the user's OAuth tokens are revoked, and clients that registered a
+/// `backchannel_logout_uri` are sent a logout token.
+///
+/// The user is taken from `id_token_hint`, or else from the refresh cookie.
+/// `post_logout_redirect_uri` must exactly match a URI registered by the
+/// client named in the hint or in `client_id`; the user is then sent there
+/// with `state`. Otherwise a JSON confirmation is returned.
+pub async fn end_session_handler(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    Query(req): Query<EndSessionRequest>,
+) -> Result<Response, OAuthError> {
+    let issuer = issuer_url(&state);
+    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone())
+        .with_issuer(issuer.clone());
+
+    let hint = match req.id_token_hint.as_deref() {
+        Some(token) => Some(
+            state
+                .jwt_manager
+                .verify_id_token_hint(token, &issuer)
+                .map_err(|_| OAuthError::InvalidRequest("id_token_hint is not a valid ID token".to_string()))?,
+        ),
+        None => None,
+    };
+
+    // The hint names the client; client_id may only repeat it
+    let client_id = match (hint.as_ref().map(|h| h.aud.as_str()), req.client_id.as_deref()) {
+        (Some(aud), Some(client_id)) if aud != client_id => {
+            return Err(OAuthError::InvalidRequest(
+                "client_id does not match id_token_hint".to_string(),
+            ));
+        }
+        (aud, client_id) => aud.or(client_id),
+    };
+
+    // Checked before anything is revoked, and never redirected to when unregistered
+    let redirect_uri = match req.post_logout_redirect_uri.as_deref() {
+        Some(uri) => {
+            let client_id = client_id.ok_or_else(|| {
+                OAuthError::InvalidRequest(
+                    "post_logout_redirect_uri requires id_token_hint or client_id".to_string(),
+                )
+            })?;
+            let client = OAuthClientRepository::new(state.pool.clone())
+                .find_by_client_id(client_id)
+                .await?
+                .ok_or(OAuthError::InvalidClient)?;
+            if !client.has_post_logout_redirect_uri(uri) {
+                return Err(OAuthError::InvalidRequest(
+                    "post_logout_redirect_uri is not registered for this client".to_string(),
+                ));
+            }
+            Some(uri)
+        }
+        None => None,
+    };
+
+    let cookie_settings = RefreshCookieSettings::from_config(&state.config);
+    let session_service = SessionService::new(state.pool.clone(), 7);
+    let session = match get_cookie(&headers, &cookie_settings.name) {
+        Some(token) => session_service
+            .find_by_refresh_token(&token)
+            .await
+            .map_err(|e| OAuthError::ServerError(format!("Session lookup failed: {}", e)))?,
+        None => None,
+    };
+
+    let user_id = match &hint {
+        Some(hint) => Some(
+            Uuid::parse_str(&hint.sub)
+                .map_err(|_| OAuthError::InvalidRequest("id_token_hint has an invalid subject".to_string()))?,
+        ),
+        None => session.as_ref().map(|s| s.user_id),
+    };
+
+    let mut cookies = Vec::new();
+    if let Some(user_id) = user_id {
+        // Only the cookie session of the same user is ended
+        if let Some(session) = session.filter(|s| s.user_id == user_id) {
+            session_service
+                .revoke_session(session.id, user_id)
+                .await
+                .map_err(|e| OAuthError::ServerError(format!("Failed to revoke session: {}", e)))?;
+            cookies.push((SET_COOKIE, cookie_settings.clear_cookie(&cookie_settings.name)));
+        }
+
+        let (tokens_revoked, clients_notified) = oauth_service.logout_user(user_id).await?;
+
+        let client_uuid = match client_id {
+            Some(client_id) => OAuthClientRepository::new(state.pool.clone())
+                .find_by_client_id(client_id)
+                .await?
+                .map(|c| c.id),
+            None => None,
+        };
+        OAuthAuditLogRepository::new(state.pool.clone())
+            .create(
+                OAuthEventType::UserLoggedOut,
+                client_uuid,
+                Some(user_id),
+                None,
+                Some(serde_json::json!({
+                    "tokens_revoked": tokens_revoked,
+                    "clients_notified": clients_notified,
+                })),
+            )
+            .await
+            .ok();
+    }
+
+    match redirect_uri {
+        Some(uri) => {
+            let mut url = uri.to_string();
+            if let Some(s) = &req.state {
+                url.push_str(if url.contains('?') { "&" } else { "?" });
+                url.push_str(&format!("state={}", urlencoding::encode(s)));
+            }
+            Ok((AppendHeaders(cookies), Redirect::to(&url)).into_response())
+        }
+        None => Ok((
+            AppendHeaders(cookies),
+            Json(serde_json::json!({ "message": "Logged out" })),
+        )
+            .into_response()),
+    }
+}
+
+// ============================================================================
+// UserInfo Endpoint (Task 11.4)
+// Requirements: 11.4
+// ============================================================================
+
+/// Request with Authorization header for userinfo
+#[derive(Debug)]
+pub struct AuthorizationHeader(pub String);
+
+/// GET /oauth/userinfo - UserInfo endpoint
+///
+/// Returns user profile information based on the granted scopes.
+///
+/// # Requirements
+/// - 11.4: Expose GET /oauth/userinfo for retrieving user profile
 /// - 9.5, 10.6: Log invalid token attempts for audit
 ///
 /// # Scopes
-/// - profile: Returns name
-/// - email: Returns email and email_verified
+/// - profile: Returns name, picture, locale and updated_at
+/// - email (or openid): Returns email and email_verified
+/// - phone: Returns phone_number
+/// - address: Returns address
+/// - custom scopes: Return the claims they are mapped to
+///
+/// Clients registered with a `userinfo_signed_response_alg` receive the
+/// claims as a JWT signed with the server key (`application/jwt`) instead of
+/// plain JSON.
 pub async fn userinfo_handler(
     State(state): State<AppState>,
     headers: axum::http::HeaderMap,
-) -> Result<Json<UserInfoResponse>, OAuthError> {
+) -> Result<Response, OAuthError> {
     let audit_repo = OAuthAuditLogRepository::new(state.pool.clone());
 
     // Extract Bearer token from Authorization header
@@ -525,26 +1387,57 @@ pub async fn userinfo_handler(
         .map_err(|e| OAuthError::ServerError(e.to_string()))?
         .ok_or_else(|| OAuthError::InvalidGrant("User not found".to_string()))?;
 
-    // Build response based on scopes
-    let mut response = UserInfoResponse {
-        sub: user_id.to_string(),
-        email: None,
-        email_verified: None,
-        name: None,
+    // Claims released by the granted standard scopes and by the claim
+    // mapping of granted custom scopes
+    let granted = &claims.scope;
+    let custom_codes: Vec<String> = granted.iter().filter(|s| s.contains(':')).cloned().collect();
+    let custom_claims: Vec<Vec<String>> = OAuthScopeRepository::new(state.pool.clone())
+        .find_by_codes(&custom_codes)
+        .await?
+        .into_iter()
+        .map(|scope| scope.claims)
+        .collect();
+    let released = released_claims(granted.iter().map(String::as_str), &custom_claims);
+
+    let address = if released.contains("address") {
+        UserAddressRepository::new(state.pool.clone())
+            .find_by_user(user_id)
+            .await
+            .map_err(|e| OAuthError::ServerError(e.to_string()))?
+    } else {
+        None
     };
 
-    // Check scopes and include appropriate fields
-    if claims.has_scope("email") || claims.has_scope("openid") {
-        response.email = Some(user.email.clone());
-        response.email_verified = Some(user.email_verified);
-    }
+    let response = UserInfoResponse {
+        sub: user_id.to_string(),
+        email: released.contains("email").then(|| user.email.clone()),
+        email_verified: released.contains("email_verified").then_some(user.email_verified),
+        name: released
+            .contains("name")
+            .then(|| user.name.clone())
+            .flatten()
+            .filter(|name| !name.trim().is_empty()),
+        picture: released.contains("picture").then(|| user.avatar_url.clone()).flatten(),
+        locale: released.contains("locale").then(|| user.locale.clone()).flatten(),
+        updated_at: released
+            .contains("updated_at")
+            .then(|| user.updated_at.unwrap_or(user.created_at).timestamp()),
+        phone_number: released.contains("phone_number").then(|| user.phone.clone()).flatten(),
+        address,
+    };
 
-    if claims.has_scope("profile") {
-        // For now, use email as name since we don't have a separate name field
-        response.name = Some(user.email.clone());
+    let client = OAuthClientRepository::new(state.pool.clone())
+        .find_by_client_id(claims.client_id())
+        .await?;
+    if client.and_then(|c| c.userinfo_signed_response_alg).is_some() {
+        let signed = state
+            .jwt_manager
+            .create_userinfo_token(&issuer_url(&state), claims.client_id(), &response)
+            .map_err(|e| OAuthError::ServerError(e.to_string()))?;
+        return Ok(([(CONTENT_TYPE, "application/jwt")], signed).into_response());
     }
 
-    Ok(Json(response))
+    Ok(Json(response).into_response())
 }
 
 // ============================================================================
@@ -561,11 +1454,7 @@ pub async fn userinfo_handler(
 pub async fn openid_configuration_handler(
     State(state): State<AppState>,
 ) -> Json<OpenIdConfiguration> {
-    // Get base URL from config or use default
-    let base_url = format!(
-        "http://{}:{}",
-        state.config.server_host, state.config.server_port
-    );
+    let base_url = issuer_url(&state);
 
     // Get available scopes from database
     let scope_repo = OAuthScopeRepository::new(state.pool.clone());
@@ -580,6 +1469,36 @@ pub async fn openid_configuration_handler(
     Json(OpenIdConfiguration::new(&base_url, scopes))
 }
 
+/// GET /.well-known/jwks.json - Public keys that verify tokens
+///
+/// Lists the active key, the staged next key and recently retired keys.
+/// Relying parties may cache it for JWKS_CACHE_MAX_AGE_SECS; a staged key is
+/// not promoted before that time has passed, so cached copies always contain
+/// the key that signs new tokens.
+pub async fn jwks_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let cache_control = format!("public, max-age={}", state.config.jwks_cache_max_age_secs.max(0));
+
+    (
+        [(CACHE_CONTROL, cache_control)],
+        Json(state.jwt_manager.jwks()),
+    )
+}
+
+/// How long `/.well-known/auth-server` may be cached; it only changes with a restart
+const CAPABILITIES_CACHE_MAX_AGE_SECS: i64 = 300;
+
+/// GET /.well-known/auth-server - Server version and capabilities
+///
+/// Supported flows, token formats and client-facing feature flags in a
+/// stable schema (`schema_version`), so SDKs and resource servers can adapt
+/// to the deployment instead of assuming its configuration.
+pub async fn server_capabilities_handler(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(CACHE_CONTROL, format!("public, max-age={}", CAPABILITIES_CACHE_MAX_AGE_SECS))],
+        Json(ServerCapabilities::new(&state.config, &issuer_url(&state))),
+    )
+}
+
 // ============================================================================
 // Scopes Endpoint
 // ============================================================================
@@ -610,6 +1529,212 @@ pub async fn list_scopes_handler(
 // ============================================================================
 
 /// Build an error redirect response as JSON for frontend to handle
+/// Verify the Bearer access token on a consent callback
+///
+/// Returns `None` if the token is missing, invalid, revoked, or belongs to
+/// a different user than the one granting consent.
+async fn verify_consent_session(
+    state: &AppState,
+    headers: &axum::http::HeaderMap,
+    user_id: Uuid,
+) -> Option<SignedInSession> {
+    signed_in_session(state, headers)
+        .await
+        .filter(|session| session.user_id == user_id)
+}
+
+/// Session an authorize request or consent decision was made with
+struct SignedInSession {
+    user_id: Uuid,
+    /// When the user signed in
+    auth_time: DateTime<Utc>,
+    /// Authentication context class of the sign-in
+    acr: Option<String>,
+    /// Authentication methods of the sign-in
+    amr: Option<Vec<String>>,
+    /// Hash of the access token, binding issued codes to the session
+    binding_hash: String,
+}
+
+/// Session of the request's Bearer token, if it is valid and not revoked
+async fn signed_in_session(state: &AppState, headers: &axum::http::HeaderMap) -> Option<SignedInSession> {
+    let token = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))?;
+
+    let claims = state.jwt_manager.verify_token(token).ok()?;
+
+    let revocation_service = TokenRevocationService::new(state.pool.clone());
+    if revocation_service.is_access_token_revoked(token).await.unwrap_or(true) {
+        return None;
+    }
+
+    Some(SignedInSession {
+        user_id: claims.user_id().ok()?,
+        auth_time: DateTime::from_timestamp(claims.auth_time(), 0)?,
+        acr: claims.acr,
+        amr: claims.amr,
+        binding_hash: hash_token(token).ok()?,
+    })
+}
+
+/// Why a signed-in user must sign in again before authorizing, if they must
+///
+/// prompt=login and max_age=0 are satisfied by a sign-in made within
+/// `FRESH_LOGIN_SECS`, so repeating the request right after signing in
+/// goes through. A session below the client's required acr always has to
+/// step up. A level asked for in `acr_values` is only enforced for users
+/// who can reach it (MFA set up for `mfa`, a passkey for either); for
+/// anyone else the ID token reports the class they signed in with.
+async fn login_required_reason(
+    state: &AppState,
+    client: &OAuthClient,
+    session: &SignedInSession,
+    req: &AuthorizationRequest,
+    prompt: Prompt,
+) -> Option<&'static str> {
+    let age = (Utc::now() - session.auth_time).num_seconds();
+    if (prompt.login || prompt.select_account) && age > FRESH_LOGIN_SECS {
+        return Some("prompt_login");
+    }
+    if let Some(max_age) = req.max_age {
+        let max_age = match max_age {
+            0 => FRESH_LOGIN_SECS,
+            max_age => i64::try_from(max_age).unwrap_or(i64::MAX),
+        };
+        if age > max_age {
+            return Some("max_age");
+        }
+    }
+
+    let level = AcrLevel::of_session(session.acr.as_deref());
+    if !level.satisfies(acr::parse_required(client.required_acr.as_deref())) {
+        return Some("acr");
+    }
+
+    let requested = AcrLevel::requested(&req.acr_values());
+    if !level.satisfies(requested) {
+        let has_passkey = WebAuthnRepository::new(state.pool.clone())
+            .user_has_passkeys(session.user_id)
+            .await
+            .unwrap_or(false);
+        let reachable = match requested {
+            Some(AcrLevel::Mfa) => {
+                has_passkey
+                    || UserRepository::new(state.pool.clone())
+                        .find_by_id(session.user_id)
+                        .await
+                        .ok()
+                        .flatten()
+                        .is_some_and(|user| user.mfa_enabled)
+            }
+            _ => has_passkey,
+        };
+        if reachable {
+            return Some("acr");
+        }
+    }
+
+    None
+}
+
+/// Whether a session may be issued a code for the client
+///
+/// Checked again when the code is issued, so skipping the step-up that
+/// /oauth/authorize asked for doesn't get around the client's required acr.
+fn meets_client_acr(client: &OAuthClient, session: &SignedInSession) -> bool {
+    AcrLevel::of_session(session.acr.as_deref()).satisfies(acr::parse_required(client.required_acr.as_deref()))
+}
+
+fn cooling_off_service(state: &AppState) -> CoolingOffService {
+    CoolingOffService::new(state.pool.clone(), CoolingOffPolicy::from_config(&state.config))
+}
+
+/// Posture of the sign-in approving a code, for clients with `posture_claims`
+async fn sign_in_posture(
+    state: &AppState,
+    client: &OAuthClient,
+    session: &SignedInSession,
+    headers: &axum::http::HeaderMap,
+) -> Option<PostureContext> {
+    if !client.posture_claims {
+        return None;
+    }
+
+    let trusted_device = match get_cookie(headers, TRUSTED_DEVICE_COOKIE_NAME) {
+        Some(cookie_value) => TrustedDeviceService::new(state.pool.clone())
+            .verify(session.user_id, &cookie_value)
+            .await
+            .ok()
+            .flatten(),
+        None => None,
+    };
+
+    Some(PostureContext {
+        device_trust: if trusted_device.is_some() { DeviceTrust::Trusted } else { DeviceTrust::Unknown },
+        mfa_level: AcrLevel::of_session(session.acr.as_deref()).as_str().to_string(),
+        network_zone: state.config.network_zones.zone_for(client_ip(headers).as_deref()).to_string(),
+    })
+}
+
+/// Callback URL carrying an issued authorization code
+fn code_redirect_url(redirect_uri: &str, code: &str, state: Option<&str>) -> String {
+    let mut url = redirect_uri.to_string();
+    url.push_str(if url.contains('?') { "&" } else { "?" });
+    url.push_str(&format!("code={}", urlencoding::encode(code)));
+    if let Some(state) = state {
+        url.push_str(&format!("&state={}", urlencoding::encode(state)));
+    }
+    url
+}
+
+/// Split requested scopes into those already granted and those needing consent
+///
+/// With `include_granted_scopes` every previously granted scope is reported
+/// as granted, not just the requested ones.
+fn split_granted_scopes(
+    requested: &[String],
+    previously_granted: &[String],
+    include_granted_scopes: bool,
+) -> (Vec<String>, Vec<String>) {
+    let (mut granted, new): (Vec<String>, Vec<String>) = requested
+        .iter()
+        .cloned()
+        .partition(|scope| previously_granted.contains(scope));
+
+    if include_granted_scopes {
+        for scope in previously_granted {
+            if !granted.contains(scope) {
+                granted.push(scope.clone());
+            }
+        }
+    }
+
+    (granted, new)
+}
+
+/// Issuer identifier / base URL advertised in discovery and ID tokens
+pub(crate) fn issuer_url(state: &AppState) -> String {
+    format!(
+        "http://{}:{}",
+        state.config.server_host, state.config.server_port
+    )
+}
+
+/// Parse the comma-separated scope list posted by the consent screen
+fn split_consent_scopes(scopes: &str) -> Vec<String> {
+    scopes
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Error response that sends the user agent back to the client
+///
+/// `redirect_uri` must already have passed
+/// `OAuthService::validate_client_redirect_uri`.
 fn build_error_redirect(
     redirect_uri: &str,
     error: &str,
@@ -648,6 +1773,12 @@ fn error_code(error: &OAuthError) -> String {
         OAuthError::UnsupportedGrantType => "unsupported_grant_type".to_string(),
         OAuthError::InvalidScope(_) => "invalid_scope".to_string(),
         OAuthError::AccessDenied => "access_denied".to_string(),
+        OAuthError::ClientSecretExpired => "client_secret_expired".to_string(),
+        OAuthError::AuthorizationPending => "authorization_pending".to_string(),
+        OAuthError::SlowDown { .. } => "slow_down".to_string(),
+        OAuthError::ExpiredToken => "expired_token".to_string(),
+        OAuthError::InvalidRequestObject(_) => "invalid_request_object".to_string(),
+        OAuthError::InvalidRequestUri(_) => "invalid_request_uri".to_string(),
         OAuthError::ServerError(_) => "server_error".to_string(),
     }
 }
@@ -676,12 +1807,29 @@ pub async fn list_clients_handler(
     let client_infos: Vec<crate::dto::oauth::OAuthClientInfo> = clients
         .into_iter()
         .map(|c| crate::dto::oauth::OAuthClientInfo {
+            secret_expires_at: c.secret_expires_at(state.config.client_secret_max_age_days),
             id: c.id.to_string(),
             client_id: c.client_id,
             name: c.name,
             redirect_uris: c.redirect_uris,
             is_internal: c.is_internal,
+            client_type: c.client_type,
             is_active: c.is_active,
+            session_idle_timeout_secs: c.session_idle_timeout_secs,
+            session_absolute_lifetime_secs: c.session_absolute_lifetime_secs,
+            access_token_ttl_secs: c.access_token_ttl_secs,
+            refresh_token_cookie: c.refresh_token_cookie,
+            skip_consent: c.skip_consent,
+            always_prompt_consent: c.always_prompt_consent,
+            posture_claims: c.posture_claims,
+            required_acr: c.required_acr,
+            userinfo_signed_response_alg: c.userinfo_signed_response_alg,
+            post_logout_redirect_uris: c.post_logout_redirect_uris,
+            backchannel_logout_uri: c.backchannel_logout_uri,
+            request_uris: c.request_uris,
+            scope: c.allowed_scopes.map(|scopes| scopes.join(" ")),
+            grant_types: c.allowed_grant_types,
+            secret_created_at: c.secret_created_at,
             created_at: c.created_at,
         })
         .collect();
@@ -715,7 +1863,8 @@ pub async fn register_client_handler(
     let owner_id = claims.user_id()
         .map_err(|_| OAuthError::InvalidGrant("Invalid user ID in token".to_string()))?;
 
-    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone());
+    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone())
+        .with_redirect_policy(RedirectUriPolicy::from_config(&state.config));
     let audit_repo = OAuthAuditLogRepository::new(state.pool.clone());
     let user_repo = crate::repositories::UserRepository::new(state.pool.clone());
 
@@ -735,9 +1884,33 @@ pub async fn register_client_handler(
         false
     };
 
-    // Validate redirect URIs (HTTPS required for external apps)
+    // Native apps (RFC 8252) are public clients, so they are never internal
+    let client_type = match req.client_type.as_deref().unwrap_or(CLIENT_TYPE_WEB) {
+        CLIENT_TYPE_WEB => CLIENT_TYPE_WEB,
+        CLIENT_TYPE_NATIVE if is_internal => {
+            return Err(OAuthError::InvalidRequest(
+                "Native apps cannot be internal".to_string(),
+            ));
+        }
+        CLIENT_TYPE_NATIVE => CLIENT_TYPE_NATIVE,
+        other => {
+            return Err(OAuthError::InvalidRequest(format!(
+                "Unsupported client_type: {}",
+                other
+            )));
+        }
+    };
+
+    // Validate redirect URIs (HTTPS required for external apps, redirect policy and blocklist)
     // Requirement 1.4
-    oauth_service.validate_redirect_uris_for_registration(&req.redirect_uris, is_internal)?;
+    let profile = RedirectUriProfile::for_client(is_internal, client_type == CLIENT_TYPE_NATIVE);
+    oauth_service.validate_redirect_uris_for_registration(&req.redirect_uris, profile).await?;
+    oauth_service.validate_redirect_uris_for_registration(&req.post_logout_redirect_uris, profile).await?;
+    oauth_service.validate_backchannel_logout_uri(req.backchannel_logout_uri.as_deref()).await?;
+    // A new client owns no scopes yet, so only global scopes can be registered
+    let allowed_scopes = registered_scopes(&state, req.scope.as_deref(), Uuid::nil()).await?;
+    let allowed_grant_types = registered_grant_types(req.grant_types)?;
+    let userinfo_alg = registered_userinfo_alg(req.userinfo_signed_response_alg)?;
 
     // Generate unique client_id
     // Requirement 1.2
@@ -754,7 +1927,7 @@ pub async fn register_client_handler(
     // Store the client with owner_id
     // Requirement 1.1
     let client_repo = OAuthClientRepository::new(state.pool.clone());
-    let client = client_repo
+    let mut client = client_repo
         .create(
             &client_id,
             &client_secret_hash,
@@ -762,9 +1935,34 @@ pub async fn register_client_handler(
             owner_id,
             &req.redirect_uris,
             is_internal,
+            client_type,
         )
         .await?;
 
+    if !req.post_logout_redirect_uris.is_empty() || req.backchannel_logout_uri.is_some() {
+        client_repo
+            .update_logout_uris(client.id, &req.post_logout_redirect_uris, req.backchannel_logout_uri.as_deref())
+            .await?;
+        client.post_logout_redirect_uris = req.post_logout_redirect_uris;
+        client.backchannel_logout_uri = req.backchannel_logout_uri;
+    }
+
+    if let Some(scopes) = &allowed_scopes {
+        client_repo.update_allowed_scopes(client.id, Some(scopes)).await?;
+    }
+    if let Some(grant_types) = &allowed_grant_types {
+        client_repo.update_allowed_grant_types(client.id, Some(grant_types)).await?;
+    }
+    if let Some(alg) = &userinfo_alg {
+        client_repo.update_userinfo_signed_response_alg(client.id, Some(alg)).await?;
+    }
+
+    // Registration access token for managing the client (RFC 7592)
+    let registration_access_token = generate_oauth_token();
+    client_repo
+        .set_registration_token(client.id, &hash_oauth_token(&registration_access_token))
+        .await?;
+
     // Log client registration event
     // Requirements: 9.5, 10.6
     audit_repo
@@ -776,6 +1974,7 @@ pub async fn register_client_handler(
             Some(serde_json::json!({
                 "name": client.name,
                 "is_internal": client.is_internal,
+                "client_type": client.client_type,
                 "redirect_uris_count": client.redirect_uris.len(),
             })),
         )
@@ -786,11 +1985,19 @@ pub async fn register_client_handler(
     Ok((
         StatusCode::CREATED,
         Json(ClientRegistrationResponse {
+            registration_client_uri: registration_client_uri(&state, &client.client_id),
             client_id: client.client_id,
             client_secret, // Plain text, only returned once
             name: client.name,
             redirect_uris: client.redirect_uris,
             is_internal: client.is_internal,
+            client_type: client.client_type,
+            post_logout_redirect_uris: client.post_logout_redirect_uris,
+            backchannel_logout_uri: client.backchannel_logout_uri,
+            scope: allowed_scopes.map(|scopes| scopes.join(" ")),
+            grant_types: allowed_grant_types,
+            userinfo_signed_response_alg: userinfo_alg,
+            registration_access_token,
         }),
     ))
 }
@@ -840,15 +2047,119 @@ pub async fn update_client_handler(
     let name = req.name.unwrap_or(existing.name.clone());
     let redirect_uris = req.redirect_uris.unwrap_or(existing.redirect_uris.clone());
 
-    // Validate redirect URIs for external apps
-    if !existing.is_internal {
-        let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone());
-        oauth_service.validate_redirect_uris_for_registration(&redirect_uris, existing.is_internal)?;
-    }
+    // Validate redirect URIs against the redirect policy and blocklist
+    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone())
+        .with_redirect_policy(RedirectUriPolicy::from_config(&state.config));
+    oauth_service
+        .validate_redirect_uris_for_registration(&redirect_uris, OAuthService::redirect_profile(&existing))
+        .await?;
 
     // Update client
     let _updated = client_repo.update(client_uuid, &name, &redirect_uris).await?;
 
+    // Logout URIs - omitted keeps the current value, an empty backchannel_logout_uri removes it
+    if req.post_logout_redirect_uris.is_some() || req.backchannel_logout_uri.is_some() {
+        let post_logout_redirect_uris = req
+            .post_logout_redirect_uris
+            .unwrap_or_else(|| existing.post_logout_redirect_uris.clone());
+        let backchannel_logout_uri = match req.backchannel_logout_uri {
+            None => existing.backchannel_logout_uri.clone(),
+            Some(uri) if uri.is_empty() => None,
+            Some(uri) => Some(uri),
+        };
+
+        oauth_service
+            .validate_redirect_uris_for_registration(&post_logout_redirect_uris, OAuthService::redirect_profile(&existing))
+            .await?;
+        oauth_service.validate_backchannel_logout_uri(backchannel_logout_uri.as_deref()).await?;
+        client_repo
+            .update_logout_uris(client_uuid, &post_logout_redirect_uris, backchannel_logout_uri.as_deref())
+            .await?;
+    }
+
+    // Hosted request objects - omitted keeps the current list, an empty list allows none
+    if let Some(request_uris) = req.request_uris {
+        oauth_service.validate_request_uris(&request_uris).await?;
+        client_repo.update_request_uris(client_uuid, &request_uris).await?;
+    }
+
+    // Session policy overrides - omitted keeps the current value, 0 restores the default
+    if req.session_idle_timeout_secs.is_some() || req.session_absolute_lifetime_secs.is_some() {
+        let resolve = |requested: Option<i64>, current: Option<i64>| match requested {
+            None => current,
+            Some(0) => None,
+            Some(secs) => Some(secs),
+        };
+        let idle = resolve(req.session_idle_timeout_secs, existing.session_idle_timeout_secs);
+        let absolute = resolve(req.session_absolute_lifetime_secs, existing.session_absolute_lifetime_secs);
+
+        SessionPolicy::validate_overrides(idle, absolute).map_err(OAuthError::InvalidRequest)?;
+        client_repo.update_session_policy(client_uuid, idle, absolute).await?;
+    }
+
+    // Access token lifetime - omitted keeps the current value, 0 restores the default
+    if let Some(secs) = req.access_token_ttl_secs {
+        let max_secs = state.config.oauth_max_access_token_ttl_secs;
+        if secs < 0 || secs > max_secs {
+            return Err(OAuthError::InvalidRequest(format!(
+                "access_token_ttl_secs must be between 1 and {} (0 restores the default)",
+                max_secs
+            )));
+        }
+        client_repo
+            .update_access_token_ttl(client_uuid, (secs > 0).then_some(secs))
+            .await?;
+    }
+
+    if let Some(refresh_token_cookie) = req.refresh_token_cookie {
+        if refresh_token_cookie != existing.refresh_token_cookie {
+            client_repo.update_refresh_token_cookie(client_uuid, refresh_token_cookie).await?;
+        }
+    }
+
+    if let Some(always_prompt_consent) = req.always_prompt_consent {
+        if always_prompt_consent != existing.always_prompt_consent {
+            client_repo.update_always_prompt_consent(client_uuid, always_prompt_consent).await?;
+        }
+    }
+
+    if let Some(posture_claims) = req.posture_claims {
+        if posture_claims != existing.posture_claims {
+            client_repo.update_posture_claims(client_uuid, posture_claims).await?;
+        }
+    }
+
+    // Required acr - omitted keeps the current value, an empty string accepts any sign-in
+    if let Some(required_acr) = req.required_acr {
+        let required = match required_acr.trim() {
+            "" => None,
+            acr => Some(acr.parse::<AcrLevel>().map_err(|_| {
+                OAuthError::InvalidRequest("required_acr must be one of: pwd, mfa, phr".to_string())
+            })?),
+        };
+        client_repo
+            .update_required_acr(client_uuid, required.map(|level| level.as_str()))
+            .await?;
+    }
+
+    // Userinfo signing - omitted keeps the current value, an empty string returns plain JSON
+    if let Some(alg) = req.userinfo_signed_response_alg {
+        let alg = registered_userinfo_alg(Some(alg))?;
+        client_repo.update_userinfo_signed_response_alg(client_uuid, alg.as_deref()).await?;
+    }
+
+    // Grant types - omitted keeps the current list, an empty list allows every grant
+    if let Some(grant_types) = req.grant_types {
+        let allowed_grant_types = if grant_types.is_empty() {
+            None
+        } else {
+            registered_grant_types(Some(grant_types))?
+        };
+        client_repo
+            .update_allowed_grant_types(client_uuid, allowed_grant_types.as_deref())
+            .await?;
+    }
+
     // Handle is_active change
     if let Some(is_active) = req.is_active {
         if is_active != existing.is_active {
@@ -879,12 +2190,29 @@ pub async fn update_client_handler(
         .ok();
 
     Ok(Json(crate::dto::oauth::OAuthClientInfo {
+        secret_expires_at: final_client.secret_expires_at(state.config.client_secret_max_age_days),
         id: final_client.id.to_string(),
         client_id: final_client.client_id,
         name: final_client.name,
         redirect_uris: final_client.redirect_uris,
         is_internal: final_client.is_internal,
+        client_type: final_client.client_type,
         is_active: final_client.is_active,
+        session_idle_timeout_secs: final_client.session_idle_timeout_secs,
+        session_absolute_lifetime_secs: final_client.session_absolute_lifetime_secs,
+        access_token_ttl_secs: final_client.access_token_ttl_secs,
+        refresh_token_cookie: final_client.refresh_token_cookie,
+        skip_consent: final_client.skip_consent,
+        always_prompt_consent: final_client.always_prompt_consent,
+        posture_claims: final_client.posture_claims,
+        required_acr: final_client.required_acr,
+        userinfo_signed_response_alg: final_client.userinfo_signed_response_alg,
+        post_logout_redirect_uris: final_client.post_logout_redirect_uris,
+        backchannel_logout_uri: final_client.backchannel_logout_uri,
+        request_uris: final_client.request_uris,
+        scope: final_client.allowed_scopes.map(|scopes| scopes.join(" ")),
+        grant_types: final_client.allowed_grant_types,
+        secret_created_at: final_client.secret_created_at,
         created_at: final_client.created_at,
     }))
 }
@@ -944,6 +2272,224 @@ pub async fn delete_client_handler(
     Ok(StatusCode::NO_CONTENT)
 }
 
+// ============================================================================
+// Client Configuration Endpoint (RFC 7592)
+// ============================================================================
+
+/// Where a client manages its registration
+fn registration_client_uri(state: &AppState, client_id: &str) -> String {
+    format!("{}/oauth/register/{}", issuer_url(state), client_id)
+}
+
+/// Parse the space-separated `scope` a client registers
+///
+/// Every scope must exist and be available to the client.
+async fn registered_scopes(
+    state: &AppState,
+    scope: Option<&str>,
+    client_uuid: Uuid,
+) -> Result<Option<Vec<String>>, OAuthError> {
+    let Some(scope) = scope else {
+        return Ok(None);
+    };
+
+    let scopes: Vec<String> = scope.split_whitespace().map(String::from).collect();
+    if !OAuthScopeRepository::new(state.pool.clone())
+        .validate_scopes(&scopes, client_uuid)
+        .await?
+    {
+        return Err(OAuthError::InvalidScope(
+            "One or more registered scopes are invalid".to_string(),
+        ));
+    }
+
+    Ok(Some(scopes))
+}
+
+/// Check the grant types a client registers
+///
+/// Every grant must be one the token endpoint supports, and at least one is needed.
+fn registered_grant_types(grant_types: Option<Vec<String>>) -> Result<Option<Vec<String>>, OAuthError> {
+    let Some(mut grant_types) = grant_types else {
+        return Ok(None);
+    };
+
+    if grant_types.is_empty() {
+        return Err(OAuthError::InvalidRequest("grant_types must not be empty".to_string()));
+    }
+    if let Some(unknown) = grant_types.iter().find(|g| !SUPPORTED_GRANT_TYPES.contains(&g.as_str())) {
+        return Err(OAuthError::InvalidRequest(format!("Unsupported grant_type: {}", unknown)));
+    }
+    grant_types.sort();
+    grant_types.dedup();
+
+    Ok(Some(grant_types))
+}
+
+/// Check the algorithm a client wants its userinfo responses signed with
+///
+/// An empty value means plain JSON responses, the same as leaving it unset.
+fn registered_userinfo_alg(alg: Option<String>) -> Result<Option<String>, OAuthError> {
+    match alg.as_deref().map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(alg) if USERINFO_SIGNING_ALG_VALUES_SUPPORTED.contains(&alg) => Ok(Some(alg.to_string())),
+        Some(alg) => Err(OAuthError::InvalidRequest(format!(
+            "Unsupported userinfo_signed_response_alg: {}",
+            alg
+        ))),
+    }
+}
+
+/// Client named in the path, authenticated by its registration access token
+///
+/// Unknown clients and wrong tokens are both `401 invalid_client`, so the
+/// endpoint does not reveal which client IDs exist (RFC 7592 Section 2).
+async fn client_from_registration_token(
+    state: &AppState,
+    headers: &axum::http::HeaderMap,
+    client_id: &str,
+) -> Result<crate::models::OAuthClient, OAuthError> {
+    let token = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or(OAuthError::InvalidClient)?;
+
+    OAuthClientRepository::new(state.pool.clone())
+        .find_by_registration_token(client_id, &hash_oauth_token(token))
+        .await?
+        .ok_or(OAuthError::InvalidClient)
+}
+
+fn client_configuration(state: &AppState, client: crate::models::OAuthClient) -> ClientConfigurationResponse {
+    ClientConfigurationResponse {
+        client_secret_expires_at: client
+            .secret_expires_at(state.config.client_secret_max_age_days)
+            .map(|at| at.timestamp())
+            .unwrap_or(0),
+        client_id_issued_at: client.created_at.timestamp(),
+        registration_client_uri: registration_client_uri(state, &client.client_id),
+        client_id: client.client_id,
+        client_name: client.name,
+        redirect_uris: client.redirect_uris,
+        scope: client.allowed_scopes.map(|scopes| scopes.join(" ")),
+        grant_types: client.allowed_grant_types,
+        userinfo_signed_response_alg: client.userinfo_signed_response_alg,
+        client_type: client.client_type,
+        is_active: client.is_active,
+        post_logout_redirect_uris: client.post_logout_redirect_uris,
+        backchannel_logout_uri: client.backchannel_logout_uri,
+    }
+}
+
+/// GET /oauth/register/{client_id} - Read a client's registration (RFC 7592 Section 2.1)
+///
+/// Authenticated with the registration access token returned at registration.
+pub async fn get_client_configuration_handler(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    Path(client_id): Path<String>,
+) -> Result<Json<ClientConfigurationResponse>, OAuthError> {
+    let client = client_from_registration_token(&state, &headers, &client_id).await?;
+
+    Ok(Json(client_configuration(&state, client)))
+}
+
+/// PUT /oauth/register/{client_id} - Update a client's registration (RFC 7592 Section 2.2)
+///
+/// Replaces the name, redirect URIs, scopes and userinfo signing, and can deactivate or
+/// reactivate the client.
+pub async fn update_client_configuration_handler(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    Path(client_id): Path<String>,
+    Json(req): Json<ClientConfigurationUpdateRequest>,
+) -> Result<Json<ClientConfigurationResponse>, OAuthError> {
+    let client = client_from_registration_token(&state, &headers, &client_id).await?;
+
+    if req.client_id != client.client_id {
+        return Err(OAuthError::InvalidRequest(
+            "client_id does not match the client being updated".to_string(),
+        ));
+    }
+
+    let oauth_service = OAuthService::new(state.pool.clone(), state.jwt_manager.clone())
+        .with_redirect_policy(RedirectUriPolicy::from_config(&state.config));
+    oauth_service
+        .validate_redirect_uris_for_registration(&req.redirect_uris, OAuthService::redirect_profile(&client))
+        .await?;
+    let allowed_scopes = registered_scopes(&state, req.scope.as_deref(), client.id).await?;
+    let allowed_grant_types = registered_grant_types(req.grant_types)?;
+    let userinfo_alg = registered_userinfo_alg(req.userinfo_signed_response_alg)?;
+
+    let client_repo = OAuthClientRepository::new(state.pool.clone());
+    let name = req.client_name.unwrap_or_else(|| client.name.clone());
+    client_repo.update(client.id, &name, &req.redirect_uris).await?;
+    client_repo.update_allowed_scopes(client.id, allowed_scopes.as_deref()).await?;
+    client_repo.update_allowed_grant_types(client.id, allowed_grant_types.as_deref()).await?;
+    client_repo.update_userinfo_signed_response_alg(client.id, userinfo_alg.as_deref()).await?;
+
+    if let Some(is_active) = req.is_active {
+        if is_active != client.is_active {
+            if is_active {
+                client_repo.activate(client.id).await?;
+            } else {
+                client_repo.deactivate(client.id).await?;
+            }
+        }
+    }
+
+    let updated = client_repo.find_by_id(client.id).await?.ok_or(OAuthError::InvalidClient)?;
+
+    OAuthAuditLogRepository::new(state.pool.clone())
+        .create(
+            OAuthEventType::ClientRegistered,
+            Some(client.id),
+            client.owner_id,
+            None,
+            Some(serde_json::json!({
+                "action": "updated",
+                "via": "registration_access_token",
+                "name": updated.name,
+                "is_active": updated.is_active,
+            })),
+        )
+        .await
+        .ok();
+
+    Ok(Json(client_configuration(&state, updated)))
+}
+
+/// DELETE /oauth/register/{client_id} - Delete a client (RFC 7592 Section 2.3)
+///
+/// The registration access token stops working with the client.
+pub async fn delete_client_configuration_handler(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    Path(client_id): Path<String>,
+) -> Result<StatusCode, OAuthError> {
+    let client = client_from_registration_token(&state, &headers, &client_id).await?;
+
+    OAuthClientRepository::new(state.pool.clone()).delete(client.id).await?;
+
+    OAuthAuditLogRepository::new(state.pool.clone())
+        .create(
+            OAuthEventType::ClientRegistered,
+            Some(client.id),
+            client.owner_id,
+            None,
+            Some(serde_json::json!({
+                "action": "deleted",
+                "via": "registration_access_token",
+                "name": client.name,
+            })),
+        )
+        .await
+        .ok();
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 // ============================================================================
 // Client Regenerate Secret Endpoint
 // ============================================================================
@@ -951,7 +2497,8 @@ pub async fn delete_client_handler(
 /// POST /oauth/clients/{id}/secret - Regenerate client secret
 ///
 /// Generates a new client secret. The old secret is invalidated.
-/// Only the owner can regenerate the secret.
+/// Only the owner can regenerate the secret. Every rotation is kept in the
+/// client's rotation history and restarts the secret's maximum age.
 pub async fn regenerate_client_secret_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
@@ -984,27 +2531,379 @@ pub async fn regenerate_client_secret_handler(
         .map_err(|e| OAuthError::ServerError(format!("Failed to hash secret: {}", e)))?;
 
     // Update secret
+    let max_age_days = state.config.client_secret_max_age_days;
+    let previous_expired = existing.is_secret_expired(max_age_days);
     client_repo.update_secret(client_uuid, &new_secret_hash).await?;
+    client_repo.revoke_previous_secrets(client_uuid).await?;
+    client_repo
+        .record_secret_rotation(&existing, Some(user_id), previous_expired, None)
+        .await?;
 
     // Log regenerate event
     audit_repo
         .create(
-            OAuthEventType::ClientRegistered,
+            OAuthEventType::ClientSecretRotated,
             Some(client_uuid),
             Some(user_id),
             None,
             Some(serde_json::json!({
-                "action": "secret_regenerated",
+                "previous_secret_created_at": existing.secret_created_at,
+                "previous_secret_expired": previous_expired,
             })),
         )
         .await
         .ok();
 
+    let secret_expires_at = client_repo
+        .find_by_id(client_uuid)
+        .await?
+        .and_then(|client| client.secret_expires_at(max_age_days));
+
     Ok(Json(RegenerateClientSecretResponse {
         client_secret: new_secret,
+        secret_expires_at,
     }))
 }
 
+/// How recent a sign-in must be to satisfy prompt=login or max_age=0
+const FRESH_LOGIN_SECS: i64 = 60;
+
+/// Longest time a replaced secret may keep working (30 days)
+const MAX_SECRET_ROTATION_GRACE_SECS: i64 = 30 * 24 * 3600;
+
+/// POST /oauth/clients/{id}/secret/rotate - Rotate the client secret with an overlap window
+///
+/// Issues a new secret while the old one keeps working for `grace_period_secs`
+/// (default CLIENT_SECRET_ROTATION_GRACE_SECS, at most 30 days), so the client
+/// can roll out the new credential without downtime. Unlike
+/// POST /oauth/clients/{id}/secret, secrets replaced by earlier rotations keep
+/// their own grace period. Only the owner can rotate.
+pub async fn rotate_client_secret_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    req: Option<Json<RotateClientSecretRequest>>,
+) -> Result<Json<RotateClientSecretResponse>, OAuthError> {
+    let user_id = claims.user_id()
+        .map_err(|_| OAuthError::InvalidGrant("Invalid user ID in token".to_string()))?;
+
+    let existing = find_owned_client(&state, user_id, &id).await?;
+
+    let grace_period_secs = req
+        .and_then(|Json(r)| r.grace_period_secs)
+        .unwrap_or(state.config.client_secret_rotation_grace_secs);
+    if !(1..=MAX_SECRET_ROTATION_GRACE_SECS).contains(&grace_period_secs) {
+        return Err(OAuthError::InvalidRequest(format!(
+            "grace_period_secs must be between 1 and {}",
+            MAX_SECRET_ROTATION_GRACE_SECS
+        )));
+    }
+    let previous_secret_expires_at = Utc::now() + chrono::Duration::seconds(grace_period_secs);
+
+    let new_secret = generate_secret();
+    let new_secret_hash = hash_secret(&new_secret)
+        .map_err(|e| OAuthError::ServerError(format!("Failed to hash secret: {}", e)))?;
+
+    let client_repo = OAuthClientRepository::new(state.pool.clone());
+    let max_age_days = state.config.client_secret_max_age_days;
+    let previous_expired = existing.is_secret_expired(max_age_days);
+    client_repo
+        .add_previous_secret(existing.id, &existing.client_secret_hash, previous_secret_expires_at)
+        .await?;
+    client_repo.update_secret(existing.id, &new_secret_hash).await?;
+    client_repo
+        .record_secret_rotation(&existing, Some(user_id), previous_expired, Some(previous_secret_expires_at))
+        .await?;
+
+    OAuthAuditLogRepository::new(state.pool.clone())
+        .create(
+            OAuthEventType::ClientSecretRotated,
+            Some(existing.id),
+            Some(user_id),
+            None,
+            Some(serde_json::json!({
+                "previous_secret_created_at": existing.secret_created_at,
+                "previous_secret_expired": previous_expired,
+                "previous_secret_expires_at": previous_secret_expires_at,
+            })),
+        )
+        .await
+        .ok();
+
+    let secret_expires_at = client_repo
+        .find_by_id(existing.id)
+        .await?
+        .and_then(|client| client.secret_expires_at(max_age_days));
+
+    Ok(Json(RotateClientSecretResponse {
+        client_secret: new_secret,
+        secret_expires_at,
+        previous_secret_expires_at,
+    }))
+}
+
+/// GET /oauth/clients/{id}/secret/rotations - Secret rotation history of a client
+///
+/// Only the owner can see the history; newest rotation first.
+pub async fn list_client_secret_rotations_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<Json<ListClientSecretRotationsResponse>, OAuthError> {
+    let user_id = claims.user_id()
+        .map_err(|_| OAuthError::InvalidGrant("Invalid user ID in token".to_string()))?;
+
+    let client = find_owned_client(&state, user_id, &id).await?;
+
+    let rotations = OAuthClientRepository::new(state.pool.clone())
+        .list_secret_rotations(client.id, 100)
+        .await?;
+
+    Ok(Json(ListClientSecretRotationsResponse {
+        secret_created_at: client.secret_created_at,
+        secret_expires_at: client.secret_expires_at(state.config.client_secret_max_age_days),
+        rotations,
+    }))
+}
+
+// ============================================================================
+// Client Request Object Keys (RFC 9101)
+// ============================================================================
+
+/// GET /oauth/clients/{id}/jwks - Public keys the client signs request objects with
+///
+/// Only the owner can see the keys.
+pub async fn get_client_jwks_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<Json<ClientJwks>, OAuthError> {
+    let user_id = claims.user_id()
+        .map_err(|_| OAuthError::InvalidGrant("Invalid user ID in token".to_string()))?;
+
+    let client = find_owned_client(&state, user_id, &id).await?;
+
+    ClientJwksRepository::new(state.pool.clone())
+        .find(client.id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| OAuthError::InvalidRequest("Client has no registered keys".to_string()))
+}
+
+/// PUT /oauth/clients/{id}/jwks - Register the client's public JWK Set
+///
+/// The body is a JWK Set of public asymmetric keys. It replaces any keys
+/// registered before; request objects must then be signed with one of them.
+pub async fn put_client_jwks_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Json(jwks): Json<serde_json::Value>,
+) -> Result<Json<ClientJwks>, OAuthError> {
+    let user_id = claims.user_id()
+        .map_err(|_| OAuthError::InvalidGrant("Invalid user ID in token".to_string()))?;
+
+    let client = find_owned_client(&state, user_id, &id).await?;
+    validate_client_jwks(&jwks).map_err(OAuthError::InvalidRequest)?;
+
+    let jwks_repo = ClientJwksRepository::new(state.pool.clone());
+    jwks_repo.upsert(client.id, &jwks).await?;
+
+    jwks_repo
+        .find(client.id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| OAuthError::ServerError("Registered keys not found".to_string()))
+}
+
+/// DELETE /oauth/clients/{id}/jwks - Remove the client's public JWK Set
+///
+/// Request objects from the client are rejected afterwards.
+pub async fn delete_client_jwks_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, OAuthError> {
+    let user_id = claims.user_id()
+        .map_err(|_| OAuthError::InvalidGrant("Invalid user ID in token".to_string()))?;
+
+    let client = find_owned_client(&state, user_id, &id).await?;
+
+    if !ClientJwksRepository::new(state.pool.clone()).delete(client.id).await? {
+        return Err(OAuthError::InvalidRequest("Client has no registered keys".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Client Custom Scopes Endpoints
+// ============================================================================
+
+/// Load a client and check that the caller owns it
+async fn find_owned_client(
+    state: &AppState,
+    user_id: Uuid,
+    id: &str,
+) -> Result<crate::models::OAuthClient, OAuthError> {
+    let client_uuid = Uuid::parse_str(id)
+        .map_err(|_| OAuthError::InvalidRequest("Invalid client ID format".to_string()))?;
+
+    let client = OAuthClientRepository::new(state.pool.clone())
+        .find_by_id(client_uuid)
+        .await?
+        .ok_or(OAuthError::InvalidClient)?;
+
+    if !client.is_owner(user_id) {
+        return Err(OAuthError::UnauthorizedClient);
+    }
+
+    Ok(client)
+}
+
+/// Load a custom scope and check that it belongs to the client
+async fn find_client_scope(
+    scope_repo: &OAuthScopeRepository,
+    client_id: Uuid,
+    scope_id: &str,
+) -> Result<crate::models::OAuthScope, OAuthError> {
+    let scope_uuid = Uuid::parse_str(scope_id)
+        .map_err(|_| OAuthError::InvalidRequest("Invalid scope ID format".to_string()))?;
+
+    scope_repo
+        .find_by_id(scope_uuid)
+        .await?
+        .filter(|scope| scope.owner_client_id == Some(client_id))
+        .ok_or_else(|| OAuthError::InvalidScope("Scope not found".to_string()))
+}
+
+/// POST /oauth/clients/:id/scopes - Define a custom scope for a client
+///
+/// The scope must be namespaced (`myapp:orders.read`) and the namespace must
+/// not be used by a standard scope or another client. Scopes are private to
+/// the client unless `global` is requested and an admin approves it.
+pub async fn create_client_scope_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Json(req): Json<CreateClientScopeRequest>,
+) -> Result<(StatusCode, Json<ClientScopeInfo>), OAuthError> {
+    let user_id = claims.user_id()
+        .map_err(|_| OAuthError::InvalidGrant("Invalid user ID in token".to_string()))?;
+
+    let client = find_owned_client(&state, user_id, &id).await?;
+
+    let namespace = validate_custom_scope_code(&req.code).map_err(OAuthError::InvalidScope)?;
+    if req.description.trim().is_empty() {
+        return Err(OAuthError::InvalidRequest("description is required".to_string()));
+    }
+    let scope_claims = validate_scope_claims(&req.claims).map_err(OAuthError::InvalidScope)?;
+
+    let scope_repo = OAuthScopeRepository::new(state.pool.clone());
+    if scope_repo.is_namespace_taken(namespace, client.id).await? {
+        return Err(OAuthError::InvalidScope(format!(
+            "Scope namespace '{}' is already in use",
+            namespace
+        )));
+    }
+
+    let visibility = if req.global { SCOPE_VISIBILITY_PENDING } else { SCOPE_VISIBILITY_PRIVATE };
+    let scope = scope_repo
+        .create_custom(client.id, &req.code, req.description.trim(), visibility, &scope_claims)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(ClientScopeInfo::from(scope))))
+}
+
+/// GET /oauth/clients/:id/scopes - List the custom scopes of a client
+pub async fn list_client_scopes_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<Json<ListClientScopesResponse>, OAuthError> {
+    let user_id = claims.user_id()
+        .map_err(|_| OAuthError::InvalidGrant("Invalid user ID in token".to_string()))?;
+
+    let client = find_owned_client(&state, user_id, &id).await?;
+
+    let scopes = OAuthScopeRepository::new(state.pool.clone())
+        .list_by_owner(client.id)
+        .await?
+        .into_iter()
+        .map(ClientScopeInfo::from)
+        .collect();
+
+    Ok(Json(ListClientScopesResponse { scopes }))
+}
+
+/// PUT /oauth/clients/:id/scopes/:scope_id - Update a custom scope
+///
+/// Changing the description or claims of a global scope sends it back for
+/// approval, since other clients' consent screens and userinfo depend on them.
+pub async fn update_client_scope_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((id, scope_id)): Path<(String, String)>,
+    Json(req): Json<UpdateClientScopeRequest>,
+) -> Result<Json<ClientScopeInfo>, OAuthError> {
+    let user_id = claims.user_id()
+        .map_err(|_| OAuthError::InvalidGrant("Invalid user ID in token".to_string()))?;
+
+    let client = find_owned_client(&state, user_id, &id).await?;
+    let scope_repo = OAuthScopeRepository::new(state.pool.clone());
+    let mut scope = find_client_scope(&scope_repo, client.id, &scope_id).await?;
+
+    let mut needs_review = false;
+    if let Some(description) = req.description {
+        let description = description.trim();
+        if description.is_empty() {
+            return Err(OAuthError::InvalidRequest("description is required".to_string()));
+        }
+        if description != scope.description {
+            scope = scope_repo.update(scope.id, description).await?;
+            needs_review = true;
+        }
+    }
+
+    if let Some(claims) = req.claims {
+        let claims = validate_scope_claims(&claims).map_err(OAuthError::InvalidScope)?;
+        if claims != scope.claims {
+            scope = scope_repo.set_claims(scope.id, &claims).await?;
+            needs_review = true;
+        }
+    }
+
+    let wants_global = req.global.unwrap_or(scope.visibility != SCOPE_VISIBILITY_PRIVATE);
+    let visibility = match (wants_global, scope.visibility.as_str()) {
+        (false, _) => SCOPE_VISIBILITY_PRIVATE,
+        (true, SCOPE_VISIBILITY_GLOBAL) if !needs_review => SCOPE_VISIBILITY_GLOBAL,
+        (true, _) => SCOPE_VISIBILITY_PENDING,
+    };
+    if visibility != scope.visibility {
+        scope = scope_repo.set_visibility(scope.id, visibility).await?;
+    }
+
+    Ok(Json(ClientScopeInfo::from(scope)))
+}
+
+/// DELETE /oauth/clients/:id/scopes/:scope_id - Delete a custom scope
+pub async fn delete_client_scope_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((id, scope_id)): Path<(String, String)>,
+) -> Result<StatusCode, OAuthError> {
+    let user_id = claims.user_id()
+        .map_err(|_| OAuthError::InvalidGrant("Invalid user ID in token".to_string()))?;
+
+    let client = find_owned_client(&state, user_id, &id).await?;
+    let scope_repo = OAuthScopeRepository::new(state.pool.clone());
+    let scope = find_client_scope(&scope_repo, client.id, &scope_id).await?;
+
+    scope_repo.delete(scope.id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 // ============================================================================
 // Connected Apps Endpoint (Task 13.3)
 // Requirements: 9.1
@@ -1041,6 +2940,7 @@ pub async fn connected_apps_handler(
                 client_id: client.client_id,
                 name: consent.client_name,
                 scopes: consent.scopes,
+                is_implicit: consent.is_implicit,
                 granted_at: consent.granted_at,
             });
         }
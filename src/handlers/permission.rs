@@ -8,7 +8,8 @@ use uuid::Uuid;
 use crate::config::AppState;
 use crate::dto::{CreatePermissionRequest, PermissionResponse};
 use crate::error::{AppAuthError, PermissionError};
-use crate::middleware::AppContext;
+use crate::middleware::MachineContext;
+use crate::services::api_key_scopes;
 use crate::services::PermissionService;
 
 /// POST /apps/{app_id}/permissions - Create a new permission for an app
@@ -38,21 +39,22 @@ pub async fn create_permission_handler(
 /// POST /apps/{id}/permissions - Create a new permission for an app (App Auth)
 /// 
 /// This endpoint is protected by app authentication middleware.
-/// The app_id from the token must match the path parameter.
+/// The app_id from the app token or API key must match the path parameter.
 /// 
 /// # Requirements
 /// - 5.1: WHEN an authenticated App creates a permission, THE Auth_Server SHALL create the permission scoped to that App
 /// - 5.5: IF an App attempts to access permissions of another App, THEN THE Auth_Server SHALL reject with 403 Forbidden
 pub async fn create_permission_app_auth_handler(
     State(state): State<AppState>,
-    AppContext(token_app_id): AppContext,
+    machine: MachineContext,
     Path(path_app_id): Path<Uuid>,
     Json(req): Json<CreatePermissionRequest>,
 ) -> Result<(StatusCode, Json<PermissionResponse>), AppAuthError> {
-    // Verify app_id from token matches path parameter (Requirement 5.5)
-    if token_app_id != path_app_id {
-        return Err(AppAuthError::CrossAppAccess);
-    }
+    // Verify app_id from the credential matches path parameter (Requirement 5.5)
+    machine.require_app(path_app_id)?;
+
+    // API keys need the matching scope; app tokens have every scope
+    machine.require_scope(api_key_scopes::WRITE_PERMISSIONS)?;
     
     let permission_service = PermissionService::new(state.pool.clone());
     
@@ -72,20 +74,21 @@ pub async fn create_permission_app_auth_handler(
 /// GET /apps/{id}/permissions - List all permissions for an app (App Auth)
 /// 
 /// This endpoint is protected by app authentication middleware.
-/// The app_id from the token must match the path parameter.
+/// The app_id from the app token or API key must match the path parameter.
 /// 
 /// # Requirements
 /// - 5.2: WHEN an authenticated App lists permissions, THE Auth_Server SHALL return only permissions belonging to that App
 /// - 5.5: IF an App attempts to access permissions of another App, THEN THE Auth_Server SHALL reject with 403 Forbidden
 pub async fn list_permissions_app_auth_handler(
     State(state): State<AppState>,
-    AppContext(token_app_id): AppContext,
+    machine: MachineContext,
     Path(path_app_id): Path<Uuid>,
 ) -> Result<Json<Vec<PermissionResponse>>, AppAuthError> {
-    // Verify app_id from token matches path parameter (Requirement 5.5)
-    if token_app_id != path_app_id {
-        return Err(AppAuthError::CrossAppAccess);
-    }
+    // Verify app_id from the credential matches path parameter (Requirement 5.5)
+    machine.require_app(path_app_id)?;
+
+    // API keys need the matching scope; app tokens have every scope
+    machine.require_scope(api_key_scopes::READ_PERMISSIONS)?;
     
     let permission_service = PermissionService::new(state.pool.clone());
     
@@ -108,7 +111,7 @@ pub async fn list_permissions_app_auth_handler(
 /// POST /apps/{id}/roles/{role_id}/permissions - Assign a permission to a role (App Auth)
 /// 
 /// This endpoint is protected by app authentication middleware.
-/// The app_id from the token must match the path parameter.
+/// The app_id from the app token or API key must match the path parameter.
 /// Both the role and permission must belong to the same app.
 /// 
 /// # Requirements
@@ -117,14 +120,15 @@ pub async fn list_permissions_app_auth_handler(
 /// - 6.3: IF the role or permission belongs to a different App, THEN THE Auth_Server SHALL reject with 403 Forbidden
 pub async fn assign_permission_to_role_handler(
     State(state): State<AppState>,
-    AppContext(token_app_id): AppContext,
+    machine: MachineContext,
     Path((path_app_id, role_id)): Path<(Uuid, Uuid)>,
     Json(req): Json<crate::dto::AssignPermissionRequest>,
 ) -> Result<StatusCode, AppAuthError> {
-    // Verify app_id from token matches path parameter (Requirement 6.3)
-    if token_app_id != path_app_id {
-        return Err(AppAuthError::CrossAppAccess);
-    }
+    // Verify app_id from the credential matches path parameter (Requirement 6.3)
+    machine.require_app(path_app_id)?;
+
+    // API keys need the matching scope; app tokens have every scope
+    machine.require_scope(api_key_scopes::WRITE_ROLES)?;
     
     let permission_service = PermissionService::new(state.pool.clone());
     
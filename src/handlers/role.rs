@@ -1,15 +1,22 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Extension, Path, Query, State},
     http::StatusCode,
     Json,
 };
 use uuid::Uuid;
 
 use crate::config::AppState;
-use crate::dto::{AssignRoleRequest, CreateRoleRequest, RoleResponse};
-use crate::error::{AppAuthError, RoleError};
-use crate::middleware::AppContext;
-use crate::services::RoleService;
+use crate::dto::{
+    AssignRoleRequest, CreateElevationRequest, CreateRoleRequest, ElevationRequestResponse,
+    ListElevationRequestsQuery, RoleResponse, SyncRbacQuery, SyncRbacRequest, SyncRbacResponse,
+};
+use crate::dto::user_management::{PaginatedResponse, PaginationQuery};
+use crate::error::{AppAuthError, AppError, RoleError, UserManagementError};
+use crate::middleware::MachineContext;
+use crate::models::{RoleAssignmentHistory, RoleChangeActor};
+use crate::services::api_key_scopes;
+use crate::services::{RbacSyncService, RoleElevationService, RoleService, UserManagementService};
+use crate::utils::jwt::Claims;
 
 /// POST /apps/{app_id}/roles - Create a new role for an app
 /// 
@@ -38,21 +45,22 @@ pub async fn create_role_handler(
 /// POST /apps/{id}/roles - Create a new role for an app (App Auth)
 /// 
 /// This endpoint is protected by app authentication middleware.
-/// The app_id from the token must match the path parameter.
+/// The app_id from the app token or API key must match the path parameter.
 /// 
 /// # Requirements
 /// - 4.1: WHEN an authenticated App creates a role, THE Auth_Server SHALL create the role scoped to that App
 /// - 4.5: IF an App attempts to access roles of another App, THEN THE Auth_Server SHALL reject with 403 Forbidden
 pub async fn create_role_app_auth_handler(
     State(state): State<AppState>,
-    AppContext(token_app_id): AppContext,
+    machine: MachineContext,
     Path(path_app_id): Path<Uuid>,
     Json(req): Json<CreateRoleRequest>,
 ) -> Result<(StatusCode, Json<RoleResponse>), AppAuthError> {
-    // Verify app_id from token matches path parameter (Requirement 4.5)
-    if token_app_id != path_app_id {
-        return Err(AppAuthError::CrossAppAccess);
-    }
+    // Verify app_id from the credential matches path parameter (Requirement 4.5)
+    machine.require_app(path_app_id)?;
+
+    // API keys need the matching scope; app tokens have every scope
+    machine.require_scope(api_key_scopes::WRITE_ROLES)?;
     
     let role_service = RoleService::new(state.pool.clone());
     
@@ -72,20 +80,21 @@ pub async fn create_role_app_auth_handler(
 /// GET /apps/{id}/roles - List all roles for an app (App Auth)
 /// 
 /// This endpoint is protected by app authentication middleware.
-/// The app_id from the token must match the path parameter.
+/// The app_id from the app token or API key must match the path parameter.
 /// 
 /// # Requirements
 /// - 4.2: WHEN an authenticated App lists roles, THE Auth_Server SHALL return only roles belonging to that App
 /// - 4.5: IF an App attempts to access roles of another App, THEN THE Auth_Server SHALL reject with 403 Forbidden
 pub async fn list_roles_app_auth_handler(
     State(state): State<AppState>,
-    AppContext(token_app_id): AppContext,
+    machine: MachineContext,
     Path(path_app_id): Path<Uuid>,
 ) -> Result<Json<Vec<RoleResponse>>, AppAuthError> {
-    // Verify app_id from token matches path parameter (Requirement 4.5)
-    if token_app_id != path_app_id {
-        return Err(AppAuthError::CrossAppAccess);
-    }
+    // Verify app_id from the credential matches path parameter (Requirement 4.5)
+    machine.require_app(path_app_id)?;
+
+    // API keys need the matching scope; app tokens have every scope
+    machine.require_scope(api_key_scopes::READ_ROLES)?;
     
     let role_service = RoleService::new(state.pool.clone());
     
@@ -104,6 +113,44 @@ pub async fn list_roles_app_auth_handler(
     Ok(Json(response))
 }
 
+/// PUT /app-api/apps/{id}/rbac - Sync the app's full role/permission catalog (App Auth)
+/// 
+/// Creates missing roles and permissions, applies mapping changes and deletes
+/// entries absent from the request, all in one transaction. Roles still held
+/// by users are kept and reported as retained. `?dry_run=true` only reports
+/// the changes.
+pub async fn sync_rbac_app_auth_handler(
+    State(state): State<AppState>,
+    machine: MachineContext,
+    Path(path_app_id): Path<Uuid>,
+    Query(query): Query<SyncRbacQuery>,
+    Json(req): Json<SyncRbacRequest>,
+) -> Result<Json<SyncRbacResponse>, AppAuthError> {
+    // Verify app_id from the credential matches path parameter (Requirement 4.5)
+    machine.require_app(path_app_id)?;
+
+    // API keys need both write scopes; app tokens have every scope
+    machine.require_scope(api_key_scopes::WRITE_ROLES)?;
+    machine.require_scope(api_key_scopes::WRITE_PERMISSIONS)?;
+
+    let sync_service = RbacSyncService::new(state.pool.clone());
+    let changes = sync_service.sync(path_app_id, &req, query.dry_run).await?;
+
+    Ok(Json(SyncRbacResponse {
+        app_id: path_app_id,
+        dry_run: query.dry_run,
+        changed: !changes.is_empty(),
+        changes,
+    }))
+}
+
+fn role_change_actor(claims: &Claims) -> Result<RoleChangeActor, RoleError> {
+    claims
+        .user_id()
+        .map(RoleChangeActor::user)
+        .map_err(|_| RoleError::InternalError(anyhow::anyhow!("Invalid user ID in token")))
+}
+
 /// POST /apps/{app_id}/users/{user_id}/roles - Assign a role to a user
 /// 
 /// # Requirements
@@ -111,12 +158,14 @@ pub async fn list_roles_app_auth_handler(
 /// - 8.1-8.2: User role assignment requirements
 pub async fn assign_role_handler(
     State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
     Path((app_id, user_id)): Path<(Uuid, Uuid)>,
     Json(req): Json<AssignRoleRequest>,
 ) -> Result<StatusCode, RoleError> {
+    let actor = role_change_actor(&claims)?;
     let role_service = RoleService::new(state.pool.clone());
     
-    role_service.assign_role_to_user(user_id, app_id, req.role_id).await?;
+    role_service.assign_role_to_user(user_id, app_id, req.role_id, req.expires_at, req.access_window.as_deref(), actor).await?;
     
     Ok(StatusCode::NO_CONTENT)
 }
@@ -124,15 +173,35 @@ pub async fn assign_role_handler(
 /// DELETE /apps/{app_id}/users/{user_id}/roles/{role_id} - Remove a role from a user
 pub async fn remove_role_handler(
     State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
     Path((app_id, user_id, role_id)): Path<(Uuid, Uuid, Uuid)>,
 ) -> Result<StatusCode, RoleError> {
+    let actor = role_change_actor(&claims)?;
     let role_service = RoleService::new(state.pool.clone());
     
-    role_service.remove_role_from_user(user_id, app_id, role_id).await?;
+    role_service.remove_role_from_user(user_id, app_id, role_id, actor).await?;
     
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// GET /apps/{app_id}/users/{user_id}/roles/history - Role grants and removals for a user (owner or admin)
+pub async fn get_role_history_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((app_id, user_id)): Path<(Uuid, Uuid)>,
+    Query(pagination): Query<PaginationQuery>,
+) -> Result<Json<PaginatedResponse<RoleAssignmentHistory>>, UserManagementError> {
+    let actor_id = claims.user_id()
+        .map_err(|_| UserManagementError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
+
+    let service = UserManagementService::new(state.pool.clone());
+    let history = service
+        .get_role_history(actor_id, app_id, user_id, pagination.page, pagination.limit)
+        .await?;
+
+    Ok(Json(history))
+}
+
 /// GET /apps/{app_id}/users/{user_id}/roles - Get all roles for a user in an app
 pub async fn get_user_roles_in_app_handler(
     State(state): State<AppState>,
@@ -153,3 +222,71 @@ pub async fn get_user_roles_in_app_handler(
     
     Ok(Json(response))
 }
+
+fn requester_id(claims: &Claims) -> Result<Uuid, AppError> {
+    claims
+        .user_id()
+        .map_err(|_| AppError::InternalError(anyhow::anyhow!("Invalid user ID in token")))
+}
+
+/// POST /apps/{app_id}/elevation-requests - Request a role for a limited time (app members)
+pub async fn create_elevation_request_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(app_id): Path<Uuid>,
+    Json(req): Json<CreateElevationRequest>,
+) -> Result<(StatusCode, Json<ElevationRequestResponse>), AppError> {
+    let requester_id = requester_id(&claims)?;
+    let service = RoleElevationService::new(state.pool.clone(), state.config.role_elevation_max_secs);
+
+    let request = service
+        .request_elevation(requester_id, app_id, req.role_id, req.duration_secs, req.reason.as_deref())
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(request.into())))
+}
+
+/// GET /apps/{app_id}/elevation-requests - List elevation requests (owner only)
+pub async fn list_elevation_requests_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(app_id): Path<Uuid>,
+    Query(query): Query<ListElevationRequestsQuery>,
+) -> Result<Json<Vec<ElevationRequestResponse>>, AppError> {
+    let requester_id = requester_id(&claims)?;
+    let service = RoleElevationService::new(state.pool.clone(), state.config.role_elevation_max_secs);
+
+    let requests = service
+        .list_requests(requester_id, app_id, query.status.as_deref())
+        .await?;
+
+    Ok(Json(requests.into_iter().map(Into::into).collect()))
+}
+
+/// POST /apps/{app_id}/elevation-requests/{request_id}/approve - Grant the requested role temporarily (owner only)
+pub async fn approve_elevation_request_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((app_id, request_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ElevationRequestResponse>, AppError> {
+    let requester_id = requester_id(&claims)?;
+    let service = RoleElevationService::new(state.pool.clone(), state.config.role_elevation_max_secs);
+
+    let request = service.approve(requester_id, app_id, request_id).await?;
+
+    Ok(Json(request.into()))
+}
+
+/// POST /apps/{app_id}/elevation-requests/{request_id}/deny - Deny an elevation request (owner only)
+pub async fn deny_elevation_request_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((app_id, request_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ElevationRequestResponse>, AppError> {
+    let requester_id = requester_id(&claims)?;
+    let service = RoleElevationService::new(state.pool.clone(), state.config.role_elevation_max_secs);
+
+    let request = service.deny(requester_id, app_id, request_id).await?;
+
+    Ok(Json(request.into()))
+}
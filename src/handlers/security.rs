@@ -1,26 +1,42 @@
 use axum::{
     extract::{Query, State, Path},
-    http::HeaderMap,
+    http::{header::SET_COOKIE, HeaderMap, StatusCode},
+    response::{AppendHeaders, IntoResponse, Response},
     Extension, Json,
 };
 use uuid::Uuid;
 
 use crate::config::AppState;
 use crate::dto::{
-    AuditLogQuery, AuditLogResponse, DisableMfaRequest,
-    ListAuditLogsResponse, ListMfaMethodsResponse, ListSessionsResponse, LogoutRequest,
-    LogoutResponse, MfaMethodResponse, RegenerateBackupCodesRequest,
-    RegenerateBackupCodesResponse, RevokeSessionRequest, RevokeSessionsResponse, SessionResponse,
-    SetupTotpResponse, VerifyTotpSetupRequest, VerifyTotpSetupResponse,
+    applied_filters, has_more, AppliedSort, AuditLogQuery, AuditLogResponse, DeviceResponse, DisableMfaRequest, ListAuditLogsResponse,
+    ListDevicesResponse, ListMfaMethodsResponse, ListSessionsResponse, LogoutRequest,
+    ListTrustedDevicesResponse, LogoutResponse, MfaMethodResponse, RegenerateBackupCodesRequest,
+    RegenerateBackupCodesResponse, RegisterDeviceRequest, RevokeAllResponse, RevokeSessionRequest,
+    RevokeSessionsResponse, SessionResponse, SetupEmailMfaResponse, SetupSmsRequest, SetupTotpResponse, SmsCodeSentResponse,
+    TrustedDeviceResponse,
+    VerifySmsSetupRequest, VerifySmsSetupResponse, VerifyTotpSetupRequest, VerifyTotpSetupResponse,
 };
 use crate::error::AuthError;
 use crate::middleware::AccessToken;
-use crate::models::AuditAction;
+use crate::models::{AuditAction, UserDevice, WebhookEvent};
+use crate::handlers::oauth::issuer_url;
+use crate::repositories::{
+    AuthorizationCodeRepository, DeviceRepository, SsoSessionRepository, UserAppRepository,
+};
 use crate::services::{
-    AccountLockoutService, AuditService, LockoutConfig, MfaService, SessionService,
-    TokenRevocationService,
+    AccountLockoutService, AuditService, AuthService, CoolingOffService, DeviceService, LockoutConfig, MfaService,
+    OAuthService, SessionService, TokenLineageService, TokenRevocationService, TrustedDeviceService,
+    WebhookService,
 };
+use crate::services::sms::mask_phone;
+use crate::services::token_lineage::REVOKED_LOGOUT;
+use crate::utils::cooling_off::{CoolingOffPolicy, CoolingOffReason};
+use crate::utils::cookie::{get_cookie, RefreshCookieSettings};
 use crate::utils::jwt::Claims;
+use crate::utils::request_id::spawn_in_request;
+
+/// Reason recorded for the revoke-all marker of `POST /account/revoke-all`
+const REVOKED_ALL: &str = "revoke_all";
 
 // ============================================================================
 // Helper Functions for Request Context
@@ -58,17 +74,26 @@ fn extract_user_agent(headers: &HeaderMap) -> Option<String> {
 // ============================================================================
 
 /// POST /auth/logout - Logout and revoke tokens
+///
+/// Ends the current session only; other apps continued from the browser's
+/// SSO session stay signed in. `sso: true` also ends the SSO session and
+/// every app session continued from it, `all_sessions: true` ends every
+/// session of the user. Both clear the SSO cookie.
 pub async fn logout_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
     Extension(access_token): Extension<AccessToken>,
     headers: HeaderMap,
     Json(req): Json<LogoutRequest>,
-) -> Result<Json<LogoutResponse>, AuthError> {
+) -> Result<Response, AuthError> {
     let user_id = claims.user_id()?;
     let session_service = SessionService::new(state.pool.clone(), 7);
     let token_revocation_service = TokenRevocationService::new(state.pool.clone());
     let audit_service = AuditService::new(state.pool.clone());
+    let cookie_settings = RefreshCookieSettings::from_config(&state.config);
+    let refresh_cookie = get_cookie(&headers, &cookie_settings.name);
+    let sso_cookie_settings = RefreshCookieSettings::sso_from_config(&state.config);
+    let sso_cookie = get_cookie(&headers, &sso_cookie_settings.name);
 
     let ip_address = extract_ip_address(&headers);
     let user_agent = extract_user_agent(&headers);
@@ -79,17 +104,29 @@ pub async fn logout_handler(
             &access_token.0,
             Some(user_id),
             state.config.access_token_expiry_secs,
-            Some("logout"),
+            Some(REVOKED_LOGOUT),
         )
         .await;
+    if let Some(jti) = &claims.jti {
+        let _ = TokenLineageService::new(state.pool.clone())
+            .revoke(jti, REVOKED_LOGOUT)
+            .await;
+    }
 
     let sessions_revoked = if req.all_sessions {
         // Revoke all sessions
         session_service.revoke_all_sessions(user_id).await?
-    } else {
-        // Revoke current session only (find by refresh token would be ideal)
-        // For now, just count as 1
+    } else if let Some(token) = sso_cookie.as_deref().filter(|_| req.sso) {
+        // The SSO session, its login session and the app sessions continued from it
+        AuthService::new(state.pool.clone(), state.jwt_manager.clone())
+            .end_sso_session(token, user_id)
+            .await?
+    } else if let Some(session_id) = current_session_id(&state, &session_service, &claims, &headers).await? {
+        session_service.revoke_session(session_id, user_id).await?;
         1
+    } else {
+        // A token from outside a tracked session; only the token itself is revoked
+        0
     };
 
     // Log the logout event
@@ -99,41 +136,200 @@ pub async fn logout_handler(
             AuditAction::Logout,
             ip_address.as_deref(),
             user_agent.as_deref(),
-            Some(serde_json::json!({ "all_sessions": req.all_sessions })),
+            Some(serde_json::json!({ "all_sessions": req.all_sessions, "sso": req.sso })),
             true,
         )
         .await;
 
-    Ok(Json(LogoutResponse {
+    if req.all_sessions {
+        notify_logout_all(&state, user_id, sessions_revoked).await;
+    }
+
+    let body = Json(LogoutResponse {
         message: "Successfully logged out".to_string(),
         sessions_revoked,
-    }))
+    });
+
+    let mut cookies = Vec::new();
+    if refresh_cookie.is_some() {
+        cookies.push((SET_COOKIE, cookie_settings.clear_cookie(&cookie_settings.name)));
+    }
+    if sso_cookie.is_some() && (req.sso || req.all_sessions) {
+        cookies.push((SET_COOKIE, sso_cookie_settings.clear_cookie(&sso_cookie_settings.name)));
+    }
+
+    Ok((AppendHeaders(cookies), body).into_response())
+}
+
+/// POST /account/revoke-all - Disconnect everywhere
+///
+/// For a suspected account compromise. Ends every session and SSO session,
+/// revokes all OAuth tokens (clients with a `backchannel_logout_uri` are
+/// sent a logout token), deletes authorization codes not yet redeemed,
+/// deactivates registered devices, forgets devices trusted to skip MFA and
+/// refuses every access token issued so far, including the one used for
+/// this request. Consents and MFA settings are kept.
+pub async fn revoke_all_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
+) -> Result<Response, AuthError> {
+    let user_id = claims.user_id()?;
+    let oauth_error = |e: crate::error::OAuthError| AuthError::InternalError(anyhow::anyhow!("{}", e));
+
+    let sessions_revoked = SessionService::new(state.pool.clone(), 7)
+        .revoke_all_sessions(user_id)
+        .await?;
+    let sso_sessions_revoked = SsoSessionRepository::new(state.pool.clone())
+        .revoke_all_for_user(user_id)
+        .await?;
+    let (oauth_tokens_revoked, clients_notified) = OAuthService::new(state.pool.clone(), state.jwt_manager.clone())
+        .with_issuer(issuer_url(&state))
+        .logout_user(user_id)
+        .await
+        .map_err(oauth_error)?;
+    let authorization_codes_revoked = AuthorizationCodeRepository::new(state.pool.clone())
+        .delete_for_user(user_id)
+        .await
+        .map_err(oauth_error)?;
+    let devices_revoked = DeviceRepository::new(state.pool.clone())
+        .revoke_all_for_user(user_id)
+        .await?;
+    let trusted_devices_revoked = TrustedDeviceService::new(state.pool.clone())
+        .revoke_all(user_id)
+        .await?;
+
+    // Access tokens are self-contained; refuse every one issued until now
+    TokenRevocationService::new(state.pool.clone())
+        .revoke_all_user_tokens(user_id, state.config.access_token_expiry_secs, REVOKED_ALL)
+        .await?;
+
+    let _ = AuditService::new(state.pool.clone())
+        .log_auth_event(
+            Some(user_id),
+            AuditAction::AllCredentialsRevoked,
+            extract_ip_address(&headers).as_deref(),
+            extract_user_agent(&headers).as_deref(),
+            Some(serde_json::json!({
+                "sessions_revoked": sessions_revoked,
+                "sso_sessions_revoked": sso_sessions_revoked,
+                "oauth_tokens_revoked": oauth_tokens_revoked,
+                "clients_notified": clients_notified,
+                "authorization_codes_revoked": authorization_codes_revoked,
+                "devices_revoked": devices_revoked,
+                "trusted_devices_revoked": trusted_devices_revoked,
+            })),
+            true,
+        )
+        .await;
+
+    notify_logout_all(&state, user_id, sessions_revoked).await;
+
+    let body = Json(RevokeAllResponse {
+        message: "Signed out everywhere".to_string(),
+        sessions_revoked,
+        sso_sessions_revoked,
+        oauth_tokens_revoked,
+        clients_notified,
+        authorization_codes_revoked,
+        devices_revoked,
+        trusted_devices_revoked,
+    });
+
+    let cookie_settings = RefreshCookieSettings::from_config(&state.config);
+    let sso_cookie_settings = RefreshCookieSettings::sso_from_config(&state.config);
+    let mut cookies = Vec::new();
+    if get_cookie(&headers, &cookie_settings.name).is_some() {
+        cookies.push((SET_COOKIE, cookie_settings.clear_cookie(&cookie_settings.name)));
+    }
+    if get_cookie(&headers, &sso_cookie_settings.name).is_some() {
+        cookies.push((SET_COOKIE, sso_cookie_settings.clear_cookie(&sso_cookie_settings.name)));
+    }
+
+    Ok((AppendHeaders(cookies), body).into_response())
+}
+
+/// Send `user.logout_all` to every app the user is registered to, so they can
+/// clear their own sessions for the user
+async fn notify_logout_all(state: &AppState, user_id: Uuid, sessions_revoked: u64) {
+    let app_ids = match UserAppRepository::new(state.pool.clone()).find_app_ids_by_user(user_id).await {
+        Ok(app_ids) => app_ids,
+        Err(e) => {
+            tracing::warn!("Failed to load apps of user {} for logout webhooks: {:?}", user_id, e);
+            return;
+        }
+    };
+
+    let webhook_service = WebhookService::new(state.pool.clone());
+    let timestamp = chrono::Utc::now().to_rfc3339();
+    spawn_in_request(async move {
+        for app_id in app_ids {
+            let payload = serde_json::json!({
+                "event": "user.logout_all",
+                "user_id": user_id.to_string(),
+                "app_id": app_id.to_string(),
+                "sessions_revoked": sessions_revoked,
+                "timestamp": timestamp
+            });
+            let _ = webhook_service.trigger_event(app_id, WebhookEvent::UserLogoutAll, payload).await;
+        }
+    });
 }
 
 // ============================================================================
 // Session Management Handlers
 // ============================================================================
 
+/// The session a request was made from
+///
+/// Taken from the `sid` of the access token; tokens issued before sessions
+/// were bound fall back to the session of the refresh cookie, if any.
+async fn current_session_id(
+    state: &AppState,
+    session_service: &SessionService,
+    claims: &Claims,
+    headers: &HeaderMap,
+) -> Result<Option<Uuid>, AuthError> {
+    if let Some(session_id) = claims.session_id() {
+        return Ok(Some(session_id));
+    }
+
+    let cookie_settings = RefreshCookieSettings::from_config(&state.config);
+    let Some(refresh_token) = get_cookie(headers, &cookie_settings.name) else {
+        return Ok(None);
+    };
+    let user_id = claims.user_id()?;
+
+    Ok(session_service
+        .find_by_refresh_token(&refresh_token)
+        .await?
+        .filter(|session| session.user_id == user_id)
+        .map(|session| session.id))
+}
+
 /// GET /auth/sessions - List active sessions
 pub async fn list_sessions_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
 ) -> Result<Json<ListSessionsResponse>, AuthError> {
     let user_id = claims.user_id()?;
     let session_service = SessionService::new(state.pool.clone(), 7);
     let sessions = session_service.get_user_sessions(user_id).await?;
+    let current = current_session_id(&state, &session_service, &claims, &headers).await?;
 
     let session_responses: Vec<SessionResponse> = sessions
         .into_iter()
         .map(|s| SessionResponse {
             id: s.id,
+            device_id: s.device_id,
             device_name: s.device_name,
             device_type: s.device_type,
             ip_address: s.ip_address,
             user_agent: s.user_agent,
             last_used_at: s.last_active_at,
             created_at: s.created_at,
-            is_current: false, // Would need to track current session
+            is_current: current == Some(s.id),
         })
         .collect();
 
@@ -194,8 +390,12 @@ pub async fn revoke_other_sessions_handler(
     let ip_address = extract_ip_address(&headers);
     let user_agent = extract_user_agent(&headers);
 
-    // Note: In a real implementation, you'd pass the current session ID
-    let revoked_count = session_service.revoke_all_sessions(user_id).await?;
+    // Without a known current session there is nothing to keep
+    let current = current_session_id(&state, &session_service, &claims, &headers).await?;
+    let revoked_count = match current {
+        Some(session_id) => session_service.revoke_other_sessions(user_id, session_id).await?,
+        None => session_service.revoke_all_sessions(user_id).await?,
+    };
 
     // Log the session revocation
     let _ = audit_service
@@ -205,7 +405,7 @@ pub async fn revoke_other_sessions_handler(
             None,
             ip_address.as_deref(),
             user_agent.as_deref(),
-            Some(serde_json::json!({ "revoked_all": true })),
+            Some(serde_json::json!({ "revoked_all": true, "kept_session_id": current })),
         )
         .await;
 
@@ -215,6 +415,212 @@ pub async fn revoke_other_sessions_handler(
     }))
 }
 
+// ============================================================================
+// Device Handlers
+// ============================================================================
+
+fn device_response(device: UserDevice) -> DeviceResponse {
+    DeviceResponse {
+        push_enabled: device.has_push_token(),
+        signing_secret: None,
+        id: device.id,
+        platform: device.platform,
+        device_name: device.device_name,
+        last_seen_at: device.last_seen_at,
+        created_at: device.created_at,
+    }
+}
+
+/// POST /auth/devices - Register a native app device
+///
+/// Binds the session of the supplied refresh token to the new device; its next
+/// refresh returns a long-lived, rotating refresh token.
+pub async fn register_device_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
+    Json(req): Json<RegisterDeviceRequest>,
+) -> Result<(StatusCode, Json<DeviceResponse>), AuthError> {
+    let user_id = claims.user_id()?;
+    let device_service = DeviceService::new(state.pool.clone());
+    let audit_service = AuditService::new(state.pool.clone());
+
+    let device = device_service
+        .register_device(
+            user_id,
+            &req.platform,
+            req.device_name.as_deref(),
+            req.push_token.as_deref(),
+            &req.refresh_token,
+        )
+        .await?;
+
+    let _ = audit_service
+        .log_auth_event(
+            Some(user_id),
+            AuditAction::DeviceRegistered,
+            extract_ip_address(&headers).as_deref(),
+            extract_user_agent(&headers).as_deref(),
+            Some(serde_json::json!({
+                "device_id": device.id.to_string(),
+                "platform": device.platform
+            })),
+            true,
+        )
+        .await;
+
+    let signing_secret = device.signing_secret.clone();
+    Ok((
+        StatusCode::CREATED,
+        Json(DeviceResponse {
+            signing_secret,
+            ..device_response(device)
+        }),
+    ))
+}
+
+/// GET /auth/devices - List registered devices
+pub async fn list_devices_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ListDevicesResponse>, AuthError> {
+    let user_id = claims.user_id()?;
+    let device_service = DeviceService::new(state.pool.clone());
+
+    let devices: Vec<DeviceResponse> = device_service
+        .list_devices(user_id)
+        .await?
+        .into_iter()
+        .map(device_response)
+        .collect();
+    let total = devices.len();
+
+    Ok(Json(ListDevicesResponse { devices, total }))
+}
+
+/// DELETE /auth/devices/:device_id - Revoke a device and all sessions bound to it
+pub async fn revoke_device_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(device_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<RevokeSessionsResponse>, AuthError> {
+    let user_id = claims.user_id()?;
+    let device_service = DeviceService::new(state.pool.clone());
+    let audit_service = AuditService::new(state.pool.clone());
+
+    let revoked_count = device_service.revoke_device(user_id, device_id).await?;
+
+    let _ = audit_service
+        .log_auth_event(
+            Some(user_id),
+            AuditAction::DeviceRevoked,
+            extract_ip_address(&headers).as_deref(),
+            extract_user_agent(&headers).as_deref(),
+            Some(serde_json::json!({
+                "device_id": device_id.to_string(),
+                "sessions_revoked": revoked_count
+            })),
+            true,
+        )
+        .await;
+
+    Ok(Json(RevokeSessionsResponse {
+        message: "Device revoked successfully".to_string(),
+        revoked_count,
+    }))
+}
+
+/// GET /auth/trusted-devices - List devices trusted to skip MFA
+pub async fn list_trusted_devices_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ListTrustedDevicesResponse>, AuthError> {
+    let user_id = claims.user_id()?;
+    let trusted_device_service = TrustedDeviceService::new(state.pool.clone());
+
+    let trusted_devices: Vec<TrustedDeviceResponse> = trusted_device_service
+        .list(user_id)
+        .await?
+        .into_iter()
+        .map(|device| TrustedDeviceResponse {
+            id: device.id,
+            ip_address: device.ip_address,
+            user_agent: device.user_agent,
+            last_used_at: device.last_used_at,
+            expires_at: device.expires_at,
+            created_at: device.created_at,
+        })
+        .collect();
+    let total = trusted_devices.len();
+
+    Ok(Json(ListTrustedDevicesResponse { trusted_devices, total }))
+}
+
+/// DELETE /auth/trusted-devices/:device_id - Stop trusting a device; it is asked for MFA again
+pub async fn revoke_trusted_device_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(device_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<RevokeSessionsResponse>, AuthError> {
+    let user_id = claims.user_id()?;
+    let trusted_device_service = TrustedDeviceService::new(state.pool.clone());
+    let audit_service = AuditService::new(state.pool.clone());
+
+    trusted_device_service.revoke(user_id, device_id).await?;
+
+    let _ = audit_service
+        .log_auth_event(
+            Some(user_id),
+            AuditAction::TrustedDeviceRevoked,
+            extract_ip_address(&headers).as_deref(),
+            extract_user_agent(&headers).as_deref(),
+            Some(serde_json::json!({
+                "trusted_device_id": device_id.to_string()
+            })),
+            true,
+        )
+        .await;
+
+    Ok(Json(RevokeSessionsResponse {
+        message: "Trusted device revoked successfully".to_string(),
+        revoked_count: 1,
+    }))
+}
+
+/// DELETE /auth/trusted-devices - Stop trusting every device of the user
+pub async fn revoke_all_trusted_devices_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
+) -> Result<Json<RevokeSessionsResponse>, AuthError> {
+    let user_id = claims.user_id()?;
+    let trusted_device_service = TrustedDeviceService::new(state.pool.clone());
+    let audit_service = AuditService::new(state.pool.clone());
+
+    let revoked_count = trusted_device_service.revoke_all(user_id).await?;
+
+    let _ = audit_service
+        .log_auth_event(
+            Some(user_id),
+            AuditAction::TrustedDeviceRevoked,
+            extract_ip_address(&headers).as_deref(),
+            extract_user_agent(&headers).as_deref(),
+            Some(serde_json::json!({
+                "all": true,
+                "revoked_count": revoked_count
+            })),
+            true,
+        )
+        .await;
+
+    Ok(Json(RevokeSessionsResponse {
+        message: "Trusted devices revoked successfully".to_string(),
+        revoked_count,
+    }))
+}
+
 // ============================================================================
 // MFA Handlers
 // ============================================================================
@@ -276,6 +682,153 @@ pub async fn verify_totp_setup_handler(
     }))
 }
 
+/// POST /auth/mfa/sms/setup - Start SMS setup by texting a code to a phone
+///
+/// Sends are rate limited per account and per phone number.
+pub async fn setup_sms_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
+    Json(req): Json<SetupSmsRequest>,
+) -> Result<Json<SmsCodeSentResponse>, AuthError> {
+    let user_id = claims.user_id()?;
+    let mfa_service = MfaService::new(state.pool.clone(), "AuthServer".to_string());
+    let audit_service = AuditService::new(state.pool.clone());
+
+    let ip_address = extract_ip_address(&headers);
+    let user_agent = extract_user_agent(&headers);
+
+    let sent = mfa_service.setup_sms(user_id, &req.phone_number).await?;
+
+    let _ = audit_service
+        .log_mfa_event(
+            user_id,
+            AuditAction::MfaSmsSent,
+            ip_address.as_deref(),
+            user_agent.as_deref(),
+            Some(serde_json::json!({
+                "purpose": "enroll",
+                "method_id": sent.method_id,
+                "provider": mfa_service.sms_provider_name()
+            })),
+            true,
+        )
+        .await;
+
+    Ok(Json(SmsCodeSentResponse {
+        method_id: sent.method_id,
+        phone_hint: sent.phone_hint,
+        expires_in: sent.expires_in,
+    }))
+}
+
+/// POST /auth/mfa/sms/verify - Verify SMS setup with the texted code
+pub async fn verify_sms_setup_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
+    Json(req): Json<VerifySmsSetupRequest>,
+) -> Result<Json<VerifySmsSetupResponse>, AuthError> {
+    let user_id = claims.user_id()?;
+    let mfa_service = MfaService::new(state.pool.clone(), "AuthServer".to_string());
+    let audit_service = AuditService::new(state.pool.clone());
+
+    let ip_address = extract_ip_address(&headers);
+    let user_agent = extract_user_agent(&headers);
+
+    let backup_codes = mfa_service
+        .verify_sms_setup(user_id, req.method_id, &req.code)
+        .await?;
+
+    // Update user's mfa_enabled flag
+    sqlx::query("UPDATE users SET mfa_enabled = TRUE WHERE id = ?")
+        .bind(user_id.to_string())
+        .execute(&state.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+    // Log MFA enabled
+    let _ = audit_service
+        .log_mfa_event(
+            user_id,
+            AuditAction::MfaEnabled,
+            ip_address.as_deref(),
+            user_agent.as_deref(),
+            Some(serde_json::json!({ "method": "sms", "method_id": req.method_id })),
+            true,
+        )
+        .await;
+
+    let message = if backup_codes.is_empty() {
+        "SMS verification enabled successfully."
+    } else {
+        "SMS verification enabled successfully. Save your backup codes!"
+    };
+
+    Ok(Json(VerifySmsSetupResponse {
+        message: message.to_string(),
+        backup_codes,
+    }))
+}
+
+/// POST /auth/mfa/email/setup - Use codes emailed to the account address as a second factor
+///
+/// The address must be verified already, so there is no confirmation step.
+pub async fn setup_email_mfa_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
+) -> Result<Json<SetupEmailMfaResponse>, AuthError> {
+    let user_id = claims.user_id()?;
+    let mfa_service = MfaService::new(state.pool.clone(), "AuthServer".to_string());
+    let audit_service = AuditService::new(state.pool.clone());
+
+    let ip_address = extract_ip_address(&headers);
+    let user_agent = extract_user_agent(&headers);
+
+    let email_verified = sqlx::query_scalar::<_, bool>("SELECT email_verified FROM users WHERE id = ?")
+        .bind(user_id.to_string())
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?
+        .ok_or(AuthError::UserNotFound)?;
+    if !email_verified {
+        return Err(AuthError::EmailNotVerified);
+    }
+
+    let backup_codes = mfa_service.setup_email(user_id).await?;
+
+    // Update user's mfa_enabled flag
+    sqlx::query("UPDATE users SET mfa_enabled = TRUE WHERE id = ?")
+        .bind(user_id.to_string())
+        .execute(&state.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+    // Log MFA enabled
+    let _ = audit_service
+        .log_mfa_event(
+            user_id,
+            AuditAction::MfaEnabled,
+            ip_address.as_deref(),
+            user_agent.as_deref(),
+            Some(serde_json::json!({ "method": "email" })),
+            true,
+        )
+        .await;
+
+    let message = if backup_codes.is_empty() {
+        "Email verification codes enabled successfully."
+    } else {
+        "Email verification codes enabled successfully. Save your backup codes!"
+    };
+
+    Ok(Json(SetupEmailMfaResponse {
+        message: message.to_string(),
+        backup_codes,
+    }))
+}
+
 /// GET /auth/mfa/methods - List MFA methods
 pub async fn list_mfa_methods_handler(
     State(state): State<AppState>,
@@ -292,6 +845,7 @@ pub async fn list_mfa_methods_handler(
         .into_iter()
         .map(|m| MfaMethodResponse {
             id: m.id,
+            phone_hint: m.phone_number.as_deref().map(mask_phone),
             method_type: m.method_type,
             is_primary: m.is_primary,
             is_verified: m.is_verified,
@@ -342,6 +896,11 @@ pub async fn disable_mfa_handler(
         .log_mfa_event(user_id, AuditAction::MfaDisabled, ip_address.as_deref(), user_agent.as_deref(), None, true)
         .await;
 
+    // Removing MFA is a common step of a takeover; pause high-risk actions for a while
+    CoolingOffService::new(state.pool.clone(), CoolingOffPolicy::from_config(&state.config))
+        .start(user_id, CoolingOffReason::MfaReset, ip_address.as_deref(), user_agent.as_deref())
+        .await?;
+
     Ok(Json(crate::dto::MessageResponse {
         message: "MFA disabled successfully".to_string(),
     }))
@@ -379,10 +938,12 @@ pub async fn get_audit_logs_handler(
 ) -> Result<Json<ListAuditLogsResponse>, AuthError> {
     let user_id = claims.user_id()?;
     let audit_service = AuditService::new(state.pool.clone());
+    let (page, limit) = query.pagination().normalized();
 
     let logs = audit_service
-        .get_user_logs(user_id, query.page, query.limit)
+        .get_user_logs(user_id, page, limit)
         .await?;
+    let total = audit_service.count_user_logs(user_id).await?;
 
     let log_responses: Vec<AuditLogResponse> = logs
         .into_iter()
@@ -396,14 +957,18 @@ pub async fn get_audit_logs_handler(
             status: l.status,
             created_at: l.created_at,
             details: l.details,
+            correlation_id: l.correlation_id,
         })
         .collect();
 
     Ok(Json(ListAuditLogsResponse {
         logs: log_responses,
-        page: query.page,
-        limit: query.limit,
-        total: 0, // Would need to implement count
+        page,
+        limit,
+        total,
+        has_more: has_more(page, limit, total),
+        sort: AppliedSort { by: "created_at".to_string(), order: "desc".to_string() },
+        filters: None,
     }))
 }
 
@@ -415,13 +980,22 @@ pub async fn get_all_audit_logs_handler(
 ) -> Result<Json<ListAuditLogsResponse>, AuthError> {
     // Check if user is admin (would need to implement proper check)
     let audit_service = AuditService::new(state.pool.clone());
+    let (page, limit) = query.pagination().normalized();
 
     let logs = audit_service
         .get_all_logs(
             query.action.as_deref(),
             query.resource_type.as_deref(),
-            query.page,
-            query.limit,
+            query.correlation_id.as_deref(),
+            page,
+            limit,
+        )
+        .await?;
+    let total = audit_service
+        .count_all_logs(
+            query.action.as_deref(),
+            query.resource_type.as_deref(),
+            query.correlation_id.as_deref(),
         )
         .await?;
 
@@ -437,14 +1011,22 @@ pub async fn get_all_audit_logs_handler(
             status: l.status,
             created_at: l.created_at,
             details: l.details,
+            correlation_id: l.correlation_id,
         })
         .collect();
 
     Ok(Json(ListAuditLogsResponse {
         logs: log_responses,
-        page: query.page,
-        limit: query.limit,
-        total: 0,
+        page,
+        limit,
+        total,
+        has_more: has_more(page, limit, total),
+        sort: AppliedSort { by: "created_at".to_string(), order: "desc".to_string() },
+        filters: applied_filters(serde_json::json!({
+            "action": query.action,
+            "resource_type": query.resource_type,
+            "correlation_id": query.correlation_id,
+        })),
     }))
 }
 
@@ -6,7 +6,7 @@ use axum::{
 use uuid::Uuid;
 
 use crate::config::AppState;
-use crate::dto::user_management::{AppUserInfo, BanUserRequest, PaginatedResponse, PaginationQuery};
+use crate::dto::user_management::{AppUserInfo, AppUserListQuery, BanUserRequest, PaginatedResponse};
 use crate::error::UserManagementError;
 use crate::models::UserApp;
 use crate::services::{UserManagementService, IpRuleService, IpAccessResult};
@@ -122,7 +122,10 @@ pub async fn remove_user_handler(
     Ok(StatusCode::NO_CONTENT)
 }
 
-/// GET /apps/{app_id}/users - List all users in an app
+/// GET /apps/{app_id}/users - List users in an app
+///
+/// Supports `status`, `role_id`, `email`, `registered_from`/`registered_to`
+/// filters and `sort_by` (created_at, email) / `sort_order`.
 /// 
 /// # Requirements
 /// - 8.4: Expose GET /apps/{app_id}/users for listing app users
@@ -131,13 +134,13 @@ pub async fn list_app_users_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
     Path(app_id): Path<Uuid>,
-    Query(pagination): Query<PaginationQuery>,
+    Query(query): Query<AppUserListQuery>,
 ) -> Result<Json<PaginatedResponse<AppUserInfo>>, UserManagementError> {
     let actor_id = claims.user_id()
         .map_err(|_| UserManagementError::InternalError(anyhow::anyhow!("Invalid user ID in token")))?;
     
     let service = UserManagementService::new(state.pool.clone());
-    let response = service.list_app_users(actor_id, app_id, pagination.page, pagination.limit).await?;
+    let response = service.list_app_users(actor_id, app_id, &query).await?;
     
     Ok(Json(response))
 }
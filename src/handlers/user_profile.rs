@@ -112,7 +112,8 @@ pub async fn search_users_handler(
         return Err(AuthError::InsufficientScope);
     }
 
-    let service = UserProfileService::new(state.pool.clone());
+    let service = UserProfileService::new(state.pool.clone())
+        .with_registration_fields(state.config.registration_fields.clone());
     let results = service.search_users(query).await?;
 
     Ok(Json(results))
@@ -181,7 +182,7 @@ pub async fn bulk_assign_role_handler(
     }
 
     let service = UserProfileService::new(state.pool.clone());
-    let result = service.bulk_assign_role(req).await?;
+    let result = service.bulk_assign_role(user_id, req).await?;
 
     Ok(Json(result))
 }
@@ -1,6 +1,6 @@
 use axum::{
     extract::{Extension, Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     Json,
 };
 use uuid::Uuid;
@@ -11,8 +11,11 @@ use crate::dto::{
     FinishAuthenticationRequest, RenameCredentialRequest, PasskeyResponse, PasskeyAuthResponse,
 };
 use crate::error::AppError;
-use crate::services::{WebAuthnService, RegistrationResponse, AuthenticationResponse};
+use crate::handlers::auth::{extract_ip_address, extract_user_agent};
+use crate::services::{AuthService, LoginContext, WebAuthnService, RegistrationResponse, AuthenticationResponse};
+use crate::utils::acr::SignInMethod;
 use crate::utils::jwt::Claims;
+use crate::utils::travel::TravelPolicy;
 use crate::repositories::UserRepository;
 
 fn get_webauthn_service(state: &AppState) -> WebAuthnService {
@@ -21,7 +24,7 @@ fn get_webauthn_service(state: &AppState) -> WebAuthnService {
     // Default to frontend origin for development
     let rp_origin = std::env::var("WEBAUTHN_RP_ORIGIN").unwrap_or_else(|_| "http://localhost:5173".to_string());
     
-    WebAuthnService::new(state.pool.clone(), rp_id, rp_name, rp_origin)
+    WebAuthnService::new(state.pool.clone(), state.challenge_store.clone(), rp_id, rp_name, rp_origin)
 }
 
 /// POST /auth/webauthn/register/start - Start passkey registration
@@ -111,8 +114,11 @@ pub async fn start_authentication_handler(
 }
 
 /// POST /auth/webauthn/authenticate/finish - Complete passkey authentication
+///
+/// A passkey sign-in is phishing-resistant, so its tokens carry `acr=phr`.
 pub async fn finish_authentication_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<FinishAuthenticationRequest>,
 ) -> Result<Json<PasskeyAuthResponse>, AppError> {
     let service = get_webauthn_service(&state);
@@ -135,10 +141,14 @@ pub async fn finish_authentication_handler(
     let user = user_repo.find_by_id(user_id).await?
         .ok_or(AppError::NotFound("User not found".into()))?;
 
-    // Generate tokens using jwt_manager
-    let apps = std::collections::HashMap::new();
-    let token_pair = state.jwt_manager.create_token_pair(user.id, apps)
-        .map_err(|e| AppError::InternalError(e.into()))?;
+    let context = LoginContext {
+        ip_address: extract_ip_address(&headers),
+        user_agent: extract_user_agent(&headers),
+    };
+    let (token_pair, _session_id) = AuthService::new(state.pool.clone(), state.jwt_manager.clone())
+        .with_travel_policy(TravelPolicy::from_config(&state.config))
+        .complete_login(user.id, None, None, SignInMethod::Passkey, &context)
+        .await?;
 
     Ok(Json(PasskeyAuthResponse {
         access_token: token_pair.access_token,
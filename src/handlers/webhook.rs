@@ -1,13 +1,17 @@
 use axum::{
-    extract::{Extension, Path, State},
+    extract::{Extension, Path, Query, State},
     http::StatusCode,
     Json,
 };
 use uuid::Uuid;
 
 use crate::config::AppState;
-use crate::dto::{CreateWebhookRequest, UpdateWebhookRequest, WebhookResponse, WebhookWithSecretResponse};
+use crate::dto::{
+    CreateWebhookRequest, ReplayWebhookQuery, ReplayWebhookResponse, UpdateWebhookRequest,
+    WebhookResponse, WebhookWithSecretResponse,
+};
 use crate::error::AppError;
+use crate::repositories::AppRepository;
 use crate::services::WebhookService;
 use crate::utils::jwt::Claims;
 
@@ -22,7 +26,7 @@ pub async fn create_webhook_handler(
     let _ = claims.user_id()?;
 
     let service = WebhookService::new(state.pool.clone());
-    let (webhook, secret) = service.create_webhook(app_id, &req.url, req.events).await?;
+    let (webhook, secret) = service.create_webhook(app_id, &req.url, req.events, req.payload_version).await?;
 
     Ok((
         StatusCode::CREATED,
@@ -33,6 +37,7 @@ pub async fn create_webhook_handler(
             secret,
             events: webhook.events.0,
             is_active: webhook.is_active,
+            payload_version: webhook.payload_version,
             created_at: webhook.created_at,
         }),
     ))
@@ -55,6 +60,8 @@ pub async fn list_webhooks_handler(
             url: w.url,
             events: w.events.0,
             is_active: w.is_active,
+            payload_version: w.payload_version,
+            circuit_open_until: w.circuit_open_until,
             created_at: w.created_at,
         })
         .collect();
@@ -82,6 +89,8 @@ pub async fn get_webhook_handler(
         url: webhook.url,
         events: webhook.events.0,
         is_active: webhook.is_active,
+        payload_version: webhook.payload_version,
+        circuit_open_until: webhook.circuit_open_until,
         created_at: webhook.created_at,
     }))
 }
@@ -99,6 +108,7 @@ pub async fn update_webhook_handler(
         req.url.as_deref(),
         req.events,
         req.is_active,
+        req.payload_version,
     ).await?;
 
     Ok(Json(WebhookResponse {
@@ -107,6 +117,8 @@ pub async fn update_webhook_handler(
         url: webhook.url,
         events: webhook.events.0,
         is_active: webhook.is_active,
+        payload_version: webhook.payload_version,
+        circuit_open_until: webhook.circuit_open_until,
         created_at: webhook.created_at,
     }))
 }
@@ -121,3 +133,38 @@ pub async fn delete_webhook_handler(
     service.delete_webhook(webhook_id).await?;
     Ok(StatusCode::NO_CONTENT)
 }
+
+/// POST /apps/:app_id/webhooks/:webhook_id/replay?from=&to= - Re-deliver past events (app owner only)
+///
+/// Re-enqueues the app's events from the outbox that the webhook is
+/// subscribed to, rendered in its current payload version.
+pub async fn replay_webhook_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((app_id, webhook_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<ReplayWebhookQuery>,
+) -> Result<Json<ReplayWebhookResponse>, AppError> {
+    let user_id = claims.user_id()?;
+
+    let app = AppRepository::new(state.pool.clone())
+        .find_by_id(app_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("App not found".into()))?;
+    if app.owner_id != Some(user_id) {
+        return Err(AppError::NotAppOwner);
+    }
+
+    let service = WebhookService::new(state.pool.clone());
+    let webhook = service.get_webhook(webhook_id).await?
+        .filter(|webhook| webhook.app_id == app_id)
+        .ok_or_else(|| AppError::NotFound("Webhook not found".into()))?;
+
+    let to = query.to.unwrap_or_else(chrono::Utc::now);
+    let replay = service.replay_events(&webhook, query.from, to).await?;
+
+    Ok(Json(ReplayWebhookResponse {
+        queued: replay.queued,
+        truncated: replay.next_from.is_some(),
+        next_from: replay.next_from,
+    }))
+}
@@ -17,6 +17,7 @@ use axum::{
 };
 use serde::Serialize;
 use sqlx::mysql::MySqlPoolOptions;
+use std::net::SocketAddr;
 use std::time::Duration;
 use tower_http::{
     cors::{Any, CorsLayer},
@@ -29,25 +30,66 @@ use crate::config::{AppState, Config};
 use crate::handlers::{
     admin::{
         activate_user_handler, deactivate_user_handler, delete_app_handler, delete_user_handler,
+        dormant_users_handler, duplicate_users_handler,
         get_app_handler, get_user_handler, get_user_roles_handler, list_all_apps_handler,
-        list_all_users_handler, update_app_handler, update_user_handler,
+        list_all_users_handler, privacy_ledger_handler, set_legal_hold_handler, update_app_handler,
+        update_user_handler,
+    },
+    admin_approval::{approve_handler, get_approval_handler, list_approvals_handler, reject_handler},
+    admin_broadcast::{
+        broadcast_handler, cancel_broadcast_handler, get_broadcast_handler, list_broadcasts_handler,
+        list_suppressions_handler, suppress_user_handler, unsuppress_user_handler,
+    },
+    email_bounce::{clear_bounce_handler, email_webhook_handler, list_bounces_handler},
+    metrics::metrics_handler,
+    admin_chaos::{clear_chaos_fault_handler, inject_chaos_fault_handler, list_chaos_faults_handler},
+    admin_debug::{access_simulator_handler, debug_config_handler, debug_routes_handler, debug_workers_handler, instance_handler},
+    admin_encryption::{encryption_status_handler, rotate_encryption_handler},
+    admin_signing_key::{
+        get_signing_key_handler, import_signing_key_handler, list_signing_keys_handler,
+        promote_signing_key_handler, rotate_signing_key_handler, stage_signing_key_handler,
+    },
+    admin_token::token_lineage_handler,
+    admin_oauth_client::{
+        create_redirect_uri_block_handler, delete_redirect_uri_block_handler,
+        list_redirect_uri_blocks_handler, oauth_stats_handler, update_secret_policy_handler,
+        update_skip_consent_handler,
     },
     admin_scope::{
         list_all_scopes_handler, create_scope_handler, get_scope_handler,
         update_scope_handler, activate_scope_handler, deactivate_scope_handler,
-        delete_scope_handler,
+        delete_scope_handler, list_pending_scopes_handler, approve_scope_handler,
+        reject_scope_handler,
+    },
+    app::{
+        app_auth_handler, app_auth_methods_handler, create_app_handler, get_my_app_handler,
+        list_my_apps_handler, regenerate_secret_handler, update_app_deletion_policy_handler,
+        update_app_digest_handler, update_app_email_login_handler,
+        update_app_required_acr_handler, update_app_session_policy_handler, update_app_sso_handler,
+        update_app_token_binding_handler,
     },
-    app::{app_auth_handler, create_app_handler, get_my_app_handler, list_my_apps_handler, regenerate_secret_handler},
     auth::{
-        complete_mfa_login_handler, forgot_password_handler, login_handler, refresh_handler,
-        register_handler, reset_password_handler,
+        complete_mfa_login_handler, continue_sso_session_handler, csrf_token_handler,
+        email_login_handler, forgot_password_handler, login_handler, magic_link_verify_handler,
+        push_mfa_respond_handler, qr_login_approve_handler, qr_login_start_handler,
+        qr_login_token_handler, refresh_handler, register_handler, registration_fields_handler,
+        reset_password_handler, resolve_claims_handler, send_email_login_code_handler, send_email_mfa_handler,
+        send_magic_link_handler, send_sms_mfa_handler, start_push_mfa_handler, verify_email_mfa_handler,
+        start_sso_session_handler,
     },
     oauth::{
         authorize_callback_handler, authorize_handler, connected_apps_handler,
-        delete_client_handler, list_clients_handler, list_scopes_handler,
+        create_client_scope_handler, delete_client_handler, delete_client_jwks_handler,
+        delete_client_scope_handler, get_client_jwks_handler, put_client_jwks_handler,
+        device_authorization_handler, device_decision_handler, device_verification_handler,
+        end_session_handler, get_client_configuration_handler, update_client_configuration_handler,
+        delete_client_configuration_handler,
+        par_handler,
+        list_client_scopes_handler, list_client_secret_rotations_handler, rotate_client_secret_handler, list_clients_handler,
+        introspect_handler, jwks_handler, list_scopes_handler, server_capabilities_handler,
         openid_configuration_handler, regenerate_client_secret_handler,
         register_client_handler, revoke_consent_handler, revoke_handler, token_handler,
-        update_client_handler, userinfo_handler,
+        update_client_handler, update_client_scope_handler, userinfo_handler,
     },
     permission::{
         assign_permission_to_role_handler, assign_permission_to_role_user_handler,
@@ -56,8 +98,10 @@ use crate::handlers::{
         remove_permission_from_role_handler,
     },
     role::{
-        assign_role_handler, create_role_app_auth_handler, create_role_handler,
-        get_user_roles_in_app_handler, list_roles_app_auth_handler, remove_role_handler,
+        approve_elevation_request_handler, assign_role_handler, create_elevation_request_handler,
+        create_role_app_auth_handler, create_role_handler, deny_elevation_request_handler,
+        get_role_history_handler, get_user_roles_in_app_handler, list_elevation_requests_handler, list_roles_app_auth_handler,
+        remove_role_handler, sync_rbac_app_auth_handler,
     },
     user_management::{
         ban_user_handler, list_app_users_handler, register_to_app_handler, remove_user_handler,
@@ -70,13 +114,15 @@ use crate::handlers::{
     },
     security::{
         disable_mfa_handler, get_all_audit_logs_handler, get_audit_logs_handler,
-        list_mfa_methods_handler, list_sessions_handler, logout_handler,
-        regenerate_backup_codes_handler, revoke_other_sessions_handler, revoke_session_handler,
-        setup_totp_handler, unlock_account_handler, verify_totp_setup_handler,
+        list_devices_handler, list_mfa_methods_handler, list_sessions_handler, logout_handler,
+        regenerate_backup_codes_handler, register_device_handler, revoke_all_handler, revoke_device_handler,
+        list_trusted_devices_handler, revoke_all_trusted_devices_handler, revoke_trusted_device_handler,
+        revoke_other_sessions_handler, revoke_session_handler, setup_email_mfa_handler, setup_sms_handler, setup_totp_handler,
+        unlock_account_handler, verify_sms_setup_handler, verify_totp_setup_handler,
     },
     webhook::{
         create_webhook_handler, list_webhooks_handler, get_webhook_handler,
-        update_webhook_handler, delete_webhook_handler,
+        update_webhook_handler, delete_webhook_handler, replay_webhook_handler,
     },
     api_key::{
         create_api_key_handler, list_api_keys_handler, get_api_key_handler,
@@ -87,10 +133,14 @@ use crate::handlers::{
         ban_user_api_key_handler, unban_user_api_key_handler,
         list_roles_api_key_handler, get_user_roles_api_key_handler,
         assign_role_api_key_handler, remove_role_api_key_handler,
+        submit_provisioning_event_handler, list_provisioning_events_handler,
+        retry_provisioning_event_handler,
     },
     ip_rule::{
         create_ip_rule_handler, create_app_ip_rule_handler, list_ip_rules_handler,
         list_app_ip_rules_handler, check_ip_handler, delete_ip_rule_handler,
+        list_ip_rule_suggestions_handler, approve_ip_rule_suggestion_handler,
+        reject_ip_rule_suggestion_handler,
     },
     webauthn::{
         start_registration_handler, finish_registration_handler,
@@ -98,7 +148,8 @@ use crate::handlers::{
         list_credentials_handler, rename_credential_handler, delete_credential_handler,
     },
 };
-use crate::middleware::{app_auth_middleware, jwt_auth_middleware, oauth_auth_middleware, api_key_auth_middleware};
+use crate::middleware::{abuse_telemetry_middleware, acr_guard, app_auth_middleware, client_ip_middleware, csrf_middleware, jwt_auth_middleware, oauth_auth_middleware, api_key_auth_middleware, request_id_middleware};
+use crate::utils::acr::AcrLevel;
 
 /// Health check response
 #[derive(Serialize)]
@@ -137,21 +188,48 @@ async fn ready_handler(
 /// 
 /// ## Public Routes (no authentication required)
 /// - POST /auth/register - User registration (Requirement 14.1)
+/// - GET /auth/register/fields - Extra registration fields (REGISTRATION_FIELDS)
 /// - POST /auth/login - User authentication (Requirement 14.2)
 /// - POST /auth/refresh - Token refresh (Requirement 14.3)
+/// - GET /auth/csrf - Issue a CSRF token for cookie-authenticated requests
 /// - POST /auth/forgot-password - Initiate password reset (Requirement 14.4)
 /// - POST /auth/reset-password - Complete password reset (Requirement 14.5)
 /// - POST /auth/verify-email - Verify email with token
 /// - POST /auth/resend-verification - Resend verification email
+/// - POST /auth/qr/start - Open a QR login channel on the device to be logged in
+/// - POST /auth/qr/token - Poll a QR login channel for tokens
+/// - POST /auth/sso/session - Set the SSO cookie from a hosted login's refresh token
+/// - POST /auth/sso/continue - Sign in to another app from the SSO cookie
+/// - POST /auth/mfa/push - Send a push login approval to the user's devices
+/// - POST /auth/mfa/push/respond - Approve or deny a push login from a device (signed)
+/// - POST /auth/mfa/sms/send - Text a login code to the user's verified phone
+/// - POST /auth/mfa/email/send - Email a login code during the MFA step
+/// - POST /auth/mfa/email/verify - Complete the MFA step with an emailed code
+/// - POST /auth/email-login/send - Email a passwordless sign-in code (apps that allow it)
+/// - POST /auth/email-login/verify - Sign in with an emailed code
+/// - POST /auth/magic-link - Email a single-use sign-in link (apps that allow email sign-in)
+/// - GET /auth/magic-link/verify - Sign in with an emailed link
 /// - POST /apps/auth - App authentication with ID and Secret (Requirement 7.1)
+/// - GET /apps/{code}/auth-methods - Login options enabled for an app (cacheable)
+/// - POST /webhooks/email/{provider} - Bounce and complaint events from the email provider (`?token=`)
+/// - GET /metrics - Per-app and per-client SLO series in OpenMetrics format (`METRICS_TOKEN` bearer)
 /// 
 /// ## OAuth2 Public Routes (no authentication required)
 /// - GET /oauth/authorize - Authorization endpoint (Requirement 11.1)
 /// - POST /oauth/authorize/callback - Consent callback endpoint
 /// - POST /oauth/token - Token endpoint (Requirement 11.2)
 /// - POST /oauth/revoke - Token revocation endpoint (Requirement 11.3)
+/// - POST /oauth/introspect - Token introspection endpoint (RFC 7662)
+/// - POST /oauth/par - Pushed authorization request endpoint (RFC 9126)
+/// - POST /oauth/device_authorization - Device authorization endpoint (RFC 8628)
+/// - GET /oauth/device - Device verification endpoint (RFC 8628)
+/// - GET /oauth/logout - End session endpoint (RP-initiated and back-channel logout)
+/// - GET/PUT/DELETE /oauth/register/{client_id} - Client configuration endpoint (RFC 7592, registration access token)
+/// - POST /oauth/device/verify - Approve or deny a device (RFC 8628, requires JWT)
 /// - POST /oauth/clients - Client registration endpoint (Requirement 1.1, 1.4)
 /// - GET /.well-known/openid-configuration - Discovery endpoint (Requirement 11.5)
+/// - GET /.well-known/jwks.json - Public token signing keys (next, active and recently retired)
+/// - GET /.well-known/auth-server - Server version, supported flows, token formats and feature flags
 /// 
 /// ## OAuth2 Protected Routes (OAuth2 token required)
 /// - GET /oauth/userinfo - UserInfo endpoint (Requirement 11.4)
@@ -160,11 +238,36 @@ async fn ready_handler(
 /// - POST /apps - Create new app (Requirement 14.6)
 /// - POST /apps/{app_id}/roles - Create role for app (Requirement 14.7)
 /// - POST /apps/{app_id}/permissions - Create permission for app (Requirement 14.8)
-/// - POST /apps/{app_id}/users/{user_id}/roles - Assign role to user (Requirement 14.9)
+/// - POST /apps/{app_id}/users/{user_id}/roles - Assign role to user, optionally until `expires_at` (Requirement 14.9)
+/// - GET /apps/{app_id}/users/{user_id}/roles/history - Role grant/removal history (owner or admin)
+/// - POST /apps/{app_id}/elevation-requests - Request a role temporarily (app members)
+/// - GET /apps/{app_id}/elevation-requests - List elevation requests (owner only)
+/// - POST /apps/{app_id}/elevation-requests/{request_id}/approve|deny - Decide an elevation request (owner only)
 /// - POST /apps/{id}/secret/regenerate - Regenerate app secret (Requirement 7.2)
+/// - PUT /apps/{app_id}/session-policy - Set session idle timeout and absolute lifetime
+/// - PUT /apps/{app_id}/token-binding - Bind app tokens to the caller's IP range or client certificate
+/// - PUT /apps/{app_id}/deletion-policy - Choose whether deleted members are removed or anonymized
+/// - PUT /apps/{app_id}/required-acr - Require a minimum sign-in assurance level (pwd, mfa, phr)
+/// - PUT /apps/{app_id}/sso - Allow or refuse sign-ins continued from the SSO session
+/// - PUT /apps/{app_id}/digest - Subscribe the owner to the weekly app digest
+/// - PUT /apps/{app_id}/email-login - Allow passwordless sign-in with emailed codes
+/// - POST /apps/{app_id}/webhooks/{webhook_id}/replay - Re-deliver past events from the outbox (owner only)
 /// - GET /users/me - Get current user profile (Requirement 8.1)
 /// - PUT /users/me - Update current user profile
 /// - POST /users/me/change-password - Change password when logged in
+/// - POST /auth/qr/approve - Approve or deny a QR login from this device
+/// - GET /auth/claims - Resolve the full app claims of a roles_only or compressed access token
+/// - POST /auth/devices - Register a native app device and bind the current session
+/// - GET /auth/devices - List registered devices
+/// - DELETE /auth/devices/{device_id} - Revoke a device and its sessions
+/// - GET /auth/trusted-devices - List devices trusted to skip MFA
+/// - DELETE /auth/trusted-devices/{device_id} - Stop trusting a device
+/// - DELETE /auth/trusted-devices - Stop trusting every device
+/// - GET/POST /oauth/clients/{id}/scopes - List or define namespaced custom scopes for an owned client
+/// - PUT/DELETE /oauth/clients/{id}/scopes/{scope_id} - Update or delete a custom scope
+/// - POST /oauth/clients/{id}/secret/rotate - Rotate an owned client's secret, keeping the old one valid for a grace period
+/// - GET /oauth/clients/{id}/secret/rotations - Secret rotation history of an owned client
+/// - GET/PUT/DELETE /oauth/clients/{id}/jwks - Public keys an owned client signs request objects with
 /// 
 /// ## App User Management Routes (JWT authentication required)
 /// - POST /apps/{app_id}/register - Register current user to app (Requirement 8.5)
@@ -173,16 +276,18 @@ async fn ready_handler(
 /// - DELETE /apps/{app_id}/users/{user_id} - Remove user from app (Requirement 8.3)
 /// - GET /apps/{app_id}/users - List app users (Requirement 8.4)
 /// 
-/// ## App-Authenticated Routes (App JWT token required)
+/// ## App-Authenticated Routes (App JWT token or scoped X-API-Key)
 /// - POST /app-api/apps/{id}/roles - Create role (App auth, Requirement 4.1)
 /// - GET /app-api/apps/{id}/roles - List roles (App auth, Requirement 4.2)
 /// - POST /app-api/apps/{id}/permissions - Create permission (App auth, Requirement 5.1)
 /// - GET /app-api/apps/{id}/permissions - List permissions (App auth, Requirement 5.2)
 /// - POST /app-api/apps/{id}/roles/{role_id}/permissions - Assign permission to role (App auth, Requirement 6.1)
+/// - PUT /app-api/apps/{id}/rbac - Sync the full role/permission catalog (App auth)
 /// 
 /// ## Account Management Routes (JWT authentication required)
 /// - GET /account/connected-apps - List connected OAuth apps (Requirement 9.1)
 /// - DELETE /account/connected-apps/{client_id} - Revoke consent (Requirement 9.2, 9.3)
+/// - POST /account/revoke-all - Revoke every session, token and device of the user
 /// 
 /// ## Admin Routes (JWT authentication required, system admin only)
 /// - GET /admin/users - List all users (Requirement 8.6)
@@ -192,21 +297,78 @@ async fn ready_handler(
 /// - GET /admin/users/export - Export all users
 /// - POST /admin/users/import - Import users
 /// - POST /admin/users/bulk-assign-role - Bulk assign role to users
+/// - GET /admin/users/duplicates - Report probable duplicate accounts
+/// - GET /admin/users/dormant - Report accounts without recent activity
+/// - GET /admin/users/{user_id}/privacy-ledger - Export a user's privacy ledger (JSON or CSV)
+/// - PUT /admin/users/{user_id}/legal-hold - Place a user under legal hold or release it
+/// - PUT /admin/oauth-clients/{client_id}/skip-consent - Mark an internal client as first-party
+/// - PUT /admin/oauth-clients/{client_id}/secret-policy - Set a client's maximum secret age
+/// - GET /admin/oauth/stats - Grant, error and consent statistics per client over a time window
+/// - GET/POST /admin/redirect-uri-blocklist - List or add blocked redirect URI patterns
+/// - DELETE /admin/redirect-uri-blocklist/{id} - Remove a blocked redirect URI pattern
+/// - GET /admin/ip-rules/suggestions - IP deny rules suggested from 4xx/429 bursts
+/// - POST /admin/ip-rules/suggestions/{suggestion_id}/approve|reject - Create the rule or dismiss it
+/// - GET /admin/scopes/pending - Custom scopes awaiting approval for global visibility
+/// - POST /admin/scopes/{scope_id}/approve|reject - Approve or reject global visibility
+/// - GET /admin/debug/config - Running configuration with secrets redacted
+/// - GET /admin/debug/routes - Mounted route table with auth requirements
+/// - GET /admin/debug/workers - Background worker leadership across instances
+/// - POST /admin/access-simulator - Explain whether a user's sign-in to an app would be allowed
+/// - GET /admin/instance - Version, enabled features and coarse counts (the usage heartbeat report)
+/// - GET /admin/chaos - Injected faults running on this instance (`chaos` builds only)
+/// - PUT/DELETE /admin/chaos/{fault} - Inject or stop DB latency, webhook failures or email errors
+/// - GET /admin/encryption/status - Encryption coverage of sensitive columns per key
+/// - POST /admin/encryption/rotate - Re-encrypt a batch of a column under the active key
+/// - GET /admin/signing-keys - Token signing keys with status and fingerprints
+/// - POST /admin/signing-keys - Import an externally generated signing key
+/// - GET /admin/signing-keys/{kid} - Export a signing key's public PEM and JWK
+/// - POST /admin/signing-keys/{kid}/stage - Publish an imported key as the next key
+/// - POST /admin/signing-keys/{kid}/promote - Sign with the staged next key
+/// - POST /admin/keys/rotate - Generate a new signing key, promoted once published
+/// - GET /admin/tokens/{jti}/lineage - Trace a token from its login through refreshes to revocation
+/// - POST /admin/notifications/broadcast - Preview or schedule an email to a user segment
+/// - GET /admin/notifications/broadcasts[/{broadcast_id}] - Broadcasts with delivery counts
+/// - POST /admin/notifications/broadcasts/{broadcast_id}/cancel - Stop a broadcast
+/// - GET /admin/notifications/suppressions - Users who receive no broadcasts
+/// - PUT/DELETE /admin/notifications/suppressions/{user_id} - Suppress a user or lift it
+/// - GET /admin/notifications/bounces - Addresses that hard bounced or complained
+/// - DELETE /admin/notifications/bounces/{email} - Send email to a bounced address again
+/// - GET /admin/approvals[/{approval_id}] - Sensitive admin actions awaiting a second admin
+/// - POST /admin/approvals/{approval_id}/approve|reject - Execute the held action or drop it
 pub fn create_router(state: AppState) -> Router {
     // Public auth routes - no authentication required
     let auth_routes = Router::new()
         .route("/register", post(register_handler))
+        .route("/register/fields", get(registration_fields_handler))
         .route("/login", post(login_handler))
         .route("/refresh", post(refresh_handler))
+        .route("/csrf", get(csrf_token_handler))
         .route("/forgot-password", post(forgot_password_handler))
         .route("/reset-password", post(reset_password_handler))
         .route("/verify-email", post(verify_email_handler))
         .route("/resend-verification", post(resend_verification_handler))
         // MFA login completion - public (uses mfa_token for auth)
         .route("/mfa/verify", post(complete_mfa_login_handler))
+        // Push MFA - the device answer is authenticated by its signing secret
+        .route("/mfa/push", post(start_push_mfa_handler))
+        .route("/mfa/push/respond", post(push_mfa_respond_handler))
+        .route("/mfa/sms/send", post(send_sms_mfa_handler))
+        .route("/mfa/email/send", post(send_email_mfa_handler))
+        .route("/mfa/email/verify", post(verify_email_mfa_handler))
+        // Passwordless email codes - only for apps that enabled them
+        .route("/email-login/send", post(send_email_login_code_handler))
+        .route("/email-login/verify", post(email_login_handler))
+        .route("/magic-link", post(send_magic_link_handler))
+        .route("/magic-link/verify", get(magic_link_verify_handler))
         // WebAuthn public routes
         .route("/webauthn/authenticate/start", post(start_authentication_handler))
-        .route("/webauthn/authenticate/finish", post(finish_authentication_handler));
+        .route("/webauthn/authenticate/finish", post(finish_authentication_handler))
+        // QR cross-device login - the displaying device polls with its poll_secret
+        .route("/qr/start", post(qr_login_start_handler))
+        .route("/qr/token", post(qr_login_token_handler))
+        // SSO session - continuation is authenticated by the SSO cookie
+        .route("/sso/session", post(start_sso_session_handler))
+        .route("/sso/continue", post(continue_sso_session_handler));
 
     // Protected auth routes - JWT authentication required
     let protected_auth_routes = Router::new()
@@ -214,18 +376,34 @@ pub fn create_router(state: AppState) -> Router {
         .route("/sessions", get(list_sessions_handler))
         .route("/sessions", delete(revoke_other_sessions_handler))
         .route("/sessions/revoke", post(revoke_session_handler))
+        .route("/devices", post(register_device_handler))
+        .route("/devices", get(list_devices_handler))
+        .route("/devices/:device_id", delete(revoke_device_handler))
+        .route("/trusted-devices", get(list_trusted_devices_handler))
+        .route("/trusted-devices", delete(revoke_all_trusted_devices_handler))
+        .route("/trusted-devices/:device_id", delete(revoke_trusted_device_handler))
         .route("/mfa/totp/setup", post(setup_totp_handler))
         .route("/mfa/totp/verify", post(verify_totp_setup_handler))
+        .route("/mfa/sms/setup", post(setup_sms_handler))
+        .route("/mfa/sms/verify", post(verify_sms_setup_handler))
+        .route("/mfa/email/setup", post(setup_email_mfa_handler))
         .route("/mfa/methods", get(list_mfa_methods_handler))
-        .route("/mfa", delete(disable_mfa_handler))
-        .route("/mfa/backup-codes/regenerate", post(regenerate_backup_codes_handler))
         .route("/audit-logs", get(get_audit_logs_handler))
+        .route("/qr/approve", post(qr_login_approve_handler))
+        .route("/claims", get(resolve_claims_handler))
         // WebAuthn protected routes
         .route("/webauthn/register/start", post(start_registration_handler))
         .route("/webauthn/register/finish", post(finish_registration_handler))
         .route("/webauthn/credentials", get(list_credentials_handler))
         .route("/webauthn/credentials/:credential_id", put(rename_credential_handler))
         .route("/webauthn/credentials/:credential_id", delete(delete_credential_handler))
+        // Turning off or resetting the second factor needs a session that used it
+        .merge(
+            Router::new()
+                .route("/mfa", delete(disable_mfa_handler))
+                .route("/mfa/backup-codes/regenerate", post(regenerate_backup_codes_handler))
+                .layer(axum_middleware::from_fn(acr_guard(AcrLevel::Mfa))),
+        )
         .layer(axum_middleware::from_fn_with_state(
             state.clone(),
             jwt_auth_middleware,
@@ -238,6 +416,14 @@ pub fn create_router(state: AppState) -> Router {
         .route("/authorize/callback", post(authorize_callback_handler))
         .route("/token", post(token_handler))
         .route("/revoke", post(revoke_handler))
+        .route("/introspect", post(introspect_handler))
+        .route("/par", post(par_handler))
+        .route("/device_authorization", post(device_authorization_handler))
+        .route("/device", get(device_verification_handler))
+        .route("/logout", get(end_session_handler))
+        .route("/register/:client_id", get(get_client_configuration_handler))
+        .route("/register/:client_id", put(update_client_configuration_handler))
+        .route("/register/:client_id", delete(delete_client_configuration_handler))
         .route("/scopes", get(list_scopes_handler));
 
     // OAuth2 protected routes - requires JWT authentication
@@ -248,6 +434,16 @@ pub fn create_router(state: AppState) -> Router {
         .route("/clients/:id", put(update_client_handler))
         .route("/clients/:id", delete(delete_client_handler))
         .route("/clients/:id/secret", post(regenerate_client_secret_handler))
+        .route("/clients/:id/secret/rotate", post(rotate_client_secret_handler))
+        .route("/clients/:id/secret/rotations", get(list_client_secret_rotations_handler))
+        .route("/clients/:id/jwks", get(get_client_jwks_handler))
+        .route("/clients/:id/jwks", put(put_client_jwks_handler))
+        .route("/clients/:id/jwks", delete(delete_client_jwks_handler))
+        .route("/clients/:id/scopes", post(create_client_scope_handler))
+        .route("/clients/:id/scopes", get(list_client_scopes_handler))
+        .route("/clients/:id/scopes/:scope_id", put(update_client_scope_handler))
+        .route("/clients/:id/scopes/:scope_id", delete(delete_client_scope_handler))
+        .route("/device/verify", post(device_decision_handler))
         .layer(axum_middleware::from_fn_with_state(
             state.clone(),
             jwt_auth_middleware,
@@ -265,7 +461,9 @@ pub fn create_router(state: AppState) -> Router {
     // OpenID Connect discovery endpoint - public
     // Requirement: 11.5
     let wellknown_routes = Router::new()
-        .route("/openid-configuration", get(openid_configuration_handler));
+        .route("/openid-configuration", get(openid_configuration_handler))
+        .route("/jwks.json", get(jwks_handler))
+        .route("/auth-server", get(server_capabilities_handler));
 
     // Protected user routes - JWT authentication required (Requirement 8.1)
     let protected_user_routes = Router::new()
@@ -282,6 +480,7 @@ pub fn create_router(state: AppState) -> Router {
     let account_routes = Router::new()
         .route("/connected-apps", get(connected_apps_handler))
         .route("/connected-apps/:client_id", delete(revoke_consent_handler))
+        .route("/revoke-all", post(revoke_all_handler))
         .layer(axum_middleware::from_fn_with_state(
             state.clone(),
             jwt_auth_middleware,
@@ -300,9 +499,21 @@ pub fn create_router(state: AppState) -> Router {
         // User role management
         .route("/apps/:app_id/users/:user_id/roles", post(assign_role_handler))
         .route("/apps/:app_id/users/:user_id/roles", get(get_user_roles_in_app_handler))
+        .route("/apps/:app_id/users/:user_id/roles/history", get(get_role_history_handler))
         .route("/apps/:app_id/users/:user_id/roles/:role_id", delete(remove_role_handler))
+        .route("/apps/:app_id/elevation-requests", post(create_elevation_request_handler))
+        .route("/apps/:app_id/elevation-requests", get(list_elevation_requests_handler))
+        .route("/apps/:app_id/elevation-requests/:request_id/approve", post(approve_elevation_request_handler))
+        .route("/apps/:app_id/elevation-requests/:request_id/deny", post(deny_elevation_request_handler))
         // Secret regeneration (Requirement 7.2)
         .route("/apps/:id/secret/regenerate", post(regenerate_secret_handler))
+        .route("/apps/:app_id/session-policy", put(update_app_session_policy_handler))
+        .route("/apps/:app_id/token-binding", put(update_app_token_binding_handler))
+        .route("/apps/:app_id/deletion-policy", put(update_app_deletion_policy_handler))
+        .route("/apps/:app_id/required-acr", put(update_app_required_acr_handler))
+        .route("/apps/:app_id/sso", put(update_app_sso_handler))
+        .route("/apps/:app_id/digest", put(update_app_digest_handler))
+        .route("/apps/:app_id/email-login", put(update_app_email_login_handler))
         // App user management routes (Requirements 8.1-8.5)
         .route("/apps/:app_id/register", post(register_to_app_handler))
         .route("/apps/:app_id/users/:user_id/ban", post(ban_user_handler))
@@ -315,6 +526,7 @@ pub fn create_router(state: AppState) -> Router {
         .route("/apps/:app_id/webhooks/:webhook_id", get(get_webhook_handler))
         .route("/apps/:app_id/webhooks/:webhook_id", put(update_webhook_handler))
         .route("/apps/:app_id/webhooks/:webhook_id", delete(delete_webhook_handler))
+        .route("/apps/:app_id/webhooks/:webhook_id/replay", post(replay_webhook_handler))
         // API Key routes
         .route("/apps/:app_id/api-keys", post(create_api_key_handler))
         .route("/apps/:app_id/api-keys", get(list_api_keys_handler))
@@ -337,6 +549,7 @@ pub fn create_router(state: AppState) -> Router {
         .route("/:id/permissions", post(create_permission_app_auth_handler))
         .route("/:id/permissions", get(list_permissions_app_auth_handler))
         .route("/:id/roles/:role_id/permissions", post(assign_permission_to_role_handler))
+        .route("/:id/rbac", put(sync_rbac_app_auth_handler))
         .layer(axum_middleware::from_fn_with_state(
             state.clone(),
             app_auth_middleware,
@@ -351,13 +564,17 @@ pub fn create_router(state: AppState) -> Router {
         .route("/users/export", get(export_users_handler))
         .route("/users/import", post(import_users_handler))
         .route("/users/bulk-assign-role", post(bulk_assign_role_handler))
+        .route("/users/duplicates", get(duplicate_users_handler))
+        .route("/users/dormant", get(dormant_users_handler))
         .route("/users/:user_id", get(get_user_handler))
         .route("/users/:user_id", put(update_user_handler))
         .route("/users/:user_id", delete(delete_user_handler))
         .route("/users/:user_id/deactivate", post(deactivate_user_handler))
         .route("/users/:user_id/activate", post(activate_user_handler))
         .route("/users/:user_id/unlock", post(unlock_account_handler))
+        .route("/users/:user_id/legal-hold", put(set_legal_hold_handler))
         .route("/users/:user_id/roles", get(get_user_roles_handler))
+        .route("/users/:user_id/privacy-ledger", get(privacy_ledger_handler))
         // App management
         .route("/apps", get(list_all_apps_handler))
         .route("/apps/:app_id", get(get_app_handler))
@@ -369,15 +586,65 @@ pub fn create_router(state: AppState) -> Router {
         .route("/ip-rules", post(create_ip_rule_handler))
         .route("/ip-rules", get(list_ip_rules_handler))
         .route("/ip-rules/check", get(check_ip_handler))
+        .route("/ip-rules/suggestions", get(list_ip_rule_suggestions_handler))
+        .route("/ip-rules/suggestions/:suggestion_id/approve", post(approve_ip_rule_suggestion_handler))
+        .route("/ip-rules/suggestions/:suggestion_id/reject", post(reject_ip_rule_suggestion_handler))
         .route("/ip-rules/:rule_id", delete(delete_ip_rule_handler))
         // OAuth Scopes management (admin only)
         .route("/scopes", get(list_all_scopes_handler))
         .route("/scopes", post(create_scope_handler))
+        .route("/scopes/pending", get(list_pending_scopes_handler))
         .route("/scopes/:scope_id", get(get_scope_handler))
         .route("/scopes/:scope_id", put(update_scope_handler))
         .route("/scopes/:scope_id", delete(delete_scope_handler))
         .route("/scopes/:scope_id/activate", post(activate_scope_handler))
         .route("/scopes/:scope_id/deactivate", post(deactivate_scope_handler))
+        .route("/scopes/:scope_id/approve", post(approve_scope_handler))
+        .route("/scopes/:scope_id/reject", post(reject_scope_handler))
+        // OAuth client trust level (admin only)
+        .route("/oauth-clients/:client_id/skip-consent", put(update_skip_consent_handler))
+        .route("/oauth-clients/:client_id/secret-policy", put(update_secret_policy_handler))
+        .route("/oauth/stats", get(oauth_stats_handler))
+        // Redirect URI blocklist (admin only)
+        .route("/redirect-uri-blocklist", get(list_redirect_uri_blocks_handler))
+        .route("/redirect-uri-blocklist", post(create_redirect_uri_block_handler))
+        .route("/redirect-uri-blocklist/:id", delete(delete_redirect_uri_block_handler))
+        // Ops introspection (admin only)
+        .route("/debug/config", get(debug_config_handler))
+        .route("/debug/routes", get(debug_routes_handler))
+        .route("/debug/workers", get(debug_workers_handler))
+        .route("/access-simulator", post(access_simulator_handler))
+        .route("/instance", get(instance_handler))
+        // Fault injection for staging (`chaos` builds only)
+        .route("/chaos", get(list_chaos_faults_handler))
+        .route("/chaos/:fault", put(inject_chaos_fault_handler))
+        .route("/chaos/:fault", delete(clear_chaos_fault_handler))
+        // Encryption of sensitive columns (admin only)
+        .route("/encryption/status", get(encryption_status_handler))
+        .route("/encryption/rotate", post(rotate_encryption_handler))
+        // Token signing key ceremony (admin only)
+        .route("/signing-keys", get(list_signing_keys_handler))
+        .route("/signing-keys", post(import_signing_key_handler))
+        .route("/signing-keys/:kid", get(get_signing_key_handler))
+        .route("/signing-keys/:kid/stage", post(stage_signing_key_handler))
+        .route("/signing-keys/:kid/promote", post(promote_signing_key_handler))
+        .route("/keys/rotate", post(rotate_signing_key_handler))
+        // Token lineage for incident forensics (admin only)
+        .route("/tokens/:jti/lineage", get(token_lineage_handler))
+        // Email broadcasts to user segments (admin only)
+        .route("/notifications/broadcast", post(broadcast_handler))
+        .route("/notifications/broadcasts", get(list_broadcasts_handler))
+        .route("/notifications/broadcasts/:broadcast_id", get(get_broadcast_handler))
+        .route("/notifications/broadcasts/:broadcast_id/cancel", post(cancel_broadcast_handler))
+        .route("/notifications/suppressions", get(list_suppressions_handler))
+        .route("/notifications/suppressions/:user_id", put(suppress_user_handler))
+        .route("/notifications/suppressions/:user_id", delete(unsuppress_user_handler))
+        .route("/notifications/bounces", get(list_bounces_handler))
+        .route("/notifications/bounces/:email", delete(clear_bounce_handler))
+        .route("/approvals", get(list_approvals_handler))
+        .route("/approvals/:approval_id", get(get_approval_handler))
+        .route("/approvals/:approval_id/approve", post(approve_handler))
+        .route("/approvals/:approval_id/reject", post(reject_handler))
         .layer(axum_middleware::from_fn_with_state(
             state.clone(),
             jwt_auth_middleware,
@@ -396,6 +663,10 @@ pub fn create_router(state: AppState) -> Router {
         .route("/users/:user_id/roles", get(get_user_roles_api_key_handler))
         .route("/users/:user_id/roles", post(assign_role_api_key_handler))
         .route("/users/:user_id/roles/:role_id", delete(remove_role_api_key_handler))
+        // Inbound provisioning events (scope depends on the event type)
+        .route("/provisioning/events", post(submit_provisioning_event_handler))
+        .route("/provisioning/events", get(list_provisioning_events_handler))
+        .route("/provisioning/events/:event_id/retry", post(retry_provisioning_event_handler))
         .layer(axum_middleware::from_fn_with_state(
             state.clone(),
             api_key_auth_middleware,
@@ -412,6 +683,12 @@ pub fn create_router(state: AppState) -> Router {
         .merge(protected_app_routes)
         // Public app auth route - no authentication required (Requirement 7.1)
         .route("/apps/auth", post(app_auth_handler))
+        // Public login options of an app, for rendering login pages
+        .route("/apps/:code/auth-methods", get(app_auth_methods_handler))
+        // Email provider bounce webhook, authenticated by EMAIL_WEBHOOK_SECRET
+        .route("/webhooks/email/:provider", post(email_webhook_handler))
+        // OpenMetrics scrape endpoint, authenticated by METRICS_TOKEN
+        .route("/metrics", get(metrics_handler))
         .nest("/app-api/apps", app_auth_routes)
         .nest("/admin", admin_routes)
         // API Key authenticated routes
@@ -424,6 +701,14 @@ pub fn create_router(state: AppState) -> Router {
         // Account management routes (Requirements 9.1-9.3)
         .nest("/account", account_routes)
         // Middleware layers
+        // CSRF check for state-changing requests authenticated by cookie
+        .layer(axum_middleware::from_fn_with_state(state.clone(), csrf_middleware))
+        // Count 4xx/429 responses per IP and route for suggested IP rules
+        .layer(axum_middleware::from_fn(abuse_telemetry_middleware))
+        // Request id for logs, audit rows and webhooks; echoed as X-Request-Id
+        .layer(axum_middleware::from_fn(request_id_middleware))
+        // Client IP from the peer address and trusted proxies, replacing forwarding headers
+        .layer(axum_middleware::from_fn_with_state(state.clone(), client_ip_middleware))
         .layer(TraceLayer::new_for_http())
         .layer(TimeoutLayer::new(Duration::from_secs(30)))
         .layer(
@@ -441,7 +726,10 @@ pub fn create_router(state: AppState) -> Router {
                     header::CONTENT_TYPE,
                     header::ACCEPT,
                     "X-API-Key".parse().unwrap(),
+                    "X-CSRF-Token".parse().unwrap(),
+                    "X-Request-Id".parse().unwrap(),
                 ])
+                .expose_headers(["X-Request-Id".parse::<header::HeaderName>().unwrap()])
                 .max_age(Duration::from_secs(3600)),
         )
         .with_state(state)
@@ -462,29 +750,210 @@ async fn main() -> anyhow::Result<()> {
     dotenvy::dotenv().ok();
     let config = Config::from_env()?;
 
+    // Install provider-specific email canonicalization rules
+    utils::email::init_canonical_rules(utils::email::EmailCanonicalRules::from_config(&config));
+
+    // Install the key-encryption keys for sensitive columns
+    let field_cipher = utils::field_crypto::FieldCipher::from_spec(&config.field_encryption_keys)?;
+    match &field_cipher {
+        Some(cipher) => tracing::info!("Field encryption enabled (active key: {})", cipher.active_key_id()),
+        None if config.mfa_require_encrypted_secrets => {
+            anyhow::bail!("MFA_REQUIRE_ENCRYPTED_SECRETS is set but FIELD_ENCRYPTION_KEYS is empty")
+        }
+        None => tracing::warn!("FIELD_ENCRYPTION_KEYS is not set; sensitive columns are stored as plaintext"),
+    }
+    utils::field_crypto::init_field_cipher(field_cipher);
+
+    // Load the GeoIP database that locates sign-ins for impossible travel detection
+    if !config.geoip_database.is_empty() {
+        let geoip = utils::geoip::GeoIpDatabase::load(&config.geoip_database)?;
+        if geoip.is_empty() {
+            tracing::warn!("GEOIP_DATABASE has no located networks; impossible travel is not detected");
+        } else {
+            tracing::info!("GeoIP database loaded ({} networks)", geoip.len());
+        }
+        utils::geoip::init_geoip(geoip);
+    }
+
     // Create database pool with production settings
+    let min_connections = config.db_min_connections.min(config.db_max_connections);
     let pool = MySqlPoolOptions::new()
-        .max_connections(50)
-        .min_connections(5)
+        .max_connections(config.db_max_connections)
+        .min_connections(min_connections)
         .acquire_timeout(Duration::from_secs(10))
         .idle_timeout(Duration::from_secs(600))
         .max_lifetime(Duration::from_secs(1800))
+        // Injected DB latency in `chaos` builds; returns at once otherwise
+        .before_acquire(|_conn, _meta| {
+            Box::pin(async move {
+                utils::chaos::delay_db().await;
+                Ok(true)
+            })
+        })
         .connect(&config.database_url)
         .await?;
 
     // Run migrations
     sqlx::migrate!("./migrations").run(&pool).await?;
 
+    // Maintenance commands (`auth-server <command>`) run instead of the server
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some((command, command_args)) = args.split_first() {
+        return run_command(command, command_args, &pool, &config).await;
+    }
+
+    // Optionally open the minimum connections and prime hot statements before
+    // accepting traffic, so the first requests don't pay for them
+    if config.db_warmup_enabled {
+        let report = repositories::warmup::warm_up_pool(
+            &pool,
+            min_connections,
+            Duration::from_secs(config.db_warmup_timeout_secs),
+        )
+        .await?;
+        tracing::info!(
+            "Database pool warmed up ({} connections, {} statements primed, {} attempt(s))",
+            report.connections,
+            report.statements,
+            report.attempts
+        );
+    }
+
     // Get socket address before moving config
     let addr = config.socket_addr();
 
     // Create app state
     let state = AppState::new(pool.clone(), config.clone());
 
+    // Load the token signing keys; the configured key signs until a key
+    // ceremony promotes another one
+    let signing_keys = services::SigningKeyService::new(
+        pool.clone(),
+        state.jwt_manager.clone(),
+        services::SigningKeyPolicy::from_config(&config),
+    );
+    if let Err(e) = signing_keys.bootstrap().await {
+        tracing::error!("Failed to load signing keys, signing with the configured key: {:?}", e);
+    }
+    tracing::info!("Signing tokens with key {}", state.jwt_manager.signing_kid());
+
     // Spawn background workers
     let webhook_interval = config.webhook_worker_interval_secs;
-    let webhook_worker_handle = workers::webhook_worker::spawn_webhook_worker(pool.clone(), webhook_interval);
-    tracing::info!("Background workers started (webhook interval: {}s)", webhook_interval);
+    let webhook_worker_handle = workers::webhook_worker::spawn_webhook_worker(
+        pool.clone(),
+        webhook_interval,
+        config.instance_id.clone(),
+        services::WebhookDispatchLimits::from_config(&config),
+        config.webhook_event_retention_days,
+    );
+    let duplicate_interval = config.duplicate_scan_interval_secs;
+    let duplicate_worker_handle = workers::duplicate_account_worker::spawn_duplicate_account_worker(
+        pool.clone(),
+        duplicate_interval,
+        config.duplicate_scan_batch_size,
+        config.instance_id.clone(),
+    );
+    let role_expiry_interval = config.role_expiry_worker_interval_secs;
+    let role_expiry_worker_handle = workers::role_expiry_worker::spawn_role_expiry_worker(
+        pool.clone(),
+        role_expiry_interval,
+        config.role_expiry_notice_secs,
+        config.instance_id.clone(),
+    );
+    let provisioning_interval = config.provisioning_worker_interval_secs;
+    // Inbound provisioning over NATS (PROVISIONING_NATS_URL empty = HTTP only)
+    let provisioning_nats_enabled = !config.provisioning_nats_url.is_empty();
+    let provisioning_worker_handle = workers::provisioning_worker::spawn_provisioning_worker(
+        pool.clone(),
+        provisioning_interval,
+        config.instance_id.clone(),
+        provisioning_nats_enabled.then(|| {
+            utils::nats::NatsTopic::new(
+                config.provisioning_nats_url.clone(),
+                config.provisioning_nats_dead_letter_subject.clone(),
+            )
+        }),
+    );
+    let provisioning_consumer_handle = provisioning_nats_enabled.then(|| {
+        workers::provisioning_consumer::spawn_provisioning_consumer(
+            pool.clone(),
+            config.provisioning_nats_url.clone(),
+            config.provisioning_nats_subject.clone(),
+            config.provisioning_nats_dead_letter_subject.clone(),
+        )
+    });
+    let reencrypt_interval = config.field_reencrypt_interval_secs;
+    let field_encryption_worker_handle = workers::field_encryption_worker::spawn_field_encryption_worker(
+        pool.clone(),
+        reencrypt_interval,
+    );
+    let abuse_interval = config.abuse_telemetry_interval_secs;
+    let abuse_telemetry_worker_handle = workers::abuse_telemetry_worker::spawn_abuse_telemetry_worker(
+        pool.clone(),
+        abuse_interval,
+        config.abuse_window_secs,
+        config.abuse_error_threshold,
+        config.abuse_rate_limited_threshold,
+        config.instance_id.clone(),
+    );
+    let secret_expiry_interval = config.client_secret_expiry_interval_secs;
+    let client_secret_expiry_worker_handle = workers::client_secret_expiry_worker::spawn_client_secret_expiry_worker(
+        pool.clone(),
+        secret_expiry_interval,
+        config.client_secret_max_age_days,
+        config.client_secret_expiry_warning_days,
+        config.instance_id.clone(),
+    );
+    let broadcast_interval = config.broadcast_worker_interval_secs;
+    let broadcast_worker_handle = workers::broadcast_worker::spawn_broadcast_worker(
+        pool.clone(),
+        broadcast_interval,
+        config.broadcast_max_per_minute,
+        config.instance_id.clone(),
+    );
+    let digest_interval = config.digest_worker_interval_secs;
+    let app_digest_worker_handle = workers::app_digest_worker::spawn_app_digest_worker(
+        pool.clone(),
+        digest_interval,
+        config.instance_id.clone(),
+    );
+    let signing_key_interval = config.signing_key_refresh_interval_secs;
+    let signing_key_worker_handle = workers::signing_key_worker::spawn_signing_key_worker(
+        signing_keys,
+        signing_key_interval,
+    );
+    // Dormant account policy (DORMANT_ACCOUNT_DAYS = 0 disables it)
+    let dormancy_policy = utils::dormancy::DormancyPolicy::from_config(&config);
+    let dormant_account_worker_handle = dormancy_policy.is_enabled().then(|| {
+        workers::dormant_account_worker::spawn_dormant_account_worker(
+            pool.clone(),
+            config.dormant_worker_interval_secs,
+            dormancy_policy,
+            config.instance_id.clone(),
+        )
+    });
+    // Opt-in usage heartbeat
+    let heartbeat_worker_handle = (!config.heartbeat_url.is_empty()).then(|| {
+        workers::heartbeat_worker::spawn_heartbeat_worker(
+            services::InstanceService::new(pool.clone(), state.config.clone()),
+            config.heartbeat_url.clone(),
+            config.heartbeat_interval_secs,
+        )
+    });
+    tracing::info!(
+        "Background workers started on instance {} (webhook interval: {}s, duplicate scan interval: {}s, role expiry interval: {}s, provisioning interval: {}s, re-encryption interval: {}s, abuse telemetry interval: {}s, client secret expiry interval: {}s, broadcast interval: {}s, app digest interval: {}s, signing key refresh interval: {}s)",
+        config.instance_id,
+        webhook_interval,
+        duplicate_interval,
+        role_expiry_interval,
+        provisioning_interval,
+        reencrypt_interval,
+        abuse_interval,
+        secret_expiry_interval,
+        broadcast_interval,
+        digest_interval,
+        signing_key_interval
+    );
 
     // Build router
     let app = create_router(state);
@@ -494,18 +963,164 @@ async fn main() -> anyhow::Result<()> {
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
     
-    axum::serve(listener, app)
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
         .with_graceful_shutdown(shutdown_signal())
         .await?;
 
     // Abort background workers on shutdown
     webhook_worker_handle.abort();
+    duplicate_worker_handle.abort();
+    role_expiry_worker_handle.abort();
+    provisioning_worker_handle.abort();
+    field_encryption_worker_handle.abort();
+    abuse_telemetry_worker_handle.abort();
+    client_secret_expiry_worker_handle.abort();
+    broadcast_worker_handle.abort();
+    app_digest_worker_handle.abort();
+    signing_key_worker_handle.abort();
+    if let Some(handle) = dormant_account_worker_handle {
+        handle.abort();
+    }
+    if let Some(handle) = provisioning_consumer_handle {
+        handle.abort();
+    }
+    if let Some(handle) = heartbeat_worker_handle {
+        handle.abort();
+    }
     tracing::info!("Background workers stopped");
 
     tracing::info!("Server shutdown complete");
     Ok(())
 }
 
+/// Run a one-off maintenance command against the database and exit
+async fn run_command(
+    command: &str,
+    args: &[String],
+    pool: &sqlx::MySqlPool,
+    config: &Config,
+) -> anyhow::Result<()> {
+    match command {
+        // Re-encrypt every TOTP secret under the active key (plaintext or retired-key values)
+        "reencrypt-mfa-secrets" => {
+            let cipher = utils::field_crypto::field_cipher()
+                .ok_or_else(|| anyhow::anyhow!("FIELD_ENCRYPTION_KEYS must be set to re-encrypt MFA secrets"))?;
+
+            let report = repositories::FieldEncryptionRepository::new(pool.clone())
+                .rotate_column(cipher, utils::field_crypto::EncryptedColumn::MfaSecret, 500)
+                .await?;
+            tracing::info!(
+                "Re-encrypted {} of {} MFA secrets under key {}",
+                report.rewritten,
+                report.scanned,
+                cipher.active_key_id()
+            );
+
+            if report.failed > 0 {
+                anyhow::bail!("{} MFA secrets could not be decrypted with the configured keys", report.failed);
+            }
+            Ok(())
+        }
+        // Key ceremony: running servers pick changes up within SIGNING_KEY_REFRESH_INTERVAL_SECS
+        "list-signing-keys" | "import-signing-key" | "stage-signing-key" | "promote-signing-key"
+        | "rotate-signing-key" | "export-public-keys" => run_signing_key_command(command, args, pool, config).await,
+        other => anyhow::bail!(
+            "Unknown command '{}' (available: reencrypt-mfa-secrets, list-signing-keys, import-signing-key, \
+             stage-signing-key, promote-signing-key, rotate-signing-key, export-public-keys)",
+            other
+        ),
+    }
+}
+
+/// Signing key ceremony commands
+async fn run_signing_key_command(
+    command: &str,
+    args: &[String],
+    pool: &sqlx::MySqlPool,
+    config: &Config,
+) -> anyhow::Result<()> {
+    let jwt_manager = utils::jwt::JwtManager::new(
+        &config.jwt_private_key,
+        &config.jwt_public_key,
+        config.access_token_expiry_secs,
+        config.refresh_token_expiry_secs,
+    )?;
+    let service = services::SigningKeyService::new(
+        pool.clone(),
+        jwt_manager.clone(),
+        services::SigningKeyPolicy::from_config(config),
+    );
+    service.bootstrap().await.map_err(|e| anyhow::anyhow!("{:?}", e))?;
+
+    let arg = |i: usize, usage: &str| {
+        args.get(i)
+            .map(String::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Usage: auth-server {} {}", command, usage))
+    };
+
+    match command {
+        "list-signing-keys" => {
+            for info in service.list().await.map_err(|e| anyhow::anyhow!("{:?}", e))? {
+                println!(
+                    "{}  {:<8}  {:<8}  sha256:{}{}",
+                    info.key.kid,
+                    info.key.status,
+                    info.key.source,
+                    info.key.fingerprint,
+                    info.promotable_at
+                        .map(|at| format!("  promotable at {}", at.to_rfc3339()))
+                        .unwrap_or_default()
+                );
+            }
+        }
+        "import-signing-key" => {
+            let usage = "<private_key.pem> <public_key.pem>";
+            let private_key_pem = std::fs::read_to_string(arg(0, usage)?)?;
+            let public_key_pem = std::fs::read_to_string(arg(1, usage)?)?;
+
+            let key = service
+                .import(&private_key_pem, &public_key_pem, None)
+                .await
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            println!("Imported {} (sha256:{})", key.kid, key.fingerprint);
+        }
+        "stage-signing-key" => {
+            let key = service
+                .stage(arg(0, "<kid>")?)
+                .await
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            println!("Staged {} as next; it is now published in the JWKS", key.kid);
+        }
+        "promote-signing-key" => {
+            let force = args.iter().any(|a| a == "--force");
+            let key = service
+                .promote(arg(0, "<kid> [--force]")?, force)
+                .await
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            println!("Promoted {}; running servers sign with it after their next key refresh", key.kid);
+        }
+        "rotate-signing-key" => {
+            let force = args.iter().any(|a| a == "--force");
+            let key = service
+                .rotate(None, force)
+                .await
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            if force {
+                println!("Generated and promoted {} (sha256:{})", key.kid, key.fingerprint);
+            } else {
+                println!(
+                    "Generated {} (sha256:{}) and staged it as next; it is promoted automatically after the staging delay",
+                    key.kid, key.fingerprint
+                );
+            }
+        }
+        // JWKS of the published keys, e.g. for relying parties that pin keys
+        _ => println!("{}", serde_json::to_string_pretty(&jwt_manager.jwks())?),
+    }
+
+    Ok(())
+}
+
 /// Graceful shutdown signal handler
 async fn shutdown_signal() {
     let ctrl_c = async {
@@ -0,0 +1,94 @@
+use axum::{
+    body::Body,
+    extract::MatchedPath,
+    http::Request,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::utils::abuse_telemetry::{is_abuse_status, record_response, UNMATCHED_ROUTE};
+use crate::utils::token_binding::client_ip;
+
+/// Abuse Telemetry Middleware
+///
+/// Counts 4xx (including 429) responses per caller IP and route template
+/// (`/users/:user_id`, not the concrete path, so probing IDs lands in one
+/// bucket). Requests that matched no route are counted under
+/// `<unmatched>`. The counters are flushed and evaluated by the abuse
+/// telemetry worker; requests without a known caller IP are not counted.
+///
+/// # Usage
+/// ```rust,ignore
+/// let app = Router::new()
+///     .route("/auth/login", post(login_handler))
+///     .layer(middleware::from_fn(abuse_telemetry_middleware));
+/// ```
+pub async fn abuse_telemetry_middleware(request: Request<Body>, next: Next) -> Response {
+    let ip = client_ip(request.headers());
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string());
+
+    let response = next.run(request).await;
+
+    if let Some(ip) = ip.filter(|ip| !ip.is_empty()) {
+        if is_abuse_status(response.status()) {
+            record_response(&ip, route.as_deref().unwrap_or(UNMATCHED_ROUTE), response.status());
+        }
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::abuse_telemetry::take_abuse_counts_for;
+    use axum::{http::StatusCode, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let nested = Router::new().route("/:user_id", get(|| async { StatusCode::NOT_FOUND }));
+
+        Router::new()
+            .route("/ok", get(|| async { StatusCode::OK }))
+            .route("/limited", get(|| async { StatusCode::TOO_MANY_REQUESTS }))
+            .nest("/users", nested)
+            .layer(middleware::from_fn(abuse_telemetry_middleware))
+    }
+
+    async fn call(uri: &str, ip: Option<&str>) {
+        let mut request = Request::builder().uri(uri);
+        if let Some(ip) = ip {
+            request = request.header(crate::utils::token_binding::CLIENT_IP_HEADER, ip);
+        }
+        app().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_counts_rejections_by_route_template() {
+        let ip = "203.0.113.201";
+        call("/ok", Some(ip)).await;
+        call("/limited", Some(ip)).await;
+        call("/users/1", Some(ip)).await;
+        call("/users/2", Some(ip)).await;
+        call("/nope", Some(ip)).await;
+        call("/limited", None).await;
+
+        let mut routes: Vec<_> = take_abuse_counts_for(ip)
+            .into_iter()
+            .map(|(route, c)| (route, c.client_errors, c.rate_limited))
+            .collect();
+        routes.sort();
+
+        assert_eq!(
+            routes,
+            vec![
+                ("/limited".to_string(), 0, 1),
+                ("/users/:user_id".to_string(), 2, 0),
+                (UNMATCHED_ROUTE.to_string(), 1, 0),
+            ]
+        );
+    }
+}
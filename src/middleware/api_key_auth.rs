@@ -1,15 +1,17 @@
 use axum::{
     body::Body,
     extract::{FromRequestParts, State},
-    http::{request::Parts, Request},
+    http::{request::Parts, HeaderMap, Request},
     middleware::Next,
     response::Response,
 };
+use chrono::Utc;
 use uuid::Uuid;
 
 use crate::config::AppState;
 use crate::error::AppError;
 use crate::services::{ApiKeyService, IpRuleService, IpAccessResult};
+use crate::utils::access_window;
 
 /// Header name for API Key authentication
 pub const API_KEY_HEADER: &str = "X-API-Key";
@@ -48,7 +50,26 @@ pub async fn api_key_auth_middleware(
         }
     };
 
-    // 2. Verify API key
+    // 2. Verify the key and the caller's IP
+    let context = authenticate_api_key(&state, key, request.headers()).await?;
+
+    // 3. Inject context into request extensions
+    request.extensions_mut().insert(context);
+
+    // 4. Call next handler
+    Ok(next.run(request).await)
+}
+
+/// Verify an API key and the caller's IP against the key's app rules
+///
+/// Shared by `api_key_auth_middleware` and the combined machine authenticator
+/// on `/app-api` routes.
+pub async fn authenticate_api_key(
+    state: &AppState,
+    key: &str,
+    headers: &HeaderMap,
+) -> Result<ApiKeyContext, AppError> {
+    // 1. Verify API key
     let service = ApiKeyService::new(state.pool.clone());
     let api_key = service.verify_api_key(key).await?
         .ok_or_else(|| {
@@ -56,20 +77,26 @@ pub async fn api_key_auth_middleware(
             AppError::Auth(crate::error::AuthError::InvalidToken)
         })?;
 
-    // 3. Check if key is active
+    // 2. Check if key is active
     if !api_key.is_active {
         tracing::warn!("Revoked API key attempted: {}", api_key.key_prefix);
         return Err(AppError::Auth(crate::error::AuthError::InvalidToken));
     }
 
-    // 4. Check if key is expired
+    // 3. Check if key is expired
     if api_key.is_expired() {
         tracing::warn!("Expired API key attempted: {}", api_key.key_prefix);
         return Err(AppError::Auth(crate::error::AuthError::TokenExpired));
     }
 
+    // 4. Check the key's access window
+    if !access_window::admits(api_key.access_window.as_deref(), Utc::now()) {
+        tracing::warn!("API key {} used outside its access window", api_key.key_prefix);
+        return Err(AppError::Auth(crate::error::AuthError::OutsideAccessWindow));
+    }
+
     // 5. Check IP rules for this app
-    let client_ip = extract_client_ip(&request);
+    let client_ip = extract_client_ip(headers);
     if let Some(ref ip) = client_ip {
         let ip_service = IpRuleService::new(state.pool.clone());
         let ip_result = ip_service.check_ip_access(ip, Some(api_key.app_id)).await
@@ -77,9 +104,7 @@ pub async fn api_key_auth_middleware(
         
         if ip_result == IpAccessResult::Blocked {
             tracing::warn!("IP {} blocked for app {} via API key", ip, api_key.app_id);
-            return Err(AppError::Auth(crate::error::AuthError::UserBanned {
-                reason: Some(format!("IP address {} is blocked for this app", ip)),
-            }));
+            return Err(AppError::Auth(crate::error::AuthError::IpBlocked));
         }
     }
 
@@ -91,29 +116,25 @@ pub async fn api_key_auth_middleware(
         let _ = repo.update_last_used(key_id).await;
     });
 
-    // 7. Create context and inject into request extensions
-    let context = ApiKeyContext {
+    // 7. Create context
+    Ok(ApiKeyContext {
         api_key_id: api_key.id,
         app_id: api_key.app_id,
         scopes: api_key.scopes.0.clone(),
-    };
-    request.extensions_mut().insert(context);
-
-    // 8. Call next handler
-    Ok(next.run(request).await)
+    })
 }
 
 /// Extract client IP from request headers
-fn extract_client_ip(request: &Request<Body>) -> Option<String> {
+fn extract_client_ip(headers: &HeaderMap) -> Option<String> {
     // Check X-Forwarded-For first (for proxied requests)
-    if let Some(forwarded) = request.headers().get("x-forwarded-for") {
+    if let Some(forwarded) = headers.get("x-forwarded-for") {
         if let Ok(value) = forwarded.to_str() {
             return Some(value.split(',').next()?.trim().to_string());
         }
     }
 
     // Check X-Real-IP
-    if let Some(real_ip) = request.headers().get("x-real-ip") {
+    if let Some(real_ip) = headers.get("x-real-ip") {
         if let Ok(value) = real_ip.to_str() {
             return Some(value.to_string());
         }
@@ -0,0 +1,43 @@
+use std::net::SocketAddr;
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::Request,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::config::AppState;
+use crate::utils::token_binding::{resolve_client_ip, set_client_ip, strip_client_cert};
+
+/// Client IP Middleware
+///
+/// Derives the caller IP from the peer address and `TRUSTED_PROXY_HOPS`,
+/// then replaces the request's `X-Forwarded-For` and `X-Real-IP` with it.
+/// Everything behind this layer (IP rules, token binding, rate limits,
+/// audit rows) reads `X-Real-IP`, so a client can't pick its own address
+/// by sending forwarding headers. Without trusted proxies it also drops
+/// `X-Client-Cert-SHA256`, which only a proxy may set. Layer it outermost.
+///
+/// # Usage
+/// ```rust,ignore
+/// let app = Router::new()
+///     .route("/apps/auth", post(app_auth_handler))
+///     .layer(middleware::from_fn_with_state(state.clone(), client_ip_middleware));
+/// ```
+pub async fn client_ip_middleware(
+    State(state): State<AppState>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_canonical());
+    let ip = resolve_client_ip(request.headers(), peer, state.config.trusted_proxy_hops);
+    set_client_ip(request.headers_mut(), ip);
+    strip_client_cert(request.headers_mut(), state.config.trusted_proxy_hops);
+
+    next.run(request).await
+}
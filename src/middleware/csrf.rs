@@ -0,0 +1,128 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header::AUTHORIZATION, HeaderMap, Method, Request},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::config::AppState;
+use crate::error::AuthError;
+use crate::middleware::API_KEY_HEADER;
+use crate::utils::cookie::{verify_csrf, RefreshCookieSettings};
+
+/// CSRF Protection Middleware
+///
+/// Browsers attach cookies to cross-site requests automatically, so any
+/// state-changing request that carries an auth cookie must also pass the
+/// double-submit check: the `X-CSRF-Token` header has to match the
+/// `csrf_token` cookie (issued by `GET /auth/csrf` and on cookie refreshes).
+///
+/// Requests authenticated with an `Authorization` header or API key are
+/// exempt - a cross-site page cannot attach those headers without a CORS
+/// preflight, and pure Bearer-token APIs never rely on cookies.
+///
+/// # Usage
+/// ```rust,ignore
+/// let app = Router::new()
+///     .route("/auth/refresh", post(refresh_handler))
+///     .layer(middleware::from_fn_with_state(state.clone(), csrf_middleware));
+/// ```
+pub async fn csrf_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response, AuthError> {
+    let cookie_settings = RefreshCookieSettings::from_config(&state.config);
+
+    // The SSO cookie authenticates `POST /auth/sso/continue`, so it counts too
+    let cookie_authenticated = [cookie_settings.name.as_str(), state.config.sso_cookie_name.as_str()]
+        .iter()
+        .any(|name| requires_csrf_check(request.method(), request.headers(), name));
+
+    if cookie_authenticated && !verify_csrf(request.headers()) {
+        tracing::warn!("CSRF check failed for path: {}", request.uri().path());
+        return Err(AuthError::CsrfTokenInvalid);
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Whether a request is cookie-authenticated and state-changing
+fn requires_csrf_check(method: &Method, headers: &HeaderMap, cookie_name: &str) -> bool {
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return false;
+    }
+
+    if headers.contains_key(AUTHORIZATION) || headers.contains_key(API_KEY_HEADER) {
+        return false;
+    }
+
+    has_auth_cookie(headers, cookie_name)
+}
+
+/// Whether the request carries a refresh cookie (first-party or per OAuth client)
+fn has_auth_cookie(headers: &HeaderMap, cookie_name: &str) -> bool {
+    let client_prefix = format!("{}_", cookie_name);
+
+    headers
+        .get_all(axum::http::header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .any(|(key, value)| !value.is_empty() && (key == cookie_name || key.starts_with(&client_prefix)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{header::COOKIE, HeaderValue};
+
+    fn cookie_headers(cookie: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(COOKIE, HeaderValue::from_static(cookie));
+        headers
+    }
+
+    #[test]
+    fn test_safe_methods_are_exempt() {
+        let headers = cookie_headers("refresh_token=abc");
+
+        assert!(!requires_csrf_check(&Method::GET, &headers, "refresh_token"));
+        assert!(!requires_csrf_check(&Method::OPTIONS, &headers, "refresh_token"));
+    }
+
+    #[test]
+    fn test_cookie_authenticated_post_requires_check() {
+        let headers = cookie_headers("refresh_token=abc");
+        assert!(requires_csrf_check(&Method::POST, &headers, "refresh_token"));
+
+        let headers = cookie_headers("refresh_token_client-123=abc");
+        assert!(requires_csrf_check(&Method::DELETE, &headers, "refresh_token"));
+    }
+
+    #[test]
+    fn test_sso_cookie_requires_check() {
+        let headers = cookie_headers("sso_session=abc");
+
+        assert!(requires_csrf_check(&Method::POST, &headers, "sso_session"));
+        assert!(!requires_csrf_check(&Method::POST, &headers, "refresh_token"));
+    }
+
+    #[test]
+    fn test_bearer_requests_are_exempt() {
+        let mut headers = cookie_headers("refresh_token=abc");
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer token"));
+
+        assert!(!requires_csrf_check(&Method::POST, &headers, "refresh_token"));
+    }
+
+    #[test]
+    fn test_requests_without_auth_cookie_are_exempt() {
+        let headers = cookie_headers("theme=dark; csrf_token=abc");
+
+        assert!(!requires_csrf_check(&Method::POST, &headers, "refresh_token"));
+        assert!(!requires_csrf_check(&Method::POST, &HeaderMap::new(), "refresh_token"));
+    }
+}
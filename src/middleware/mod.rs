@@ -1,9 +1,17 @@
+pub mod abuse_telemetry;
 pub mod app_auth;
+pub mod client_ip;
+pub mod csrf;
 pub mod jwt_auth;
 pub mod oauth_auth;
+pub mod request_id;
 pub mod api_key_auth;
 
-pub use app_auth::{app_auth_middleware, AppContext};
-pub use jwt_auth::{jwt_auth_middleware, AccessToken};
+pub use abuse_telemetry::abuse_telemetry_middleware;
+pub use app_auth::{app_auth_middleware, MachineContext};
+pub use client_ip::client_ip_middleware;
+pub use csrf::csrf_middleware;
+pub use jwt_auth::{acr_guard, jwt_auth_middleware, AccessToken};
 pub use oauth_auth::{oauth_auth_middleware, scope_guard, OAuth2Context, ScopeError};
 pub use api_key_auth::{api_key_auth_middleware, ApiKeyContext, require_scope, require_any_scope, API_KEY_HEADER};
+pub use request_id::request_id_middleware;
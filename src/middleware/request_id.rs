@@ -0,0 +1,83 @@
+use axum::{
+    body::Body,
+    http::{HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+
+use crate::utils::request_id::{request_id_or_new, with_request_id, REQUEST_ID_HEADER};
+
+/// Request Id Middleware
+///
+/// Takes the request id from `X-Request-Id` (or generates one), makes it the
+/// current request id for everything the request runs, adds it to the log
+/// span, and echoes it back in the response's `X-Request-Id`.
+///
+/// # Usage
+/// ```rust,ignore
+/// let app = Router::new()
+///     .route("/auth/login", post(login_handler))
+///     .layer(middleware::from_fn(request_id_middleware));
+/// ```
+pub async fn request_id_middleware(request: Request<Body>, next: Next) -> Response {
+    let request_id = request_id_or_new(
+        request
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok()),
+    );
+
+    let span = tracing::info_span!("request", request_id = %request_id);
+    let mut response = with_request_id(request_id.clone(), next.run(request))
+        .instrument(span)
+        .await;
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::request_id::current_request_id;
+    use axum::{middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/", get(|| async { current_request_id().unwrap_or_default() }))
+            .layer(middleware::from_fn(request_id_middleware))
+    }
+
+    async fn call(request_id: Option<&str>) -> (String, String) {
+        let mut request = Request::builder().uri("/");
+        if let Some(request_id) = request_id {
+            request = request.header(REQUEST_ID_HEADER, request_id);
+        }
+        let response = app().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+
+        let header = response.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (header, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_uses_supplied_request_id() {
+        let (header, seen) = call(Some("client-trace-1")).await;
+
+        assert_eq!(header, "client-trace-1");
+        assert_eq!(seen, "client-trace-1");
+    }
+
+    #[tokio::test]
+    async fn test_generates_request_id() {
+        let (header, seen) = call(None).await;
+
+        assert!(uuid::Uuid::parse_str(&header).is_ok());
+        assert_eq!(seen, header);
+    }
+}
@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Delete or anonymize a user (`params.mode` as for `DELETE /admin/users/:id`)
+pub const ADMIN_ACTION_DELETE_USER: &str = "delete_user";
+
+/// Make a user a system admin
+pub const ADMIN_ACTION_GRANT_SYSTEM_ADMIN: &str = "grant_system_admin";
+
+/// Delete an app permanently
+pub const ADMIN_ACTION_DELETE_APP: &str = "delete_app";
+
+/// Actions that can be configured to need a second admin's approval
+pub const ADMIN_APPROVAL_ACTIONS: &[&str] = &[
+    ADMIN_ACTION_DELETE_USER,
+    ADMIN_ACTION_GRANT_SYSTEM_ADMIN,
+    ADMIN_ACTION_DELETE_APP,
+];
+
+/// Awaiting a second admin
+pub const APPROVAL_STATUS_PENDING: &str = "pending";
+
+/// Approved and executed
+pub const APPROVAL_STATUS_APPROVED: &str = "approved";
+
+/// Refused by an admin; the action was not executed
+pub const APPROVAL_STATUS_REJECTED: &str = "rejected";
+
+/// Approved, but executing the action failed (see `error`)
+pub const APPROVAL_STATUS_FAILED: &str = "failed";
+
+/// Pending past `expires_at`; reported only, never stored
+pub const APPROVAL_STATUS_EXPIRED: &str = "expired";
+
+/// Sensitive admin action held for a second admin's approval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminApproval {
+    pub id: Uuid,
+    pub action: String,
+    pub target_id: Uuid,
+    pub params: Option<serde_json::Value>,
+    /// `pending`, `approved`, `rejected`, `failed` or `expired`
+    pub status: String,
+    pub requested_by: Uuid,
+    pub decided_by: Option<Uuid>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct AdminApprovalRow {
+    pub id: String,
+    pub action: String,
+    pub target_id: String,
+    pub params: Option<sqlx::types::Json<serde_json::Value>>,
+    pub status: String,
+    pub requested_by: String,
+    pub decided_by: Option<String>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AdminApprovalRow> for AdminApproval {
+    fn from(row: AdminApprovalRow) -> Self {
+        let expired = row.status == APPROVAL_STATUS_PENDING && row.expires_at <= Utc::now();
+        Self {
+            id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            action: row.action,
+            target_id: Uuid::parse_str(&row.target_id).unwrap_or_default(),
+            params: row.params.map(|p| p.0),
+            status: if expired { APPROVAL_STATUS_EXPIRED.to_string() } else { row.status },
+            requested_by: Uuid::parse_str(&row.requested_by).unwrap_or_default(),
+            decided_by: row.decided_by.and_then(|id| Uuid::parse_str(&id).ok()),
+            decided_at: row.decided_at,
+            error: row.error,
+            expires_at: row.expires_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for AdminApproval {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let approval_row = AdminApprovalRow::from_row(row)?;
+        Ok(AdminApproval::from(approval_row))
+    }
+}
+
+impl AdminApproval {
+    /// Whether the request can still be approved or rejected
+    pub fn is_pending(&self) -> bool {
+        self.status == APPROVAL_STATUS_PENDING
+    }
+
+    /// String parameter of the action, e.g. `mode` of a user deletion
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.as_ref()?.get(name)?.as_str()
+    }
+}
@@ -10,10 +10,13 @@ pub struct ApiKey {
     #[sqlx(try_from = "String")]
     pub app_id: Uuid,
     pub name: String,
+    #[serde(skip_serializing)]
     pub key_hash: String,
     pub key_prefix: String,
     pub scopes: sqlx::types::Json<Vec<String>>,
     pub expires_at: Option<DateTime<Utc>>,
+    /// Weekly schedule the key is usable in (None = any time)
+    pub access_window: Option<String>,
     pub last_used_at: Option<DateTime<Utc>>,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
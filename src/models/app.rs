@@ -2,6 +2,21 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+/// App tokens are not bound to the caller
+pub const TOKEN_BINDING_NONE: &str = "none";
+
+/// App tokens are bound to the caller's IP range
+pub const TOKEN_BINDING_IP: &str = "ip";
+
+/// App tokens are bound to the caller's client certificate thumbprint
+pub const TOKEN_BINDING_MTLS: &str = "mtls";
+
+/// Deleting a member of the app removes the account
+pub const DELETION_POLICY_DELETE: &str = "delete";
+
+/// Deleting a member of the app scrubs its PII and keeps the row
+pub const DELETION_POLICY_ANONYMIZE: &str = "anonymize";
+
 /// App domain model - represents a client application
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct App {
@@ -11,6 +26,24 @@ pub struct App {
     pub owner_id: Option<Uuid>,
     #[serde(skip_serializing)]
     pub secret_hash: Option<String>,
+    /// Session idle timeout override in seconds (None = server default)
+    pub session_idle_timeout_secs: Option<i64>,
+    /// Session absolute lifetime override in seconds (None = server default)
+    pub session_absolute_lifetime_secs: Option<i64>,
+    /// How app tokens are bound to the caller: `none`, `ip` or `mtls`
+    pub token_binding: String,
+    /// IPv4 prefix length for `ip` binding (None = exact address)
+    pub token_binding_ip_prefix: Option<i64>,
+    /// What deleting a member does: `delete` or `anonymize`
+    pub deletion_policy: String,
+    /// Minimum acr of sign-ins issuing tokens for the app (None = any)
+    pub required_acr: Option<String>,
+    /// Whether a browser's SSO session may sign users in to the app
+    pub sso_enabled: bool,
+    /// Whether the owner is emailed a weekly digest of the app's activity
+    pub digest_enabled: bool,
+    /// Whether users may sign in with a code emailed to them instead of a password
+    pub email_login_enabled: bool,
 }
 
 /// Row type for MySQL query results
@@ -21,6 +54,15 @@ pub struct AppRow {
     pub name: String,
     pub owner_id: Option<String>,
     pub secret_hash: Option<String>,
+    pub session_idle_timeout_secs: Option<i32>,
+    pub session_absolute_lifetime_secs: Option<i32>,
+    pub token_binding: String,
+    pub token_binding_ip_prefix: Option<i32>,
+    pub deletion_policy: String,
+    pub required_acr: Option<String>,
+    pub sso_enabled: bool,
+    pub digest_enabled: bool,
+    pub email_login_enabled: bool,
 }
 
 impl From<AppRow> for App {
@@ -31,6 +73,15 @@ impl From<AppRow> for App {
             name: row.name,
             owner_id: row.owner_id.and_then(|id| Uuid::parse_str(&id).ok()),
             secret_hash: row.secret_hash,
+            session_idle_timeout_secs: row.session_idle_timeout_secs.map(i64::from),
+            session_absolute_lifetime_secs: row.session_absolute_lifetime_secs.map(i64::from),
+            token_binding: row.token_binding,
+            token_binding_ip_prefix: row.token_binding_ip_prefix.map(i64::from),
+            deletion_policy: row.deletion_policy,
+            required_acr: row.required_acr,
+            sso_enabled: row.sso_enabled,
+            digest_enabled: row.digest_enabled,
+            email_login_enabled: row.email_login_enabled,
         }
     }
 }
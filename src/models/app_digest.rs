@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Digest waiting in the queue
+pub const DIGEST_STATUS_PENDING: &str = "pending";
+
+/// Email handed to the mailer
+pub const DIGEST_STATUS_SENT: &str = "sent";
+
+/// Mailer rejected the email
+pub const DIGEST_STATUS_FAILED: &str = "failed";
+
+/// Owner is on the suppression list, or their address bounced
+pub const DIGEST_STATUS_SUPPRESSED: &str = "suppressed";
+
+/// API key of the app that expires soon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpiringApiKey {
+    pub name: String,
+    pub key_prefix: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct ExpiringApiKeyRow {
+    pub name: String,
+    pub key_prefix: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<ExpiringApiKeyRow> for ExpiringApiKey {
+    fn from(row: ExpiringApiKeyRow) -> Self {
+        Self {
+            name: row.name,
+            key_prefix: row.key_prefix,
+            expires_at: row.expires_at,
+        }
+    }
+}
+
+/// Figures of one week of an app, as emailed to its owner
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppDigestStats {
+    /// Users who joined the app
+    pub new_users: i64,
+    /// Users banned from the app
+    pub bans: i64,
+    /// Webhook deliveries that gave up after their last retry
+    pub failed_webhook_deliveries: i64,
+    /// Active API keys expiring within the warning window
+    pub expiring_api_keys: Vec<ExpiringApiKey>,
+    /// Failed sign-ins to the app
+    pub failed_sign_ins: i64,
+    /// Weekly average of failed sign-ins over the preceding weeks
+    pub failed_sign_ins_baseline: f64,
+    /// Failed sign-ins were unusually high compared to the baseline
+    pub failed_sign_in_spike: bool,
+}
+
+/// Queued digest with what its email needs
+#[derive(Debug, Clone)]
+pub struct PendingAppDigest {
+    pub id: Uuid,
+    pub app_name: String,
+    pub owner_email: String,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub stats: AppDigestStats,
+    /// Owner joined the suppression list, or their address bounced
+    pub suppressed: bool,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct PendingAppDigestRow {
+    pub id: String,
+    pub app_name: String,
+    pub owner_email: String,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub stats: sqlx::types::Json<serde_json::Value>,
+    pub suppressed: i64,
+}
+
+impl From<PendingAppDigestRow> for PendingAppDigest {
+    fn from(row: PendingAppDigestRow) -> Self {
+        Self {
+            id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            app_name: row.app_name,
+            owner_email: row.owner_email,
+            period_start: row.period_start,
+            period_end: row.period_end,
+            stats: serde_json::from_value(row.stats.0).unwrap_or_default(),
+            suppressed: row.suppressed != 0,
+        }
+    }
+}
@@ -3,11 +3,15 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::utils::posture::PostureContext;
+
 /// Authorization Code - temporary code for token exchange
 /// Requirement 3.4: Generate short-lived authorization code (max 10 minutes)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthorizationCode {
     pub id: Uuid,
+    #[allow(dead_code)]
+    #[serde(skip_serializing)]
     pub code_hash: String,
     pub client_id: Uuid,
     pub user_id: Uuid,
@@ -15,8 +19,24 @@ pub struct AuthorizationCode {
     pub scopes: Vec<String>,
     pub code_challenge: String,
     pub code_challenge_method: String,
+    /// OpenID Connect nonce, echoed in the id_token
+    pub nonce: Option<String>,
     pub expires_at: DateTime<Utc>,
     pub used: bool,
+    /// Hash of the approving session's access token
+    #[serde(skip_serializing)]
+    pub session_binding_hash: Option<String>,
+    /// Hash of the approving browser's binding cookie
+    #[serde(skip_serializing)]
+    pub browser_binding_hash: Option<String>,
+    /// When the approving user signed in (the id_token auth_time)
+    pub auth_time: Option<DateTime<Utc>>,
+    /// Authentication context class of that sign-in (the id_token acr)
+    pub acr: Option<String>,
+    /// Authentication methods of that sign-in (the id_token amr)
+    pub amr: Option<Vec<String>>,
+    /// Posture of that sign-in, for clients that opted in to `azp_ctx`
+    pub posture: Option<PostureContext>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -31,8 +51,15 @@ pub struct AuthorizationCodeRow {
     pub scopes: serde_json::Value,
     pub code_challenge: String,
     pub code_challenge_method: String,
+    pub nonce: Option<String>,
     pub expires_at: DateTime<Utc>,
     pub used: bool,
+    pub session_binding_hash: Option<String>,
+    pub browser_binding_hash: Option<String>,
+    pub auth_time: Option<DateTime<Utc>>,
+    pub acr: Option<String>,
+    pub amr: Option<serde_json::Value>,
+    pub posture: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -50,8 +77,15 @@ impl From<AuthorizationCodeRow> for AuthorizationCode {
             scopes,
             code_challenge: row.code_challenge,
             code_challenge_method: row.code_challenge_method,
+            nonce: row.nonce,
             expires_at: row.expires_at,
             used: row.used,
+            session_binding_hash: row.session_binding_hash,
+            browser_binding_hash: row.browser_binding_hash,
+            auth_time: row.auth_time,
+            acr: row.acr,
+            amr: row.amr.and_then(|amr| serde_json::from_value(amr).ok()),
+            posture: row.posture.and_then(|posture| serde_json::from_value(posture).ok()),
             created_at: row.created_at,
         }
     }
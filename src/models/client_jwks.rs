@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Public keys a client signs its request objects with (RFC 9101)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientJwks {
+    pub client_id: Uuid,
+    /// JWK Set containing public keys only
+    pub jwks: serde_json::Value,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct ClientJwksRow {
+    pub client_id: String,
+    pub jwks: serde_json::Value,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ClientJwksRow> for ClientJwks {
+    fn from(row: ClientJwksRow) -> Self {
+        Self {
+            client_id: Uuid::parse_str(&row.client_id).unwrap_or_default(),
+            jwks: row.jwks,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for ClientJwks {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let jwks_row = ClientJwksRow::from_row(row)?;
+        Ok(ClientJwks::from(jwks_row))
+    }
+}
@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::utils::cooling_off::CoolingOffReason;
+
+/// Period after a credential change during which high-risk actions are refused
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoolingOff {
+    pub user_id: Uuid,
+    /// Latest credential change
+    pub reason: CoolingOffReason,
+    pub started_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct CoolingOffRow {
+    pub user_id: String,
+    pub reason: String,
+    pub started_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+impl From<CoolingOffRow> for CoolingOff {
+    fn from(row: CoolingOffRow) -> Self {
+        Self {
+            user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
+            reason: row.reason.parse().unwrap_or(CoolingOffReason::PasswordReset),
+            started_at: row.started_at,
+            ends_at: row.ends_at,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for CoolingOff {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let cooling_off_row = CoolingOffRow::from_row(row)?;
+        Ok(CoolingOff::from(cooling_off_row))
+    }
+}
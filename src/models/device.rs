@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Platform of a registered device
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DevicePlatform {
+    Ios,
+    Android,
+    Web,
+    Desktop,
+}
+
+impl DevicePlatform {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DevicePlatform::Ios => "ios",
+            DevicePlatform::Android => "android",
+            DevicePlatform::Web => "web",
+            DevicePlatform::Desktop => "desktop",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "ios" => Some(DevicePlatform::Ios),
+            "android" => Some(DevicePlatform::Android),
+            "web" => Some(DevicePlatform::Web),
+            "desktop" => Some(DevicePlatform::Desktop),
+            _ => None,
+        }
+    }
+}
+
+/// A device registered by a user (typically a native mobile app install)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserDevice {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub platform: String,
+    pub device_name: Option<String>,
+    #[serde(skip_serializing)]
+    pub push_token: Option<String>,
+    #[serde(skip_serializing)]
+    pub signing_secret: Option<String>,
+    pub is_active: bool,
+    pub last_seen_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct UserDeviceRow {
+    pub id: String,
+    pub user_id: String,
+    pub platform: String,
+    pub device_name: Option<String>,
+    pub push_token: Option<String>,
+    pub signing_secret: Option<String>,
+    pub is_active: bool,
+    pub last_seen_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<UserDeviceRow> for UserDevice {
+    fn from(row: UserDeviceRow) -> Self {
+        Self {
+            id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
+            platform: row.platform,
+            device_name: row.device_name,
+            push_token: row.push_token,
+            signing_secret: row.signing_secret,
+            is_active: row.is_active,
+            last_seen_at: row.last_seen_at,
+            revoked_at: row.revoked_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for UserDevice {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let device_row = UserDeviceRow::from_row(row)?;
+        Ok(UserDevice::from(device_row))
+    }
+}
+
+impl UserDevice {
+    /// Whether the device can receive push notifications
+    pub fn has_push_token(&self) -> bool {
+        self.push_token.as_deref().is_some_and(|t| !t.is_empty())
+    }
+
+    /// Whether the device can receive and sign push login approvals
+    pub fn supports_push_approval(&self) -> bool {
+        self.is_active && self.has_push_token() && self.signing_secret.is_some()
+    }
+}
@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Status of a device authorization
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeviceCodeStatus {
+    /// Waiting for the user to enter the user code
+    Pending,
+    /// Approved by the user, tokens not yet collected
+    Approved,
+    /// Rejected by the user
+    Denied,
+    /// Tokens have been issued to the polling device
+    Redeemed,
+}
+
+/// Device Code - a device authorization waiting for the user (RFC 8628)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceCode {
+    pub id: Uuid,
+    #[serde(skip_serializing)]
+    pub device_code_hash: String,
+    pub client_id: Uuid,
+    pub scopes: Vec<String>,
+    pub status: DeviceCodeStatus,
+    pub user_id: Option<Uuid>,
+    /// When the approving user signed in (the id_token auth_time)
+    pub auth_time: Option<DateTime<Utc>>,
+    /// Minimum seconds between two polls of the token endpoint
+    pub interval_secs: i32,
+    pub last_polled_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Challenge store entry for a device authorization, keyed by its device code hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceCodeEntry {
+    pub id: Uuid,
+    pub device_code_hash: String,
+    pub client_id: Uuid,
+    pub scopes: Vec<String>,
+    pub status: DeviceCodeStatus,
+    pub user_id: Option<Uuid>,
+    pub auth_time: Option<DateTime<Utc>>,
+    pub interval_secs: i32,
+    pub last_polled_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<DeviceCodeEntry> for DeviceCode {
+    fn from(entry: DeviceCodeEntry) -> Self {
+        Self {
+            id: entry.id,
+            device_code_hash: entry.device_code_hash,
+            client_id: entry.client_id,
+            scopes: entry.scopes,
+            status: entry.status,
+            user_id: entry.user_id,
+            auth_time: entry.auth_time,
+            interval_secs: entry.interval_secs,
+            last_polled_at: entry.last_polled_at,
+            expires_at: entry.expires_at,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+impl DeviceCodeEntry {
+    /// Check if the device code has expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at < Utc::now()
+    }
+}
+
+impl DeviceCode {
+    /// Check if the device code has expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at < Utc::now()
+    }
+
+    /// Check if the device polled again before its interval elapsed
+    pub fn polled_too_soon(&self) -> bool {
+        self.last_polled_at
+            .map(|at| Utc::now() < at + chrono::Duration::seconds(self.interval_secs as i64))
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Signal that links probable duplicate accounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateMatchType {
+    /// Same mailbox once dots, `+tags` and domain aliases are removed
+    Email,
+    /// Same phone number once formatting is removed
+    Phone,
+    /// Same WebAuthn public key registered on several accounts
+    Webauthn,
+}
+
+/// Accounts sharing the same match key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateAccountGroup {
+    pub match_type: DuplicateMatchType,
+    pub match_key: String,
+    pub user_ids: Vec<Uuid>,
+}
+
+/// Row type for grouped match key queries (`user_ids` is a GROUP_CONCAT list)
+#[derive(Debug, Clone, FromRow)]
+pub struct DuplicateAccountGroupRow {
+    pub match_key: String,
+    pub user_ids: String,
+}
+
+impl DuplicateAccountGroup {
+    pub fn from_row(match_type: DuplicateMatchType, row: DuplicateAccountGroupRow) -> Self {
+        Self {
+            match_type,
+            match_key: row.match_key,
+            user_ids: row
+                .user_ids
+                .split(',')
+                .filter_map(|id| Uuid::parse_str(id.trim()).ok())
+                .collect(),
+        }
+    }
+}
+
+/// User whose match keys need (re)computing
+#[derive(Debug, Clone, FromRow)]
+pub struct PendingMatchKeyRow {
+    pub id: String,
+    pub email: String,
+    pub phone: Option<String>,
+}
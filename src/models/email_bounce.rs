@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Mail to the address was permanently rejected by the receiving server
+pub const BOUNCE_KIND_HARD: &str = "hard_bounce";
+
+/// The recipient reported an email as spam
+pub const BOUNCE_KIND_COMPLAINT: &str = "complaint";
+
+/// Address no email is sent to, after a hard bounce or complaint
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EmailBounce {
+    pub email: String,
+    pub kind: String,
+    pub provider: String,
+    pub detail: Option<String>,
+    /// Bounces and complaints reported for the address so far
+    pub event_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub last_event_at: DateTime<Utc>,
+}
@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Broadcast waiting for its scheduled time
+pub const BROADCAST_STATUS_SCHEDULED: &str = "scheduled";
+
+/// Recipients selected; the queue is being worked through
+pub const BROADCAST_STATUS_SENDING: &str = "sending";
+
+/// Every recipient was sent to, failed or suppressed
+pub const BROADCAST_STATUS_SENT: &str = "sent";
+
+/// Cancelled by an admin; pending recipients are not sent to
+pub const BROADCAST_STATUS_CANCELLED: &str = "cancelled";
+
+/// Recipient waiting in the queue
+pub const RECIPIENT_STATUS_PENDING: &str = "pending";
+
+/// Email handed to the mailer
+pub const RECIPIENT_STATUS_SENT: &str = "sent";
+
+/// Mailer rejected the email
+pub const RECIPIENT_STATUS_FAILED: &str = "failed";
+
+/// User was put on the suppression list after being queued
+pub const RECIPIENT_STATUS_SUPPRESSED: &str = "suppressed";
+
+/// Which users a broadcast goes to
+///
+/// Filters are combined with AND; an empty segment selects every active
+/// user. Deactivated and anonymized users are never selected.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BroadcastSegment {
+    /// Users registered to this app and not banned from it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<Uuid>,
+    /// Users holding this role
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role_id: Option<Uuid>,
+    /// Users with a session active within this many days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_within_days: Option<u32>,
+    /// Users without a session active for this many days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inactive_for_days: Option<u32>,
+    /// Users whose email is (or is not) verified
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+}
+
+/// Email sent by an admin to a segment of users
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailBroadcast {
+    pub id: Uuid,
+    pub subject: String,
+    pub body: String,
+    pub segment: BroadcastSegment,
+    /// `scheduled`, `sending`, `sent` or `cancelled`
+    pub status: String,
+    pub scheduled_at: DateTime<Utc>,
+    pub created_by: Option<Uuid>,
+    pub recipient_count: i32,
+    pub sent_count: i32,
+    pub failed_count: i32,
+    pub suppressed_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct EmailBroadcastRow {
+    pub id: String,
+    pub subject: String,
+    pub body: String,
+    pub segment: sqlx::types::Json<serde_json::Value>,
+    pub status: String,
+    pub scheduled_at: DateTime<Utc>,
+    pub created_by: Option<String>,
+    pub recipient_count: i32,
+    pub sent_count: i32,
+    pub failed_count: i32,
+    pub suppressed_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<EmailBroadcastRow> for EmailBroadcast {
+    fn from(row: EmailBroadcastRow) -> Self {
+        Self {
+            id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            subject: row.subject,
+            body: row.body,
+            segment: serde_json::from_value(row.segment.0).unwrap_or_default(),
+            status: row.status,
+            scheduled_at: row.scheduled_at,
+            created_by: row.created_by.and_then(|id| Uuid::parse_str(&id).ok()),
+            recipient_count: row.recipient_count,
+            sent_count: row.sent_count,
+            failed_count: row.failed_count,
+            suppressed_count: row.suppressed_count,
+            created_at: row.created_at,
+            started_at: row.started_at,
+            completed_at: row.completed_at,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for EmailBroadcast {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let broadcast_row = EmailBroadcastRow::from_row(row)?;
+        Ok(EmailBroadcast::from(broadcast_row))
+    }
+}
+
+/// Queued recipient of a broadcast, with what its template needs
+#[derive(Debug, Clone)]
+pub struct BroadcastRecipient {
+    pub user_id: Uuid,
+    pub email: String,
+    pub name: Option<String>,
+    /// User joined the suppression list after being queued
+    pub suppressed: bool,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct BroadcastRecipientRow {
+    pub user_id: String,
+    pub email: String,
+    pub name: Option<String>,
+    pub suppressed: i64,
+}
+
+impl From<BroadcastRecipientRow> for BroadcastRecipient {
+    fn from(row: BroadcastRecipientRow) -> Self {
+        Self {
+            user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
+            email: row.email,
+            name: row.name,
+            suppressed: row.suppressed != 0,
+        }
+    }
+}
+
+/// User who must not receive broadcasts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailSuppression {
+    pub user_id: Uuid,
+    pub email: String,
+    pub reason: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct EmailSuppressionRow {
+    pub user_id: String,
+    pub email: String,
+    pub reason: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<EmailSuppressionRow> for EmailSuppression {
+    fn from(row: EmailSuppressionRow) -> Self {
+        Self {
+            user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
+            email: row.email,
+            reason: row.reason,
+            created_by: row.created_by.and_then(|id| Uuid::parse_str(&id).ok()),
+            created_at: row.created_at,
+        }
+    }
+}
@@ -105,3 +105,34 @@ impl IpRule {
         (ip_num & mask) == (net_num & mask)
     }
 }
+
+/// Review state of a suggested IP rule
+pub const SUGGESTION_PENDING: &str = "pending";
+pub const SUGGESTION_APPROVED: &str = "approved";
+pub const SUGGESTION_REJECTED: &str = "rejected";
+
+/// Global deny rule proposed by the abuse telemetry worker, awaiting admin review
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct IpRuleSuggestion {
+    pub id: String,
+    pub ip_address: String,
+    /// Route template of the largest burst seen while pending
+    pub route: String,
+    pub window_start: DateTime<Utc>,
+    pub client_errors: i32,
+    pub rate_limited: i32,
+    pub reason: String,
+    pub status: String,
+    /// Rule created when the suggestion was approved
+    pub ip_rule_id: Option<String>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub last_seen_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl IpRuleSuggestion {
+    pub fn id_uuid(&self) -> Uuid {
+        Uuid::parse_str(&self.id).unwrap_or_else(|_| Uuid::nil())
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::utils::geoip::GeoLocation;
+
+/// Located sign-in, compared with the next one for impossible travel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginLocation {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub ip_address: String,
+    pub country: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Reached faster than the impossible travel policy allows
+    pub impossible_travel: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct LoginLocationRow {
+    pub id: String,
+    pub user_id: String,
+    pub ip_address: String,
+    pub country: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub impossible_travel: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<LoginLocationRow> for LoginLocation {
+    fn from(row: LoginLocationRow) -> Self {
+        Self {
+            id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
+            ip_address: row.ip_address,
+            country: row.country,
+            latitude: row.latitude,
+            longitude: row.longitude,
+            impossible_travel: row.impossible_travel,
+            created_at: row.created_at,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for LoginLocation {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let location_row = LoginLocationRow::from_row(row)?;
+        Ok(LoginLocation::from(location_row))
+    }
+}
+
+impl LoginLocation {
+    pub fn location(&self) -> GeoLocation {
+        GeoLocation {
+            latitude: self.latitude,
+            longitude: self.longitude,
+            country: self.country.clone(),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Magic link token - a single-use link emailed to the account address
+/// that signs the user in to an app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MagicLinkToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub app_id: Uuid,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct MagicLinkTokenRow {
+    pub id: String,
+    pub user_id: String,
+    pub app_id: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<MagicLinkTokenRow> for MagicLinkToken {
+    fn from(row: MagicLinkTokenRow) -> Self {
+        Self {
+            id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
+            app_id: Uuid::parse_str(&row.app_id).unwrap_or_default(),
+            ip_address: row.ip_address,
+            user_agent: row.user_agent,
+            expires_at: row.expires_at,
+            used_at: row.used_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for MagicLinkToken {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let token_row = MagicLinkTokenRow::from_row(row)?;
+        Ok(MagicLinkToken::from(token_row))
+    }
+}
+
+impl MagicLinkToken {
+    /// Check if the link has expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at < Utc::now()
+    }
+
+    /// Check if the link was already used to sign in
+    pub fn is_used(&self) -> bool {
+        self.used_at.is_some()
+    }
+}
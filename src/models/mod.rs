@@ -7,6 +7,7 @@ pub mod oauth_client;
 pub mod oauth_scope;
 pub mod user_consent;
 pub mod authorization_code;
+pub mod device_code;
 pub mod oauth_token;
 pub mod oauth_audit_log;
 pub mod security;
@@ -14,6 +15,27 @@ pub mod webhook;
 pub mod api_key;
 pub mod ip_rule;
 pub mod webauthn;
+pub mod qr_login;
+pub mod device;
+pub mod push_mfa;
+pub mod redirect_uri_block;
+pub mod duplicate_account;
+pub mod rbac;
+pub mod worker_leader;
+pub mod provisioning;
+pub mod user_address;
+pub mod signing_key;
+pub mod token_lineage;
+pub mod client_jwks;
+pub mod email_broadcast;
+pub mod email_bounce;
+pub mod admin_approval;
+pub mod sso_session;
+pub mod app_digest;
+pub mod magic_link;
+pub mod trusted_device;
+pub mod login_history;
+pub mod cooling_off;
 
 pub use user::*;
 pub use app::*;
@@ -24,6 +46,7 @@ pub use oauth_client::*;
 pub use oauth_scope::*;
 pub use user_consent::*;
 pub use authorization_code::*;
+pub use device_code::*;
 pub use oauth_token::*;
 pub use oauth_audit_log::*;
 pub use security::*;
@@ -31,3 +54,24 @@ pub use webhook::*;
 pub use api_key::*;
 pub use ip_rule::*;
 pub use webauthn::*;
+pub use qr_login::*;
+pub use device::*;
+pub use push_mfa::*;
+pub use redirect_uri_block::*;
+pub use duplicate_account::*;
+pub use rbac::*;
+pub use worker_leader::*;
+pub use provisioning::*;
+pub use user_address::*;
+pub use signing_key::*;
+pub use token_lineage::*;
+pub use client_jwks::*;
+pub use email_broadcast::*;
+pub use email_bounce::*;
+pub use admin_approval::*;
+pub use sso_session::*;
+pub use app_digest::*;
+pub use magic_link::*;
+pub use trusted_device::*;
+pub use login_history::*;
+pub use cooling_off::*;
@@ -14,6 +14,8 @@ pub struct OAuthAuditLog {
     pub user_id: Option<Uuid>,
     pub ip_address: Option<String>,
     pub details: Option<serde_json::Value>,
+    /// Request that wrote the row
+    pub correlation_id: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -26,6 +28,8 @@ pub struct OAuthAuditLogRow {
     pub user_id: Option<String>,
     pub ip_address: Option<String>,
     pub details: Option<serde_json::Value>,
+    /// Request that wrote the row
+    pub correlation_id: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -38,6 +42,7 @@ impl From<OAuthAuditLogRow> for OAuthAuditLog {
             user_id: row.user_id.and_then(|id| Uuid::parse_str(&id).ok()),
             ip_address: row.ip_address,
             details: row.details,
+            correlation_id: row.correlation_id,
             created_at: row.created_at,
         }
     }
@@ -50,6 +55,20 @@ impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for OAuthAuditLog {
     }
 }
 
+/// Number of audit events sharing a time bucket, type, client, grant type and error
+#[derive(Debug, Clone, FromRow)]
+pub struct OAuthAuditCount {
+    /// Start of the bucket (Unix seconds)
+    pub bucket: i64,
+    pub event_type: String,
+    pub client_id: Option<String>,
+    /// `grant_type` from the event details
+    pub grant_type: Option<String>,
+    /// `error` from the event details
+    pub error: Option<String>,
+    pub count: i64,
+}
+
 /// Event types for OAuth audit logging
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OAuthEventType {
@@ -75,6 +94,24 @@ pub enum OAuthEventType {
     InvalidTokenAttempt,
     /// Invalid client credentials
     InvalidClientCredentials,
+    /// OpenID Connect nonce reused by a client
+    NonceReplayDetected,
+    /// Authorization code redeemed from a different browser than approved it
+    CodeBrowserMismatch,
+    /// Client sent a missing or low-entropy state parameter
+    WeakStateParameter,
+    /// Admin changed a client's first-party trust level
+    ClientTrustUpdated,
+    /// Client secret rotated by its owner
+    ClientSecretRotated,
+    /// Client authenticated with a correct but expired secret
+    ClientSecretExpired,
+    /// Admin changed a client's maximum secret age
+    ClientSecretPolicyUpdated,
+    /// Token endpoint request rejected with an OAuth error
+    TokenRequestFailed,
+    /// User signed out through the end_session_endpoint
+    UserLoggedOut,
 }
 
 impl OAuthEventType {
@@ -91,6 +128,15 @@ impl OAuthEventType {
             OAuthEventType::ConsentRevoked => "consent_revoked",
             OAuthEventType::InvalidTokenAttempt => "invalid_token_attempt",
             OAuthEventType::InvalidClientCredentials => "invalid_client_credentials",
+            OAuthEventType::NonceReplayDetected => "nonce_replay_detected",
+            OAuthEventType::CodeBrowserMismatch => "code_browser_mismatch",
+            OAuthEventType::WeakStateParameter => "weak_state_parameter",
+            OAuthEventType::ClientTrustUpdated => "client_trust_updated",
+            OAuthEventType::ClientSecretRotated => "client_secret_rotated",
+            OAuthEventType::ClientSecretExpired => "client_secret_expired",
+            OAuthEventType::ClientSecretPolicyUpdated => "client_secret_policy_updated",
+            OAuthEventType::TokenRequestFailed => "token_request_failed",
+            OAuthEventType::UserLoggedOut => "user_logged_out",
         }
     }
 }
@@ -3,6 +3,12 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+/// Client type for browser/server-side web applications
+pub const CLIENT_TYPE_WEB: &str = "web";
+
+/// Client type for desktop, mobile and CLI apps (RFC 8252)
+pub const CLIENT_TYPE_NATIVE: &str = "native";
+
 /// OAuth Client - represents an external or internal application
 /// Requirement 1.1: Store client_id, client_secret, redirect_uris, and is_internal flag
 /// Requirement 1.5: Distinguish between Internal_App and External_App
@@ -16,7 +22,41 @@ pub struct OAuthClient {
     pub owner_id: Option<Uuid>,
     pub redirect_uris: Vec<String>,
     pub is_internal: bool,
+    /// `web` or `native`
+    pub client_type: String,
     pub is_active: bool,
+    /// Session idle timeout override in seconds (None = server default)
+    pub session_idle_timeout_secs: Option<i64>,
+    /// Session absolute lifetime override in seconds (None = server default)
+    pub session_absolute_lifetime_secs: Option<i64>,
+    /// Access token lifetime override in seconds (None = server default)
+    pub access_token_ttl_secs: Option<i64>,
+    /// Deliver refresh tokens in an HttpOnly cookie (browser SPAs)
+    pub refresh_token_cookie: bool,
+    /// First-party client that bypasses the consent screen (internal clients only)
+    pub skip_consent: bool,
+    /// Show the consent screen on every authorization, even for granted scopes
+    pub always_prompt_consent: bool,
+    /// Minimum acr of sign-ins the client accepts codes from (None = any)
+    pub required_acr: Option<String>,
+    /// Algorithm /oauth/userinfo responses are signed with (None = plain JSON)
+    pub userinfo_signed_response_alg: Option<String>,
+    /// Put the sign-in posture (`azp_ctx`) in access tokens
+    pub posture_claims: bool,
+    /// When the current secret was issued
+    pub secret_created_at: DateTime<Utc>,
+    /// Admin-set maximum secret age in days (None = server default, 0 = never expires)
+    pub secret_max_age_days: Option<i64>,
+    /// Where /oauth/logout may redirect after signing the user out
+    pub post_logout_redirect_uris: Vec<String>,
+    /// Endpoint receiving back-channel logout tokens
+    pub backchannel_logout_uri: Option<String>,
+    /// URLs hosting request objects the client may pass as request_uri
+    pub request_uris: Vec<String>,
+    /// Scopes the client may request (None = every scope available to it)
+    pub allowed_scopes: Option<Vec<String>>,
+    /// Grant types the client may use (None = every supported grant)
+    pub allowed_grant_types: Option<Vec<String>>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -30,7 +70,24 @@ pub struct OAuthClientRow {
     pub owner_id: Option<String>,
     pub redirect_uris: serde_json::Value,
     pub is_internal: bool,
+    pub client_type: String,
     pub is_active: bool,
+    pub session_idle_timeout_secs: Option<i32>,
+    pub session_absolute_lifetime_secs: Option<i32>,
+    pub access_token_ttl_secs: Option<i32>,
+    pub refresh_token_cookie: bool,
+    pub skip_consent: bool,
+    pub always_prompt_consent: bool,
+    pub required_acr: Option<String>,
+    pub userinfo_signed_response_alg: Option<String>,
+    pub posture_claims: bool,
+    pub secret_created_at: DateTime<Utc>,
+    pub secret_max_age_days: Option<i32>,
+    pub post_logout_redirect_uris: Option<serde_json::Value>,
+    pub backchannel_logout_uri: Option<String>,
+    pub request_uris: Option<serde_json::Value>,
+    pub allowed_scopes: Option<serde_json::Value>,
+    pub allowed_grant_types: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -47,7 +104,34 @@ impl From<OAuthClientRow> for OAuthClient {
             owner_id: row.owner_id.and_then(|id| Uuid::parse_str(&id).ok()),
             redirect_uris,
             is_internal: row.is_internal,
+            client_type: row.client_type,
             is_active: row.is_active,
+            session_idle_timeout_secs: row.session_idle_timeout_secs.map(i64::from),
+            session_absolute_lifetime_secs: row.session_absolute_lifetime_secs.map(i64::from),
+            access_token_ttl_secs: row.access_token_ttl_secs.map(i64::from),
+            refresh_token_cookie: row.refresh_token_cookie,
+            skip_consent: row.skip_consent,
+            always_prompt_consent: row.always_prompt_consent,
+            required_acr: row.required_acr,
+            userinfo_signed_response_alg: row.userinfo_signed_response_alg,
+            posture_claims: row.posture_claims,
+            secret_created_at: row.secret_created_at,
+            secret_max_age_days: row.secret_max_age_days.map(i64::from),
+            post_logout_redirect_uris: row
+                .post_logout_redirect_uris
+                .and_then(|uris| serde_json::from_value(uris).ok())
+                .unwrap_or_default(),
+            backchannel_logout_uri: row.backchannel_logout_uri,
+            request_uris: row
+                .request_uris
+                .and_then(|uris| serde_json::from_value(uris).ok())
+                .unwrap_or_default(),
+            allowed_scopes: row
+                .allowed_scopes
+                .and_then(|scopes| serde_json::from_value(scopes).ok()),
+            allowed_grant_types: row
+                .allowed_grant_types
+                .and_then(|grants| serde_json::from_value(grants).ok()),
             created_at: row.created_at,
         }
     }
@@ -66,13 +150,91 @@ impl OAuthClient {
         !self.is_internal
     }
 
+    /// Check if this is a native (desktop, mobile or CLI) app
+    pub fn is_native(&self) -> bool {
+        self.client_type == CLIENT_TYPE_NATIVE
+    }
+
+    /// Check if consent should be skipped (only first-party internal clients
+    /// that do not always prompt)
+    pub fn skips_consent(&self) -> bool {
+        self.is_internal && self.skip_consent && !self.always_prompt_consent
+    }
+
     /// Check if a redirect URI is registered for this client
     pub fn has_redirect_uri(&self, uri: &str) -> bool {
         self.redirect_uris.iter().any(|u| u == uri)
     }
 
+    /// Check if a post-logout redirect URI is registered for this client
+    pub fn has_post_logout_redirect_uri(&self, uri: &str) -> bool {
+        self.post_logout_redirect_uris.iter().any(|u| u == uri)
+    }
+
+    /// Check if the client may request all of these scopes
+    pub fn allows_scopes(&self, scopes: &[String]) -> bool {
+        match &self.allowed_scopes {
+            Some(allowed) => scopes.iter().all(|scope| allowed.contains(scope)),
+            None => true,
+        }
+    }
+
+    /// Check if the client may use a grant type
+    pub fn allows_grant_type(&self, grant_type: &str) -> bool {
+        match &self.allowed_grant_types {
+            Some(allowed) => allowed.iter().any(|g| g == grant_type),
+            None => true,
+        }
+    }
+
     /// Check if a user is the owner of this client
     pub fn is_owner(&self, user_id: Uuid) -> bool {
         self.owner_id == Some(user_id)
     }
+
+    /// When the current secret expires, given the server-wide maximum age
+    ///
+    /// The client's own maximum age wins over the server default; a maximum
+    /// age of 0 means the secret never expires.
+    pub fn secret_expires_at(&self, default_max_age_days: i64) -> Option<DateTime<Utc>> {
+        let max_age_days = self.secret_max_age_days.unwrap_or(default_max_age_days);
+        (max_age_days > 0).then(|| self.secret_created_at + chrono::Duration::days(max_age_days))
+    }
+
+    /// Lifetime of access tokens issued to this client
+    ///
+    /// The client's own lifetime wins over the server default; either is
+    /// capped at the server maximum.
+    pub fn access_token_ttl(&self, default_secs: i64, max_secs: i64) -> i64 {
+        self.access_token_ttl_secs.unwrap_or(default_secs).min(max_secs)
+    }
+
+    /// Check if the current secret is past its maximum age
+    pub fn is_secret_expired(&self, default_max_age_days: i64) -> bool {
+        self.secret_expires_at(default_max_age_days)
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+}
+
+/// One rotation of an OAuth client's secret
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ClientSecretRotation {
+    pub id: String,
+    pub client_id: String,
+    pub rotated_by: Option<String>,
+    pub previous_secret_created_at: DateTime<Utc>,
+    pub previous_secret_expired: bool,
+    /// Until when the replaced secret stayed valid (None = invalidated immediately)
+    pub previous_secret_valid_until: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A client secret about to expire, with the owner to warn
+#[derive(Debug, Clone, FromRow)]
+pub struct ExpiringClientSecret {
+    pub id: String,
+    pub client_id: String,
+    pub name: String,
+    pub owner_email: String,
+    pub secret_expires_at: DateTime<Utc>,
 }
@@ -3,6 +3,15 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+/// Scope usable only by its owning client
+pub const SCOPE_VISIBILITY_PRIVATE: &str = "private";
+
+/// Owner asked for global visibility; usable only by the owner until an admin approves
+pub const SCOPE_VISIBILITY_PENDING: &str = "pending";
+
+/// Scope any client may request and listed in `/oauth/scopes`
+pub const SCOPE_VISIBILITY_GLOBAL: &str = "global";
+
 /// OAuth Scope - defines a permission scope
 /// Requirement 2.1: Support defining scopes with unique code and description
 /// Requirement 2.2: Enforce unique scope codes
@@ -12,6 +21,12 @@ pub struct OAuthScope {
     pub code: String,
     pub description: String,
     pub is_active: bool,
+    /// Client owning this namespaced custom scope (None = standard scope)
+    pub owner_client_id: Option<Uuid>,
+    /// `private`, `pending` or `global`
+    pub visibility: String,
+    /// Userinfo claims released by this custom scope
+    pub claims: Vec<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -22,6 +37,9 @@ pub struct OAuthScopeRow {
     pub code: String,
     pub description: String,
     pub is_active: bool,
+    pub owner_client_id: Option<String>,
+    pub visibility: String,
+    pub claims: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -32,6 +50,12 @@ impl From<OAuthScopeRow> for OAuthScope {
             code: row.code,
             description: row.description,
             is_active: row.is_active,
+            owner_client_id: row.owner_client_id.and_then(|id| Uuid::parse_str(&id).ok()),
+            visibility: row.visibility,
+            claims: row
+                .claims
+                .and_then(|claims| serde_json::from_value(claims).ok())
+                .unwrap_or_default(),
             created_at: row.created_at,
         }
     }
@@ -43,3 +67,16 @@ impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for OAuthScope {
         Ok(OAuthScope::from(scope_row))
     }
 }
+
+impl OAuthScope {
+    /// Check if a client may request this scope
+    ///
+    /// Standard and approved global scopes are open to every client; other
+    /// custom scopes only to the client that defined them.
+    pub fn is_available_to(&self, client_id: Uuid) -> bool {
+        match self.owner_client_id {
+            None => true,
+            Some(owner) => owner == client_id || self.visibility == SCOPE_VISIBILITY_GLOBAL,
+        }
+    }
+}
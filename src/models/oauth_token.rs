@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::utils::posture::PostureContext;
+
 /// OAuth Token - stores issued tokens
 /// Requirement 5.1: Issue access_token and refresh_token
 /// Requirement 5.6: Hash tokens before storing in the database
@@ -18,6 +20,12 @@ pub struct OAuthToken {
     pub scopes: Vec<String>,
     pub expires_at: DateTime<Utc>,
     pub revoked: bool,
+    /// When the grant this token was rotated from started
+    pub session_started_at: DateTime<Utc>,
+    /// Authorization code the token chain was issued from
+    pub authorization_code_id: Option<Uuid>,
+    /// Posture of the grant's sign-in, for clients that opted in to `azp_ctx`
+    pub posture: Option<PostureContext>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -32,6 +40,9 @@ pub struct OAuthTokenRow {
     pub scopes: serde_json::Value,
     pub expires_at: DateTime<Utc>,
     pub revoked: bool,
+    pub session_started_at: Option<DateTime<Utc>>,
+    pub authorization_code_id: Option<String>,
+    pub posture: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -49,6 +60,9 @@ impl From<OAuthTokenRow> for OAuthToken {
             scopes,
             expires_at: row.expires_at,
             revoked: row.revoked,
+            session_started_at: row.session_started_at.unwrap_or(row.created_at),
+            authorization_code_id: row.authorization_code_id.and_then(|id| Uuid::parse_str(&id).ok()),
+            posture: row.posture.and_then(|posture| serde_json::from_value(posture).ok()),
             created_at: row.created_at,
         }
     }
@@ -0,0 +1,136 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Remove a user from the app (roles included)
+pub const PROVISIONING_EVENT_USER_DEPROVISION: &str = "user.deprovision";
+
+/// Make a user's roles in the app exactly the listed set
+pub const PROVISIONING_EVENT_ROLE_SYNC: &str = "role.sync";
+
+/// Event types accepted on the inbound provisioning topic
+pub const PROVISIONING_EVENT_TYPES: &[&str] = &[PROVISIONING_EVENT_USER_DEPROVISION, PROVISIONING_EVENT_ROLE_SYNC];
+
+/// Event waiting to be applied (or retried)
+pub const PROVISIONING_STATUS_PENDING: &str = "pending";
+
+/// Event applied successfully
+pub const PROVISIONING_STATUS_PROCESSED: &str = "processed";
+
+/// Event rejected by schema validation or out of retries
+pub const PROVISIONING_STATUS_DEAD_LETTER: &str = "dead_letter";
+
+/// Inbound provisioning event submitted by an upstream system
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvisioningEvent {
+    pub id: Uuid,
+    pub app_id: Uuid,
+    pub event_id: String,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub source_api_key_id: Option<Uuid>,
+    /// `pending`, `processed` or `dead_letter`
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub processed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct ProvisioningEventRow {
+    pub id: String,
+    pub app_id: String,
+    pub event_id: String,
+    pub event_type: String,
+    pub payload: sqlx::types::Json<serde_json::Value>,
+    pub source_api_key_id: Option<String>,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub processed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ProvisioningEventRow> for ProvisioningEvent {
+    fn from(row: ProvisioningEventRow) -> Self {
+        Self {
+            id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            app_id: Uuid::parse_str(&row.app_id).unwrap_or_default(),
+            event_id: row.event_id,
+            event_type: row.event_type,
+            payload: row.payload.0,
+            source_api_key_id: row.source_api_key_id.and_then(|id| Uuid::parse_str(&id).ok()),
+            status: row.status,
+            attempts: row.attempts,
+            last_error: row.last_error,
+            next_attempt_at: row.next_attempt_at,
+            processed_at: row.processed_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for ProvisioningEvent {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let event_row = ProvisioningEventRow::from_row(row)?;
+        Ok(ProvisioningEvent::from(event_row))
+    }
+}
+
+/// Provisioning event received from the message broker
+///
+/// Unlike the HTTP API, where the API key names the app, the message does.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BrokerProvisioningEvent {
+    pub app_id: Uuid,
+    /// Producer-assigned ID; redelivering the same ID is a no-op
+    pub event_id: String,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+}
+
+/// Payload of `user.deprovision`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserDeprovisionPayload {
+    pub user_id: Uuid,
+}
+
+/// Payload of `role.sync`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoleSyncPayload {
+    pub user_id: Uuid,
+    /// Role names; roles not listed are removed
+    pub roles: Vec<String>,
+}
+
+/// Schema-validated provisioning command
+#[derive(Debug, Clone)]
+pub enum ProvisioningCommand {
+    UserDeprovision(UserDeprovisionPayload),
+    RoleSync(RoleSyncPayload),
+}
+
+impl ProvisioningCommand {
+    /// Validate an event payload against the schema of its type
+    pub fn parse(event_type: &str, payload: &serde_json::Value) -> Result<Self, String> {
+        let invalid = |e: serde_json::Error| format!("invalid {} payload: {}", event_type, e);
+
+        match event_type {
+            PROVISIONING_EVENT_USER_DEPROVISION => serde_json::from_value(payload.clone())
+                .map(Self::UserDeprovision)
+                .map_err(invalid),
+            PROVISIONING_EVENT_ROLE_SYNC => serde_json::from_value(payload.clone())
+                .map(Self::RoleSync)
+                .map_err(invalid),
+            other => Err(format!("unsupported event type: {}", other)),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Status of a push MFA challenge
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PushChallengeStatus {
+    Pending,
+    Approved,
+    Denied,
+    /// Approval already used to complete a login
+    Consumed,
+}
+
+impl PushChallengeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PushChallengeStatus::Pending => "pending",
+            PushChallengeStatus::Approved => "approved",
+            PushChallengeStatus::Denied => "denied",
+            PushChallengeStatus::Consumed => "consumed",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(PushChallengeStatus::Pending),
+            "approved" => Some(PushChallengeStatus::Approved),
+            "denied" => Some(PushChallengeStatus::Denied),
+            "consumed" => Some(PushChallengeStatus::Consumed),
+            _ => None,
+        }
+    }
+}
+
+/// Push MFA challenge - a login approval request sent to the user's devices,
+/// bound to the pending MFA token of the login it approves
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushMfaChallenge {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[serde(skip_serializing)]
+    pub mfa_token_hash: String,
+    pub status: PushChallengeStatus,
+    pub device_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct PushMfaChallengeRow {
+    pub id: String,
+    pub user_id: String,
+    pub mfa_token_hash: String,
+    pub status: String,
+    pub device_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<PushMfaChallengeRow> for PushMfaChallenge {
+    fn from(row: PushMfaChallengeRow) -> Self {
+        Self {
+            id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
+            mfa_token_hash: row.mfa_token_hash,
+            status: PushChallengeStatus::from_str(&row.status).unwrap_or(PushChallengeStatus::Pending),
+            device_id: row.device_id.and_then(|s| Uuid::parse_str(&s).ok()),
+            ip_address: row.ip_address,
+            user_agent: row.user_agent,
+            responded_at: row.responded_at,
+            expires_at: row.expires_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for PushMfaChallenge {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let challenge_row = PushMfaChallengeRow::from_row(row)?;
+        Ok(PushMfaChallenge::from(challenge_row))
+    }
+}
+
+impl PushMfaChallenge {
+    /// Check if the challenge has expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at < Utc::now()
+    }
+}
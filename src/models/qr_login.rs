@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Status of a QR login channel
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum QrLoginStatus {
+    /// Waiting for the user to scan and approve
+    Pending,
+    /// Approved on the phone, tokens not yet collected
+    Approved,
+    /// Rejected on the phone
+    Denied,
+    /// Tokens have been issued to the polling device
+    Redeemed,
+}
+
+impl QrLoginStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QrLoginStatus::Pending => "pending",
+            QrLoginStatus::Approved => "approved",
+            QrLoginStatus::Denied => "denied",
+            QrLoginStatus::Redeemed => "redeemed",
+        }
+    }
+}
+
+/// QR login channel - a short-lived rendezvous between the device showing the
+/// QR code and the phone approving it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QrLoginChannel {
+    pub id: Uuid,
+    #[serde(skip_serializing)]
+    pub poll_secret_hash: String,
+    pub status: QrLoginStatus,
+    pub user_id: Option<Uuid>,
+    pub app_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub approved_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Challenge store entry for a channel, keyed by its channel code hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QrLoginChannelEntry {
+    pub id: Uuid,
+    pub poll_secret_hash: String,
+    pub status: QrLoginStatus,
+    pub user_id: Option<Uuid>,
+    pub app_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub approved_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<QrLoginChannelEntry> for QrLoginChannel {
+    fn from(entry: QrLoginChannelEntry) -> Self {
+        Self {
+            id: entry.id,
+            poll_secret_hash: entry.poll_secret_hash,
+            status: entry.status,
+            user_id: entry.user_id,
+            app_id: entry.app_id,
+            ip_address: entry.ip_address,
+            user_agent: entry.user_agent,
+            approved_at: entry.approved_at,
+            expires_at: entry.expires_at,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+impl QrLoginChannelEntry {
+    /// Check if the channel has expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at < Utc::now()
+    }
+}
+
+impl QrLoginChannel {
+    /// Check if the channel has expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at < Utc::now()
+    }
+}
@@ -0,0 +1,56 @@
+use serde::Serialize;
+
+/// Desired role with the permission codes it grants
+#[derive(Debug, Clone)]
+pub struct RbacRoleSpec {
+    pub name: String,
+    pub permissions: Vec<String>,
+}
+
+/// Validated desired role/permission catalog of an app
+///
+/// Names and codes are unique ignoring case, and every role only references
+/// permissions listed in `permissions`.
+#[derive(Debug, Clone)]
+pub struct RbacCatalog {
+    pub permissions: Vec<String>,
+    pub roles: Vec<RbacRoleSpec>,
+}
+
+/// Role-permission mapping identified by names, as used in catalog syncs
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct RbacMapping {
+    pub role: String,
+    pub permission: String,
+}
+
+/// Changes applied (or, for a dry run, that would be applied) by a catalog sync
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RbacChangeSummary {
+    pub roles_created: Vec<String>,
+    /// Roles whose name only changed in letter case
+    pub roles_updated: Vec<String>,
+    pub roles_deleted: Vec<String>,
+    /// Roles missing from the catalog but kept because users still hold them
+    pub roles_retained: Vec<String>,
+    pub permissions_created: Vec<String>,
+    /// Permissions whose code only changed in letter case
+    pub permissions_updated: Vec<String>,
+    pub permissions_deleted: Vec<String>,
+    pub mappings_added: Vec<RbacMapping>,
+    pub mappings_removed: Vec<RbacMapping>,
+}
+
+impl RbacChangeSummary {
+    /// Check if the sync changed anything
+    pub fn is_empty(&self) -> bool {
+        self.roles_created.is_empty()
+            && self.roles_updated.is_empty()
+            && self.roles_deleted.is_empty()
+            && self.permissions_created.is_empty()
+            && self.permissions_updated.is_empty()
+            && self.permissions_deleted.is_empty()
+            && self.mappings_added.is_empty()
+            && self.mappings_removed.is_empty()
+    }
+}
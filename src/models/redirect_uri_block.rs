@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Admin-managed pattern that OAuth redirect URIs must not match
+///
+/// A pattern is one of:
+/// - a host (`evil.example`) - matches that host exactly
+/// - a subdomain wildcard (`*.evil.example`) - matches the host and any subdomain
+/// - a URI prefix (`https://example.com/redirect`) - matches URIs starting with it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedirectUriBlock {
+    pub id: Uuid,
+    pub pattern: String,
+    pub reason: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct RedirectUriBlockRow {
+    pub id: String,
+    pub pattern: String,
+    pub reason: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<RedirectUriBlockRow> for RedirectUriBlock {
+    fn from(row: RedirectUriBlockRow) -> Self {
+        Self {
+            id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            pattern: row.pattern,
+            reason: row.reason,
+            created_by: row.created_by.and_then(|s| Uuid::parse_str(&s).ok()),
+            created_at: row.created_at,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for RedirectUriBlock {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let block_row = RedirectUriBlockRow::from_row(row)?;
+        Ok(RedirectUriBlock::from(block_row))
+    }
+}
+
+impl RedirectUriBlock {
+    /// Check whether a redirect URI matches this pattern
+    pub fn matches(&self, uri: &str) -> bool {
+        let pattern = self.pattern.to_ascii_lowercase();
+        let uri = uri.to_ascii_lowercase();
+
+        if pattern.contains("://") {
+            return uri.starts_with(&pattern);
+        }
+
+        let host = match reqwest::Url::parse(&uri) {
+            Ok(url) => match url.host_str() {
+                Some(host) => host.to_string(),
+                None => return false,
+            },
+            Err(_) => return false,
+        };
+
+        match pattern.strip_prefix("*.") {
+            Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+            None => host == pattern,
+        }
+    }
+}
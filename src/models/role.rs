@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
@@ -42,6 +43,8 @@ pub struct UserAppRole {
     pub user_id: Uuid,
     pub app_id: Uuid,
     pub role_id: Uuid,
+    /// When a time-bounded assignment ends (None = permanent)
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 /// Row type for MySQL query results
@@ -50,6 +53,7 @@ pub struct UserAppRoleRow {
     pub user_id: String,
     pub app_id: String,
     pub role_id: String,
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 impl From<UserAppRoleRow> for UserAppRole {
@@ -58,6 +62,7 @@ impl From<UserAppRoleRow> for UserAppRole {
             user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
             app_id: Uuid::parse_str(&row.app_id).unwrap_or_default(),
             role_id: Uuid::parse_str(&row.role_id).unwrap_or_default(),
+            expires_at: row.expires_at,
         }
     }
 }
@@ -69,3 +74,157 @@ impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for UserAppRole {
         Ok(UserAppRole::from(uar_row))
     }
 }
+
+/// Elevation request awaiting the app owner's decision
+pub const ELEVATION_STATUS_PENDING: &str = "pending";
+
+/// Elevation request granted; the role was assigned until `grant_expires_at`
+pub const ELEVATION_STATUS_APPROVED: &str = "approved";
+
+/// Elevation request refused by the app owner
+pub const ELEVATION_STATUS_DENIED: &str = "denied";
+
+/// Request from an app member for temporary elevated access to a role
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleElevationRequest {
+    pub id: Uuid,
+    pub app_id: Uuid,
+    pub user_id: Uuid,
+    pub role_id: Uuid,
+    pub reason: Option<String>,
+    pub duration_secs: i64,
+    /// `pending`, `approved` or `denied`
+    pub status: String,
+    pub decided_by: Option<Uuid>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub grant_expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct RoleElevationRequestRow {
+    pub id: String,
+    pub app_id: String,
+    pub user_id: String,
+    pub role_id: String,
+    pub reason: Option<String>,
+    pub duration_secs: i32,
+    pub status: String,
+    pub decided_by: Option<String>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub grant_expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<RoleElevationRequestRow> for RoleElevationRequest {
+    fn from(row: RoleElevationRequestRow) -> Self {
+        Self {
+            id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            app_id: Uuid::parse_str(&row.app_id).unwrap_or_default(),
+            user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
+            role_id: Uuid::parse_str(&row.role_id).unwrap_or_default(),
+            reason: row.reason,
+            duration_secs: i64::from(row.duration_secs),
+            status: row.status,
+            decided_by: row.decided_by.and_then(|id| Uuid::parse_str(&id).ok()),
+            decided_at: row.decided_at,
+            grant_expires_at: row.grant_expires_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for RoleElevationRequest {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let request_row = RoleElevationRequestRow::from_row(row)?;
+        Ok(RoleElevationRequest::from(request_row))
+    }
+}
+
+/// Role was assigned (or its expiry changed)
+pub const ROLE_HISTORY_GRANTED: &str = "granted";
+
+/// Role was removed by an owner, admin or API key
+pub const ROLE_HISTORY_REMOVED: &str = "removed";
+
+/// Time-bounded role lapsed and was removed by the expiry worker
+pub const ROLE_HISTORY_EXPIRED: &str = "expired";
+
+/// Who changed a role assignment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoleChangeActor {
+    /// `user`, `api_key` or `system`
+    pub actor_type: &'static str,
+    pub actor_id: Option<Uuid>,
+}
+
+impl RoleChangeActor {
+    pub fn user(user_id: Uuid) -> Self {
+        Self { actor_type: "user", actor_id: Some(user_id) }
+    }
+
+    pub fn api_key(key_id: Uuid) -> Self {
+        Self { actor_type: "api_key", actor_id: Some(key_id) }
+    }
+
+    pub fn system() -> Self {
+        Self { actor_type: "system", actor_id: None }
+    }
+}
+
+/// Recorded grant or removal of a role
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleAssignmentHistory {
+    pub id: Uuid,
+    pub app_id: Uuid,
+    pub user_id: Uuid,
+    pub role_id: Uuid,
+    /// Role name at the time of the change
+    pub role_name: Option<String>,
+    /// `granted`, `removed` or `expired`
+    pub action: String,
+    pub actor_type: String,
+    pub actor_id: Option<Uuid>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct RoleAssignmentHistoryRow {
+    pub id: String,
+    pub app_id: String,
+    pub user_id: String,
+    pub role_id: String,
+    pub role_name: Option<String>,
+    pub action: String,
+    pub actor_type: String,
+    pub actor_id: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<RoleAssignmentHistoryRow> for RoleAssignmentHistory {
+    fn from(row: RoleAssignmentHistoryRow) -> Self {
+        Self {
+            id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            app_id: Uuid::parse_str(&row.app_id).unwrap_or_default(),
+            user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
+            role_id: Uuid::parse_str(&row.role_id).unwrap_or_default(),
+            role_name: row.role_name,
+            action: row.action,
+            actor_type: row.actor_type,
+            actor_id: row.actor_id.and_then(|id| Uuid::parse_str(&id).ok()),
+            expires_at: row.expires_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for RoleAssignmentHistory {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let history_row = RoleAssignmentHistoryRow::from_row(row)?;
+        Ok(RoleAssignmentHistory::from(history_row))
+    }
+}
@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::utils::field_crypto::{open_optional, EncryptedColumn};
+
 // ============================================================================
 // Audit Log Models
 // ============================================================================
@@ -19,11 +21,15 @@ pub enum AuditAction {
     TokenRefresh,
     AccountLocked,
     AccountUnlocked,
+    /// Flagged by the dormant account policy (and possibly deactivated)
+    AccountDormant,
     MfaEnabled,
     MfaDisabled,
     MfaVerified,
     MfaFailed,
     SessionRevoked,
+    /// Every session, token and device of the user revoked at once
+    AllCredentialsRevoked,
     RoleAssigned,
     RoleRemoved,
     PermissionChanged,
@@ -31,10 +37,56 @@ pub enum AuditAction {
     // Admin actions
     UserUpdated,
     UserDeleted,
+    UserAnonymized,
     UserActivated,
     UserDeactivated,
     AppUpdated,
     AppDeleted,
+    LegalHoldSet,
+    LegalHoldReleased,
+    /// Deletion, anonymization or purge refused because of a legal hold
+    LegalHoldBlocked,
+    // Privacy events
+    DataExportRequested,
+    DeletionRequested,
+    TosAccepted,
+    EmailPreferencesChanged,
+    // Cross-device login
+    QrLoginApproved,
+    QrLoginDenied,
+    // Devices
+    DeviceRegistered,
+    DeviceRevoked,
+    // Devices that skip MFA after completing it once
+    TrustedDeviceAdded,
+    TrustedDeviceRevoked,
+    /// Sign-in from a location the user could not have reached since the previous one
+    ImpossibleTravel,
+    // High-risk actions paused after a credential change
+    CoolingOffStarted,
+    CoolingOffBlocked,
+    // Push login approval
+    PushMfaApproved,
+    PushMfaDenied,
+    // SMS one-time password sent for an enrollment or a login
+    MfaSmsSent,
+    // Email one-time code sent for an MFA step or a passwordless login
+    EmailCodeSent,
+    // Magic link emailed for a passwordless login, and its use
+    MagicLinkSent,
+    MagicLinkUsed,
+    // Refresh from a client other than the one that logged in
+    TokenFingerprintMismatch,
+    /// Rotated refresh token replayed; its session was revoked
+    RefreshTokenReused,
+    // Admin email broadcasts
+    BroadcastScheduled,
+    BroadcastCancelled,
+    EmailBounceCleared,
+    // Second-admin approval of sensitive admin actions
+    AdminActionRequested,
+    AdminActionApproved,
+    AdminActionRejected,
 }
 
 impl AuditAction {
@@ -50,21 +102,54 @@ impl AuditAction {
             AuditAction::TokenRefresh => "token_refresh",
             AuditAction::AccountLocked => "account_locked",
             AuditAction::AccountUnlocked => "account_unlocked",
+            AuditAction::AccountDormant => "account_dormant",
             AuditAction::MfaEnabled => "mfa_enabled",
             AuditAction::MfaDisabled => "mfa_disabled",
             AuditAction::MfaVerified => "mfa_verified",
             AuditAction::MfaFailed => "mfa_failed",
             AuditAction::SessionRevoked => "session_revoked",
+            AuditAction::AllCredentialsRevoked => "all_credentials_revoked",
             AuditAction::RoleAssigned => "role_assigned",
             AuditAction::RoleRemoved => "role_removed",
             AuditAction::PermissionChanged => "permission_changed",
             AuditAction::ProfileUpdated => "profile_updated",
             AuditAction::UserUpdated => "user_updated",
             AuditAction::UserDeleted => "user_deleted",
+            AuditAction::UserAnonymized => "user_anonymized",
             AuditAction::UserActivated => "user_activated",
             AuditAction::UserDeactivated => "user_deactivated",
             AuditAction::AppUpdated => "app_updated",
             AuditAction::AppDeleted => "app_deleted",
+            AuditAction::LegalHoldSet => "legal_hold_set",
+            AuditAction::LegalHoldReleased => "legal_hold_released",
+            AuditAction::LegalHoldBlocked => "legal_hold_blocked",
+            AuditAction::DataExportRequested => "data_export_requested",
+            AuditAction::DeletionRequested => "deletion_requested",
+            AuditAction::TosAccepted => "tos_accepted",
+            AuditAction::EmailPreferencesChanged => "email_preferences_changed",
+            AuditAction::QrLoginApproved => "qr_login_approved",
+            AuditAction::QrLoginDenied => "qr_login_denied",
+            AuditAction::DeviceRegistered => "device_registered",
+            AuditAction::DeviceRevoked => "device_revoked",
+            AuditAction::TrustedDeviceAdded => "trusted_device_added",
+            AuditAction::TrustedDeviceRevoked => "trusted_device_revoked",
+            AuditAction::ImpossibleTravel => "impossible_travel",
+            AuditAction::CoolingOffStarted => "cooling_off_started",
+            AuditAction::CoolingOffBlocked => "cooling_off_blocked",
+            AuditAction::PushMfaApproved => "push_mfa_approved",
+            AuditAction::PushMfaDenied => "push_mfa_denied",
+            AuditAction::MfaSmsSent => "mfa_sms_sent",
+            AuditAction::EmailCodeSent => "email_code_sent",
+            AuditAction::MagicLinkSent => "magic_link_sent",
+            AuditAction::MagicLinkUsed => "magic_link_used",
+            AuditAction::TokenFingerprintMismatch => "token_fingerprint_mismatch",
+            AuditAction::RefreshTokenReused => "refresh_token_reused",
+            AuditAction::BroadcastScheduled => "broadcast_scheduled",
+            AuditAction::BroadcastCancelled => "broadcast_cancelled",
+            AuditAction::EmailBounceCleared => "email_bounce_cleared",
+            AuditAction::AdminActionRequested => "admin_action_requested",
+            AuditAction::AdminActionApproved => "admin_action_approved",
+            AuditAction::AdminActionRejected => "admin_action_rejected",
         }
     }
 }
@@ -80,6 +165,8 @@ pub struct AuditLog {
     pub user_agent: Option<String>,
     pub details: Option<serde_json::Value>,
     pub status: String,
+    /// Request that wrote the row
+    pub correlation_id: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -94,6 +181,8 @@ pub struct AuditLogRow {
     pub user_agent: Option<String>,
     pub details: Option<serde_json::Value>,
     pub status: String,
+    /// Request that wrote the row
+    pub correlation_id: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -109,6 +198,7 @@ impl From<AuditLogRow> for AuditLog {
             user_agent: row.user_agent,
             details: row.details,
             status: row.status,
+            correlation_id: row.correlation_id,
             created_at: row.created_at,
         }
     }
@@ -129,6 +219,10 @@ impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for AuditLog {
 pub struct UserSession {
     pub id: Uuid,
     pub user_id: Uuid,
+    pub device_id: Option<Uuid>,
+    pub app_id: Option<Uuid>,
+    #[allow(dead_code)]
+    #[serde(skip_serializing)]
     pub refresh_token_hash: String,
     pub device_name: Option<String>,
     pub device_type: Option<String>,
@@ -145,6 +239,8 @@ pub struct UserSession {
 pub struct UserSessionRow {
     pub id: String,
     pub user_id: String,
+    pub device_id: Option<String>,
+    pub app_id: Option<String>,
     pub refresh_token_hash: String,
     pub device_name: Option<String>,
     pub device_type: Option<String>,
@@ -162,6 +258,8 @@ impl From<UserSessionRow> for UserSession {
         Self {
             id: Uuid::parse_str(&row.id).unwrap_or_default(),
             user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
+            device_id: row.device_id.and_then(|s| Uuid::parse_str(&s).ok()),
+            app_id: row.app_id.and_then(|s| Uuid::parse_str(&s).ok()),
             refresh_token_hash: row.refresh_token_hash,
             device_name: row.device_name,
             device_type: row.device_type,
@@ -190,6 +288,7 @@ impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for UserSession {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RevokedToken {
     pub id: Uuid,
+    #[serde(skip_serializing)]
     pub token_hash: String,
     pub token_type: String,
     pub user_id: Option<Uuid>,
@@ -292,6 +391,7 @@ pub struct UserMfaMethod {
     pub id: Uuid,
     pub user_id: Uuid,
     pub method_type: String,
+    #[serde(skip_serializing)]
     pub secret_encrypted: Option<String>,
     pub phone_number: Option<String>,
     pub email: Option<String>,
@@ -321,8 +421,8 @@ impl From<UserMfaMethodRow> for UserMfaMethod {
             id: Uuid::parse_str(&row.id).unwrap_or_default(),
             user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
             method_type: row.method_type,
-            secret_encrypted: row.secret_encrypted,
-            phone_number: row.phone_number,
+            secret_encrypted: open_optional(EncryptedColumn::MfaSecret, &row.id, row.secret_encrypted),
+            phone_number: open_optional(EncryptedColumn::MfaPhone, &row.id, row.phone_number),
             email: row.email,
             is_primary: row.is_primary,
             is_verified: row.is_verified,
@@ -343,6 +443,8 @@ impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for UserMfaMethod {
 pub struct UserMfaBackupCode {
     pub id: Uuid,
     pub user_id: Uuid,
+    #[allow(dead_code)]
+    #[serde(skip_serializing)]
     pub code_hash: String,
     pub is_used: bool,
     pub used_at: Option<DateTime<Utc>>,
@@ -378,3 +480,81 @@ impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for UserMfaBackupCode {
         Ok(UserMfaBackupCode::from(code_row))
     }
 }
+
+/// One-time password sent by SMS, to enroll a phone or to complete a login
+///
+/// Only the fields needed to check a guess are loaded.
+#[derive(Debug, Clone)]
+pub struct MfaSmsCode {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub method_id: Uuid,
+    pub code_hash: String,
+    pub attempts: i32,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct MfaSmsCodeRow {
+    pub id: String,
+    pub user_id: String,
+    pub method_id: String,
+    pub code_hash: String,
+    pub attempts: i32,
+}
+
+impl From<MfaSmsCodeRow> for MfaSmsCode {
+    fn from(row: MfaSmsCodeRow) -> Self {
+        Self {
+            id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
+            method_id: Uuid::parse_str(&row.method_id).unwrap_or_default(),
+            code_hash: row.code_hash,
+            attempts: row.attempts,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for MfaSmsCode {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let code_row = MfaSmsCodeRow::from_row(row)?;
+        Ok(MfaSmsCode::from(code_row))
+    }
+}
+
+/// One-time code emailed to the account address, to complete an MFA login
+/// or to sign in to an app without a password
+///
+/// Only the fields needed to check a guess are loaded.
+#[derive(Debug, Clone)]
+pub struct EmailOtpCode {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub code_hash: String,
+    pub attempts: i32,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct EmailOtpCodeRow {
+    pub id: String,
+    pub user_id: String,
+    pub code_hash: String,
+    pub attempts: i32,
+}
+
+impl From<EmailOtpCodeRow> for EmailOtpCode {
+    fn from(row: EmailOtpCodeRow) -> Self {
+        Self {
+            id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
+            code_hash: row.code_hash,
+            attempts: row.attempts,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for EmailOtpCode {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let code_row = EmailOtpCodeRow::from_row(row)?;
+        Ok(EmailOtpCode::from(code_row))
+    }
+}
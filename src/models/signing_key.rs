@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+/// Lifecycle of a token signing key
+pub const SIGNING_KEY_IMPORTED: &str = "imported";
+pub const SIGNING_KEY_NEXT: &str = "next";
+pub const SIGNING_KEY_ACTIVE: &str = "active";
+pub const SIGNING_KEY_RETIRED: &str = "retired";
+
+/// Where a signing key came from
+pub const SIGNING_KEY_SOURCE_CONFIG: &str = "config";
+pub const SIGNING_KEY_SOURCE_IMPORTED: &str = "imported";
+pub const SIGNING_KEY_SOURCE_GENERATED: &str = "generated";
+
+/// RSA key that signs (or signed) tokens
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SigningKeyRecord {
+    pub id: String,
+    /// JWK thumbprint, sent as the JWT `kid`
+    pub kid: String,
+    /// SHA-256 (hex) of the DER public key
+    pub fingerprint: String,
+    pub public_key_pem: String,
+    #[serde(skip_serializing)]
+    pub jwk_n: String,
+    #[serde(skip_serializing)]
+    pub jwk_e: String,
+    /// Sealed PEM private key (None for the configured key)
+    #[serde(skip_serializing)]
+    pub private_key: Option<String>,
+    pub source: String,
+    pub status: String,
+    pub imported_by: Option<String>,
+    pub staged_at: Option<DateTime<Utc>>,
+    pub activated_at: Option<DateTime<Utc>>,
+    pub retired_at: Option<DateTime<Utc>>,
+    /// Promoted by the signing key worker once the staging delay has passed
+    pub auto_promote: bool,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A browser's SSO session, continued by first-party apps on the cookie domain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SsoSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Hosted login session this SSO session continues
+    pub session_id: Uuid,
+    /// Authentication class of the sign-in
+    pub acr: Option<String>,
+    /// Authentication methods of the sign-in (RFC 8176)
+    pub amr: Option<Vec<String>>,
+    /// When the user signed in
+    pub auth_time: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct SsoSessionRow {
+    pub id: String,
+    pub user_id: String,
+    pub session_id: String,
+    pub acr: Option<String>,
+    pub amr: Option<serde_json::Value>,
+    pub auth_time: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<SsoSessionRow> for SsoSession {
+    fn from(row: SsoSessionRow) -> Self {
+        Self {
+            id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
+            session_id: Uuid::parse_str(&row.session_id).unwrap_or_default(),
+            acr: row.acr,
+            amr: row.amr.and_then(|amr| serde_json::from_value(amr).ok()),
+            auth_time: row.auth_time,
+            expires_at: row.expires_at,
+            revoked_at: row.revoked_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for SsoSession {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let sso_row = SsoSessionRow::from_row(row)?;
+        Ok(SsoSession::from(sso_row))
+    }
+}
@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Recorded issuance of a user token
+///
+/// Refresh tokens are always recorded; access tokens are sampled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenIssuance {
+    pub jti: String,
+    /// jti of the refresh token issued at login that started the chain
+    pub family_id: String,
+    /// Refresh token presented to obtain this token (None = issued at login)
+    pub parent_jti: Option<String>,
+    /// `access` or `refresh`
+    pub token_type: String,
+    pub user_id: Uuid,
+    pub session_id: Option<Uuid>,
+    pub app: Option<String>,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// `rotated`, `logout` or `session_revoked`
+    pub revoked_reason: Option<String>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct TokenIssuanceRow {
+    pub jti: String,
+    pub family_id: String,
+    pub parent_jti: Option<String>,
+    pub token_type: String,
+    pub user_id: String,
+    pub session_id: Option<String>,
+    pub app: Option<String>,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub revoked_reason: Option<String>,
+}
+
+impl From<TokenIssuanceRow> for TokenIssuance {
+    fn from(row: TokenIssuanceRow) -> Self {
+        Self {
+            jti: row.jti,
+            family_id: row.family_id,
+            parent_jti: row.parent_jti,
+            token_type: row.token_type,
+            user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
+            session_id: row.session_id.and_then(|id| Uuid::parse_str(&id).ok()),
+            app: row.app,
+            issued_at: row.issued_at,
+            expires_at: row.expires_at,
+            revoked_at: row.revoked_at,
+            revoked_reason: row.revoked_reason,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for TokenIssuance {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let issuance_row = TokenIssuanceRow::from_row(row)?;
+        Ok(TokenIssuance::from(issuance_row))
+    }
+}
@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Trusted device - a browser that may skip MFA after completing it once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedDevice {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row type for MySQL query results
+#[derive(Debug, Clone, FromRow)]
+pub struct TrustedDeviceRow {
+    pub id: String,
+    pub user_id: String,
+    pub token_hash: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<TrustedDeviceRow> for TrustedDevice {
+    fn from(row: TrustedDeviceRow) -> Self {
+        Self {
+            id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
+            token_hash: row.token_hash,
+            ip_address: row.ip_address,
+            user_agent: row.user_agent,
+            last_used_at: row.last_used_at,
+            expires_at: row.expires_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::mysql::MySqlRow> for TrustedDevice {
+    fn from_row(row: &'r sqlx::mysql::MySqlRow) -> Result<Self, sqlx::Error> {
+        let device_row = TrustedDeviceRow::from_row(row)?;
+        Ok(TrustedDevice::from(device_row))
+    }
+}
+
+impl TrustedDevice {
+    /// Check if the device no longer skips MFA
+    pub fn is_expired(&self) -> bool {
+        self.expires_at < Utc::now()
+    }
+}
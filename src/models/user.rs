@@ -3,19 +3,32 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::utils::field_crypto::{open_optional, EncryptedColumn};
+
 /// User domain model
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     pub id: Uuid,
     pub email: String,
+    #[serde(skip_serializing)]
     pub password_hash: String,
     pub name: Option<String>,
     pub avatar_url: Option<String>,
     pub phone: Option<String>,
+    /// Preferred language as a BCP 47 tag (e.g. `en-US`)
+    pub locale: Option<String>,
+    /// Values of the configured extra registration fields, keyed by field name
+    pub metadata: Option<serde_json::Value>,
     pub is_active: bool,
     pub email_verified: bool,
     pub is_system_admin: bool,
     pub mfa_enabled: bool,
+    /// Account is under legal hold: it cannot be deleted or anonymized
+    pub legal_hold: bool,
+    pub legal_hold_reason: Option<String>,
+    /// Dormant account: the email address must be verified again before the next password login
+    #[serde(default)]
+    pub reverify_required: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
 }
@@ -29,10 +42,15 @@ pub struct UserRow {
     pub name: Option<String>,
     pub avatar_url: Option<String>,
     pub phone: Option<String>,
+    pub locale: Option<String>,
+    pub metadata: Option<serde_json::Value>,
     pub is_active: bool,
     pub email_verified: bool,
     pub is_system_admin: bool,
     pub mfa_enabled: bool,
+    pub legal_hold: bool,
+    pub legal_hold_reason: Option<String>,
+    pub reverify_required: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
 }
@@ -45,17 +63,67 @@ impl From<UserRow> for User {
             password_hash: row.password_hash,
             name: row.name,
             avatar_url: row.avatar_url,
-            phone: row.phone,
+            phone: open_optional(EncryptedColumn::UserPhone, &row.id, row.phone),
+            locale: row.locale,
+            metadata: row.metadata,
             is_active: row.is_active,
             email_verified: row.email_verified,
             is_system_admin: row.is_system_admin,
             mfa_enabled: row.mfa_enabled,
+            legal_hold: row.legal_hold,
+            legal_hold_reason: row.legal_hold_reason,
+            reverify_required: row.reverify_required,
             created_at: row.created_at,
             updated_at: row.updated_at,
         }
     }
 }
 
+/// Account without recent activity, as shown in the dormant account report
+#[derive(Debug, Clone, Serialize)]
+pub struct DormantAccount {
+    pub user_id: Uuid,
+    pub email: String,
+    pub name: Option<String>,
+    pub is_active: bool,
+    pub is_system_admin: bool,
+    /// Latest sign-in or session activity (registration time if there was none)
+    pub last_activity_at: DateTime<Utc>,
+    pub days_inactive: i64,
+    /// When the dormant account worker flagged it (None = not flagged yet)
+    pub dormant_at: Option<DateTime<Utc>>,
+    pub reverify_required: bool,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DormantAccountRow {
+    pub id: String,
+    pub email: String,
+    pub name: Option<String>,
+    pub is_active: bool,
+    pub is_system_admin: bool,
+    pub last_activity_at: DateTime<Utc>,
+    pub days_inactive: i64,
+    pub dormant_at: Option<DateTime<Utc>>,
+    pub reverify_required: bool,
+}
+
+impl From<DormantAccountRow> for DormantAccount {
+    fn from(row: DormantAccountRow) -> Self {
+        Self {
+            user_id: Uuid::parse_str(&row.id).unwrap_or_default(),
+            email: row.email,
+            name: row.name,
+            is_active: row.is_active,
+            is_system_admin: row.is_system_admin,
+            last_activity_at: row.last_activity_at,
+            days_inactive: row.days_inactive,
+            dormant_at: row.dormant_at,
+            reverify_required: row.reverify_required,
+        }
+    }
+}
+
 /// Email verification token
 #[derive(Debug, Clone)]
 pub struct EmailVerificationToken {
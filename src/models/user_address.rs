@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Postal address of a user, shaped like the OpenID Connect `address` claim
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct UserAddress {
+    /// Full address as it should be displayed or printed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub street_address: Option<String>,
+    /// City or locality
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locality: Option<String>,
+    /// State, province or region
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+}
+
+impl UserAddress {
+    /// Whether no part of the address is set
+    pub fn is_empty(&self) -> bool {
+        [
+            &self.formatted,
+            &self.street_address,
+            &self.locality,
+            &self.region,
+            &self.postal_code,
+            &self.country,
+        ]
+        .iter()
+        .all(|part| part.as_deref().is_none_or(|p| p.trim().is_empty()))
+    }
+}
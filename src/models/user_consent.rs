@@ -11,6 +11,8 @@ pub struct UserConsent {
     pub user_id: Uuid,
     pub client_id: Uuid,
     pub scopes: Vec<String>,
+    /// Recorded automatically for a first-party client that skips consent
+    pub is_implicit: bool,
     pub granted_at: DateTime<Utc>,
 }
 
@@ -21,6 +23,7 @@ pub struct UserConsentRow {
     pub user_id: String,
     pub client_id: String,
     pub scopes: serde_json::Value,
+    pub is_implicit: bool,
     pub granted_at: DateTime<Utc>,
 }
 
@@ -34,6 +37,7 @@ impl From<UserConsentRow> for UserConsent {
             user_id: Uuid::parse_str(&row.user_id).unwrap_or_default(),
             client_id: Uuid::parse_str(&row.client_id).unwrap_or_default(),
             scopes,
+            is_implicit: row.is_implicit,
             granted_at: row.granted_at,
         }
     }
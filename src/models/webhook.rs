@@ -14,9 +14,16 @@ pub struct Webhook {
     #[sqlx(try_from = "String")]
     pub app_id: Uuid,
     pub url: String,
+    #[serde(skip_serializing)]
     pub secret: String,
     pub events: sqlx::types::Json<Vec<String>>,
     pub is_active: bool,
+    /// Failed deliveries in a row
+    pub consecutive_failures: i32,
+    /// Deliveries are held back until then after repeated failures
+    pub circuit_open_until: Option<DateTime<Utc>>,
+    /// Payload schema the receiver is pinned to
+    pub payload_version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -34,6 +41,26 @@ pub struct WebhookDelivery {
     pub attempts: i32,
     pub next_retry_at: Option<DateTime<Utc>>,
     pub delivered_at: Option<DateTime<Utc>>,
+    /// Outbox event the payload was rendered from
+    pub event_id: Option<String>,
+    /// Re-enqueued through the replay API
+    pub is_replay: bool,
+    /// Request that raised the event
+    pub correlation_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A webhook event as raised, kept in the outbox for replay
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WebhookEventRecord {
+    #[sqlx(try_from = "String")]
+    pub id: Uuid,
+    #[sqlx(try_from = "String")]
+    pub app_id: Uuid,
+    pub event_type: String,
+    pub payload: sqlx::types::Json<serde_json::Value>,
+    /// Request that raised the event
+    pub correlation_id: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -45,6 +72,8 @@ pub enum WebhookEvent {
     UserLogin,
     #[serde(rename = "user.logout")]
     UserLogout,
+    #[serde(rename = "user.logout_all")]
+    UserLogoutAll,
     #[serde(rename = "user.password_changed")]
     UserPasswordChanged,
     #[serde(rename = "user.password_reset")]
@@ -79,6 +108,14 @@ pub enum WebhookEvent {
     RoleAssigned,
     #[serde(rename = "role.removed")]
     RoleRemoved,
+    #[serde(rename = "role.expiring")]
+    RoleExpiring,
+    #[serde(rename = "role.expired")]
+    RoleExpired,
+    #[serde(rename = "role.elevation_requested")]
+    RoleElevationRequested,
+    #[serde(rename = "provisioning.dead_lettered")]
+    ProvisioningDeadLettered,
 }
 
 impl WebhookEvent {
@@ -87,6 +124,7 @@ impl WebhookEvent {
             Self::UserRegistered => "user.registered",
             Self::UserLogin => "user.login",
             Self::UserLogout => "user.logout",
+            Self::UserLogoutAll => "user.logout_all",
             Self::UserPasswordChanged => "user.password_changed",
             Self::UserPasswordReset => "user.password_reset",
             Self::UserEmailVerified => "user.email_verified",
@@ -104,6 +142,10 @@ impl WebhookEvent {
             Self::AppSecretRegenerated => "app.secret_regenerated",
             Self::RoleAssigned => "role.assigned",
             Self::RoleRemoved => "role.removed",
+            Self::RoleExpiring => "role.expiring",
+            Self::RoleExpired => "role.expired",
+            Self::RoleElevationRequested => "role.elevation_requested",
+            Self::ProvisioningDeadLettered => "provisioning.dead_lettered",
         }
     }
 }
@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Last known leader of a periodic background worker
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WorkerLeader {
+    pub worker: String,
+    pub instance_id: String,
+    pub acquired_at: DateTime<Utc>,
+    pub heartbeat_at: DateTime<Utc>,
+    /// Whether the worker's lock is currently held by any instance
+    pub active: bool,
+}
@@ -0,0 +1,265 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, MySqlPool};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{IpRuleSuggestion, SUGGESTION_PENDING, SUGGESTION_REJECTED};
+use crate::utils::abuse_telemetry::AbuseCounts;
+
+/// One IP's rejected requests to one route within a window that crossed a threshold
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AbuseBurst {
+    pub ip_address: String,
+    pub route: String,
+    pub window_start: DateTime<Utc>,
+    pub client_errors: i32,
+    pub rate_limited: i32,
+}
+
+/// Repository for windowed abuse counters and the IP rules suggested from them
+#[derive(Clone)]
+pub struct AbuseTelemetryRepository {
+    pool: MySqlPool,
+}
+
+impl AbuseTelemetryRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Add counts to the (IP, route, window) counter
+    pub async fn add_counts(
+        &self,
+        ip: &str,
+        route: &str,
+        window_start: DateTime<Utc>,
+        counts: AbuseCounts,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO abuse_telemetry (ip_address, route, window_start, client_errors, rate_limited)
+            VALUES (?, ?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                client_errors = client_errors + VALUES(client_errors),
+                rate_limited = rate_limited + VALUES(rate_limited)
+            "#,
+        )
+        .bind(ip)
+        .bind(route)
+        .bind(window_start)
+        .bind(counts.client_errors)
+        .bind(counts.rate_limited)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Windows since `since` reaching either threshold (0 disables a threshold), largest first
+    pub async fn find_bursts(
+        &self,
+        since: DateTime<Utc>,
+        error_threshold: i64,
+        rate_limited_threshold: i64,
+        limit: i64,
+    ) -> Result<Vec<AbuseBurst>, AppError> {
+        let bursts = sqlx::query_as::<_, AbuseBurst>(
+            r#"
+            SELECT ip_address, route, window_start, client_errors, rate_limited
+            FROM abuse_telemetry
+            WHERE window_start >= ?
+              AND ((? > 0 AND client_errors >= ?) OR (? > 0 AND rate_limited >= ?))
+            ORDER BY client_errors + rate_limited DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(since)
+        .bind(error_threshold)
+        .bind(error_threshold)
+        .bind(rate_limited_threshold)
+        .bind(rate_limited_threshold)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(bursts)
+    }
+
+    /// Delete counters of windows that started before `before`
+    pub async fn purge_before(&self, before: DateTime<Utc>) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM abuse_telemetry WHERE window_start < ?")
+            .bind(before)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Create a pending suggestion from a burst
+    pub async fn create_suggestion(&self, burst: &AbuseBurst, reason: &str) -> Result<Uuid, AppError> {
+        let id = Uuid::new_v4();
+
+        sqlx::query(
+            r#"
+            INSERT INTO ip_rule_suggestions
+                (id, ip_address, route, window_start, client_errors, rate_limited, reason, status)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(&burst.ip_address)
+        .bind(&burst.route)
+        .bind(burst.window_start)
+        .bind(burst.client_errors)
+        .bind(burst.rate_limited)
+        .bind(reason)
+        .bind(SUGGESTION_PENDING)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(id)
+    }
+
+    /// Record another burst on a pending suggestion, keeping the largest one
+    pub async fn touch_suggestion(&self, id: &str, burst: &AbuseBurst, reason: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE ip_rule_suggestions
+            SET route = ?, window_start = ?, client_errors = ?, rate_limited = ?, reason = ?
+            WHERE id = ? AND status = ? AND client_errors + rate_limited < ?
+            "#,
+        )
+        .bind(&burst.route)
+        .bind(burst.window_start)
+        .bind(burst.client_errors)
+        .bind(burst.rate_limited)
+        .bind(reason)
+        .bind(id)
+        .bind(SUGGESTION_PENDING)
+        .bind(burst.client_errors + burst.rate_limited)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        sqlx::query("UPDATE ip_rule_suggestions SET last_seen_at = NOW() WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Pending suggestion for an IP, if any
+    pub async fn find_pending_for_ip(&self, ip: &str) -> Result<Option<IpRuleSuggestion>, AppError> {
+        let suggestion = sqlx::query_as::<_, IpRuleSuggestion>(
+            "SELECT * FROM ip_rule_suggestions WHERE ip_address = ? AND status = ? LIMIT 1",
+        )
+        .bind(ip)
+        .bind(SUGGESTION_PENDING)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(suggestion)
+    }
+
+    /// Whether a suggestion for an IP was rejected after `since`
+    pub async fn rejected_since(&self, ip: &str, since: DateTime<Utc>) -> Result<bool, AppError> {
+        let exists = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM ip_rule_suggestions
+            WHERE ip_address = ? AND status = ? AND reviewed_at > ?
+            "#,
+        )
+        .bind(ip)
+        .bind(SUGGESTION_REJECTED)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(exists > 0)
+    }
+
+    /// Whether an unexpired global rule (either type) already names this IP
+    pub async fn has_global_rule(&self, ip: &str) -> Result<bool, AppError> {
+        let exists = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM ip_rules
+            WHERE ip_address = ? AND app_id IS NULL
+              AND (expires_at IS NULL OR expires_at > NOW())
+            "#,
+        )
+        .bind(ip)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(exists > 0)
+    }
+
+    pub async fn find_suggestion(&self, id: Uuid) -> Result<Option<IpRuleSuggestion>, AppError> {
+        let suggestion = sqlx::query_as::<_, IpRuleSuggestion>(
+            "SELECT * FROM ip_rule_suggestions WHERE id = ?",
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(suggestion)
+    }
+
+    /// List suggestions, optionally by status, newest first
+    pub async fn list_suggestions(&self, status: Option<&str>, limit: i64) -> Result<Vec<IpRuleSuggestion>, AppError> {
+        let suggestions = sqlx::query_as::<_, IpRuleSuggestion>(
+            r#"
+            SELECT * FROM ip_rule_suggestions
+            WHERE (? IS NULL OR status = ?)
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(status)
+        .bind(status)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(suggestions)
+    }
+
+    /// Close a pending suggestion; returns false if it was no longer pending
+    pub async fn review_suggestion(
+        &self,
+        id: Uuid,
+        status: &str,
+        ip_rule_id: Option<Uuid>,
+        reviewed_by: Uuid,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE ip_rule_suggestions
+            SET status = ?, ip_rule_id = ?, reviewed_by = ?, reviewed_at = NOW()
+            WHERE id = ? AND status = ?
+            "#,
+        )
+        .bind(status)
+        .bind(ip_rule_id.map(|u| u.to_string()))
+        .bind(reviewed_by.to_string())
+        .bind(id.to_string())
+        .bind(SUGGESTION_PENDING)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,179 @@
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::UserManagementError;
+use crate::models::{
+    AdminApproval, APPROVAL_STATUS_APPROVED, APPROVAL_STATUS_FAILED, APPROVAL_STATUS_PENDING,
+    APPROVAL_STATUS_REJECTED,
+};
+
+const APPROVAL_COLUMNS: &str = "id, action, target_id, params, status, requested_by, decided_by, \
+                                decided_at, error, expires_at, created_at";
+
+/// Repository for admin actions awaiting a second admin's approval
+#[derive(Clone)]
+pub struct AdminApprovalRepository {
+    pool: MySqlPool,
+}
+
+impl AdminApprovalRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a pending approval request
+    pub async fn create(
+        &self,
+        action: &str,
+        target_id: Uuid,
+        params: Option<&serde_json::Value>,
+        requested_by: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<AdminApproval, UserManagementError> {
+        let id = Uuid::new_v4();
+
+        sqlx::query(
+            r#"
+            INSERT INTO admin_approvals (id, action, target_id, params, status, requested_by, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(action)
+        .bind(target_id.to_string())
+        .bind(params.map(sqlx::types::Json))
+        .bind(APPROVAL_STATUS_PENDING)
+        .bind(requested_by.to_string())
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserManagementError::InternalError(e.into()))?;
+
+        self.find_by_id(id).await?.ok_or_else(|| {
+            UserManagementError::InternalError(anyhow::anyhow!("Failed to fetch created approval request"))
+        })
+    }
+
+    /// Find an approval request by ID
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<AdminApproval>, UserManagementError> {
+        let approval = sqlx::query_as::<_, AdminApproval>(&format!(
+            "SELECT {} FROM admin_approvals WHERE id = ?",
+            APPROVAL_COLUMNS
+        ))
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| UserManagementError::InternalError(e.into()))?;
+
+        Ok(approval)
+    }
+
+    /// Unexpired pending request for the same action on the same target
+    pub async fn find_pending(
+        &self,
+        action: &str,
+        target_id: Uuid,
+    ) -> Result<Option<AdminApproval>, UserManagementError> {
+        let approval = sqlx::query_as::<_, AdminApproval>(&format!(
+            r#"
+            SELECT {} FROM admin_approvals
+            WHERE action = ? AND target_id = ? AND status = ? AND expires_at > NOW()
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+            APPROVAL_COLUMNS
+        ))
+        .bind(action)
+        .bind(target_id.to_string())
+        .bind(APPROVAL_STATUS_PENDING)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| UserManagementError::InternalError(e.into()))?;
+
+        Ok(approval)
+    }
+
+    /// List approval requests, newest first, optionally filtered by reported status
+    ///
+    /// `pending` only matches unexpired requests and `expired` the pending
+    /// requests past their expiry.
+    pub async fn list(&self, status: Option<&str>, limit: i64) -> Result<Vec<AdminApproval>, UserManagementError> {
+        let approvals = sqlx::query_as::<_, AdminApproval>(&format!(
+            r#"
+            SELECT {} FROM admin_approvals
+            WHERE ? IS NULL
+               OR (? = 'expired' AND status = 'pending' AND expires_at <= NOW())
+               OR (status = ? AND (status <> 'pending' OR expires_at > NOW()))
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+            APPROVAL_COLUMNS
+        ))
+        .bind(status)
+        .bind(status)
+        .bind(status)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| UserManagementError::InternalError(e.into()))?;
+
+        Ok(approvals)
+    }
+
+    /// Approve a pending, unexpired request made by another admin
+    /// Returns Ok(false) if it was decided, expired or is the approver's own
+    pub async fn approve(&self, id: Uuid, approver_id: Uuid) -> Result<bool, UserManagementError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE admin_approvals
+            SET status = ?, decided_by = ?, decided_at = NOW()
+            WHERE id = ? AND status = ? AND expires_at > NOW() AND requested_by <> ?
+            "#,
+        )
+        .bind(APPROVAL_STATUS_APPROVED)
+        .bind(approver_id.to_string())
+        .bind(id.to_string())
+        .bind(APPROVAL_STATUS_PENDING)
+        .bind(approver_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserManagementError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Reject a pending, unexpired request
+    /// Returns Ok(false) if it was already decided or expired
+    pub async fn reject(&self, id: Uuid, actor_id: Uuid) -> Result<bool, UserManagementError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE admin_approvals
+            SET status = ?, decided_by = ?, decided_at = NOW()
+            WHERE id = ? AND status = ? AND expires_at > NOW()
+            "#,
+        )
+        .bind(APPROVAL_STATUS_REJECTED)
+        .bind(actor_id.to_string())
+        .bind(id.to_string())
+        .bind(APPROVAL_STATUS_PENDING)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserManagementError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record that an approved action could not be executed
+    pub async fn mark_failed(&self, id: Uuid, error: &str) -> Result<(), UserManagementError> {
+        sqlx::query("UPDATE admin_approvals SET status = ?, error = ? WHERE id = ?")
+            .bind(APPROVAL_STATUS_FAILED)
+            .bind(error.chars().take(500).collect::<String>())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+}
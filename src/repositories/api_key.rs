@@ -22,6 +22,7 @@ impl ApiKeyRepository {
         key: &str,
         scopes: Vec<String>,
         expires_at: Option<DateTime<Utc>>,
+        access_window: Option<&str>,
     ) -> Result<ApiKey, AppError> {
         let id = Uuid::new_v4();
         let key_hash = hash_secret(key)?;
@@ -31,8 +32,8 @@ impl ApiKeyRepository {
 
         sqlx::query(
             r#"
-            INSERT INTO api_keys (id, app_id, name, key_hash, key_prefix, scopes, expires_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO api_keys (id, app_id, name, key_hash, key_prefix, scopes, expires_at, access_window)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id.to_string())
@@ -42,6 +43,7 @@ impl ApiKeyRepository {
         .bind(key_prefix)
         .bind(&scopes_json)
         .bind(expires_at)
+        .bind(access_window)
         .execute(&self.pool)
         .await?;
 
@@ -117,6 +119,7 @@ impl ApiKeyRepository {
         name: Option<&str>,
         scopes: Option<Vec<String>>,
         is_active: Option<bool>,
+        access_window: Option<Option<String>>,
     ) -> Result<ApiKey, AppError> {
         if let Some(name) = name {
             sqlx::query("UPDATE api_keys SET name = ? WHERE id = ?")
@@ -144,6 +147,14 @@ impl ApiKeyRepository {
                 .await?;
         }
 
+        if let Some(access_window) = access_window {
+            sqlx::query("UPDATE api_keys SET access_window = ? WHERE id = ?")
+                .bind(access_window)
+                .bind(id.to_string())
+                .execute(&self.pool)
+                .await?;
+        }
+
         self.find_by_id(id).await?.ok_or(AppError::NotFound("API key not found".into()))
     }
 
@@ -82,7 +82,9 @@ impl AppRepository {
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<App>, AppError> {
         let app = sqlx::query_as::<_, App>(
             r#"
-            SELECT id, code, name, owner_id, secret_hash
+            SELECT id, code, name, owner_id, secret_hash, session_idle_timeout_secs, session_absolute_lifetime_secs,
+                   token_binding, token_binding_ip_prefix, deletion_policy, required_acr, sso_enabled, digest_enabled,
+                   email_login_enabled
             FROM apps
             WHERE id = ?
             "#,
@@ -100,7 +102,9 @@ impl AppRepository {
     pub async fn find_by_code(&self, code: &str) -> Result<Option<App>, AppError> {
         let app = sqlx::query_as::<_, App>(
             r#"
-            SELECT id, code, name, owner_id, secret_hash
+            SELECT id, code, name, owner_id, secret_hash, session_idle_timeout_secs, session_absolute_lifetime_secs,
+                   token_binding, token_binding_ip_prefix, deletion_policy, required_acr, sso_enabled, digest_enabled,
+                   email_login_enabled
             FROM apps
             WHERE code = ?
             "#,
@@ -157,7 +161,9 @@ impl AppRepository {
 
         let apps = sqlx::query_as::<_, App>(
             r#"
-            SELECT id, code, name, owner_id, secret_hash
+            SELECT id, code, name, owner_id, secret_hash, session_idle_timeout_secs, session_absolute_lifetime_secs,
+                   token_binding, token_binding_ip_prefix, deletion_policy, required_acr, sso_enabled, digest_enabled,
+                   email_login_enabled
             FROM apps
             WHERE owner_id = ?
             ORDER BY code ASC
@@ -198,7 +204,9 @@ impl AppRepository {
 
         let apps = sqlx::query_as::<_, App>(
             r#"
-            SELECT id, code, name, owner_id, secret_hash
+            SELECT id, code, name, owner_id, secret_hash, session_idle_timeout_secs, session_absolute_lifetime_secs,
+                   token_binding, token_binding_ip_prefix, deletion_policy, required_acr, sso_enabled, digest_enabled,
+                   email_login_enabled
             FROM apps
             ORDER BY code ASC
             LIMIT ? OFFSET ?
@@ -349,6 +357,160 @@ impl AppRepository {
         self.find_by_id(app_id).await?.ok_or(AppError::NotFound("App not found".into()))
     }
 
+    /// Set the session policy overrides for an app (None restores the server default)
+    pub async fn update_session_policy(
+        &self,
+        app_id: Uuid,
+        idle_timeout_secs: Option<i64>,
+        absolute_lifetime_secs: Option<i64>,
+    ) -> Result<App, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE apps
+            SET session_idle_timeout_secs = ?, session_absolute_lifetime_secs = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(idle_timeout_secs)
+        .bind(absolute_lifetime_secs)
+        .bind(app_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("App not found".into()));
+        }
+
+        self.find_by_id(app_id).await?.ok_or(AppError::NotFound("App not found".into()))
+    }
+
+    /// Set what deleting a member of the app does
+    pub async fn update_deletion_policy(&self, app_id: Uuid, deletion_policy: &str) -> Result<App, AppError> {
+        let result = sqlx::query("UPDATE apps SET deletion_policy = ? WHERE id = ?")
+            .bind(deletion_policy)
+            .bind(app_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("App not found".into()));
+        }
+
+        self.find_by_id(app_id).await?.ok_or(AppError::NotFound("App not found".into()))
+    }
+
+    /// Set the minimum acr of sign-ins for the app (None accepts any sign-in)
+    pub async fn update_required_acr(&self, app_id: Uuid, required_acr: Option<&str>) -> Result<App, AppError> {
+        let result = sqlx::query("UPDATE apps SET required_acr = ? WHERE id = ?")
+            .bind(required_acr)
+            .bind(app_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("App not found".into()));
+        }
+
+        self.find_by_id(app_id).await?.ok_or(AppError::NotFound("App not found".into()))
+    }
+
+    /// Allow or refuse sign-ins continued from a browser's SSO session
+    pub async fn update_sso_enabled(&self, app_id: Uuid, enabled: bool) -> Result<App, AppError> {
+        let result = sqlx::query("UPDATE apps SET sso_enabled = ? WHERE id = ?")
+            .bind(enabled)
+            .bind(app_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("App not found".into()));
+        }
+
+        self.find_by_id(app_id).await?.ok_or(AppError::NotFound("App not found".into()))
+    }
+
+    /// Turn the owner's weekly digest on or off
+    pub async fn update_digest_enabled(&self, app_id: Uuid, enabled: bool) -> Result<App, AppError> {
+        let result = sqlx::query("UPDATE apps SET digest_enabled = ? WHERE id = ?")
+            .bind(enabled)
+            .bind(app_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("App not found".into()));
+        }
+
+        self.find_by_id(app_id).await?.ok_or(AppError::NotFound("App not found".into()))
+    }
+
+    /// Accept or refuse passwordless sign-in with emailed codes
+    pub async fn update_email_login_enabled(&self, app_id: Uuid, enabled: bool) -> Result<App, AppError> {
+        let result = sqlx::query("UPDATE apps SET email_login_enabled = ? WHERE id = ?")
+            .bind(enabled)
+            .bind(app_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("App not found".into()));
+        }
+
+        self.find_by_id(app_id).await?.ok_or(AppError::NotFound("App not found".into()))
+    }
+
+    /// Check whether any app the user belongs to anonymizes deleted members
+    pub async fn member_app_requires_anonymization(&self, user_id: Uuid) -> Result<bool, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM user_apps ua
+            INNER JOIN apps a ON a.id = ua.app_id
+            WHERE ua.user_id = ? AND a.deletion_policy = 'anonymize'
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(count > 0)
+    }
+
+    /// Set how app tokens are bound to the caller
+    pub async fn update_token_binding(
+        &self,
+        app_id: Uuid,
+        token_binding: &str,
+        ip_prefix: Option<i64>,
+    ) -> Result<App, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE apps
+            SET token_binding = ?, token_binding_ip_prefix = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(token_binding)
+        .bind(ip_prefix)
+        .bind(app_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("App not found".into()));
+        }
+
+        self.find_by_id(app_id).await?.ok_or(AppError::NotFound("App not found".into()))
+    }
+
     /// Delete an app
     pub async fn delete(&self, app_id: Uuid) -> Result<(), AppError> {
         let result = sqlx::query("DELETE FROM apps WHERE id = ?")
@@ -0,0 +1,244 @@
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    AppDigestStats, ExpiringApiKey, ExpiringApiKeyRow, PendingAppDigest, PendingAppDigestRow,
+    DIGEST_STATUS_PENDING, DIGEST_STATUS_SENT,
+};
+
+/// App whose owner is due a digest
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DigestSubscriptionRow {
+    pub app_id: String,
+    pub owner_id: String,
+}
+
+/// Repository for weekly app owner digests and the figures they report
+#[derive(Clone)]
+pub struct AppDigestRepository {
+    pool: MySqlPool,
+}
+
+impl AppDigestRepository {
+    /// Create a new AppDigestRepository with the given database pool
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Apps with the digest enabled and an active owner that have no digest for the period yet
+    pub async fn find_due(
+        &self,
+        period_start: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<(Uuid, Uuid)>, AppError> {
+        let rows = sqlx::query_as::<_, DigestSubscriptionRow>(
+            r#"
+            SELECT a.id AS app_id, a.owner_id
+            FROM apps a
+            JOIN users u ON u.id = a.owner_id
+            LEFT JOIN app_digests d ON d.app_id = a.id AND d.period_start = ?
+            WHERE a.digest_enabled = TRUE
+              AND u.is_active = TRUE
+              AND d.id IS NULL
+            ORDER BY a.id
+            LIMIT ?
+            "#,
+        )
+        .bind(period_start)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| Some((Uuid::parse_str(&row.app_id).ok()?, Uuid::parse_str(&row.owner_id).ok()?)))
+            .collect())
+    }
+
+    /// Users who joined the app in the period
+    pub async fn count_new_users(
+        &self,
+        app_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM user_apps WHERE app_id = ? AND created_at >= ? AND created_at < ?",
+        )
+        .bind(app_id.to_string())
+        .bind(from)
+        .bind(to)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Users banned from the app in the period (and still banned)
+    pub async fn count_bans(&self, app_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM user_apps
+            WHERE app_id = ? AND status = 'banned' AND banned_at >= ? AND banned_at < ?
+            "#,
+        )
+        .bind(app_id.to_string())
+        .bind(from)
+        .bind(to)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Webhook deliveries created in the period that gave up after their last retry
+    pub async fn count_failed_webhook_deliveries(
+        &self,
+        app_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM webhook_deliveries d
+            JOIN webhooks w ON w.id = d.webhook_id
+            WHERE w.app_id = ?
+              AND d.created_at >= ? AND d.created_at < ?
+              AND d.delivered_at IS NULL
+              AND d.attempts >= 5
+            "#,
+        )
+        .bind(app_id.to_string())
+        .bind(from)
+        .bind(to)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Active API keys of the app expiring before `until`
+    pub async fn find_expiring_api_keys(
+        &self,
+        app_id: Uuid,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<ExpiringApiKey>, AppError> {
+        let rows = sqlx::query_as::<_, ExpiringApiKeyRow>(
+            r#"
+            SELECT name, key_prefix, expires_at
+            FROM api_keys
+            WHERE app_id = ? AND is_active = TRUE
+              AND expires_at IS NOT NULL AND expires_at > NOW() AND expires_at <= ?
+            ORDER BY expires_at
+            "#,
+        )
+        .bind(app_id.to_string())
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(ExpiringApiKey::from).collect())
+    }
+
+    /// Failed password sign-ins to the app in the period
+    pub async fn count_failed_sign_ins(
+        &self,
+        app_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM audit_logs
+            WHERE action = 'login_failed'
+              AND created_at >= ? AND created_at < ?
+              AND JSON_UNQUOTE(JSON_EXTRACT(details, '$.app_id')) = ?
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(app_id.to_string())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Queue the digest of a period; returns false if one was already queued
+    pub async fn enqueue(
+        &self,
+        app_id: Uuid,
+        owner_id: Uuid,
+        period_start: DateTime<Utc>,
+        period_end: DateTime<Utc>,
+        stats: &AppDigestStats,
+    ) -> Result<bool, AppError> {
+        let stats = serde_json::to_value(stats).map_err(|e| AppError::InternalError(e.into()))?;
+        let result = sqlx::query(
+            r#"
+            INSERT IGNORE INTO app_digests (id, app_id, owner_id, period_start, period_end, stats, status)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(app_id.to_string())
+        .bind(owner_id.to_string())
+        .bind(period_start)
+        .bind(period_end)
+        .bind(sqlx::types::Json(stats))
+        .bind(DIGEST_STATUS_PENDING)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Oldest queued digests, flagged if the owner was suppressed or bounced since
+    pub async fn next_pending(&self, limit: i64) -> Result<Vec<PendingAppDigest>, AppError> {
+        let rows = sqlx::query_as::<_, PendingAppDigestRow>(
+            r#"
+            SELECT d.id, a.name AS app_name, u.email AS owner_email, d.period_start, d.period_end, d.stats,
+                   CAST(es.user_id IS NOT NULL OR eb.email IS NOT NULL AS SIGNED) AS suppressed
+            FROM app_digests d
+            JOIN apps a ON a.id = d.app_id
+            JOIN users u ON u.id = d.owner_id
+            LEFT JOIN email_suppressions es ON es.user_id = d.owner_id
+            LEFT JOIN email_bounces eb ON eb.email = u.email
+            WHERE d.status = ?
+            ORDER BY d.created_at, d.id
+            LIMIT ?
+            "#,
+        )
+        .bind(DIGEST_STATUS_PENDING)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(PendingAppDigest::from).collect())
+    }
+
+    /// Record the outcome of a queued digest
+    pub async fn mark(&self, id: Uuid, status: &str, error: Option<&str>) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE app_digests
+            SET status = ?, error = ?, sent_at = IF(? = ?, NOW(), NULL)
+            WHERE id = ? AND status = ?
+            "#,
+        )
+        .bind(status)
+        .bind(error.map(|e| e.chars().take(500).collect::<String>()))
+        .bind(status)
+        .bind(DIGEST_STATUS_SENT)
+        .bind(id.to_string())
+        .bind(DIGEST_STATUS_PENDING)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
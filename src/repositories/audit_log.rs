@@ -3,6 +3,7 @@ use uuid::Uuid;
 
 use crate::error::AuthError;
 use crate::models::{AuditAction, AuditLog};
+use crate::utils::request_id::current_request_id;
 
 /// Repository for audit log database operations
 #[derive(Clone)]
@@ -31,8 +32,8 @@ impl AuditLogRepository {
 
         sqlx::query(
             r#"
-            INSERT INTO audit_logs (id, user_id, action, resource_type, resource_id, ip_address, user_agent, details, status)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO audit_logs (id, user_id, action, resource_type, resource_id, ip_address, user_agent, details, status, correlation_id)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id.to_string())
@@ -44,6 +45,7 @@ impl AuditLogRepository {
         .bind(user_agent)
         .bind(&details)
         .bind(status)
+        .bind(current_request_id())
         .execute(&self.pool)
         .await
         .map_err(|e| AuthError::InternalError(e.into()))?;
@@ -55,7 +57,7 @@ impl AuditLogRepository {
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<AuditLog>, AuthError> {
         let log = sqlx::query_as::<_, AuditLog>(
             r#"
-            SELECT id, user_id, action, resource_type, resource_id, ip_address, user_agent, details, status, created_at
+            SELECT id, user_id, action, resource_type, resource_id, ip_address, user_agent, details, status, correlation_id, created_at
             FROM audit_logs
             WHERE id = ?
             "#,
@@ -79,10 +81,10 @@ impl AuditLogRepository {
 
         let logs = sqlx::query_as::<_, AuditLog>(
             r#"
-            SELECT id, user_id, action, resource_type, resource_id, ip_address, user_agent, details, status, created_at
+            SELECT id, user_id, action, resource_type, resource_id, ip_address, user_agent, details, status, correlation_id, created_at
             FROM audit_logs
             WHERE user_id = ?
-            ORDER BY created_at DESC
+            ORDER BY created_at DESC, id DESC
             LIMIT ? OFFSET ?
             "#,
         )
@@ -101,6 +103,7 @@ impl AuditLogRepository {
         &self,
         action: Option<&str>,
         resource_type: Option<&str>,
+        correlation_id: Option<&str>,
         page: u32,
         limit: u32,
     ) -> Result<Vec<AuditLog>, AuthError> {
@@ -108,11 +111,12 @@ impl AuditLogRepository {
 
         let logs = sqlx::query_as::<_, AuditLog>(
             r#"
-            SELECT id, user_id, action, resource_type, resource_id, ip_address, user_agent, details, status, created_at
+            SELECT id, user_id, action, resource_type, resource_id, ip_address, user_agent, details, status, correlation_id, created_at
             FROM audit_logs
             WHERE (? IS NULL OR action = ?)
               AND (? IS NULL OR resource_type = ?)
-            ORDER BY created_at DESC
+              AND (? IS NULL OR correlation_id = ?)
+            ORDER BY created_at DESC, id DESC
             LIMIT ? OFFSET ?
             "#,
         )
@@ -120,6 +124,8 @@ impl AuditLogRepository {
         .bind(action.unwrap_or(""))
         .bind(resource_type)
         .bind(resource_type.unwrap_or(""))
+        .bind(correlation_id)
+        .bind(correlation_id.unwrap_or(""))
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pool)
@@ -129,12 +135,104 @@ impl AuditLogRepository {
         Ok(logs)
     }
 
+    /// Count audit logs for a user (for pagination)
+    pub async fn count_by_user(&self, user_id: Uuid) -> Result<u64, AuthError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) as count
+            FROM audit_logs
+            WHERE user_id = ?
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(count as u64)
+    }
+
+    /// Count audit logs matching the filters (for pagination)
+    pub async fn count_all(
+        &self,
+        action: Option<&str>,
+        resource_type: Option<&str>,
+        correlation_id: Option<&str>,
+    ) -> Result<u64, AuthError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) as count
+            FROM audit_logs
+            WHERE (? IS NULL OR action = ?)
+              AND (? IS NULL OR resource_type = ?)
+              AND (? IS NULL OR correlation_id = ?)
+            "#,
+        )
+        .bind(action)
+        .bind(action.unwrap_or(""))
+        .bind(resource_type)
+        .bind(resource_type.unwrap_or(""))
+        .bind(correlation_id)
+        .bind(correlation_id.unwrap_or(""))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(count as u64)
+    }
+
+    /// List audit logs where the user is either the actor or the target,
+    /// restricted to the given actions, oldest first
+    pub async fn list_for_subject_by_actions(
+        &self,
+        user_id: Uuid,
+        actions: &[&str],
+    ) -> Result<Vec<AuditLog>, AuthError> {
+        if actions.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let placeholders = vec!["?"; actions.len()].join(", ");
+        let sql = format!(
+            r#"
+            SELECT id, user_id, action, resource_type, resource_id, ip_address, user_agent, details, status, correlation_id, created_at
+            FROM audit_logs
+            WHERE (user_id = ? OR (resource_type = 'user' AND resource_id = ?))
+              AND action IN ({})
+            ORDER BY created_at ASC
+            "#,
+            placeholders
+        );
+
+        let mut query = sqlx::query_as::<_, AuditLog>(&sql)
+            .bind(user_id.to_string())
+            .bind(user_id.to_string());
+        for action in actions {
+            query = query.bind(*action);
+        }
+
+        let logs = query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(logs)
+    }
+
     /// Delete old audit logs (for cleanup)
+    ///
+    /// Rows by or about a user under legal hold are kept.
     pub async fn delete_older_than_days(&self, days: i64) -> Result<u64, AuthError> {
         let result = sqlx::query(
             r#"
             DELETE FROM audit_logs
             WHERE created_at < DATE_SUB(NOW(), INTERVAL ? DAY)
+              AND NOT EXISTS (
+                  SELECT 1 FROM users
+                  WHERE users.legal_hold = TRUE
+                    AND (users.id = audit_logs.user_id
+                         OR (audit_logs.resource_type = 'user' AND users.id = audit_logs.resource_id))
+              )
             "#,
         )
         .bind(days)
@@ -4,6 +4,7 @@ use uuid::Uuid;
 
 use crate::error::OAuthError;
 use crate::models::AuthorizationCode;
+use crate::utils::posture::PostureContext;
 
 /// Repository for authorization code database operations
 /// Requirements: 3.4
@@ -29,7 +30,14 @@ impl AuthorizationCodeRepository {
         scopes: &[String],
         code_challenge: &str,
         code_challenge_method: &str,
+        nonce: Option<&str>,
         expires_in_seconds: i64,
+        session_binding_hash: Option<&str>,
+        browser_binding_hash: Option<&str>,
+        auth_time: Option<DateTime<Utc>>,
+        acr: Option<&str>,
+        amr: Option<&[String]>,
+        posture: Option<&PostureContext>,
     ) -> Result<AuthorizationCode, OAuthError> {
         // Enforce max 10 minutes expiration
         let max_expiration = 600; // 10 minutes in seconds
@@ -43,8 +51,8 @@ impl AuthorizationCodeRepository {
         sqlx::query(
             r#"
             INSERT INTO oauth_authorization_codes 
-            (id, code_hash, client_id, user_id, redirect_uri, scopes, code_challenge, code_challenge_method, expires_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            (id, code_hash, client_id, user_id, redirect_uri, scopes, code_challenge, code_challenge_method, nonce, expires_at, session_binding_hash, browser_binding_hash, auth_time, acr, amr, posture)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id.to_string())
@@ -55,7 +63,14 @@ impl AuthorizationCodeRepository {
         .bind(&scopes_json)
         .bind(code_challenge)
         .bind(code_challenge_method)
+        .bind(nonce)
         .bind(expires_at)
+        .bind(session_binding_hash)
+        .bind(browser_binding_hash)
+        .bind(auth_time)
+        .bind(acr)
+        .bind(amr.map(|amr| serde_json::json!(amr)))
+        .bind(posture.map(|posture| serde_json::json!(posture)))
         .execute(&self.pool)
         .await
         .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
@@ -70,7 +85,7 @@ impl AuthorizationCodeRepository {
         let code = sqlx::query_as::<_, AuthorizationCode>(
             r#"
             SELECT id, code_hash, client_id, user_id, redirect_uri, scopes, 
-                   code_challenge, code_challenge_method, expires_at, used, created_at
+                   code_challenge, code_challenge_method, nonce, expires_at, used, session_binding_hash, browser_binding_hash, auth_time, acr, amr, posture, created_at
             FROM oauth_authorization_codes
             WHERE id = ?
             "#,
@@ -88,7 +103,7 @@ impl AuthorizationCodeRepository {
         let code = sqlx::query_as::<_, AuthorizationCode>(
             r#"
             SELECT id, code_hash, client_id, user_id, redirect_uri, scopes, 
-                   code_challenge, code_challenge_method, expires_at, used, created_at
+                   code_challenge, code_challenge_method, nonce, expires_at, used, session_binding_hash, browser_binding_hash, auth_time, acr, amr, posture, created_at
             FROM oauth_authorization_codes
             WHERE code_hash = ?
             "#,
@@ -101,12 +116,29 @@ impl AuthorizationCodeRepository {
         Ok(code)
     }
 
+    /// Check whether a client has already used a nonce in an earlier authorization
+    pub async fn nonce_exists(&self, client_id: Uuid, nonce: &str) -> Result<bool, OAuthError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM oauth_authorization_codes
+            WHERE client_id = ? AND nonce = ?
+            "#,
+        )
+        .bind(client_id.to_string())
+        .bind(nonce)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(count > 0)
+    }
+
     /// Find a valid (not used, not expired) authorization code by its hash
     pub async fn find_valid_by_code_hash(&self, code_hash: &str) -> Result<Option<AuthorizationCode>, OAuthError> {
         let code = sqlx::query_as::<_, AuthorizationCode>(
             r#"
             SELECT id, code_hash, client_id, user_id, redirect_uri, scopes, 
-                   code_challenge, code_challenge_method, expires_at, used, created_at
+                   code_challenge, code_challenge_method, nonce, expires_at, used, session_binding_hash, browser_binding_hash, auth_time, acr, amr, posture, created_at
             FROM oauth_authorization_codes
             WHERE code_hash = ? AND used = false AND expires_at > NOW()
             "#,
@@ -121,12 +153,15 @@ impl AuthorizationCodeRepository {
 
     /// Mark an authorization code as used
     /// Requirements: 3.4 - Authorization codes are single-use
-    pub async fn mark_as_used(&self, id: Uuid) -> Result<(), OAuthError> {
+    ///
+    /// Atomic compare-and-set: returns false if the code was already used or
+    /// expired, so of two concurrent redemptions only one can win.
+    pub async fn mark_as_used(&self, id: Uuid) -> Result<bool, OAuthError> {
         let result = sqlx::query(
             r#"
             UPDATE oauth_authorization_codes
             SET used = true
-            WHERE id = ? AND used = false
+            WHERE id = ? AND used = false AND expires_at > NOW()
             "#,
         )
         .bind(id.to_string())
@@ -134,11 +169,7 @@ impl AuthorizationCodeRepository {
         .await
         .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
 
-        if result.rows_affected() == 0 {
-            return Err(OAuthError::InvalidGrant("Authorization code already used or not found".to_string()));
-        }
-
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
 
     /// Delete an authorization code
@@ -208,6 +239,22 @@ impl AuthorizationCodeRepository {
         Ok(result.rows_affected())
     }
 
+    /// Delete every authorization code issued to a user, redeemed or not
+    pub async fn delete_for_user(&self, user_id: Uuid) -> Result<u64, OAuthError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM oauth_authorization_codes
+            WHERE user_id = ?
+            "#,
+        )
+        .bind(user_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Get expiration time for a code
     pub async fn get_expiration(&self, id: Uuid) -> Result<Option<DateTime<Utc>>, OAuthError> {
         let expires_at = sqlx::query_scalar::<_, DateTime<Utc>>(
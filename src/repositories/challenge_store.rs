@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+
+use crate::error::AppError;
+
+/// Expired entries removed per write, so purging never holds up a request
+const PURGE_BATCH_SIZE: i64 = 100;
+
+/// Repository for the database backend of the challenge store
+#[derive(Clone)]
+pub struct ChallengeStoreRepository {
+    pool: MySqlPool,
+}
+
+impl ChallengeStoreRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a value, replacing any earlier value under the same key
+    pub async fn put(
+        &self,
+        kind: &str,
+        key: &str,
+        value: &serde_json::Value,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO challenge_store (kind, challenge_key, value, expires_at)
+            VALUES (?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE value = VALUES(value), expires_at = VALUES(expires_at)
+            "#,
+        )
+        .bind(kind)
+        .bind(key)
+        .bind(value)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Read a value that has not expired
+    pub async fn get(&self, kind: &str, key: &str) -> Result<Option<serde_json::Value>, AppError> {
+        let value = sqlx::query_scalar::<_, serde_json::Value>(
+            r#"
+            SELECT value FROM challenge_store
+            WHERE kind = ? AND challenge_key = ? AND expires_at > NOW()
+            "#,
+        )
+        .bind(kind)
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(value)
+    }
+
+    /// Replace a value that has not expired, only if it still equals `current`
+    /// Returns false if the value changed or expired meanwhile
+    pub async fn replace(
+        &self,
+        kind: &str,
+        key: &str,
+        current: &serde_json::Value,
+        value: &serde_json::Value,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE challenge_store
+            SET value = ?
+            WHERE kind = ? AND challenge_key = ? AND value = CAST(? AS JSON) AND expires_at > NOW()
+            "#,
+        )
+        .bind(value)
+        .bind(kind)
+        .bind(key)
+        .bind(current.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a value; returns false if it was already gone
+    pub async fn delete(&self, kind: &str, key: &str) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM challenge_store WHERE kind = ? AND challenge_key = ?")
+            .bind(kind)
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a batch of expired values
+    pub async fn purge_expired(&self) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM challenge_store WHERE expires_at < NOW() LIMIT ?")
+            .bind(PURGE_BATCH_SIZE)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,65 @@
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::OAuthError;
+use crate::models::ClientJwks;
+
+/// Repository for the public keys clients sign request objects with
+#[derive(Clone)]
+pub struct ClientJwksRepository {
+    pool: MySqlPool,
+}
+
+impl ClientJwksRepository {
+    /// Create a new ClientJwksRepository with the given database pool
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find the JWK Set registered for a client
+    pub async fn find(&self, client_id: Uuid) -> Result<Option<ClientJwks>, OAuthError> {
+        let jwks = sqlx::query_as::<_, ClientJwks>(
+            r#"
+            SELECT client_id, jwks, updated_at
+            FROM oauth_client_jwks
+            WHERE client_id = ?
+            "#,
+        )
+        .bind(client_id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(jwks)
+    }
+
+    /// Register or replace a client's JWK Set
+    pub async fn upsert(&self, client_id: Uuid, jwks: &serde_json::Value) -> Result<(), OAuthError> {
+        sqlx::query(
+            r#"
+            INSERT INTO oauth_client_jwks (client_id, jwks)
+            VALUES (?, ?)
+            ON DUPLICATE KEY UPDATE jwks = VALUES(jwks)
+            "#,
+        )
+        .bind(client_id.to_string())
+        .bind(jwks)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Remove a client's JWK Set
+    /// Returns false if none was registered
+    pub async fn delete(&self, client_id: Uuid) -> Result<bool, OAuthError> {
+        let result = sqlx::query("DELETE FROM oauth_client_jwks WHERE client_id = ?")
+            .bind(client_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
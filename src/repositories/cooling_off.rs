@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::CoolingOff;
+use crate::utils::cooling_off::CoolingOffReason;
+
+/// Repository for credential cooling-off periods
+#[derive(Clone)]
+pub struct CoolingOffRepository {
+    pool: MySqlPool,
+}
+
+impl CoolingOffRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Start a period until `ends_at`; a running one is only ever extended
+    pub async fn start(
+        &self,
+        user_id: Uuid,
+        reason: CoolingOffReason,
+        ends_at: DateTime<Utc>,
+    ) -> Result<CoolingOff, AuthError> {
+        sqlx::query(
+            r#"
+            INSERT INTO credential_cooling_off (user_id, reason, ends_at)
+            VALUES (?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                reason = VALUES(reason),
+                started_at = IF(ends_at > NOW(), started_at, NOW()),
+                ends_at = GREATEST(ends_at, VALUES(ends_at))
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(reason.as_str())
+        .bind(ends_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        self.find_active(user_id)
+            .await?
+            .ok_or(AuthError::InternalError(anyhow::anyhow!("Failed to fetch started cooling-off")))
+    }
+
+    /// The user's running period, if any
+    pub async fn find_active(&self, user_id: Uuid) -> Result<Option<CoolingOff>, AuthError> {
+        let cooling_off = sqlx::query_as::<_, CoolingOff>(
+            r#"
+            SELECT user_id, reason, started_at, ends_at
+            FROM credential_cooling_off
+            WHERE user_id = ? AND ends_at > NOW()
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(cooling_off)
+    }
+}
@@ -0,0 +1,133 @@
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::UserDevice;
+
+/// Repository for registered device database operations
+#[derive(Clone)]
+pub struct DeviceRepository {
+    pool: MySqlPool,
+}
+
+impl DeviceRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Register a new device for a user
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        platform: &str,
+        device_name: Option<&str>,
+        push_token: Option<&str>,
+        signing_secret: &str,
+    ) -> Result<UserDevice, AuthError> {
+        let id = Uuid::new_v4();
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_devices (id, user_id, platform, device_name, push_token, signing_secret)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(user_id.to_string())
+        .bind(platform)
+        .bind(device_name)
+        .bind(push_token)
+        .bind(signing_secret)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        self.find_by_id(id).await?.ok_or(AuthError::InternalError(anyhow::anyhow!("Failed to fetch created device")))
+    }
+
+    /// Find device by ID
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<UserDevice>, AuthError> {
+        let device = sqlx::query_as::<_, UserDevice>(
+            r#"
+            SELECT id, user_id, platform, device_name, push_token, signing_secret, is_active, last_seen_at, revoked_at, created_at
+            FROM user_devices
+            WHERE id = ?
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(device)
+    }
+
+    /// List active devices for a user
+    pub async fn list_active_by_user(&self, user_id: Uuid) -> Result<Vec<UserDevice>, AuthError> {
+        let devices = sqlx::query_as::<_, UserDevice>(
+            r#"
+            SELECT id, user_id, platform, device_name, push_token, signing_secret, is_active, last_seen_at, revoked_at, created_at
+            FROM user_devices
+            WHERE user_id = ? AND is_active = TRUE
+            ORDER BY last_seen_at DESC
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(devices)
+    }
+
+    /// Update last seen timestamp
+    pub async fn touch(&self, id: Uuid) -> Result<(), AuthError> {
+        sqlx::query(
+            r#"
+            UPDATE user_devices
+            SET last_seen_at = NOW()
+            WHERE id = ?
+            "#,
+        )
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Deactivate a device
+    pub async fn revoke(&self, id: Uuid) -> Result<(), AuthError> {
+        sqlx::query(
+            r#"
+            UPDATE user_devices
+            SET is_active = FALSE, revoked_at = NOW(), push_token = NULL
+            WHERE id = ?
+            "#,
+        )
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Deactivate every active device of a user
+    pub async fn revoke_all_for_user(&self, user_id: Uuid) -> Result<u64, AuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_devices
+            SET is_active = FALSE, revoked_at = NOW(), push_token = NULL
+            WHERE user_id = ? AND is_active = TRUE
+            "#,
+        )
+        .bind(user_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,196 @@
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::{DormantAccount, DormantAccountRow};
+use crate::utils::dormancy::DormantAction;
+
+/// Accounts with their latest activity: the last sign-in or session use,
+/// or the registration time if there was none. Anonymized accounts are left out.
+const ACCOUNT_ACTIVITY_SQL: &str = r#"
+    SELECT u.id, u.email, u.name, u.is_active, u.is_system_admin, u.dormant_at, u.reverify_required,
+           GREATEST(
+               COALESCE(u.last_activity_at, u.created_at),
+               COALESCE((SELECT MAX(s.last_active_at) FROM user_sessions s WHERE s.user_id = u.id), u.created_at)
+           ) AS last_activity_at
+    FROM users u
+    WHERE u.anonymized_at IS NULL
+"#;
+
+/// Repository for the dormant account policy
+#[derive(Clone)]
+pub struct DormantAccountRepository {
+    pool: MySqlPool,
+}
+
+impl DormantAccountRepository {
+    /// Create a new DormantAccountRepository with the given database pool
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    fn select(condition: &str, suffix: &str) -> String {
+        format!(
+            r#"
+            SELECT a.id, a.email, a.name, a.is_active, a.is_system_admin, a.last_activity_at,
+                   TIMESTAMPDIFF(DAY, a.last_activity_at, NOW()) AS days_inactive,
+                   a.dormant_at, a.reverify_required
+            FROM ({}) a
+            WHERE {}
+            {}
+            "#,
+            ACCOUNT_ACTIVITY_SQL, condition, suffix
+        )
+    }
+
+    /// List accounts inactive for at least `days` days, least recently active first
+    ///
+    /// `flagged` restricts the list to accounts the worker has (or has not yet) flagged.
+    pub async fn list(
+        &self,
+        days: i64,
+        flagged: Option<bool>,
+        page: u32,
+        limit: u32,
+    ) -> Result<Vec<DormantAccount>, AuthError> {
+        let offset = (page.saturating_sub(1)) * limit;
+        let query = Self::select(
+            "a.last_activity_at <= NOW() - INTERVAL ? DAY AND (? IS NULL OR (a.dormant_at IS NOT NULL) = ?)",
+            "ORDER BY a.last_activity_at ASC, a.id LIMIT ? OFFSET ?",
+        );
+
+        let rows = sqlx::query_as::<_, DormantAccountRow>(&query)
+            .bind(days)
+            .bind(flagged)
+            .bind(flagged.unwrap_or(false))
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(rows.into_iter().map(DormantAccount::from).collect())
+    }
+
+    /// Count accounts inactive for at least `days` days
+    pub async fn count(&self, days: i64, flagged: Option<bool>) -> Result<u64, AuthError> {
+        let query = format!(
+            r#"
+            SELECT COUNT(*)
+            FROM ({}) a
+            WHERE a.last_activity_at <= NOW() - INTERVAL ? DAY
+              AND (? IS NULL OR (a.dormant_at IS NOT NULL) = ?)
+            "#,
+            ACCOUNT_ACTIVITY_SQL
+        );
+
+        let count = sqlx::query_scalar::<_, i64>(&query)
+            .bind(days)
+            .bind(flagged)
+            .bind(flagged.unwrap_or(false))
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(count as u64)
+    }
+
+    /// Find active accounts that become dormant within `window` days and have
+    /// not been warned for this window (or a shorter one) since their last activity
+    ///
+    /// System admins are left out: the action is never applied to them.
+    pub async fn find_notices_due(
+        &self,
+        days: i64,
+        window: i64,
+        limit: i64,
+    ) -> Result<Vec<DormantAccount>, AuthError> {
+        let query = Self::select(
+            r#"a.is_active = TRUE
+              AND a.is_system_admin = FALSE
+              AND a.dormant_at IS NULL
+              AND a.last_activity_at <= NOW() - INTERVAL ? DAY
+              AND NOT EXISTS (
+                  SELECT 1 FROM dormant_account_notices n
+                  WHERE n.user_id = a.id AND n.activity_at = a.last_activity_at AND n.days_before <= ?
+              )"#,
+            "ORDER BY a.last_activity_at ASC LIMIT ?",
+        );
+
+        let rows = sqlx::query_as::<_, DormantAccountRow>(&query)
+            .bind(days - window)
+            .bind(window)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(rows.into_iter().map(DormantAccount::from).collect())
+    }
+
+    /// Record that the owner was warned `days_before` days before the action
+    pub async fn record_notice(
+        &self,
+        user_id: Uuid,
+        activity_at: DateTime<Utc>,
+        days_before: i64,
+    ) -> Result<(), AuthError> {
+        sqlx::query(
+            r#"
+            INSERT IGNORE INTO dormant_account_notices (user_id, activity_at, days_before)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(activity_at)
+        .bind(days_before)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Find active accounts inactive for at least `days` days that are not flagged yet
+    pub async fn find_newly_dormant(&self, days: i64, limit: i64) -> Result<Vec<DormantAccount>, AuthError> {
+        let query = Self::select(
+            "a.is_active = TRUE AND a.dormant_at IS NULL AND a.last_activity_at <= NOW() - INTERVAL ? DAY",
+            "ORDER BY a.last_activity_at ASC LIMIT ?",
+        );
+
+        let rows = sqlx::query_as::<_, DormantAccountRow>(&query)
+            .bind(days)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(rows.into_iter().map(DormantAccount::from).collect())
+    }
+
+    /// Flag an account as dormant and apply the action
+    ///
+    /// Returns false if the account was already flagged.
+    pub async fn mark_dormant(&self, user_id: Uuid, action: DormantAction) -> Result<bool, AuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET dormant_at = NOW(),
+                is_active = IF(? = 'deactivate', FALSE, is_active),
+                email_verified = IF(? = 'reverify', FALSE, email_verified),
+                reverify_required = IF(? = 'reverify', TRUE, reverify_required)
+            WHERE id = ? AND dormant_at IS NULL
+            "#,
+        )
+        .bind(action.as_str())
+        .bind(action.as_str())
+        .bind(action.as_str())
+        .bind(user_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
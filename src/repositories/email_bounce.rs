@@ -0,0 +1,87 @@
+use sqlx::MySqlPool;
+
+use crate::error::AuthError;
+use crate::models::EmailBounce;
+
+/// Repository for addresses that hard bounced or complained
+#[derive(Clone)]
+pub struct EmailBounceRepository {
+    pool: MySqlPool,
+}
+
+impl EmailBounceRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a bounce or complaint; repeated events update the kind and detail
+    pub async fn record(
+        &self,
+        email: &str,
+        kind: &str,
+        provider: &str,
+        detail: Option<&str>,
+    ) -> Result<(), AuthError> {
+        sqlx::query(
+            r#"
+            INSERT INTO email_bounces (email, kind, provider, detail)
+            VALUES (?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                kind = VALUES(kind),
+                provider = VALUES(provider),
+                detail = VALUES(detail),
+                event_count = event_count + 1,
+                last_event_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(email.trim().to_lowercase())
+        .bind(kind)
+        .bind(provider)
+        .bind(detail.map(|d| d.chars().take(500).collect::<String>()))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Whether email to the address is suppressed
+    pub async fn is_bounced(&self, email: &str) -> Result<bool, AuthError> {
+        let found: Option<(i32,)> = sqlx::query_as("SELECT 1 FROM email_bounces WHERE email = ?")
+            .bind(email.trim().to_lowercase())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(found.is_some())
+    }
+
+    /// Suppressed addresses, most recent event first
+    pub async fn list(&self, limit: i64) -> Result<Vec<EmailBounce>, AuthError> {
+        let bounces = sqlx::query_as::<_, EmailBounce>(
+            r#"
+            SELECT email, kind, provider, detail, event_count, created_at, last_event_at
+            FROM email_bounces
+            ORDER BY last_event_at DESC, email
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(bounces)
+    }
+
+    /// Send to the address again; returns false if it was not suppressed
+    pub async fn remove(&self, email: &str) -> Result<bool, AuthError> {
+        let result = sqlx::query("DELETE FROM email_bounces WHERE email = ?")
+            .bind(email.trim().to_lowercase())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
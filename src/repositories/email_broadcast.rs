@@ -0,0 +1,406 @@
+use chrono::{DateTime, Utc};
+use sqlx::mysql::MySqlArguments;
+use sqlx::query::Query;
+use sqlx::{FromRow, MySql, MySqlPool, Row};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    BroadcastRecipient, BroadcastRecipientRow, BroadcastSegment, EmailBroadcast,
+    BROADCAST_STATUS_CANCELLED, BROADCAST_STATUS_SCHEDULED, BROADCAST_STATUS_SENDING,
+    BROADCAST_STATUS_SENT, RECIPIENT_STATUS_FAILED, RECIPIENT_STATUS_PENDING, RECIPIENT_STATUS_SENT,
+    RECIPIENT_STATUS_SUPPRESSED,
+};
+
+const BROADCAST_COLUMNS: &str = r#"
+    id, subject, body, segment, status, scheduled_at, created_by, recipient_count,
+    sent_count, failed_count, suppressed_count, created_at, started_at, completed_at
+"#;
+
+/// Users of a segment, as `u`; bound with [`bind_segment`]
+///
+/// Deactivated and anonymized users are never part of a segment.
+const SEGMENT_FILTER: &str = r#"
+    u.is_active = TRUE
+    AND u.anonymized_at IS NULL
+    AND (? IS NULL OR EXISTS (
+        SELECT 1 FROM user_apps ua
+        WHERE ua.user_id = u.id AND ua.app_id = ? AND ua.status = 'active'))
+    AND (? IS NULL OR EXISTS (
+        SELECT 1 FROM user_app_roles uar
+        WHERE uar.user_id = u.id AND uar.role_id = ?
+          AND (uar.expires_at IS NULL OR uar.expires_at > NOW())))
+    AND (? IS NULL OR EXISTS (
+        SELECT 1 FROM user_sessions s
+        WHERE s.user_id = u.id AND s.last_active_at >= NOW() - INTERVAL ? DAY))
+    AND (? IS NULL OR NOT EXISTS (
+        SELECT 1 FROM user_sessions s
+        WHERE s.user_id = u.id AND s.last_active_at >= NOW() - INTERVAL ? DAY))
+    AND (? IS NULL OR u.email_verified = ?)
+"#;
+
+/// Whether user `u` gets no broadcasts: suppressed, or their address bounced
+const SUPPRESSED: &str = r#"(
+    EXISTS (SELECT 1 FROM email_suppressions es WHERE es.user_id = u.id)
+    OR EXISTS (SELECT 1 FROM email_bounces eb WHERE eb.email = u.email))
+"#;
+
+/// Bind the parameters of [`SEGMENT_FILTER`] in order
+fn bind_segment<'q>(
+    query: Query<'q, MySql, MySqlArguments>,
+    segment: &BroadcastSegment,
+) -> Query<'q, MySql, MySqlArguments> {
+    let app_id = segment.app_id.map(|id| id.to_string());
+    let role_id = segment.role_id.map(|id| id.to_string());
+
+    query
+        .bind(app_id.clone())
+        .bind(app_id.unwrap_or_default())
+        .bind(role_id.clone())
+        .bind(role_id.unwrap_or_default())
+        .bind(segment.active_within_days)
+        .bind(segment.active_within_days.unwrap_or(0))
+        .bind(segment.inactive_for_days)
+        .bind(segment.inactive_for_days.unwrap_or(0))
+        .bind(segment.email_verified)
+        .bind(segment.email_verified.unwrap_or(false))
+}
+
+/// Repository for admin email broadcasts and their recipient queue
+#[derive(Clone)]
+pub struct EmailBroadcastRepository {
+    pool: MySqlPool,
+}
+
+impl EmailBroadcastRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Schedule a broadcast
+    pub async fn create(
+        &self,
+        subject: &str,
+        body: &str,
+        segment: &BroadcastSegment,
+        scheduled_at: DateTime<Utc>,
+        created_by: Option<Uuid>,
+    ) -> Result<EmailBroadcast, AppError> {
+        let id = Uuid::new_v4();
+
+        sqlx::query(
+            r#"
+            INSERT INTO email_broadcasts (id, subject, body, segment, status, scheduled_at, created_by)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(subject)
+        .bind(body)
+        .bind(sqlx::types::Json(segment))
+        .bind(BROADCAST_STATUS_SCHEDULED)
+        .bind(scheduled_at)
+        .bind(created_by.map(|id| id.to_string()))
+        .execute(&self.pool)
+        .await?;
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::InternalError(anyhow::anyhow!("Failed to fetch created broadcast")))
+    }
+
+    /// Find a broadcast by its UUID
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<EmailBroadcast>, AppError> {
+        let broadcast = sqlx::query_as::<_, EmailBroadcast>(&format!(
+            "SELECT {} FROM email_broadcasts WHERE id = ?",
+            BROADCAST_COLUMNS
+        ))
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(broadcast)
+    }
+
+    /// Most recent broadcasts first
+    pub async fn list(&self, limit: i64) -> Result<Vec<EmailBroadcast>, AppError> {
+        let broadcasts = sqlx::query_as::<_, EmailBroadcast>(&format!(
+            "SELECT {} FROM email_broadcasts ORDER BY created_at DESC, id LIMIT ?",
+            BROADCAST_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(broadcasts)
+    }
+
+    /// Cancel a broadcast that has not finished; returns false otherwise
+    pub async fn cancel(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE email_broadcasts
+            SET status = ?, completed_at = NOW()
+            WHERE id = ? AND status IN (?, ?)
+            "#,
+        )
+        .bind(BROADCAST_STATUS_CANCELLED)
+        .bind(id.to_string())
+        .bind(BROADCAST_STATUS_SCHEDULED)
+        .bind(BROADCAST_STATUS_SENDING)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Count the users of a segment and how many of them are suppressed
+    pub async fn count_segment(&self, segment: &BroadcastSegment) -> Result<(i64, i64), AppError> {
+        let query = format!(
+            r#"
+            SELECT COUNT(*) AS matching,
+                   CAST(COALESCE(SUM({}), 0) AS SIGNED) AS suppressed
+            FROM users u
+            WHERE {}
+            "#,
+            SUPPRESSED, SEGMENT_FILTER
+        );
+
+        let row = bind_segment(sqlx::query(&query), segment)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok((row.try_get("matching")?, row.try_get("suppressed")?))
+    }
+
+    /// First users of a segment that would receive a broadcast
+    pub async fn sample_segment(
+        &self,
+        segment: &BroadcastSegment,
+        limit: i64,
+    ) -> Result<Vec<BroadcastRecipient>, AppError> {
+        let query = format!(
+            r#"
+            SELECT u.id AS user_id, u.email, u.name, CAST(0 AS SIGNED) AS suppressed
+            FROM users u
+            WHERE {}
+              AND NOT {}
+            ORDER BY u.created_at, u.id
+            LIMIT ?
+            "#,
+            SEGMENT_FILTER, SUPPRESSED
+        );
+
+        let rows = bind_segment(sqlx::query(&query), segment)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter()
+            .map(|row| Ok(BroadcastRecipientRow::from_row(row)?.into()))
+            .collect()
+    }
+
+    /// Scheduled broadcasts whose time has come
+    pub async fn find_due(&self, limit: i64) -> Result<Vec<EmailBroadcast>, AppError> {
+        let broadcasts = sqlx::query_as::<_, EmailBroadcast>(&format!(
+            r#"
+            SELECT {} FROM email_broadcasts
+            WHERE status = ? AND scheduled_at <= NOW()
+            ORDER BY scheduled_at, id
+            LIMIT ?
+            "#,
+            BROADCAST_COLUMNS
+        ))
+        .bind(BROADCAST_STATUS_SCHEDULED)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(broadcasts)
+    }
+
+    /// Broadcasts with recipients being sent to, oldest first
+    pub async fn find_sending(&self) -> Result<Vec<EmailBroadcast>, AppError> {
+        let broadcasts = sqlx::query_as::<_, EmailBroadcast>(&format!(
+            "SELECT {} FROM email_broadcasts WHERE status = ? ORDER BY started_at, id",
+            BROADCAST_COLUMNS
+        ))
+        .bind(BROADCAST_STATUS_SENDING)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(broadcasts)
+    }
+
+    /// Select the recipients of a scheduled broadcast and start sending it
+    ///
+    /// Suppressed users and bounced addresses are left out of the queue and
+    /// counted. Returns false if the broadcast was no longer scheduled
+    /// (cancelled, or started by an earlier leader).
+    pub async fn start(&self, broadcast: &EmailBroadcast) -> Result<bool, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let started = sqlx::query(
+            "UPDATE email_broadcasts SET status = ?, started_at = NOW() WHERE id = ? AND status = ?",
+        )
+        .bind(BROADCAST_STATUS_SENDING)
+        .bind(broadcast.id.to_string())
+        .bind(BROADCAST_STATUS_SCHEDULED)
+        .execute(&mut *tx)
+        .await?;
+
+        if started.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        let insert = format!(
+            r#"
+            INSERT IGNORE INTO email_broadcast_recipients (broadcast_id, user_id, email, status)
+            SELECT ?, u.id, u.email, ?
+            FROM users u
+            WHERE {}
+              AND NOT {}
+            "#,
+            SEGMENT_FILTER, SUPPRESSED
+        );
+        let queued = bind_segment(
+            sqlx::query(&insert)
+                .bind(broadcast.id.to_string())
+                .bind(RECIPIENT_STATUS_PENDING),
+            &broadcast.segment,
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        let count = format!(
+            r#"
+            SELECT COUNT(*) FROM users u
+            WHERE {}
+              AND {}
+            "#,
+            SEGMENT_FILTER, SUPPRESSED
+        );
+        let suppressed: i64 = bind_segment(sqlx::query(&count), &broadcast.segment)
+            .fetch_one(&mut *tx)
+            .await?
+            .try_get(0)?;
+
+        sqlx::query(
+            "UPDATE email_broadcasts SET recipient_count = ?, suppressed_count = ? WHERE id = ?",
+        )
+        .bind(queued as i64)
+        .bind(suppressed)
+        .bind(broadcast.id.to_string())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Next pending recipients of a broadcast, flagged if suppressed or bounced since queued
+    pub async fn next_recipients(
+        &self,
+        broadcast_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<BroadcastRecipient>, AppError> {
+        let rows = sqlx::query_as::<_, BroadcastRecipientRow>(
+            r#"
+            SELECT r.user_id, r.email, u.name,
+                   CAST(es.user_id IS NOT NULL OR eb.email IS NOT NULL AS SIGNED) AS suppressed
+            FROM email_broadcast_recipients r
+            JOIN users u ON u.id = r.user_id
+            LEFT JOIN email_suppressions es ON es.user_id = r.user_id
+            LEFT JOIN email_bounces eb ON eb.email = r.email
+            WHERE r.broadcast_id = ? AND r.status = ?
+            ORDER BY r.user_id
+            LIMIT ?
+            "#,
+        )
+        .bind(broadcast_id.to_string())
+        .bind(RECIPIENT_STATUS_PENDING)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(BroadcastRecipient::from).collect())
+    }
+
+    /// Record the outcome for a pending recipient and count it on the broadcast
+    pub async fn mark_recipient(
+        &self,
+        broadcast_id: Uuid,
+        user_id: Uuid,
+        status: &str,
+        error: Option<&str>,
+    ) -> Result<(), AppError> {
+        let counter = match status {
+            RECIPIENT_STATUS_SENT => "sent_count",
+            RECIPIENT_STATUS_FAILED => "failed_count",
+            RECIPIENT_STATUS_SUPPRESSED => "suppressed_count",
+            other => {
+                return Err(AppError::InternalError(anyhow::anyhow!(
+                    "Invalid broadcast recipient status '{}'",
+                    other
+                )))
+            }
+        };
+
+        let mut tx = self.pool.begin().await?;
+
+        let updated = sqlx::query(
+            r#"
+            UPDATE email_broadcast_recipients
+            SET status = ?, error = ?, sent_at = IF(? = ?, NOW(), NULL)
+            WHERE broadcast_id = ? AND user_id = ? AND status = ?
+            "#,
+        )
+        .bind(status)
+        .bind(error.map(|e| e.chars().take(500).collect::<String>()))
+        .bind(status)
+        .bind(RECIPIENT_STATUS_SENT)
+        .bind(broadcast_id.to_string())
+        .bind(user_id.to_string())
+        .bind(RECIPIENT_STATUS_PENDING)
+        .execute(&mut *tx)
+        .await?;
+
+        if updated.rows_affected() > 0 {
+            sqlx::query(&format!(
+                "UPDATE email_broadcasts SET {counter} = {counter} + 1 WHERE id = ?",
+                counter = counter
+            ))
+            .bind(broadcast_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Mark a sending broadcast as sent once no recipient is pending
+    pub async fn complete_if_drained(&self, broadcast_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE email_broadcasts
+            SET status = ?, completed_at = NOW()
+            WHERE id = ? AND status = ?
+              AND NOT EXISTS (
+                  SELECT 1 FROM email_broadcast_recipients
+                  WHERE broadcast_id = ? AND status = ?)
+            "#,
+        )
+        .bind(BROADCAST_STATUS_SENT)
+        .bind(broadcast_id.to_string())
+        .bind(BROADCAST_STATUS_SENDING)
+        .bind(broadcast_id.to_string())
+        .bind(RECIPIENT_STATUS_PENDING)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
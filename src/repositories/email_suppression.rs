@@ -0,0 +1,68 @@
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{EmailSuppression, EmailSuppressionRow};
+
+/// Repository for the list of users who must not receive broadcasts
+#[derive(Clone)]
+pub struct EmailSuppressionRepository {
+    pool: MySqlPool,
+}
+
+impl EmailSuppressionRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Suppress a user, replacing the reason if already suppressed
+    pub async fn add(
+        &self,
+        user_id: Uuid,
+        reason: Option<&str>,
+        created_by: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO email_suppressions (user_id, reason, created_by)
+            VALUES (?, ?, ?)
+            ON DUPLICATE KEY UPDATE reason = VALUES(reason), created_by = VALUES(created_by)
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(reason)
+        .bind(created_by.map(|id| id.to_string()))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Lift a suppression; returns false if the user was not suppressed
+    pub async fn remove(&self, user_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM email_suppressions WHERE user_id = ?")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Suppressed users, most recent first
+    pub async fn list(&self, limit: i64) -> Result<Vec<EmailSuppression>, AppError> {
+        let rows = sqlx::query_as::<_, EmailSuppressionRow>(
+            r#"
+            SELECT es.user_id, u.email, es.reason, es.created_by, es.created_at
+            FROM email_suppressions es
+            JOIN users u ON u.id = es.user_id
+            ORDER BY es.created_at DESC, es.user_id
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(EmailSuppression::from).collect())
+    }
+}
@@ -0,0 +1,212 @@
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+use crate::error::AppError;
+use crate::utils::field_crypto::{EncryptedColumn, FieldCipher, ENCRYPTED_PREFIX};
+
+/// How the stored values of one encrypted column are distributed over keys
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnEncryptionStatus {
+    pub column: &'static str,
+    /// Rows with a non-NULL value
+    pub total: i64,
+    /// Values still stored as plaintext
+    pub plaintext: i64,
+    /// Values encrypted under the active key
+    pub current: i64,
+    /// Values encrypted under a retired key
+    pub retired_key: i64,
+}
+
+/// Outcome of one rotation batch over a column
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RotationBatch {
+    pub scanned: usize,
+    pub rewritten: usize,
+    /// Values that could not be decrypted with any configured key
+    pub failed: usize,
+    /// Last row ID scanned, to resume from; None once the column is exhausted
+    pub next_after: Option<String>,
+}
+
+/// Repository for re-encrypting sensitive columns under the active key
+///
+/// Table and column names come from [`EncryptedColumn`], never from input.
+#[derive(Clone)]
+pub struct FieldEncryptionRepository {
+    pool: MySqlPool,
+}
+
+impl FieldEncryptionRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Count plaintext, current and retired-key values of a column
+    pub async fn status(
+        &self,
+        column: EncryptedColumn,
+        active_key_id: Option<&str>,
+    ) -> Result<ColumnEncryptionStatus, AppError> {
+        let current_prefix = format!("{}{}:%", ENCRYPTED_PREFIX, active_key_id.unwrap_or_default());
+
+        let (total, plaintext, current) = sqlx::query_as::<_, (i64, i64, i64)>(&format!(
+            r#"
+            SELECT COUNT(*),
+                   CAST(COALESCE(SUM({col} NOT LIKE ?), 0) AS SIGNED),
+                   CAST(COALESCE(SUM({col} LIKE ?), 0) AS SIGNED)
+            FROM {table}
+            WHERE {col} IS NOT NULL
+            "#,
+            col = column.column(),
+            table = column.table(),
+        ))
+        .bind(format!("{}%", ENCRYPTED_PREFIX))
+        .bind(current_prefix)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(ColumnEncryptionStatus {
+            column: column.name(),
+            total,
+            plaintext,
+            current,
+            retired_key: total - plaintext - current,
+        })
+    }
+
+    /// Re-encrypt the given rows of a column if their value is stale
+    /// Returns the number of rows rewritten
+    pub async fn reencrypt_rows(
+        &self,
+        cipher: &FieldCipher,
+        column: EncryptedColumn,
+        ids: &[String],
+    ) -> Result<usize, AppError> {
+        let mut rewritten = 0;
+
+        for id in ids {
+            let stored = sqlx::query_scalar::<_, Option<String>>(&format!(
+                "SELECT {col} FROM {table} WHERE id = ?",
+                col = column.column(),
+                table = column.table(),
+            ))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::InternalError(e.into()))?
+            .flatten();
+
+            if let Some(stored) = stored {
+                if self.rewrite(cipher, column, id, &stored).await? == Some(true) {
+                    rewritten += 1;
+                }
+            }
+        }
+
+        Ok(rewritten)
+    }
+
+    /// Re-encrypt up to `limit` rows of a column with ID greater than `after`
+    pub async fn rotate_batch(
+        &self,
+        cipher: &FieldCipher,
+        column: EncryptedColumn,
+        after: Option<&str>,
+        limit: i64,
+    ) -> Result<RotationBatch, AppError> {
+        let rows = sqlx::query_as::<_, (String, String)>(&format!(
+            r#"
+            SELECT id, {col}
+            FROM {table}
+            WHERE {col} IS NOT NULL AND id > ?
+            ORDER BY id
+            LIMIT ?
+            "#,
+            col = column.column(),
+            table = column.table(),
+        ))
+        .bind(after.unwrap_or_default())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        let mut batch = RotationBatch {
+            scanned: rows.len(),
+            next_after: (rows.len() as i64 == limit).then(|| rows.last().map(|(id, _)| id.clone())).flatten(),
+            ..Default::default()
+        };
+
+        for (id, stored) in &rows {
+            match self.rewrite(cipher, column, id, stored).await? {
+                Some(true) => batch.rewritten += 1,
+                Some(false) => {}
+                None => batch.failed += 1,
+            }
+        }
+
+        Ok(batch)
+    }
+
+    /// Re-encrypt every value of a column, `batch_size` rows at a time
+    pub async fn rotate_column(
+        &self,
+        cipher: &FieldCipher,
+        column: EncryptedColumn,
+        batch_size: i64,
+    ) -> Result<RotationBatch, AppError> {
+        let mut total = RotationBatch::default();
+        let mut after = None;
+
+        loop {
+            let batch = self.rotate_batch(cipher, column, after.as_deref(), batch_size).await?;
+            total.scanned += batch.scanned;
+            total.rewritten += batch.rewritten;
+            total.failed += batch.failed;
+
+            match batch.next_after {
+                Some(next) => after = Some(next),
+                None => return Ok(total),
+            }
+        }
+    }
+
+    /// Rewrite one value under the active key
+    ///
+    /// Compare-and-swap on the old value so a concurrent write is never
+    /// overwritten; `updated_at` is kept so re-encryption isn't seen as a
+    /// profile change. Returns None if the value can't be decrypted.
+    async fn rewrite(
+        &self,
+        cipher: &FieldCipher,
+        column: EncryptedColumn,
+        id: &str,
+        stored: &str,
+    ) -> Result<Option<bool>, AppError> {
+        let rewrapped = match cipher.rewrap(column, stored) {
+            Ok(Some(value)) => value,
+            Ok(None) => return Ok(Some(false)),
+            Err(e) => {
+                tracing::warn!("Cannot re-encrypt {} of row {}: {}", column.name(), id, e);
+                return Ok(None);
+            }
+        };
+
+        let keep_updated_at = if column.has_updated_at() { ", updated_at = updated_at" } else { "" };
+        let result = sqlx::query(&format!(
+            "UPDATE {table} SET {col} = ?{keep_updated_at} WHERE id = ? AND {col} = ?",
+            col = column.column(),
+            table = column.table(),
+        ))
+        .bind(rewrapped)
+        .bind(id)
+        .bind(stored)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(Some(result.rows_affected() > 0))
+    }
+}
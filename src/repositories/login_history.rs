@@ -0,0 +1,88 @@
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::LoginLocation;
+use crate::utils::geoip::GeoLocation;
+
+/// Located sign-ins kept per user; older ones are pruned as new ones come in
+pub const LOGIN_HISTORY_PER_USER: i64 = 20;
+
+/// Repository for login history database operations
+#[derive(Clone)]
+pub struct LoginHistoryRepository {
+    pool: MySqlPool,
+}
+
+impl LoginHistoryRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a located sign-in and forget the user's oldest ones
+    pub async fn record(
+        &self,
+        user_id: Uuid,
+        ip_address: &str,
+        location: &GeoLocation,
+        impossible_travel: bool,
+    ) -> Result<(), AuthError> {
+        sqlx::query(
+            r#"
+            INSERT INTO login_history (id, user_id, ip_address, country, latitude, longitude, impossible_travel)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(user_id.to_string())
+        .bind(ip_address)
+        .bind(&location.country)
+        .bind(location.latitude)
+        .bind(location.longitude)
+        .bind(impossible_travel)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM login_history
+            WHERE user_id = ? AND id NOT IN (
+                SELECT id FROM (
+                    SELECT id FROM login_history
+                    WHERE user_id = ?
+                    ORDER BY created_at DESC
+                    LIMIT ?
+                ) AS recent
+            )
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(user_id.to_string())
+        .bind(LOGIN_HISTORY_PER_USER)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// The user's most recent located sign-in
+    pub async fn latest(&self, user_id: Uuid) -> Result<Option<LoginLocation>, AuthError> {
+        let location = sqlx::query_as::<_, LoginLocation>(
+            r#"
+            SELECT id, user_id, ip_address, country, latitude, longitude, impossible_travel, created_at
+            FROM login_history
+            WHERE user_id = ?
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(location)
+    }
+}
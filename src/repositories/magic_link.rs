@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::MagicLinkToken;
+
+/// Repository for magic link token database operations
+#[derive(Clone)]
+pub struct MagicLinkRepository {
+    pool: MySqlPool,
+}
+
+impl MagicLinkRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a new link for a user and app
+    /// Earlier unused links of the user for the same app stop working
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        app_id: Uuid,
+        token_hash: &str,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<MagicLinkToken, AuthError> {
+        let mut tx = self.pool.begin().await.map_err(|e| AuthError::InternalError(e.into()))?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM magic_link_tokens
+            WHERE user_id = ? AND app_id = ? AND used_at IS NULL
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(app_id.to_string())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO magic_link_tokens (id, user_id, app_id, token_hash, ip_address, user_agent, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(user_id.to_string())
+        .bind(app_id.to_string())
+        .bind(token_hash)
+        .bind(ip_address)
+        .bind(user_agent)
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        tx.commit().await.map_err(|e| AuthError::InternalError(e.into()))?;
+
+        self.find_by_hash(token_hash)
+            .await?
+            .ok_or(AuthError::InternalError(anyhow::anyhow!("Failed to fetch created magic link")))
+    }
+
+    /// Find a link by the hash of its token
+    pub async fn find_by_hash(&self, token_hash: &str) -> Result<Option<MagicLinkToken>, AuthError> {
+        let token = sqlx::query_as::<_, MagicLinkToken>(
+            r#"
+            SELECT id, user_id, app_id, ip_address, user_agent, expires_at, used_at, created_at
+            FROM magic_link_tokens
+            WHERE token_hash = ?
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(token)
+    }
+
+    /// Atomically mark an unexpired link as used
+    /// Returns false if it was already used or has expired, so a link signs in only once
+    pub async fn mark_used(&self, id: Uuid) -> Result<bool, AuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE magic_link_tokens
+            SET used_at = NOW()
+            WHERE id = ? AND used_at IS NULL AND expires_at > NOW()
+            "#,
+        )
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,9 +1,10 @@
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use sqlx::MySqlPool;
 use uuid::Uuid;
 
 use crate::error::AuthError;
-use crate::models::{UserMfaBackupCode, UserMfaMethod};
+use crate::models::{EmailOtpCode, MfaSmsCode, UserMfaBackupCode, UserMfaMethod};
+use crate::utils::field_crypto::{seal_optional, EncryptedColumn};
 
 /// Repository for MFA database operations
 #[derive(Clone)]
@@ -41,8 +42,8 @@ impl MfaRepository {
         .bind(id.to_string())
         .bind(user_id.to_string())
         .bind(method_type)
-        .bind(secret_encrypted)
-        .bind(phone_number)
+        .bind(seal_optional(EncryptedColumn::MfaSecret, secret_encrypted))
+        .bind(seal_optional(EncryptedColumn::MfaPhone, phone_number))
         .bind(email)
         .bind(is_primary)
         .execute(&self.pool)
@@ -211,6 +212,245 @@ impl MfaRepository {
         Ok(result.rows_affected())
     }
 
+    // ========================================================================
+    // SMS Codes
+    // ========================================================================
+
+    /// Store a new SMS code for a method, replacing its unused codes for the same purpose
+    pub async fn create_sms_code(
+        &self,
+        user_id: Uuid,
+        method_id: Uuid,
+        purpose: &str,
+        code_hash: &str,
+        mfa_token_hash: Option<&str>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AuthError> {
+        sqlx::query(
+            r#"
+            DELETE FROM mfa_sms_codes
+            WHERE method_id = ? AND purpose = ? AND used_at IS NULL
+            "#,
+        )
+        .bind(method_id.to_string())
+        .bind(purpose)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO mfa_sms_codes (id, user_id, method_id, purpose, code_hash, mfa_token_hash, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(user_id.to_string())
+        .bind(method_id.to_string())
+        .bind(purpose)
+        .bind(code_hash)
+        .bind(mfa_token_hash)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Latest unused, unexpired enrollment code of a method
+    pub async fn find_enrollment_sms_code(&self, method_id: Uuid) -> Result<Option<MfaSmsCode>, AuthError> {
+        let code = sqlx::query_as::<_, MfaSmsCode>(
+            r#"
+            SELECT id, user_id, method_id, code_hash, attempts
+            FROM mfa_sms_codes
+            WHERE method_id = ? AND purpose = 'enroll' AND used_at IS NULL AND expires_at > NOW()
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(method_id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(code)
+    }
+
+    /// Latest unused, unexpired login code sent for a pending MFA token
+    pub async fn find_login_sms_code(&self, mfa_token_hash: &str) -> Result<Option<MfaSmsCode>, AuthError> {
+        let code = sqlx::query_as::<_, MfaSmsCode>(
+            r#"
+            SELECT id, user_id, method_id, code_hash, attempts
+            FROM mfa_sms_codes
+            WHERE mfa_token_hash = ? AND purpose = 'login' AND used_at IS NULL AND expires_at > NOW()
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(mfa_token_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(code)
+    }
+
+    /// Count a wrong guess against an SMS code
+    pub async fn record_sms_code_attempt(&self, id: Uuid) -> Result<(), AuthError> {
+        sqlx::query(
+            r#"
+            UPDATE mfa_sms_codes
+            SET attempts = attempts + 1
+            WHERE id = ?
+            "#,
+        )
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Mark an SMS code as used
+    /// Returns false if it was used concurrently
+    pub async fn use_sms_code(&self, id: Uuid) -> Result<bool, AuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE mfa_sms_codes
+            SET used_at = NOW()
+            WHERE id = ? AND used_at IS NULL
+            "#,
+        )
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ========================================================================
+    // Email Codes
+    // ========================================================================
+
+    /// Store a new email code, replacing the user's unused codes for the same purpose
+    pub async fn create_email_code(
+        &self,
+        user_id: Uuid,
+        purpose: &str,
+        code_hash: &str,
+        mfa_token_hash: Option<&str>,
+        app_id: Option<Uuid>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AuthError> {
+        sqlx::query(
+            r#"
+            DELETE FROM email_otp_codes
+            WHERE user_id = ? AND purpose = ? AND used_at IS NULL
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(purpose)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO email_otp_codes (id, user_id, purpose, code_hash, mfa_token_hash, app_id, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(user_id.to_string())
+        .bind(purpose)
+        .bind(code_hash)
+        .bind(mfa_token_hash)
+        .bind(app_id.map(|id| id.to_string()))
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Latest unused, unexpired email code sent for a pending MFA token
+    pub async fn find_mfa_email_code(&self, mfa_token_hash: &str) -> Result<Option<EmailOtpCode>, AuthError> {
+        let code = sqlx::query_as::<_, EmailOtpCode>(
+            r#"
+            SELECT id, user_id, code_hash, attempts
+            FROM email_otp_codes
+            WHERE mfa_token_hash = ? AND purpose = 'mfa' AND used_at IS NULL AND expires_at > NOW()
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(mfa_token_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(code)
+    }
+
+    /// Latest unused, unexpired passwordless login code of a user for an app
+    pub async fn find_login_email_code(&self, user_id: Uuid, app_id: Uuid) -> Result<Option<EmailOtpCode>, AuthError> {
+        let code = sqlx::query_as::<_, EmailOtpCode>(
+            r#"
+            SELECT id, user_id, code_hash, attempts
+            FROM email_otp_codes
+            WHERE user_id = ? AND app_id = ? AND purpose = 'login' AND used_at IS NULL AND expires_at > NOW()
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(app_id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(code)
+    }
+
+    /// Count a wrong guess against an email code
+    pub async fn record_email_code_attempt(&self, id: Uuid) -> Result<(), AuthError> {
+        sqlx::query(
+            r#"
+            UPDATE email_otp_codes
+            SET attempts = attempts + 1
+            WHERE id = ?
+            "#,
+        )
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Mark an email code as used
+    /// Returns false if it was used concurrently
+    pub async fn use_email_code(&self, id: Uuid) -> Result<bool, AuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE email_otp_codes
+            SET used_at = NOW()
+            WHERE id = ? AND used_at IS NULL
+            "#,
+        )
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     // ========================================================================
     // Backup Codes
     // ========================================================================
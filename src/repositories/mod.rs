@@ -1,5 +1,6 @@
 pub mod app;
 pub mod authorization_code;
+pub mod client_jwks;
 pub mod oauth_audit_log;
 pub mod oauth_client;
 pub mod oauth_scope;
@@ -20,9 +21,37 @@ pub mod webhook;
 pub mod api_key;
 pub mod ip_rule;
 pub mod webauthn;
+pub mod device;
+pub mod push_mfa;
+pub mod redirect_uri_block;
+pub mod user_match_key;
+pub mod rbac_sync;
+pub mod role_elevation;
+pub mod role_history;
+pub mod warmup;
+pub mod worker_leader;
+pub mod provisioning_event;
+pub mod field_encryption;
+pub mod abuse_telemetry;
+pub mod user_address;
+pub mod signing_key;
+pub mod token_lineage;
+pub mod challenge_store;
+pub mod email_broadcast;
+pub mod email_suppression;
+pub mod email_bounce;
+pub mod admin_approval;
+pub mod sso_session;
+pub mod dormant_account;
+pub mod app_digest;
+pub mod magic_link;
+pub mod trusted_device;
+pub mod login_history;
+pub mod cooling_off;
 
 pub use app::AppRepository;
 pub use authorization_code::AuthorizationCodeRepository;
+pub use client_jwks::ClientJwksRepository;
 pub use oauth_audit_log::OAuthAuditLogRepository;
 pub use oauth_client::OAuthClientRepository;
 pub use oauth_scope::OAuthScopeRepository;
@@ -43,3 +72,29 @@ pub use webhook::WebhookRepository;
 pub use api_key::ApiKeyRepository;
 pub use ip_rule::IpRuleRepository;
 pub use webauthn::WebAuthnRepository;
+pub use device::DeviceRepository;
+pub use push_mfa::PushMfaRepository;
+pub use redirect_uri_block::RedirectUriBlockRepository;
+pub use user_match_key::UserMatchKeyRepository;
+pub use rbac_sync::RbacSyncRepository;
+pub use role_elevation::RoleElevationRepository;
+pub use role_history::RoleHistoryRepository;
+pub use worker_leader::WorkerLeaderRepository;
+pub use provisioning_event::ProvisioningEventRepository;
+pub use field_encryption::FieldEncryptionRepository;
+pub use abuse_telemetry::AbuseTelemetryRepository;
+pub use user_address::UserAddressRepository;
+pub use signing_key::SigningKeyRepository;
+pub use token_lineage::TokenLineageRepository;
+pub use challenge_store::ChallengeStoreRepository;
+pub use email_broadcast::EmailBroadcastRepository;
+pub use email_suppression::EmailSuppressionRepository;
+pub use email_bounce::EmailBounceRepository;
+pub use admin_approval::AdminApprovalRepository;
+pub use sso_session::SsoSessionRepository;
+pub use dormant_account::DormantAccountRepository;
+pub use app_digest::AppDigestRepository;
+pub use magic_link::MagicLinkRepository;
+pub use trusted_device::TrustedDeviceRepository;
+pub use login_history::LoginHistoryRepository;
+pub use cooling_off::CoolingOffRepository;
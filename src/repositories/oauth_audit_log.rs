@@ -3,7 +3,8 @@ use sqlx::MySqlPool;
 use uuid::Uuid;
 
 use crate::error::OAuthError;
-use crate::models::{OAuthAuditLog, OAuthEventType};
+use crate::models::{OAuthAuditCount, OAuthAuditLog, OAuthEventType};
+use crate::utils::request_id::current_request_id;
 
 /// Repository for OAuth audit log database operations
 /// Requirements: 9.5, 10.6
@@ -32,8 +33,8 @@ impl OAuthAuditLogRepository {
 
         sqlx::query(
             r#"
-            INSERT INTO oauth_audit_logs (id, event_type, client_id, user_id, ip_address, details)
-            VALUES (?, ?, ?, ?, ?, ?)
+            INSERT INTO oauth_audit_logs (id, event_type, client_id, user_id, ip_address, details, correlation_id)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id.to_string())
@@ -42,6 +43,7 @@ impl OAuthAuditLogRepository {
         .bind(user_id.map(|u| u.to_string()))
         .bind(ip_address)
         .bind(&details)
+        .bind(current_request_id())
         .execute(&self.pool)
         .await
         .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
@@ -64,8 +66,8 @@ impl OAuthAuditLogRepository {
 
         sqlx::query(
             r#"
-            INSERT INTO oauth_audit_logs (id, event_type, client_id, user_id, ip_address, details)
-            VALUES (?, ?, ?, ?, ?, ?)
+            INSERT INTO oauth_audit_logs (id, event_type, client_id, user_id, ip_address, details, correlation_id)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id.to_string())
@@ -74,6 +76,7 @@ impl OAuthAuditLogRepository {
         .bind(user_id.map(|u| u.to_string()))
         .bind(ip_address)
         .bind(&details)
+        .bind(current_request_id())
         .execute(&self.pool)
         .await
         .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
@@ -87,7 +90,7 @@ impl OAuthAuditLogRepository {
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<OAuthAuditLog>, OAuthError> {
         let log = sqlx::query_as::<_, OAuthAuditLog>(
             r#"
-            SELECT id, event_type, client_id, user_id, ip_address, details, created_at
+            SELECT id, event_type, client_id, user_id, ip_address, details, correlation_id, created_at
             FROM oauth_audit_logs
             WHERE id = ?
             "#,
@@ -111,7 +114,7 @@ impl OAuthAuditLogRepository {
 
         let logs = sqlx::query_as::<_, OAuthAuditLog>(
             r#"
-            SELECT id, event_type, client_id, user_id, ip_address, details, created_at
+            SELECT id, event_type, client_id, user_id, ip_address, details, correlation_id, created_at
             FROM oauth_audit_logs
             WHERE user_id = ?
             ORDER BY created_at DESC
@@ -139,7 +142,7 @@ impl OAuthAuditLogRepository {
 
         let logs = sqlx::query_as::<_, OAuthAuditLog>(
             r#"
-            SELECT id, event_type, client_id, user_id, ip_address, details, created_at
+            SELECT id, event_type, client_id, user_id, ip_address, details, correlation_id, created_at
             FROM oauth_audit_logs
             WHERE client_id = ?
             ORDER BY created_at DESC
@@ -167,7 +170,7 @@ impl OAuthAuditLogRepository {
 
         let logs = sqlx::query_as::<_, OAuthAuditLog>(
             r#"
-            SELECT id, event_type, client_id, user_id, ip_address, details, created_at
+            SELECT id, event_type, client_id, user_id, ip_address, details, correlation_id, created_at
             FROM oauth_audit_logs
             WHERE event_type = ?
             ORDER BY created_at DESC
@@ -196,7 +199,7 @@ impl OAuthAuditLogRepository {
 
         let logs = sqlx::query_as::<_, OAuthAuditLog>(
             r#"
-            SELECT id, event_type, client_id, user_id, ip_address, details, created_at
+            SELECT id, event_type, client_id, user_id, ip_address, details, correlation_id, created_at
             FROM oauth_audit_logs
             WHERE created_at >= ? AND created_at <= ?
             ORDER BY created_at DESC
@@ -220,7 +223,7 @@ impl OAuthAuditLogRepository {
 
         let logs = sqlx::query_as::<_, OAuthAuditLog>(
             r#"
-            SELECT id, event_type, client_id, user_id, ip_address, details, created_at
+            SELECT id, event_type, client_id, user_id, ip_address, details, correlation_id, created_at
             FROM oauth_audit_logs
             ORDER BY created_at DESC
             LIMIT ? OFFSET ?
@@ -235,6 +238,92 @@ impl OAuthAuditLogRepository {
         Ok(logs)
     }
 
+    /// List a user's audit logs restricted to the given event types, oldest first
+    pub async fn list_by_user_and_event_types(
+        &self,
+        user_id: Uuid,
+        event_types: &[OAuthEventType],
+    ) -> Result<Vec<OAuthAuditLog>, OAuthError> {
+        if event_types.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let placeholders = vec!["?"; event_types.len()].join(", ");
+        let sql = format!(
+            r#"
+            SELECT id, event_type, client_id, user_id, ip_address, details, correlation_id, created_at
+            FROM oauth_audit_logs
+            WHERE user_id = ? AND event_type IN ({})
+            ORDER BY created_at ASC
+            "#,
+            placeholders
+        );
+
+        let mut query = sqlx::query_as::<_, OAuthAuditLog>(&sql).bind(user_id.to_string());
+        for event_type in event_types {
+            query = query.bind(event_type.as_str());
+        }
+
+        let logs = query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(logs)
+    }
+
+    /// Count events of the given types since `since`, per `bucket_secs` time bucket
+    ///
+    /// Rows are grouped by bucket, event type, client, and the `grant_type`
+    /// and `error` recorded in the details.
+    pub async fn count_events_since(
+        &self,
+        since: DateTime<Utc>,
+        bucket_secs: i64,
+        event_types: &[OAuthEventType],
+        client_id: Option<Uuid>,
+    ) -> Result<Vec<OAuthAuditCount>, OAuthError> {
+        if event_types.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let placeholders = vec!["?"; event_types.len()].join(", ");
+        let sql = format!(
+            r#"
+            SELECT CAST(FLOOR(UNIX_TIMESTAMP(created_at) / ?) * ? AS SIGNED) AS bucket,
+                   event_type,
+                   client_id,
+                   JSON_UNQUOTE(JSON_EXTRACT(details, '$.grant_type')) AS grant_type,
+                   JSON_UNQUOTE(JSON_EXTRACT(details, '$.error')) AS error,
+                   COUNT(*) AS count
+            FROM oauth_audit_logs
+            WHERE created_at >= ? AND event_type IN ({}) {}
+            GROUP BY bucket, event_type, client_id, grant_type, error
+            ORDER BY bucket ASC
+            "#,
+            placeholders,
+            if client_id.is_some() { "AND client_id = ?" } else { "" }
+        );
+
+        let mut query = sqlx::query_as::<_, OAuthAuditCount>(&sql)
+            .bind(bucket_secs)
+            .bind(bucket_secs)
+            .bind(since);
+        for event_type in event_types {
+            query = query.bind(event_type.as_str());
+        }
+        if let Some(client_id) = client_id {
+            query = query.bind(client_id.to_string());
+        }
+
+        let counts = query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(counts)
+    }
+
     /// Count audit logs by user
     pub async fn count_by_user(&self, user_id: Uuid) -> Result<u64, OAuthError> {
         let count = sqlx::query_scalar::<_, i64>(
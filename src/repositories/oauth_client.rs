@@ -1,8 +1,9 @@
+use chrono::{DateTime, Utc};
 use sqlx::MySqlPool;
 use uuid::Uuid;
 
 use crate::error::OAuthError;
-use crate::models::OAuthClient;
+use crate::models::{ClientSecretRotation, ExpiringClientSecret, OAuthClient};
 
 /// Repository for OAuth client database operations
 /// Requirements: 1.1, 1.2
@@ -27,6 +28,7 @@ impl OAuthClientRepository {
         owner_id: Uuid,
         redirect_uris: &[String],
         is_internal: bool,
+        client_type: &str,
     ) -> Result<OAuthClient, OAuthError> {
         let id = Uuid::new_v4();
         let redirect_uris_json = serde_json::to_value(redirect_uris)
@@ -34,8 +36,8 @@ impl OAuthClientRepository {
 
         sqlx::query(
             r#"
-            INSERT INTO oauth_clients (id, client_id, client_secret_hash, name, owner_id, redirect_uris, is_internal)
-            VALUES (?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO oauth_clients (id, client_id, client_secret_hash, name, owner_id, redirect_uris, is_internal, client_type)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id.to_string())
@@ -45,6 +47,7 @@ impl OAuthClientRepository {
         .bind(owner_id.to_string())
         .bind(&redirect_uris_json)
         .bind(is_internal)
+        .bind(client_type)
         .execute(&self.pool)
         .await
         .map_err(|e| {
@@ -67,7 +70,10 @@ impl OAuthClientRepository {
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<OAuthClient>, OAuthError> {
         let client = sqlx::query_as::<_, OAuthClient>(
             r#"
-            SELECT id, client_id, client_secret_hash, name, owner_id, redirect_uris, is_internal, is_active, created_at
+            SELECT id, client_id, client_secret_hash, name, owner_id, redirect_uris, is_internal, client_type, is_active,
+                   session_idle_timeout_secs, session_absolute_lifetime_secs, access_token_ttl_secs, refresh_token_cookie, skip_consent, always_prompt_consent,
+                   required_acr, userinfo_signed_response_alg, posture_claims,
+                   secret_created_at, secret_max_age_days, post_logout_redirect_uris, backchannel_logout_uri, request_uris, allowed_scopes, allowed_grant_types, created_at
             FROM oauth_clients
             WHERE id = ?
             "#,
@@ -85,7 +91,10 @@ impl OAuthClientRepository {
     pub async fn find_by_client_id(&self, client_id: &str) -> Result<Option<OAuthClient>, OAuthError> {
         let client = sqlx::query_as::<_, OAuthClient>(
             r#"
-            SELECT id, client_id, client_secret_hash, name, owner_id, redirect_uris, is_internal, is_active, created_at
+            SELECT id, client_id, client_secret_hash, name, owner_id, redirect_uris, is_internal, client_type, is_active,
+                   session_idle_timeout_secs, session_absolute_lifetime_secs, access_token_ttl_secs, refresh_token_cookie, skip_consent, always_prompt_consent,
+                   required_acr, userinfo_signed_response_alg, posture_claims,
+                   secret_created_at, secret_max_age_days, post_logout_redirect_uris, backchannel_logout_uri, request_uris, allowed_scopes, allowed_grant_types, created_at
             FROM oauth_clients
             WHERE client_id = ?
             "#,
@@ -102,7 +111,10 @@ impl OAuthClientRepository {
     pub async fn find_active_by_client_id(&self, client_id: &str) -> Result<Option<OAuthClient>, OAuthError> {
         let client = sqlx::query_as::<_, OAuthClient>(
             r#"
-            SELECT id, client_id, client_secret_hash, name, owner_id, redirect_uris, is_internal, is_active, created_at
+            SELECT id, client_id, client_secret_hash, name, owner_id, redirect_uris, is_internal, client_type, is_active,
+                   session_idle_timeout_secs, session_absolute_lifetime_secs, access_token_ttl_secs, refresh_token_cookie, skip_consent, always_prompt_consent,
+                   required_acr, userinfo_signed_response_alg, posture_claims,
+                   secret_created_at, secret_max_age_days, post_logout_redirect_uris, backchannel_logout_uri, request_uris, allowed_scopes, allowed_grant_types, created_at
             FROM oauth_clients
             WHERE client_id = ? AND is_active = true
             "#,
@@ -148,12 +160,346 @@ impl OAuthClientRepository {
             .ok_or(OAuthError::InvalidClient)
     }
 
-    /// Update client secret hash
+    /// Set the logout URIs of a client
+    pub async fn update_logout_uris(
+        &self,
+        id: Uuid,
+        post_logout_redirect_uris: &[String],
+        backchannel_logout_uri: Option<&str>,
+    ) -> Result<(), OAuthError> {
+        let uris_json = serde_json::to_value(post_logout_redirect_uris)
+            .map_err(|e| OAuthError::ServerError(format!("Failed to serialize post_logout_redirect_uris: {}", e)))?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_clients
+            SET post_logout_redirect_uris = ?, backchannel_logout_uri = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&uris_json)
+        .bind(backchannel_logout_uri)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidClient);
+        }
+
+        Ok(())
+    }
+
+    /// Set the URLs a client may pass as request_uri
+    pub async fn update_request_uris(&self, id: Uuid, request_uris: &[String]) -> Result<(), OAuthError> {
+        let uris_json = serde_json::to_value(request_uris)
+            .map_err(|e| OAuthError::ServerError(format!("Failed to serialize request_uris: {}", e)))?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_clients
+            SET request_uris = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&uris_json)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidClient);
+        }
+
+        Ok(())
+    }
+
+    /// Find an active client by its registration access token (RFC 7592)
+    pub async fn find_by_registration_token(
+        &self,
+        client_id: &str,
+        token_hash: &str,
+    ) -> Result<Option<OAuthClient>, OAuthError> {
+        let client = sqlx::query_as::<_, OAuthClient>(
+            r#"
+            SELECT id, client_id, client_secret_hash, name, owner_id, redirect_uris, is_internal, client_type, is_active,
+                   session_idle_timeout_secs, session_absolute_lifetime_secs, access_token_ttl_secs, refresh_token_cookie, skip_consent, always_prompt_consent,
+                   required_acr, userinfo_signed_response_alg, posture_claims,
+                   secret_created_at, secret_max_age_days, post_logout_redirect_uris, backchannel_logout_uri, request_uris, allowed_scopes, allowed_grant_types, created_at
+            FROM oauth_clients
+            WHERE client_id = ? AND registration_access_token_hash = ?
+            "#,
+        )
+        .bind(client_id)
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(client)
+    }
+
+    /// Store the hash of a client's registration access token
+    pub async fn set_registration_token(&self, id: Uuid, token_hash: &str) -> Result<(), OAuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_clients
+            SET registration_access_token_hash = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(token_hash)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidClient);
+        }
+
+        Ok(())
+    }
+
+    /// Restrict the scopes a client may request (None allows every available scope)
+    pub async fn update_allowed_scopes(&self, id: Uuid, allowed_scopes: Option<&[String]>) -> Result<(), OAuthError> {
+        let scopes_json = allowed_scopes
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| OAuthError::ServerError(format!("Failed to serialize allowed_scopes: {}", e)))?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_clients
+            SET allowed_scopes = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&scopes_json)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidClient);
+        }
+
+        Ok(())
+    }
+    /// Restrict the grant types a client may use (None allows every supported grant)
+    pub async fn update_allowed_grant_types(&self, id: Uuid, allowed_grant_types: Option<&[String]>) -> Result<(), OAuthError> {
+        let grants_json = allowed_grant_types
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| OAuthError::ServerError(format!("Failed to serialize allowed_grant_types: {}", e)))?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_clients
+            SET allowed_grant_types = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&grants_json)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidClient);
+        }
+
+        Ok(())
+    }
+
+    /// Set the access token lifetime override for a client (None restores the server default)
+    pub async fn update_access_token_ttl(
+        &self,
+        id: Uuid,
+        access_token_ttl_secs: Option<i64>,
+    ) -> Result<(), OAuthError> {
+        let result = sqlx::query("UPDATE oauth_clients SET access_token_ttl_secs = ? WHERE id = ?")
+            .bind(access_token_ttl_secs)
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidClient);
+        }
+
+        Ok(())
+    }
+
+    /// Set the session policy overrides for a client (None restores the server default)
+    pub async fn update_session_policy(
+        &self,
+        id: Uuid,
+        idle_timeout_secs: Option<i64>,
+        absolute_lifetime_secs: Option<i64>,
+    ) -> Result<(), OAuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_clients
+            SET session_idle_timeout_secs = ?, session_absolute_lifetime_secs = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(idle_timeout_secs)
+        .bind(absolute_lifetime_secs)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidClient);
+        }
+
+        Ok(())
+    }
+
+    /// Set the minimum acr of sign-ins the client accepts codes from (None accepts any)
+    pub async fn update_required_acr(&self, id: Uuid, required_acr: Option<&str>) -> Result<(), OAuthError> {
+        let result = sqlx::query("UPDATE oauth_clients SET required_acr = ? WHERE id = ?")
+            .bind(required_acr)
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidClient);
+        }
+
+        Ok(())
+    }
+
+    /// Enable or disable showing the consent screen on every authorization
+    pub async fn update_always_prompt_consent(&self, id: Uuid, enabled: bool) -> Result<(), OAuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_clients
+            SET always_prompt_consent = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(enabled)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidClient);
+        }
+
+        Ok(())
+    }
+
+    /// Enable or disable the posture claim (`azp_ctx`) in access tokens
+    pub async fn update_posture_claims(&self, id: Uuid, enabled: bool) -> Result<(), OAuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_clients
+            SET posture_claims = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(enabled)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidClient);
+        }
+
+        Ok(())
+    }
+
+    /// Set the algorithm userinfo responses are signed with (None = plain JSON)
+    pub async fn update_userinfo_signed_response_alg(
+        &self,
+        id: Uuid,
+        alg: Option<&str>,
+    ) -> Result<(), OAuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_clients
+            SET userinfo_signed_response_alg = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(alg)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidClient);
+        }
+
+        Ok(())
+    }
+
+    /// Enable or disable cookie delivery of refresh tokens
+    pub async fn update_refresh_token_cookie(&self, id: Uuid, enabled: bool) -> Result<(), OAuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_clients
+            SET refresh_token_cookie = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(enabled)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidClient);
+        }
+
+        Ok(())
+    }
+
+    /// Set whether a client skips the consent screen
+    pub async fn update_skip_consent(&self, id: Uuid, skip_consent: bool) -> Result<(), OAuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_clients
+            SET skip_consent = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(skip_consent)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidClient);
+        }
+
+        Ok(())
+    }
+
+    /// Update client secret hash, restarting its age and expiry warning
     pub async fn update_secret(&self, id: Uuid, client_secret_hash: &str) -> Result<(), OAuthError> {
         let result = sqlx::query(
             r#"
             UPDATE oauth_clients
-            SET client_secret_hash = ?
+            SET client_secret_hash = ?, secret_created_at = NOW(), secret_expiry_warned_at = NULL
             WHERE id = ?
             "#,
         )
@@ -170,6 +516,186 @@ impl OAuthClientRepository {
         Ok(())
     }
 
+    /// Keep a replaced secret valid until `expires_at`
+    ///
+    /// Previous secrets that have already run out are dropped at the same time.
+    pub async fn add_previous_secret(
+        &self,
+        client_id: Uuid,
+        secret_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), OAuthError> {
+        sqlx::query("DELETE FROM oauth_client_previous_secrets WHERE client_id = ? AND expires_at <= NOW()")
+            .bind(client_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO oauth_client_previous_secrets (id, client_id, secret_hash, expires_at)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(client_id.to_string())
+        .bind(secret_hash)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Hashes of the client's replaced secrets that are still valid
+    pub async fn find_previous_secret_hashes(&self, client_id: Uuid) -> Result<Vec<String>, OAuthError> {
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT secret_hash FROM oauth_client_previous_secrets
+            WHERE client_id = ? AND expires_at > NOW()
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(client_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))
+    }
+
+    /// Invalidate every replaced secret of the client at once
+    pub async fn revoke_previous_secrets(&self, client_id: Uuid) -> Result<(), OAuthError> {
+        sqlx::query("DELETE FROM oauth_client_previous_secrets WHERE client_id = ?")
+            .bind(client_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+        Ok(())
+    }
+
+    /// Record a secret rotation in the client's history
+    pub async fn record_secret_rotation(
+        &self,
+        client: &OAuthClient,
+        rotated_by: Option<Uuid>,
+        previous_secret_expired: bool,
+        previous_secret_valid_until: Option<DateTime<Utc>>,
+    ) -> Result<(), OAuthError> {
+        sqlx::query(
+            r#"
+            INSERT INTO oauth_client_secret_rotations
+                (id, client_id, rotated_by, previous_secret_created_at, previous_secret_expired, previous_secret_valid_until)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(client.id.to_string())
+        .bind(rotated_by.map(|id| id.to_string()))
+        .bind(client.secret_created_at)
+        .bind(previous_secret_expired)
+        .bind(previous_secret_valid_until)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Secret rotation history of a client, newest first
+    pub async fn list_secret_rotations(
+        &self,
+        client_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<ClientSecretRotation>, OAuthError> {
+        let rotations = sqlx::query_as::<_, ClientSecretRotation>(
+            r#"
+            SELECT id, client_id, rotated_by, previous_secret_created_at, previous_secret_expired,
+                   previous_secret_valid_until, created_at
+            FROM oauth_client_secret_rotations
+            WHERE client_id = ?
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(client_id.to_string())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(rotations)
+    }
+
+    /// Set the client's maximum secret age (None restores the server default)
+    ///
+    /// The expiry warning is re-armed since the expiry date may have moved.
+    pub async fn update_secret_max_age(&self, id: Uuid, max_age_days: Option<i64>) -> Result<(), OAuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_clients
+            SET secret_max_age_days = ?, secret_expiry_warned_at = NULL
+            WHERE id = ?
+            "#,
+        )
+        .bind(max_age_days)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidClient);
+        }
+
+        Ok(())
+    }
+
+    /// Active clients whose secret expires within `warning_days` and whose
+    /// owner has not been warned about the current secret yet
+    pub async fn find_expiring_secrets(
+        &self,
+        default_max_age_days: i64,
+        warning_days: i64,
+        limit: i64,
+    ) -> Result<Vec<ExpiringClientSecret>, OAuthError> {
+        let clients = sqlx::query_as::<_, ExpiringClientSecret>(
+            r#"
+            SELECT c.id, c.client_id, c.name, u.email AS owner_email,
+                   DATE_ADD(c.secret_created_at, INTERVAL COALESCE(c.secret_max_age_days, ?) DAY) AS secret_expires_at
+            FROM oauth_clients c
+            JOIN users u ON u.id = c.owner_id
+            WHERE c.is_active = true
+              AND c.secret_expiry_warned_at IS NULL
+              AND COALESCE(c.secret_max_age_days, ?) > 0
+              AND DATE_ADD(c.secret_created_at, INTERVAL COALESCE(c.secret_max_age_days, ?) DAY)
+                  <= DATE_ADD(NOW(), INTERVAL ? DAY)
+            ORDER BY secret_expires_at ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(default_max_age_days)
+        .bind(default_max_age_days)
+        .bind(default_max_age_days)
+        .bind(warning_days)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(clients)
+    }
+
+    /// Remember that the owner was warned about the current secret expiring
+    pub async fn mark_secret_expiry_warned(&self, id: &str) -> Result<(), OAuthError> {
+        sqlx::query("UPDATE oauth_clients SET secret_expiry_warned_at = NOW() WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(())
+    }
+
     /// Deactivate an OAuth client
     pub async fn deactivate(&self, id: Uuid) -> Result<(), OAuthError> {
         let result = sqlx::query(
@@ -238,7 +764,10 @@ impl OAuthClientRepository {
 
         let clients = sqlx::query_as::<_, OAuthClient>(
             r#"
-            SELECT id, client_id, client_secret_hash, name, owner_id, redirect_uris, is_internal, is_active, created_at
+            SELECT id, client_id, client_secret_hash, name, owner_id, redirect_uris, is_internal, client_type, is_active,
+                   session_idle_timeout_secs, session_absolute_lifetime_secs, access_token_ttl_secs, refresh_token_cookie, skip_consent, always_prompt_consent,
+                   required_acr, userinfo_signed_response_alg, posture_claims,
+                   secret_created_at, secret_max_age_days, post_logout_redirect_uris, backchannel_logout_uri, request_uris, allowed_scopes, allowed_grant_types, created_at
             FROM oauth_clients
             ORDER BY created_at DESC
             LIMIT ? OFFSET ?
@@ -257,7 +786,10 @@ impl OAuthClientRepository {
     pub async fn list_all(&self) -> Result<Vec<OAuthClient>, OAuthError> {
         let clients = sqlx::query_as::<_, OAuthClient>(
             r#"
-            SELECT id, client_id, client_secret_hash, name, owner_id, redirect_uris, is_internal, is_active, created_at
+            SELECT id, client_id, client_secret_hash, name, owner_id, redirect_uris, is_internal, client_type, is_active,
+                   session_idle_timeout_secs, session_absolute_lifetime_secs, access_token_ttl_secs, refresh_token_cookie, skip_consent, always_prompt_consent,
+                   required_acr, userinfo_signed_response_alg, posture_claims,
+                   secret_created_at, secret_max_age_days, post_logout_redirect_uris, backchannel_logout_uri, request_uris, allowed_scopes, allowed_grant_types, created_at
             FROM oauth_clients
             ORDER BY created_at DESC
             "#,
@@ -273,7 +805,10 @@ impl OAuthClientRepository {
     pub async fn list_by_owner(&self, owner_id: Uuid) -> Result<Vec<OAuthClient>, OAuthError> {
         let clients = sqlx::query_as::<_, OAuthClient>(
             r#"
-            SELECT id, client_id, client_secret_hash, name, owner_id, redirect_uris, is_internal, is_active, created_at
+            SELECT id, client_id, client_secret_hash, name, owner_id, redirect_uris, is_internal, client_type, is_active,
+                   session_idle_timeout_secs, session_absolute_lifetime_secs, access_token_ttl_secs, refresh_token_cookie, skip_consent, always_prompt_consent,
+                   required_acr, userinfo_signed_response_alg, posture_claims,
+                   secret_created_at, secret_max_age_days, post_logout_redirect_uris, backchannel_logout_uri, request_uris, allowed_scopes, allowed_grant_types, created_at
             FROM oauth_clients
             WHERE owner_id = ?
             ORDER BY created_at DESC
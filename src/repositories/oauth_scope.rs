@@ -2,7 +2,7 @@ use sqlx::MySqlPool;
 use uuid::Uuid;
 
 use crate::error::OAuthError;
-use crate::models::OAuthScope;
+use crate::models::{OAuthScope, SCOPE_VISIBILITY_GLOBAL};
 
 /// Repository for OAuth scope database operations
 /// Requirements: 2.1, 2.2
@@ -57,7 +57,7 @@ impl OAuthScopeRepository {
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<OAuthScope>, OAuthError> {
         let scope = sqlx::query_as::<_, OAuthScope>(
             r#"
-            SELECT id, code, description, is_active, created_at
+            SELECT id, code, description, is_active, owner_client_id, visibility, claims, created_at
             FROM oauth_scopes
             WHERE id = ?
             "#,
@@ -75,7 +75,7 @@ impl OAuthScopeRepository {
     pub async fn find_by_code(&self, code: &str) -> Result<Option<OAuthScope>, OAuthError> {
         let scope = sqlx::query_as::<_, OAuthScope>(
             r#"
-            SELECT id, code, description, is_active, created_at
+            SELECT id, code, description, is_active, owner_client_id, visibility, claims, created_at
             FROM oauth_scopes
             WHERE code = ?
             "#,
@@ -92,7 +92,7 @@ impl OAuthScopeRepository {
     pub async fn find_active_by_code(&self, code: &str) -> Result<Option<OAuthScope>, OAuthError> {
         let scope = sqlx::query_as::<_, OAuthScope>(
             r#"
-            SELECT id, code, description, is_active, created_at
+            SELECT id, code, description, is_active, owner_client_id, visibility, claims, created_at
             FROM oauth_scopes
             WHERE code = ? AND is_active = true
             "#,
@@ -115,7 +115,7 @@ impl OAuthScopeRepository {
         let placeholders = codes.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
         let query = format!(
             r#"
-            SELECT id, code, description, is_active, created_at
+            SELECT id, code, description, is_active, owner_client_id, visibility, claims, created_at
             FROM oauth_scopes
             WHERE code IN ({}) AND is_active = true
             "#,
@@ -135,15 +135,164 @@ impl OAuthScopeRepository {
         Ok(scopes)
     }
 
-    /// Validate that all scope codes exist and are active
+    /// Validate that all scope codes exist, are active and may be requested by the client
+    ///
+    /// Custom scopes are only valid for the client that defined them unless
+    /// an admin has approved them for global use.
     /// Requirements: 2.4
-    pub async fn validate_scopes(&self, codes: &[String]) -> Result<bool, OAuthError> {
+    pub async fn validate_scopes(&self, codes: &[String], client_id: Uuid) -> Result<bool, OAuthError> {
         if codes.is_empty() {
             return Ok(true);
         }
 
         let found_scopes = self.find_by_codes(codes).await?;
-        Ok(found_scopes.len() == codes.len())
+        Ok(found_scopes.len() == codes.len()
+            && found_scopes.iter().all(|scope| scope.is_available_to(client_id)))
+    }
+
+    /// Create a namespaced custom scope owned by an OAuth client
+    pub async fn create_custom(
+        &self,
+        owner_client_id: Uuid,
+        code: &str,
+        description: &str,
+        visibility: &str,
+        claims: &[String],
+    ) -> Result<OAuthScope, OAuthError> {
+        let id = Uuid::new_v4();
+
+        sqlx::query(
+            r#"
+            INSERT INTO oauth_scopes (id, code, description, owner_client_id, visibility, claims)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(code)
+        .bind(description)
+        .bind(owner_client_id.to_string())
+        .bind(visibility)
+        .bind(claims_json(claims)?)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            if let sqlx::Error::Database(db_err) = &e {
+                if db_err.code().map(|c| c == "23000").unwrap_or(false)
+                    || db_err.message().contains("Duplicate entry")
+                {
+                    return OAuthError::InvalidScope(format!("Scope code '{}' already exists", code));
+                }
+            }
+            OAuthError::ServerError(format!("Database error: {}", e))
+        })?;
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| OAuthError::ServerError("Failed to fetch created scope".to_string()))
+    }
+
+    /// List the custom scopes defined by an OAuth client
+    pub async fn list_by_owner(&self, owner_client_id: Uuid) -> Result<Vec<OAuthScope>, OAuthError> {
+        let scopes = sqlx::query_as::<_, OAuthScope>(
+            r#"
+            SELECT id, code, description, is_active, owner_client_id, visibility, claims, created_at
+            FROM oauth_scopes
+            WHERE owner_client_id = ?
+            ORDER BY code ASC
+            "#,
+        )
+        .bind(owner_client_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(scopes)
+    }
+
+    /// List scopes with the given visibility (e.g. the pending approval queue)
+    pub async fn list_by_visibility(&self, visibility: &str) -> Result<Vec<OAuthScope>, OAuthError> {
+        let scopes = sqlx::query_as::<_, OAuthScope>(
+            r#"
+            SELECT id, code, description, is_active, owner_client_id, visibility, claims, created_at
+            FROM oauth_scopes
+            WHERE visibility = ? AND owner_client_id IS NOT NULL
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(visibility)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(scopes)
+    }
+
+    /// Check if a scope namespace is already used by a standard scope or another client
+    pub async fn is_namespace_taken(&self, namespace: &str, client_id: Uuid) -> Result<bool, OAuthError> {
+        let taken = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM oauth_scopes
+            WHERE LOCATE(':', code) > 0
+              AND SUBSTRING_INDEX(code, ':', 1) = ?
+              AND (owner_client_id IS NULL OR owner_client_id <> ?)
+            "#,
+        )
+        .bind(namespace)
+        .bind(client_id.to_string())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(taken > 0)
+    }
+
+    /// Set the visibility of a custom scope
+    pub async fn set_visibility(&self, id: Uuid, visibility: &str) -> Result<OAuthScope, OAuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_scopes
+            SET visibility = ?
+            WHERE id = ? AND owner_client_id IS NOT NULL
+            "#,
+        )
+        .bind(visibility)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidScope("Scope not found".to_string()));
+        }
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| OAuthError::InvalidScope("Scope not found".to_string()))
+    }
+
+    /// Set the userinfo claims released by a custom scope
+    pub async fn set_claims(&self, id: Uuid, claims: &[String]) -> Result<OAuthScope, OAuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_scopes
+            SET claims = ?
+            WHERE id = ? AND owner_client_id IS NOT NULL
+            "#,
+        )
+        .bind(claims_json(claims)?)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidScope("Scope not found".to_string()));
+        }
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| OAuthError::InvalidScope("Scope not found".to_string()))
     }
 
     /// Update an OAuth scope
@@ -242,7 +391,7 @@ impl OAuthScopeRepository {
 
         let scopes = sqlx::query_as::<_, OAuthScope>(
             r#"
-            SELECT id, code, description, is_active, created_at
+            SELECT id, code, description, is_active, owner_client_id, visibility, claims, created_at
             FROM oauth_scopes
             ORDER BY code ASC
             LIMIT ? OFFSET ?
@@ -257,16 +406,17 @@ impl OAuthScopeRepository {
         Ok(scopes)
     }
 
-    /// List all active OAuth scopes
+    /// List all active OAuth scopes visible to every client
     pub async fn list_active(&self) -> Result<Vec<OAuthScope>, OAuthError> {
         let scopes = sqlx::query_as::<_, OAuthScope>(
             r#"
-            SELECT id, code, description, is_active, created_at
+            SELECT id, code, description, is_active, owner_client_id, visibility, claims, created_at
             FROM oauth_scopes
-            WHERE is_active = true
+            WHERE is_active = true AND visibility = ?
             ORDER BY code ASC
             "#,
         )
+        .bind(SCOPE_VISIBILITY_GLOBAL)
         .fetch_all(&self.pool)
         .await
         .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
@@ -289,3 +439,13 @@ impl OAuthScopeRepository {
         Ok(count as u64)
     }
 }
+
+/// Claims column value (NULL when the scope releases no claims)
+fn claims_json(claims: &[String]) -> Result<Option<serde_json::Value>, OAuthError> {
+    if claims.is_empty() {
+        return Ok(None);
+    }
+    serde_json::to_value(claims)
+        .map(Some)
+        .map_err(|e| OAuthError::ServerError(format!("Failed to serialize claims: {}", e)))
+}
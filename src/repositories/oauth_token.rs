@@ -4,6 +4,7 @@ use uuid::Uuid;
 
 use crate::error::OAuthError;
 use crate::models::OAuthToken;
+use crate::utils::posture::PostureContext;
 
 /// Repository for OAuth token database operations
 /// Requirements: 5.1, 5.6, 7.4, 9.2
@@ -28,6 +29,9 @@ impl OAuthTokenRepository {
         refresh_token_hash: Option<&str>,
         scopes: &[String],
         expires_in_seconds: i64,
+        session_started_at: Option<DateTime<Utc>>,
+        authorization_code_id: Option<Uuid>,
+        posture: Option<&PostureContext>,
     ) -> Result<OAuthToken, OAuthError> {
         let id = Uuid::new_v4();
         let expires_at = Utc::now() + Duration::seconds(expires_in_seconds);
@@ -37,8 +41,9 @@ impl OAuthTokenRepository {
         sqlx::query(
             r#"
             INSERT INTO oauth_tokens 
-            (id, user_id, client_id, access_token_hash, refresh_token_hash, scopes, expires_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?)
+            (id, user_id, client_id, access_token_hash, refresh_token_hash, scopes, expires_at,
+             session_started_at, authorization_code_id, posture)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id.to_string())
@@ -48,6 +53,9 @@ impl OAuthTokenRepository {
         .bind(refresh_token_hash)
         .bind(&scopes_json)
         .bind(expires_at)
+        .bind(session_started_at.unwrap_or_else(Utc::now))
+        .bind(authorization_code_id.map(|id| id.to_string()))
+        .bind(posture.map(|posture| serde_json::json!(posture)))
         .execute(&self.pool)
         .await
         .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
@@ -62,7 +70,7 @@ impl OAuthTokenRepository {
         let token = sqlx::query_as::<_, OAuthToken>(
             r#"
             SELECT id, user_id, client_id, access_token_hash, refresh_token_hash, 
-                   scopes, expires_at, revoked, created_at
+                   scopes, expires_at, revoked, session_started_at, authorization_code_id, posture, created_at
             FROM oauth_tokens
             WHERE id = ?
             "#,
@@ -80,7 +88,7 @@ impl OAuthTokenRepository {
         let token = sqlx::query_as::<_, OAuthToken>(
             r#"
             SELECT id, user_id, client_id, access_token_hash, refresh_token_hash, 
-                   scopes, expires_at, revoked, created_at
+                   scopes, expires_at, revoked, session_started_at, authorization_code_id, posture, created_at
             FROM oauth_tokens
             WHERE access_token_hash = ?
             "#,
@@ -98,7 +106,7 @@ impl OAuthTokenRepository {
         let token = sqlx::query_as::<_, OAuthToken>(
             r#"
             SELECT id, user_id, client_id, access_token_hash, refresh_token_hash, 
-                   scopes, expires_at, revoked, created_at
+                   scopes, expires_at, revoked, session_started_at, authorization_code_id, posture, created_at
             FROM oauth_tokens
             WHERE access_token_hash = ? AND revoked = false AND expires_at > NOW()
             "#,
@@ -116,7 +124,7 @@ impl OAuthTokenRepository {
         let token = sqlx::query_as::<_, OAuthToken>(
             r#"
             SELECT id, user_id, client_id, access_token_hash, refresh_token_hash, 
-                   scopes, expires_at, revoked, created_at
+                   scopes, expires_at, revoked, session_started_at, authorization_code_id, posture, created_at
             FROM oauth_tokens
             WHERE refresh_token_hash = ?
             "#,
@@ -135,7 +143,7 @@ impl OAuthTokenRepository {
         let token = sqlx::query_as::<_, OAuthToken>(
             r#"
             SELECT id, user_id, client_id, access_token_hash, refresh_token_hash, 
-                   scopes, expires_at, revoked, created_at
+                   scopes, expires_at, revoked, session_started_at, authorization_code_id, posture, created_at
             FROM oauth_tokens
             WHERE refresh_token_hash = ? AND revoked = false
             "#,
@@ -232,6 +240,23 @@ impl OAuthTokenRepository {
         Ok(result.rows_affected())
     }
 
+    /// Revoke every token issued from an authorization code (including rotations)
+    pub async fn revoke_by_authorization_code(&self, authorization_code_id: Uuid) -> Result<u64, OAuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_tokens
+            SET revoked = true
+            WHERE authorization_code_id = ? AND revoked = false
+            "#,
+        )
+        .bind(authorization_code_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Revoke all tokens for a client (service tokens)
     pub async fn revoke_all_for_client(&self, client_id: Uuid) -> Result<u64, OAuthError> {
         let result = sqlx::query(
@@ -266,6 +291,24 @@ impl OAuthTokenRepository {
         Ok(result.rows_affected())
     }
 
+    /// Clients the user holds an unrevoked refresh token or unexpired access token of
+    pub async fn list_client_ids_with_active_tokens(&self, user_id: Uuid) -> Result<Vec<Uuid>, OAuthError> {
+        let ids = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT DISTINCT client_id
+            FROM oauth_tokens
+            WHERE user_id = ? AND revoked = false
+              AND (refresh_token_hash IS NOT NULL OR expires_at > NOW())
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(ids.iter().filter_map(|id| Uuid::parse_str(id).ok()).collect())
+    }
+
     /// Check if a token is revoked
     pub async fn is_revoked(&self, id: Uuid) -> Result<bool, OAuthError> {
         let revoked = sqlx::query_scalar::<_, bool>(
@@ -288,7 +331,7 @@ impl OAuthTokenRepository {
         let tokens = sqlx::query_as::<_, OAuthToken>(
             r#"
             SELECT id, user_id, client_id, access_token_hash, refresh_token_hash, 
-                   scopes, expires_at, revoked, created_at
+                   scopes, expires_at, revoked, session_started_at, authorization_code_id, posture, created_at
             FROM oauth_tokens
             WHERE user_id = ?
             ORDER BY created_at DESC
@@ -311,7 +354,7 @@ impl OAuthTokenRepository {
         let tokens = sqlx::query_as::<_, OAuthToken>(
             r#"
             SELECT id, user_id, client_id, access_token_hash, refresh_token_hash, 
-                   scopes, expires_at, revoked, created_at
+                   scopes, expires_at, revoked, session_started_at, authorization_code_id, posture, created_at
             FROM oauth_tokens
             WHERE user_id = ? AND client_id = ? AND revoked = false
             ORDER BY created_at DESC
@@ -0,0 +1,228 @@
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    ProvisioningEvent, PROVISIONING_STATUS_DEAD_LETTER, PROVISIONING_STATUS_PENDING,
+    PROVISIONING_STATUS_PROCESSED,
+};
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, app_id, event_id, event_type, payload, source_api_key_id, status,
+           attempts, last_error, next_attempt_at, processed_at, created_at
+    FROM provisioning_events
+"#;
+
+/// Repository for inbound provisioning events
+#[derive(Clone)]
+pub struct ProvisioningEventRepository {
+    pool: MySqlPool,
+}
+
+impl ProvisioningEventRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a pending event
+    ///
+    /// Returns the stored event and whether it was new; a redelivered
+    /// `event_id` returns the existing event unchanged.
+    pub async fn create(
+        &self,
+        app_id: Uuid,
+        event_id: &str,
+        event_type: &str,
+        payload: &serde_json::Value,
+        source_api_key_id: Option<Uuid>,
+    ) -> Result<(ProvisioningEvent, bool), AppError> {
+        let id = Uuid::new_v4();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO provisioning_events (id, app_id, event_id, event_type, payload, source_api_key_id, status)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(app_id.to_string())
+        .bind(event_id)
+        .bind(event_type)
+        .bind(sqlx::types::Json(payload))
+        .bind(source_api_key_id.map(|id| id.to_string()))
+        .bind(PROVISIONING_STATUS_PENDING)
+        .execute(&self.pool)
+        .await;
+
+        let created = match result {
+            Ok(_) => true,
+            Err(sqlx::Error::Database(db_err))
+                if db_err.code().map(|c| c == "23000").unwrap_or(false)
+                    || db_err.message().contains("Duplicate entry") =>
+            {
+                false
+            }
+            Err(e) => return Err(AppError::InternalError(e.into())),
+        };
+
+        let event = self
+            .find_by_event_id(app_id, event_id)
+            .await?
+            .ok_or_else(|| AppError::InternalError(anyhow::anyhow!("Failed to fetch provisioning event")))?;
+
+        Ok((event, created))
+    }
+
+    /// Find an event by the producer's event ID
+    pub async fn find_by_event_id(&self, app_id: Uuid, event_id: &str) -> Result<Option<ProvisioningEvent>, AppError> {
+        let event = sqlx::query_as::<_, ProvisioningEvent>(&format!(
+            "{} WHERE app_id = ? AND event_id = ?",
+            SELECT_COLUMNS
+        ))
+        .bind(app_id.to_string())
+        .bind(event_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(event)
+    }
+
+    /// Pending events whose next attempt is due, oldest first
+    pub async fn find_due(&self, limit: i64) -> Result<Vec<ProvisioningEvent>, AppError> {
+        let events = sqlx::query_as::<_, ProvisioningEvent>(&format!(
+            "{} WHERE status = ? AND next_attempt_at <= NOW() ORDER BY created_at, id LIMIT ?",
+            SELECT_COLUMNS
+        ))
+        .bind(PROVISIONING_STATUS_PENDING)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(events)
+    }
+
+    /// List events of an app, newest first, optionally filtered by status
+    pub async fn list_by_app(
+        &self,
+        app_id: Uuid,
+        status: Option<&str>,
+        page: u32,
+        limit: u32,
+    ) -> Result<Vec<ProvisioningEvent>, AppError> {
+        let offset = page.saturating_sub(1) * limit;
+
+        let events = sqlx::query_as::<_, ProvisioningEvent>(&format!(
+            "{} WHERE app_id = ? AND (? IS NULL OR status = ?) ORDER BY created_at DESC, id LIMIT ? OFFSET ?",
+            SELECT_COLUMNS
+        ))
+        .bind(app_id.to_string())
+        .bind(status)
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(events)
+    }
+
+    /// Count events of an app, optionally filtered by status
+    pub async fn count_by_app(&self, app_id: Uuid, status: Option<&str>) -> Result<u64, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM provisioning_events
+            WHERE app_id = ? AND (? IS NULL OR status = ?)
+            "#,
+        )
+        .bind(app_id.to_string())
+        .bind(status)
+        .bind(status)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(count as u64)
+    }
+
+    /// Mark an event as applied
+    pub async fn mark_processed(&self, id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE provisioning_events
+            SET status = ?, attempts = attempts + 1, last_error = NULL, processed_at = NOW()
+            WHERE id = ?
+            "#,
+        )
+        .bind(PROVISIONING_STATUS_PROCESSED)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Record a failed attempt and schedule the next one
+    pub async fn mark_retry(&self, id: Uuid, error: &str, next_attempt_at: DateTime<Utc>) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE provisioning_events
+            SET attempts = attempts + 1, last_error = ?, next_attempt_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(error)
+        .bind(next_attempt_at)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Move an event to the dead-letter state
+    pub async fn mark_dead_letter(&self, id: Uuid, error: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE provisioning_events
+            SET status = ?, attempts = attempts + 1, last_error = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(PROVISIONING_STATUS_DEAD_LETTER)
+        .bind(error)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Put a dead-lettered event back in the queue with a fresh retry budget
+    /// Returns Ok(false) if the event is not dead-lettered
+    pub async fn requeue(&self, app_id: Uuid, event_id: &str) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE provisioning_events
+            SET status = ?, attempts = 0, next_attempt_at = NOW()
+            WHERE app_id = ? AND event_id = ? AND status = ?
+            "#,
+        )
+        .bind(PROVISIONING_STATUS_PENDING)
+        .bind(app_id.to_string())
+        .bind(event_id)
+        .bind(PROVISIONING_STATUS_DEAD_LETTER)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::PushMfaChallenge;
+
+/// Repository for push MFA challenge database operations
+#[derive(Clone)]
+pub struct PushMfaRepository {
+    pool: MySqlPool,
+}
+
+impl PushMfaRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a new pending challenge
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        mfa_token_hash: &str,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<PushMfaChallenge, AuthError> {
+        let id = Uuid::new_v4();
+
+        sqlx::query(
+            r#"
+            INSERT INTO push_mfa_challenges (id, user_id, mfa_token_hash, ip_address, user_agent, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(user_id.to_string())
+        .bind(mfa_token_hash)
+        .bind(ip_address)
+        .bind(user_agent)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        self.find_by_id(id).await?.ok_or(AuthError::InternalError(anyhow::anyhow!("Failed to fetch created push challenge")))
+    }
+
+    /// Find challenge by ID
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<PushMfaChallenge>, AuthError> {
+        let challenge = sqlx::query_as::<_, PushMfaChallenge>(
+            r#"
+            SELECT id, user_id, mfa_token_hash, status, device_id, ip_address, user_agent, responded_at, expires_at, created_at
+            FROM push_mfa_challenges
+            WHERE id = ?
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(challenge)
+    }
+
+    /// Record a device's approve/deny answer on a pending challenge
+    /// Returns false if the challenge was already answered or has expired
+    pub async fn respond(&self, id: Uuid, device_id: Uuid, approved: bool) -> Result<bool, AuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE push_mfa_challenges
+            SET status = ?, device_id = ?, responded_at = NOW()
+            WHERE id = ? AND status = 'pending' AND expires_at > NOW()
+            "#,
+        )
+        .bind(if approved { "approved" } else { "denied" })
+        .bind(device_id.to_string())
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Atomically consume an approved challenge so it completes a single login
+    pub async fn consume(&self, id: Uuid) -> Result<bool, AuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE push_mfa_challenges
+            SET status = 'consumed'
+            WHERE id = ? AND status = 'approved' AND expires_at > NOW()
+            "#,
+        )
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
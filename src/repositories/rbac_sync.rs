@@ -0,0 +1,257 @@
+use std::collections::{HashMap, HashSet};
+
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::RbacSyncError;
+use crate::models::{Permission, RbacCatalog, RbacChangeSummary, RbacMapping, Role, RolePermission};
+
+/// Repository reconciling an app's roles, permissions and mappings with a desired catalog
+///
+/// Names and codes are matched ignoring case, like the unique indexes on
+/// `roles` and `permissions`; a change in case only renames the row.
+#[derive(Clone)]
+pub struct RbacSyncRepository {
+    pool: MySqlPool,
+}
+
+fn internal(e: sqlx::Error) -> RbacSyncError {
+    RbacSyncError::InternalError(e.into())
+}
+
+impl RbacSyncRepository {
+    /// Create a new RbacSyncRepository with the given database pool
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Reconcile the app's catalog in a single transaction
+    ///
+    /// Roles missing from the catalog are deleted unless users still hold
+    /// them; those are left untouched and reported as retained. With
+    /// `dry_run` the transaction is rolled back and only the summary is returned.
+    pub async fn sync_catalog(
+        &self,
+        app_id: Uuid,
+        catalog: &RbacCatalog,
+        dry_run: bool,
+    ) -> Result<RbacChangeSummary, RbacSyncError> {
+        let mut tx = self.pool.begin().await.map_err(internal)?;
+
+        // Serialize concurrent syncs of the same app
+        sqlx::query("SELECT id FROM apps WHERE id = ? FOR UPDATE")
+            .bind(app_id.to_string())
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(internal)?;
+
+        let roles = sqlx::query_as::<_, Role>("SELECT id, app_id, name FROM roles WHERE app_id = ?")
+            .bind(app_id.to_string())
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(internal)?;
+
+        let permissions = sqlx::query_as::<_, Permission>(
+            "SELECT id, app_id, code FROM permissions WHERE app_id = ?",
+        )
+        .bind(app_id.to_string())
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(internal)?;
+
+        let mappings = sqlx::query_as::<_, RolePermission>(
+            r#"
+            SELECT rp.role_id, rp.permission_id
+            FROM role_permissions rp
+            INNER JOIN roles r ON r.id = rp.role_id
+            WHERE r.app_id = ?
+            "#,
+        )
+        .bind(app_id.to_string())
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(internal)?;
+
+        let assigned_role_ids: HashSet<Uuid> = sqlx::query_scalar::<_, String>(
+            "SELECT DISTINCT role_id FROM user_app_roles WHERE app_id = ?",
+        )
+        .bind(app_id.to_string())
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(internal)?
+        .iter()
+        .filter_map(|id| Uuid::parse_str(id).ok())
+        .collect();
+
+        let mut summary = RbacChangeSummary::default();
+
+        // Permissions: create missing ones, fix letter case of existing ones
+        let existing_permissions: HashMap<String, &Permission> =
+            permissions.iter().map(|p| (p.code.to_lowercase(), p)).collect();
+        let mut permission_ids: HashMap<String, Uuid> = HashMap::new();
+        for code in &catalog.permissions {
+            let key = code.to_lowercase();
+            let id = match existing_permissions.get(&key) {
+                Some(existing) => {
+                    if existing.code != *code {
+                        sqlx::query("UPDATE permissions SET code = ? WHERE id = ?")
+                            .bind(code)
+                            .bind(existing.id.to_string())
+                            .execute(&mut *tx)
+                            .await
+                            .map_err(internal)?;
+                        summary.permissions_updated.push(code.clone());
+                    }
+                    existing.id
+                }
+                None => {
+                    let id = Uuid::new_v4();
+                    sqlx::query("INSERT INTO permissions (id, app_id, code) VALUES (?, ?, ?)")
+                        .bind(id.to_string())
+                        .bind(app_id.to_string())
+                        .bind(code)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(internal)?;
+                    summary.permissions_created.push(code.clone());
+                    id
+                }
+            };
+            permission_ids.insert(key, id);
+        }
+
+        // Roles: create missing ones, fix letter case of existing ones
+        let existing_roles: HashMap<String, &Role> =
+            roles.iter().map(|r| (r.name.to_lowercase(), r)).collect();
+        let mut role_ids: HashMap<String, Uuid> = HashMap::new();
+        for spec in &catalog.roles {
+            let key = spec.name.to_lowercase();
+            let id = match existing_roles.get(&key) {
+                Some(existing) => {
+                    if existing.name != spec.name {
+                        sqlx::query("UPDATE roles SET name = ? WHERE id = ?")
+                            .bind(&spec.name)
+                            .bind(existing.id.to_string())
+                            .execute(&mut *tx)
+                            .await
+                            .map_err(internal)?;
+                        summary.roles_updated.push(spec.name.clone());
+                    }
+                    existing.id
+                }
+                None => {
+                    let id = Uuid::new_v4();
+                    sqlx::query("INSERT INTO roles (id, app_id, name) VALUES (?, ?, ?)")
+                        .bind(id.to_string())
+                        .bind(app_id.to_string())
+                        .bind(&spec.name)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(internal)?;
+                    summary.roles_created.push(spec.name.clone());
+                    id
+                }
+            };
+            role_ids.insert(key, id);
+        }
+
+        // Mappings of the desired roles
+        let permission_codes: HashMap<Uuid, &str> =
+            permissions.iter().map(|p| (p.id, p.code.as_str())).collect();
+        let role_names: HashMap<Uuid, &str> = roles.iter().map(|r| (r.id, r.name.as_str())).collect();
+        let current: HashSet<(Uuid, Uuid)> =
+            mappings.iter().map(|m| (m.role_id, m.permission_id)).collect();
+
+        for spec in &catalog.roles {
+            let role_id = role_ids[&spec.name.to_lowercase()];
+            let mut desired: HashSet<Uuid> = HashSet::new();
+
+            for code in &spec.permissions {
+                let permission_id = permission_ids[&code.to_lowercase()];
+                if !desired.insert(permission_id) || current.contains(&(role_id, permission_id)) {
+                    continue;
+                }
+                sqlx::query("INSERT INTO role_permissions (role_id, permission_id) VALUES (?, ?)")
+                    .bind(role_id.to_string())
+                    .bind(permission_id.to_string())
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(internal)?;
+                summary.mappings_added.push(RbacMapping {
+                    role: spec.name.clone(),
+                    permission: code.clone(),
+                });
+            }
+
+            for &(mapped_role, permission_id) in &current {
+                if mapped_role != role_id || desired.contains(&permission_id) {
+                    continue;
+                }
+                sqlx::query("DELETE FROM role_permissions WHERE role_id = ? AND permission_id = ?")
+                    .bind(role_id.to_string())
+                    .bind(permission_id.to_string())
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(internal)?;
+                summary.mappings_removed.push(RbacMapping {
+                    role: spec.name.clone(),
+                    permission: permission_codes.get(&permission_id).unwrap_or(&"").to_string(),
+                });
+            }
+        }
+
+        // Roles missing from the catalog
+        let mut retained_role_ids: HashSet<Uuid> = HashSet::new();
+        for role in &roles {
+            if role_ids.contains_key(&role.name.to_lowercase()) {
+                continue;
+            }
+            if assigned_role_ids.contains(&role.id) {
+                retained_role_ids.insert(role.id);
+                summary.roles_retained.push(role.name.clone());
+                continue;
+            }
+            sqlx::query("DELETE FROM roles WHERE id = ?")
+                .bind(role.id.to_string())
+                .execute(&mut *tx)
+                .await
+                .map_err(internal)?;
+            summary.roles_deleted.push(role.name.clone());
+        }
+
+        // Permissions missing from the catalog (their mappings cascade)
+        for permission in &permissions {
+            if permission_ids.contains_key(&permission.code.to_lowercase()) {
+                continue;
+            }
+            for &(role_id, permission_id) in &current {
+                if permission_id == permission.id && retained_role_ids.contains(&role_id) {
+                    summary.mappings_removed.push(RbacMapping {
+                        role: role_names.get(&role_id).unwrap_or(&"").to_string(),
+                        permission: permission.code.clone(),
+                    });
+                }
+            }
+            sqlx::query("DELETE FROM permissions WHERE id = ?")
+                .bind(permission.id.to_string())
+                .execute(&mut *tx)
+                .await
+                .map_err(internal)?;
+            summary.permissions_deleted.push(permission.code.clone());
+        }
+
+        if dry_run {
+            tx.rollback().await.map_err(internal)?;
+        } else {
+            tx.commit().await.map_err(internal)?;
+        }
+
+        summary.roles_deleted.sort();
+        summary.roles_retained.sort();
+        summary.permissions_deleted.sort();
+        summary.mappings_added.sort();
+        summary.mappings_removed.sort();
+
+        Ok(summary)
+    }
+}
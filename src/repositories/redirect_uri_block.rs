@@ -0,0 +1,109 @@
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::OAuthError;
+use crate::models::RedirectUriBlock;
+
+/// Repository for the admin-managed redirect URI blocklist
+#[derive(Clone)]
+pub struct RedirectUriBlockRepository {
+    pool: MySqlPool,
+}
+
+impl RedirectUriBlockRepository {
+    /// Create a new RedirectUriBlockRepository with the given database pool
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Add a pattern to the blocklist
+    pub async fn create(
+        &self,
+        pattern: &str,
+        reason: Option<&str>,
+        created_by: Option<Uuid>,
+    ) -> Result<RedirectUriBlock, OAuthError> {
+        let id = Uuid::new_v4();
+
+        sqlx::query(
+            r#"
+            INSERT INTO redirect_uri_blocklist (id, pattern, reason, created_by)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(pattern)
+        .bind(reason)
+        .bind(created_by.map(|u| u.to_string()))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            if let sqlx::Error::Database(db_err) = &e {
+                if db_err.code().map(|c| c == "23000").unwrap_or(false)
+                    || db_err.message().contains("Duplicate entry")
+                {
+                    return OAuthError::InvalidRequest(format!("Pattern '{}' is already blocked", pattern));
+                }
+            }
+            OAuthError::ServerError(format!("Database error: {}", e))
+        })?;
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| OAuthError::ServerError("Failed to fetch created blocklist entry".to_string()))
+    }
+
+    /// Find a blocklist entry by its UUID
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<RedirectUriBlock>, OAuthError> {
+        let block = sqlx::query_as::<_, RedirectUriBlock>(
+            r#"
+            SELECT id, pattern, reason, created_by, created_at
+            FROM redirect_uri_blocklist
+            WHERE id = ?
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(block)
+    }
+
+    /// List all blocklist entries
+    pub async fn list_all(&self) -> Result<Vec<RedirectUriBlock>, OAuthError> {
+        let blocks = sqlx::query_as::<_, RedirectUriBlock>(
+            r#"
+            SELECT id, pattern, reason, created_by, created_at
+            FROM redirect_uri_blocklist
+            ORDER BY created_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        Ok(blocks)
+    }
+
+    /// Find the first blocklist entry matching a redirect URI
+    pub async fn find_matching(&self, uri: &str) -> Result<Option<RedirectUriBlock>, OAuthError> {
+        let blocks = self.list_all().await?;
+        Ok(blocks.into_iter().find(|b| b.matches(uri)))
+    }
+
+    /// Remove a pattern from the blocklist
+    pub async fn delete(&self, id: Uuid) -> Result<(), OAuthError> {
+        let result = sqlx::query("DELETE FROM redirect_uri_blocklist WHERE id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthError::InvalidRequest("Blocklist entry not found".to_string()));
+        }
+
+        Ok(())
+    }
+}
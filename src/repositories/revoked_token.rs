@@ -46,6 +46,39 @@ impl RevokedTokenRepository {
         Ok(())
     }
 
+    /// Add a token to the revocation list unless it is already on it
+    ///
+    /// Returns whether this call revoked it, so of two concurrent callers
+    /// only one goes on.
+    pub async fn revoke_once(
+        &self,
+        token_hash: &str,
+        token_type: &str,
+        user_id: Option<Uuid>,
+        expires_at: DateTime<Utc>,
+        reason: Option<&str>,
+    ) -> Result<bool, AuthError> {
+        let id = Uuid::new_v4();
+
+        let result = sqlx::query(
+            r#"
+            INSERT IGNORE INTO revoked_tokens (id, token_hash, token_type, user_id, expires_at, reason)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(token_hash)
+        .bind(token_type)
+        .bind(user_id.map(|u| u.to_string()))
+        .bind(expires_at)
+        .bind(reason)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
     /// Check if a token is revoked
     pub async fn is_revoked(&self, token_hash: &str) -> Result<bool, AuthError> {
         let exists = sqlx::query_scalar::<_, i64>(
@@ -63,6 +96,50 @@ impl RevokedTokenRepository {
         Ok(exists > 0)
     }
 
+    /// Check if a token was revoked for the given reason
+    pub async fn is_revoked_for_reason(&self, token_hash: &str, reason: &str) -> Result<bool, AuthError> {
+        let exists = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) as count
+            FROM revoked_tokens
+            WHERE token_hash = ? AND reason = ?
+            "#,
+        )
+        .bind(token_hash)
+        .bind(reason)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(exists > 0)
+    }
+
+    /// Check if a user's token is revoked, by its hash or by a revoke-all of
+    /// the user made at or after `issued_at` (Unix seconds)
+    pub async fn is_revoked_for_user(
+        &self,
+        token_hash: &str,
+        user_id: Uuid,
+        issued_at: i64,
+    ) -> Result<bool, AuthError> {
+        let exists = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) as count
+            FROM revoked_tokens
+            WHERE token_hash = ?
+               OR (user_id = ? AND token_type = 'all' AND revoked_at >= FROM_UNIXTIME(?))
+            "#,
+        )
+        .bind(token_hash)
+        .bind(user_id.to_string())
+        .bind(issued_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(exists > 0)
+    }
+
     /// Revoke all tokens for a user
     pub async fn revoke_all_for_user(
         &self,
@@ -119,6 +119,7 @@ impl RoleRepository {
             FROM roles r
             INNER JOIN user_app_roles uar ON r.id = uar.role_id
             WHERE uar.user_id = ? AND uar.app_id = ?
+              AND (uar.expires_at IS NULL OR uar.expires_at > NOW())
             ORDER BY r.name
             "#,
         )
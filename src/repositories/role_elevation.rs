@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{RoleElevationRequest, ELEVATION_STATUS_PENDING};
+
+/// Repository for temporary role elevation requests
+#[derive(Clone)]
+pub struct RoleElevationRepository {
+    pool: MySqlPool,
+}
+
+impl RoleElevationRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a pending elevation request
+    pub async fn create(
+        &self,
+        app_id: Uuid,
+        user_id: Uuid,
+        role_id: Uuid,
+        reason: Option<&str>,
+        duration_secs: i64,
+    ) -> Result<RoleElevationRequest, AppError> {
+        let id = Uuid::new_v4();
+
+        sqlx::query(
+            r#"
+            INSERT INTO role_elevation_requests (id, app_id, user_id, role_id, reason, duration_secs, status)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(app_id.to_string())
+        .bind(user_id.to_string())
+        .bind(role_id.to_string())
+        .bind(reason)
+        .bind(duration_secs)
+        .bind(ELEVATION_STATUS_PENDING)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::InternalError(anyhow::anyhow!("Failed to fetch created elevation request")))
+    }
+
+    /// Find an elevation request by ID
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<RoleElevationRequest>, AppError> {
+        let request = sqlx::query_as::<_, RoleElevationRequest>(
+            r#"
+            SELECT id, app_id, user_id, role_id, reason, duration_secs, status,
+                   decided_by, decided_at, grant_expires_at, created_at
+            FROM role_elevation_requests
+            WHERE id = ?
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(request)
+    }
+
+    /// List elevation requests of an app, newest first, optionally filtered by status
+    pub async fn list_by_app(
+        &self,
+        app_id: Uuid,
+        status: Option<&str>,
+    ) -> Result<Vec<RoleElevationRequest>, AppError> {
+        let requests = sqlx::query_as::<_, RoleElevationRequest>(
+            r#"
+            SELECT id, app_id, user_id, role_id, reason, duration_secs, status,
+                   decided_by, decided_at, grant_expires_at, created_at
+            FROM role_elevation_requests
+            WHERE app_id = ? AND (? IS NULL OR status = ?)
+            ORDER BY created_at DESC
+            LIMIT 500
+            "#,
+        )
+        .bind(app_id.to_string())
+        .bind(status)
+        .bind(status)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(requests)
+    }
+
+    /// Check whether the user already has a pending request for the role
+    pub async fn has_pending(&self, app_id: Uuid, user_id: Uuid, role_id: Uuid) -> Result<bool, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM role_elevation_requests
+            WHERE app_id = ? AND user_id = ? AND role_id = ? AND status = ?
+            "#,
+        )
+        .bind(app_id.to_string())
+        .bind(user_id.to_string())
+        .bind(role_id.to_string())
+        .bind(ELEVATION_STATUS_PENDING)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(count > 0)
+    }
+
+    /// Record the decision on a pending request
+    /// Returns Ok(false) if the request was already decided
+    pub async fn decide(
+        &self,
+        id: Uuid,
+        status: &str,
+        decided_by: Uuid,
+        grant_expires_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE role_elevation_requests
+            SET status = ?, decided_by = ?, decided_at = NOW(), grant_expires_at = ?
+            WHERE id = ? AND status = ?
+            "#,
+        )
+        .bind(status)
+        .bind(decided_by.to_string())
+        .bind(grant_expires_at)
+        .bind(id.to_string())
+        .bind(ELEVATION_STATUS_PENDING)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::RoleError;
+use crate::models::{RoleAssignmentHistory, RoleChangeActor};
+
+/// Repository for the role assignment history
+#[derive(Clone)]
+pub struct RoleHistoryRepository {
+    pool: MySqlPool,
+}
+
+impl RoleHistoryRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a grant or removal; the role name is captured from `roles` if it still exists
+    pub async fn record(
+        &self,
+        app_id: Uuid,
+        user_id: Uuid,
+        role_id: Uuid,
+        action: &str,
+        actor: RoleChangeActor,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(), RoleError> {
+        sqlx::query(
+            r#"
+            INSERT INTO role_assignment_history
+                (id, app_id, user_id, role_id, role_name, action, actor_type, actor_id, expires_at)
+            VALUES (?, ?, ?, ?, (SELECT name FROM roles WHERE id = ?), ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(app_id.to_string())
+        .bind(user_id.to_string())
+        .bind(role_id.to_string())
+        .bind(role_id.to_string())
+        .bind(action)
+        .bind(actor.actor_type)
+        .bind(actor.actor_id.map(|id| id.to_string()))
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RoleError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// List the history of a user's roles in an app, newest first
+    pub async fn list_for_user(
+        &self,
+        app_id: Uuid,
+        user_id: Uuid,
+        page: u32,
+        limit: u32,
+    ) -> Result<Vec<RoleAssignmentHistory>, RoleError> {
+        let offset = (page.saturating_sub(1)) * limit;
+
+        let entries = sqlx::query_as::<_, RoleAssignmentHistory>(
+            r#"
+            SELECT id, app_id, user_id, role_id, role_name, action, actor_type, actor_id, expires_at, created_at
+            FROM role_assignment_history
+            WHERE app_id = ? AND user_id = ?
+            ORDER BY created_at DESC
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(app_id.to_string())
+        .bind(user_id.to_string())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RoleError::InternalError(e.into()))?;
+
+        Ok(entries)
+    }
+
+    /// Count history entries for a user in an app
+    pub async fn count_for_user(&self, app_id: Uuid, user_id: Uuid) -> Result<u64, RoleError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM role_assignment_history
+            WHERE app_id = ? AND user_id = ?
+            "#,
+        )
+        .bind(app_id.to_string())
+        .bind(user_id.to_string())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RoleError::InternalError(e.into()))?;
+
+        Ok(count as u64)
+    }
+}
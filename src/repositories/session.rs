@@ -5,6 +5,12 @@ use uuid::Uuid;
 use crate::error::AuthError;
 use crate::models::UserSession;
 
+/// Session insert issued on every login and token refresh
+pub(crate) const INSERT_SESSION_SQL: &str = r#"
+    INSERT INTO user_sessions (id, user_id, app_id, refresh_token_hash, device_name, device_type, ip_address, user_agent, expires_at)
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#;
+
 /// Repository for user session database operations
 #[derive(Clone)]
 pub struct SessionRepository {
@@ -19,7 +25,9 @@ impl SessionRepository {
     /// Create a new session
     pub async fn create(
         &self,
+        id: Uuid,
         user_id: Uuid,
+        app_id: Option<Uuid>,
         refresh_token_hash: &str,
         device_name: Option<&str>,
         device_type: Option<&str>,
@@ -27,16 +35,10 @@ impl SessionRepository {
         user_agent: Option<&str>,
         expires_at: DateTime<Utc>,
     ) -> Result<UserSession, AuthError> {
-        let id = Uuid::new_v4();
-
-        sqlx::query(
-            r#"
-            INSERT INTO user_sessions (id, user_id, refresh_token_hash, device_name, device_type, ip_address, user_agent, expires_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
+        sqlx::query(INSERT_SESSION_SQL)
         .bind(id.to_string())
         .bind(user_id.to_string())
+        .bind(app_id.map(|id| id.to_string()))
         .bind(refresh_token_hash)
         .bind(device_name)
         .bind(device_type)
@@ -54,7 +56,7 @@ impl SessionRepository {
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<UserSession>, AuthError> {
         let session = sqlx::query_as::<_, UserSession>(
             r#"
-            SELECT id, user_id, refresh_token_hash, device_name, device_type, ip_address, user_agent, 
+            SELECT id, user_id, device_id, app_id, refresh_token_hash, device_name, device_type, ip_address, user_agent, 
                    last_active_at, expires_at, is_revoked, revoked_at, created_at
             FROM user_sessions
             WHERE id = ?
@@ -72,7 +74,7 @@ impl SessionRepository {
     pub async fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<UserSession>, AuthError> {
         let session = sqlx::query_as::<_, UserSession>(
             r#"
-            SELECT id, user_id, refresh_token_hash, device_name, device_type, ip_address, user_agent, 
+            SELECT id, user_id, device_id, app_id, refresh_token_hash, device_name, device_type, ip_address, user_agent, 
                    last_active_at, expires_at, is_revoked, revoked_at, created_at
             FROM user_sessions
             WHERE refresh_token_hash = ? AND is_revoked = FALSE AND expires_at > NOW()
@@ -86,11 +88,29 @@ impl SessionRepository {
         Ok(session)
     }
 
+    /// Find session by refresh token hash regardless of revocation or expiry
+    pub async fn find_any_by_token_hash(&self, token_hash: &str) -> Result<Option<UserSession>, AuthError> {
+        let session = sqlx::query_as::<_, UserSession>(
+            r#"
+            SELECT id, user_id, device_id, app_id, refresh_token_hash, device_name, device_type, ip_address, user_agent, 
+                   last_active_at, expires_at, is_revoked, revoked_at, created_at
+            FROM user_sessions
+            WHERE refresh_token_hash = ?
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(session)
+    }
+
     /// List active sessions for a user
     pub async fn list_active_by_user(&self, user_id: Uuid) -> Result<Vec<UserSession>, AuthError> {
         let sessions = sqlx::query_as::<_, UserSession>(
             r#"
-            SELECT id, user_id, refresh_token_hash, device_name, device_type, ip_address, user_agent, 
+            SELECT id, user_id, device_id, app_id, refresh_token_hash, device_name, device_type, ip_address, user_agent, 
                    last_active_at, expires_at, is_revoked, revoked_at, created_at
             FROM user_sessions
             WHERE user_id = ? AND is_revoked = FALSE AND expires_at > NOW()
@@ -122,6 +142,71 @@ impl SessionRepository {
         Ok(())
     }
 
+    /// Bind a session to a registered device and extend its expiry
+    pub async fn bind_device(&self, id: Uuid, device_id: Uuid, expires_at: DateTime<Utc>) -> Result<(), AuthError> {
+        sqlx::query(
+            r#"
+            UPDATE user_sessions
+            SET device_id = ?, expires_at = ?
+            WHERE id = ? AND is_revoked = FALSE
+            "#,
+        )
+        .bind(device_id.to_string())
+        .bind(expires_at)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Replace the session's refresh token hash (rotation) and set a new expiry
+    ///
+    /// Only swaps out `old_token_hash`; returns false when the session no
+    /// longer holds it, i.e. another refresh with the same token won.
+    pub async fn rotate_refresh_token(
+        &self,
+        id: Uuid,
+        old_token_hash: &str,
+        refresh_token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool, AuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_sessions
+            SET refresh_token_hash = ?, expires_at = ?, last_active_at = NOW()
+            WHERE id = ? AND refresh_token_hash = ?
+            "#,
+        )
+        .bind(refresh_token_hash)
+        .bind(expires_at)
+        .bind(id.to_string())
+        .bind(old_token_hash)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Check whether a session is revoked; a session that no longer exists counts as revoked
+    pub async fn is_revoked(&self, id: Uuid) -> Result<bool, AuthError> {
+        let active = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) as count
+            FROM user_sessions
+            WHERE id = ? AND is_revoked = FALSE
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(active == 0)
+    }
+
     /// Revoke a specific session
     pub async fn revoke(&self, id: Uuid) -> Result<(), AuthError> {
         let result = sqlx::query(
@@ -160,6 +245,23 @@ impl SessionRepository {
         Ok(result.rows_affected())
     }
 
+    /// Revoke all sessions bound to a device
+    pub async fn revoke_by_device(&self, device_id: Uuid) -> Result<u64, AuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_sessions
+            SET is_revoked = TRUE, revoked_at = NOW()
+            WHERE device_id = ? AND is_revoked = FALSE
+            "#,
+        )
+        .bind(device_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Revoke all sessions except current
     pub async fn revoke_all_except(&self, user_id: Uuid, current_session_id: Uuid) -> Result<u64, AuthError> {
         let result = sqlx::query(
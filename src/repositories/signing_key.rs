@@ -0,0 +1,241 @@
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    SigningKeyRecord, SIGNING_KEY_ACTIVE, SIGNING_KEY_IMPORTED, SIGNING_KEY_NEXT, SIGNING_KEY_RETIRED,
+    SIGNING_KEY_SOURCE_CONFIG, SIGNING_KEY_SOURCE_GENERATED, SIGNING_KEY_SOURCE_IMPORTED,
+};
+use crate::utils::field_crypto::{open_optional, seal_field, EncryptedColumn};
+use crate::utils::signing_key::SigningKey;
+
+/// Decrypt the private key of a row read from the database
+fn open_key(mut record: SigningKeyRecord) -> SigningKeyRecord {
+    let id = record.id.clone();
+    record.private_key = open_optional(EncryptedColumn::SigningKey, &id, record.private_key);
+    record
+}
+
+/// Repository for token signing keys
+#[derive(Clone)]
+pub struct SigningKeyRepository {
+    pool: MySqlPool,
+}
+
+impl SigningKeyRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store an imported key pair; it signs nothing until staged and promoted
+    pub async fn create_imported(
+        &self,
+        key: &SigningKey,
+        private_key_pem: &str,
+        imported_by: Option<Uuid>,
+    ) -> Result<SigningKeyRecord, AppError> {
+        self.create(key, private_key_pem, SIGNING_KEY_SOURCE_IMPORTED, imported_by, false)
+            .await
+    }
+
+    /// Store a key pair generated by the server, to be promoted automatically
+    pub async fn create_generated(
+        &self,
+        key: &SigningKey,
+        private_key_pem: &str,
+        generated_by: Option<Uuid>,
+    ) -> Result<SigningKeyRecord, AppError> {
+        self.create(key, private_key_pem, SIGNING_KEY_SOURCE_GENERATED, generated_by, true)
+            .await
+    }
+
+    async fn create(
+        &self,
+        key: &SigningKey,
+        private_key_pem: &str,
+        source: &str,
+        created_by: Option<Uuid>,
+        auto_promote: bool,
+    ) -> Result<SigningKeyRecord, AppError> {
+        let id = Uuid::new_v4().to_string();
+
+        sqlx::query(
+            r#"
+            INSERT INTO signing_keys
+                (id, kid, fingerprint, public_key_pem, jwk_n, jwk_e, private_key, source, status, imported_by, auto_promote)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(key.kid())
+        .bind(&key.fingerprint)
+        .bind(&key.public_key_pem)
+        .bind(&key.verification.jwk.n)
+        .bind(&key.verification.jwk.e)
+        .bind(seal_field(EncryptedColumn::SigningKey, private_key_pem))
+        .bind(source)
+        .bind(SIGNING_KEY_IMPORTED)
+        .bind(created_by.map(|u| u.to_string()))
+        .bind(auto_promote)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        self.find_by_kid(key.kid())
+            .await?
+            .ok_or_else(|| AppError::InternalError(anyhow::anyhow!("Signing key vanished after insert")))
+    }
+
+    /// Record the configured key as the active one (when no key is active yet)
+    ///
+    /// Its private key stays in the environment and is not stored.
+    pub async fn activate_config_key(&self, key: &SigningKey) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO signing_keys
+                (id, kid, fingerprint, public_key_pem, jwk_n, jwk_e, source, status, activated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, NOW())
+            ON DUPLICATE KEY UPDATE status = VALUES(status), activated_at = NOW(), retired_at = NULL
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(key.kid())
+        .bind(&key.fingerprint)
+        .bind(&key.public_key_pem)
+        .bind(&key.verification.jwk.n)
+        .bind(&key.verification.jwk.e)
+        .bind(SIGNING_KEY_SOURCE_CONFIG)
+        .bind(SIGNING_KEY_ACTIVE)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_kid(&self, kid: &str) -> Result<Option<SigningKeyRecord>, AppError> {
+        let key = sqlx::query_as::<_, SigningKeyRecord>("SELECT * FROM signing_keys WHERE kid = ?")
+            .bind(kid)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(key.map(open_key))
+    }
+
+    pub async fn find_active(&self) -> Result<Option<SigningKeyRecord>, AppError> {
+        let key = sqlx::query_as::<_, SigningKeyRecord>(
+            "SELECT * FROM signing_keys WHERE status = ? ORDER BY activated_at DESC LIMIT 1",
+        )
+        .bind(SIGNING_KEY_ACTIVE)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(key.map(open_key))
+    }
+
+    /// The staged next key, if any
+    pub async fn find_next(&self) -> Result<Option<SigningKeyRecord>, AppError> {
+        let key = sqlx::query_as::<_, SigningKeyRecord>("SELECT * FROM signing_keys WHERE status = ? LIMIT 1")
+            .bind(SIGNING_KEY_NEXT)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(key.map(open_key))
+    }
+
+    /// All keys, newest first
+    pub async fn list(&self) -> Result<Vec<SigningKeyRecord>, AppError> {
+        let keys = sqlx::query_as::<_, SigningKeyRecord>("SELECT * FROM signing_keys ORDER BY created_at DESC")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(keys.into_iter().map(open_key).collect())
+    }
+
+    /// Keys published in the JWKS: next, active, and retired after `retired_since`
+    pub async fn find_published(&self, retired_since: DateTime<Utc>) -> Result<Vec<SigningKeyRecord>, AppError> {
+        let keys = sqlx::query_as::<_, SigningKeyRecord>(
+            r#"
+            SELECT * FROM signing_keys
+            WHERE status IN (?, ?) OR (status = ? AND retired_at >= ?)
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(SIGNING_KEY_NEXT)
+        .bind(SIGNING_KEY_ACTIVE)
+        .bind(SIGNING_KEY_RETIRED)
+        .bind(retired_since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(keys.into_iter().map(open_key).collect())
+    }
+
+    /// Stage an imported key as next, unstaging any previous next key
+    ///
+    /// Returns false if the key is not in the imported state.
+    pub async fn stage(&self, kid: &str) -> Result<bool, AppError> {
+        let mut tx = self.pool.begin().await.map_err(|e| AppError::InternalError(e.into()))?;
+
+        sqlx::query("UPDATE signing_keys SET status = ?, staged_at = NULL WHERE status = ? AND kid <> ?")
+            .bind(SIGNING_KEY_IMPORTED)
+            .bind(SIGNING_KEY_NEXT)
+            .bind(kid)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        let result = sqlx::query("UPDATE signing_keys SET status = ?, staged_at = NOW() WHERE kid = ? AND status = ?")
+            .bind(SIGNING_KEY_NEXT)
+            .bind(kid)
+            .bind(SIGNING_KEY_IMPORTED)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            tx.rollback().await.map_err(|e| AppError::InternalError(e.into()))?;
+            return Ok(false);
+        }
+
+        tx.commit().await.map_err(|e| AppError::InternalError(e.into()))?;
+        Ok(true)
+    }
+
+    /// Make the next key active and retire the previously active one
+    ///
+    /// Returns false if the key is not the staged next key.
+    pub async fn promote(&self, kid: &str) -> Result<bool, AppError> {
+        let mut tx = self.pool.begin().await.map_err(|e| AppError::InternalError(e.into()))?;
+
+        let result = sqlx::query("UPDATE signing_keys SET status = ?, activated_at = NOW() WHERE kid = ? AND status = ?")
+            .bind(SIGNING_KEY_ACTIVE)
+            .bind(kid)
+            .bind(SIGNING_KEY_NEXT)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            tx.rollback().await.map_err(|e| AppError::InternalError(e.into()))?;
+            return Ok(false);
+        }
+
+        sqlx::query("UPDATE signing_keys SET status = ?, retired_at = NOW() WHERE status = ? AND kid <> ?")
+            .bind(SIGNING_KEY_RETIRED)
+            .bind(SIGNING_KEY_ACTIVE)
+            .bind(kid)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        tx.commit().await.map_err(|e| AppError::InternalError(e.into()))?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::SsoSession;
+
+/// SSO session to create for a hosted login session
+#[derive(Debug, Clone)]
+pub struct NewSsoSession<'a> {
+    pub user_id: Uuid,
+    /// Login session the SSO session continues from
+    pub session_id: Uuid,
+    pub token_hash: &'a str,
+    pub acr: Option<&'a str>,
+    pub amr: Option<&'a [String]>,
+    pub auth_time: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Repository for browser SSO sessions and the app sessions continued from them
+#[derive(Clone)]
+pub struct SsoSessionRepository {
+    pool: MySqlPool,
+}
+
+impl SsoSessionRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create an SSO session for a hosted login session
+    pub async fn create(&self, sso: &NewSsoSession<'_>) -> Result<SsoSession, AuthError> {
+        let id = Uuid::new_v4();
+
+        sqlx::query(
+            r#"
+            INSERT INTO sso_sessions (id, user_id, session_id, token_hash, acr, amr, auth_time, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(sso.user_id.to_string())
+        .bind(sso.session_id.to_string())
+        .bind(sso.token_hash)
+        .bind(sso.acr)
+        .bind(sso.amr.map(|amr| serde_json::json!(amr)))
+        .bind(sso.auth_time)
+        .bind(sso.expires_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        self.find_by_id(id)
+            .await?
+            .ok_or(AuthError::InternalError(anyhow::anyhow!("Failed to fetch created SSO session")))
+    }
+
+    /// Find an SSO session by ID
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<SsoSession>, AuthError> {
+        let session = sqlx::query_as::<_, SsoSession>(
+            r#"
+            SELECT id, user_id, session_id, acr, amr, auth_time, expires_at, revoked_at, created_at
+            FROM sso_sessions
+            WHERE id = ?
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(session)
+    }
+
+    /// Find an unrevoked, unexpired SSO session by its cookie hash
+    pub async fn find_active_by_token_hash(&self, token_hash: &str) -> Result<Option<SsoSession>, AuthError> {
+        let session = sqlx::query_as::<_, SsoSession>(
+            r#"
+            SELECT id, user_id, session_id, acr, amr, auth_time, expires_at, revoked_at, created_at
+            FROM sso_sessions
+            WHERE token_hash = ? AND revoked_at IS NULL AND expires_at > NOW()
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(session)
+    }
+
+    /// Record that an app session was continued from an SSO session
+    pub async fn link_session(&self, sso_session_id: Uuid, session_id: Uuid) -> Result<(), AuthError> {
+        sqlx::query("UPDATE user_sessions SET sso_session_id = ? WHERE id = ?")
+            .bind(sso_session_id.to_string())
+            .bind(session_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Revoke an SSO session, its hosted login session and every app session continued from it
+    ///
+    /// Returns the number of user sessions revoked.
+    pub async fn revoke_with_sessions(&self, sso: &SsoSession) -> Result<u64, AuthError> {
+        let mut tx = self.pool.begin().await.map_err(|e| AuthError::InternalError(e.into()))?;
+
+        sqlx::query("UPDATE sso_sessions SET revoked_at = NOW() WHERE id = ? AND revoked_at IS NULL")
+            .bind(sso.id.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE user_sessions
+            SET is_revoked = TRUE, revoked_at = NOW()
+            WHERE (id = ? OR sso_session_id = ?) AND is_revoked = FALSE
+            "#,
+        )
+        .bind(sso.session_id.to_string())
+        .bind(sso.id.to_string())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        tx.commit().await.map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Revoke every active SSO session of a user
+    ///
+    /// Their user sessions are revoked separately. Returns the number of SSO
+    /// sessions revoked.
+    pub async fn revoke_all_for_user(&self, user_id: Uuid) -> Result<u64, AuthError> {
+        let result = sqlx::query("UPDATE sso_sessions SET revoked_at = NOW() WHERE user_id = ? AND revoked_at IS NULL")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,174 @@
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::TokenIssuance;
+
+/// Token issuance to record
+#[derive(Debug, Clone)]
+pub struct NewTokenIssuance<'a> {
+    pub jti: &'a str,
+    pub family_id: &'a str,
+    pub parent_jti: Option<&'a str>,
+    /// `access` or `refresh`
+    pub token_type: &'a str,
+    pub user_id: Uuid,
+    pub session_id: Option<Uuid>,
+    pub app: Option<&'a str>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Repository for the token lineage
+#[derive(Clone)]
+pub struct TokenLineageRepository {
+    pool: MySqlPool,
+}
+
+impl TokenLineageRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record an issued token
+    pub async fn record(&self, issuance: &NewTokenIssuance<'_>) -> Result<(), AuthError> {
+        sqlx::query(
+            r#"
+            INSERT INTO token_lineage
+                (jti, family_id, parent_jti, token_type, user_id, session_id, app, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(issuance.jti)
+        .bind(issuance.family_id)
+        .bind(issuance.parent_jti)
+        .bind(issuance.token_type)
+        .bind(issuance.user_id.to_string())
+        .bind(issuance.session_id.map(|id| id.to_string()))
+        .bind(issuance.app)
+        .bind(issuance.expires_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Find a recorded token by jti
+    pub async fn find_by_jti(&self, jti: &str) -> Result<Option<TokenIssuance>, AuthError> {
+        let issuance = sqlx::query_as::<_, TokenIssuance>(
+            r#"
+            SELECT jti, family_id, parent_jti, token_type, user_id, session_id, app,
+                   issued_at, expires_at, revoked_at, revoked_reason
+            FROM token_lineage
+            WHERE jti = ?
+            "#,
+        )
+        .bind(jti)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(issuance)
+    }
+
+    /// List every recorded token of a family, in issue order
+    pub async fn list_family(&self, family_id: &str) -> Result<Vec<TokenIssuance>, AuthError> {
+        let tokens = sqlx::query_as::<_, TokenIssuance>(
+            r#"
+            SELECT jti, family_id, parent_jti, token_type, user_id, session_id, app,
+                   issued_at, expires_at, revoked_at, revoked_reason
+            FROM token_lineage
+            WHERE family_id = ?
+            ORDER BY issued_at ASC, token_type DESC
+            "#,
+        )
+        .bind(family_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(tokens)
+    }
+
+    /// Mark a token revoked; a token keeps the first revocation recorded for it
+    pub async fn revoke(&self, jti: &str, reason: &str) -> Result<(), AuthError> {
+        sqlx::query(
+            r#"
+            UPDATE token_lineage
+            SET revoked_at = NOW(), revoked_reason = ?
+            WHERE jti = ? AND revoked_at IS NULL
+            "#,
+        )
+        .bind(reason)
+        .bind(jti)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Mark every unrevoked token of a session revoked
+    pub async fn revoke_session(&self, session_id: Uuid, reason: &str) -> Result<u64, AuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE token_lineage
+            SET revoked_at = NOW(), revoked_reason = ?
+            WHERE session_id = ? AND revoked_at IS NULL
+            "#,
+        )
+        .bind(reason)
+        .bind(session_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Mark every unrevoked token of a user's sessions revoked, optionally sparing one session
+    pub async fn revoke_for_user(
+        &self,
+        user_id: Uuid,
+        except_session_id: Option<Uuid>,
+        reason: &str,
+    ) -> Result<u64, AuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE token_lineage
+            SET revoked_at = NOW(), revoked_reason = ?
+            WHERE user_id = ? AND revoked_at IS NULL AND session_id IS NOT NULL
+              AND (? IS NULL OR session_id <> ?)
+            "#,
+        )
+        .bind(reason)
+        .bind(user_id.to_string())
+        .bind(except_session_id.map(|id| id.to_string()))
+        .bind(except_session_id.map(|id| id.to_string()))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Mark every unrevoked token of the sessions bound to a device revoked
+    pub async fn revoke_for_device(&self, device_id: Uuid, reason: &str) -> Result<u64, AuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE token_lineage
+            SET revoked_at = NOW(), revoked_reason = ?
+            WHERE revoked_at IS NULL
+              AND session_id IN (SELECT id FROM user_sessions WHERE device_id = ?)
+            "#,
+        )
+        .bind(reason)
+        .bind(device_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected())
+    }
+}
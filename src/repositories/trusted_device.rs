@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::TrustedDevice;
+
+/// Repository for trusted device database operations
+#[derive(Clone)]
+pub struct TrustedDeviceRepository {
+    pool: MySqlPool,
+}
+
+impl TrustedDeviceRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Trust a device until `expires_at`
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        token_hash: &str,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<TrustedDevice, AuthError> {
+        let id = Uuid::new_v4();
+
+        sqlx::query(
+            r#"
+            INSERT INTO trusted_devices (id, user_id, token_hash, ip_address, user_agent, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(user_id.to_string())
+        .bind(token_hash)
+        .bind(ip_address)
+        .bind(user_agent)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        self.find_by_id(id)
+            .await?
+            .ok_or(AuthError::InternalError(anyhow::anyhow!("Failed to fetch created trusted device")))
+    }
+
+    /// Find device by ID
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<TrustedDevice>, AuthError> {
+        let device = sqlx::query_as::<_, TrustedDevice>(
+            r#"
+            SELECT id, user_id, token_hash, ip_address, user_agent, last_used_at, expires_at, created_at
+            FROM trusted_devices
+            WHERE id = ?
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(device)
+    }
+
+    /// Unexpired devices of a user, most recently trusted first
+    pub async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<TrustedDevice>, AuthError> {
+        let devices = sqlx::query_as::<_, TrustedDevice>(
+            r#"
+            SELECT id, user_id, token_hash, ip_address, user_agent, last_used_at, expires_at, created_at
+            FROM trusted_devices
+            WHERE user_id = ? AND expires_at > NOW()
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(devices)
+    }
+
+    /// Record that the device skipped MFA
+    pub async fn touch(&self, id: Uuid) -> Result<(), AuthError> {
+        sqlx::query("UPDATE trusted_devices SET last_used_at = NOW() WHERE id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Stop trusting one device of a user
+    /// Returns false if the user has no such device
+    pub async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<bool, AuthError> {
+        let result = sqlx::query("DELETE FROM trusted_devices WHERE id = ? AND user_id = ?")
+            .bind(id.to_string())
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Stop trusting every device of a user
+    pub async fn delete_all_for_user(&self, user_id: Uuid) -> Result<u64, AuthError> {
+        let result = sqlx::query("DELETE FROM trusted_devices WHERE user_id = ?")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected())
+    }
+}
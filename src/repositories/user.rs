@@ -3,7 +3,29 @@ use uuid::Uuid;
 
 use crate::error::AuthError;
 use crate::models::User;
-
+use crate::utils::email::{canonicalize_email, email_skeleton, normalize_email};
+use crate::utils::field_crypto::{seal_optional, EncryptedColumn};
+
+/// Login lookup (shared with the pool warm-up so the primed statement is reused)
+pub(crate) const FIND_BY_EMAIL_SQL: &str = r#"
+    SELECT id, email, password_hash, name, avatar_url, phone, locale, metadata, is_active, email_verified, is_system_admin, mfa_enabled, legal_hold, legal_hold_reason, reverify_required, created_at, updated_at
+    FROM users
+    WHERE email_canonical = ? OR (email_canonical IS NULL AND email = ?)
+    ORDER BY email = ? DESC
+    LIMIT 1
+"#;
+
+/// Lookup by ID, run on most authenticated requests
+pub(crate) const FIND_BY_ID_SQL: &str = r#"
+    SELECT id, email, password_hash, name, avatar_url, phone, locale, metadata, is_active, email_verified, is_system_admin, mfa_enabled, legal_hold, legal_hold_reason, reverify_required, created_at, updated_at
+    FROM users
+    WHERE id = ?
+"#;
+
+/// JSON path of a top-level metadata key
+fn metadata_json_path(field: &str) -> String {
+    format!("$.\"{}\"", field.replace(['"', '\\'], ""))
+}
 
 /// Repository for user database operations
 #[derive(Clone)]
@@ -18,20 +40,28 @@ impl UserRepository {
     }
 
     /// Create a new user with the given email and password hash
-    /// Returns AuthError::EmailAlreadyExists if email is taken
+    /// Returns AuthError::EmailAlreadyExists if the canonical email is taken
     /// Requirements: 1.1, 1.2
-    pub async fn create_user(&self, email: &str, password_hash: &str) -> Result<User, AuthError> {
+    pub async fn create_user(
+        &self,
+        email: &str,
+        password_hash: &str,
+        metadata: Option<&serde_json::Value>,
+    ) -> Result<User, AuthError> {
         let id = Uuid::new_v4();
         
         sqlx::query(
             r#"
-            INSERT INTO users (id, email, password_hash)
-            VALUES (?, ?, ?)
+            INSERT INTO users (id, email, email_canonical, email_skeleton, password_hash, metadata)
+            VALUES (?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id.to_string())
-        .bind(email)
+        .bind(normalize_email(email))
+        .bind(canonicalize_email(email))
+        .bind(email_skeleton(email))
         .bind(password_hash)
+        .bind(metadata)
         .execute(&self.pool)
         .await
         .map_err(|e| {
@@ -50,16 +80,40 @@ impl UserRepository {
     }
 
     /// Find a user by their email address
+    ///
+    /// Matches on the canonical form. Legacy accounts that lost the canonical
+    /// address to an older duplicate are only found by their exact email.
     /// Requirements: 2.1
     pub async fn find_by_email(&self, email: &str) -> Result<Option<User>, AuthError> {
+        let user = sqlx::query_as::<_, User>(
+            FIND_BY_EMAIL_SQL,
+        )
+        .bind(canonicalize_email(email))
+        .bind(normalize_email(email))
+        .bind(normalize_email(email))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(user)
+    }
+
+    /// Find an existing account whose email looks the same as `email` but is a different address
+    ///
+    /// Compares homoglyph skeletons, so `pаypal@example.com` (Cyrillic `а`)
+    /// finds the `paypal@example.com` account.
+    pub async fn find_confusable(&self, email: &str) -> Result<Option<User>, AuthError> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, email, password_hash, name, avatar_url, phone, is_active, email_verified, is_system_admin, mfa_enabled, created_at, updated_at
+            SELECT id, email, password_hash, name, avatar_url, phone, locale, metadata, is_active, email_verified, is_system_admin, mfa_enabled, legal_hold, legal_hold_reason, reverify_required, created_at, updated_at
             FROM users
-            WHERE email = ?
+            WHERE email_skeleton = ?
+              AND (email_canonical IS NULL OR email_canonical <> ?)
+            LIMIT 1
             "#,
         )
-        .bind(email)
+        .bind(email_skeleton(email))
+        .bind(canonicalize_email(email))
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| AuthError::InternalError(e.into()))?;
@@ -70,11 +124,7 @@ impl UserRepository {
     /// Find a user by their UUID
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, AuthError> {
         let user = sqlx::query_as::<_, User>(
-            r#"
-            SELECT id, email, password_hash, name, avatar_url, phone, is_active, email_verified, is_system_admin, mfa_enabled, created_at, updated_at
-            FROM users
-            WHERE id = ?
-            "#,
+            FIND_BY_ID_SQL,
         )
         .bind(id.to_string())
         .fetch_optional(&self.pool)
@@ -129,6 +179,25 @@ impl UserRepository {
         Ok(())
     }
 
+    /// Record a sign-in (or an admin reactivation) and clear the dormant flag
+    ///
+    /// `updated_at` is kept: a sign-in is not a profile change.
+    pub async fn record_activity(&self, user_id: Uuid) -> Result<(), AuthError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET last_activity_at = NOW(), dormant_at = NULL, updated_at = updated_at
+            WHERE id = ?
+            "#,
+        )
+        .bind(user_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
     /// Check if a user is a system admin
     /// Requirements: 7.1
     pub async fn is_system_admin(&self, user_id: Uuid) -> Result<bool, AuthError> {
@@ -183,9 +252,9 @@ impl UserRepository {
 
         let users = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, email, password_hash, name, avatar_url, phone, is_active, email_verified, is_system_admin, mfa_enabled, created_at, updated_at
+            SELECT id, email, password_hash, name, avatar_url, phone, locale, metadata, is_active, email_verified, is_system_admin, mfa_enabled, legal_hold, legal_hold_reason, reverify_required, created_at, updated_at
             FROM users
-            ORDER BY created_at DESC
+            ORDER BY created_at DESC, id DESC
             LIMIT ? OFFSET ?
             "#,
         )
@@ -220,6 +289,7 @@ impl UserRepository {
         name: Option<String>,
         avatar_url: Option<String>,
         phone: Option<String>,
+        locale: Option<String>,
     ) -> Result<User, AuthError> {
         sqlx::query(
             r#"
@@ -227,13 +297,15 @@ impl UserRepository {
             SET name = COALESCE(?, name),
                 avatar_url = COALESCE(?, avatar_url),
                 phone = COALESCE(?, phone),
+                locale = COALESCE(?, locale),
                 updated_at = NOW()
             WHERE id = ?
             "#,
         )
         .bind(name)
         .bind(avatar_url)
-        .bind(phone)
+        .bind(seal_optional(EncryptedColumn::UserPhone, phone.as_deref()))
+        .bind(locale)
         .bind(user_id.to_string())
         .execute(&self.pool)
         .await
@@ -245,15 +317,18 @@ impl UserRepository {
     }
 
     /// Set email verified status
+    ///
+    /// Verifying the address also lifts a re-verification required by the dormant account policy.
     pub async fn set_email_verified(&self, user_id: Uuid, verified: bool) -> Result<(), AuthError> {
         let result = sqlx::query(
             r#"
             UPDATE users
-            SET email_verified = ?, updated_at = NOW()
+            SET email_verified = ?, reverify_required = reverify_required AND NOT ?, updated_at = NOW()
             WHERE id = ?
             "#,
         )
         .bind(verified)
+        .bind(verified)
         .bind(user_id.to_string())
         .execute(&self.pool)
         .await
@@ -267,6 +342,9 @@ impl UserRepository {
     }
 
     /// Search users with filters
+    ///
+    /// `metadata` is a `(field, value)` pair matched exactly against the
+    /// user's metadata (numbers and booleans compare as their JSON text).
     pub async fn search(
         &self,
         email: Option<&str>,
@@ -274,6 +352,7 @@ impl UserRepository {
         is_active: Option<bool>,
         email_verified: Option<bool>,
         is_system_admin: Option<bool>,
+        metadata: Option<(&str, &str)>,
         sort_by: &str,
         sort_order: &str,
         page: u32,
@@ -290,20 +369,22 @@ impl UserRepository {
         };
         
         let sort_dir = if sort_order.to_lowercase() == "asc" { "ASC" } else { "DESC" };
+        let metadata_path = metadata.map(|(field, _)| metadata_json_path(field));
         
         let query = format!(
             r#"
-            SELECT id, email, password_hash, name, avatar_url, phone, is_active, email_verified, is_system_admin, mfa_enabled, created_at, updated_at
+            SELECT id, email, password_hash, name, avatar_url, phone, locale, metadata, is_active, email_verified, is_system_admin, mfa_enabled, legal_hold, legal_hold_reason, reverify_required, created_at, updated_at
             FROM users
             WHERE (? IS NULL OR email LIKE CONCAT('%', ?, '%'))
               AND (? IS NULL OR name LIKE CONCAT('%', ?, '%'))
               AND (? IS NULL OR is_active = ?)
               AND (? IS NULL OR email_verified = ?)
               AND (? IS NULL OR is_system_admin = ?)
-            ORDER BY {} {}
+              AND (? IS NULL OR JSON_UNQUOTE(JSON_EXTRACT(metadata, ?)) = ?)
+            ORDER BY {} {}, id {}
             LIMIT ? OFFSET ?
             "#,
-            sort_column, sort_dir
+            sort_column, sort_dir, sort_dir
         );
 
         let users = sqlx::query_as::<_, User>(&query)
@@ -317,6 +398,9 @@ impl UserRepository {
             .bind(email_verified.unwrap_or(false))
             .bind(is_system_admin)
             .bind(is_system_admin.unwrap_or(false))
+            .bind(metadata.map(|(field, _)| field))
+            .bind(&metadata_path)
+            .bind(metadata.map(|(_, value)| value))
             .bind(limit)
             .bind(offset)
             .fetch_all(&self.pool)
@@ -334,7 +418,9 @@ impl UserRepository {
         is_active: Option<bool>,
         email_verified: Option<bool>,
         is_system_admin: Option<bool>,
+        metadata: Option<(&str, &str)>,
     ) -> Result<u64, AuthError> {
+        let metadata_path = metadata.map(|(field, _)| metadata_json_path(field));
         let count = sqlx::query_scalar::<_, i64>(
             r#"
             SELECT COUNT(*) as count
@@ -344,6 +430,7 @@ impl UserRepository {
               AND (? IS NULL OR is_active = ?)
               AND (? IS NULL OR email_verified = ?)
               AND (? IS NULL OR is_system_admin = ?)
+              AND (? IS NULL OR JSON_UNQUOTE(JSON_EXTRACT(metadata, ?)) = ?)
             "#,
         )
         .bind(email)
@@ -356,6 +443,9 @@ impl UserRepository {
         .bind(email_verified.unwrap_or(false))
         .bind(is_system_admin)
         .bind(is_system_admin.unwrap_or(false))
+        .bind(metadata.map(|(field, _)| field))
+        .bind(metadata_path)
+        .bind(metadata.map(|(_, value)| value))
         .fetch_one(&self.pool)
         .await
         .map_err(|e| AuthError::InternalError(e.into()))?;
@@ -375,15 +465,17 @@ impl UserRepository {
         
         sqlx::query(
             r#"
-            INSERT INTO users (id, email, password_hash, name, phone)
-            VALUES (?, ?, ?, ?, ?)
+            INSERT INTO users (id, email, email_canonical, email_skeleton, password_hash, name, phone)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id.to_string())
-        .bind(email)
+        .bind(normalize_email(email))
+        .bind(canonicalize_email(email))
+        .bind(email_skeleton(email))
         .bind(password_hash)
         .bind(name)
-        .bind(phone)
+        .bind(seal_optional(EncryptedColumn::UserPhone, phone))
         .execute(&self.pool)
         .await
         .map_err(|e| {
@@ -400,8 +492,11 @@ impl UserRepository {
     }
 
     /// Delete a user permanently
+    ///
+    /// Accounts under legal hold are never deleted, even if the hold was set
+    /// after the caller checked.
     pub async fn delete(&self, user_id: Uuid) -> Result<(), AuthError> {
-        let result = sqlx::query("DELETE FROM users WHERE id = ?")
+        let result = sqlx::query("DELETE FROM users WHERE id = ? AND legal_hold = FALSE")
             .bind(user_id.to_string())
             .execute(&self.pool)
             .await
@@ -414,6 +509,99 @@ impl UserRepository {
         Ok(())
     }
 
+    /// Scrub a user's PII while keeping the row for audit and role references
+    ///
+    /// The email is replaced by a hash of itself, profile fields are cleared,
+    /// the account is disabled with an unusable password, and credentials,
+    /// sessions, tokens and stored personal data are removed. Accounts under
+    /// legal hold are never anonymized.
+    pub async fn anonymize(&self, user_id: Uuid) -> Result<(), AuthError> {
+        let internal = |e: sqlx::Error| AuthError::InternalError(e.into());
+        let mut tx = self.pool.begin().await.map_err(internal)?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET email = CONCAT(SHA2(email, 256), '@anonymized.invalid'),
+                email_canonical = NULL,
+                email_skeleton = NULL,
+                password_hash = '!',
+                name = NULL,
+                avatar_url = NULL,
+                phone = NULL,
+                locale = NULL,
+                is_active = FALSE,
+                email_verified = FALSE,
+                mfa_enabled = FALSE,
+                anonymized_at = COALESCE(anonymized_at, NOW())
+            WHERE id = ? AND legal_hold = FALSE
+            "#,
+        )
+        .bind(user_id.to_string())
+        .execute(&mut *tx)
+        .await
+        .map_err(internal)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthError::UserNotFound);
+        }
+
+        for table in [
+            "user_sessions",
+            "refresh_tokens",
+            "oauth_tokens",
+            "user_consents",
+            "mfa_sms_codes",
+            "email_otp_codes",
+            "magic_link_tokens",
+            "trusted_devices",
+            "login_history",
+            "credential_cooling_off",
+            "user_mfa_methods",
+            "user_mfa_backup_codes",
+            "push_mfa_challenges",
+            "webauthn_credentials",
+            "user_devices",
+            "user_addresses",
+            "user_match_keys",
+            "password_reset_tokens",
+            "email_verification_tokens",
+        ] {
+            sqlx::query(&format!("DELETE FROM {} WHERE user_id = ?", table))
+                .bind(user_id.to_string())
+                .execute(&mut *tx)
+                .await
+                .map_err(internal)?;
+        }
+
+        tx.commit().await.map_err(internal)?;
+
+        Ok(())
+    }
+
+    /// Place a user under legal hold or release it (the reason is cleared on release)
+    pub async fn set_legal_hold(&self, user_id: Uuid, legal_hold: bool, reason: Option<&str>) -> Result<(), AuthError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET legal_hold = ?, legal_hold_reason = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(legal_hold)
+        .bind(if legal_hold { reason } else { None })
+        .bind(user_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
     /// Update user by admin (email, is_active, is_system_admin)
     pub async fn admin_update(
         &self,
@@ -429,6 +617,8 @@ impl UserRepository {
 
         if let Some(e) = email {
             updates.push("email = ?");
+            updates.push("email_canonical = ?");
+            updates.push("email_skeleton = ?");
             bindings.push(e.to_string());
         }
         if let Some(a) = is_active {
@@ -458,7 +648,10 @@ impl UserRepository {
         let mut q = sqlx::query(&query);
         
         if let Some(e) = email {
-            q = q.bind(e);
+            q = q
+                .bind(normalize_email(e))
+                .bind(canonicalize_email(e))
+                .bind(email_skeleton(e));
         }
         if let Some(a) = is_active {
             q = q.bind(a);
@@ -563,10 +756,10 @@ mod tests {
 
                 cleanup_test_data(&pool, &[email.clone()]).await;
 
-                let result1 = repo.create_user(&email, &password_hash1).await;
+                let result1 = repo.create_user(&email, &password_hash1, None).await;
                 prop_assert!(result1.is_ok(), "First user creation should succeed");
 
-                let result2 = repo.create_user(&email, &password_hash2).await;
+                let result2 = repo.create_user(&email, &password_hash2, None).await;
                 prop_assert!(result2.is_err(), "Second user creation with same email should fail");
                 
                 match result2 {
@@ -597,7 +790,7 @@ mod tests {
 
                 cleanup_test_data(&pool, &[email.clone()]).await;
 
-                let create_result = repo.create_user(&email, &password_hash).await;
+                let create_result = repo.create_user(&email, &password_hash, None).await;
                 prop_assert!(create_result.is_ok(), "User creation should succeed");
 
                 let created_user = create_result.unwrap();
@@ -0,0 +1,72 @@
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::UserAddress;
+
+/// Repository for users' postal addresses
+#[derive(Clone)]
+pub struct UserAddressRepository {
+    pool: MySqlPool,
+}
+
+impl UserAddressRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_by_user(&self, user_id: Uuid) -> Result<Option<UserAddress>, AuthError> {
+        let address = sqlx::query_as::<_, UserAddress>(
+            r#"
+            SELECT formatted, street_address, locality, region, postal_code, country
+            FROM user_addresses
+            WHERE user_id = ?
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(address)
+    }
+
+    /// Replace the user's address; an empty address removes it
+    pub async fn set(&self, user_id: Uuid, address: &UserAddress) -> Result<(), AuthError> {
+        if address.is_empty() {
+            sqlx::query("DELETE FROM user_addresses WHERE user_id = ?")
+                .bind(user_id.to_string())
+                .execute(&self.pool)
+                .await
+                .map_err(|e| AuthError::InternalError(e.into()))?;
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_addresses
+                (user_id, formatted, street_address, locality, region, postal_code, country)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                formatted = VALUES(formatted),
+                street_address = VALUES(street_address),
+                locality = VALUES(locality),
+                region = VALUES(region),
+                postal_code = VALUES(postal_code),
+                country = VALUES(country)
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(&address.formatted)
+        .bind(&address.street_address)
+        .bind(&address.locality)
+        .bind(&address.region)
+        .bind(&address.postal_code)
+        .bind(&address.country)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+}
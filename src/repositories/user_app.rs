@@ -1,10 +1,76 @@
-use chrono::Utc;
-use sqlx::MySqlPool;
+use chrono::{DateTime, Utc};
+use sqlx::mysql::MySqlArguments;
+use sqlx::{MySql, MySqlPool};
 use uuid::Uuid;
 
 use crate::error::UserManagementError;
 use crate::models::user_app::{UserApp, UserAppStatus};
 
+/// Filters for listing the users of an app; `None` fields match everything
+#[derive(Debug, Default, Clone)]
+pub struct AppUserFilter<'a> {
+    pub status: Option<UserAppStatus>,
+    /// Only users currently holding this role (expired assignments excluded)
+    pub role_id: Option<Uuid>,
+    /// Email substring
+    pub email: Option<&'a str>,
+    /// Registered at or after
+    pub registered_from: Option<DateTime<Utc>>,
+    /// Registered before
+    pub registered_to: Option<DateTime<Utc>>,
+}
+
+impl AppUserFilter<'_> {
+    /// Conditions on `user_apps ua` joined with `users u`, bound by [`AppUserFilter::bind`]
+    const WHERE_CLAUSE: &'static str = r#"
+              AND (? IS NULL OR ua.status = ?)
+              AND (? IS NULL OR u.email LIKE CONCAT('%', ?, '%') ESCAPE '\\')
+              AND (? IS NULL OR ua.created_at >= ?)
+              AND (? IS NULL OR ua.created_at < ?)
+              AND (? IS NULL OR EXISTS (
+                  SELECT 1 FROM user_app_roles uar
+                  WHERE uar.app_id = ua.app_id AND uar.user_id = ua.user_id AND uar.role_id = ?
+                    AND (uar.expires_at IS NULL OR uar.expires_at > NOW())
+              ))"#;
+
+    fn bind<'q, O>(
+        &self,
+        query: sqlx::query::QueryAs<'q, MySql, O, MySqlArguments>,
+    ) -> sqlx::query::QueryAs<'q, MySql, O, MySqlArguments> {
+        let status = self.status.map(|s| s.as_str());
+        let email = self.email.map(escape_like);
+        let role_id = self.role_id.map(|id| id.to_string());
+        query
+            .bind(status)
+            .bind(status)
+            .bind(email.clone())
+            .bind(email)
+            .bind(self.registered_from)
+            .bind(self.registered_from)
+            .bind(self.registered_to)
+            .bind(self.registered_to)
+            .bind(role_id.clone())
+            .bind(role_id)
+    }
+}
+
+/// Escape `LIKE` wildcards so the value only matches itself
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// Column a listing of app users can be sorted by
+///
+/// Any other value is refused rather than replaced, so a client learns its
+/// sort wasn't applied.
+pub fn app_user_sort_column(sort_by: &str) -> Result<&'static str, UserManagementError> {
+    match sort_by {
+        "email" => Ok("u.email"),
+        "created_at" => Ok("ua.created_at"),
+        _ => Err(UserManagementError::ValidationError(format!("Invalid sort_by: {}", sort_by))),
+    }
+}
+
 /// Repository for user-app association database operations
 /// Requirements: 2.1, 2.4, 3.1, 4.1, 5.1
 #[derive(Clone)]
@@ -67,6 +133,19 @@ impl UserAppRepository {
         Ok(user_app)
     }
 
+    /// IDs of all apps the user is registered to, whatever the status
+    pub async fn find_app_ids_by_user(&self, user_id: Uuid) -> Result<Vec<Uuid>, UserManagementError> {
+        let app_ids = sqlx::query_scalar::<_, String>(
+            "SELECT app_id FROM user_apps WHERE user_id = ?",
+        )
+        .bind(user_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| UserManagementError::InternalError(e.into()))?;
+
+        Ok(app_ids.iter().filter_map(|id| Uuid::parse_str(id).ok()).collect())
+    }
+
 
     /// Update user-app status (for ban/unban operations)
     /// Requirements: 3.1, 4.1
@@ -168,48 +247,74 @@ impl UserAppRepository {
         Ok(())
     }
 
-    /// List users in an app with pagination
+    /// List users in an app matching `filter`, sorted and paginated
+    ///
+    /// Status and registration date use the (app_id, status, created_at)
+    /// index; the role filter uses the (app_id, role_id) index on assignments.
     /// Requirements: 6.1, 6.2
-    pub async fn list_by_app(
+    pub async fn search_by_app(
         &self,
         app_id: Uuid,
+        filter: &AppUserFilter<'_>,
+        sort_by: &str,
+        sort_order: &str,
         page: u32,
         limit: u32,
     ) -> Result<Vec<UserApp>, UserManagementError> {
         let offset = (page.saturating_sub(1)) * limit;
 
-        let user_apps = sqlx::query_as::<_, UserApp>(
+        // Only known columns reach the SQL
+        let sort_column = app_user_sort_column(sort_by)?;
+
+        let sort_dir = if sort_order.to_lowercase() == "asc" { "ASC" } else { "DESC" };
+
+        let query = format!(
             r#"
-            SELECT user_id, app_id, status, banned_at, banned_reason, created_at
-            FROM user_apps
-            WHERE app_id = ?
-            ORDER BY created_at DESC
+            SELECT ua.user_id, ua.app_id, ua.status, ua.banned_at, ua.banned_reason, ua.created_at
+            FROM user_apps ua
+            INNER JOIN users u ON u.id = ua.user_id
+            WHERE ua.app_id = ?
+            {}
+            ORDER BY {} {}, ua.user_id {}
             LIMIT ? OFFSET ?
             "#,
-        )
-        .bind(app_id.to_string())
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&self.pool)
-        .await
-        .map_err(|e| UserManagementError::InternalError(e.into()))?;
+            AppUserFilter::WHERE_CLAUSE, sort_column, sort_dir, sort_dir
+        );
+
+        let user_apps = filter
+            .bind(sqlx::query_as::<_, UserApp>(&query).bind(app_id.to_string()))
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?;
 
         Ok(user_apps)
     }
 
-    /// Count total users in an app (for pagination)
-    pub async fn count_by_app(&self, app_id: Uuid) -> Result<u64, UserManagementError> {
-        let count = sqlx::query_scalar::<_, i64>(
+    /// Count users in an app matching `filter` (for pagination)
+    pub async fn count_search_by_app(
+        &self,
+        app_id: Uuid,
+        filter: &AppUserFilter<'_>,
+    ) -> Result<u64, UserManagementError> {
+        let query = format!(
             r#"
             SELECT COUNT(*) as count
-            FROM user_apps
-            WHERE app_id = ?
+            FROM user_apps ua
+            INNER JOIN users u ON u.id = ua.user_id
+            WHERE ua.app_id = ?
+            {}
             "#,
-        )
-        .bind(app_id.to_string())
-        .fetch_one(&self.pool)
-        .await
-        .map_err(|e| UserManagementError::InternalError(e.into()))?;
+            AppUserFilter::WHERE_CLAUSE
+        );
+
+        let count = filter
+            .bind(sqlx::query_as::<_, (i64,)>(&query).bind(app_id.to_string()))
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?
+            .0;
 
         Ok(count as u64)
     }
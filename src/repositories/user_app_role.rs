@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use sqlx::MySqlPool;
 use uuid::Uuid;
 
@@ -17,6 +18,8 @@ impl UserAppRoleRepository {
     }
 
     /// Assign a role to a user for a specific app
+    /// `expires_at` makes the assignment time-bounded and `access_window` limits
+    /// it to a weekly schedule; re-assigning replaces both
     /// Returns RoleError if user, app, or role doesn't exist
     /// Requirements: 8.1
     pub async fn assign_role(
@@ -24,44 +27,86 @@ impl UserAppRoleRepository {
         user_id: Uuid,
         app_id: Uuid,
         role_id: Uuid,
+        expires_at: Option<DateTime<Utc>>,
+        access_window: Option<&str>,
     ) -> Result<UserAppRole, RoleError> {
         sqlx::query(
             r#"
-            INSERT INTO user_app_roles (user_id, app_id, role_id)
-            VALUES (?, ?, ?)
-            ON DUPLICATE KEY UPDATE user_id = user_id
+            INSERT INTO user_app_roles (user_id, app_id, role_id, expires_at, access_window)
+            VALUES (?, ?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                expires_at = VALUES(expires_at),
+                expiry_notified_at = NULL,
+                access_window = VALUES(access_window)
             "#,
         )
         .bind(user_id.to_string())
         .bind(app_id.to_string())
         .bind(role_id.to_string())
+        .bind(expires_at)
+        .bind(access_window)
         .execute(&self.pool)
         .await
-        .map_err(|e| {
-            if let sqlx::Error::Database(db_err) = &e {
-                if db_err.message().contains("foreign key constraint")
-                    || db_err.message().contains("Cannot add or update") {
-                    let msg = db_err.message().to_lowercase();
-                    if msg.contains("user") {
-                        return RoleError::UserNotFound;
-                    } else if msg.contains("app") {
-                        return RoleError::AppNotFound;
-                    } else if msg.contains("role") {
-                        return RoleError::NotFound;
-                    }
-                    return RoleError::NotFound;
-                }
-            }
-            RoleError::InternalError(e.into())
-        })?;
+        .map_err(Self::map_assign_error)?;
 
         Ok(UserAppRole {
             user_id,
             app_id,
             role_id,
+            expires_at,
         })
     }
 
+    /// Grant a role until `until` without shortening an existing assignment
+    ///
+    /// A permanent assignment stays permanent and a later expiry is kept.
+    pub async fn grant_role_until(
+        &self,
+        user_id: Uuid,
+        app_id: Uuid,
+        role_id: Uuid,
+        until: DateTime<Utc>,
+    ) -> Result<(), RoleError> {
+        // expiry_notified_at is reset before expires_at changes, since MySQL
+        // applies the assignments left to right
+        sqlx::query(
+            r#"
+            INSERT INTO user_app_roles (user_id, app_id, role_id, expires_at)
+            VALUES (?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                expiry_notified_at = IF(expires_at IS NOT NULL AND VALUES(expires_at) > expires_at, NULL, expiry_notified_at),
+                expires_at = IF(expires_at IS NULL, NULL, GREATEST(expires_at, VALUES(expires_at)))
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(app_id.to_string())
+        .bind(role_id.to_string())
+        .bind(until)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_assign_error)?;
+
+        Ok(())
+    }
+
+    fn map_assign_error(e: sqlx::Error) -> RoleError {
+        if let sqlx::Error::Database(db_err) = &e {
+            if db_err.message().contains("foreign key constraint")
+                || db_err.message().contains("Cannot add or update") {
+                let msg = db_err.message().to_lowercase();
+                if msg.contains("user") {
+                    return RoleError::UserNotFound;
+                } else if msg.contains("app") {
+                    return RoleError::AppNotFound;
+                } else if msg.contains("role") {
+                    return RoleError::NotFound;
+                }
+                return RoleError::NotFound;
+            }
+        }
+        RoleError::InternalError(e.into())
+    }
+
     /// Remove a role from a user for a specific app
     /// Returns Ok(true) if the role was removed, Ok(false) if it didn't exist
     pub async fn remove_role(
@@ -86,14 +131,14 @@ impl UserAppRoleRepository {
         Ok(result.rows_affected() > 0)
     }
 
-    /// Find all role assignments for a user across all apps
+    /// Find all active role assignments for a user across all apps
     /// Requirements: 8.3
     pub async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<UserAppRole>, RoleError> {
         let user_app_roles = sqlx::query_as::<_, UserAppRole>(
             r#"
-            SELECT user_id, app_id, role_id
+            SELECT user_id, app_id, role_id, expires_at
             FROM user_app_roles
-            WHERE user_id = ?
+            WHERE user_id = ? AND (expires_at IS NULL OR expires_at > NOW())
             "#,
         )
         .bind(user_id.to_string())
@@ -104,7 +149,7 @@ impl UserAppRoleRepository {
         Ok(user_app_roles)
     }
 
-    /// Find all role assignments for a user within a specific app
+    /// Find all active role assignments for a user within a specific app
     /// Requirements: 8.1, 8.3
     pub async fn find_by_user_and_app(
         &self,
@@ -113,9 +158,9 @@ impl UserAppRoleRepository {
     ) -> Result<Vec<UserAppRole>, RoleError> {
         let user_app_roles = sqlx::query_as::<_, UserAppRole>(
             r#"
-            SELECT user_id, app_id, role_id
+            SELECT user_id, app_id, role_id, expires_at
             FROM user_app_roles
-            WHERE user_id = ? AND app_id = ?
+            WHERE user_id = ? AND app_id = ? AND (expires_at IS NULL OR expires_at > NOW())
             "#,
         )
         .bind(user_id.to_string())
@@ -148,4 +193,93 @@ impl UserAppRoleRepository {
 
         Ok(())
     }
+    /// Find time-bounded assignments expiring within `notice_secs` that were not announced yet
+    pub async fn find_expiring(&self, notice_secs: i64, limit: i64) -> Result<Vec<UserAppRole>, RoleError> {
+        let user_app_roles = sqlx::query_as::<_, UserAppRole>(
+            r#"
+            SELECT user_id, app_id, role_id, expires_at
+            FROM user_app_roles
+            WHERE expires_at > NOW()
+              AND expires_at <= DATE_ADD(NOW(), INTERVAL ? SECOND)
+              AND expiry_notified_at IS NULL
+            ORDER BY expires_at
+            LIMIT ?
+            "#,
+        )
+        .bind(notice_secs)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RoleError::InternalError(e.into()))?;
+
+        Ok(user_app_roles)
+    }
+
+    /// Record that the near-expiry notification for an assignment was sent
+    pub async fn mark_expiry_notified(
+        &self,
+        user_id: Uuid,
+        app_id: Uuid,
+        role_id: Uuid,
+    ) -> Result<(), RoleError> {
+        sqlx::query(
+            r#"
+            UPDATE user_app_roles
+            SET expiry_notified_at = NOW()
+            WHERE user_id = ? AND app_id = ? AND role_id = ?
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(app_id.to_string())
+        .bind(role_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RoleError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Find assignments whose expiry has passed
+    pub async fn find_expired(&self, limit: i64) -> Result<Vec<UserAppRole>, RoleError> {
+        let user_app_roles = sqlx::query_as::<_, UserAppRole>(
+            r#"
+            SELECT user_id, app_id, role_id, expires_at
+            FROM user_app_roles
+            WHERE expires_at <= NOW()
+            ORDER BY expires_at
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RoleError::InternalError(e.into()))?;
+
+        Ok(user_app_roles)
+    }
+
+    /// Delete an assignment if it is still expired
+    /// Returns Ok(false) if it was extended or removed in the meantime
+    pub async fn delete_if_expired(
+        &self,
+        user_id: Uuid,
+        app_id: Uuid,
+        role_id: Uuid,
+    ) -> Result<bool, RoleError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM user_app_roles
+            WHERE user_id = ? AND app_id = ? AND role_id = ?
+              AND expires_at IS NOT NULL AND expires_at <= NOW()
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(app_id.to_string())
+        .bind(role_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RoleError::InternalError(e.into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
 }
@@ -24,6 +24,7 @@ impl UserConsentRepository {
         user_id: Uuid,
         client_id: Uuid,
         scopes: &[String],
+        is_implicit: bool,
     ) -> Result<UserConsent, OAuthError> {
         let id = Uuid::new_v4();
         let scopes_json = serde_json::to_value(scopes)
@@ -32,15 +33,16 @@ impl UserConsentRepository {
         // Use INSERT ... ON DUPLICATE KEY UPDATE for upsert
         sqlx::query(
             r#"
-            INSERT INTO user_consents (id, user_id, client_id, scopes)
-            VALUES (?, ?, ?, ?)
-            ON DUPLICATE KEY UPDATE scopes = VALUES(scopes), granted_at = CURRENT_TIMESTAMP
+            INSERT INTO user_consents (id, user_id, client_id, scopes, is_implicit)
+            VALUES (?, ?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE scopes = VALUES(scopes), is_implicit = VALUES(is_implicit), granted_at = CURRENT_TIMESTAMP
             "#,
         )
         .bind(id.to_string())
         .bind(user_id.to_string())
         .bind(client_id.to_string())
         .bind(&scopes_json)
+        .bind(is_implicit)
         .execute(&self.pool)
         .await
         .map_err(|e| OAuthError::ServerError(format!("Database error: {}", e)))?;
@@ -58,7 +60,7 @@ impl UserConsentRepository {
     ) -> Result<Option<UserConsent>, OAuthError> {
         let consent = sqlx::query_as::<_, UserConsent>(
             r#"
-            SELECT id, user_id, client_id, scopes, granted_at
+            SELECT id, user_id, client_id, scopes, is_implicit, granted_at
             FROM user_consents
             WHERE user_id = ? AND client_id = ?
             "#,
@@ -93,7 +95,7 @@ impl UserConsentRepository {
     pub async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<UserConsent>, OAuthError> {
         let consents = sqlx::query_as::<_, UserConsent>(
             r#"
-            SELECT id, user_id, client_id, scopes, granted_at
+            SELECT id, user_id, client_id, scopes, is_implicit, granted_at
             FROM user_consents
             WHERE user_id = ?
             ORDER BY granted_at DESC
@@ -111,7 +113,7 @@ impl UserConsentRepository {
     pub async fn list_by_client(&self, client_id: Uuid) -> Result<Vec<UserConsent>, OAuthError> {
         let consents = sqlx::query_as::<_, UserConsent>(
             r#"
-            SELECT id, user_id, client_id, scopes, granted_at
+            SELECT id, user_id, client_id, scopes, is_implicit, granted_at
             FROM user_consents
             WHERE client_id = ?
             ORDER BY granted_at DESC
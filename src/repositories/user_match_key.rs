@@ -0,0 +1,140 @@
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::{
+    DuplicateAccountGroup, DuplicateAccountGroupRow, DuplicateMatchType, PendingMatchKeyRow,
+};
+
+/// Repository for normalized user match keys used by duplicate-account detection
+#[derive(Clone)]
+pub struct UserMatchKeyRepository {
+    pool: MySqlPool,
+}
+
+impl UserMatchKeyRepository {
+    /// Create a new UserMatchKeyRepository with the given database pool
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find users with no match keys yet, or whose profile changed since they were computed
+    pub async fn find_pending(&self, limit: i64) -> Result<Vec<PendingMatchKeyRow>, AuthError> {
+        let rows = sqlx::query_as::<_, PendingMatchKeyRow>(
+            r#"
+            SELECT u.id, u.email, u.phone
+            FROM users u
+            LEFT JOIN user_match_keys k ON k.user_id = u.id
+            WHERE k.user_id IS NULL
+               OR COALESCE(u.updated_at, u.created_at) > k.computed_at
+            ORDER BY u.id
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(rows)
+    }
+
+    /// Store the match keys computed for a user
+    pub async fn upsert(
+        &self,
+        user_id: Uuid,
+        normalized_email: &str,
+        normalized_phone: Option<&str>,
+    ) -> Result<(), AuthError> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_match_keys (user_id, normalized_email, normalized_phone, computed_at)
+            VALUES (?, ?, ?, NOW())
+            ON DUPLICATE KEY UPDATE
+                normalized_email = VALUES(normalized_email),
+                normalized_phone = VALUES(normalized_phone),
+                computed_at = NOW()
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(normalized_email)
+        .bind(normalized_phone)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Find groups of accounts sharing a normalized email
+    pub async fn find_email_groups(&self, limit: i64) -> Result<Vec<DuplicateAccountGroup>, AuthError> {
+        self.find_groups(
+            DuplicateMatchType::Email,
+            r#"
+            SELECT normalized_email AS match_key, GROUP_CONCAT(user_id) AS user_ids
+            FROM user_match_keys
+            GROUP BY normalized_email
+            HAVING COUNT(*) > 1
+            ORDER BY COUNT(*) DESC, normalized_email
+            LIMIT ?
+            "#,
+            limit,
+        )
+        .await
+    }
+
+    /// Find groups of accounts sharing a normalized phone number
+    pub async fn find_phone_groups(&self, limit: i64) -> Result<Vec<DuplicateAccountGroup>, AuthError> {
+        self.find_groups(
+            DuplicateMatchType::Phone,
+            r#"
+            SELECT normalized_phone AS match_key, GROUP_CONCAT(user_id) AS user_ids
+            FROM user_match_keys
+            WHERE normalized_phone IS NOT NULL
+            GROUP BY normalized_phone
+            HAVING COUNT(*) > 1
+            ORDER BY COUNT(*) DESC, normalized_phone
+            LIMIT ?
+            "#,
+            limit,
+        )
+        .await
+    }
+
+    /// Find groups of accounts that registered the same WebAuthn public key
+    pub async fn find_webauthn_groups(&self, limit: i64) -> Result<Vec<DuplicateAccountGroup>, AuthError> {
+        self.find_groups(
+            DuplicateMatchType::Webauthn,
+            r#"
+            SELECT LOWER(SHA2(public_key, 256)) AS match_key,
+                   GROUP_CONCAT(DISTINCT user_id) AS user_ids
+            FROM webauthn_credentials
+            WHERE is_active = TRUE
+            GROUP BY SHA2(public_key, 256)
+            HAVING COUNT(DISTINCT user_id) > 1
+            ORDER BY COUNT(DISTINCT user_id) DESC, match_key
+            LIMIT ?
+            "#,
+            limit,
+        )
+        .await
+    }
+
+    async fn find_groups(
+        &self,
+        match_type: DuplicateMatchType,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<DuplicateAccountGroup>, AuthError> {
+        let rows = sqlx::query_as::<_, DuplicateAccountGroupRow>(query)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DuplicateAccountGroup::from_row(match_type, row))
+            .collect())
+    }
+}
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use sqlx::pool::PoolConnection;
+use sqlx::{Executor, MySql, MySqlPool};
+use tokio::time::Instant;
+
+use super::session::INSERT_SESSION_SQL;
+use super::user::{FIND_BY_EMAIL_SQL, FIND_BY_ID_SQL};
+
+/// Statements prepared on every warm connection
+///
+/// The text must match the repository queries exactly, since sqlx caches
+/// prepared statements per connection keyed by SQL text.
+const PRIMED_STATEMENTS: &[&str] = &[FIND_BY_EMAIL_SQL, FIND_BY_ID_SQL, INSERT_SESSION_SQL];
+
+/// Delay between warm-up attempts after a failed connection
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Outcome of a successful pool warm-up
+#[derive(Debug, Clone, Copy)]
+pub struct WarmupReport {
+    pub connections: u32,
+    pub statements: usize,
+    pub attempts: u32,
+}
+
+/// Open `min_connections` healthy connections and prime the hot statements on each
+///
+/// Every connection is pinged before priming; a connection that fails is
+/// closed and the attempt retried until `timeout` elapses. The connections
+/// are held together so the pool has to open distinct ones, then returned
+/// to the pool idle and ready for the first requests.
+pub async fn warm_up_pool(
+    pool: &MySqlPool,
+    min_connections: u32,
+    timeout: Duration,
+) -> anyhow::Result<WarmupReport> {
+    let deadline = Instant::now() + timeout;
+    let mut attempts = 0;
+
+    loop {
+        attempts += 1;
+
+        let result = tokio::time::timeout_at(deadline, warm_connections(pool, min_connections)).await;
+        match result {
+            Ok(Ok(())) => {
+                return Ok(WarmupReport {
+                    connections: min_connections,
+                    statements: PRIMED_STATEMENTS.len(),
+                    attempts,
+                });
+            }
+            Ok(Err(e)) if Instant::now() + RETRY_DELAY < deadline => {
+                tracing::warn!("Database warm-up attempt {} failed, retrying: {}", attempts, e);
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+            Ok(Err(e)) => {
+                return Err(anyhow::anyhow!("database warm-up failed after {} attempts: {}", attempts, e));
+            }
+            Err(_) => {
+                return Err(anyhow::anyhow!(
+                    "database warm-up timed out after {}s ({} attempts)",
+                    timeout.as_secs(),
+                    attempts
+                ));
+            }
+        }
+    }
+}
+
+async fn warm_connections(pool: &MySqlPool, count: u32) -> Result<(), sqlx::Error> {
+    let mut held: Vec<PoolConnection<MySql>> = Vec::with_capacity(count as usize);
+
+    for _ in 0..count {
+        let mut conn = pool.acquire().await?;
+        if let Err(e) = prime_connection(&mut conn).await {
+            // Don't hand a broken connection back to the pool
+            let _ = conn.close().await;
+            return Err(e);
+        }
+        held.push(conn);
+    }
+
+    Ok(())
+}
+
+async fn prime_connection(conn: &mut PoolConnection<MySql>) -> Result<(), sqlx::Error> {
+    conn.execute("SELECT 1").await?;
+    for sql in PRIMED_STATEMENTS {
+        conn.prepare(sql).await?;
+    }
+    Ok(())
+}
@@ -1,9 +1,16 @@
 use sqlx::MySqlPool;
 use uuid::Uuid;
-use chrono::{Utc, Duration};
 
 use crate::error::AppError;
-use crate::models::{WebAuthnCredential, WebAuthnChallenge, ChallengeType};
+use crate::models::WebAuthnCredential;
+use crate::utils::field_crypto::{open_optional, seal_field, seal_optional, EncryptedColumn};
+
+/// Decrypt the device name of a fetched credential
+fn decrypt_device_name(mut cred: WebAuthnCredential) -> WebAuthnCredential {
+    let id = cred.id.to_string();
+    cred.device_name = open_optional(EncryptedColumn::WebAuthnDeviceName, &id, cred.device_name);
+    cred
+}
 
 pub struct WebAuthnRepository {
     pool: MySqlPool,
@@ -41,7 +48,7 @@ impl WebAuthnRepository {
         .bind(public_key)
         .bind(counter)
         .bind(aaguid)
-        .bind(device_name)
+        .bind(seal_optional(EncryptedColumn::WebAuthnDeviceName, device_name))
         .bind(transports_json)
         .execute(&self.pool)
         .await?;
@@ -59,7 +66,7 @@ impl WebAuthnRepository {
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(cred)
+        Ok(cred.map(decrypt_device_name))
     }
 
     pub async fn find_credential_by_credential_id(&self, credential_id: &[u8]) -> Result<Option<WebAuthnCredential>, AppError> {
@@ -70,7 +77,7 @@ impl WebAuthnRepository {
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(cred)
+        Ok(cred.map(decrypt_device_name))
     }
 
     pub async fn find_credentials_by_user(&self, user_id: Uuid) -> Result<Vec<WebAuthnCredential>, AppError> {
@@ -81,7 +88,7 @@ impl WebAuthnRepository {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(creds)
+        Ok(creds.into_iter().map(decrypt_device_name).collect())
     }
 
     pub async fn update_counter(&self, id: Uuid, counter: u32) -> Result<(), AppError> {
@@ -95,7 +102,7 @@ impl WebAuthnRepository {
 
     pub async fn update_device_name(&self, id: Uuid, name: &str) -> Result<(), AppError> {
         sqlx::query("UPDATE webauthn_credentials SET device_name = ? WHERE id = ?")
-            .bind(name)
+            .bind(seal_field(EncryptedColumn::WebAuthnDeviceName, name))
             .bind(id.to_string())
             .execute(&self.pool)
             .await?;
@@ -118,77 +125,6 @@ impl WebAuthnRepository {
         Ok(())
     }
 
-    // Challenge methods
-    pub async fn create_challenge(
-        &self,
-        user_id: Option<Uuid>,
-        challenge: &[u8],
-        challenge_type: ChallengeType,
-        ttl_seconds: i64,
-    ) -> Result<WebAuthnChallenge, AppError> {
-        let id = Uuid::new_v4();
-        let expires_at = Utc::now() + Duration::seconds(ttl_seconds);
-        let type_str = match challenge_type {
-            ChallengeType::Registration => "registration",
-            ChallengeType::Authentication => "authentication",
-        };
-
-        sqlx::query(
-            r#"
-            INSERT INTO webauthn_challenges (id, user_id, challenge, challenge_type, expires_at)
-            VALUES (?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(id.to_string())
-        .bind(user_id.map(|u| u.to_string()))
-        .bind(challenge)
-        .bind(type_str)
-        .bind(expires_at)
-        .execute(&self.pool)
-        .await?;
-
-        self.find_challenge_by_id(id).await?.ok_or(AppError::InternalError(
-            anyhow::anyhow!("Failed to create challenge"),
-        ))
-    }
-
-    pub async fn find_challenge_by_id(&self, id: Uuid) -> Result<Option<WebAuthnChallenge>, AppError> {
-        let challenge = sqlx::query_as::<_, WebAuthnChallenge>(
-            "SELECT * FROM webauthn_challenges WHERE id = ?",
-        )
-        .bind(id.to_string())
-        .fetch_optional(&self.pool)
-        .await?;
-
-        Ok(challenge)
-    }
-
-    pub async fn find_valid_challenge(&self, challenge: &[u8]) -> Result<Option<WebAuthnChallenge>, AppError> {
-        let result = sqlx::query_as::<_, WebAuthnChallenge>(
-            "SELECT * FROM webauthn_challenges WHERE challenge = ? AND expires_at > NOW()",
-        )
-        .bind(challenge)
-        .fetch_optional(&self.pool)
-        .await?;
-
-        Ok(result)
-    }
-
-    pub async fn delete_challenge(&self, id: Uuid) -> Result<(), AppError> {
-        sqlx::query("DELETE FROM webauthn_challenges WHERE id = ?")
-            .bind(id.to_string())
-            .execute(&self.pool)
-            .await?;
-        Ok(())
-    }
-
-    pub async fn delete_expired_challenges(&self) -> Result<u64, AppError> {
-        let result = sqlx::query("DELETE FROM webauthn_challenges WHERE expires_at < NOW()")
-            .execute(&self.pool)
-            .await?;
-        Ok(result.rows_affected())
-    }
-
     pub async fn user_has_passkeys(&self, user_id: Uuid) -> Result<bool, AppError> {
         let count: (i64,) = sqlx::query_as(
             "SELECT COUNT(*) FROM webauthn_credentials WHERE user_id = ? AND is_active = TRUE",
@@ -1,9 +1,18 @@
 use sqlx::MySqlPool;
 use uuid::Uuid;
-use chrono::{Utc, Duration};
+use chrono::{DateTime, Utc, Duration};
 
 use crate::error::AppError;
-use crate::models::{Webhook, WebhookDelivery};
+use crate::models::{Webhook, WebhookDelivery, WebhookEventRecord};
+use crate::utils::field_crypto::{open_field, seal_field, EncryptedColumn};
+use crate::utils::request_id::current_request_id;
+
+/// Decrypt the signing secret of a fetched webhook
+fn decrypt_secret(mut webhook: Webhook) -> Webhook {
+    let id = webhook.id.to_string();
+    webhook.secret = open_field(EncryptedColumn::WebhookSecret, &id, webhook.secret).unwrap_or_default();
+    webhook
+}
 
 #[derive(Clone)]
 pub struct WebhookRepository {
@@ -21,6 +30,7 @@ impl WebhookRepository {
         url: &str,
         secret: &str,
         events: Vec<String>,
+        payload_version: i32,
     ) -> Result<Webhook, AppError> {
         let id = Uuid::new_v4();
         let events_json = serde_json::to_string(&events)
@@ -28,15 +38,16 @@ impl WebhookRepository {
 
         sqlx::query(
             r#"
-            INSERT INTO webhooks (id, app_id, url, secret, events)
-            VALUES (?, ?, ?, ?, ?)
+            INSERT INTO webhooks (id, app_id, url, secret, events, payload_version)
+            VALUES (?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id.to_string())
         .bind(app_id.to_string())
         .bind(url)
-        .bind(secret)
+        .bind(seal_field(EncryptedColumn::WebhookSecret, secret))
         .bind(&events_json)
+        .bind(payload_version)
         .execute(&self.pool)
         .await?;
 
@@ -53,7 +64,7 @@ impl WebhookRepository {
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(webhook)
+        Ok(webhook.map(decrypt_secret))
     }
 
     pub async fn find_by_app(&self, app_id: Uuid) -> Result<Vec<Webhook>, AppError> {
@@ -64,7 +75,7 @@ impl WebhookRepository {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(webhooks)
+        Ok(webhooks.into_iter().map(decrypt_secret).collect())
     }
 
     pub async fn find_by_event(&self, app_id: Uuid, event: &str) -> Result<Vec<Webhook>, AppError> {
@@ -80,7 +91,7 @@ impl WebhookRepository {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(webhooks)
+        Ok(webhooks.into_iter().map(decrypt_secret).collect())
     }
 
     pub async fn update(
@@ -89,6 +100,7 @@ impl WebhookRepository {
         url: Option<&str>,
         events: Option<Vec<String>>,
         is_active: Option<bool>,
+        payload_version: Option<i32>,
     ) -> Result<Webhook, AppError> {
         if let Some(url) = url {
             sqlx::query("UPDATE webhooks SET url = ? WHERE id = ?")
@@ -116,6 +128,14 @@ impl WebhookRepository {
                 .await?;
         }
 
+        if let Some(payload_version) = payload_version {
+            sqlx::query("UPDATE webhooks SET payload_version = ? WHERE id = ?")
+                .bind(payload_version)
+                .bind(id.to_string())
+                .execute(&self.pool)
+                .await?;
+        }
+
         self.find_by_id(id).await?.ok_or(AppError::NotFound("Webhook not found".into()))
     }
 
@@ -127,12 +147,93 @@ impl WebhookRepository {
         Ok(())
     }
 
+    // Event outbox methods
+    pub async fn create_event(
+        &self,
+        app_id: Uuid,
+        event_type: &str,
+        payload: &serde_json::Value,
+    ) -> Result<WebhookEventRecord, AppError> {
+        let id = Uuid::new_v4();
+        let payload_json = serde_json::to_string(payload)
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_events (id, app_id, event_type, payload, correlation_id)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(app_id.to_string())
+        .bind(event_type)
+        .bind(&payload_json)
+        .bind(current_request_id())
+        .execute(&self.pool)
+        .await?;
+
+        let event = sqlx::query_as::<_, WebhookEventRecord>("SELECT * FROM webhook_events WHERE id = ?")
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await?;
+
+        event.ok_or(AppError::InternalError(anyhow::anyhow!("Failed to record webhook event")))
+    }
+
+    /// Events of an app raised in `[from, to)` with one of the given types, oldest first
+    pub async fn find_events(
+        &self,
+        app_id: Uuid,
+        event_types: &[String],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookEventRecord>, AppError> {
+        if event_types.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let types_json = serde_json::to_string(event_types)
+            .map_err(|e| AppError::InternalError(e.into()))?;
+
+        let events = sqlx::query_as::<_, WebhookEventRecord>(
+            r#"
+            SELECT * FROM webhook_events
+            WHERE app_id = ? AND created_at >= ? AND created_at < ?
+            AND JSON_CONTAINS(?, JSON_QUOTE(event_type))
+            ORDER BY created_at ASC, id ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(app_id.to_string())
+        .bind(from)
+        .bind(to)
+        .bind(types_json)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Delete outbox events raised before `before`
+    ///
+    /// Returns the number of events deleted.
+    pub async fn prune_events(&self, before: DateTime<Utc>) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM webhook_events WHERE created_at < ?")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
     // Delivery methods
     pub async fn create_delivery(
         &self,
         webhook_id: Uuid,
-        event_type: &str,
+        event: &WebhookEventRecord,
         payload: serde_json::Value,
+        is_replay: bool,
     ) -> Result<WebhookDelivery, AppError> {
         let id = Uuid::new_v4();
         let payload_json = serde_json::to_string(&payload)
@@ -140,14 +241,17 @@ impl WebhookRepository {
 
         sqlx::query(
             r#"
-            INSERT INTO webhook_deliveries (id, webhook_id, event_type, payload)
-            VALUES (?, ?, ?, ?)
+            INSERT INTO webhook_deliveries (id, webhook_id, event_id, event_type, payload, is_replay, correlation_id)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id.to_string())
         .bind(webhook_id.to_string())
-        .bind(event_type)
+        .bind(event.id.to_string())
+        .bind(&event.event_type)
         .bind(&payload_json)
+        .bind(is_replay)
+        .bind(&event.correlation_id)
         .execute(&self.pool)
         .await?;
 
@@ -167,17 +271,37 @@ impl WebhookRepository {
         Ok(delivery)
     }
 
-    pub async fn get_pending_deliveries(&self, limit: i32) -> Result<Vec<WebhookDelivery>, AppError> {
+    /// Pending deliveries of active webhooks whose circuit is closed
+    ///
+    /// At most `per_webhook` deliveries are taken from each webhook, oldest
+    /// first, so one backlogged receiver cannot fill the batch. A webhook
+    /// whose circuit has just cooled down gets a single probe delivery.
+    pub async fn get_pending_deliveries(
+        &self,
+        per_webhook: i64,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, AppError> {
         let deliveries = sqlx::query_as::<_, WebhookDelivery>(
             r#"
-            SELECT * FROM webhook_deliveries 
-            WHERE delivered_at IS NULL 
-            AND (next_retry_at IS NULL OR next_retry_at <= NOW())
-            AND attempts < 5
+            SELECT id, webhook_id, event_type, payload, response_status, response_body,
+                   attempts, next_retry_at, delivered_at, event_id, is_replay, correlation_id, created_at
+            FROM (
+                SELECT d.*, w.circuit_open_until,
+                       ROW_NUMBER() OVER (PARTITION BY d.webhook_id ORDER BY d.created_at) AS position
+                FROM webhook_deliveries d
+                JOIN webhooks w ON w.id = d.webhook_id
+                WHERE d.delivered_at IS NULL
+                AND (d.next_retry_at IS NULL OR d.next_retry_at <= NOW())
+                AND d.attempts < 5
+                AND w.is_active = TRUE
+                AND (w.circuit_open_until IS NULL OR w.circuit_open_until <= NOW())
+            ) pending
+            WHERE position <= IF(circuit_open_until IS NULL, ?, 1)
             ORDER BY created_at ASC
             LIMIT ?
             "#,
         )
+        .bind(per_webhook)
         .bind(limit)
         .fetch_all(&self.pool)
         .await?;
@@ -185,6 +309,52 @@ impl WebhookRepository {
         Ok(deliveries)
     }
 
+    /// Close the circuit after a successful delivery
+    pub async fn record_success(&self, webhook_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE webhooks
+            SET consecutive_failures = 0, circuit_open_until = NULL
+            WHERE id = ? AND (consecutive_failures > 0 OR circuit_open_until IS NOT NULL)
+            "#,
+        )
+        .bind(webhook_id.to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Count a failed delivery, opening the circuit for `cooldown_secs` once
+    /// `threshold` failures in a row are reached (0 = never)
+    ///
+    /// Returns the number of failures in a row.
+    pub async fn record_failure(&self, webhook_id: Uuid, threshold: i32, cooldown_secs: i64) -> Result<i32, AppError> {
+        // MySQL applies the assignments left to right, so the circuit is
+        // checked against the count before it is incremented
+        sqlx::query(
+            r#"
+            UPDATE webhooks
+            SET circuit_open_until = IF(? > 0 AND consecutive_failures + 1 >= ?,
+                                        NOW() + INTERVAL ? SECOND, circuit_open_until),
+                consecutive_failures = consecutive_failures + 1
+            WHERE id = ?
+            "#,
+        )
+        .bind(threshold)
+        .bind(threshold)
+        .bind(cooldown_secs)
+        .bind(webhook_id.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        let failures = sqlx::query_scalar::<_, i32>("SELECT consecutive_failures FROM webhooks WHERE id = ?")
+            .bind(webhook_id.to_string())
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(failures.unwrap_or_default())
+    }
+
     pub async fn mark_delivered(&self, id: Uuid, status: i32, body: Option<&str>) -> Result<(), AppError> {
         sqlx::query(
             r#"
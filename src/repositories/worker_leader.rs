@@ -0,0 +1,72 @@
+use sqlx::MySqlPool;
+
+use crate::error::AppError;
+use crate::models::WorkerLeader;
+
+/// Repository recording which instance leads each background worker
+#[derive(Clone)]
+pub struct WorkerLeaderRepository {
+    pool: MySqlPool,
+}
+
+impl WorkerLeaderRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record that `instance_id` has just taken leadership of `worker`
+    pub async fn record_acquired(&self, worker: &str, instance_id: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO worker_leaders (worker, instance_id, acquired_at, heartbeat_at)
+            VALUES (?, ?, NOW(), NOW())
+            ON DUPLICATE KEY UPDATE
+                instance_id = VALUES(instance_id),
+                acquired_at = VALUES(acquired_at),
+                heartbeat_at = VALUES(heartbeat_at)
+            "#,
+        )
+        .bind(worker)
+        .bind(instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Refresh the heartbeat of the current leader
+    pub async fn heartbeat(&self, worker: &str, instance_id: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE worker_leaders
+            SET heartbeat_at = NOW()
+            WHERE worker = ? AND instance_id = ?
+            "#,
+        )
+        .bind(worker)
+        .bind(instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// List every worker's last leader and whether its lock is currently held
+    pub async fn list(&self) -> Result<Vec<WorkerLeader>, AppError> {
+        let leaders = sqlx::query_as::<_, WorkerLeader>(
+            r#"
+            SELECT worker, instance_id, acquired_at, heartbeat_at,
+                   IS_USED_LOCK(CONCAT(DATABASE(), ':', worker)) IS NOT NULL AS active
+            FROM worker_leaders
+            ORDER BY worker
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::InternalError(e.into()))?;
+
+        Ok(leaders)
+    }
+}
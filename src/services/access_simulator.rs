@@ -0,0 +1,479 @@
+//! What-if evaluation of access decisions for admins
+//!
+//! Replays the checks `POST /auth/login` makes for a sign-in to an app with
+//! app-scoped tokens (lockout, account state, IP rules, bans, role access
+//! windows, the app's required acr) plus RBAC for one permission, without
+//! side effects. Every check is evaluated so the trace shows all reasons a
+//! user would be refused, not only the first one.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, AuthError};
+use crate::models::user_app::UserAppStatus;
+use crate::models::{App, User};
+use crate::repositories::{AppRepository, DeviceRepository, UserAppRepository, UserRepository};
+use crate::services::{AccountLockoutService, IpAccessResult, IpRuleService, LockoutConfig};
+use crate::utils::access_window;
+use crate::utils::acr::{self, AcrLevel};
+
+/// Inputs of a simulated access decision
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AccessSimulation {
+    /// User by id; `email` is used when not given
+    pub user_id: Option<Uuid>,
+    pub email: Option<String>,
+    /// App by id; `app` (code) is used when not given. Without an app only
+    /// the account checks run.
+    pub app_id: Option<Uuid>,
+    pub app: Option<String>,
+    /// Permission code to check in the app
+    pub permission: Option<String>,
+    /// Client IP the request comes from
+    pub ip: Option<String>,
+    /// Registered device the request comes from
+    pub device_id: Option<Uuid>,
+    /// Assurance level of the sign-in (`pwd`, `mfa`, `phr`); defaults to the
+    /// level a password sign-in of the user reaches
+    pub acr: Option<String>,
+    /// When the access happens (role access windows and expiries); defaults to now
+    pub at: Option<DateTime<Utc>>,
+}
+
+/// Result of one check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckOutcome {
+    Pass,
+    Fail,
+    /// Not evaluated (missing input, or an earlier lookup failed)
+    Skip,
+    /// Reported for context; does not change the decision
+    Info,
+}
+
+/// One step of the explanation trace
+#[derive(Debug, Clone, Serialize)]
+pub struct AccessCheck {
+    pub check: &'static str,
+    pub outcome: CheckOutcome,
+    /// Error code the request would fail with (failed checks only);
+    /// `permission_not_granted` means the token lacks the permission
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<&'static str>,
+    pub detail: String,
+}
+
+impl AccessCheck {
+    fn pass(check: &'static str, detail: impl Into<String>) -> Self {
+        Self { check, outcome: CheckOutcome::Pass, error: None, detail: detail.into() }
+    }
+
+    fn fail(check: &'static str, error: &'static str, detail: impl Into<String>) -> Self {
+        Self { check, outcome: CheckOutcome::Fail, error: Some(error), detail: detail.into() }
+    }
+
+    fn skip(check: &'static str, detail: impl Into<String>) -> Self {
+        Self { check, outcome: CheckOutcome::Skip, error: None, detail: detail.into() }
+    }
+
+    fn info(check: &'static str, detail: impl Into<String>) -> Self {
+        Self { check, outcome: CheckOutcome::Info, error: None, detail: detail.into() }
+    }
+}
+
+/// Decision with the trace that explains it
+#[derive(Debug, Clone, Serialize)]
+pub struct AccessDecision {
+    pub allowed: bool,
+    /// Error code of the first failed check, as the real request would return it
+    pub error: Option<&'static str>,
+    pub user_id: Option<Uuid>,
+    pub app_id: Option<Uuid>,
+    /// Assurance level the simulation assumed
+    pub acr: String,
+    /// Roles and permissions the user holds in the app at `evaluated_at`
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+    pub trace: Vec<AccessCheck>,
+    pub evaluated_at: DateTime<Utc>,
+}
+
+impl AccessDecision {
+    fn from_trace(trace: Vec<AccessCheck>, evaluated_at: DateTime<Utc>) -> Self {
+        let error = trace
+            .iter()
+            .find(|check| check.outcome == CheckOutcome::Fail)
+            .and_then(|check| check.error);
+
+        Self {
+            allowed: error.is_none(),
+            error,
+            user_id: None,
+            app_id: None,
+            acr: String::new(),
+            roles: Vec::new(),
+            permissions: Vec::new(),
+            trace,
+            evaluated_at,
+        }
+    }
+}
+
+/// A role of the user in the app, with the permissions it grants
+struct HeldRole {
+    name: String,
+    access_window: Option<String>,
+    permissions: Vec<String>,
+}
+
+/// Service answering "would this user get in, and why not"
+pub struct AccessSimulatorService {
+    pool: MySqlPool,
+    user_repo: UserRepository,
+    app_repo: AppRepository,
+    user_app_repo: UserAppRepository,
+    device_repo: DeviceRepository,
+    ip_rule_service: IpRuleService,
+    lockout_service: AccountLockoutService,
+    require_verified_email: bool,
+}
+
+impl AccessSimulatorService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self {
+            user_repo: UserRepository::new(pool.clone()),
+            app_repo: AppRepository::new(pool.clone()),
+            user_app_repo: UserAppRepository::new(pool.clone()),
+            device_repo: DeviceRepository::new(pool.clone()),
+            ip_rule_service: IpRuleService::new(pool.clone()),
+            lockout_service: AccountLockoutService::new(pool.clone(), LockoutConfig::default()),
+            pool,
+            require_verified_email: false,
+        }
+    }
+
+    /// Simulate `LOGIN_REQUIRE_VERIFIED_EMAIL`
+    pub fn with_verified_email_required(mut self, required: bool) -> Self {
+        self.require_verified_email = required;
+        self
+    }
+
+    /// Evaluate every check for the simulated access and explain the result
+    pub async fn simulate(&self, input: &AccessSimulation) -> Result<AccessDecision, AppError> {
+        if input.user_id.is_none() && input.email.is_none() {
+            return Err(AppError::ValidationError("user_id or email is required".to_string()));
+        }
+        let requested_acr = input
+            .acr
+            .as_deref()
+            .map(|acr| acr.parse::<AcrLevel>().map_err(|e| AppError::ValidationError(e.to_string())))
+            .transpose()?;
+        let at = input.at.unwrap_or_else(Utc::now);
+        let mut trace = Vec::new();
+
+        let user = match input.user_id {
+            Some(user_id) => self.user_repo.find_by_id(user_id).await?,
+            None => self.user_repo.find_by_email(input.email.as_deref().unwrap_or_default()).await?,
+        };
+        let app = self.find_app(input).await?;
+
+        let Some(user) = user else {
+            trace.push(AccessCheck::fail("user", "invalid_credentials", "No user with this id or email"));
+            let mut decision = AccessDecision::from_trace(trace, at);
+            decision.app_id = app.map(|app| app.id);
+            return Ok(decision);
+        };
+        trace.push(AccessCheck::pass("user", format!("Found user {}", user.id)));
+
+        self.check_account(&user, &mut trace).await?;
+
+        let acr = requested_acr.unwrap_or(if user.mfa_enabled { AcrLevel::Mfa } else { AcrLevel::Password });
+        let mut roles = Vec::new();
+        match &app {
+            Some(app) => {
+                trace.push(AccessCheck::pass("app", format!("Found app '{}'", app.code)));
+                roles = self.held_roles(user.id, app.id, at).await?;
+                self.check_app(&user, app, input, &roles, acr, at, &mut trace).await?;
+            }
+            None if input.app_id.is_some() || input.app.is_some() => {
+                trace.push(AccessCheck::fail("app", "not_app_member", "No app with this id or code"));
+            }
+            None => trace.push(AccessCheck::skip("app", "No app given; only account checks ran")),
+        }
+
+        trace.push(if user.mfa_enabled {
+            AccessCheck::info("mfa", "A password sign-in is asked for a second factor")
+        } else {
+            AccessCheck::info("mfa", "MFA is not enabled; a password sign-in reaches pwd")
+        });
+        self.report_device(user.id, input.device_id, &mut trace).await?;
+
+        let admitted: Vec<&HeldRole> = roles
+            .iter()
+            .filter(|role| access_window::admits(role.access_window.as_deref(), at))
+            .collect();
+        let mut permissions: Vec<String> = admitted.iter().flat_map(|role| role.permissions.clone()).collect();
+        permissions.sort();
+        permissions.dedup();
+
+        let mut decision = AccessDecision::from_trace(trace, at);
+        decision.user_id = Some(user.id);
+        decision.app_id = app.map(|app| app.id);
+        decision.acr = acr.to_string();
+        decision.roles = admitted.iter().map(|role| role.name.clone()).collect();
+        decision.permissions = permissions;
+        Ok(decision)
+    }
+
+    async fn find_app(&self, input: &AccessSimulation) -> Result<Option<App>, AppError> {
+        match (input.app_id, input.app.as_deref()) {
+            (Some(app_id), _) => self.app_repo.find_by_id(app_id).await,
+            (None, Some(code)) => self.app_repo.find_by_code(code).await,
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Lockout, active flag and verified email, in the order login checks them
+    async fn check_account(&self, user: &User, trace: &mut Vec<AccessCheck>) -> Result<(), AppError> {
+        let lockout = self.lockout_service.get_lockout_info(user.id).await?;
+        trace.push(match lockout.locked_until.filter(|_| lockout.is_locked) {
+            Some(until) => AccessCheck::fail("account_lock", "account_locked", format!("Locked until {}", until)),
+            None => AccessCheck::pass("account_lock", format!("{} failed attempts", lockout.failed_attempts)),
+        });
+
+        trace.push(if user.is_active {
+            AccessCheck::pass("user_active", "Account is active")
+        } else {
+            AccessCheck::fail("user_active", "user_inactive", "Account is deactivated")
+        });
+
+        trace.push(if !self.require_verified_email {
+            AccessCheck::skip("email_verified", "Verified email is not required for sign-in")
+        } else if user.email_verified {
+            AccessCheck::pass("email_verified", "Email is verified")
+        } else {
+            AccessCheck::fail("email_verified", "email_not_verified", "Email is not verified")
+        });
+
+        Ok(())
+    }
+
+    /// IP rules, ban, role membership, required acr and the permission
+    #[allow(clippy::too_many_arguments)]
+    async fn check_app(
+        &self,
+        user: &User,
+        app: &App,
+        input: &AccessSimulation,
+        roles: &[HeldRole],
+        acr: AcrLevel,
+        at: DateTime<Utc>,
+        trace: &mut Vec<AccessCheck>,
+    ) -> Result<(), AppError> {
+        trace.push(match input.ip.as_deref() {
+            Some(ip) => match self.ip_rule_service.check_ip_access(ip, Some(app.id)).await? {
+                IpAccessResult::Blocked => AccessCheck::fail("ip_rules", "ip_blocked", format!("{} is blacklisted", ip)),
+                IpAccessResult::Allowed => AccessCheck::pass("ip_rules", format!("{} is whitelisted", ip)),
+                IpAccessResult::NoRule => AccessCheck::pass("ip_rules", format!("No rule matches {}", ip)),
+            },
+            None => AccessCheck::skip("ip_rules", "No IP given"),
+        });
+
+        let membership = self
+            .user_app_repo
+            .find(user.id, app.id)
+            .await
+            .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?;
+        trace.push(match membership {
+            Some(user_app) if user_app.status == UserAppStatus::Banned => AccessCheck::fail(
+                "app_ban",
+                "user_banned",
+                format!("Banned: {}", user_app.banned_reason.as_deref().unwrap_or("no reason given")),
+            ),
+            _ => AccessCheck::pass("app_ban", "Not banned from the app"),
+        });
+
+        trace.push(membership_check(roles, at));
+
+        let required = acr::parse_required(app.required_acr.as_deref());
+        trace.push(match required {
+            Some(required) if !acr.satisfies(Some(required)) => AccessCheck::fail(
+                "required_acr",
+                "insufficient_user_authentication",
+                format!("App requires {}, sign-in reaches {}", required, acr),
+            ),
+            Some(required) => AccessCheck::pass("required_acr", format!("App requires {}, sign-in reaches {}", required, acr)),
+            None => AccessCheck::pass("required_acr", "App has no minimum level"),
+        });
+
+        if let Some(permission) = input.permission.as_deref() {
+            trace.push(permission_check(roles, permission, at));
+        }
+
+        Ok(())
+    }
+
+    /// Whether the device could approve push sign-ins; informational only
+    async fn report_device(
+        &self,
+        user_id: Uuid,
+        device_id: Option<Uuid>,
+        trace: &mut Vec<AccessCheck>,
+    ) -> Result<(), AuthError> {
+        let Some(device_id) = device_id else {
+            return Ok(());
+        };
+
+        let device = self.device_repo.find_by_id(device_id).await?.filter(|d| d.user_id == user_id);
+        trace.push(match device {
+            Some(device) if device.is_active && device.revoked_at.is_none() => AccessCheck::info(
+                "device",
+                format!("Registered {} device, last seen {}; can approve push sign-ins", device.platform, device.last_seen_at),
+            ),
+            Some(_) => AccessCheck::info("device", "Device was revoked; it cannot approve push sign-ins"),
+            None => AccessCheck::info("device", "Not a registered device of this user"),
+        });
+
+        Ok(())
+    }
+
+    /// Unexpired roles of a user in an app at `at`, with their permissions
+    async fn held_roles(&self, user_id: Uuid, app_id: Uuid, at: DateTime<Utc>) -> Result<Vec<HeldRole>, AppError> {
+        let rows = sqlx::query_as::<_, (String, Option<String>, Option<String>)>(
+            r#"
+            SELECT r.name, uar.access_window, p.code
+            FROM user_app_roles uar
+            JOIN roles r ON uar.role_id = r.id
+            LEFT JOIN role_permissions rp ON r.id = rp.role_id
+            LEFT JOIN permissions p ON rp.permission_id = p.id
+            WHERE uar.user_id = ? AND uar.app_id = ?
+              AND (uar.expires_at IS NULL OR uar.expires_at > ?)
+            ORDER BY r.name, p.code
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(app_id.to_string())
+        .bind(at)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut roles: Vec<HeldRole> = Vec::new();
+        for (name, access_window, permission) in rows {
+            if roles.last().is_none_or(|role| role.name != name) {
+                roles.push(HeldRole { name, access_window, permissions: Vec::new() });
+            }
+            if let (Some(role), Some(permission)) = (roles.last_mut(), permission) {
+                role.permissions.push(permission);
+            }
+        }
+
+        Ok(roles)
+    }
+}
+
+/// App-scoped tokens need a role whose access window is open
+fn membership_check(roles: &[HeldRole], at: DateTime<Utc>) -> AccessCheck {
+    if roles.is_empty() {
+        return AccessCheck::fail("app_membership", "not_app_member", "User holds no unexpired role in the app");
+    }
+
+    let (open, closed): (Vec<&HeldRole>, Vec<&HeldRole>) = roles
+        .iter()
+        .partition(|role| access_window::admits(role.access_window.as_deref(), at));
+    if open.is_empty() {
+        let windows: Vec<String> = closed
+            .iter()
+            .map(|role| format!("{} ({})", role.name, role.access_window.as_deref().unwrap_or_default()))
+            .collect();
+        return AccessCheck::fail(
+            "app_membership",
+            "not_app_member",
+            format!("Every role is outside its access window: {}", windows.join(", ")),
+        );
+    }
+
+    let names: Vec<&str> = open.iter().map(|role| role.name.as_str()).collect();
+    AccessCheck::pass("app_membership", format!("Roles: {}", names.join(", ")))
+}
+
+/// RBAC: a role open at `at` must grant the permission
+fn permission_check(roles: &[HeldRole], permission: &str, at: DateTime<Utc>) -> AccessCheck {
+    let granting: Vec<&HeldRole> = roles
+        .iter()
+        .filter(|role| role.permissions.iter().any(|p| p == permission))
+        .collect();
+
+    match granting.iter().find(|role| access_window::admits(role.access_window.as_deref(), at)) {
+        Some(role) => AccessCheck::pass("permission", format!("'{}' is granted by role {}", permission, role.name)),
+        None if !granting.is_empty() => AccessCheck::fail(
+            "permission",
+            "permission_not_granted",
+            format!("'{}' is only granted by roles outside their access window", permission),
+        ),
+        None => AccessCheck::fail(
+            "permission",
+            "permission_not_granted",
+            format!("No role of the user grants '{}'", permission),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(name: &str, window: Option<&str>, permissions: &[&str]) -> HeldRole {
+        HeldRole {
+            name: name.to_string(),
+            access_window: window.map(String::from),
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_decision_reports_first_failure() {
+        let trace = vec![
+            AccessCheck::pass("user", ""),
+            AccessCheck::info("mfa", ""),
+            AccessCheck::fail("ip_rules", "ip_blocked", ""),
+            AccessCheck::fail("app_membership", "not_app_member", ""),
+        ];
+        let decision = AccessDecision::from_trace(trace, Utc::now());
+
+        assert!(!decision.allowed);
+        assert_eq!(decision.error, Some("ip_blocked"));
+    }
+
+    #[test]
+    fn test_decision_allows_without_failures() {
+        let trace = vec![AccessCheck::pass("user", ""), AccessCheck::skip("ip_rules", "")];
+        let decision = AccessDecision::from_trace(trace, Utc::now());
+
+        assert!(decision.allowed);
+        assert_eq!(decision.error, None);
+    }
+
+    #[test]
+    fn test_membership_requires_an_open_role() {
+        let at = Utc::now();
+        assert_eq!(membership_check(&[], at).outcome, CheckOutcome::Fail);
+        assert_eq!(membership_check(&[role("viewer", None, &[])], at).outcome, CheckOutcome::Pass);
+    }
+
+    #[test]
+    fn test_permission_check() {
+        let at = Utc::now();
+        let roles = vec![role("editor", None, &["posts.write"]), role("viewer", None, &["posts.read"])];
+
+        let check = permission_check(&roles, "posts.write", at);
+        assert_eq!(check.outcome, CheckOutcome::Pass);
+        assert!(check.detail.contains("editor"));
+
+        let check = permission_check(&roles, "posts.delete", at);
+        assert_eq!(check.outcome, CheckOutcome::Fail);
+        assert_eq!(check.error, Some("permission_not_granted"));
+    }
+}
@@ -2,7 +2,7 @@ use chrono::{Duration, Utc};
 use sqlx::MySqlPool;
 use uuid::Uuid;
 
-use crate::error::AuthError;
+use crate::error::{AuthError, Throttle};
 
 /// Configuration for account lockout
 #[derive(Debug, Clone)]
@@ -34,6 +34,17 @@ impl AccountLockoutService {
         Self { pool, config }
     }
 
+    /// Retry hint for a login refused because the account is locked until `locked_until`
+    pub fn throttle(&self, locked_until: chrono::DateTime<Utc>) -> Throttle {
+        Throttle {
+            policy: "account_lockout",
+            retry_after_seconds: (locked_until - Utc::now()).num_seconds().max(0),
+            limit: self.config.max_failed_attempts,
+            remaining: 0,
+            window_seconds: self.config.reset_after_minutes * 60,
+        }
+    }
+
     /// Check if an account is currently locked
     pub async fn is_locked(&self, user_id: Uuid) -> Result<bool, AuthError> {
         let result = sqlx::query_scalar::<_, Option<chrono::DateTime<Utc>>>(
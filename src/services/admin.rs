@@ -2,9 +2,12 @@ use sqlx::MySqlPool;
 use uuid::Uuid;
 
 use crate::dto::user_management::PaginatedResponse;
-use crate::error::UserManagementError;
-use crate::models::{App, User};
-use crate::repositories::{AppRepository, UserRepository, UserAppRoleRepository};
+use crate::error::{AuthError, UserManagementError};
+use crate::models::{App, DormantAccount, User, DELETION_POLICY_ANONYMIZE, DELETION_POLICY_DELETE};
+use crate::repositories::{AppRepository, DormantAccountRepository, UserRepository, UserAppRoleRepository};
+
+/// Longest accepted legal hold reason (matches `users.legal_hold_reason`)
+const MAX_LEGAL_HOLD_REASON_LEN: usize = 500;
 
 /// User roles info across all apps
 #[derive(Debug, Clone, serde::Serialize)]
@@ -118,6 +121,35 @@ impl AdminService {
         Ok(PaginatedResponse::new(users, page, limit, total))
     }
 
+    /// List accounts inactive for at least `days` days (admin only)
+    ///
+    /// `flagged` keeps only the accounts the dormant account worker has
+    /// (`true`) or has not yet (`false`) flagged.
+    pub async fn list_dormant_users(
+        &self,
+        actor_id: Uuid,
+        days: i64,
+        flagged: Option<bool>,
+        page: u32,
+        limit: u32,
+    ) -> Result<PaginatedResponse<DormantAccount>, UserManagementError> {
+        self.verify_admin(actor_id).await?;
+
+        if days <= 0 {
+            return Err(UserManagementError::ValidationError(
+                "days must be positive (dormant account detection is disabled, pass days explicitly)".to_string(),
+            ));
+        }
+
+        let repo = DormantAccountRepository::new(self.pool.clone());
+        let total = repo.count(days, flagged).await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?;
+        let accounts = repo.list(days, flagged, page, limit).await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?;
+
+        Ok(PaginatedResponse::new(accounts, page, limit, total))
+    }
+
     /// List all apps with pagination (admin only)
     /// 
     /// # Arguments
@@ -226,14 +258,29 @@ impl AdminService {
             .map_err(|e| UserManagementError::InternalError(e.into()))
     }
 
-    /// Delete user permanently (admin only)
+    /// Delete or anonymize a user (admin only)
+    ///
+    /// `mode` is `delete` (default) or `anonymize`. A member of any app whose
+    /// deletion policy is `anonymize` is always anonymized. Returns the mode
+    /// that was applied.
     pub async fn delete_user(
         &self,
         actor_id: Uuid,
         user_id: Uuid,
-    ) -> Result<(), UserManagementError> {
+        mode: Option<&str>,
+    ) -> Result<&'static str, UserManagementError> {
         self.verify_admin(actor_id).await?;
 
+        let requested = match mode.unwrap_or(DELETION_POLICY_DELETE) {
+            DELETION_POLICY_DELETE => DELETION_POLICY_DELETE,
+            DELETION_POLICY_ANONYMIZE => DELETION_POLICY_ANONYMIZE,
+            _ => {
+                return Err(UserManagementError::ValidationError(
+                    "Deletion mode must be one of: delete, anonymize".to_string(),
+                ))
+            }
+        };
+
         // Prevent admin from deleting themselves
         if actor_id == user_id {
             return Err(UserManagementError::InternalError(
@@ -243,17 +290,73 @@ impl AdminService {
 
         // Check if user exists
         let user = self.user_repo.find_by_id(user_id).await
-            .map_err(|e| UserManagementError::InternalError(e.into()))?;
-        
-        if user.is_none() {
-            return Err(UserManagementError::UserNotFound);
+            .map_err(|e| UserManagementError::InternalError(e.into()))?
+            .ok_or(UserManagementError::UserNotFound)?;
+
+        if user.legal_hold {
+            return Err(UserManagementError::LegalHold);
         }
 
-        self.user_repo.delete(user_id).await
-            .map_err(|e| UserManagementError::InternalError(e.into()))
+        let anonymize = requested == DELETION_POLICY_ANONYMIZE
+            || self.app_repo.member_app_requires_anonymization(user_id).await
+                .map_err(|e| UserManagementError::InternalError(e.into()))?;
+
+        if anonymize {
+            self.user_repo.anonymize(user_id).await
+                .map_err(|e| UserManagementError::InternalError(e.into()))?;
+            Ok(DELETION_POLICY_ANONYMIZE)
+        } else {
+            self.user_repo.delete(user_id).await
+                .map_err(|e| UserManagementError::InternalError(e.into()))?;
+            Ok(DELETION_POLICY_DELETE)
+        }
+    }
+
+    /// Place a user under legal hold or release it (admin only)
+    ///
+    /// A reason is required when setting the hold.
+    pub async fn set_legal_hold(
+        &self,
+        actor_id: Uuid,
+        user_id: Uuid,
+        legal_hold: bool,
+        reason: Option<&str>,
+    ) -> Result<User, UserManagementError> {
+        self.verify_admin(actor_id).await?;
+
+        let reason = reason.map(str::trim).filter(|r| !r.is_empty());
+        if legal_hold {
+            match reason {
+                None => {
+                    return Err(UserManagementError::ValidationError(
+                        "A reason is required to place a legal hold".to_string(),
+                    ))
+                }
+                Some(r) if r.len() > MAX_LEGAL_HOLD_REASON_LEN => {
+                    return Err(UserManagementError::ValidationError(format!(
+                        "Reason must be at most {} characters",
+                        MAX_LEGAL_HOLD_REASON_LEN
+                    )))
+                }
+                Some(_) => {}
+            }
+        }
+
+        self.user_repo.set_legal_hold(user_id, legal_hold, reason).await
+            .map_err(|e| match e {
+                AuthError::UserNotFound => UserManagementError::UserNotFound,
+                e => UserManagementError::InternalError(e.into()),
+            })?;
+
+        self.user_repo.find_by_id(user_id).await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?
+            .ok_or(UserManagementError::UserNotFound)
     }
 
     /// Activate a user (admin only)
+    ///
+    /// Also restarts the dormant account clock, so an account deactivated
+    /// for inactivity is not flagged again on the next worker run.
     pub async fn activate_user(
         &self,
         actor_id: Uuid,
@@ -262,6 +365,9 @@ impl AdminService {
         self.verify_admin(actor_id).await?;
 
         self.user_repo.set_active(user_id, true).await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?;
+
+        self.user_repo.record_activity(user_id).await
             .map_err(|e| UserManagementError::InternalError(e.into()))
     }
 
@@ -333,6 +439,7 @@ impl AdminService {
             JOIN apps a ON uar.app_id = a.id
             JOIN roles r ON uar.role_id = r.id
             WHERE uar.user_id = ?
+              AND (uar.expires_at IS NULL OR uar.expires_at > NOW())
             ORDER BY a.code, r.name
             "#,
         )
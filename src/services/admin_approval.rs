@@ -0,0 +1,211 @@
+use chrono::{Duration, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::UserManagementError;
+use crate::models::{
+    AdminApproval, AuditAction, ADMIN_ACTION_DELETE_APP, ADMIN_ACTION_DELETE_USER,
+    ADMIN_ACTION_GRANT_SYSTEM_ADMIN, APPROVAL_STATUS_APPROVED, APPROVAL_STATUS_EXPIRED,
+    APPROVAL_STATUS_FAILED, APPROVAL_STATUS_PENDING, APPROVAL_STATUS_REJECTED,
+    DELETION_POLICY_ANONYMIZE, DELETION_POLICY_DELETE,
+};
+use crate::repositories::{AdminApprovalRepository, AppRepository, UserRepository};
+use crate::services::{AdminService, AuditService};
+
+/// Most approval requests returned by the list endpoint
+const LIST_LIMIT: i64 = 500;
+
+/// Service for the four-eyes approval of sensitive admin actions
+///
+/// For each action listed in `ADMIN_APPROVAL_ACTIONS`, the first admin's
+/// call only records a pending request. A different admin approves it,
+/// after which the caller executes the action; requests nobody approves
+/// expire after `ADMIN_APPROVAL_TTL_SECS`.
+#[derive(Clone)]
+pub struct AdminApprovalService {
+    repo: AdminApprovalRepository,
+    admin_service: AdminService,
+    user_repo: UserRepository,
+    app_repo: AppRepository,
+    audit_service: AuditService,
+    required_actions: Vec<String>,
+    ttl_secs: i64,
+}
+
+impl AdminApprovalService {
+    pub fn new(pool: MySqlPool, required_actions: &[String], ttl_secs: i64) -> Self {
+        Self {
+            repo: AdminApprovalRepository::new(pool.clone()),
+            admin_service: AdminService::new(pool.clone()),
+            user_repo: UserRepository::new(pool.clone()),
+            app_repo: AppRepository::new(pool.clone()),
+            audit_service: AuditService::new(pool),
+            required_actions: required_actions.to_vec(),
+            ttl_secs,
+        }
+    }
+
+    /// Whether `action` needs a second admin's approval
+    pub fn requires_approval(&self, action: &str) -> bool {
+        self.required_actions.iter().any(|a| a == action)
+    }
+
+    /// Hold `action` on `target_id` for approval (admin only)
+    ///
+    /// The target must exist; an unexpired pending request for the same
+    /// action and target is returned instead of creating another.
+    pub async fn request(
+        &self,
+        actor_id: Uuid,
+        action: &str,
+        target_id: Uuid,
+        params: Option<serde_json::Value>,
+    ) -> Result<AdminApproval, UserManagementError> {
+        self.admin_service.verify_admin(actor_id).await?;
+        self.check_target(actor_id, action, target_id, params.as_ref()).await?;
+
+        if let Some(pending) = self.repo.find_pending(action, target_id).await? {
+            return Ok(pending);
+        }
+
+        let expires_at = Utc::now() + Duration::seconds(self.ttl_secs);
+        let approval = self.repo
+            .create(action, target_id, params.as_ref(), actor_id, expires_at)
+            .await?;
+        self.log(actor_id, AuditAction::AdminActionRequested, &approval, true).await;
+
+        Ok(approval)
+    }
+
+    /// List approval requests, optionally by status (admin only)
+    pub async fn list(
+        &self,
+        actor_id: Uuid,
+        status: Option<&str>,
+    ) -> Result<Vec<AdminApproval>, UserManagementError> {
+        self.admin_service.verify_admin(actor_id).await?;
+
+        if let Some(status) = status {
+            let known = [
+                APPROVAL_STATUS_PENDING,
+                APPROVAL_STATUS_APPROVED,
+                APPROVAL_STATUS_REJECTED,
+                APPROVAL_STATUS_FAILED,
+                APPROVAL_STATUS_EXPIRED,
+            ];
+            if !known.contains(&status) {
+                return Err(UserManagementError::ValidationError(format!("Invalid status: {}", status)));
+            }
+        }
+
+        self.repo.list(status, LIST_LIMIT).await
+    }
+
+    /// Get an approval request (admin only)
+    pub async fn get(&self, actor_id: Uuid, id: Uuid) -> Result<AdminApproval, UserManagementError> {
+        self.admin_service.verify_admin(actor_id).await?;
+        self.find(id).await
+    }
+
+    /// Approve a pending request of another admin (admin only)
+    ///
+    /// Returns the approved request; the caller then executes the action
+    /// and reports a failure with `record_failure`.
+    pub async fn approve(&self, approver_id: Uuid, id: Uuid) -> Result<AdminApproval, UserManagementError> {
+        self.admin_service.verify_admin(approver_id).await?;
+
+        let approval = self.find(id).await?;
+        if !approval.is_pending() {
+            return Err(UserManagementError::ApprovalNotPending);
+        }
+        if approval.requested_by == approver_id {
+            return Err(UserManagementError::SelfApproval);
+        }
+        if !self.repo.approve(id, approver_id).await? {
+            return Err(UserManagementError::ApprovalNotPending);
+        }
+
+        let approval = self.find(id).await?;
+        self.log(approver_id, AuditAction::AdminActionApproved, &approval, true).await;
+        Ok(approval)
+    }
+
+    /// Reject a pending request; the requester may withdraw their own (admin only)
+    pub async fn reject(&self, actor_id: Uuid, id: Uuid) -> Result<AdminApproval, UserManagementError> {
+        self.admin_service.verify_admin(actor_id).await?;
+
+        let approval = self.find(id).await?;
+        if !approval.is_pending() || !self.repo.reject(id, actor_id).await? {
+            return Err(UserManagementError::ApprovalNotPending);
+        }
+
+        let approval = self.find(id).await?;
+        self.log(actor_id, AuditAction::AdminActionRejected, &approval, true).await;
+        Ok(approval)
+    }
+
+    /// Record that an approved action failed when executed
+    pub async fn record_failure(
+        &self,
+        approver_id: Uuid,
+        approval: &AdminApproval,
+        error: &UserManagementError,
+    ) -> Result<AdminApproval, UserManagementError> {
+        self.repo.mark_failed(approval.id, &error.to_string()).await?;
+
+        let approval = self.find(approval.id).await?;
+        self.log(approver_id, AuditAction::AdminActionApproved, &approval, false).await;
+        Ok(approval)
+    }
+
+    async fn find(&self, id: Uuid) -> Result<AdminApproval, UserManagementError> {
+        self.repo.find_by_id(id).await?.ok_or(UserManagementError::ApprovalNotFound)
+    }
+
+    /// Reject requests that could never be executed
+    async fn check_target(
+        &self,
+        actor_id: Uuid,
+        action: &str,
+        target_id: Uuid,
+        params: Option<&serde_json::Value>,
+    ) -> Result<(), UserManagementError> {
+        match action {
+            ADMIN_ACTION_DELETE_USER => {
+                let mode = params.and_then(|p| p.get("mode")).and_then(|m| m.as_str());
+                if !matches!(mode, None | Some(DELETION_POLICY_DELETE) | Some(DELETION_POLICY_ANONYMIZE)) {
+                    return Err(UserManagementError::ValidationError(
+                        "Deletion mode must be one of: delete, anonymize".to_string(),
+                    ));
+                }
+                if target_id == actor_id {
+                    return Err(UserManagementError::ValidationError(
+                        "Cannot delete your own account".to_string(),
+                    ));
+                }
+                self.require_user(target_id).await
+            }
+            ADMIN_ACTION_GRANT_SYSTEM_ADMIN => self.require_user(target_id).await,
+            ADMIN_ACTION_DELETE_APP => {
+                self.app_repo.find_by_id(target_id).await
+                    .map_err(|e| UserManagementError::InternalError(e.into()))?
+                    .ok_or(UserManagementError::AppNotFound)?;
+                Ok(())
+            }
+            other => Err(UserManagementError::ValidationError(format!("Unknown admin action: {}", other))),
+        }
+    }
+
+    async fn require_user(&self, user_id: Uuid) -> Result<(), UserManagementError> {
+        self.user_repo.find_by_id(user_id).await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?
+            .ok_or(UserManagementError::UserNotFound)?;
+        Ok(())
+    }
+
+    async fn log(&self, actor_id: Uuid, action: AuditAction, approval: &AdminApproval, success: bool) {
+        let _ = self.audit_service
+            .log_admin_approval_event(actor_id, action, approval, success)
+            .await;
+    }
+}
@@ -6,6 +6,7 @@ use rand::Rng;
 use crate::error::AppError;
 use crate::models::ApiKey;
 use crate::repositories::ApiKeyRepository;
+use crate::utils::access_window::AccessWindow;
 
 pub struct ApiKeyService {
     repo: ApiKeyRepository,
@@ -26,11 +27,16 @@ impl ApiKeyService {
         name: &str,
         scopes: Vec<String>,
         expires_at: Option<DateTime<Utc>>,
+        access_window: Option<&str>,
     ) -> Result<(ApiKey, String), AppError> {
+        let access_window = access_window.map(normalize_access_window).transpose()?;
+
         // Generate a secure random key
         let key = Self::generate_key();
         
-        let api_key = self.repo.create(app_id, name, &key, scopes, expires_at).await?;
+        let api_key = self.repo
+            .create(app_id, name, &key, scopes, expires_at, access_window.as_deref())
+            .await?;
         
         Ok((api_key, key))
     }
@@ -59,8 +65,16 @@ impl ApiKeyService {
         name: Option<&str>,
         scopes: Option<Vec<String>>,
         is_active: Option<bool>,
+        access_window: Option<&str>,
     ) -> Result<ApiKey, AppError> {
-        self.repo.update(id, name, scopes, is_active).await
+        // Some("") clears the window, None leaves it unchanged
+        let access_window = access_window
+            .map(|window| match window.trim() {
+                "" => Ok(None),
+                window => normalize_access_window(window).map(Some),
+            })
+            .transpose()?;
+        self.repo.update(id, name, scopes, is_active, access_window).await
     }
 
     pub async fn revoke_api_key(&self, id: Uuid) -> Result<(), AppError> {
@@ -77,6 +91,14 @@ impl ApiKeyService {
     }
 }
 
+/// Validate an access window, returning its normalized spelling
+fn normalize_access_window(window: &str) -> Result<String, AppError> {
+    window
+        .parse::<AccessWindow>()
+        .map(|w| w.to_string())
+        .map_err(|e| AppError::ValidationError(format!("Invalid access window: {}", e)))
+}
+
 // Common API key scopes
 pub mod scopes {
     pub const READ_USERS: &str = "read:users";
@@ -3,10 +3,16 @@ use sqlx::MySqlPool;
 use uuid::Uuid;
 
 use crate::error::AppError;
-use crate::models::App;
+use crate::models::{
+    App, DELETION_POLICY_ANONYMIZE, DELETION_POLICY_DELETE, TOKEN_BINDING_IP, TOKEN_BINDING_MTLS,
+    TOKEN_BINDING_NONE,
+};
 use crate::repositories::AppRepository;
-use crate::utils::jwt::JwtManager;
+use crate::services::SessionPolicy;
+use crate::utils::jwt::{JwtManager, TokenConfirmation};
 use crate::utils::secret::{generate_secret, hash_secret, verify_secret};
+use crate::utils::acr::AcrLevel;
+use crate::utils::token_binding::ip_range_for;
 
 /// Generate code with timestamp suffix (code_timestamp) like JS Date.now()
 fn generate_code_with_timestamp(code: &str) -> String {
@@ -136,6 +142,8 @@ impl AppService {
     /// # Arguments
     /// * `app_id` - The app's UUID
     /// * `secret` - The plain-text secret to verify
+    /// * `client_ip` - The caller's IP (used when the app binds tokens to an IP range)
+    /// * `cert_thumbprint` - The caller's client certificate thumbprint (used for mTLS binding)
     /// 
     /// # Returns
     /// * `Ok(String)` - The access token if authentication succeeds
@@ -146,7 +154,13 @@ impl AppService {
     /// - 3.3: Reject with 401 Unauthorized if App_Secret is invalid
     /// - 3.4: Reject with 401 Unauthorized if App_ID does not exist
     /// - 9.3: Not reveal whether the App_ID or Secret was incorrect
-    pub async fn authenticate_app(&self, app_id: Uuid, secret: &str) -> Result<String, AppError> {
+    pub async fn authenticate_app(
+        &self,
+        app_id: Uuid,
+        secret: &str,
+        client_ip: Option<&str>,
+        cert_thumbprint: Option<&str>,
+    ) -> Result<String, AppError> {
         // Get the app's secret hash (Requirements: 3.4 - generic error if app doesn't exist)
         let secret_hash = self.app_repo.get_secret_hash(app_id).await?;
         
@@ -164,9 +178,36 @@ impl AppService {
             return Err(AppError::InvalidCredentials);
         }
         
-        // Generate and return an app token (Requirements: 3.1, 3.2)
-        self.jwt_manager.create_app_token(app_id)
-            .map_err(|e| AppError::InternalError(anyhow::anyhow!("Token creation failed: {}", e)))
+        let app = self.app_repo.find_by_id(app_id).await?
+            .ok_or(AppError::InvalidCredentials)?;
+
+        // Generate and return an app token (Requirements: 3.1, 3.2),
+        // bound to the caller if the app opted in
+        let token = match app.token_binding.as_str() {
+            TOKEN_BINDING_IP => {
+                let ip_range = client_ip
+                    .and_then(|ip| ip_range_for(ip, app.token_binding_ip_prefix.map(|p| p as u32)))
+                    .ok_or_else(|| AppError::ValidationError(
+                        "Client IP is required for IP-bound app tokens".into(),
+                    ))?;
+                self.jwt_manager.create_bound_app_token(
+                    app_id,
+                    TokenConfirmation { ip_range: Some(ip_range), x5t_s256: None },
+                )
+            }
+            TOKEN_BINDING_MTLS => {
+                let thumbprint = cert_thumbprint.ok_or_else(|| AppError::ValidationError(
+                    "Client certificate is required for mTLS-bound app tokens".into(),
+                ))?;
+                self.jwt_manager.create_bound_app_token(
+                    app_id,
+                    TokenConfirmation { ip_range: None, x5t_s256: Some(thumbprint.to_string()) },
+                )
+            }
+            _ => self.jwt_manager.create_app_token(app_id),
+        };
+
+        token.map_err(|e| AppError::InternalError(anyhow::anyhow!("Token creation failed: {}", e)))
     }
 
     /// Regenerate the secret for an app (owner only)
@@ -208,4 +249,134 @@ impl AppService {
         // Return the new plain-text secret (returned only once)
         Ok(plain_secret)
     }
+
+    /// Set the session policy overrides of an app (owner only)
+    ///
+    /// `None` for either limit falls back to the server default.
+    pub async fn update_session_policy(
+        &self,
+        app_id: Uuid,
+        requester_id: Uuid,
+        idle_timeout_secs: Option<i64>,
+        absolute_lifetime_secs: Option<i64>,
+    ) -> Result<App, AppError> {
+        if !self.app_repo.is_owner(app_id, requester_id).await? {
+            return Err(AppError::NotAppOwner);
+        }
+
+        SessionPolicy::validate_overrides(idle_timeout_secs, absolute_lifetime_secs)
+            .map_err(AppError::ValidationError)?;
+
+        self.app_repo
+            .update_session_policy(app_id, idle_timeout_secs, absolute_lifetime_secs)
+            .await
+    }
+
+    /// Set how app tokens are bound to the caller (owner only)
+    ///
+    /// `ip_prefix` narrows IP binding to an IPv4 network (e.g. 24); omitted
+    /// binds tokens to the exact address.
+    pub async fn update_token_binding(
+        &self,
+        app_id: Uuid,
+        requester_id: Uuid,
+        mode: &str,
+        ip_prefix: Option<i64>,
+    ) -> Result<App, AppError> {
+        if !self.app_repo.is_owner(app_id, requester_id).await? {
+            return Err(AppError::NotAppOwner);
+        }
+
+        if ![TOKEN_BINDING_NONE, TOKEN_BINDING_IP, TOKEN_BINDING_MTLS].contains(&mode) {
+            return Err(AppError::ValidationError(
+                "Token binding must be one of: none, ip, mtls".into(),
+            ));
+        }
+
+        let ip_prefix = if mode == TOKEN_BINDING_IP { ip_prefix } else { None };
+        if let Some(prefix) = ip_prefix {
+            if !(8..=32).contains(&prefix) {
+                return Err(AppError::ValidationError(
+                    "IP prefix length must be between 8 and 32".into(),
+                ));
+            }
+        }
+
+        self.app_repo.update_token_binding(app_id, mode, ip_prefix).await
+    }
+
+    /// Set what deleting a member of the app does (owner only)
+    pub async fn update_deletion_policy(
+        &self,
+        app_id: Uuid,
+        requester_id: Uuid,
+        policy: &str,
+    ) -> Result<App, AppError> {
+        if !self.app_repo.is_owner(app_id, requester_id).await? {
+            return Err(AppError::NotAppOwner);
+        }
+
+        if ![DELETION_POLICY_DELETE, DELETION_POLICY_ANONYMIZE].contains(&policy) {
+            return Err(AppError::ValidationError(
+                "Deletion policy must be one of: delete, anonymize".into(),
+            ));
+        }
+
+        self.app_repo.update_deletion_policy(app_id, policy).await
+    }
+
+    /// Set the minimum acr of sign-ins issuing tokens for the app (owner only)
+    ///
+    /// `None` accepts any sign-in.
+    pub async fn update_required_acr(
+        &self,
+        app_id: Uuid,
+        requester_id: Uuid,
+        required_acr: Option<&str>,
+    ) -> Result<App, AppError> {
+        if !self.app_repo.is_owner(app_id, requester_id).await? {
+            return Err(AppError::NotAppOwner);
+        }
+
+        let required = required_acr
+            .map(|acr| acr.parse::<AcrLevel>())
+            .transpose()
+            .map_err(|_| AppError::ValidationError("Required acr must be one of: pwd, mfa, phr".into()))?;
+
+        self.app_repo
+            .update_required_acr(app_id, required.map(|level| level.as_str()))
+            .await
+    }
+
+    /// Allow or refuse SSO continuation into the app (owner only)
+    pub async fn update_sso_enabled(&self, app_id: Uuid, requester_id: Uuid, enabled: bool) -> Result<App, AppError> {
+        if !self.app_repo.is_owner(app_id, requester_id).await? {
+            return Err(AppError::NotAppOwner);
+        }
+
+        self.app_repo.update_sso_enabled(app_id, enabled).await
+    }
+
+    /// Subscribe the owner to the weekly app digest, or unsubscribe (owner only)
+    pub async fn update_digest_enabled(&self, app_id: Uuid, requester_id: Uuid, enabled: bool) -> Result<App, AppError> {
+        if !self.app_repo.is_owner(app_id, requester_id).await? {
+            return Err(AppError::NotAppOwner);
+        }
+
+        self.app_repo.update_digest_enabled(app_id, enabled).await
+    }
+
+    /// Accept or refuse passwordless sign-in with emailed codes (owner only)
+    pub async fn update_email_login_enabled(
+        &self,
+        app_id: Uuid,
+        requester_id: Uuid,
+        enabled: bool,
+    ) -> Result<App, AppError> {
+        if !self.app_repo.is_owner(app_id, requester_id).await? {
+            return Err(AppError::NotAppOwner);
+        }
+
+        self.app_repo.update_email_login_enabled(app_id, enabled).await
+    }
 }
@@ -0,0 +1,133 @@
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::AppDigestStats;
+use crate::repositories::AppDigestRepository;
+
+/// Weeks of failed sign-ins averaged into the spike baseline
+const BASELINE_WEEKS: i64 = 4;
+
+/// Failed sign-ins in a week below which nothing is called a spike
+const SPIKE_MIN_FAILURES: i64 = 20;
+
+/// How many times the baseline a week has to reach to be a spike
+const SPIKE_FACTOR: f64 = 3.0;
+
+/// API keys expiring within this many days of the digest are listed
+const API_KEY_EXPIRY_WINDOW_DAYS: i64 = 14;
+
+/// The last full week (Monday 00:00 to Monday 00:00 UTC) before `now`
+pub fn digest_period(now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let today = now.date_naive();
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let end = Utc.from_utc_datetime(&monday.and_hms_opt(0, 0, 0).unwrap_or_default());
+    (end - Duration::weeks(1), end)
+}
+
+/// Whether a week's failed sign-ins are unusually high compared to the baseline
+///
+/// A spike needs both a minimum number of failures, so a quiet app isn't
+/// alarmed by a handful of typos, and a multiple of the weekly average.
+pub fn is_failure_spike(failures: i64, baseline: f64) -> bool {
+    failures >= SPIKE_MIN_FAILURES && failures as f64 >= baseline * SPIKE_FACTOR
+}
+
+/// Weekly digests for app owners
+///
+/// The digest worker queues one digest per subscribed app and week, with
+/// the figures computed once, then sends the queue.
+pub struct AppDigestService {
+    repo: AppDigestRepository,
+}
+
+impl AppDigestService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self {
+            repo: AppDigestRepository::new(pool),
+        }
+    }
+
+    /// Figures of an app for the week `[from, to)`
+    pub async fn build_stats(
+        &self,
+        app_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<AppDigestStats, AppError> {
+        let failed_sign_ins = self.repo.count_failed_sign_ins(app_id, from, to).await?;
+        let baseline_total = self
+            .repo
+            .count_failed_sign_ins(app_id, from - Duration::weeks(BASELINE_WEEKS), from)
+            .await?;
+        let failed_sign_ins_baseline = baseline_total as f64 / BASELINE_WEEKS as f64;
+
+        Ok(AppDigestStats {
+            new_users: self.repo.count_new_users(app_id, from, to).await?,
+            bans: self.repo.count_bans(app_id, from, to).await?,
+            failed_webhook_deliveries: self.repo.count_failed_webhook_deliveries(app_id, from, to).await?,
+            expiring_api_keys: self
+                .repo
+                .find_expiring_api_keys(app_id, Utc::now() + Duration::days(API_KEY_EXPIRY_WINDOW_DAYS))
+                .await?,
+            failed_sign_ins,
+            failed_sign_ins_baseline,
+            failed_sign_in_spike: is_failure_spike(failed_sign_ins, failed_sign_ins_baseline),
+        })
+    }
+
+    /// Queue the last full week's digest of up to `limit` subscribed apps
+    ///
+    /// Returns how many digests were queued.
+    pub async fn queue_due(&self, now: DateTime<Utc>, limit: i64) -> Result<usize, AppError> {
+        let (from, to) = digest_period(now);
+
+        let mut queued = 0;
+        for (app_id, owner_id) in self.repo.find_due(from, limit).await? {
+            let stats = self.build_stats(app_id, from, to).await?;
+            if self.repo.enqueue(app_id, owner_id, from, to, &stats).await? {
+                queued += 1;
+            }
+        }
+
+        Ok(queued)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_period() {
+        // Wednesday
+        let now = Utc.with_ymd_and_hms(2025, 3, 12, 15, 30, 0).unwrap();
+        let (from, to) = digest_period(now);
+        assert_eq!(from, Utc.with_ymd_and_hms(2025, 3, 3, 0, 0, 0).unwrap());
+        assert_eq!(to, Utc.with_ymd_and_hms(2025, 3, 10, 0, 0, 0).unwrap());
+
+        // Monday morning already covers the week that just ended
+        let now = Utc.with_ymd_and_hms(2025, 3, 10, 0, 5, 0).unwrap();
+        assert_eq!(digest_period(now).1, Utc.with_ymd_and_hms(2025, 3, 10, 0, 0, 0).unwrap());
+
+        // Sunday still reports the week before
+        let now = Utc.with_ymd_and_hms(2025, 3, 9, 23, 59, 0).unwrap();
+        assert_eq!(digest_period(now).1, Utc.with_ymd_and_hms(2025, 3, 3, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_is_failure_spike() {
+        // Too few failures to matter
+        assert!(!is_failure_spike(10, 0.0));
+
+        // Many failures with no history
+        assert!(is_failure_spike(25, 0.0));
+
+        // Within the usual range
+        assert!(!is_failure_spike(100, 40.0));
+
+        // Three times the usual
+        assert!(is_failure_spike(120, 40.0));
+    }
+}
@@ -2,7 +2,7 @@ use sqlx::MySqlPool;
 use uuid::Uuid;
 
 use crate::error::AuthError;
-use crate::models::{AuditAction, AuditLog};
+use crate::models::{AdminApproval, AuditAction, AuditLog};
 use crate::repositories::AuditLogRepository;
 
 /// Service for audit logging
@@ -91,6 +91,28 @@ impl AuditService {
             .await
     }
 
+    /// Log an admin action on an app
+    pub async fn log_app_event(
+        &self,
+        actor_id: Uuid,
+        action: AuditAction,
+        app_id: Uuid,
+        details: Option<serde_json::Value>,
+    ) -> Result<AuditLog, AuthError> {
+        self.repo
+            .create(
+                Some(actor_id),
+                action,
+                "app",
+                Some(app_id),
+                None,
+                None,
+                details,
+                "success",
+            )
+            .await
+    }
+
     /// Log an MFA event
     pub async fn log_mfa_event(
         &self,
@@ -140,6 +162,78 @@ impl AuditService {
             .await
     }
 
+    /// Log an admin email broadcast event
+    pub async fn log_broadcast_event(
+        &self,
+        actor_id: Uuid,
+        action: AuditAction,
+        broadcast_id: Uuid,
+        details: Option<serde_json::Value>,
+    ) -> Result<AuditLog, AuthError> {
+        self.repo
+            .create(
+                Some(actor_id),
+                action,
+                "email_broadcast",
+                Some(broadcast_id),
+                None,
+                None,
+                details,
+                "success",
+            )
+            .await
+    }
+
+    /// Log an admin letting email to a bounced address be sent again
+    pub async fn log_email_bounce_cleared(
+        &self,
+        actor_id: Uuid,
+        email: &str,
+    ) -> Result<AuditLog, AuthError> {
+        self.repo
+            .create(
+                Some(actor_id),
+                AuditAction::EmailBounceCleared,
+                "email_bounce",
+                None,
+                None,
+                None,
+                Some(serde_json::json!({ "email": email })),
+                "success",
+            )
+            .await
+    }
+
+    /// Log a request, approval or rejection of an admin action held for approval
+    ///
+    /// A failed approval is an approved action that could not be executed.
+    pub async fn log_admin_approval_event(
+        &self,
+        actor_id: Uuid,
+        action: AuditAction,
+        approval: &AdminApproval,
+        success: bool,
+    ) -> Result<AuditLog, AuthError> {
+        self.repo
+            .create(
+                Some(actor_id),
+                action,
+                "admin_approval",
+                Some(approval.id),
+                None,
+                None,
+                Some(serde_json::json!({
+                    "action": approval.action,
+                    "target_id": approval.target_id,
+                    "params": approval.params,
+                    "requested_by": approval.requested_by,
+                    "error": approval.error,
+                })),
+                if success { "success" } else { "failure" },
+            )
+            .await
+    }
+
     /// Get audit logs for a user
     pub async fn get_user_logs(
         &self,
@@ -150,15 +244,31 @@ impl AuditService {
         self.repo.list_by_user(user_id, page, limit).await
     }
 
+    /// Count audit logs for a user
+    pub async fn count_user_logs(&self, user_id: Uuid) -> Result<u64, AuthError> {
+        self.repo.count_by_user(user_id).await
+    }
+
+    /// Count audit logs matching the filters (admin)
+    pub async fn count_all_logs(
+        &self,
+        action: Option<&str>,
+        resource_type: Option<&str>,
+        correlation_id: Option<&str>,
+    ) -> Result<u64, AuthError> {
+        self.repo.count_all(action, resource_type, correlation_id).await
+    }
+
     /// Get all audit logs with filters (admin)
     pub async fn get_all_logs(
         &self,
         action: Option<&str>,
         resource_type: Option<&str>,
+        correlation_id: Option<&str>,
         page: u32,
         limit: u32,
     ) -> Result<Vec<AuditLog>, AuthError> {
-        self.repo.list_all(action, resource_type, page, limit).await
+        self.repo.list_all(action, resource_type, correlation_id, page, limit).await
     }
 
     /// Cleanup old audit logs
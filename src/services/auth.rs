@@ -1,21 +1,39 @@
 use std::collections::HashMap;
 
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use sqlx::MySqlPool;
 use uuid::Uuid;
 
 use crate::error::AuthError;
-use crate::models::User;
-use crate::repositories::{MfaRepository, UserAppRepository, UserRepository};
+use crate::models::{App, PushMfaChallenge, SsoSession, TrustedDevice, User, UserSession};
+use crate::repositories::{
+    AppRepository, DeviceRepository, MagicLinkRepository, MfaRepository, SsoSessionRepository,
+    UserAppRepository, UserRepository,
+};
+use crate::repositories::sso_session::NewSsoSession;
 use crate::services::{
     AccountLockoutService, AuditService, LockoutConfig, MfaService, RateLimitConfig,
     RateLimiterService, SessionService, DeviceInfo, IpRuleService, IpAccessResult,
-    WebhookService,
+    TokenRevocationService, WebhookService, PushMfaService, SessionPolicy, SmsCodeSent,
+    TokenLineageService, EmailCodeSent, EmailConfig, EmailService, MockEmailService, TrustedDeviceService,
+    LoginAnomalyService,
 };
+use crate::services::mfa::EMAIL_CODE_EXPIRY_SECONDS;
+use crate::services::session::DEVICE_SESSION_EXPIRY_DAYS;
+use crate::services::token_lineage::REVOKED_ROTATED;
 use crate::models::{AuditAction, WebhookEvent};
-use crate::utils::email::validate_email;
-use crate::utils::jwt::{AppClaims, JwtManager, TokenPair};
+use crate::utils::access_window::{self, AccessWindow};
+use crate::utils::acr::{self, AcrLevel, SignInMethod};
+use crate::utils::client_fingerprint::{ClientFingerprint, FingerprintMode, FingerprintPolicy};
+use crate::utils::email::{canonicalize_email, validate_email};
+use crate::utils::jwt::{
+    AppClaims, Claims, JwtManager, SessionClaims, TokenPair, ACR_MFA, ACR_PASSWORD,
+};
 use crate::utils::password::{hash_password, hash_token, verify_password};
+use crate::utils::registration_fields::RegistrationSchema;
+use crate::utils::request_id::spawn_in_request;
+use crate::utils::secret::{generate_oauth_token, hash_oauth_token};
+use crate::utils::travel::TravelPolicy;
 
 /// Minimum password length requirement
 const MIN_PASSWORD_LENGTH: usize = 8;
@@ -29,6 +47,33 @@ const PASSWORD_RESET_TOKEN_EXPIRY_HOURS: i64 = 1;
 /// MFA token expiry in minutes
 const MFA_TOKEN_EXPIRY_MINUTES: i64 = 5;
 
+/// First factor of a sign-in: the account password
+const FIRST_FACTOR_PASSWORD: &str = "password";
+
+/// First factor of a sign-in: a code emailed to the account address
+const FIRST_FACTOR_EMAIL_CODE: &str = "email_code";
+
+/// First factor of a sign-in: a link emailed to the account address
+const FIRST_FACTOR_EMAIL_LINK: &str = "email_link";
+
+/// Magic link expiry in seconds
+pub const MAGIC_LINK_EXPIRY_SECONDS: i64 = 900;
+
+/// Whether the first factor was something emailed to the account address
+fn is_email_first_factor(first_factor: &str) -> bool {
+    first_factor == FIRST_FACTOR_EMAIL_CODE || first_factor == FIRST_FACTOR_EMAIL_LINK
+}
+
+/// Device details recorded on a session opened from a request
+fn device_info(context: &LoginContext) -> DeviceInfo {
+    DeviceInfo::new(
+        context.user_agent.as_ref().map(|ua| DeviceInfo::parse_device_name(ua)),
+        context.user_agent.as_ref().map(|ua| DeviceInfo::parse_device_type(ua)),
+        context.ip_address.clone(),
+        context.user_agent.clone(),
+    )
+}
+
 /// Login context containing request metadata
 #[derive(Debug, Clone, Default)]
 pub struct LoginContext {
@@ -36,6 +81,15 @@ pub struct LoginContext {
     pub user_agent: Option<String>,
 }
 
+/// How a user signed in, as carried by the tokens of a new session
+#[derive(Debug, Clone)]
+struct SignIn<'a> {
+    acr: &'a str,
+    amr: Option<Vec<String>>,
+    /// Unix seconds
+    auth_time: i64,
+}
+
 /// Result of login attempt - either tokens or MFA required
 #[derive(Debug, Clone)]
 pub enum LoginResult {
@@ -52,11 +106,52 @@ pub enum LoginResult {
     },
 }
 
+/// Outcome of a registration that must not reveal whether the email was taken
+#[derive(Debug, Clone)]
+pub enum RegistrationOutcome {
+    /// A new account was created
+    Created,
+    /// The email, or a look-alike of it, already belongs to this account
+    AlreadyRegistered(Option<User>),
+}
+
+/// Second factor presented to complete an MFA login
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MfaFactor {
+    /// Code from an authenticator app
+    Totp,
+    /// One of the user's backup codes
+    BackupCode,
+    /// Code sent by SMS for this login
+    Sms,
+    /// Code emailed for this login
+    Email,
+    /// Push challenge approved on an enrolled device
+    Push(Uuid),
+}
+
+impl MfaFactor {
+    /// Method name used in audit logs
+    pub fn method(&self) -> &'static str {
+        match self {
+            MfaFactor::Totp => "totp",
+            MfaFactor::BackupCode => "backup",
+            MfaFactor::Sms => "sms",
+            MfaFactor::Email => "email",
+            MfaFactor::Push(_) => "push",
+        }
+    }
+}
+
 /// MFA token data stored temporarily
 #[derive(Debug, Clone)]
 pub struct MfaTokenData {
     pub user_id: Uuid,
     pub app_id: Option<Uuid>,
+    /// App code the tokens were requested for
+    pub app_scope: Option<String>,
+    /// How the user proved who they are before the MFA step
+    pub first_factor: String,
     pub expires_at: chrono::DateTime<Utc>,
 }
 
@@ -75,6 +170,26 @@ pub struct AuthService {
     session_service: SessionService,
     ip_rule_service: IpRuleService,
     webhook_service: WebhookService,
+    device_repo: DeviceRepository,
+    token_revocation_service: TokenRevocationService,
+    token_lineage_service: TokenLineageService,
+    push_mfa_service: PushMfaService,
+    app_repo: AppRepository,
+    sso_repo: SsoSessionRepository,
+    magic_link_repo: MagicLinkRepository,
+    trusted_device_service: TrustedDeviceService,
+    login_anomaly_service: LoginAnomalyService,
+    session_defaults: SessionPolicy,
+    require_verified_email: bool,
+    fingerprint_policy: FingerprintPolicy,
+    enumeration_safe: bool,
+    registration_fields: RegistrationSchema,
+    /// Days a device trusted after MFA skips it (0 = disabled)
+    trusted_device_days: i64,
+    /// Trusted device cookie presented with the sign-in
+    trusted_device: Option<String>,
+    /// Trust the device once it completes the MFA challenge
+    remember_device: bool,
 }
 
 impl AuthService {
@@ -90,6 +205,15 @@ impl AuthService {
         let mfa_repo = MfaRepository::new(pool.clone());
         let ip_rule_service = IpRuleService::new(pool.clone());
         let webhook_service = WebhookService::new(pool.clone());
+        let device_repo = DeviceRepository::new(pool.clone());
+        let token_revocation_service = TokenRevocationService::new(pool.clone());
+        let token_lineage_service = TokenLineageService::new(pool.clone());
+        let push_mfa_service = PushMfaService::new(pool.clone());
+        let app_repo = AppRepository::new(pool.clone());
+        let sso_repo = SsoSessionRepository::new(pool.clone());
+        let magic_link_repo = MagicLinkRepository::new(pool.clone());
+        let trusted_device_service = TrustedDeviceService::new(pool.clone());
+        let login_anomaly_service = LoginAnomalyService::new(pool.clone(), TravelPolicy::default());
         Self {
             pool,
             user_repo,
@@ -103,26 +227,148 @@ impl AuthService {
             session_service,
             ip_rule_service,
             webhook_service,
+            device_repo,
+            token_revocation_service,
+            token_lineage_service,
+            push_mfa_service,
+            app_repo,
+            sso_repo,
+            magic_link_repo,
+            trusted_device_service,
+            login_anomaly_service,
+            session_defaults: SessionPolicy::default(),
+            require_verified_email: false,
+            fingerprint_policy: FingerprintPolicy::default(),
+            enumeration_safe: false,
+            registration_fields: RegistrationSchema::default(),
+            trusted_device_days: 0,
+            trusted_device: None,
+            remember_device: false,
         }
     }
 
+    /// Use the server-wide session policy defaults (apps may override them)
+    pub fn with_session_defaults(mut self, session_defaults: SessionPolicy) -> Self {
+        self.session_defaults = session_defaults;
+        self
+    }
+
+    /// Reject password logins until the user has verified their email
+    pub fn with_verified_email_required(mut self, required: bool) -> Self {
+        self.require_verified_email = required;
+        self
+    }
+
+    /// Spend the same work on unknown emails as on registered ones, so
+    /// response times do not reveal which emails exist
+    pub fn with_enumeration_safe(mut self, enumeration_safe: bool) -> Self {
+        self.enumeration_safe = enumeration_safe;
+        self
+    }
+
+    /// Accept these extra fields at registration
+    pub fn with_registration_fields(mut self, registration_fields: RegistrationSchema) -> Self {
+        self.registration_fields = registration_fields;
+        self
+    }
+
+    /// Let devices that completed MFA skip it for this many days (0 = disabled)
+    pub fn with_trusted_device_days(mut self, days: i64) -> Self {
+        self.trusted_device_days = days;
+        self
+    }
+
+    /// Check fresh sign-ins for impossible travel with this policy
+    pub fn with_travel_policy(mut self, policy: TravelPolicy) -> Self {
+        self.login_anomaly_service = LoginAnomalyService::new(self.pool.clone(), policy);
+        self
+    }
+
+    /// Trusted device cookie sent with the sign-in, if any
+    pub fn with_trusted_device(mut self, cookie_value: Option<String>) -> Self {
+        self.trusted_device = cookie_value;
+        self
+    }
+
+    /// Trust the device once it completes the MFA challenge
+    pub fn with_remember_device(mut self, remember: bool) -> Self {
+        self.remember_device = remember;
+        self
+    }
+
+    /// Compare refreshing clients with the one that logged in
+    pub fn with_fingerprint_policy(mut self, fingerprint_policy: FingerprintPolicy) -> Self {
+        self.fingerprint_policy = fingerprint_policy;
+        self
+    }
+
+    /// Record only this fraction of access tokens in the token lineage
+    pub fn with_token_audit_sample_rate(mut self, rate: f64) -> Self {
+        self.token_lineage_service = self.token_lineage_service.with_access_sample_rate(rate);
+        self
+    }
+
     /// Register a new user with email and password
-    pub async fn register(&self, email: &str, password: &str) -> Result<User, AuthError> {
+    pub async fn register(
+        &self,
+        email: &str,
+        password: &str,
+        fields: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<User, AuthError> {
         // Validate email format (Requirement 1.3)
         validate_email(email)?;
 
         // Validate password strength (Requirement 1.4)
         self.validate_password(password)?;
 
+        // Extra fields must match the configured schema; they become the user's metadata
+        let metadata = self
+            .registration_fields
+            .validate(fields)
+            .map_err(AuthError::InvalidRequest)?;
+
         // Hash password using argon2 (Requirement 1.1, 1.5)
         let password_hash = hash_password(password)?;
 
+        // Refuse look-alike (homoglyph) variants of existing accounts with the
+        // same error as an exact duplicate, so neither reveals the other account
+        if self.user_repo.find_confusable(email).await?.is_some() {
+            return Err(AuthError::EmailAlreadyExists);
+        }
+
         // Create user (Requirement 1.2 - uniqueness enforced by database)
-        let user = self.user_repo.create_user(email, &password_hash).await?;
+        let user = self
+            .user_repo
+            .create_user(email, &password_hash, metadata.as_ref())
+            .await?;
 
         Ok(user)
     }
 
+    /// Register a new user without failing when the email is taken
+    ///
+    /// Validation errors are still returned; an existing account (exact or
+    /// look-alike email) is reported as `AlreadyRegistered` so the caller can
+    /// answer exactly as for a new account and notify the owner instead.
+    pub async fn register_enumeration_safe(
+        &self,
+        email: &str,
+        password: &str,
+        fields: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<RegistrationOutcome, AuthError> {
+        match self.register(email, password, fields).await {
+            Ok(_) => Ok(RegistrationOutcome::Created),
+            Err(AuthError::EmailAlreadyExists) => {
+                let owner = match self.user_repo.find_by_email(email).await? {
+                    Some(user) => Some(user),
+                    None => self.user_repo.find_confusable(email).await?,
+                };
+                Ok(RegistrationOutcome::AlreadyRegistered(owner))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
     /// Login a user with email and password
     /// If app_id is provided, checks if user is banned from that app (Requirement 3.4)
     /// Now includes rate limiting, account lockout protection, and MFA support
@@ -131,12 +377,15 @@ impl AuthService {
         email: &str,
         password: &str,
         app_id: Option<Uuid>,
+        app_scope: Option<&str>,
         context: LoginContext,
     ) -> Result<LoginResult, AuthError> {
-        // Create rate limit identifier from IP + email
+        // Create rate limit identifier from IP + canonical email, so case and
+        // alias variants of the same address share one limit
+        let canonical_email = canonicalize_email(email);
         let identifier = RateLimiterService::create_identifier(
             context.ip_address.as_deref(),
-            Some(email),
+            Some(&canonical_email),
         );
 
         // Check rate limit before processing login
@@ -164,11 +413,7 @@ impl AuthService {
                 )
                 .await;
 
-            return Err(AuthError::RateLimitExceeded {
-                retry_after_seconds: rate_result.retry_after_seconds.unwrap_or(60),
-                limit: rate_result.max_requests,
-                remaining: rate_result.remaining,
-            });
+            return Err(AuthError::RateLimitExceeded(rate_result.throttle("login")));
         }
 
         // Find user by email (Requirement 2.2)
@@ -185,7 +430,8 @@ impl AuthService {
                         context.user_agent.as_deref(),
                         Some(serde_json::json!({
                             "reason": "user_not_found",
-                            "email": email
+                            "email": email,
+                            "app_id": app_id
                         })),
                         false,
                     )
@@ -198,8 +444,6 @@ impl AuthService {
         if self.lockout_service.is_locked(user.id).await? {
             let lockout_info = self.lockout_service.get_lockout_info(user.id).await?;
             if let Some(locked_until) = lockout_info.locked_until {
-                let remaining_seconds = (locked_until - Utc::now()).num_seconds().max(0);
-
                 // Log locked account access attempt
                 let _ = self
                     .audit_service
@@ -218,7 +462,7 @@ impl AuthService {
 
                 return Err(AuthError::AccountLocked {
                     locked_until,
-                    remaining_seconds,
+                    throttle: self.lockout_service.throttle(locked_until),
                 });
             }
         }
@@ -243,7 +487,8 @@ impl AuthService {
                     Some(serde_json::json!({
                         "reason": "invalid_password",
                         "failed_attempts": lockout_info.failed_attempts,
-                        "remaining_attempts": lockout_info.remaining_attempts
+                        "remaining_attempts": lockout_info.remaining_attempts,
+                        "app_id": app_id
                     })),
                     false,
                 )
@@ -252,8 +497,6 @@ impl AuthService {
             // Check if account just got locked
             if lockout_info.is_locked {
                 if let Some(locked_until) = lockout_info.locked_until {
-                    let remaining_seconds = (locked_until - Utc::now()).num_seconds().max(0);
-
                     // Log account locked event
                     let _ = self
                         .audit_service
@@ -272,7 +515,7 @@ impl AuthService {
 
                     return Err(AuthError::AccountLocked {
                         locked_until,
-                        remaining_seconds,
+                        throttle: self.lockout_service.throttle(locked_until),
                     });
                 }
             }
@@ -280,6 +523,27 @@ impl AuthService {
             return Err(AuthError::InvalidCredentials);
         }
 
+        let result = self
+            .finish_sign_in(user, app_id, app_scope, FIRST_FACTOR_PASSWORD, &context)
+            .await?;
+        let _ = self.rate_limiter.reset(&identifier, "login").await;
+        Ok(result)
+    }
+
+    /// Checks and second factor of a sign-in whose first factor (password or
+    /// emailed code) was verified
+    ///
+    /// Refuses inactive, unverified, IP-blocked and banned users and
+    /// non-members of the requested app, asks for a second factor when the
+    /// user enabled MFA, and otherwise completes the login.
+    async fn finish_sign_in(
+        &self,
+        user: User,
+        app_id: Option<Uuid>,
+        app_scope: Option<&str>,
+        first_factor: &str,
+        context: &LoginContext,
+    ) -> Result<LoginResult, AuthError> {
         // Check if user is active (Requirement 2.3)
         if !user.is_active {
             let _ = self
@@ -296,6 +560,27 @@ impl AuthService {
             return Err(AuthError::UserInactive);
         }
 
+        // Dormant accounts may have to verify their address again whatever the server-wide setting
+        if (self.require_verified_email || user.reverify_required) && !user.email_verified {
+            let reason = if user.reverify_required {
+                "reverification_required"
+            } else {
+                "email_not_verified"
+            };
+            let _ = self
+                .audit_service
+                .log_auth_event(
+                    Some(user.id),
+                    AuditAction::LoginFailed,
+                    context.ip_address.as_deref(),
+                    context.user_agent.as_deref(),
+                    Some(serde_json::json!({ "reason": reason })),
+                    false,
+                )
+                .await;
+            return Err(AuthError::EmailNotVerified);
+        }
+
         // Check if user is banned from the specified app (Requirement 3.4)
         if let Some(app_id) = app_id {
             // Check IP rules for this app first
@@ -319,9 +604,7 @@ impl AuthService {
                             false,
                         )
                         .await;
-                    return Err(AuthError::UserBanned {
-                        reason: Some(format!("IP address {} is blocked for this app", ip)),
-                    });
+                    return Err(AuthError::IpBlocked);
                 }
             }
 
@@ -353,22 +636,61 @@ impl AuthService {
             }
         }
 
-        // Password verified - reset failed attempts
+        // Tokens scoped to one app are only issued to its members; checked
+        // before MFA so the user is not asked for a second factor in vain
+        if let Some(app_code) = app_scope {
+            if let Err(e) = self.check_app_membership(user.id, app_code).await {
+                let _ = self
+                    .audit_service
+                    .log_auth_event(
+                        Some(user.id),
+                        AuditAction::LoginFailed,
+                        context.ip_address.as_deref(),
+                        context.user_agent.as_deref(),
+                        Some(serde_json::json!({
+                            "reason": "not_app_member",
+                            "app": app_code
+                        })),
+                        false,
+                    )
+                    .await;
+                return Err(e);
+            }
+        }
+
+        // First factor verified - reset failed attempts
         self.lockout_service.record_successful_login(user.id).await?;
-        let _ = self.rate_limiter.reset(&identifier, "login").await;
+
+        // An app that only accepts passkeys can't be reached with a password
+        // or an emailed code, even after a second factor
+        self.check_app_acr(app_id, app_scope, ACR_MFA).await?;
+
+        // A device trusted after an earlier MFA challenge skips it
+        let trusted_device = if user.mfa_enabled {
+            self.trusted_device_for(user.id, app_id, app_scope).await?
+        } else {
+            None
+        };
 
         // Check if MFA is enabled for this user
-        if user.mfa_enabled {
+        if user.mfa_enabled && trusted_device.is_none() {
             let mfa_methods = self.mfa_repo.list_methods_by_user(user.id).await?;
-            let verified_methods: Vec<String> = mfa_methods
+            // A code or link emailed as the first factor can't also be the second
+            let mut verified_methods: Vec<String> = mfa_methods
                 .iter()
                 .filter(|m| m.is_verified)
+                .filter(|m| !(is_email_first_factor(first_factor) && m.method_type == "email"))
                 .map(|m| m.method_type.clone())
                 .collect();
 
+            // Registered devices can approve the login instead of a code
+            if !verified_methods.is_empty() && self.push_mfa_service.is_available(user.id).await? {
+                verified_methods.push("push".to_string());
+            }
+
             if !verified_methods.is_empty() {
                 // Generate MFA token
-                let mfa_token = self.create_mfa_token(user.id, app_id).await?;
+                let mfa_token = self.create_mfa_token(user.id, app_id, app_scope, first_factor).await?;
 
                 // Log MFA required
                 let _ = self
@@ -394,193 +716,720 @@ impl AuthService {
             }
         }
 
+        if let Some(device) = &trusted_device {
+            let _ = self
+                .audit_service
+                .log_mfa_event(
+                    user.id,
+                    AuditAction::MfaVerified,
+                    context.ip_address.as_deref(),
+                    context.user_agent.as_deref(),
+                    Some(serde_json::json!({
+                        "method": "trusted_device",
+                        "trusted_device_id": device.id
+                    })),
+                    true,
+                )
+                .await;
+        }
+
         // No MFA required - complete login
-        let (tokens, session_id) = self.complete_login(user.id, app_id, &context).await?;
+        let method = if is_email_first_factor(first_factor) {
+            SignInMethod::EmailCode
+        } else {
+            SignInMethod::Password
+        };
+        let (tokens, session_id) = self
+            .complete_login(user.id, app_id, app_scope, method, context)
+            .await?;
         Ok(LoginResult::Success { tokens, session_id })
     }
 
-    /// Complete login after password verification (and MFA if required)
-    /// Returns (TokenPair, session_id)
-    async fn complete_login(
+    /// Email a passwordless sign-in code for an app that accepts them
+    ///
+    /// Answers the same whether or not the address belongs to an active
+    /// account, so it can't be used to find out who is registered. Returns
+    /// how long the code is valid (in seconds).
+    pub async fn send_email_login_code(
         &self,
-        user_id: Uuid,
-        app_id: Option<Uuid>,
-        context: &LoginContext,
-    ) -> Result<(TokenPair, Uuid), AuthError> {
-        // Get user's apps, roles, and permissions for token payload
-        let apps = self.get_user_app_claims(user_id).await?;
-
-        // Generate token pair (Requirement 2.4, 2.5)
-        let token_pair = self.jwt_manager.create_token_pair(user_id, apps)?;
+        email: &str,
+        app_code: &str,
+        context: LoginContext,
+    ) -> Result<i64, AuthError> {
+        let app = self.email_login_app(app_code).await?;
 
-        // Create session with device info
-        let device_info = DeviceInfo::new(
-            context.user_agent.as_ref().map(|ua| DeviceInfo::parse_device_name(ua)),
-            context.user_agent.as_ref().map(|ua| DeviceInfo::parse_device_type(ua)),
-            context.ip_address.clone(),
-            context.user_agent.clone(),
+        let identifier = RateLimiterService::create_identifier(
+            context.ip_address.as_deref(),
+            Some(&canonicalize_email(email)),
         );
-
-        let session = self
-            .session_service
-            .create_session(user_id, &token_pair.refresh_token, Some(device_info))
-            .await?;
-
-        // Log successful login
-        let _ = self
-            .audit_service
-            .log_auth_event(
-                Some(user_id),
-                AuditAction::Login,
-                context.ip_address.as_deref(),
-                context.user_agent.as_deref(),
-                Some(serde_json::json!({ 
-                    "status": "completed",
-                    "session_id": session.id.to_string()
-                })),
-                true,
-            )
-            .await;
-
-        // Trigger webhook for login event (if app_id is provided)
-        if let Some(app_id) = app_id {
-            let webhook_service = self.webhook_service.clone();
-            let payload = serde_json::json!({
-                "event": "user.login",
-                "user_id": user_id.to_string(),
-                "app_id": app_id.to_string(),
-                "ip_address": context.ip_address,
-                "user_agent": context.user_agent,
-                "session_id": session.id.to_string(),
-                "timestamp": chrono::Utc::now().to_rfc3339()
-            });
-            tokio::spawn(async move {
-                let _ = webhook_service.trigger_event(app_id, WebhookEvent::UserLogin, payload).await;
-            });
+        let rate_result = self
+            .rate_limiter
+            .check_and_increment(&identifier, "email_login_send", &RateLimitConfig::email_code_send())
+            .await
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("{}", e)))?;
+        if !rate_result.allowed {
+            return Err(AuthError::RateLimitExceeded(rate_result.throttle("email_login_send")));
         }
 
-        Ok((token_pair, session.id))
-    }
-
-    /// Create a temporary MFA token for 2-step login
-    async fn create_mfa_token(&self, user_id: Uuid, app_id: Option<Uuid>) -> Result<String, AuthError> {
-        let token = Uuid::new_v4().to_string();
-        let token_hash = hash_token(&token)?;
-        let expires_at = Utc::now() + Duration::minutes(MFA_TOKEN_EXPIRY_MINUTES);
-        let id = Uuid::new_v4();
-
-        // Store MFA token in a temporary table (reusing password_reset_tokens structure)
-        // In production, you might want a dedicated mfa_tokens table
-        sqlx::query(
-            r#"
-            INSERT INTO mfa_pending_tokens (id, user_id, token_hash, app_id, expires_at)
-            VALUES (?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(id.to_string())
-        .bind(user_id.to_string())
-        .bind(&token_hash)
-        .bind(app_id.map(|id| id.to_string()))
-        .bind(expires_at)
-        .execute(&self.pool)
-        .await
-        .map_err(|e| AuthError::InternalError(e.into()))?;
-
-        Ok(token)
-    }
-
-    /// Verify MFA token and get associated data
-    async fn verify_mfa_token(&self, token: &str) -> Result<MfaTokenData, AuthError> {
-        let token_hash = hash_token(token)?;
-
-        let row = sqlx::query_as::<_, (String, Option<String>, chrono::DateTime<Utc>)>(
-            r#"
-            SELECT user_id, app_id, expires_at
-            FROM mfa_pending_tokens
-            WHERE token_hash = ? AND used = FALSE AND expires_at > NOW()
-            "#,
-        )
-        .bind(&token_hash)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(|e| AuthError::InternalError(e.into()))?
-        .ok_or(AuthError::InvalidToken)?;
-
-        let user_id = Uuid::parse_str(&row.0)
-            .map_err(|e| AuthError::InternalError(e.into()))?;
-        let app_id = row.1.and_then(|s| Uuid::parse_str(&s).ok());
-
-        Ok(MfaTokenData {
-            user_id,
-            app_id,
-            expires_at: row.2,
-        })
-    }
-
-    /// Mark MFA token as used
-    async fn consume_mfa_token(&self, token: &str) -> Result<(), AuthError> {
-        let token_hash = hash_token(token)?;
+        let Some(user) = self.user_repo.find_by_email(email).await?.filter(|u| u.is_active) else {
+            return Ok(EMAIL_CODE_EXPIRY_SECONDS);
+        };
 
-        sqlx::query(
-            r#"
-            UPDATE mfa_pending_tokens
-            SET used = TRUE
-            WHERE token_hash = ?
-            "#,
-        )
-        .bind(&token_hash)
-        .execute(&self.pool)
-        .await
-        .map_err(|e| AuthError::InternalError(e.into()))?;
+        // Failures (including the per-account send limit) are only logged,
+        // since reporting them would reveal that the account exists
+        match self.mfa_service.send_email_login_code(user.id, &user.email, app.id).await {
+            Ok(_) => {
+                let _ = self
+                    .audit_service
+                    .log_auth_event(
+                        Some(user.id),
+                        AuditAction::EmailCodeSent,
+                        context.ip_address.as_deref(),
+                        context.user_agent.as_deref(),
+                        Some(serde_json::json!({ "purpose": "login", "app_id": app.id })),
+                        true,
+                    )
+                    .await;
+            }
+            Err(e) => tracing::warn!("Failed to send email sign-in code to user {}: {:?}", user.id, e),
+        }
 
-        Ok(())
+        Ok(EMAIL_CODE_EXPIRY_SECONDS)
     }
 
-    /// Complete MFA login - verify code and return tokens
-    pub async fn complete_mfa_login(
+    /// Sign in to an app with a code sent by `send_email_login_code`
+    ///
+    /// The tokens are scoped to the app. Users who enabled MFA still complete
+    /// a second factor other than email codes.
+    pub async fn login_with_email_code(
         &self,
-        mfa_token: &str,
+        email: &str,
         code: &str,
-        is_backup_code: bool,
+        app_code: &str,
         context: LoginContext,
-    ) -> Result<TokenPair, AuthError> {
-        // Verify MFA token
-        let mfa_data = self.verify_mfa_token(mfa_token).await?;
+    ) -> Result<LoginResult, AuthError> {
+        let app = self.email_login_app(app_code).await?;
 
-        // Check rate limit for MFA verification
-        let identifier = format!("mfa:{}", mfa_data.user_id);
-        let rate_limit_config = RateLimitConfig::mfa_verify();
+        // Shares the password login limit, so codes can't be guessed faster than passwords
+        let identifier = RateLimiterService::create_identifier(
+            context.ip_address.as_deref(),
+            Some(&canonicalize_email(email)),
+        );
         let rate_result = self
             .rate_limiter
-            .check_and_increment(&identifier, "mfa_verify", &rate_limit_config)
+            .check_and_increment(&identifier, "login", &RateLimitConfig::login())
             .await
             .map_err(|e| AuthError::InternalError(anyhow::anyhow!("{}", e)))?;
-
         if !rate_result.allowed {
+            return Err(AuthError::RateLimitExceeded(rate_result.throttle("login")));
+        }
+
+        let Some(mut user) = self.user_repo.find_by_email(email).await? else {
             let _ = self
                 .audit_service
-                .log_mfa_event(
-                    mfa_data.user_id,
-                    AuditAction::MfaFailed,
+                .log_auth_event(
+                    None,
+                    AuditAction::LoginFailed,
+                    context.ip_address.as_deref(),
+                    context.user_agent.as_deref(),
+                    Some(serde_json::json!({
+                        "reason": "user_not_found",
+                        "email": email,
+                        "app_id": app.id
+                    })),
+                    false,
+                )
+                .await;
+            return Err(AuthError::InvalidCredentials);
+        };
+
+        if self.lockout_service.is_locked(user.id).await? {
+            let lockout_info = self.lockout_service.get_lockout_info(user.id).await?;
+            if let Some(locked_until) = lockout_info.locked_until {
+                return Err(AuthError::AccountLocked {
+                    locked_until,
+                    throttle: self.lockout_service.throttle(locked_until),
+                });
+            }
+        }
+
+        if !self.mfa_service.verify_email_login(user.id, app.id, code).await? {
+            let _ = self
+                .audit_service
+                .log_auth_event(
+                    Some(user.id),
+                    AuditAction::LoginFailed,
                     context.ip_address.as_deref(),
                     context.user_agent.as_deref(),
-                    Some(serde_json::json!({ "reason": "rate_limited" })),
+                    Some(serde_json::json!({
+                        "reason": "invalid_email_code",
+                        "app_id": app.id
+                    })),
                     false,
                 )
                 .await;
+            return Err(AuthError::InvalidCredentials);
+        }
+
+        // The code proves the address, also for accounts that had to verify it again
+        if !user.email_verified || user.reverify_required {
+            self.user_repo.set_email_verified(user.id, true).await?;
+            user.email_verified = true;
+            user.reverify_required = false;
+        }
+
+        let result = self
+            .finish_sign_in(user, Some(app.id), Some(&app.code), FIRST_FACTOR_EMAIL_CODE, &context)
+            .await?;
+        let _ = self.rate_limiter.reset(&identifier, "login").await;
+        Ok(result)
+    }
+
+    /// Email a single-use sign-in link for an app that accepts email sign-in
+    ///
+    /// Like `send_email_login_code`, answers the same whether or not the
+    /// address belongs to an active account. A new link replaces the user's
+    /// unused ones for the app. Returns how long the link is valid (in seconds).
+    pub async fn send_magic_link(
+        &self,
+        email: &str,
+        app_code: &str,
+        context: LoginContext,
+    ) -> Result<i64, AuthError> {
+        let app = self.email_login_app(app_code).await?;
+
+        let identifier = RateLimiterService::create_identifier(
+            context.ip_address.as_deref(),
+            Some(&canonicalize_email(email)),
+        );
+        let rate_result = self
+            .rate_limiter
+            .check_and_increment(&identifier, "magic_link_send", &RateLimitConfig::email_code_send())
+            .await
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("{}", e)))?;
+        if !rate_result.allowed {
+            return Err(AuthError::RateLimitExceeded(rate_result.throttle("magic_link_send")));
+        }
+
+        let Some(user) = self.user_repo.find_by_email(email).await?.filter(|u| u.is_active) else {
+            return Ok(MAGIC_LINK_EXPIRY_SECONDS);
+        };
+
+        // Failures are only logged, since reporting them would reveal that the account exists
+        match self.email_magic_link(&user, &app, &context).await {
+            Ok(()) => {
+                let _ = self
+                    .audit_service
+                    .log_auth_event(
+                        Some(user.id),
+                        AuditAction::MagicLinkSent,
+                        context.ip_address.as_deref(),
+                        context.user_agent.as_deref(),
+                        Some(serde_json::json!({ "app_id": app.id })),
+                        true,
+                    )
+                    .await;
+            }
+            Err(e) => tracing::warn!("Failed to send magic link to user {}: {:?}", user.id, e),
+        }
+
+        Ok(MAGIC_LINK_EXPIRY_SECONDS)
+    }
+
+    /// Store a new link for the user and email it
+    async fn email_magic_link(&self, user: &User, app: &App, context: &LoginContext) -> Result<(), AuthError> {
+        // Per-account cap, so one address can't be flooded from many IPs
+        let rate_result = self
+            .rate_limiter
+            .check_and_increment(
+                &format!("magic_link:{}", user.id),
+                "magic_link_send",
+                &RateLimitConfig::email_code_send(),
+            )
+            .await
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("{}", e)))?;
+        if !rate_result.allowed {
+            return Err(AuthError::RateLimitExceeded(rate_result.throttle("magic_link_send")));
+        }
+
+        let token = generate_oauth_token();
+        let expires_at = Utc::now() + Duration::seconds(MAGIC_LINK_EXPIRY_SECONDS);
+        self.magic_link_repo
+            .create(
+                user.id,
+                app.id,
+                &hash_oauth_token(&token),
+                context.ip_address.as_deref(),
+                context.user_agent.as_deref(),
+                expires_at,
+            )
+            .await?;
+
+        let minutes = MAGIC_LINK_EXPIRY_SECONDS / 60;
+        match EmailConfig::from_env().map(EmailService::new) {
+            Some(Ok(mailer)) => {
+                mailer
+                    .with_bounce_list(self.pool.clone())
+                    .send_magic_link(&user.email, &token, minutes)
+                    .await
+            }
+            Some(Err(e)) => Err(e),
+            None => MockEmailService::new().send_magic_link(&user.email, &token, minutes).await,
+        }
+    }
+
+    /// Sign in with a link sent by `send_magic_link`
+    ///
+    /// The link works once and only until it expires. The tokens are scoped
+    /// to the app the link was requested for; users who enabled MFA still
+    /// complete a second factor other than email codes.
+    pub async fn login_with_magic_link(&self, token: &str, context: LoginContext) -> Result<LoginResult, AuthError> {
+        let link = match self.magic_link_repo.find_by_hash(&hash_oauth_token(token)).await? {
+            Some(link) if !link.is_used() && !link.is_expired() => link,
+            found => {
+                let reason = match &found {
+                    None => "invalid_magic_link",
+                    Some(link) if link.is_used() => "magic_link_used",
+                    Some(_) => "magic_link_expired",
+                };
+                let _ = self
+                    .audit_service
+                    .log_auth_event(
+                        found.as_ref().map(|link| link.user_id),
+                        AuditAction::LoginFailed,
+                        context.ip_address.as_deref(),
+                        context.user_agent.as_deref(),
+                        Some(serde_json::json!({ "reason": reason })),
+                        false,
+                    )
+                    .await;
+                return Err(match found {
+                    Some(link) if !link.is_used() => AuthError::TokenExpired,
+                    _ => AuthError::InvalidToken,
+                });
+            }
+        };
+
+        // The app may have turned email sign-in off since the link was sent
+        let app = self
+            .app_repo
+            .find_by_id(link.app_id)
+            .await
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("{}", e)))?
+            .ok_or(AuthError::InvalidToken)?;
+        if !app.email_login_enabled {
+            return Err(AuthError::EmailLoginNotAllowed);
+        }
+
+        let mut user = self
+            .user_repo
+            .find_by_id(link.user_id)
+            .await?
+            .ok_or(AuthError::InvalidToken)?;
+
+        if self.lockout_service.is_locked(user.id).await? {
+            let lockout_info = self.lockout_service.get_lockout_info(user.id).await?;
+            if let Some(locked_until) = lockout_info.locked_until {
+                return Err(AuthError::AccountLocked {
+                    locked_until,
+                    throttle: self.lockout_service.throttle(locked_until),
+                });
+            }
+        }
+
+        // Two clicks racing on the same link: only one signs in
+        if !self.magic_link_repo.mark_used(link.id).await? {
+            return Err(AuthError::InvalidToken);
+        }
 
-            return Err(AuthError::RateLimitExceeded {
-                retry_after_seconds: rate_result.retry_after_seconds.unwrap_or(300),
-                limit: rate_result.max_requests,
-                remaining: rate_result.remaining,
+        let _ = self
+            .audit_service
+            .log_auth_event(
+                Some(user.id),
+                AuditAction::MagicLinkUsed,
+                context.ip_address.as_deref(),
+                context.user_agent.as_deref(),
+                Some(serde_json::json!({
+                    "app_id": app.id,
+                    "requested_from": link.ip_address
+                })),
+                true,
+            )
+            .await;
+
+        // The link proves the address, also for accounts that had to verify it again
+        if !user.email_verified || user.reverify_required {
+            self.user_repo.set_email_verified(user.id, true).await?;
+            user.email_verified = true;
+            user.reverify_required = false;
+        }
+
+        self.finish_sign_in(user, Some(app.id), Some(&app.code), FIRST_FACTOR_EMAIL_LINK, &context)
+            .await
+    }
+
+    /// App accepting passwordless sign-in with emailed codes or links
+    async fn email_login_app(&self, app_code: &str) -> Result<App, AuthError> {
+        let app = self
+            .app_repo
+            .find_by_code(app_code)
+            .await
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("{}", e)))?
+            .ok_or_else(|| AuthError::InvalidRequest(format!("Unknown app '{}'", app_code)))?;
+        if !app.email_login_enabled {
+            return Err(AuthError::EmailLoginNotAllowed);
+        }
+
+        Ok(app)
+    }
+
+    /// Complete login after password verification (and MFA if required),
+    /// or after another already-authenticated device approved the login
+    /// Returns (TokenPair, session_id)
+    ///
+    /// With `app_scope` the tokens only carry the claims of that app. The
+    /// tokens (and ID tokens issued from them) report how the user signed in
+    /// as `acr` and `amr`.
+    pub async fn complete_login(
+        &self,
+        user_id: Uuid,
+        app_id: Option<Uuid>,
+        app_scope: Option<&str>,
+        method: SignInMethod,
+        context: &LoginContext,
+    ) -> Result<(TokenPair, Uuid), AuthError> {
+        // Continued sessions keep their sign-in, so only fresh ones are located
+        self.login_anomaly_service.check_sign_in(user_id, context).await?;
+
+        let sign_in = SignIn {
+            acr: method.acr().as_str(),
+            amr: Some(method.amr()),
+            auth_time: Utc::now().timestamp(),
+        };
+        self.complete_login_at(user_id, app_id, app_scope, sign_in, context).await
+    }
+
+    /// Complete a login for a sign-in that may have happened earlier
+    ///
+    /// Used when a session is continued from an earlier sign-in, so `max_age`
+    /// checks still see the original sign-in time.
+    async fn complete_login_at(
+        &self,
+        user_id: Uuid,
+        app_id: Option<Uuid>,
+        app_scope: Option<&str>,
+        sign_in: SignIn<'_>,
+        context: &LoginContext,
+    ) -> Result<(TokenPair, Uuid), AuthError> {
+        let SignIn { acr, amr, auth_time } = sign_in;
+        // The app may require a stronger sign-in than this one
+        self.check_app_acr(app_id, app_scope, acr).await?;
+
+        // Get user's apps, roles, and permissions for token payload
+        let (apps, access_until) = self.token_app_claims(user_id, app_scope).await?;
+
+        // The tokens carry the session they belong to, so it is known without the refresh token
+        let session_id = Uuid::new_v4();
+
+        // Generate token pair (Requirement 2.4, 2.5)
+        let token_pair = self.jwt_manager.create_session_token_pair(
+            user_id,
+            apps,
+            self.jwt_manager.refresh_token_expiry_secs(),
+            SessionClaims {
+                auth_time,
+                app: app_scope.map(String::from),
+                acr: Some(acr.to_string()),
+                amr,
+                access_until,
+                session_id: Some(session_id),
+            },
+        )?;
+
+        // Create session with device info
+        let session = self
+            .session_service
+            .create_session(session_id, user_id, app_id, &token_pair.refresh_token, Some(device_info(context)))
+            .await?;
+
+        // The lineage is forensic only; failing to record it doesn't fail the login
+        let _ = self
+            .token_lineage_service
+            .record_pair(&token_pair, user_id, Some(session.id), None)
+            .await;
+
+        // Restarts the dormant account clock
+        let _ = self.user_repo.record_activity(user_id).await;
+
+        // Log successful login
+        let _ = self
+            .audit_service
+            .log_auth_event(
+                Some(user_id),
+                AuditAction::Login,
+                context.ip_address.as_deref(),
+                context.user_agent.as_deref(),
+                Some(serde_json::json!({ 
+                    "status": "completed",
+                    "session_id": session.id.to_string()
+                })),
+                true,
+            )
+            .await;
+
+        // Trigger webhook for login event (if app_id is provided)
+        if let Some(app_id) = app_id {
+            let webhook_service = self.webhook_service.clone();
+            let payload = serde_json::json!({
+                "event": "user.login",
+                "user_id": user_id.to_string(),
+                "app_id": app_id.to_string(),
+                "ip_address": context.ip_address,
+                "user_agent": context.user_agent,
+                "session_id": session.id.to_string(),
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            });
+            spawn_in_request(async move {
+                let _ = webhook_service.trigger_event(app_id, WebhookEvent::UserLogin, payload).await;
             });
         }
 
-        // Verify the MFA code
-        let is_valid = if is_backup_code {
-            self.mfa_service.verify_backup_code(mfa_data.user_id, code).await?
-        } else {
-            self.mfa_service.verify_totp(mfa_data.user_id, code).await?
+        Ok((token_pair, session.id))
+    }
+
+    /// Create a temporary MFA token for 2-step login
+    async fn create_mfa_token(
+        &self,
+        user_id: Uuid,
+        app_id: Option<Uuid>,
+        app_scope: Option<&str>,
+        first_factor: &str,
+    ) -> Result<String, AuthError> {
+        let token = Uuid::new_v4().to_string();
+        let token_hash = hash_token(&token)?;
+        let expires_at = Utc::now() + Duration::minutes(MFA_TOKEN_EXPIRY_MINUTES);
+        let id = Uuid::new_v4();
+
+        // Store MFA token in a temporary table (reusing password_reset_tokens structure)
+        // In production, you might want a dedicated mfa_tokens table
+        sqlx::query(
+            r#"
+            INSERT INTO mfa_pending_tokens (id, user_id, token_hash, app_id, app_scope, first_factor, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(user_id.to_string())
+        .bind(&token_hash)
+        .bind(app_id.map(|id| id.to_string()))
+        .bind(app_scope)
+        .bind(first_factor)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(token)
+    }
+
+    /// Verify MFA token and get associated data
+    async fn verify_mfa_token(&self, token: &str) -> Result<MfaTokenData, AuthError> {
+        let token_hash = hash_token(token)?;
+
+        let row = sqlx::query_as::<_, (String, Option<String>, Option<String>, String, chrono::DateTime<Utc>)>(
+            r#"
+            SELECT user_id, app_id, app_scope, first_factor, expires_at
+            FROM mfa_pending_tokens
+            WHERE token_hash = ? AND used = FALSE AND expires_at > NOW()
+            "#,
+        )
+        .bind(&token_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?
+        .ok_or(AuthError::InvalidToken)?;
+
+        let user_id = Uuid::parse_str(&row.0)
+            .map_err(|e| AuthError::InternalError(e.into()))?;
+        let app_id = row.1.and_then(|s| Uuid::parse_str(&s).ok());
+
+        Ok(MfaTokenData {
+            user_id,
+            app_id,
+            app_scope: row.2,
+            first_factor: row.3,
+            expires_at: row.4,
+        })
+    }
+
+    /// Mark MFA token as used
+    async fn consume_mfa_token(&self, token: &str) -> Result<(), AuthError> {
+        let token_hash = hash_token(token)?;
+
+        sqlx::query(
+            r#"
+            UPDATE mfa_pending_tokens
+            SET used = TRUE
+            WHERE token_hash = ?
+            "#,
+        )
+        .bind(&token_hash)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Start a push approval for a pending MFA login
+    /// Returns the challenge and how many devices were notified
+    pub async fn start_push_mfa(
+        &self,
+        mfa_token: &str,
+        context: LoginContext,
+    ) -> Result<(PushMfaChallenge, usize), AuthError> {
+        let mfa_data = self.verify_mfa_token(mfa_token).await?;
+        let mfa_token_hash = hash_token(mfa_token)?;
+
+        self.push_mfa_service
+            .start_challenge(mfa_data.user_id, &mfa_token_hash, &context)
+            .await
+    }
+
+    /// Text a login code to the user's verified phone for a pending MFA login
+    pub async fn send_sms_mfa(
+        &self,
+        mfa_token: &str,
+        method_id: Option<Uuid>,
+        context: LoginContext,
+    ) -> Result<SmsCodeSent, AuthError> {
+        let mfa_data = self.verify_mfa_token(mfa_token).await?;
+        let mfa_token_hash = hash_token(mfa_token)?;
+
+        let sent = self
+            .mfa_service
+            .send_sms_login_code(mfa_data.user_id, &mfa_token_hash, method_id)
+            .await?;
+
+        let _ = self
+            .audit_service
+            .log_mfa_event(
+                mfa_data.user_id,
+                AuditAction::MfaSmsSent,
+                context.ip_address.as_deref(),
+                context.user_agent.as_deref(),
+                Some(serde_json::json!({
+                    "purpose": "login",
+                    "method_id": sent.method_id,
+                    "provider": self.mfa_service.sms_provider_name()
+                })),
+                true,
+            )
+            .await;
+
+        Ok(sent)
+    }
+
+    /// Email a code to the account address for a pending MFA login
+    ///
+    /// Not offered when the first factor already was an emailed code.
+    pub async fn send_email_mfa(&self, mfa_token: &str, context: LoginContext) -> Result<EmailCodeSent, AuthError> {
+        let mfa_data = self.verify_mfa_token(mfa_token).await?;
+        if is_email_first_factor(&mfa_data.first_factor) {
+            return Err(AuthError::InvalidRequest("Email codes can't complete an email sign-in".to_string()));
+        }
+        let mfa_token_hash = hash_token(mfa_token)?;
+
+        let user = self
+            .user_repo
+            .find_by_id(mfa_data.user_id)
+            .await?
+            .ok_or(AuthError::InvalidToken)?;
+
+        let sent = self
+            .mfa_service
+            .send_email_mfa_code(user.id, &user.email, &mfa_token_hash)
+            .await?;
+
+        let _ = self
+            .audit_service
+            .log_mfa_event(
+                user.id,
+                AuditAction::EmailCodeSent,
+                context.ip_address.as_deref(),
+                context.user_agent.as_deref(),
+                Some(serde_json::json!({ "purpose": "mfa" })),
+                true,
+            )
+            .await;
+
+        Ok(sent)
+    }
+
+    /// Complete MFA login - verify code (or approved push challenge) and return tokens
+    pub async fn complete_mfa_login(
+        &self,
+        mfa_token: &str,
+        code: &str,
+        factor: MfaFactor,
+        context: LoginContext,
+    ) -> Result<(TokenPair, Option<String>), AuthError> {
+        // Verify MFA token
+        let mfa_data = self.verify_mfa_token(mfa_token).await?;
+
+        // Push approvals are polled until answered and cannot be guessed,
+        // so only codes count against the MFA rate limit
+        let identifier = format!("mfa:{}", mfa_data.user_id);
+        if !matches!(factor, MfaFactor::Push(_)) {
+            let rate_limit_config = RateLimitConfig::mfa_verify();
+            let rate_result = self
+                .rate_limiter
+                .check_and_increment(&identifier, "mfa_verify", &rate_limit_config)
+                .await
+                .map_err(|e| AuthError::InternalError(anyhow::anyhow!("{}", e)))?;
+
+            if !rate_result.allowed {
+                let _ = self
+                    .audit_service
+                    .log_mfa_event(
+                        mfa_data.user_id,
+                        AuditAction::MfaFailed,
+                        context.ip_address.as_deref(),
+                        context.user_agent.as_deref(),
+                        Some(serde_json::json!({ "reason": "rate_limited" })),
+                        false,
+                    )
+                    .await;
+
+                return Err(AuthError::RateLimitExceeded(rate_result.throttle("mfa_verify")));
+            }
+        }
+
+        let method = factor.method();
+        let is_backup_code = factor == MfaFactor::BackupCode;
+
+        // Verify the MFA code or push approval
+        let is_valid = match factor {
+            MfaFactor::Push(challenge_id) => {
+                let mfa_token_hash = hash_token(mfa_token)?;
+                match self.push_mfa_service.consume_approval(challenge_id, &mfa_token_hash).await {
+                    Ok(()) => true,
+                    Err(AuthError::InvalidMfaCode) => false,
+                    Err(e) => return Err(e),
+                }
+            }
+            MfaFactor::BackupCode => self.mfa_service.verify_backup_code(mfa_data.user_id, code).await?,
+            MfaFactor::Sms => {
+                let mfa_token_hash = hash_token(mfa_token)?;
+                self.mfa_service.verify_sms(mfa_data.user_id, &mfa_token_hash, code).await?
+            }
+            MfaFactor::Email => {
+                // The emailed first factor can't double as the second
+                let mfa_token_hash = hash_token(mfa_token)?;
+                !is_email_first_factor(&mfa_data.first_factor)
+                    && self.mfa_service.verify_email_mfa(mfa_data.user_id, &mfa_token_hash, code).await?
+            }
+            MfaFactor::Totp => self.mfa_service.verify_totp(mfa_data.user_id, code).await?,
         };
 
         if !is_valid {
@@ -593,7 +1442,8 @@ impl AuthService {
                     context.ip_address.as_deref(),
                     context.user_agent.as_deref(),
                     Some(serde_json::json!({
-                        "is_backup_code": is_backup_code
+                        "is_backup_code": is_backup_code,
+                        "method": method
                     })),
                     false,
                 )
@@ -604,56 +1454,183 @@ impl AuthService {
                 .mfa_service
                 .record_attempt(
                     mfa_data.user_id,
-                    if is_backup_code { "backup" } else { "totp" },
+                    method,
                     false,
                     context.ip_address.as_deref(),
                 )
                 .await;
 
-            return Err(AuthError::InvalidMfaCode);
+            return Err(AuthError::InvalidMfaCode);
+        }
+
+        // MFA verified - consume the token
+        self.consume_mfa_token(mfa_token).await?;
+
+        // Reset MFA rate limit
+        let _ = self.rate_limiter.reset(&identifier, "mfa_verify").await;
+
+        // Log successful MFA
+        let _ = self
+            .audit_service
+            .log_mfa_event(
+                mfa_data.user_id,
+                AuditAction::MfaVerified,
+                context.ip_address.as_deref(),
+                context.user_agent.as_deref(),
+                Some(serde_json::json!({
+                    "is_backup_code": is_backup_code,
+                    "method": method
+                })),
+                true,
+            )
+            .await;
+
+        // Complete login
+        let (tokens, _session_id) = self
+            .complete_login(
+                mfa_data.user_id,
+                mfa_data.app_id,
+                mfa_data.app_scope.as_deref(),
+                match (mfa_data.first_factor.as_str(), method) {
+                    (FIRST_FACTOR_EMAIL_CODE | FIRST_FACTOR_EMAIL_LINK, _) => SignInMethod::EmailCodeAndMfa,
+                    (_, "push") => SignInMethod::PasswordAndPush,
+                    (_, "sms") => SignInMethod::PasswordAndSms,
+                    (_, "email") => SignInMethod::PasswordAndEmail,
+                    _ => SignInMethod::PasswordAndOtp,
+                },
+                &context,
+            )
+            .await?;
+
+        // Remember the device only once the challenge succeeded
+        let trusted_device = if self.remember_device && self.trusted_device_days > 0 {
+            let (device, cookie_value) = self
+                .trusted_device_service
+                .trust(
+                    mfa_data.user_id,
+                    context.ip_address.as_deref(),
+                    context.user_agent.as_deref(),
+                    self.trusted_device_days,
+                )
+                .await?;
+            let _ = self
+                .audit_service
+                .log_auth_event(
+                    Some(mfa_data.user_id),
+                    AuditAction::TrustedDeviceAdded,
+                    context.ip_address.as_deref(),
+                    context.user_agent.as_deref(),
+                    Some(serde_json::json!({
+                        "trusted_device_id": device.id,
+                        "expires_at": device.expires_at
+                    })),
+                    true,
+                )
+                .await;
+            Some(cookie_value)
+        } else {
+            None
+        };
+
+        Ok((tokens, trusted_device))
+    }
+
+    /// App claims for a new token, limited to `app_scope` when given
+    ///
+    /// Also returns when the first access window of a role in the claims
+    /// closes; the access token must not outlive it.
+    async fn token_app_claims(
+        &self,
+        user_id: Uuid,
+        app_scope: Option<&str>,
+    ) -> Result<(HashMap<String, AppClaims>, Option<i64>), AuthError> {
+        let (mut apps, mut window_closes) = self.load_app_claims(user_id).await?;
+
+        if let Some(app_code) = app_scope {
+            self.check_app_membership(user_id, app_code).await?;
+            apps.retain(|code, _| code == app_code);
+            window_closes.retain(|code, _| code == app_code);
         }
 
-        // MFA verified - consume the token
-        self.consume_mfa_token(mfa_token).await?;
+        let access_until = window_closes.into_values().min().map(|at| at.timestamp());
+        Ok((apps, access_until))
+    }
 
-        // Reset MFA rate limit
-        let _ = self.rate_limiter.reset(&identifier, "mfa_verify").await;
+    /// Check that a user may get tokens scoped to an app
+    ///
+    /// Members hold an unexpired role in the app, inside its access window,
+    /// and are not banned from it.
+    /// Unknown app codes are reported like missing membership.
+    async fn check_app_membership(&self, user_id: Uuid, app_code: &str) -> Result<(), AuthError> {
+        let banned = sqlx::query_as::<_, (Option<String>,)>(
+            r#"
+            SELECT ua.banned_reason
+            FROM user_apps ua
+            JOIN apps a ON ua.app_id = a.id
+            WHERE ua.user_id = ? AND a.code = ? AND ua.status = 'banned'
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(app_code)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
 
-        // Log successful MFA
-        let _ = self
-            .audit_service
-            .log_mfa_event(
-                mfa_data.user_id,
-                AuditAction::MfaVerified,
-                context.ip_address.as_deref(),
-                context.user_agent.as_deref(),
-                Some(serde_json::json!({
-                    "is_backup_code": is_backup_code
-                })),
-                true,
-            )
-            .await;
+        if let Some((reason,)) = banned {
+            return Err(AuthError::UserBanned { reason });
+        }
 
-        // Complete login
-        let (tokens, _session_id) = self.complete_login(mfa_data.user_id, mfa_data.app_id, &context).await?;
-        Ok(tokens)
+        let windows = sqlx::query_scalar::<_, Option<String>>(
+            r#"
+            SELECT uar.access_window
+            FROM user_app_roles uar
+            JOIN apps a ON uar.app_id = a.id
+            WHERE uar.user_id = ? AND a.code = ?
+              AND (uar.expires_at IS NULL OR uar.expires_at > NOW())
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(app_code)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuthError::InternalError(e.into()))?;
+
+        let now = Utc::now();
+        if !windows.iter().any(|window| access_window::admits(window.as_deref(), now)) {
+            return Err(AuthError::NotAppMember);
+        }
+
+        Ok(())
     }
 
     /// Get user's app claims (roles and permissions) for JWT token
-    async fn get_user_app_claims(&self, user_id: Uuid) -> Result<HashMap<String, AppClaims>, AuthError> {
+    ///
+    /// Roles outside their access window are left out.
+    pub async fn get_user_app_claims(&self, user_id: Uuid) -> Result<HashMap<String, AppClaims>, AuthError> {
+        Ok(self.load_app_claims(user_id).await?.0)
+    }
+
+    /// App claims of a user, with when the earliest access window of a role
+    /// in each app closes
+    async fn load_app_claims(
+        &self,
+        user_id: Uuid,
+    ) -> Result<(HashMap<String, AppClaims>, HashMap<String, DateTime<Utc>>), AuthError> {
         // Query to get all apps, roles, and permissions for a user
-        let rows = sqlx::query_as::<_, (String, String, Option<String>)>(
+        let rows = sqlx::query_as::<_, (String, String, Option<String>, Option<String>)>(
             r#"
             SELECT 
                 a.code as app_code,
                 r.name as role_name,
-                p.code as permission_code
+                p.code as permission_code,
+                uar.access_window
             FROM user_app_roles uar
             JOIN apps a ON uar.app_id = a.id
             JOIN roles r ON uar.role_id = r.id
             LEFT JOIN role_permissions rp ON r.id = rp.role_id
             LEFT JOIN permissions p ON rp.permission_id = p.id
             WHERE uar.user_id = ?
+              AND (uar.expires_at IS NULL OR uar.expires_at > NOW())
             ORDER BY a.code, r.name, p.code
             "#,
         )
@@ -664,8 +1641,21 @@ impl AuthService {
 
         // Build app claims map
         let mut apps: HashMap<String, AppClaims> = HashMap::new();
+        let mut window_closes: HashMap<String, DateTime<Utc>> = HashMap::new();
+        let now = Utc::now();
         
-        for (app_code, role_name, permission_code) in rows {
+        for (app_code, role_name, permission_code, window) in rows {
+            // Roles outside their window (or with an unreadable one) are not granted
+            if let Some(window) = window {
+                let Some(closes_at) = window.parse::<AccessWindow>().ok().and_then(|w| w.closes_at(now)) else {
+                    continue;
+                };
+                window_closes
+                    .entry(app_code.clone())
+                    .and_modify(|at| *at = (*at).min(closes_at))
+                    .or_insert(closes_at);
+            }
+
             let app_claims = apps.entry(app_code).or_insert_with(|| AppClaims {
                 roles: Vec::new(),
                 permissions: Vec::new(),
@@ -684,30 +1674,7 @@ impl AuthService {
             }
         }
 
-        Ok(apps)
-    }
-
-    /// Store refresh token hash in database
-    async fn store_refresh_token(&self, user_id: Uuid, refresh_token: &str) -> Result<(), AuthError> {
-        let token_hash = hash_password(refresh_token)?;
-        let expires_at = Utc::now() + Duration::days(REFRESH_TOKEN_EXPIRY_DAYS);
-        let id = Uuid::new_v4();
-
-        sqlx::query(
-            r#"
-            INSERT INTO refresh_tokens (id, user_id, token_hash, expires_at)
-            VALUES (?, ?, ?, ?)
-            "#,
-        )
-        .bind(id.to_string())
-        .bind(user_id.to_string())
-        .bind(token_hash)
-        .bind(expires_at)
-        .execute(&self.pool)
-        .await
-        .map_err(|e| AuthError::InternalError(e.into()))?;
-
-        Ok(())
+        Ok((apps, window_closes))
     }
 
     /// Validate password meets requirements
@@ -729,7 +1696,18 @@ impl AuthService {
     }
 
     /// Refresh access token using a valid refresh token
-    pub async fn refresh(&self, refresh_token: &str) -> Result<TokenPair, AuthError> {
+    ///
+    /// `context` describes the refreshing client; it is compared with the
+    /// client recorded on the session according to the fingerprint policy.
+    ///
+    /// `app_scope` narrows the new tokens to one app. A refresh token that is
+    /// already scoped keeps its app and cannot be widened or moved to another.
+    pub async fn refresh(
+        &self,
+        refresh_token: &str,
+        app_scope: Option<&str>,
+        context: &LoginContext,
+    ) -> Result<TokenPair, AuthError> {
         // Verify the refresh token JWT (Requirement 3.2)
         let claims = self.jwt_manager.verify_token(refresh_token)?;
 
@@ -746,18 +1724,530 @@ impl AuthService {
             return Err(AuthError::UserInactive);
         }
 
+        // Rotated-out and logged-out refresh tokens are blacklisted
+        if self.token_revocation_service.is_refresh_token_revoked(refresh_token).await? {
+            // A rotated-out token only comes back as a copy: the session is compromised
+            if self.token_revocation_service.is_refresh_token_rotated(refresh_token).await? {
+                self.revoke_reused_session(&claims, user_id, context).await;
+            }
+            return Err(AuthError::InvalidToken);
+        }
+
+        let app_scope = match (claims.app.as_deref(), app_scope) {
+            (Some(scoped), Some(requested)) if scoped != requested => {
+                return Err(AuthError::InvalidRequest(format!(
+                    "Refresh token is scoped to app '{}'",
+                    scoped
+                )));
+            }
+            (Some(scoped), _) => Some(scoped.to_string()),
+            (None, requested) => requested.map(String::from),
+        };
+
         // Get updated roles and permissions (Requirement 3.3)
-        let apps = self.get_user_app_claims(user_id).await?;
+        let (apps, access_until) = self.token_app_claims(user_id, app_scope.as_deref()).await?;
+
+        let session = match self.session_service.find_by_refresh_token(refresh_token).await? {
+            Some(session) => session,
+            None => {
+                // Token issued before sessions were tracked: it moves into a new
+                // session, so it rotates and a replay is caught like any other
+                if !self.is_legacy_refresh_token(refresh_token, &claims, user_id).await? {
+                    return Err(AuthError::InvalidToken);
+                }
+                // Revoke it before opening the session: of two concurrent
+                // refreshes only the one that revoked it gets a session
+                let remaining_secs = (claims.exp - Utc::now().timestamp()).max(0);
+                let claimed = self
+                    .token_revocation_service
+                    .revoke_refresh_token_once(refresh_token, Some(user_id), remaining_secs, Some(REVOKED_ROTATED))
+                    .await?;
+                if !claimed {
+                    self.revoke_reused_session(&claims, user_id, context).await;
+                    return Err(AuthError::InvalidToken);
+                }
+                let session_id = Uuid::new_v4();
+                let token_pair = self.jwt_manager.create_session_token_pair(
+                    user_id,
+                    apps,
+                    self.jwt_manager.refresh_token_expiry_secs(),
+                    SessionClaims {
+                        auth_time: claims.auth_time(),
+                        app: app_scope,
+                        acr: claims.acr.clone(),
+                        amr: claims.amr.clone(),
+                        access_until,
+                        session_id: Some(session_id),
+                    },
+                )?;
+                let app_id = match claims.app.as_deref() {
+                    Some(app_code) => self.app_repo.find_by_code(app_code).await.ok().flatten().map(|app| app.id),
+                    None => None,
+                };
+                self.session_service
+                    .create_session(session_id, user_id, app_id, &token_pair.refresh_token, Some(device_info(context)))
+                    .await?;
+
+                self.record_rotation(&claims, user_id, session_id, &token_pair).await;
+                return Ok(token_pair);
+            }
+        };
+
+        if session.user_id != user_id || session.is_revoked {
+            return Err(AuthError::InvalidToken);
+        }
+        if claims.session_id().is_some_and(|sid| sid != session.id) {
+            return Err(AuthError::InvalidToken);
+        }
+        if session.expires_at < Utc::now() {
+            return Err(AuthError::TokenExpired);
+        }
+
+        self.check_client_fingerprint(&session, context).await?;
+
+        // Session policy of the app the session was opened in
+        let policy = self.session_policy_for(session.app_id).await?;
+        let absolute_expiry = policy.absolute_expiry(session.created_at);
+        if absolute_expiry < Utc::now() {
+            let _ = self.session_service.revoke_session(session.id, user_id).await;
+            return Err(AuthError::TokenExpired);
+        }
+
+        // The refreshed tokens keep the time and strength of the sign-in
+        let session_claims = SessionClaims {
+            auth_time: session.created_at.timestamp(),
+            app: app_scope,
+            acr: claims.acr.clone(),
+            amr: claims.amr.clone(),
+            access_until,
+            session_id: Some(session.id),
+        };
+
+        // Device-bound sessions get long-lived, sliding refresh tokens;
+        // unbound sessions keep their expiry and are subject to the idle timeout
+        let (token_pair, expires_at) = match session.device_id {
+            Some(device_id) => {
+                let device = self
+                    .device_repo
+                    .find_by_id(device_id)
+                    .await?
+                    .filter(|d| d.is_active)
+                    .ok_or(AuthError::InvalidToken)?;
+                self.device_repo.touch(device.id).await?;
+
+                let expires_at = (Utc::now() + Duration::days(DEVICE_SESSION_EXPIRY_DAYS)).min(absolute_expiry);
+                let token_pair = self.jwt_manager.create_session_token_pair(
+                    user_id,
+                    apps,
+                    (expires_at - Utc::now()).num_seconds(),
+                    session_claims,
+                )?;
+                (token_pair, expires_at)
+            }
+            None => {
+                if policy.is_idle_expired(session.last_active_at) {
+                    let _ = self.session_service.revoke_session(session.id, user_id).await;
+                    return Err(AuthError::TokenExpired);
+                }
+                let token_pair = self.jwt_manager.create_session_token_pair(
+                    user_id,
+                    apps,
+                    self.jwt_manager.refresh_token_expiry_secs(),
+                    session_claims,
+                )?;
+                (token_pair, session.expires_at.min(absolute_expiry))
+            }
+        };
 
-        // Generate new token pair (Requirement 3.1)
-        let token_pair = self.jwt_manager.create_token_pair(user_id, apps)?;
+        // Rotate: the session now only accepts the new refresh token (Requirement 3.1).
+        // Losing the swap means the same token was just used for another refresh
+        let rotated = self
+            .session_service
+            .rotate_refresh_token(session.id, refresh_token, &token_pair.refresh_token, expires_at)
+            .await?;
+        if !rotated {
+            self.revoke_reused_session(&claims, user_id, context).await;
+            return Err(AuthError::InvalidToken);
+        }
 
-        // Store new refresh token hash
-        self.store_refresh_token(user_id, &token_pair.refresh_token).await?;
+        self.retire_refresh_token(refresh_token, &claims, user_id, session.id, &token_pair)
+            .await?;
 
         Ok(token_pair)
     }
 
+    /// Whether a refresh token without a session may be moved into a new one
+    ///
+    /// Only tokens without `sid` qualify, and only when nothing revoked them
+    /// and they were never part of a session: once the row of a revoked
+    /// session is cleaned up, its tokens must not come back to life.
+    async fn is_legacy_refresh_token(
+        &self,
+        refresh_token: &str,
+        claims: &Claims,
+        user_id: Uuid,
+    ) -> Result<bool, AuthError> {
+        if claims.sid.is_some() {
+            return Ok(false);
+        }
+        if self
+            .token_revocation_service
+            .is_user_refresh_token_revoked(refresh_token, user_id, claims.iat)
+            .await?
+        {
+            return Ok(false);
+        }
+        if let Some(jti) = claims.jti.as_deref() {
+            if self.token_lineage_service.is_revoked(jti).await?
+                || self.token_lineage_service.session_of(jti).await?.is_some()
+            {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Blacklist a refresh token replaced by `token_pair` in the session
+    ///
+    /// The token is kept as rotated until it would have expired, so a replay
+    /// of it is told apart from a logout.
+    async fn retire_refresh_token(
+        &self,
+        refresh_token: &str,
+        claims: &Claims,
+        user_id: Uuid,
+        session_id: Uuid,
+        token_pair: &TokenPair,
+    ) -> Result<(), AuthError> {
+        let remaining_secs = (claims.exp - Utc::now().timestamp()).max(0);
+        self.token_revocation_service
+            .revoke_refresh_token(refresh_token, Some(user_id), remaining_secs, Some(REVOKED_ROTATED))
+            .await?;
+
+        self.record_rotation(claims, user_id, session_id, token_pair).await;
+
+        Ok(())
+    }
+
+    /// Record `token_pair` as the successor of the token `claims` belong to
+    async fn record_rotation(&self, claims: &Claims, user_id: Uuid, session_id: Uuid, token_pair: &TokenPair) {
+        let _ = self
+            .token_lineage_service
+            .record_pair(token_pair, user_id, Some(session_id), claims.jti.as_deref())
+            .await;
+        if let Some(jti) = &claims.jti {
+            let _ = self.token_lineage_service.revoke(jti, REVOKED_ROTATED).await;
+        }
+    }
+
+    /// Revoke the session of a replayed refresh token
+    ///
+    /// Either the user or whoever copied the token holds its successor;
+    /// since it can't be told which, the whole session and every token
+    /// issued in it go, and the user has to sign in again.
+    async fn revoke_reused_session(&self, claims: &Claims, user_id: Uuid, context: &LoginContext) {
+        let session_id = match (claims.session_id(), claims.jti.as_deref()) {
+            (Some(session_id), _) => Some(session_id),
+            (None, Some(jti)) => self.token_lineage_service.session_of(jti).await.ok().flatten(),
+            (None, None) => None,
+        };
+        if let Some(session_id) = session_id {
+            if let Err(e) = self.session_service.revoke_session(session_id, user_id).await {
+                tracing::error!("Failed to revoke session {} after refresh token reuse: {}", session_id, e);
+            }
+        }
+
+        tracing::warn!("Rotated refresh token of user {} replayed, session {:?} revoked", user_id, session_id);
+
+        let _ = self
+            .audit_service
+            .log_auth_event(
+                Some(user_id),
+                AuditAction::RefreshTokenReused,
+                context.ip_address.as_deref(),
+                context.user_agent.as_deref(),
+                Some(serde_json::json!({
+                    "session_id": session_id,
+                    "jti": claims.jti,
+                })),
+                false,
+            )
+            .await;
+    }
+
+    // ========================================================================
+    // SSO sessions shared by first-party apps
+    // ========================================================================
+
+    /// Hand a hosted login session to an SSO cookie
+    ///
+    /// The refresh token must be the current one of an unscoped session. The
+    /// returned value goes in the SSO cookie; the SSO session lasts until the
+    /// login session's absolute lifetime ends or it is revoked.
+    pub async fn start_sso_session(&self, refresh_token: &str) -> Result<(String, SsoSession), AuthError> {
+        let claims = self.jwt_manager.verify_token(refresh_token)?;
+        if claims.app.is_some() {
+            return Err(AuthError::InvalidRequest(
+                "Only an unscoped sign-in can start an SSO session".to_string(),
+            ));
+        }
+        if self.token_revocation_service.is_refresh_token_revoked(refresh_token).await? {
+            return Err(AuthError::InvalidToken);
+        }
+
+        let user_id = claims.user_id()?;
+        let session = self
+            .session_service
+            .find_by_refresh_token(refresh_token)
+            .await?
+            .filter(|s| s.user_id == user_id && !s.is_revoked)
+            .ok_or(AuthError::InvalidToken)?;
+        let user = self.user_repo.find_by_id(user_id).await?.ok_or(AuthError::InvalidToken)?;
+        if !user.is_active {
+            return Err(AuthError::UserInactive);
+        }
+
+        let expires_at = self.session_policy_for(session.app_id).await?.absolute_expiry(session.created_at);
+        let auth_time = DateTime::from_timestamp(claims.auth_time(), 0).unwrap_or(session.created_at);
+        let token = generate_oauth_token();
+        let sso = self
+            .sso_repo
+            .create(&NewSsoSession {
+                user_id,
+                session_id: session.id,
+                token_hash: &hash_token(&token)?,
+                acr: claims.acr.as_deref(),
+                amr: claims.amr.as_deref(),
+                auth_time,
+                expires_at,
+            })
+            .await?;
+
+        Ok((token, sso))
+    }
+
+    /// Sign a user in to an app by continuing the browser's SSO session
+    ///
+    /// The new app session keeps the sign-in's time and acr, so apps that
+    /// require a stronger sign-in or opted out of SSO still ask for one.
+    /// Continuing keeps the SSO session from idling out.
+    pub async fn continue_sso_session(
+        &self,
+        sso_token: &str,
+        app_code: &str,
+        context: &LoginContext,
+    ) -> Result<(TokenPair, Uuid), AuthError> {
+        let sso = self
+            .sso_repo
+            .find_active_by_token_hash(&hash_token(sso_token)?)
+            .await?
+            .ok_or(AuthError::InvalidToken)?;
+
+        // Ending the login session, or letting it idle out, ends SSO
+        let login_session = self
+            .session_service
+            .find_by_id(sso.session_id)
+            .await?
+            .filter(|s| !s.is_revoked)
+            .ok_or(AuthError::InvalidToken)?;
+        if self.session_policy_for(login_session.app_id).await?.is_idle_expired(login_session.last_active_at) {
+            return Err(AuthError::TokenExpired);
+        }
+
+        let user = self.user_repo.find_by_id(sso.user_id).await?.ok_or(AuthError::InvalidToken)?;
+        if !user.is_active {
+            return Err(AuthError::UserInactive);
+        }
+
+        let app = self
+            .app_repo
+            .find_by_code(app_code)
+            .await
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("{}", e)))?
+            .ok_or_else(|| AuthError::InvalidRequest(format!("Unknown app '{}'", app_code)))?;
+        if !app.sso_enabled {
+            return Err(AuthError::SsoNotAllowed);
+        }
+
+        if let Some(ref ip) = context.ip_address {
+            let ip_result = self.ip_rule_service.check_ip_access(ip, Some(app.id)).await
+                .map_err(|e| AuthError::InternalError(anyhow::anyhow!("{}", e)))?;
+            if ip_result == IpAccessResult::Blocked {
+                return Err(AuthError::IpBlocked);
+            }
+        }
+        let membership = self
+            .user_app_repo
+            .find(user.id, app.id)
+            .await
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("{}", e)))?;
+        if let Some(user_app) = membership.filter(|m| m.status == crate::models::user_app::UserAppStatus::Banned) {
+            return Err(AuthError::UserBanned { reason: user_app.banned_reason });
+        }
+
+        let (token_pair, session_id) = self
+            .complete_login_at(
+                user.id,
+                Some(app.id),
+                Some(&app.code),
+                SignIn {
+                    acr: sso.acr.as_deref().unwrap_or(ACR_PASSWORD),
+                    amr: sso.amr.clone(),
+                    auth_time: sso.auth_time.timestamp(),
+                },
+                context,
+            )
+            .await?;
+        self.sso_repo.link_session(sso.id, session_id).await?;
+        self.session_service.touch(login_session.id).await?;
+
+        Ok((token_pair, session_id))
+    }
+
+    /// End a user's SSO session with its login session and every app session continued from it
+    ///
+    /// Returns the number of sessions revoked (0 when the SSO session is unknown).
+    pub async fn end_sso_session(&self, sso_token: &str, user_id: Uuid) -> Result<u64, AuthError> {
+        let sso = self
+            .sso_repo
+            .find_active_by_token_hash(&hash_token(sso_token)?)
+            .await?
+            .filter(|sso| sso.user_id == user_id);
+
+        match sso {
+            Some(sso) => self.sso_repo.revoke_with_sessions(&sso).await,
+            None => Ok(0),
+        }
+    }
+
+    /// Compare the refreshing client with the one recorded at login
+    ///
+    /// Mismatches are audited; in reject mode the refresh fails.
+    async fn check_client_fingerprint(&self, session: &UserSession, context: &LoginContext) -> Result<(), AuthError> {
+        let policy = self.fingerprint_policy;
+        if policy.mode == FingerprintMode::Off {
+            return Ok(());
+        }
+
+        let recorded = ClientFingerprint::new(
+            session.user_agent.as_deref(),
+            session.ip_address.as_deref(),
+            policy.ipv4_prefix,
+        );
+        let presented = ClientFingerprint::new(
+            context.user_agent.as_deref(),
+            context.ip_address.as_deref(),
+            policy.ipv4_prefix,
+        );
+
+        let mismatches = recorded.mismatches(&presented);
+        if mismatches.is_empty() {
+            return Ok(());
+        }
+
+        let rejected = policy.mode == FingerprintMode::Reject;
+        tracing::warn!(
+            "Refresh of session {} from a different client ({}), {}",
+            session.id,
+            mismatches.join(", "),
+            if rejected { "rejected" } else { "allowed" }
+        );
+
+        let _ = self
+            .audit_service
+            .log_auth_event(
+                Some(session.user_id),
+                AuditAction::TokenFingerprintMismatch,
+                context.ip_address.as_deref(),
+                context.user_agent.as_deref(),
+                Some(serde_json::json!({
+                    "session_id": session.id,
+                    "mode": policy.mode.as_str(),
+                    "mismatch": mismatches,
+                    "recorded": recorded,
+                    "presented": presented,
+                })),
+                !rejected,
+            )
+            .await;
+
+        if rejected {
+            return Err(AuthError::TokenBindingMismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Check a sign-in's acr against the minimum of the app it logs in to
+    ///
+    /// The app is the one logged in to (`app_id`) or the one the tokens are
+    /// scoped to (`app_scope`); both are checked when given.
+    /// The trusted device presented with the sign-in, if it may skip MFA
+    ///
+    /// Never for apps that require MFA or more: a sign-in that skips MFA
+    /// only reaches the password level.
+    async fn trusted_device_for(
+        &self,
+        user_id: Uuid,
+        app_id: Option<Uuid>,
+        app_scope: Option<&str>,
+    ) -> Result<Option<TrustedDevice>, AuthError> {
+        let Some(cookie_value) = self.trusted_device.as_deref().filter(|_| self.trusted_device_days > 0) else {
+            return Ok(None);
+        };
+
+        match self.check_app_acr(app_id, app_scope, ACR_PASSWORD).await {
+            Ok(()) => {}
+            Err(AuthError::InsufficientUserAuthentication { .. }) => return Ok(None),
+            Err(e) => return Err(e),
+        }
+
+        self.trusted_device_service.verify(user_id, cookie_value).await
+    }
+
+    async fn check_app_acr(&self, app_id: Option<Uuid>, app_scope: Option<&str>, acr: &str) -> Result<(), AuthError> {
+        let mut apps = Vec::new();
+        if let Some(app_id) = app_id {
+            apps.push(self.app_repo.find_by_id(app_id).await);
+        }
+        if let Some(app_code) = app_scope {
+            apps.push(self.app_repo.find_by_code(app_code).await);
+        }
+
+        let level = AcrLevel::of_session(Some(acr));
+        for app in apps {
+            let app = app.map_err(|e| AuthError::InternalError(anyhow::anyhow!("{}", e)))?;
+            let required = app.and_then(|app| acr::parse_required(app.required_acr.as_deref()));
+            if let Some(required) = required.filter(|required| !level.satisfies(Some(*required))) {
+                return Err(AuthError::InsufficientUserAuthentication {
+                    required_acr: required.to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the session policy for an app context
+    async fn session_policy_for(&self, app_id: Option<Uuid>) -> Result<SessionPolicy, AuthError> {
+        let app = match app_id {
+            Some(app_id) => self
+                .app_repo
+                .find_by_id(app_id)
+                .await
+                .map_err(|e| AuthError::InternalError(anyhow::anyhow!("{}", e)))?,
+            None => None,
+        };
+
+        Ok(match app {
+            Some(app) => self
+                .session_defaults
+                .with_overrides(app.session_idle_timeout_secs, app.session_absolute_lifetime_secs),
+            None => self.session_defaults,
+        })
+    }
+
     /// Request password reset for an email address
     pub async fn forgot_password(&self, email: &str) -> Result<Option<String>, AuthError> {
         // Try to find user by email
@@ -766,7 +2256,13 @@ impl AuthService {
         // If user doesn't exist, return Ok(None) without revealing this fact (Requirement 4.2)
         let user = match user {
             Some(u) => u,
-            None => return Ok(None),
+            None => {
+                // Hash a throwaway token so the response takes as long as for a real account
+                if self.enumeration_safe {
+                    hash_password(&Uuid::new_v4().to_string())?;
+                }
+                return Ok(None);
+            }
         };
 
         // Generate a secure random reset token
@@ -796,8 +2292,8 @@ impl AuthService {
         Ok(Some(reset_token))
     }
 
-    /// Reset password using a valid reset token
-    pub async fn reset_password(&self, token: &str, new_password: &str) -> Result<(), AuthError> {
+    /// Reset password using a valid reset token, returning the user whose password was reset
+    pub async fn reset_password(&self, token: &str, new_password: &str) -> Result<Uuid, AuthError> {
         // Validate new password strength
         self.validate_password(new_password)?;
 
@@ -846,6 +2342,6 @@ impl AuthService {
         .await
         .map_err(|e| AuthError::InternalError(e.into()))?;
 
-        Ok(())
+        Ok(user_id)
     }
 }
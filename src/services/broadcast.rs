@@ -0,0 +1,252 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{BroadcastRecipient, BroadcastSegment, EmailBroadcast, EmailSuppression};
+use crate::repositories::{EmailBroadcastRepository, EmailSuppressionRepository, UserRepository};
+use crate::services::EmailConfig;
+use crate::utils::email_template::{self, TemplateVars};
+
+/// Longest subject an admin can write
+const MAX_SUBJECT_LEN: usize = 255;
+
+/// Longest body template an admin can write
+const MAX_BODY_LEN: usize = 50_000;
+
+/// How far in the past `scheduled_at` may be, to allow for clock skew
+const SCHEDULE_SKEW_SECS: i64 = 60;
+
+/// Broadcasts and suppressions returned by the list endpoints
+const LIST_LIMIT: i64 = 100;
+
+/// Broadcast as one recipient receives it
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedBroadcast {
+    pub to: String,
+    pub subject: String,
+    /// Body fragment, placed inside the broadcast email layout
+    pub html: String,
+}
+
+/// What sending a broadcast now would do
+#[derive(Debug, Clone, Serialize)]
+pub struct BroadcastPreview {
+    /// Users in the segment who would be emailed
+    pub recipient_count: i64,
+    /// Users in the segment left out because they are suppressed
+    pub suppressed_count: i64,
+    /// The email as the first recipient would receive it
+    pub sample: Option<RenderedBroadcast>,
+}
+
+/// Admin email broadcasts to user segments, and the suppression list they honor
+///
+/// Scheduling only records the broadcast; the broadcast worker selects the
+/// recipients once `scheduled_at` passes and sends at the configured rate.
+pub struct BroadcastService {
+    broadcasts: EmailBroadcastRepository,
+    suppressions: EmailSuppressionRepository,
+    users: UserRepository,
+    app_name: String,
+}
+
+impl BroadcastService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self {
+            broadcasts: EmailBroadcastRepository::new(pool.clone()),
+            suppressions: EmailSuppressionRepository::new(pool.clone()),
+            users: UserRepository::new(pool),
+            app_name: EmailConfig::app_name(),
+        }
+    }
+
+    /// Count the recipients of a broadcast and render it for the first one
+    pub async fn preview(
+        &self,
+        subject: &str,
+        body: &str,
+        segment: &BroadcastSegment,
+    ) -> Result<BroadcastPreview, AppError> {
+        validate(subject, body, segment)?;
+
+        let (matching, suppressed) = self.broadcasts.count_segment(segment).await?;
+        let sample = match self.broadcasts.sample_segment(segment, 1).await?.first() {
+            Some(recipient) => Some(
+                render(subject, body, recipient, &self.app_name).map_err(AppError::ValidationError)?,
+            ),
+            None => None,
+        };
+
+        Ok(BroadcastPreview {
+            recipient_count: matching - suppressed,
+            suppressed_count: suppressed,
+            sample,
+        })
+    }
+
+    /// Schedule a broadcast; without `scheduled_at` it goes out on the worker's next tick
+    pub async fn schedule(
+        &self,
+        subject: &str,
+        body: &str,
+        segment: &BroadcastSegment,
+        scheduled_at: Option<DateTime<Utc>>,
+        created_by: Uuid,
+    ) -> Result<EmailBroadcast, AppError> {
+        validate(subject, body, segment)?;
+
+        let now = Utc::now();
+        let scheduled_at = scheduled_at.unwrap_or(now);
+        if scheduled_at < now - Duration::seconds(SCHEDULE_SKEW_SECS) {
+            return Err(AppError::ValidationError("scheduled_at is in the past".into()));
+        }
+
+        self.broadcasts
+            .create(subject.trim(), body, segment, scheduled_at, Some(created_by))
+            .await
+    }
+
+    pub async fn list(&self) -> Result<Vec<EmailBroadcast>, AppError> {
+        self.broadcasts.list(LIST_LIMIT).await
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<EmailBroadcast, AppError> {
+        self.broadcasts
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Broadcast not found".into()))
+    }
+
+    /// Stop a broadcast; recipients not yet emailed are skipped
+    pub async fn cancel(&self, id: Uuid) -> Result<EmailBroadcast, AppError> {
+        let broadcast = self.get(id).await?;
+        if !self.broadcasts.cancel(id).await? {
+            return Err(AppError::ValidationError(format!(
+                "Broadcast is already {}",
+                broadcast.status
+            )));
+        }
+        self.get(id).await
+    }
+
+    /// Stop sending broadcasts to a user
+    pub async fn suppress(
+        &self,
+        user_id: Uuid,
+        reason: Option<&str>,
+        created_by: Uuid,
+    ) -> Result<(), AppError> {
+        self.users
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+
+        let reason = reason.map(str::trim).filter(|r| !r.is_empty());
+        if reason.is_some_and(|r| r.len() > 255) {
+            return Err(AppError::ValidationError("reason must be at most 255 characters".into()));
+        }
+
+        self.suppressions.add(user_id, reason, Some(created_by)).await
+    }
+
+    /// Send broadcasts to a user again
+    pub async fn unsuppress(&self, user_id: Uuid) -> Result<(), AppError> {
+        if !self.suppressions.remove(user_id).await? {
+            return Err(AppError::NotFound("User is not suppressed".into()));
+        }
+        Ok(())
+    }
+
+    pub async fn list_suppressions(&self) -> Result<Vec<EmailSuppression>, AppError> {
+        self.suppressions.list(LIST_LIMIT).await
+    }
+}
+
+/// Check a broadcast before it is previewed or scheduled
+fn validate(subject: &str, body: &str, segment: &BroadcastSegment) -> Result<(), AppError> {
+    let subject = subject.trim();
+    if subject.is_empty() || subject.len() > MAX_SUBJECT_LEN {
+        return Err(AppError::ValidationError(format!(
+            "subject must be 1 to {} characters",
+            MAX_SUBJECT_LEN
+        )));
+    }
+    if subject.contains(['\r', '\n']) {
+        return Err(AppError::ValidationError("subject must be a single line".into()));
+    }
+    if body.trim().is_empty() || body.len() > MAX_BODY_LEN {
+        return Err(AppError::ValidationError(format!(
+            "body must be 1 to {} characters",
+            MAX_BODY_LEN
+        )));
+    }
+    email_template::validate(subject).map_err(|e| AppError::ValidationError(format!("subject: {}", e)))?;
+    email_template::validate(body).map_err(|e| AppError::ValidationError(format!("body: {}", e)))?;
+
+    if segment.active_within_days == Some(0) || segment.inactive_for_days == Some(0) {
+        return Err(AppError::ValidationError(
+            "active_within_days and inactive_for_days must be at least 1".into(),
+        ));
+    }
+    if let (Some(active), Some(inactive)) = (segment.active_within_days, segment.inactive_for_days) {
+        if active <= inactive {
+            return Err(AppError::ValidationError(
+                "active_within_days must be greater than inactive_for_days when both are set".into(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Render a broadcast for one recipient
+pub fn render(
+    subject: &str,
+    body: &str,
+    recipient: &BroadcastRecipient,
+    app_name: &str,
+) -> Result<RenderedBroadcast, String> {
+    let vars = TemplateVars {
+        email: &recipient.email,
+        name: recipient.name.as_deref(),
+        app_name,
+    };
+
+    Ok(RenderedBroadcast {
+        to: recipient.email.clone(),
+        subject: email_template::render_text(subject.trim(), &vars)?,
+        html: email_template::render_html(body, &vars)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_rejects_unknown_placeholders() {
+        let segment = BroadcastSegment::default();
+        assert!(validate("News for {{name}}", "Hello {{name}}", &segment).is_ok());
+        assert!(validate("News", "Hello {{token}}", &segment).is_err());
+        assert!(validate("News\nBcc: x@example.com", "Hello", &segment).is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_contradictory_activity_filters() {
+        let segment = BroadcastSegment {
+            active_within_days: Some(30),
+            inactive_for_days: Some(30),
+            ..Default::default()
+        };
+        assert!(validate("News", "Hello", &segment).is_err());
+
+        let segment = BroadcastSegment {
+            active_within_days: Some(90),
+            inactive_for_days: Some(30),
+            ..Default::default()
+        };
+        assert!(validate("News", "Hello", &segment).is_ok());
+    }
+}
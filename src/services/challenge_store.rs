@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::repositories::ChallengeStoreRepository;
+
+/// Kind of the WebAuthn registration and authentication challenges
+pub const CHALLENGE_KIND_WEBAUTHN: &str = "webauthn";
+
+/// Kind of pushed authorization requests (RFC 9126), keyed by request_uri hash
+pub const CHALLENGE_KIND_PAR: &str = "par";
+
+/// Kind of QR login channels, keyed by channel code hash
+pub const CHALLENGE_KIND_QR_LOGIN: &str = "qr_login";
+
+/// Kind of device authorizations (RFC 8628), keyed by device code hash
+pub const CHALLENGE_KIND_DEVICE_CODE: &str = "device_code";
+
+/// Kind mapping a device authorization's user code hash to its device code hash
+pub const CHALLENGE_KIND_DEVICE_USER_CODE: &str = "device_user_code";
+
+/// Attempts at an update before giving up on a value that keeps changing
+const UPDATE_ATTEMPTS: usize = 5;
+
+/// Where short-lived challenges and flow state are kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeStoreBackend {
+    /// Shared `challenge_store` table: any instance can finish a flow
+    Database,
+    /// Process memory: single instance only, nothing written to the database
+    Memory,
+}
+
+impl ChallengeStoreBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Database => "database",
+            Self::Memory => "memory",
+        }
+    }
+}
+
+impl FromStr for ChallengeStoreBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "database" => Ok(Self::Database),
+            "memory" => Ok(Self::Memory),
+            other => Err(anyhow::anyhow!(
+                "Invalid challenge store '{}' (expected database or memory)",
+                other
+            )),
+        }
+    }
+}
+
+type MemoryEntries = HashMap<(String, String), (serde_json::Value, DateTime<Utc>)>;
+
+#[derive(Clone)]
+enum Backend {
+    Database(ChallengeStoreRepository),
+    Memory(Arc<Mutex<MemoryEntries>>),
+}
+
+/// Key-value store for one-time values with a short time to live
+///
+/// Holds the state a multi-step flow needs between its requests (WebAuthn
+/// challenges, pushed authorization requests, QR login channels and device
+/// authorizations), so the flow does not depend
+/// on which instance serves each step. Values are grouped by kind, are
+/// unreadable once expired and are purged as new values are written.
+#[derive(Clone)]
+pub struct ChallengeStore {
+    backend: Backend,
+}
+
+impl ChallengeStore {
+    /// Store backed by the `challenge_store` table
+    pub fn database(pool: MySqlPool) -> Self {
+        Self {
+            backend: Backend::Database(ChallengeStoreRepository::new(pool)),
+        }
+    }
+
+    /// Store kept in process memory
+    pub fn memory() -> Self {
+        Self {
+            backend: Backend::Memory(Arc::new(Mutex::new(HashMap::new()))),
+        }
+    }
+
+    /// Store using the configured backend
+    pub fn from_config(pool: MySqlPool, config: &Config) -> Self {
+        match config.challenge_store {
+            ChallengeStoreBackend::Database => Self::database(pool),
+            ChallengeStoreBackend::Memory => Self::memory(),
+        }
+    }
+
+    /// Store a value for `ttl_secs` seconds, replacing any earlier value
+    pub async fn put(
+        &self,
+        kind: &str,
+        key: &str,
+        value: &serde_json::Value,
+        ttl_secs: i64,
+    ) -> Result<(), AppError> {
+        let expires_at = Utc::now() + Duration::seconds(ttl_secs);
+        match &self.backend {
+            Backend::Database(repo) => {
+                // Purging is housekeeping; a failure doesn't fail the write
+                let _ = repo.purge_expired().await;
+                repo.put(kind, key, value, expires_at).await
+            }
+            Backend::Memory(entries) => {
+                let mut entries = lock(entries);
+                let now = Utc::now();
+                entries.retain(|_, (_, expires_at)| *expires_at > now);
+                entries.insert((kind.to_string(), key.to_string()), (value.clone(), expires_at));
+                Ok(())
+            }
+        }
+    }
+
+    /// Read a value without using it up
+    pub async fn get(&self, kind: &str, key: &str) -> Result<Option<serde_json::Value>, AppError> {
+        match &self.backend {
+            Backend::Database(repo) => repo.get(kind, key).await,
+            Backend::Memory(entries) => Ok(lock(entries)
+                .get(&(kind.to_string(), key.to_string()))
+                .filter(|(_, expires_at)| *expires_at > Utc::now())
+                .map(|(value, _)| value.clone())),
+        }
+    }
+
+    /// Read and remove a value
+    ///
+    /// Of concurrent calls for the same key only one gets the value.
+    pub async fn take(&self, kind: &str, key: &str) -> Result<Option<serde_json::Value>, AppError> {
+        match &self.backend {
+            Backend::Database(repo) => {
+                let Some(value) = repo.get(kind, key).await? else {
+                    return Ok(None);
+                };
+                Ok(repo.delete(kind, key).await?.then_some(value))
+            }
+            Backend::Memory(entries) => Ok(lock(entries)
+                .remove(&(kind.to_string(), key.to_string()))
+                .filter(|(_, expires_at)| *expires_at > Utc::now())
+                .map(|(value, _)| value)),
+        }
+    }
+
+    /// Read a value stored as `T` without using it up
+    pub async fn get_as<T: DeserializeOwned>(&self, kind: &str, key: &str) -> Result<Option<T>, AppError> {
+        self.get(kind, key)
+            .await?
+            .map(|value| serde_json::from_value(value).map_err(|e| AppError::InternalError(e.into())))
+            .transpose()
+    }
+
+    /// Change a value stored as `T` in place, keeping its expiry
+    ///
+    /// `change` edits the current value and returns false to leave it alone.
+    /// If another write lands in between, `change` runs again on the newer
+    /// value, so concurrent updates never overwrite each other. Returns false
+    /// if the value is gone or `change` declined.
+    pub async fn update<T, F>(&self, kind: &str, key: &str, mut change: F) -> Result<bool, AppError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnMut(&mut T) -> bool,
+    {
+        for _ in 0..UPDATE_ATTEMPTS {
+            let Some(current) = self.get(kind, key).await? else {
+                return Ok(false);
+            };
+            let mut value: T = serde_json::from_value(current.clone())
+                .map_err(|e| AppError::InternalError(e.into()))?;
+            if !change(&mut value) {
+                return Ok(false);
+            }
+            let value = serde_json::to_value(&value).map_err(|e| AppError::InternalError(e.into()))?;
+            if self.replace(kind, key, &current, &value).await? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Swap `current` for `value` if nothing else changed it
+    async fn replace(
+        &self,
+        kind: &str,
+        key: &str,
+        current: &serde_json::Value,
+        value: &serde_json::Value,
+    ) -> Result<bool, AppError> {
+        match &self.backend {
+            Backend::Database(repo) => repo.replace(kind, key, current, value).await,
+            Backend::Memory(entries) => {
+                let mut entries = lock(entries);
+                match entries.get_mut(&(kind.to_string(), key.to_string())) {
+                    Some((stored, expires_at)) if *stored == *current && *expires_at > Utc::now() => {
+                        *stored = value.clone();
+                        Ok(true)
+                    }
+                    _ => Ok(false),
+                }
+            }
+        }
+    }
+}
+
+/// Lock the memory entries; a panic while holding the lock leaves them usable
+fn lock(entries: &Mutex<MemoryEntries>) -> std::sync::MutexGuard<'_, MemoryEntries> {
+    entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_store_takes_a_value_once() {
+        let store = ChallengeStore::memory();
+        let value = serde_json::json!({ "user_id": null });
+        store.put(CHALLENGE_KIND_WEBAUTHN, "abc", &value, 60).await.unwrap();
+
+        assert_eq!(store.get(CHALLENGE_KIND_WEBAUTHN, "abc").await.unwrap(), Some(value.clone()));
+        assert_eq!(store.take(CHALLENGE_KIND_WEBAUTHN, "abc").await.unwrap(), Some(value));
+        assert_eq!(store.take(CHALLENGE_KIND_WEBAUTHN, "abc").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_separates_kinds() {
+        let store = ChallengeStore::memory();
+        store.put(CHALLENGE_KIND_WEBAUTHN, "abc", &serde_json::json!(1), 60).await.unwrap();
+
+        assert_eq!(store.take(CHALLENGE_KIND_PAR, "abc").await.unwrap(), None);
+        assert!(store.get(CHALLENGE_KIND_WEBAUTHN, "abc").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_hides_expired_values() {
+        let store = ChallengeStore::memory();
+        store.put(CHALLENGE_KIND_PAR, "old", &serde_json::json!(1), -1).await.unwrap();
+
+        assert_eq!(store.get(CHALLENGE_KIND_PAR, "old").await.unwrap(), None);
+        assert_eq!(store.take(CHALLENGE_KIND_PAR, "old").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_clones_share_values() {
+        let store = ChallengeStore::memory();
+        let other_handle = store.clone();
+        store.put(CHALLENGE_KIND_PAR, "shared", &serde_json::json!(1), 60).await.unwrap();
+
+        assert!(other_handle.take(CHALLENGE_KIND_PAR, "shared").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_updates_in_place() {
+        let store = ChallengeStore::memory();
+        store.put(CHALLENGE_KIND_QR_LOGIN, "abc", &serde_json::json!({ "status": "pending" }), 60).await.unwrap();
+
+        let approve = |value: &mut serde_json::Value| {
+            if value["status"] != "pending" {
+                return false;
+            }
+            value["status"] = "approved".into();
+            true
+        };
+        assert!(store.update(CHALLENGE_KIND_QR_LOGIN, "abc", approve).await.unwrap());
+        assert!(!store.update(CHALLENGE_KIND_QR_LOGIN, "abc", approve).await.unwrap());
+        assert!(!store.update(CHALLENGE_KIND_QR_LOGIN, "missing", approve).await.unwrap());
+        assert_eq!(
+            store.get(CHALLENGE_KIND_QR_LOGIN, "abc").await.unwrap(),
+            Some(serde_json::json!({ "status": "approved" }))
+        );
+    }
+
+    #[test]
+    fn test_backend_parses_case_insensitively() {
+        assert_eq!("Memory".parse::<ChallengeStoreBackend>().unwrap(), ChallengeStoreBackend::Memory);
+        assert_eq!(" database ".parse::<ChallengeStoreBackend>().unwrap(), ChallengeStoreBackend::Database);
+        assert!("redis".parse::<ChallengeStoreBackend>().is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::MySqlPool;
 use uuid::Uuid;
@@ -14,6 +14,7 @@ pub struct ConsentInfo {
     pub client_id: Uuid,
     pub client_name: String,
     pub scopes: Vec<String>,
+    pub is_implicit: bool,
     pub granted_at: DateTime<Utc>,
 }
 
@@ -24,6 +25,8 @@ pub struct ConsentService {
     consent_repo: UserConsentRepository,
     client_repo: OAuthClientRepository,
     audit_repo: OAuthAuditLogRepository,
+    /// Days a stored consent stays valid (0 = never expires)
+    consent_ttl_days: i64,
 }
 
 impl ConsentService {
@@ -33,33 +36,129 @@ impl ConsentService {
             consent_repo: UserConsentRepository::new(pool.clone()),
             client_repo: OAuthClientRepository::new(pool.clone()),
             audit_repo: OAuthAuditLogRepository::new(pool),
+            consent_ttl_days: 0,
         }
     }
 
+    /// Treat consents older than this many days as expired (0 = never expires)
+    pub fn with_consent_ttl_days(mut self, consent_ttl_days: i64) -> Self {
+        self.consent_ttl_days = consent_ttl_days;
+        self
+    }
+
+    /// Check whether a stored consent is older than the consent TTL
+    pub fn is_expired(&self, consent: &UserConsent) -> bool {
+        self.consent_ttl_days > 0
+            && consent.granted_at + Duration::days(self.consent_ttl_days) <= Utc::now()
+    }
+
+    /// The user's consent for a client, unless it has expired
+    async fn current_consent(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+    ) -> Result<Option<UserConsent>, OAuthError> {
+        Ok(self
+            .consent_repo
+            .find_by_user_and_client(user_id, client_id)
+            .await?
+            .filter(|consent| !self.is_expired(consent)))
+    }
+
     /// Check if user has already consented to all requested scopes
     /// Requirements: 4.5 - Skip consent screen if user has previously consented
     /// 
     /// Returns true if user has consented to ALL requested scopes,
-    /// false if any scope is missing from previous consent
+    /// false if any scope is missing from previous consent or it has expired
     pub async fn has_consent(
         &self,
         user_id: Uuid,
         client_id: Uuid,
         scopes: &[String],
     ) -> Result<bool, OAuthError> {
-        self.consent_repo.has_consent(user_id, client_id, scopes).await
+        Ok(self
+            .current_consent(user_id, client_id)
+            .await?
+            .map(|consent| scopes.iter().all(|scope| consent.scopes.contains(scope)))
+            .unwrap_or(false))
+    }
+
+    /// Scopes the user has already granted a client (empty if none or expired)
+    pub async fn granted_scopes(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+    ) -> Result<Vec<String>, OAuthError> {
+        Ok(self
+            .current_consent(user_id, client_id)
+            .await?
+            .map(|consent| consent.scopes)
+            .unwrap_or_default())
+    }
+
+    /// Check whether the user's consent for a client exists but has expired
+    pub async fn consent_expired(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+    ) -> Result<bool, OAuthError> {
+        Ok(self
+            .consent_repo
+            .find_by_user_and_client(user_id, client_id)
+            .await?
+            .is_some_and(|consent| self.is_expired(&consent)))
     }
 
     /// Store user consent for a client with specific scopes
     /// Requirements: 4.3 - Store consent record with user_id, client_id, scopes, and timestamp
     /// Requirements: 9.5, 10.6 - Log consent events for audit
     /// 
-    /// If consent already exists, updates the scopes (upsert behavior)
+    /// If consent already exists, the scopes are added to the ones granted
+    /// before (incremental authorization), never replacing them. An expired
+    /// consent is replaced.
     pub async fn grant_consent(
         &self,
         user_id: Uuid,
         client_id: Uuid,
         scopes: &[String],
+    ) -> Result<UserConsent, OAuthError> {
+        self.store_consent(user_id, client_id, scopes, &[], false).await
+    }
+
+    /// Store consent for the scopes the user ticked on the consent screen
+    ///
+    /// `approved` is added to the grant like `grant_consent`; `declined`
+    /// scopes are withdrawn from it even if they were granted before.
+    pub async fn grant_partial_consent(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        approved: &[String],
+        declined: &[String],
+    ) -> Result<UserConsent, OAuthError> {
+        self.store_consent(user_id, client_id, approved, declined, false).await
+    }
+
+    /// Record an implicit grant for a first-party client that skips consent
+    /// 
+    /// No consent screen is shown, but the grant is still stored so it is
+    /// audited and shows up in connected apps where it can be revoked.
+    pub async fn grant_implicit_consent(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        scopes: &[String],
+    ) -> Result<UserConsent, OAuthError> {
+        self.store_consent(user_id, client_id, scopes, &[], true).await
+    }
+
+    async fn store_consent(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        scopes: &[String],
+        declined: &[String],
+        is_implicit: bool,
     ) -> Result<UserConsent, OAuthError> {
         // Verify client exists
         let client = self.client_repo.find_by_id(client_id).await?;
@@ -67,8 +166,18 @@ impl ConsentService {
             return Err(OAuthError::InvalidClient);
         }
 
+        // Merge with what was granted before so re-authorizing with more
+        // scopes only adds to the grant
+        let mut granted = self.granted_scopes(user_id, client_id).await?;
+        granted.retain(|scope| !declined.contains(scope));
+        for scope in scopes {
+            if !granted.contains(scope) {
+                granted.push(scope.clone());
+            }
+        }
+
         // Store or update consent
-        let consent = self.consent_repo.upsert(user_id, client_id, scopes).await?;
+        let consent = self.consent_repo.upsert(user_id, client_id, &granted, is_implicit).await?;
 
         // Log the consent granted event
         // Requirements: 9.5, 10.6
@@ -80,6 +189,9 @@ impl ConsentService {
                 None,
                 Some(serde_json::json!({
                     "scopes": scopes,
+                    "declined_scopes": declined,
+                    "granted_scopes": granted,
+                    "implicit": is_implicit,
                 })),
             )
             .await
@@ -157,6 +269,7 @@ impl ConsentService {
                     client_id: client.id,
                     client_name: client.name,
                     scopes: consent.scopes,
+                    is_implicit: consent.is_implicit,
                     granted_at: consent.granted_at,
                 });
             }
@@ -179,8 +292,10 @@ impl ConsentService {
     /// Requirements: 4.2, 4.5, 4.6
     /// 
     /// Returns true if consent screen should be shown:
-    /// - Internal apps never require consent (4.6)
-    /// - External apps require consent if user hasn't consented to all scopes (4.2, 4.5)
+    /// - Internal apps flagged `skip_consent` never require consent (4.6)
+    /// - Clients flagged `always_prompt_consent` always require it
+    /// - Other apps require consent if user hasn't consented to all scopes (4.2, 4.5)
+    ///   or the consent has expired
     pub async fn requires_consent(
         &self,
         user_id: Uuid,
@@ -191,10 +306,14 @@ impl ConsentService {
         let client = self.client_repo.find_by_id(client_id).await?;
         let client = client.ok_or(OAuthError::InvalidClient)?;
         
-        // Internal apps don't require consent (Requirement 4.6)
-        if client.is_internal {
+        // First-party internal apps don't require consent (Requirement 4.6)
+        if client.skips_consent() {
             return Ok(false);
         }
+
+        if client.always_prompt_consent {
+            return Ok(true);
+        }
         
         // Check if user has already consented to all requested scopes
         let has_consent = self.has_consent(user_id, client_id, scopes).await?;
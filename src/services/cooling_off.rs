@@ -0,0 +1,186 @@
+use chrono::{Duration, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::{AuditAction, CoolingOff};
+use crate::repositories::{CoolingOffRepository, UserRepository};
+use crate::services::{AuditService, EmailConfig, EmailService, MockEmailService, SecurityAlertType};
+use crate::utils::cooling_off::{CoolingOffPolicy, CoolingOffReason, HighRiskAction};
+use crate::utils::request_id::spawn_in_request;
+
+/// Service for the cooling-off period after credential changes
+///
+/// Changes start (or extend) the period; high-risk actions check it and
+/// are refused with `CoolingOff` while it runs. The user is emailed about
+/// both, and both are audited.
+#[derive(Clone)]
+pub struct CoolingOffService {
+    pool: MySqlPool,
+    repo: CoolingOffRepository,
+    audit_service: AuditService,
+    policy: CoolingOffPolicy,
+}
+
+impl CoolingOffService {
+    pub fn new(pool: MySqlPool, policy: CoolingOffPolicy) -> Self {
+        Self {
+            repo: CoolingOffRepository::new(pool.clone()),
+            audit_service: AuditService::new(pool.clone()),
+            pool,
+            policy,
+        }
+    }
+
+    /// Start the period after a credential change; None when disabled
+    pub async fn start(
+        &self,
+        user_id: Uuid,
+        reason: CoolingOffReason,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Result<Option<CoolingOff>, AuthError> {
+        if !self.policy.is_enabled() {
+            return Ok(None);
+        }
+
+        let ends_at = Utc::now() + Duration::hours(self.policy.hours);
+        let cooling_off = self.repo.start(user_id, reason, ends_at).await?;
+
+        let _ = self
+            .audit_service
+            .log_auth_event(
+                Some(user_id),
+                AuditAction::CoolingOffStarted,
+                ip_address,
+                user_agent,
+                Some(serde_json::json!({
+                    "reason": reason.as_str(),
+                    "ends_at": cooling_off.ends_at,
+                })),
+                true,
+            )
+            .await;
+
+        self.notify(user_id, None, SecurityAlertType::CoolingOffStarted, started_details(&cooling_off));
+
+        Ok(Some(cooling_off))
+    }
+
+    /// Also tell a former address of the user about the period
+    pub fn notify_address(&self, to: &str, cooling_off: &CoolingOff) {
+        self.notify(
+            cooling_off.user_id,
+            Some(to.to_string()),
+            SecurityAlertType::CoolingOffStarted,
+            started_details(cooling_off),
+        );
+    }
+
+    /// Refuse a high-risk action while the user's period runs
+    pub async fn check(
+        &self,
+        user_id: Uuid,
+        action: HighRiskAction,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Result<(), AuthError> {
+        if !self.policy.is_enabled() {
+            return Ok(());
+        }
+        let Some(cooling_off) = self.repo.find_active(user_id).await? else {
+            return Ok(());
+        };
+
+        let _ = self
+            .audit_service
+            .log_auth_event(
+                Some(user_id),
+                AuditAction::CoolingOffBlocked,
+                ip_address,
+                user_agent,
+                Some(serde_json::json!({
+                    "action": action.as_str(),
+                    "reason": cooling_off.reason.as_str(),
+                    "ends_at": cooling_off.ends_at,
+                })),
+                false,
+            )
+            .await;
+
+        self.notify(
+            user_id,
+            None,
+            SecurityAlertType::CoolingOffBlocked,
+            format!(
+                "Blocked: {}. High-risk actions are allowed again after {}.",
+                action.describe(),
+                cooling_off.ends_at.format("%Y-%m-%d %H:%M UTC")
+            ),
+        );
+
+        Err(AuthError::CoolingOff {
+            reason: cooling_off.reason.as_str(),
+            ends_at: cooling_off.ends_at,
+        })
+    }
+
+    /// Refuse a consent that connects a new client or grants a high-risk scope
+    ///
+    /// `granted` is what the user already granted the client, `approved`
+    /// what the consent adds.
+    pub async fn check_consent(
+        &self,
+        user_id: Uuid,
+        granted: &[String],
+        approved: &[String],
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Result<(), AuthError> {
+        if !self.policy.is_enabled() {
+            return Ok(());
+        }
+
+        match self.policy.consent_risk(granted, approved) {
+            Some(action) => self.check(user_id, action, ip_address, user_agent).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Email the user (or `to`) in the background
+    fn notify(&self, user_id: Uuid, to: Option<String>, alert_type: SecurityAlertType, details: String) {
+        let pool = self.pool.clone();
+
+        spawn_in_request(async move {
+            let to = match to {
+                Some(to) => to,
+                None => match UserRepository::new(pool.clone()).find_by_id(user_id).await {
+                    Ok(Some(user)) => user.email,
+                    _ => return,
+                },
+            };
+
+            let result = match EmailConfig::from_env().map(EmailService::new) {
+                Some(Ok(mailer)) => {
+                    mailer
+                        .with_bounce_list(pool)
+                        .send_security_alert(&to, alert_type, Some(&details))
+                        .await
+                }
+                Some(Err(e)) => Err(e),
+                None => MockEmailService::new().send_security_alert(&to, alert_type, Some(&details)).await,
+            };
+            if let Err(e) = result {
+                tracing::warn!("Failed to send cooling-off notice to user {}: {}", user_id, e);
+            }
+        });
+    }
+}
+
+fn started_details(cooling_off: &CoolingOff) -> String {
+    format!(
+        "Because {}, high-risk actions are paused until {}.",
+        cooling_off.reason.describe(),
+        cooling_off.ends_at.format("%Y-%m-%d %H:%M UTC")
+    )
+}
@@ -0,0 +1,85 @@
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::{DevicePlatform, UserDevice};
+use crate::repositories::DeviceRepository;
+use crate::services::SessionService;
+use crate::utils::secret::generate_secret;
+
+/// Service for native app device registration
+///
+/// Registering a device binds the caller's refresh-token session to it, which
+/// switches that session to the longer device lifetime on its next refresh.
+#[derive(Clone)]
+pub struct DeviceService {
+    repo: DeviceRepository,
+    session_service: SessionService,
+}
+
+impl DeviceService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self {
+            repo: DeviceRepository::new(pool.clone()),
+            session_service: SessionService::new(pool, 7),
+        }
+    }
+
+    /// Register a device and bind the session owning `refresh_token` to it
+    ///
+    /// A fresh signing secret is generated for the device; the app uses it to
+    /// sign push login approvals.
+    pub async fn register_device(
+        &self,
+        user_id: Uuid,
+        platform: &str,
+        device_name: Option<&str>,
+        push_token: Option<&str>,
+        refresh_token: &str,
+    ) -> Result<UserDevice, AuthError> {
+        let platform = DevicePlatform::from_str(platform).ok_or_else(|| {
+            AuthError::InvalidRequest(format!("Unsupported platform: {}", platform))
+        })?;
+
+        let session = self
+            .session_service
+            .validate_session(refresh_token)
+            .await?
+            .ok_or(AuthError::SessionNotFound)?;
+
+        if session.user_id != user_id {
+            return Err(AuthError::InvalidToken);
+        }
+
+        let device = self
+            .repo
+            .create(user_id, platform.as_str(), device_name, push_token, &generate_secret())
+            .await?;
+
+        self.session_service.bind_device(session.id, device.id).await?;
+
+        Ok(device)
+    }
+
+    /// List active devices for a user
+    pub async fn list_devices(&self, user_id: Uuid) -> Result<Vec<UserDevice>, AuthError> {
+        self.repo.list_active_by_user(user_id).await
+    }
+
+    /// Revoke a device and every session bound to it
+    /// Returns the number of sessions revoked
+    pub async fn revoke_device(&self, user_id: Uuid, device_id: Uuid) -> Result<u64, AuthError> {
+        let device = self
+            .repo
+            .find_by_id(device_id)
+            .await?
+            .ok_or(AuthError::SessionNotFound)?;
+
+        if device.user_id != user_id {
+            return Err(AuthError::InsufficientScope);
+        }
+
+        self.repo.revoke(device_id).await?;
+        self.session_service.revoke_device_sessions(device_id).await
+    }
+}
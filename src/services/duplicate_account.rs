@@ -0,0 +1,172 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::UserManagementError;
+use crate::models::{DuplicateMatchType, User};
+use crate::repositories::{UserMatchKeyRepository, UserRepository};
+use crate::utils::account_match::{normalize_email, normalize_phone};
+use crate::utils::field_crypto::{open_optional, EncryptedColumn};
+
+/// Account summary included in a duplicate group
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateAccountUser {
+    pub id: Uuid,
+    pub email: String,
+    pub name: Option<String>,
+    pub phone: Option<String>,
+    pub is_active: bool,
+    pub email_verified: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<User> for DuplicateAccountUser {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id,
+            email: user.email,
+            name: user.name,
+            phone: user.phone,
+            is_active: user.is_active,
+            email_verified: user.email_verified,
+            created_at: user.created_at,
+        }
+    }
+}
+
+/// Accounts that probably belong to the same person
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateAccountReportGroup {
+    pub match_type: DuplicateMatchType,
+    pub match_key: String,
+    /// Oldest account first - the usual merge target
+    pub users: Vec<DuplicateAccountUser>,
+}
+
+/// Report of probable duplicate accounts, input for account merging
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateAccountReport {
+    pub generated_at: DateTime<Utc>,
+    pub groups: Vec<DuplicateAccountReportGroup>,
+}
+
+/// Service detecting probable duplicate accounts
+///
+/// Email and phone matches are read from precomputed match keys, which the
+/// background duplicate scan keeps up to date in batches; WebAuthn matches
+/// are computed directly from registered credentials.
+#[derive(Clone)]
+pub struct DuplicateAccountService {
+    user_repo: UserRepository,
+    match_key_repo: UserMatchKeyRepository,
+}
+
+impl DuplicateAccountService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self {
+            user_repo: UserRepository::new(pool.clone()),
+            match_key_repo: UserMatchKeyRepository::new(pool),
+        }
+    }
+
+    /// Compute match keys for one batch of new or changed users
+    ///
+    /// Returns the number of users processed; fewer than `batch_size` means
+    /// the backlog is drained.
+    pub async fn process_batch(&self, batch_size: i64) -> Result<usize, UserManagementError> {
+        let pending = self
+            .match_key_repo
+            .find_pending(batch_size)
+            .await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?;
+
+        for row in &pending {
+            let user_id = match Uuid::parse_str(&row.id) {
+                Ok(id) => id,
+                Err(_) => continue,
+            };
+
+            let email = normalize_email(&row.email);
+            let phone = open_optional(EncryptedColumn::UserPhone, &row.id, row.phone.clone())
+                .as_deref()
+                .and_then(normalize_phone);
+
+            self.match_key_repo
+                .upsert(user_id, &email, phone.as_deref())
+                .await
+                .map_err(|e| UserManagementError::InternalError(e.into()))?;
+        }
+
+        Ok(pending.len())
+    }
+
+    /// Build the duplicate-account report
+    ///
+    /// `limit` caps the number of groups returned per match type.
+    pub async fn build_report(
+        &self,
+        match_type: Option<DuplicateMatchType>,
+        limit: i64,
+    ) -> Result<DuplicateAccountReport, UserManagementError> {
+        let wanted = |t: DuplicateMatchType| match_type.map(|m| m == t).unwrap_or(true);
+
+        let mut groups = Vec::new();
+        if wanted(DuplicateMatchType::Email) {
+            groups.extend(self.match_key_repo.find_email_groups(limit).await
+                .map_err(|e| UserManagementError::InternalError(e.into()))?);
+        }
+        if wanted(DuplicateMatchType::Phone) {
+            groups.extend(self.match_key_repo.find_phone_groups(limit).await
+                .map_err(|e| UserManagementError::InternalError(e.into()))?);
+        }
+        if wanted(DuplicateMatchType::Webauthn) {
+            groups.extend(self.match_key_repo.find_webauthn_groups(limit).await
+                .map_err(|e| UserManagementError::InternalError(e.into()))?);
+        }
+
+        let mut users: HashMap<Uuid, Option<DuplicateAccountUser>> = HashMap::new();
+        let mut report_groups = Vec::with_capacity(groups.len());
+
+        for group in groups {
+            let mut members = Vec::with_capacity(group.user_ids.len());
+            for user_id in group.user_ids {
+                let user = match users.entry(user_id) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        let user = self
+                            .user_repo
+                            .find_by_id(user_id)
+                            .await
+                            .map_err(|e| UserManagementError::InternalError(e.into()))?
+                            .map(DuplicateAccountUser::from);
+                        entry.insert(user)
+                    }
+                };
+                if let Some(user) = user {
+                    members.push(user.clone());
+                }
+            }
+
+            // Accounts deleted since the keys were computed can leave a single member
+            if members.len() < 2 {
+                continue;
+            }
+
+            members.sort_by_key(|u| u.created_at);
+            report_groups.push(DuplicateAccountReportGroup {
+                match_type: group.match_type,
+                match_key: group.match_key,
+                users: members,
+            });
+        }
+
+        Ok(DuplicateAccountReport {
+            generated_at: Utc::now(),
+            groups: report_groups,
+        })
+    }
+}
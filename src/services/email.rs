@@ -3,10 +3,16 @@ use lettre::{
     transport::smtp::authentication::Credentials,
     AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
 };
+use sqlx::MySqlPool;
 use std::sync::Arc;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::error::AuthError;
+use crate::models::AppDigestStats;
+use crate::repositories::EmailBounceRepository;
+use crate::utils::chaos;
+use crate::utils::dormancy::DormantAction;
+use crate::utils::email::to_ascii_address;
 
 /// Email configuration
 #[derive(Clone, Debug)]
@@ -22,6 +28,11 @@ pub struct EmailConfig {
 }
 
 impl EmailConfig {
+    /// Product name shown in emails (`APP_NAME`), also without SMTP configured
+    pub fn app_name() -> String {
+        std::env::var("APP_NAME").unwrap_or_else(|_| "Auth Server".to_string())
+    }
+
     pub fn from_env() -> Option<Self> {
         let smtp_host = std::env::var("SMTP_HOST").ok()?;
         let smtp_port = std::env::var("SMTP_PORT")
@@ -32,7 +43,7 @@ impl EmailConfig {
         let smtp_password = std::env::var("SMTP_PASSWORD").ok()?;
         let from_email = std::env::var("SMTP_FROM_EMAIL").ok()?;
         let from_name = std::env::var("SMTP_FROM_NAME").unwrap_or_else(|_| "Auth Server".to_string());
-        let app_name = std::env::var("APP_NAME").unwrap_or_else(|_| "Auth Server".to_string());
+        let app_name = Self::app_name();
         let app_url = std::env::var("APP_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
 
         Some(Self {
@@ -53,6 +64,7 @@ impl EmailConfig {
 pub struct EmailService {
     config: Arc<EmailConfig>,
     mailer: Arc<AsyncSmtpTransport<Tokio1Executor>>,
+    bounces: Option<EmailBounceRepository>,
 }
 
 impl EmailService {
@@ -69,16 +81,37 @@ impl EmailService {
         Ok(Self {
             config: Arc::new(config),
             mailer: Arc::new(mailer),
+            bounces: None,
         })
     }
 
+    /// Skip addresses that hard bounced or complained
+    pub fn with_bounce_list(mut self, pool: MySqlPool) -> Self {
+        self.bounces = Some(EmailBounceRepository::new(pool));
+        self
+    }
+
     /// Send an email
+    ///
+    /// Addresses on the bounce list are skipped without an error.
     async fn send_email(&self, to: &str, subject: &str, html_body: &str) -> Result<(), AuthError> {
+        if chaos::email_fails() {
+            return Err(AuthError::InternalError(anyhow::anyhow!(chaos::INJECTED_FAILURE)));
+        }
+
+        if let Some(bounces) = &self.bounces {
+            if bounces.is_bounced(to).await? {
+                warn!("Not emailing {}: address bounced or complained", to);
+                return Ok(());
+            }
+        }
+
         let from: Mailbox = format!("{} <{}>", self.config.from_name, self.config.from_email)
             .parse()
             .map_err(|e: lettre::address::AddressError| AuthError::InternalError(e.into()))?;
 
-        let to_mailbox: Mailbox = to
+        // Internationalized domains are sent in punycode form
+        let to_mailbox: Mailbox = to_ascii_address(to)
             .parse()
             .map_err(|e: lettre::address::AddressError| AuthError::InternalError(e.into()))?;
 
@@ -150,6 +183,57 @@ impl EmailService {
         self.send_email(to, &format!("Reset your {} password", self.config.app_name), &html).await
     }
 
+    /// Tell an account owner that someone tried to register with their email
+    pub async fn send_registration_attempt(&self, to: &str) -> Result<(), AuthError> {
+        let login_url = format!("{}/login", self.config.app_url);
+        let reset_url = format!("{}/forgot-password", self.config.app_url);
+
+        let html = format!(
+            r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <style>
+        body {{ font-family: Arial, sans-serif; line-height: 1.6; color: #333; }}
+        .container {{ max-width: 600px; margin: 0 auto; padding: 20px; }}
+        .header {{ background: #4F46E5; color: white; padding: 20px; text-align: center; }}
+        .content {{ padding: 30px; background: #f9fafb; }}
+        .button {{ display: inline-block; padding: 12px 24px; background: #4F46E5; color: white; text-decoration: none; border-radius: 6px; margin: 20px 0; }}
+        .footer {{ padding: 20px; text-align: center; color: #666; font-size: 12px; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="header">
+            <h1>{app_name}</h1>
+        </div>
+        <div class="content">
+            <h2>You already have an account</h2>
+            <p>Someone tried to create a new account with this email address, but it is already registered.</p>
+            <p>If this was you, sign in instead:</p>
+            <p style="text-align: center;">
+                <a href="{login_url}" class="button">Sign In</a>
+            </p>
+            <p>Forgot your password? <a href="{reset_url}">Reset it here</a>.</p>
+            <p>If this wasn't you, you can safely ignore this email. Your account has not been changed.</p>
+        </div>
+        <div class="footer">
+            <p>© {year} {app_name}. All rights reserved.</p>
+        </div>
+    </div>
+</body>
+</html>
+"#,
+            app_name = self.config.app_name,
+            login_url = login_url,
+            reset_url = reset_url,
+            year = chrono::Utc::now().format("%Y")
+        );
+
+        self.send_email(to, &format!("[{}] Sign-up attempt with your email", self.config.app_name), &html).await
+    }
+
     /// Send email verification email
     pub async fn send_email_verification(&self, to: &str, verification_token: &str) -> Result<(), AuthError> {
         let verify_url = format!("{}/verify-email?token={}", self.config.app_url, verification_token);
@@ -284,6 +368,18 @@ impl EmailService {
                 "Suspicious Activity Detected",
                 "We detected suspicious activity on your account.",
             ),
+            SecurityAlertType::ImpossibleTravel => (
+                "Sign-In From an Unusual Location",
+                "Your account was signed in to from a place too far from your previous sign-in to have travelled there in the time between them.",
+            ),
+            SecurityAlertType::CoolingOffStarted => (
+                "High-Risk Actions Paused",
+                "After a recent change to your account's credentials, high-risk actions such as creating API keys and connecting new applications are paused for a while.",
+            ),
+            SecurityAlertType::CoolingOffBlocked => (
+                "High-Risk Action Blocked",
+                "A high-risk action on your account was blocked because its credentials were changed recently. If this wasn't you, reset your password now.",
+            ),
         };
 
         let details_html = details
@@ -343,6 +439,350 @@ impl EmailService {
         self.send_email(to, &format!("[{}] {}", self.config.app_name, title), &html).await
     }
 
+    /// Warn an OAuth client owner that the client secret is about to expire
+    pub async fn send_client_secret_expiring(
+        &self,
+        to: &str,
+        client_name: &str,
+        client_id: &str,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), AuthError> {
+        // Client names are chosen by their owners
+        let client_name = client_name
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;");
+
+        let html = format!(
+            r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <style>
+        body {{ font-family: Arial, sans-serif; line-height: 1.6; color: #333; }}
+        .container {{ max-width: 600px; margin: 0 auto; padding: 20px; }}
+        .header {{ background: #d97706; color: white; padding: 20px; text-align: center; }}
+        .content {{ padding: 30px; background: #f9fafb; }}
+        .alert {{ background: #fffbeb; border: 1px solid #fde68a; padding: 15px; border-radius: 6px; margin: 20px 0; }}
+        .footer {{ padding: 20px; text-align: center; color: #666; font-size: 12px; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="header">
+            <h1>Client Secret Expiring</h1>
+        </div>
+        <div class="content">
+            <p>The secret of your OAuth client <strong>{client_name}</strong> is about to expire.</p>
+            <div class="alert">
+                <p><strong>Client ID:</strong> {client_id}</p>
+                <p><strong>Expires:</strong> {expires_at}</p>
+            </div>
+            <p>After this date token requests using the current secret are rejected with <code>client_secret_expired</code>.
+               Rotate the secret from the client settings and deploy the new one before then.</p>
+        </div>
+        <div class="footer">
+            <p>© {year} {app_name}. All rights reserved.</p>
+        </div>
+    </div>
+</body>
+</html>
+"#,
+            client_name = client_name,
+            client_id = client_id,
+            expires_at = expires_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            app_name = self.config.app_name,
+            year = chrono::Utc::now().format("%Y")
+        );
+
+        self.send_email(
+            to,
+            &format!("[{}] Client secret expiring", self.config.app_name),
+            &html,
+        )
+        .await
+    }
+
+    /// Warn a user that their inactive account is about to become dormant
+    pub async fn send_dormant_account_notice(
+        &self,
+        to: &str,
+        action: DormantAction,
+        last_activity_at: chrono::DateTime<chrono::Utc>,
+        action_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), AuthError> {
+        let consequence = match action {
+            DormantAction::Deactivate => {
+                "your account will be deactivated and only an administrator will be able to reactivate it"
+            }
+            _ => "you will have to verify your email address again before you can sign in with your password",
+        };
+
+        let html = format!(
+            r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <style>
+        body {{ font-family: Arial, sans-serif; line-height: 1.6; color: #333; }}
+        .container {{ max-width: 600px; margin: 0 auto; padding: 20px; }}
+        .header {{ background: #d97706; color: white; padding: 20px; text-align: center; }}
+        .content {{ padding: 30px; background: #f9fafb; }}
+        .alert {{ background: #fffbeb; border: 1px solid #fde68a; padding: 15px; border-radius: 6px; margin: 20px 0; }}
+        .footer {{ padding: 20px; text-align: center; color: #666; font-size: 12px; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="header">
+            <h1>Inactive Account</h1>
+        </div>
+        <div class="content">
+            <p>You have not signed in to your {app_name} account for a while.</p>
+            <div class="alert">
+                <p><strong>Last activity:</strong> {last_activity_at}</p>
+                <p><strong>Inactive from:</strong> {action_at}</p>
+            </div>
+            <p>If you don't sign in before then, {consequence}.
+               Signing in once is enough to keep your account as it is.</p>
+        </div>
+        <div class="footer">
+            <p>© {year} {app_name}. All rights reserved.</p>
+        </div>
+    </div>
+</body>
+</html>
+"#,
+            consequence = consequence,
+            last_activity_at = last_activity_at.format("%Y-%m-%d"),
+            action_at = action_at.format("%Y-%m-%d %H:%M UTC"),
+            app_name = self.config.app_name,
+            year = chrono::Utc::now().format("%Y")
+        );
+
+        self.send_email(
+            to,
+            &format!("[{}] Your account is inactive", self.config.app_name),
+            &html,
+        )
+        .await
+    }
+
+    /// Send a one-time sign-in code
+    pub async fn send_one_time_code(&self, to: &str, code: &str, expires_minutes: i64) -> Result<(), AuthError> {
+        let html = format!(
+            r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <style>
+        body {{ font-family: Arial, sans-serif; line-height: 1.6; color: #333; }}
+        .container {{ max-width: 600px; margin: 0 auto; padding: 20px; }}
+        .header {{ background: #2563eb; color: white; padding: 20px; text-align: center; }}
+        .content {{ padding: 30px; background: #f9fafb; }}
+        .code {{ font-size: 32px; font-weight: bold; letter-spacing: 8px; text-align: center; padding: 20px; background: white; border-radius: 6px; margin: 20px 0; }}
+        .footer {{ padding: 20px; text-align: center; color: #666; font-size: 12px; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="header">
+            <h1>Your Sign-In Code</h1>
+        </div>
+        <div class="content">
+            <p>Enter this code to finish signing in to {app_name}:</p>
+            <div class="code">{code}</div>
+            <p>The code expires in {expires_minutes} minutes and can only be used once.</p>
+            <p>If you didn't try to sign in, someone may know your email address or password.
+               Don't share this code with anyone.</p>
+        </div>
+        <div class="footer">
+            <p>© {year} {app_name}. All rights reserved.</p>
+        </div>
+    </div>
+</body>
+</html>
+"#,
+            code = code,
+            expires_minutes = expires_minutes,
+            app_name = self.config.app_name,
+            year = chrono::Utc::now().format("%Y")
+        );
+
+        self.send_email(
+            to,
+            &format!("[{}] {} is your sign-in code", self.config.app_name, code),
+            &html,
+        )
+        .await
+    }
+
+    /// Send a single-use sign-in link
+    pub async fn send_magic_link(&self, to: &str, token: &str, expires_minutes: i64) -> Result<(), AuthError> {
+        let login_url = format!("{}/magic-link?token={}", self.config.app_url, token);
+
+        let html = format!(
+            r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <style>
+        body {{ font-family: Arial, sans-serif; line-height: 1.6; color: #333; }}
+        .container {{ max-width: 600px; margin: 0 auto; padding: 20px; }}
+        .header {{ background: #2563eb; color: white; padding: 20px; text-align: center; }}
+        .content {{ padding: 30px; background: #f9fafb; }}
+        .button {{ display: inline-block; padding: 12px 24px; background: #2563eb; color: white; text-decoration: none; border-radius: 6px; margin: 20px 0; }}
+        .footer {{ padding: 20px; text-align: center; color: #666; font-size: 12px; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="header">
+            <h1>Sign In to {app_name}</h1>
+        </div>
+        <div class="content">
+            <p>Click the button below to sign in to {app_name}:</p>
+            <p style="text-align: center;">
+                <a href="{login_url}" class="button">Sign In</a>
+            </p>
+            <p>Or copy and paste this link into your browser:</p>
+            <p style="word-break: break-all; color: #2563eb;">{login_url}</p>
+            <p>The link expires in {expires_minutes} minutes and can only be used once.</p>
+            <p>If you didn't try to sign in, you can ignore this email. Don't forward it to anyone.</p>
+        </div>
+        <div class="footer">
+            <p>© {year} {app_name}. All rights reserved.</p>
+        </div>
+    </div>
+</body>
+</html>
+"#,
+            login_url = login_url,
+            expires_minutes = expires_minutes,
+            app_name = self.config.app_name,
+            year = chrono::Utc::now().format("%Y")
+        );
+
+        self.send_email(to, &format!("[{}] Your sign-in link", self.config.app_name), &html).await
+    }
+
+    /// Send an app owner the weekly digest of their app
+    pub async fn send_app_digest(
+        &self,
+        to: &str,
+        app_name: &str,
+        period_start: chrono::DateTime<chrono::Utc>,
+        period_end: chrono::DateTime<chrono::Utc>,
+        stats: &AppDigestStats,
+    ) -> Result<(), AuthError> {
+        // App names are chosen by their owners
+        let escape = |s: &str| {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+        };
+        let app = escape(app_name);
+
+        let spike_html = if stats.failed_sign_in_spike {
+            format!(
+                r#"<div class="alert"><p><strong>Unusual number of failed sign-ins:</strong> {} this week against an average of {:.0} over the previous weeks.</p></div>"#,
+                stats.failed_sign_ins, stats.failed_sign_ins_baseline
+            )
+        } else {
+            String::new()
+        };
+
+        let keys_html = if stats.expiring_api_keys.is_empty() {
+            String::new()
+        } else {
+            let items = stats
+                .expiring_api_keys
+                .iter()
+                .map(|k| {
+                    format!(
+                        "<li>{} (<code>{}</code>) expires {}</li>",
+                        escape(&k.name),
+                        escape(&k.key_prefix),
+                        k.expires_at.format("%Y-%m-%d")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("");
+            format!(
+                r#"<div class="alert"><p><strong>API keys expiring soon:</strong></p><ul>{}</ul></div>"#,
+                items
+            )
+        };
+
+        let html = format!(
+            r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <style>
+        body {{ font-family: Arial, sans-serif; line-height: 1.6; color: #333; }}
+        .container {{ max-width: 600px; margin: 0 auto; padding: 20px; }}
+        .header {{ background: #2563eb; color: white; padding: 20px; text-align: center; }}
+        .content {{ padding: 30px; background: #f9fafb; }}
+        .alert {{ background: #fffbeb; border: 1px solid #fde68a; padding: 15px; border-radius: 6px; margin: 20px 0; }}
+        table {{ width: 100%; border-collapse: collapse; }}
+        td {{ padding: 8px 0; border-bottom: 1px solid #e5e7eb; }}
+        .footer {{ padding: 20px; text-align: center; color: #666; font-size: 12px; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="header">
+            <h1>Weekly Digest</h1>
+        </div>
+        <div class="content">
+            <p>Here is what happened in <strong>{app}</strong> from {period_start} to {period_end}.</p>
+            {spike_html}
+            <table>
+                <tr><td>New users</td><td><strong>{new_users}</strong></td></tr>
+                <tr><td>Banned users</td><td><strong>{bans}</strong></td></tr>
+                <tr><td>Failed webhook deliveries</td><td><strong>{failed_webhook_deliveries}</strong></td></tr>
+                <tr><td>Failed sign-ins</td><td><strong>{failed_sign_ins}</strong></td></tr>
+            </table>
+            {keys_html}
+            <p>You receive this email because the weekly digest is enabled for this app.
+               You can turn it off in the app settings.</p>
+        </div>
+        <div class="footer">
+            <p>© {year} {app_name}. All rights reserved.</p>
+        </div>
+    </div>
+</body>
+</html>
+"#,
+            app = app,
+            period_start = period_start.format("%Y-%m-%d"),
+            period_end = (period_end - chrono::Duration::days(1)).format("%Y-%m-%d"),
+            spike_html = spike_html,
+            new_users = stats.new_users,
+            bans = stats.bans,
+            failed_webhook_deliveries = stats.failed_webhook_deliveries,
+            failed_sign_ins = stats.failed_sign_ins,
+            keys_html = keys_html,
+            app_name = self.config.app_name,
+            year = chrono::Utc::now().format("%Y")
+        );
+
+        self.send_email(
+            to,
+            &format!("[{}] Weekly digest for {}", self.config.app_name, app_name),
+            &html,
+        )
+        .await
+    }
+
     /// Send MFA backup codes email
     pub async fn send_backup_codes(&self, to: &str, codes: &[String]) -> Result<(), AuthError> {
         let codes_html = codes
@@ -407,6 +847,49 @@ impl EmailService {
 
         self.send_email(to, &format!("[{}] Your Backup Codes", self.config.app_name), &html).await
     }
+
+    /// Send an admin broadcast
+    ///
+    /// `body_html` is the rendered, already escaped broadcast template.
+    pub async fn send_broadcast(&self, to: &str, subject: &str, body_html: &str) -> Result<(), AuthError> {
+        let html = format!(
+            r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <style>
+        body {{ font-family: Arial, sans-serif; line-height: 1.6; color: #333; }}
+        .container {{ max-width: 600px; margin: 0 auto; padding: 20px; }}
+        .header {{ background: #4F46E5; color: white; padding: 20px; text-align: center; }}
+        .content {{ padding: 30px; background: #f9fafb; }}
+        .footer {{ padding: 20px; text-align: center; color: #666; font-size: 12px; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="header">
+            <h1>{app_name}</h1>
+        </div>
+        <div class="content">
+            <p>{body_html}</p>
+        </div>
+        <div class="footer">
+            <p>You are receiving this email because you have an account with {app_name}.
+               Contact support to stop receiving announcements.</p>
+            <p>© {year} {app_name}. All rights reserved.</p>
+        </div>
+    </div>
+</body>
+</html>
+"#,
+            app_name = self.config.app_name,
+            body_html = body_html,
+            year = chrono::Utc::now().format("%Y")
+        );
+
+        self.send_email(to, subject, &html).await
+    }
 }
 
 /// Types of security alerts
@@ -418,6 +901,9 @@ pub enum SecurityAlertType {
     MfaDisabled,
     AccountLocked,
     SuspiciousActivity,
+    ImpossibleTravel,
+    CoolingOffStarted,
+    CoolingOffBlocked,
 }
 
 /// Mock email service for development/testing
@@ -434,6 +920,11 @@ impl MockEmailService {
         Ok(())
     }
 
+    pub async fn send_registration_attempt(&self, to: &str) -> Result<(), AuthError> {
+        info!("[MOCK EMAIL] Registration attempt notice to {}", to);
+        Ok(())
+    }
+
     pub async fn send_email_verification(&self, to: &str, verification_token: &str) -> Result<(), AuthError> {
         info!("[MOCK EMAIL] Email verification to {}: token={}", to, verification_token);
         Ok(())
@@ -454,10 +945,91 @@ impl MockEmailService {
         Ok(())
     }
 
+    pub async fn send_client_secret_expiring(
+        &self,
+        to: &str,
+        client_name: &str,
+        client_id: &str,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), AuthError> {
+        info!(
+            "[MOCK EMAIL] Client secret expiring to {}: client={} ({}), expires_at={}",
+            to, client_name, client_id, expires_at
+        );
+        Ok(())
+    }
+
+    pub async fn send_dormant_account_notice(
+        &self,
+        to: &str,
+        action: DormantAction,
+        last_activity_at: chrono::DateTime<chrono::Utc>,
+        action_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), AuthError> {
+        info!(
+            "[MOCK EMAIL] Dormant account notice to {}: action={}, last_activity_at={}, action_at={}",
+            to,
+            action.as_str(),
+            last_activity_at,
+            action_at
+        );
+        Ok(())
+    }
+
+    pub async fn send_one_time_code(&self, to: &str, code: &str, expires_minutes: i64) -> Result<(), AuthError> {
+        info!(
+            "[MOCK EMAIL] One-time code to {}: code={}, expires_in={}m",
+            to, code, expires_minutes
+        );
+        Ok(())
+    }
+
+    pub async fn send_magic_link(&self, to: &str, token: &str, expires_minutes: i64) -> Result<(), AuthError> {
+        info!(
+            "[MOCK EMAIL] Magic link to {}: token={}, expires_in={}m",
+            to, token, expires_minutes
+        );
+        Ok(())
+    }
+
+    pub async fn send_app_digest(
+        &self,
+        to: &str,
+        app_name: &str,
+        period_start: chrono::DateTime<chrono::Utc>,
+        period_end: chrono::DateTime<chrono::Utc>,
+        stats: &AppDigestStats,
+    ) -> Result<(), AuthError> {
+        info!(
+            "[MOCK EMAIL] App digest to {}: app={}, period={}..{}, new_users={}, bans={}, failed_webhooks={}, expiring_keys={}, failed_sign_ins={}, spike={}",
+            to,
+            app_name,
+            period_start,
+            period_end,
+            stats.new_users,
+            stats.bans,
+            stats.failed_webhook_deliveries,
+            stats.expiring_api_keys.len(),
+            stats.failed_sign_ins,
+            stats.failed_sign_in_spike
+        );
+        Ok(())
+    }
+
     pub async fn send_backup_codes(&self, to: &str, codes: &[String]) -> Result<(), AuthError> {
         info!("[MOCK EMAIL] Backup codes to {}: {} codes", to, codes.len());
         Ok(())
     }
+
+    pub async fn send_broadcast(&self, to: &str, subject: &str, body_html: &str) -> Result<(), AuthError> {
+        info!(
+            "[MOCK EMAIL] Broadcast to {}: subject={}, {} bytes",
+            to,
+            subject,
+            body_html.len()
+        );
+        Ok(())
+    }
 }
 
 impl Default for MockEmailService {
@@ -0,0 +1,262 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::repositories::{AppRepository, OAuthClientRepository, UserRepository};
+use crate::services::oauth::SUPPORTED_GRANT_TYPES;
+use crate::utils::chaos;
+use crate::utils::client_fingerprint::FingerprintMode;
+
+/// Counts rounded down to a power of ten, so reports don't reveal exact sizes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstanceCounts {
+    pub users: String,
+    pub apps: String,
+    pub oauth_clients: String,
+}
+
+/// What this instance runs, as sent by the usage heartbeat
+///
+/// Contains no hostnames, URLs, user data or exact counts.
+#[derive(Debug, Clone, Serialize)]
+pub struct InstanceReport {
+    /// Truncated SHA-256 of INSTANCE_ID; stable across restarts, but not the hostname
+    pub instance: String,
+    pub version: &'static str,
+    /// Optional features and whether they are enabled
+    pub features: BTreeMap<&'static str, bool>,
+    pub counts: InstanceCounts,
+    pub reported_at: DateTime<Utc>,
+}
+
+/// Bucket a count: "0", then "1+", "10+", "100+", ...
+fn coarse_count(count: u64) -> String {
+    if count == 0 {
+        return "0".to_string();
+    }
+    format!("{}+", 10u64.pow(count.ilog10()))
+}
+
+/// Stable identifier of an instance that does not disclose its name
+fn anonymous_instance_id(instance_id: &str) -> String {
+    hex::encode(&Sha256::digest(instance_id.as_bytes())[..8])
+}
+
+/// Optional features, derived from the configuration
+fn enabled_features(config: &Config) -> BTreeMap<&'static str, bool> {
+    BTreeMap::from([
+        ("field_encryption", !config.field_encryption_keys.is_empty()),
+        ("mfa_require_encrypted_secrets", config.mfa_require_encrypted_secrets),
+        ("oauth_strict", config.oauth_strict),
+        ("login_require_verified_email", config.login_require_verified_email),
+        ("enumeration_safe_auth", config.enumeration_safe_auth),
+        ("refresh_fingerprint", config.refresh_fingerprint_mode != FingerprintMode::Off),
+        ("client_secret_expiry", config.client_secret_max_age_days > 0),
+        ("dormant_accounts", config.dormant_account_days > 0),
+        ("mfa_trusted_devices", config.mfa_trusted_device_days > 0),
+        ("network_zones", !config.network_zones.is_empty()),
+        ("impossible_travel", !config.geoip_database.is_empty() && config.impossible_travel_max_kmh > 0.0),
+        ("cooling_off", config.cooling_off_hours > 0),
+        ("db_warmup", config.db_warmup_enabled),
+        ("heartbeat", !config.heartbeat_url.is_empty()),
+        ("provisioning_nats", !config.provisioning_nats_url.is_empty()),
+        ("email_bounce_webhook", !config.email_webhook_secret.is_empty()),
+        ("metrics", !config.metrics_token.is_empty()),
+        ("admin_approvals", !config.admin_approval_actions.is_empty()),
+        ("chaos", chaos::ENABLED),
+    ])
+}
+
+/// Version of the `/.well-known/auth-server` document
+///
+/// Within a version fields and values are only ever added; removing or
+/// changing the meaning of one bumps it.
+pub const CAPABILITIES_SCHEMA_VERSION: u32 = 1;
+
+/// Sign-in flows of the first-party API (apps decide which their users may use)
+const SIGN_IN_FLOWS: &[&str] = &["password", "email_code", "magic_link", "passkey", "qr_login", "sso_session"];
+
+/// Algorithms signing the JWTs a deployment issues
+const TOKEN_SIGNING_ALG_VALUES: &[&str] = &["RS256"];
+
+/// Second factors users can enroll
+const MFA_METHODS: &[&str] = &["totp", "sms", "email", "push", "webauthn", "backup_code"];
+
+/// Features that change what clients and resource servers see; the rest
+/// only concern operators and are left out of the public document
+const PUBLIC_FEATURES: &[&str] = &[
+    "oauth_strict",
+    "login_require_verified_email",
+    "enumeration_safe_auth",
+    "refresh_fingerprint",
+    "client_secret_expiry",
+    "dormant_accounts",
+    "mfa_trusted_devices",
+    "network_zones",
+    "impossible_travel",
+    "cooling_off",
+];
+
+fn public_features(features: BTreeMap<&'static str, bool>) -> BTreeMap<&'static str, bool> {
+    features
+        .into_iter()
+        .filter(|(feature, _)| PUBLIC_FEATURES.contains(feature))
+        .collect()
+}
+
+/// Flows a deployment supports
+#[derive(Debug, Clone, Serialize)]
+pub struct SupportedFlows {
+    pub sign_in: &'static [&'static str],
+    pub mfa: &'static [&'static str],
+    pub oauth_grant_types: &'static [&'static str],
+}
+
+/// Format of a token; `opaque` ones must not be parsed by clients
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenFormat {
+    pub format: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_alg_values: Option<&'static [&'static str]>,
+}
+
+impl TokenFormat {
+    const fn jwt() -> Self {
+        Self { format: "jwt", signing_alg_values: Some(TOKEN_SIGNING_ALG_VALUES) }
+    }
+
+    const fn opaque() -> Self {
+        Self { format: "opaque", signing_alg_values: None }
+    }
+}
+
+/// Formats of the tokens a deployment issues
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenFormats {
+    pub access_token: TokenFormat,
+    /// How the apps of a user appear in first-party access tokens
+    pub access_token_claims_mode: &'static str,
+    /// First-party refresh tokens from `/auth/login` and `/auth/refresh`
+    pub refresh_token: TokenFormat,
+    /// Refresh tokens issued by `/oauth/token`
+    pub oauth_refresh_token: TokenFormat,
+    pub id_token: TokenFormat,
+    pub api_key: TokenFormat,
+}
+
+/// Public document served at `GET /.well-known/auth-server`
+///
+/// Lets SDKs and resource servers adapt to a deployment at runtime. See
+/// [`CAPABILITIES_SCHEMA_VERSION`] for the compatibility rules; clients
+/// should ignore fields and flags they don't know and treat a missing flag
+/// as disabled.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerCapabilities {
+    pub schema_version: u32,
+    pub server: &'static str,
+    pub version: &'static str,
+    pub issuer: String,
+    pub openid_configuration: String,
+    pub jwks_uri: String,
+    pub flows: SupportedFlows,
+    pub token_formats: TokenFormats,
+    pub features: BTreeMap<&'static str, bool>,
+}
+
+impl ServerCapabilities {
+    pub fn new(config: &Config, issuer: &str) -> Self {
+        Self {
+            schema_version: CAPABILITIES_SCHEMA_VERSION,
+            server: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+            issuer: issuer.to_string(),
+            openid_configuration: format!("{}/.well-known/openid-configuration", issuer),
+            jwks_uri: format!("{}/.well-known/jwks.json", issuer),
+            flows: SupportedFlows {
+                sign_in: SIGN_IN_FLOWS,
+                mfa: MFA_METHODS,
+                oauth_grant_types: &SUPPORTED_GRANT_TYPES,
+            },
+            token_formats: TokenFormats {
+                access_token: TokenFormat::jwt(),
+                access_token_claims_mode: config.jwt_claims_mode.as_str(),
+                refresh_token: TokenFormat::jwt(),
+                oauth_refresh_token: TokenFormat::opaque(),
+                id_token: TokenFormat::jwt(),
+                api_key: TokenFormat::opaque(),
+            },
+            features: public_features(enabled_features(config)),
+        }
+    }
+}
+
+/// Builds the instance report shown at `GET /admin/instance` and sent by the heartbeat
+#[derive(Clone)]
+pub struct InstanceService {
+    pool: MySqlPool,
+    config: Arc<Config>,
+}
+
+impl InstanceService {
+    pub fn new(pool: MySqlPool, config: Arc<Config>) -> Self {
+        Self { pool, config }
+    }
+
+    pub async fn report(&self) -> Result<InstanceReport, AppError> {
+        let users = UserRepository::new(self.pool.clone()).count_all().await?;
+        let apps = AppRepository::new(self.pool.clone()).count_all().await?;
+        let oauth_clients = OAuthClientRepository::new(self.pool.clone())
+            .count_all()
+            .await
+            .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?;
+
+        Ok(InstanceReport {
+            instance: anonymous_instance_id(&self.config.instance_id),
+            version: env!("CARGO_PKG_VERSION"),
+            features: enabled_features(&self.config),
+            counts: InstanceCounts {
+                users: coarse_count(users),
+                apps: coarse_count(apps),
+                oauth_clients: coarse_count(oauth_clients),
+            },
+            reported_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coarse_count() {
+        assert_eq!(coarse_count(0), "0");
+        assert_eq!(coarse_count(7), "1+");
+        assert_eq!(coarse_count(10), "10+");
+        assert_eq!(coarse_count(4321), "1000+");
+    }
+
+    #[test]
+    fn test_anonymous_instance_id() {
+        let id = anonymous_instance_id("auth-server-7f9c");
+        assert_eq!(id.len(), 16);
+        assert_eq!(id, anonymous_instance_id("auth-server-7f9c"));
+        assert_ne!(id, anonymous_instance_id("auth-server-0000"));
+    }
+
+    #[test]
+    fn test_public_features_leave_out_operator_settings() {
+        let features = BTreeMap::from([("cooling_off", true), ("oauth_strict", false), ("chaos", true), ("metrics", true)]);
+
+        assert_eq!(
+            public_features(features),
+            BTreeMap::from([("cooling_off", true), ("oauth_strict", false)])
+        );
+    }
+}
@@ -3,18 +3,24 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
 use crate::error::AppError;
-use crate::models::{IpRule, IpRuleType};
-use crate::repositories::IpRuleRepository;
+use crate::models::{IpRule, IpRuleSuggestion, IpRuleType, SUGGESTION_APPROVED, SUGGESTION_PENDING, SUGGESTION_REJECTED};
+use crate::repositories::abuse_telemetry::AbuseBurst;
+use crate::repositories::{AbuseTelemetryRepository, IpRuleRepository};
+
+/// Bursts evaluated per suggestion pass
+const MAX_BURSTS_PER_PASS: i64 = 500;
 
 #[derive(Clone)]
 pub struct IpRuleService {
     repo: IpRuleRepository,
+    telemetry_repo: AbuseTelemetryRepository,
 }
 
 impl IpRuleService {
     pub fn new(pool: MySqlPool) -> Self {
         Self {
-            repo: IpRuleRepository::new(pool),
+            repo: IpRuleRepository::new(pool.clone()),
+            telemetry_repo: AbuseTelemetryRepository::new(pool),
         }
     }
 
@@ -95,6 +101,108 @@ impl IpRuleService {
         self.repo.delete_expired().await
     }
 
+    /// Turn recent abuse bursts into pending deny-rule suggestions
+    ///
+    /// Windows starting at or after `since` that reach a threshold create one
+    /// pending suggestion per IP; further bursts only refresh it. IPs that
+    /// already have a global rule, or whose suggestion was rejected after
+    /// `rejected_after`, are skipped. Returns the number of suggestions created.
+    pub async fn suggest_from_bursts(
+        &self,
+        since: DateTime<Utc>,
+        window_secs: i64,
+        error_threshold: i64,
+        rate_limited_threshold: i64,
+        rejected_after: DateTime<Utc>,
+    ) -> Result<usize, AppError> {
+        let bursts = self
+            .telemetry_repo
+            .find_bursts(since, error_threshold, rate_limited_threshold, MAX_BURSTS_PER_PASS)
+            .await?;
+
+        let mut created = 0;
+        for burst in bursts {
+            let reason = Self::suggestion_reason(&burst, window_secs);
+
+            if let Some(pending) = self.telemetry_repo.find_pending_for_ip(&burst.ip_address).await? {
+                self.telemetry_repo.touch_suggestion(&pending.id, &burst, &reason).await?;
+                continue;
+            }
+
+            if self.telemetry_repo.has_global_rule(&burst.ip_address).await?
+                || self.telemetry_repo.rejected_since(&burst.ip_address, rejected_after).await?
+            {
+                continue;
+            }
+
+            self.telemetry_repo.create_suggestion(&burst, &reason).await?;
+            tracing::warn!("Suggested IP deny rule for {}: {}", burst.ip_address, reason);
+            created += 1;
+        }
+
+        Ok(created)
+    }
+
+    pub async fn list_suggestions(&self, status: Option<&str>, limit: i64) -> Result<Vec<IpRuleSuggestion>, AppError> {
+        self.telemetry_repo.list_suggestions(status, limit).await
+    }
+
+    /// Approve a pending suggestion by creating the global blacklist rule it proposes
+    pub async fn approve_suggestion(
+        &self,
+        id: Uuid,
+        expires_at: Option<DateTime<Utc>>,
+        reviewed_by: Uuid,
+    ) -> Result<(IpRuleSuggestion, IpRule), AppError> {
+        let suggestion = self.pending_suggestion(id).await?;
+
+        let rule = self
+            .blacklist_ip(&suggestion.ip_address, None, Some(&suggestion.reason), expires_at, Some(reviewed_by))
+            .await?;
+
+        // Lost a race with another reviewer: drop the rule we just created
+        if !self.telemetry_repo.review_suggestion(id, SUGGESTION_APPROVED, Some(rule.id_uuid()), reviewed_by).await? {
+            self.repo.delete(rule.id_uuid()).await?;
+            return Err(AppError::ValidationError("Suggestion is not pending".into()));
+        }
+
+        let suggestion = self.get_suggestion(id).await?;
+        Ok((suggestion, rule))
+    }
+
+    /// Reject a pending suggestion; the IP is not suggested again for a while
+    pub async fn reject_suggestion(&self, id: Uuid, reviewed_by: Uuid) -> Result<IpRuleSuggestion, AppError> {
+        self.pending_suggestion(id).await?;
+
+        if !self.telemetry_repo.review_suggestion(id, SUGGESTION_REJECTED, None, reviewed_by).await? {
+            return Err(AppError::ValidationError("Suggestion is not pending".into()));
+        }
+
+        self.get_suggestion(id).await
+    }
+
+    async fn pending_suggestion(&self, id: Uuid) -> Result<IpRuleSuggestion, AppError> {
+        let suggestion = self.get_suggestion(id).await?;
+        if suggestion.status != SUGGESTION_PENDING {
+            return Err(AppError::ValidationError("Suggestion is not pending".into()));
+        }
+        Ok(suggestion)
+    }
+
+    async fn get_suggestion(&self, id: Uuid) -> Result<IpRuleSuggestion, AppError> {
+        self.telemetry_repo
+            .find_suggestion(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("IP rule suggestion not found".into()))
+    }
+
+    fn suggestion_reason(burst: &AbuseBurst, window_secs: i64) -> String {
+        format!(
+            "Abuse telemetry: {} client errors and {} rate-limited requests on {} within {}s",
+            burst.client_errors, burst.rate_limited, burst.route, window_secs
+        )
+    }
+
     fn is_valid_ip(ip: &str) -> bool {
         // Simple IPv4 validation
         let parts: Vec<&str> = ip.split('.').collect();
@@ -0,0 +1,136 @@
+use chrono::Utc;
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::AuditAction;
+use crate::repositories::{LoginHistoryRepository, UserRepository};
+use crate::services::{
+    AuditService, EmailConfig, EmailService, LoginContext, MockEmailService, SecurityAlertType,
+};
+use crate::utils::geoip::{self, GeoLocation};
+use crate::utils::request_id::spawn_in_request;
+use crate::utils::travel::{Travel, TravelAction, TravelPolicy};
+
+/// Service that detects sign-ins implying impossible travel
+///
+/// Each fresh sign-in is located with the GeoIP database and compared with
+/// the user's previous located one. Sign-ins from addresses the database
+/// doesn't know are neither checked nor recorded.
+#[derive(Clone)]
+pub struct LoginAnomalyService {
+    pool: MySqlPool,
+    history_repo: LoginHistoryRepository,
+    audit_service: AuditService,
+    policy: TravelPolicy,
+}
+
+impl LoginAnomalyService {
+    pub fn new(pool: MySqlPool, policy: TravelPolicy) -> Self {
+        Self {
+            history_repo: LoginHistoryRepository::new(pool.clone()),
+            audit_service: AuditService::new(pool.clone()),
+            pool,
+            policy,
+        }
+    }
+
+    /// Check a sign-in before its session is created
+    ///
+    /// Impossible travel is audited and emailed to the user; with the deny
+    /// action the sign-in fails with `ImpossibleTravel` and is not recorded,
+    /// so the previous location stays the reference.
+    pub async fn check_sign_in(&self, user_id: Uuid, context: &LoginContext) -> Result<(), AuthError> {
+        if !self.policy.is_enabled() || !geoip::geoip_enabled() {
+            return Ok(());
+        }
+        let Some(ip) = context.ip_address.as_deref() else {
+            return Ok(());
+        };
+        let Some(location) = geoip::locate(ip) else {
+            return Ok(());
+        };
+
+        let previous = self.history_repo.latest(user_id).await?;
+        let travel = previous
+            .as_ref()
+            .map(|previous| Travel::between(&previous.location(), previous.created_at, &location, Utc::now()))
+            .filter(|travel| travel.is_impossible(&self.policy));
+
+        if let (Some(travel), Some(previous)) = (&travel, &previous) {
+            let _ = self
+                .audit_service
+                .log_auth_event(
+                    Some(user_id),
+                    AuditAction::ImpossibleTravel,
+                    Some(ip),
+                    context.user_agent.as_deref(),
+                    Some(serde_json::json!({
+                        "action": self.policy.action.as_str(),
+                        "previous_ip": previous.ip_address,
+                        "previous_country": previous.country,
+                        "country": location.country,
+                        "distance_km": travel.distance_km.round(),
+                        "elapsed_secs": travel.elapsed_secs,
+                        "speed_kmh": travel.speed_kmh.round(),
+                    })),
+                    self.policy.action == TravelAction::Flag,
+                )
+                .await;
+
+            self.send_alert(user_id, ip, &location, travel);
+
+            if self.policy.action == TravelAction::Deny {
+                return Err(AuthError::ImpossibleTravel);
+            }
+        }
+
+        // The history only feeds the check; failing to record doesn't fail the sign-in
+        let _ = self
+            .history_repo
+            .record(user_id, ip, &location, travel.is_some())
+            .await;
+
+        Ok(())
+    }
+
+    /// Email the user about the sign-in in the background
+    fn send_alert(&self, user_id: Uuid, ip: &str, location: &GeoLocation, travel: &Travel) {
+        let pool = self.pool.clone();
+        let details = format!(
+            "Sign-in from {}{}, {:.0} km from your previous sign-in {} minutes earlier.",
+            ip,
+            location
+                .country
+                .as_deref()
+                .map(|country| format!(" ({})", country))
+                .unwrap_or_default(),
+            travel.distance_km,
+            travel.elapsed_secs / 60
+        );
+
+        spawn_in_request(async move {
+            let Ok(Some(user)) = UserRepository::new(pool.clone()).find_by_id(user_id).await else {
+                return;
+            };
+
+            let result = match EmailConfig::from_env().map(EmailService::new) {
+                Some(Ok(mailer)) => {
+                    mailer
+                        .with_bounce_list(pool)
+                        .send_security_alert(&user.email, SecurityAlertType::ImpossibleTravel, Some(&details))
+                        .await
+                }
+                Some(Err(e)) => Err(e),
+                None => {
+                    MockEmailService::new()
+                        .send_security_alert(&user.email, SecurityAlertType::ImpossibleTravel, Some(&details))
+                        .await
+                }
+            };
+            if let Err(e) = result {
+                tracing::warn!("Failed to send impossible travel alert to user {}: {}", user_id, e);
+            }
+        });
+    }
+}
@@ -1,11 +1,16 @@
+use chrono::{Duration, Utc};
 use rand::Rng;
 use sqlx::MySqlPool;
 use uuid::Uuid;
 
 use crate::error::AuthError;
-use crate::models::UserMfaMethod;
+use crate::models::{EmailOtpCode, MfaSmsCode, UserMfaMethod};
 use crate::repositories::MfaRepository;
+use crate::services::sms::{mask_phone, normalize_e164};
+use crate::services::{EmailConfig, EmailService, MockEmailService, RateLimitConfig, RateLimiterService, SmsService};
+use crate::utils::email::mask_email;
 use crate::utils::password::hash_token;
+use crate::utils::secret::constant_time_compare;
 
 /// Number of backup codes to generate
 const BACKUP_CODE_COUNT: usize = 10;
@@ -17,18 +22,36 @@ const BACKUP_CODE_LENGTH: usize = 8;
 const TOTP_DIGITS: u32 = 6;
 const TOTP_PERIOD: u64 = 30;
 
+/// SMS code configuration
+const SMS_CODE_DIGITS: u32 = 6;
+const SMS_CODE_EXPIRY_SECONDS: i64 = 300;
+/// Wrong guesses after which an SMS code stops working
+const SMS_CODE_MAX_ATTEMPTS: i32 = 5;
+
+/// Email code configuration
+const EMAIL_CODE_DIGITS: u32 = 6;
+pub const EMAIL_CODE_EXPIRY_SECONDS: i64 = 600;
+/// Wrong guesses after which an email code stops working
+const EMAIL_CODE_MAX_ATTEMPTS: i32 = 5;
+
 /// Service for MFA operations
 #[derive(Clone)]
 pub struct MfaService {
+    pool: MySqlPool,
     repo: MfaRepository,
     totp_issuer: String,
+    sms_service: SmsService,
+    rate_limiter: RateLimiterService,
 }
 
 impl MfaService {
     pub fn new(pool: MySqlPool, totp_issuer: String) -> Self {
         Self {
-            repo: MfaRepository::new(pool),
+            repo: MfaRepository::new(pool.clone()),
             totp_issuer,
+            sms_service: SmsService::from_env(),
+            rate_limiter: RateLimiterService::new(pool.clone()),
+            pool,
         }
     }
 
@@ -42,7 +65,7 @@ impl MfaService {
         let secret = generate_totp_secret();
         let secret_base32 = base32_encode(&secret);
 
-        // Create the MFA method (not verified yet)
+        // Create the MFA method (not verified yet); the repository encrypts the secret
         let method = self
             .repo
             .create_method(user_id, "totp", Some(&secret_base32), None, None, true)
@@ -125,6 +148,329 @@ impl MfaService {
         Ok(is_valid)
     }
 
+    // ========================================================================
+    // SMS Codes
+    // ========================================================================
+
+    /// Start SMS setup - store the (unverified) phone number and text it a code
+    pub async fn setup_sms(&self, user_id: Uuid, phone_number: &str) -> Result<SmsCodeSent, AuthError> {
+        let phone = normalize_e164(phone_number).ok_or_else(|| {
+            AuthError::InvalidRequest(
+                "Phone number must be in international format, e.g. +84912345678".to_string(),
+            )
+        })?;
+
+        // Limit sends per account and per number, so one number can't be
+        // flooded from many accounts
+        self.check_sms_send_rate(&user_id.to_string()).await?;
+        self.check_sms_send_rate(&phone).await?;
+
+        let method = self
+            .repo
+            .create_method(user_id, "sms", None, Some(&phone), None, false)
+            .await?;
+
+        self.send_sms_code(&method, "enroll", None).await
+    }
+
+    /// Verify SMS setup with the code texted to the phone
+    ///
+    /// Returns new backup codes when the user has none left (the first
+    /// method enrolled); otherwise the existing ones stay valid.
+    pub async fn verify_sms_setup(
+        &self,
+        user_id: Uuid,
+        method_id: Uuid,
+        code: &str,
+    ) -> Result<Vec<String>, AuthError> {
+        let method = self
+            .repo
+            .find_method_by_id(method_id)
+            .await?
+            .ok_or(AuthError::InvalidMfaCode)?;
+
+        if method.user_id != user_id || method.method_type != "sms" || method.is_verified {
+            return Err(AuthError::InvalidMfaCode);
+        }
+
+        let sms_code = self
+            .repo
+            .find_enrollment_sms_code(method_id)
+            .await?
+            .ok_or(AuthError::InvalidMfaCode)?;
+
+        if !self.check_sms_code(&sms_code, code).await? {
+            return Err(AuthError::InvalidMfaCode);
+        }
+
+        self.repo.verify_method(method_id).await?;
+
+        if self.repo.count_unused_backup_codes(user_id).await? > 0 {
+            return Ok(Vec::new());
+        }
+        self.generate_backup_codes(user_id).await
+    }
+
+    /// Text a login code to one of the user's verified phones
+    ///
+    /// The code only completes the pending login identified by `mfa_token_hash`.
+    /// Without `method_id` the first verified phone is used.
+    pub async fn send_sms_login_code(
+        &self,
+        user_id: Uuid,
+        mfa_token_hash: &str,
+        method_id: Option<Uuid>,
+    ) -> Result<SmsCodeSent, AuthError> {
+        let method = self
+            .repo
+            .list_methods_by_user(user_id)
+            .await?
+            .into_iter()
+            .filter(|m| m.method_type == "sms" && m.is_verified)
+            .find(|m| method_id.is_none_or(|id| m.id == id))
+            .ok_or_else(|| AuthError::InvalidRequest("No verified phone number for SMS codes".to_string()))?;
+
+        self.check_sms_send_rate(&user_id.to_string()).await?;
+
+        self.send_sms_code(&method, "login", Some(mfa_token_hash)).await
+    }
+
+    /// Verify an SMS code during login
+    pub async fn verify_sms(&self, user_id: Uuid, mfa_token_hash: &str, code: &str) -> Result<bool, AuthError> {
+        let Some(sms_code) = self.repo.find_login_sms_code(mfa_token_hash).await? else {
+            return Ok(false);
+        };
+
+        if sms_code.user_id != user_id || !self.check_sms_code(&sms_code, code).await? {
+            return Ok(false);
+        }
+
+        self.repo.update_last_used(sms_code.method_id).await?;
+
+        Ok(true)
+    }
+
+    /// Name of the provider SMS codes are sent through
+    pub fn sms_provider_name(&self) -> &'static str {
+        self.sms_service.provider_name()
+    }
+
+    async fn check_sms_send_rate(&self, key: &str) -> Result<(), AuthError> {
+        let rate_result = self
+            .rate_limiter
+            .check_and_increment(&format!("sms:{}", key), "sms_send", &RateLimitConfig::sms_send())
+            .await
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("{}", e)))?;
+
+        if !rate_result.allowed {
+            return Err(AuthError::RateLimitExceeded(rate_result.throttle("sms_send")));
+        }
+
+        Ok(())
+    }
+
+    /// Generate, store and text a code for an SMS method
+    async fn send_sms_code(
+        &self,
+        method: &UserMfaMethod,
+        purpose: &str,
+        mfa_token_hash: Option<&str>,
+    ) -> Result<SmsCodeSent, AuthError> {
+        let phone = method
+            .phone_number
+            .as_deref()
+            .ok_or(AuthError::InternalError(anyhow::anyhow!("SMS method has no phone number")))?;
+
+        let code = generate_numeric_code(SMS_CODE_DIGITS);
+        let expires_at = Utc::now() + Duration::seconds(SMS_CODE_EXPIRY_SECONDS);
+        self.repo
+            .create_sms_code(method.user_id, method.id, purpose, &hash_token(&code)?, mfa_token_hash, expires_at)
+            .await?;
+
+        let message = format!(
+            "{} is your {} verification code. It expires in {} minutes.",
+            code,
+            self.totp_issuer,
+            SMS_CODE_EXPIRY_SECONDS / 60
+        );
+        self.sms_service.send(phone, &message).await?;
+
+        Ok(SmsCodeSent {
+            method_id: method.id,
+            phone_hint: mask_phone(phone),
+            expires_in: SMS_CODE_EXPIRY_SECONDS,
+        })
+    }
+
+    /// Check a submitted code against a stored one, consuming it on success
+    async fn check_sms_code(&self, sms_code: &MfaSmsCode, code: &str) -> Result<bool, AuthError> {
+        if sms_code.attempts >= SMS_CODE_MAX_ATTEMPTS {
+            return Ok(false);
+        }
+
+        if !constant_time_compare(&hash_token(code.trim())?, &sms_code.code_hash) {
+            self.repo.record_sms_code_attempt(sms_code.id).await?;
+            return Ok(false);
+        }
+
+        self.repo.use_sms_code(sms_code.id).await
+    }
+
+    // ========================================================================
+    // Email Codes
+    // ========================================================================
+
+    /// Enable email codes as a second factor
+    ///
+    /// The codes go to the account address, which the caller must have
+    /// verified already, so there is no confirmation step. Returns new backup
+    /// codes when the user has none left.
+    pub async fn setup_email(&self, user_id: Uuid) -> Result<Vec<String>, AuthError> {
+        let methods = self.repo.list_methods_by_user(user_id).await?;
+        if methods.iter().any(|m| m.method_type == "email" && m.is_verified) {
+            return Err(AuthError::InvalidRequest("Email codes are already enabled".to_string()));
+        }
+
+        let method = self
+            .repo
+            .create_method(user_id, "email", None, None, None, false)
+            .await?;
+        self.repo.verify_method(method.id).await?;
+
+        if self.repo.count_unused_backup_codes(user_id).await? > 0 {
+            return Ok(Vec::new());
+        }
+        self.generate_backup_codes(user_id).await
+    }
+
+    /// Email a code completing the pending login identified by `mfa_token_hash`
+    pub async fn send_email_mfa_code(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        mfa_token_hash: &str,
+    ) -> Result<EmailCodeSent, AuthError> {
+        self.find_email_method(user_id)
+            .await?
+            .ok_or_else(|| AuthError::InvalidRequest("Email codes are not enabled".to_string()))?;
+
+        self.check_email_send_rate(user_id).await?;
+
+        self.send_email_code(user_id, email, "mfa", Some(mfa_token_hash), None).await
+    }
+
+    /// Verify an email code during the MFA step
+    pub async fn verify_email_mfa(&self, user_id: Uuid, mfa_token_hash: &str, code: &str) -> Result<bool, AuthError> {
+        let Some(email_code) = self.repo.find_mfa_email_code(mfa_token_hash).await? else {
+            return Ok(false);
+        };
+
+        if email_code.user_id != user_id || !self.check_email_code(&email_code, code).await? {
+            return Ok(false);
+        }
+
+        if let Some(method) = self.find_email_method(user_id).await? {
+            self.repo.update_last_used(method.id).await?;
+        }
+
+        Ok(true)
+    }
+
+    /// Email a code signing the user in to an app without a password
+    pub async fn send_email_login_code(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        app_id: Uuid,
+    ) -> Result<EmailCodeSent, AuthError> {
+        self.check_email_send_rate(user_id).await?;
+
+        self.send_email_code(user_id, email, "login", None, Some(app_id)).await
+    }
+
+    /// Verify a passwordless login code
+    pub async fn verify_email_login(&self, user_id: Uuid, app_id: Uuid, code: &str) -> Result<bool, AuthError> {
+        let Some(email_code) = self.repo.find_login_email_code(user_id, app_id).await? else {
+            return Ok(false);
+        };
+
+        self.check_email_code(&email_code, code).await
+    }
+
+    async fn find_email_method(&self, user_id: Uuid) -> Result<Option<UserMfaMethod>, AuthError> {
+        Ok(self
+            .repo
+            .list_methods_by_user(user_id)
+            .await?
+            .into_iter()
+            .find(|m| m.method_type == "email" && m.is_verified))
+    }
+
+    async fn check_email_send_rate(&self, user_id: Uuid) -> Result<(), AuthError> {
+        let rate_result = self
+            .rate_limiter
+            .check_and_increment(
+                &format!("email_code:{}", user_id),
+                "email_code_send",
+                &RateLimitConfig::email_code_send(),
+            )
+            .await
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("{}", e)))?;
+
+        if !rate_result.allowed {
+            return Err(AuthError::RateLimitExceeded(rate_result.throttle("email_code_send")));
+        }
+
+        Ok(())
+    }
+
+    /// Generate, store and email a code
+    async fn send_email_code(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        purpose: &str,
+        mfa_token_hash: Option<&str>,
+        app_id: Option<Uuid>,
+    ) -> Result<EmailCodeSent, AuthError> {
+        let code = generate_numeric_code(EMAIL_CODE_DIGITS);
+        let expires_at = Utc::now() + Duration::seconds(EMAIL_CODE_EXPIRY_SECONDS);
+        self.repo
+            .create_email_code(user_id, purpose, &hash_token(&code)?, mfa_token_hash, app_id, expires_at)
+            .await?;
+
+        let minutes = EMAIL_CODE_EXPIRY_SECONDS / 60;
+        match EmailConfig::from_env().map(EmailService::new) {
+            Some(Ok(mailer)) => {
+                mailer
+                    .with_bounce_list(self.pool.clone())
+                    .send_one_time_code(email, &code, minutes)
+                    .await?
+            }
+            Some(Err(e)) => return Err(e),
+            None => MockEmailService::new().send_one_time_code(email, &code, minutes).await?,
+        }
+
+        Ok(EmailCodeSent {
+            email_hint: mask_email(email),
+            expires_in: EMAIL_CODE_EXPIRY_SECONDS,
+        })
+    }
+
+    /// Check a submitted code against a stored one, consuming it on success
+    async fn check_email_code(&self, email_code: &EmailOtpCode, code: &str) -> Result<bool, AuthError> {
+        if email_code.attempts >= EMAIL_CODE_MAX_ATTEMPTS {
+            return Ok(false);
+        }
+
+        if !constant_time_compare(&hash_token(code.trim())?, &email_code.code_hash) {
+            self.repo.record_email_code_attempt(email_code.id).await?;
+            return Ok(false);
+        }
+
+        self.repo.use_email_code(email_code.id).await
+    }
+
     // ========================================================================
     // Backup Codes
     // ========================================================================
@@ -220,6 +566,23 @@ pub struct TotpSetupResponse {
     pub provisioning_uri: String,
 }
 
+/// An SMS code was texted
+#[derive(Debug, Clone)]
+pub struct SmsCodeSent {
+    pub method_id: Uuid,
+    /// Phone number with all but the last digits hidden
+    pub phone_hint: String,
+    pub expires_in: i64,
+}
+
+/// An email code was sent
+#[derive(Debug, Clone)]
+pub struct EmailCodeSent {
+    /// Address with the local part hidden but its first character
+    pub email_hint: String,
+    pub expires_in: i64,
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -344,3 +707,9 @@ fn generate_backup_code() -> String {
         })
         .collect()
 }
+
+/// Generate a random numeric code of `digits` digits, for SMS and email codes
+fn generate_numeric_code(digits: u32) -> String {
+    let code = rand::thread_rng().gen_range(0..10u32.pow(digits));
+    format!("{:0>width$}", code, width = digits as usize)
+}
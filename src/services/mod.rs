@@ -15,27 +15,75 @@ pub mod token_revocation;
 pub mod mfa;
 pub mod account_lockout;
 pub mod webhook;
+pub mod webhook_dispatch;
 pub mod api_key;
 pub mod ip_rule;
 pub mod webauthn;
+pub mod privacy_ledger;
+pub mod duplicate_account;
+pub mod qr_login;
+pub mod device;
+pub mod trusted_device;
+pub mod login_anomaly;
+pub mod cooling_off;
+pub mod push;
+pub mod push_mfa;
+pub mod sms;
+pub mod rbac_sync;
+pub mod role_elevation;
+pub mod provisioning;
+pub mod signing_key;
+pub mod token_lineage;
+pub mod oauth_stats;
+pub mod instance;
+pub mod challenge_store;
+pub mod broadcast;
+pub mod admin_approval;
+pub mod app_digest;
+pub mod access_simulator;
 
 pub use admin::AdminService;
 pub use app::AppService;
-pub use auth::{AuthService, LoginContext, LoginResult, MfaTokenData};
+pub use auth::{AuthService, LoginContext, LoginResult, MfaFactor, MfaTokenData, RegistrationOutcome};
 pub use consent::{ConsentInfo, ConsentService};
 pub use email::{EmailConfig, EmailService, MockEmailService, SecurityAlertType};
-pub use oauth::{OAuthService, OAuthTokenResponse};
+pub use oauth::{
+    DeviceAuthorizationResponse, IntrospectionResponse, OAuthService, OAuthTokenResponse,
+    PushedAuthorizationResponse,
+};
 pub use permission::PermissionService;
 pub use role::RoleService;
 pub use user_management::UserManagementService;
 pub use user_profile::UserProfileService;
 pub use audit::AuditService;
 pub use rate_limiter::{RateLimitConfig, RateLimiterService, RateLimitResult};
-pub use session::{DeviceInfo, SessionService};
+pub use session::{DeviceInfo, SessionPolicy, SessionService};
 pub use token_revocation::TokenRevocationService;
-pub use mfa::{MfaService, TotpSetupResponse};
+pub use mfa::{EmailCodeSent, MfaService, SmsCodeSent, TotpSetupResponse};
 pub use account_lockout::{AccountLockoutService, LockoutConfig, LockoutInfo};
 pub use webhook::WebhookService;
+pub use webhook_dispatch::{WebhookDispatchLimits, WebhookDispatcher};
 pub use api_key::{ApiKeyService, scopes as api_key_scopes};
 pub use ip_rule::{IpRuleService, IpAccessResult};
 pub use webauthn::{WebAuthnService, RegistrationResponse, AuthenticationResponse, AuthenticatorAttestationResponse, AuthenticatorAssertionResponse};
+pub use privacy_ledger::PrivacyLedgerService;
+pub use duplicate_account::DuplicateAccountService;
+pub use qr_login::{QrLoginService, QrPollResult};
+pub use device::DeviceService;
+pub use trusted_device::TrustedDeviceService;
+pub use login_anomaly::LoginAnomalyService;
+pub use cooling_off::CoolingOffService;
+pub use push_mfa::PushMfaService;
+pub use sms::SmsService;
+pub use rbac_sync::RbacSyncService;
+pub use role_elevation::RoleElevationService;
+pub use provisioning::ProvisioningService;
+pub use signing_key::{SigningKeyPolicy, SigningKeyService};
+pub use token_lineage::{TokenLineage, TokenLineageService};
+pub use oauth_stats::{OAuthStats, OAuthStatsService, StatsWindow};
+pub use instance::{InstanceReport, InstanceService};
+pub use challenge_store::{ChallengeStore, ChallengeStoreBackend};
+pub use broadcast::BroadcastService;
+pub use admin_approval::AdminApprovalService;
+pub use app_digest::AppDigestService;
+pub use access_simulator::{AccessDecision, AccessSimulation, AccessSimulatorService};
@@ -11,20 +11,66 @@
 //! - 7.1, 7.2, 7.4: Token refresh with rotation
 //! - 9.2, 9.4: Token revocation
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::MySqlPool;
 use uuid::Uuid;
 
-use crate::error::OAuthError;
-use crate::models::{OAuthClient, OAuthEventType};
+use crate::dto::AuthorizationRequest;
+use crate::error::{AppError, OAuthError};
+use crate::models::{AuthorizationCode, DeviceCode, DeviceCodeEntry, DeviceCodeStatus, OAuthClient, OAuthEventType};
 use crate::repositories::{
-    AuthorizationCodeRepository, OAuthAuditLogRepository, OAuthClientRepository,
-    OAuthScopeRepository, OAuthTokenRepository, UserConsentRepository,
+    AuthorizationCodeRepository, ClientJwksRepository, OAuthAuditLogRepository, OAuthClientRepository,
+    OAuthScopeRepository, OAuthTokenRepository, RedirectUriBlockRepository,
+    RevokedTokenRepository, UserConsentRepository, UserRepository,
 };
-use crate::services::ConsentService;
-use crate::utils::jwt::JwtManager;
+use crate::services::challenge_store::{CHALLENGE_KIND_DEVICE_CODE, CHALLENGE_KIND_DEVICE_USER_CODE, CHALLENGE_KIND_PAR};
+use crate::services::{ChallengeStore, ConsentService, SessionPolicy};
+use crate::utils::device_code::{
+    generate_user_code, normalize_user_code, DEVICE_CODE_EXPIRY_SECS, DEVICE_CODE_RETENTION_SECS, DEVICE_POLL_INTERVAL_SECS,
+    SLOW_DOWN_INCREMENT_SECS,
+};
+use crate::utils::jwt::{IdTokenUserClaims, JwtManager, OAuth2Claims};
 use crate::utils::pkce::{validate_code_challenge, validate_code_verifier, verify_pkce, PKCE_METHOD_S256};
-use crate::utils::secret::{generate_oauth_token, hash_oauth_token, verify_secret};
+use crate::utils::posture::PostureContext;
+use crate::utils::outbound_url::{check_outbound_url, pinned_client};
+use crate::utils::redirect_uri::{loopback_redirect_matches, RedirectUriPolicy, RedirectUriProfile};
+use crate::utils::request_id::spawn_in_request;
+use crate::utils::request_object::{validate_client_jwks, verify_request_object};
+use crate::utils::secret::{constant_time_compare, generate_oauth_token, hash_oauth_token, verify_secret};
+use crate::utils::userinfo_claims::released_claims;
+
+/// Maximum accepted length of an OpenID Connect nonce
+pub const MAX_NONCE_LENGTH: usize = 255;
+
+/// grant_type of the Device Authorization Grant (RFC 8628 Section 3.4)
+pub const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Grant types the token endpoint accepts, in discovery order
+pub const SUPPORTED_GRANT_TYPES: [&str; 4] = [
+    "authorization_code",
+    "client_credentials",
+    "refresh_token",
+    DEVICE_CODE_GRANT_TYPE,
+];
+
+/// Prefix of the request_uri returned for a pushed authorization request (RFC 9126 Section 2.2)
+pub const PAR_REQUEST_URI_PREFIX: &str = "urn:ietf:params:oauth:request_uri:";
+
+/// Lifetime of a pushed authorization request, in seconds
+pub const PAR_REQUEST_URI_EXPIRY_SECS: i64 = 90;
+
+/// How long to wait for a client's back-channel logout endpoint
+const BACKCHANNEL_LOGOUT_TIMEOUT_SECS: u64 = 5;
+
+/// How long to wait for a hosted request object
+const REQUEST_URI_FETCH_TIMEOUT_SECS: u64 = 5;
+
+/// Largest hosted request object accepted, in bytes
+const MAX_REQUEST_OBJECT_BYTES: usize = 16 * 1024;
+
+/// Maximum number of request_uris a client may register
+pub const MAX_CLIENT_REQUEST_URIS: usize = 10;
 
 /// OAuth2 Token Response
 /// Requirements: 5.1, 5.3
@@ -36,6 +82,8 @@ pub struct OAuthTokenResponse {
     pub token_type: String,
     pub expires_in: i64,
     pub scope: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
 }
 
 impl OAuthTokenResponse {
@@ -51,21 +99,84 @@ impl OAuthTokenResponse {
             token_type: "Bearer".to_string(),
             expires_in,
             scope: scopes.join(" "),
+            id_token: None,
         }
     }
 }
 
+/// Device Authorization Response (RFC 8628 Section 3.2)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceAuthorizationResponse {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: String,
+    pub expires_in: i64,
+    pub interval: i32,
+}
+
+/// Pushed Authorization Response (RFC 9126 Section 2.2)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushedAuthorizationResponse {
+    pub request_uri: String,
+    pub expires_in: i64,
+}
+
+/// Token Introspection Response (RFC 7662 Section 2.2)
+///
+/// Inactive tokens carry nothing but `active: false`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntrospectionResponse {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    /// Sign-in posture, for clients that opted in to posture claims
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub azp_ctx: Option<PostureContext>,
+}
+
+impl IntrospectionResponse {
+    /// Response for unknown, expired, revoked or foreign tokens
+    pub fn inactive() -> Self {
+        Self::default()
+    }
+}
+
 /// OAuth2 Service - handles OAuth2 authorization flows
 #[derive(Clone)]
 pub struct OAuthService {
     client_repo: OAuthClientRepository,
     scope_repo: OAuthScopeRepository,
     code_repo: AuthorizationCodeRepository,
+    /// Holds pushed authorization requests and device authorizations while they are pending
+    challenges: ChallengeStore,
+    client_jwks_repo: ClientJwksRepository,
     token_repo: OAuthTokenRepository,
     consent_repo: UserConsentRepository,
     audit_repo: OAuthAuditLogRepository,
+    revoked_token_repo: RevokedTokenRepository,
+    redirect_block_repo: RedirectUriBlockRepository,
     consent_service: ConsentService,
     jwt_manager: JwtManager,
+    session_defaults: SessionPolicy,
+    redirect_policy: RedirectUriPolicy,
+    issuer: String,
+    /// Server-wide maximum client secret age in days (0 = no expiry)
+    secret_max_age_days: i64,
+    /// Longest access token lifetime, including per-client overrides
+    max_access_token_ttl_secs: i64,
+    /// Enforce the specs exactly (OAUTH_STRICT)
+    strict: bool,
     pool: MySqlPool,
 }
 
@@ -77,15 +188,73 @@ impl OAuthService {
             client_repo: OAuthClientRepository::new(pool.clone()),
             scope_repo: OAuthScopeRepository::new(pool.clone()),
             code_repo: AuthorizationCodeRepository::new(pool.clone()),
+            challenges: ChallengeStore::database(pool.clone()),
+            client_jwks_repo: ClientJwksRepository::new(pool.clone()),
             token_repo: OAuthTokenRepository::new(pool.clone()),
             consent_repo: UserConsentRepository::new(pool.clone()),
             audit_repo: OAuthAuditLogRepository::new(pool.clone()),
+            revoked_token_repo: RevokedTokenRepository::new(pool.clone()),
+            redirect_block_repo: RedirectUriBlockRepository::new(pool.clone()),
             consent_service: ConsentService::new(pool.clone()),
             jwt_manager,
+            session_defaults: SessionPolicy::default(),
+            redirect_policy: RedirectUriPolicy::default(),
+            issuer: String::new(),
+            secret_max_age_days: 0,
+            max_access_token_ttl_secs: OAuth2Claims::MAX_ACCESS_TOKEN_EXPIRY_SECS,
+            strict: false,
             pool,
         }
     }
 
+    /// Use the server's challenge store for pushed requests and device authorizations
+    pub fn with_challenge_store(mut self, challenges: ChallengeStore) -> Self {
+        self.challenges = challenges;
+        self
+    }
+
+    /// Use the server-wide session policy defaults (clients may override them)
+    pub fn with_session_defaults(mut self, session_defaults: SessionPolicy) -> Self {
+        self.session_defaults = session_defaults;
+        self
+    }
+
+    /// Use the server-wide redirect URI policy
+    pub fn with_redirect_policy(mut self, redirect_policy: RedirectUriPolicy) -> Self {
+        self.redirect_policy = redirect_policy;
+        self
+    }
+
+    /// Reject client secrets older than this many days unless the client has its own limit
+    pub fn with_secret_max_age_days(mut self, secret_max_age_days: i64) -> Self {
+        self.secret_max_age_days = secret_max_age_days;
+        self
+    }
+
+    /// Cap access token lifetimes, including per-client overrides, at this many seconds
+    pub fn with_max_access_token_ttl_secs(mut self, max_access_token_ttl_secs: i64) -> Self {
+        self.max_access_token_ttl_secs = max_access_token_ttl_secs;
+        self
+    }
+
+    /// Enforce the OAuth/OIDC specs exactly instead of accepting common client quirks
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Treat stored consents older than this many days as expired (0 = never expires)
+    pub fn with_consent_ttl_days(mut self, consent_ttl_days: i64) -> Self {
+        self.consent_service = self.consent_service.with_consent_ttl_days(consent_ttl_days);
+        self
+    }
+
+    /// Set the issuer identifier placed in ID tokens
+    pub fn with_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = issuer.into();
+        self
+    }
+
     // ========================================================================
     // Authorization Request Validation (Task 8.1)
     // Requirements: 3.1, 3.3, 10.5
@@ -117,6 +286,27 @@ impl OAuthService {
         scopes: &[String],
         code_challenge: Option<&str>,
         code_challenge_method: Option<&str>,
+    ) -> Result<OAuthClient, OAuthError> {
+        let client = self.validate_client_redirect_uri(client_id, redirect_uri).await?;
+        self.validate_authorization_parameters(&client, scopes, code_challenge, code_challenge_method)
+            .await?;
+
+        Ok(client)
+    }
+
+    /// Find the client and check the redirect_uri against it
+    ///
+    /// Until this succeeds the redirect_uri is untrusted: errors must be
+    /// returned to the user agent directly, never redirected (RFC 6749
+    /// Section 4.1.2.1), or the endpoint becomes an open redirector.
+    ///
+    /// # Requirements
+    /// - 3.3: Reject request if redirect_uri does not match registered URIs
+    /// - 10.5: Validate redirect_uri exactly matches registered URIs
+    pub async fn validate_client_redirect_uri(
+        &self,
+        client_id: &str,
+        redirect_uri: &str,
     ) -> Result<OAuthClient, OAuthError> {
         // Find the client
         let client = self.client_repo
@@ -132,9 +322,28 @@ impl OAuthService {
             ));
         }
 
-        // For external apps, PKCE is required
+        // Re-check the registered URI against the current policy and blocklist,
+        // so URIs registered before a rule was added are covered too
+        self.check_redirect_uri_policy(redirect_uri, Self::redirect_profile(&client)).await?;
+
+        Ok(client)
+    }
+
+    /// Validate PKCE and scopes of an authorization request for a known client
+    ///
+    /// # Requirements
+    /// - 3.2: Reject request if code_challenge is missing for External_App
+    /// - 10.2: Require PKCE for all External_App authorization requests
+    pub async fn validate_authorization_parameters(
+        &self,
+        client: &OAuthClient,
+        scopes: &[String],
+        code_challenge: Option<&str>,
+        code_challenge_method: Option<&str>,
+    ) -> Result<(), OAuthError> {
+        // For external and native apps, PKCE is required
         // Requirements: 3.2, 10.2
-        if client.is_external() {
+        if client.is_external() || client.is_native() {
             let challenge = code_challenge.ok_or_else(|| {
                 OAuthError::InvalidRequest("code_challenge is required for external apps".to_string())
             })?;
@@ -155,18 +364,36 @@ impl OAuthService {
             }
         }
 
+        self.check_grant_type(client, "authorization_code")?;
+
         // Validate scopes exist
         // Requirement: 2.4
-        if !scopes.is_empty() {
-            let valid = self.scope_repo.validate_scopes(scopes).await?;
-            if !valid {
-                return Err(OAuthError::InvalidScope(
-                    "One or more requested scopes are invalid".to_string(),
-                ));
-            }
+        self.validate_scopes(scopes, client).await?;
+
+        Ok(())
+    }
+
+    /// Checks that only apply in strict mode
+    ///
+    /// Public clients (native apps) have no secret to bind the response to
+    /// the client, so strict mode requires them to send `state`
+    /// (RFC 6749 Section 10.12).
+    pub fn check_strict_authorization_request(
+        &self,
+        client: &OAuthClient,
+        state: Option<&str>,
+    ) -> Result<(), OAuthError> {
+        if !self.strict {
+            return Ok(());
         }
 
-        Ok(client)
+        if client.is_native() && state.is_none_or(str::is_empty) {
+            return Err(OAuthError::InvalidRequest(
+                "state is required for public clients".to_string(),
+            ));
+        }
+
+        Ok(())
     }
 
     /// Validate that a redirect_uri exactly matches one of the registered URIs
@@ -174,34 +401,182 @@ impl OAuthService {
     /// # Requirements
     /// - 3.3: Reject request if redirect_uri does not match registered URIs
     /// - 10.5: Validate redirect_uri exactly matches registered URIs (no partial matching)
+    ///
+    /// Native apps may redirect to a registered loopback URI on any port (RFC 8252 Section 7.3).
     pub fn validate_redirect_uri(&self, client: &OAuthClient, redirect_uri: &str) -> bool {
         client.has_redirect_uri(redirect_uri)
+            || (client.is_native()
+                && client
+                    .redirect_uris
+                    .iter()
+                    .any(|registered| loopback_redirect_matches(registered, redirect_uri)))
+    }
+
+    /// Redirect URI rules that apply to a client
+    pub fn redirect_profile(client: &OAuthClient) -> RedirectUriProfile {
+        RedirectUriProfile::for_client(client.is_internal, client.is_native())
     }
 
     /// Validate redirect URIs for client registration
     ///
     /// # Requirements
     /// - 1.4: Validate that each URI uses HTTPS protocol for External_App
-    pub fn validate_redirect_uris_for_registration(
+    pub async fn validate_redirect_uris_for_registration(
         &self,
         redirect_uris: &[String],
-        is_internal: bool,
+        profile: RedirectUriProfile,
     ) -> Result<(), OAuthError> {
         for uri in redirect_uris {
-            // For external apps, require HTTPS
-            if !is_internal && !uri.starts_with("https://") {
-                // Allow localhost for development
-                if !uri.starts_with("http://localhost") && !uri.starts_with("http://127.0.0.1") {
-                    return Err(OAuthError::InvalidRequest(format!(
-                        "External apps must use HTTPS for redirect URIs: {}",
-                        uri
-                    )));
-                }
+            self.check_redirect_uri_policy(uri, profile).await?;
+        }
+        Ok(())
+    }
+
+    /// Check a redirect URI against the redirect policy and the admin blocklist
+    pub async fn check_redirect_uri_policy(
+        &self,
+        uri: &str,
+        profile: RedirectUriProfile,
+    ) -> Result<(), OAuthError> {
+        if let Err(reason) = self.redirect_policy.check(uri, profile) {
+            return Err(OAuthError::InvalidRequest(format!(
+                "redirect_uri is not allowed ({}): {}",
+                reason, uri
+            )));
+        }
+
+        if let Some(block) = self.redirect_block_repo.find_matching(uri).await? {
+            return Err(OAuthError::InvalidRequest(format!(
+                "redirect_uri matches blocked pattern '{}': {}",
+                block.pattern, uri
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Check the URLs a client registers for hosted request objects
+    ///
+    /// The server fetches them, so each must be a public HTTPS URL without a
+    /// fragment and pass the redirect URI blocklist.
+    pub async fn validate_request_uris(&self, uris: &[String]) -> Result<(), OAuthError> {
+        if uris.len() > MAX_CLIENT_REQUEST_URIS {
+            return Err(OAuthError::InvalidRequest(format!(
+                "request_uris may contain at most {} URLs",
+                MAX_CLIENT_REQUEST_URIS
+            )));
+        }
+        for uri in uris {
+            if uri.contains('#') {
+                return Err(OAuthError::InvalidRequest(
+                    "request_uris must not contain a fragment".to_string(),
+                ));
             }
+            self.check_outbound_uri("request_uris", uri).await?;
+        }
+        Ok(())
+    }
+
+    /// Check a URL the server calls against the outbound URL rules and the admin blocklist
+    async fn check_outbound_uri(&self, field: &str, uri: &str) -> Result<(), OAuthError> {
+        if let Err(reason) = check_outbound_url(uri) {
+            return Err(OAuthError::InvalidRequest(format!(
+                "{} is not allowed ({}): {}",
+                field, reason, uri
+            )));
+        }
+
+        if let Some(block) = self.redirect_block_repo.find_matching(uri).await? {
+            return Err(OAuthError::InvalidRequest(format!(
+                "{} matches blocked pattern '{}': {}",
+                field, block.pattern, uri
+            )));
         }
+
         Ok(())
     }
 
+    /// Check a back-channel logout URI: a public HTTPS URL without a fragment,
+    /// since the server posts logout tokens to it
+    pub async fn validate_backchannel_logout_uri(&self, uri: Option<&str>) -> Result<(), OAuthError> {
+        let Some(uri) = uri else {
+            return Ok(());
+        };
+        if uri.contains('#') {
+            return Err(OAuthError::InvalidRequest(
+                "backchannel_logout_uri must not contain a fragment".to_string(),
+            ));
+        }
+        self.check_outbound_uri("backchannel_logout_uri", uri).await
+    }
+
+    // ========================================================================
+    // Logout (OpenID Connect RP-Initiated and Back-Channel Logout)
+    // ========================================================================
+
+    /// Revoke a user's OAuth tokens and notify the affected clients
+    ///
+    /// Every client holding an active token for the user and registering a
+    /// `backchannel_logout_uri` is sent a logout token. Deliveries run in the
+    /// background so a slow client cannot hold up the logout; failures are
+    /// only logged.
+    ///
+    /// # Returns
+    /// The number of tokens revoked and of clients notified
+    pub async fn logout_user(&self, user_id: Uuid) -> Result<(u64, usize), OAuthError> {
+        let client_ids = self.token_repo.list_client_ids_with_active_tokens(user_id).await?;
+        let tokens_revoked = self.token_repo.revoke_all_for_user(user_id).await?;
+
+        let mut notified = 0;
+        for client_id in client_ids {
+            let Some(client) = self.client_repo.find_by_id(client_id).await? else {
+                continue;
+            };
+            let Some(uri) = client.backchannel_logout_uri else {
+                continue;
+            };
+            let logout_token = self
+                .jwt_manager
+                .create_logout_token(&self.issuer, user_id, &client.client_id)
+                .map_err(|e| OAuthError::ServerError(format!("Failed to create logout token: {}", e)))?;
+
+            spawn_in_request(async move {
+                // Checked again at send time, pinned to the addresses that passed
+                let builder = reqwest::Client::builder()
+                    .timeout(std::time::Duration::from_secs(BACKCHANNEL_LOGOUT_TIMEOUT_SECS))
+                    .redirect(reqwest::redirect::Policy::none());
+                let http = match pinned_client(builder, &uri).await {
+                    Ok(http) => http,
+                    Err(reason) => {
+                        tracing::warn!(
+                            "Back-channel logout to client {} not sent: {}",
+                            client.client_id,
+                            reason
+                        );
+                        return;
+                    }
+                };
+                let result = http
+                    .post(&uri)
+                    .header(reqwest::header::CACHE_CONTROL, "no-store")
+                    .form(&[("logout_token", logout_token)])
+                    .send()
+                    .await;
+                match result {
+                    Ok(response) if response.status().is_success() => {}
+                    Ok(response) => tracing::warn!(
+                        "Back-channel logout to client {} returned {}",
+                        client.client_id,
+                        response.status()
+                    ),
+                    Err(e) => tracing::warn!("Back-channel logout to client {} failed: {}", client.client_id, e),
+                }
+            });
+            notified += 1;
+        }
+
+        Ok((tokens_revoked, notified))
+    }
 
     // ========================================================================
     // Authorization Code Generation (Task 8.3)
@@ -217,6 +592,13 @@ impl OAuthService {
     /// * `scopes` - The granted scopes
     /// * `code_challenge` - The PKCE code challenge
     /// * `code_challenge_method` - The PKCE method (default: "S256")
+    /// * `nonce` - The OpenID Connect nonce from the authorization request
+    /// * `session_binding_hash` - Hash of the approving session's access token
+    /// * `browser_binding` - The approving browser's binding cookie
+    /// * `auth_time` - When the approving user signed in
+    /// * `acr` - Authentication context class of that sign-in
+    /// * `amr` - Authentication methods of that sign-in
+    /// * `posture` - Posture of that sign-in, for clients with `posture_claims`
     ///
     /// # Returns
     /// * `Ok(String)` - The authorization code (plain text, to be sent to client)
@@ -232,12 +614,48 @@ impl OAuthService {
         scopes: &[String],
         code_challenge: &str,
         code_challenge_method: Option<&str>,
+        nonce: Option<&str>,
+        session_binding_hash: Option<&str>,
+        browser_binding: Option<&str>,
+        auth_time: Option<DateTime<Utc>>,
+        acr: Option<&str>,
+        amr: Option<&[String]>,
+        posture: Option<&PostureContext>,
     ) -> Result<String, OAuthError> {
+        // A nonce may only be used once per client, otherwise an old id_token
+        // could be replayed against it
+        if let Some(nonce) = nonce {
+            if nonce.is_empty() || nonce.len() > MAX_NONCE_LENGTH {
+                return Err(OAuthError::InvalidRequest(format!(
+                    "nonce must be between 1 and {} characters",
+                    MAX_NONCE_LENGTH
+                )));
+            }
+
+            if self.code_repo.nonce_exists(client_id, nonce).await? {
+                self.audit_repo
+                    .create(
+                        OAuthEventType::NonceReplayDetected,
+                        Some(client_id),
+                        Some(user_id),
+                        None,
+                        Some(serde_json::json!({
+                            "redirect_uri": redirect_uri,
+                        })),
+                    )
+                    .await
+                    .ok();
+
+                return Err(OAuthError::InvalidRequest("nonce has already been used".to_string()));
+            }
+        }
+
         // Generate a random authorization code
         let code = generate_oauth_token();
         let code_hash = hash_oauth_token(&code);
 
         let method = code_challenge_method.unwrap_or(PKCE_METHOD_S256);
+        let browser_binding_hash = browser_binding.map(hash_oauth_token);
 
         // Store the authorization code (max 10 minutes = 600 seconds)
         self.code_repo
@@ -249,7 +667,14 @@ impl OAuthService {
                 scopes,
                 code_challenge,
                 method,
+                nonce,
                 600, // 10 minutes max
+                session_binding_hash,
+                browser_binding_hash.as_deref(),
+                auth_time,
+                acr,
+                amr,
+                posture,
             )
             .await?;
 
@@ -284,6 +709,8 @@ impl OAuthService {
     /// * `client_secret` - The client's secret (optional for public clients)
     /// * `redirect_uri` - The redirect URI (must match the one used in authorization)
     /// * `code_verifier` - The PKCE code verifier
+    /// * `browser_binding` - Binding cookie of the browser making the request, if any;
+    ///   required for clients with cookie refresh delivery
     ///
     /// # Returns
     /// * `Ok(OAuthTokenResponse)` - The access and refresh tokens
@@ -301,6 +728,7 @@ impl OAuthService {
         client_secret: Option<&str>,
         redirect_uri: &str,
         code_verifier: &str,
+        browser_binding: Option<&str>,
     ) -> Result<OAuthTokenResponse, OAuthError> {
         // Find the client
         let client = self.client_repo
@@ -310,20 +738,30 @@ impl OAuthService {
 
         // Verify client secret if provided (confidential clients)
         if let Some(secret) = client_secret {
-            let valid = verify_secret(secret, &client.client_secret_hash)
-                .map_err(|_| OAuthError::InvalidClient)?;
+            let valid = self.verify_client_secret(&client, secret).await?;
             if !valid {
                 return Err(OAuthError::InvalidClient);
             }
+            self.check_secret_age(&client).await?;
         }
+        self.check_grant_type(&client, "authorization_code")?;
 
         // Find the authorization code
         let code_hash = hash_oauth_token(code);
         let auth_code = self.code_repo
-            .find_valid_by_code_hash(&code_hash)
+            .find_by_code_hash(&code_hash)
             .await?
             .ok_or_else(|| OAuthError::InvalidGrant("Invalid or expired authorization code".to_string()))?;
 
+        // A second redemption means the code leaked - revoke what it issued
+        if auth_code.used {
+            self.revoke_tokens_from_reused_code(&auth_code).await?;
+            return Err(OAuthError::InvalidGrant("Invalid or expired authorization code".to_string()));
+        }
+        if auth_code.is_expired() {
+            return Err(OAuthError::InvalidGrant("Invalid or expired authorization code".to_string()));
+        }
+
         // Verify the code belongs to this client
         if auth_code.client_id != client.id {
             return Err(OAuthError::InvalidGrant("Authorization code was not issued to this client".to_string()));
@@ -344,17 +782,83 @@ impl OAuthService {
             return Err(OAuthError::InvalidGrant("code_verifier does not match code_challenge".to_string()));
         }
 
-        // Mark the code as used
-        self.code_repo.mark_as_used(auth_code.id).await?;
+        // The session that approved the code must still be signed in
+        if let Some(binding_hash) = &auth_code.session_binding_hash {
+            let revoked = self.revoked_token_repo
+                .is_revoked(binding_hash)
+                .await
+                .map_err(|e| OAuthError::ServerError(e.to_string()))?;
+            if revoked {
+                return Err(OAuthError::InvalidGrant("Authorizing session has ended".to_string()));
+            }
+        }
+
+        // A browser that presents its binding cookie must be the one that
+        // approved the code, otherwise the code was injected into its callback.
+        // Backends redeem without the cookie, so this only protects clients
+        // that redeem from the browser; cookie-delivery SPAs always do, and
+        // for them a missing cookie counts as a different browser.
+        if let Some(expected) = &auth_code.browser_binding_hash {
+            let matches = match browser_binding {
+                Some(presented) => constant_time_compare(expected, &hash_oauth_token(presented)),
+                None => !client.refresh_token_cookie,
+            };
+            if !matches {
+                self.audit_repo
+                    .create(
+                        OAuthEventType::CodeBrowserMismatch,
+                        Some(client.id),
+                        Some(auth_code.user_id),
+                        None,
+                        Some(serde_json::json!({
+                            "redirect_uri": redirect_uri,
+                        })),
+                    )
+                    .await
+                    .ok();
+
+                return Err(OAuthError::InvalidGrant(
+                    "Authorization code was issued to a different browser".to_string(),
+                ));
+            }
+        }
+
+        // Mark the code as used - only one concurrent redemption wins
+        if !self.code_repo.mark_as_used(auth_code.id).await? {
+            self.revoke_tokens_from_reused_code(&auth_code).await?;
+            return Err(OAuthError::InvalidGrant("Invalid or expired authorization code".to_string()));
+        }
 
         // Issue tokens
-        let token_response = self.issue_tokens(
+        let mut token_response = self.issue_tokens(
             Some(auth_code.user_id),
-            client.id,
-            &client.client_id,
+            &client,
             &auth_code.scopes,
+            None,
+            Some(auth_code.id),
+            auth_code.posture.as_ref(),
         ).await?;
 
+        // OpenID Connect: include an ID token echoing the request nonce
+        if auth_code.scopes.iter().any(|s| s == "openid") {
+            let auth_time = auth_code.auth_time.unwrap_or(auth_code.created_at);
+            let mut user_claims = self
+                .id_token_user_claims(auth_code.user_id, &auth_code.scopes, auth_time)
+                .await?;
+            user_claims.acr = auth_code.acr.clone();
+            user_claims.amr = auth_code.amr.clone();
+            let id_token = self.jwt_manager
+                .create_id_token(
+                    &self.issuer,
+                    auth_code.user_id,
+                    &client.client_id,
+                    auth_code.nonce.as_deref(),
+                    user_claims,
+                )
+                .map_err(|e| OAuthError::ServerError(e.to_string()))?;
+            token_response.id_token = Some(id_token);
+        }
+
         // Log the event
         self.audit_repo
             .create(
@@ -374,6 +878,60 @@ impl OAuthService {
     }
 
 
+    /// auth_time and the email claims the granted scopes release, for an ID token
+    ///
+    /// Codes issued before auth_time was recorded pass the time of consent.
+    async fn id_token_user_claims(
+        &self,
+        user_id: Uuid,
+        scopes: &[String],
+        auth_time: DateTime<Utc>,
+    ) -> Result<IdTokenUserClaims, OAuthError> {
+        let custom_codes: Vec<String> = scopes.iter().filter(|s| s.contains(':')).cloned().collect();
+        let custom_claims: Vec<Vec<String>> = self.scope_repo
+            .find_by_codes(&custom_codes)
+            .await?
+            .into_iter()
+            .map(|scope| scope.claims)
+            .collect();
+        let released = released_claims(scopes.iter().map(String::as_str), &custom_claims);
+
+        let user = UserRepository::new(self.pool.clone())
+            .find_by_id(user_id)
+            .await
+            .map_err(|e| OAuthError::ServerError(e.to_string()))?
+            .ok_or_else(|| OAuthError::InvalidGrant("User not found".to_string()))?;
+
+        Ok(IdTokenUserClaims {
+            auth_time: Some(auth_time.timestamp()),
+            email: released.contains("email").then(|| user.email.clone()),
+            email_verified: released.contains("email_verified").then_some(user.email_verified),
+            ..Default::default()
+        })
+    }
+
+    /// Revoke every token issued from an authorization code that was redeemed twice
+    async fn revoke_tokens_from_reused_code(&self, auth_code: &AuthorizationCode) -> Result<(), OAuthError> {
+        let revoked = self.token_repo.revoke_by_authorization_code(auth_code.id).await?;
+
+        self.audit_repo
+            .create(
+                OAuthEventType::TokenRevoked,
+                Some(auth_code.client_id),
+                Some(auth_code.user_id),
+                None,
+                Some(serde_json::json!({
+                    "reason": "authorization_code_reuse",
+                    "authorization_code_id": auth_code.id.to_string(),
+                    "tokens_revoked": revoked,
+                })),
+            )
+            .await
+            .ok();
+
+        Ok(())
+    }
+
     // ========================================================================
     // Client Credentials Flow (Task 8.7)
     // Requirements: 6.1, 6.2, 6.5
@@ -409,8 +967,7 @@ impl OAuthService {
             .ok_or(OAuthError::InvalidClient)?;
 
         // Verify client secret
-        let valid = verify_secret(client_secret, &client.client_secret_hash)
-            .map_err(|_| OAuthError::InvalidClient)?;
+        let valid = self.verify_client_secret(&client, client_secret).await?;
         if !valid {
             // Log failed attempt
             self.audit_repo
@@ -425,21 +982,22 @@ impl OAuthService {
                 .ok();
             return Err(OAuthError::InvalidClient);
         }
+        self.check_secret_age(&client).await?;
 
-        // Validate scopes if provided
-        if !scopes.is_empty() {
-            let valid = self.scope_repo.validate_scopes(scopes).await?;
-            if !valid {
-                return Err(OAuthError::InvalidScope(
-                    "One or more requested scopes are invalid".to_string(),
-                ));
-            }
+        // Native apps are public clients and cannot keep a secret (RFC 8252 Section 8.5)
+        if client.is_native() {
+            return Err(OAuthError::UnauthorizedClient);
         }
+        self.check_grant_type(&client, "client_credentials")?;
+
+        // Validate scopes if provided
+        self.validate_scopes(scopes, &client).await?;
 
         // Issue access token only (no refresh token for client credentials)
         // Requirements: 6.5
+        let expires_in = self.access_token_ttl(&client);
         let access_token = self.jwt_manager
-            .create_oauth2_client_credentials_token(&client.client_id, scopes.to_vec())
+            .create_oauth2_client_credentials_token_with_expiry(&client.client_id, scopes.to_vec(), expires_in)
             .map_err(|e| OAuthError::ServerError(format!("Failed to create token: {}", e)))?;
 
         let access_token_hash = hash_oauth_token(&access_token);
@@ -452,7 +1010,10 @@ impl OAuthService {
                 &access_token_hash,
                 None, // No refresh token
                 scopes,
-                self.jwt_manager.access_token_expiry_secs(),
+                expires_in,
+                None,
+                None,
+                None,
             )
             .await?;
 
@@ -474,59 +1035,579 @@ impl OAuthService {
         Ok(OAuthTokenResponse::new(
             access_token,
             None, // No refresh token for client credentials
-            self.jwt_manager.access_token_expiry_secs(),
+            expires_in,
             scopes,
         ))
     }
 
     // ========================================================================
-    // Token Refresh (Task 8.9)
-    // Requirements: 7.1, 7.2, 7.4
+    // Device Authorization Grant (RFC 8628)
     // ========================================================================
 
-    /// Refresh an access token using a refresh token
+    /// Start a device authorization for a client that cannot open a browser
     ///
     /// # Arguments
-    /// * `refresh_token` - The refresh token
     /// * `client_id` - The client's public identifier
+    /// * `client_secret` - The client's secret (optional for public clients)
+    /// * `scopes` - The requested scopes
+    /// * `verification_uri` - Where the user enters the user code
     ///
     /// # Returns
-    /// * `Ok(OAuthTokenResponse)` - New access and refresh tokens
-    /// * `Err(OAuthError)` - If refresh fails
-    ///
-    /// # Requirements
-    /// - 7.1: Issue a new access_token when valid refresh_token is provided
-    /// - 7.2: Implement refresh token rotation (issue new refresh_token on each refresh)
-    /// - 7.3: Reject with invalid_grant error if refresh_token is expired or invalid
-    /// - 7.4: Invalidate the old refresh_token after rotation
-    /// - 7.5: If a revoked refresh_token is used, revoke all tokens for that client-user pair
-    pub async fn refresh_token(
+    /// * `Ok(DeviceAuthorizationResponse)` - The device code to poll with and the user code to show
+    /// * `Err(OAuthError)` - If the client or scopes are invalid
+    pub async fn start_device_authorization(
         &self,
-        refresh_token: &str,
         client_id: &str,
-    ) -> Result<OAuthTokenResponse, OAuthError> {
-        // Find the client
-        let client = self.client_repo
-            .find_active_by_client_id(client_id)
-            .await?
-            .ok_or(OAuthError::InvalidClient)?;
-
-        // Find the token by refresh token hash
-        let refresh_token_hash = hash_oauth_token(refresh_token);
-        let token = self.token_repo
-            .find_by_refresh_token_hash(&refresh_token_hash)
-            .await?
-            .ok_or_else(|| OAuthError::InvalidGrant("Invalid refresh token".to_string()))?;
-
-        // Check if token belongs to this client
-        if token.client_id != client.id {
-            return Err(OAuthError::InvalidGrant("Refresh token was not issued to this client".to_string()));
-        }
+        client_secret: Option<&str>,
+        scopes: &[String],
+        verification_uri: &str,
+    ) -> Result<DeviceAuthorizationResponse, OAuthError> {
+        let client = self.find_client_checking_secret(client_id, client_secret).await?;
+        self.check_grant_type(&client, DEVICE_CODE_GRANT_TYPE)?;
+
+        self.validate_scopes(scopes, &client).await?;
+
+        let device_code = generate_oauth_token();
+        let user_code = generate_user_code();
+        let user_code_hash = normalize_user_code(&user_code)
+            .map(|code| hash_oauth_token(&code))
+            .ok_or_else(|| OAuthError::ServerError("Generated an invalid user code".to_string()))?;
+
+        let now = Utc::now();
+        let pending = DeviceCodeEntry {
+            id: Uuid::new_v4(),
+            device_code_hash: hash_oauth_token(&device_code),
+            client_id: client.id,
+            scopes: scopes.to_vec(),
+            status: DeviceCodeStatus::Pending,
+            user_id: None,
+            auth_time: None,
+            interval_secs: DEVICE_POLL_INTERVAL_SECS,
+            last_polled_at: None,
+            expires_at: now + chrono::Duration::seconds(DEVICE_CODE_EXPIRY_SECS),
+            created_at: now,
+        };
+        let stored = serde_json::to_value(&pending)
+            .map_err(|e| OAuthError::ServerError(format!("Failed to serialize device code: {}", e)))?;
+        let ttl_secs = DEVICE_CODE_EXPIRY_SECS + DEVICE_CODE_RETENTION_SECS;
+        self.challenges
+            .put(CHALLENGE_KIND_DEVICE_CODE, &pending.device_code_hash, &stored, ttl_secs)
+            .await
+            .map_err(store_error)?;
+        self.challenges
+            .put(CHALLENGE_KIND_DEVICE_USER_CODE, &user_code_hash, &pending.device_code_hash.as_str().into(), ttl_secs)
+            .await
+            .map_err(store_error)?;
 
-        // Check if token is revoked
-        // Requirement 7.5: If revoked token is used, revoke all tokens for client-user pair
-        if token.revoked {
-            if let Some(user_id) = token.user_id {
+        self.audit_repo
+            .create(
+                OAuthEventType::AuthorizationRequested,
+                Some(client.id),
+                None,
+                None,
+                Some(serde_json::json!({
+                    "scopes": scopes,
+                    "grant_type": "device_code",
+                })),
+            )
+            .await
+            .ok();
+
+        Ok(DeviceAuthorizationResponse {
+            device_code,
+            verification_uri_complete: format!(
+                "{}?user_code={}",
+                verification_uri,
+                urlencoding::encode(&user_code)
+            ),
+            user_code,
+            verification_uri: verification_uri.to_string(),
+            expires_in: DEVICE_CODE_EXPIRY_SECS,
+            interval: DEVICE_POLL_INTERVAL_SECS,
+        })
+    }
+
+    /// Find the pending device authorization a user code refers to
+    ///
+    /// # Returns
+    /// * `Ok((DeviceCode, OAuthClient))` - The authorization and the client asking for it
+    /// * `Err(OAuthError::InvalidRequest)` - If the code is unknown, expired or already decided
+    pub async fn find_pending_device_authorization(
+        &self,
+        user_code: &str,
+    ) -> Result<(DeviceCode, OAuthClient), OAuthError> {
+        let invalid = || OAuthError::InvalidRequest("Invalid or expired user_code".to_string());
+
+        let user_code = normalize_user_code(user_code).ok_or_else(invalid)?;
+        let device_code_hash = self.challenges
+            .get_as::<String>(CHALLENGE_KIND_DEVICE_USER_CODE, &hash_oauth_token(&user_code))
+            .await
+            .map_err(store_error)?
+            .ok_or_else(invalid)?;
+        let device_code = self
+            .find_device_code(&device_code_hash)
+            .await?
+            .filter(|code| code.status == DeviceCodeStatus::Pending && !code.is_expired())
+            .ok_or_else(invalid)?;
+
+        let client = self.client_repo
+            .find_by_id(device_code.client_id)
+            .await?
+            .filter(|client| client.is_active)
+            .ok_or_else(invalid)?;
+
+        Ok((device_code, client))
+    }
+
+    /// Record the signed-in user's decision on a device authorization
+    ///
+    /// Approval stores consent exactly as the authorization code flow does.
+    ///
+    /// # Arguments
+    /// * `user_code` - The code shown on the device
+    /// * `user_id` - The approving user
+    /// * `approved` - Whether the user approved the device
+    /// * `auth_time` - When the user signed in (the id_token auth_time)
+    pub async fn decide_device_authorization(
+        &self,
+        user_code: &str,
+        user_id: Uuid,
+        approved: bool,
+        auth_time: DateTime<Utc>,
+    ) -> Result<(), OAuthError> {
+        let (device_code, client) = self.find_pending_device_authorization(user_code).await?;
+
+        if !approved {
+            self.update_device_code(&device_code.device_code_hash, |code| {
+                if code.status != DeviceCodeStatus::Pending {
+                    return false;
+                }
+                code.status = DeviceCodeStatus::Denied;
+                code.user_id = Some(user_id);
+                true
+            })
+            .await?;
+            self.consent_service
+                .log_consent_denied(user_id, client.id, &device_code.scopes)
+                .await
+                .ok();
+            return Ok(());
+        }
+
+        // First-party clients record an implicit grant, external ones a consent
+        if client.skips_consent() {
+            self.consent_service
+                .grant_implicit_consent(user_id, client.id, &device_code.scopes)
+                .await?;
+        } else if client.is_external() {
+            self.consent_service
+                .grant_consent(user_id, client.id, &device_code.scopes)
+                .await?;
+        }
+
+        let approved = self.update_device_code(&device_code.device_code_hash, |code| {
+            if code.status != DeviceCodeStatus::Pending || code.is_expired() {
+                return false;
+            }
+            code.status = DeviceCodeStatus::Approved;
+            code.user_id = Some(user_id);
+            code.auth_time = Some(auth_time);
+            true
+        })
+        .await?;
+        if !approved {
+            return Err(OAuthError::InvalidRequest("Invalid or expired user_code".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Device code grant: the device polls until the user has decided
+    ///
+    /// # Arguments
+    /// * `device_code` - The device code from the device authorization response
+    /// * `client_id` - The client's public identifier
+    /// * `client_secret` - The client's secret (optional for public clients)
+    ///
+    /// # Returns
+    /// * `Ok(OAuthTokenResponse)` - Tokens, once the user approved
+    /// * `Err(OAuthError::AuthorizationPending)` - The user has not decided yet
+    /// * `Err(OAuthError::SlowDown { .. })` - The device polled before its interval elapsed
+    /// * `Err(OAuthError::AccessDenied)` - The user denied the device
+    /// * `Err(OAuthError::ExpiredToken)` - The device code expired
+    pub async fn device_code_grant(
+        &self,
+        device_code: &str,
+        client_id: &str,
+        client_secret: Option<&str>,
+    ) -> Result<OAuthTokenResponse, OAuthError> {
+        let client = self.find_client_checking_secret(client_id, client_secret).await?;
+        self.check_grant_type(&client, DEVICE_CODE_GRANT_TYPE)?;
+
+        let code = self
+            .find_device_code(&hash_oauth_token(device_code))
+            .await?
+            .ok_or_else(|| OAuthError::InvalidGrant("Invalid device code".to_string()))?;
+
+        if code.client_id != client.id {
+            return Err(OAuthError::InvalidGrant("Device code was not issued to this client".to_string()));
+        }
+        if code.status == DeviceCodeStatus::Redeemed {
+            return Err(OAuthError::InvalidGrant("Device code has already been used".to_string()));
+        }
+        if code.is_expired() {
+            return Err(OAuthError::ExpiredToken);
+        }
+
+        let user_id = match (code.status, code.user_id) {
+            (DeviceCodeStatus::Denied, _) => return Err(OAuthError::AccessDenied),
+            (DeviceCodeStatus::Approved, Some(user_id)) => user_id,
+            _ => {
+                // Each poll inside the interval pushes the interval further out
+                if code.polled_too_soon() {
+                    let interval_secs = code.interval_secs + SLOW_DOWN_INCREMENT_SECS;
+                    self.record_device_poll(&code.device_code_hash, interval_secs).await?;
+                    return Err(OAuthError::SlowDown {
+                        interval_secs: interval_secs.into(),
+                    });
+                }
+                self.record_device_poll(&code.device_code_hash, code.interval_secs).await?;
+                return Err(OAuthError::AuthorizationPending);
+            }
+        };
+
+        // Only one concurrent poll collects the tokens
+        let redeemed = self.update_device_code(&code.device_code_hash, |code| {
+            if code.status != DeviceCodeStatus::Approved || code.is_expired() {
+                return false;
+            }
+            code.status = DeviceCodeStatus::Redeemed;
+            true
+        })
+        .await?;
+        if !redeemed {
+            return Err(OAuthError::InvalidGrant("Device code has already been used".to_string()));
+        }
+
+        let mut token_response = self.issue_tokens(
+            Some(user_id),
+            &client,
+            &code.scopes,
+            None,
+            None,
+            None,
+        ).await?;
+
+        if code.scopes.iter().any(|s| s == "openid") {
+            let auth_time = code.auth_time.unwrap_or(code.created_at);
+            let user_claims = self.id_token_user_claims(user_id, &code.scopes, auth_time).await?;
+            let id_token = self.jwt_manager
+                .create_id_token(&self.issuer, user_id, &client.client_id, None, user_claims)
+                .map_err(|e| OAuthError::ServerError(e.to_string()))?;
+            token_response.id_token = Some(id_token);
+        }
+
+        self.audit_repo
+            .create(
+                OAuthEventType::TokenIssued,
+                Some(client.id),
+                Some(user_id),
+                None,
+                Some(serde_json::json!({
+                    "scopes": code.scopes,
+                    "grant_type": "device_code",
+                })),
+            )
+            .await
+            .ok();
+
+        Ok(token_response)
+    }
+
+    /// Load a device authorization by its device code hash
+    async fn find_device_code(&self, device_code_hash: &str) -> Result<Option<DeviceCode>, OAuthError> {
+        let entry = self.challenges
+            .get_as::<DeviceCodeEntry>(CHALLENGE_KIND_DEVICE_CODE, device_code_hash)
+            .await
+            .map_err(store_error)?;
+
+        Ok(entry.map(DeviceCode::from))
+    }
+
+    /// Change a device authorization; `change` returns false to leave it alone
+    /// Returns false if it was gone or `change` declined
+    async fn update_device_code(
+        &self,
+        device_code_hash: &str,
+        change: impl FnMut(&mut DeviceCodeEntry) -> bool,
+    ) -> Result<bool, OAuthError> {
+        self.challenges
+            .update(CHALLENGE_KIND_DEVICE_CODE, device_code_hash, change)
+            .await
+            .map_err(store_error)
+    }
+
+    /// Record a poll of the token endpoint and the interval the device must keep
+    async fn record_device_poll(&self, device_code_hash: &str, interval_secs: i32) -> Result<(), OAuthError> {
+        self.update_device_code(device_code_hash, |code| {
+            code.last_polled_at = Some(Utc::now());
+            code.interval_secs = interval_secs;
+            true
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Find a client, checking its secret if one was sent (device flow and PAR)
+    async fn find_client_checking_secret(
+        &self,
+        client_id: &str,
+        client_secret: Option<&str>,
+    ) -> Result<OAuthClient, OAuthError> {
+        let client = self.client_repo
+            .find_active_by_client_id(client_id)
+            .await?
+            .ok_or(OAuthError::InvalidClient)?;
+
+        if let Some(secret) = client_secret {
+            let valid = self.verify_client_secret(&client, secret).await?;
+            if !valid {
+                self.audit_repo
+                    .create(
+                        OAuthEventType::InvalidClientCredentials,
+                        Some(client.id),
+                        None,
+                        None,
+                        None,
+                    )
+                    .await
+                    .ok();
+                return Err(OAuthError::InvalidClient);
+            }
+            self.check_secret_age(&client).await?;
+        }
+
+        Ok(client)
+    }
+
+    // ========================================================================
+    // Pushed Authorization Requests (RFC 9126)
+    // ========================================================================
+
+    /// Store the parameters of an authorization request pushed by a client
+    ///
+    /// The request is validated as `GET /oauth/authorize` would validate it.
+    /// Confidential clients must authenticate; native apps push with their
+    /// client_id alone. The returned request_uri can be used once.
+    pub async fn push_authorization_request(
+        &self,
+        req: &AuthorizationRequest,
+        client_secret: Option<&str>,
+    ) -> Result<PushedAuthorizationResponse, OAuthError> {
+        let client = self.find_client_checking_secret(&req.client_id, client_secret).await?;
+        if client_secret.is_none() && !client.is_native() {
+            return Err(OAuthError::InvalidClient);
+        }
+
+        if req.request_uri.is_some() {
+            return Err(OAuthError::InvalidRequest(
+                "request_uri is not allowed in a pushed authorization request".to_string(),
+            ));
+        }
+        let resolved;
+        let req = match req.request {
+            Some(_) => {
+                resolved = self.resolve_request_object(req).await?;
+                &resolved
+            }
+            None => req,
+        };
+        if req.response_type != "code" {
+            return Err(OAuthError::InvalidRequest(
+                "Only response_type=code is supported".to_string(),
+            ));
+        }
+
+        self.validate_authorization_request(
+            &req.client_id,
+            &req.redirect_uri,
+            &req.scopes(),
+            req.code_challenge.as_deref(),
+            req.code_challenge_method.as_deref(),
+        )
+        .await?;
+        self.check_strict_authorization_request(&client, req.state.as_deref())?;
+
+        let reference = generate_oauth_token();
+        let parameters = serde_json::to_value(req)
+            .map_err(|e| OAuthError::ServerError(format!("Failed to serialize request: {}", e)))?;
+        let pushed = serde_json::json!({
+            "client_id": client.id,
+            "parameters": parameters,
+        });
+        self.challenges
+            .put(CHALLENGE_KIND_PAR, &hash_oauth_token(&reference), &pushed, PAR_REQUEST_URI_EXPIRY_SECS)
+            .await
+            .map_err(store_error)?;
+
+        Ok(PushedAuthorizationResponse {
+            request_uri: format!("{}{}", PAR_REQUEST_URI_PREFIX, reference),
+            expires_in: PAR_REQUEST_URI_EXPIRY_SECS,
+        })
+    }
+
+    /// Resolve a request_uri into the authorization request pushed for it
+    ///
+    /// The request_uri must belong to `client_id`, must not have expired and
+    /// is consumed by this call.
+    pub async fn resolve_pushed_authorization_request(
+        &self,
+        request_uri: &str,
+        client_id: &str,
+    ) -> Result<AuthorizationRequest, OAuthError> {
+        let invalid = || OAuthError::InvalidRequest("Invalid or expired request_uri".to_string());
+
+        let reference = request_uri.strip_prefix(PAR_REQUEST_URI_PREFIX).ok_or_else(invalid)?;
+        let key = hash_oauth_token(reference);
+        let pushed = self.challenges
+            .get(CHALLENGE_KIND_PAR, &key)
+            .await
+            .map_err(store_error)?
+            .ok_or_else(invalid)?;
+
+        let client = self.client_repo
+            .find_active_by_client_id(client_id)
+            .await?
+            .ok_or(OAuthError::InvalidClient)?;
+        if pushed["client_id"].as_str() != Some(client.id.to_string().as_str()) {
+            return Err(invalid());
+        }
+
+        // Only one concurrent resolution gets the parameters
+        let mut pushed = self.challenges
+            .take(CHALLENGE_KIND_PAR, &key)
+            .await
+            .map_err(store_error)?
+            .ok_or_else(invalid)?;
+
+        serde_json::from_value(pushed["parameters"].take())
+            .map_err(|e| OAuthError::ServerError(format!("Stored request is unreadable: {}", e)))
+    }
+
+    // ========================================================================
+    // Request Objects (RFC 9101)
+    // ========================================================================
+
+    /// Apply the signed request object in `req.request` to the request
+    ///
+    /// The object must be signed with a key from the client's registered JWK
+    /// Set and addressed to this server's issuer. Its parameters win over the
+    /// query; in strict mode they are the only parameters used.
+    pub async fn resolve_request_object(
+        &self,
+        req: &AuthorizationRequest,
+    ) -> Result<AuthorizationRequest, OAuthError> {
+        let token = req.request.as_deref().ok_or_else(|| {
+            OAuthError::InvalidRequest("request is required".to_string())
+        })?;
+
+        let client = self.client_repo
+            .find_active_by_client_id(&req.client_id)
+            .await?
+            .ok_or(OAuthError::InvalidClient)?;
+
+        let registered = self.client_jwks_repo.find(client.id).await?.ok_or_else(|| {
+            OAuthError::InvalidRequestObject("Client has no registered keys".to_string())
+        })?;
+        let jwks = validate_client_jwks(&registered.jwks).map_err(OAuthError::InvalidRequestObject)?;
+
+        let claims = verify_request_object(token, &jwks, &client.client_id, &self.issuer)
+            .map_err(OAuthError::InvalidRequestObject)?;
+
+        req.with_request_object(claims, self.strict)
+            .map_err(OAuthError::InvalidRequestObject)
+    }
+
+    /// Fetch the request object `req.request_uri` points at and apply it
+    ///
+    /// The URL must be one the client registered in `request_uris`; only then
+    /// is it fetched, without following redirects. The fetched object is
+    /// verified exactly like one passed in `request`.
+    pub async fn resolve_request_uri(
+        &self,
+        req: &AuthorizationRequest,
+    ) -> Result<AuthorizationRequest, OAuthError> {
+        let request_uri = req.request_uri.as_deref().ok_or_else(|| {
+            OAuthError::InvalidRequest("request_uri is required".to_string())
+        })?;
+
+        let client = self.client_repo
+            .find_active_by_client_id(&req.client_id)
+            .await?
+            .ok_or(OAuthError::InvalidClient)?;
+        if !client.request_uris.iter().any(|registered| registered == request_uri) {
+            return Err(OAuthError::InvalidRequestUri(
+                "request_uri is not registered for this client".to_string(),
+            ));
+        }
+
+        let request = fetch_request_object(request_uri).await?;
+        let mut with_object = req.clone();
+        with_object.request_uri = None;
+        with_object.request = Some(request);
+        self.resolve_request_object(&with_object).await
+    }
+
+    // ========================================================================
+    // Token Refresh (Task 8.9)
+    // Requirements: 7.1, 7.2, 7.4
+    // ========================================================================
+
+    /// Refresh an access token using a refresh token
+    ///
+    /// # Arguments
+    /// * `refresh_token` - The refresh token
+    /// * `client_id` - The client's public identifier
+    ///
+    /// # Returns
+    /// * `Ok(OAuthTokenResponse)` - New access and refresh tokens
+    /// * `Err(OAuthError)` - If refresh fails
+    ///
+    /// # Requirements
+    /// - 7.1: Issue a new access_token when valid refresh_token is provided
+    /// - 7.2: Implement refresh token rotation (issue new refresh_token on each refresh)
+    /// - 7.3: Reject with invalid_grant error if refresh_token is expired or invalid
+    /// - 7.4: Invalidate the old refresh_token after rotation
+    /// - 7.5: If a revoked refresh_token is used, revoke all tokens for that client-user pair
+    pub async fn refresh_token(
+        &self,
+        refresh_token: &str,
+        client_id: &str,
+    ) -> Result<OAuthTokenResponse, OAuthError> {
+        // Find the client
+        let client = self.client_repo
+            .find_active_by_client_id(client_id)
+            .await?
+            .ok_or(OAuthError::InvalidClient)?;
+        self.check_grant_type(&client, "refresh_token")?;
+
+        // Find the token by refresh token hash
+        let refresh_token_hash = hash_oauth_token(refresh_token);
+        let token = self.token_repo
+            .find_by_refresh_token_hash(&refresh_token_hash)
+            .await?
+            .ok_or_else(|| OAuthError::InvalidGrant("Invalid refresh token".to_string()))?;
+
+        // Check if token belongs to this client
+        if token.client_id != client.id {
+            return Err(OAuthError::InvalidGrant("Refresh token was not issued to this client".to_string()));
+        }
+
+        // Check if token is revoked
+        // Requirement 7.5: If revoked token is used, revoke all tokens for client-user pair
+        if token.revoked {
+            if let Some(user_id) = token.user_id {
                 self.token_repo.revoke_all_for_user_client(user_id, client.id).await?;
                 
                 // Log the cascade revocation
@@ -547,6 +1628,19 @@ impl OAuthService {
             return Err(OAuthError::InvalidGrant("Refresh token has been revoked".to_string()));
         }
 
+        // Enforce the client's session policy: idle time since the last
+        // rotation and total time since the original grant
+        let policy = self.session_defaults.with_overrides(
+            client.session_idle_timeout_secs,
+            client.session_absolute_lifetime_secs,
+        );
+        if policy.is_idle_expired(token.created_at)
+            || policy.absolute_expiry(token.session_started_at) < Utc::now()
+        {
+            self.token_repo.revoke(token.id).await?;
+            return Err(OAuthError::InvalidGrant("Refresh token has expired".to_string()));
+        }
+
         // Revoke the old token (rotation)
         // Requirement 7.4
         self.token_repo.revoke(token.id).await?;
@@ -554,9 +1648,11 @@ impl OAuthService {
         // Issue new tokens
         let token_response = self.issue_tokens(
             token.user_id,
-            client.id,
-            &client.client_id,
+            &client,
             &token.scopes,
+            Some(token.session_started_at),
+            token.authorization_code_id,
+            token.posture.as_ref(),
         ).await?;
 
         // Log the event
@@ -664,6 +1760,144 @@ impl OAuthService {
         Ok(())
     }
 
+    // ========================================================================
+    // Token Introspection (RFC 7662)
+    // ========================================================================
+
+    /// Check whether an access or refresh token is active
+    ///
+    /// The calling client authenticates with its secret. A client can only
+    /// introspect tokens issued to itself; internal clients (resource servers
+    /// run by us) can introspect any token.
+    ///
+    /// # Arguments
+    /// * `token` - The access token or refresh token
+    /// * `token_type_hint` - "access_token" or "refresh_token", tried first
+    /// * `client_id` - The calling client's public identifier
+    /// * `client_secret` - The calling client's secret
+    ///
+    /// # Returns
+    /// * `Ok(IntrospectionResponse)` - `active: false` for any token that is
+    ///   unknown, expired, revoked or not visible to the client
+    /// * `Err(OAuthError)` - If client authentication fails
+    pub async fn introspect_token(
+        &self,
+        token: &str,
+        token_type_hint: Option<&str>,
+        client_id: &str,
+        client_secret: &str,
+    ) -> Result<IntrospectionResponse, OAuthError> {
+        // Find the client
+        let client = self.client_repo
+            .find_active_by_client_id(client_id)
+            .await?
+            .ok_or(OAuthError::InvalidClient)?;
+
+        // Verify client secret
+        let valid = self.verify_client_secret(&client, client_secret).await?;
+        if !valid {
+            self.audit_repo
+                .create(
+                    OAuthEventType::InvalidClientCredentials,
+                    Some(client.id),
+                    None,
+                    None,
+                    None,
+                )
+                .await
+                .ok();
+            return Err(OAuthError::InvalidClient);
+        }
+        self.check_secret_age(&client).await?;
+
+        // Native apps cannot keep a secret and are never resource servers
+        if client.is_native() {
+            return Err(OAuthError::UnauthorizedClient);
+        }
+
+        let token_hash = hash_oauth_token(token);
+        let refresh_first = token_type_hint == Some("refresh_token");
+
+        let mut found = None;
+        for is_refresh in [refresh_first, !refresh_first] {
+            let oauth_token = if is_refresh {
+                self.token_repo.find_by_refresh_token_hash(&token_hash).await?
+            } else {
+                self.token_repo.find_by_access_token_hash(&token_hash).await?
+            };
+            if let Some(oauth_token) = oauth_token {
+                found = Some((oauth_token, is_refresh));
+                break;
+            }
+        }
+
+        let Some((oauth_token, is_refresh)) = found else {
+            return Ok(IntrospectionResponse::inactive());
+        };
+
+        // Do not reveal tokens of other clients
+        if oauth_token.client_id != client.id && !client.is_internal {
+            return Ok(IntrospectionResponse::inactive());
+        }
+
+        if oauth_token.revoked {
+            return Ok(IntrospectionResponse::inactive());
+        }
+
+        let issued_to = if oauth_token.client_id == client.id {
+            client
+        } else {
+            match self.client_repo.find_by_id(oauth_token.client_id).await? {
+                Some(issued_to) => issued_to,
+                None => return Ok(IntrospectionResponse::inactive()),
+            }
+        };
+
+        let (exp, token_type) = if is_refresh {
+            // Refresh tokens live as long as the client's session policy allows
+            let policy = self.session_defaults.with_overrides(
+                issued_to.session_idle_timeout_secs,
+                issued_to.session_absolute_lifetime_secs,
+            );
+            if policy.is_idle_expired(oauth_token.created_at) {
+                return Ok(IntrospectionResponse::inactive());
+            }
+            let expires_at = policy.absolute_expiry(oauth_token.session_started_at);
+            if expires_at < Utc::now() {
+                return Ok(IntrospectionResponse::inactive());
+            }
+            (expires_at.timestamp(), "refresh_token")
+        } else {
+            // The stored expiry and the signed one agree; checking the JWT
+            // also catches tokens signed with a retired key
+            if oauth_token.is_expired() || self.jwt_manager.verify_oauth2_token(token).is_err() {
+                return Ok(IntrospectionResponse::inactive());
+            }
+            (oauth_token.expires_at.timestamp(), "Bearer")
+        };
+
+        let azp_ctx = oauth_token
+            .posture
+            .filter(|_| issued_to.posture_claims)
+            .and_then(|posture| posture.claim());
+
+        Ok(IntrospectionResponse {
+            active: true,
+            scope: Some(oauth_token.scopes.join(" ")),
+            sub: Some(
+                oauth_token
+                    .user_id
+                    .map(|id| id.to_string())
+                    .unwrap_or_else(|| issued_to.client_id.clone()),
+            ),
+            client_id: Some(issued_to.client_id),
+            exp: Some(exp),
+            iat: Some(oauth_token.created_at.timestamp()),
+            token_type: Some(token_type.to_string()),
+            azp_ctx,
+        })
+    }
+
     /// Revoke all tokens for a user-client pair
     ///
     /// # Arguments
@@ -709,26 +1943,35 @@ impl OAuthService {
     ///
     /// # Requirements
     /// - 5.1: Issue access_token and refresh_token
-    /// - 5.2: Set access_token expiration to max 15 minutes
+    /// - 5.2: Set access_token expiration to the client's lifetime, capped at the server maximum
     /// - 5.3: Include granted scopes in the token response
     /// - 5.4: Include sub, aud, scope, and exp claims in JWT
     /// - 5.5: Sign JWT tokens using RS256 algorithm
     /// - 5.6: Hash tokens before storing in the database
+    ///
+    /// `posture` is kept with the token so refreshed tokens carry it too; it
+    /// is only put in the token while the client has `posture_claims`.
     async fn issue_tokens(
         &self,
         user_id: Option<Uuid>,
-        client_uuid: Uuid,
-        client_id: &str,
+        client: &OAuthClient,
         scopes: &[String],
+        session_started_at: Option<DateTime<Utc>>,
+        authorization_code_id: Option<Uuid>,
+        posture: Option<&PostureContext>,
     ) -> Result<OAuthTokenResponse, OAuthError> {
         // Generate access token
+        let expires_in = self.access_token_ttl(client);
+        let azp_ctx = posture
+            .filter(|_| client.posture_claims)
+            .and_then(PostureContext::claim);
         let access_token = if let Some(uid) = user_id {
             self.jwt_manager
-                .create_oauth2_token(uid, client_id, scopes.to_vec())
+                .create_oauth2_token_with_posture(uid, &client.client_id, scopes.to_vec(), expires_in, azp_ctx)
                 .map_err(|e| OAuthError::ServerError(format!("Failed to create access token: {}", e)))?
         } else {
             self.jwt_manager
-                .create_oauth2_client_credentials_token(client_id, scopes.to_vec())
+                .create_oauth2_client_credentials_token_with_expiry(&client.client_id, scopes.to_vec(), expires_in)
                 .map_err(|e| OAuthError::ServerError(format!("Failed to create access token: {}", e)))?
         };
 
@@ -743,27 +1986,77 @@ impl OAuthService {
         self.token_repo
             .create(
                 user_id,
-                client_uuid,
+                client.id,
                 &access_token_hash,
                 Some(&refresh_token_hash),
                 scopes,
-                self.jwt_manager.access_token_expiry_secs(),
+                expires_in,
+                session_started_at,
+                authorization_code_id,
+                posture,
             )
             .await?;
 
         Ok(OAuthTokenResponse::new(
             access_token,
             Some(refresh_token),
-            self.jwt_manager.access_token_expiry_secs(),
+            expires_in,
             scopes,
         ))
     }
 
+    /// Lifetime of access tokens issued to a client
+    fn access_token_ttl(&self, client: &OAuthClient) -> i64 {
+        client.access_token_ttl(self.jwt_manager.access_token_expiry_secs(), self.max_access_token_ttl_secs)
+    }
+
     /// Get the consent service for checking/granting consent
     pub fn consent_service(&self) -> &ConsentService {
         &self.consent_service
     }
 
+    /// Reject a correct client secret that is past its maximum age
+    ///
+    /// Only checked after the secret was verified, so the error does not
+    /// reveal anything to a caller holding a wrong secret.
+    async fn check_secret_age(&self, client: &OAuthClient) -> Result<(), OAuthError> {
+        if !client.is_secret_expired(self.secret_max_age_days) {
+            return Ok(());
+        }
+
+        self.audit_repo
+            .create(
+                OAuthEventType::ClientSecretExpired,
+                Some(client.id),
+                None,
+                None,
+                Some(serde_json::json!({
+                    "secret_created_at": client.secret_created_at,
+                    "secret_expires_at": client.secret_expires_at(self.secret_max_age_days),
+                })),
+            )
+            .await
+            .ok();
+
+        Err(OAuthError::ClientSecretExpired)
+    }
+
+    /// Check a client secret against the current secret and any replaced
+    /// secret still inside its rotation grace period
+    async fn verify_client_secret(&self, client: &OAuthClient, secret: &str) -> Result<bool, OAuthError> {
+        if verify_secret(secret, &client.client_secret_hash).map_err(|_| OAuthError::InvalidClient)? {
+            return Ok(true);
+        }
+
+        for hash in self.client_repo.find_previous_secret_hashes(client.id).await? {
+            if verify_secret(secret, &hash).map_err(|_| OAuthError::InvalidClient)? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
     /// Get the client repository for client operations
     pub fn client_repo(&self) -> &OAuthClientRepository {
         &self.client_repo
@@ -774,10 +2067,21 @@ impl OAuthService {
         &self.scope_repo
     }
 
-    /// Validate that all requested scopes exist and are active
+    /// Reject a grant the client is not registered for
+    pub fn check_grant_type(&self, client: &OAuthClient, grant_type: &str) -> Result<(), OAuthError> {
+        if client.allows_grant_type(grant_type) {
+            Ok(())
+        } else {
+            Err(OAuthError::UnauthorizedClient)
+        }
+    }
+
+    /// Validate that all requested scopes exist, are active, available to the
+    /// client and within the scopes it registered
     ///
     /// # Arguments
     /// * `scopes` - The scopes to validate
+    /// * `client` - The requesting client
     ///
     /// # Returns
     /// * `Ok(())` - All scopes are valid
@@ -785,12 +2089,18 @@ impl OAuthService {
     ///
     /// # Requirements
     /// - 2.4: Verify all requested scopes exist and are valid
-    pub async fn validate_scopes(&self, scopes: &[String]) -> Result<(), OAuthError> {
+    pub async fn validate_scopes(&self, scopes: &[String], client: &OAuthClient) -> Result<(), OAuthError> {
         if scopes.is_empty() {
             return Ok(());
         }
 
-        let valid = self.scope_repo.validate_scopes(scopes).await?;
+        if !client.allows_scopes(scopes) {
+            return Err(OAuthError::InvalidScope(
+                "One or more requested scopes are not registered for this client".to_string(),
+            ));
+        }
+
+        let valid = self.scope_repo.validate_scopes(scopes, client.id).await?;
         if !valid {
             return Err(OAuthError::InvalidScope(
                 "One or more requested scopes are invalid".to_string(),
@@ -800,3 +2110,55 @@ impl OAuthService {
         Ok(())
     }
 }
+
+/// Challenge store failures are server errors
+fn store_error(e: AppError) -> OAuthError {
+    OAuthError::ServerError(e.to_string())
+}
+
+/// Download a hosted request object (RFC 9101 Section 5.2.3)
+///
+/// Redirects are not followed and bodies over `MAX_REQUEST_OBJECT_BYTES`
+/// are rejected, so a request_uri cannot make the server fetch large or
+/// unexpected content.
+async fn fetch_request_object(request_uri: &str) -> Result<String, OAuthError> {
+    let unreachable = |e: reqwest::Error| {
+        OAuthError::InvalidRequestUri(format!("request_uri could not be fetched: {}", e))
+    };
+
+    // Checked again here: the registration may predate the rule, and the
+    // host is pinned to the addresses that passed
+    let builder = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(REQUEST_URI_FETCH_TIMEOUT_SECS))
+        .redirect(reqwest::redirect::Policy::none());
+    let http = pinned_client(builder, request_uri)
+        .await
+        .map_err(|reason| OAuthError::InvalidRequestUri(format!("request_uri is not allowed ({})", reason)))?;
+
+    let mut response = http
+        .get(request_uri)
+        .header(reqwest::header::ACCEPT, "application/oauth-authz-req+jwt")
+        .send()
+        .await
+        .map_err(unreachable)?;
+    if !response.status().is_success() {
+        return Err(OAuthError::InvalidRequestUri(format!(
+            "request_uri returned {}",
+            response.status()
+        )));
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(unreachable)? {
+        if body.len() + chunk.len() > MAX_REQUEST_OBJECT_BYTES {
+            return Err(OAuthError::InvalidRequestUri(
+                "request object at request_uri is too large".to_string(),
+            ));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    String::from_utf8(body)
+        .map(|object| object.trim().to_string())
+        .map_err(|_| OAuthError::InvalidRequestObject("request is not a signed JWT".to_string()))
+}
@@ -0,0 +1,329 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::OAuthError;
+use crate::models::{OAuthAuditCount, OAuthEventType};
+use crate::repositories::{OAuthAuditLogRepository, OAuthClientRepository};
+
+/// Audit events the statistics are computed from
+const STATS_EVENTS: [OAuthEventType; 5] = [
+    OAuthEventType::TokenIssued,
+    OAuthEventType::TokenRefreshed,
+    OAuthEventType::TokenRequestFailed,
+    OAuthEventType::ConsentGranted,
+    OAuthEventType::ConsentDenied,
+];
+
+/// Time window of the statistics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsWindow {
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+impl StatsWindow {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "1h" => Some(Self::Hour),
+            "24h" => Some(Self::Day),
+            "7d" => Some(Self::Week),
+            "30d" => Some(Self::Month),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hour => "1h",
+            Self::Day => "24h",
+            Self::Week => "7d",
+            Self::Month => "30d",
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        match self {
+            Self::Hour => Duration::hours(1),
+            Self::Day => Duration::hours(24),
+            Self::Week => Duration::days(7),
+            Self::Month => Duration::days(30),
+        }
+    }
+
+    /// Width of one point of the time series
+    pub fn bucket_secs(&self) -> i64 {
+        match self {
+            Self::Hour => 300,
+            Self::Day => 3600,
+            Self::Week | Self::Month => 86400,
+        }
+    }
+}
+
+/// Event counts of a client, a time bucket or the whole window
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EventCounts {
+    pub tokens_issued: u64,
+    pub tokens_refreshed: u64,
+    /// Token endpoint requests rejected with an OAuth error
+    pub token_errors: u64,
+    pub consents_granted: u64,
+    pub consents_denied: u64,
+}
+
+impl EventCounts {
+    fn add(&mut self, event_type: &str, count: u64) {
+        if event_type == OAuthEventType::TokenIssued.as_str() {
+            self.tokens_issued += count;
+        } else if event_type == OAuthEventType::TokenRefreshed.as_str() {
+            self.tokens_refreshed += count;
+        } else if event_type == OAuthEventType::TokenRequestFailed.as_str() {
+            self.token_errors += count;
+        } else if event_type == OAuthEventType::ConsentGranted.as_str() {
+            self.consents_granted += count;
+        } else if event_type == OAuthEventType::ConsentDenied.as_str() {
+            self.consents_denied += count;
+        }
+    }
+
+    /// Share of consent decisions that were denials (None without decisions)
+    pub fn consent_denial_rate(&self) -> Option<f64> {
+        let decisions = self.consents_granted + self.consents_denied;
+        (decisions > 0).then(|| self.consents_denied as f64 / decisions as f64)
+    }
+
+    fn total(&self) -> u64 {
+        self.tokens_issued + self.tokens_refreshed + self.token_errors + self.consents_granted + self.consents_denied
+    }
+}
+
+/// Activity of one client
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientStats {
+    /// Public client_id (the internal id for deleted clients)
+    pub client_id: String,
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub counts: EventCounts,
+    pub consent_denial_rate: Option<f64>,
+    /// Tokens issued per grant type, refreshes included
+    pub grants: BTreeMap<String, u64>,
+    /// Token endpoint errors per error code
+    pub errors: BTreeMap<String, u64>,
+}
+
+/// One point of the time series
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsBucket {
+    pub start: DateTime<Utc>,
+    #[serde(flatten)]
+    pub counts: EventCounts,
+}
+
+/// Grant, error and consent statistics over a time window
+#[derive(Debug, Clone, Serialize)]
+pub struct OAuthStats {
+    pub window: &'static str,
+    pub since: DateTime<Utc>,
+    pub bucket_secs: i64,
+    pub totals: EventCounts,
+    /// Refresh token grants per hour over the window
+    pub refreshes_per_hour: f64,
+    pub consent_denial_rate: Option<f64>,
+    /// Token endpoint errors per error code
+    pub errors: BTreeMap<String, u64>,
+    /// Clients with any activity, most active first
+    pub clients: Vec<ClientStats>,
+    pub series: Vec<StatsBucket>,
+}
+
+/// Fold audit event counts into statistics
+///
+/// Clients are keyed by their internal id; events without a client only
+/// count towards the totals.
+fn aggregate(counts: &[OAuthAuditCount], window: StatsWindow, now: DateTime<Utc>) -> OAuthStats {
+    let since = now - window.duration();
+    let bucket_secs = window.bucket_secs();
+
+    let mut series: BTreeMap<i64, EventCounts> = BTreeMap::new();
+    let first_bucket = since.timestamp().div_euclid(bucket_secs) * bucket_secs;
+    let mut bucket = first_bucket;
+    while bucket <= now.timestamp() {
+        series.insert(bucket, EventCounts::default());
+        bucket += bucket_secs;
+    }
+
+    let mut totals = EventCounts::default();
+    let mut errors: BTreeMap<String, u64> = BTreeMap::new();
+    let mut clients: BTreeMap<String, ClientStats> = BTreeMap::new();
+
+    for row in counts {
+        let count = row.count.max(0) as u64;
+        let error = (row.event_type == OAuthEventType::TokenRequestFailed.as_str())
+            .then(|| row.error.clone().unwrap_or_else(|| "unknown".to_string()));
+        let grant = if row.event_type == OAuthEventType::TokenIssued.as_str() {
+            Some(row.grant_type.clone().unwrap_or_else(|| "unknown".to_string()))
+        } else if row.event_type == OAuthEventType::TokenRefreshed.as_str() {
+            Some("refresh_token".to_string())
+        } else {
+            None
+        };
+
+        totals.add(&row.event_type, count);
+        series.entry(row.bucket).or_default().add(&row.event_type, count);
+        if let Some(error) = &error {
+            *errors.entry(error.clone()).or_default() += count;
+        }
+
+        let Some(client_id) = &row.client_id else {
+            continue;
+        };
+        let client = clients.entry(client_id.clone()).or_insert_with(|| ClientStats {
+            client_id: client_id.clone(),
+            name: None,
+            counts: EventCounts::default(),
+            consent_denial_rate: None,
+            grants: BTreeMap::new(),
+            errors: BTreeMap::new(),
+        });
+        client.counts.add(&row.event_type, count);
+        if let Some(grant) = grant {
+            *client.grants.entry(grant).or_default() += count;
+        }
+        if let Some(error) = error {
+            *client.errors.entry(error).or_default() += count;
+        }
+    }
+
+    let mut clients: Vec<ClientStats> = clients
+        .into_values()
+        .map(|mut client| {
+            client.consent_denial_rate = client.counts.consent_denial_rate();
+            client
+        })
+        .collect();
+    clients.sort_by_key(|client| std::cmp::Reverse(client.counts.total()));
+
+    OAuthStats {
+        window: window.as_str(),
+        since,
+        bucket_secs,
+        refreshes_per_hour: totals.tokens_refreshed as f64 / (window.duration().num_seconds() as f64 / 3600.0),
+        consent_denial_rate: totals.consent_denial_rate(),
+        totals,
+        errors,
+        clients,
+        series: series
+            .into_iter()
+            .map(|(start, counts)| StatsBucket {
+                start: DateTime::from_timestamp(start, 0).unwrap_or(since),
+                counts,
+            })
+            .collect(),
+    }
+}
+
+/// Aggregates the OAuth audit log for client health monitoring
+#[derive(Clone)]
+pub struct OAuthStatsService {
+    audit_repo: OAuthAuditLogRepository,
+    client_repo: OAuthClientRepository,
+}
+
+impl OAuthStatsService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self {
+            audit_repo: OAuthAuditLogRepository::new(pool.clone()),
+            client_repo: OAuthClientRepository::new(pool),
+        }
+    }
+
+    /// Statistics over `window`, optionally for a single client (internal id)
+    pub async fn stats(&self, window: StatsWindow, client_id: Option<Uuid>) -> Result<OAuthStats, OAuthError> {
+        let now = Utc::now();
+        let counts = self
+            .audit_repo
+            .count_events_since(now - window.duration(), window.bucket_secs(), &STATS_EVENTS, client_id)
+            .await?;
+
+        let mut stats = aggregate(&counts, window, now);
+
+        for client in &mut stats.clients {
+            let Ok(id) = Uuid::parse_str(&client.client_id) else {
+                continue;
+            };
+            if let Some(found) = self.client_repo.find_by_id(id).await? {
+                client.client_id = found.client_id;
+                client.name = Some(found.name);
+            }
+        }
+
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(bucket: i64, event_type: OAuthEventType, client_id: Option<&str>, detail: Option<&str>, count: i64) -> OAuthAuditCount {
+        OAuthAuditCount {
+            bucket,
+            event_type: event_type.as_str().to_string(),
+            client_id: client_id.map(str::to_string),
+            grant_type: (event_type == OAuthEventType::TokenIssued).then(|| detail.unwrap_or_default().to_string()),
+            error: (event_type == OAuthEventType::TokenRequestFailed).then(|| detail.unwrap_or_default().to_string()),
+            count,
+        }
+    }
+
+    #[test]
+    fn test_window_parse() {
+        assert_eq!(StatsWindow::parse("24h"), Some(StatsWindow::Day));
+        assert_eq!(StatsWindow::parse("30d").map(|w| w.bucket_secs()), Some(86400));
+        assert_eq!(StatsWindow::parse("1y"), None);
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let bucket = now.timestamp() / 3600 * 3600;
+        let counts = vec![
+            row(bucket, OAuthEventType::TokenIssued, Some("a"), Some("authorization_code"), 4),
+            row(bucket, OAuthEventType::TokenRefreshed, Some("a"), None, 12),
+            row(bucket, OAuthEventType::TokenRequestFailed, Some("a"), Some("invalid_grant"), 2),
+            row(bucket - 3600, OAuthEventType::TokenRequestFailed, None, Some("invalid_client"), 3),
+            row(bucket - 3600, OAuthEventType::ConsentGranted, Some("b"), None, 3),
+            row(bucket - 3600, OAuthEventType::ConsentDenied, Some("b"), None, 1),
+        ];
+
+        let stats = aggregate(&counts, StatsWindow::Day, now);
+
+        assert_eq!(stats.totals.tokens_issued, 4);
+        assert_eq!(stats.totals.token_errors, 5);
+        assert_eq!(stats.refreshes_per_hour, 0.5);
+        assert_eq!(stats.consent_denial_rate, Some(0.25));
+        assert_eq!(stats.errors.get("invalid_client"), Some(&3));
+        assert_eq!(stats.errors.get("invalid_grant"), Some(&2));
+
+        // Most active client first; the error without a client counts only in the totals
+        assert_eq!(stats.clients.len(), 2);
+        assert_eq!(stats.clients[0].client_id, "a");
+        assert_eq!(stats.clients[0].grants.get("refresh_token"), Some(&12));
+        assert_eq!(stats.clients[0].grants.get("authorization_code"), Some(&4));
+        assert_eq!(stats.clients[0].errors.get("invalid_client"), None);
+        assert_eq!(stats.clients[1].consent_denial_rate, Some(0.25));
+
+        // Every hour of the window has a point, empty ones included
+        assert_eq!(stats.series.len(), 25);
+        assert_eq!(stats.series.last().unwrap().counts.tokens_refreshed, 12);
+        assert_eq!(stats.series[stats.series.len() - 2].counts.consents_granted, 3);
+        assert_eq!(stats.series[0].counts, EventCounts::default());
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::UserManagementError;
+use crate::models::{AuditAction, OAuthEventType};
+use crate::repositories::{AuditLogRepository, OAuthAuditLogRepository, OAuthClientRepository};
+
+/// Audit actions that are relevant for a user's privacy ledger
+const PRIVACY_AUDIT_ACTIONS: [AuditAction; 6] = [
+    AuditAction::DataExportRequested,
+    AuditAction::DeletionRequested,
+    AuditAction::UserDeleted,
+    AuditAction::UserAnonymized,
+    AuditAction::TosAccepted,
+    AuditAction::EmailPreferencesChanged,
+];
+
+/// OAuth events that are relevant for a user's privacy ledger
+const PRIVACY_OAUTH_EVENTS: [OAuthEventType; 3] = [
+    OAuthEventType::ConsentGranted,
+    OAuthEventType::ConsentDenied,
+    OAuthEventType::ConsentRevoked,
+];
+
+/// A single entry in a user's privacy ledger
+#[derive(Debug, Clone, Serialize)]
+pub struct PrivacyLedgerEntry {
+    pub occurred_at: DateTime<Utc>,
+    /// consent, data_export, deletion, terms_of_service or email_preferences
+    pub category: String,
+    /// The underlying audit event name
+    pub event: String,
+    pub client_id: Option<Uuid>,
+    pub client_name: Option<String>,
+    pub actor_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub details: Option<serde_json::Value>,
+}
+
+/// Privacy ledger for a single user, in chronological order
+#[derive(Debug, Clone, Serialize)]
+pub struct PrivacyLedger {
+    pub user_id: Uuid,
+    pub generated_at: DateTime<Utc>,
+    pub entries: Vec<PrivacyLedgerEntry>,
+}
+
+impl PrivacyLedger {
+    /// Render the ledger as CSV (RFC 4180 quoting)
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "occurred_at,category,event,client_id,client_name,actor_id,ip_address,details\n",
+        );
+
+        for entry in &self.entries {
+            let fields = [
+                entry.occurred_at.to_rfc3339(),
+                entry.category.clone(),
+                entry.event.clone(),
+                entry.client_id.map(|id| id.to_string()).unwrap_or_default(),
+                entry.client_name.clone().unwrap_or_default(),
+                entry.actor_id.map(|id| id.to_string()).unwrap_or_default(),
+                entry.ip_address.clone().unwrap_or_default(),
+                entry.details.as_ref().map(|d| d.to_string()).unwrap_or_default(),
+            ];
+            let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            out.push_str(&line.join(","));
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') || value.contains('\r') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Service compiling GDPR privacy ledgers from the audit trails
+#[derive(Clone)]
+pub struct PrivacyLedgerService {
+    audit_repo: AuditLogRepository,
+    oauth_audit_repo: OAuthAuditLogRepository,
+    client_repo: OAuthClientRepository,
+}
+
+impl PrivacyLedgerService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self {
+            audit_repo: AuditLogRepository::new(pool.clone()),
+            oauth_audit_repo: OAuthAuditLogRepository::new(pool.clone()),
+            client_repo: OAuthClientRepository::new(pool),
+        }
+    }
+
+    /// Compile consents granted/denied/revoked, data exports, deletion requests,
+    /// ToS acceptances and email preference changes for a user
+    pub async fn build_ledger(&self, user_id: Uuid) -> Result<PrivacyLedger, UserManagementError> {
+        let mut entries = Vec::new();
+
+        let oauth_logs = self
+            .oauth_audit_repo
+            .list_by_user_and_event_types(user_id, &PRIVACY_OAUTH_EVENTS)
+            .await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?;
+
+        let mut client_names: HashMap<Uuid, Option<String>> = HashMap::new();
+        for log in oauth_logs {
+            let client_name = match log.client_id {
+                Some(client_id) => match client_names.entry(client_id) {
+                    Entry::Occupied(entry) => entry.get().clone(),
+                    Entry::Vacant(entry) => {
+                        let name = self
+                            .client_repo
+                            .find_by_id(client_id)
+                            .await
+                            .map_err(|e| UserManagementError::InternalError(e.into()))?
+                            .map(|c| c.name);
+                        entry.insert(name).clone()
+                    }
+                },
+                None => None,
+            };
+
+            entries.push(PrivacyLedgerEntry {
+                occurred_at: log.created_at,
+                category: "consent".to_string(),
+                event: log.event_type,
+                client_id: log.client_id,
+                client_name,
+                actor_id: log.user_id,
+                ip_address: log.ip_address,
+                details: log.details,
+            });
+        }
+
+        let actions: Vec<&str> = PRIVACY_AUDIT_ACTIONS.iter().map(|a| a.as_str()).collect();
+        let audit_logs = self
+            .audit_repo
+            .list_for_subject_by_actions(user_id, &actions)
+            .await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?;
+
+        for log in audit_logs {
+            entries.push(PrivacyLedgerEntry {
+                occurred_at: log.created_at,
+                category: category_for_action(&log.action).to_string(),
+                event: log.action,
+                client_id: None,
+                client_name: None,
+                actor_id: log.user_id,
+                ip_address: log.ip_address,
+                details: log.details,
+            });
+        }
+
+        entries.sort_by_key(|e| e.occurred_at);
+
+        Ok(PrivacyLedger {
+            user_id,
+            generated_at: Utc::now(),
+            entries,
+        })
+    }
+}
+
+/// Map an audit action name to its ledger category
+fn category_for_action(action: &str) -> &'static str {
+    match action {
+        "data_export_requested" => "data_export",
+        "deletion_requested" | "user_deleted" | "user_anonymized" => "deletion",
+        "tos_accepted" => "terms_of_service",
+        "email_preferences_changed" => "email_preferences",
+        _ => "other",
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::HashSet;
+
+use chrono::{Duration, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::dto::user_management::{PaginatedResponse, MAX_PAGE_LIMIT};
+use crate::error::AppError;
+use crate::models::{
+    ProvisioningCommand, ProvisioningEvent, RoleChangeActor, RoleSyncPayload, WebhookEvent,
+    PROVISIONING_EVENT_TYPES, PROVISIONING_STATUS_DEAD_LETTER, PROVISIONING_STATUS_PENDING,
+    PROVISIONING_STATUS_PROCESSED,
+};
+use crate::repositories::{ProvisioningEventRepository, UserAppRepository};
+use crate::services::{RoleService, UserManagementService, WebhookService};
+use crate::utils::nats::NatsTopic;
+use crate::utils::request_id::spawn_in_request;
+
+/// Attempts before a failing event is dead-lettered
+pub const MAX_PROVISIONING_ATTEMPTS: i32 = 5;
+
+/// Delay before the first retry; doubles on every further attempt
+const RETRY_BASE_SECS: i64 = 30;
+
+/// Name this server connects to the message broker with
+pub const PROVISIONING_NATS_CLIENT: &str = "auth-server-provisioning";
+
+/// Why an event could not be applied
+enum ApplyError {
+    /// The event can never succeed (schema or reference error): dead-letter now
+    Invalid(String),
+    /// The event may succeed later: retry with backoff
+    Transient(String),
+}
+
+/// Service for inbound provisioning events
+///
+/// Upstream systems (HR, IdP) submit events with an API key or publish
+/// them to the NATS subject the provisioning consumer reads; the
+/// provisioning worker applies them asynchronously. Redelivering an
+/// `event_id` is a no-op and every handler converges to the requested
+/// state, so events are safe to replay. Events failing schema validation,
+/// or still failing after `MAX_PROVISIONING_ATTEMPTS`, are dead-lettered,
+/// and published to the dead-letter subject when one is configured.
+#[derive(Clone)]
+pub struct ProvisioningService {
+    event_repo: ProvisioningEventRepository,
+    user_app_repo: UserAppRepository,
+    role_service: RoleService,
+    user_management_service: UserManagementService,
+    webhook_service: WebhookService,
+    dead_letter_topic: Option<NatsTopic>,
+}
+
+impl ProvisioningService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self {
+            event_repo: ProvisioningEventRepository::new(pool.clone()),
+            user_app_repo: UserAppRepository::new(pool.clone()),
+            role_service: RoleService::new(pool.clone()),
+            user_management_service: UserManagementService::new(pool.clone()),
+            webhook_service: WebhookService::new(pool),
+            dead_letter_topic: None,
+        }
+    }
+
+    /// Also publish dead-lettered events to this subject
+    pub fn with_dead_letter_topic(mut self, topic: Option<NatsTopic>) -> Self {
+        self.dead_letter_topic = topic;
+        self
+    }
+
+    /// Queue an event for the worker
+    ///
+    /// `api_key_id` is the key of an HTTP submission; broker messages have none.
+    /// Returns the stored event and whether it was new.
+    pub async fn submit(
+        &self,
+        app_id: Uuid,
+        api_key_id: Option<Uuid>,
+        event_id: &str,
+        event_type: &str,
+        payload: &serde_json::Value,
+    ) -> Result<(ProvisioningEvent, bool), AppError> {
+        let event_id = event_id.trim();
+        if event_id.is_empty() || event_id.len() > 255 {
+            return Err(AppError::ValidationError("event_id must be 1 to 255 characters".into()));
+        }
+        if !PROVISIONING_EVENT_TYPES.contains(&event_type) {
+            return Err(AppError::ValidationError(format!(
+                "event_type must be one of: {}",
+                PROVISIONING_EVENT_TYPES.join(", ")
+            )));
+        }
+
+        self.event_repo
+            .create(app_id, event_id, event_type, payload, api_key_id)
+            .await
+    }
+
+    /// List events of an app, optionally filtered by status
+    pub async fn list_events(
+        &self,
+        app_id: Uuid,
+        status: Option<&str>,
+        page: u32,
+        limit: u32,
+    ) -> Result<PaginatedResponse<ProvisioningEvent>, AppError> {
+        if let Some(status) = status {
+            if ![PROVISIONING_STATUS_PENDING, PROVISIONING_STATUS_PROCESSED, PROVISIONING_STATUS_DEAD_LETTER]
+                .contains(&status)
+            {
+                return Err(AppError::ValidationError(format!("Invalid status: {}", status)));
+            }
+        }
+
+        let page = page.max(1);
+        let limit = limit.clamp(1, MAX_PAGE_LIMIT);
+        let total = self.event_repo.count_by_app(app_id, status).await?;
+        let events = self.event_repo.list_by_app(app_id, status, page, limit).await?;
+
+        Ok(PaginatedResponse::new(events, page, limit, total)
+            .with_sort("created_at", "desc")
+            .with_filters(serde_json::json!({ "status": status })))
+    }
+
+    /// Requeue a dead-lettered event
+    pub async fn retry_dead_letter(&self, app_id: Uuid, event_id: &str) -> Result<ProvisioningEvent, AppError> {
+        if !self.event_repo.requeue(app_id, event_id).await? {
+            return match self.event_repo.find_by_event_id(app_id, event_id).await? {
+                Some(_) => Err(AppError::ValidationError("Only dead-lettered events can be retried".into())),
+                None => Err(AppError::NotFound("Provisioning event not found".into())),
+            };
+        }
+
+        self.event_repo
+            .find_by_event_id(app_id, event_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Provisioning event not found".into()))
+    }
+
+    /// Apply due pending events
+    ///
+    /// Returns the number of events handled (applied, retried or dead-lettered).
+    pub async fn process_due(&self, limit: i64) -> Result<usize, AppError> {
+        let events = self.event_repo.find_due(limit).await?;
+        let count = events.len();
+
+        for event in events {
+            match self.apply(&event).await {
+                Ok(()) => self.event_repo.mark_processed(event.id).await?,
+                Err(ApplyError::Transient(error)) if event.attempts + 1 < MAX_PROVISIONING_ATTEMPTS => {
+                    let delay = RETRY_BASE_SECS << event.attempts.clamp(0, 10);
+                    tracing::warn!(
+                        "Provisioning event {} failed (attempt {}), retrying in {}s: {}",
+                        event.event_id,
+                        event.attempts + 1,
+                        delay,
+                        error
+                    );
+                    self.event_repo
+                        .mark_retry(event.id, &error, Utc::now() + Duration::seconds(delay))
+                        .await?;
+                }
+                Err(ApplyError::Transient(error)) | Err(ApplyError::Invalid(error)) => {
+                    self.dead_letter(&event, &error).await?;
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    async fn dead_letter(&self, event: &ProvisioningEvent, error: &str) -> Result<(), AppError> {
+        tracing::warn!("Provisioning event {} dead-lettered: {}", event.event_id, error);
+        self.event_repo.mark_dead_letter(event.id, error).await?;
+
+        let app_id = event.app_id;
+        let webhook_service = self.webhook_service.clone();
+        let payload = serde_json::json!({
+            "event": "provisioning.dead_lettered",
+            "app_id": app_id.to_string(),
+            "event_id": event.event_id,
+            "event_type": event.event_type,
+            "error": error,
+            "timestamp": Utc::now().to_rfc3339()
+        });
+        spawn_in_request(async move {
+            let _ = webhook_service.trigger_event(app_id, WebhookEvent::ProvisioningDeadLettered, payload).await;
+        });
+
+        if let Some(topic) = self.dead_letter_topic.clone() {
+            // Shaped like a broker message, so it can be published again as is
+            let message = dead_letter_message(
+                serde_json::json!({
+                    "app_id": app_id,
+                    "event_id": event.event_id,
+                    "event_type": event.event_type,
+                    "payload": event.payload,
+                }),
+                error,
+            );
+            let event_id = event.event_id.clone();
+            spawn_in_request(async move {
+                if let Err(e) = topic.publish(PROVISIONING_NATS_CLIENT, &message).await {
+                    tracing::warn!("Failed to publish dead-lettered provisioning event {}: {}", event_id, e);
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn apply(&self, event: &ProvisioningEvent) -> Result<(), ApplyError> {
+        let command = ProvisioningCommand::parse(&event.event_type, &event.payload).map_err(ApplyError::Invalid)?;
+
+        match command {
+            ProvisioningCommand::UserDeprovision(payload) => self
+                .user_management_service
+                .remove_user_by_api_key(event.app_id, payload.user_id, event.source_api_key_id)
+                .await
+                .map_err(|e| ApplyError::Transient(e.to_string())),
+            ProvisioningCommand::RoleSync(payload) => self.sync_roles(event, payload).await,
+        }
+    }
+
+    /// Grant missing roles and remove unlisted ones
+    async fn sync_roles(&self, event: &ProvisioningEvent, payload: RoleSyncPayload) -> Result<(), ApplyError> {
+        let app_id = event.app_id;
+        let user_id = payload.user_id;
+        let actor = event
+            .source_api_key_id
+            .map(RoleChangeActor::api_key)
+            .unwrap_or_else(RoleChangeActor::system);
+
+        // The user may not have joined yet when HR systems provision ahead; retry until they do
+        let membership = self.user_app_repo.find(user_id, app_id).await
+            .map_err(|e| ApplyError::Transient(e.to_string()))?;
+        if membership.is_none() {
+            return Err(ApplyError::Transient("user is not a member of the app".into()));
+        }
+
+        let app_roles = self.role_service.get_roles_by_app(app_id).await
+            .map_err(|e| ApplyError::Transient(e.to_string()))?;
+
+        let mut desired = HashSet::new();
+        for name in &payload.roles {
+            let role = app_roles
+                .iter()
+                .find(|r| r.name.eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| ApplyError::Invalid(format!("unknown role: {}", name)))?;
+            desired.insert(role.id);
+        }
+
+        let current: HashSet<Uuid> = self.role_service.get_user_roles_in_app(user_id, app_id).await
+            .map_err(|e| ApplyError::Transient(e.to_string()))?
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+
+        for role_id in desired.difference(&current) {
+            self.role_service.assign_role_to_user(user_id, app_id, *role_id, None, None, actor).await
+                .map_err(|e| ApplyError::Transient(e.to_string()))?;
+        }
+        for role_id in current.difference(&desired) {
+            self.role_service.remove_role_from_user(user_id, app_id, *role_id, actor).await
+                .map_err(|e| ApplyError::Transient(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Dead-letter message for an event (or the raw message) and why it failed
+pub fn dead_letter_message(event: serde_json::Value, error: &str) -> Vec<u8> {
+    serde_json::json!({
+        "event": event,
+        "error": error,
+        "failed_at": Utc::now().to_rfc3339(),
+    })
+    .to_string()
+    .into_bytes()
+}
@@ -0,0 +1,228 @@
+use std::sync::Arc;
+
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use serde::Serialize;
+
+use crate::error::AuthError;
+use crate::models::{DevicePlatform, UserDevice};
+
+/// APNs (Apple Push Notification service) token-based credentials
+#[derive(Debug, Clone)]
+pub struct ApnsConfig {
+    /// Contents of the .p8 signing key (PEM)
+    pub key_pem: String,
+    pub key_id: String,
+    pub team_id: String,
+    /// App bundle id, sent as apns-topic
+    pub topic: String,
+    pub endpoint: String,
+}
+
+/// Push provider credentials
+#[derive(Debug, Clone, Default)]
+pub struct PushConfig {
+    pub fcm_server_key: Option<String>,
+    pub fcm_endpoint: String,
+    pub apns: Option<ApnsConfig>,
+}
+
+impl PushConfig {
+    /// Load push configuration from environment variables
+    ///
+    /// Providers without credentials fall back to logging the notification,
+    /// which keeps local development working without FCM/APNs accounts.
+    pub fn from_env() -> Self {
+        let apns = match (
+            std::env::var("APNS_KEY_PEM").ok(),
+            std::env::var("APNS_KEY_ID").ok(),
+            std::env::var("APNS_TEAM_ID").ok(),
+            std::env::var("APNS_TOPIC").ok(),
+        ) {
+            (Some(key_pem), Some(key_id), Some(team_id), Some(topic)) => Some(ApnsConfig {
+                key_pem,
+                key_id,
+                team_id,
+                topic,
+                endpoint: std::env::var("APNS_ENDPOINT")
+                    .unwrap_or_else(|_| "https://api.push.apple.com".to_string()),
+            }),
+            _ => None,
+        };
+
+        Self {
+            fcm_server_key: std::env::var("FCM_SERVER_KEY").ok(),
+            fcm_endpoint: std::env::var("FCM_ENDPOINT")
+                .unwrap_or_else(|_| "https://fcm.googleapis.com/fcm/send".to_string()),
+            apns,
+        }
+    }
+}
+
+/// Provider a notification is routed through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushProvider {
+    Fcm,
+    Apns,
+    /// No credentials configured - notification is only logged
+    Log,
+}
+
+/// A push notification to deliver to a device
+#[derive(Debug, Clone, Serialize)]
+pub struct PushNotification {
+    pub title: String,
+    pub body: String,
+    /// Custom payload delivered to the app
+    pub data: serde_json::Value,
+}
+
+#[derive(Serialize)]
+struct ApnsClaims {
+    iss: String,
+    iat: i64,
+}
+
+/// Service delivering push notifications through FCM (Android) or APNs (iOS)
+#[derive(Clone)]
+pub struct PushNotificationService {
+    config: Arc<PushConfig>,
+    client: reqwest::Client,
+}
+
+impl PushNotificationService {
+    pub fn new(config: PushConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Create a service using credentials from the environment
+    pub fn from_env() -> Self {
+        Self::new(PushConfig::from_env())
+    }
+
+    /// Pick the provider for a device based on its platform and configured credentials
+    pub fn provider_for(&self, device: &UserDevice) -> PushProvider {
+        match DevicePlatform::from_str(&device.platform) {
+            Some(DevicePlatform::Ios) if self.config.apns.is_some() => PushProvider::Apns,
+            Some(DevicePlatform::Android) if self.config.fcm_server_key.is_some() => PushProvider::Fcm,
+            _ => PushProvider::Log,
+        }
+    }
+
+    /// Send a notification to a device
+    pub async fn send(
+        &self,
+        device: &UserDevice,
+        notification: &PushNotification,
+    ) -> Result<(), AuthError> {
+        let push_token = device
+            .push_token
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| AuthError::InvalidRequest("Device has no push token".to_string()))?;
+
+        match self.provider_for(device) {
+            PushProvider::Fcm => self.send_fcm(push_token, notification).await,
+            PushProvider::Apns => self.send_apns(push_token, notification).await,
+            PushProvider::Log => {
+                tracing::info!(
+                    "Push notification for device {} ({}): {} - {}",
+                    device.id,
+                    device.platform,
+                    notification.title,
+                    notification.data
+                );
+                Ok(())
+            }
+        }
+    }
+
+    async fn send_fcm(&self, push_token: &str, notification: &PushNotification) -> Result<(), AuthError> {
+        let server_key = self.config.fcm_server_key.as_deref().unwrap_or_default();
+        let body = serde_json::json!({
+            "to": push_token,
+            "priority": "high",
+            "notification": {
+                "title": notification.title,
+                "body": notification.body
+            },
+            "data": notification.data
+        });
+
+        let response = self
+            .client
+            .post(&self.config.fcm_endpoint)
+            .header("Authorization", format!("key={}", server_key))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("FCM request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(AuthError::InternalError(anyhow::anyhow!(
+                "FCM rejected notification: {}",
+                response.status()
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn send_apns(&self, push_token: &str, notification: &PushNotification) -> Result<(), AuthError> {
+        let apns = self
+            .config
+            .apns
+            .as_ref()
+            .ok_or_else(|| AuthError::InternalError(anyhow::anyhow!("APNs not configured")))?;
+
+        // Provider authentication token (ES256, signed with the .p8 key)
+        let mut header = Header::new(Algorithm::ES256);
+        header.kid = Some(apns.key_id.clone());
+        let claims = ApnsClaims {
+            iss: apns.team_id.clone(),
+            iat: chrono::Utc::now().timestamp(),
+        };
+        let key = EncodingKey::from_ec_pem(apns.key_pem.as_bytes())
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("Invalid APNs key: {}", e)))?;
+        let auth_token = encode(&header, &claims, &key)
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("APNs token encoding failed: {}", e)))?;
+
+        let mut body = serde_json::json!({
+            "aps": {
+                "alert": {
+                    "title": notification.title,
+                    "body": notification.body
+                },
+                "sound": "default"
+            }
+        });
+        if let (Some(body), Some(data)) = (body.as_object_mut(), notification.data.as_object()) {
+            for (key, value) in data {
+                body.insert(key.clone(), value.clone());
+            }
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/3/device/{}", apns.endpoint, push_token))
+            .header("authorization", format!("bearer {}", auth_token))
+            .header("apns-topic", &apns.topic)
+            .header("apns-push-type", "alert")
+            .header("apns-priority", "10")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("APNs request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(AuthError::InternalError(anyhow::anyhow!(
+                "APNs rejected notification: {}",
+                response.status()
+            )));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+use chrono::{Duration, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::{AuditAction, PushChallengeStatus, PushMfaChallenge};
+use crate::repositories::{DeviceRepository, PushMfaRepository};
+use crate::services::push::{PushNotification, PushNotificationService};
+use crate::services::{AuditService, LoginContext};
+use crate::utils::secret::constant_time_compare;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How long a push approval request stays valid, in seconds
+const PUSH_CHALLENGE_EXPIRY_SECONDS: i64 = 120;
+
+/// Service for push-approval MFA
+///
+/// A challenge is bound to the pending MFA token of a login. Every registered
+/// device that can receive pushes is notified; one of them answers with an
+/// HMAC signature made with its device signing secret, and the approval is
+/// then consumed exactly once by `complete_mfa_login`.
+#[derive(Clone)]
+pub struct PushMfaService {
+    repo: PushMfaRepository,
+    device_repo: DeviceRepository,
+    push_service: PushNotificationService,
+    audit_service: AuditService,
+}
+
+impl PushMfaService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self {
+            repo: PushMfaRepository::new(pool.clone()),
+            device_repo: DeviceRepository::new(pool.clone()),
+            push_service: PushNotificationService::from_env(),
+            audit_service: AuditService::new(pool),
+        }
+    }
+
+    /// Whether the user has at least one device able to approve logins
+    pub async fn is_available(&self, user_id: Uuid) -> Result<bool, AuthError> {
+        let devices = self.device_repo.list_active_by_user(user_id).await?;
+        Ok(devices.iter().any(|d| d.supports_push_approval()))
+    }
+
+    /// Create a challenge and notify the user's devices
+    /// Returns the challenge and how many devices were notified
+    pub async fn start_challenge(
+        &self,
+        user_id: Uuid,
+        mfa_token_hash: &str,
+        context: &LoginContext,
+    ) -> Result<(PushMfaChallenge, usize), AuthError> {
+        let devices: Vec<_> = self
+            .device_repo
+            .list_active_by_user(user_id)
+            .await?
+            .into_iter()
+            .filter(|d| d.supports_push_approval())
+            .collect();
+
+        if devices.is_empty() {
+            return Err(AuthError::InvalidRequest(
+                "No device registered for push approval".to_string(),
+            ));
+        }
+
+        let expires_at = Utc::now() + Duration::seconds(PUSH_CHALLENGE_EXPIRY_SECONDS);
+        let challenge = self
+            .repo
+            .create(
+                user_id,
+                mfa_token_hash,
+                context.ip_address.as_deref(),
+                context.user_agent.as_deref(),
+                expires_at,
+            )
+            .await?;
+
+        let notification = PushNotification {
+            title: "Approve sign-in?".to_string(),
+            body: format!(
+                "Sign-in attempt from {}",
+                context.ip_address.as_deref().unwrap_or("an unknown location")
+            ),
+            data: serde_json::json!({
+                "type": "mfa_push",
+                "challenge_id": challenge.id.to_string(),
+                "ip_address": context.ip_address,
+                "user_agent": context.user_agent,
+                "expires_at": expires_at.to_rfc3339()
+            }),
+        };
+
+        let mut notified = 0;
+        for device in &devices {
+            match self.push_service.send(device, &notification).await {
+                Ok(()) => notified += 1,
+                Err(e) => tracing::warn!("Push to device {} failed: {:?}", device.id, e),
+            }
+        }
+
+        if notified == 0 {
+            return Err(AuthError::InternalError(anyhow::anyhow!(
+                "Failed to deliver push approval to any device"
+            )));
+        }
+
+        Ok((challenge, notified))
+    }
+
+    /// Record an approve/deny answer signed by one of the user's devices
+    pub async fn respond(
+        &self,
+        challenge_id: Uuid,
+        device_id: Uuid,
+        approve: bool,
+        signature: &str,
+    ) -> Result<PushMfaChallenge, AuthError> {
+        let challenge = self
+            .repo
+            .find_by_id(challenge_id)
+            .await?
+            .ok_or(AuthError::InvalidToken)?;
+
+        if challenge.is_expired() {
+            return Err(AuthError::TokenExpired);
+        }
+
+        let device = self
+            .device_repo
+            .find_by_id(device_id)
+            .await?
+            .filter(|d| d.user_id == challenge.user_id && d.is_active)
+            .ok_or(AuthError::InvalidToken)?;
+
+        let secret = device.signing_secret.as_deref().ok_or(AuthError::InvalidToken)?;
+        let expected = Self::sign_response(secret, challenge_id, approve);
+        if !constant_time_compare(&expected, signature) {
+            return Err(AuthError::InvalidToken);
+        }
+
+        // Conditional update - only the first answer within the expiry window counts
+        if !self.repo.respond(challenge_id, device_id, approve).await? {
+            return Err(AuthError::InvalidToken);
+        }
+
+        let action = if approve {
+            AuditAction::PushMfaApproved
+        } else {
+            AuditAction::PushMfaDenied
+        };
+        let _ = self
+            .audit_service
+            .log_auth_event(
+                Some(challenge.user_id),
+                action,
+                challenge.ip_address.as_deref(),
+                challenge.user_agent.as_deref(),
+                Some(serde_json::json!({
+                    "challenge_id": challenge_id.to_string(),
+                    "device_id": device_id.to_string()
+                })),
+                true,
+            )
+            .await;
+
+        self.repo
+            .find_by_id(challenge_id)
+            .await?
+            .ok_or(AuthError::InvalidToken)
+    }
+
+    /// Consume an approved challenge as the second factor of the login it is bound to
+    pub async fn consume_approval(
+        &self,
+        challenge_id: Uuid,
+        mfa_token_hash: &str,
+    ) -> Result<(), AuthError> {
+        let challenge = self
+            .repo
+            .find_by_id(challenge_id)
+            .await?
+            .ok_or(AuthError::InvalidMfaCode)?;
+
+        if !constant_time_compare(&challenge.mfa_token_hash, mfa_token_hash) {
+            return Err(AuthError::InvalidMfaCode);
+        }
+        if challenge.is_expired() {
+            return Err(AuthError::TokenExpired);
+        }
+
+        match challenge.status {
+            PushChallengeStatus::Pending => Err(AuthError::AuthorizationPending),
+            PushChallengeStatus::Denied | PushChallengeStatus::Consumed => {
+                Err(AuthError::InvalidMfaCode)
+            }
+            PushChallengeStatus::Approved => {
+                if !self.repo.consume(challenge_id).await? {
+                    return Err(AuthError::InvalidMfaCode);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Signature a device must send: hex HMAC-SHA256 over "{challenge_id}:{approve|deny}"
+    pub fn sign_response(secret: &str, challenge_id: Uuid, approve: bool) -> String {
+        let message = format!("{}:{}", challenge_id, if approve { "approve" } else { "deny" });
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(message.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
@@ -0,0 +1,304 @@
+use chrono::{Duration, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, AuthError};
+use crate::models::{AuditAction, QrLoginChannel, QrLoginChannelEntry, QrLoginStatus};
+use crate::repositories::{UserAppRepository, UserRepository};
+use crate::services::challenge_store::CHALLENGE_KIND_QR_LOGIN;
+use crate::services::{AuditService, AuthService, ChallengeStore, LoginContext};
+use crate::utils::acr::SignInMethod;
+use crate::utils::jwt::{JwtManager, TokenPair};
+use crate::utils::secret::{constant_time_compare, generate_oauth_token, hash_oauth_token};
+use crate::utils::travel::TravelPolicy;
+
+/// How long a QR channel stays valid, in seconds
+const QR_CHANNEL_EXPIRY_SECONDS: i64 = 120;
+
+/// How long an expired channel is kept, so a late poll is told it expired
+/// rather than that it never existed, in seconds
+const QR_CHANNEL_RETENTION_SECONDS: i64 = 300;
+
+/// Minimum polling interval advertised to the waiting device, in seconds
+const QR_POLL_INTERVAL_SECONDS: i64 = 2;
+
+/// A freshly opened QR login channel
+#[derive(Debug, Clone)]
+pub struct QrLoginStart {
+    /// Encoded in the QR code and scanned by the phone
+    pub channel_code: String,
+    /// Kept by the displaying device only; required to collect the tokens
+    pub poll_secret: String,
+    pub expires_in: i64,
+    pub interval: i64,
+}
+
+/// Outcome of a poll from the displaying device
+#[derive(Debug, Clone)]
+pub enum QrPollResult {
+    /// Not approved yet, keep polling
+    Pending { interval: i64 },
+    /// Approved - tokens issued and channel consumed
+    Approved { tokens: TokenPair },
+}
+
+/// Service for cross-device login via QR code
+///
+/// The device showing the QR code opens a channel, a logged-in phone approves
+/// it, and the original device polls with its poll secret to receive tokens.
+/// Channels live in the challenge store, so each step may reach any instance.
+#[derive(Clone)]
+pub struct QrLoginService {
+    challenges: ChallengeStore,
+    user_repo: UserRepository,
+    user_app_repo: UserAppRepository,
+    auth_service: AuthService,
+    audit_service: AuditService,
+}
+
+impl QrLoginService {
+    pub fn new(pool: MySqlPool, challenges: ChallengeStore, jwt_manager: JwtManager) -> Self {
+        Self {
+            challenges,
+            user_repo: UserRepository::new(pool.clone()),
+            user_app_repo: UserAppRepository::new(pool.clone()),
+            auth_service: AuthService::new(pool.clone(), jwt_manager),
+            audit_service: AuditService::new(pool),
+        }
+    }
+
+    /// Check redeemed sign-ins for impossible travel with this policy
+    pub fn with_travel_policy(mut self, policy: TravelPolicy) -> Self {
+        self.auth_service = self.auth_service.with_travel_policy(policy);
+        self
+    }
+
+    /// Open a new channel for the device that will display the QR code
+    pub async fn start(
+        &self,
+        app_id: Option<Uuid>,
+        context: &LoginContext,
+    ) -> Result<QrLoginStart, AuthError> {
+        let channel_code = generate_oauth_token();
+        let poll_secret = generate_oauth_token();
+        let now = Utc::now();
+        let channel = QrLoginChannelEntry {
+            id: Uuid::new_v4(),
+            poll_secret_hash: hash_oauth_token(&poll_secret),
+            status: QrLoginStatus::Pending,
+            user_id: None,
+            app_id,
+            ip_address: context.ip_address.clone(),
+            user_agent: context.user_agent.clone(),
+            approved_at: None,
+            expires_at: now + Duration::seconds(QR_CHANNEL_EXPIRY_SECONDS),
+            created_at: now,
+        };
+        let stored = serde_json::to_value(&channel).map_err(|e| AuthError::InternalError(e.into()))?;
+        self.challenges
+            .put(
+                CHALLENGE_KIND_QR_LOGIN,
+                &hash_oauth_token(&channel_code),
+                &stored,
+                QR_CHANNEL_EXPIRY_SECONDS + QR_CHANNEL_RETENTION_SECONDS,
+            )
+            .await
+            .map_err(store_error)?;
+
+        Ok(QrLoginStart {
+            channel_code,
+            poll_secret,
+            expires_in: QR_CHANNEL_EXPIRY_SECONDS,
+            interval: QR_POLL_INTERVAL_SECONDS,
+        })
+    }
+
+    /// Approve or deny a channel from the user's already-authenticated phone
+    ///
+    /// Returns the channel so the phone can show which device was approved.
+    pub async fn respond(
+        &self,
+        channel_code: &str,
+        user_id: Uuid,
+        approve: bool,
+        context: &LoginContext,
+    ) -> Result<QrLoginChannel, AuthError> {
+        let channel_code_hash = hash_oauth_token(channel_code);
+        let channel = self.find_channel(&channel_code_hash).await?;
+
+        if channel.status != QrLoginStatus::Pending {
+            return Err(AuthError::InvalidToken);
+        }
+
+        if !approve {
+            self.update_channel(&channel_code_hash, |channel| {
+                if channel.status != QrLoginStatus::Pending {
+                    return false;
+                }
+                channel.status = QrLoginStatus::Denied;
+                channel.user_id = Some(user_id);
+                true
+            })
+            .await?;
+            self.log_response(user_id, AuditAction::QrLoginDenied, &channel, context).await;
+            return self.load(&channel_code_hash).await;
+        }
+
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or(AuthError::UserNotFound)?;
+
+        if !user.is_active {
+            return Err(AuthError::UserInactive);
+        }
+
+        if let Some(app_id) = channel.app_id {
+            if let Some(user_app) = self
+                .user_app_repo
+                .find(user_id, app_id)
+                .await
+                .map_err(|e| AuthError::InternalError(anyhow::anyhow!("{}", e)))?
+            {
+                if user_app.status == crate::models::user_app::UserAppStatus::Banned {
+                    return Err(AuthError::UserBanned {
+                        reason: user_app.banned_reason,
+                    });
+                }
+            }
+        }
+
+        // Conditional update - loses cleanly if the channel expired or was answered meanwhile
+        let approved = self
+            .update_channel(&channel_code_hash, |channel| {
+                if channel.status != QrLoginStatus::Pending || channel.is_expired() {
+                    return false;
+                }
+                channel.status = QrLoginStatus::Approved;
+                channel.user_id = Some(user_id);
+                channel.approved_at = Some(Utc::now());
+                true
+            })
+            .await?;
+        if !approved {
+            return Err(AuthError::TokenExpired);
+        }
+
+        self.log_response(user_id, AuditAction::QrLoginApproved, &channel, context).await;
+        self.load(&channel_code_hash).await
+    }
+
+    /// Poll a channel from the displaying device
+    ///
+    /// The poll secret binds the channel to the device that opened it, so a
+    /// bystander who photographs the QR code cannot collect the tokens.
+    pub async fn poll(
+        &self,
+        channel_code: &str,
+        poll_secret: &str,
+        context: &LoginContext,
+    ) -> Result<QrPollResult, AuthError> {
+        let channel_code_hash = hash_oauth_token(channel_code);
+        let channel = self.find_channel(&channel_code_hash).await?;
+
+        if !constant_time_compare(&hash_oauth_token(poll_secret), &channel.poll_secret_hash) {
+            return Err(AuthError::InvalidToken);
+        }
+
+        match channel.status {
+            QrLoginStatus::Pending => Ok(QrPollResult::Pending {
+                interval: QR_POLL_INTERVAL_SECONDS,
+            }),
+            QrLoginStatus::Denied => Err(AuthError::InvalidCredentials),
+            QrLoginStatus::Redeemed => Err(AuthError::InvalidToken),
+            QrLoginStatus::Approved => {
+                let user_id = channel.user_id.ok_or(AuthError::InvalidToken)?;
+
+                // One-time redemption: only the poll that flips the status gets tokens
+                let redeemed = self
+                    .update_channel(&channel_code_hash, |channel| {
+                        if channel.status != QrLoginStatus::Approved || channel.is_expired() {
+                            return false;
+                        }
+                        channel.status = QrLoginStatus::Redeemed;
+                        true
+                    })
+                    .await?;
+                if !redeemed {
+                    return Err(AuthError::InvalidToken);
+                }
+
+                let (tokens, _session_id) = self
+                    .auth_service
+                    .complete_login(user_id, channel.app_id, None, SignInMethod::CrossDevice, context)
+                    .await?;
+
+                Ok(QrPollResult::Approved { tokens })
+            }
+        }
+    }
+
+    /// Look up a live channel by its code hash
+    async fn find_channel(&self, channel_code_hash: &str) -> Result<QrLoginChannel, AuthError> {
+        let channel = self.load(channel_code_hash).await?;
+
+        if channel.is_expired() {
+            return Err(AuthError::TokenExpired);
+        }
+
+        Ok(channel)
+    }
+
+    /// Look up a channel by its code hash, live or not
+    async fn load(&self, channel_code_hash: &str) -> Result<QrLoginChannel, AuthError> {
+        self.challenges
+            .get_as::<QrLoginChannelEntry>(CHALLENGE_KIND_QR_LOGIN, channel_code_hash)
+            .await
+            .map_err(store_error)?
+            .map(QrLoginChannel::from)
+            .ok_or(AuthError::InvalidToken)
+    }
+
+    /// Change a channel; `change` returns false to leave it alone
+    /// Returns false if the channel was gone or `change` declined
+    async fn update_channel(
+        &self,
+        channel_code_hash: &str,
+        change: impl FnMut(&mut QrLoginChannelEntry) -> bool,
+    ) -> Result<bool, AuthError> {
+        self.challenges
+            .update(CHALLENGE_KIND_QR_LOGIN, channel_code_hash, change)
+            .await
+            .map_err(store_error)
+    }
+
+    async fn log_response(
+        &self,
+        user_id: Uuid,
+        action: AuditAction,
+        channel: &QrLoginChannel,
+        context: &LoginContext,
+    ) {
+        let _ = self
+            .audit_service
+            .log_auth_event(
+                Some(user_id),
+                action,
+                context.ip_address.as_deref(),
+                context.user_agent.as_deref(),
+                Some(serde_json::json!({
+                    "channel_id": channel.id.to_string(),
+                    "device_ip_address": channel.ip_address,
+                    "device_user_agent": channel.user_agent
+                })),
+                true,
+            )
+            .await;
+    }
+}
+
+/// Challenge store failures are internal errors
+fn store_error(e: AppError) -> AuthError {
+    AuthError::InternalError(e.into())
+}
@@ -1,6 +1,6 @@
 use sqlx::MySqlPool;
 
-use crate::error::AppError;
+use crate::error::{AppError, Throttle};
 use crate::repositories::RateLimitRepository;
 use crate::repositories::rate_limit::RateLimitConfig as RepoRateLimitConfig;
 
@@ -54,6 +54,22 @@ impl RateLimitConfig {
         }
     }
 
+    /// SMS code sends: 3 messages per 10 minutes
+    pub fn sms_send() -> Self {
+        Self {
+            max_requests: 3,
+            window_seconds: 600,
+        }
+    }
+
+    /// Email code sends: 3 emails per 10 minutes
+    pub fn email_code_send() -> Self {
+        Self {
+            max_requests: 3,
+            window_seconds: 600,
+        }
+    }
+
     /// Token refresh: 10 attempts per minute
     pub fn token_refresh() -> Self {
         Self {
@@ -85,9 +101,23 @@ pub struct RateLimitResult {
     pub current_count: i32,
     pub max_requests: i32,
     pub remaining: i32,
+    pub window_seconds: i64,
     pub retry_after_seconds: Option<i64>,
 }
 
+impl RateLimitResult {
+    /// Retry hint for a refused request, named after the limit's `policy`
+    pub fn throttle(&self, policy: &'static str) -> Throttle {
+        Throttle {
+            policy,
+            retry_after_seconds: self.retry_after_seconds.unwrap_or(self.window_seconds),
+            limit: self.max_requests,
+            remaining: self.remaining,
+            window_seconds: self.window_seconds,
+        }
+    }
+}
+
 /// Service for rate limiting
 #[derive(Clone)]
 pub struct RateLimiterService {
@@ -128,6 +158,7 @@ impl RateLimiterService {
             current_count,
             max_requests: config.max_requests,
             remaining,
+            window_seconds: config.window_seconds,
             retry_after_seconds: retry_after,
         })
     }
@@ -152,6 +183,7 @@ impl RateLimiterService {
             current_count,
             max_requests: config.max_requests,
             remaining,
+            window_seconds: config.window_seconds,
             retry_after_seconds: None,
         })
     }
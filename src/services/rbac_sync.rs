@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::dto::SyncRbacRequest;
+use crate::error::RbacSyncError;
+use crate::models::{RbacCatalog, RbacChangeSummary, RbacRoleSpec};
+use crate::repositories::RbacSyncRepository;
+
+/// Maximum number of roles or permissions in a synced catalog
+const MAX_CATALOG_ENTRIES: usize = 1000;
+
+/// Maximum length of role names and permission codes (matches the columns)
+const MAX_NAME_LEN: usize = 100;
+
+/// Service syncing an app's role/permission catalog from config-as-code
+///
+/// The request carries the full desired state; everything not in it is
+/// removed, so deploys can push the same catalog repeatedly.
+#[derive(Clone)]
+pub struct RbacSyncService {
+    sync_repo: RbacSyncRepository,
+}
+
+impl RbacSyncService {
+    /// Create a new RbacSyncService with the given database pool
+    pub fn new(pool: MySqlPool) -> Self {
+        Self {
+            sync_repo: RbacSyncRepository::new(pool),
+        }
+    }
+
+    /// Reconcile the app's roles, permissions and mappings with the request
+    ///
+    /// # Returns
+    /// * `Ok(RbacChangeSummary)` - What was (or, for a dry run, would be) changed
+    /// * `Err(RbacSyncError::InvalidCatalog)` - If the desired catalog is inconsistent
+    pub async fn sync(
+        &self,
+        app_id: Uuid,
+        req: &SyncRbacRequest,
+        dry_run: bool,
+    ) -> Result<RbacChangeSummary, RbacSyncError> {
+        let catalog = Self::validate(req)?;
+        self.sync_repo.sync_catalog(app_id, &catalog, dry_run).await
+    }
+
+    /// Check the desired catalog and turn it into an `RbacCatalog`
+    fn validate(req: &SyncRbacRequest) -> Result<RbacCatalog, RbacSyncError> {
+        if req.permissions.len() > MAX_CATALOG_ENTRIES || req.roles.len() > MAX_CATALOG_ENTRIES {
+            return Err(RbacSyncError::InvalidCatalog(format!(
+                "A catalog can hold at most {} roles and {} permissions",
+                MAX_CATALOG_ENTRIES, MAX_CATALOG_ENTRIES
+            )));
+        }
+
+        let mut permission_keys = HashSet::new();
+        for code in &req.permissions {
+            Self::validate_name("Permission code", code)?;
+            if !permission_keys.insert(code.to_lowercase()) {
+                return Err(RbacSyncError::InvalidCatalog(format!(
+                    "Duplicate permission '{}'",
+                    code
+                )));
+            }
+        }
+
+        let declared: HashSet<&str> = req.permissions.iter().map(String::as_str).collect();
+        let mut role_keys = HashSet::new();
+        for role in &req.roles {
+            Self::validate_name("Role name", &role.name)?;
+            if !role_keys.insert(role.name.to_lowercase()) {
+                return Err(RbacSyncError::InvalidCatalog(format!(
+                    "Duplicate role '{}'",
+                    role.name
+                )));
+            }
+            if let Some(unknown) = role.permissions.iter().find(|p| !declared.contains(p.as_str())) {
+                return Err(RbacSyncError::InvalidCatalog(format!(
+                    "Role '{}' references undeclared permission '{}'",
+                    role.name, unknown
+                )));
+            }
+        }
+
+        Ok(RbacCatalog {
+            permissions: req.permissions.clone(),
+            roles: req
+                .roles
+                .iter()
+                .map(|role| RbacRoleSpec {
+                    name: role.name.clone(),
+                    permissions: role.permissions.clone(),
+                })
+                .collect(),
+        })
+    }
+
+    fn validate_name(kind: &str, value: &str) -> Result<(), RbacSyncError> {
+        if value.trim().is_empty() || value.trim() != value || value.len() > MAX_NAME_LEN {
+            return Err(RbacSyncError::InvalidCatalog(format!(
+                "{} '{}' must be 1-{} characters without surrounding whitespace",
+                kind, value, MAX_NAME_LEN
+            )));
+        }
+        Ok(())
+    }
+}
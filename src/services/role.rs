@@ -1,9 +1,17 @@
+use chrono::{DateTime, Utc};
 use sqlx::MySqlPool;
 use uuid::Uuid;
 
 use crate::error::RoleError;
-use crate::models::Role;
-use crate::repositories::{AppRepository, RoleRepository, UserAppRoleRepository, UserRepository};
+use crate::models::{
+    Role, RoleChangeActor, UserAppRole, WebhookEvent, ROLE_HISTORY_EXPIRED, ROLE_HISTORY_GRANTED,
+    ROLE_HISTORY_REMOVED,
+};
+use crate::repositories::{
+    AppRepository, RoleHistoryRepository, RoleRepository, UserAppRoleRepository, UserRepository,
+};
+use crate::services::WebhookService;
+use crate::utils::access_window::AccessWindow;
 
 /// Service for role management operations
 /// 
@@ -14,6 +22,8 @@ pub struct RoleService {
     app_repo: AppRepository,
     user_repo: UserRepository,
     user_app_role_repo: UserAppRoleRepository,
+    role_history_repo: RoleHistoryRepository,
+    webhook_service: WebhookService,
 }
 
 impl RoleService {
@@ -23,7 +33,9 @@ impl RoleService {
             role_repo: RoleRepository::new(pool.clone()),
             app_repo: AppRepository::new(pool.clone()),
             user_repo: UserRepository::new(pool.clone()),
-            user_app_role_repo: UserAppRoleRepository::new(pool),
+            user_app_role_repo: UserAppRoleRepository::new(pool.clone()),
+            role_history_repo: RoleHistoryRepository::new(pool.clone()),
+            webhook_service: WebhookService::new(pool),
         }
     }
 
@@ -72,9 +84,14 @@ impl RoleService {
     /// * `user_id` - The UUID of the user
     /// * `app_id` - The UUID of the app
     /// * `role_id` - The UUID of the role to assign
+    /// * `expires_at` - When the assignment lapses (`None` for a permanent assignment)
+    /// * `access_window` - Weekly schedule the role is usable in (`None` for any time)
+    /// * `actor` - Who made the change, recorded in the role history
     /// 
     /// # Returns
     /// * `Ok(())` - Role was successfully assigned
+    /// * `Err(RoleError::InvalidExpiry)` - If `expires_at` is not in the future
+    /// * `Err(RoleError::InvalidAccessWindow)` - If `access_window` does not parse
     /// * `Err(RoleError::UserNotFound)` - If user doesn't exist
     /// * `Err(RoleError::AppNotFound)` - If app doesn't exist
     /// * `Err(RoleError::NotFound)` - If role doesn't exist or doesn't belong to the app
@@ -87,7 +104,19 @@ impl RoleService {
         user_id: Uuid,
         app_id: Uuid,
         role_id: Uuid,
+        expires_at: Option<DateTime<Utc>>,
+        access_window: Option<&str>,
+        actor: RoleChangeActor,
     ) -> Result<(), RoleError> {
+        if expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(RoleError::InvalidExpiry);
+        }
+        // Stored normalized, so listings show one spelling per schedule
+        let access_window = access_window
+            .map(|window| window.parse::<AccessWindow>().map(|w| w.to_string()))
+            .transpose()
+            .map_err(RoleError::InvalidAccessWindow)?;
+
         // Verify user exists (Requirement 8.2)
         let user = self.user_repo.find_by_id(user_id).await
             .map_err(|e| RoleError::InternalError(e.into()))?;
@@ -114,17 +143,25 @@ impl RoleService {
         }
 
         // Create the user-app-role association (Requirement 8.1)
-        self.user_app_role_repo.assign_role(user_id, app_id, role_id).await?;
+        self.user_app_role_repo
+            .assign_role(user_id, app_id, role_id, expires_at, access_window.as_deref())
+            .await?;
+        self.role_history_repo
+            .record(app_id, user_id, role_id, ROLE_HISTORY_GRANTED, actor, expires_at)
+            .await?;
 
         Ok(())
     }
 
     /// Remove a role from a user for a specific app
+    ///
+    /// Only an assignment that actually existed is recorded in the role history.
     pub async fn remove_role_from_user(
         &self,
         user_id: Uuid,
         app_id: Uuid,
         role_id: Uuid,
+        actor: RoleChangeActor,
     ) -> Result<(), RoleError> {
         // Verify user exists
         let user = self.user_repo.find_by_id(user_id).await
@@ -144,7 +181,11 @@ impl RoleService {
         }
 
         // Remove the role
-        self.user_app_role_repo.remove_role(user_id, app_id, role_id).await?;
+        if self.user_app_role_repo.remove_role(user_id, app_id, role_id).await? {
+            self.role_history_repo
+                .record(app_id, user_id, role_id, ROLE_HISTORY_REMOVED, actor, None)
+                .await?;
+        }
 
         Ok(())
     }
@@ -166,4 +207,62 @@ impl RoleService {
         
         Ok(roles)
     }
+    /// Send `role.expiring` webhooks for one batch of assignments nearing expiry
+    ///
+    /// Returns the number of assignments processed; fewer than `batch_size`
+    /// means none are left.
+    pub async fn notify_expiring_roles(&self, notice_secs: i64, batch_size: i64) -> Result<usize, RoleError> {
+        let expiring = self.user_app_role_repo.find_expiring(notice_secs, batch_size).await?;
+
+        for uar in &expiring {
+            self.trigger_expiry_event(uar, WebhookEvent::RoleExpiring).await;
+            self.user_app_role_repo
+                .mark_expiry_notified(uar.user_id, uar.app_id, uar.role_id)
+                .await?;
+        }
+
+        Ok(expiring.len())
+    }
+
+    /// Remove one batch of expired assignments and send `role.expired` webhooks
+    ///
+    /// Returns the number of assignments processed; fewer than `batch_size`
+    /// means none are left.
+    pub async fn remove_expired_roles(&self, batch_size: i64) -> Result<usize, RoleError> {
+        let expired = self.user_app_role_repo.find_expired(batch_size).await?;
+
+        for uar in &expired {
+            // Skip assignments extended since they were read
+            if self.user_app_role_repo
+                .delete_if_expired(uar.user_id, uar.app_id, uar.role_id)
+                .await?
+            {
+                self.role_history_repo
+                    .record(uar.app_id, uar.user_id, uar.role_id, ROLE_HISTORY_EXPIRED, RoleChangeActor::system(), uar.expires_at)
+                    .await?;
+                self.trigger_expiry_event(uar, WebhookEvent::RoleExpired).await;
+            }
+        }
+
+        Ok(expired.len())
+    }
+
+    async fn trigger_expiry_event(&self, uar: &UserAppRole, event: WebhookEvent) {
+        let role_name = self.role_repo.find_by_id(uar.role_id).await
+            .ok()
+            .flatten()
+            .map(|r| r.name);
+        let payload = serde_json::json!({
+            "event": event.as_str(),
+            "user_id": uar.user_id.to_string(),
+            "app_id": uar.app_id.to_string(),
+            "role_id": uar.role_id.to_string(),
+            "role_name": role_name,
+            "expires_at": uar.expires_at.map(|at| at.to_rfc3339()),
+            "timestamp": Utc::now().to_rfc3339()
+        });
+        if let Err(e) = self.webhook_service.trigger_event(uar.app_id, event, payload).await {
+            tracing::warn!("Failed to queue role expiry webhook: {}", e);
+        }
+    }
 }
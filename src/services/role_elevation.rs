@@ -0,0 +1,221 @@
+use chrono::{Duration, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::user_app::UserAppStatus;
+use crate::models::{
+    RoleChangeActor, RoleElevationRequest, WebhookEvent, ELEVATION_STATUS_APPROVED,
+    ELEVATION_STATUS_DENIED, ELEVATION_STATUS_PENDING, ROLE_HISTORY_GRANTED,
+};
+use crate::repositories::{
+    AppRepository, RoleElevationRepository, RoleHistoryRepository, RoleRepository, UserAppRepository,
+    UserAppRoleRepository,
+};
+use crate::services::WebhookService;
+use crate::utils::request_id::spawn_in_request;
+
+/// Shortest elevation a member may request
+pub const MIN_ELEVATION_SECS: i64 = 60;
+
+/// Service for temporary role elevation
+///
+/// App members request a role for a limited time; the app owner approves
+/// or denies. Approval grants a time-bounded assignment which the role
+/// expiry worker removes once it lapses.
+#[derive(Clone)]
+pub struct RoleElevationService {
+    elevation_repo: RoleElevationRepository,
+    app_repo: AppRepository,
+    role_repo: RoleRepository,
+    user_app_repo: UserAppRepository,
+    user_app_role_repo: UserAppRoleRepository,
+    role_history_repo: RoleHistoryRepository,
+    webhook_service: WebhookService,
+    max_duration_secs: i64,
+}
+
+impl RoleElevationService {
+    pub fn new(pool: MySqlPool, max_duration_secs: i64) -> Self {
+        Self {
+            elevation_repo: RoleElevationRepository::new(pool.clone()),
+            app_repo: AppRepository::new(pool.clone()),
+            role_repo: RoleRepository::new(pool.clone()),
+            user_app_repo: UserAppRepository::new(pool.clone()),
+            user_app_role_repo: UserAppRoleRepository::new(pool.clone()),
+            role_history_repo: RoleHistoryRepository::new(pool.clone()),
+            webhook_service: WebhookService::new(pool),
+            max_duration_secs,
+        }
+    }
+
+    /// Request a role for `duration_secs` (active app members only)
+    pub async fn request_elevation(
+        &self,
+        requester_id: Uuid,
+        app_id: Uuid,
+        role_id: Uuid,
+        duration_secs: i64,
+        reason: Option<&str>,
+    ) -> Result<RoleElevationRequest, AppError> {
+        if !(MIN_ELEVATION_SECS..=self.max_duration_secs).contains(&duration_secs) {
+            return Err(AppError::ValidationError(format!(
+                "duration_secs must be between {} and {}",
+                MIN_ELEVATION_SECS, self.max_duration_secs
+            )));
+        }
+
+        let reason = reason.map(str::trim).filter(|r| !r.is_empty());
+        if reason.is_some_and(|r| r.len() > 1000) {
+            return Err(AppError::ValidationError("reason must be at most 1000 characters".into()));
+        }
+
+        self.find_app_role(app_id, role_id).await?;
+
+        let membership = self.user_app_repo.find(requester_id, app_id).await
+            .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?;
+        if !matches!(membership, Some(ref m) if m.status == UserAppStatus::Active) {
+            return Err(AppError::NotFound("App membership not found".into()));
+        }
+
+        if self.elevation_repo.has_pending(app_id, requester_id, role_id).await? {
+            return Err(AppError::ValidationError(
+                "A pending elevation request for this role already exists".into(),
+            ));
+        }
+
+        let request = self.elevation_repo
+            .create(app_id, requester_id, role_id, reason, duration_secs)
+            .await?;
+
+        let webhook_service = self.webhook_service.clone();
+        let payload = serde_json::json!({
+            "event": "role.elevation_requested",
+            "request_id": request.id.to_string(),
+            "user_id": requester_id.to_string(),
+            "app_id": app_id.to_string(),
+            "role_id": role_id.to_string(),
+            "duration_secs": duration_secs,
+            "reason": request.reason,
+            "timestamp": Utc::now().to_rfc3339()
+        });
+        spawn_in_request(async move {
+            let _ = webhook_service.trigger_event(app_id, WebhookEvent::RoleElevationRequested, payload).await;
+        });
+
+        Ok(request)
+    }
+
+    /// List elevation requests of an app (owner only)
+    pub async fn list_requests(
+        &self,
+        requester_id: Uuid,
+        app_id: Uuid,
+        status: Option<&str>,
+    ) -> Result<Vec<RoleElevationRequest>, AppError> {
+        if let Some(status) = status {
+            if ![ELEVATION_STATUS_PENDING, ELEVATION_STATUS_APPROVED, ELEVATION_STATUS_DENIED].contains(&status) {
+                return Err(AppError::ValidationError(format!("Invalid status: {}", status)));
+            }
+        }
+
+        self.require_owner(app_id, requester_id).await?;
+        self.elevation_repo.list_by_app(app_id, status).await
+    }
+
+    /// Approve a pending request and grant the role until now + requested duration (owner only)
+    pub async fn approve(
+        &self,
+        requester_id: Uuid,
+        app_id: Uuid,
+        request_id: Uuid,
+    ) -> Result<RoleElevationRequest, AppError> {
+        self.require_owner(app_id, requester_id).await?;
+        let request = self.find_pending(app_id, request_id).await?;
+
+        // The role may have been deleted since the request was made
+        self.find_app_role(app_id, request.role_id).await?;
+
+        let grant_expires_at = Utc::now() + Duration::seconds(request.duration_secs);
+        if !self.elevation_repo
+            .decide(request_id, ELEVATION_STATUS_APPROVED, requester_id, Some(grant_expires_at))
+            .await?
+        {
+            return Err(AppError::ValidationError("Elevation request was already decided".into()));
+        }
+
+        self.user_app_role_repo
+            .grant_role_until(request.user_id, app_id, request.role_id, grant_expires_at)
+            .await
+            .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?;
+        self.role_history_repo
+            .record(
+                app_id,
+                request.user_id,
+                request.role_id,
+                ROLE_HISTORY_GRANTED,
+                RoleChangeActor::user(requester_id),
+                Some(grant_expires_at),
+            )
+            .await
+            .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?;
+
+        self.reload(request_id).await
+    }
+
+    /// Deny a pending request (owner only)
+    pub async fn deny(
+        &self,
+        requester_id: Uuid,
+        app_id: Uuid,
+        request_id: Uuid,
+    ) -> Result<RoleElevationRequest, AppError> {
+        self.require_owner(app_id, requester_id).await?;
+        self.find_pending(app_id, request_id).await?;
+
+        if !self.elevation_repo
+            .decide(request_id, ELEVATION_STATUS_DENIED, requester_id, None)
+            .await?
+        {
+            return Err(AppError::ValidationError("Elevation request was already decided".into()));
+        }
+
+        self.reload(request_id).await
+    }
+
+    async fn require_owner(&self, app_id: Uuid, requester_id: Uuid) -> Result<(), AppError> {
+        if self.app_repo.find_by_id(app_id).await?.is_none() {
+            return Err(AppError::NotFound("App not found".into()));
+        }
+        if !self.app_repo.is_owner(app_id, requester_id).await? {
+            return Err(AppError::NotAppOwner);
+        }
+        Ok(())
+    }
+
+    async fn find_app_role(&self, app_id: Uuid, role_id: Uuid) -> Result<(), AppError> {
+        let role = self.role_repo.find_by_id(role_id).await
+            .map_err(|e| AppError::InternalError(anyhow::anyhow!("{}", e)))?;
+        match role {
+            Some(r) if r.app_id == app_id => Ok(()),
+            _ => Err(AppError::NotFound("Role not found".into())),
+        }
+    }
+
+    async fn find_pending(&self, app_id: Uuid, request_id: Uuid) -> Result<RoleElevationRequest, AppError> {
+        let request = self.elevation_repo.find_by_id(request_id).await?
+            .filter(|r| r.app_id == app_id)
+            .ok_or_else(|| AppError::NotFound("Elevation request not found".into()))?;
+
+        if request.status != ELEVATION_STATUS_PENDING {
+            return Err(AppError::ValidationError("Elevation request was already decided".into()));
+        }
+
+        Ok(request)
+    }
+
+    async fn reload(&self, request_id: Uuid) -> Result<RoleElevationRequest, AppError> {
+        self.elevation_repo.find_by_id(request_id).await?
+            .ok_or_else(|| AppError::NotFound("Elevation request not found".into()))
+    }
+}
@@ -1,31 +1,114 @@
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use sqlx::MySqlPool;
 use uuid::Uuid;
 
+use crate::config::Config;
 use crate::error::AuthError;
 use crate::models::UserSession;
-use crate::repositories::SessionRepository;
+use crate::repositories::{SessionRepository, TokenLineageRepository};
+use crate::services::token_lineage::REVOKED_SESSION;
 use crate::utils::password::hash_token;
 
+/// Sliding lifetime of sessions bound to a registered device, in days
+pub const DEVICE_SESSION_EXPIRY_DAYS: i64 = 60;
+
+/// Idle timeout and absolute lifetime enforced when a session is refreshed
+///
+/// Server-wide defaults come from `Config`; apps and OAuth clients can
+/// override either limit for tokens issued in their context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionPolicy {
+    /// Maximum time between refreshes, in seconds
+    pub idle_timeout_secs: i64,
+    /// Maximum time since login, in seconds, regardless of activity
+    pub absolute_lifetime_secs: i64,
+}
+
+impl Default for SessionPolicy {
+    fn default() -> Self {
+        Self {
+            idle_timeout_secs: 24 * 60 * 60,
+            absolute_lifetime_secs: 90 * 24 * 60 * 60,
+        }
+    }
+}
+
+impl SessionPolicy {
+    /// Server-wide defaults
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            idle_timeout_secs: config.session_idle_timeout_secs,
+            absolute_lifetime_secs: config.session_absolute_lifetime_secs,
+        }
+    }
+
+    /// Apply per-app or per-client overrides on top of this policy
+    pub fn with_overrides(self, idle_timeout_secs: Option<i64>, absolute_lifetime_secs: Option<i64>) -> Self {
+        Self {
+            idle_timeout_secs: idle_timeout_secs.unwrap_or(self.idle_timeout_secs),
+            absolute_lifetime_secs: absolute_lifetime_secs.unwrap_or(self.absolute_lifetime_secs),
+        }
+    }
+
+    /// Validate overrides set by an app owner
+    pub fn validate_overrides(
+        idle_timeout_secs: Option<i64>,
+        absolute_lifetime_secs: Option<i64>,
+    ) -> Result<(), String> {
+        if idle_timeout_secs.is_some_and(|secs| secs <= 0) {
+            return Err("idle_timeout_secs must be positive".to_string());
+        }
+        if absolute_lifetime_secs.is_some_and(|secs| secs <= 0) {
+            return Err("absolute_lifetime_secs must be positive".to_string());
+        }
+        if let (Some(idle), Some(absolute)) = (idle_timeout_secs, absolute_lifetime_secs) {
+            if idle > absolute {
+                return Err("idle_timeout_secs cannot exceed absolute_lifetime_secs".to_string());
+            }
+        }
+        // Stored as INT
+        if idle_timeout_secs.into_iter().chain(absolute_lifetime_secs).any(|secs| secs > i32::MAX as i64) {
+            return Err("Session lifetime is too long".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether a session last used at `last_active_at` has been idle too long
+    pub fn is_idle_expired(&self, last_active_at: DateTime<Utc>) -> bool {
+        last_active_at + Duration::seconds(self.idle_timeout_secs) < Utc::now()
+    }
+
+    /// Absolute end of a session started at `started_at`
+    pub fn absolute_expiry(&self, started_at: DateTime<Utc>) -> DateTime<Utc> {
+        started_at + Duration::seconds(self.absolute_lifetime_secs)
+    }
+}
+
 /// Service for session management
 #[derive(Clone)]
 pub struct SessionService {
     repo: SessionRepository,
+    lineage_repo: TokenLineageRepository,
     session_expiry_days: i64,
 }
 
 impl SessionService {
     pub fn new(pool: MySqlPool, session_expiry_days: i64) -> Self {
         Self {
-            repo: SessionRepository::new(pool),
+            repo: SessionRepository::new(pool.clone()),
+            lineage_repo: TokenLineageRepository::new(pool),
             session_expiry_days,
         }
     }
 
     /// Create a new session for a user
+    ///
+    /// `session_id` is chosen by the caller so the session's tokens can carry it.
     pub async fn create_session(
         &self,
+        session_id: Uuid,
         user_id: Uuid,
+        app_id: Option<Uuid>,
         refresh_token: &str,
         device_info: Option<DeviceInfo>,
     ) -> Result<UserSession, AuthError> {
@@ -39,7 +122,9 @@ impl SessionService {
 
         self.repo
             .create(
+                session_id,
                 user_id,
+                app_id,
                 &token_hash,
                 device_name.as_deref(),
                 device_type.as_deref(),
@@ -63,6 +148,50 @@ impl SessionService {
         Ok(session)
     }
 
+    /// Find the session a refresh token belongs to, including revoked or expired ones
+    pub async fn find_by_refresh_token(&self, refresh_token: &str) -> Result<Option<UserSession>, AuthError> {
+        let token_hash = hash_token(refresh_token)?;
+        self.repo.find_any_by_token_hash(&token_hash).await
+    }
+
+    /// Find a session by ID, including revoked or expired ones
+    pub async fn find_by_id(&self, session_id: Uuid) -> Result<Option<UserSession>, AuthError> {
+        self.repo.find_by_id(session_id).await
+    }
+
+    /// Mark a session as active now, restarting its idle timeout
+    pub async fn touch(&self, session_id: Uuid) -> Result<(), AuthError> {
+        self.repo.update_last_active(session_id).await
+    }
+
+    /// Swap the session's refresh token for a newly issued one
+    ///
+    /// Returns false when the session no longer holds `old_refresh_token`,
+    /// because a concurrent refresh already rotated it.
+    pub async fn rotate_refresh_token(
+        &self,
+        session_id: Uuid,
+        old_refresh_token: &str,
+        new_refresh_token: &str,
+        expires_at: chrono::DateTime<Utc>,
+    ) -> Result<bool, AuthError> {
+        let old_hash = hash_token(old_refresh_token)?;
+        let token_hash = hash_token(new_refresh_token)?;
+        self.repo.rotate_refresh_token(session_id, &old_hash, &token_hash, expires_at).await
+    }
+
+    /// Bind a session to a registered device, switching it to the device lifetime
+    pub async fn bind_device(&self, session_id: Uuid, device_id: Uuid) -> Result<(), AuthError> {
+        let expires_at = Utc::now() + Duration::days(DEVICE_SESSION_EXPIRY_DAYS);
+        self.repo.bind_device(session_id, device_id, expires_at).await
+    }
+
+    /// Revoke every session bound to a device
+    pub async fn revoke_device_sessions(&self, device_id: Uuid) -> Result<u64, AuthError> {
+        self.lineage_repo.revoke_for_device(device_id, REVOKED_SESSION).await?;
+        self.repo.revoke_by_device(device_id).await
+    }
+
     /// Get all active sessions for a user
     pub async fn get_user_sessions(&self, user_id: Uuid) -> Result<Vec<UserSession>, AuthError> {
         self.repo.list_active_by_user(user_id).await
@@ -76,12 +205,14 @@ impl SessionService {
                 return Err(AuthError::InsufficientScope);
             }
             self.repo.revoke(session_id).await?;
+            self.lineage_repo.revoke_session(session_id, REVOKED_SESSION).await?;
         }
         Ok(())
     }
 
     /// Revoke all sessions for a user (logout everywhere)
     pub async fn revoke_all_sessions(&self, user_id: Uuid) -> Result<u64, AuthError> {
+        self.lineage_repo.revoke_for_user(user_id, None, REVOKED_SESSION).await?;
         self.repo.revoke_all_for_user(user_id).await
     }
 
@@ -91,6 +222,9 @@ impl SessionService {
         user_id: Uuid,
         current_session_id: Uuid,
     ) -> Result<u64, AuthError> {
+        self.lineage_repo
+            .revoke_for_user(user_id, Some(current_session_id), REVOKED_SESSION)
+            .await?;
         self.repo.revoke_all_except(user_id, current_session_id).await
     }
 
@@ -0,0 +1,336 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{SigningKeyRecord, SIGNING_KEY_ACTIVE, SIGNING_KEY_IMPORTED, SIGNING_KEY_NEXT, SIGNING_KEY_RETIRED};
+use crate::repositories::SigningKeyRepository;
+use crate::utils::field_crypto::field_cipher;
+use crate::utils::jwt::JwtManager;
+use crate::utils::signing_key::{generate_key_pair, SigningKey, VerificationKey, GENERATED_KEY_BITS};
+
+/// Timing of a signing key ceremony
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SigningKeyPolicy {
+    /// Cache-Control max-age of the JWKS
+    pub jwks_cache_max_age_secs: i64,
+    /// How often every instance reloads the keys
+    pub refresh_interval_secs: i64,
+    /// How long a retired key stays published and verifying
+    pub retention_secs: i64,
+}
+
+impl SigningKeyPolicy {
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        Self {
+            jwks_cache_max_age_secs: config.jwks_cache_max_age_secs,
+            refresh_interval_secs: config.signing_key_refresh_interval_secs as i64,
+            retention_secs: config.signing_key_retention_secs,
+        }
+    }
+
+    /// How long a key must be staged before it may sign
+    ///
+    /// By then every instance has loaded it and relying parties caching the
+    /// JWKS for up to its max-age have fetched a copy that includes it.
+    pub fn staging_delay(&self) -> Duration {
+        Duration::seconds(self.jwks_cache_max_age_secs + self.refresh_interval_secs)
+    }
+}
+
+/// Signing key as listed to admins
+#[derive(Debug, Serialize)]
+pub struct SigningKeyInfo {
+    #[serde(flatten)]
+    pub key: SigningKeyRecord,
+    /// Whether the key is in the JWKS
+    pub published: bool,
+    /// Whether this instance signs new tokens with it
+    pub signing: bool,
+    /// Earliest time a staged key may be promoted without `force`
+    pub promotable_at: Option<DateTime<Utc>>,
+    /// When a retired key stops verifying and leaves the JWKS
+    pub verifies_until: Option<DateTime<Utc>>,
+}
+
+/// Service for importing, staging and promoting token signing keys
+///
+/// Keys live in the database so every instance converges on the same
+/// signing key; each instance reloads them on a timer and right after a
+/// ceremony step it handled itself.
+#[derive(Clone)]
+pub struct SigningKeyService {
+    repo: SigningKeyRepository,
+    jwt_manager: JwtManager,
+    policy: SigningKeyPolicy,
+}
+
+impl SigningKeyService {
+    pub fn new(pool: MySqlPool, jwt_manager: JwtManager, policy: SigningKeyPolicy) -> Self {
+        Self {
+            repo: SigningKeyRepository::new(pool),
+            jwt_manager,
+            policy,
+        }
+    }
+
+    /// Record the configured key as active if no key is, then load the keys
+    pub async fn bootstrap(&self) -> Result<(), AppError> {
+        if self.repo.find_active().await?.is_none() {
+            self.repo.activate_config_key(self.jwt_manager.config_key()).await?;
+            tracing::info!("Configured signing key {} is the active key", self.jwt_manager.config_key().kid());
+        }
+
+        self.reload().await
+    }
+
+    /// Load the published keys into the JWT manager
+    ///
+    /// On error the keys in use are left unchanged.
+    pub async fn reload(&self) -> Result<(), AppError> {
+        let published = self.repo.find_published(self.retired_since()).await?;
+
+        let mut signer = None;
+        let mut verifiers = Vec::with_capacity(published.len());
+        for record in &published {
+            match VerificationKey::from_components(&record.jwk_n, &record.jwk_e) {
+                Ok(key) => verifiers.push(key),
+                Err(e) => {
+                    tracing::error!("Skipping signing key {}: {}", record.kid, e);
+                    continue;
+                }
+            }
+
+            if record.status == SIGNING_KEY_ACTIVE {
+                signer = Some(self.signing_key(record)?);
+            }
+        }
+
+        let signer = signer.unwrap_or_else(|| {
+            tracing::warn!("No active signing key found; signing with the configured key");
+            self.jwt_manager.config_key().clone()
+        });
+
+        self.jwt_manager.install_keys(signer, verifiers);
+        Ok(())
+    }
+
+    /// Import an externally generated key pair
+    ///
+    /// The private key is stored sealed with field encryption, so importing
+    /// requires FIELD_ENCRYPTION_KEYS.
+    pub async fn import(
+        &self,
+        private_key_pem: &str,
+        public_key_pem: &str,
+        imported_by: Option<Uuid>,
+    ) -> Result<SigningKeyRecord, AppError> {
+        if field_cipher().is_none() {
+            return Err(AppError::ValidationError(
+                "FIELD_ENCRYPTION_KEYS must be set to store imported signing keys".into(),
+            ));
+        }
+
+        let key = SigningKey::from_pem(private_key_pem, public_key_pem)
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        if self.repo.find_by_kid(key.kid()).await?.is_some() {
+            return Err(AppError::ValidationError(format!("Signing key {} already exists", key.kid())));
+        }
+
+        self.repo.create_imported(&key, private_key_pem, imported_by).await
+    }
+
+    /// Generate a new key pair and stage it as the next key
+    ///
+    /// The key is promoted by the signing key worker once the staging delay
+    /// has passed, or right away with `force`. Tokens signed with the
+    /// current key keep verifying after it is retired.
+    pub async fn rotate(&self, generated_by: Option<Uuid>, force: bool) -> Result<SigningKeyRecord, AppError> {
+        if field_cipher().is_none() {
+            return Err(AppError::ValidationError(
+                "FIELD_ENCRYPTION_KEYS must be set to store generated signing keys".into(),
+            ));
+        }
+
+        // Don't silently unstage a key that is already on its way in
+        if let Some(next) = self.repo.find_next().await? {
+            return Err(AppError::ValidationError(format!(
+                "Signing key {} is already staged; promote it before rotating again",
+                next.kid
+            )));
+        }
+
+        let (private_key_pem, public_key_pem) =
+            tokio::task::spawn_blocking(|| generate_key_pair(GENERATED_KEY_BITS))
+                .await
+                .map_err(|e| AppError::InternalError(e.into()))?
+                .map_err(|e| AppError::InternalError(e.into()))?;
+
+        let key = SigningKey::from_pem(&private_key_pem, &public_key_pem)
+            .map_err(|e| AppError::InternalError(e.into()))?;
+        self.repo.create_generated(&key, &private_key_pem, generated_by).await?;
+
+        let staged = self.stage(key.kid()).await?;
+        if force {
+            return self.promote(&staged.kid, true).await;
+        }
+
+        Ok(staged)
+    }
+
+    /// Promote the staged key if it was generated and its staging delay has passed
+    ///
+    /// Returns the promoted key. Every instance runs this; only one wins the
+    /// promotion, the others see the key already active.
+    pub async fn promote_due(&self) -> Result<Option<SigningKeyRecord>, AppError> {
+        let Some(record) = self.repo.find_next().await? else {
+            return Ok(None);
+        };
+
+        if !record.auto_promote || self.promotable_at(&record).is_none_or(|at| Utc::now() < at) {
+            return Ok(None);
+        }
+
+        self.signing_key(&record)?;
+
+        if !self.repo.promote(&record.kid).await? {
+            return Ok(None);
+        }
+
+        self.reload().await?;
+        self.find(&record.kid).await.map(Some)
+    }
+
+    /// Publish an imported key in the JWKS as the next key
+    pub async fn stage(&self, kid: &str) -> Result<SigningKeyRecord, AppError> {
+        let record = self.find(kid).await?;
+        if record.status != SIGNING_KEY_IMPORTED {
+            return Err(AppError::ValidationError(format!(
+                "Only imported keys can be staged (key {} is {})",
+                kid, record.status
+            )));
+        }
+
+        // Never publish a key this server could not sign with
+        self.signing_key(&record)?;
+
+        if !self.repo.stage(kid).await? {
+            return Err(AppError::ValidationError(format!("Signing key {} changed state concurrently", kid)));
+        }
+
+        self.reload().await?;
+        self.find(kid).await
+    }
+
+    /// Sign with the staged next key, retiring the active one
+    ///
+    /// Refused until the key has been published for the staging delay,
+    /// unless `force` is set.
+    pub async fn promote(&self, kid: &str, force: bool) -> Result<SigningKeyRecord, AppError> {
+        let record = self.find(kid).await?;
+        if record.status != SIGNING_KEY_NEXT {
+            return Err(AppError::ValidationError(format!(
+                "Only the staged next key can be promoted (key {} is {})",
+                kid, record.status
+            )));
+        }
+
+        if let Some(promotable_at) = self.promotable_at(&record) {
+            if !force && Utc::now() < promotable_at {
+                return Err(AppError::ValidationError(format!(
+                    "Key {} may not be in cached JWKS copies yet; promote after {} or pass force",
+                    kid,
+                    promotable_at.to_rfc3339()
+                )));
+            }
+        }
+
+        self.signing_key(&record)?;
+
+        if !self.repo.promote(kid).await? {
+            return Err(AppError::ValidationError(format!("Signing key {} changed state concurrently", kid)));
+        }
+
+        self.reload().await?;
+        self.find(kid).await
+    }
+
+    /// All keys with their publication state
+    pub async fn list(&self) -> Result<Vec<SigningKeyInfo>, AppError> {
+        let signing_kid = self.jwt_manager.signing_kid();
+        let retired_since = self.retired_since();
+
+        Ok(self
+            .repo
+            .list()
+            .await?
+            .into_iter()
+            .map(|key| SigningKeyInfo {
+                published: key.status == SIGNING_KEY_NEXT
+                    || key.status == SIGNING_KEY_ACTIVE
+                    || (key.status == SIGNING_KEY_RETIRED && key.retired_at.is_some_and(|at| at >= retired_since)),
+                signing: key.kid == signing_kid,
+                promotable_at: self.promotable_at(&key),
+                verifies_until: key
+                    .retired_at
+                    .map(|at| at + Duration::seconds(self.policy.retention_secs)),
+                key,
+            })
+            .collect())
+    }
+
+    pub async fn find(&self, kid: &str) -> Result<SigningKeyRecord, AppError> {
+        self.repo
+            .find_by_kid(kid)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Signing key {} not found", kid)))
+    }
+
+    fn promotable_at(&self, key: &SigningKeyRecord) -> Option<DateTime<Utc>> {
+        if key.status != SIGNING_KEY_NEXT {
+            return None;
+        }
+        key.staged_at.map(|at| at + self.policy.staging_delay())
+    }
+
+    fn retired_since(&self) -> DateTime<Utc> {
+        Utc::now() - Duration::seconds(self.policy.retention_secs)
+    }
+
+    /// Key pair of a stored key (the configured key comes from the environment)
+    fn signing_key(&self, record: &SigningKeyRecord) -> Result<SigningKey, AppError> {
+        let config_key = self.jwt_manager.config_key();
+        if record.kid == config_key.kid() {
+            return Ok(config_key.clone());
+        }
+
+        let private_key = record.private_key.as_deref().ok_or_else(|| {
+            AppError::InternalError(anyhow::anyhow!(
+                "Private key of signing key {} is not available (check JWT_PRIVATE_KEY and FIELD_ENCRYPTION_KEYS)",
+                record.kid
+            ))
+        })?;
+
+        SigningKey::from_pem(private_key, &record.public_key_pem).map_err(|e| {
+            AppError::InternalError(anyhow::anyhow!("Signing key {} can't be loaded: {}", record.kid, e))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staging_delay_covers_cache_and_refresh() {
+        let policy = SigningKeyPolicy {
+            jwks_cache_max_age_secs: 3600,
+            refresh_interval_secs: 60,
+            retention_secs: 2592000,
+        };
+
+        assert_eq!(policy.staging_delay(), Duration::seconds(3660));
+    }
+}
@@ -0,0 +1,463 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::error::AuthError;
+use crate::utils::account_match::normalize_phone;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Shortest and longest E.164 subscriber numbers, in digits
+const MIN_E164_DIGITS: usize = 8;
+const MAX_E164_DIGITS: usize = 15;
+
+/// Twilio account credentials
+#[derive(Debug, Clone)]
+pub struct TwilioConfig {
+    pub account_sid: String,
+    pub auth_token: String,
+    /// Sender number in E.164 form
+    pub from_number: String,
+    pub endpoint: String,
+}
+
+/// Amazon SNS credentials (SMS are published directly to the phone number)
+#[derive(Debug, Clone)]
+pub struct SnsConfig {
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Set when using temporary credentials
+    pub session_token: Option<String>,
+    /// Alphanumeric sender ID, where the destination country supports one
+    pub sender_id: Option<String>,
+    pub endpoint: String,
+}
+
+/// SMS provider credentials
+#[derive(Debug, Clone, Default)]
+pub struct SmsConfig {
+    pub twilio: Option<TwilioConfig>,
+    pub sns: Option<SnsConfig>,
+    /// Provider chosen with `SMS_PROVIDER`; the first one configured otherwise
+    pub provider: Option<String>,
+}
+
+impl SmsConfig {
+    /// Load SMS configuration from environment variables
+    ///
+    /// Without credentials the code is only logged, which keeps local
+    /// development working without a Twilio or AWS account.
+    pub fn from_env() -> Self {
+        let twilio = match (
+            std::env::var("TWILIO_ACCOUNT_SID").ok(),
+            std::env::var("TWILIO_AUTH_TOKEN").ok(),
+            std::env::var("TWILIO_FROM_NUMBER").ok(),
+        ) {
+            (Some(account_sid), Some(auth_token), Some(from_number)) => Some(TwilioConfig {
+                account_sid,
+                auth_token,
+                from_number,
+                endpoint: std::env::var("TWILIO_ENDPOINT")
+                    .unwrap_or_else(|_| "https://api.twilio.com".to_string()),
+            }),
+            _ => None,
+        };
+
+        let sns = match (
+            std::env::var("AWS_SNS_REGION").or_else(|_| std::env::var("AWS_REGION")).ok(),
+            std::env::var("AWS_ACCESS_KEY_ID").ok(),
+            std::env::var("AWS_SECRET_ACCESS_KEY").ok(),
+        ) {
+            (Some(region), Some(access_key_id), Some(secret_access_key)) => Some(SnsConfig {
+                endpoint: std::env::var("AWS_SNS_ENDPOINT")
+                    .unwrap_or_else(|_| format!("https://sns.{}.amazonaws.com", region)),
+                region,
+                access_key_id,
+                secret_access_key,
+                session_token: std::env::var("AWS_SESSION_TOKEN").ok().filter(|t| !t.is_empty()),
+                sender_id: std::env::var("SMS_SENDER_ID").ok().filter(|s| !s.is_empty()),
+            }),
+            _ => None,
+        };
+
+        Self {
+            twilio,
+            sns,
+            provider: std::env::var("SMS_PROVIDER").ok().filter(|p| !p.is_empty()),
+        }
+    }
+}
+
+/// Future returned by [`SmsProvider::send`]
+pub type SmsFuture<'a> = Pin<Box<dyn Future<Output = Result<(), AuthError>> + Send + 'a>>;
+
+/// A gateway that delivers text messages
+///
+/// Implement it to route one-time passwords through another carrier and
+/// pass it to [`SmsService::new`].
+pub trait SmsProvider: Send + Sync {
+    /// Short name reported in logs and audit records
+    fn name(&self) -> &'static str;
+
+    /// Send `message` to a phone number in E.164 form
+    fn send<'a>(&'a self, to: &'a str, message: &'a str) -> SmsFuture<'a>;
+}
+
+/// Twilio Programmable Messaging
+pub struct TwilioSmsProvider {
+    config: TwilioConfig,
+    client: reqwest::Client,
+}
+
+impl TwilioSmsProvider {
+    pub fn new(config: TwilioConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+impl SmsProvider for TwilioSmsProvider {
+    fn name(&self) -> &'static str {
+        "twilio"
+    }
+
+    fn send<'a>(&'a self, to: &'a str, message: &'a str) -> SmsFuture<'a> {
+        Box::pin(async move {
+            let url = format!(
+                "{}/2010-04-01/Accounts/{}/Messages.json",
+                self.config.endpoint, self.config.account_sid
+            );
+            let response = self
+                .client
+                .post(url)
+                .basic_auth(&self.config.account_sid, Some(&self.config.auth_token))
+                .form(&[("To", to), ("From", self.config.from_number.as_str()), ("Body", message)])
+                .send()
+                .await
+                .map_err(|e| AuthError::InternalError(anyhow::anyhow!("Twilio request failed: {}", e)))?;
+
+            if !response.status().is_success() {
+                return Err(AuthError::InternalError(anyhow::anyhow!(
+                    "Twilio rejected message: {}",
+                    response.status()
+                )));
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Amazon SNS direct-to-phone publishing
+pub struct SnsSmsProvider {
+    config: SnsConfig,
+    client: reqwest::Client,
+}
+
+impl SnsSmsProvider {
+    pub fn new(config: SnsConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Form body of a `Publish` call sending a transactional SMS
+    fn publish_body(&self, to: &str, message: &str) -> String {
+        let mut params = vec![
+            ("Action", "Publish".to_string()),
+            ("Message", message.to_string()),
+            ("PhoneNumber", to.to_string()),
+            ("MessageAttributes.entry.1.Name", "AWS.SNS.SMS.SMSType".to_string()),
+            ("MessageAttributes.entry.1.Value.DataType", "String".to_string()),
+            ("MessageAttributes.entry.1.Value.StringValue", "Transactional".to_string()),
+        ];
+        if let Some(sender_id) = &self.config.sender_id {
+            params.push(("MessageAttributes.entry.2.Name", "AWS.SNS.SMS.SenderID".to_string()));
+            params.push(("MessageAttributes.entry.2.Value.DataType", "String".to_string()));
+            params.push(("MessageAttributes.entry.2.Value.StringValue", sender_id.clone()));
+        }
+        params.push(("Version", "2010-03-31".to_string()));
+
+        params
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, urlencoding::encode(value)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+impl SmsProvider for SnsSmsProvider {
+    fn name(&self) -> &'static str {
+        "sns"
+    }
+
+    fn send<'a>(&'a self, to: &'a str, message: &'a str) -> SmsFuture<'a> {
+        Box::pin(async move {
+            let host = reqwest::Url::parse(&self.config.endpoint)
+                .ok()
+                .and_then(|url| {
+                    let host = url.host_str()?;
+                    Some(match url.port() {
+                        Some(port) => format!("{}:{}", host, port),
+                        None => host.to_string(),
+                    })
+                })
+                .ok_or_else(|| AuthError::InternalError(anyhow::anyhow!("Invalid SNS endpoint")))?;
+            let body = self.publish_body(to, message);
+            let content_type = "application/x-www-form-urlencoded; charset=utf-8";
+            let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+            let mut headers = vec![
+                ("content-type", content_type.to_string()),
+                ("host", host),
+                ("x-amz-date", amz_date.clone()),
+            ];
+            if let Some(token) = &self.config.session_token {
+                headers.push(("x-amz-security-token", token.clone()));
+            }
+            let authorization = sigv4_authorization(
+                &self.config.access_key_id,
+                &self.config.secret_access_key,
+                &self.config.region,
+                "sns",
+                &amz_date,
+                &headers,
+                &body,
+            );
+
+            let mut request = self
+                .client
+                .post(&self.config.endpoint)
+                .header("content-type", content_type)
+                .header("x-amz-date", &amz_date)
+                .header("authorization", authorization);
+            if let Some(token) = &self.config.session_token {
+                request = request.header("x-amz-security-token", token);
+            }
+
+            let response = request
+                .body(body)
+                .send()
+                .await
+                .map_err(|e| AuthError::InternalError(anyhow::anyhow!("SNS request failed: {}", e)))?;
+
+            if !response.status().is_success() {
+                return Err(AuthError::InternalError(anyhow::anyhow!(
+                    "SNS rejected message: {}",
+                    response.status()
+                )));
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// No credentials configured - the message is only logged
+pub struct LogSmsProvider;
+
+impl SmsProvider for LogSmsProvider {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    fn send<'a>(&'a self, to: &'a str, message: &'a str) -> SmsFuture<'a> {
+        Box::pin(async move {
+            tracing::info!("SMS to {}: {}", to, message);
+            Ok(())
+        })
+    }
+}
+
+/// Service delivering text messages through the configured provider
+#[derive(Clone)]
+pub struct SmsService {
+    provider: Arc<dyn SmsProvider>,
+}
+
+impl SmsService {
+    pub fn new(provider: Arc<dyn SmsProvider>) -> Self {
+        Self { provider }
+    }
+
+    /// Pick the provider named in the configuration, or the first one with credentials
+    pub fn from_config(config: SmsConfig) -> Self {
+        let provider: Arc<dyn SmsProvider> = match (config.provider.as_deref(), config.twilio, config.sns) {
+            (Some("twilio") | None, Some(twilio), _) => Arc::new(TwilioSmsProvider::new(twilio)),
+            (Some("sns") | None, _, Some(sns)) => Arc::new(SnsSmsProvider::new(sns)),
+            (Some(name), _, _) if !matches!(name, "twilio" | "sns" | "log") => {
+                tracing::warn!("Unknown SMS_PROVIDER '{}', SMS will only be logged", name);
+                Arc::new(LogSmsProvider)
+            }
+            _ => Arc::new(LogSmsProvider),
+        };
+        Self::new(provider)
+    }
+
+    /// Create a service using credentials from the environment
+    pub fn from_env() -> Self {
+        Self::from_config(SmsConfig::from_env())
+    }
+
+    /// Name of the provider messages are routed through
+    pub fn provider_name(&self) -> &'static str {
+        self.provider.name()
+    }
+
+    /// Send a message to a phone number in E.164 form
+    pub async fn send(&self, to: &str, message: &str) -> Result<(), AuthError> {
+        self.provider.send(to, message).await
+    }
+}
+
+/// Normalize a phone number to E.164 (`+` and 8 to 15 digits)
+///
+/// Spaces, dashes and brackets are dropped and a `00` prefix is read as `+`.
+/// Numbers without a country code are rejected, since providers need one.
+pub fn normalize_e164(phone: &str) -> Option<String> {
+    let normalized = normalize_phone(phone)?;
+    let digits = normalized.strip_prefix('+')?;
+    let valid = (MIN_E164_DIGITS..=MAX_E164_DIGITS).contains(&digits.len()) && !digits.starts_with('0');
+    valid.then_some(normalized)
+}
+
+/// Hide all but the last three digits of a phone number, e.g. `+*********789`
+pub fn mask_phone(phone: &str) -> String {
+    let digits = phone.chars().filter(|c| c.is_ascii_digit()).count();
+    let mut seen = 0;
+    phone
+        .chars()
+        .map(|c| {
+            if !c.is_ascii_digit() {
+                return c;
+            }
+            seen += 1;
+            if seen + 3 > digits { c } else { '*' }
+        })
+        .collect()
+}
+
+/// `Authorization` header of an AWS Signature Version 4 signed POST to `/`
+///
+/// `headers` are the lowercase names and values to sign; they must include `host`.
+fn sigv4_authorization(
+    access_key_id: &str,
+    secret_access_key: &str,
+    region: &str,
+    service: &str,
+    amz_date: &str,
+    headers: &[(&str, String)],
+    body: &str,
+) -> String {
+    let date = &amz_date[..8];
+    let mut headers = headers.to_vec();
+    headers.sort_by(|a, b| a.0.cmp(b.0));
+
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+        .collect();
+    let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+    let canonical_request = format!(
+        "POST\n/\n\n{}\n{}\n{}",
+        canonical_headers,
+        signed_headers,
+        hex::encode(Sha256::digest(body.as_bytes()))
+    );
+
+    let scope = format!("{}/{}/{}/aws4_request", date, region, service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let signing_key = sigv4_signing_key(secret_access_key, date, region, service);
+    let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key_id, scope, signed_headers, signature
+    )
+}
+
+/// Key derived from the secret for one day, region and service
+fn sigv4_signing_key(secret_access_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(format!("AWS4{}", secret_access_key).as_bytes(), date.as_bytes());
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_e164() {
+        assert_eq!(normalize_e164("+84 912-345-678").as_deref(), Some("+84912345678"));
+        assert_eq!(normalize_e164("0084 (912) 345 678").as_deref(), Some("+84912345678"));
+        assert_eq!(normalize_e164("0912345678"), None);
+        assert_eq!(normalize_e164("+0912345678"), None);
+        assert_eq!(normalize_e164("+1234"), None);
+        assert_eq!(normalize_e164("+1234567890123456"), None);
+    }
+
+    #[test]
+    fn test_mask_phone_keeps_last_three_digits() {
+        assert_eq!(mask_phone("+84912345678"), "+********678");
+        assert_eq!(mask_phone("12"), "12");
+    }
+
+    #[test]
+    fn test_sigv4_signing_key_matches_aws_example() {
+        // Example from the AWS Signature Version 4 documentation
+        let key = sigv4_signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_provider_selection() {
+        let twilio = TwilioConfig {
+            account_sid: "AC123".to_string(),
+            auth_token: "token".to_string(),
+            from_number: "+15005550006".to_string(),
+            endpoint: "https://api.twilio.com".to_string(),
+        };
+        let sns = SnsConfig {
+            region: "us-east-1".to_string(),
+            access_key_id: "AKID".to_string(),
+            secret_access_key: "secret".to_string(),
+            session_token: None,
+            sender_id: None,
+            endpoint: "https://sns.us-east-1.amazonaws.com".to_string(),
+        };
+        let service = |provider: Option<&str>, twilio: Option<TwilioConfig>, sns: Option<SnsConfig>| {
+            SmsService::from_config(SmsConfig { twilio, sns, provider: provider.map(str::to_string) }).provider_name()
+        };
+
+        assert_eq!(service(None, None, None), "log");
+        assert_eq!(service(None, Some(twilio.clone()), Some(sns.clone())), "twilio");
+        assert_eq!(service(None, None, Some(sns.clone())), "sns");
+        assert_eq!(service(Some("sns"), Some(twilio.clone()), Some(sns)), "sns");
+        // A named provider without credentials falls back to logging
+        assert_eq!(service(Some("sns"), Some(twilio), None), "log");
+    }
+}
@@ -0,0 +1,184 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::TokenIssuance;
+use crate::repositories::token_lineage::NewTokenIssuance;
+use crate::repositories::TokenLineageRepository;
+use crate::utils::jwt::{IssuedToken, TokenPair};
+
+/// Reason recorded for a refresh token replaced on refresh
+pub const REVOKED_ROTATED: &str = "rotated";
+/// Reason recorded for the access token presented on logout
+pub const REVOKED_LOGOUT: &str = "logout";
+/// Reason recorded for the tokens of a revoked session
+pub const REVOKED_SESSION: &str = "session_revoked";
+
+/// Every token issued from one login, in issue order
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenLineage {
+    /// Token the lineage was requested for
+    pub jti: String,
+    pub family_id: String,
+    pub user_id: Uuid,
+    pub tokens: Vec<TokenIssuance>,
+}
+
+fn expires_at(token: &IssuedToken) -> DateTime<Utc> {
+    DateTime::from_timestamp(token.exp, 0).unwrap_or_else(Utc::now)
+}
+
+/// Whether an access token issuance is recorded, given a uniform roll in [0, 1)
+fn sampled(rate: f64, roll: f64) -> bool {
+    roll < rate
+}
+
+/// Records issued user tokens so they can be traced from the login through
+/// refreshes to their revocation
+///
+/// Refresh tokens are always recorded, since they link the chain; access
+/// tokens are recorded for a configurable fraction of issuances.
+#[derive(Clone)]
+pub struct TokenLineageService {
+    repo: TokenLineageRepository,
+    access_sample_rate: f64,
+}
+
+impl TokenLineageService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self {
+            repo: TokenLineageRepository::new(pool),
+            access_sample_rate: 1.0,
+        }
+    }
+
+    /// Record only this fraction (0.0 - 1.0) of access token issuances
+    pub fn with_access_sample_rate(mut self, rate: f64) -> Self {
+        self.access_sample_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Record a session token pair
+    ///
+    /// `parent_jti` is the refresh token presented to obtain the pair; both
+    /// tokens join its family. A pair issued at login starts a new family
+    /// named after its refresh token.
+    pub async fn record_pair(
+        &self,
+        pair: &TokenPair,
+        user_id: Uuid,
+        session_id: Option<Uuid>,
+        parent_jti: Option<&str>,
+    ) -> Result<(), AuthError> {
+        let Some(refresh) = &pair.refresh else {
+            return Ok(());
+        };
+
+        let family_id = match parent_jti {
+            // Parents issued before lineage was recorded start the family themselves
+            Some(parent) => self
+                .repo
+                .find_by_jti(parent)
+                .await?
+                .map(|t| t.family_id)
+                .unwrap_or_else(|| parent.to_string()),
+            None => refresh.jti.clone(),
+        };
+
+        let refresh_issuance = NewTokenIssuance {
+            jti: &refresh.jti,
+            family_id: &family_id,
+            parent_jti,
+            token_type: "refresh",
+            user_id,
+            session_id,
+            app: refresh.app.as_deref(),
+            expires_at: expires_at(refresh),
+        };
+        self.repo.record(&refresh_issuance).await?;
+
+        if let Some(access) = &pair.access {
+            if sampled(self.access_sample_rate, rand::random::<f64>()) {
+                self.repo
+                    .record(&NewTokenIssuance {
+                        jti: &access.jti,
+                        token_type: "access",
+                        app: access.app.as_deref(),
+                        expires_at: expires_at(access),
+                        ..refresh_issuance
+                    })
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Mark a recorded token revoked
+    pub async fn revoke(&self, jti: &str, reason: &str) -> Result<(), AuthError> {
+        self.repo.revoke(jti, reason).await
+    }
+
+    /// Session a token was issued in
+    ///
+    /// Tokens issued outside a session fall back to the latest session of
+    /// their family, which is where a refresh moved them.
+    pub async fn session_of(&self, jti: &str) -> Result<Option<Uuid>, AuthError> {
+        let family_id = match self.repo.find_by_jti(jti).await? {
+            Some(TokenIssuance { session_id: Some(session_id), .. }) => return Ok(Some(session_id)),
+            Some(token) => token.family_id,
+            // Parents issued before lineage was recorded start the family themselves
+            None => jti.to_string(),
+        };
+
+        let tokens = self.repo.list_family(&family_id).await?;
+        Ok(tokens.iter().rev().find_map(|t| t.session_id))
+    }
+
+    /// Whether a recorded token has been revoked
+    pub async fn is_revoked(&self, jti: &str) -> Result<bool, AuthError> {
+        Ok(self
+            .repo
+            .find_by_jti(jti)
+            .await?
+            .is_some_and(|token| token.revoked_at.is_some()))
+    }
+
+    /// Every recorded token of the family a token belongs to
+    pub async fn lineage(&self, jti: &str) -> Result<Option<TokenLineage>, AuthError> {
+        let Some(token) = self.repo.find_by_jti(jti).await? else {
+            return Ok(None);
+        };
+
+        let tokens = self.repo.list_family(&token.family_id).await?;
+
+        Ok(Some(TokenLineage {
+            jti: token.jti,
+            family_id: token.family_id,
+            user_id: token.user_id,
+            tokens,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_rate_bounds() {
+        assert!(sampled(1.0, 0.0));
+        assert!(sampled(1.0, 0.999_999));
+        assert!(!sampled(0.0, 0.0));
+        assert!(!sampled(0.0, 0.5));
+    }
+
+    #[test]
+    fn test_partial_sample_rate() {
+        assert!(sampled(0.25, 0.1));
+        assert!(!sampled(0.25, 0.25));
+        assert!(!sampled(0.25, 0.9));
+    }
+}
@@ -3,19 +3,22 @@ use sqlx::MySqlPool;
 use uuid::Uuid;
 
 use crate::error::AuthError;
-use crate::repositories::RevokedTokenRepository;
+use crate::repositories::{RevokedTokenRepository, SessionRepository};
+use crate::services::token_lineage::REVOKED_ROTATED;
 use crate::utils::password::hash_token;
 
 /// Service for token revocation (logout, token blacklisting)
 #[derive(Clone)]
 pub struct TokenRevocationService {
     repo: RevokedTokenRepository,
+    session_repo: SessionRepository,
 }
 
 impl TokenRevocationService {
     pub fn new(pool: MySqlPool) -> Self {
         Self {
-            repo: RevokedTokenRepository::new(pool),
+            repo: RevokedTokenRepository::new(pool.clone()),
+            session_repo: SessionRepository::new(pool),
         }
     }
 
@@ -51,19 +54,80 @@ impl TokenRevocationService {
             .await
     }
 
+    /// Revoke a refresh token unless it is already revoked
+    ///
+    /// Returns `false` when it was, e.g. by a concurrent refresh of it.
+    pub async fn revoke_refresh_token_once(
+        &self,
+        token: &str,
+        user_id: Option<Uuid>,
+        expires_in_secs: i64,
+        reason: Option<&str>,
+    ) -> Result<bool, AuthError> {
+        let token_hash = hash_token(token)?;
+        let expires_at = Utc::now() + Duration::seconds(expires_in_secs);
+
+        self.repo
+            .revoke_once(&token_hash, "refresh", user_id, expires_at, reason)
+            .await
+    }
+
     /// Check if an access token is revoked
     pub async fn is_access_token_revoked(&self, token: &str) -> Result<bool, AuthError> {
         let token_hash = hash_token(token)?;
         self.repo.is_revoked(&token_hash).await
     }
 
+    /// Check if a user's access token is revoked, on its own or because all
+    /// of the user's tokens were revoked after it was issued
+    pub async fn is_user_access_token_revoked(
+        &self,
+        token: &str,
+        user_id: Uuid,
+        issued_at: i64,
+    ) -> Result<bool, AuthError> {
+        let token_hash = hash_token(token)?;
+        self.repo.is_revoked_for_user(&token_hash, user_id, issued_at).await
+    }
+
+    /// Check if the session a token was issued in is revoked
+    ///
+    /// Revoking a session (logout, revoking other sessions, a replayed
+    /// refresh token) thereby also ends the access tokens issued in it.
+    pub async fn is_session_revoked(&self, session_id: Uuid) -> Result<bool, AuthError> {
+        self.session_repo.is_revoked(session_id).await
+    }
+
     /// Check if a refresh token is revoked
     pub async fn is_refresh_token_revoked(&self, token: &str) -> Result<bool, AuthError> {
         let token_hash = hash_token(token)?;
         self.repo.is_revoked(&token_hash).await
     }
 
+    /// Check if a user's refresh token is revoked, on its own or because all
+    /// of the user's tokens were revoked after it was issued
+    pub async fn is_user_refresh_token_revoked(
+        &self,
+        token: &str,
+        user_id: Uuid,
+        issued_at: i64,
+    ) -> Result<bool, AuthError> {
+        let token_hash = hash_token(token)?;
+        self.repo.is_revoked_for_user(&token_hash, user_id, issued_at).await
+    }
+
+    /// Check if a refresh token was replaced on refresh
+    ///
+    /// Such a token comes back only when a copy of it is replayed.
+    pub async fn is_refresh_token_rotated(&self, token: &str) -> Result<bool, AuthError> {
+        let token_hash = hash_token(token)?;
+        self.repo.is_revoked_for_reason(&token_hash, REVOKED_ROTATED).await
+    }
+
     /// Revoke all tokens for a user (force logout everywhere)
+    ///
+    /// Access tokens issued up to now are refused by `jwt_auth_middleware`;
+    /// the marker is kept for `expires_in_secs`, the longest they can live.
     pub async fn revoke_all_user_tokens(
         &self,
         user_id: Uuid,
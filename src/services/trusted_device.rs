@@ -0,0 +1,110 @@
+use chrono::{Duration, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::models::TrustedDevice;
+use crate::repositories::TrustedDeviceRepository;
+use crate::utils::secret::{constant_time_compare, generate_oauth_token, hash_oauth_token};
+
+/// Service for devices that skip MFA after completing it once
+///
+/// The device keeps `<device id>.<secret>` in an HttpOnly cookie; only the
+/// hash of the secret is stored, so a leaked table can't be replayed.
+#[derive(Clone)]
+pub struct TrustedDeviceService {
+    repo: TrustedDeviceRepository,
+}
+
+impl TrustedDeviceService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self {
+            repo: TrustedDeviceRepository::new(pool),
+        }
+    }
+
+    /// Trust a device of the user for `days`
+    ///
+    /// Returns the device and the cookie value to hand to it.
+    pub async fn trust(
+        &self,
+        user_id: Uuid,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+        days: i64,
+    ) -> Result<(TrustedDevice, String), AuthError> {
+        let secret = generate_oauth_token();
+        let expires_at = Utc::now() + Duration::days(days);
+
+        let device = self
+            .repo
+            .create(user_id, &hash_oauth_token(&secret), ip_address, user_agent, expires_at)
+            .await?;
+        let cookie_value = format!("{}.{}", device.id, secret);
+
+        Ok((device, cookie_value))
+    }
+
+    /// The user's unexpired device a cookie value belongs to, if any
+    ///
+    /// A match is recorded as the device's last use.
+    pub async fn verify(&self, user_id: Uuid, cookie_value: &str) -> Result<Option<TrustedDevice>, AuthError> {
+        let Some((device_id, secret)) = parse_cookie_value(cookie_value) else {
+            return Ok(None);
+        };
+
+        let device = self
+            .repo
+            .find_by_id(device_id)
+            .await?
+            .filter(|device| device.user_id == user_id && !device.is_expired())
+            .filter(|device| constant_time_compare(&hash_oauth_token(secret), &device.token_hash));
+
+        if let Some(device) = &device {
+            self.repo.touch(device.id).await?;
+        }
+
+        Ok(device)
+    }
+
+    /// Unexpired trusted devices of a user
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<TrustedDevice>, AuthError> {
+        self.repo.list_by_user(user_id).await
+    }
+
+    /// Stop trusting a device; its next sign-in asks for MFA again
+    pub async fn revoke(&self, user_id: Uuid, device_id: Uuid) -> Result<(), AuthError> {
+        if !self.repo.delete(user_id, device_id).await? {
+            return Err(AuthError::SessionNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Stop trusting every device of a user
+    pub async fn revoke_all(&self, user_id: Uuid) -> Result<u64, AuthError> {
+        self.repo.delete_all_for_user(user_id).await
+    }
+}
+
+/// Split a cookie value into the device ID and its secret
+fn parse_cookie_value(value: &str) -> Option<(Uuid, &str)> {
+    let (device_id, secret) = value.split_once('.')?;
+    let device_id = Uuid::parse_str(device_id).ok()?;
+    (!secret.is_empty()).then_some((device_id, secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cookie_value() {
+        let id = Uuid::new_v4();
+
+        assert_eq!(parse_cookie_value(&format!("{}.s3cret", id)), Some((id, "s3cret")));
+        assert_eq!(parse_cookie_value(&format!("{}.", id)), None);
+        assert_eq!(parse_cookie_value("not-a-uuid.s3cret"), None);
+        assert_eq!(parse_cookie_value("s3cret"), None);
+    }
+}
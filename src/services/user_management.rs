@@ -1,12 +1,14 @@
 use sqlx::MySqlPool;
 use uuid::Uuid;
 
-use crate::dto::user_management::{AppUserInfo, PaginatedResponse};
+use crate::dto::user_management::{AppUserInfo, AppUserListQuery, PaginatedResponse, MAX_PAGE_LIMIT};
 use crate::error::UserManagementError;
 use crate::models::user_app::{UserApp, UserAppStatus};
-use crate::models::WebhookEvent;
-use crate::repositories::{AppRepository, RoleRepository, UserAppRepository, UserAppRoleRepository, UserRepository, WebhookRepository};
+use crate::models::{RoleAssignmentHistory, RoleChangeActor, WebhookEvent, ROLE_HISTORY_REMOVED};
+use crate::repositories::user_app::{app_user_sort_column, AppUserFilter};
+use crate::repositories::{AppRepository, RoleHistoryRepository, RoleRepository, UserAppRepository, UserAppRoleRepository, UserRepository, WebhookRepository};
 use crate::services::WebhookService;
+use crate::utils::request_id::spawn_in_request;
 
 /// Service for user management within apps
 /// 
@@ -20,6 +22,7 @@ pub struct UserManagementService {
     user_app_repo: UserAppRepository,
     user_app_role_repo: UserAppRoleRepository,
     role_repo: RoleRepository,
+    role_history_repo: RoleHistoryRepository,
     webhook_service: WebhookService,
 }
 
@@ -33,6 +36,7 @@ impl UserManagementService {
             user_app_repo: UserAppRepository::new(pool.clone()),
             user_app_role_repo: UserAppRoleRepository::new(pool.clone()),
             role_repo: RoleRepository::new(pool.clone()),
+            role_history_repo: RoleHistoryRepository::new(pool.clone()),
             webhook_service: WebhookService::new(pool),
         }
     }
@@ -143,7 +147,7 @@ impl UserManagementService {
             "status": "active",
             "timestamp": chrono::Utc::now().to_rfc3339()
         });
-        tokio::spawn(async move {
+        spawn_in_request(async move {
             let _ = webhook_service.trigger_event(app_id, WebhookEvent::UserAppJoined, payload).await;
         });
 
@@ -212,7 +216,7 @@ impl UserManagementService {
                     "reason": reason,
                     "timestamp": chrono::Utc::now().to_rfc3339()
                 });
-                tokio::spawn(async move {
+                spawn_in_request(async move {
                     let _ = webhook_service.trigger_event(app_id, WebhookEvent::UserAppBanned, payload).await;
                 });
 
@@ -234,7 +238,7 @@ impl UserManagementService {
                     "pre_registered": false,
                     "timestamp": chrono::Utc::now().to_rfc3339()
                 });
-                tokio::spawn(async move {
+                spawn_in_request(async move {
                     let _ = webhook_service.trigger_event(app_id, WebhookEvent::UserAppBanned, payload).await;
                 });
 
@@ -299,7 +303,7 @@ impl UserManagementService {
                         "unbanned_by": actor_id.to_string(),
                         "timestamp": chrono::Utc::now().to_rfc3339()
                     });
-                    tokio::spawn(async move {
+                    spawn_in_request(async move {
                         let _ = webhook_service.trigger_event(app_id, WebhookEvent::UserAppUnbanned, payload).await;
                     });
 
@@ -345,13 +349,43 @@ impl UserManagementService {
         // Requirements: 5.2
         self.check_permission(actor_id, app_id).await?;
 
+        self.remove_membership(user_id, app_id, RoleChangeActor::user(actor_id)).await
+    }
+
+    /// Remove a user from an app (without actor permission check)
+    /// Used by API Key authentication where permission is checked via scopes
+    pub async fn remove_user_by_api_key(
+        &self,
+        app_id: Uuid,
+        user_id: Uuid,
+        api_key_id: Option<Uuid>,
+    ) -> Result<(), UserManagementError> {
+        let actor = api_key_id.map(RoleChangeActor::api_key).unwrap_or_else(RoleChangeActor::system);
+        self.remove_membership(user_id, app_id, actor).await
+    }
+
+    /// Delete the user's roles and membership in the app; idempotent
+    async fn remove_membership(
+        &self,
+        user_id: Uuid,
+        app_id: Uuid,
+        actor: RoleChangeActor,
+    ) -> Result<(), UserManagementError> {
         // Check if user was registered (for webhook)
         let was_registered = self.user_app_repo.find(user_id, app_id).await?.is_some();
 
-        // Delete user_app_roles for this user in this app
+        // Delete user_app_roles for this user in this app, recording each removal
         // Requirements: 5.1
+        let assigned = self.user_app_role_repo.find_by_user_and_app(user_id, app_id).await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?;
         self.user_app_role_repo.delete_by_user_and_app(user_id, app_id).await
             .map_err(|e| UserManagementError::InternalError(e.into()))?;
+        for uar in assigned {
+            self.role_history_repo
+                .record(app_id, user_id, uar.role_id, ROLE_HISTORY_REMOVED, actor, None)
+                .await
+                .map_err(|e| UserManagementError::InternalError(e.into()))?;
+        }
 
         // Delete user_app association
         // Requirements: 5.1, 5.3 (idempotent - delete succeeds even if not exists)
@@ -364,10 +398,11 @@ impl UserManagementService {
                 "event": "user.app.removed",
                 "user_id": user_id.to_string(),
                 "app_id": app_id.to_string(),
-                "removed_by": actor_id.to_string(),
+                "removed_by": actor.actor_id.map(|id| id.to_string()),
+                "via_api_key": actor.actor_type == "api_key",
                 "timestamp": chrono::Utc::now().to_rfc3339()
             });
-            tokio::spawn(async move {
+            spawn_in_request(async move {
                 let _ = webhook_service.trigger_event(app_id, WebhookEvent::UserAppRemoved, payload).await;
             });
         }
@@ -385,11 +420,11 @@ impl UserManagementService {
     /// # Arguments
     /// * `actor_id` - The user requesting the list (must be owner or admin)
     /// * `app_id` - The app to list users for
-    /// * `page` - Page number (1-indexed)
-    /// * `limit` - Number of items per page
+    /// * `query` - Filters, sorting and pagination
     /// 
     /// # Returns
     /// * `Ok(PaginatedResponse<AppUserInfo>)` - Paginated list of users
+    /// * `Err(UserManagementError::ValidationError)` - If a filter or sort option is invalid
     /// * `Err(UserManagementError::NotAppOwner)` - If actor lacks permission
     /// * `Err(UserManagementError::AppNotFound)` - If app doesn't exist
     /// 
@@ -402,18 +437,22 @@ impl UserManagementService {
         &self,
         actor_id: Uuid,
         app_id: Uuid,
-        page: u32,
-        limit: u32,
+        query: &AppUserListQuery,
     ) -> Result<PaginatedResponse<AppUserInfo>, UserManagementError> {
+        let filter = Self::app_user_filter(query)?;
+        let (page, limit) = query.pagination().normalized();
+
         // Check permission (owner or admin)
         // Requirements: 6.3
         self.check_permission(actor_id, app_id).await?;
 
         // Get total count for pagination
-        let total = self.user_app_repo.count_by_app(app_id).await?;
+        let total = self.user_app_repo.count_search_by_app(app_id, &filter).await?;
 
         // Get user_apps for this page
-        let user_apps = self.user_app_repo.list_by_app(app_id, page, limit).await?;
+        let user_apps = self.user_app_repo
+            .search_by_app(app_id, &filter, &query.sort_by, &query.sort_order, page, limit)
+            .await?;
 
         // Build AppUserInfo for each user_app
         let mut app_users = Vec::with_capacity(user_apps.len());
@@ -440,7 +479,76 @@ impl UserManagementService {
             });
         }
 
-        Ok(PaginatedResponse::new(app_users, page, limit, total))
+        Ok(PaginatedResponse::new(app_users, page, limit, total)
+            .with_sort(&query.sort_by, &query.sort_order)
+            .with_filters(Self::filter_echo(&filter)))
+    }
+
+    /// Get the role grant/removal history of a user in an app, newest first
+    ///
+    /// Actor must be the app owner or a system admin.
+    pub async fn get_role_history(
+        &self,
+        actor_id: Uuid,
+        app_id: Uuid,
+        user_id: Uuid,
+        page: u32,
+        limit: u32,
+    ) -> Result<PaginatedResponse<RoleAssignmentHistory>, UserManagementError> {
+        self.check_permission(actor_id, app_id).await?;
+
+        let page = page.max(1);
+        let limit = limit.clamp(1, MAX_PAGE_LIMIT);
+
+        let total = self.role_history_repo.count_for_user(app_id, user_id).await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?;
+        let entries = self.role_history_repo.list_for_user(app_id, user_id, page, limit).await
+            .map_err(|e| UserManagementError::InternalError(e.into()))?;
+
+        Ok(PaginatedResponse::new(entries, page, limit, total))
+    }
+
+    /// Applied filters as echoed in list responses
+    fn filter_echo(filter: &AppUserFilter<'_>) -> serde_json::Value {
+        serde_json::json!({
+            "status": filter.status,
+            "role_id": filter.role_id,
+            "email": filter.email,
+            "registered_from": filter.registered_from,
+            "registered_to": filter.registered_to,
+        })
+    }
+
+    /// Validate list filters and sort options
+    fn app_user_filter(query: &AppUserListQuery) -> Result<AppUserFilter<'_>, UserManagementError> {
+        let status = query
+            .status
+            .as_deref()
+            .map(|s| s.parse::<UserAppStatus>())
+            .transpose()
+            .map_err(UserManagementError::ValidationError)?;
+
+        app_user_sort_column(&query.sort_by)?;
+        if !matches!(query.sort_order.to_lowercase().as_str(), "asc" | "desc") {
+            return Err(UserManagementError::ValidationError(format!(
+                "Invalid sort_order: {}", query.sort_order
+            )));
+        }
+        if let (Some(from), Some(to)) = (query.registered_from, query.registered_to) {
+            if from >= to {
+                return Err(UserManagementError::ValidationError(
+                    "registered_from must be before registered_to".into(),
+                ));
+            }
+        }
+
+        Ok(AppUserFilter {
+            status,
+            role_id: query.role_id,
+            email: query.email.as_deref().map(str::trim).filter(|e| !e.is_empty()),
+            registered_from: query.registered_from,
+            registered_to: query.registered_to,
+        })
     }
 
     /// List all users registered to an app with pagination (without permission check)
@@ -448,14 +556,18 @@ impl UserManagementService {
     pub async fn list_app_users_by_api_key(
         &self,
         app_id: Uuid,
-        page: u32,
-        limit: u32,
-    ) -> Result<(Vec<crate::dto::UserAppResponse>, i64), UserManagementError> {
+        query: &AppUserListQuery,
+    ) -> Result<PaginatedResponse<crate::dto::UserAppResponse>, UserManagementError> {
+        let filter = Self::app_user_filter(query)?;
+        let (page, limit) = query.pagination().normalized();
+
         // Get total count for pagination
-        let total = self.user_app_repo.count_by_app(app_id).await?;
+        let total = self.user_app_repo.count_search_by_app(app_id, &filter).await?;
 
         // Get user_apps for this page
-        let user_apps = self.user_app_repo.list_by_app(app_id, page, limit).await?;
+        let user_apps = self.user_app_repo
+            .search_by_app(app_id, &filter, &query.sort_by, &query.sort_order, page, limit)
+            .await?;
 
         // Build response for each user_app
         let mut users = Vec::with_capacity(user_apps.len());
@@ -482,7 +594,9 @@ impl UserManagementService {
             });
         }
 
-        Ok((users, total as i64))
+        Ok(PaginatedResponse::new(users, page, limit, total)
+            .with_sort(&query.sort_by, &query.sort_order)
+            .with_filters(Self::filter_echo(&filter)))
     }
 
     /// Get a specific user in an app (without permission check)
@@ -555,7 +669,7 @@ impl UserManagementService {
             "via_api_key": true,
             "timestamp": chrono::Utc::now().to_rfc3339()
         });
-        tokio::spawn(async move {
+        spawn_in_request(async move {
             let _ = webhook_service.trigger_event(app_id, WebhookEvent::UserAppBanned, payload).await;
         });
 
@@ -588,7 +702,7 @@ impl UserManagementService {
                         "via_api_key": true,
                         "timestamp": chrono::Utc::now().to_rfc3339()
                     });
-                    tokio::spawn(async move {
+                    spawn_in_request(async move {
                         let _ = webhook_service.trigger_event(app_id, WebhookEvent::UserAppUnbanned, payload).await;
                     });
 
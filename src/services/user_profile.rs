@@ -5,12 +5,15 @@ use uuid::Uuid;
 use crate::dto::auth::{ChangePasswordRequest, UpdateProfileRequest, UserProfileResponse};
 use crate::dto::user_management::{
     BulkImportResponse, BulkOperationError, BulkOperationResponse, BulkRoleAssignmentRequest,
-    ImportError, PaginatedResponse, UserExportData, UserImportRequest, UserSearchQuery,
+    ImportError, PaginatedResponse, MAX_PAGE_LIMIT, UserExportData, UserImportRequest, UserSearchQuery,
     UserSearchResult,
 };
 use crate::error::AuthError;
-use crate::repositories::UserRepository;
+use crate::models::{RoleChangeActor, ROLE_HISTORY_GRANTED};
+use crate::repositories::{EmailBounceRepository, RoleHistoryRepository, UserAddressRepository, UserRepository};
 use crate::utils::password::{hash_password, verify_password};
+use crate::utils::registration_fields::RegistrationSchema;
+use crate::utils::userinfo_claims::normalize_locale;
 
 /// Email verification token expiry in hours
 const EMAIL_VERIFICATION_TOKEN_EXPIRY_HOURS: i64 = 24;
@@ -20,6 +23,7 @@ const EMAIL_VERIFICATION_TOKEN_EXPIRY_HOURS: i64 = 24;
 pub struct UserProfileService {
     pool: MySqlPool,
     user_repo: UserRepository,
+    registration_fields: RegistrationSchema,
 }
 
 impl UserProfileService {
@@ -27,9 +31,16 @@ impl UserProfileService {
         Self {
             user_repo: UserRepository::new(pool.clone()),
             pool,
+            registration_fields: RegistrationSchema::default(),
         }
     }
 
+    /// Extra registration fields admins may search users by
+    pub fn with_registration_fields(mut self, registration_fields: RegistrationSchema) -> Self {
+        self.registration_fields = registration_fields;
+        self
+    }
+
     /// Get current user's profile
     pub async fn get_profile(&self, user_id: Uuid) -> Result<UserProfileResponse, AuthError> {
         let user = self
@@ -37,6 +48,12 @@ impl UserProfileService {
             .find_by_id(user_id)
             .await?
             .ok_or(AuthError::UserNotFound)?;
+        let address = UserAddressRepository::new(self.pool.clone())
+            .find_by_user(user_id)
+            .await?;
+        let email_undeliverable = EmailBounceRepository::new(self.pool.clone())
+            .is_bounced(&user.email)
+            .await?;
 
         Ok(UserProfileResponse {
             id: user.id,
@@ -44,8 +61,11 @@ impl UserProfileService {
             name: user.name,
             avatar_url: user.avatar_url,
             phone: user.phone,
+            locale: user.locale,
+            address,
             is_active: user.is_active,
             email_verified: user.email_verified,
+            email_undeliverable,
             is_system_admin: user.is_system_admin,
             created_at: user.created_at,
             updated_at: user.updated_at,
@@ -58,9 +78,24 @@ impl UserProfileService {
         user_id: Uuid,
         req: UpdateProfileRequest,
     ) -> Result<UserProfileResponse, AuthError> {
+        let locale = req
+            .locale
+            .as_deref()
+            .map(normalize_locale)
+            .transpose()
+            .map_err(AuthError::InvalidRequest)?;
         let user = self
             .user_repo
-            .update_profile(user_id, req.name, req.avatar_url, req.phone)
+            .update_profile(user_id, req.name, req.avatar_url, req.phone, locale)
+            .await?;
+
+        let address_repo = UserAddressRepository::new(self.pool.clone());
+        if let Some(address) = &req.address {
+            address_repo.set(user_id, address).await?;
+        }
+        let address = address_repo.find_by_user(user_id).await?;
+        let email_undeliverable = EmailBounceRepository::new(self.pool.clone())
+            .is_bounced(&user.email)
             .await?;
 
         Ok(UserProfileResponse {
@@ -69,8 +104,11 @@ impl UserProfileService {
             name: user.name,
             avatar_url: user.avatar_url,
             phone: user.phone,
+            locale: user.locale,
+            address,
             is_active: user.is_active,
             email_verified: user.email_verified,
+            email_undeliverable,
             is_system_admin: user.is_system_admin,
             created_at: user.created_at,
             updated_at: user.updated_at,
@@ -211,6 +249,27 @@ impl UserProfileService {
         &self,
         query: UserSearchQuery,
     ) -> Result<PaginatedResponse<UserSearchResult>, AuthError> {
+        let page = query.page.max(1);
+        let limit = query.limit.clamp(1, MAX_PAGE_LIMIT);
+        // Mirror the repository's whitelist so the echoed sort is the one applied
+        let sort_by = match query.sort_by.as_str() {
+            "email" | "name" => query.sort_by.as_str(),
+            _ => "created_at",
+        };
+        let sort_order = if query.sort_order.eq_ignore_ascii_case("asc") { "asc" } else { "desc" };
+        let metadata = match (query.metadata_field.as_deref(), query.metadata_value.as_deref()) {
+            (Some(field), Some(value)) if self.registration_fields.contains(field) => Some((field, value)),
+            (Some(field), Some(_)) => {
+                return Err(AuthError::InvalidRequest(format!("Unknown registration field '{}'", field)));
+            }
+            (None, None) => None,
+            _ => {
+                return Err(AuthError::InvalidRequest(
+                    "metadata_field and metadata_value must be given together".to_string(),
+                ));
+            }
+        };
+
         let users = self
             .user_repo
             .search(
@@ -219,10 +278,11 @@ impl UserProfileService {
                 query.is_active,
                 query.email_verified,
                 query.is_system_admin,
-                &query.sort_by,
-                &query.sort_order,
-                query.page,
-                query.limit,
+                metadata,
+                sort_by,
+                sort_order,
+                page,
+                limit,
             )
             .await?;
 
@@ -234,6 +294,7 @@ impl UserProfileService {
                 query.is_active,
                 query.email_verified,
                 query.is_system_admin,
+                metadata,
             )
             .await?;
 
@@ -246,11 +307,22 @@ impl UserProfileService {
                 is_active: u.is_active,
                 email_verified: u.email_verified,
                 is_system_admin: u.is_system_admin,
+                metadata: u.metadata,
                 created_at: u.created_at,
             })
             .collect();
 
-        Ok(PaginatedResponse::new(results, query.page, query.limit, total))
+        Ok(PaginatedResponse::new(results, page, limit, total)
+            .with_sort(sort_by, sort_order)
+            .with_filters(serde_json::json!({
+                "email": query.email,
+                "name": query.name,
+                "is_active": query.is_active,
+                "email_verified": query.email_verified,
+                "is_system_admin": query.is_system_admin,
+                "metadata_field": query.metadata_field,
+                "metadata_value": query.metadata_value,
+            })))
     }
 
     /// Export users (admin only)
@@ -274,6 +346,7 @@ impl UserProfileService {
                     is_active: u.is_active,
                     email_verified: u.email_verified,
                     is_system_admin: u.is_system_admin,
+                    metadata: u.metadata,
                     created_at: u.created_at,
                 });
             }
@@ -319,6 +392,17 @@ impl UserProfileService {
                 }
             };
 
+            // Refuse look-alike (homoglyph) variants of existing accounts
+            if let Ok(Some(_)) = self.user_repo.find_confusable(&user_req.email).await {
+                failed_count += 1;
+                errors.push(ImportError {
+                    row: idx as u32 + 1,
+                    email: user_req.email,
+                    error: "Email is confusable with an existing account".to_string(),
+                });
+                continue;
+            }
+
             // Create user
             match self
                 .user_repo
@@ -358,10 +442,15 @@ impl UserProfileService {
     }
 
     /// Bulk assign role to users (admin only)
+    ///
+    /// New assignments are recorded in the role history with `actor_id` as the granter.
     pub async fn bulk_assign_role(
         &self,
+        actor_id: Uuid,
         req: BulkRoleAssignmentRequest,
     ) -> Result<BulkOperationResponse, AuthError> {
+        let role_history_repo = RoleHistoryRepository::new(self.pool.clone());
+
         let mut success_count = 0u32;
         let mut failed_count = 0u32;
         let mut errors = Vec::new();
@@ -392,7 +481,15 @@ impl UserProfileService {
             .await;
 
             match result {
-                Ok(_) => success_count += 1,
+                Ok(done) => {
+                    success_count += 1;
+                    if done.rows_affected() > 0 {
+                        role_history_repo
+                            .record(req.app_id, user_id, req.role_id, ROLE_HISTORY_GRANTED, RoleChangeActor::user(actor_id), None)
+                            .await
+                            .map_err(|e| AuthError::InternalError(e.into()))?;
+                    }
+                }
                 Err(e) => {
                     failed_count += 1;
                     errors.push(BulkOperationError {
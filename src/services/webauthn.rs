@@ -7,9 +7,22 @@ use serde::{Deserialize, Serialize};
 use crate::error::AppError;
 use crate::models::{WebAuthnCredential, ChallengeType};
 use crate::repositories::WebAuthnRepository;
+use crate::services::challenge_store::{ChallengeStore, CHALLENGE_KIND_WEBAUTHN};
+
+/// How long a registration or authentication challenge can be answered
+const CHALLENGE_TTL_SECS: i64 = 300;
+
+/// Challenge kept in the challenge store until the ceremony finishes
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredChallenge {
+    /// User the ceremony was started for (none for usernameless sign-in)
+    user_id: Option<Uuid>,
+    challenge_type: ChallengeType,
+}
 
 pub struct WebAuthnService {
     repo: WebAuthnRepository,
+    challenges: ChallengeStore,
     rp_id: String,
     rp_name: String,
     rp_origin: String,
@@ -103,15 +116,49 @@ pub struct AuthenticatorAssertionResponse {
 }
 
 impl WebAuthnService {
-    pub fn new(pool: MySqlPool, rp_id: String, rp_name: String, rp_origin: String) -> Self {
+    pub fn new(
+        pool: MySqlPool,
+        challenges: ChallengeStore,
+        rp_id: String,
+        rp_name: String,
+        rp_origin: String,
+    ) -> Self {
         Self {
             repo: WebAuthnRepository::new(pool),
+            challenges,
             rp_id,
             rp_name,
             rp_origin,
         }
     }
 
+    /// Keep a challenge, under its base64url encoding, until it is answered
+    async fn store_challenge(
+        &self,
+        challenge: &str,
+        user_id: Option<Uuid>,
+        challenge_type: ChallengeType,
+    ) -> Result<(), AppError> {
+        let stored = serde_json::to_value(StoredChallenge { user_id, challenge_type })
+            .map_err(|e| AppError::InternalError(e.into()))?;
+        self.challenges
+            .put(CHALLENGE_KIND_WEBAUTHN, challenge, &stored, CHALLENGE_TTL_SECS)
+            .await
+    }
+
+    /// Use up the challenge the client data answers
+    async fn take_challenge(&self, challenge_b64: &str) -> Result<StoredChallenge, AppError> {
+        let challenge = URL_SAFE_NO_PAD.decode(challenge_b64)
+            .map_err(|_| AppError::ValidationError("Invalid challenge encoding".into()))?;
+
+        let stored = self.challenges
+            .take(CHALLENGE_KIND_WEBAUTHN, &URL_SAFE_NO_PAD.encode(challenge))
+            .await?
+            .ok_or_else(|| AppError::ValidationError("Challenge not found or expired".into()))?;
+        serde_json::from_value(stored)
+            .map_err(|_| AppError::ValidationError("Challenge not found or expired".into()))
+    }
+
     pub async fn start_registration(
         &self,
         user_id: Uuid,
@@ -122,15 +169,10 @@ impl WebAuthnService {
         let mut challenge_bytes = [0u8; 32];
         rand::thread_rng().fill_bytes(&mut challenge_bytes);
         
-        // Store challenge
-        self.repo.create_challenge(
-            Some(user_id),
-            &challenge_bytes,
-            ChallengeType::Registration,
-            300, // 5 minutes
-        ).await?;
+        let challenge = URL_SAFE_NO_PAD.encode(challenge_bytes);
 
-        let challenge = URL_SAFE_NO_PAD.encode(&challenge_bytes);
+        // Store challenge
+        self.store_challenge(&challenge, Some(user_id), ChallengeType::Registration).await?;
         let user_id_encoded = URL_SAFE_NO_PAD.encode(user_id.as_bytes());
 
         Ok(RegistrationOptions {
@@ -180,13 +222,12 @@ impl WebAuthnService {
         let challenge_b64 = client_data["challenge"].as_str()
             .ok_or_else(|| AppError::ValidationError("Missing challenge".into()))?;
         
-        let challenge = URL_SAFE_NO_PAD.decode(challenge_b64)
-            .map_err(|_| AppError::ValidationError("Invalid challenge encoding".into()))?;
-
-        let stored_challenge = self.repo.find_valid_challenge(&challenge).await?
-            .ok_or_else(|| AppError::ValidationError("Challenge not found or expired".into()))?;
+        // A challenge is used up by its first answer, right or wrong
+        let stored_challenge = self.take_challenge(challenge_b64).await?;
 
-        if stored_challenge.challenge_type != ChallengeType::Registration {
+        if stored_challenge.challenge_type != ChallengeType::Registration
+            || stored_challenge.user_id != Some(user_id)
+        {
             return Err(AppError::ValidationError("Invalid challenge type".into()));
         }
 
@@ -206,9 +247,6 @@ impl WebAuthnService {
         // For now, we'll store the raw attestation object as the public key
         // In a real implementation, you'd extract the actual public key from the attestation
 
-        // Delete used challenge
-        self.repo.delete_challenge(stored_challenge.id).await?;
-
         // Create credential
         let credential = self.repo.create_credential(
             user_id,
@@ -231,15 +269,10 @@ impl WebAuthnService {
         let mut challenge_bytes = [0u8; 32];
         rand::thread_rng().fill_bytes(&mut challenge_bytes);
 
-        // Store challenge
-        self.repo.create_challenge(
-            user_id,
-            &challenge_bytes,
-            ChallengeType::Authentication,
-            300,
-        ).await?;
+        let challenge = URL_SAFE_NO_PAD.encode(challenge_bytes);
 
-        let challenge = URL_SAFE_NO_PAD.encode(&challenge_bytes);
+        // Store challenge
+        self.store_challenge(&challenge, user_id, ChallengeType::Authentication).await?;
 
         // Get allowed credentials
         let allow_credentials = if let Some(user_id) = user_id {
@@ -286,13 +319,12 @@ impl WebAuthnService {
         let challenge_b64 = client_data["challenge"].as_str()
             .ok_or_else(|| AppError::ValidationError("Missing challenge".into()))?;
         
-        let challenge = URL_SAFE_NO_PAD.decode(challenge_b64)
-            .map_err(|_| AppError::ValidationError("Invalid challenge encoding".into()))?;
+        // A challenge is used up by its first answer, right or wrong
+        let stored_challenge = self.take_challenge(challenge_b64).await?;
 
-        let stored_challenge = self.repo.find_valid_challenge(&challenge).await?
-            .ok_or_else(|| AppError::ValidationError("Challenge not found or expired".into()))?;
-
-        if stored_challenge.challenge_type != ChallengeType::Authentication {
+        if stored_challenge.challenge_type != ChallengeType::Authentication
+            || stored_challenge.user_id.is_some_and(|id| id != credential.user_id)
+        {
             return Err(AppError::ValidationError("Invalid challenge type".into()));
         }
 
@@ -325,9 +357,6 @@ impl WebAuthnService {
         // In production, verify the signature here using the stored public key
         // This is simplified for demonstration
 
-        // Delete used challenge
-        self.repo.delete_challenge(stored_challenge.id).await?;
-
         // Update counter
         self.repo.update_counter(credential.id, new_counter).await?;
 
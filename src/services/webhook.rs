@@ -1,16 +1,34 @@
+use chrono::{DateTime, Utc};
 use sqlx::MySqlPool;
 use uuid::Uuid;
-use chrono::Utc;
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
 
 use crate::error::AppError;
-use crate::models::{Webhook, WebhookEvent};
+use crate::models::{Webhook, WebhookEvent, WebhookEventRecord};
 use crate::repositories::WebhookRepository;
 use crate::utils::secret::generate_secret;
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// Payload schema versions a webhook can be pinned to
+pub const WEBHOOK_PAYLOAD_VERSIONS: [i32; 2] = [1, 2];
+
+/// Payload schema version new webhooks get by default
+pub const LATEST_WEBHOOK_PAYLOAD_VERSION: i32 = 2;
+
+/// Events re-enqueued by one replay call
+pub const MAX_REPLAY_EVENTS: i64 = 1000;
+
+/// Outcome of a replay call
+#[derive(Debug, Clone)]
+pub struct WebhookReplay {
+    /// Deliveries enqueued
+    pub queued: usize,
+    /// Where to continue when the window held more than one call's worth
+    pub next_from: Option<DateTime<Utc>>,
+}
+
 #[derive(Clone)]
 pub struct WebhookService {
     pool: MySqlPool,
@@ -30,16 +48,20 @@ impl WebhookService {
         app_id: Uuid,
         url: &str,
         events: Vec<String>,
+        payload_version: Option<i32>,
     ) -> Result<(Webhook, String), AppError> {
         // Validate URL
         if !url.starts_with("https://") && !url.starts_with("http://localhost") {
             return Err(AppError::ValidationError("Webhook URL must use HTTPS".into()));
         }
 
+        let payload_version = payload_version.unwrap_or(LATEST_WEBHOOK_PAYLOAD_VERSION);
+        Self::validate_payload_version(payload_version)?;
+
         // Generate secret
         let secret = generate_secret();
         
-        let webhook = self.repo.create(app_id, url, &secret, events).await?;
+        let webhook = self.repo.create(app_id, url, &secret, events, payload_version).await?;
         
         Ok((webhook, secret))
     }
@@ -58,6 +80,7 @@ impl WebhookService {
         url: Option<&str>,
         events: Option<Vec<String>>,
         is_active: Option<bool>,
+        payload_version: Option<i32>,
     ) -> Result<Webhook, AppError> {
         if let Some(url) = url {
             if !url.starts_with("https://") && !url.starts_with("http://localhost") {
@@ -65,7 +88,22 @@ impl WebhookService {
             }
         }
 
-        self.repo.update(id, url, events, is_active).await
+        if let Some(payload_version) = payload_version {
+            Self::validate_payload_version(payload_version)?;
+        }
+
+        self.repo.update(id, url, events, is_active, payload_version).await
+    }
+
+    fn validate_payload_version(version: i32) -> Result<(), AppError> {
+        if WEBHOOK_PAYLOAD_VERSIONS.contains(&version) {
+            Ok(())
+        } else {
+            Err(AppError::ValidationError(format!(
+                "Unsupported payload_version {}, expected one of {:?}",
+                version, WEBHOOK_PAYLOAD_VERSIONS
+            )))
+        }
     }
 
     pub async fn delete_webhook(&self, id: Uuid) -> Result<(), AppError> {
@@ -79,15 +117,94 @@ impl WebhookService {
         payload: serde_json::Value,
     ) -> Result<(), AppError> {
         let event_str = event.as_str();
+
+        // Recorded even with no subscribers, so a webhook added later can backfill
+        let record = self.repo.create_event(app_id, event_str, &payload).await?;
         let webhooks = self.repo.find_by_event(app_id, event_str).await?;
 
         for webhook in webhooks {
-            self.repo.create_delivery(webhook.id, event_str, payload.clone()).await?;
+            let payload = Self::render_payload(webhook.payload_version, &record);
+            self.repo.create_delivery(webhook.id, &record, payload, false).await?;
         }
 
         Ok(())
     }
 
+    /// Re-enqueue a webhook's events raised in `[from, to)`
+    ///
+    /// Only events the webhook is currently subscribed to are replayed, in
+    /// its current payload version. At most `MAX_REPLAY_EVENTS` are taken per
+    /// call; `next_from` tells the caller where to continue.
+    pub async fn replay_events(
+        &self,
+        webhook: &Webhook,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<WebhookReplay, AppError> {
+        if from >= to {
+            return Err(AppError::ValidationError("'from' must be before 'to'".into()));
+        }
+
+        // One extra row tells whether the window was cut short
+        let mut events = self
+            .repo
+            .find_events(webhook.app_id, &webhook.events.0, from, to, MAX_REPLAY_EVENTS + 1)
+            .await?;
+        let mut next_from = if events.len() as i64 > MAX_REPLAY_EVENTS {
+            events.pop().map(|next| next.created_at)
+        } else {
+            None
+        };
+
+        // Events sharing the cut-off second are left for the next call. If the
+        // whole batch is that one second, move past it so the caller progresses.
+        if let Some(cutoff) = next_from {
+            if events.iter().any(|event| event.created_at < cutoff) {
+                events.retain(|event| event.created_at < cutoff);
+            } else {
+                next_from = Some(cutoff + chrono::Duration::seconds(1));
+            }
+        }
+
+        for event in &events {
+            let payload = Self::render_payload(webhook.payload_version, event);
+            self.repo.create_delivery(webhook.id, event, payload, true).await?;
+        }
+
+        Ok(WebhookReplay {
+            queued: events.len(),
+            next_from,
+        })
+    }
+
+    /// Shape an outbox event into the payload schema of `version`
+    ///
+    /// Version 1 is the event's flat payload as raised. Version 2 wraps it in
+    /// an envelope with a stable event id, so receivers can deduplicate
+    /// replays, and moves the event-specific fields under `data`.
+    pub fn render_payload(version: i32, event: &WebhookEventRecord) -> serde_json::Value {
+        if version < 2 {
+            return event.payload.0.clone();
+        }
+
+        let mut data = match &event.payload.0 {
+            serde_json::Value::Object(fields) => fields.clone(),
+            _ => serde_json::Map::new(),
+        };
+        for envelope_field in ["event", "app_id", "timestamp"] {
+            data.remove(envelope_field);
+        }
+
+        serde_json::json!({
+            "version": version,
+            "id": event.id.to_string(),
+            "event": event.event_type,
+            "app_id": event.app_id.to_string(),
+            "occurred_at": event.created_at.to_rfc3339(),
+            "data": data,
+        })
+    }
+
     pub fn sign_payload(secret: &str, payload: &str) -> String {
         let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
             .expect("HMAC can take key of any size");
@@ -100,55 +217,52 @@ impl WebhookService {
         let expected = Self::sign_payload(secret, payload);
         expected == signature
     }
+}
 
-    pub async fn process_pending_deliveries(&self) -> Result<u32, AppError> {
-        let deliveries = self.repo.get_pending_deliveries(100).await?;
-        let mut processed = 0;
-
-        for delivery in deliveries {
-            let webhook = match self.repo.find_by_id(delivery.webhook_id).await? {
-                Some(w) => w,
-                None => continue,
-            };
-
-            let payload_str = serde_json::to_string(&delivery.payload)
-                .map_err(|e| AppError::InternalError(e.into()))?;
-            
-            let signature = Self::sign_payload(&webhook.secret, &payload_str);
-            let timestamp = Utc::now().timestamp();
-
-            // Build request
-            let client = reqwest::Client::new();
-            let result = client
-                .post(&webhook.url)
-                .header("Content-Type", "application/json")
-                .header("X-Webhook-Signature", &signature)
-                .header("X-Webhook-Timestamp", timestamp.to_string())
-                .header("X-Webhook-Event", &delivery.event_type)
-                .body(payload_str)
-                .timeout(std::time::Duration::from_secs(30))
-                .send()
-                .await;
-
-            match result {
-                Ok(response) => {
-                    let status = response.status().as_u16() as i32;
-                    let body = response.text().await.ok();
-                    
-                    if status >= 200 && status < 300 {
-                        self.repo.mark_delivered(delivery.id, status, body.as_deref()).await?;
-                    } else {
-                        self.repo.mark_failed(delivery.id, Some(status), body.as_deref()).await?;
-                    }
-                }
-                Err(e) => {
-                    self.repo.mark_failed(delivery.id, None, Some(&e.to_string())).await?;
-                }
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            processed += 1;
+    fn event(payload: serde_json::Value) -> WebhookEventRecord {
+        WebhookEventRecord {
+            id: Uuid::new_v4(),
+            app_id: Uuid::new_v4(),
+            event_type: "user.app.joined".into(),
+            payload: sqlx::types::Json(payload),
+            correlation_id: None,
+            created_at: Utc::now(),
         }
+    }
+
+    #[test]
+    fn test_render_payload_v1_is_unchanged() {
+        let payload = serde_json::json!({
+            "event": "user.app.joined",
+            "user_id": "u1",
+            "timestamp": "2025-01-01T00:00:00Z"
+        });
+        let record = event(payload.clone());
+
+        assert_eq!(WebhookService::render_payload(1, &record), payload);
+    }
+
+    #[test]
+    fn test_render_payload_v2_envelope() {
+        let record = event(serde_json::json!({
+            "event": "user.app.joined",
+            "user_id": "u1",
+            "app_id": "ignored",
+            "status": "active",
+            "timestamp": "2025-01-01T00:00:00Z"
+        }));
+
+        let rendered = WebhookService::render_payload(2, &record);
 
-        Ok(processed)
+        assert_eq!(rendered["version"], 2);
+        assert_eq!(rendered["id"], record.id.to_string());
+        assert_eq!(rendered["event"], "user.app.joined");
+        assert_eq!(rendered["app_id"], record.app_id.to_string());
+        assert_eq!(rendered["occurred_at"], record.created_at.to_rfc3339());
+        assert_eq!(rendered["data"], serde_json::json!({ "user_id": "u1", "status": "active" }));
     }
 }
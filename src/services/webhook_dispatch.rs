@@ -0,0 +1,358 @@
+//! Webhook delivery scheduling
+//!
+//! Keeps one slow or failing receiver from holding up everyone else:
+//! - deliveries run concurrently, capped overall and per receiver host
+//! - each app may only send so many deliveries per minute
+//! - a webhook failing repeatedly is paused (circuit breaker, stored on the webhook)
+//! - the batch is interleaved across apps so a large backlog cannot starve small ones
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use sqlx::MySqlPool;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::models::{Webhook, WebhookDelivery};
+use crate::repositories::WebhookRepository;
+use crate::services::WebhookService;
+use crate::utils::{chaos, metrics};
+use crate::utils::request_id::CORRELATION_ID_HEADER;
+
+/// Deliveries fetched per tick
+const BATCH_SIZE: i64 = 100;
+
+/// Deliveries taken from one webhook per tick
+const MAX_PER_WEBHOOK: i64 = 10;
+
+/// How long to wait for a receiver
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Delivery limits, from configuration
+#[derive(Debug, Clone)]
+pub struct WebhookDispatchLimits {
+    /// Deliveries in flight at once across all receivers
+    pub max_concurrency: usize,
+    /// Deliveries in flight at once to one receiver host
+    pub target_max_concurrency: usize,
+    /// Deliveries per minute for one app (0 = unlimited)
+    pub app_rate_limit_per_minute: u32,
+    /// Failures in a row that open a webhook's circuit (0 = never)
+    pub circuit_failure_threshold: i32,
+    /// How long an open circuit holds deliveries back
+    pub circuit_cooldown_secs: i64,
+}
+
+impl WebhookDispatchLimits {
+    /// Build the limits from server configuration
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_concurrency: config.webhook_max_concurrency.max(1),
+            target_max_concurrency: config.webhook_target_max_concurrency.max(1),
+            app_rate_limit_per_minute: config.webhook_app_rate_limit_per_minute,
+            circuit_failure_threshold: config.webhook_circuit_failure_threshold,
+            circuit_cooldown_secs: config.webhook_circuit_cooldown_secs.max(1),
+        }
+    }
+}
+
+/// Token bucket per app, refilled continuously up to one minute's worth
+#[derive(Debug, Default)]
+struct AppRateLimiter {
+    per_minute: u32,
+    buckets: HashMap<Uuid, (f64, Instant)>,
+}
+
+impl AppRateLimiter {
+    fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Take one delivery from the app's budget; false when it is used up
+    fn try_acquire(&mut self, app_id: Uuid, now: Instant) -> bool {
+        if self.per_minute == 0 {
+            return true;
+        }
+
+        let capacity = self.per_minute as f64;
+        let (tokens, updated) = self.buckets.entry(app_id).or_insert((capacity, now));
+        let elapsed = now.saturating_duration_since(*updated).as_secs_f64();
+        *tokens = (*tokens + elapsed * capacity / 60.0).min(capacity);
+        *updated = now;
+
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Reorder items round-robin by key, keeping each key's own order
+///
+/// Keys take turns in the order they first appear.
+fn interleave_by_key<T, K: Eq + Hash + Clone>(items: Vec<T>, key: impl Fn(&T) -> K) -> Vec<T> {
+    let mut order: Vec<K> = Vec::new();
+    let mut queues: HashMap<K, VecDeque<T>> = HashMap::new();
+    for item in items {
+        let k = key(&item);
+        if !queues.contains_key(&k) {
+            order.push(k.clone());
+        }
+        queues.entry(k).or_default().push_back(item);
+    }
+
+    let mut result = Vec::new();
+    loop {
+        let mut taken = false;
+        for k in &order {
+            if let Some(item) = queues.get_mut(k).and_then(VecDeque::pop_front) {
+                result.push(item);
+                taken = true;
+            }
+        }
+        if !taken {
+            return result;
+        }
+    }
+}
+
+/// Receiver a webhook URL points at; concurrency is capped per host
+fn target_key(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| {
+            url.host_str()
+                .map(|host| format!("{}:{}", host, url.port_or_known_default().unwrap_or_default()))
+        })
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Sends pending webhook deliveries within the configured limits
+///
+/// Lives as long as the webhook worker so rate limits carry across ticks.
+pub struct WebhookDispatcher {
+    repo: WebhookRepository,
+    client: reqwest::Client,
+    limits: WebhookDispatchLimits,
+    rate_limiter: AppRateLimiter,
+    targets: Arc<Mutex<HashMap<String, Arc<Semaphore>>>>,
+}
+
+impl WebhookDispatcher {
+    pub fn new(pool: MySqlPool, limits: WebhookDispatchLimits) -> Self {
+        Self {
+            repo: WebhookRepository::new(pool),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+                .build()
+                .unwrap_or_default(),
+            rate_limiter: AppRateLimiter::new(limits.app_rate_limit_per_minute),
+            limits,
+            targets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn target_semaphore(&self, url: &str) -> Arc<Semaphore> {
+        let mut targets = self.targets.lock().unwrap_or_else(|e| e.into_inner());
+        targets
+            .entry(target_key(url))
+            .or_insert_with(|| Arc::new(Semaphore::new(self.limits.target_max_concurrency)))
+            .clone()
+    }
+
+    /// Send one batch of pending deliveries
+    ///
+    /// Deliveries over an app's rate limit stay pending for a later tick
+    /// without using up an attempt.
+    ///
+    /// # Returns
+    /// The number of deliveries attempted
+    pub async fn dispatch_pending(&mut self) -> Result<u32, AppError> {
+        let deliveries = self.repo.get_pending_deliveries(MAX_PER_WEBHOOK, BATCH_SIZE).await?;
+
+        let mut webhooks: HashMap<Uuid, Arc<Webhook>> = HashMap::new();
+        let mut batch: Vec<(Arc<Webhook>, WebhookDelivery)> = Vec::new();
+        for delivery in deliveries {
+            let webhook = match webhooks.get(&delivery.webhook_id) {
+                Some(webhook) => webhook.clone(),
+                None => match self.repo.find_by_id(delivery.webhook_id).await? {
+                    Some(webhook) => {
+                        let webhook = Arc::new(webhook);
+                        webhooks.insert(webhook.id, webhook.clone());
+                        webhook
+                    }
+                    None => continue,
+                },
+            };
+            batch.push((webhook, delivery));
+        }
+
+        let now = Instant::now();
+        let mut throttled = 0;
+        let global = Arc::new(Semaphore::new(self.limits.max_concurrency));
+        let mut tasks = JoinSet::new();
+
+        for (webhook, delivery) in interleave_by_key(batch, |(webhook, _)| webhook.app_id) {
+            if !self.rate_limiter.try_acquire(webhook.app_id, now) {
+                throttled += 1;
+                continue;
+            }
+
+            let target = self.target_semaphore(&webhook.url);
+            let global = global.clone();
+            let repo = self.repo.clone();
+            let client = self.client.clone();
+            let limits = self.limits.clone();
+            tasks.spawn(async move {
+                // Wait for the receiver before taking a global slot, so a
+                // slow receiver's queue does not block the others
+                let _target = target.acquire_owned().await;
+                let _global = global.acquire_owned().await;
+                deliver(&repo, &client, &limits, &webhook, &delivery).await
+            });
+        }
+
+        let mut processed = 0;
+        while let Some(result) = tasks.join_next().await {
+            match result {
+                Ok(Ok(())) => processed += 1,
+                Ok(Err(e)) => tracing::error!("Webhook delivery bookkeeping failed: {:?}", e),
+                Err(e) => tracing::error!("Webhook delivery task failed: {}", e),
+            }
+        }
+
+        if throttled > 0 {
+            tracing::debug!("{} webhook deliveries deferred by app rate limits", throttled);
+        }
+
+        Ok(processed)
+    }
+}
+
+/// Send one delivery and record the outcome on it and on its webhook's circuit
+async fn deliver(
+    repo: &WebhookRepository,
+    client: &reqwest::Client,
+    limits: &WebhookDispatchLimits,
+    webhook: &Webhook,
+    delivery: &WebhookDelivery,
+) -> Result<(), AppError> {
+    let payload_str = serde_json::to_string(&delivery.payload).map_err(|e| AppError::InternalError(e.into()))?;
+    let signature = WebhookService::sign_payload(&webhook.secret, &payload_str);
+    let timestamp = Utc::now().timestamp();
+
+    let mut request = client
+        .post(&webhook.url)
+        .header("Content-Type", "application/json")
+        .header("X-Webhook-Signature", &signature)
+        .header("X-Webhook-Timestamp", timestamp.to_string())
+        .header("X-Webhook-Event", &delivery.event_type)
+        .header("X-Webhook-Version", webhook.payload_version.to_string());
+    if delivery.is_replay {
+        request = request.header("X-Webhook-Replay", "true");
+    }
+    if let Some(correlation_id) = &delivery.correlation_id {
+        request = request.header(CORRELATION_ID_HEADER, correlation_id);
+    }
+
+    let delivered = if chaos::webhook_fails() {
+        repo.mark_failed(delivery.id, None, Some(chaos::INJECTED_FAILURE)).await?;
+        false
+    } else {
+        match request.body(payload_str).send().await {
+            Ok(response) => {
+                let status = response.status().as_u16() as i32;
+                let body = response.text().await.ok();
+
+                if (200..300).contains(&status) {
+                    repo.mark_delivered(delivery.id, status, body.as_deref()).await?;
+                    true
+                } else {
+                    repo.mark_failed(delivery.id, Some(status), body.as_deref()).await?;
+                    false
+                }
+            }
+            Err(e) => {
+                repo.mark_failed(delivery.id, None, Some(&e.to_string())).await?;
+                false
+            }
+        }
+    };
+
+    metrics::record_webhook_delivery(&webhook.app_id.to_string(), delivered);
+
+    if delivered {
+        repo.record_success(webhook.id).await?;
+    } else {
+        let failures = repo
+            .record_failure(webhook.id, limits.circuit_failure_threshold, limits.circuit_cooldown_secs)
+            .await?;
+        if limits.circuit_failure_threshold > 0 && failures >= limits.circuit_failure_threshold {
+            tracing::warn!(
+                "Webhook {} failed {} times in a row, pausing deliveries for {} seconds",
+                webhook.id,
+                failures,
+                limits.circuit_cooldown_secs
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interleave_by_key() {
+        let items = vec![("a", 1), ("a", 2), ("a", 3), ("b", 1), ("c", 1), ("b", 2)];
+
+        let ordered = interleave_by_key(items, |(key, _)| *key);
+
+        assert_eq!(ordered, vec![("a", 1), ("b", 1), ("c", 1), ("a", 2), ("b", 2), ("a", 3)]);
+    }
+
+    #[test]
+    fn test_app_rate_limiter() {
+        let mut limiter = AppRateLimiter::new(2);
+        let (busy, quiet) = (Uuid::new_v4(), Uuid::new_v4());
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire(busy, start));
+        assert!(limiter.try_acquire(busy, start));
+        assert!(!limiter.try_acquire(busy, start));
+        // Other apps have their own budget
+        assert!(limiter.try_acquire(quiet, start));
+        // Two per minute refills one every 30 seconds
+        assert!(limiter.try_acquire(busy, start + Duration::from_secs(30)));
+        assert!(!limiter.try_acquire(busy, start + Duration::from_secs(31)));
+    }
+
+    #[test]
+    fn test_app_rate_limiter_unlimited() {
+        let mut limiter = AppRateLimiter::new(0);
+        let app = Uuid::new_v4();
+        let now = Instant::now();
+
+        assert!((0..1000).all(|_| limiter.try_acquire(app, now)));
+    }
+
+    #[test]
+    fn test_target_key() {
+        assert_eq!(target_key("https://hooks.example.com/a"), "hooks.example.com:443");
+        assert_eq!(target_key("https://hooks.example.com/b?x=1"), "hooks.example.com:443");
+        assert_eq!(target_key("http://localhost:8080/hook"), "localhost:8080");
+    }
+}
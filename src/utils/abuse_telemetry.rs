@@ -0,0 +1,96 @@
+//! Per-IP, per-route counters of rejected requests
+//!
+//! `abuse_telemetry_middleware` counts every 4xx response by caller IP and
+//! matched route template in memory; the abuse telemetry worker drains the
+//! counters into the current time window on every tick and turns bursts over
+//! the configured thresholds into suggested IP deny rules.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use axum::http::StatusCode;
+
+/// Upper bound on distinct (IP, route) pairs buffered between flushes
+const MAX_TRACKED_PAIRS: usize = 10_000;
+
+/// Route recorded for requests that matched no route (scanners probing paths)
+pub const UNMATCHED_ROUTE: &str = "<unmatched>";
+
+/// Rejected requests from one IP to one route since the last flush
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AbuseCounts {
+    /// 4xx responses other than 429
+    pub client_errors: u32,
+    /// 429 responses
+    pub rate_limited: u32,
+}
+
+static ABUSE_COUNTS: Mutex<Option<HashMap<(String, String), AbuseCounts>>> = Mutex::new(None);
+
+/// Whether a response status counts towards abuse telemetry
+pub fn is_abuse_status(status: StatusCode) -> bool {
+    status.is_client_error()
+}
+
+/// Count a rejected response from `ip` to `route`
+///
+/// Statuses outside 4xx are ignored. Once the buffer is full new pairs are
+/// dropped until the next flush; pairs already tracked keep counting.
+pub fn record_response(ip: &str, route: &str, status: StatusCode) {
+    if !is_abuse_status(status) {
+        return;
+    }
+
+    let mut counts = ABUSE_COUNTS.lock().unwrap_or_else(|e| e.into_inner());
+    let map = counts.get_or_insert_with(HashMap::new);
+
+    let key = (ip.to_string(), route.to_string());
+    if !map.contains_key(&key) && map.len() >= MAX_TRACKED_PAIRS {
+        return;
+    }
+
+    let entry = map.entry(key).or_default();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        entry.rate_limited += 1;
+    } else {
+        entry.client_errors += 1;
+    }
+}
+
+/// Take the counters accumulated since the last call, keyed by (IP, route)
+pub fn take_abuse_counts() -> Vec<((String, String), AbuseCounts)> {
+    let mut counts = ABUSE_COUNTS.lock().unwrap_or_else(|e| e.into_inner());
+    counts.take().map(|map| map.into_iter().collect()).unwrap_or_default()
+}
+
+/// Take only the counters recorded for `ip` (tests run concurrently)
+#[cfg(test)]
+pub(crate) fn take_abuse_counts_for(ip: &str) -> HashMap<String, AbuseCounts> {
+    let mut counts = ABUSE_COUNTS.lock().unwrap_or_else(|e| e.into_inner());
+    let map = counts.get_or_insert_with(HashMap::new);
+    let keys: Vec<_> = map.keys().filter(|(i, _)| i == ip).cloned().collect();
+    keys.into_iter()
+        .filter_map(|key| map.remove(&key).map(|c| (key.1, c)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_take_counts() {
+        let ip = "198.51.100.77";
+        record_response(ip, "/auth/login", StatusCode::UNAUTHORIZED);
+        record_response(ip, "/auth/login", StatusCode::UNAUTHORIZED);
+        record_response(ip, "/auth/login", StatusCode::TOO_MANY_REQUESTS);
+        record_response(ip, "/auth/login", StatusCode::OK);
+        record_response(ip, "/auth/login", StatusCode::INTERNAL_SERVER_ERROR);
+        record_response(ip, UNMATCHED_ROUTE, StatusCode::NOT_FOUND);
+
+        let counts = take_abuse_counts_for(ip);
+        assert_eq!(counts["/auth/login"], AbuseCounts { client_errors: 2, rate_limited: 1 });
+        assert_eq!(counts[UNMATCHED_ROUTE], AbuseCounts { client_errors: 1, rate_limited: 0 });
+        assert!(take_abuse_counts_for(ip).is_empty());
+    }
+}
@@ -0,0 +1,353 @@
+//! Recurring weekly schedules during which a grant is usable
+//!
+//! Role assignments and API keys may carry an access window so that e.g. a
+//! support role only works during office hours. A window is one or more
+//! spans separated by `;`, each written as `<days> <HH:MM>-<HH:MM> [offset]`:
+//!
+//! - `mon-fri 09:00-17:00 +07:00` - weekdays, office hours at UTC+7
+//! - `sat,sun 10:00-14:00` - weekend mornings, UTC
+//! - `* 22:00-06:00` - every night; the span belongs to the day it starts on
+//!
+//! Days are `mon`..`sun`, comma-separated lists or ranges of them, or `*`.
+//! The end time is exclusive and may be `24:00`. The offset is `UTC`, `Z`
+//! or `+HH:MM` / `-HH:MM` and defaults to UTC.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Timelike, Utc};
+
+/// Longest window spec stored with a grant
+pub const MAX_ACCESS_WINDOW_LEN: usize = 255;
+
+const DAY_NAMES: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+const FULL_DAY_NAMES: [&str; 7] = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// A parsed access window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessWindow {
+    spans: Vec<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Span {
+    /// Days the span starts on, Monday first
+    days: [bool; 7],
+    /// Minutes after local midnight
+    start: u32,
+    end: u32,
+    offset: FixedOffset,
+}
+
+impl AccessWindow {
+    /// Whether the window is open at `at`
+    pub fn is_open(&self, at: DateTime<Utc>) -> bool {
+        self.spans.iter().any(|span| span.close_after(at).is_some())
+    }
+
+    /// When the window open at `at` closes, or `None` if it is closed
+    ///
+    /// With overlapping spans the latest close is returned; spans that only
+    /// touch are not chained, so the result may be earlier than the last
+    /// moment access is allowed.
+    pub fn closes_at(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.spans.iter().filter_map(|span| span.close_after(at)).max()
+    }
+}
+
+/// Whether a grant with the stored `window` (`None` = no window) is usable at `at`
+///
+/// A stored window that no longer parses denies access.
+pub fn admits(window: Option<&str>, at: DateTime<Utc>) -> bool {
+    match window.map(str::parse::<AccessWindow>) {
+        None => true,
+        Some(Ok(window)) => window.is_open(at),
+        Some(Err(e)) => {
+            tracing::warn!("Unreadable access window '{}': {}", window.unwrap_or_default(), e);
+            false
+        }
+    }
+}
+
+impl Span {
+    fn close_after(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let local = at.with_timezone(&self.offset);
+        let day = local.weekday().num_days_from_monday() as usize;
+        let minute = local.hour() * 60 + local.minute();
+        let midnight = at
+            - Duration::minutes(minute as i64)
+            - Duration::seconds(local.second() as i64)
+            - Duration::nanoseconds(local.nanosecond() as i64);
+
+        if self.start < self.end {
+            return (self.days[day] && (self.start..self.end).contains(&minute))
+                .then(|| midnight + Duration::minutes(self.end as i64));
+        }
+
+        // Overnight span: the evening part opened today, or the morning part
+        // of one that opened yesterday
+        if self.days[day] && minute >= self.start {
+            Some(midnight + Duration::minutes((MINUTES_PER_DAY + self.end) as i64))
+        } else if self.days[(day + 6) % 7] && minute < self.end {
+            Some(midnight + Duration::minutes(self.end as i64))
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for AccessWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() > MAX_ACCESS_WINDOW_LEN {
+            return Err(format!("Access window is longer than {} characters", MAX_ACCESS_WINDOW_LEN));
+        }
+
+        let spans = s
+            .split(';')
+            .map(str::trim)
+            .filter(|span| !span.is_empty())
+            .map(parse_span)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if spans.is_empty() {
+            return Err("Access window has no spans".to_string());
+        }
+
+        Ok(Self { spans })
+    }
+}
+
+impl fmt::Display for AccessWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, span) in self.spans.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{} {}-{}", format_days(&span.days), format_minute(span.start), format_minute(span.end))?;
+            if span.offset.local_minus_utc() != 0 {
+                write!(f, " {}", span.offset)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_span(span: &str) -> Result<Span, String> {
+    let parts: Vec<&str> = span.split_whitespace().collect();
+    let (days, times, offset) = match parts.as_slice() {
+        [days, times] => (*days, *times, None),
+        [days, times, offset] => (*days, *times, Some(*offset)),
+        _ => return Err(format!("Access window span '{}' must be '<days> <HH:MM>-<HH:MM> [offset]'", span)),
+    };
+
+    let (start, end) = times
+        .split_once('-')
+        .ok_or_else(|| format!("Access window times '{}' must be '<HH:MM>-<HH:MM>'", times))?;
+    let start = parse_time(start)?;
+    let end = parse_time(end)?;
+    if start == MINUTES_PER_DAY {
+        return Err("Access window cannot start at 24:00".to_string());
+    }
+    if start == end {
+        return Err(format!("Access window span '{}' is empty", span));
+    }
+
+    Ok(Span {
+        days: parse_days(days)?,
+        start,
+        end,
+        offset: offset.map(parse_offset).transpose()?.unwrap_or_else(|| FixedOffset::east_opt(0).unwrap()),
+    })
+}
+
+fn parse_days(days: &str) -> Result<[bool; 7], String> {
+    let mut set = [false; 7];
+    if days == "*" {
+        return Ok([true; 7]);
+    }
+
+    for item in days.split(',') {
+        let (first, last) = match item.split_once('-') {
+            Some((first, last)) => (parse_day(first)?, parse_day(last)?),
+            None => (parse_day(item)?, parse_day(item)?),
+        };
+        // Ranges may wrap past Sunday, e.g. fri-mon
+        let mut day = first;
+        loop {
+            set[day] = true;
+            if day == last {
+                break;
+            }
+            day = (day + 1) % 7;
+        }
+    }
+
+    Ok(set)
+}
+
+fn parse_day(day: &str) -> Result<usize, String> {
+    let lower = day.trim().to_ascii_lowercase();
+    DAY_NAMES
+        .iter()
+        .zip(FULL_DAY_NAMES)
+        .position(|(short, full)| lower == *short || lower == full)
+        .ok_or_else(|| format!("Unknown day '{}' (expected mon, tue, wed, thu, fri, sat or sun)", day))
+}
+
+fn parse_time(time: &str) -> Result<u32, String> {
+    let invalid = || format!("Invalid time '{}' (expected HH:MM)", time);
+    let (hours, minutes) = time.split_once(':').ok_or_else(invalid)?;
+    if hours.is_empty() || hours.len() > 2 || minutes.len() != 2 {
+        return Err(invalid());
+    }
+    let hours: u32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u32 = minutes.parse().map_err(|_| invalid())?;
+    let total = hours * 60 + minutes;
+    if minutes >= 60 || total > MINUTES_PER_DAY {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+fn parse_offset(offset: &str) -> Result<FixedOffset, String> {
+    let invalid = || format!("Invalid UTC offset '{}' (expected UTC or +HH:MM)", offset);
+    if offset.eq_ignore_ascii_case("utc") || offset.eq_ignore_ascii_case("z") {
+        return Ok(FixedOffset::east_opt(0).unwrap());
+    }
+
+    let (sign, rest) = match offset.as_bytes().first() {
+        Some(b'+') => (1, &offset[1..]),
+        Some(b'-') => (-1, &offset[1..]),
+        _ => return Err(invalid()),
+    };
+    let minutes = parse_time(rest).map_err(|_| invalid())?;
+    if minutes >= 18 * 60 {
+        return Err(invalid());
+    }
+    FixedOffset::east_opt(sign * minutes as i32 * 60).ok_or_else(invalid)
+}
+
+fn format_days(days: &[bool; 7]) -> String {
+    if days.iter().all(|&d| d) {
+        return "*".to_string();
+    }
+
+    // Collapse runs of consecutive days into ranges
+    let mut parts = Vec::new();
+    let mut day = 0;
+    while day < 7 {
+        if !days[day] {
+            day += 1;
+            continue;
+        }
+        let first = day;
+        while day + 1 < 7 && days[day + 1] {
+            day += 1;
+        }
+        parts.push(if first == day {
+            DAY_NAMES[first].to_string()
+        } else {
+            format!("{}-{}", DAY_NAMES[first], DAY_NAMES[day])
+        });
+        day += 1;
+    }
+    parts.join(",")
+}
+
+fn format_minute(minute: u32) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_weekday_office_hours() {
+        let window: AccessWindow = "mon-fri 09:00-17:00".parse().unwrap();
+
+        // 2025-01-06 is a Monday
+        assert!(window.is_open(at(2025, 1, 6, 9, 0)));
+        assert!(window.is_open(at(2025, 1, 10, 16, 59)));
+        assert!(!window.is_open(at(2025, 1, 6, 17, 0)));
+        assert!(!window.is_open(at(2025, 1, 6, 8, 59)));
+        assert!(!window.is_open(at(2025, 1, 11, 12, 0)));
+        assert_eq!(window.closes_at(at(2025, 1, 6, 12, 30)), Some(at(2025, 1, 6, 17, 0)));
+        assert_eq!(window.closes_at(at(2025, 1, 11, 12, 0)), None);
+    }
+
+    #[test]
+    fn test_offset_is_applied() {
+        let window: AccessWindow = "mon-fri 09:00-17:00 +07:00".parse().unwrap();
+
+        // 02:00 UTC Monday is 09:00 in UTC+7
+        assert!(window.is_open(at(2025, 1, 6, 2, 0)));
+        assert!(!window.is_open(at(2025, 1, 6, 10, 0)));
+        // 20:00 UTC Sunday is already Monday 03:00 in UTC+7, before hours
+        assert!(!window.is_open(at(2025, 1, 5, 20, 0)));
+        assert_eq!(window.closes_at(at(2025, 1, 6, 2, 0)), Some(at(2025, 1, 6, 10, 0)));
+    }
+
+    #[test]
+    fn test_overnight_span_belongs_to_start_day() {
+        let window: AccessWindow = "fri 22:00-06:00".parse().unwrap();
+
+        assert!(window.is_open(at(2025, 1, 10, 23, 0)));
+        assert!(window.is_open(at(2025, 1, 11, 5, 59)));
+        assert!(!window.is_open(at(2025, 1, 10, 5, 0)));
+        assert_eq!(window.closes_at(at(2025, 1, 10, 23, 0)), Some(at(2025, 1, 11, 6, 0)));
+        assert_eq!(window.closes_at(at(2025, 1, 11, 1, 0)), Some(at(2025, 1, 11, 6, 0)));
+    }
+
+    #[test]
+    fn test_multiple_spans_and_day_lists() {
+        let window: AccessWindow = "mon,wed 08:00-12:00; sat-sun 10:00-24:00".parse().unwrap();
+
+        assert!(window.is_open(at(2025, 1, 8, 9, 0)));
+        assert!(!window.is_open(at(2025, 1, 7, 9, 0)));
+        assert!(window.is_open(at(2025, 1, 12, 23, 59)));
+        assert_eq!(window.closes_at(at(2025, 1, 12, 11, 0)), Some(at(2025, 1, 13, 0, 0)));
+        assert_eq!(window.to_string(), "mon,wed 08:00-12:00; sat-sun 10:00-24:00");
+    }
+
+    #[test]
+    fn test_display_normalizes() {
+        let window: AccessWindow = "Monday-Friday 9:00-17:00 +07:00; * 00:00-01:00 UTC".parse().unwrap();
+        assert_eq!(window.to_string(), "mon-fri 09:00-17:00 +07:00; * 00:00-01:00");
+        assert_eq!(window.to_string().parse::<AccessWindow>().unwrap(), window);
+    }
+
+    #[test]
+    fn test_admits_without_window_and_denies_unreadable_ones() {
+        let monday_noon = at(2025, 1, 6, 12, 0);
+        assert!(admits(None, monday_noon));
+        assert!(admits(Some("mon 09:00-17:00"), monday_noon));
+        assert!(!admits(Some("tue 09:00-17:00"), monday_noon));
+        assert!(!admits(Some("garbage"), monday_noon));
+    }
+
+    #[test]
+    fn test_rejects_malformed_windows() {
+        for bad in [
+            "",
+            "mon-fri",
+            "mon-fri 9-17",
+            "funday 09:00-17:00",
+            "mon 09:00-09:00",
+            "mon 24:00-02:00",
+            "mon 09:00-25:00",
+            "mon 09:00-17:00 +25:00",
+            "mon 09:00-17:00 CET",
+        ] {
+            assert!(bad.parse::<AccessWindow>().is_err(), "{:?} should be rejected", bad);
+        }
+    }
+}
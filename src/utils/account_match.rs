@@ -0,0 +1,80 @@
+//! Normalization for duplicate-account detection
+//!
+//! These keys are only used to flag *probable* duplicates for an admin to
+//! review, so they deliberately over-match: two addresses that differ only by
+//! dots or a `+tag` in the local part are treated as the same mailbox on every
+//! domain, not just the providers that document that behaviour.
+
+/// Domains that are aliases of another mailbox domain
+const DOMAIN_ALIASES: &[(&str, &str)] = &[("googlemail.com", "gmail.com")];
+
+/// Phone numbers with fewer digits than this are too short to compare
+const MIN_PHONE_DIGITS: usize = 7;
+
+/// Normalize an email address into a match key
+///
+/// Lowercases the address, drops any `+tag` suffix and all dots from the
+/// local part, and folds known domain aliases.
+pub fn normalize_email(email: &str) -> String {
+    let email = email.trim().to_lowercase();
+
+    let (local, domain) = match email.rsplit_once('@') {
+        Some(parts) => parts,
+        None => return email,
+    };
+
+    let local = local.split('+').next().unwrap_or(local).replace('.', "");
+    let domain = DOMAIN_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == domain)
+        .map(|(_, canonical)| *canonical)
+        .unwrap_or(domain);
+
+    format!("{}@{}", local, domain)
+}
+
+/// Normalize a phone number into a match key
+///
+/// Keeps digits only, treating a leading `00` international prefix as `+`.
+/// Returns `None` when the number is too short to be meaningful.
+pub fn normalize_phone(phone: &str) -> Option<String> {
+    let phone = phone.trim();
+    let international = phone.starts_with('+') || phone.starts_with("00");
+
+    let mut digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+    if phone.starts_with("00") {
+        digits.drain(..2);
+    }
+
+    if digits.len() < MIN_PHONE_DIGITS {
+        return None;
+    }
+
+    Some(if international { format!("+{}", digits) } else { digits })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_email_strips_dots_and_tags() {
+        assert_eq!(normalize_email("John.Doe+work@Example.com"), "johndoe@example.com");
+        assert_eq!(normalize_email("j.o.h.n@gmail.com"), "john@gmail.com");
+        assert_eq!(normalize_email(" john@googlemail.com "), "john@gmail.com");
+    }
+
+    #[test]
+    fn test_normalize_email_keeps_domain_dots() {
+        assert_eq!(normalize_email("a@mail.example.co.uk"), "a@mail.example.co.uk");
+        assert_eq!(normalize_email("not-an-email"), "not-an-email");
+    }
+
+    #[test]
+    fn test_normalize_phone() {
+        assert_eq!(normalize_phone("+84 (912) 345-678"), Some("+84912345678".to_string()));
+        assert_eq!(normalize_phone("0084912345678"), Some("+84912345678".to_string()));
+        assert_eq!(normalize_phone("0912.345.678"), Some("0912345678".to_string()));
+        assert_eq!(normalize_phone("12-34"), None);
+    }
+}
@@ -0,0 +1,196 @@
+//! Authentication assurance levels (OpenID Connect `acr`)
+//!
+//! Every sign-in records the class it reached in the `acr` claim of its
+//! tokens: `pwd` for a single factor (password or QR login), `mfa` once a
+//! second factor was completed, and `phr` for a phishing-resistant passkey
+//! sign-in. Apps and OAuth clients may require a minimum level, and an
+//! authorization request may ask for one with `acr_values`; a session below
+//! the level has to sign in again (step-up) before it is accepted.
+//!
+//! The methods used are reported next to it in the `amr` claim, with the
+//! values registered by RFC 8176.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::utils::jwt::{ACR_MFA, ACR_PASSWORD, ACR_PHISHING_RESISTANT};
+
+/// Authentication assurance level, weakest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AcrLevel {
+    /// Password or another single factor
+    Password,
+    /// A second factor was completed
+    Mfa,
+    /// A phishing-resistant authenticator (passkey) was used
+    PhishingResistant,
+}
+
+impl AcrLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Password => ACR_PASSWORD,
+            Self::Mfa => ACR_MFA,
+            Self::PhishingResistant => ACR_PHISHING_RESISTANT,
+        }
+    }
+
+    /// Level reached by a session from its `acr` claim
+    ///
+    /// Tokens issued before the claim existed, or carrying a class this
+    /// server does not know, count as single-factor.
+    pub fn of_session(acr: Option<&str>) -> Self {
+        acr.and_then(|acr| acr.parse().ok()).unwrap_or(Self::Password)
+    }
+
+    /// Strongest level asked for in an `acr_values` list (unknown values are ignored)
+    pub fn requested(acr_values: &[String]) -> Option<Self> {
+        acr_values.iter().filter_map(|acr| acr.parse().ok()).max()
+    }
+
+    /// Whether a sign-in at this level meets `required`
+    pub fn satisfies(&self, required: Option<Self>) -> bool {
+        required.is_none_or(|required| *self >= required)
+    }
+}
+
+impl fmt::Display for AcrLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AcrLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            ACR_PASSWORD => Ok(Self::Password),
+            ACR_MFA => Ok(Self::Mfa),
+            ACR_PHISHING_RESISTANT => Ok(Self::PhishingResistant),
+            other => Err(anyhow::anyhow!(
+                "Invalid acr '{}' (expected {}, {} or {})",
+                other,
+                ACR_PASSWORD,
+                ACR_MFA,
+                ACR_PHISHING_RESISTANT
+            )),
+        }
+    }
+}
+
+/// How a user signed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignInMethod {
+    /// Password only
+    Password,
+    /// Password and a TOTP or backup code
+    PasswordAndOtp,
+    /// Password and a code texted to a verified phone
+    PasswordAndSms,
+    /// Password and an approval from a registered device
+    PasswordAndPush,
+    /// Password and a code emailed to the account address
+    PasswordAndEmail,
+    /// Code or single-use link emailed to the account address, without a password
+    EmailCode,
+    /// Emailed code or link and a second factor
+    EmailCodeAndMfa,
+    /// Passkey (WebAuthn)
+    Passkey,
+    /// Approved from another signed-in device (QR login)
+    CrossDevice,
+}
+
+impl SignInMethod {
+    /// Assurance level the sign-in reaches
+    pub fn acr(&self) -> AcrLevel {
+        match self {
+            Self::Password | Self::CrossDevice | Self::EmailCode => AcrLevel::Password,
+            Self::PasswordAndOtp
+            | Self::PasswordAndSms
+            | Self::PasswordAndPush
+            | Self::PasswordAndEmail
+            | Self::EmailCodeAndMfa => AcrLevel::Mfa,
+            Self::Passkey => AcrLevel::PhishingResistant,
+        }
+    }
+
+    /// Authentication methods references (RFC 8176) of the sign-in
+    pub fn amr(&self) -> Vec<String> {
+        let methods: &[&str] = match self {
+            Self::Password => &["pwd"],
+            Self::PasswordAndOtp => &["pwd", "otp", "mfa"],
+            Self::PasswordAndSms => &["pwd", "sms", "mfa"],
+            Self::PasswordAndPush => &["pwd", "swk", "mfa"],
+            Self::PasswordAndEmail => &["pwd", "otp", "mfa"],
+            Self::EmailCode => &["otp"],
+            Self::EmailCodeAndMfa => &["otp", "mfa"],
+            Self::Passkey => &["hwk"],
+            Self::CrossDevice => &["mca"],
+        };
+        methods.iter().map(|m| m.to_string()).collect()
+    }
+}
+
+/// Parse a stored minimum level; unknown values are treated as no requirement
+pub fn parse_required(required_acr: Option<&str>) -> Option<AcrLevel> {
+    required_acr.and_then(|acr| acr.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_are_ordered() {
+        assert!(AcrLevel::Password < AcrLevel::Mfa);
+        assert!(AcrLevel::Mfa < AcrLevel::PhishingResistant);
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        for level in [AcrLevel::Password, AcrLevel::Mfa, AcrLevel::PhishingResistant] {
+            assert_eq!(level.as_str().parse::<AcrLevel>().unwrap(), level);
+        }
+        assert!("loa3".parse::<AcrLevel>().is_err());
+    }
+
+    #[test]
+    fn test_session_level_defaults_to_password() {
+        assert_eq!(AcrLevel::of_session(None), AcrLevel::Password);
+        assert_eq!(AcrLevel::of_session(Some("unknown")), AcrLevel::Password);
+        assert_eq!(AcrLevel::of_session(Some("phr")), AcrLevel::PhishingResistant);
+    }
+
+    #[test]
+    fn test_requested_takes_strongest_known_value() {
+        let values = vec!["urn:example:loa".to_string(), "pwd".to_string(), "mfa".to_string()];
+        assert_eq!(AcrLevel::requested(&values), Some(AcrLevel::Mfa));
+        assert_eq!(AcrLevel::requested(&["loa2".to_string()]), None);
+        assert_eq!(AcrLevel::requested(&[]), None);
+    }
+
+    #[test]
+    fn test_sign_in_methods() {
+        assert_eq!(SignInMethod::Password.acr(), AcrLevel::Password);
+        assert_eq!(SignInMethod::Password.amr(), vec!["pwd"]);
+        assert_eq!(SignInMethod::PasswordAndOtp.acr(), AcrLevel::Mfa);
+        assert!(SignInMethod::PasswordAndPush.amr().contains(&"mfa".to_string()));
+        assert_eq!(SignInMethod::PasswordAndSms.acr(), AcrLevel::Mfa);
+        assert_eq!(SignInMethod::PasswordAndSms.amr(), vec!["pwd", "sms", "mfa"]);
+        assert_eq!(SignInMethod::EmailCode.acr(), AcrLevel::Password);
+        assert_eq!(SignInMethod::EmailCodeAndMfa.acr(), AcrLevel::Mfa);
+        assert!(!SignInMethod::EmailCodeAndMfa.amr().contains(&"pwd".to_string()));
+        assert_eq!(SignInMethod::Passkey.acr(), AcrLevel::PhishingResistant);
+    }
+
+    #[test]
+    fn test_satisfies() {
+        assert!(AcrLevel::Password.satisfies(None));
+        assert!(AcrLevel::PhishingResistant.satisfies(Some(AcrLevel::Mfa)));
+        assert!(AcrLevel::Mfa.satisfies(Some(AcrLevel::Mfa)));
+        assert!(!AcrLevel::Password.satisfies(Some(AcrLevel::Mfa)));
+        assert!(!AcrLevel::Mfa.satisfies(Some(AcrLevel::PhishingResistant)));
+    }
+}
@@ -0,0 +1,258 @@
+//! Fault injection for resilience tests in staging
+//!
+//! Builds with the `chaos` Cargo feature let system admins inject slow
+//! database connections, failing webhook deliveries and failing emails
+//! through `/admin/chaos`, to check retries, webhook circuit breakers and
+//! alerting end to end. In other builds the endpoints answer 404 and the
+//! hooks below return at once. Every fault clears itself after at most
+//! `MAX_FAULT_SECONDS`, and faults only affect the instance that received
+//! the request.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Whether this build can inject faults
+pub const ENABLED: bool = cfg!(feature = "chaos");
+
+/// Longest a fault may run before it clears itself
+pub const MAX_FAULT_SECONDS: i64 = 3600;
+
+/// Largest delay added to a database connection checkout, below the 30s request timeout
+pub const MAX_DB_DELAY_MS: u64 = 10_000;
+
+/// Error recorded for injected webhook and email failures
+pub const INJECTED_FAILURE: &str = "chaos: injected failure";
+
+/// Kind of fault
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultKind {
+    /// Delay every database connection checkout
+    DbLatency,
+    /// Fail webhook deliveries without sending them
+    WebhookFailure,
+    /// Fail outgoing emails without sending them
+    EmailError,
+}
+
+impl FaultKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FaultKind::DbLatency => "db_latency",
+            FaultKind::WebhookFailure => "webhook_failure",
+            FaultKind::EmailError => "email_error",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "db_latency" => Some(FaultKind::DbLatency),
+            "webhook_failure" => Some(FaultKind::WebhookFailure),
+            "email_error" => Some(FaultKind::EmailError),
+            _ => None,
+        }
+    }
+}
+
+/// A running fault
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ActiveFault {
+    pub fault: FaultKind,
+    /// Delay added to each database connection checkout (db_latency only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+    /// Share of deliveries or emails that fail, from 0 to 1 (the other faults)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_rate: Option<f64>,
+    pub started_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Running faults by kind; expired ones are dropped when next looked at
+#[derive(Debug, Default)]
+struct FaultTable {
+    faults: BTreeMap<FaultKind, ActiveFault>,
+}
+
+impl FaultTable {
+    /// Start a fault, replacing a running one of the same kind
+    fn inject(
+        &mut self,
+        fault: FaultKind,
+        delay_ms: Option<u64>,
+        failure_rate: Option<f64>,
+        duration_secs: i64,
+        now: DateTime<Utc>,
+    ) -> Result<ActiveFault, String> {
+        if !(1..=MAX_FAULT_SECONDS).contains(&duration_secs) {
+            return Err(format!("duration_seconds must be between 1 and {}", MAX_FAULT_SECONDS));
+        }
+
+        let (delay_ms, failure_rate) = match fault {
+            FaultKind::DbLatency => {
+                let delay_ms = delay_ms.ok_or("delay_ms is required for db_latency")?;
+                if !(1..=MAX_DB_DELAY_MS).contains(&delay_ms) {
+                    return Err(format!("delay_ms must be between 1 and {}", MAX_DB_DELAY_MS));
+                }
+                (Some(delay_ms), None)
+            }
+            FaultKind::WebhookFailure | FaultKind::EmailError => {
+                let failure_rate = failure_rate.unwrap_or(1.0);
+                if !(failure_rate > 0.0 && failure_rate <= 1.0) {
+                    return Err("failure_rate must be above 0 and at most 1".to_string());
+                }
+                (None, Some(failure_rate))
+            }
+        };
+
+        let active = ActiveFault {
+            fault,
+            delay_ms,
+            failure_rate,
+            started_at: now,
+            expires_at: now + Duration::seconds(duration_secs),
+        };
+        self.faults.insert(fault, active.clone());
+        Ok(active)
+    }
+
+    /// Stop a fault; false if it was not running
+    fn clear(&mut self, fault: FaultKind, now: DateTime<Utc>) -> bool {
+        self.expire(now);
+        self.faults.remove(&fault).is_some()
+    }
+
+    /// Faults still running at `now`
+    fn active(&mut self, now: DateTime<Utc>) -> Vec<ActiveFault> {
+        self.expire(now);
+        self.faults.values().cloned().collect()
+    }
+
+    /// The fault of this kind, if running at `now`
+    fn get(&mut self, fault: FaultKind, now: DateTime<Utc>) -> Option<&ActiveFault> {
+        self.expire(now);
+        self.faults.get(&fault)
+    }
+
+    /// Whether an operation hit by a failure fault fails; `roll` is uniform in [0, 1)
+    fn fails(&mut self, fault: FaultKind, now: DateTime<Utc>, roll: f64) -> bool {
+        self.get(fault, now)
+            .and_then(|active| active.failure_rate)
+            .is_some_and(|rate| roll < rate)
+    }
+
+    fn expire(&mut self, now: DateTime<Utc>) {
+        self.faults.retain(|_, active| active.expires_at > now);
+    }
+}
+
+static FAULTS: Mutex<Option<FaultTable>> = Mutex::new(None);
+
+fn with_faults<R>(f: impl FnOnce(&mut FaultTable) -> R) -> R {
+    let mut faults = FAULTS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(faults.get_or_insert_with(FaultTable::default))
+}
+
+/// Start a fault on this instance for `duration_secs`
+pub fn inject(
+    fault: FaultKind,
+    delay_ms: Option<u64>,
+    failure_rate: Option<f64>,
+    duration_secs: i64,
+) -> Result<ActiveFault, String> {
+    with_faults(|faults| faults.inject(fault, delay_ms, failure_rate, duration_secs, Utc::now()))
+}
+
+/// Stop a fault before it expires
+pub fn clear(fault: FaultKind) -> bool {
+    with_faults(|faults| faults.clear(fault, Utc::now()))
+}
+
+/// Faults running on this instance
+pub fn active() -> Vec<ActiveFault> {
+    with_faults(|faults| faults.active(Utc::now()))
+}
+
+/// Hook: wait out the injected database latency, if any
+pub async fn delay_db() {
+    if !ENABLED {
+        return;
+    }
+
+    let delay_ms = with_faults(|faults| {
+        faults
+            .get(FaultKind::DbLatency, Utc::now())
+            .and_then(|active| active.delay_ms)
+    });
+    if let Some(delay_ms) = delay_ms {
+        tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+    }
+}
+
+/// Hook: whether this webhook delivery should fail without being sent
+pub fn webhook_fails() -> bool {
+    ENABLED && with_faults(|faults| faults.fails(FaultKind::WebhookFailure, Utc::now(), rand::random()))
+}
+
+/// Hook: whether this email should fail without being sent
+pub fn email_fails() -> bool {
+    ENABLED && with_faults(|faults| faults.fails(FaultKind::EmailError, Utc::now(), rand::random()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fault_expires_after_its_duration() {
+        let mut faults = FaultTable::default();
+        let now = Utc::now();
+
+        faults.inject(FaultKind::WebhookFailure, None, None, 60, now).unwrap();
+
+        assert!(faults.fails(FaultKind::WebhookFailure, now + Duration::seconds(59), 0.99));
+        assert!(!faults.fails(FaultKind::WebhookFailure, now + Duration::seconds(60), 0.0));
+        assert!(faults.active(now + Duration::seconds(60)).is_empty());
+    }
+
+    #[test]
+    fn test_failure_rate_applies_to_the_roll() {
+        let mut faults = FaultTable::default();
+        let now = Utc::now();
+
+        faults.inject(FaultKind::EmailError, None, Some(0.25), 60, now).unwrap();
+
+        assert!(faults.fails(FaultKind::EmailError, now, 0.1));
+        assert!(!faults.fails(FaultKind::EmailError, now, 0.5));
+        assert!(!faults.fails(FaultKind::WebhookFailure, now, 0.0));
+    }
+
+    #[test]
+    fn test_inject_rejects_unbounded_or_invalid_faults() {
+        let mut faults = FaultTable::default();
+        let now = Utc::now();
+
+        assert!(faults.inject(FaultKind::EmailError, None, None, MAX_FAULT_SECONDS + 1, now).is_err());
+        assert!(faults.inject(FaultKind::EmailError, None, None, 0, now).is_err());
+        assert!(faults.inject(FaultKind::EmailError, None, Some(1.5), 60, now).is_err());
+        assert!(faults.inject(FaultKind::DbLatency, None, None, 60, now).is_err());
+        assert!(faults.inject(FaultKind::DbLatency, Some(MAX_DB_DELAY_MS + 1), None, 60, now).is_err());
+        assert!(faults.active(now).is_empty());
+    }
+
+    #[test]
+    fn test_inject_replaces_and_clear_stops() {
+        let mut faults = FaultTable::default();
+        let now = Utc::now();
+
+        faults.inject(FaultKind::DbLatency, Some(100), None, 60, now).unwrap();
+        faults.inject(FaultKind::DbLatency, Some(250), None, 60, now).unwrap();
+
+        assert_eq!(faults.get(FaultKind::DbLatency, now).and_then(|f| f.delay_ms), Some(250));
+        assert!(faults.clear(FaultKind::DbLatency, now));
+        assert!(!faults.clear(FaultKind::DbLatency, now));
+    }
+}
@@ -0,0 +1,249 @@
+//! Size guard and compact representations for user access token claims
+//!
+//! A user with roles in many apps carries every app's roles and permissions
+//! in the access token, which can outgrow the header limits of proxies and
+//! load balancers (often 8 KB for all request headers). The encoded size of
+//! each access token is estimated before signing: above the warning
+//! threshold it is logged, above the maximum the token is not issued.
+//!
+//! The claims mode trades completeness for size. `roles_only` drops the
+//! permissions and keeps the roles; `compressed` replaces the whole `apps`
+//! object. Both set `apps_ref`, a digest of the full app claims, which
+//! relying apps resolve through `GET /auth/claims`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::Header;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::AuthError;
+use crate::utils::jwt::AppClaims;
+
+/// How the apps of a user are represented in access tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaimsMode {
+    /// Roles and permissions of every app
+    Full,
+    /// Roles of every app, permissions resolved on demand
+    RolesOnly,
+    /// Only `apps_ref`, everything resolved on demand
+    Compressed,
+}
+
+impl ClaimsMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::RolesOnly => "roles_only",
+            Self::Compressed => "compressed",
+        }
+    }
+
+    /// Reduce `apps` to this representation
+    ///
+    /// Returns the digest of the full app claims when anything was left out.
+    pub fn apply(&self, apps: &mut HashMap<String, AppClaims>) -> Option<String> {
+        match self {
+            Self::Full => None,
+            Self::RolesOnly => {
+                let apps_ref = apps_digest(apps);
+                for app in apps.values_mut() {
+                    app.permissions.clear();
+                }
+                Some(apps_ref)
+            }
+            Self::Compressed => {
+                let apps_ref = apps_digest(apps);
+                apps.clear();
+                Some(apps_ref)
+            }
+        }
+    }
+}
+
+impl FromStr for ClaimsMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "full" => Ok(Self::Full),
+            "roles_only" => Ok(Self::RolesOnly),
+            "compressed" => Ok(Self::Compressed),
+            other => Err(anyhow::anyhow!(
+                "Invalid claims mode '{}' (expected full, roles_only or compressed)",
+                other
+            )),
+        }
+    }
+}
+
+/// Server-wide access token size policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClaimsSizePolicy {
+    pub mode: ClaimsMode,
+    /// Log a warning for larger tokens (0 = never)
+    pub warn_bytes: usize,
+    /// Refuse to issue larger tokens (0 = no limit)
+    pub max_bytes: usize,
+}
+
+impl Default for ClaimsSizePolicy {
+    fn default() -> Self {
+        Self {
+            mode: ClaimsMode::Full,
+            warn_bytes: 0,
+            max_bytes: 0,
+        }
+    }
+}
+
+impl ClaimsSizePolicy {
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        Self {
+            mode: config.jwt_claims_mode,
+            warn_bytes: config.jwt_claims_warn_bytes,
+            max_bytes: config.jwt_claims_max_bytes,
+        }
+    }
+
+    /// Check the estimated size of a user's access token
+    pub fn check(&self, size: usize, user_id: &str) -> Result<(), AuthError> {
+        if self.max_bytes > 0 && size > self.max_bytes {
+            tracing::error!(
+                "Access token for user {} would be {} bytes (limit {}, claims mode {}); consider JWT_CLAIMS_MODE=roles_only or compressed",
+                user_id,
+                size,
+                self.max_bytes,
+                self.mode.as_str()
+            );
+            return Err(AuthError::TokenTooLarge { size, limit: self.max_bytes });
+        }
+
+        if self.warn_bytes > 0 && size > self.warn_bytes {
+            tracing::warn!(
+                "Access token for user {} is {} bytes (warning threshold {}, claims mode {})",
+                user_id,
+                size,
+                self.warn_bytes,
+                self.mode.as_str()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Length of `len` bytes encoded as unpadded base64url
+fn base64url_len(len: usize) -> usize {
+    (len * 4).div_ceil(3)
+}
+
+/// Encoded size of a JWT with this header and claims
+///
+/// `signature_len` is the length of the encoded signature; for RS256 it is
+/// the length of the key's base64url modulus.
+pub fn estimate_token_size<T: Serialize>(header: &Header, claims: &T, signature_len: usize) -> usize {
+    let header_len = serde_json::to_vec(header).map(|h| h.len()).unwrap_or(0);
+    let claims_len = serde_json::to_vec(claims).map(|c| c.len()).unwrap_or(0);
+
+    base64url_len(header_len) + 1 + base64url_len(claims_len) + 1 + signature_len
+}
+
+/// Digest of a user's app claims, independent of ordering
+pub fn apps_digest(apps: &HashMap<String, AppClaims>) -> String {
+    let canonical: BTreeMap<&str, (BTreeSet<&str>, BTreeSet<&str>)> = apps
+        .iter()
+        .map(|(code, app)| {
+            (
+                code.as_str(),
+                (
+                    app.roles.iter().map(String::as_str).collect(),
+                    app.permissions.iter().map(String::as_str).collect(),
+                ),
+            )
+        })
+        .collect();
+
+    let json = serde_json::to_vec(&canonical).unwrap_or_default();
+    URL_SAFE_NO_PAD.encode(&Sha256::digest(&json)[..16])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_apps() -> HashMap<String, AppClaims> {
+        let mut apps = HashMap::new();
+        apps.insert(
+            "app_a".to_string(),
+            AppClaims {
+                roles: vec!["admin".to_string(), "user".to_string()],
+                permissions: vec!["read".to_string(), "write".to_string()],
+            },
+        );
+        apps.insert(
+            "app_b".to_string(),
+            AppClaims {
+                roles: vec!["viewer".to_string()],
+                permissions: vec!["read".to_string()],
+            },
+        );
+        apps
+    }
+
+    #[test]
+    fn test_apps_digest_ignores_order() {
+        let apps = sample_apps();
+        let mut reordered = sample_apps();
+        reordered.get_mut("app_a").unwrap().permissions.reverse();
+
+        assert_eq!(apps_digest(&apps), apps_digest(&reordered));
+
+        reordered.get_mut("app_b").unwrap().permissions.push("delete".to_string());
+        assert_ne!(apps_digest(&apps), apps_digest(&reordered));
+    }
+
+    #[test]
+    fn test_modes_reduce_apps() {
+        let digest = apps_digest(&sample_apps());
+
+        let mut apps = sample_apps();
+        assert_eq!(ClaimsMode::Full.apply(&mut apps), None);
+        assert_eq!(apps, sample_apps());
+
+        let mut apps = sample_apps();
+        assert_eq!(ClaimsMode::RolesOnly.apply(&mut apps), Some(digest.clone()));
+        assert_eq!(apps["app_a"].roles, vec!["admin", "user"]);
+        assert!(apps.values().all(|app| app.permissions.is_empty()));
+
+        let mut apps = sample_apps();
+        assert_eq!(ClaimsMode::Compressed.apply(&mut apps), Some(digest));
+        assert!(apps.is_empty());
+    }
+
+    #[test]
+    fn test_size_policy_thresholds() {
+        let policy = ClaimsSizePolicy {
+            mode: ClaimsMode::Full,
+            warn_bytes: 100,
+            max_bytes: 200,
+        };
+
+        assert!(policy.check(150, "user").is_ok());
+        assert!(matches!(
+            policy.check(201, "user"),
+            Err(AuthError::TokenTooLarge { size: 201, limit: 200 })
+        ));
+        assert!(ClaimsSizePolicy::default().check(usize::MAX, "user").is_ok());
+    }
+
+    #[test]
+    fn test_parse_claims_mode() {
+        assert_eq!("Roles_Only".parse::<ClaimsMode>().unwrap(), ClaimsMode::RolesOnly);
+        assert!("gzip".parse::<ClaimsMode>().is_err());
+    }
+}
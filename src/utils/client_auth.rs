@@ -0,0 +1,237 @@
+//! Client authentication and request body parsing for OAuth endpoints
+//!
+//! RFC 6749 Section 2.3.1 lets a client send its credentials either in the
+//! request body or with HTTP Basic authentication, where the client_id and
+//! client_secret are form-urlencoded before being joined with ':'. A client
+//! must not use more than one method in the same request.
+//!
+//! The token-style endpoints are specified as form-encoded, but many client
+//! libraries post JSON, so [`OAuthBody`] accepts either based on Content-Type.
+//! In strict mode (`OAUTH_STRICT`) only the form encoding is accepted.
+
+use axum::extract::{FromRef, FromRequest, Request};
+use axum::http::{header, HeaderMap};
+use axum::{Form, Json};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::de::DeserializeOwned;
+
+use crate::config::AppState;
+use crate::error::OAuthError;
+
+/// Request body of an OAuth endpoint, form-encoded or JSON
+///
+/// JSON is used when the Content-Type is `application/json`; anything else
+/// is parsed as `application/x-www-form-urlencoded`. In strict mode any
+/// other Content-Type is rejected. Parse failures are reported as
+/// `invalid_request` rather than axum's plain-text rejections.
+#[derive(Debug, Clone)]
+pub struct OAuthBody<T>(pub T);
+
+impl<T, S> FromRequest<S> for OAuthBody<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+    AppState: FromRef<S>,
+{
+    type Rejection = OAuthError;
+
+    fn from_request<'life0, 'async_trait>(
+        req: Request,
+        state: &'life0 S,
+    ) -> core::pin::Pin<
+        Box<dyn core::future::Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>,
+    >
+    where
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            let content_type = media_type(req.headers());
+            if AppState::from_ref(state).config.oauth_strict
+                && content_type.as_deref() != Some(FORM_MEDIA_TYPE)
+            {
+                return Err(OAuthError::InvalidRequest(format!(
+                    "Content-Type must be {}",
+                    FORM_MEDIA_TYPE
+                )));
+            }
+
+            if content_type.as_deref() == Some(JSON_MEDIA_TYPE) {
+                Json::<T>::from_request(req, state)
+                    .await
+                    .map(|Json(body)| OAuthBody(body))
+                    .map_err(|rejection| OAuthError::InvalidRequest(rejection.body_text()))
+            } else {
+                Form::<T>::from_request(req, state)
+                    .await
+                    .map(|Form(body)| OAuthBody(body))
+                    .map_err(|rejection| OAuthError::InvalidRequest(rejection.body_text()))
+            }
+        })
+    }
+}
+
+const FORM_MEDIA_TYPE: &str = "application/x-www-form-urlencoded";
+const JSON_MEDIA_TYPE: &str = "application/json";
+
+/// Media type of the request body, lowercased and without parameters
+fn media_type(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .map(|mime| mime.trim().to_ascii_lowercase())
+}
+
+/// Client credentials from an `Authorization: Basic` header
+///
+/// Returns `None` when the request carries no Basic credentials (including
+/// Bearer authorization), and `invalid_client` when the header is malformed.
+pub fn basic_client_credentials(
+    headers: &HeaderMap,
+) -> Result<Option<(String, String)>, OAuthError> {
+    let value = match headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+
+    let encoded = match value.split_once(' ') {
+        Some((scheme, rest)) if scheme.eq_ignore_ascii_case("basic") => rest.trim(),
+        _ => return Ok(None),
+    };
+
+    let decoded = STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or(OAuthError::InvalidClient)?;
+
+    let (client_id, client_secret) = decoded.split_once(':').ok_or(OAuthError::InvalidClient)?;
+    let client_id = form_urldecode(client_id)?;
+    if client_id.is_empty() {
+        return Err(OAuthError::InvalidClient);
+    }
+
+    Ok(Some((client_id, form_urldecode(client_secret)?)))
+}
+
+/// Resolve the client credentials of a request
+///
+/// Merges Basic credentials with the client_id/client_secret from the body.
+/// Sending a secret both ways, or a body client_id that differs from the
+/// Basic one, is rejected as `invalid_request`.
+pub fn client_credentials(
+    headers: &HeaderMap,
+    body_client_id: Option<String>,
+    body_client_secret: Option<String>,
+) -> Result<(Option<String>, Option<String>), OAuthError> {
+    let (basic_id, basic_secret) = match basic_client_credentials(headers)? {
+        Some(credentials) => credentials,
+        None => return Ok((body_client_id, body_client_secret)),
+    };
+
+    if body_client_secret.is_some() {
+        return Err(OAuthError::InvalidRequest(
+            "Only one client authentication method may be used".to_string(),
+        ));
+    }
+    if body_client_id.is_some_and(|id| id != basic_id) {
+        return Err(OAuthError::InvalidRequest(
+            "client_id does not match the authenticated client".to_string(),
+        ));
+    }
+
+    Ok((Some(basic_id), Some(basic_secret)))
+}
+
+/// Decode an `application/x-www-form-urlencoded` value
+fn form_urldecode(value: &str) -> Result<String, OAuthError> {
+    urlencoding::decode(&value.replace('+', " "))
+        .map(|decoded| decoded.into_owned())
+        .map_err(|_| OAuthError::InvalidClient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn basic(credentials: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let value = format!("Basic {}", STANDARD.encode(credentials));
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_str(&value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_basic_credentials_are_form_urldecoded() {
+        let headers = basic("my%20app:s3cr%3At+x");
+        let (id, secret) = basic_client_credentials(&headers).unwrap().unwrap();
+        assert_eq!(id, "my app");
+        assert_eq!(secret, "s3cr:t x");
+    }
+
+    #[test]
+    fn test_non_basic_authorization_is_ignored() {
+        let mut headers = HeaderMap::new();
+        assert!(basic_client_credentials(&headers).unwrap().is_none());
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer abc"));
+        assert!(basic_client_credentials(&headers).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_malformed_basic_is_invalid_client() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Basic !!!"));
+        assert!(matches!(basic_client_credentials(&headers), Err(OAuthError::InvalidClient)));
+
+        let headers = basic("no-colon");
+        assert!(matches!(basic_client_credentials(&headers), Err(OAuthError::InvalidClient)));
+    }
+
+    #[test]
+    fn test_client_credentials_prefers_basic() {
+        let headers = basic("client:secret");
+        let (id, secret) = client_credentials(&headers, Some("client".into()), None).unwrap();
+        assert_eq!(id.as_deref(), Some("client"));
+        assert_eq!(secret.as_deref(), Some("secret"));
+
+        let (id, secret) =
+            client_credentials(&HeaderMap::new(), Some("body".into()), Some("pw".into())).unwrap();
+        assert_eq!(id.as_deref(), Some("body"));
+        assert_eq!(secret.as_deref(), Some("pw"));
+    }
+
+    #[test]
+    fn test_client_credentials_rejects_mixed_methods() {
+        let headers = basic("client:secret");
+        assert!(matches!(
+            client_credentials(&headers, None, Some("secret".into())),
+            Err(OAuthError::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            client_credentials(&headers, Some("other".into()), None),
+            Err(OAuthError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_media_type_ignores_parameters_and_case() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(media_type(&headers), None);
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("Application/JSON; charset=utf-8"),
+        );
+        assert_eq!(media_type(&headers).as_deref(), Some(JSON_MEDIA_TYPE));
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+        assert_eq!(media_type(&headers).as_deref(), Some(FORM_MEDIA_TYPE));
+    }
+}
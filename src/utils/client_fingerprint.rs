@@ -0,0 +1,221 @@
+//! Lightweight client fingerprint for user refresh tokens
+//!
+//! A refresh token is bound to the client that logged in: its user-agent
+//! family (browser or client product plus OS) and a coarse prefix of its IP
+//! address (the /16 by default for IPv4, the /64 for IPv6). On refresh the
+//! presented client is compared with the one recorded on the session; the
+//! configured mode decides whether a mismatch is only audited or rejected.
+//! Neither part identifies a device - they just make a stolen token less
+//! useful from somewhere else.
+
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::utils::token_binding::ip_range_for;
+
+/// How a refresh from a client with a different fingerprint is handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FingerprintMode {
+    /// Not checked
+    Off,
+    /// Allowed, logged and audited
+    Warn,
+    /// Rejected with `token_binding_mismatch` and audited
+    Reject,
+}
+
+impl FingerprintMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Warn => "warn",
+            Self::Reject => "reject",
+        }
+    }
+}
+
+impl FromStr for FingerprintMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "warn" => Ok(Self::Warn),
+            "reject" => Ok(Self::Reject),
+            other => Err(anyhow::anyhow!("Invalid fingerprint mode '{}' (expected off, warn or reject)", other)),
+        }
+    }
+}
+
+/// Server-wide fingerprint policy for user refresh tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FingerprintPolicy {
+    pub mode: FingerprintMode,
+    /// IPv4 prefix length compared (IPv6 always compares the /64)
+    pub ipv4_prefix: u32,
+}
+
+impl Default for FingerprintPolicy {
+    fn default() -> Self {
+        Self {
+            mode: FingerprintMode::Off,
+            ipv4_prefix: 16,
+        }
+    }
+}
+
+impl FingerprintPolicy {
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        Self {
+            mode: config.refresh_fingerprint_mode,
+            ipv4_prefix: config.refresh_fingerprint_ipv4_prefix,
+        }
+    }
+}
+
+/// User-agent family and IP prefix of a client
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ClientFingerprint {
+    pub user_agent_family: Option<String>,
+    pub ip_prefix: Option<String>,
+}
+
+impl ClientFingerprint {
+    pub fn new(user_agent: Option<&str>, ip: Option<&str>, ipv4_prefix: u32) -> Self {
+        Self {
+            user_agent_family: user_agent.and_then(user_agent_family),
+            ip_prefix: ip.and_then(|ip| ip_range_for(ip.trim(), Some(ipv4_prefix))),
+        }
+    }
+
+    /// Parts that differ from `presented` ("user_agent", "ip")
+    ///
+    /// A part unknown on either side (no User-Agent, no forwarded IP, or a
+    /// session created before it was recorded) is not compared.
+    pub fn mismatches(&self, presented: &ClientFingerprint) -> Vec<&'static str> {
+        let mut parts = Vec::new();
+
+        if differs(&self.user_agent_family, &presented.user_agent_family) {
+            parts.push("user_agent");
+        }
+        if differs(&self.ip_prefix, &presented.ip_prefix) {
+            parts.push("ip");
+        }
+
+        parts
+    }
+}
+
+fn differs(recorded: &Option<String>, presented: &Option<String>) -> bool {
+    matches!((recorded, presented), (Some(a), Some(b)) if a != b)
+}
+
+/// Browser (or client product) and OS family of a User-Agent, e.g. `chrome/windows`
+///
+/// Versions are ignored so browser updates don't count as a different client.
+pub fn user_agent_family(user_agent: &str) -> Option<String> {
+    let ua = user_agent.trim();
+    if ua.is_empty() {
+        return None;
+    }
+
+    let lower = ua.to_ascii_lowercase();
+
+    // Order matters: Edge and Opera also claim Chrome, Chrome also claims Safari
+    let client = if lower.contains("edg/") || lower.contains("edge/") {
+        "edge".to_string()
+    } else if lower.contains("opr/") || lower.contains("opera") {
+        "opera".to_string()
+    } else if lower.contains("firefox/") || lower.contains("fxios/") {
+        "firefox".to_string()
+    } else if lower.contains("chrome/") || lower.contains("crios/") || lower.contains("chromium/") {
+        "chrome".to_string()
+    } else if lower.contains("safari/") {
+        "safari".to_string()
+    } else {
+        // Non-browser clients: product token without version, e.g. okhttp/4.9 -> okhttp
+        lower.split(['/', ' ']).next().unwrap_or_default().to_string()
+    };
+
+    // iOS before macOS (iPhone UAs say "like Mac OS X"), Android before Linux
+    let os = if lower.contains("iphone") || lower.contains("ipad") || lower.contains("ios") {
+        "ios"
+    } else if lower.contains("android") {
+        "android"
+    } else if lower.contains("windows") {
+        "windows"
+    } else if lower.contains("mac os") || lower.contains("macintosh") {
+        "macos"
+    } else if lower.contains("cros") {
+        "chromeos"
+    } else if lower.contains("linux") {
+        "linux"
+    } else {
+        "other"
+    };
+
+    Some(format!("{}/{}", client, os))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHROME_WIN: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+    const CHROME_WIN_NEWER: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36";
+    const EDGE_WIN: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.2210.91";
+    const SAFARI_IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 Mobile/15E148 Safari/604.1";
+    const FIREFOX_LINUX: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0";
+
+    #[test]
+    fn test_user_agent_family() {
+        assert_eq!(user_agent_family(CHROME_WIN).as_deref(), Some("chrome/windows"));
+        assert_eq!(user_agent_family(EDGE_WIN).as_deref(), Some("edge/windows"));
+        assert_eq!(user_agent_family(SAFARI_IPHONE).as_deref(), Some("safari/ios"));
+        assert_eq!(user_agent_family(FIREFOX_LINUX).as_deref(), Some("firefox/linux"));
+        assert_eq!(user_agent_family("okhttp/4.9.3").as_deref(), Some("okhttp/other"));
+        assert_eq!(user_agent_family("  "), None);
+
+        // Browser updates keep the family
+        assert_eq!(user_agent_family(CHROME_WIN), user_agent_family(CHROME_WIN_NEWER));
+    }
+
+    #[test]
+    fn test_mismatches() {
+        let recorded = ClientFingerprint::new(Some(CHROME_WIN), Some("203.0.113.7"), 16);
+
+        // Same /16, newer browser version
+        let same = ClientFingerprint::new(Some(CHROME_WIN_NEWER), Some("203.0.200.1"), 16);
+        assert!(recorded.mismatches(&same).is_empty());
+
+        let other_network = ClientFingerprint::new(Some(CHROME_WIN), Some("198.51.100.1"), 16);
+        assert_eq!(recorded.mismatches(&other_network), vec!["ip"]);
+
+        let other_client = ClientFingerprint::new(Some("curl/8.4.0"), Some("198.51.100.1"), 16);
+        assert_eq!(recorded.mismatches(&other_client), vec!["user_agent", "ip"]);
+
+        // Unknown parts are not compared
+        let anonymous = ClientFingerprint::new(None, None, 16);
+        assert!(recorded.mismatches(&anonymous).is_empty());
+        assert!(anonymous.mismatches(&other_client).is_empty());
+    }
+
+    #[test]
+    fn test_ipv6_prefix() {
+        let recorded = ClientFingerprint::new(None, Some("2001:db8:1:2::10"), 16);
+        let same_64 = ClientFingerprint::new(None, Some("2001:db8:1:2::ffff"), 16);
+        let other_64 = ClientFingerprint::new(None, Some("2001:db8:1:3::10"), 16);
+
+        assert!(recorded.mismatches(&same_64).is_empty());
+        assert_eq!(recorded.mismatches(&other_64), vec!["ip"]);
+    }
+
+    #[test]
+    fn test_mode_from_str() {
+        assert_eq!("Reject".parse::<FingerprintMode>().unwrap(), FingerprintMode::Reject);
+        assert_eq!(" warn ".parse::<FingerprintMode>().unwrap(), FingerprintMode::Warn);
+        assert!("strict".parse::<FingerprintMode>().is_err());
+    }
+}
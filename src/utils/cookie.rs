@@ -0,0 +1,226 @@
+//! Cookie utilities for browser-based (SPA) token delivery
+//!
+//! Refresh tokens for SPAs are kept in an HttpOnly cookie so they are never
+//! readable from JavaScript. Because the browser attaches that cookie
+//! automatically, requests that use it must also pass a double-submit CSRF
+//! check: a random value is set in a readable cookie and the client echoes it
+//! back in the `X-CSRF-Token` header.
+//!
+//! The hosted login page can also hand its session to an HttpOnly SSO
+//! cookie, scoped to a parent domain, so first-party apps on its subdomains
+//! continue the session instead of asking the user to sign in again.
+//!
+//! Authorization codes are also bound to the browser that approved them
+//! through the HttpOnly `oauth_browser` cookie, so a code injected into a
+//! different browser's callback cannot be redeemed from there.
+//!
+//! A browser trusted after completing MFA keeps the HttpOnly
+//! `trusted_device` cookie, which lets later sign-ins skip the challenge.
+
+use axum::http::{header, HeaderMap};
+
+use crate::config::Config;
+use crate::utils::secret::{constant_time_compare, generate_oauth_token};
+
+/// Name of the readable cookie holding the CSRF token
+pub const CSRF_COOKIE_NAME: &str = "csrf_token";
+
+/// Header the client must echo the CSRF token in
+pub const CSRF_HEADER_NAME: &str = "x-csrf-token";
+
+/// Name of the HttpOnly cookie identifying the browser that approves authorization codes
+pub const BROWSER_BINDING_COOKIE_NAME: &str = "oauth_browser";
+
+/// Name of the HttpOnly cookie identifying a device trusted to skip MFA
+pub const TRUSTED_DEVICE_COOKIE_NAME: &str = "trusted_device";
+
+/// Settings for the refresh token cookie
+#[derive(Debug, Clone)]
+pub struct RefreshCookieSettings {
+    pub name: String,
+    pub domain: Option<String>,
+    pub path: String,
+    pub secure: bool,
+    pub same_site: String,
+}
+
+impl RefreshCookieSettings {
+    /// Build the cookie settings from server configuration
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            name: config.refresh_cookie_name.clone(),
+            domain: config.refresh_cookie_domain.clone(),
+            path: config.refresh_cookie_path.clone(),
+            secure: config.refresh_cookie_secure,
+            same_site: config.refresh_cookie_same_site.clone(),
+        }
+    }
+
+    /// Settings for the SSO session cookie
+    ///
+    /// Same path and attributes as the refresh cookie, under its own name and
+    /// domain so it can be shared by sibling subdomains.
+    pub fn sso_from_config(config: &Config) -> Self {
+        Self {
+            name: config.sso_cookie_name.clone(),
+            domain: config.sso_cookie_domain.clone(),
+            ..Self::from_config(config)
+        }
+    }
+
+    /// Cookie name used for an OAuth client's refresh token
+    ///
+    /// Each client gets its own cookie so several SPAs can share a domain.
+    pub fn oauth_cookie_name(&self, client_id: &str) -> String {
+        format!("{}_{}", self.name, client_id)
+    }
+
+    /// Build a `Set-Cookie` value for a refresh token
+    pub fn refresh_cookie(&self, name: &str, token: &str, max_age_secs: i64) -> String {
+        self.build(name, token, max_age_secs, true)
+    }
+
+    /// Build a `Set-Cookie` value for the CSRF token (readable by JavaScript)
+    pub fn csrf_cookie(&self, token: &str, max_age_secs: i64) -> String {
+        self.build(CSRF_COOKIE_NAME, token, max_age_secs, false)
+    }
+
+    /// Build a `Set-Cookie` value for the browser binding of authorization codes
+    pub fn browser_binding_cookie(&self, value: &str, max_age_secs: i64) -> String {
+        self.build(BROWSER_BINDING_COOKIE_NAME, value, max_age_secs, true)
+    }
+
+    /// Build a `Set-Cookie` value for a device trusted to skip MFA
+    pub fn trusted_device_cookie(&self, value: &str, max_age_secs: i64) -> String {
+        self.build(TRUSTED_DEVICE_COOKIE_NAME, value, max_age_secs, true)
+    }
+
+    /// Build a `Set-Cookie` value that removes a cookie
+    pub fn clear_cookie(&self, name: &str) -> String {
+        self.build(name, "", 0, true)
+    }
+
+    fn build(&self, name: &str, value: &str, max_age_secs: i64, http_only: bool) -> String {
+        let mut cookie = format!(
+            "{}={}; Path={}; Max-Age={}; SameSite={}",
+            name, value, self.path, max_age_secs.max(0), self.same_site
+        );
+        if let Some(domain) = &self.domain {
+            cookie.push_str(&format!("; Domain={}", domain));
+        }
+        if self.secure {
+            cookie.push_str("; Secure");
+        }
+        if http_only {
+            cookie.push_str("; HttpOnly");
+        }
+        cookie
+    }
+}
+
+/// Read a cookie value from the request headers
+pub fn get_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// The request's browser binding, or a new one to set on the response
+pub fn browser_binding(headers: &HeaderMap) -> String {
+    get_cookie(headers, BROWSER_BINDING_COOKIE_NAME).unwrap_or_else(generate_oauth_token)
+}
+
+/// Generate a new random CSRF token
+pub fn generate_csrf_token() -> String {
+    generate_oauth_token()
+}
+
+/// Verify the double-submit CSRF check: the header must match the cookie
+pub fn verify_csrf(headers: &HeaderMap) -> bool {
+    let cookie = match get_cookie(headers, CSRF_COOKIE_NAME) {
+        Some(c) => c,
+        None => return false,
+    };
+
+    headers
+        .get(CSRF_HEADER_NAME)
+        .and_then(|v| v.to_str().ok())
+        .map(|h| constant_time_compare(h, &cookie))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn settings() -> RefreshCookieSettings {
+        RefreshCookieSettings {
+            name: "refresh_token".to_string(),
+            domain: Some("example.com".to_string()),
+            path: "/".to_string(),
+            secure: true,
+            same_site: "Strict".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_get_cookie() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, HeaderValue::from_static("a=1; refresh_token=abc; b=2"));
+
+        assert_eq!(get_cookie(&headers, "refresh_token"), Some("abc".to_string()));
+        assert_eq!(get_cookie(&headers, "missing"), None);
+    }
+
+    #[test]
+    fn test_refresh_cookie_is_http_only() {
+        let cookie = settings().refresh_cookie("refresh_token", "abc", 60);
+
+        assert!(cookie.starts_with("refresh_token=abc;"));
+        assert!(cookie.contains("HttpOnly"));
+        assert!(cookie.contains("Secure"));
+        assert!(cookie.contains("Domain=example.com"));
+        assert!(cookie.contains("SameSite=Strict"));
+    }
+
+    #[test]
+    fn test_csrf_cookie_is_readable() {
+        let cookie = settings().csrf_cookie("xyz", 60);
+
+        assert!(cookie.starts_with("csrf_token=xyz;"));
+        assert!(!cookie.contains("HttpOnly"));
+    }
+
+    #[test]
+    fn test_browser_binding_reuses_cookie() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, HeaderValue::from_static("oauth_browser=b1"));
+
+        assert_eq!(browser_binding(&headers), "b1");
+        assert_ne!(browser_binding(&HeaderMap::new()), browser_binding(&HeaderMap::new()));
+
+        let cookie = settings().browser_binding_cookie("b1", 60);
+        assert!(cookie.starts_with("oauth_browser=b1;"));
+        assert!(cookie.contains("HttpOnly"));
+    }
+
+    #[test]
+    fn test_verify_csrf() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, HeaderValue::from_static("csrf_token=token123"));
+        assert!(!verify_csrf(&headers));
+
+        headers.insert(CSRF_HEADER_NAME, HeaderValue::from_static("token123"));
+        assert!(verify_csrf(&headers));
+
+        headers.insert(CSRF_HEADER_NAME, HeaderValue::from_static("other"));
+        assert!(!verify_csrf(&headers));
+    }
+}
@@ -0,0 +1,168 @@
+//! Cooling-off after credential changes
+//!
+//! Someone who took over an account usually changes its email, resets its
+//! password or removes its MFA first, then cashes in. For
+//! `COOLING_OFF_HOURS` after any of these changes, high-risk actions are
+//! refused with `cooling_off`: creating API keys, consenting to a client the
+//! user has not used before and granting any of the `COOLING_OFF_SCOPES`
+//! (payouts, transfers, ...) to a client. The user is emailed when the period
+//! starts and whenever an action is refused, so the owner of a taken-over
+//! account finds out while the damage is still limited.
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+/// Credential change that starts a cooling-off period
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoolingOffReason {
+    EmailChanged,
+    PasswordReset,
+    MfaReset,
+}
+
+impl CoolingOffReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EmailChanged => "email_changed",
+            Self::PasswordReset => "password_reset",
+            Self::MfaReset => "mfa_reset",
+        }
+    }
+
+    /// How the change is described to the user
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::EmailChanged => "the email address of your account was changed",
+            Self::PasswordReset => "your password was reset",
+            Self::MfaReset => "two-factor authentication was removed from your account",
+        }
+    }
+}
+
+impl FromStr for CoolingOffReason {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "email_changed" => Ok(Self::EmailChanged),
+            "password_reset" => Ok(Self::PasswordReset),
+            "mfa_reset" => Ok(Self::MfaReset),
+            other => Err(anyhow::anyhow!("Unknown cooling-off reason '{}'", other)),
+        }
+    }
+}
+
+/// Action refused during a cooling-off period
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HighRiskAction {
+    ApiKeyCreation,
+    NewClientConsent,
+    HighRiskScope,
+}
+
+impl HighRiskAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ApiKeyCreation => "api_key_creation",
+            Self::NewClientConsent => "new_client_consent",
+            Self::HighRiskScope => "high_risk_scope",
+        }
+    }
+
+    /// How the action is described to the user
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::ApiKeyCreation => "creating an API key",
+            Self::NewClientConsent => "connecting a new application",
+            Self::HighRiskScope => "granting a high-risk permission to an application",
+        }
+    }
+}
+
+/// Cooling-off settings
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoolingOffPolicy {
+    /// Length of the period (0 = disabled)
+    pub hours: i64,
+    /// OAuth scopes that can't be granted during the period
+    pub high_risk_scopes: Vec<String>,
+}
+
+impl CoolingOffPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            hours: config.cooling_off_hours,
+            high_risk_scopes: config.cooling_off_scopes.clone(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.hours > 0
+    }
+
+    /// The high-risk action a consent would be, if any
+    ///
+    /// `granted` is what the user already granted the client (empty for a
+    /// client never used before), `approved` what the consent adds.
+    pub fn consent_risk(&self, granted: &[String], approved: &[String]) -> Option<HighRiskAction> {
+        if granted.is_empty() {
+            return Some(HighRiskAction::NewClientConsent);
+        }
+
+        approved
+            .iter()
+            .any(|scope| !granted.contains(scope) && self.high_risk_scopes.contains(scope))
+            .then_some(HighRiskAction::HighRiskScope)
+    }
+}
+
+/// Parse `COOLING_OFF_SCOPES` (comma-separated)
+pub fn parse_scopes(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|scope| !scope.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes(scopes: &[&str]) -> Vec<String> {
+        scopes.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_consent_risk() {
+        let policy = CoolingOffPolicy {
+            hours: 24,
+            high_risk_scopes: parse_scopes("payments:write, transfers"),
+        };
+
+        assert_eq!(
+            policy.consent_risk(&[], &scopes(&["openid"])),
+            Some(HighRiskAction::NewClientConsent)
+        );
+        assert_eq!(
+            policy.consent_risk(&scopes(&["openid"]), &scopes(&["openid", "payments:write"])),
+            Some(HighRiskAction::HighRiskScope)
+        );
+        assert_eq!(policy.consent_risk(&scopes(&["openid", "transfers"]), &scopes(&["transfers"])), None);
+        assert_eq!(policy.consent_risk(&scopes(&["openid"]), &scopes(&["profile"])), None);
+    }
+
+    #[test]
+    fn test_reason_round_trips() {
+        for reason in [CoolingOffReason::EmailChanged, CoolingOffReason::PasswordReset, CoolingOffReason::MfaReset] {
+            assert_eq!(reason.as_str().parse::<CoolingOffReason>().unwrap(), reason);
+        }
+        assert!("password_changed".parse::<CoolingOffReason>().is_err());
+    }
+}
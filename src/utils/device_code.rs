@@ -0,0 +1,79 @@
+//! User codes for the Device Authorization Grant (RFC 8628)
+//!
+//! The user types the code shown on the device into a browser, so it uses
+//! consonants only (no vowels to spell words, no digits to confuse with
+//! letters) and is shown as `XXXX-XXXX`. Input is matched case-insensitively
+//! and ignores dashes and spaces.
+
+use rand::Rng;
+
+/// Characters of a user code (RFC 8628 Section 6.1)
+const USER_CODE_CHARSET: &[u8] = b"BCDFGHJKLMNPQRSTVWXZ";
+
+/// Number of characters in a user code, without the dash
+const USER_CODE_LENGTH: usize = 8;
+
+/// How long the device has to get the code approved
+pub const DEVICE_CODE_EXPIRY_SECS: i64 = 600;
+
+/// How long an expired device code is kept, so a late poll still gets
+/// expired_token rather than invalid_grant
+pub const DEVICE_CODE_RETENTION_SECS: i64 = 600;
+
+/// Minimum seconds between two polls of the token endpoint
+pub const DEVICE_POLL_INTERVAL_SECS: i32 = 5;
+
+/// Seconds added to the interval each time a device polls too fast
+pub const SLOW_DOWN_INCREMENT_SECS: i32 = 5;
+
+/// Generate a user code, formatted for display as `XXXX-XXXX`
+pub fn generate_user_code() -> String {
+    let mut rng = rand::thread_rng();
+    let code: String = (0..USER_CODE_LENGTH)
+        .map(|_| USER_CODE_CHARSET[rng.gen_range(0..USER_CODE_CHARSET.len())] as char)
+        .collect();
+
+    format!("{}-{}", &code[..USER_CODE_LENGTH / 2], &code[USER_CODE_LENGTH / 2..])
+}
+
+/// Canonical form of a user code as typed by the user
+///
+/// Returns `None` if the input cannot be a user code.
+pub fn normalize_user_code(input: &str) -> Option<String> {
+    let code: String = input
+        .chars()
+        .filter(|c| *c != '-' && !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    let valid = code.len() == USER_CODE_LENGTH
+        && code.bytes().all(|b| USER_CODE_CHARSET.contains(&b));
+    valid.then_some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_code_is_displayable_and_normalizes() {
+        let code = generate_user_code();
+
+        assert_eq!(code.len(), USER_CODE_LENGTH + 1);
+        assert_eq!(&code[4..5], "-");
+        assert_eq!(normalize_user_code(&code), Some(code.replace('-', "")));
+    }
+
+    #[test]
+    fn test_normalize_accepts_typing_variations() {
+        assert_eq!(normalize_user_code("wdjb-mjht"), Some("WDJBMJHT".to_string()));
+        assert_eq!(normalize_user_code(" WDJB MJHT "), Some("WDJBMJHT".to_string()));
+    }
+
+    #[test]
+    fn test_normalize_rejects_foreign_characters() {
+        assert_eq!(normalize_user_code("WDJB-MJH"), None);
+        assert_eq!(normalize_user_code("WDJB-MJHA"), None);
+        assert_eq!(normalize_user_code("WDJB-MJH1"), None);
+    }
+}
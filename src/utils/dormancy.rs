@@ -0,0 +1,146 @@
+//! Dormant account policy
+//!
+//! An account is dormant once it has not signed in (or used a session) for
+//! `DORMANT_ACCOUNT_DAYS` days. The dormant account worker flags such
+//! accounts and applies the configured action; unless the action is only a
+//! flag, the owner is warned by email the configured number of days before.
+
+use std::str::FromStr;
+
+use serde::Serialize;
+
+/// What happens to an account once it is dormant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DormantAction {
+    /// Only flagged (shown in the admin report)
+    Flag,
+    /// Flagged and deactivated; an admin has to activate it again
+    Deactivate,
+    /// Flagged and the email address has to be verified again before the next password login
+    Reverify,
+}
+
+impl DormantAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Flag => "flag",
+            Self::Deactivate => "deactivate",
+            Self::Reverify => "reverify",
+        }
+    }
+}
+
+impl FromStr for DormantAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "flag" => Ok(Self::Flag),
+            "deactivate" => Ok(Self::Deactivate),
+            "reverify" => Ok(Self::Reverify),
+            other => Err(anyhow::anyhow!(
+                "Invalid dormant account action '{}' (expected flag, deactivate or reverify)",
+                other
+            )),
+        }
+    }
+}
+
+/// Parse `DORMANT_NOTICE_DAYS`, e.g. `30,7,1`
+///
+/// Windows are returned in ascending order without duplicates.
+pub fn parse_notice_days(value: &str) -> anyhow::Result<Vec<i64>> {
+    let mut days = Vec::new();
+    for part in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let day: i64 = part
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid DORMANT_NOTICE_DAYS entry '{}'", part))?;
+        if day <= 0 {
+            anyhow::bail!("DORMANT_NOTICE_DAYS entries must be positive, got {}", day);
+        }
+        days.push(day);
+    }
+    days.sort_unstable();
+    days.dedup();
+    Ok(days)
+}
+
+/// Server-wide dormant account policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DormancyPolicy {
+    /// Days without activity after which an account is dormant (0 = disabled)
+    pub days: i64,
+    pub action: DormantAction,
+    /// Days before the action the owner is warned, ascending
+    pub notice_days: Vec<i64>,
+}
+
+impl DormancyPolicy {
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        Self {
+            days: config.dormant_account_days,
+            action: config.dormant_account_action,
+            notice_days: config.dormant_notice_days.clone(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.days > 0
+    }
+
+    /// Warning windows that apply, ascending
+    ///
+    /// Nothing is announced for a flag-only policy, and a window as long as
+    /// the dormancy period itself would warn right after a sign-in.
+    pub fn notice_windows(&self) -> Vec<i64> {
+        if !self.is_enabled() || self.action == DormantAction::Flag {
+            return Vec::new();
+        }
+        self.notice_days
+            .iter()
+            .copied()
+            .filter(|d| *d < self.days)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(days: i64, action: DormantAction) -> DormancyPolicy {
+        DormancyPolicy {
+            days,
+            action,
+            notice_days: vec![1, 7, 30],
+        }
+    }
+
+    #[test]
+    fn test_parse_action() {
+        assert_eq!("flag".parse::<DormantAction>().unwrap(), DormantAction::Flag);
+        assert_eq!(" Deactivate ".parse::<DormantAction>().unwrap(), DormantAction::Deactivate);
+        assert_eq!("reverify".parse::<DormantAction>().unwrap(), DormantAction::Reverify);
+        assert!("delete".parse::<DormantAction>().is_err());
+    }
+
+    #[test]
+    fn test_parse_notice_days() {
+        assert_eq!(parse_notice_days("30,7,1").unwrap(), vec![1, 7, 30]);
+        assert_eq!(parse_notice_days(" 7, 7 ,,30").unwrap(), vec![7, 30]);
+        assert!(parse_notice_days("").unwrap().is_empty());
+        assert!(parse_notice_days("7,abc").is_err());
+        assert!(parse_notice_days("0").is_err());
+    }
+
+    #[test]
+    fn test_notice_windows() {
+        assert_eq!(policy(365, DormantAction::Deactivate).notice_windows(), vec![1, 7, 30]);
+        assert_eq!(policy(14, DormantAction::Reverify).notice_windows(), vec![1, 7]);
+
+        // Nothing to announce
+        assert!(policy(365, DormantAction::Flag).notice_windows().is_empty());
+        assert!(policy(0, DormantAction::Deactivate).notice_windows().is_empty());
+    }
+}
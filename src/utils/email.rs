@@ -1,6 +1,8 @@
 use regex::Regex;
-use std::sync::LazyLock;
+use std::sync::{LazyLock, OnceLock};
+use unicode_normalization::UnicodeNormalization;
 
+use crate::config::Config;
 use crate::error::AuthError;
 
 // Email regex pattern - practical validation for common email formats
@@ -12,7 +14,19 @@ static EMAIL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     ).expect("Invalid email regex pattern")
 });
 
+/// Invisible and bidi-control characters that are never allowed in an address
+const INVISIBLE_CHARS: &[char] = &[
+    '\u{200B}', '\u{200C}', '\u{200D}', '\u{200E}', '\u{200F}',
+    '\u{202A}', '\u{202B}', '\u{202C}', '\u{202D}', '\u{202E}',
+    '\u{2060}', '\u{2061}', '\u{2062}', '\u{2063}', '\u{2064}',
+    '\u{FEFF}',
+];
+
 /// Validate an email address format
+///
+/// Internationalized addresses (RFC 6531) are accepted: the local part may
+/// contain UTF-8 characters and the domain may be an IDN, which is checked
+/// in its punycode (ASCII) form.
 /// 
 /// # Arguments
 /// * `email` - The email address to validate
@@ -33,33 +47,82 @@ pub fn validate_email(email: &str) -> Result<(), AuthError> {
     if email.len() > 254 {
         return Err(AuthError::InvalidEmailFormat);
     }
-    
+
+    let (local_part, domain) = email.rsplit_once('@').ok_or(AuthError::InvalidEmailFormat)?;
+
     // Check local part length (max 64 characters)
-    if let Some(at_pos) = email.find('@') {
-        let local_part = &email[..at_pos];
-        if local_part.len() > 64 {
-            return Err(AuthError::InvalidEmailFormat);
-        }
-        
-        // Check for leading/trailing dots in local part
-        if local_part.starts_with('.') || local_part.ends_with('.') {
-            return Err(AuthError::InvalidEmailFormat);
-        }
-        
-        // Check for consecutive dots in local part
-        if local_part.contains("..") {
-            return Err(AuthError::InvalidEmailFormat);
-        }
+    if local_part.len() > 64 {
+        return Err(AuthError::InvalidEmailFormat);
     }
-    
-    // Validate against regex pattern
-    if !EMAIL_REGEX.is_match(email) {
+
+    // Check for leading/trailing dots in local part
+    if local_part.starts_with('.') || local_part.ends_with('.') {
+        return Err(AuthError::InvalidEmailFormat);
+    }
+
+    // Check for consecutive dots in local part
+    if local_part.contains("..") {
+        return Err(AuthError::InvalidEmailFormat);
+    }
+
+    // UTF-8 local parts: no controls, whitespace or invisible characters
+    if local_part
+        .chars()
+        .any(|c| !c.is_ascii() && (c.is_control() || c.is_whitespace() || INVISIBLE_CHARS.contains(&c)))
+    {
+        return Err(AuthError::InvalidEmailFormat);
+    }
+
+    let ascii_domain = domain_to_ascii(domain).ok_or(AuthError::InvalidEmailFormat)?;
+
+    // Validate against regex pattern, with non-ASCII local characters stood in
+    // by an ASCII letter and the domain in its punycode form
+    let ascii_local: String = local_part.chars().map(|c| if c.is_ascii() { c } else { 'a' }).collect();
+    if !EMAIL_REGEX.is_match(&format!("{}@{}", ascii_local, ascii_domain)) {
         return Err(AuthError::InvalidEmailFormat);
     }
     
     Ok(())
 }
 
+/// Normalize an email address as entered: trimmed and in Unicode NFC
+///
+/// This is the form stored and used for sending, so that visually identical
+/// addresses typed on different keyboards are byte-for-byte equal.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().nfc().collect()
+}
+
+/// Convert a domain to its ASCII (punycode) form
+pub fn domain_to_ascii(domain: &str) -> Option<String> {
+    idna::domain_to_ascii(domain).ok().filter(|d| !d.is_empty())
+}
+
+/// Email address with the domain in ASCII (punycode) form, for SMTP delivery
+///
+/// A UTF-8 local part is kept as is and requires an SMTPUTF8-capable relay.
+pub fn to_ascii_address(email: &str) -> String {
+    let email = normalize_email(email);
+    match email.rsplit_once('@') {
+        Some((local, domain)) => match domain_to_ascii(domain) {
+            Some(domain) => format!("{}@{}", local, domain),
+            None => email,
+        },
+        None => email,
+    }
+}
+
+/// Hide the local part of an address but its first character, e.g. `a***@example.com`
+pub fn mask_email(email: &str) -> String {
+    match email.rsplit_once('@') {
+        Some((local, domain)) => {
+            let first: String = local.chars().take(1).collect();
+            format!("{}***@{}", first, domain)
+        }
+        None => "***".to_string(),
+    }
+}
+
 /// Check if an email address is valid (returns boolean)
 /// 
 /// # Arguments
@@ -72,6 +135,139 @@ pub fn is_valid_email(email: &str) -> bool {
     validate_email(email).is_ok()
 }
 
+/// Mailbox domains that are aliases of another domain
+const DOMAIN_ALIASES: &[(&str, &str)] = &[("googlemail.com", "gmail.com")];
+
+/// Provider-specific rules used to canonicalize email addresses
+///
+/// Every address is trimmed and lowercased. On the configured domains, dots
+/// in the local part are ignored and/or a `+tag` suffix is dropped, since
+/// those providers deliver such variants to the same mailbox.
+#[derive(Debug, Clone)]
+pub struct EmailCanonicalRules {
+    /// Domains where dots in the local part are ignored
+    pub dot_insensitive_domains: Vec<String>,
+    /// Domains where a `+tag` suffix in the local part is ignored
+    pub plus_alias_domains: Vec<String>,
+}
+
+impl Default for EmailCanonicalRules {
+    fn default() -> Self {
+        Self {
+            dot_insensitive_domains: vec!["gmail.com".to_string()],
+            plus_alias_domains: vec![
+                "gmail.com".to_string(),
+                "outlook.com".to_string(),
+                "hotmail.com".to_string(),
+                "icloud.com".to_string(),
+            ],
+        }
+    }
+}
+
+impl EmailCanonicalRules {
+    /// Build the rules from server configuration
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            dot_insensitive_domains: config.email_canonical_dot_domains.clone(),
+            plus_alias_domains: config.email_canonical_plus_domains.clone(),
+        }
+    }
+
+    /// Canonicalize an email address
+    ///
+    /// Unicode addresses are NFC-normalized and the domain is compared in
+    /// its punycode form, so `user@bücher.example` and
+    /// `user@xn--bcher-kva.example` are the same account.
+    pub fn canonicalize(&self, email: &str) -> String {
+        let email = normalize_email(email).to_lowercase();
+
+        let (local, domain) = match email.rsplit_once('@') {
+            Some(parts) => parts,
+            None => return email,
+        };
+
+        let ascii_domain = domain_to_ascii(domain).unwrap_or_else(|| domain.to_string());
+        let domain = ascii_domain.as_str();
+        let domain = DOMAIN_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == domain)
+            .map(|(_, canonical)| *canonical)
+            .unwrap_or(domain);
+
+        let mut local = local.to_string();
+        if self.plus_alias_domains.iter().any(|d| d == domain) {
+            if let Some((base, _)) = local.split_once('+') {
+                local = base.to_string();
+            }
+        }
+        if self.dot_insensitive_domains.iter().any(|d| d == domain) {
+            local = local.replace('.', "");
+        }
+
+        format!("{}@{}", local, domain)
+    }
+}
+
+/// Characters from other scripts that render like an ASCII letter
+///
+/// A practical subset of the Unicode confusables data (UTS #39) covering the
+/// Cyrillic, Greek, Armenian and Latin look-alikes used in spoofing.
+const CONFUSABLES: &[(char, char)] = &[
+    // Cyrillic
+    ('а', 'a'), ('с', 'c'), ('ԁ', 'd'), ('е', 'e'), ('ё', 'e'), ('һ', 'h'), ('і', 'i'),
+    ('ї', 'i'), ('ј', 'j'), ('ӏ', 'l'), ('о', 'o'), ('р', 'p'), ('ԛ', 'q'), ('ѕ', 's'),
+    ('ԝ', 'w'), ('х', 'x'), ('у', 'y'),
+    // Greek
+    ('α', 'a'), ('ε', 'e'), ('η', 'n'), ('ι', 'i'), ('κ', 'k'), ('ν', 'v'), ('ο', 'o'),
+    ('ρ', 'p'), ('υ', 'u'), ('χ', 'x'), ('γ', 'y'),
+    // Armenian
+    ('օ', 'o'), ('ց', 'g'), ('հ', 'h'), ('ո', 'n'), ('ս', 'u'),
+    // Latin
+    ('ı', 'i'), ('ɑ', 'a'), ('ɡ', 'g'), ('ɩ', 'i'), ('ʋ', 'u'), ('ɒ', 'a'),
+];
+
+/// Homoglyph skeleton of an email address
+///
+/// Two addresses with the same skeleton look the same to a person even if
+/// their canonical forms differ (e.g. a Cyrillic `а` in place of `a`). The
+/// skeleton folds compatibility forms (NFKC), maps known confusables to their
+/// ASCII look-alike and then applies the canonical rules.
+pub fn email_skeleton(email: &str) -> String {
+    let email = email.trim();
+    let (local, domain) = match email.rsplit_once('@') {
+        Some((local, domain)) => (local, idna::domain_to_unicode(domain).0),
+        None => (email, String::new()),
+    };
+
+    let fold = |s: &str| -> String {
+        s.nfkc()
+            .flat_map(char::to_lowercase)
+            .map(|c| CONFUSABLES.iter().find(|(from, _)| *from == c).map(|(_, to)| *to).unwrap_or(c))
+            .collect()
+    };
+
+    if domain.is_empty() {
+        return canonicalize_email(&fold(local));
+    }
+    canonicalize_email(&format!("{}@{}", fold(local), fold(&domain)))
+}
+
+static CANONICAL_RULES: OnceLock<EmailCanonicalRules> = OnceLock::new();
+
+/// Install the canonicalization rules (called once at startup)
+pub fn init_canonical_rules(rules: EmailCanonicalRules) {
+    let _ = CANONICAL_RULES.set(rules);
+}
+
+/// Canonicalize an email address with the configured rules
+///
+/// The canonical form is what uniqueness and lookups are based on; the
+/// address as entered by the user is still stored and used for sending.
+pub fn canonicalize_email(email: &str) -> String {
+    CANONICAL_RULES.get_or_init(EmailCanonicalRules::default).canonicalize(email)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,4 +352,79 @@ mod tests {
         assert!(validate_email("user.name@example.com").is_ok());
         assert!(validate_email("user_name@example.com").is_ok());
     }
+
+    #[test]
+    fn test_canonicalize_folds_case_everywhere() {
+        let rules = EmailCanonicalRules::default();
+
+        assert_eq!(rules.canonicalize(" Foo@Example.com "), "foo@example.com");
+        assert_eq!(rules.canonicalize("first.last+tag@example.com"), "first.last+tag@example.com");
+    }
+
+    #[test]
+    fn test_canonicalize_applies_provider_rules() {
+        let rules = EmailCanonicalRules::default();
+
+        assert_eq!(rules.canonicalize("John.Doe+news@Gmail.com"), "johndoe@gmail.com");
+        assert_eq!(rules.canonicalize("john.doe@googlemail.com"), "johndoe@gmail.com");
+        assert_eq!(rules.canonicalize("john.doe+news@outlook.com"), "john.doe@outlook.com");
+    }
+
+    #[test]
+    fn test_canonicalize_rules_are_configurable() {
+        let rules = EmailCanonicalRules {
+            dot_insensitive_domains: vec!["corp.example".to_string()],
+            plus_alias_domains: vec![],
+        };
+
+        assert_eq!(rules.canonicalize("a.b+x@corp.example"), "ab+x@corp.example");
+        assert_eq!(rules.canonicalize("a.b+x@gmail.com"), "a.b+x@gmail.com");
+    }
+
+    #[test]
+    fn test_internationalized_emails_are_valid() {
+        assert!(validate_email("用户@例子.广告").is_ok());
+        assert!(validate_email("josé.garcía@bücher.example").is_ok());
+        assert!(validate_email("user@xn--bcher-kva.example").is_ok());
+    }
+
+    #[test]
+    fn test_invisible_characters_are_rejected() {
+        assert!(validate_email("us\u{200B}er@example.com").is_err());
+        assert!(validate_email("josé\u{202E}@example.com").is_err());
+    }
+
+    #[test]
+    fn test_canonicalize_normalizes_unicode() {
+        let rules = EmailCanonicalRules::default();
+        let composed = "jos\u{E9}@b\u{FC}cher.example";
+        let decomposed = "jose\u{301}@bu\u{308}cher.example";
+
+        assert_eq!(rules.canonicalize(composed), rules.canonicalize(decomposed));
+        assert_eq!(rules.canonicalize(composed), "jos\u{E9}@xn--bcher-kva.example");
+        assert_eq!(rules.canonicalize("JOSÉ@xn--bcher-kva.example"), "jos\u{E9}@xn--bcher-kva.example");
+    }
+
+    #[test]
+    fn test_to_ascii_address_uses_punycode_domain() {
+        assert_eq!(to_ascii_address("josé@bücher.example"), "josé@xn--bcher-kva.example");
+        assert_eq!(to_ascii_address("user@example.com"), "user@example.com");
+    }
+
+    #[test]
+    fn test_mask_email() {
+        assert_eq!(mask_email("alice@example.com"), "a***@example.com");
+        assert_eq!(mask_email("josé@bücher.example"), "j***@bücher.example");
+        assert_eq!(mask_email("not-an-address"), "***");
+    }
+
+    #[test]
+    fn test_skeleton_catches_homoglyphs() {
+        // Cyrillic "а" and "о" in place of Latin letters
+        assert_eq!(email_skeleton("p\u{430}ypal@example.com"), email_skeleton("paypal@example.com"));
+        assert_eq!(email_skeleton("admin@g\u{43E}\u{43E}gle.com"), email_skeleton("admin@google.com"));
+        // Fullwidth forms fold under NFKC
+        assert_eq!(email_skeleton("ｊｏｈｎ@example.com"), email_skeleton("john@example.com"));
+        assert_ne!(email_skeleton("john@example.com"), email_skeleton("joan@example.com"));
+    }
 }
@@ -0,0 +1,279 @@
+//! Bounce and complaint events from email provider webhooks
+//!
+//! Each provider posts its own payload shape; these are reduced to the
+//! events that stop email to an address. Soft bounces, deliveries and other
+//! events are dropped, since a later attempt to the address may succeed.
+
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::models::{BOUNCE_KIND_COMPLAINT, BOUNCE_KIND_HARD};
+
+/// Webhook payload format, named in the webhook path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailProvider {
+    /// SendGrid Event Webhook (batches of events)
+    SendGrid,
+    /// Mailgun webhooks (`event-data`)
+    Mailgun,
+    /// Amazon SES notifications, delivered by SNS or raw
+    Ses,
+    /// `{"email", "type": "hard_bounce" | "complaint", "detail"}`, alone or in a list
+    Generic,
+}
+
+impl EmailProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SendGrid => "sendgrid",
+            Self::Mailgun => "mailgun",
+            Self::Ses => "ses",
+            Self::Generic => "generic",
+        }
+    }
+}
+
+impl FromStr for EmailProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "sendgrid" => Ok(Self::SendGrid),
+            "mailgun" => Ok(Self::Mailgun),
+            "ses" => Ok(Self::Ses),
+            "generic" => Ok(Self::Generic),
+            other => Err(format!(
+                "Unknown email provider '{}' (expected sendgrid, mailgun, ses or generic)",
+                other
+            )),
+        }
+    }
+}
+
+/// A hard bounce or complaint for one address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BounceEvent {
+    /// Lowercased address
+    pub email: String,
+    /// `hard_bounce` or `complaint`
+    pub kind: &'static str,
+    pub detail: Option<String>,
+}
+
+impl BounceEvent {
+    fn new(email: &str, kind: &'static str, detail: Option<&str>) -> Self {
+        Self {
+            email: email.trim().to_lowercase(),
+            kind,
+            detail: detail.map(str::trim).filter(|d| !d.is_empty()).map(str::to_string),
+        }
+    }
+}
+
+/// Extract the hard bounces and complaints from a webhook body
+pub fn parse(provider: EmailProvider, body: &str) -> Result<Vec<BounceEvent>, String> {
+    let payload: Value =
+        serde_json::from_str(body).map_err(|e| format!("Invalid webhook payload: {}", e))?;
+
+    let events = match provider {
+        EmailProvider::SendGrid => sendgrid(&payload),
+        EmailProvider::Mailgun => mailgun(&payload),
+        EmailProvider::Ses => ses(&payload)?,
+        EmailProvider::Generic => generic(&payload),
+    };
+
+    Ok(events.into_iter().filter(|event| event.email.contains('@')).collect())
+}
+
+fn sendgrid(payload: &Value) -> Vec<BounceEvent> {
+    items(payload)
+        .filter_map(|event| {
+            let kind = match field(event, "event")? {
+                // "blocked" bounces are temporary rejections
+                "bounce" if field(event, "type") != Some("blocked") => BOUNCE_KIND_HARD,
+                "spamreport" => BOUNCE_KIND_COMPLAINT,
+                _ => return None,
+            };
+            Some(BounceEvent::new(field(event, "email")?, kind, field(event, "reason")))
+        })
+        .collect()
+}
+
+fn mailgun(payload: &Value) -> Vec<BounceEvent> {
+    let Some(data) = payload.get("event-data") else {
+        return Vec::new();
+    };
+    let kind = match field(data, "event") {
+        Some("failed") if field(data, "severity") == Some("permanent") => BOUNCE_KIND_HARD,
+        Some("complained") => BOUNCE_KIND_COMPLAINT,
+        _ => return Vec::new(),
+    };
+    let detail = data
+        .pointer("/delivery-status/description")
+        .and_then(Value::as_str)
+        .filter(|d| !d.is_empty())
+        .or_else(|| data.pointer("/delivery-status/message").and_then(Value::as_str));
+
+    field(data, "recipient")
+        .map(|email| BounceEvent::new(email, kind, detail))
+        .into_iter()
+        .collect()
+}
+
+fn ses(payload: &Value) -> Result<Vec<BounceEvent>, String> {
+    match field(payload, "Type") {
+        Some("SubscriptionConfirmation") => {
+            tracing::info!(
+                "SES bounce topic subscription needs confirming at {}",
+                field(payload, "SubscribeURL").unwrap_or("(no SubscribeURL)")
+            );
+            return Ok(Vec::new());
+        }
+        Some("UnsubscribeConfirmation") => return Ok(Vec::new()),
+        _ => {}
+    }
+
+    // SNS carries the SES notification as a JSON string
+    let notification = match field(payload, "Message") {
+        Some(message) => serde_json::from_str(message)
+            .map_err(|e| format!("Invalid SES notification: {}", e))?,
+        None => payload.clone(),
+    };
+
+    let kind = field(&notification, "notificationType").or_else(|| field(&notification, "eventType"));
+    let events = match kind {
+        Some("Bounce") => {
+            let bounce = &notification["bounce"];
+            if field(bounce, "bounceType") != Some("Permanent") {
+                return Ok(Vec::new());
+            }
+            items(&bounce["bouncedRecipients"])
+                .filter_map(|r| {
+                    Some(BounceEvent::new(field(r, "emailAddress")?, BOUNCE_KIND_HARD, field(r, "diagnosticCode")))
+                })
+                .collect()
+        }
+        Some("Complaint") => {
+            let complaint = &notification["complaint"];
+            let detail = field(complaint, "complaintFeedbackType");
+            items(&complaint["complainedRecipients"])
+                .filter_map(|r| Some(BounceEvent::new(field(r, "emailAddress")?, BOUNCE_KIND_COMPLAINT, detail)))
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    Ok(events)
+}
+
+fn generic(payload: &Value) -> Vec<BounceEvent> {
+    items(payload)
+        .filter_map(|event| {
+            let kind = match field(event, "type")? {
+                BOUNCE_KIND_HARD => BOUNCE_KIND_HARD,
+                BOUNCE_KIND_COMPLAINT => BOUNCE_KIND_COMPLAINT,
+                _ => return None,
+            };
+            Some(BounceEvent::new(field(event, "email")?, kind, field(event, "detail")))
+        })
+        .collect()
+}
+
+/// Elements of a list, or the value itself
+fn items(value: &Value) -> std::slice::Iter<'_, Value> {
+    match value {
+        Value::Array(items) => items.iter(),
+        Value::Null => [].iter(),
+        other => std::slice::from_ref(other).iter(),
+    }
+}
+
+fn field<'a>(value: &'a Value, name: &str) -> Option<&'a str> {
+    value.get(name).and_then(Value::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sendgrid_keeps_hard_bounces_and_spam_reports() {
+        let body = r#"[
+            {"email": "Gone@Example.com", "event": "bounce", "type": "bounce", "reason": "550 5.1.1 unknown user"},
+            {"email": "full@example.com", "event": "bounce", "type": "blocked"},
+            {"email": "angry@example.com", "event": "spamreport"},
+            {"email": "fine@example.com", "event": "delivered"}
+        ]"#;
+
+        let events = parse(EmailProvider::SendGrid, body).unwrap();
+        assert_eq!(
+            events,
+            vec![
+                BounceEvent {
+                    email: "gone@example.com".into(),
+                    kind: BOUNCE_KIND_HARD,
+                    detail: Some("550 5.1.1 unknown user".into()),
+                },
+                BounceEvent { email: "angry@example.com".into(), kind: BOUNCE_KIND_COMPLAINT, detail: None },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mailgun_ignores_temporary_failures() {
+        let permanent = r#"{"event-data": {"event": "failed", "severity": "permanent", "recipient": "gone@example.com",
+            "delivery-status": {"message": "", "description": "No such mailbox"}}}"#;
+        let temporary = r#"{"event-data": {"event": "failed", "severity": "temporary", "recipient": "busy@example.com"}}"#;
+
+        let events = parse(EmailProvider::Mailgun, permanent).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].detail.as_deref(), Some("No such mailbox"));
+        assert!(parse(EmailProvider::Mailgun, temporary).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_ses_unwraps_sns_notifications() {
+        let bounce = serde_json::json!({
+            "Type": "Notification",
+            "Message": serde_json::json!({
+                "notificationType": "Bounce",
+                "bounce": {
+                    "bounceType": "Permanent",
+                    "bouncedRecipients": [{"emailAddress": "gone@example.com", "diagnosticCode": "smtp; 550"}]
+                }
+            }).to_string()
+        });
+        let complaint = serde_json::json!({
+            "notificationType": "Complaint",
+            "complaint": {"complaintFeedbackType": "abuse", "complainedRecipients": [{"emailAddress": "angry@example.com"}]}
+        });
+        let transient = serde_json::json!({
+            "notificationType": "Bounce",
+            "bounce": {"bounceType": "Transient", "bouncedRecipients": [{"emailAddress": "busy@example.com"}]}
+        });
+
+        let events = parse(EmailProvider::Ses, &bounce.to_string()).unwrap();
+        assert_eq!(events[0].kind, BOUNCE_KIND_HARD);
+        assert_eq!(events[0].detail.as_deref(), Some("smtp; 550"));
+        let events = parse(EmailProvider::Ses, &complaint.to_string()).unwrap();
+        assert_eq!(events[0].kind, BOUNCE_KIND_COMPLAINT);
+        assert!(parse(EmailProvider::Ses, &transient.to_string()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_generic_accepts_one_event_or_a_list() {
+        let one = r#"{"email": "gone@example.com", "type": "hard_bounce"}"#;
+        let list = r#"[{"email": "gone@example.com", "type": "hard_bounce"}, {"email": "x@example.com", "type": "soft_bounce"}]"#;
+
+        assert_eq!(parse(EmailProvider::Generic, one).unwrap().len(), 1);
+        assert_eq!(parse(EmailProvider::Generic, list).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_rejects_malformed_payloads_and_providers() {
+        assert!(parse(EmailProvider::Generic, "not json").is_err());
+        assert!("postmark".parse::<EmailProvider>().is_err());
+        assert_eq!("SES".parse::<EmailProvider>().unwrap(), EmailProvider::Ses);
+    }
+}
@@ -0,0 +1,134 @@
+//! Placeholder templates for admin-written emails
+//!
+//! A template is plain text with `{{variable}}` placeholders. Unknown
+//! placeholders are rejected when the template is written rather than sent
+//! as-is to thousands of users. In the HTML body both the template text and
+//! the substituted values are escaped, so neither can inject markup, and
+//! line breaks are kept.
+
+/// Placeholders a template may use
+pub const TEMPLATE_VARIABLES: &[&str] = &["email", "name", "app_name"];
+
+/// Values substituted for the placeholders of one recipient
+#[derive(Debug, Clone)]
+pub struct TemplateVars<'a> {
+    pub email: &'a str,
+    /// Display name; the local part of the email is used when unset
+    pub name: Option<&'a str>,
+    pub app_name: &'a str,
+}
+
+impl TemplateVars<'_> {
+    fn get(&self, variable: &str) -> Option<String> {
+        match variable {
+            "email" => Some(self.email.to_string()),
+            "name" => Some(
+                self.name
+                    .filter(|name| !name.trim().is_empty())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| self.email.split('@').next().unwrap_or_default().to_string()),
+            ),
+            "app_name" => Some(self.app_name.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Check that a template only uses known placeholders
+pub fn validate(template: &str) -> Result<(), String> {
+    render(template, |variable| {
+        TEMPLATE_VARIABLES.contains(&variable).then(String::new)
+    }, |text| text.to_string())
+    .map(|_| ())
+}
+
+/// Render a template as plain text (e.g. a subject line)
+pub fn render_text(template: &str, vars: &TemplateVars<'_>) -> Result<String, String> {
+    render(template, |variable| vars.get(variable), |text| text.to_string())
+}
+
+/// Render a template as an HTML fragment
+pub fn render_html(template: &str, vars: &TemplateVars<'_>) -> Result<String, String> {
+    let html = render(template, |variable| vars.get(variable), escape_html)?;
+    Ok(html.replace("\r\n", "\n").replace('\n', "<br>\n"))
+}
+
+fn render(
+    template: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+    escape: impl Fn(&str) -> String,
+) -> Result<String, String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&escape(&rest[..start]));
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| "Unclosed '{{' in template".to_string())?;
+        let variable = after[..end].trim();
+        let value = lookup(variable).ok_or_else(|| {
+            format!(
+                "Unknown template variable '{}' (supported: {})",
+                variable,
+                TEMPLATE_VARIABLES.join(", ")
+            )
+        })?;
+        output.push_str(&escape(&value));
+        rest = &after[end + 2..];
+    }
+    output.push_str(&escape(rest));
+
+    Ok(output)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> TemplateVars<'static> {
+        TemplateVars {
+            email: "ann@example.com",
+            name: Some("Ann <b>"),
+            app_name: "Auth Server",
+        }
+    }
+
+    #[test]
+    fn test_render_text_substitutes_variables() {
+        assert_eq!(
+            render_text("Hi {{ name }}, news from {{app_name}}", &vars()).unwrap(),
+            "Hi Ann <b>, news from Auth Server"
+        );
+    }
+
+    #[test]
+    fn test_render_html_escapes_text_and_values() {
+        assert_eq!(
+            render_html("<i>Hi</i> {{name}}\nBye", &vars()).unwrap(),
+            "&lt;i&gt;Hi&lt;/i&gt; Ann &lt;b&gt;<br>\nBye"
+        );
+    }
+
+    #[test]
+    fn test_name_falls_back_to_email_local_part() {
+        let vars = TemplateVars { name: None, ..vars() };
+        assert_eq!(render_text("{{name}}", &vars).unwrap(), "ann");
+    }
+
+    #[test]
+    fn test_unknown_and_unclosed_placeholders_are_rejected() {
+        assert!(validate("Hello {{email}} from {{app_name}}").is_ok());
+        assert!(validate("Hello {{password}}").unwrap_err().contains("password"));
+        assert!(validate("Hello {{name").is_err());
+    }
+}
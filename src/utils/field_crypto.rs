@@ -0,0 +1,406 @@
+//! Envelope encryption for sensitive columns
+//!
+//! Each value is encrypted with its own random data key (AES-256-GCM), and
+//! the data key is wrapped with a key-encryption key (KEK). KEKs are loaded
+//! at startup (from the environment, typically populated by a KMS or secret
+//! manager) and identified by a short ID stored with every value, so old
+//! values stay readable after the active KEK changes:
+//!
+//! `enc:v1:<kek_id>:<wrapped data key>:<ciphertext>`
+//!
+//! Values without the prefix are legacy plaintext. Reads of plaintext or of
+//! values under a retired KEK are reported as stale so they can be
+//! re-encrypted lazily; see [`take_stale_fields`].
+
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use rand::RngCore;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use serde::{Deserialize, Serialize};
+
+/// Prefix of encrypted values (format version 1)
+pub const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+/// Most stale fields remembered between re-encryption passes
+const MAX_STALE_FIELDS: usize = 10_000;
+
+/// Column holding encrypted values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EncryptedColumn {
+    UserPhone,
+    MfaSecret,
+    MfaPhone,
+    #[serde(rename = "webauthn_device_name")]
+    WebAuthnDeviceName,
+    WebhookSecret,
+    SigningKey,
+}
+
+impl EncryptedColumn {
+    pub const ALL: [Self; 6] = [
+        Self::UserPhone,
+        Self::MfaSecret,
+        Self::MfaPhone,
+        Self::WebAuthnDeviceName,
+        Self::WebhookSecret,
+        Self::SigningKey,
+    ];
+
+    pub fn table(&self) -> &'static str {
+        match self {
+            Self::UserPhone => "users",
+            Self::MfaSecret | Self::MfaPhone => "user_mfa_methods",
+            Self::WebAuthnDeviceName => "webauthn_credentials",
+            Self::WebhookSecret => "webhooks",
+            Self::SigningKey => "signing_keys",
+        }
+    }
+
+    pub fn column(&self) -> &'static str {
+        match self {
+            Self::UserPhone => "phone",
+            Self::MfaSecret => "secret_encrypted",
+            Self::MfaPhone => "phone_number",
+            Self::WebAuthnDeviceName => "device_name",
+            Self::WebhookSecret => "secret",
+            Self::SigningKey => "private_key",
+        }
+    }
+
+    /// Whether the table has an auto-updating `updated_at` that re-encryption must preserve
+    pub fn has_updated_at(&self) -> bool {
+        matches!(self, Self::UserPhone | Self::WebhookSecret)
+    }
+
+    /// `table.column`, also used as associated data so values can't be moved between columns
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::UserPhone => "users.phone",
+            Self::MfaSecret => "user_mfa_methods.secret_encrypted",
+            Self::MfaPhone => "user_mfa_methods.phone_number",
+            Self::WebAuthnDeviceName => "webauthn_credentials.device_name",
+            Self::WebhookSecret => "webhooks.secret",
+            Self::SigningKey => "signing_keys.private_key",
+        }
+    }
+}
+
+/// Failure to decrypt a stored value
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FieldCryptoError {
+    #[error("value is encrypted with unknown key '{0}'")]
+    UnknownKey(String),
+    #[error("encrypted value is malformed")]
+    Malformed,
+    #[error("encrypted value failed authentication")]
+    Tampered,
+    #[error("value is encrypted but no encryption keys are configured")]
+    NotConfigured,
+}
+
+/// Decrypted value and whether it should be re-encrypted under the active KEK
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenedField {
+    pub value: String,
+    pub stale: bool,
+}
+
+/// Key-encryption keys, the first one being active for new values
+pub struct FieldCipher {
+    active_id: String,
+    keys: Vec<(String, LessSafeKey)>,
+}
+
+impl FieldCipher {
+    /// Parse `id:base64key,id:base64key` (32-byte keys, URL-safe or standard base64)
+    ///
+    /// The first key encrypts new values; the others only decrypt. Returns
+    /// Ok(None) for an empty spec (encryption disabled).
+    pub fn from_spec(spec: &str) -> anyhow::Result<Option<Self>> {
+        let mut keys = Vec::new();
+
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (id, material) = entry
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("encryption key entry must be 'id:base64key'"))?;
+            let id = id.trim();
+            if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                anyhow::bail!("encryption key ID '{}' must be alphanumeric", id);
+            }
+            if keys.iter().any(|(existing, _)| existing == id) {
+                anyhow::bail!("duplicate encryption key ID '{}'", id);
+            }
+
+            let material = material.trim();
+            let bytes = URL_SAFE_NO_PAD
+                .decode(material.trim_end_matches('='))
+                .or_else(|_| base64::engine::general_purpose::STANDARD.decode(material))
+                .map_err(|_| anyhow::anyhow!("encryption key '{}' is not valid base64", id))?;
+            let key = UnboundKey::new(&AES_256_GCM, &bytes)
+                .map_err(|_| anyhow::anyhow!("encryption key '{}' must be 32 bytes", id))?;
+
+            keys.push((id.to_string(), LessSafeKey::new(key)));
+        }
+
+        Ok(keys.first().map(|(id, _)| id.clone()).map(|active_id| Self { active_id, keys }))
+    }
+
+    /// ID of the KEK used for new values
+    pub fn active_key_id(&self) -> &str {
+        &self.active_id
+    }
+
+    /// Encrypt a value under the active KEK
+    pub fn encrypt(&self, column: EncryptedColumn, plaintext: &str) -> String {
+        let kek = self.key(&self.active_id).expect("active key is always present");
+
+        let mut data_key = [0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut data_key);
+        let dek = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &data_key).expect("32-byte key"));
+
+        let wrapped = seal(kek, column.name(), &data_key);
+        let ciphertext = seal(&dek, column.name(), plaintext.as_bytes());
+
+        format!(
+            "{}{}:{}:{}",
+            ENCRYPTED_PREFIX,
+            self.active_id,
+            URL_SAFE_NO_PAD.encode(wrapped),
+            URL_SAFE_NO_PAD.encode(ciphertext)
+        )
+    }
+
+    /// Decrypt a stored value (plaintext legacy values are returned as-is)
+    pub fn decrypt(&self, column: EncryptedColumn, stored: &str) -> Result<OpenedField, FieldCryptoError> {
+        let Some(rest) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Ok(OpenedField { value: stored.to_string(), stale: true });
+        };
+
+        let mut parts = rest.splitn(3, ':');
+        let (Some(key_id), Some(wrapped), Some(ciphertext)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(FieldCryptoError::Malformed);
+        };
+        let kek = self.key(key_id).ok_or_else(|| FieldCryptoError::UnknownKey(key_id.to_string()))?;
+
+        let wrapped = URL_SAFE_NO_PAD.decode(wrapped).map_err(|_| FieldCryptoError::Malformed)?;
+        let data_key = open(kek, column.name(), wrapped)?;
+        let dek = LessSafeKey::new(
+            UnboundKey::new(&AES_256_GCM, &data_key).map_err(|_| FieldCryptoError::Malformed)?,
+        );
+
+        let ciphertext = URL_SAFE_NO_PAD.decode(ciphertext).map_err(|_| FieldCryptoError::Malformed)?;
+        let plaintext = open(&dek, column.name(), ciphertext)?;
+        let value = String::from_utf8(plaintext).map_err(|_| FieldCryptoError::Malformed)?;
+
+        Ok(OpenedField { value, stale: key_id != self.active_id })
+    }
+
+    /// Re-encrypt a stored value under the active KEK if it isn't already
+    ///
+    /// Returns Ok(None) when the value is current.
+    pub fn rewrap(&self, column: EncryptedColumn, stored: &str) -> Result<Option<String>, FieldCryptoError> {
+        let opened = self.decrypt(column, stored)?;
+        Ok(opened.stale.then(|| self.encrypt(column, &opened.value)))
+    }
+
+    fn key(&self, id: &str) -> Option<&LessSafeKey> {
+        self.keys.iter().find(|(key_id, _)| key_id == id).map(|(_, key)| key)
+    }
+}
+
+/// Encrypt `plaintext` as `nonce || ciphertext || tag`
+fn seal(key: &LessSafeKey, aad: &str, plaintext: &[u8]) -> Vec<u8> {
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+    let mut buffer = plaintext.to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(aad.as_bytes()), &mut buffer)
+        .expect("AES-GCM input is within size limits");
+
+    let mut out = nonce.to_vec();
+    out.extend_from_slice(&buffer);
+    out
+}
+
+/// Decrypt `nonce || ciphertext || tag`
+fn open(key: &LessSafeKey, aad: &str, mut sealed: Vec<u8>) -> Result<Vec<u8>, FieldCryptoError> {
+    if sealed.len() < NONCE_LEN {
+        return Err(FieldCryptoError::Malformed);
+    }
+    let mut ciphertext = sealed.split_off(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(&sealed).map_err(|_| FieldCryptoError::Malformed)?;
+
+    let plaintext = key
+        .open_in_place(nonce, Aad::from(aad.as_bytes()), &mut ciphertext)
+        .map_err(|_| FieldCryptoError::Tampered)?;
+    Ok(plaintext.to_vec())
+}
+
+static FIELD_CIPHER: OnceLock<Option<FieldCipher>> = OnceLock::new();
+
+static STALE_FIELDS: Mutex<Option<HashSet<(EncryptedColumn, String)>>> = Mutex::new(None);
+
+/// Install the field cipher (called once at startup; None disables encryption)
+pub fn init_field_cipher(cipher: Option<FieldCipher>) {
+    let _ = FIELD_CIPHER.set(cipher);
+}
+
+/// The configured field cipher, if encryption is enabled
+pub fn field_cipher() -> Option<&'static FieldCipher> {
+    FIELD_CIPHER.get().and_then(Option::as_ref)
+}
+
+/// Encrypt a value for storage (stored as plaintext when encryption is disabled)
+pub fn seal_field(column: EncryptedColumn, value: &str) -> String {
+    match field_cipher() {
+        Some(cipher) => cipher.encrypt(column, value),
+        None => value.to_string(),
+    }
+}
+
+/// Encrypt an optional value for storage
+pub fn seal_optional(column: EncryptedColumn, value: Option<&str>) -> Option<String> {
+    value.map(|v| seal_field(column, v))
+}
+
+/// Decrypt a stored value read from row `row_id`
+///
+/// Stale values are queued for lazy re-encryption. A value that can't be
+/// decrypted (unknown key, tampering) is logged and read as None.
+pub fn open_field(column: EncryptedColumn, row_id: &str, stored: String) -> Option<String> {
+    let result = match field_cipher() {
+        Some(cipher) => cipher.decrypt(column, &stored),
+        None if stored.starts_with(ENCRYPTED_PREFIX) => Err(FieldCryptoError::NotConfigured),
+        None => return Some(stored),
+    };
+
+    match result {
+        Ok(opened) => {
+            if opened.stale {
+                mark_stale(column, row_id);
+            }
+            Some(opened.value)
+        }
+        Err(e) => {
+            tracing::error!("Failed to decrypt {} of row {}: {}", column.name(), row_id, e);
+            None
+        }
+    }
+}
+
+/// Decrypt an optional stored value read from row `row_id`
+pub fn open_optional(column: EncryptedColumn, row_id: &str, stored: Option<String>) -> Option<String> {
+    stored.and_then(|s| open_field(column, row_id, s))
+}
+
+fn mark_stale(column: EncryptedColumn, row_id: &str) {
+    let mut stale = STALE_FIELDS.lock().unwrap_or_else(|e| e.into_inner());
+    let set = stale.get_or_insert_with(HashSet::new);
+    if set.len() < MAX_STALE_FIELDS {
+        set.insert((column, row_id.to_string()));
+    }
+}
+
+/// Take the fields read since the last call that need re-encryption
+pub fn take_stale_fields() -> Vec<(EncryptedColumn, String)> {
+    let mut stale = STALE_FIELDS.lock().unwrap_or_else(|e| e.into_inner());
+    stale.take().map(|set| set.into_iter().collect()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_A: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8";
+    const KEY_B: &str = "HxwdHhsaGRgXFhUUExIREA8ODQwLCgkIBwYFBAMCAQA";
+
+    fn cipher(spec: &str) -> FieldCipher {
+        FieldCipher::from_spec(spec).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let c = cipher(&format!("a:{}", KEY_A));
+        let stored = c.encrypt(EncryptedColumn::UserPhone, "+84901234567");
+
+        assert!(stored.starts_with("enc:v1:a:"));
+        assert!(!stored.contains("84901234567"));
+        assert_eq!(
+            c.decrypt(EncryptedColumn::UserPhone, &stored).unwrap(),
+            OpenedField { value: "+84901234567".into(), stale: false }
+        );
+    }
+
+    #[test]
+    fn test_random_data_key_per_value() {
+        let c = cipher(&format!("a:{}", KEY_A));
+        assert_ne!(
+            c.encrypt(EncryptedColumn::WebhookSecret, "same"),
+            c.encrypt(EncryptedColumn::WebhookSecret, "same")
+        );
+    }
+
+    #[test]
+    fn test_plaintext_is_stale() {
+        let c = cipher(&format!("a:{}", KEY_A));
+        let opened = c.decrypt(EncryptedColumn::MfaSecret, "JBSWY3DPEHPK3PXP").unwrap();
+
+        assert_eq!(opened.value, "JBSWY3DPEHPK3PXP");
+        assert!(opened.stale);
+    }
+
+    #[test]
+    fn test_rotation_keeps_old_values_readable() {
+        let old = cipher(&format!("a:{}", KEY_A));
+        let stored = old.encrypt(EncryptedColumn::MfaPhone, "0901234567");
+
+        let rotated = cipher(&format!("b:{},a:{}", KEY_B, KEY_A));
+        let opened = rotated.decrypt(EncryptedColumn::MfaPhone, &stored).unwrap();
+        assert_eq!(opened.value, "0901234567");
+        assert!(opened.stale);
+
+        let rewrapped = rotated.rewrap(EncryptedColumn::MfaPhone, &stored).unwrap().unwrap();
+        assert!(rewrapped.starts_with("enc:v1:b:"));
+        assert_eq!(rotated.rewrap(EncryptedColumn::MfaPhone, &rewrapped).unwrap(), None);
+    }
+
+    #[test]
+    fn test_unknown_key_and_tampering_are_rejected() {
+        let a = cipher(&format!("a:{}", KEY_A));
+        let b = cipher(&format!("b:{}", KEY_B));
+        let stored = a.encrypt(EncryptedColumn::UserPhone, "0901234567");
+
+        assert_eq!(
+            b.decrypt(EncryptedColumn::UserPhone, &stored),
+            Err(FieldCryptoError::UnknownKey("a".into()))
+        );
+
+        let mut tampered = stored.clone();
+        let last = tampered.pop().unwrap();
+        tampered.push(if last == 'A' { 'B' } else { 'A' });
+        assert!(a.decrypt(EncryptedColumn::UserPhone, &tampered).is_err());
+    }
+
+    #[test]
+    fn test_values_are_bound_to_their_column() {
+        let c = cipher(&format!("a:{}", KEY_A));
+        let stored = c.encrypt(EncryptedColumn::UserPhone, "0901234567");
+
+        assert_eq!(
+            c.decrypt(EncryptedColumn::MfaPhone, &stored),
+            Err(FieldCryptoError::Tampered)
+        );
+    }
+
+    #[test]
+    fn test_spec_parsing() {
+        assert!(FieldCipher::from_spec("").unwrap().is_none());
+        assert!(FieldCipher::from_spec("a:short").is_err());
+        assert!(FieldCipher::from_spec(&format!("a:{},a:{}", KEY_A, KEY_B)).is_err());
+        assert!(FieldCipher::from_spec(&format!("bad id:{}", KEY_A)).is_err());
+        assert_eq!(cipher(&format!("b:{}, a:{}", KEY_B, KEY_A)).active_key_id(), "b");
+    }
+}
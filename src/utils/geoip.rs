@@ -0,0 +1,197 @@
+//! IP geolocation from a local database
+//!
+//! `GEOIP_DATABASE` points to a CSV file with a header row and at least the
+//! `network` (CIDR), `latitude` and `longitude` columns, such as the MaxMind
+//! GeoLite2 City blocks files (IPv4 and IPv6 files can be concatenated after
+//! removing the second header). A `country_iso_code` or `country` column is
+//! used when present. Fields must not contain commas. The file is read once
+//! at startup; addresses outside every network have no location.
+
+use std::net::IpAddr;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+/// Mean radius of the earth, for great-circle distances
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Approximate location of an address
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GeoLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// ISO 3166 country code, if the database has one
+    pub country: Option<String>,
+}
+
+impl GeoLocation {
+    /// Great-circle distance to another location in kilometres
+    pub fn distance_km(&self, other: &GeoLocation) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.longitude - self.longitude).to_radians();
+
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().min(1.0).asin()
+    }
+}
+
+/// Networks and their locations, sorted by first address
+#[derive(Debug, Default)]
+pub struct GeoIpDatabase {
+    /// (first address, last address, location); IPv4 as IPv4-mapped IPv6
+    networks: Vec<(u128, u128, GeoLocation)>,
+}
+
+impl GeoIpDatabase {
+    /// Read a database file
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let csv = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Cannot read GEOIP_DATABASE '{}': {}", path, e))?;
+        Self::from_csv(&csv)
+    }
+
+    /// Parse a database; rows without coordinates are skipped
+    pub fn from_csv(csv: &str) -> anyhow::Result<Self> {
+        let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+        let header: Vec<String> = lines
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("GeoIP database is empty"))?
+            .split(',')
+            .map(|column| unquote(column).to_ascii_lowercase())
+            .collect();
+        let column = |name: &str| header.iter().position(|c| c == name);
+
+        let (network, latitude, longitude) = match (column("network"), column("latitude"), column("longitude")) {
+            (Some(n), Some(lat), Some(lon)) => (n, lat, lon),
+            _ => anyhow::bail!("GeoIP database needs network, latitude and longitude columns"),
+        };
+        let country = column("country_iso_code").or_else(|| column("country"));
+
+        let mut networks = Vec::new();
+        for (index, line) in lines.enumerate() {
+            let fields: Vec<&str> = line.split(',').map(unquote).collect();
+            let field = |i: usize| fields.get(i).copied().unwrap_or_default();
+
+            let (Ok(lat), Ok(lon)) = (field(latitude).parse::<f64>(), field(longitude).parse::<f64>()) else {
+                continue;
+            };
+            let (first, last) = network_bounds(field(network))
+                .ok_or_else(|| anyhow::anyhow!("Invalid network '{}' on line {}", field(network), index + 2))?;
+
+            networks.push((
+                first,
+                last,
+                GeoLocation {
+                    latitude: lat,
+                    longitude: lon,
+                    country: country.map(field).filter(|c| !c.is_empty()).map(String::from),
+                },
+            ));
+        }
+        networks.sort_by_key(|(first, _, _)| *first);
+
+        Ok(Self { networks })
+    }
+
+    /// Location of an address, if a network contains it
+    pub fn lookup(&self, ip: &str) -> Option<&GeoLocation> {
+        let addr = to_u128(ip.parse().ok()?);
+        let candidate = self.networks.partition_point(|(first, _, _)| *first <= addr).checked_sub(1)?;
+        let (_, last, location) = &self.networks[candidate];
+        (addr <= *last).then_some(location)
+    }
+
+    pub fn len(&self) -> usize {
+        self.networks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.networks.is_empty()
+    }
+}
+
+fn unquote(field: &str) -> &str {
+    field.trim().trim_matches('"')
+}
+
+fn to_u128(addr: IpAddr) -> u128 {
+    match addr {
+        IpAddr::V4(v4) => u128::from(v4.to_ipv6_mapped()),
+        IpAddr::V6(v6) => u128::from(v6),
+    }
+}
+
+/// First and last address of a CIDR network
+fn network_bounds(network: &str) -> Option<(u128, u128)> {
+    let (addr, prefix) = network.split_once('/')?;
+    let addr: IpAddr = addr.parse().ok()?;
+    let prefix: u32 = prefix.parse().ok()?;
+    let prefix = match addr {
+        IpAddr::V4(_) if prefix <= 32 => prefix + 96,
+        IpAddr::V6(_) if prefix <= 128 => prefix,
+        _ => return None,
+    };
+
+    let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+    let first = to_u128(addr) & mask;
+    Some((first, first | !mask))
+}
+
+static GEOIP_DATABASE: OnceLock<GeoIpDatabase> = OnceLock::new();
+
+/// Install the database (called once at startup)
+pub fn init_geoip(database: GeoIpDatabase) {
+    let _ = GEOIP_DATABASE.set(database);
+}
+
+/// Whether a database is installed
+pub fn geoip_enabled() -> bool {
+    GEOIP_DATABASE.get().is_some()
+}
+
+/// Location of an address in the installed database
+pub fn locate(ip: &str) -> Option<GeoLocation> {
+    GEOIP_DATABASE.get()?.lookup(ip).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "network,geoname_id,country_iso_code,latitude,longitude\n\
+        203.0.113.0/24,1,VN,21.0285,105.8542\n\
+        198.51.100.0/25,2,FR,48.8566,2.3522\n\
+        198.51.100.128/25,3,,,\n\
+        2001:db8::/32,4,US,40.7128,-74.0060\n";
+
+    #[test]
+    fn test_lookup_finds_the_containing_network() {
+        let db = GeoIpDatabase::from_csv(CSV).unwrap();
+        assert_eq!(db.len(), 3);
+
+        assert_eq!(db.lookup("203.0.113.200").unwrap().country.as_deref(), Some("VN"));
+        assert_eq!(db.lookup("198.51.100.1").unwrap().country.as_deref(), Some("FR"));
+        assert_eq!(db.lookup("2001:db8::1").unwrap().country.as_deref(), Some("US"));
+        assert!(db.lookup("198.51.100.200").is_none());
+        assert!(db.lookup("192.0.2.1").is_none());
+        assert!(db.lookup("not-an-ip").is_none());
+    }
+
+    #[test]
+    fn test_from_csv_requires_the_columns() {
+        assert!(GeoIpDatabase::from_csv("").is_err());
+        assert!(GeoIpDatabase::from_csv("network,latitude\n10.0.0.0/8,1").is_err());
+        assert!(GeoIpDatabase::from_csv("network,latitude,longitude\n10.0.0.0/40,1,2").is_err());
+    }
+
+    #[test]
+    fn test_distance_km() {
+        let hanoi = GeoLocation { latitude: 21.0285, longitude: 105.8542, country: None };
+        let paris = GeoLocation { latitude: 48.8566, longitude: 2.3522, country: None };
+
+        let distance = hanoi.distance_km(&paris);
+        assert!((distance - 9190.0).abs() < 50.0, "{}", distance);
+        assert_eq!(hanoi.distance_km(&hanoi), 0.0);
+    }
+}
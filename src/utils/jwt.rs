@@ -1,12 +1,16 @@
 use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, Header, Validation};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
 use crate::error::AuthError;
+use crate::utils::claims_size::{estimate_token_size, ClaimsSizePolicy};
+use crate::utils::posture::PostureContext;
+use crate::utils::signing_key::{JwkSet, SigningKey, VerificationKey};
 
 /// Claims for each app in the user JWT token (roles/permissions per app)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -32,6 +36,20 @@ pub struct AppTokenClaims {
     pub exp: i64,
     /// Issued at timestamp (Unix timestamp)
     pub iat: i64,
+    /// Confirmation claim binding the token to its caller (opt-in per app)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cnf: Option<TokenConfirmation>,
+}
+
+/// Confirmation (`cnf`) claim for sender-constrained app tokens (RFC 7800)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TokenConfirmation {
+    /// Network (CIDR) the caller's IP must belong to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip_range: Option<String>,
+    /// SHA-256 thumbprint of the caller's client certificate (RFC 8705)
+    #[serde(rename = "x5t#S256", default, skip_serializing_if = "Option::is_none")]
+    pub x5t_s256: Option<String>,
 }
 
 impl AppTokenClaims {
@@ -44,6 +62,7 @@ impl AppTokenClaims {
             token_type: "app".to_string(),
             exp: (now + Duration::seconds(expiry_secs)).timestamp(),
             iat: now.timestamp(),
+            cnf: None,
         }
     }
 
@@ -77,10 +96,15 @@ pub struct OAuth2Claims {
     pub iat: i64,
     /// Token type - "oauth2" to distinguish from other token types
     pub token_type: String,
+    /// Posture of the sign-in, for clients that opted in (`posture_claims`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub azp_ctx: Option<PostureContext>,
 }
 
 impl OAuth2Claims {
-    /// Maximum allowed expiration for OAuth2 access tokens (15 minutes = 900 seconds)
+    /// Default maximum expiration for OAuth2 access tokens (15 minutes = 900 seconds)
+    ///
+    /// The server maximum is configured with OAUTH_MAX_ACCESS_TOKEN_TTL_SECS.
     pub const MAX_ACCESS_TOKEN_EXPIRY_SECS: i64 = 900;
 
     /// Create new OAuth2 claims for a user token
@@ -89,18 +113,17 @@ impl OAuth2Claims {
     /// * `user_id` - The user's UUID
     /// * `client_id` - The OAuth client's ID
     /// * `scopes` - The granted scopes
-    /// * `expiry_secs` - Token expiry in seconds (capped at MAX_ACCESS_TOKEN_EXPIRY_SECS)
+    /// * `expiry_secs` - Token expiry in seconds (already capped by the caller)
     pub fn new(user_id: Uuid, client_id: &str, scopes: Vec<String>, expiry_secs: i64) -> Self {
         let now = Utc::now();
-        // Cap expiry at maximum allowed (15 minutes)
-        let actual_expiry = expiry_secs.min(Self::MAX_ACCESS_TOKEN_EXPIRY_SECS);
         Self {
             sub: user_id.to_string(),
             aud: client_id.to_string(),
             scope: scopes,
-            exp: (now + Duration::seconds(actual_expiry)).timestamp(),
+            exp: (now + Duration::seconds(expiry_secs)).timestamp(),
             iat: now.timestamp(),
             token_type: "oauth2".to_string(),
+            azp_ctx: None,
         }
     }
 
@@ -109,18 +132,17 @@ impl OAuth2Claims {
     /// # Arguments
     /// * `client_id` - The OAuth client's ID (used as both sub and aud)
     /// * `scopes` - The granted scopes
-    /// * `expiry_secs` - Token expiry in seconds (capped at MAX_ACCESS_TOKEN_EXPIRY_SECS)
+    /// * `expiry_secs` - Token expiry in seconds (already capped by the caller)
     pub fn new_client_credentials(client_id: &str, scopes: Vec<String>, expiry_secs: i64) -> Self {
         let now = Utc::now();
-        // Cap expiry at maximum allowed (15 minutes)
-        let actual_expiry = expiry_secs.min(Self::MAX_ACCESS_TOKEN_EXPIRY_SECS);
         Self {
             sub: client_id.to_string(),
             aud: client_id.to_string(),
             scope: scopes,
-            exp: (now + Duration::seconds(actual_expiry)).timestamp(),
+            exp: (now + Duration::seconds(expiry_secs)).timestamp(),
             iat: now.timestamp(),
             token_type: "oauth2".to_string(),
+            azp_ctx: None,
         }
     }
 
@@ -161,6 +183,108 @@ impl OAuth2Claims {
     }
 }
 
+/// OpenID Connect ID Token Claims
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdTokenClaims {
+    /// Issuer - the authorization server's base URL
+    pub iss: String,
+    /// Subject - user_id
+    pub sub: String,
+    /// Audience - client_id
+    pub aud: String,
+    /// Expiration timestamp (Unix timestamp)
+    pub exp: i64,
+    /// Issued at timestamp (Unix timestamp)
+    pub iat: i64,
+    /// Nonce from the authorization request, used by the client to detect replay
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// When the user authenticated (Unix timestamp)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_time: Option<i64>,
+    /// Authentication context class the user signed in with (`ACR_*`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acr: Option<String>,
+    /// Authentication methods used to sign in (RFC 8176)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amr: Option<Vec<String>>,
+    /// User's email - only when the granted scopes release it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+    /// Unique token ID
+    pub jti: String,
+}
+
+impl IdTokenClaims {
+    /// Create new ID token claims for a user
+    pub fn new(issuer: &str, user_id: Uuid, client_id: &str, nonce: Option<&str>, expiry_secs: i64) -> Self {
+        let now = Utc::now();
+        Self {
+            iss: issuer.to_string(),
+            sub: user_id.to_string(),
+            aud: client_id.to_string(),
+            exp: (now + Duration::seconds(expiry_secs)).timestamp(),
+            iat: now.timestamp(),
+            nonce: nonce.map(String::from),
+            auth_time: None,
+            acr: None,
+            amr: None,
+            email: None,
+            email_verified: None,
+            jti: Uuid::new_v4().to_string(),
+        }
+    }
+}
+
+/// User claims carried in an ID token besides the identifiers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IdTokenUserClaims {
+    /// When the user authenticated (Unix timestamp)
+    pub auth_time: Option<i64>,
+    pub acr: Option<String>,
+    pub amr: Option<Vec<String>>,
+    pub email: Option<String>,
+    pub email_verified: Option<bool>,
+}
+
+/// Event identifying a back-channel logout token
+pub const BACKCHANNEL_LOGOUT_EVENT: &str = "http://schemas.openid.net/event/backchannel-logout";
+
+/// How long a logout token is valid; it is delivered right away
+const LOGOUT_TOKEN_EXPIRY_SECS: i64 = 120;
+
+/// OpenID Connect Back-Channel Logout token claims
+///
+/// Identifies the user by `sub` only: sessions are not tracked per client,
+/// so there is no `sid`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LogoutTokenClaims {
+    pub iss: String,
+    /// User that signed out
+    pub sub: String,
+    /// Client being notified
+    pub aud: String,
+    pub iat: i64,
+    pub exp: i64,
+    pub jti: String,
+    /// `{"http://schemas.openid.net/event/backchannel-logout": {}}`
+    pub events: serde_json::Value,
+}
+
+/// Signed userinfo response (OpenID Connect Core 1.0, section 5.3.2)
+///
+/// The released userinfo claims plus the issuer and the client as audience.
+#[derive(Debug, Clone, Serialize)]
+struct SignedUserInfoClaims<'a, T: Serialize> {
+    iss: &'a str,
+    aud: &'a str,
+    iat: i64,
+    #[serde(flatten)]
+    userinfo: &'a T,
+}
+
 /// JWT Claims structure
 /// 
 /// # Requirements
@@ -176,6 +300,31 @@ pub struct Claims {
     pub exp: i64,
     /// Issued at timestamp (Unix timestamp)
     pub iat: i64,
+    /// Unique token ID, recorded in the token lineage
+    /// (absent on access tokens issued before every token carried one)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    /// When the user signed in (Unix timestamp) - kept across refreshes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_time: Option<i64>,
+    /// App code the claims are limited to, for tokens requested with `app=code`;
+    /// refresh tokens carry it so refreshed tokens stay scoped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    /// Authentication context class of the sign-in (`ACR_*`) - kept across refreshes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acr: Option<String>,
+    /// Authentication methods of the sign-in (RFC 8176) - kept across refreshes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amr: Option<Vec<String>>,
+    /// Digest of the full app claims when `apps` was reduced to fit the
+    /// token size policy; resolve them with `GET /auth/claims`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apps_ref: Option<String>,
+    /// Session the token was issued for - kept across refreshes
+    /// (absent on tokens issued before sessions were bound to their tokens)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
 }
 
 impl Claims {
@@ -187,14 +336,85 @@ impl Claims {
             apps,
             exp: (now + Duration::seconds(expiry_secs)).timestamp(),
             iat: now.timestamp(),
+            jti: Some(Uuid::new_v4().to_string()),
+            auth_time: Some(now.timestamp()),
+            app: None,
+            acr: None,
+            amr: None,
+            apps_ref: None,
+            sid: None,
         }
     }
 
+    /// When the user signed in, for tokens issued before auth_time was recorded
+    pub fn auth_time(&self) -> i64 {
+        self.auth_time.unwrap_or(self.iat)
+    }
+
     /// Get the user_id from claims
     pub fn user_id(&self) -> Result<Uuid, AuthError> {
         Uuid::parse_str(&self.sub)
             .map_err(|_| AuthError::InvalidToken)
     }
+
+    /// The session the token was issued for, if it carries one
+    pub fn session_id(&self) -> Option<Uuid> {
+        self.sid.as_deref().and_then(|sid| Uuid::parse_str(sid).ok())
+    }
+}
+
+/// Claims shared by every token issued for one sign-in
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionClaims {
+    /// When the user signed in (Unix timestamp)
+    pub auth_time: i64,
+    /// App code the tokens are scoped to
+    pub app: Option<String>,
+    /// Authentication context class the user signed in with (`ACR_*`)
+    pub acr: Option<String>,
+    /// Authentication methods the user signed in with (RFC 8176)
+    pub amr: Option<Vec<String>>,
+    /// Latest expiry of the access token (Unix timestamp), e.g. when the
+    /// access window of a role in it closes
+    pub access_until: Option<i64>,
+    /// Session the tokens belong to
+    pub session_id: Option<Uuid>,
+}
+
+/// Authentication context class of a sign-in with a password (or another
+/// single factor)
+pub const ACR_PASSWORD: &str = "pwd";
+
+/// Authentication context class of a sign-in that completed a second factor
+pub const ACR_MFA: &str = "mfa";
+
+/// Authentication context class of a sign-in with a phishing-resistant
+/// authenticator (passkey)
+pub const ACR_PHISHING_RESISTANT: &str = "phr";
+
+/// Authentication context classes advertised in discovery, weakest first
+pub const ACR_VALUES_SUPPORTED: &[&str] = &[ACR_PASSWORD, ACR_MFA, ACR_PHISHING_RESISTANT];
+
+/// Algorithms a client can ask userinfo responses to be signed with
+pub const USERINFO_SIGNING_ALG_VALUES_SUPPORTED: &[&str] = &["RS256"];
+
+/// ID and claims of an issued token, for recording its lineage
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssuedToken {
+    pub jti: String,
+    /// Expiration timestamp (Unix timestamp)
+    pub exp: i64,
+    pub app: Option<String>,
+}
+
+impl IssuedToken {
+    fn of(claims: &Claims) -> Self {
+        Self {
+            jti: claims.jti.clone().unwrap_or_default(),
+            exp: claims.exp,
+            app: claims.app.clone(),
+        }
+    }
 }
 
 /// Token pair returned on login/refresh
@@ -204,6 +424,11 @@ pub struct TokenPair {
     pub refresh_token: String,
     pub token_type: String,
     pub expires_in: i64,
+    /// Set on session token pairs
+    #[serde(skip)]
+    pub access: Option<IssuedToken>,
+    #[serde(skip)]
+    pub refresh: Option<IssuedToken>,
 }
 
 impl TokenPair {
@@ -213,21 +438,35 @@ impl TokenPair {
             refresh_token,
             token_type: "Bearer".to_string(),
             expires_in,
+            access: None,
+            refresh: None,
         }
     }
 }
 
+/// Keys a JwtManager currently signs and verifies with
+struct KeyRing {
+    signer: SigningKey,
+    /// Keys tokens may carry: the signer, a staged next key and recently retired keys
+    verifiers: Vec<VerificationKey>,
+}
+
 /// JWT token manager for creating and verifying tokens
 /// 
+/// Clones share one key ring, so keys installed by the signing key service
+/// take effect everywhere without a restart.
+/// 
 /// # Requirements
 /// - 10.3: Sign all tokens using RS256 algorithm
 /// - 10.4: Use public/private key pairs, not shared secrets
 #[derive(Clone)]
 pub struct JwtManager {
-    encoding_key: Arc<EncodingKey>,
-    decoding_key: Arc<DecodingKey>,
+    keys: Arc<RwLock<Arc<KeyRing>>>,
+    /// Key pair from JWT_PRIVATE_KEY / JWT_PUBLIC_KEY
+    config_key: Arc<SigningKey>,
     access_token_expiry_secs: i64,
     refresh_token_expiry_secs: i64,
+    claims_policy: ClaimsSizePolicy,
 }
 
 impl JwtManager {
@@ -244,26 +483,111 @@ impl JwtManager {
         access_token_expiry_secs: i64,
         refresh_token_expiry_secs: i64,
     ) -> Result<Self, AuthError> {
-        // Try PKCS#8 format first (BEGIN PRIVATE KEY), then fall back to PKCS#1 (BEGIN RSA PRIVATE KEY)
-        let encoding_key = if private_key_pem.contains("BEGIN PRIVATE KEY") {
-            EncodingKey::from_rsa_pem(private_key_pem.as_bytes())
-                .map_err(|e| AuthError::InternalError(anyhow::anyhow!("Invalid private key: {}", e)))?
-        } else {
-            EncodingKey::from_rsa_pem(private_key_pem.as_bytes())
-                .map_err(|e| AuthError::InternalError(anyhow::anyhow!("Invalid private key: {}", e)))?
+        // Accepts both PKCS#8 (BEGIN PRIVATE KEY) and PKCS#1 (BEGIN RSA PRIVATE KEY)
+        let config_key = SigningKey::from_pem(private_key_pem, public_key_pem)
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("Invalid JWT key pair: {}", e)))?;
+
+        let ring = KeyRing {
+            verifiers: vec![config_key.verification.clone()],
+            signer: config_key.clone(),
         };
-        
-        let decoding_key = DecodingKey::from_rsa_pem(public_key_pem.as_bytes())
-            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("Invalid public key: {}", e)))?;
-        
+
         Ok(Self {
-            encoding_key: Arc::new(encoding_key),
-            decoding_key: Arc::new(decoding_key),
+            keys: Arc::new(RwLock::new(Arc::new(ring))),
+            config_key: Arc::new(config_key),
             access_token_expiry_secs,
             refresh_token_expiry_secs,
+            claims_policy: ClaimsSizePolicy::default(),
         })
     }
 
+    /// Apply a size policy and claims mode to user access tokens
+    pub fn with_claims_policy(mut self, claims_policy: ClaimsSizePolicy) -> Self {
+        self.claims_policy = claims_policy;
+        self
+    }
+
+    /// Key pair loaded from the configuration
+    pub fn config_key(&self) -> &SigningKey {
+        &self.config_key
+    }
+
+    /// Switch the signing key and the set of keys accepted for verification
+    ///
+    /// The signer is always accepted. Tokens signed by a key that is no
+    /// longer in `verifiers` stop verifying.
+    pub fn install_keys(&self, signer: SigningKey, mut verifiers: Vec<VerificationKey>) {
+        if !verifiers.iter().any(|k| k.kid() == signer.kid()) {
+            verifiers.insert(0, signer.verification.clone());
+        }
+
+        let ring = Arc::new(KeyRing { signer, verifiers });
+        *self.keys.write().unwrap_or_else(|e| e.into_inner()) = ring;
+    }
+
+    /// Key ID new tokens are signed with
+    pub fn signing_kid(&self) -> String {
+        self.key_ring().signer.kid().to_string()
+    }
+
+    /// Public keys accepted for verification, as a JWK Set
+    pub fn jwks(&self) -> JwkSet {
+        JwkSet {
+            keys: self.key_ring().verifiers.iter().map(|k| k.jwk.clone()).collect(),
+        }
+    }
+
+    fn key_ring(&self) -> Arc<KeyRing> {
+        self.keys.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Sign claims with the current signing key, naming it in the `kid` header
+    fn sign<T: Serialize>(&self, claims: &T, token_kind: &str) -> Result<String, AuthError> {
+        let ring = self.key_ring();
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(ring.signer.kid().to_string());
+
+        encode(&header, claims, ring.signer.encoding_key())
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("{} encoding failed: {}", token_kind, e)))
+    }
+
+    /// Sign user access claims in the configured claims mode, enforcing the size policy
+    fn sign_access_claims(&self, mut claims: Claims) -> Result<String, AuthError> {
+        claims.apps_ref = self.claims_policy.mode.apply(&mut claims.apps);
+
+        let ring = self.key_ring();
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(ring.signer.kid().to_string());
+        let size = estimate_token_size(&header, &claims, ring.signer.verification.jwk.n.len());
+        self.claims_policy.check(size, &claims.sub)?;
+
+        self.sign(&claims, "Token")
+    }
+
+    /// Verify a token with the key named by its `kid` header
+    ///
+    /// Tokens issued before key IDs were added have no `kid` and are tried
+    /// against every accepted key.
+    fn verify_claims<T: DeserializeOwned>(&self, token: &str, validation: &Validation) -> Result<T, AuthError> {
+        let kid = decode_header(token).map_err(|_| AuthError::InvalidToken)?.kid;
+        let ring = self.key_ring();
+
+        let mut result = Err(AuthError::InvalidToken);
+        for key in ring.verifiers.iter().filter(|k| kid.as_deref().is_none_or(|kid| k.kid() == kid)) {
+            match decode::<T>(token, key.decoding_key(), validation) {
+                Ok(data) => return Ok(data.claims),
+                Err(e) => {
+                    result = Err(match e.kind() {
+                        jsonwebtoken::errors::ErrorKind::ExpiredSignature => AuthError::TokenExpired,
+                        _ => AuthError::InvalidToken,
+                    })
+                }
+            }
+        }
+
+        result
+    }
+
     /// Create an access token for a user
     /// 
     /// # Arguments
@@ -284,10 +608,7 @@ impl JwtManager {
     ) -> Result<String, AuthError> {
         let claims = Claims::new(user_id, apps, self.access_token_expiry_secs);
         
-        let header = Header::new(Algorithm::RS256);
-        
-        encode(&header, &claims, &self.encoding_key)
-            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("Token encoding failed: {}", e)))
+        self.sign_access_claims(claims)
     }
 
     /// Create a refresh token for a user
@@ -299,13 +620,20 @@ impl JwtManager {
     /// * `Ok(String)` - The JWT refresh token
     /// * `Err(AuthError)` - If token creation fails
     pub fn create_refresh_token(&self, user_id: Uuid) -> Result<String, AuthError> {
-        // Refresh tokens have minimal claims - just user_id
-        let claims = Claims::new(user_id, HashMap::new(), self.refresh_token_expiry_secs);
-        
-        let header = Header::new(Algorithm::RS256);
+        self.create_refresh_token_with_expiry(user_id, self.refresh_token_expiry_secs)
+    }
+
+    /// Create a refresh token with a custom lifetime (e.g. for device-bound sessions)
+    pub fn create_refresh_token_with_expiry(
+        &self,
+        user_id: Uuid,
+        expiry_secs: i64,
+    ) -> Result<String, AuthError> {
+        // Refresh tokens have minimal claims - just user_id and a unique jti,
+        // so two tokens issued within the same second never collide
+        let claims = Claims::new(user_id, HashMap::new(), expiry_secs);
         
-        encode(&header, &claims, &self.encoding_key)
-            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("Token encoding failed: {}", e)))
+        self.sign(&claims, "Token")
     }
 
     /// Create a token pair (access + refresh tokens)
@@ -332,6 +660,45 @@ impl JwtManager {
         ))
     }
 
+    /// Create a token pair carrying the claims of a sign-in
+    ///
+    /// Used on login and refresh, so refreshed tokens keep the original
+    /// sign-in time and app scope; the refresh token gets a custom lifetime
+    /// (e.g. for device-bound sessions).
+    pub fn create_session_token_pair(
+        &self,
+        user_id: Uuid,
+        apps: HashMap<String, AppClaims>,
+        refresh_expiry_secs: i64,
+        session: SessionClaims,
+    ) -> Result<TokenPair, AuthError> {
+        let mut claims = Claims::new(user_id, apps, self.access_token_expiry_secs);
+        claims.auth_time = Some(session.auth_time);
+        claims.app = session.app.clone();
+        claims.acr = session.acr.clone();
+        claims.amr = session.amr.clone();
+        claims.sid = session.session_id.map(|id| id.to_string());
+        if let Some(until) = session.access_until {
+            claims.exp = claims.exp.min(until);
+        }
+        let expires_in = claims.exp - claims.iat;
+        let access = IssuedToken::of(&claims);
+        let access_token = self.sign_access_claims(claims)?;
+
+        let mut refresh_claims = Claims::new(user_id, HashMap::new(), refresh_expiry_secs);
+        refresh_claims.auth_time = Some(session.auth_time);
+        refresh_claims.app = session.app;
+        refresh_claims.acr = session.acr;
+        refresh_claims.amr = session.amr;
+        refresh_claims.sid = session.session_id.map(|id| id.to_string());
+        let refresh_token = self.sign(&refresh_claims, "Token")?;
+
+        let mut pair = TokenPair::new(access_token, refresh_token, expires_in);
+        pair.access = Some(access);
+        pair.refresh = Some(IssuedToken::of(&refresh_claims));
+        Ok(pair)
+    }
+
     /// Verify and decode a JWT token
     /// 
     /// # Arguments
@@ -349,14 +716,7 @@ impl JwtManager {
         let mut validation = Validation::new(Algorithm::RS256);
         validation.validate_exp = true;
         
-        decode::<Claims>(token, &self.decoding_key, &validation)
-            .map(|data| data.claims)
-            .map_err(|e| {
-                match e.kind() {
-                    jsonwebtoken::errors::ErrorKind::ExpiredSignature => AuthError::TokenExpired,
-                    _ => AuthError::InvalidToken,
-                }
-            })
+        self.verify_claims::<Claims>(token, &validation)
     }
 
     /// Create an access token for an App (machine-to-machine authentication)
@@ -374,10 +734,22 @@ impl JwtManager {
     pub fn create_app_token(&self, app_id: Uuid) -> Result<String, AuthError> {
         let claims = AppTokenClaims::new(app_id, self.access_token_expiry_secs);
         
-        let header = Header::new(Algorithm::RS256);
-        
-        encode(&header, &claims, &self.encoding_key)
-            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("App token encoding failed: {}", e)))
+        self.sign(&claims, "App token")
+    }
+
+    /// Create an app token bound to its caller via a `cnf` claim
+    ///
+    /// `app_auth_middleware` rejects the token when presented from another
+    /// IP range or with another client certificate.
+    pub fn create_bound_app_token(
+        &self,
+        app_id: Uuid,
+        confirmation: TokenConfirmation,
+    ) -> Result<String, AuthError> {
+        let mut claims = AppTokenClaims::new(app_id, self.access_token_expiry_secs);
+        claims.cnf = Some(confirmation);
+
+        self.sign(&claims, "App token")
     }
 
     /// Verify and decode an App JWT token
@@ -397,14 +769,7 @@ impl JwtManager {
         let mut validation = Validation::new(Algorithm::RS256);
         validation.validate_exp = true;
         
-        let claims = decode::<AppTokenClaims>(token, &self.decoding_key, &validation)
-            .map(|data| data.claims)
-            .map_err(|e| {
-                match e.kind() {
-                    jsonwebtoken::errors::ErrorKind::ExpiredSignature => AuthError::TokenExpired,
-                    _ => AuthError::InvalidToken,
-                }
-            })?;
+        let claims = self.verify_claims::<AppTokenClaims>(token, &validation)?;
         
         // Verify this is actually an app token
         if !claims.is_app_token() {
@@ -434,12 +799,36 @@ impl JwtManager {
         client_id: &str,
         scopes: Vec<String>,
     ) -> Result<String, AuthError> {
-        let claims = OAuth2Claims::new(user_id, client_id, scopes, self.access_token_expiry_secs);
-        
-        let header = Header::new(Algorithm::RS256);
+        let expiry_secs = self.access_token_expiry_secs.min(OAuth2Claims::MAX_ACCESS_TOKEN_EXPIRY_SECS);
+        self.create_oauth2_token_with_expiry(user_id, client_id, scopes, expiry_secs)
+    }
+
+    /// Create an OAuth2 access token for a user that expires after `expiry_secs`
+    pub fn create_oauth2_token_with_expiry(
+        &self,
+        user_id: Uuid,
+        client_id: &str,
+        scopes: Vec<String>,
+        expiry_secs: i64,
+    ) -> Result<String, AuthError> {
+        self.create_oauth2_token_with_posture(user_id, client_id, scopes, expiry_secs, None)
+    }
+
+    /// Create an OAuth2 access token for a user carrying the sign-in posture (`azp_ctx`)
+    pub fn create_oauth2_token_with_posture(
+        &self,
+        user_id: Uuid,
+        client_id: &str,
+        scopes: Vec<String>,
+        expiry_secs: i64,
+        azp_ctx: Option<PostureContext>,
+    ) -> Result<String, AuthError> {
+        let claims = OAuth2Claims {
+            azp_ctx,
+            ..OAuth2Claims::new(user_id, client_id, scopes, expiry_secs)
+        };
         
-        encode(&header, &claims, &self.encoding_key)
-            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("OAuth2 token encoding failed: {}", e)))
+        self.sign(&claims, "OAuth2 token")
     }
 
     /// Create an OAuth2 access token for client credentials flow (no user)
@@ -460,12 +849,103 @@ impl JwtManager {
         client_id: &str,
         scopes: Vec<String>,
     ) -> Result<String, AuthError> {
-        let claims = OAuth2Claims::new_client_credentials(client_id, scopes, self.access_token_expiry_secs);
+        let expiry_secs = self.access_token_expiry_secs.min(OAuth2Claims::MAX_ACCESS_TOKEN_EXPIRY_SECS);
+        self.create_oauth2_client_credentials_token_with_expiry(client_id, scopes, expiry_secs)
+    }
+
+    /// Create a client credentials access token that expires after `expiry_secs`
+    pub fn create_oauth2_client_credentials_token_with_expiry(
+        &self,
+        client_id: &str,
+        scopes: Vec<String>,
+        expiry_secs: i64,
+    ) -> Result<String, AuthError> {
+        let claims = OAuth2Claims::new_client_credentials(client_id, scopes, expiry_secs);
         
-        let header = Header::new(Algorithm::RS256);
+        self.sign(&claims, "OAuth2 client credentials token")
+    }
+
+    /// Create an OpenID Connect ID token for a user
+    /// 
+    /// # Arguments
+    /// * `issuer` - The authorization server's issuer identifier
+    /// * `user_id` - The user's UUID
+    /// * `client_id` - The OAuth client's ID
+    /// * `nonce` - The nonce from the authorization request, if any
+    /// * `user_claims` - auth_time and the email claims released to the client
+    /// 
+    /// # Returns
+    /// * `Ok(String)` - The signed ID token
+    /// * `Err(AuthError)` - If token creation fails
+    pub fn create_id_token(
+        &self,
+        issuer: &str,
+        user_id: Uuid,
+        client_id: &str,
+        nonce: Option<&str>,
+        user_claims: IdTokenUserClaims,
+    ) -> Result<String, AuthError> {
+        let mut claims = IdTokenClaims::new(issuer, user_id, client_id, nonce, self.access_token_expiry_secs);
+        claims.auth_time = user_claims.auth_time;
+        claims.acr = user_claims.acr;
+        claims.amr = user_claims.amr;
+        claims.email = user_claims.email;
+        claims.email_verified = user_claims.email_verified;
         
-        encode(&header, &claims, &self.encoding_key)
-            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("OAuth2 client credentials token encoding failed: {}", e)))
+        self.sign(&claims, "ID token")
+    }
+
+    /// Create a back-channel logout token telling a client the user signed out
+    pub fn create_logout_token(&self, issuer: &str, user_id: Uuid, client_id: &str) -> Result<String, AuthError> {
+        let now = Utc::now();
+        let claims = LogoutTokenClaims {
+            iss: issuer.to_string(),
+            sub: user_id.to_string(),
+            aud: client_id.to_string(),
+            iat: now.timestamp(),
+            exp: (now + Duration::seconds(LOGOUT_TOKEN_EXPIRY_SECS)).timestamp(),
+            jti: Uuid::new_v4().to_string(),
+            events: serde_json::json!({ BACKCHANNEL_LOGOUT_EVENT: {} }),
+        };
+
+        let ring = self.key_ring();
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(ring.signer.kid().to_string());
+        header.typ = Some("logout+jwt".to_string());
+
+        encode(&header, &claims, ring.signer.encoding_key())
+            .map_err(|e| AuthError::InternalError(anyhow::anyhow!("Logout token encoding failed: {}", e)))
+    }
+
+    /// Sign a userinfo response for a client registered for signed userinfo
+    pub fn create_userinfo_token<T: Serialize>(
+        &self,
+        issuer: &str,
+        client_id: &str,
+        userinfo: &T,
+    ) -> Result<String, AuthError> {
+        let claims = SignedUserInfoClaims {
+            iss: issuer,
+            aud: client_id,
+            iat: Utc::now().timestamp(),
+            userinfo,
+        };
+
+        self.sign(&claims, "Userinfo response")
+    }
+
+    /// Verify an ID token passed back as `id_token_hint`
+    ///
+    /// The signature and issuer are checked but not the expiry: an expired
+    /// ID token still identifies the user and client (OpenID Connect
+    /// RP-Initiated Logout 1.0, section 2). The caller checks the audience.
+    pub fn verify_id_token_hint(&self, token: &str, issuer: &str) -> Result<IdTokenClaims, AuthError> {
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.validate_exp = false;
+        validation.validate_aud = false;
+        validation.set_issuer(&[issuer]);
+
+        self.verify_claims::<IdTokenClaims>(token, &validation)
     }
 
     /// Verify and decode an OAuth2 JWT token
@@ -487,14 +967,7 @@ impl JwtManager {
         // Disable audience validation since we handle it manually
         validation.validate_aud = false;
         
-        let claims = decode::<OAuth2Claims>(token, &self.decoding_key, &validation)
-            .map(|data| data.claims)
-            .map_err(|e| {
-                match e.kind() {
-                    jsonwebtoken::errors::ErrorKind::ExpiredSignature => AuthError::TokenExpired,
-                    _ => AuthError::InvalidToken,
-                }
-            })?;
+        let claims = self.verify_claims::<OAuth2Claims>(token, &validation)?;
         
         // Verify this is actually an OAuth2 token
         if !claims.is_oauth2_token() {
@@ -586,6 +1059,18 @@ mod tests {
         assert_eq!(token.split('.').count(), 3);
     }
 
+    #[test]
+    fn test_refresh_tokens_are_unique() {
+        let manager = create_test_jwt_manager();
+        let user_id = Uuid::new_v4();
+
+        let first = manager.create_refresh_token(user_id).unwrap();
+        let second = manager.create_refresh_token(user_id).unwrap();
+
+        assert_ne!(first, second);
+        assert!(manager.verify_token(&first).unwrap().jti.is_some());
+    }
+
     #[test]
     fn test_create_token_pair() {
         let manager = create_test_jwt_manager();
@@ -770,6 +1255,24 @@ mod tests {
         assert_eq!(claims.token_type, "app");
     }
 
+    #[test]
+    fn test_bound_app_token_carries_confirmation() {
+        let manager = create_test_jwt_manager();
+        let app_id = Uuid::new_v4();
+        let confirmation = TokenConfirmation {
+            ip_range: Some("203.0.113.0/24".to_string()),
+            x5t_s256: None,
+        };
+
+        let token = manager.create_bound_app_token(app_id, confirmation.clone()).unwrap();
+        let claims = manager.verify_app_token(&token).unwrap();
+
+        assert_eq!(claims.cnf, Some(confirmation));
+
+        let unbound = manager.verify_app_token(&manager.create_app_token(app_id).unwrap()).unwrap();
+        assert!(unbound.cnf.is_none());
+    }
+
     #[test]
     fn test_app_token_contains_app_context() {
         // Property 7: App Token Contains App Context
@@ -956,19 +1459,29 @@ mod tests {
     }
 
     #[test]
-    fn test_oauth2_claims_expiry_capped() {
-        // Even if we try to create claims with longer expiry, it should be capped
-        let user_id = Uuid::new_v4();
-        let client_id = "test-client-id";
-        let scopes = vec!["profile.read".to_string()];
-        
-        // Try to create claims with 1 hour expiry
-        let claims = OAuth2Claims::new(user_id, client_id, scopes, 3600);
-        
-        let duration = claims.exp - claims.iat;
-        
+    fn test_oauth2_token_expiry_capped() {
+        // Even if the manager is configured with a longer expiry, it should be capped
+        let (private_key, public_key) = get_test_keys();
+        let manager = JwtManager::new(&private_key, &public_key, 3600, 604800).unwrap();
+        let token = manager
+            .create_oauth2_token(Uuid::new_v4(), "test-client-id", vec!["profile.read".to_string()])
+            .unwrap();
+        let claims = manager.verify_oauth2_token(&token).unwrap();
+
         // Should be capped at 900 seconds (15 minutes)
-        assert_eq!(duration, 900);
+        assert_eq!(claims.exp - claims.iat, 900);
+    }
+
+    #[test]
+    fn test_oauth2_token_with_expiry() {
+        // A per-client lifetime is used as given; the caller applies the server maximum
+        let manager = create_test_jwt_manager();
+        let token = manager
+            .create_oauth2_token_with_expiry(Uuid::new_v4(), "test-client-id", vec![], 3600)
+            .unwrap();
+        let claims = manager.verify_oauth2_token(&token).unwrap();
+
+        assert_eq!(claims.exp - claims.iat, 3600);
     }
 
     #[test]
@@ -1093,4 +1606,269 @@ mod tests {
         // Fresh token should not be expired
         assert!(!claims.is_expired());
     }
+
+    #[test]
+    fn test_create_id_token_includes_nonce() {
+        let manager = create_test_jwt_manager();
+        let user_id = Uuid::new_v4();
+        
+        let token = manager
+            .create_id_token("https://auth.example.com", user_id, "client-id", Some("n-0S6_WzA2Mj"), IdTokenUserClaims::default())
+            .unwrap();
+        
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&["client-id"]);
+        let claims = decode::<IdTokenClaims>(&token, manager.config_key().verification.decoding_key(), &validation)
+            .unwrap()
+            .claims;
+        
+        assert_eq!(claims.iss, "https://auth.example.com");
+        assert_eq!(claims.sub, user_id.to_string());
+        assert_eq!(claims.nonce.as_deref(), Some("n-0S6_WzA2Mj"));
+        assert_eq!(claims.email, None);
+    }
+
+    #[test]
+    fn test_create_id_token_includes_auth_time_and_email() {
+        let manager = create_test_jwt_manager();
+        let user_id = Uuid::new_v4();
+        let user_claims = IdTokenUserClaims {
+            auth_time: Some(1_700_000_000),
+            acr: Some(ACR_MFA.to_string()),
+            amr: Some(vec!["pwd".to_string(), "otp".to_string()]),
+            email: Some("user@example.com".to_string()),
+            email_verified: Some(true),
+        };
+
+        let token = manager
+            .create_id_token("https://auth.example.com", user_id, "client-id", None, user_claims)
+            .unwrap();
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&["client-id"]);
+        let claims = decode::<IdTokenClaims>(&token, manager.config_key().verification.decoding_key(), &validation)
+            .unwrap()
+            .claims;
+
+        assert_eq!(claims.auth_time, Some(1_700_000_000));
+        assert_eq!(claims.acr.as_deref(), Some(ACR_MFA));
+        assert_eq!(claims.amr, Some(vec!["pwd".to_string(), "otp".to_string()]));
+        assert_eq!(claims.email.as_deref(), Some("user@example.com"));
+        assert_eq!(claims.email_verified, Some(true));
+    }
+
+    #[test]
+    fn test_verify_id_token_hint_accepts_expired_tokens() {
+        let manager = create_test_jwt_manager();
+        let user_id = Uuid::new_v4();
+
+        let mut claims = IdTokenClaims::new("https://auth.example.com", user_id, "client-id", None, 900);
+        claims.exp = Utc::now().timestamp() - 3600;
+        let expired = manager.sign(&claims, "ID token").unwrap();
+
+        let hint = manager.verify_id_token_hint(&expired, "https://auth.example.com").unwrap();
+        assert_eq!(hint.sub, user_id.to_string());
+        assert_eq!(hint.aud, "client-id");
+
+        // Other issuers and OAuth access tokens (no iss) are not hints
+        assert!(manager.verify_id_token_hint(&expired, "https://other.example.com").is_err());
+        let access_token = manager
+            .create_oauth2_token(user_id, "client-id", vec!["openid".to_string()])
+            .unwrap();
+        assert!(manager.verify_id_token_hint(&access_token, "https://auth.example.com").is_err());
+    }
+
+    #[test]
+    fn test_create_logout_token() {
+        let manager = create_test_jwt_manager();
+        let user_id = Uuid::new_v4();
+
+        let token = manager.create_logout_token("https://auth.example.com", user_id, "client-id").unwrap();
+
+        assert_eq!(decode_header(&token).unwrap().typ.as_deref(), Some("logout+jwt"));
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&["client-id"]);
+        let claims = decode::<LogoutTokenClaims>(&token, manager.config_key().verification.decoding_key(), &validation)
+            .unwrap()
+            .claims;
+
+        assert_eq!(claims.sub, user_id.to_string());
+        assert!(claims.events.get(BACKCHANNEL_LOGOUT_EVENT).is_some());
+        // Logout tokens must not carry a nonce
+        assert!(!serde_json::to_value(&claims).unwrap().as_object().unwrap().contains_key("nonce"));
+    }
+
+    #[test]
+    fn test_create_userinfo_token() {
+        let manager = create_test_jwt_manager();
+        let userinfo = serde_json::json!({ "sub": "user-1", "email": "ann@example.com" });
+
+        let token = manager
+            .create_userinfo_token("https://auth.example.com", "client-id", &userinfo)
+            .unwrap();
+
+        assert_eq!(decode_header(&token).unwrap().kid, Some(manager.signing_kid()));
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&["client-id"]);
+        validation.set_issuer(&["https://auth.example.com"]);
+        validation.set_required_spec_claims(&["iss", "aud"]);
+        validation.validate_exp = false;
+        let claims = decode::<serde_json::Value>(&token, manager.config_key().verification.decoding_key(), &validation)
+            .unwrap()
+            .claims;
+
+        assert_eq!(claims["sub"], "user-1");
+        assert_eq!(claims["email"], "ann@example.com");
+    }
+
+    #[test]
+    fn test_claims_policy_reduces_and_limits_access_tokens() {
+        use crate::utils::claims_size::{apps_digest, ClaimsMode, ClaimsSizePolicy};
+
+        let mut apps = HashMap::new();
+        apps.insert(
+            "app_a".to_string(),
+            AppClaims {
+                roles: vec!["admin".to_string()],
+                permissions: (0..200).map(|i| format!("resource_{}:read", i)).collect(),
+            },
+        );
+        let user_id = Uuid::new_v4();
+
+        let compressed = create_test_jwt_manager().with_claims_policy(ClaimsSizePolicy {
+            mode: ClaimsMode::Compressed,
+            warn_bytes: 0,
+            max_bytes: 2048,
+        });
+        let token = compressed.create_access_token(user_id, apps.clone()).unwrap();
+        let claims = compressed.verify_token(&token).unwrap();
+        assert!(claims.apps.is_empty());
+        assert_eq!(claims.apps_ref, Some(apps_digest(&apps)));
+
+        let full = create_test_jwt_manager().with_claims_policy(ClaimsSizePolicy {
+            mode: ClaimsMode::Full,
+            warn_bytes: 0,
+            max_bytes: 2048,
+        });
+        assert!(matches!(
+            full.create_access_token(user_id, apps),
+            Err(AuthError::TokenTooLarge { limit: 2048, .. })
+        ));
+    }
+
+    #[test]
+    fn test_session_token_pair_keeps_session_claims() {
+        let manager = create_test_jwt_manager();
+        let user_id = Uuid::new_v4();
+        let session_id = Uuid::new_v4();
+
+        let pair = manager
+            .create_session_token_pair(
+                user_id,
+                HashMap::new(),
+                3600,
+                SessionClaims {
+                    auth_time: 1_700_000_000,
+                    app: Some("app_a".to_string()),
+                    acr: Some(ACR_MFA.to_string()),
+                    amr: Some(vec!["pwd".to_string(), "otp".to_string()]),
+                    access_until: None,
+                    session_id: Some(session_id),
+                },
+            )
+            .unwrap();
+
+        let access = manager.verify_token(&pair.access_token).unwrap();
+        let refresh = manager.verify_token(&pair.refresh_token).unwrap();
+        assert_eq!(access.auth_time(), 1_700_000_000);
+        assert_eq!(refresh.auth_time(), 1_700_000_000);
+        assert!(refresh.jti.is_some());
+        assert_eq!(access.app.as_deref(), Some("app_a"));
+        assert_eq!(refresh.app.as_deref(), Some("app_a"));
+        assert_eq!(access.acr.as_deref(), Some(ACR_MFA));
+        assert_eq!(refresh.acr.as_deref(), Some(ACR_MFA));
+        assert_eq!(access.amr, refresh.amr);
+        assert_eq!(access.amr.as_deref().map(<[String]>::len), Some(2));
+        assert_eq!(access.session_id(), Some(session_id));
+        assert_eq!(refresh.session_id(), Some(session_id));
+    }
+
+    #[test]
+    fn test_session_token_pair_caps_access_token_at_access_until() {
+        let manager = create_test_jwt_manager();
+        let until = Utc::now().timestamp() + 60;
+
+        let pair = manager
+            .create_session_token_pair(
+                Uuid::new_v4(),
+                HashMap::new(),
+                3600,
+                SessionClaims { access_until: Some(until), ..SessionClaims::default() },
+            )
+            .unwrap();
+
+        let access = manager.verify_token(&pair.access_token).unwrap();
+        let refresh = manager.verify_token(&pair.refresh_token).unwrap();
+        assert_eq!(access.exp, until);
+        assert!(pair.expires_in <= 60);
+        assert!(refresh.exp > until);
+    }
+
+    #[test]
+    fn test_session_token_pair_reports_issued_jtis() {
+        let manager = create_test_jwt_manager();
+        let user_id = Uuid::new_v4();
+
+        let pair = manager
+            .create_session_token_pair(user_id, HashMap::new(), 3600, SessionClaims::default())
+            .unwrap();
+
+        let access = manager.verify_token(&pair.access_token).unwrap();
+        let refresh = manager.verify_token(&pair.refresh_token).unwrap();
+        let issued_access = pair.access.unwrap();
+        let issued_refresh = pair.refresh.unwrap();
+        assert_eq!(access.jti.as_deref(), Some(issued_access.jti.as_str()));
+        assert_eq!(refresh.jti.as_deref(), Some(issued_refresh.jti.as_str()));
+        assert_ne!(issued_access.jti, issued_refresh.jti);
+        assert_eq!(issued_refresh.exp, refresh.exp);
+    }
+
+    #[test]
+    fn test_installed_keys_sign_and_verify_by_kid() {
+        let manager = create_test_jwt_manager();
+        let user_id = Uuid::new_v4();
+        let old_token = manager.create_access_token(user_id, HashMap::new()).unwrap();
+        let old_kid = manager.signing_kid();
+        assert_eq!(decode_header(&old_token).unwrap().kid.as_deref(), Some(old_kid.as_str()));
+
+        let next = SigningKey::from_pem(
+            &std::fs::read_to_string("keys/test_rotation_private.pem").unwrap(),
+            &std::fs::read_to_string("keys/test_rotation_public.pem").unwrap(),
+        )
+        .unwrap();
+
+        // Promote the next key, keeping the old one as a verifier
+        manager.install_keys(next.clone(), vec![manager.config_key().verification.clone()]);
+        let new_token = manager.create_access_token(user_id, HashMap::new()).unwrap();
+
+        assert_eq!(manager.signing_kid(), next.kid());
+        assert_eq!(manager.jwks().keys.len(), 2);
+        assert!(manager.verify_token(&old_token).is_ok());
+        assert!(manager.verify_token(&new_token).is_ok());
+
+        // Once the old key is dropped its tokens no longer verify
+        manager.install_keys(next, vec![]);
+        assert!(matches!(manager.verify_token(&old_token), Err(AuthError::InvalidToken)));
+        assert!(manager.verify_token(&new_token).is_ok());
+    }
+
+    #[test]
+    fn test_id_tokens_have_unique_jti() {
+        let user_id = Uuid::new_v4();
+        let first = IdTokenClaims::new("iss", user_id, "client-id", None, 900);
+        let second = IdTokenClaims::new("iss", user_id, "client-id", None, 900);
+        
+        assert_ne!(first.jti, second.jti);
+        assert!(first.nonce.is_none());
+    }
 }
@@ -0,0 +1,374 @@
+//! Per-app and per-client SLO series in the OpenMetrics text format
+//!
+//! Sign-ins, OAuth token requests and webhook deliveries are counted in
+//! memory since the process started, labeled by app or OAuth client, and
+//! rendered by `GET /metrics`. Only the busiest apps and clients
+//! (`METRICS_TOP_N`) keep their own label; the others are summed under
+//! `other`, so the number of series stays bounded however many tenants the
+//! server has. Each instance reports its own counts.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bound on distinct apps or clients tracked per family
+const MAX_TRACKED_TENANTS: usize = 1_000;
+
+/// Upper bounds of the token latency buckets, in seconds
+const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Label of the tenants outside the top N, or past the tracking bound
+pub const OTHER_TENANT: &str = "other";
+
+/// App label of sign-ins that named no app
+pub const NO_APP: &str = "none";
+
+/// Client label of token requests whose client could not be identified
+pub const UNKNOWN_CLIENT: &str = "unknown";
+
+/// Content type of the rendered metrics
+pub const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// How a request ended, from the SLO point of view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Outcome {
+    Success,
+    /// First factor accepted, second factor pending (sign-ins only)
+    MfaRequired,
+    /// Rejected because of the request (wrong password, invalid grant...)
+    Failure,
+    /// Failed on the server's side
+    Error,
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::MfaRequired => "mfa_required",
+            Self::Failure => "failure",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// Series of one app or client that can be summed into `other`
+trait TenantSeries: Clone + Default {
+    /// Requests counted, used to rank tenants
+    fn volume(&self) -> u64;
+    fn merge(&mut self, other: &Self);
+}
+
+/// Requests by outcome
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct OutcomeCounts(BTreeMap<Outcome, u64>);
+
+impl TenantSeries for OutcomeCounts {
+    fn volume(&self) -> u64 {
+        self.0.values().sum()
+    }
+
+    fn merge(&mut self, other: &Self) {
+        for (outcome, count) in &other.0 {
+            *self.0.entry(*outcome).or_default() += count;
+        }
+    }
+}
+
+/// Latency histogram with the `LATENCY_BUCKETS` bounds
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Histogram {
+    /// Observations per bucket (not cumulative); slower ones are only in `count`
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, secs: f64) {
+        if let Some(i) = LATENCY_BUCKETS.iter().position(|bound| secs <= *bound) {
+            self.buckets[i] += 1;
+        }
+        self.sum += secs;
+        self.count += 1;
+    }
+
+    fn merge(&mut self, other: &Self) {
+        for (bucket, count) in self.buckets.iter_mut().zip(other.buckets) {
+            *bucket += count;
+        }
+        self.sum += other.sum;
+        self.count += other.count;
+    }
+}
+
+/// Token requests of one OAuth client
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ClientTokens {
+    /// Requests by grant type and outcome
+    requests: BTreeMap<(String, Outcome), u64>,
+    latency: Histogram,
+}
+
+impl TenantSeries for ClientTokens {
+    fn volume(&self) -> u64 {
+        self.latency.count
+    }
+
+    fn merge(&mut self, other: &Self) {
+        for (key, count) in &other.requests {
+            *self.requests.entry(key.clone()).or_default() += count;
+        }
+        self.latency.merge(&other.latency);
+    }
+}
+
+/// Counters of sign-ins, token requests and webhook deliveries
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    sign_ins: HashMap<String, OutcomeCounts>,
+    tokens: HashMap<String, ClientTokens>,
+    webhook_deliveries: HashMap<String, OutcomeCounts>,
+}
+
+/// Series of a tenant, or of `other` once the family tracks too many tenants
+fn tenant_entry<'a, T: Default>(map: &'a mut HashMap<String, T>, tenant: &str) -> &'a mut T {
+    let key = if map.contains_key(tenant) || map.len() < MAX_TRACKED_TENANTS {
+        tenant
+    } else {
+        OTHER_TENANT
+    };
+    map.entry(key.to_string()).or_default()
+}
+
+/// Keep the `top_n` busiest tenants and sum the others under `other`
+fn top_tenants<T: TenantSeries>(map: &HashMap<String, T>, top_n: usize) -> BTreeMap<String, T> {
+    let mut ranked: Vec<_> = map.iter().filter(|(tenant, _)| *tenant != OTHER_TENANT).collect();
+    ranked.sort_by(|(a, x), (b, y)| y.volume().cmp(&x.volume()).then_with(|| a.cmp(b)));
+    let kept: HashSet<&str> = ranked.iter().take(top_n).map(|(tenant, _)| tenant.as_str()).collect();
+
+    let mut collapsed = BTreeMap::new();
+    for (tenant, series) in map {
+        let key = if kept.contains(tenant.as_str()) { tenant.as_str() } else { OTHER_TENANT };
+        collapsed.entry(key.to_string()).or_insert_with(T::default).merge(series);
+    }
+    collapsed
+}
+
+/// Escape a label value (backslash, double quote and line feed)
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn write_outcome_family(out: &mut String, name: &str, help: &str, label: &str, series: &BTreeMap<String, OutcomeCounts>) {
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    for (tenant, counts) in series {
+        for (outcome, count) in &counts.0 {
+            let _ = writeln!(
+                out,
+                "{}_total{{{}=\"{}\",result=\"{}\"}} {}",
+                name,
+                label,
+                escape_label(tenant),
+                outcome.as_str(),
+                count
+            );
+        }
+    }
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a sign-in to `app` (its id or code as the client sent it)
+    pub fn record_sign_in(&mut self, app: Option<&str>, outcome: Outcome) {
+        let counts = tenant_entry(&mut self.sign_ins, app.unwrap_or(NO_APP));
+        *counts.0.entry(outcome).or_default() += 1;
+    }
+
+    /// Count a token endpoint request of `client_id` and how long it took
+    pub fn record_token_request(&mut self, client_id: Option<&str>, grant_type: &str, outcome: Outcome, elapsed: Duration) {
+        let client = tenant_entry(&mut self.tokens, client_id.unwrap_or(UNKNOWN_CLIENT));
+        *client.requests.entry((grant_type.to_string(), outcome)).or_default() += 1;
+        client.latency.observe(elapsed.as_secs_f64());
+    }
+
+    /// Count a webhook delivery attempt of `app_id`
+    pub fn record_webhook_delivery(&mut self, app_id: &str, delivered: bool) {
+        let counts = tenant_entry(&mut self.webhook_deliveries, app_id);
+        let outcome = if delivered { Outcome::Success } else { Outcome::Failure };
+        *counts.0.entry(outcome).or_default() += 1;
+    }
+
+    /// Render the series in the OpenMetrics text format, keeping `top_n` tenants per family
+    pub fn render(&self, top_n: usize) -> String {
+        let mut out = String::new();
+
+        write_outcome_family(
+            &mut out,
+            "auth_server_app_sign_ins",
+            "Password and email code sign-ins by app and result.",
+            "app",
+            &top_tenants(&self.sign_ins, top_n),
+        );
+
+        let tokens = top_tenants(&self.tokens, top_n);
+        let _ = writeln!(out, "# TYPE auth_server_client_token_requests counter");
+        let _ = writeln!(out, "# HELP auth_server_client_token_requests OAuth token endpoint requests by client, grant type and result.");
+        for (client, series) in &tokens {
+            for ((grant_type, outcome), count) in &series.requests {
+                let _ = writeln!(
+                    out,
+                    "auth_server_client_token_requests_total{{client_id=\"{}\",grant_type=\"{}\",result=\"{}\"}} {}",
+                    escape_label(client),
+                    escape_label(grant_type),
+                    outcome.as_str(),
+                    count
+                );
+            }
+        }
+
+        let _ = writeln!(out, "# TYPE auth_server_client_token_duration_seconds histogram");
+        let _ = writeln!(out, "# UNIT auth_server_client_token_duration_seconds seconds");
+        let _ = writeln!(out, "# HELP auth_server_client_token_duration_seconds OAuth token endpoint latency by client.");
+        for (client, series) in &tokens {
+            let client = escape_label(client);
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(series.latency.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "auth_server_client_token_duration_seconds_bucket{{client_id=\"{}\",le=\"{:?}\"}} {}",
+                    client, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "auth_server_client_token_duration_seconds_bucket{{client_id=\"{}\",le=\"+Inf\"}} {}",
+                client, series.latency.count
+            );
+            let _ = writeln!(
+                out,
+                "auth_server_client_token_duration_seconds_sum{{client_id=\"{}\"}} {}",
+                client, series.latency.sum
+            );
+            let _ = writeln!(
+                out,
+                "auth_server_client_token_duration_seconds_count{{client_id=\"{}\"}} {}",
+                client, series.latency.count
+            );
+        }
+
+        write_outcome_family(
+            &mut out,
+            "auth_server_app_webhook_deliveries",
+            "Webhook delivery attempts by app and result.",
+            "app_id",
+            &top_tenants(&self.webhook_deliveries, top_n),
+        );
+
+        out.push_str("# EOF\n");
+        out
+    }
+}
+
+static METRICS: Mutex<Option<MetricsRegistry>> = Mutex::new(None);
+
+fn with_metrics<R>(f: impl FnOnce(&mut MetricsRegistry) -> R) -> R {
+    let mut metrics = METRICS.lock().unwrap_or_else(|e| e.into_inner());
+    f(metrics.get_or_insert_with(MetricsRegistry::new))
+}
+
+/// Count a sign-in in this process's registry
+pub fn record_sign_in(app: Option<&str>, outcome: Outcome) {
+    with_metrics(|m| m.record_sign_in(app, outcome));
+}
+
+/// Count a token endpoint request in this process's registry
+pub fn record_token_request(client_id: Option<&str>, grant_type: &str, outcome: Outcome, elapsed: Duration) {
+    with_metrics(|m| m.record_token_request(client_id, grant_type, outcome, elapsed));
+}
+
+/// Count a webhook delivery attempt in this process's registry
+pub fn record_webhook_delivery(app_id: &str, delivered: bool) {
+    with_metrics(|m| m.record_webhook_delivery(app_id, delivered));
+}
+
+/// Render this process's registry
+pub fn render(top_n: usize) -> String {
+    with_metrics(|m| m.render(top_n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_series() {
+        let mut metrics = MetricsRegistry::new();
+        metrics.record_sign_in(Some("my-app"), Outcome::Success);
+        metrics.record_sign_in(Some("my-app"), Outcome::Success);
+        metrics.record_sign_in(Some("my-app"), Outcome::Failure);
+        metrics.record_sign_in(None, Outcome::MfaRequired);
+        metrics.record_token_request(Some("client-1"), "refresh_token", Outcome::Success, Duration::from_millis(20));
+        metrics.record_token_request(Some("client-1"), "refresh_token", Outcome::Error, Duration::from_secs(30));
+        metrics.record_webhook_delivery("app-1", false);
+
+        let out = metrics.render(10);
+
+        assert!(out.contains("auth_server_app_sign_ins_total{app=\"my-app\",result=\"success\"} 2\n"));
+        assert!(out.contains("auth_server_app_sign_ins_total{app=\"my-app\",result=\"failure\"} 1\n"));
+        assert!(out.contains("auth_server_app_sign_ins_total{app=\"none\",result=\"mfa_required\"} 1\n"));
+        assert!(out.contains(
+            "auth_server_client_token_requests_total{client_id=\"client-1\",grant_type=\"refresh_token\",result=\"error\"} 1\n"
+        ));
+        assert!(out.contains("auth_server_client_token_duration_seconds_bucket{client_id=\"client-1\",le=\"0.01\"} 0\n"));
+        assert!(out.contains("auth_server_client_token_duration_seconds_bucket{client_id=\"client-1\",le=\"0.025\"} 1\n"));
+        assert!(out.contains("auth_server_client_token_duration_seconds_bucket{client_id=\"client-1\",le=\"10.0\"} 1\n"));
+        assert!(out.contains("auth_server_client_token_duration_seconds_bucket{client_id=\"client-1\",le=\"+Inf\"} 2\n"));
+        assert!(out.contains("auth_server_client_token_duration_seconds_count{client_id=\"client-1\"} 2\n"));
+        assert!(out.contains("auth_server_app_webhook_deliveries_total{app_id=\"app-1\",result=\"failure\"} 1\n"));
+        assert!(out.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn test_top_n_sums_the_rest_under_other() {
+        let mut metrics = MetricsRegistry::new();
+        for (app, sign_ins) in [("busy", 5), ("medium", 3), ("quiet", 1), ("idle", 1)] {
+            for _ in 0..sign_ins {
+                metrics.record_sign_in(Some(app), Outcome::Success);
+            }
+        }
+
+        let out = metrics.render(2);
+
+        assert!(out.contains("{app=\"busy\",result=\"success\"} 5\n"));
+        assert!(out.contains("{app=\"medium\",result=\"success\"} 3\n"));
+        assert!(out.contains("{app=\"other\",result=\"success\"} 2\n"));
+        assert!(!out.contains("quiet"));
+        assert!(!out.contains("idle"));
+    }
+
+    #[test]
+    fn test_tracking_bound() {
+        let mut metrics = MetricsRegistry::new();
+        for i in 0..MAX_TRACKED_TENANTS {
+            metrics.record_webhook_delivery(&format!("app-{}", i), true);
+        }
+        metrics.record_webhook_delivery("app-new", true);
+        metrics.record_webhook_delivery("app-0", true);
+
+        assert!(!metrics.webhook_deliveries.contains_key("app-new"));
+        assert_eq!(metrics.webhook_deliveries[OTHER_TENANT].volume(), 1);
+        assert_eq!(metrics.webhook_deliveries["app-0"].volume(), 2);
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
@@ -1,6 +1,38 @@
+pub mod abuse_telemetry;
+pub mod access_window;
+pub mod acr;
+pub mod account_match;
 pub mod auth;
+pub mod chaos;
+pub mod claims_size;
+pub mod client_auth;
+pub mod client_fingerprint;
+pub mod cookie;
+pub mod cooling_off;
+pub mod device_code;
+pub mod dormancy;
 pub mod email;
+pub mod email_events;
+pub mod email_template;
+pub mod field_crypto;
+pub mod geoip;
 pub mod jwt;
+pub mod metrics;
+pub mod nats;
+pub mod outbound_url;
 pub mod password;
 pub mod pkce;
+pub mod posture;
+pub mod redirect_uri;
+pub mod registration_fields;
+pub mod request_id;
+pub mod request_object;
+pub mod route_table;
+pub mod scope_code;
 pub mod secret;
+pub mod signing_key;
+#[cfg(test)]
+mod sensitive_fields;
+pub mod token_binding;
+pub mod travel;
+pub mod userinfo_claims;
@@ -0,0 +1,253 @@
+//! Minimal NATS core client
+//!
+//! Covers what inbound provisioning needs: connect (with the user and
+//! password of the URL, if any), subscribe in a queue group, publish and
+//! receive messages. Core NATS delivers at most once, so whatever can't be
+//! stored is published to a dead-letter subject instead of being retried.
+//! TLS is not supported; the server must be reachable over a trusted network.
+
+use std::io;
+
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::TcpStream;
+
+/// Port used when the URL has none
+const DEFAULT_PORT: u16 = 4222;
+
+/// Largest message accepted from the server
+const MAX_PAYLOAD_BYTES: usize = 1024 * 1024;
+
+/// Message received on a subscription
+#[derive(Debug, Clone)]
+pub struct NatsMessage {
+    pub subject: String,
+    pub payload: Vec<u8>,
+}
+
+/// Connection to a NATS server
+pub struct NatsConnection {
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+}
+
+impl NatsConnection {
+    /// Connect to `url` (`nats://[user:password@]host[:port]`)
+    ///
+    /// Returns once the server has accepted the connection options.
+    pub async fn connect(url: &str, name: &str) -> io::Result<Self> {
+        let url = reqwest::Url::parse(url).map_err(|e| invalid(format!("invalid NATS URL: {}", e)))?;
+        if url.scheme() != "nats" {
+            return Err(invalid("NATS URL must use the nats:// scheme"));
+        }
+        let host = url.host_str().ok_or_else(|| invalid("NATS URL has no host"))?;
+        let stream = TcpStream::connect((host, url.port().unwrap_or(DEFAULT_PORT))).await?;
+        let (reader, writer) = stream.into_split();
+        let mut conn = Self { reader: BufReader::new(reader), writer };
+
+        let greeting = conn.read_line().await?;
+        let info: serde_json::Value = greeting
+            .strip_prefix("INFO ")
+            .and_then(|info| serde_json::from_str(info).ok())
+            .ok_or_else(|| invalid(format!("unexpected greeting: {}", greeting)))?;
+        if info["tls_required"].as_bool() == Some(true) {
+            return Err(invalid("NATS server requires TLS, which is not supported"));
+        }
+
+        let mut options = serde_json::json!({
+            "verbose": false,
+            "pedantic": false,
+            "lang": "rust",
+            "version": env!("CARGO_PKG_VERSION"),
+            "name": name,
+            "protocol": 1,
+        });
+        if !url.username().is_empty() {
+            options["user"] = decode(url.username()).into();
+            options["pass"] = decode(url.password().unwrap_or_default()).into();
+        }
+        conn.write(format!("CONNECT {}\r\n", options).as_bytes()).await?;
+        conn.flush().await?;
+
+        Ok(conn)
+    }
+
+    /// Subscribe to `subject`; in a queue group each message goes to one member
+    pub async fn subscribe(&mut self, subject: &str, queue_group: Option<&str>, sid: &str) -> io::Result<()> {
+        let line = match queue_group {
+            Some(group) => format!("SUB {} {} {}\r\n", subject, group, sid),
+            None => format!("SUB {} {}\r\n", subject, sid),
+        };
+        self.write(line.as_bytes()).await
+    }
+
+    /// Publish `payload` to `subject`
+    pub async fn publish(&mut self, subject: &str, payload: &[u8]) -> io::Result<()> {
+        let mut frame = format!("PUB {} {}\r\n", subject, payload.len()).into_bytes();
+        frame.extend_from_slice(payload);
+        frame.extend_from_slice(b"\r\n");
+        self.write(&frame).await
+    }
+
+    /// Wait until the server has processed everything sent so far
+    ///
+    /// Messages arriving meanwhile are dropped, so only call this before
+    /// subscribing or on a connection that only publishes.
+    pub async fn flush(&mut self) -> io::Result<()> {
+        self.write(b"PING\r\n").await?;
+        loop {
+            match self.read_line().await?.as_str() {
+                "PONG" => return Ok(()),
+                "PING" => self.write(b"PONG\r\n").await?,
+                line if line.starts_with("-ERR") => return Err(server_error(line)),
+                _ => {}
+            }
+        }
+    }
+
+    /// Wait for the next message on any subscription
+    ///
+    /// Answers the server's keep-alive pings meanwhile.
+    pub async fn next_message(&mut self) -> io::Result<NatsMessage> {
+        loop {
+            let line = self.read_line().await?;
+            match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["PING"] => self.write(b"PONG\r\n").await?,
+                // MSG <subject> <sid> [reply-to] <#bytes>
+                ["MSG", subject, _sid, .., size] => {
+                    let size: usize = size.parse().map_err(|_| invalid(format!("bad message header: {}", line)))?;
+                    if size > MAX_PAYLOAD_BYTES {
+                        return Err(invalid(format!("message of {} bytes is too large", size)));
+                    }
+                    let mut payload = vec![0; size + 2];
+                    self.reader.read_exact(&mut payload).await?;
+                    payload.truncate(size);
+                    return Ok(NatsMessage { subject: subject.to_string(), payload });
+                }
+                [err, ..] if err.starts_with("-ERR") => return Err(server_error(&line)),
+                _ => {}
+            }
+        }
+    }
+
+    async fn read_line(&mut self) -> io::Result<String> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line).await? == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "NATS connection closed"));
+        }
+        Ok(line.trim_end_matches(['\r', '\n']).to_string())
+    }
+
+    async fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.writer.write_all(bytes).await
+    }
+}
+
+/// Subject published to over a short-lived connection
+///
+/// For rare messages (dead letters) sent from outside a consumer.
+#[derive(Debug, Clone)]
+pub struct NatsTopic {
+    url: String,
+    subject: String,
+}
+
+impl NatsTopic {
+    pub fn new(url: String, subject: String) -> Self {
+        Self { url, subject }
+    }
+
+    /// Publish one message and wait until the server has it
+    pub async fn publish(&self, name: &str, payload: &[u8]) -> io::Result<()> {
+        let mut conn = NatsConnection::connect(&self.url, name).await?;
+        conn.publish(&self.subject, payload).await?;
+        conn.flush().await
+    }
+}
+
+fn decode(value: &str) -> String {
+    urlencoding::decode(value).map(|v| v.into_owned()).unwrap_or_else(|_| value.to_string())
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn server_error(line: &str) -> io::Error {
+    io::Error::other(format!("NATS server error: {}", line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// Listener of a fake server and a URL with credentials pointing at it
+    async fn fake_server() -> (String, TcpListener) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("nats://alice:s%40cret@{}", listener.local_addr().unwrap());
+        (url, listener)
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_receive_and_publish() {
+        let (url, listener) = fake_server().await;
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut reader = BufReader::new(reader);
+            writer.write_all(b"INFO {\"server_id\":\"test\"}\r\n").await.unwrap();
+
+            let mut line = String::new();
+            reader.read_line(&mut line).await.unwrap();
+            assert!(line.starts_with("CONNECT "));
+            let options: serde_json::Value = serde_json::from_str(&line[8..]).unwrap();
+            assert_eq!(options["user"], "alice");
+            assert_eq!(options["pass"], "s@cret");
+
+            line.clear();
+            reader.read_line(&mut line).await.unwrap();
+            assert_eq!(line, "PING\r\n");
+            writer.write_all(b"PONG\r\n").await.unwrap();
+
+            line.clear();
+            reader.read_line(&mut line).await.unwrap();
+            assert_eq!(line, "SUB provisioning.events workers 1\r\n");
+            writer
+                .write_all(b"PING\r\nMSG provisioning.events 1 5\r\nhello\r\n")
+                .await
+                .unwrap();
+
+            line.clear();
+            reader.read_line(&mut line).await.unwrap();
+            assert_eq!(line, "PONG\r\n");
+
+            let mut published = vec![0; "PUB provisioning.dlq 2\r\nno\r\n".len()];
+            reader.read_exact(&mut published).await.unwrap();
+            assert_eq!(published, b"PUB provisioning.dlq 2\r\nno\r\n");
+        });
+
+        let mut conn = NatsConnection::connect(&url, "test").await.unwrap();
+        conn.subscribe("provisioning.events", Some("workers"), "1").await.unwrap();
+
+        let message = conn.next_message().await.unwrap();
+        assert_eq!(message.subject, "provisioning.events");
+        assert_eq!(message.payload, b"hello");
+
+        conn.publish("provisioning.dlq", b"no").await.unwrap();
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_connect_refuses_tls_only_servers() {
+        let (url, listener) = fake_server().await;
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            stream.write_all(b"INFO {\"tls_required\":true}\r\n").await.unwrap();
+        });
+
+        assert!(NatsConnection::connect(&url, "test").await.is_err());
+        assert!(NatsConnection::connect("http://localhost:4222", "test").await.is_err());
+    }
+}
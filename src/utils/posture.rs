@@ -0,0 +1,187 @@
+//! Zero-trust posture attributes for OAuth access tokens
+//!
+//! OAuth clients can opt in (`posture_claims`) to receiving the posture of
+//! the sign-in in the `azp_ctx` claim of their access tokens: whether the
+//! browser was a device trusted after MFA, the MFA level (acr) of the
+//! sign-in and the network zone its IP belonged to. Resource servers can
+//! then apply zero-trust rules without calling back. The posture is taken
+//! when the user approves the authorization and kept across refreshes.
+//!
+//! Network zones are named CIDR ranges from `NETWORK_ZONES`; addresses
+//! outside every zone are `external`. Zone names are short and the claim is
+//! left out of a token if it ever grows past `MAX_POSTURE_BYTES`.
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::token_binding::ip_in_range;
+
+/// Longest network zone name
+pub const MAX_ZONE_NAME_LENGTH: usize = 32;
+
+/// Largest serialized `azp_ctx` claim put in a token
+pub const MAX_POSTURE_BYTES: usize = 256;
+
+/// Zone of addresses outside every configured zone
+pub const ZONE_EXTERNAL: &str = "external";
+
+/// Zone when the caller's address is not known
+pub const ZONE_UNKNOWN: &str = "unknown";
+
+/// Whether the signing-in browser was trusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceTrust {
+    /// A device trusted after completing MFA, not yet expired or revoked
+    Trusted,
+    /// Any other device
+    Unknown,
+}
+
+/// Posture of a sign-in, carried as `azp_ctx` in access tokens
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostureContext {
+    pub device_trust: DeviceTrust,
+    /// Authentication context class of the sign-in (`pwd`, `mfa` or `phr`)
+    pub mfa_level: String,
+    /// Network zone the user's address belonged to
+    pub network_zone: String,
+}
+
+impl PostureContext {
+    /// The context as a claim value, or None if it is too large for a token
+    pub fn claim(&self) -> Option<Self> {
+        let size = serde_json::to_vec(self).map(|json| json.len()).unwrap_or(usize::MAX);
+        if size > MAX_POSTURE_BYTES {
+            tracing::warn!(
+                "azp_ctx of {} bytes exceeds {} bytes; left out of the token",
+                size,
+                MAX_POSTURE_BYTES
+            );
+            return None;
+        }
+
+        Some(self.clone())
+    }
+}
+
+/// A named CIDR range
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NetworkZone {
+    pub name: String,
+    pub range: String,
+}
+
+/// Named network zones (`NETWORK_ZONES=office=10.0.0.0/8,vpn=100.64.0.0/10`)
+///
+/// The first zone containing an address wins, so list narrow ranges first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct NetworkZones {
+    zones: Vec<NetworkZone>,
+}
+
+impl NetworkZones {
+    /// Zone an address belongs to
+    pub fn zone_for(&self, ip: Option<&str>) -> &str {
+        let Some(ip) = ip else {
+            return ZONE_UNKNOWN;
+        };
+
+        self.zones
+            .iter()
+            .find(|zone| ip_in_range(ip, &zone.range))
+            .map(|zone| zone.name.as_str())
+            .unwrap_or(ZONE_EXTERNAL)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.zones.is_empty()
+    }
+}
+
+impl FromStr for NetworkZones {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut zones = Vec::new();
+        for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, range) = entry
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Invalid network zone '{}' (expected name=cidr)", entry))?;
+            let (name, range) = (name.trim(), range.trim());
+
+            let valid_name = !name.is_empty()
+                && name.len() <= MAX_ZONE_NAME_LENGTH
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+            if !valid_name || name == ZONE_EXTERNAL || name == ZONE_UNKNOWN {
+                return Err(anyhow::anyhow!(
+                    "Invalid network zone name '{}' (lowercase letters, digits, '_' or '-', at most {} characters, not external or unknown)",
+                    name,
+                    MAX_ZONE_NAME_LENGTH
+                ));
+            }
+
+            // A range that contains its own network address parses as CIDR
+            let network = range.split('/').next().unwrap_or_default();
+            if !ip_in_range(network, range) {
+                return Err(anyhow::anyhow!("Invalid CIDR '{}' for network zone '{}'", range, name));
+            }
+
+            zones.push(NetworkZone {
+                name: name.to_string(),
+                range: range.to_string(),
+            });
+        }
+
+        Ok(Self { zones })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zone_for_picks_the_first_matching_zone() {
+        let zones: NetworkZones = "lab=10.1.0.0/16, office=10.0.0.0/8,vpn=2001:db8::/32".parse().unwrap();
+
+        assert_eq!(zones.zone_for(Some("10.1.2.3")), "lab");
+        assert_eq!(zones.zone_for(Some("10.9.2.3")), "office");
+        assert_eq!(zones.zone_for(Some("2001:db8::1")), "vpn");
+        assert_eq!(zones.zone_for(Some("203.0.113.9")), ZONE_EXTERNAL);
+        assert_eq!(zones.zone_for(None), ZONE_UNKNOWN);
+    }
+
+    #[test]
+    fn test_network_zones_reject_invalid_entries() {
+        assert!("".parse::<NetworkZones>().unwrap().is_empty());
+        assert!("office".parse::<NetworkZones>().is_err());
+        assert!("Office=10.0.0.0/8".parse::<NetworkZones>().is_err());
+        assert!("external=10.0.0.0/8".parse::<NetworkZones>().is_err());
+        assert!(format!("{}=10.0.0.0/8", "z".repeat(MAX_ZONE_NAME_LENGTH + 1))
+            .parse::<NetworkZones>()
+            .is_err());
+        assert!("office=10.0.0.0".parse::<NetworkZones>().is_err());
+        assert!("office=10.0.0.0/33".parse::<NetworkZones>().is_err());
+    }
+
+    #[test]
+    fn test_claim_stays_within_the_size_limit() {
+        let context = PostureContext {
+            device_trust: DeviceTrust::Trusted,
+            mfa_level: "mfa".to_string(),
+            network_zone: "z".repeat(MAX_ZONE_NAME_LENGTH),
+        };
+        assert_eq!(context.claim(), Some(context.clone()));
+
+        let oversized = PostureContext {
+            network_zone: "z".repeat(MAX_POSTURE_BYTES),
+            ..context
+        };
+        assert_eq!(oversized.claim(), None);
+    }
+}
@@ -0,0 +1,284 @@
+//! Extra fields collected at registration
+//!
+//! Deployments describe the fields in `REGISTRATION_FIELDS` as a JSON array:
+//!
+//! ```json
+//! [
+//!   {"name": "company", "type": "string", "required": true, "max_length": 100},
+//!   {"name": "country", "type": "enum", "options": ["VN", "US", "JP"]},
+//!   {"name": "referral_code", "type": "string", "pattern": "^[A-Z0-9]{6}$"},
+//!   {"name": "seats", "type": "integer", "min": 1, "max": 500},
+//!   {"name": "newsletter", "type": "boolean"}
+//! ]
+//! ```
+//!
+//! `POST /auth/register` accepts the values in its `fields` object; they are
+//! validated against the schema and stored in the user's metadata.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Most fields a schema may define
+pub const MAX_REGISTRATION_FIELDS: usize = 20;
+
+/// Length limit applied to string fields without a `max_length`
+const DEFAULT_MAX_LENGTH: usize = 255;
+
+/// Names that belong to the account itself and cannot be redefined
+const RESERVED_NAMES: [&str; 6] = ["email", "password", "name", "phone", "id", "fields"];
+
+/// Value type of a registration field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegistrationFieldType {
+    String,
+    Integer,
+    Boolean,
+    /// A string out of `options`
+    Enum,
+}
+
+/// One field of the registration schema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistrationField {
+    /// Key in the `fields` object and in the user's metadata (lowercase snake_case)
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: RegistrationFieldType,
+    /// Human-readable label for sign-up forms
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    /// Longest accepted string (string fields)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    /// Regular expression the whole string must match (string fields)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Inclusive bounds (integer fields)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<i64>,
+    /// Accepted values (enum fields)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+}
+
+/// The configured registration fields (empty = registration takes no extra fields)
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(transparent)]
+pub struct RegistrationSchema {
+    fields: Vec<RegistrationField>,
+}
+
+impl RegistrationSchema {
+    /// Parse and check a schema given as a JSON array
+    pub fn parse(json: &str) -> Result<Self, String> {
+        if json.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let fields: Vec<RegistrationField> =
+            serde_json::from_str(json).map_err(|e| format!("invalid JSON: {}", e))?;
+        if fields.len() > MAX_REGISTRATION_FIELDS {
+            return Err(format!("at most {} fields are allowed", MAX_REGISTRATION_FIELDS));
+        }
+
+        for (i, field) in fields.iter().enumerate() {
+            let valid_name = !field.name.is_empty()
+                && field.name.len() <= 64
+                && field.name.starts_with(|c: char| c.is_ascii_lowercase())
+                && field.name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !valid_name {
+                return Err(format!("field name '{}' must be lowercase snake_case", field.name));
+            }
+            if RESERVED_NAMES.contains(&field.name.as_str()) {
+                return Err(format!("field name '{}' is reserved", field.name));
+            }
+            if fields[..i].iter().any(|f| f.name == field.name) {
+                return Err(format!("field '{}' is defined twice", field.name));
+            }
+            if let Some(pattern) = &field.pattern {
+                Regex::new(pattern).map_err(|e| format!("field '{}' has an invalid pattern: {}", field.name, e))?;
+            }
+            if field.field_type == RegistrationFieldType::Enum && field.options.is_empty() {
+                return Err(format!("enum field '{}' needs options", field.name));
+            }
+            if let (Some(min), Some(max)) = (field.min, field.max) {
+                if min > max {
+                    return Err(format!("field '{}' has min greater than max", field.name));
+                }
+            }
+        }
+
+        Ok(Self { fields })
+    }
+
+    /// Defined fields, in configuration order
+    pub fn fields(&self) -> &[RegistrationField] {
+        &self.fields
+    }
+
+    /// Whether `name` is a defined field
+    pub fn contains(&self, name: &str) -> bool {
+        self.fields.iter().any(|f| f.name == name)
+    }
+
+    /// Validate submitted values, returning the metadata to store
+    ///
+    /// Unknown fields and values of the wrong type are rejected; strings are
+    /// trimmed and empty optional strings dropped. Returns `None` when there
+    /// is nothing to store.
+    pub fn validate(&self, values: &Map<String, Value>) -> Result<Option<Value>, String> {
+        if let Some(unknown) = values.keys().find(|k| !self.contains(k)) {
+            return Err(format!("Unknown registration field '{}'", unknown));
+        }
+
+        let mut metadata = Map::new();
+        for field in &self.fields {
+            let value = match values.get(&field.name) {
+                None | Some(Value::Null) => None,
+                Some(Value::String(s)) if s.trim().is_empty() => None,
+                Some(value) => Some(field.check(value)?),
+            };
+            match value {
+                Some(value) => {
+                    metadata.insert(field.name.clone(), value);
+                }
+                None if field.required => {
+                    return Err(format!("Registration field '{}' is required", field.name));
+                }
+                None => {}
+            }
+        }
+
+        Ok((!metadata.is_empty()).then_some(Value::Object(metadata)))
+    }
+}
+
+impl RegistrationField {
+    /// Check one submitted value, returning it normalized
+    fn check(&self, value: &Value) -> Result<Value, String> {
+        let invalid = |reason: &str| format!("Registration field '{}' {}", self.name, reason);
+        match self.field_type {
+            RegistrationFieldType::String => {
+                let s = value.as_str().ok_or_else(|| invalid("must be a string"))?.trim();
+                if s.chars().count() > self.max_length.unwrap_or(DEFAULT_MAX_LENGTH) {
+                    return Err(invalid("is too long"));
+                }
+                if let Some(pattern) = &self.pattern {
+                    // The schema was checked at startup, so the pattern compiles
+                    let matches = Regex::new(&format!("^(?:{})$", pattern))
+                        .map(|re| re.is_match(s))
+                        .unwrap_or(false);
+                    if !matches {
+                        return Err(invalid("has an invalid format"));
+                    }
+                }
+                Ok(Value::String(s.to_string()))
+            }
+            RegistrationFieldType::Integer => {
+                let n = value.as_i64().ok_or_else(|| invalid("must be an integer"))?;
+                if self.min.is_some_and(|min| n < min) || self.max.is_some_and(|max| n > max) {
+                    return Err(invalid("is out of range"));
+                }
+                Ok(Value::from(n))
+            }
+            RegistrationFieldType::Boolean => {
+                value.as_bool().map(Value::Bool).ok_or_else(|| invalid("must be true or false"))
+            }
+            RegistrationFieldType::Enum => {
+                let s = value.as_str().ok_or_else(|| invalid("must be a string"))?.trim();
+                if !self.options.iter().any(|o| o == s) {
+                    return Err(invalid(&format!("must be one of {}", self.options.join(", "))));
+                }
+                Ok(Value::String(s.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> RegistrationSchema {
+        RegistrationSchema::parse(
+            r#"[
+                {"name": "company", "type": "string", "required": true, "max_length": 10},
+                {"name": "country", "type": "enum", "options": ["VN", "US"]},
+                {"name": "referral_code", "type": "string", "pattern": "[A-Z0-9]{6}"},
+                {"name": "seats", "type": "integer", "min": 1, "max": 50},
+                {"name": "newsletter", "type": "boolean"}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    fn values(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_schemas() {
+        assert!(RegistrationSchema::parse("").unwrap().fields().is_empty());
+        assert!(RegistrationSchema::parse("{").is_err());
+        assert!(RegistrationSchema::parse(r#"[{"name": "Company", "type": "string"}]"#).is_err());
+        assert!(RegistrationSchema::parse(r#"[{"name": "email", "type": "string"}]"#).is_err());
+        assert!(RegistrationSchema::parse(r#"[{"name": "plan", "type": "enum"}]"#).is_err());
+        assert!(RegistrationSchema::parse(r#"[{"name": "code", "type": "string", "pattern": "("}]"#).is_err());
+        assert!(RegistrationSchema::parse(r#"[{"name": "seats", "type": "integer", "min": 5, "max": 1}]"#).is_err());
+        assert!(RegistrationSchema::parse(
+            r#"[{"name": "a", "type": "string"}, {"name": "a", "type": "boolean"}]"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_accepts_and_normalizes() {
+        let metadata = schema()
+            .validate(&values(json!({
+                "company": "  Acme ",
+                "country": "VN",
+                "referral_code": "AB12CD",
+                "seats": 5,
+                "newsletter": true,
+            })))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            metadata,
+            json!({"company": "Acme", "country": "VN", "referral_code": "AB12CD", "seats": 5, "newsletter": true})
+        );
+
+        // Empty optional values are not stored
+        let metadata = schema()
+            .validate(&values(json!({"company": "Acme", "country": "", "seats": null})))
+            .unwrap()
+            .unwrap();
+        assert_eq!(metadata, json!({"company": "Acme"}));
+
+        // No schema, no values: nothing to store
+        assert_eq!(RegistrationSchema::default().validate(&Map::new()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_values() {
+        let schema = schema();
+        let check = |v: Value| schema.validate(&values(v));
+
+        assert!(check(json!({})).unwrap_err().contains("required"));
+        assert!(check(json!({"company": "Acme", "plan": "pro"})).unwrap_err().contains("Unknown"));
+        assert!(check(json!({"company": "Acme Corporation"})).is_err());
+        assert!(check(json!({"company": 7})).is_err());
+        assert!(check(json!({"company": "Acme", "country": "FR"})).is_err());
+        assert!(check(json!({"company": "Acme", "referral_code": "ab12cd"})).is_err());
+        assert!(check(json!({"company": "Acme", "referral_code": "AB12CD99"})).is_err());
+        assert!(check(json!({"company": "Acme", "seats": 0})).is_err());
+        assert!(check(json!({"company": "Acme", "seats": 2.5})).is_err());
+        assert!(check(json!({"company": "Acme", "newsletter": "yes"})).is_err());
+    }
+}
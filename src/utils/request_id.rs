@@ -0,0 +1,93 @@
+//! Request correlation ids
+//!
+//! Each HTTP request gets an id, taken from a well-formed `X-Request-Id`
+//! header or generated. It is available to everything the request runs
+//! through `current_request_id`, recorded on audit rows and webhook events,
+//! sent to webhook receivers as `X-Correlation-Id`, and attached to log lines
+//! through the request's tracing span.
+
+use std::future::Future;
+
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Header a caller may set, echoed back on every response
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Header webhook deliveries carry the originating request id in
+pub const CORRELATION_ID_HEADER: &str = "X-Correlation-Id";
+
+/// Longest caller-supplied id accepted
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// The caller's id when it is safe to store and log, otherwise a new one
+pub fn request_id_or_new(supplied: Option<&str>) -> String {
+    supplied
+        .map(str::trim)
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= MAX_REQUEST_ID_LEN
+                && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+/// Id of the request the current task is serving, if any
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Run `future` with `request_id` as the current request id
+pub async fn with_request_id<F: Future>(request_id: String, future: F) -> F::Output {
+    REQUEST_ID.scope(request_id, future).await
+}
+
+/// Spawn a background task that keeps the current request id and log span
+///
+/// Use instead of `tokio::spawn` for work started on behalf of a request
+/// (webhooks, emails, audit writes) so it stays correlated with it.
+pub fn spawn_in_request<F>(future: F) -> tokio::task::JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let future = future.instrument(tracing::Span::current());
+    match current_request_id() {
+        Some(request_id) => tokio::spawn(REQUEST_ID.scope(request_id, future)),
+        None => tokio::spawn(future),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_id_or_new() {
+        assert_eq!(request_id_or_new(Some("abc-123")), "abc-123");
+        assert_eq!(request_id_or_new(Some(" trace:01.a_b ")), "trace:01.a_b");
+
+        for rejected in [None, Some(""), Some("has space"), Some("line\nbreak"), Some(&"a".repeat(129))] {
+            let generated = request_id_or_new(rejected);
+            assert!(Uuid::parse_str(&generated).is_ok(), "{:?} should be replaced", rejected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn_in_request_keeps_id() {
+        assert_eq!(current_request_id(), None);
+
+        let spawned = with_request_id("req-1".into(), async {
+            spawn_in_request(async { current_request_id() }).await.unwrap()
+        })
+        .await;
+
+        assert_eq!(spawned.as_deref(), Some("req-1"));
+        assert_eq!(spawn_in_request(async { current_request_id() }).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,284 @@
+//! Request objects (JAR, RFC 9101)
+//!
+//! A client may send its authorization request parameters as a signed JWT in
+//! the `request` parameter. The JWT is verified against the public JWK Set the
+//! client registered, must be issued by the client (`iss` = client_id) to this
+//! server (`aud` = issuer), and must carry an expiry.
+//!
+//! Parameters in the request object take precedence over the query
+//! (OpenID Connect Core Section 6.3.3). In strict mode only the request
+//! object's parameters are used, as RFC 9101 Section 6.3 requires.
+
+use jsonwebtoken::jwk::{AlgorithmParameters, JwkSet};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde_json::{Map, Value};
+
+/// Algorithms a request object may be signed with (asymmetric only)
+pub const REQUEST_OBJECT_SIGNING_ALGS: &[Algorithm] = &[
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+/// Maximum number of keys in a client's JWK Set
+pub const MAX_CLIENT_JWKS_KEYS: usize = 10;
+
+/// JWT claims of a request object that are not request parameters
+const REGISTERED_CLAIMS: &[&str] = &["iss", "aud", "exp", "iat", "nbf", "jti"];
+
+/// JWK members that only private or symmetric keys have
+const PRIVATE_KEY_MEMBERS: &[&str] = &["d", "p", "q", "dp", "dq", "qi", "oth", "k"];
+
+/// Parse and check a JWK Set a client registers
+///
+/// Only public asymmetric keys are accepted; when the set holds more than one
+/// key every key needs a distinct `kid` so request objects can name theirs.
+pub fn validate_client_jwks(jwks: &Value) -> Result<JwkSet, String> {
+    let raw_keys = jwks
+        .get("keys")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "jwks must be a JWK Set with a keys array".to_string())?;
+    if raw_keys.is_empty() {
+        return Err("jwks must contain at least one key".to_string());
+    }
+    if raw_keys.len() > MAX_CLIENT_JWKS_KEYS {
+        return Err(format!("jwks may contain at most {} keys", MAX_CLIENT_JWKS_KEYS));
+    }
+    let has_private_member = raw_keys
+        .iter()
+        .any(|key| PRIVATE_KEY_MEMBERS.iter().any(|member| key.get(member).is_some()));
+    if has_private_member {
+        return Err("jwks must contain public keys only".to_string());
+    }
+
+    let set: JwkSet = serde_json::from_value(jwks.clone())
+        .map_err(|e| format!("jwks is not a valid JWK Set: {}", e))?;
+
+    for jwk in &set.keys {
+        if matches!(jwk.algorithm, AlgorithmParameters::OctetKey(_)) {
+            return Err("symmetric (oct) keys are not accepted".to_string());
+        }
+        DecodingKey::from_jwk(jwk).map_err(|e| format!("unusable key: {}", e))?;
+    }
+
+    if set.keys.len() > 1 {
+        let mut kids = Vec::with_capacity(set.keys.len());
+        for jwk in &set.keys {
+            let kid = jwk
+                .common
+                .key_id
+                .as_deref()
+                .ok_or_else(|| "every key needs a kid when jwks has more than one key".to_string())?;
+            if kids.contains(&kid) {
+                return Err(format!("duplicate kid: {}", kid));
+            }
+            kids.push(kid);
+        }
+    }
+
+    Ok(set)
+}
+
+/// Verify a request object and return its claims
+///
+/// The claims must not nest another `request` or `request_uri`, and a
+/// `client_id` claim must name the same client as the `iss`.
+pub fn verify_request_object(
+    token: &str,
+    jwks: &JwkSet,
+    client_id: &str,
+    audience: &str,
+) -> Result<Map<String, Value>, String> {
+    let header = decode_header(token).map_err(|_| "request is not a signed JWT".to_string())?;
+    if !REQUEST_OBJECT_SIGNING_ALGS.contains(&header.alg) {
+        return Err(format!("signing algorithm {:?} is not accepted", header.alg));
+    }
+
+    let jwk = match header.kid.as_deref() {
+        Some(kid) => jwks.find(kid).ok_or_else(|| format!("unknown kid: {}", kid))?,
+        None if jwks.keys.len() == 1 => &jwks.keys[0],
+        None => return Err("kid is required when the client has more than one key".to_string()),
+    };
+    let key = DecodingKey::from_jwk(jwk).map_err(|e| format!("unusable key: {}", e))?;
+
+    let mut validation = Validation::new(header.alg);
+    validation.set_audience(&[audience]);
+    validation.set_issuer(&[client_id]);
+    validation.set_required_spec_claims(&["exp", "aud", "iss"]);
+
+    let claims = decode::<Map<String, Value>>(token, &key, &validation)
+        .map_err(|e| format!("request object rejected: {}", e))?
+        .claims;
+
+    if claims.contains_key("request") || claims.contains_key("request_uri") {
+        return Err("request object must not contain request or request_uri".to_string());
+    }
+    if claims.get("client_id").is_some_and(|id| id.as_str() != Some(client_id)) {
+        return Err("client_id in the request object does not match".to_string());
+    }
+
+    Ok(claims)
+}
+
+/// Combine query parameters with the verified claims of a request object
+///
+/// `client_id` is always taken from the query, which
+/// [`verify_request_object`] has checked against the request object.
+pub fn merge_request_parameters(
+    query: &Map<String, Value>,
+    claims: Map<String, Value>,
+    strict: bool,
+) -> Map<String, Value> {
+    let mut merged = if strict { Map::new() } else { query.clone() };
+    if let Some(client_id) = query.get("client_id") {
+        merged.insert("client_id".to_string(), client_id.clone());
+    }
+    for (name, value) in claims {
+        if !REGISTERED_CLAIMS.contains(&name.as_str()) {
+            merged.insert(name, value);
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::jwt::JwtManager;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    const CLIENT_ID: &str = "client-123";
+    const AUDIENCE: &str = "https://auth.example.com";
+
+    fn client_keys() -> (EncodingKey, Value, String) {
+        let private_key = std::fs::read_to_string("keys/private.pem").unwrap();
+        let public_key = std::fs::read_to_string("keys/public.pem").unwrap();
+        let manager = JwtManager::new(&private_key, &public_key, 900, 604800).unwrap();
+        let jwks = serde_json::to_value(manager.jwks()).unwrap();
+        let kid = jwks["keys"][0]["kid"].as_str().unwrap().to_string();
+        (EncodingKey::from_rsa_pem(private_key.as_bytes()).unwrap(), jwks, kid)
+    }
+
+    fn sign(key: &EncodingKey, kid: &str, claims: Value) -> String {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(kid.to_string());
+        encode(&header, &claims, key).unwrap()
+    }
+
+    fn claims(extra: Value) -> Value {
+        let mut claims = serde_json::json!({
+            "iss": CLIENT_ID,
+            "aud": AUDIENCE,
+            "exp": chrono::Utc::now().timestamp() + 300,
+            "response_type": "code",
+            "state": "from-request-object",
+        });
+        claims.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        claims
+    }
+
+    #[test]
+    fn test_verifies_request_object_signed_with_client_key() {
+        let (key, jwks, kid) = client_keys();
+        let set = validate_client_jwks(&jwks).unwrap();
+
+        let token = sign(&key, &kid, claims(serde_json::json!({})));
+        let verified = verify_request_object(&token, &set, CLIENT_ID, AUDIENCE).unwrap();
+        assert_eq!(verified["state"], "from-request-object");
+    }
+
+    #[test]
+    fn test_rejects_wrong_issuer_audience_and_expired() {
+        let (key, jwks, kid) = client_keys();
+        let set = validate_client_jwks(&jwks).unwrap();
+
+        let wrong_iss = sign(&key, &kid, claims(serde_json::json!({ "iss": "other" })));
+        assert!(verify_request_object(&wrong_iss, &set, CLIENT_ID, AUDIENCE).is_err());
+
+        let wrong_aud = sign(&key, &kid, claims(serde_json::json!({ "aud": "https://evil.example" })));
+        assert!(verify_request_object(&wrong_aud, &set, CLIENT_ID, AUDIENCE).is_err());
+
+        let expired = sign(&key, &kid, claims(serde_json::json!({ "exp": 1_000_000 })));
+        assert!(verify_request_object(&expired, &set, CLIENT_ID, AUDIENCE).is_err());
+    }
+
+    #[test]
+    fn test_rejects_nested_request_and_client_id_mismatch() {
+        let (key, jwks, kid) = client_keys();
+        let set = validate_client_jwks(&jwks).unwrap();
+
+        let nested = sign(&key, &kid, claims(serde_json::json!({ "request_uri": "https://x" })));
+        assert!(verify_request_object(&nested, &set, CLIENT_ID, AUDIENCE).is_err());
+
+        let mismatch = sign(&key, &kid, claims(serde_json::json!({ "client_id": "other" })));
+        assert!(verify_request_object(&mismatch, &set, CLIENT_ID, AUDIENCE).is_err());
+    }
+
+    #[test]
+    fn test_rejects_symmetric_signature() {
+        let (_, jwks, kid) = client_keys();
+        let set = validate_client_jwks(&jwks).unwrap();
+
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some(kid);
+        let token = encode(&header, &claims(serde_json::json!({})), &EncodingKey::from_secret(b"secret"))
+            .unwrap();
+        assert!(verify_request_object(&token, &set, CLIENT_ID, AUDIENCE).is_err());
+    }
+
+    #[test]
+    fn test_request_object_parameters_take_precedence() {
+        let query = serde_json::json!({
+            "client_id": CLIENT_ID,
+            "state": "from-query",
+            "nonce": "query-only",
+        });
+        let object = claims(serde_json::json!({ "client_id": CLIENT_ID }));
+        let object = object.as_object().unwrap().clone();
+
+        let merged = merge_request_parameters(query.as_object().unwrap(), object.clone(), false);
+        assert_eq!(merged["state"], "from-request-object");
+        assert_eq!(merged["nonce"], "query-only");
+        assert_eq!(merged["response_type"], "code");
+        assert!(!merged.contains_key("iss") && !merged.contains_key("exp"));
+
+        let strict = merge_request_parameters(query.as_object().unwrap(), object, true);
+        assert_eq!(strict["client_id"], CLIENT_ID);
+        assert_eq!(strict["state"], "from-request-object");
+        assert!(!strict.contains_key("nonce"));
+    }
+
+    #[test]
+    fn test_client_jwks_must_be_public_keys() {
+        let (_, jwks, _) = client_keys();
+
+        let mut private = jwks.clone();
+        private["keys"][0]["d"] = Value::String("secret".to_string());
+        assert!(validate_client_jwks(&private).is_err());
+
+        let oct = serde_json::json!({ "keys": [{ "kty": "oct", "k": "c2VjcmV0" }] });
+        assert!(validate_client_jwks(&oct).is_err());
+
+        assert!(validate_client_jwks(&serde_json::json!({ "keys": [] })).is_err());
+    }
+
+    #[test]
+    fn test_client_jwks_with_several_keys_need_distinct_kids() {
+        let (_, jwks, _) = client_keys();
+        let key = jwks["keys"][0].clone();
+
+        let duplicate = serde_json::json!({ "keys": [key.clone(), key.clone()] });
+        assert!(validate_client_jwks(&duplicate).is_err());
+
+        let mut renamed = key.clone();
+        renamed["kid"] = Value::String("second".to_string());
+        let distinct = serde_json::json!({ "keys": [key, renamed] });
+        assert!(validate_client_jwks(&distinct).is_ok());
+    }
+}
@@ -0,0 +1,320 @@
+//! Static table of the mounted routes, for operator introspection
+//!
+//! axum cannot enumerate a built `Router`, so this mirrors `create_router`
+//! in `main.rs`. The tests below parse `main.rs` and fail when a route is
+//! added or removed there without updating this table.
+
+use serde::Serialize;
+
+/// How a route is authenticated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteAuth {
+    /// No authentication
+    Public,
+    /// User access token (`jwt_auth_middleware`)
+    UserToken,
+    /// User access token; handler requires a system admin
+    SystemAdmin,
+    /// App token from `/apps/auth` or a scoped `X-API-Key` (`app_auth_middleware`)
+    AppToken,
+    /// `X-API-Key` header (`api_key_auth_middleware`)
+    ApiKey,
+    /// OAuth access token (`oauth_auth_middleware`)
+    OAuthToken,
+}
+
+/// A mounted route
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RouteInfo {
+    pub method: &'static str,
+    pub path: &'static str,
+    pub auth: RouteAuth,
+}
+
+const fn route(method: &'static str, path: &'static str, auth: RouteAuth) -> RouteInfo {
+    RouteInfo { method, path, auth }
+}
+
+/// Layers applied to every route, outermost first
+pub const GLOBAL_LAYERS: &[&str] = &["cors", "timeout (30s)", "trace", "abuse telemetry", "csrf"];
+
+/// Every route mounted by `create_router`
+pub const ROUTES: &[RouteInfo] = &[
+    route("GET", "/health", RouteAuth::Public),
+    route("GET", "/ready", RouteAuth::Public),
+    route("POST", "/auth/register", RouteAuth::Public),
+    route("GET", "/auth/register/fields", RouteAuth::Public),
+    route("POST", "/auth/login", RouteAuth::Public),
+    route("POST", "/auth/refresh", RouteAuth::Public),
+    route("GET", "/auth/csrf", RouteAuth::Public),
+    route("POST", "/auth/forgot-password", RouteAuth::Public),
+    route("POST", "/auth/reset-password", RouteAuth::Public),
+    route("POST", "/auth/verify-email", RouteAuth::Public),
+    route("POST", "/auth/resend-verification", RouteAuth::Public),
+    route("POST", "/auth/mfa/verify", RouteAuth::Public),
+    route("POST", "/auth/mfa/push", RouteAuth::Public),
+    route("POST", "/auth/mfa/push/respond", RouteAuth::Public),
+    route("POST", "/auth/mfa/sms/send", RouteAuth::Public),
+    route("POST", "/auth/mfa/email/send", RouteAuth::Public),
+    route("POST", "/auth/mfa/email/verify", RouteAuth::Public),
+    route("POST", "/auth/email-login/send", RouteAuth::Public),
+    route("POST", "/auth/email-login/verify", RouteAuth::Public),
+    route("POST", "/auth/magic-link", RouteAuth::Public),
+    route("GET", "/auth/magic-link/verify", RouteAuth::Public),
+    route("POST", "/auth/webauthn/authenticate/start", RouteAuth::Public),
+    route("POST", "/auth/webauthn/authenticate/finish", RouteAuth::Public),
+    route("POST", "/auth/qr/start", RouteAuth::Public),
+    route("POST", "/auth/qr/token", RouteAuth::Public),
+    route("POST", "/auth/sso/session", RouteAuth::Public),
+    route("POST", "/auth/sso/continue", RouteAuth::Public),
+    route("POST", "/auth/logout", RouteAuth::UserToken),
+    route("GET", "/auth/sessions", RouteAuth::UserToken),
+    route("DELETE", "/auth/sessions", RouteAuth::UserToken),
+    route("POST", "/auth/sessions/revoke", RouteAuth::UserToken),
+    route("POST", "/auth/devices", RouteAuth::UserToken),
+    route("GET", "/auth/devices", RouteAuth::UserToken),
+    route("DELETE", "/auth/devices/:device_id", RouteAuth::UserToken),
+    route("GET", "/auth/trusted-devices", RouteAuth::UserToken),
+    route("DELETE", "/auth/trusted-devices", RouteAuth::UserToken),
+    route("DELETE", "/auth/trusted-devices/:device_id", RouteAuth::UserToken),
+    route("POST", "/auth/mfa/totp/setup", RouteAuth::UserToken),
+    route("POST", "/auth/mfa/totp/verify", RouteAuth::UserToken),
+    route("POST", "/auth/mfa/sms/setup", RouteAuth::UserToken),
+    route("POST", "/auth/mfa/sms/verify", RouteAuth::UserToken),
+    route("POST", "/auth/mfa/email/setup", RouteAuth::UserToken),
+    route("GET", "/auth/mfa/methods", RouteAuth::UserToken),
+    route("DELETE", "/auth/mfa", RouteAuth::UserToken),
+    route("POST", "/auth/mfa/backup-codes/regenerate", RouteAuth::UserToken),
+    route("GET", "/auth/audit-logs", RouteAuth::UserToken),
+    route("POST", "/auth/qr/approve", RouteAuth::UserToken),
+    route("GET", "/auth/claims", RouteAuth::UserToken),
+    route("POST", "/auth/webauthn/register/start", RouteAuth::UserToken),
+    route("POST", "/auth/webauthn/register/finish", RouteAuth::UserToken),
+    route("GET", "/auth/webauthn/credentials", RouteAuth::UserToken),
+    route("PUT", "/auth/webauthn/credentials/:credential_id", RouteAuth::UserToken),
+    route("DELETE", "/auth/webauthn/credentials/:credential_id", RouteAuth::UserToken),
+    route("GET", "/users/me", RouteAuth::UserToken),
+    route("PUT", "/users/me", RouteAuth::UserToken),
+    route("POST", "/users/me/change-password", RouteAuth::UserToken),
+    route("GET", "/apps", RouteAuth::UserToken),
+    route("POST", "/apps", RouteAuth::UserToken),
+    route("GET", "/apps/:app_id", RouteAuth::UserToken),
+    route("POST", "/apps/:app_id/roles", RouteAuth::UserToken),
+    route("POST", "/apps/:app_id/permissions", RouteAuth::UserToken),
+    route("POST", "/apps/:app_id/roles/:role_id/permissions", RouteAuth::UserToken),
+    route("GET", "/apps/:app_id/roles/:role_id/permissions", RouteAuth::UserToken),
+    route("DELETE", "/apps/:app_id/roles/:role_id/permissions/:permission_id", RouteAuth::UserToken),
+    route("POST", "/apps/:app_id/users/:user_id/roles", RouteAuth::UserToken),
+    route("GET", "/apps/:app_id/users/:user_id/roles", RouteAuth::UserToken),
+    route("GET", "/apps/:app_id/users/:user_id/roles/history", RouteAuth::UserToken),
+    route("DELETE", "/apps/:app_id/users/:user_id/roles/:role_id", RouteAuth::UserToken),
+    route("POST", "/apps/:app_id/elevation-requests", RouteAuth::UserToken),
+    route("GET", "/apps/:app_id/elevation-requests", RouteAuth::UserToken),
+    route("POST", "/apps/:app_id/elevation-requests/:request_id/approve", RouteAuth::UserToken),
+    route("POST", "/apps/:app_id/elevation-requests/:request_id/deny", RouteAuth::UserToken),
+    route("POST", "/apps/:id/secret/regenerate", RouteAuth::UserToken),
+    route("PUT", "/apps/:app_id/session-policy", RouteAuth::UserToken),
+    route("PUT", "/apps/:app_id/token-binding", RouteAuth::UserToken),
+    route("PUT", "/apps/:app_id/deletion-policy", RouteAuth::UserToken),
+    route("PUT", "/apps/:app_id/required-acr", RouteAuth::UserToken),
+    route("PUT", "/apps/:app_id/sso", RouteAuth::UserToken),
+    route("PUT", "/apps/:app_id/digest", RouteAuth::UserToken),
+    route("PUT", "/apps/:app_id/email-login", RouteAuth::UserToken),
+    route("POST", "/apps/:app_id/register", RouteAuth::UserToken),
+    route("POST", "/apps/:app_id/users/:user_id/ban", RouteAuth::UserToken),
+    route("POST", "/apps/:app_id/users/:user_id/unban", RouteAuth::UserToken),
+    route("DELETE", "/apps/:app_id/users/:user_id", RouteAuth::UserToken),
+    route("GET", "/apps/:app_id/users", RouteAuth::UserToken),
+    route("POST", "/apps/:app_id/webhooks", RouteAuth::UserToken),
+    route("GET", "/apps/:app_id/webhooks", RouteAuth::UserToken),
+    route("GET", "/apps/:app_id/webhooks/:webhook_id", RouteAuth::UserToken),
+    route("PUT", "/apps/:app_id/webhooks/:webhook_id", RouteAuth::UserToken),
+    route("DELETE", "/apps/:app_id/webhooks/:webhook_id", RouteAuth::UserToken),
+    route("POST", "/apps/:app_id/webhooks/:webhook_id/replay", RouteAuth::UserToken),
+    route("POST", "/apps/:app_id/api-keys", RouteAuth::UserToken),
+    route("GET", "/apps/:app_id/api-keys", RouteAuth::UserToken),
+    route("GET", "/apps/:app_id/api-keys/:key_id", RouteAuth::UserToken),
+    route("PUT", "/apps/:app_id/api-keys/:key_id", RouteAuth::UserToken),
+    route("DELETE", "/apps/:app_id/api-keys/:key_id", RouteAuth::UserToken),
+    route("POST", "/apps/:app_id/api-keys/:key_id/revoke", RouteAuth::UserToken),
+    route("POST", "/apps/:app_id/ip-rules", RouteAuth::UserToken),
+    route("GET", "/apps/:app_id/ip-rules", RouteAuth::UserToken),
+    route("POST", "/apps/auth", RouteAuth::Public),
+    route("GET", "/apps/:code/auth-methods", RouteAuth::Public),
+    route("POST", "/webhooks/email/:provider", RouteAuth::Public),
+    route("GET", "/metrics", RouteAuth::Public),
+    route("POST", "/app-api/apps/:id/roles", RouteAuth::AppToken),
+    route("GET", "/app-api/apps/:id/roles", RouteAuth::AppToken),
+    route("POST", "/app-api/apps/:id/permissions", RouteAuth::AppToken),
+    route("GET", "/app-api/apps/:id/permissions", RouteAuth::AppToken),
+    route("POST", "/app-api/apps/:id/roles/:role_id/permissions", RouteAuth::AppToken),
+    route("PUT", "/app-api/apps/:id/rbac", RouteAuth::AppToken),
+    route("GET", "/admin/users", RouteAuth::SystemAdmin),
+    route("GET", "/admin/users/search", RouteAuth::SystemAdmin),
+    route("GET", "/admin/users/export", RouteAuth::SystemAdmin),
+    route("POST", "/admin/users/import", RouteAuth::SystemAdmin),
+    route("POST", "/admin/users/bulk-assign-role", RouteAuth::SystemAdmin),
+    route("GET", "/admin/users/duplicates", RouteAuth::SystemAdmin),
+    route("GET", "/admin/users/dormant", RouteAuth::SystemAdmin),
+    route("GET", "/admin/users/:user_id", RouteAuth::SystemAdmin),
+    route("PUT", "/admin/users/:user_id", RouteAuth::SystemAdmin),
+    route("DELETE", "/admin/users/:user_id", RouteAuth::SystemAdmin),
+    route("POST", "/admin/users/:user_id/deactivate", RouteAuth::SystemAdmin),
+    route("POST", "/admin/users/:user_id/activate", RouteAuth::SystemAdmin),
+    route("POST", "/admin/users/:user_id/unlock", RouteAuth::SystemAdmin),
+    route("PUT", "/admin/users/:user_id/legal-hold", RouteAuth::SystemAdmin),
+    route("GET", "/admin/users/:user_id/roles", RouteAuth::SystemAdmin),
+    route("GET", "/admin/users/:user_id/privacy-ledger", RouteAuth::SystemAdmin),
+    route("GET", "/admin/apps", RouteAuth::SystemAdmin),
+    route("GET", "/admin/apps/:app_id", RouteAuth::SystemAdmin),
+    route("PUT", "/admin/apps/:app_id", RouteAuth::SystemAdmin),
+    route("DELETE", "/admin/apps/:app_id", RouteAuth::SystemAdmin),
+    route("GET", "/admin/audit-logs", RouteAuth::SystemAdmin),
+    route("POST", "/admin/ip-rules", RouteAuth::SystemAdmin),
+    route("GET", "/admin/ip-rules", RouteAuth::SystemAdmin),
+    route("GET", "/admin/ip-rules/check", RouteAuth::SystemAdmin),
+    route("GET", "/admin/ip-rules/suggestions", RouteAuth::SystemAdmin),
+    route("POST", "/admin/ip-rules/suggestions/:suggestion_id/approve", RouteAuth::SystemAdmin),
+    route("POST", "/admin/ip-rules/suggestions/:suggestion_id/reject", RouteAuth::SystemAdmin),
+    route("DELETE", "/admin/ip-rules/:rule_id", RouteAuth::SystemAdmin),
+    route("GET", "/admin/scopes", RouteAuth::SystemAdmin),
+    route("POST", "/admin/scopes", RouteAuth::SystemAdmin),
+    route("GET", "/admin/scopes/pending", RouteAuth::SystemAdmin),
+    route("GET", "/admin/scopes/:scope_id", RouteAuth::SystemAdmin),
+    route("PUT", "/admin/scopes/:scope_id", RouteAuth::SystemAdmin),
+    route("DELETE", "/admin/scopes/:scope_id", RouteAuth::SystemAdmin),
+    route("POST", "/admin/scopes/:scope_id/activate", RouteAuth::SystemAdmin),
+    route("POST", "/admin/scopes/:scope_id/deactivate", RouteAuth::SystemAdmin),
+    route("POST", "/admin/scopes/:scope_id/approve", RouteAuth::SystemAdmin),
+    route("POST", "/admin/scopes/:scope_id/reject", RouteAuth::SystemAdmin),
+    route("PUT", "/admin/oauth-clients/:client_id/skip-consent", RouteAuth::SystemAdmin),
+    route("PUT", "/admin/oauth-clients/:client_id/secret-policy", RouteAuth::SystemAdmin),
+    route("GET", "/admin/oauth/stats", RouteAuth::SystemAdmin),
+    route("GET", "/admin/redirect-uri-blocklist", RouteAuth::SystemAdmin),
+    route("POST", "/admin/redirect-uri-blocklist", RouteAuth::SystemAdmin),
+    route("DELETE", "/admin/redirect-uri-blocklist/:id", RouteAuth::SystemAdmin),
+    route("GET", "/admin/debug/config", RouteAuth::SystemAdmin),
+    route("GET", "/admin/debug/routes", RouteAuth::SystemAdmin),
+    route("GET", "/admin/debug/workers", RouteAuth::SystemAdmin),
+    route("POST", "/admin/access-simulator", RouteAuth::SystemAdmin),
+    route("GET", "/admin/instance", RouteAuth::SystemAdmin),
+    route("GET", "/admin/chaos", RouteAuth::SystemAdmin),
+    route("PUT", "/admin/chaos/:fault", RouteAuth::SystemAdmin),
+    route("DELETE", "/admin/chaos/:fault", RouteAuth::SystemAdmin),
+    route("GET", "/admin/encryption/status", RouteAuth::SystemAdmin),
+    route("POST", "/admin/encryption/rotate", RouteAuth::SystemAdmin),
+    route("GET", "/admin/signing-keys", RouteAuth::SystemAdmin),
+    route("POST", "/admin/signing-keys", RouteAuth::SystemAdmin),
+    route("GET", "/admin/signing-keys/:kid", RouteAuth::SystemAdmin),
+    route("POST", "/admin/signing-keys/:kid/stage", RouteAuth::SystemAdmin),
+    route("POST", "/admin/signing-keys/:kid/promote", RouteAuth::SystemAdmin),
+    route("POST", "/admin/keys/rotate", RouteAuth::SystemAdmin),
+    route("GET", "/admin/tokens/:jti/lineage", RouteAuth::SystemAdmin),
+    route("POST", "/admin/notifications/broadcast", RouteAuth::SystemAdmin),
+    route("GET", "/admin/notifications/broadcasts", RouteAuth::SystemAdmin),
+    route("GET", "/admin/notifications/broadcasts/:broadcast_id", RouteAuth::SystemAdmin),
+    route("POST", "/admin/notifications/broadcasts/:broadcast_id/cancel", RouteAuth::SystemAdmin),
+    route("GET", "/admin/notifications/suppressions", RouteAuth::SystemAdmin),
+    route("PUT", "/admin/notifications/suppressions/:user_id", RouteAuth::SystemAdmin),
+    route("DELETE", "/admin/notifications/suppressions/:user_id", RouteAuth::SystemAdmin),
+    route("GET", "/admin/notifications/bounces", RouteAuth::SystemAdmin),
+    route("DELETE", "/admin/notifications/bounces/:email", RouteAuth::SystemAdmin),
+    route("GET", "/admin/approvals", RouteAuth::SystemAdmin),
+    route("GET", "/admin/approvals/:approval_id", RouteAuth::SystemAdmin),
+    route("POST", "/admin/approvals/:approval_id/approve", RouteAuth::SystemAdmin),
+    route("POST", "/admin/approvals/:approval_id/reject", RouteAuth::SystemAdmin),
+    route("GET", "/api/v1/users", RouteAuth::ApiKey),
+    route("GET", "/api/v1/users/:user_id", RouteAuth::ApiKey),
+    route("POST", "/api/v1/users/:user_id/ban", RouteAuth::ApiKey),
+    route("POST", "/api/v1/users/:user_id/unban", RouteAuth::ApiKey),
+    route("GET", "/api/v1/roles", RouteAuth::ApiKey),
+    route("GET", "/api/v1/users/:user_id/roles", RouteAuth::ApiKey),
+    route("POST", "/api/v1/users/:user_id/roles", RouteAuth::ApiKey),
+    route("DELETE", "/api/v1/users/:user_id/roles/:role_id", RouteAuth::ApiKey),
+    route("POST", "/api/v1/provisioning/events", RouteAuth::ApiKey),
+    route("GET", "/api/v1/provisioning/events", RouteAuth::ApiKey),
+    route("POST", "/api/v1/provisioning/events/:event_id/retry", RouteAuth::ApiKey),
+    route("GET", "/oauth/authorize", RouteAuth::Public),
+    route("POST", "/oauth/authorize/callback", RouteAuth::Public),
+    route("POST", "/oauth/token", RouteAuth::Public),
+    route("POST", "/oauth/revoke", RouteAuth::Public),
+    route("POST", "/oauth/introspect", RouteAuth::Public),
+    route("POST", "/oauth/par", RouteAuth::Public),
+    route("POST", "/oauth/device_authorization", RouteAuth::Public),
+    route("GET", "/oauth/device", RouteAuth::Public),
+    route("GET", "/oauth/logout", RouteAuth::Public),
+    route("GET", "/oauth/register/:client_id", RouteAuth::Public),
+    route("PUT", "/oauth/register/:client_id", RouteAuth::Public),
+    route("DELETE", "/oauth/register/:client_id", RouteAuth::Public),
+    route("GET", "/oauth/scopes", RouteAuth::Public),
+    route("POST", "/oauth/clients", RouteAuth::UserToken),
+    route("GET", "/oauth/clients", RouteAuth::UserToken),
+    route("PUT", "/oauth/clients/:id", RouteAuth::UserToken),
+    route("DELETE", "/oauth/clients/:id", RouteAuth::UserToken),
+    route("POST", "/oauth/clients/:id/secret", RouteAuth::UserToken),
+    route("POST", "/oauth/clients/:id/secret/rotate", RouteAuth::UserToken),
+    route("GET", "/oauth/clients/:id/secret/rotations", RouteAuth::UserToken),
+    route("GET", "/oauth/clients/:id/jwks", RouteAuth::UserToken),
+    route("PUT", "/oauth/clients/:id/jwks", RouteAuth::UserToken),
+    route("DELETE", "/oauth/clients/:id/jwks", RouteAuth::UserToken),
+    route("POST", "/oauth/clients/:id/scopes", RouteAuth::UserToken),
+    route("GET", "/oauth/clients/:id/scopes", RouteAuth::UserToken),
+    route("PUT", "/oauth/clients/:id/scopes/:scope_id", RouteAuth::UserToken),
+    route("DELETE", "/oauth/clients/:id/scopes/:scope_id", RouteAuth::UserToken),
+    route("POST", "/oauth/device/verify", RouteAuth::UserToken),
+    route("GET", "/oauth/userinfo", RouteAuth::OAuthToken),
+    route("GET", "/.well-known/openid-configuration", RouteAuth::Public),
+    route("GET", "/.well-known/jwks.json", RouteAuth::Public),
+    route("GET", "/.well-known/auth-server", RouteAuth::Public),
+    route("GET", "/account/connected-apps", RouteAuth::UserToken),
+    route("DELETE", "/account/connected-apps/:client_id", RouteAuth::UserToken),
+    route("POST", "/account/revoke-all", RouteAuth::UserToken),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    /// `(method, path)` pairs registered with `.route(...)` in `main.rs`
+    fn registered_routes() -> Vec<(String, String)> {
+        let source = include_str!("../main.rs");
+        let route_re = Regex::new(r#"\.route\("([^"]+)",\s*(.+)\)"#).unwrap();
+        let method_re = Regex::new(r"\b(get|post|put|delete)\(").unwrap();
+
+        route_re
+            .captures_iter(source)
+            .flat_map(|caps| {
+                let path = caps[1].to_string();
+                method_re
+                    .captures_iter(&caps[2])
+                    .map(|m| (m[1].to_uppercase(), path.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_route_table_matches_router() {
+        let registered = registered_routes();
+        assert_eq!(registered.len(), ROUTES.len(), "route table is out of sync with create_router");
+
+        for (method, path) in &registered {
+            assert!(
+                ROUTES.iter().any(|r| r.method == method && r.path.ends_with(path.as_str())),
+                "{} {} is missing from the route table",
+                method,
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_admin_routes_require_system_admin() {
+        assert!(ROUTES
+            .iter()
+            .filter(|r| r.path.starts_with("/admin/"))
+            .all(|r| r.auth == RouteAuth::SystemAdmin));
+    }
+}
@@ -0,0 +1,75 @@
+//! Validation of namespaced custom OAuth scope codes
+//!
+//! Clients define their own scopes as `namespace:name` (e.g.
+//! `myapp:orders.read`). The namespace identifies the owning client, so it
+//! must not shadow the standard OpenID Connect scopes.
+
+/// Namespaces that can never be claimed by a client
+const RESERVED_NAMESPACES: &[&str] = &["openid", "profile", "email", "phone", "address", "offline_access", "admin", "oauth"];
+
+/// Maximum length of a scope code (matches the `oauth_scopes.code` column)
+const MAX_SCOPE_CODE_LEN: usize = 100;
+
+/// Validate a custom scope code and return its namespace
+pub fn validate_custom_scope_code(code: &str) -> Result<&str, String> {
+    if code.len() > MAX_SCOPE_CODE_LEN {
+        return Err(format!("Scope code must be at most {} characters", MAX_SCOPE_CODE_LEN));
+    }
+
+    let (namespace, name) = code
+        .split_once(':')
+        .ok_or_else(|| "Custom scopes must be namespaced as 'namespace:name'".to_string())?;
+
+    let namespace_valid = (2..=32).contains(&namespace.len())
+        && namespace.starts_with(|c: char| c.is_ascii_lowercase())
+        && namespace.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !namespace_valid {
+        return Err(
+            "Scope namespace must be 2-32 lowercase letters, digits, '-' or '_' and start with a letter"
+                .to_string(),
+        );
+    }
+
+    if RESERVED_NAMESPACES.contains(&namespace) {
+        return Err(format!("Scope namespace '{}' is reserved", namespace));
+    }
+
+    let name_valid = !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '-' | '_'))
+        && !name.starts_with('.')
+        && !name.ends_with('.');
+    if !name_valid {
+        return Err("Scope name must be lowercase letters, digits, '.', '-' or '_'".to_string());
+    }
+
+    Ok(namespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_custom_scope_codes() {
+        assert_eq!(validate_custom_scope_code("myapp:orders.read"), Ok("myapp"));
+        assert_eq!(validate_custom_scope_code("my-app_2:orders"), Ok("my-app_2"));
+    }
+
+    #[test]
+    fn test_rejects_unnamespaced_or_malformed_codes() {
+        assert!(validate_custom_scope_code("orders.read").is_err());
+        assert!(validate_custom_scope_code("MyApp:orders").is_err());
+        assert!(validate_custom_scope_code("1app:orders").is_err());
+        assert!(validate_custom_scope_code("a:orders").is_err());
+        assert!(validate_custom_scope_code("myapp:").is_err());
+        assert!(validate_custom_scope_code("myapp:orders:read").is_err());
+        assert!(validate_custom_scope_code("myapp:.orders").is_err());
+        assert!(validate_custom_scope_code(&format!("myapp:{}", "a".repeat(100))).is_err());
+    }
+
+    #[test]
+    fn test_rejects_reserved_namespaces() {
+        assert!(validate_custom_scope_code("openid:orders").is_err());
+        assert!(validate_custom_scope_code("admin:users").is_err());
+    }
+}
@@ -172,7 +172,7 @@ pub fn verify_oauth_token(token: &str, hash: &str) -> bool {
 }
 
 /// Constant-time string comparison to prevent timing attacks
-fn constant_time_compare(a: &str, b: &str) -> bool {
+pub fn constant_time_compare(a: &str, b: &str) -> bool {
     if a.len() != b.len() {
         return false;
     }
@@ -184,6 +184,55 @@ fn constant_time_compare(a: &str, b: &str) -> bool {
     result == 0
 }
 
+// ============================================================================
+// OAuth State Parameter Checks
+// ============================================================================
+
+/// Minimum length of a client-supplied `state` before it is flagged as weak
+pub const MIN_STATE_LENGTH: usize = 16;
+
+/// Minimum estimated entropy (in bits) of a client-supplied `state`
+pub const MIN_STATE_ENTROPY_BITS: f64 = 48.0;
+
+/// Estimate the entropy of a value in bits from its character distribution
+///
+/// This is the Shannon entropy per character multiplied by the length, so it
+/// underestimates truly random values but flags repetitive or constant ones.
+pub fn estimate_entropy_bits(value: &str) -> f64 {
+    let len = value.chars().count();
+    if len == 0 {
+        return 0.0;
+    }
+
+    let mut counts = std::collections::HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_insert(0usize) += 1;
+    }
+
+    let per_char: f64 = counts
+        .values()
+        .map(|&n| {
+            let p = n as f64 / len as f64;
+            -p * p.log2()
+        })
+        .sum();
+
+    per_char * len as f64
+}
+
+/// Check a client-supplied OAuth `state` value for weak randomness
+///
+/// # Returns
+/// The reason the value looks weak, or `None` if it appears random enough
+pub fn weak_state_reason(state: Option<&str>) -> Option<&'static str> {
+    match state {
+        None | Some("") => Some("missing"),
+        Some(s) if s.chars().count() < MIN_STATE_LENGTH => Some("too_short"),
+        Some(s) if estimate_entropy_bits(s) < MIN_STATE_ENTROPY_BITS => Some("low_entropy"),
+        Some(_) => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -404,4 +453,20 @@ mod tests {
         assert!(!constant_time_compare("hello", "hello!"));
         assert!(!constant_time_compare("hello", "hell"));
     }
+
+    #[test]
+    fn test_weak_state_reason() {
+        assert_eq!(weak_state_reason(None), Some("missing"));
+        assert_eq!(weak_state_reason(Some("")), Some("missing"));
+        assert_eq!(weak_state_reason(Some("12345")), Some("too_short"));
+        assert_eq!(weak_state_reason(Some("abcabcabcabcabcabcabc")), Some("low_entropy"));
+        assert_eq!(weak_state_reason(Some(&generate_oauth_token())), None);
+    }
+
+    #[test]
+    fn test_estimate_entropy_bits() {
+        assert_eq!(estimate_entropy_bits(""), 0.0);
+        assert_eq!(estimate_entropy_bits("aaaaaaaa"), 0.0);
+        assert!((estimate_entropy_bits("abcd") - 8.0).abs() < 1e-9);
+    }
 }
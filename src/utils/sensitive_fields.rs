@@ -0,0 +1,341 @@
+//! Sensitive model fields that must never appear in an API response
+//!
+//! Models holding password hashes, token/secret hashes or stored secrets
+//! mark those fields `#[serde(skip_serializing)]`, so a model handed to
+//! `Json` (directly, via a DTO conversion or through an error path) can't
+//! expose them. The tests below serialize every such model with sentinel
+//! values and fail if anything slips through; add new models here.
+//!
+//! Test-only: the guarantee comes from the serde attributes, not a runtime filter.
+
+/// Field names that are never allowed in a serialized response
+pub const SENSITIVE_FIELDS: &[&str] = &["password_hash", "secret_encrypted"];
+
+/// Whether a serialized field name denotes a sensitive value
+///
+/// Besides [`SENSITIVE_FIELDS`], every `*_hash` field is sensitive.
+pub fn is_sensitive_field(name: &str) -> bool {
+    SENSITIVE_FIELDS.contains(&name) || name.ends_with("_hash")
+}
+
+/// JSON paths of sensitive fields anywhere in a serialized value
+pub fn find_sensitive_fields(value: &serde_json::Value) -> Vec<String> {
+    fn walk(value: &serde_json::Value, path: &str, found: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, child) in map {
+                    let child_path = format!("{}.{}", path, key);
+                    if is_sensitive_field(key) {
+                        found.push(child_path.clone());
+                    }
+                    walk(child, &child_path, found);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for (i, child) in items.iter().enumerate() {
+                    walk(child, &format!("{}[{}]", path, i), found);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut found = Vec::new();
+    walk(value, "$", &mut found);
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde::Serialize;
+    use uuid::Uuid;
+
+    use crate::error::{AppError, AuthError, ErrorResponse};
+    use crate::models::*;
+
+    /// Value planted in every sensitive field
+    const SENTINEL: &str = "SENSITIVE-SENTINEL";
+
+    fn assert_clean<T: Serialize>(name: &str, value: &T) {
+        let json = serde_json::to_value(value).unwrap();
+        assert_eq!(find_sensitive_fields(&json), Vec::<String>::new(), "{} leaks a sensitive field", name);
+        assert!(!json.to_string().contains(SENTINEL), "{} leaks a sensitive value: {}", name, json);
+    }
+
+    #[test]
+    fn test_find_sensitive_fields() {
+        let value = serde_json::json!({
+            "user": { "email": "a@example.com", "password_hash": "x" },
+            "keys": [{ "key_hash": "y", "key_prefix": "ak_" }],
+            "secret": "shown once",
+        });
+
+        assert_eq!(find_sensitive_fields(&value), vec!["$.keys[0].key_hash", "$.user.password_hash"]);
+        assert!(is_sensitive_field("secret_encrypted"));
+        assert!(!is_sensitive_field("client_secret"));
+    }
+
+    #[test]
+    fn test_user_models_never_serialize_secrets() {
+        let now = Utc::now();
+
+        assert_clean("User", &User {
+            id: Uuid::new_v4(),
+            email: "a@example.com".into(),
+            password_hash: SENTINEL.into(),
+            name: None,
+            avatar_url: None,
+            phone: None,
+            locale: None,
+            metadata: None,
+            is_active: true,
+            email_verified: true,
+            is_system_admin: false,
+            mfa_enabled: true,
+            legal_hold: false,
+            legal_hold_reason: None,
+            reverify_required: false,
+            created_at: now,
+            updated_at: None,
+        });
+
+        assert_clean("UserSession", &UserSession {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            device_id: None,
+            app_id: None,
+            refresh_token_hash: SENTINEL.into(),
+            device_name: None,
+            device_type: None,
+            ip_address: None,
+            user_agent: None,
+            last_active_at: now,
+            expires_at: now,
+            is_revoked: false,
+            revoked_at: None,
+            created_at: now,
+        });
+
+        assert_clean("RevokedToken", &RevokedToken {
+            id: Uuid::new_v4(),
+            token_hash: SENTINEL.into(),
+            token_type: "access".into(),
+            user_id: None,
+            expires_at: now,
+            revoked_at: now,
+            reason: None,
+        });
+
+        assert_clean("UserDevice", &UserDevice {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            platform: "ios".into(),
+            device_name: None,
+            push_token: Some(SENTINEL.into()),
+            signing_secret: Some(SENTINEL.into()),
+            is_active: true,
+            last_seen_at: now,
+            revoked_at: None,
+            created_at: now,
+        });
+    }
+
+    #[test]
+    fn test_mfa_models_never_serialize_secrets() {
+        let now = Utc::now();
+
+        assert_clean("UserMfaMethod", &UserMfaMethod {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            method_type: "totp".into(),
+            secret_encrypted: Some(SENTINEL.into()),
+            phone_number: None,
+            email: None,
+            is_primary: true,
+            is_verified: true,
+            last_used_at: None,
+            created_at: now,
+        });
+
+        assert_clean("UserMfaBackupCode", &UserMfaBackupCode {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            code_hash: SENTINEL.into(),
+            is_used: false,
+            used_at: None,
+            created_at: now,
+        });
+
+        assert_clean("PushMfaChallenge", &PushMfaChallenge {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            mfa_token_hash: SENTINEL.into(),
+            status: PushChallengeStatus::Pending,
+            device_id: None,
+            ip_address: None,
+            user_agent: None,
+            responded_at: None,
+            expires_at: now,
+            created_at: now,
+        });
+
+        assert_clean("QrLoginChannel", &QrLoginChannel {
+            id: Uuid::new_v4(),
+            poll_secret_hash: SENTINEL.into(),
+            status: QrLoginStatus::Pending,
+            user_id: None,
+            app_id: None,
+            ip_address: None,
+            user_agent: None,
+            approved_at: None,
+            expires_at: now,
+            created_at: now,
+        });
+    }
+
+    #[test]
+    fn test_app_and_oauth_models_never_serialize_secrets() {
+        let now = Utc::now();
+
+        assert_clean("App", &App {
+            id: Uuid::new_v4(),
+            code: "app".into(),
+            name: "App".into(),
+            owner_id: None,
+            secret_hash: Some(SENTINEL.into()),
+            session_idle_timeout_secs: None,
+            session_absolute_lifetime_secs: None,
+            token_binding: "none".into(),
+            token_binding_ip_prefix: None,
+            deletion_policy: "delete".into(),
+            required_acr: None,
+            sso_enabled: true,
+            digest_enabled: false,
+            email_login_enabled: false,
+        });
+
+        assert_clean("ApiKey", &ApiKey {
+            id: Uuid::new_v4(),
+            app_id: Uuid::new_v4(),
+            name: "ci".into(),
+            key_hash: SENTINEL.into(),
+            key_prefix: "ak_".into(),
+            scopes: sqlx::types::Json(vec!["read:users".into()]),
+            expires_at: None,
+            access_window: None,
+            last_used_at: None,
+            is_active: true,
+            created_at: now,
+        });
+
+        assert_clean("Webhook", &Webhook {
+            id: Uuid::new_v4(),
+            app_id: Uuid::new_v4(),
+            url: "https://example.com/hook".into(),
+            secret: SENTINEL.into(),
+            events: sqlx::types::Json(vec!["user.login".into()]),
+            is_active: true,
+            consecutive_failures: 0,
+            circuit_open_until: None,
+            payload_version: 2,
+            created_at: now,
+            updated_at: now,
+        });
+
+        assert_clean("OAuthClient", &OAuthClient {
+            id: Uuid::new_v4(),
+            client_id: "client".into(),
+            client_secret_hash: SENTINEL.into(),
+            name: "Client".into(),
+            owner_id: None,
+            redirect_uris: vec![],
+            is_internal: false,
+            client_type: "web".into(),
+            is_active: true,
+            session_idle_timeout_secs: None,
+            session_absolute_lifetime_secs: None,
+            access_token_ttl_secs: None,
+            refresh_token_cookie: false,
+            skip_consent: false,
+            always_prompt_consent: false,
+            required_acr: None,
+            userinfo_signed_response_alg: None,
+            posture_claims: false,
+            secret_created_at: now,
+            secret_max_age_days: None,
+            post_logout_redirect_uris: vec![],
+            backchannel_logout_uri: None,
+            request_uris: vec![],
+            allowed_scopes: None,
+            allowed_grant_types: None,
+            created_at: now,
+        });
+
+        assert_clean("OAuthToken", &OAuthToken {
+            id: Uuid::new_v4(),
+            user_id: None,
+            client_id: Uuid::new_v4(),
+            access_token_hash: SENTINEL.into(),
+            refresh_token_hash: Some(SENTINEL.into()),
+            scopes: vec![],
+            expires_at: now,
+            revoked: false,
+            session_started_at: now,
+            authorization_code_id: None,
+            posture: None,
+            created_at: now,
+        });
+
+        assert_clean("AuthorizationCode", &AuthorizationCode {
+            id: Uuid::new_v4(),
+            code_hash: SENTINEL.into(),
+            client_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            redirect_uri: "https://example.com/cb".into(),
+            scopes: vec![],
+            code_challenge: "challenge".into(),
+            code_challenge_method: "S256".into(),
+            nonce: None,
+            expires_at: now,
+            used: false,
+            session_binding_hash: Some(SENTINEL.into()),
+            browser_binding_hash: Some(SENTINEL.into()),
+            auth_time: Some(now),
+            acr: None,
+            amr: None,
+            posture: None,
+            created_at: now,
+        });
+
+        assert_clean("DeviceCode", &DeviceCode {
+            id: Uuid::new_v4(),
+            device_code_hash: SENTINEL.into(),
+            client_id: Uuid::new_v4(),
+            scopes: vec![],
+            status: DeviceCodeStatus::Pending,
+            user_id: None,
+            auth_time: None,
+            interval_secs: 5,
+            last_polled_at: None,
+            expires_at: now,
+            created_at: now,
+        });
+    }
+
+    #[test]
+    fn test_error_responses_never_echo_internals() {
+        let internal = AppError::InternalError(anyhow::anyhow!("password_hash={}", SENTINEL));
+        let auth_internal = AuthError::InternalError(anyhow::anyhow!("token_hash={}", SENTINEL));
+
+        for message in [internal.to_string(), auth_internal.to_string()] {
+            assert_clean("ErrorResponse", &ErrorResponse {
+                error: "internal_error".into(),
+                message,
+                status_code: 500,
+                details: None,
+            });
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Token binding for app (machine-to-machine) tokens
+//!
+//! Apps can opt in to having their tokens bound to the caller that obtained
+//! them at `/apps/auth`: either to the caller's IP range or to the SHA-256
+//! thumbprint of its client certificate. TLS is terminated by the reverse
+//! proxy, which must forward the verified certificate thumbprint in
+//! `X-Client-Cert-SHA256` and strip that header from client requests.
+//! Without trusted proxies (`TRUSTED_PROXY_HOPS=0`) the header is dropped,
+//! since only the client could have sent it.
+
+use std::net::IpAddr;
+
+use axum::http::{HeaderMap, HeaderValue};
+
+/// Header the TLS-terminating proxy uses to forward the client certificate thumbprint
+pub const CLIENT_CERT_THUMBPRINT_HEADER: &str = "x-client-cert-sha256";
+
+/// Prefix length IPv6 callers are bound to when an IP range is configured
+const IPV6_RANGE_PREFIX: u32 = 64;
+
+/// Header carrying the client IP derived by `client_ip_middleware`
+pub const CLIENT_IP_HEADER: &str = "x-real-ip";
+
+/// Header reverse proxies append the address of their peer to
+const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+
+/// The caller IP, as derived by `client_ip_middleware`
+///
+/// The middleware replaces whatever forwarding headers the request came
+/// with, so this is never a value the client chose.
+pub fn client_ip(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(CLIENT_IP_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Derive the caller IP from the peer address and the trusted proxies
+///
+/// Each of the `trusted_proxy_hops` proxies appends the address it received
+/// the request from to `X-Forwarded-For`, so the client is the entry that
+/// many hops from the right; anything further left was sent by the client.
+/// Without trusted proxies the headers are ignored and the peer is the
+/// client. A chain shorter than the configured hops falls back to the peer.
+pub fn resolve_client_ip(headers: &HeaderMap, peer: Option<IpAddr>, trusted_proxy_hops: usize) -> Option<IpAddr> {
+    if trusted_proxy_hops == 0 {
+        return peer;
+    }
+
+    let forwarded: Vec<&str> = headers
+        .get_all(FORWARDED_FOR_HEADER)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .collect();
+
+    forwarded
+        .len()
+        .checked_sub(trusted_proxy_hops)
+        .and_then(|index| forwarded[index].parse().ok())
+        .or(peer)
+}
+
+/// Replace the forwarding headers of a request with the derived caller IP
+pub fn set_client_ip(headers: &mut HeaderMap, ip: Option<IpAddr>) {
+    headers.remove(FORWARDED_FOR_HEADER);
+    headers.remove(CLIENT_IP_HEADER);
+    if let Some(value) = ip.and_then(|ip| HeaderValue::from_str(&ip.to_string()).ok()) {
+        headers.insert(CLIENT_IP_HEADER, value);
+    }
+}
+
+/// Drop a client certificate thumbprint no trusted proxy could have set
+///
+/// Without trusted proxies the request comes straight from the client, so
+/// the header is the client's own claim and must not bind anything.
+pub fn strip_client_cert(headers: &mut HeaderMap, trusted_proxy_hops: usize) {
+    if trusted_proxy_hops == 0 {
+        headers.remove(CLIENT_CERT_THUMBPRINT_HEADER);
+    }
+}
+
+/// Extract the client certificate thumbprint forwarded by the proxy
+pub fn client_cert_thumbprint(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(CLIENT_CERT_THUMBPRINT_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_ascii_lowercase().replace(':', ""))
+        .filter(|v| !v.is_empty())
+}
+
+/// Network (CIDR) a token issued to `ip` is bound to
+///
+/// Without a prefix the token is bound to the exact address. With an IPv4
+/// prefix, IPv4 callers are bound to that network and IPv6 callers to their /64.
+pub fn ip_range_for(ip: &str, ipv4_prefix: Option<u32>) -> Option<String> {
+    let addr: IpAddr = ip.parse().ok()?;
+
+    let prefix = match (addr, ipv4_prefix) {
+        (IpAddr::V4(_), Some(p)) if p <= 32 => p,
+        (IpAddr::V4(_), Some(_)) => return None,
+        (IpAddr::V4(_), None) => 32,
+        (IpAddr::V6(_), Some(_)) => IPV6_RANGE_PREFIX,
+        (IpAddr::V6(_), None) => 128,
+    };
+
+    let network = match addr {
+        IpAddr::V4(v4) => IpAddr::from((u32::from(v4) & v4_mask(prefix)).to_be_bytes()),
+        IpAddr::V6(v6) => IpAddr::from((u128::from(v6) & v6_mask(prefix)).to_be_bytes()),
+    };
+
+    Some(format!("{}/{}", network, prefix))
+}
+
+/// Check whether an IP belongs to a CIDR network
+pub fn ip_in_range(ip: &str, range: &str) -> bool {
+    let (network, prefix) = match range.split_once('/') {
+        Some((n, p)) => (n, p),
+        None => return false,
+    };
+
+    let (addr, network, prefix): (IpAddr, IpAddr, u32) =
+        match (ip.parse(), network.parse(), prefix.parse()) {
+            (Ok(a), Ok(n), Ok(p)) => (a, n, p),
+            _ => return false,
+        };
+
+    match (addr, network) {
+        (IpAddr::V4(a), IpAddr::V4(n)) if prefix <= 32 => {
+            u32::from(a) & v4_mask(prefix) == u32::from(n) & v4_mask(prefix)
+        }
+        (IpAddr::V6(a), IpAddr::V6(n)) if prefix <= 128 => {
+            u128::from(a) & v6_mask(prefix) == u128::from(n) & v6_mask(prefix)
+        }
+        _ => false,
+    }
+}
+
+fn v4_mask(prefix: u32) -> u32 {
+    u32::MAX.checked_shl(32 - prefix).unwrap_or(0)
+}
+
+fn v6_mask(prefix: u32) -> u128 {
+    u128::MAX.checked_shl(128 - prefix).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_range_for_ipv4() {
+        assert_eq!(ip_range_for("203.0.113.57", None), Some("203.0.113.57/32".to_string()));
+        assert_eq!(ip_range_for("203.0.113.57", Some(24)), Some("203.0.113.0/24".to_string()));
+        assert_eq!(ip_range_for("203.0.113.57", Some(0)), Some("0.0.0.0/0".to_string()));
+        assert_eq!(ip_range_for("203.0.113.57", Some(33)), None);
+        assert_eq!(ip_range_for("not-an-ip", None), None);
+    }
+
+    #[test]
+    fn test_ip_range_for_ipv6() {
+        assert_eq!(ip_range_for("2001:db8::1", None), Some("2001:db8::1/128".to_string()));
+        assert_eq!(ip_range_for("2001:db8::1", Some(24)), Some("2001:db8::/64".to_string()));
+    }
+
+    #[test]
+    fn test_ip_in_range() {
+        assert!(ip_in_range("203.0.113.9", "203.0.113.0/24"));
+        assert!(!ip_in_range("198.51.100.9", "203.0.113.0/24"));
+        assert!(ip_in_range("2001:db8::ffff", "2001:db8::/64"));
+        assert!(!ip_in_range("203.0.113.9", "2001:db8::/64"));
+        assert!(!ip_in_range("203.0.113.9", "garbage"));
+    }
+
+    fn forwarded(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(FORWARDED_FOR_HEADER, HeaderValue::from_static(value));
+        headers.insert(CLIENT_IP_HEADER, HeaderValue::from_static("203.0.113.1"));
+        headers
+    }
+
+    #[test]
+    fn test_forwarding_headers_are_ignored_without_trusted_proxies() {
+        let peer = "198.51.100.7".parse().ok();
+        assert_eq!(resolve_client_ip(&forwarded("203.0.113.9"), peer, 0), peer);
+    }
+
+    #[test]
+    fn test_client_is_the_rightmost_untrusted_hop() {
+        let peer = "10.0.0.2".parse().ok();
+        let headers = forwarded("203.0.113.66, 198.51.100.7, 10.0.0.1");
+
+        assert_eq!(resolve_client_ip(&headers, peer, 1), "10.0.0.1".parse().ok());
+        assert_eq!(resolve_client_ip(&headers, peer, 2), "198.51.100.7".parse().ok());
+        // Fewer entries than proxies: the chain is not what the config says
+        assert_eq!(resolve_client_ip(&headers, peer, 4), peer);
+    }
+
+    #[test]
+    fn test_set_client_ip_replaces_forwarding_headers() {
+        let mut headers = forwarded("203.0.113.9");
+        set_client_ip(&mut headers, "198.51.100.7".parse().ok());
+
+        assert!(headers.get(FORWARDED_FOR_HEADER).is_none());
+        assert_eq!(client_ip(&headers), Some("198.51.100.7".to_string()));
+
+        set_client_ip(&mut headers, None);
+        assert_eq!(client_ip(&headers), None);
+    }
+
+    #[test]
+    fn test_client_cert_thumbprint_is_normalized() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CLIENT_CERT_THUMBPRINT_HEADER, HeaderValue::from_static("AB:CD:EF"));
+
+        assert_eq!(client_cert_thumbprint(&headers), Some("abcdef".to_string()));
+        assert_eq!(client_cert_thumbprint(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_client_cert_of_direct_client_is_ignored() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CLIENT_CERT_THUMBPRINT_HEADER, HeaderValue::from_static("AB:CD:EF"));
+
+        strip_client_cert(&mut headers, 1);
+        assert_eq!(client_cert_thumbprint(&headers), Some("abcdef".to_string()));
+
+        strip_client_cert(&mut headers, 0);
+        assert_eq!(client_cert_thumbprint(&headers), None);
+    }
+}
@@ -0,0 +1,157 @@
+//! Impossible travel policy
+//!
+//! With a GeoIP database installed, every fresh sign-in is located and
+//! compared with the user's previous one. When covering the distance in the
+//! time between them would take a speed above `IMPOSSIBLE_TRAVEL_MAX_KMH`,
+//! the sign-in is audited, the user is emailed and, depending on
+//! `IMPOSSIBLE_TRAVEL_ACTION`, it is flagged or refused. Short distances are
+//! ignored since IP geolocation is not precise enough for them.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::utils::geoip::GeoLocation;
+
+/// Distances below this never count as impossible travel
+pub const MIN_TRAVEL_KM: f64 = 500.0;
+
+/// Shortest time between sign-ins used for the speed, so instant ones don't divide by zero
+const MIN_ELAPSED_SECS: i64 = 60;
+
+/// What happens to a sign-in that implies impossible travel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TravelAction {
+    /// Allowed, but audited and the user is emailed
+    Flag,
+    /// Refused with `impossible_travel`, audited and the user is emailed
+    Deny,
+}
+
+impl TravelAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Flag => "flag",
+            Self::Deny => "deny",
+        }
+    }
+}
+
+impl FromStr for TravelAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "flag" => Ok(Self::Flag),
+            "deny" => Ok(Self::Deny),
+            other => Err(anyhow::anyhow!(
+                "Invalid impossible travel action '{}' (expected flag or deny)",
+                other
+            )),
+        }
+    }
+}
+
+/// Impossible travel settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TravelPolicy {
+    /// Fastest plausible travel between sign-ins (0 = disabled)
+    pub max_speed_kmh: f64,
+    pub action: TravelAction,
+}
+
+impl Default for TravelPolicy {
+    fn default() -> Self {
+        Self {
+            max_speed_kmh: 0.0,
+            action: TravelAction::Flag,
+        }
+    }
+}
+
+impl TravelPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_speed_kmh: config.impossible_travel_max_kmh,
+            action: config.impossible_travel_action,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_speed_kmh > 0.0
+    }
+}
+
+/// Travel between two sign-ins
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Travel {
+    pub distance_km: f64,
+    pub elapsed_secs: i64,
+    pub speed_kmh: f64,
+}
+
+impl Travel {
+    pub fn between(
+        from: &GeoLocation,
+        from_at: DateTime<Utc>,
+        to: &GeoLocation,
+        to_at: DateTime<Utc>,
+    ) -> Self {
+        let distance_km = from.distance_km(to);
+        let elapsed_secs = (to_at - from_at).num_seconds().max(0);
+        let hours = elapsed_secs.max(MIN_ELAPSED_SECS) as f64 / 3600.0;
+
+        Self {
+            distance_km,
+            elapsed_secs,
+            speed_kmh: distance_km / hours,
+        }
+    }
+
+    /// Whether the travel is too fast for the policy
+    pub fn is_impossible(&self, policy: &TravelPolicy) -> bool {
+        policy.is_enabled() && self.distance_km >= MIN_TRAVEL_KM && self.speed_kmh > policy.max_speed_kmh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn at(latitude: f64, longitude: f64) -> GeoLocation {
+        GeoLocation { latitude, longitude, country: None }
+    }
+
+    #[test]
+    fn test_travel_faster_than_the_policy_is_impossible() {
+        let policy = TravelPolicy { max_speed_kmh: 1000.0, action: TravelAction::Deny };
+        let (hanoi, paris) = (at(21.0285, 105.8542), at(48.8566, 2.3522));
+        let now = Utc::now();
+
+        assert!(Travel::between(&hanoi, now - Duration::hours(2), &paris, now).is_impossible(&policy));
+        assert!(!Travel::between(&hanoi, now - Duration::hours(12), &paris, now).is_impossible(&policy));
+        assert!(!Travel::between(&hanoi, now, &paris, now).is_impossible(&TravelPolicy::default()));
+    }
+
+    #[test]
+    fn test_short_distances_are_never_impossible() {
+        let policy = TravelPolicy { max_speed_kmh: 1000.0, action: TravelAction::Flag };
+        let (hanoi, haiphong) = (at(21.0285, 105.8542), at(20.8449, 106.6881));
+        let now = Utc::now();
+
+        let travel = Travel::between(&hanoi, now, &haiphong, now);
+        assert!(travel.speed_kmh > policy.max_speed_kmh);
+        assert!(!travel.is_impossible(&policy));
+    }
+
+    #[test]
+    fn test_travel_action_parses() {
+        assert_eq!("Deny".parse::<TravelAction>().unwrap(), TravelAction::Deny);
+        assert_eq!("flag".parse::<TravelAction>().unwrap(), TravelAction::Flag);
+        assert!("block".parse::<TravelAction>().is_err());
+    }
+}
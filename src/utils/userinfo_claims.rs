@@ -0,0 +1,140 @@
+//! Which userinfo claims a set of granted scopes releases
+//!
+//! Standard scopes release the OpenID Connect claims below. A client's
+//! custom scope releases the claims it was configured with, so an app can
+//! ask for e.g. `myapp:contact` and receive `name` and `phone_number`
+//! without requesting the broader `profile` and `phone` scopes. `sub` is
+//! always returned.
+
+use std::collections::BTreeSet;
+
+/// Claims a custom scope may be mapped to
+pub const SUPPORTED_CLAIMS: &[&str] = &[
+    "name",
+    "picture",
+    "locale",
+    "updated_at",
+    "email",
+    "email_verified",
+    "phone_number",
+    "address",
+];
+
+/// Claims released by a standard scope
+pub fn standard_scope_claims(scope: &str) -> &'static [&'static str] {
+    match scope {
+        // openid has always released the email for clients that only ask for it
+        "openid" | "email" => &["email", "email_verified"],
+        "profile" => &["name", "picture", "locale", "updated_at"],
+        "phone" => &["phone_number"],
+        "address" => &["address"],
+        _ => &[],
+    }
+}
+
+/// Validate the claims a custom scope maps to, returning them deduplicated
+pub fn validate_scope_claims(claims: &[String]) -> Result<Vec<String>, String> {
+    let mut valid: Vec<String> = Vec::with_capacity(claims.len());
+
+    for claim in claims {
+        let claim = claim.trim();
+        if !SUPPORTED_CLAIMS.contains(&claim) {
+            return Err(format!(
+                "Unsupported claim '{}' (supported: {})",
+                claim,
+                SUPPORTED_CLAIMS.join(", ")
+            ));
+        }
+        if !valid.iter().any(|c| c == claim) {
+            valid.push(claim.to_string());
+        }
+    }
+
+    Ok(valid)
+}
+
+/// Canonical form of a BCP 47 language tag for the `locale` claim
+///
+/// Accepts `_` as separator (`en_us` becomes `en-US`); the language is
+/// lowercased, a script titlecased and a region uppercased.
+pub fn normalize_locale(locale: &str) -> Result<String, String> {
+    let invalid = || format!("Invalid locale '{}' (expected a language tag such as en-US)", locale);
+    let trimmed = locale.trim();
+    if trimmed.is_empty() || trimmed.len() > 35 {
+        return Err(invalid());
+    }
+
+    let mut subtags = Vec::new();
+    for (i, subtag) in trimmed.split(['-', '_']).enumerate() {
+        if subtag.is_empty() || subtag.len() > 8 || !subtag.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(invalid());
+        }
+        let subtag = match (i, subtag.len()) {
+            (0, 2..=3) if subtag.chars().all(|c| c.is_ascii_alphabetic()) => subtag.to_ascii_lowercase(),
+            (0, _) => return Err(invalid()),
+            (_, 4) if subtag.chars().all(|c| c.is_ascii_alphabetic()) => {
+                subtag[..1].to_ascii_uppercase() + &subtag[1..].to_ascii_lowercase()
+            }
+            (_, 2) => subtag.to_ascii_uppercase(),
+            _ => subtag.to_ascii_lowercase(),
+        };
+        subtags.push(subtag);
+    }
+
+    Ok(subtags.join("-"))
+}
+
+/// Claims released by the granted scopes
+///
+/// `custom` holds the claim mapping of the granted custom scopes.
+pub fn released_claims<'a>(
+    granted_scopes: impl IntoIterator<Item = &'a str>,
+    custom: &'a [Vec<String>],
+) -> BTreeSet<&'a str> {
+    let mut claims: BTreeSet<&str> = granted_scopes
+        .into_iter()
+        .flat_map(|scope| standard_scope_claims(scope).iter().copied())
+        .collect();
+    claims.extend(custom.iter().flatten().map(String::as_str));
+    claims
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_released_claims() {
+        let none: Vec<Vec<String>> = vec![];
+        let claims = released_claims(["profile"], &none);
+        assert_eq!(claims.into_iter().collect::<Vec<_>>(), vec!["locale", "name", "picture", "updated_at"]);
+
+        let claims = released_claims(["openid", "address"], &none);
+        assert_eq!(claims.into_iter().collect::<Vec<_>>(), vec!["address", "email", "email_verified"]);
+
+        let custom = vec![vec!["name".to_string(), "phone_number".to_string()]];
+        let claims = released_claims(["myapp:contact"], &custom);
+        assert_eq!(claims.into_iter().collect::<Vec<_>>(), vec!["name", "phone_number"]);
+
+        assert!(released_claims(["offline_access"], &none).is_empty());
+    }
+
+    #[test]
+    fn test_validate_scope_claims() {
+        let claims = vec!["name".to_string(), " address ".to_string(), "name".to_string()];
+        assert_eq!(validate_scope_claims(&claims).unwrap(), vec!["name", "address"]);
+        assert!(validate_scope_claims(&["password_hash".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_normalize_locale() {
+        assert_eq!(normalize_locale("vi").unwrap(), "vi");
+        assert_eq!(normalize_locale(" en_us ").unwrap(), "en-US");
+        assert_eq!(normalize_locale("ZH-hant-tw").unwrap(), "zh-Hant-TW");
+        assert_eq!(normalize_locale("es-419").unwrap(), "es-419");
+        assert!(normalize_locale("").is_err());
+        assert!(normalize_locale("english").is_err());
+        assert!(normalize_locale("en--US").is_err());
+        assert!(normalize_locale("en-US<script>").is_err());
+    }
+}
@@ -0,0 +1,185 @@
+use chrono::{DateTime, Duration as ChronoDuration, TimeZone, Utc};
+use sqlx::MySqlPool;
+use std::time::Duration;
+use tokio::time::interval;
+
+use crate::repositories::AbuseTelemetryRepository;
+use crate::services::IpRuleService;
+use crate::utils::abuse_telemetry::take_abuse_counts;
+use crate::workers::leader::LeaderLock;
+
+/// Leader lock name; only one instance turns bursts into suggestions at a time
+pub const WORKER_NAME: &str = "abuse_telemetry_worker";
+
+/// How long window counters are kept
+const RETENTION_DAYS: i64 = 7;
+
+/// How long a rejected suggestion keeps its IP from being suggested again
+const REJECTION_COOLDOWN_HOURS: i64 = 24;
+
+/// Background worker turning abuse telemetry into suggested IP deny rules
+///
+/// On every tick each instance flushes its in-memory 4xx/429 counters into
+/// the current window. The leader then looks at the current and previous
+/// windows and creates a pending suggestion for every IP whose rejected
+/// requests to one route reach a threshold. Suggestions only become rules
+/// once an admin approves them.
+pub struct AbuseTelemetryWorker {
+    pool: MySqlPool,
+    leader: LeaderLock,
+    interval_secs: u64,
+    window_secs: i64,
+    error_threshold: i64,
+    rate_limited_threshold: i64,
+}
+
+impl AbuseTelemetryWorker {
+    /// Create a new abuse telemetry worker
+    ///
+    /// # Arguments
+    /// * `pool` - Database connection pool
+    /// * `interval_secs` - How often to flush counters and look for bursts (in seconds)
+    /// * `window_secs` - Length of a counting window
+    /// * `error_threshold` - 4xx responses per window that trigger a suggestion (0 disables)
+    /// * `rate_limited_threshold` - 429 responses per window that trigger a suggestion (0 disables)
+    /// * `instance_id` - This replica's identity, recorded when it takes leadership
+    pub fn new(
+        pool: MySqlPool,
+        interval_secs: u64,
+        window_secs: i64,
+        error_threshold: i64,
+        rate_limited_threshold: i64,
+        instance_id: String,
+    ) -> Self {
+        Self {
+            leader: LeaderLock::new(pool.clone(), WORKER_NAME, instance_id),
+            pool,
+            interval_secs,
+            window_secs: window_secs.max(1),
+            error_threshold,
+            rate_limited_threshold,
+        }
+    }
+
+    /// Start the abuse telemetry worker
+    ///
+    /// This method runs indefinitely until the task is cancelled.
+    pub async fn run(&mut self) {
+        tracing::info!(
+            "Abuse telemetry worker started, flushing every {} seconds ({}s windows)",
+            self.interval_secs,
+            self.window_secs
+        );
+
+        let mut ticker = interval(Duration::from_secs(self.interval_secs));
+
+        loop {
+            ticker.tick().await;
+
+            // Every instance flushes its own counters
+            if let Err(e) = self.flush().await {
+                tracing::error!("Abuse telemetry flush error: {}", e);
+            }
+
+            if !self.leader.ensure_leader().await {
+                continue;
+            }
+
+            if let Err(e) = self.suggest().await {
+                tracing::error!("Abuse telemetry worker error: {}", e);
+            }
+        }
+    }
+
+    /// Add the counters recorded since the last tick to the current window
+    async fn flush(&self) -> Result<(), anyhow::Error> {
+        let counts = take_abuse_counts();
+        if counts.is_empty() {
+            return Ok(());
+        }
+
+        let repo = AbuseTelemetryRepository::new(self.pool.clone());
+        let window_start = window_start(Utc::now(), self.window_secs);
+
+        for ((ip, route), c) in counts {
+            repo.add_counts(&ip, &route, window_start, c)
+                .await
+                .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Create suggestions from recent bursts and purge old windows
+    async fn suggest(&self) -> Result<(), anyhow::Error> {
+        let now = Utc::now();
+        let since = window_start(now, self.window_secs) - ChronoDuration::seconds(self.window_secs);
+
+        let created = IpRuleService::new(self.pool.clone())
+            .suggest_from_bursts(
+                since,
+                self.window_secs,
+                self.error_threshold,
+                self.rate_limited_threshold,
+                now - ChronoDuration::hours(REJECTION_COOLDOWN_HOURS),
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+
+        let purged = AbuseTelemetryRepository::new(self.pool.clone())
+            .purge_before(now - ChronoDuration::days(RETENTION_DAYS))
+            .await
+            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+
+        if created > 0 || purged > 0 {
+            tracing::info!(
+                "Abuse telemetry worker suggested {} IP rules, purged {} old windows",
+                created,
+                purged
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Start of the window containing `at`
+fn window_start(at: DateTime<Utc>, window_secs: i64) -> DateTime<Utc> {
+    let secs = at.timestamp();
+    Utc.timestamp_opt(secs - secs.rem_euclid(window_secs), 0)
+        .single()
+        .unwrap_or(at)
+}
+
+/// Spawn the abuse telemetry worker as a background task
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `interval_secs` - Flush interval in seconds (default: 60)
+/// * `window_secs` - Counting window in seconds (default: 300)
+/// * `error_threshold` - 4xx responses per window (default: 100)
+/// * `rate_limited_threshold` - 429 responses per window (default: 20)
+/// * `instance_id` - This replica's identity
+///
+/// # Returns
+/// A JoinHandle that can be used to await or abort the worker
+pub fn spawn_abuse_telemetry_worker(
+    pool: MySqlPool,
+    interval_secs: u64,
+    window_secs: i64,
+    error_threshold: i64,
+    rate_limited_threshold: i64,
+    instance_id: String,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut worker = AbuseTelemetryWorker::new(
+            pool,
+            interval_secs,
+            window_secs,
+            error_threshold,
+            rate_limited_threshold,
+            instance_id,
+        );
+        worker.run().await;
+    })
+}
@@ -0,0 +1,183 @@
+use chrono::Utc;
+use sqlx::MySqlPool;
+use std::time::Duration;
+use tokio::time::interval;
+
+use crate::models::{PendingAppDigest, DIGEST_STATUS_FAILED, DIGEST_STATUS_SENT, DIGEST_STATUS_SUPPRESSED};
+use crate::repositories::AppDigestRepository;
+use crate::services::{AppDigestService, EmailConfig, EmailService, MockEmailService};
+use crate::workers::leader::LeaderLock;
+
+/// Digests queued and sent per tick
+const BATCH_SIZE: i64 = 100;
+
+/// Leader lock name; only one instance builds and sends digests at a time
+pub const WORKER_NAME: &str = "app_digest_worker";
+
+/// Background worker for weekly app owner digests
+///
+/// On every tick it queues the last full week's digest of each app that
+/// has the digest enabled and none for that week yet, then sends the
+/// oldest queued digests. Owners on the suppression list or whose address
+/// bounced are skipped.
+pub struct AppDigestWorker {
+    pool: MySqlPool,
+    leader: LeaderLock,
+    interval_secs: u64,
+    /// SMTP mailer, or None to only log the digests
+    mailer: Option<EmailService>,
+}
+
+impl AppDigestWorker {
+    /// Create a new app digest worker
+    ///
+    /// # Arguments
+    /// * `pool` - Database connection pool
+    /// * `interval_secs` - How often to queue and send digests (in seconds)
+    /// * `instance_id` - This replica's identity, recorded when it takes leadership
+    pub fn new(pool: MySqlPool, interval_secs: u64, instance_id: String) -> Self {
+        let mailer = EmailConfig::from_env().and_then(|config| match EmailService::new(config) {
+            Ok(mailer) => Some(mailer.with_bounce_list(pool.clone())),
+            Err(e) => {
+                tracing::error!("App digest worker cannot send email: {:?}", e);
+                None
+            }
+        });
+
+        Self {
+            leader: LeaderLock::new(pool.clone(), WORKER_NAME, instance_id),
+            pool,
+            interval_secs,
+            mailer,
+        }
+    }
+
+    /// Start the app digest worker
+    ///
+    /// This method runs indefinitely until the task is cancelled.
+    pub async fn run(&mut self) {
+        tracing::info!("App digest worker started, running every {} seconds", self.interval_secs);
+
+        let mut ticker = interval(Duration::from_secs(self.interval_secs));
+
+        loop {
+            ticker.tick().await;
+
+            if !self.leader.ensure_leader().await {
+                continue;
+            }
+
+            if let Err(e) = self.queue_digests().await {
+                tracing::error!("App digest worker error while queueing digests: {}", e);
+            }
+
+            if let Err(e) = self.send_pending().await {
+                tracing::error!("App digest worker error: {}", e);
+            }
+        }
+    }
+
+    /// Queue the digests that are due
+    async fn queue_digests(&self) -> Result<(), anyhow::Error> {
+        let queued = AppDigestService::new(self.pool.clone())
+            .queue_due(Utc::now(), BATCH_SIZE)
+            .await
+            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+
+        if queued > 0 {
+            tracing::info!("App digest worker queued {} digests", queued);
+        }
+
+        Ok(())
+    }
+
+    /// Send the oldest queued digests
+    async fn send_pending(&self) -> Result<(), anyhow::Error> {
+        let repo = AppDigestRepository::new(self.pool.clone());
+
+        let pending = repo
+            .next_pending(BATCH_SIZE)
+            .await
+            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+
+        let mut sent = 0;
+        for digest in pending {
+            if digest.suppressed {
+                repo.mark(digest.id, DIGEST_STATUS_SUPPRESSED, None)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+                continue;
+            }
+
+            match self.deliver(&digest).await {
+                Ok(()) => {
+                    repo.mark(digest.id, DIGEST_STATUS_SENT, None)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+                    sent += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to send app digest {}: {}", digest.id, e);
+                    repo.mark(digest.id, DIGEST_STATUS_FAILED, Some(&e))
+                        .await
+                        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+                }
+            }
+        }
+
+        if sent > 0 {
+            tracing::info!("App digest worker sent {} digests", sent);
+        }
+
+        Ok(())
+    }
+
+    async fn deliver(&self, digest: &PendingAppDigest) -> Result<(), String> {
+        let result = match &self.mailer {
+            Some(mailer) => {
+                mailer
+                    .send_app_digest(
+                        &digest.owner_email,
+                        &digest.app_name,
+                        digest.period_start,
+                        digest.period_end,
+                        &digest.stats,
+                    )
+                    .await
+            }
+            None => {
+                MockEmailService::new()
+                    .send_app_digest(
+                        &digest.owner_email,
+                        &digest.app_name,
+                        digest.period_start,
+                        digest.period_end,
+                        &digest.stats,
+                    )
+                    .await
+            }
+        };
+
+        result.map_err(|e| format!("{:?}", e))
+    }
+}
+
+/// Spawn the app digest worker as a background task
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `interval_secs` - Tick interval in seconds (default: 3600)
+/// * `instance_id` - This replica's identity
+///
+/// # Returns
+/// A JoinHandle that can be used to await or abort the worker
+pub fn spawn_app_digest_worker(
+    pool: MySqlPool,
+    interval_secs: u64,
+    instance_id: String,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut worker = AppDigestWorker::new(pool, interval_secs, instance_id);
+        worker.run().await;
+    })
+}
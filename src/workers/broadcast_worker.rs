@@ -0,0 +1,193 @@
+use sqlx::MySqlPool;
+use std::time::Duration;
+use tokio::time::interval;
+
+use crate::models::{
+    EmailBroadcast, RECIPIENT_STATUS_FAILED, RECIPIENT_STATUS_SENT, RECIPIENT_STATUS_SUPPRESSED,
+};
+use crate::repositories::EmailBroadcastRepository;
+use crate::services::broadcast::render;
+use crate::services::{EmailConfig, EmailService, MockEmailService};
+use crate::workers::leader::LeaderLock;
+
+/// Due broadcasts started per tick
+const START_BATCH_SIZE: i64 = 10;
+
+/// Leader lock name; only one instance sends broadcasts at a time
+pub const WORKER_NAME: &str = "broadcast_worker";
+
+/// Background worker sending admin email broadcasts
+///
+/// On every tick it selects the recipients of broadcasts whose scheduled
+/// time has passed, then works through the queued recipients, oldest
+/// broadcast first, sending at most `max_per_minute` emails a minute.
+/// Users suppressed, or whose address bounced, after being queued are
+/// skipped at send time.
+pub struct BroadcastWorker {
+    pool: MySqlPool,
+    leader: LeaderLock,
+    interval_secs: u64,
+    max_per_minute: u32,
+    /// SMTP mailer, or None to only log the emails
+    mailer: Option<EmailService>,
+    app_name: String,
+}
+
+impl BroadcastWorker {
+    /// Create a new broadcast worker
+    ///
+    /// # Arguments
+    /// * `pool` - Database connection pool
+    /// * `interval_secs` - How often to start due broadcasts and send queued emails (in seconds)
+    /// * `max_per_minute` - Emails sent per minute across all broadcasts
+    /// * `instance_id` - This replica's identity, recorded when it takes leadership
+    pub fn new(pool: MySqlPool, interval_secs: u64, max_per_minute: u32, instance_id: String) -> Self {
+        let mailer = EmailConfig::from_env().and_then(|config| match EmailService::new(config) {
+            Ok(mailer) => Some(mailer.with_bounce_list(pool.clone())),
+            Err(e) => {
+                tracing::error!("Broadcast worker cannot send email: {:?}", e);
+                None
+            }
+        });
+
+        Self {
+            leader: LeaderLock::new(pool.clone(), WORKER_NAME, instance_id),
+            pool,
+            interval_secs,
+            max_per_minute,
+            mailer,
+            app_name: EmailConfig::app_name(),
+        }
+    }
+
+    /// Start the broadcast worker
+    ///
+    /// This method runs indefinitely until the task is cancelled.
+    pub async fn run(&mut self) {
+        tracing::info!(
+            "Broadcast worker started, sending up to {} emails per minute every {} seconds",
+            self.max_per_minute,
+            self.interval_secs
+        );
+
+        let mut ticker = interval(Duration::from_secs(self.interval_secs));
+
+        loop {
+            ticker.tick().await;
+
+            if !self.leader.ensure_leader().await {
+                continue;
+            }
+
+            if let Err(e) = self.process().await {
+                tracing::error!("Broadcast worker error: {}", e);
+            }
+        }
+    }
+
+    /// Emails this tick may send, spreading the per-minute rate over the ticks
+    fn tick_budget(&self) -> i64 {
+        (self.max_per_minute as u64 * self.interval_secs / 60).max(1) as i64
+    }
+
+    /// Start due broadcasts and send the next queued emails
+    async fn process(&self) -> Result<(), anyhow::Error> {
+        let repo = EmailBroadcastRepository::new(self.pool.clone());
+
+        for broadcast in repo.find_due(START_BATCH_SIZE).await? {
+            if repo.start(&broadcast).await? {
+                tracing::info!("Broadcast {} started", broadcast.id);
+            }
+        }
+
+        let mut budget = self.tick_budget();
+        for broadcast in repo.find_sending().await? {
+            if budget == 0 {
+                break;
+            }
+            budget -= self.send_batch(&repo, &broadcast, budget).await?;
+
+            if repo.complete_if_drained(broadcast.id).await? {
+                tracing::info!("Broadcast {} sent", broadcast.id);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send up to `budget` queued emails of a broadcast; returns how many were attempted
+    async fn send_batch(
+        &self,
+        repo: &EmailBroadcastRepository,
+        broadcast: &EmailBroadcast,
+        budget: i64,
+    ) -> Result<i64, anyhow::Error> {
+        let recipients = repo.next_recipients(broadcast.id, budget).await?;
+        let mut attempted = 0;
+
+        for recipient in recipients {
+            if recipient.suppressed {
+                repo.mark_recipient(broadcast.id, recipient.user_id, RECIPIENT_STATUS_SUPPRESSED, None)
+                    .await?;
+                continue;
+            }
+
+            attempted += 1;
+            let sent = match render(&broadcast.subject, &broadcast.body, &recipient, &self.app_name) {
+                Ok(email) => match &self.mailer {
+                    Some(mailer) => mailer
+                        .send_broadcast(&email.to, &email.subject, &email.html)
+                        .await
+                        .map_err(|e| format!("{:?}", e)),
+                    None => MockEmailService::new()
+                        .send_broadcast(&email.to, &email.subject, &email.html)
+                        .await
+                        .map_err(|e| format!("{:?}", e)),
+                },
+                Err(e) => Err(e),
+            };
+
+            // Failed emails are not retried, so one bad address can't stall the queue
+            match sent {
+                Ok(()) => {
+                    repo.mark_recipient(broadcast.id, recipient.user_id, RECIPIENT_STATUS_SENT, None)
+                        .await?
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to send broadcast {} to user {}: {}",
+                        broadcast.id,
+                        recipient.user_id,
+                        e
+                    );
+                    repo.mark_recipient(broadcast.id, recipient.user_id, RECIPIENT_STATUS_FAILED, Some(&e))
+                        .await?
+                }
+            }
+        }
+
+        Ok(attempted)
+    }
+}
+
+/// Spawn the broadcast worker as a background task
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `interval_secs` - Tick interval in seconds (default: 60)
+/// * `max_per_minute` - Emails sent per minute (default: 60)
+/// * `instance_id` - This replica's identity
+///
+/// # Returns
+/// A JoinHandle that can be used to await or abort the worker
+pub fn spawn_broadcast_worker(
+    pool: MySqlPool,
+    interval_secs: u64,
+    max_per_minute: u32,
+    instance_id: String,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut worker = BroadcastWorker::new(pool, interval_secs, max_per_minute, instance_id);
+        worker.run().await;
+    })
+}
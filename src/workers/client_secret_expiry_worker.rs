@@ -0,0 +1,169 @@
+use sqlx::MySqlPool;
+use std::time::Duration;
+use tokio::time::interval;
+
+use crate::repositories::OAuthClientRepository;
+use crate::services::{EmailConfig, EmailService, MockEmailService};
+use crate::workers::leader::LeaderLock;
+
+/// Clients warned per tick
+const BATCH_SIZE: i64 = 200;
+
+/// Leader lock name; only one instance sends the warnings at a time
+pub const WORKER_NAME: &str = "client_secret_expiry_worker";
+
+/// Background worker warning OAuth client owners before their secret expires
+///
+/// On every tick it emails the owner of each active client whose secret
+/// expires within the warning window, once per secret. Rotating the secret
+/// or changing its maximum age re-arms the warning. Expired secrets are
+/// rejected at the token endpoint regardless of this worker.
+pub struct ClientSecretExpiryWorker {
+    pool: MySqlPool,
+    leader: LeaderLock,
+    interval_secs: u64,
+    max_age_days: i64,
+    warning_days: i64,
+    /// SMTP mailer, or None to only log the warnings
+    mailer: Option<EmailService>,
+}
+
+impl ClientSecretExpiryWorker {
+    /// Create a new client secret expiry worker
+    ///
+    /// # Arguments
+    /// * `pool` - Database connection pool
+    /// * `interval_secs` - How often to look for expiring secrets (in seconds)
+    /// * `max_age_days` - Server-wide maximum secret age (0 = no expiry)
+    /// * `warning_days` - How many days before expiry the owner is warned
+    /// * `instance_id` - This replica's identity, recorded when it takes leadership
+    pub fn new(
+        pool: MySqlPool,
+        interval_secs: u64,
+        max_age_days: i64,
+        warning_days: i64,
+        instance_id: String,
+    ) -> Self {
+        let mailer = EmailConfig::from_env().and_then(|config| match EmailService::new(config) {
+            Ok(mailer) => Some(mailer.with_bounce_list(pool.clone())),
+            Err(e) => {
+                tracing::error!("Client secret expiry worker cannot send email: {:?}", e);
+                None
+            }
+        });
+
+        Self {
+            leader: LeaderLock::new(pool.clone(), WORKER_NAME, instance_id),
+            pool,
+            interval_secs,
+            max_age_days,
+            warning_days,
+            mailer,
+        }
+    }
+
+    /// Start the client secret expiry worker
+    ///
+    /// This method runs indefinitely until the task is cancelled.
+    pub async fn run(&mut self) {
+        tracing::info!(
+            "Client secret expiry worker started, scanning every {} seconds",
+            self.interval_secs
+        );
+
+        let mut ticker = interval(Duration::from_secs(self.interval_secs));
+
+        loop {
+            ticker.tick().await;
+
+            if !self.leader.ensure_leader().await {
+                continue;
+            }
+
+            if let Err(e) = self.process().await {
+                tracing::error!("Client secret expiry worker error: {}", e);
+            }
+        }
+    }
+
+    /// Warn the owners of secrets entering the warning window
+    async fn process(&self) -> Result<(), anyhow::Error> {
+        let repo = OAuthClientRepository::new(self.pool.clone());
+        let expiring = repo
+            .find_expiring_secrets(self.max_age_days, self.warning_days, BATCH_SIZE)
+            .await
+            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+
+        let mut warned = 0;
+        for client in expiring {
+            let sent = match &self.mailer {
+                Some(mailer) => {
+                    mailer
+                        .send_client_secret_expiring(
+                            &client.owner_email,
+                            &client.name,
+                            &client.client_id,
+                            client.secret_expires_at,
+                        )
+                        .await
+                }
+                None => {
+                    MockEmailService::new()
+                        .send_client_secret_expiring(
+                            &client.owner_email,
+                            &client.name,
+                            &client.client_id,
+                            client.secret_expires_at,
+                        )
+                        .await
+                }
+            };
+
+            // Unsent warnings are retried on the next tick
+            if let Err(e) = sent {
+                tracing::warn!(
+                    "Failed to warn owner of client {} about its expiring secret: {:?}",
+                    client.client_id,
+                    e
+                );
+                continue;
+            }
+
+            repo.mark_secret_expiry_warned(&client.id)
+                .await
+                .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+            warned += 1;
+        }
+
+        if warned > 0 {
+            tracing::info!("Client secret expiry worker warned {} client owners", warned);
+        }
+
+        Ok(())
+    }
+}
+
+/// Spawn the client secret expiry worker as a background task
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `interval_secs` - Scan interval in seconds (default: 3600)
+/// * `max_age_days` - Server-wide maximum secret age (default: 0, no expiry)
+/// * `warning_days` - Warning window in days (default: 14)
+/// * `instance_id` - This replica's identity
+///
+/// # Returns
+/// A JoinHandle that can be used to await or abort the worker
+pub fn spawn_client_secret_expiry_worker(
+    pool: MySqlPool,
+    interval_secs: u64,
+    max_age_days: i64,
+    warning_days: i64,
+    instance_id: String,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut worker =
+            ClientSecretExpiryWorker::new(pool, interval_secs, max_age_days, warning_days, instance_id);
+        worker.run().await;
+    })
+}
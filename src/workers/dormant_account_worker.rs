@@ -0,0 +1,228 @@
+use chrono::Duration as ChronoDuration;
+use sqlx::MySqlPool;
+use std::time::Duration;
+use tokio::time::interval;
+
+use crate::models::{AuditAction, DormantAccount};
+use crate::repositories::DormantAccountRepository;
+use crate::services::{AuditService, EmailConfig, EmailService, MockEmailService};
+use crate::utils::dormancy::{DormancyPolicy, DormantAction};
+use crate::workers::leader::LeaderLock;
+
+/// Accounts warned or flagged per tick
+const BATCH_SIZE: i64 = 200;
+
+/// Leader lock name; only one instance applies the policy at a time
+pub const WORKER_NAME: &str = "dormant_account_worker";
+
+/// Background worker applying the dormant account policy
+///
+/// On every tick it first warns the owners of accounts that become dormant
+/// within one of the notice windows (once per window and period of
+/// inactivity), then flags accounts inactive for the configured number of
+/// days and applies the action. System admins are flagged but never
+/// deactivated or sent back to email verification.
+pub struct DormantAccountWorker {
+    pool: MySqlPool,
+    leader: LeaderLock,
+    interval_secs: u64,
+    policy: DormancyPolicy,
+    /// SMTP mailer, or None to only log the warnings
+    mailer: Option<EmailService>,
+}
+
+impl DormantAccountWorker {
+    /// Create a new dormant account worker
+    ///
+    /// # Arguments
+    /// * `pool` - Database connection pool
+    /// * `interval_secs` - How often to look for dormant accounts (in seconds)
+    /// * `policy` - Dormancy period, action and notice windows
+    /// * `instance_id` - This replica's identity, recorded when it takes leadership
+    pub fn new(pool: MySqlPool, interval_secs: u64, policy: DormancyPolicy, instance_id: String) -> Self {
+        let mailer = EmailConfig::from_env().and_then(|config| match EmailService::new(config) {
+            Ok(mailer) => Some(mailer.with_bounce_list(pool.clone())),
+            Err(e) => {
+                tracing::error!("Dormant account worker cannot send email: {:?}", e);
+                None
+            }
+        });
+
+        Self {
+            leader: LeaderLock::new(pool.clone(), WORKER_NAME, instance_id),
+            pool,
+            interval_secs,
+            policy,
+            mailer,
+        }
+    }
+
+    /// Start the dormant account worker
+    ///
+    /// This method runs indefinitely until the task is cancelled.
+    pub async fn run(&mut self) {
+        tracing::info!(
+            "Dormant account worker started, accounts become dormant after {} days ({}), scanning every {} seconds",
+            self.policy.days,
+            self.policy.action.as_str(),
+            self.interval_secs
+        );
+
+        let mut ticker = interval(Duration::from_secs(self.interval_secs));
+
+        loop {
+            ticker.tick().await;
+
+            if !self.leader.ensure_leader().await {
+                continue;
+            }
+
+            if let Err(e) = self.send_notices().await {
+                tracing::error!("Dormant account worker error while sending notices: {}", e);
+            }
+
+            if let Err(e) = self.flag_dormant().await {
+                tracing::error!("Dormant account worker error: {}", e);
+            }
+        }
+    }
+
+    /// Warn the owners of accounts entering a notice window
+    ///
+    /// Windows are processed shortest first, so an account already close to
+    /// the action only gets the warning for the nearest window.
+    async fn send_notices(&self) -> Result<(), anyhow::Error> {
+        let repo = DormantAccountRepository::new(self.pool.clone());
+
+        let mut warned = 0;
+        for window in self.policy.notice_windows() {
+            let due = repo
+                .find_notices_due(self.policy.days, window, BATCH_SIZE)
+                .await
+                .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+
+            for account in due {
+                let action_at = account.last_activity_at + ChronoDuration::days(self.policy.days);
+                let sent = match &self.mailer {
+                    Some(mailer) => {
+                        mailer
+                            .send_dormant_account_notice(
+                                &account.email,
+                                self.policy.action,
+                                account.last_activity_at,
+                                action_at,
+                            )
+                            .await
+                    }
+                    None => {
+                        MockEmailService::new()
+                            .send_dormant_account_notice(
+                                &account.email,
+                                self.policy.action,
+                                account.last_activity_at,
+                                action_at,
+                            )
+                            .await
+                    }
+                };
+
+                // Unsent warnings are retried on the next tick
+                if let Err(e) = sent {
+                    tracing::warn!(
+                        "Failed to warn user {} about their dormant account: {:?}",
+                        account.user_id,
+                        e
+                    );
+                    continue;
+                }
+
+                repo.record_notice(account.user_id, account.last_activity_at, window)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+                warned += 1;
+            }
+        }
+
+        if warned > 0 {
+            tracing::info!("Dormant account worker warned {} users", warned);
+        }
+
+        Ok(())
+    }
+
+    /// Flag accounts past the dormancy period and apply the action
+    async fn flag_dormant(&self) -> Result<(), anyhow::Error> {
+        let repo = DormantAccountRepository::new(self.pool.clone());
+        let audit_service = AuditService::new(self.pool.clone());
+
+        let dormant = repo
+            .find_newly_dormant(self.policy.days, BATCH_SIZE)
+            .await
+            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+
+        let mut flagged = 0;
+        for account in dormant {
+            let action = applied_action(&self.policy, &account);
+            let marked = repo
+                .mark_dormant(account.user_id, action)
+                .await
+                .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+            if !marked {
+                continue;
+            }
+
+            let _ = audit_service
+                .log_auth_event(
+                    Some(account.user_id),
+                    AuditAction::AccountDormant,
+                    None,
+                    None,
+                    Some(serde_json::json!({
+                        "action": action.as_str(),
+                        "last_activity_at": account.last_activity_at,
+                        "days_inactive": account.days_inactive,
+                    })),
+                    true,
+                )
+                .await;
+            flagged += 1;
+        }
+
+        if flagged > 0 {
+            tracing::info!("Dormant account worker flagged {} accounts", flagged);
+        }
+
+        Ok(())
+    }
+}
+
+/// Action applied to a dormant account; system admins are only flagged
+fn applied_action(policy: &DormancyPolicy, account: &DormantAccount) -> DormantAction {
+    if account.is_system_admin {
+        DormantAction::Flag
+    } else {
+        policy.action
+    }
+}
+
+/// Spawn the dormant account worker as a background task
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `interval_secs` - Scan interval in seconds (default: 3600)
+/// * `policy` - Dormancy period, action and notice windows
+/// * `instance_id` - This replica's identity
+///
+/// # Returns
+/// A JoinHandle that can be used to await or abort the worker
+pub fn spawn_dormant_account_worker(
+    pool: MySqlPool,
+    interval_secs: u64,
+    policy: DormancyPolicy,
+    instance_id: String,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut worker = DormantAccountWorker::new(pool, interval_secs, policy, instance_id);
+        worker.run().await;
+    })
+}
@@ -0,0 +1,109 @@
+use sqlx::MySqlPool;
+use std::time::Duration;
+use tokio::time::interval;
+
+use crate::services::DuplicateAccountService;
+use crate::workers::leader::LeaderLock;
+
+/// Leader lock name; only one instance runs this worker at a time
+pub const WORKER_NAME: &str = "duplicate_account_worker";
+
+/// Background worker keeping duplicate-account match keys up to date
+///
+/// On every tick it computes normalized email/phone keys for new or changed
+/// users, one batch at a time, until no pending users are left. The admin
+/// duplicate report reads these keys instead of scanning every user.
+pub struct DuplicateAccountWorker {
+    pool: MySqlPool,
+    leader: LeaderLock,
+    interval_secs: u64,
+    batch_size: i64,
+}
+
+impl DuplicateAccountWorker {
+    /// Create a new duplicate-account worker
+    ///
+    /// # Arguments
+    /// * `pool` - Database connection pool
+    /// * `interval_secs` - How often to scan for new or changed users (in seconds)
+    /// * `batch_size` - How many users to process per batch
+    /// * `instance_id` - This replica's identity, recorded when it takes leadership
+    pub fn new(pool: MySqlPool, interval_secs: u64, batch_size: i64, instance_id: String) -> Self {
+        Self {
+            leader: LeaderLock::new(pool.clone(), WORKER_NAME, instance_id),
+            pool,
+            interval_secs,
+            batch_size: batch_size.max(1),
+        }
+    }
+
+    /// Start the duplicate-account worker
+    ///
+    /// This method runs indefinitely until the task is cancelled.
+    pub async fn run(&mut self) {
+        tracing::info!(
+            "Duplicate account worker started, scanning every {} seconds",
+            self.interval_secs
+        );
+
+        let mut ticker = interval(Duration::from_secs(self.interval_secs));
+
+        loop {
+            ticker.tick().await;
+
+            if !self.leader.ensure_leader().await {
+                continue;
+            }
+
+            if let Err(e) = self.process_pending().await {
+                tracing::error!("Duplicate account worker error: {}", e);
+            }
+        }
+    }
+
+    /// Process batches until the backlog of pending users is drained
+    async fn process_pending(&self) -> Result<(), anyhow::Error> {
+        let service = DuplicateAccountService::new(self.pool.clone());
+        let mut total = 0;
+
+        loop {
+            let processed = service
+                .process_batch(self.batch_size)
+                .await
+                .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+            total += processed;
+
+            if (processed as i64) < self.batch_size {
+                break;
+            }
+        }
+
+        if total > 0 {
+            tracing::info!("Duplicate account worker refreshed match keys for {} users", total);
+        }
+
+        Ok(())
+    }
+}
+
+/// Spawn the duplicate-account worker as a background task
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `interval_secs` - Scan interval in seconds (default: 3600)
+/// * `batch_size` - Users processed per batch (default: 500)
+/// * `instance_id` - This replica's identity
+///
+/// # Returns
+/// A JoinHandle that can be used to await or abort the worker
+pub fn spawn_duplicate_account_worker(
+    pool: MySqlPool,
+    interval_secs: u64,
+    batch_size: i64,
+    instance_id: String,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut worker = DuplicateAccountWorker::new(pool, interval_secs, batch_size, instance_id);
+        worker.run().await;
+    })
+}
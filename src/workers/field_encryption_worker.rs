@@ -0,0 +1,94 @@
+use sqlx::MySqlPool;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::interval;
+
+use crate::repositories::FieldEncryptionRepository;
+use crate::utils::field_crypto::{field_cipher, take_stale_fields};
+
+/// Background worker re-encrypting sensitive values lazily
+///
+/// Reads that find a value stored as plaintext or under a retired key queue
+/// the row in memory; on every tick this worker rewrites those rows under
+/// the active key. The queue is per instance, so unlike the other workers
+/// every replica runs this one (no leader lock).
+pub struct FieldEncryptionWorker {
+    repo: FieldEncryptionRepository,
+    interval_secs: u64,
+}
+
+impl FieldEncryptionWorker {
+    /// Create a new field encryption worker
+    ///
+    /// # Arguments
+    /// * `pool` - Database connection pool
+    /// * `interval_secs` - How often to drain the re-encryption queue (in seconds)
+    pub fn new(pool: MySqlPool, interval_secs: u64) -> Self {
+        Self {
+            repo: FieldEncryptionRepository::new(pool),
+            interval_secs,
+        }
+    }
+
+    /// Start the field encryption worker
+    ///
+    /// This method runs indefinitely until the task is cancelled.
+    pub async fn run(&mut self) {
+        tracing::info!(
+            "Field encryption worker started, re-encrypting every {} seconds",
+            self.interval_secs
+        );
+
+        let mut ticker = interval(Duration::from_secs(self.interval_secs));
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = self.reencrypt_stale().await {
+                tracing::error!("Field encryption worker error: {}", e);
+            }
+        }
+    }
+
+    /// Rewrite the rows read with stale values since the last tick
+    async fn reencrypt_stale(&self) -> Result<(), anyhow::Error> {
+        let Some(cipher) = field_cipher() else {
+            return Ok(());
+        };
+
+        let mut by_column: HashMap<_, Vec<String>> = HashMap::new();
+        for (column, id) in take_stale_fields() {
+            by_column.entry(column).or_default().push(id);
+        }
+
+        let mut total = 0;
+        for (column, ids) in by_column {
+            total += self
+                .repo
+                .reencrypt_rows(cipher, column, &ids)
+                .await
+                .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+        }
+
+        if total > 0 {
+            tracing::info!("Field encryption worker re-encrypted {} values", total);
+        }
+
+        Ok(())
+    }
+}
+
+/// Spawn the field encryption worker as a background task
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `interval_secs` - Drain interval in seconds (default: 60)
+///
+/// # Returns
+/// A JoinHandle that can be used to await or abort the worker
+pub fn spawn_field_encryption_worker(pool: MySqlPool, interval_secs: u64) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut worker = FieldEncryptionWorker::new(pool, interval_secs);
+        worker.run().await;
+    })
+}
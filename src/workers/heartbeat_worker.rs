@@ -0,0 +1,92 @@
+use std::time::Duration;
+use tokio::time::interval;
+
+use crate::services::InstanceService;
+
+/// How long to wait for the heartbeat endpoint
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// Background worker posting the opt-in usage heartbeat
+///
+/// Only spawned when HEARTBEAT_URL is set. Every replica reports itself
+/// (no leader lock), so the receiver sees the whole fleet. A failed post
+/// is logged and retried on the next tick.
+pub struct HeartbeatWorker {
+    service: InstanceService,
+    client: reqwest::Client,
+    url: String,
+    interval_secs: u64,
+}
+
+impl HeartbeatWorker {
+    /// Create a new heartbeat worker
+    ///
+    /// # Arguments
+    /// * `service` - Builds the instance report
+    /// * `url` - Endpoint the report is POSTed to as JSON
+    /// * `interval_secs` - How often to send the heartbeat (in seconds)
+    pub fn new(service: InstanceService, url: String, interval_secs: u64) -> Self {
+        Self {
+            service,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+                .build()
+                .unwrap_or_default(),
+            url,
+            interval_secs,
+        }
+    }
+
+    /// Start the heartbeat worker
+    ///
+    /// This method runs indefinitely until the task is cancelled.
+    pub async fn run(&mut self) {
+        // The URL may carry a token, so only its host is logged
+        tracing::info!(
+            "Heartbeat worker started, reporting to {} every {} seconds",
+            reqwest::Url::parse(&self.url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_default(),
+            self.interval_secs
+        );
+
+        let mut ticker = interval(Duration::from_secs(self.interval_secs));
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = self.send().await {
+                tracing::warn!("Heartbeat worker error: {:?}", e);
+            }
+        }
+    }
+
+    async fn send(&self) -> anyhow::Result<()> {
+        let report = self.service.report().await.map_err(|e| anyhow::anyhow!("{:?}", e))?;
+
+        let response = self.client.post(&self.url).json(&report).send().await?;
+        if !response.status().is_success() {
+            anyhow::bail!("heartbeat endpoint returned {}", response.status());
+        }
+
+        tracing::debug!("Heartbeat sent ({})", report.instance);
+        Ok(())
+    }
+}
+
+/// Spawn the heartbeat worker as a background task
+///
+/// # Arguments
+/// * `service` - Builds the instance report
+/// * `url` - Endpoint the report is POSTed to
+/// * `interval_secs` - Heartbeat interval in seconds (default: 86400)
+///
+/// # Returns
+/// A JoinHandle that can be used to await or abort the worker
+pub fn spawn_heartbeat_worker(service: InstanceService, url: String, interval_secs: u64) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut worker = HeartbeatWorker::new(service, url, interval_secs);
+        worker.run().await;
+    })
+}
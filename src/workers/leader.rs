@@ -0,0 +1,108 @@
+use sqlx::{Connection, MySqlConnection, MySqlPool};
+
+use crate::repositories::WorkerLeaderRepository;
+
+/// Leader election for periodic workers across replicas
+///
+/// Each worker takes a MySQL named lock (`GET_LOCK`) scoped to the current
+/// database. The lock is held on a connection detached from the pool, so it
+/// lives exactly as long as that connection: if the leader crashes or loses
+/// its connection the server releases the lock and another instance takes
+/// over on its next tick. Only the instance holding the lock runs the job.
+pub struct LeaderLock {
+    pool: MySqlPool,
+    worker: &'static str,
+    instance_id: String,
+    leader_repo: WorkerLeaderRepository,
+    conn: Option<MySqlConnection>,
+}
+
+impl LeaderLock {
+    /// Create a lock for `worker` on behalf of `instance_id`
+    pub fn new(pool: MySqlPool, worker: &'static str, instance_id: String) -> Self {
+        Self {
+            leader_repo: WorkerLeaderRepository::new(pool.clone()),
+            pool,
+            worker,
+            instance_id,
+            conn: None,
+        }
+    }
+
+    /// Check (and if free, take) leadership for this tick
+    ///
+    /// Returns true when this instance should run the job.
+    pub async fn ensure_leader(&mut self) -> bool {
+        if self.conn.is_some() {
+            if self.still_held().await {
+                if let Err(e) = self.leader_repo.heartbeat(self.worker, &self.instance_id).await {
+                    tracing::warn!("Failed to record {} leader heartbeat: {:?}", self.worker, e);
+                }
+                return true;
+            }
+
+            tracing::warn!("Instance {} lost leadership of {}", self.instance_id, self.worker);
+            self.release().await;
+        }
+
+        match self.try_acquire().await {
+            Ok(true) => {
+                tracing::info!("Instance {} is now leader of {}", self.instance_id, self.worker);
+                if let Err(e) = self.leader_repo.record_acquired(self.worker, &self.instance_id).await {
+                    tracing::warn!("Failed to record {} leader: {:?}", self.worker, e);
+                }
+                true
+            }
+            Ok(false) => false,
+            Err(e) => {
+                tracing::warn!("Failed to acquire {} leader lock: {}", self.worker, e);
+                false
+            }
+        }
+    }
+
+    /// Give up leadership (closing the connection releases the lock)
+    pub async fn release(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            let _ = conn.close().await;
+        }
+    }
+
+    async fn try_acquire(&mut self) -> Result<bool, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?.detach();
+
+        // Timeout 0: never wait, another instance is leading
+        let acquired = sqlx::query_scalar::<_, Option<bool>>(
+            "SELECT GET_LOCK(CONCAT(DATABASE(), ':', ?), 0)",
+        )
+        .bind(self.worker)
+        .fetch_one(&mut conn)
+        .await?
+        .unwrap_or(false);
+
+        if acquired {
+            self.conn = Some(conn);
+        } else {
+            let _ = conn.close().await;
+        }
+
+        Ok(acquired)
+    }
+
+    /// Verify the lock is still owned by our connection (and the connection is alive)
+    async fn still_held(&mut self) -> bool {
+        let Some(conn) = self.conn.as_mut() else {
+            return false;
+        };
+
+        sqlx::query_scalar::<_, Option<bool>>(
+            "SELECT IS_USED_LOCK(CONCAT(DATABASE(), ':', ?)) = CONNECTION_ID()",
+        )
+        .bind(self.worker)
+        .fetch_one(conn)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(false)
+    }
+}
@@ -1,3 +1,16 @@
+pub mod abuse_telemetry_worker;
+pub mod app_digest_worker;
+pub mod broadcast_worker;
+pub mod client_secret_expiry_worker;
+pub mod dormant_account_worker;
+pub mod duplicate_account_worker;
+pub mod field_encryption_worker;
+pub mod heartbeat_worker;
+pub mod leader;
+pub mod provisioning_consumer;
+pub mod provisioning_worker;
+pub mod role_expiry_worker;
+pub mod signing_key_worker;
 pub mod webhook_worker;
 
 pub use webhook_worker::WebhookWorker;
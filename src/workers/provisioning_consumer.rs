@@ -0,0 +1,125 @@
+use sqlx::MySqlPool;
+use std::time::Duration;
+
+use crate::models::BrokerProvisioningEvent;
+use crate::repositories::AppRepository;
+use crate::services::provisioning::{dead_letter_message, PROVISIONING_NATS_CLIENT};
+use crate::services::ProvisioningService;
+use crate::utils::nats::NatsConnection;
+
+/// Queue group shared by all replicas, so each message is taken by one of them
+const QUEUE_GROUP: &str = "auth-server-provisioning";
+
+/// Subscription ID on the consumer's connection
+const SUBSCRIPTION_ID: &str = "1";
+
+/// Wait before reconnecting after the connection failed
+const RECONNECT_DELAY_SECS: u64 = 5;
+
+/// Background worker consuming provisioning events from NATS
+///
+/// Only spawned when PROVISIONING_NATS_URL is set. Every replica subscribes
+/// in one queue group, so no leader lock is needed. A message is validated
+/// and queued for the provisioning worker like an HTTP submission; one that
+/// can't be queued (bad schema, unknown app, database down) is published to
+/// the dead-letter subject with the reason, since core NATS won't redeliver it.
+pub struct ProvisioningConsumer {
+    service: ProvisioningService,
+    app_repo: AppRepository,
+    url: String,
+    subject: String,
+    dead_letter_subject: String,
+}
+
+impl ProvisioningConsumer {
+    /// Create a new provisioning consumer
+    ///
+    /// # Arguments
+    /// * `pool` - Database connection pool
+    /// * `url` - NATS server URL
+    /// * `subject` - Subject inbound events are published to
+    /// * `dead_letter_subject` - Subject rejected events are published to
+    pub fn new(pool: MySqlPool, url: String, subject: String, dead_letter_subject: String) -> Self {
+        Self {
+            service: ProvisioningService::new(pool.clone()),
+            app_repo: AppRepository::new(pool),
+            url,
+            subject,
+            dead_letter_subject,
+        }
+    }
+
+    /// Start the provisioning consumer
+    ///
+    /// This method runs indefinitely until the task is cancelled.
+    pub async fn run(&mut self) {
+        tracing::info!("Provisioning consumer started, subscribing to {}", self.subject);
+
+        loop {
+            if let Err(e) = self.consume().await {
+                tracing::warn!("Provisioning consumer error, reconnecting: {}", e);
+            }
+            tokio::time::sleep(Duration::from_secs(RECONNECT_DELAY_SECS)).await;
+        }
+    }
+
+    /// Handle messages until the connection fails
+    async fn consume(&self) -> std::io::Result<()> {
+        let mut conn = NatsConnection::connect(&self.url, PROVISIONING_NATS_CLIENT).await?;
+        conn.subscribe(&self.subject, Some(QUEUE_GROUP), SUBSCRIPTION_ID).await?;
+
+        loop {
+            let message = conn.next_message().await?;
+            if let Err(error) = self.submit(&message.payload).await {
+                tracing::warn!("Provisioning message on {} dead-lettered: {}", message.subject, error);
+                let event = serde_json::from_slice(&message.payload)
+                    .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(&message.payload).into_owned()));
+                conn.publish(&self.dead_letter_subject, &dead_letter_message(event, &error)).await?;
+            }
+        }
+    }
+
+    /// Queue one message; returns why it couldn't be
+    async fn submit(&self, payload: &[u8]) -> Result<(), String> {
+        let event: BrokerProvisioningEvent =
+            serde_json::from_slice(payload).map_err(|e| format!("invalid provisioning message: {}", e))?;
+
+        let app = self.app_repo.find_by_id(event.app_id).await.map_err(|e| e.to_string())?;
+        if app.is_none() {
+            return Err(format!("unknown app: {}", event.app_id));
+        }
+
+        let (stored, created) = self
+            .service
+            .submit(event.app_id, None, &event.event_id, &event.event_type, &event.payload)
+            .await
+            .map_err(|e| e.to_string())?;
+        if created {
+            tracing::debug!("Provisioning event {} queued from the broker", stored.event_id);
+        }
+
+        Ok(())
+    }
+}
+
+/// Spawn the provisioning consumer as a background task
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `url` - NATS server URL
+/// * `subject` - Subject inbound events are published to
+/// * `dead_letter_subject` - Subject rejected events are published to
+///
+/// # Returns
+/// A JoinHandle that can be used to await or abort the consumer
+pub fn spawn_provisioning_consumer(
+    pool: MySqlPool,
+    url: String,
+    subject: String,
+    dead_letter_subject: String,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut consumer = ProvisioningConsumer::new(pool, url, subject, dead_letter_subject);
+        consumer.run().await;
+    })
+}
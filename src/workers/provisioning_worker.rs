@@ -0,0 +1,119 @@
+use sqlx::MySqlPool;
+use std::time::Duration;
+use tokio::time::interval;
+
+use crate::services::ProvisioningService;
+use crate::utils::nats::NatsTopic;
+use crate::workers::leader::LeaderLock;
+
+/// Leader lock name; only one instance runs this worker at a time
+pub const WORKER_NAME: &str = "provisioning_worker";
+
+/// Events handled per batch
+const BATCH_SIZE: i64 = 100;
+
+/// Background worker applying inbound provisioning events
+///
+/// On every tick it applies due pending events in arrival order, one batch
+/// at a time, until none are left. Failed events are retried with backoff
+/// and dead-lettered by the service once they run out of attempts.
+pub struct ProvisioningWorker {
+    pool: MySqlPool,
+    leader: LeaderLock,
+    interval_secs: u64,
+    dead_letter_topic: Option<NatsTopic>,
+}
+
+impl ProvisioningWorker {
+    /// Create a new provisioning worker
+    ///
+    /// # Arguments
+    /// * `pool` - Database connection pool
+    /// * `interval_secs` - How often to check for due events (in seconds)
+    /// * `instance_id` - This replica's identity, recorded when it takes leadership
+    /// * `dead_letter_topic` - Where dead-lettered events are also published, if anywhere
+    pub fn new(
+        pool: MySqlPool,
+        interval_secs: u64,
+        instance_id: String,
+        dead_letter_topic: Option<NatsTopic>,
+    ) -> Self {
+        Self {
+            leader: LeaderLock::new(pool.clone(), WORKER_NAME, instance_id),
+            pool,
+            interval_secs,
+            dead_letter_topic,
+        }
+    }
+
+    /// Start the provisioning worker
+    ///
+    /// This method runs indefinitely until the task is cancelled.
+    pub async fn run(&mut self) {
+        tracing::info!(
+            "Provisioning worker started, polling every {} seconds",
+            self.interval_secs
+        );
+
+        let mut ticker = interval(Duration::from_secs(self.interval_secs));
+
+        loop {
+            ticker.tick().await;
+
+            if !self.leader.ensure_leader().await {
+                continue;
+            }
+
+            if let Err(e) = self.process_due().await {
+                tracing::error!("Provisioning worker error: {}", e);
+            }
+        }
+    }
+
+    /// Process batches until no due events are left
+    async fn process_due(&self) -> Result<(), anyhow::Error> {
+        let service = ProvisioningService::new(self.pool.clone())
+            .with_dead_letter_topic(self.dead_letter_topic.clone());
+        let mut total = 0;
+
+        loop {
+            let processed = service
+                .process_due(BATCH_SIZE)
+                .await
+                .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+            total += processed;
+
+            if (processed as i64) < BATCH_SIZE {
+                break;
+            }
+        }
+
+        if total > 0 {
+            tracing::info!("Provisioning worker handled {} events", total);
+        }
+
+        Ok(())
+    }
+}
+
+/// Spawn the provisioning worker as a background task
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `interval_secs` - Polling interval in seconds (default: 5)
+/// * `instance_id` - This replica's identity
+/// * `dead_letter_topic` - Where dead-lettered events are also published, if anywhere
+///
+/// # Returns
+/// A JoinHandle that can be used to await or abort the worker
+pub fn spawn_provisioning_worker(
+    pool: MySqlPool,
+    interval_secs: u64,
+    instance_id: String,
+    dead_letter_topic: Option<NatsTopic>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut worker = ProvisioningWorker::new(pool, interval_secs, instance_id, dead_letter_topic);
+        worker.run().await;
+    })
+}
@@ -0,0 +1,130 @@
+use sqlx::MySqlPool;
+use std::time::Duration;
+use tokio::time::interval;
+
+use crate::services::RoleService;
+use crate::workers::leader::LeaderLock;
+
+/// Assignments handled per batch
+const BATCH_SIZE: i64 = 500;
+
+/// Leader lock name; only one instance runs this worker at a time
+pub const WORKER_NAME: &str = "role_expiry_worker";
+
+/// Background worker enforcing time-bounded role assignments
+///
+/// On every tick it sends `role.expiring` webhooks for assignments entering
+/// the notice window, then removes assignments whose expiry has passed and
+/// sends `role.expired` for each of them. Expired assignments are already
+/// ignored by token issuance, so the interval only affects cleanup latency.
+pub struct RoleExpiryWorker {
+    pool: MySqlPool,
+    leader: LeaderLock,
+    interval_secs: u64,
+    notice_secs: i64,
+}
+
+impl RoleExpiryWorker {
+    /// Create a new role expiry worker
+    ///
+    /// # Arguments
+    /// * `pool` - Database connection pool
+    /// * `interval_secs` - How often to scan for expiring assignments (in seconds)
+    /// * `notice_secs` - How long before expiry the `role.expiring` webhook is sent
+    /// * `instance_id` - This replica's identity, recorded when it takes leadership
+    pub fn new(pool: MySqlPool, interval_secs: u64, notice_secs: i64, instance_id: String) -> Self {
+        Self {
+            leader: LeaderLock::new(pool.clone(), WORKER_NAME, instance_id),
+            pool,
+            interval_secs,
+            notice_secs,
+        }
+    }
+
+    /// Start the role expiry worker
+    ///
+    /// This method runs indefinitely until the task is cancelled.
+    pub async fn run(&mut self) {
+        tracing::info!(
+            "Role expiry worker started, scanning every {} seconds",
+            self.interval_secs
+        );
+
+        let mut ticker = interval(Duration::from_secs(self.interval_secs));
+
+        loop {
+            ticker.tick().await;
+
+            if !self.leader.ensure_leader().await {
+                continue;
+            }
+
+            if let Err(e) = self.process().await {
+                tracing::error!("Role expiry worker error: {}", e);
+            }
+        }
+    }
+
+    /// Notify and remove in batches until nothing is left
+    async fn process(&self) -> Result<(), anyhow::Error> {
+        let service = RoleService::new(self.pool.clone());
+
+        let mut notified = 0;
+        loop {
+            let processed = service
+                .notify_expiring_roles(self.notice_secs, BATCH_SIZE)
+                .await
+                .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+            notified += processed;
+
+            if (processed as i64) < BATCH_SIZE {
+                break;
+            }
+        }
+
+        let mut removed = 0;
+        loop {
+            let processed = service
+                .remove_expired_roles(BATCH_SIZE)
+                .await
+                .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+            removed += processed;
+
+            if (processed as i64) < BATCH_SIZE {
+                break;
+            }
+        }
+
+        if notified > 0 || removed > 0 {
+            tracing::info!(
+                "Role expiry worker notified {} expiring and removed {} expired assignments",
+                notified,
+                removed
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Spawn the role expiry worker as a background task
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `interval_secs` - Scan interval in seconds (default: 60)
+/// * `notice_secs` - Near-expiry notice window in seconds (default: 86400)
+/// * `instance_id` - This replica's identity
+///
+/// # Returns
+/// A JoinHandle that can be used to await or abort the worker
+pub fn spawn_role_expiry_worker(
+    pool: MySqlPool,
+    interval_secs: u64,
+    notice_secs: i64,
+    instance_id: String,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut worker = RoleExpiryWorker::new(pool, interval_secs, notice_secs, instance_id);
+        worker.run().await;
+    })
+}
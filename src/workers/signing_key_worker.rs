@@ -0,0 +1,72 @@
+use std::time::Duration;
+use tokio::time::interval;
+
+use crate::services::SigningKeyService;
+
+/// Background worker reloading token signing keys
+///
+/// A key ceremony step (stage, promote) reloads the keys on the instance
+/// that handled it; the others pick the change up on their next tick. It
+/// also promotes a generated key once its staging delay has passed. Every
+/// replica signs and verifies with its own copy of the keys, so every
+/// replica runs this worker (no leader lock).
+pub struct SigningKeyWorker {
+    service: SigningKeyService,
+    interval_secs: u64,
+}
+
+impl SigningKeyWorker {
+    /// Create a new signing key worker
+    ///
+    /// # Arguments
+    /// * `service` - Signing key service sharing the application's JWT manager
+    /// * `interval_secs` - How often to reload the keys (in seconds)
+    pub fn new(service: SigningKeyService, interval_secs: u64) -> Self {
+        Self { service, interval_secs }
+    }
+
+    /// Start the signing key worker
+    ///
+    /// This method runs indefinitely until the task is cancelled.
+    pub async fn run(&mut self) {
+        tracing::info!(
+            "Signing key worker started, reloading keys every {} seconds",
+            self.interval_secs
+        );
+
+        let mut ticker = interval(Duration::from_secs(self.interval_secs));
+
+        loop {
+            ticker.tick().await;
+
+            // Promotion reloads the keys itself
+            match self.service.promote_due().await {
+                Ok(Some(key)) => {
+                    tracing::warn!("Generated signing key {} promoted to active", key.kid);
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => tracing::error!("Signing key worker error: {:?}", e),
+            }
+
+            if let Err(e) = self.service.reload().await {
+                tracing::error!("Signing key worker error: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Spawn the signing key worker as a background task
+///
+/// # Arguments
+/// * `service` - Signing key service sharing the application's JWT manager
+/// * `interval_secs` - Reload interval in seconds (default: 60)
+///
+/// # Returns
+/// A JoinHandle that can be used to await or abort the worker
+pub fn spawn_signing_key_worker(service: SigningKeyService, interval_secs: u64) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut worker = SigningKeyWorker::new(service, interval_secs);
+        worker.run().await;
+    })
+}
@@ -1,8 +1,16 @@
 use sqlx::MySqlPool;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::interval;
 
-use crate::services::WebhookService;
+use crate::repositories::WebhookRepository;
+use crate::services::{WebhookDispatchLimits, WebhookDispatcher};
+use crate::workers::leader::LeaderLock;
+
+/// Leader lock name; only one instance runs this worker at a time
+pub const WORKER_NAME: &str = "webhook_worker";
+
+/// How often expired outbox events are pruned
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
 
 /// Background worker for processing pending webhook deliveries
 /// 
@@ -12,11 +20,17 @@ use crate::services::WebhookService;
 /// Features:
 /// - Configurable polling interval
 /// - Automatic retry with exponential backoff (handled by WebhookService)
+/// - Concurrency caps, per-app rate limits and circuit breaking (handled by WebhookDispatcher)
+/// - Hourly pruning of outbox events past the replay retention
 /// - Graceful shutdown support
 /// - Error logging without crashing
 pub struct WebhookWorker {
-    pool: MySqlPool,
+    dispatcher: WebhookDispatcher,
+    repo: WebhookRepository,
+    leader: LeaderLock,
     interval_secs: u64,
+    event_retention_days: i64,
+    last_prune: Option<Instant>,
 }
 
 impl WebhookWorker {
@@ -25,15 +39,31 @@ impl WebhookWorker {
     /// # Arguments
     /// * `pool` - Database connection pool
     /// * `interval_secs` - How often to check for pending deliveries (in seconds)
-    pub fn new(pool: MySqlPool, interval_secs: u64) -> Self {
-        Self { pool, interval_secs }
+    /// * `instance_id` - This replica's identity, recorded when it takes leadership
+    /// * `limits` - Delivery concurrency, rate and circuit breaker limits
+    /// * `event_retention_days` - Days outbox events are kept for replay (0 = forever)
+    pub fn new(
+        pool: MySqlPool,
+        interval_secs: u64,
+        instance_id: String,
+        limits: WebhookDispatchLimits,
+        event_retention_days: i64,
+    ) -> Self {
+        Self {
+            leader: LeaderLock::new(pool.clone(), WORKER_NAME, instance_id),
+            repo: WebhookRepository::new(pool.clone()),
+            dispatcher: WebhookDispatcher::new(pool, limits),
+            interval_secs,
+            event_retention_days,
+            last_prune: None,
+        }
     }
 
     /// Start the webhook worker
     /// 
     /// This method runs indefinitely until the task is cancelled.
     /// It processes pending webhook deliveries at the configured interval.
-    pub async fn run(&self) {
+    pub async fn run(&mut self) {
         tracing::info!(
             "Webhook worker started, polling every {} seconds",
             self.interval_secs
@@ -43,18 +73,39 @@ impl WebhookWorker {
 
         loop {
             ticker.tick().await;
+
+            if !self.leader.ensure_leader().await {
+                continue;
+            }
             
             if let Err(e) = self.process_batch().await {
                 tracing::error!("Webhook worker error: {}", e);
             }
+
+            self.prune_events().await;
+        }
+    }
+
+    /// Delete outbox events past the retention, at most once per `PRUNE_INTERVAL`
+    async fn prune_events(&mut self) {
+        if self.event_retention_days <= 0
+            || self.last_prune.is_some_and(|last| last.elapsed() < PRUNE_INTERVAL)
+        {
+            return;
+        }
+        self.last_prune = Some(Instant::now());
+
+        let before = chrono::Utc::now() - chrono::Duration::days(self.event_retention_days);
+        match self.repo.prune_events(before).await {
+            Ok(0) => {}
+            Ok(pruned) => tracing::info!("Webhook worker pruned {} expired events", pruned),
+            Err(e) => tracing::error!("Failed to prune webhook events: {:?}", e),
         }
     }
 
     /// Process a batch of pending webhook deliveries
-    async fn process_batch(&self) -> Result<(), anyhow::Error> {
-        let service = WebhookService::new(self.pool.clone());
-        
-        match service.process_pending_deliveries().await {
+    async fn process_batch(&mut self) -> Result<(), anyhow::Error> {
+        match self.dispatcher.dispatch_pending().await {
             Ok(processed) => {
                 if processed > 0 {
                     tracing::info!("Webhook worker processed {} deliveries", processed);
@@ -74,12 +125,21 @@ impl WebhookWorker {
 /// # Arguments
 /// * `pool` - Database connection pool
 /// * `interval_secs` - Polling interval in seconds (default: 10)
+/// * `instance_id` - This replica's identity
+/// * `limits` - Delivery concurrency, rate and circuit breaker limits
+/// * `event_retention_days` - Days outbox events are kept for replay (0 = forever)
 /// 
 /// # Returns
 /// A JoinHandle that can be used to await or abort the worker
-pub fn spawn_webhook_worker(pool: MySqlPool, interval_secs: u64) -> tokio::task::JoinHandle<()> {
+pub fn spawn_webhook_worker(
+    pool: MySqlPool,
+    interval_secs: u64,
+    instance_id: String,
+    limits: WebhookDispatchLimits,
+    event_retention_days: i64,
+) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
-        let worker = WebhookWorker::new(pool, interval_secs);
+        let mut worker = WebhookWorker::new(pool, interval_secs, instance_id, limits, event_retention_days);
         worker.run().await;
     })
 }
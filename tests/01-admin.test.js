@@ -1,4 +1,4 @@
-const { api, getAdminToken, createTestUser, generateEmail, generatePassword, registerUser } = require('./helpers');
+const { api, getAdminToken, createTestUser, generateEmail, generatePassword, registerUser, login } = require('./helpers');
 
 describe('Admin API', () => {
   let adminToken;
@@ -246,6 +246,583 @@ describe('Admin API', () => {
     });
   });
 
+  describe('GET /admin/users/duplicates', () => {
+    it('should return duplicate account groups', async () => {
+      const res = await api()
+        .get('/admin/users/duplicates')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body).toHaveProperty('generated_at');
+      expect(Array.isArray(res.body.groups)).toBe(true);
+      res.body.groups.forEach(group => {
+        expect(['email', 'phone', 'webauthn']).toContain(group.match_type);
+        expect(group.users.length).toBeGreaterThan(1);
+      });
+    });
+
+    it('should filter by match type', async () => {
+      const res = await api()
+        .get('/admin/users/duplicates?match_type=phone&limit=10')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(200);
+      res.body.groups.forEach(group => {
+        expect(group.match_type).toBe('phone');
+      });
+    });
+
+    it('should reject an unknown match type', async () => {
+      const res = await api()
+        .get('/admin/users/duplicates?match_type=name')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(400);
+    });
+
+    it('should reject non-admin users', async () => {
+      const user = await createTestUser();
+
+      const res = await api()
+        .get('/admin/users/duplicates')
+        .set('Authorization', `Bearer ${user.token}`);
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('GET /admin/users/dormant', () => {
+    it('should list accounts inactive for at least the given days', async () => {
+      const res = await api()
+        .get('/admin/users/dormant?days=30&limit=10')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.limit).toBe(10);
+      expect(res.body.sort).toEqual({ by: 'last_activity_at', order: 'asc' });
+      expect(Array.isArray(res.body.data)).toBe(true);
+      res.body.data.forEach(account => {
+        expect(account.days_inactive).toBeGreaterThanOrEqual(30);
+        expect(account).toHaveProperty('dormant_at');
+        expect(account).not.toHaveProperty('password_hash');
+      });
+      expect(res.body.data.map(a => a.user_id)).not.toContain(testUserId);
+    });
+
+    it('should filter by flagged state', async () => {
+      const res = await api()
+        .get('/admin/users/dormant?days=1&flagged=true')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(200);
+      res.body.data.forEach(account => {
+        expect(account.dormant_at).not.toBeNull();
+      });
+    });
+
+    it('should reject a non-positive number of days', async () => {
+      const res = await api()
+        .get('/admin/users/dormant?days=0')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('validation_error');
+    });
+
+    it('should reject non-admin users', async () => {
+      const user = await createTestUser();
+
+      const res = await api()
+        .get('/admin/users/dormant?days=30')
+        .set('Authorization', `Bearer ${user.token}`);
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('GET /admin/debug/*', () => {
+    it('should return the running config with secrets redacted', async () => {
+      const res = await api()
+        .get('/admin/debug/config')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body).toHaveProperty('version');
+      expect(res.body.config.jwt_private_key).toBe('[redacted]');
+      // Only the placeholder may appear as the database password
+      expect(res.body.config.database_url).not.toMatch(/:(?!redacted@)[^:@/]+@/);
+      expect(res.body.config).toHaveProperty('access_token_expiry_secs');
+    });
+
+    it('should list mounted routes with their auth requirements', async () => {
+      const res = await api()
+        .get('/admin/debug/routes')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.global_layers).toContain('csrf');
+      expect(res.body.routes).toContainEqual({ method: 'GET', path: '/admin/debug/routes', auth: 'system_admin' });
+      expect(res.body.routes).toContainEqual({ method: 'POST', path: '/auth/login', auth: 'public' });
+    });
+
+    it('should report which instance leads each background worker', async () => {
+      const res = await api()
+        .get('/admin/debug/workers')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(200);
+      expect(typeof res.body.instance_id).toBe('string');
+      // Workers take leadership on their first tick, right after startup
+      const webhook = res.body.workers.find((w) => w.worker === 'webhook_worker');
+      expect(webhook).toBeDefined();
+      expect(webhook.active).toBe(true);
+      expect(webhook.is_this_instance).toBe(true);
+    });
+
+    it('should reject non-admin users', async () => {
+      const user = await createTestUser();
+
+      for (const path of ['/admin/debug/config', '/admin/debug/routes', '/admin/debug/workers']) {
+        const res = await api()
+          .get(path)
+          .set('Authorization', `Bearer ${user.token}`);
+
+        expect(res.status).toBe(403);
+      }
+    });
+  });
+
+  describe('POST /admin/access-simulator', () => {
+    let appCode;
+
+    beforeAll(async () => {
+      const owner = await createTestUser();
+      appCode = `sim-${Date.now()}`;
+      await api()
+        .post('/apps')
+        .set('Authorization', `Bearer ${owner.token}`)
+        .send({ code: appCode, name: 'Simulator App' });
+    });
+
+    it('should allow an account-only sign-in and explain each check', async () => {
+      const res = await api()
+        .post('/admin/access-simulator')
+        .set('Authorization', `Bearer ${adminToken}`)
+        .send({ user_id: testUserId });
+
+      expect(res.status).toBe(200);
+      expect(res.body.allowed).toBe(true);
+      expect(res.body.error).toBeNull();
+      expect(res.body.acr).toBe('pwd');
+      expect(res.body.trace.map((c) => c.check)).toEqual(
+        expect.arrayContaining(['user', 'account_lock', 'user_active', 'app'])
+      );
+    });
+
+    it('should deny a user without a role in the app', async () => {
+      const res = await api()
+        .post('/admin/access-simulator')
+        .set('Authorization', `Bearer ${adminToken}`)
+        .send({ user_id: testUserId, app: appCode, permission: 'posts.write', ip: '203.0.113.7' });
+
+      expect(res.status).toBe(200);
+      expect(res.body.allowed).toBe(false);
+      expect(res.body.error).toBe('not_app_member');
+      const permission = res.body.trace.find((c) => c.check === 'permission');
+      expect(permission.outcome).toBe('fail');
+      expect(permission.error).toBe('permission_not_granted');
+      expect(res.body.trace.find((c) => c.check === 'ip_rules').outcome).toBe('pass');
+    });
+
+    it('should reject a request without a user or with an unknown acr', async () => {
+      let res = await api()
+        .post('/admin/access-simulator')
+        .set('Authorization', `Bearer ${adminToken}`)
+        .send({ app: appCode });
+      expect(res.status).toBe(400);
+
+      res = await api()
+        .post('/admin/access-simulator')
+        .set('Authorization', `Bearer ${adminToken}`)
+        .send({ user_id: testUserId, acr: 'loa3' });
+      expect(res.status).toBe(400);
+    });
+
+    it('should reject non-admin users', async () => {
+      const user = await createTestUser();
+
+      const res = await api()
+        .post('/admin/access-simulator')
+        .set('Authorization', `Bearer ${user.token}`)
+        .send({ user_id: testUserId });
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('GET /admin/instance', () => {
+    it('should report version, features and coarse counts', async () => {
+      const res = await api()
+        .get('/admin/instance')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body).toHaveProperty('version');
+      expect(res.body.instance).toMatch(/^[0-9a-f]{16}$/);
+      expect(typeof res.body.features.field_encryption).toBe('boolean');
+      // At least the admin exists; counts are rounded down to a power of ten
+      expect(res.body.counts.users).toMatch(/^1(0*)\+$/);
+      expect(res.body.heartbeat_enabled).toBe(false);
+    });
+
+    it('should reject non-admin users', async () => {
+      const user = await createTestUser();
+
+      const res = await api()
+        .get('/admin/instance')
+        .set('Authorization', `Bearer ${user.token}`);
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('/admin/chaos', () => {
+    it('should only exist in builds with the chaos feature', async () => {
+      const instance = await api()
+        .get('/admin/instance')
+        .set('Authorization', `Bearer ${adminToken}`);
+      const res = await api()
+        .get('/admin/chaos')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      if (instance.body.features.chaos) {
+        expect(res.status).toBe(200);
+        expect(Array.isArray(res.body.faults)).toBe(true);
+      } else {
+        expect(res.status).toBe(404);
+      }
+    });
+
+    it('should reject faults without a bounded duration', async () => {
+      const instance = await api()
+        .get('/admin/instance')
+        .set('Authorization', `Bearer ${adminToken}`);
+      if (!instance.body.features.chaos) return;
+
+      const res = await api()
+        .put('/admin/chaos/email_error')
+        .set('Authorization', `Bearer ${adminToken}`)
+        .send({ duration_seconds: 7200 });
+
+      expect(res.status).toBe(400);
+    });
+  });
+
+  describe('GET /admin/tokens/:jti/lineage', () => {
+    const jtiOf = (token) =>
+      JSON.parse(Buffer.from(token.split('.')[1], 'base64url').toString()).jti;
+
+    it('should trace a refresh chain back to the login', async () => {
+      const email = generateEmail();
+      const password = generatePassword();
+      await registerUser(email, password);
+      const loginRes = await login(email, password);
+      const refreshRes = await api()
+        .post('/auth/refresh')
+        .send({ refresh_token: loginRes.body.refresh_token });
+      expect(refreshRes.status).toBe(200);
+
+      const loginJti = jtiOf(loginRes.body.refresh_token);
+      const res = await api()
+        .get(`/admin/tokens/${jtiOf(refreshRes.body.refresh_token)}/lineage`)
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.family_id).toBe(loginJti);
+      const refreshTokens = res.body.tokens.filter((t) => t.token_type === 'refresh');
+      expect(refreshTokens).toHaveLength(2);
+      expect(refreshTokens[0].jti).toBe(loginJti);
+      expect(refreshTokens[0].revoked_reason).toBe('rotated');
+      expect(refreshTokens[1].parent_jti).toBe(loginJti);
+      expect(refreshTokens[1].revoked_at).toBeNull();
+    });
+
+    it('should return 404 for an unknown jti', async () => {
+      const res = await api()
+        .get('/admin/tokens/00000000-0000-0000-0000-000000000000/lineage')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(404);
+    });
+
+    it('should reject non-admin users', async () => {
+      const user = await createTestUser();
+
+      const res = await api()
+        .get(`/admin/tokens/${jtiOf(user.token)}/lineage`)
+        .set('Authorization', `Bearer ${user.token}`);
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('PUT /admin/users/:user_id/legal-hold', () => {
+    let heldUserId;
+
+    beforeAll(async () => {
+      const res = await registerUser(generateEmail(), generatePassword());
+      heldUserId = res.body.id;
+    });
+
+    it('should require a reason to set the hold', async () => {
+      const res = await api()
+        .put(`/admin/users/${heldUserId}/legal-hold`)
+        .set('Authorization', `Bearer ${adminToken}`)
+        .send({ legal_hold: true });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('validation_error');
+    });
+
+    it('should block deletion while the hold is set', async () => {
+      const held = await api()
+        .put(`/admin/users/${heldUserId}/legal-hold`)
+        .set('Authorization', `Bearer ${adminToken}`)
+        .send({ legal_hold: true, reason: 'CASE-42' });
+      expect(held.status).toBe(200);
+      expect(held.body.legal_hold).toBe(true);
+      expect(held.body.legal_hold_reason).toBe('CASE-42');
+
+      const res = await api()
+        .delete(`/admin/users/${heldUserId}`)
+        .set('Authorization', `Bearer ${adminToken}`);
+      expect(res.status).toBe(409);
+      expect(res.body.error).toBe('legal_hold');
+
+      const logs = await api()
+        .get('/admin/audit-logs?action=legal_hold_blocked')
+        .set('Authorization', `Bearer ${adminToken}`);
+      expect(logs.body.logs.some((log) => log.resource_id === heldUserId)).toBe(true);
+    });
+
+    it('should allow deletion once released', async () => {
+      const released = await api()
+        .put(`/admin/users/${heldUserId}/legal-hold`)
+        .set('Authorization', `Bearer ${adminToken}`)
+        .send({ legal_hold: false });
+      expect(released.status).toBe(200);
+      expect(released.body.legal_hold_reason).toBeNull();
+
+      const res = await api()
+        .delete(`/admin/users/${heldUserId}`)
+        .set('Authorization', `Bearer ${adminToken}`);
+      expect(res.status).toBe(204);
+    });
+
+    it('should reject non-admin users', async () => {
+      const user = await createTestUser();
+      const res = await api()
+        .put(`/admin/users/${testUserId}/legal-hold`)
+        .set('Authorization', `Bearer ${user.token}`)
+        .send({ legal_hold: true, reason: 'CASE-42' });
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('POST /admin/notifications/broadcast', () => {
+    let appId;
+    let memberEmail;
+    let memberId;
+
+    beforeAll(async () => {
+      const owner = await createTestUser();
+      const app = await api()
+        .post('/apps')
+        .set('Authorization', `Bearer ${owner.token}`)
+        .send({ code: `broadcast-app-${Date.now()}`, name: 'Broadcast App' });
+      appId = app.body.id;
+
+      const member = await createTestUser();
+      memberEmail = member.email;
+      await api()
+        .post(`/apps/${appId}/register`)
+        .set('Authorization', `Bearer ${member.token}`);
+      const me = await api()
+        .get('/users/me')
+        .set('Authorization', `Bearer ${member.token}`);
+      memberId = me.body.id;
+    });
+
+    const broadcast = (body) =>
+      api()
+        .post('/admin/notifications/broadcast')
+        .set('Authorization', `Bearer ${adminToken}`)
+        .send(body);
+
+    it('should preview the segment without sending', async () => {
+      const res = await broadcast({
+        subject: 'News from {{app_name}}',
+        body: 'Hi {{name}},\n<b>welcome</b>',
+        segment: { app_id: appId },
+        preview: true,
+      });
+
+      expect(res.status).toBe(200);
+      expect(res.body.recipient_count).toBe(1);
+      expect(res.body.suppressed_count).toBe(0);
+      expect(res.body.sample.to).toBe(memberEmail);
+      expect(res.body.sample.html).toContain(`Hi ${memberEmail.split('@')[0]},<br>`);
+      expect(res.body.sample.html).toContain('&lt;b&gt;welcome&lt;/b&gt;');
+    });
+
+    it('should leave suppressed users out', async () => {
+      const suppressed = await api()
+        .put(`/admin/notifications/suppressions/${memberId}`)
+        .set('Authorization', `Bearer ${adminToken}`)
+        .send({ reason: 'asked to opt out' });
+      expect(suppressed.status).toBe(204);
+
+      const res = await broadcast({
+        subject: 'News',
+        body: 'Hello',
+        segment: { app_id: appId },
+        preview: true,
+      });
+      expect(res.body.recipient_count).toBe(0);
+      expect(res.body.suppressed_count).toBe(1);
+      expect(res.body.sample).toBeNull();
+
+      const lifted = await api()
+        .delete(`/admin/notifications/suppressions/${memberId}`)
+        .set('Authorization', `Bearer ${adminToken}`);
+      expect(lifted.status).toBe(204);
+    });
+
+    it('should reject unknown template variables', async () => {
+      const res = await broadcast({ subject: 'News', body: 'Your token: {{token}}', preview: true });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('validation_error');
+    });
+
+    it('should schedule a broadcast and allow cancelling it', async () => {
+      const scheduledAt = new Date(Date.now() + 24 * 3600 * 1000).toISOString();
+      const res = await broadcast({
+        subject: 'Maintenance window',
+        body: 'Hi {{name}}, we will be down tomorrow.',
+        segment: { app_id: appId, email_verified: false },
+        scheduled_at: scheduledAt,
+      });
+      expect(res.status).toBe(202);
+      expect(res.body.status).toBe('scheduled');
+
+      const cancelled = await api()
+        .post(`/admin/notifications/broadcasts/${res.body.id}/cancel`)
+        .set('Authorization', `Bearer ${adminToken}`);
+      expect(cancelled.status).toBe(200);
+      expect(cancelled.body.status).toBe('cancelled');
+
+      const again = await api()
+        .post(`/admin/notifications/broadcasts/${res.body.id}/cancel`)
+        .set('Authorization', `Bearer ${adminToken}`);
+      expect(again.status).toBe(400);
+    });
+
+    it('should reject non-admin users', async () => {
+      const user = await createTestUser();
+      const res = await api()
+        .post('/admin/notifications/broadcast')
+        .set('Authorization', `Bearer ${user.token}`)
+        .send({ subject: 'News', body: 'Hello', preview: true });
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('/admin/notifications/bounces', () => {
+    it('should list bounced addresses', async () => {
+      const res = await api()
+        .get('/admin/notifications/bounces')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(200);
+      expect(Array.isArray(res.body.bounces)).toBe(true);
+    });
+
+    it('should return 404 when clearing an address that did not bounce', async () => {
+      const res = await api()
+        .delete(`/admin/notifications/bounces/${encodeURIComponent(generateEmail())}`)
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(404);
+    });
+
+    it('should reject webhook calls without the token', async () => {
+      const res = await api()
+        .post('/webhooks/email/generic')
+        .send({ email: generateEmail(), type: 'hard_bounce' });
+
+      // 404 when the webhook is disabled, 401 otherwise
+      expect([401, 404]).toContain(res.status);
+    });
+
+    it('should reject non-admin users', async () => {
+      const user = await createTestUser();
+      const res = await api()
+        .get('/admin/notifications/bounces')
+        .set('Authorization', `Bearer ${user.token}`);
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  // Needs a server started with the same EMAIL_WEBHOOK_SECRET
+  const describeEmailWebhook = process.env.EMAIL_WEBHOOK_SECRET ? describe : describe.skip;
+
+  describeEmailWebhook('POST /webhooks/email/:provider', () => {
+    const token = process.env.EMAIL_WEBHOOK_SECRET;
+
+    it('should flag the address until an admin clears it', async () => {
+      const user = await createTestUser();
+
+      const hook = await api()
+        .post(`/webhooks/email/sendgrid?token=${encodeURIComponent(token)}`)
+        .send([
+          { email: user.email, event: 'bounce', type: 'bounce', reason: '550 5.1.1 unknown user' },
+          { email: generateEmail(), event: 'delivered' },
+        ]);
+      expect(hook.status).toBe(200);
+      expect(hook.body.recorded).toBe(1);
+
+      const flagged = await api().get('/users/me').set('Authorization', `Bearer ${user.token}`);
+      expect(flagged.body.email_undeliverable).toBe(true);
+
+      const list = await api()
+        .get('/admin/notifications/bounces')
+        .set('Authorization', `Bearer ${adminToken}`);
+      const entry = list.body.bounces.find((b) => b.email === user.email.toLowerCase());
+      expect(entry.kind).toBe('hard_bounce');
+      expect(entry.provider).toBe('sendgrid');
+
+      const cleared = await api()
+        .delete(`/admin/notifications/bounces/${encodeURIComponent(user.email)}`)
+        .set('Authorization', `Bearer ${adminToken}`);
+      expect(cleared.status).toBe(204);
+
+      const unflagged = await api().get('/users/me').set('Authorization', `Bearer ${user.token}`);
+      expect(unflagged.body.email_undeliverable).toBe(false);
+    });
+
+    it('should reject an unknown provider', async () => {
+      const res = await api()
+        .post(`/webhooks/email/postmark?token=${encodeURIComponent(token)}`)
+        .send({});
+
+      expect(res.status).toBe(400);
+    });
+  });
+
   describe('DELETE /admin/users/:user_id', () => {
     let deleteUserId;
 
@@ -270,5 +847,145 @@ describe('Admin API', () => {
 
       expect(userRes.status).toBe(404);
     });
+
+    it('should reject an unknown mode', async () => {
+      const res = await api()
+        .delete(`/admin/users/${testUserId}?mode=archive`)
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(400);
+    });
+
+    it('should anonymize the user and keep the row', async () => {
+      const email = generateEmail();
+      const password = generatePassword();
+      const reg = await registerUser(email, password);
+
+      const res = await api()
+        .delete(`/admin/users/${reg.body.id}?mode=anonymize`)
+        .set('Authorization', `Bearer ${adminToken}`);
+      expect(res.status).toBe(204);
+
+      const userRes = await api()
+        .get(`/admin/users/${reg.body.id}`)
+        .set('Authorization', `Bearer ${adminToken}`);
+      expect(userRes.status).toBe(200);
+      expect(userRes.body.email).toMatch(/^[0-9a-f]{64}@anonymized\.invalid$/);
+      expect(userRes.body.name).toBeNull();
+      expect(userRes.body.is_active).toBe(false);
+
+      const loginRes = await login(email, password);
+      expect(loginRes.status).toBe(401);
+
+      const logs = await api()
+        .get('/admin/audit-logs?action=user_anonymized')
+        .set('Authorization', `Bearer ${adminToken}`);
+      expect(logs.body.logs.some((log) => log.resource_id === reg.body.id)).toBe(true);
+    });
+
+    it('should anonymize members of an app with the anonymize policy', async () => {
+      const owner = await createTestUser();
+      const app = await api()
+        .post('/apps')
+        .set('Authorization', `Bearer ${owner.token}`)
+        .send({ code: `anon-app-${Date.now()}`, name: 'Anonymizing App' });
+      await api()
+        .put(`/apps/${app.body.id}/deletion-policy`)
+        .set('Authorization', `Bearer ${owner.token}`)
+        .send({ policy: 'anonymize' });
+
+      const member = await createTestUser();
+      await api()
+        .post(`/apps/${app.body.id}/register`)
+        .set('Authorization', `Bearer ${member.token}`);
+      const me = await api()
+        .get('/users/me')
+        .set('Authorization', `Bearer ${member.token}`);
+
+      const res = await api()
+        .delete(`/admin/users/${me.body.id}?mode=delete`)
+        .set('Authorization', `Bearer ${adminToken}`);
+      expect(res.status).toBe(204);
+
+      const userRes = await api()
+        .get(`/admin/users/${me.body.id}`)
+        .set('Authorization', `Bearer ${adminToken}`);
+      expect(userRes.status).toBe(200);
+      expect(userRes.body.email).toMatch(/@anonymized\.invalid$/);
+    });
+  });
+
+  describe('Admin approvals', () => {
+    it('should list approval requests', async () => {
+      const res = await api()
+        .get('/admin/approvals?status=pending')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(200);
+      expect(Array.isArray(res.body.approvals)).toBe(true);
+    });
+
+    it('should reject an unknown status filter', async () => {
+      const res = await api()
+        .get('/admin/approvals?status=bogus')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(400);
+    });
+
+    it('should return 404 for an unknown approval', async () => {
+      const res = await api()
+        .post('/admin/approvals/00000000-0000-0000-0000-000000000000/approve')
+        .set('Authorization', `Bearer ${adminToken}`);
+
+      expect(res.status).toBe(404);
+    });
+
+    it('should require system admin', async () => {
+      const user = await createTestUser();
+      const res = await api()
+        .get('/admin/approvals')
+        .set('Authorization', `Bearer ${user.token}`);
+
+      expect(res.status).toBe(403);
+    });
+
+    // Needs the server started with ADMIN_APPROVAL_ACTIONS including delete_user
+    (process.env.ADMIN_APPROVAL_ACTIONS ? describe : describe.skip)('with delete_user held for approval', () => {
+      it('should hold the deletion and refuse self-approval', async () => {
+        const target = await createTestUser();
+        const me = await api()
+          .get('/users/me')
+          .set('Authorization', `Bearer ${target.token}`);
+
+        const res = await api()
+          .delete(`/admin/users/${me.body.id}`)
+          .set('Authorization', `Bearer ${adminToken}`);
+        expect(res.status).toBe(202);
+        expect(res.body.status).toBe('pending');
+        expect(res.body.action).toBe('delete_user');
+
+        const again = await api()
+          .delete(`/admin/users/${me.body.id}`)
+          .set('Authorization', `Bearer ${adminToken}`);
+        expect(again.body.id).toBe(res.body.id);
+
+        const approve = await api()
+          .post(`/admin/approvals/${res.body.id}/approve`)
+          .set('Authorization', `Bearer ${adminToken}`);
+        expect(approve.status).toBe(403);
+
+        const reject = await api()
+          .post(`/admin/approvals/${res.body.id}/reject`)
+          .set('Authorization', `Bearer ${adminToken}`);
+        expect(reject.status).toBe(200);
+        expect(reject.body.status).toBe('rejected');
+
+        const userRes = await api()
+          .get(`/admin/users/${me.body.id}`)
+          .set('Authorization', `Bearer ${adminToken}`);
+        expect(userRes.status).toBe(200);
+      });
+    });
   });
 });
@@ -43,6 +43,37 @@ describe('Security API', () => {
     });
   });
 
+  describe('POST /account/revoke-all', () => {
+    it('should sign the user out of every session and token', async () => {
+      const email = generateEmail();
+      const password = generatePassword();
+      await registerUser(email, password);
+      const first = (await login(email, password)).body;
+      const second = (await login(email, password)).body;
+
+      const res = await api()
+        .post('/account/revoke-all')
+        .set('Authorization', `Bearer ${first.access_token}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.sessions_revoked).toBeGreaterThanOrEqual(2);
+      expect(res.body).toHaveProperty('oauth_tokens_revoked');
+      expect(res.body).toHaveProperty('devices_revoked');
+      expect(res.body).toHaveProperty('trusted_devices_revoked');
+
+      // Access tokens of other sessions stop working too, not just the caller's
+      const me = await api()
+        .get('/users/me')
+        .set('Authorization', `Bearer ${second.access_token}`);
+      expect(me.status).toBe(401);
+
+      const refresh = await api()
+        .post('/auth/refresh')
+        .send({ refresh_token: second.refresh_token });
+      expect(refresh.status).toBe(401);
+    });
+  });
+
   describe('GET /auth/sessions', () => {
     it('should list active sessions', async () => {
       const res = await api()
@@ -55,6 +86,138 @@ describe('Security API', () => {
     });
   });
 
+  describe('Current session', () => {
+    const signInTwice = async () => {
+      const email = generateEmail();
+      const password = generatePassword();
+      await registerUser(email, password);
+      const first = (await login(email, password)).body;
+      const second = (await login(email, password)).body;
+      return { first, second };
+    };
+
+    it('should mark the session of the token as current', async () => {
+      const { first } = await signInTwice();
+
+      const res = await api()
+        .get('/auth/sessions')
+        .set('Authorization', `Bearer ${first.access_token}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.sessions).toHaveLength(2);
+      expect(res.body.sessions.filter((s) => s.is_current)).toHaveLength(1);
+    });
+
+    it('should keep the current session when revoking the others', async () => {
+      const { first, second } = await signInTwice();
+
+      const res = await api()
+        .delete('/auth/sessions')
+        .set('Authorization', `Bearer ${first.access_token}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.revoked_count).toBe(1);
+
+      const kept = await api().post('/auth/refresh').send({ refresh_token: first.refresh_token });
+      expect(kept.status).toBe(200);
+      const revoked = await api().post('/auth/refresh').send({ refresh_token: second.refresh_token });
+      expect(revoked.status).toBe(401);
+    });
+
+    it('should end only its own session on logout', async () => {
+      const { first, second } = await signInTwice();
+
+      const res = await api()
+        .post('/auth/logout')
+        .set('Authorization', `Bearer ${first.access_token}`)
+        .send({ all_sessions: false });
+
+      expect(res.status).toBe(200);
+      expect(res.body.sessions_revoked).toBe(1);
+
+      const ended = await api().post('/auth/refresh').send({ refresh_token: first.refresh_token });
+      expect(ended.status).toBe(401);
+      const other = await api().post('/auth/refresh').send({ refresh_token: second.refresh_token });
+      expect(other.status).toBe(200);
+    });
+  });
+
+  describe('Refresh token reuse', () => {
+    it('should revoke the session when a rotated refresh token is replayed', async () => {
+      const email = generateEmail();
+      const password = generatePassword();
+      await registerUser(email, password);
+      const tokens = (await login(email, password)).body;
+
+      const rotated = await api().post('/auth/refresh').send({ refresh_token: tokens.refresh_token });
+      expect(rotated.status).toBe(200);
+      expect(rotated.body.refresh_token).not.toBe(tokens.refresh_token);
+
+      const replayed = await api().post('/auth/refresh').send({ refresh_token: tokens.refresh_token });
+      expect(replayed.status).toBe(401);
+      expect(replayed.body.error).toBe('invalid_token');
+
+      const successor = await api().post('/auth/refresh').send({ refresh_token: rotated.body.refresh_token });
+      expect(successor.status).toBe(401);
+    });
+
+    it('should let only one of two concurrent refreshes with the same token through', async () => {
+      const email = generateEmail();
+      const password = generatePassword();
+      await registerUser(email, password);
+      const tokens = (await login(email, password)).body;
+
+      const results = await Promise.all([
+        api().post('/auth/refresh').send({ refresh_token: tokens.refresh_token }),
+        api().post('/auth/refresh').send({ refresh_token: tokens.refresh_token }),
+      ]);
+      expect(results.filter((res) => res.status === 200).length).toBeLessThanOrEqual(1);
+
+      for (const res of results.filter((r) => r.status === 200)) {
+        const next = await api().post('/auth/refresh').send({ refresh_token: res.body.refresh_token });
+        expect(next.status).toBe(401);
+      }
+    });
+
+    it('should refuse access tokens of the session after a replay', async () => {
+      const email = generateEmail();
+      const password = generatePassword();
+      await registerUser(email, password);
+      const tokens = (await login(email, password)).body;
+
+      const rotated = await api().post('/auth/refresh').send({ refresh_token: tokens.refresh_token });
+      expect(rotated.status).toBe(200);
+      await api().post('/auth/refresh').send({ refresh_token: tokens.refresh_token });
+
+      for (const accessToken of [tokens.access_token, rotated.body.access_token]) {
+        const res = await api()
+          .get('/auth/sessions')
+          .set('Authorization', `Bearer ${accessToken}`);
+        expect(res.status).toBe(401);
+      }
+    });
+  });
+
+  describe('GET /auth/claims', () => {
+    it('should resolve the app claims of the token', async () => {
+      const res = await api()
+        .get('/auth/claims')
+        .set('Authorization', `Bearer ${token}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.sub).toBe(userId);
+      expect(res.body).toHaveProperty('apps');
+      expect(res.body).toHaveProperty('apps_ref');
+      expect(res.body.matches_token).toBe(true);
+    });
+
+    it('should require authentication', async () => {
+      const res = await api().get('/auth/claims');
+
+      expect(res.status).toBe(401);
+    });
+  });
+
   describe('POST /auth/mfa/totp/setup', () => {
     it('should initialize TOTP setup', async () => {
       const res = await api()
@@ -148,6 +311,37 @@ describe('Security API', () => {
       // Should be locked (403) or rate limited (429)
       expect([401, 403, 429]).toContain(res.status);
     });
+
+    it('should describe lockouts and rate limits with machine-readable metadata', async () => {
+      const email = generateEmail();
+      const password = generatePassword();
+      await registerUser(email, password);
+
+      let res;
+      for (let i = 0; i < 7; i++) {
+        res = await login(email, 'wrongpassword');
+      }
+
+      if (res.status === 403) {
+        expect(res.body.error).toBe('account_locked');
+        expect(res.body).toHaveProperty('locked_until');
+        expect(res.body.retry_after).toBeGreaterThanOrEqual(0);
+        expect(res.body.retry_after_seconds).toBe(res.body.retry_after);
+        expect(res.body.policy).toBe('account_lockout');
+        expect(res.headers['retry-after']).toBeDefined();
+      } else if (res.status === 429) {
+        expect(res.body.error).toBe('rate_limit_exceeded');
+        expect(res.body.retry_after).toBeGreaterThan(0);
+        expect(res.body.retry_after_seconds).toBe(res.body.retry_after);
+        expect(res.body.window_seconds).toBe(60);
+        expect(res.body.policy).toBe('login');
+        expect(res.headers['retry-after']).toBeDefined();
+        expect(res.headers['ratelimit-policy']).toBe(`${res.body.limit};w=60`);
+      } else {
+        expect(res.status).toBe(401);
+        expect(res.body.error).toBe('invalid_credentials');
+      }
+    });
   });
 
   describe('Admin Security Endpoints', () => {
@@ -58,6 +58,54 @@ describe('API Keys', () => {
     });
   });
 
+  describe('API key access windows', () => {
+    it('should store a normalized access window', async () => {
+      const res = await api()
+        .post(`/apps/${appId}/api-keys`)
+        .set('Authorization', `Bearer ${userToken}`)
+        .send({ name: 'Office Hours Key', scopes: ['read:roles'], access_window: 'Monday-Friday 9:00-17:00 +07:00' });
+
+      expect(res.status).toBe(201);
+      expect(res.body.access_window).toBe('mon-fri 09:00-17:00 +07:00');
+    });
+
+    it('should reject a malformed access window', async () => {
+      const res = await api()
+        .post(`/apps/${appId}/api-keys`)
+        .set('Authorization', `Bearer ${userToken}`)
+        .send({ name: 'Bad Window Key', access_window: 'always' });
+
+      expect(res.status).toBe(400);
+    });
+
+    it('should refuse a key outside its window until the window is removed', async () => {
+      // A span on a day two days away is closed now
+      const days = ['sun', 'mon', 'tue', 'wed', 'thu', 'fri', 'sat'];
+      const closed = `${days[(new Date().getUTCDay() + 2) % 7]} 09:00-17:00`;
+      const created = await api()
+        .post(`/apps/${appId}/api-keys`)
+        .set('Authorization', `Bearer ${userToken}`)
+        .send({ name: 'Closed Window Key', scopes: ['read:roles'], access_window: closed });
+
+      const refused = await api()
+        .get(`/app-api/apps/${appId}/roles`)
+        .set('X-API-Key', created.body.key);
+      expect(refused.status).toBe(403);
+      expect(refused.body.error).toBe('outside_access_window');
+
+      const updated = await api()
+        .put(`/apps/${appId}/api-keys/${created.body.id}`)
+        .set('Authorization', `Bearer ${userToken}`)
+        .send({ access_window: '' });
+      expect(updated.body.access_window).toBeNull();
+
+      const allowed = await api()
+        .get(`/app-api/apps/${appId}/roles`)
+        .set('X-API-Key', created.body.key);
+      expect(allowed.status).toBe(200);
+    });
+  });
+
   describe('GET /apps/:app_id/api-keys', () => {
     it('should list API keys', async () => {
       const res = await api()
@@ -102,6 +150,127 @@ describe('API Keys', () => {
     });
   });
 
+  describe('API key auth on /app-api routes', () => {
+    let rolesKey;
+
+    beforeAll(async () => {
+      const res = await api()
+        .post(`/apps/${appId}/api-keys`)
+        .set('Authorization', `Bearer ${userToken}`)
+        .send({ name: 'Roles Key', scopes: ['read:roles'] });
+      rolesKey = res.body.key;
+    });
+
+    it('should list roles with a key holding read:roles', async () => {
+      const res = await api()
+        .get(`/app-api/apps/${appId}/roles`)
+        .set('X-API-Key', rolesKey);
+
+      expect(res.status).toBe(200);
+      expect(Array.isArray(res.body)).toBe(true);
+    });
+
+    it('should reject a key without the required scope', async () => {
+      const res = await api()
+        .post(`/app-api/apps/${appId}/roles`)
+        .set('X-API-Key', rolesKey)
+        .send({ name: `role_${Date.now()}` });
+
+      expect(res.status).toBe(403);
+      expect(res.body.error).toBe('insufficient_scope');
+    });
+
+    it('should reject an invalid key', async () => {
+      const res = await api()
+        .get(`/app-api/apps/${appId}/roles`)
+        .set('X-API-Key', 'ak_invalid');
+
+      expect(res.status).toBe(401);
+    });
+  });
+
+  describe('Provisioning events', () => {
+    let provisioningKey;
+    let readOnlyKey;
+
+    beforeAll(async () => {
+      const res = await api()
+        .post(`/apps/${appId}/api-keys`)
+        .set('Authorization', `Bearer ${userToken}`)
+        .send({ name: 'HR Sync Key', scopes: ['read:users', 'write:users', 'write:roles'] });
+      provisioningKey = res.body.key;
+
+      const readOnly = await api()
+        .post(`/apps/${appId}/api-keys`)
+        .set('Authorization', `Bearer ${userToken}`)
+        .send({ name: 'Read Only Key', scopes: ['read:roles'] });
+      readOnlyKey = readOnly.body.key;
+    });
+
+    it('should accept an event once and ignore redelivery', async () => {
+      const event = {
+        event_id: `hr-${Date.now()}`,
+        event_type: 'user.deprovision',
+        payload: { user_id: '00000000-0000-0000-0000-000000000000' },
+      };
+
+      const first = await api()
+        .post('/api/v1/provisioning/events')
+        .set('X-API-Key', provisioningKey)
+        .send(event);
+      expect(first.status).toBe(202);
+      expect(first.body.status).toBe('pending');
+
+      const again = await api()
+        .post('/api/v1/provisioning/events')
+        .set('X-API-Key', provisioningKey)
+        .send(event);
+      expect(again.status).toBe(200);
+      expect(again.body.id).toBe(first.body.id);
+    });
+
+    it('should dead-letter an event that fails schema validation', async () => {
+      const eventId = `hr-invalid-${Date.now()}`;
+      await api()
+        .post('/api/v1/provisioning/events')
+        .set('X-API-Key', provisioningKey)
+        .send({ event_id: eventId, event_type: 'role.sync', payload: { user_id: 'not-a-uuid' } });
+
+      let deadLetters;
+      for (let i = 0; i < 20; i += 1) {
+        deadLetters = await api()
+          .get('/api/v1/provisioning/events?status=dead_letter')
+          .set('X-API-Key', provisioningKey);
+        if (deadLetters.body.data.some((e) => e.event_id === eventId)) break;
+        await new Promise((resolve) => setTimeout(resolve, 1000));
+      }
+
+      const dead = deadLetters.body.data.find((e) => e.event_id === eventId);
+      expect(dead).toBeDefined();
+      expect(dead.last_error).toMatch(/invalid role.sync payload/);
+
+      const retried = await api()
+        .post(`/api/v1/provisioning/events/${eventId}/retry`)
+        .set('X-API-Key', provisioningKey);
+      expect(retried.status).toBe(200);
+      expect(retried.body.status).toBe('pending');
+    });
+
+    it('should reject unknown event types and missing scopes', async () => {
+      const unknown = await api()
+        .post('/api/v1/provisioning/events')
+        .set('X-API-Key', provisioningKey)
+        .send({ event_id: `hr-${Date.now()}`, event_type: 'user.create', payload: {} });
+      expect(unknown.status).toBe(400);
+
+      const forbidden = await api()
+        .post('/api/v1/provisioning/events')
+        .set('X-API-Key', readOnlyKey)
+        .send({ event_id: `hr-${Date.now()}`, event_type: 'user.deprovision', payload: {} });
+      expect(forbidden.status).toBe(403);
+    });
+  });
+
   describe('POST /apps/:app_id/api-keys/:key_id/revoke', () => {
     it('should revoke an API key', async () => {
       const res = await api()
@@ -183,4 +183,618 @@ describe('Apps API', () => {
       expect(res.status).toBe(401);
     });
   });
+
+  describe('GET /apps/:code/auth-methods', () => {
+    it('should list login options without authentication', async () => {
+      const res = await api().get(`/apps/${appCode}/auth-methods`);
+
+      expect(res.status).toBe(200);
+      expect(res.headers['cache-control']).toMatch(/^public, max-age=\d+$/);
+      expect(res.body.app_code).toBe(appCode);
+      expect(res.body.password.enabled).toBe(true);
+      expect(res.body.passkeys.enabled).toBe(true);
+      expect(Array.isArray(res.body.social_providers)).toBe(true);
+      expect(res.body.mfa.methods).toContain('totp');
+    });
+
+    it('should return 404 for an unknown app', async () => {
+      const res = await api().get('/apps/no-such-app-code/auth-methods');
+
+      expect(res.status).toBe(404);
+    });
+  });
+
+  describe('PUT /apps/:app_id/session-policy', () => {
+    it('should set session lifetimes for the app', async () => {
+      const res = await api()
+        .put(`/apps/${appId}/session-policy`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ idle_timeout_secs: 3600, absolute_lifetime_secs: 86400 });
+
+      expect(res.status).toBe(200);
+      expect(res.body.session_idle_timeout_secs).toBe(3600);
+      expect(res.body.session_absolute_lifetime_secs).toBe(86400);
+    });
+
+    it('should restore server defaults when limits are omitted', async () => {
+      const res = await api()
+        .put(`/apps/${appId}/session-policy`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({});
+
+      expect(res.status).toBe(200);
+      expect(res.body.session_idle_timeout_secs).toBeNull();
+      expect(res.body.session_absolute_lifetime_secs).toBeNull();
+    });
+
+    it('should reject an idle timeout longer than the absolute lifetime', async () => {
+      const res = await api()
+        .put(`/apps/${appId}/session-policy`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ idle_timeout_secs: 86400, absolute_lifetime_secs: 3600 });
+
+      expect(res.status).toBe(400);
+    });
+
+    it('should reject non-owners', async () => {
+      const other = await createTestUser();
+      const res = await api()
+        .put(`/apps/${appId}/session-policy`)
+        .set('Authorization', `Bearer ${other.token}`)
+        .send({ idle_timeout_secs: 3600 });
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('PUT /apps/:app_id/token-binding', () => {
+    afterAll(async () => {
+      await api()
+        .put(`/apps/${appId}/token-binding`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ mode: 'none' });
+    });
+
+    it('should bind app tokens to the caller IP range', async () => {
+      const res = await api()
+        .put(`/apps/${appId}/token-binding`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ mode: 'ip', ip_prefix: 24 });
+
+      expect(res.status).toBe(200);
+      expect(res.body.token_binding).toBe('ip');
+      expect(res.body.token_binding_ip_prefix).toBe(24);
+    });
+
+    it('should issue a token when the caller IP is known', async () => {
+      const res = await api()
+        .post('/apps/auth')
+        .send({ app_id: appId, secret: appSecret });
+
+      expect(res.status).toBe(200);
+      expect(res.body).toHaveProperty('access_token');
+    });
+
+    it('should bind to the peer address rather than a forged X-Forwarded-For', async () => {
+      const auth = await api()
+        .post('/apps/auth')
+        .set('X-Forwarded-For', '203.0.113.57')
+        .send({ app_id: appId, secret: appSecret });
+      expect(auth.status).toBe(200);
+
+      const res = await api()
+        .get(`/app-api/apps/${appId}/roles`)
+        .set('Authorization', `Bearer ${auth.body.access_token}`);
+      expect(res.status).toBe(200);
+    });
+
+    it('should reject an unknown mode', async () => {
+      const res = await api()
+        .put(`/apps/${appId}/token-binding`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ mode: 'cookie' });
+
+      expect(res.status).toBe(400);
+    });
+
+    it('should reject non-owners', async () => {
+      const other = await createTestUser();
+      const res = await api()
+        .put(`/apps/${appId}/token-binding`)
+        .set('Authorization', `Bearer ${other.token}`)
+        .send({ mode: 'none' });
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('PUT /apps/:app_id/required-acr', () => {
+    afterAll(async () => {
+      await api()
+        .put(`/apps/${appId}/required-acr`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ required_acr: null });
+    });
+
+    it('should require MFA for sign-ins to the app', async () => {
+      const res = await api()
+        .put(`/apps/${appId}/required-acr`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ required_acr: 'mfa' });
+
+      expect(res.status).toBe(200);
+      expect(res.body.required_acr).toBe('mfa');
+
+      const methods = await api().get(`/apps/${appCode}/auth-methods`);
+      expect(methods.body.required_acr).toBe('mfa');
+      expect(methods.body.mfa.required).toBe(true);
+    });
+
+    it('should reject an unknown level', async () => {
+      const res = await api()
+        .put(`/apps/${appId}/required-acr`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ required_acr: 'loa3' });
+
+      expect(res.status).toBe(400);
+    });
+
+    it('should reject non-owners', async () => {
+      const other = await createTestUser();
+      const res = await api()
+        .put(`/apps/${appId}/required-acr`)
+        .set('Authorization', `Bearer ${other.token}`)
+        .send({ required_acr: 'mfa' });
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('PUT /apps/:app_id/sso', () => {
+    afterAll(async () => {
+      await api()
+        .put(`/apps/${appId}/sso`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ enabled: true });
+    });
+
+    it('should refuse sign-ins continued from the SSO session', async () => {
+      const res = await api()
+        .put(`/apps/${appId}/sso`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ enabled: false });
+
+      expect(res.status).toBe(200);
+      expect(res.body.sso_enabled).toBe(false);
+
+      const methods = await api().get(`/apps/${appCode}/auth-methods`);
+      expect(methods.body.sso.enabled).toBe(false);
+    });
+
+    it('should reject non-owners', async () => {
+      const other = await createTestUser();
+      const res = await api()
+        .put(`/apps/${appId}/sso`)
+        .set('Authorization', `Bearer ${other.token}`)
+        .send({ enabled: false });
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('PUT /apps/:app_id/digest', () => {
+    afterAll(async () => {
+      await api()
+        .put(`/apps/${appId}/digest`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ enabled: false });
+    });
+
+    it('should opt the app in to the weekly digest', async () => {
+      const res = await api()
+        .put(`/apps/${appId}/digest`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ enabled: true });
+
+      expect(res.status).toBe(200);
+      expect(res.body.digest_enabled).toBe(true);
+    });
+
+    it('should reject non-owners', async () => {
+      const other = await createTestUser();
+      const res = await api()
+        .put(`/apps/${appId}/digest`)
+        .set('Authorization', `Bearer ${other.token}`)
+        .send({ enabled: true });
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('PUT /apps/:app_id/deletion-policy', () => {
+    afterAll(async () => {
+      await api()
+        .put(`/apps/${appId}/deletion-policy`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ policy: 'delete' });
+    });
+
+    it('should switch the app to anonymize deleted members', async () => {
+      const res = await api()
+        .put(`/apps/${appId}/deletion-policy`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ policy: 'anonymize' });
+
+      expect(res.status).toBe(200);
+      expect(res.body.deletion_policy).toBe('anonymize');
+    });
+
+    it('should reject an unknown policy', async () => {
+      const res = await api()
+        .put(`/apps/${appId}/deletion-policy`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ policy: 'archive' });
+
+      expect(res.status).toBe(400);
+    });
+
+    it('should reject non-owners', async () => {
+      const other = await createTestUser();
+      const res = await api()
+        .put(`/apps/${appId}/deletion-policy`)
+        .set('Authorization', `Bearer ${other.token}`)
+        .send({ policy: 'delete' });
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('PUT /app-api/apps/:id/rbac', () => {
+    let rbacAppId;
+    let appToken;
+
+    const catalog = {
+      permissions: ['orders.read', 'orders.write'],
+      roles: [
+        { name: 'viewer', permissions: ['orders.read'] },
+        { name: 'editor', permissions: ['orders.read', 'orders.write'] },
+      ],
+    };
+
+    beforeAll(async () => {
+      const app = await api()
+        .post('/apps')
+        .set('Authorization', `Bearer ${token}`)
+        .send({ code: `rbac-app-${Date.now()}`, name: 'RBAC Sync App' });
+      rbacAppId = app.body.id;
+
+      const auth = await api()
+        .post('/apps/auth')
+        .send({ app_id: rbacAppId, secret: app.body.secret });
+      appToken = auth.body.access_token;
+    });
+
+    function sync(body, query = '') {
+      return api()
+        .put(`/app-api/apps/${rbacAppId}/rbac${query}`)
+        .set('Authorization', `Bearer ${appToken}`)
+        .send(body);
+    }
+
+    it('should report changes without applying them on a dry run', async () => {
+      const res = await sync(catalog, '?dry_run=true');
+
+      expect(res.status).toBe(200);
+      expect(res.body.dry_run).toBe(true);
+      expect(res.body.changes.roles_created).toEqual(['viewer', 'editor']);
+
+      const roles = await api()
+        .get(`/app-api/apps/${rbacAppId}/roles`)
+        .set('Authorization', `Bearer ${appToken}`);
+      expect(roles.body).toHaveLength(0);
+    });
+
+    it('should create the catalog', async () => {
+      const res = await sync(catalog);
+
+      expect(res.status).toBe(200);
+      expect(res.body.changed).toBe(true);
+      expect(res.body.changes.permissions_created).toEqual(['orders.read', 'orders.write']);
+      expect(res.body.changes.mappings_added).toHaveLength(3);
+    });
+
+    it('should be a no-op when nothing changed', async () => {
+      const res = await sync(catalog);
+
+      expect(res.status).toBe(200);
+      expect(res.body.changed).toBe(false);
+    });
+
+    it('should remove roles, permissions and mappings missing from the catalog', async () => {
+      const res = await sync({
+        permissions: ['orders.read'],
+        roles: [{ name: 'viewer', permissions: ['orders.read'] }],
+      });
+
+      expect(res.status).toBe(200);
+      expect(res.body.changes.roles_deleted).toEqual(['editor']);
+      expect(res.body.changes.permissions_deleted).toEqual(['orders.write']);
+    });
+
+    it('should reject a role referencing an undeclared permission', async () => {
+      const res = await sync({
+        permissions: [],
+        roles: [{ name: 'viewer', permissions: ['orders.read'] }],
+      });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
+
+    it('should reject another app', async () => {
+      const res = await api()
+        .put(`/app-api/apps/${appId}/rbac`)
+        .set('Authorization', `Bearer ${appToken}`)
+        .send(catalog);
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('Role access windows', () => {
+    let windowAppId;
+    let windowAppCode;
+    let roleId;
+    let member;
+    let memberId;
+
+    // A span on a day two days away is closed now
+    const closedWindow = () => {
+      const days = ['sun', 'mon', 'tue', 'wed', 'thu', 'fri', 'sat'];
+      return `${days[(new Date().getUTCDay() + 2) % 7]} 09:00-17:00`;
+    };
+
+    beforeAll(async () => {
+      windowAppCode = `window-app-${Date.now()}`;
+      const app = await api()
+        .post('/apps')
+        .set('Authorization', `Bearer ${token}`)
+        .send({ code: windowAppCode, name: 'Access Window App' });
+      windowAppId = app.body.id;
+
+      const role = await api()
+        .post(`/apps/${windowAppId}/roles`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ name: 'support' });
+      roleId = role.body.id;
+
+      member = await createTestUser();
+      await api()
+        .post(`/apps/${windowAppId}/register`)
+        .set('Authorization', `Bearer ${member.token}`);
+      const me = await api()
+        .get('/users/me')
+        .set('Authorization', `Bearer ${member.token}`);
+      memberId = me.body.id;
+    });
+
+    it('should reject a malformed access window', async () => {
+      const res = await api()
+        .post(`/apps/${windowAppId}/users/${memberId}/roles`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ role_id: roleId, access_window: 'weekdays 9-5' });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_access_window');
+    });
+
+    it('should leave a role out of the claims outside its window', async () => {
+      const assigned = await api()
+        .post(`/apps/${windowAppId}/users/${memberId}/roles`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ role_id: roleId, access_window: closedWindow() });
+      expect(assigned.status).toBe(204);
+
+      const claims = await api()
+        .get('/auth/claims')
+        .set('Authorization', `Bearer ${member.token}`);
+      expect(claims.status).toBe(200);
+      expect(claims.body.apps[windowAppCode]).toBeUndefined();
+
+      const login = await api()
+        .post('/auth/login')
+        .send({ email: member.email, password: member.password, app: windowAppCode });
+      expect(login.status).toBe(403);
+      expect(login.body.error).toBe('not_app_member');
+    });
+
+    it('should grant the role inside its window and cap the token at the close', async () => {
+      await api()
+        .post(`/apps/${windowAppId}/users/${memberId}/roles`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ role_id: roleId, access_window: '* 00:00-24:00' });
+
+      const claims = await api()
+        .get('/auth/claims')
+        .set('Authorization', `Bearer ${member.token}`);
+      expect(claims.body.apps[windowAppCode].roles).toContain('support');
+
+      const login = await api()
+        .post('/auth/login')
+        .send({ email: member.email, password: member.password, app: windowAppCode });
+      expect(login.status).toBe(200);
+      const midnight = new Date();
+      midnight.setUTCHours(24, 0, 0, 0);
+      expect(Date.now() + login.body.expires_in * 1000).toBeLessThanOrEqual(midnight.getTime() + 1000);
+    });
+  });
+
+  describe('Temporary role elevation', () => {
+    let elevAppId;
+    let roleId;
+    let member;
+    let memberId;
+
+    beforeAll(async () => {
+      const app = await api()
+        .post('/apps')
+        .set('Authorization', `Bearer ${token}`)
+        .send({ code: `elev-app-${Date.now()}`, name: 'Elevation App' });
+      elevAppId = app.body.id;
+
+      const role = await api()
+        .post(`/apps/${elevAppId}/roles`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ name: 'incident-admin' });
+      roleId = role.body.id;
+
+      member = await createTestUser();
+      await api()
+        .post(`/apps/${elevAppId}/register`)
+        .set('Authorization', `Bearer ${member.token}`);
+      const me = await api()
+        .get('/users/me')
+        .set('Authorization', `Bearer ${member.token}`);
+      memberId = me.body.id;
+    });
+
+    it('should reject a role assignment expiring in the past', async () => {
+      const res = await api()
+        .post(`/apps/${elevAppId}/users/${memberId}/roles`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ role_id: roleId, expires_at: new Date(Date.now() - 60000).toISOString() });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_expiry');
+    });
+
+    it('should reject a duration outside the allowed range', async () => {
+      const res = await api()
+        .post(`/apps/${elevAppId}/elevation-requests`)
+        .set('Authorization', `Bearer ${member.token}`)
+        .send({ role_id: roleId, duration_secs: 10 });
+
+      expect(res.status).toBe(400);
+    });
+
+    it('should grant the role until the requested duration on approval', async () => {
+      const created = await api()
+        .post(`/apps/${elevAppId}/elevation-requests`)
+        .set('Authorization', `Bearer ${member.token}`)
+        .send({ role_id: roleId, duration_secs: 3600, reason: 'Incident #42' });
+
+      expect(created.status).toBe(201);
+      expect(created.body.status).toBe('pending');
+
+      const forbidden = await api()
+        .post(`/apps/${elevAppId}/elevation-requests/${created.body.id}/approve`)
+        .set('Authorization', `Bearer ${member.token}`);
+      expect(forbidden.status).toBe(403);
+
+      const pending = await api()
+        .get(`/apps/${elevAppId}/elevation-requests?status=pending`)
+        .set('Authorization', `Bearer ${token}`);
+      expect(pending.body.map((r) => r.id)).toContain(created.body.id);
+
+      const approved = await api()
+        .post(`/apps/${elevAppId}/elevation-requests/${created.body.id}/approve`)
+        .set('Authorization', `Bearer ${token}`);
+      expect(approved.status).toBe(200);
+      expect(approved.body.status).toBe('approved');
+      expect(approved.body.grant_expires_at).toBeDefined();
+
+      const roles = await api()
+        .get(`/apps/${elevAppId}/users/${memberId}/roles`)
+        .set('Authorization', `Bearer ${token}`);
+      expect(roles.body.map((r) => r.id)).toContain(roleId);
+
+      const again = await api()
+        .post(`/apps/${elevAppId}/elevation-requests/${created.body.id}/deny`)
+        .set('Authorization', `Bearer ${token}`);
+      expect(again.status).toBe(400);
+    });
+
+    it('should record grants and removals in the role history', async () => {
+      await api()
+        .delete(`/apps/${elevAppId}/users/${memberId}/roles/${roleId}`)
+        .set('Authorization', `Bearer ${token}`);
+
+      const res = await api()
+        .get(`/apps/${elevAppId}/users/${memberId}/roles/history`)
+        .set('Authorization', `Bearer ${token}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.data.map((e) => e.action)).toEqual(['removed', 'granted']);
+      expect(res.body.data[1].expires_at).toBeTruthy();
+      expect(res.body.data[0].actor_type).toBe('user');
+      expect(res.body.total).toBe(2);
+    });
+
+    it('should filter app users by role, status and email', async () => {
+      await api()
+        .post(`/apps/${elevAppId}/users/${memberId}/roles`)
+        .set('Authorization', `Bearer ${token}`)
+        .send({ role_id: roleId });
+
+      const byRole = await api()
+        .get(`/apps/${elevAppId}/users?role_id=${roleId}`)
+        .set('Authorization', `Bearer ${token}`);
+      expect(byRole.status).toBe(200);
+      expect(byRole.body.total).toBe(1);
+      expect(byRole.body.data[0].user_id).toBe(memberId);
+
+      const banned = await api()
+        .get(`/apps/${elevAppId}/users?status=banned`)
+        .set('Authorization', `Bearer ${token}`);
+      expect(banned.body.total).toBe(0);
+
+      const byEmail = await api()
+        .get(`/apps/${elevAppId}/users?email=${encodeURIComponent(member.email.split('@')[0])}&sort_by=email&sort_order=asc`)
+        .set('Authorization', `Bearer ${token}`);
+      expect(byEmail.body.data.map((u) => u.email)).toEqual([member.email]);
+      expect(byEmail.body.sort).toEqual({ by: 'email', order: 'asc' });
+      expect(byEmail.body.filters.email).toBe(member.email.split('@')[0]);
+      expect(byEmail.body.has_more).toBe(false);
+
+      // LIKE wildcards in the filter are matched literally
+      const wildcard = `_${member.email.split('@')[0].slice(1)}`;
+      const byWildcard = await api()
+        .get(`/apps/${elevAppId}/users?email=${encodeURIComponent(wildcard)}`)
+        .set('Authorization', `Bearer ${token}`);
+      expect(byWildcard.status).toBe(200);
+      expect(byWildcard.body.total).toBe(0);
+    });
+
+    it('should report has_more and the applied sort on paginated lists', async () => {
+      const res = await api()
+        .get('/apps?page=1&limit=1')
+        .set('Authorization', `Bearer ${token}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.limit).toBe(1);
+      expect(res.body.data.length).toBeLessThanOrEqual(1);
+      expect(res.body.has_more).toBe(res.body.total > 1);
+      expect(res.body.sort).toEqual({ by: 'code', order: 'asc' });
+    });
+
+    it('should reject an unknown status or sort field', async () => {
+      const status = await api()
+        .get(`/apps/${elevAppId}/users?status=pending`)
+        .set('Authorization', `Bearer ${token}`);
+      expect(status.status).toBe(400);
+
+      const sort = await api()
+        .get(`/apps/${elevAppId}/users?sort_by=password_hash`)
+        .set('Authorization', `Bearer ${token}`);
+      expect(sort.status).toBe(400);
+    });
+
+    it('should hide the role history from other users', async () => {
+      const res = await api()
+        .get(`/apps/${elevAppId}/users/${memberId}/roles/history`)
+        .set('Authorization', `Bearer ${member.token}`);
+
+      expect(res.status).toBe(403);
+    });
+  });
 });
@@ -31,6 +31,54 @@ describe('Authentication API', () => {
       expect(res.body.error).toBe('email_exists');
     });
 
+    it('should reject case and alias variants of an existing email', async () => {
+      const local = `test.${Date.now()}.${Math.random().toString(36).substring(2, 8)}`;
+      const password = generatePassword();
+
+      await registerUser(`${local}@gmail.com`, password);
+
+      for (const variant of [`${local.toUpperCase()}@Gmail.com`, `${local.replace(/\./g, '')}+alt@googlemail.com`]) {
+        const res = await api()
+          .post('/auth/register')
+          .send({ email: variant, password });
+
+        expect(res.status).toBe(409);
+        expect(res.body.error).toBe('email_exists');
+        // The stored address is never revealed
+        expect(JSON.stringify(res.body)).not.toContain(local);
+      }
+    });
+
+    it('should register an internationalized email address', async () => {
+      const suffix = `${Date.now()}${Math.random().toString(36).substring(2, 8)}`;
+      const res = await api()
+        .post('/auth/register')
+        .send({ email: `josé.${suffix}@bücher.example`, password: generatePassword() });
+
+      expect(res.status).toBe(201);
+      expect(res.body.email).toBe(`josé.${suffix}@bücher.example`);
+
+      // Same address with a decomposed "é" and a punycode domain
+      const duplicate = await api()
+        .post('/auth/register')
+        .send({ email: `jose\u0301.${suffix}@xn--bcher-kva.example`, password: generatePassword() });
+
+      expect(duplicate.status).toBe(409);
+    });
+
+    it('should reject homoglyph look-alikes of an existing email', async () => {
+      const suffix = `${Date.now()}${Math.random().toString(36).substring(2, 8)}`;
+      await registerUser(`paypal${suffix}@example.com`, generatePassword());
+
+      // Cyrillic "а" in place of the Latin "a"
+      const res = await api()
+        .post('/auth/register')
+        .send({ email: `p\u0430ypal${suffix}@example.com`, password: generatePassword() });
+
+      expect(res.status).toBe(409);
+      expect(res.body.error).toBe('email_exists');
+    });
+
     it('should reject invalid email format', async () => {
       const res = await api()
         .post('/auth/register')
@@ -48,6 +96,19 @@ describe('Authentication API', () => {
       expect(res.status).toBe(400);
       expect(res.body.error).toBe('weak_password');
     });
+
+    it('should reject registration fields that are not configured', async () => {
+      const schema = await api().get('/auth/register/fields');
+      expect(schema.status).toBe(200);
+      expect(Array.isArray(schema.body.fields)).toBe(true);
+
+      const res = await api()
+        .post('/auth/register')
+        .send({ email: generateEmail(), password: generatePassword(), fields: { not_a_configured_field: 'x' } });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
   });
 
   describe('POST /auth/login', () => {
@@ -69,6 +130,43 @@ describe('Authentication API', () => {
       expect(res.body.expires_in).toBe(900);
     });
 
+    it('should record how the user signed in as acr and amr', async () => {
+      const res = await login(testEmail, testPassword);
+      const claims = JSON.parse(Buffer.from(res.body.access_token.split('.')[1], 'base64url').toString());
+
+      expect(claims.acr).toBe('pwd');
+      expect(claims.amr).toEqual(['pwd']);
+    });
+
+    it('should require a second factor to turn MFA off', async () => {
+      const res = await login(testEmail, testPassword);
+      const stepUp = await api()
+        .delete('/auth/mfa')
+        .set('Authorization', `Bearer ${res.body.access_token}`)
+        .send({});
+
+      expect(stepUp.status).toBe(403);
+      expect(stepUp.body.error).toBe('insufficient_user_authentication');
+      expect(stepUp.body.required_acr).toBe('mfa');
+      expect(stepUp.headers['www-authenticate']).toContain('acr_values="mfa"');
+    });
+
+    it('should login with a differently cased email', async () => {
+      const res = await login(testEmail.toUpperCase(), testPassword);
+
+      expect(res.status).toBe(200);
+      expect(res.body).toHaveProperty('access_token');
+    });
+
+    it('should reject tokens scoped to an app the user is not a member of', async () => {
+      const res = await api()
+        .post('/auth/login')
+        .send({ email: testEmail, password: testPassword, app: 'no-such-app' });
+
+      expect(res.status).toBe(403);
+      expect(res.body.error).toBe('not_app_member');
+    });
+
     it('should reject invalid password', async () => {
       const res = await login(testEmail, 'wrongpassword');
 
@@ -112,6 +210,172 @@ describe('Authentication API', () => {
 
       expect(res.status).toBe(401);
     });
+
+    it('should still refresh from a different client in warn mode', async () => {
+      const email = generateEmail();
+      const password = generatePassword();
+      await registerUser(email, password);
+      const loginRes = await login(email, password);
+
+      // Default REFRESH_FINGERPRINT_MODE=warn only audits the mismatch
+      const res = await api()
+        .post('/auth/refresh')
+        .set('User-Agent', 'curl/8.4.0')
+        .set('X-Forwarded-For', '198.51.100.23')
+        .send({ refresh_token: loginRes.body.refresh_token });
+
+      expect(res.status).toBe(200);
+      expect(res.body).toHaveProperty('access_token');
+    });
+  });
+
+  describe('POST /auth/refresh (cookie mode)', () => {
+    let refreshToken;
+
+    function cookieValue(res, name) {
+      const cookies = res.headers['set-cookie'] || [];
+      const cookie = cookies.find((c) => c.startsWith(`${name}=`));
+      return cookie ? cookie.split(';')[0].slice(name.length + 1) : undefined;
+    }
+
+    beforeAll(async () => {
+      const email = generateEmail();
+      const password = generatePassword();
+      await registerUser(email, password);
+      const loginRes = await login(email, password);
+      refreshToken = loginRes.body.refresh_token;
+    });
+
+    it('should move the refresh token into an HttpOnly cookie', async () => {
+      const res = await api()
+        .post('/auth/refresh')
+        .send({ refresh_token: refreshToken, use_cookie: true });
+
+      expect(res.status).toBe(200);
+      expect(res.body).toHaveProperty('access_token');
+      expect(res.body).not.toHaveProperty('refresh_token');
+
+      const refreshCookie = res.headers['set-cookie'].find((c) => c.startsWith('refresh_token='));
+      expect(refreshCookie).toContain('HttpOnly');
+      expect(cookieValue(res, 'csrf_token')).toBeTruthy();
+
+      refreshToken = cookieValue(res, 'refresh_token');
+    });
+
+    it('should reject a cookie refresh without the CSRF header', async () => {
+      const res = await api()
+        .post('/auth/refresh')
+        .set('Cookie', `refresh_token=${refreshToken}; csrf_token=abc`)
+        .send({});
+
+      expect(res.status).toBe(403);
+      expect(res.body.error).toBe('csrf_token_invalid');
+    });
+
+    it('should refresh from the cookie with a matching CSRF header', async () => {
+      const res = await api()
+        .post('/auth/refresh')
+        .set('Cookie', `refresh_token=${refreshToken}; csrf_token=abc`)
+        .set('X-CSRF-Token', 'abc')
+        .send({});
+
+      expect(res.status).toBe(200);
+      expect(res.body).not.toHaveProperty('refresh_token');
+      expect(cookieValue(res, 'refresh_token')).toBeTruthy();
+    });
+  });
+
+  describe('SSO session', () => {
+    let ssoCookie;
+
+    function cookieValue(res, name) {
+      const cookies = res.headers['set-cookie'] || [];
+      const cookie = cookies.find((c) => c.startsWith(`${name}=`));
+      return cookie ? cookie.split(';')[0].slice(name.length + 1) : undefined;
+    }
+
+    beforeAll(async () => {
+      const email = generateEmail();
+      const password = generatePassword();
+      await registerUser(email, password);
+      const loginRes = await login(email, password);
+
+      const res = await api()
+        .post('/auth/sso/session')
+        .send({ refresh_token: loginRes.body.refresh_token });
+      expect(res.status).toBe(200);
+      expect(res.body).toHaveProperty('expires_at');
+      expect(res.headers['set-cookie'].find((c) => c.startsWith('sso_session='))).toContain('HttpOnly');
+      ssoCookie = cookieValue(res, 'sso_session');
+    });
+
+    it('should reject an invalid refresh token', async () => {
+      const res = await api()
+        .post('/auth/sso/session')
+        .send({ refresh_token: 'invalid-token' });
+
+      expect(res.status).toBe(401);
+    });
+
+    it('should reject a continuation without the SSO cookie', async () => {
+      const res = await api()
+        .post('/auth/sso/continue')
+        .send({ app: 'any-app' });
+
+      expect(res.status).toBe(401);
+    });
+
+    it('should require the CSRF header with the SSO cookie', async () => {
+      const res = await api()
+        .post('/auth/sso/continue')
+        .set('Cookie', `sso_session=${ssoCookie}; csrf_token=abc`)
+        .send({ app: 'any-app' });
+
+      expect(res.status).toBe(403);
+      expect(res.body.error).toBe('csrf_token_invalid');
+    });
+
+    it('should reject an unknown app', async () => {
+      const res = await api()
+        .post('/auth/sso/continue')
+        .set('Cookie', `sso_session=${ssoCookie}; csrf_token=abc`)
+        .set('X-CSRF-Token', 'abc')
+        .send({ app: `missing-${Date.now()}` });
+
+      expect(res.status).toBe(400);
+    });
+  });
+
+  describe('CSRF protection', () => {
+    it('should issue a CSRF token cookie', async () => {
+      const res = await api().get('/auth/csrf');
+
+      expect(res.status).toBe(200);
+      expect(res.body).toHaveProperty('csrf_token');
+      const cookie = res.headers['set-cookie'].find((c) => c.startsWith('csrf_token='));
+      expect(cookie).toContain(res.body.csrf_token);
+      expect(cookie).not.toContain('HttpOnly');
+    });
+
+    it('should reject cookie-authenticated writes without the CSRF header', async () => {
+      const res = await api()
+        .post('/auth/forgot-password')
+        .set('Cookie', 'refresh_token=abc; csrf_token=xyz')
+        .send({ email: 'any@example.com' });
+
+      expect(res.status).toBe(403);
+      expect(res.body.error).toBe('csrf_token_invalid');
+    });
+
+    it('should exempt requests using a Bearer token', async () => {
+      const res = await api()
+        .post('/auth/forgot-password')
+        .set('Cookie', 'refresh_token=abc')
+        .set('Authorization', 'Bearer anything')
+        .send({ email: 'any@example.com' });
+
+      expect(res.status).toBe(200);
+    });
   });
 
   describe('POST /auth/forgot-password', () => {
@@ -124,4 +388,81 @@ describe('Authentication API', () => {
       expect(res.body).toHaveProperty('message');
     });
   });
+
+  // Needs a server started with ENUMERATION_SAFE_AUTH=true
+  const describeEnumerationSafe = process.env.ENUMERATION_SAFE_AUTH === 'true' ? describe : describe.skip;
+
+  describeEnumerationSafe('Enumeration-safe mode', () => {
+    // Median duration of `count` requests, in milliseconds
+    async function medianDuration(count, send) {
+      const durations = [];
+      for (let i = 0; i < count; i++) {
+        const start = process.hrtime.bigint();
+        await send(i);
+        durations.push(Number(process.hrtime.bigint() - start) / 1e6);
+      }
+      durations.sort((a, b) => a - b);
+      return durations[Math.floor(durations.length / 2)];
+    }
+
+    it('should answer register identically for new and taken emails', async () => {
+      const taken = generateEmail();
+      const password = generatePassword();
+      await api().post('/auth/register').send({ email: taken, password });
+
+      const fresh = await api()
+        .post('/auth/register')
+        .send({ email: generateEmail(), password });
+      const duplicate = await api()
+        .post('/auth/register')
+        .send({ email: taken, password });
+
+      expect(fresh.status).toBe(202);
+      expect(duplicate.status).toBe(fresh.status);
+      expect(duplicate.body).toEqual(fresh.body);
+      expect(fresh.body).not.toHaveProperty('id');
+    });
+
+    it('should still reject invalid registrations', async () => {
+      const res = await api()
+        .post('/auth/register')
+        .send({ email: 'not-an-email', password: generatePassword() });
+
+      expect(res.status).toBe(400);
+    });
+
+    it('should take about as long to register a taken email as a new one', async () => {
+      const taken = generateEmail();
+      const password = generatePassword();
+      await api().post('/auth/register').send({ email: taken, password });
+
+      const freshMs = await medianDuration(5, () =>
+        api().post('/auth/register').send({ email: generateEmail(), password })
+      );
+      const takenMs = await medianDuration(5, () =>
+        api().post('/auth/register').send({ email: taken, password })
+      );
+
+      expect(Math.abs(freshMs - takenMs)).toBeLessThan(Math.max(freshMs, takenMs) * 0.5 + 50);
+    });
+
+    it('should answer forgot-password identically and in similar time', async () => {
+      const known = generateEmail();
+      await registerUser(known, generatePassword());
+
+      const knownRes = await api().post('/auth/forgot-password').send({ email: known });
+      const unknownRes = await api().post('/auth/forgot-password').send({ email: generateEmail() });
+      expect(unknownRes.status).toBe(knownRes.status);
+      expect(unknownRes.body).toEqual(knownRes.body);
+
+      const knownMs = await medianDuration(5, () =>
+        api().post('/auth/forgot-password').send({ email: known })
+      );
+      const unknownMs = await medianDuration(5, () =>
+        api().post('/auth/forgot-password').send({ email: generateEmail() })
+      );
+
+      expect(Math.abs(knownMs - unknownMs)).toBeLessThan(Math.max(knownMs, unknownMs) * 0.5 + 50);
+    });
+  });
 });
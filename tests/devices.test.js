@@ -0,0 +1,100 @@
+const { api, generateEmail, generatePassword, registerUser, login } = require('./helpers');
+
+describe('Device Registration API', () => {
+  let accessToken;
+  let refreshToken;
+
+  beforeAll(async () => {
+    const email = generateEmail();
+    const password = generatePassword();
+    await registerUser(email, password);
+    const res = await login(email, password);
+    accessToken = res.body.access_token;
+    refreshToken = res.body.refresh_token;
+  });
+
+  describe('POST /auth/devices', () => {
+    it('should require authentication', async () => {
+      const res = await api()
+        .post('/auth/devices')
+        .send({ platform: 'ios', refresh_token: refreshToken });
+
+      expect(res.status).toBe(401);
+    });
+
+    it('should reject an unsupported platform', async () => {
+      const res = await api()
+        .post('/auth/devices')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ platform: 'toaster', refresh_token: refreshToken });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
+
+    it('should register a device and bind the session', async () => {
+      const res = await api()
+        .post('/auth/devices')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({
+          platform: 'android',
+          device_name: 'Pixel Test',
+          push_token: 'fcm-test-token',
+          refresh_token: refreshToken,
+        });
+
+      expect(res.status).toBe(201);
+      expect(res.body.platform).toBe('android');
+      expect(res.body.push_enabled).toBe(true);
+
+      const sessions = await api()
+        .get('/auth/sessions')
+        .set('Authorization', `Bearer ${accessToken}`);
+
+      expect(sessions.body.sessions.some((s) => s.device_id === res.body.id)).toBe(true);
+    });
+  });
+
+  describe('POST /auth/refresh', () => {
+    it('should rotate the refresh token and reject the old one', async () => {
+      const first = await api()
+        .post('/auth/refresh')
+        .send({ refresh_token: refreshToken });
+
+      expect(first.status).toBe(200);
+      expect(first.body.refresh_token).not.toBe(refreshToken);
+
+      const replay = await api()
+        .post('/auth/refresh')
+        .send({ refresh_token: refreshToken });
+
+      expect(replay.status).toBe(401);
+      refreshToken = first.body.refresh_token;
+    });
+  });
+
+  describe('DELETE /auth/devices/:device_id', () => {
+    it('should revoke the device and its sessions', async () => {
+      const list = await api()
+        .get('/auth/devices')
+        .set('Authorization', `Bearer ${accessToken}`);
+
+      expect(list.status).toBe(200);
+      expect(list.body.total).toBeGreaterThan(0);
+
+      const deviceId = list.body.devices[0].id;
+      const res = await api()
+        .delete(`/auth/devices/${deviceId}`)
+        .set('Authorization', `Bearer ${accessToken}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.revoked_count).toBeGreaterThan(0);
+
+      const refresh = await api()
+        .post('/auth/refresh')
+        .send({ refresh_token: refreshToken });
+
+      expect(refresh.status).toBe(401);
+    });
+  });
+});
@@ -0,0 +1,126 @@
+const { api, createTestUser, generateEmail } = require('./helpers');
+
+describe('Email Code API', () => {
+  let owner;
+  let appId;
+  let appCode;
+
+  beforeAll(async () => {
+    owner = await createTestUser();
+    appCode = `email-login-${Date.now()}`;
+
+    const res = await api()
+      .post('/apps')
+      .set('Authorization', `Bearer ${owner.token}`)
+      .send({ code: appCode, name: 'Email Login App' });
+
+    appId = res.body.id;
+  });
+
+  describe('POST /auth/mfa/email/setup', () => {
+    it('should require authentication', async () => {
+      const res = await api().post('/auth/mfa/email/setup');
+
+      expect(res.status).toBe(401);
+    });
+
+    it('should require a verified email address', async () => {
+      const user = await createTestUser();
+      const res = await api()
+        .post('/auth/mfa/email/setup')
+        .set('Authorization', `Bearer ${user.token}`);
+
+      expect(res.status).toBe(403);
+      expect(res.body.error).toBe('email_not_verified');
+    });
+  });
+
+  describe('POST /auth/mfa/email/send', () => {
+    it('should reject an invalid mfa_token', async () => {
+      const res = await api()
+        .post('/auth/mfa/email/send')
+        .send({ mfa_token: 'invalid-token' });
+
+      expect(res.status).toBe(401);
+      expect(res.body.error).toBe('invalid_token');
+    });
+  });
+
+  describe('POST /auth/mfa/email/verify', () => {
+    it('should reject an invalid mfa_token', async () => {
+      const res = await api()
+        .post('/auth/mfa/email/verify')
+        .send({ mfa_token: 'invalid-token', code: '123456' });
+
+      expect(res.status).toBe(401);
+    });
+  });
+
+  describe('PUT /apps/:app_id/email-login', () => {
+    it('should let the owner enable email sign-in', async () => {
+      const res = await api()
+        .put(`/apps/${appId}/email-login`)
+        .set('Authorization', `Bearer ${owner.token}`)
+        .send({ enabled: true });
+
+      expect(res.status).toBe(200);
+      expect(res.body.email_login_enabled).toBe(true);
+
+      const methods = await api().get(`/apps/${appCode}/auth-methods`);
+      expect(methods.body.email_code.enabled).toBe(true);
+      expect(methods.body.mfa.methods).toContain('email');
+    });
+
+    it('should reject non-owners', async () => {
+      const other = await createTestUser();
+      const res = await api()
+        .put(`/apps/${appId}/email-login`)
+        .set('Authorization', `Bearer ${other.token}`)
+        .send({ enabled: true });
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('POST /auth/email-login/send', () => {
+    it('should answer the same for unknown addresses', async () => {
+      const known = await api()
+        .post('/auth/email-login/send')
+        .send({ email: owner.email, app: appCode });
+      const unknown = await api()
+        .post('/auth/email-login/send')
+        .send({ email: generateEmail(), app: appCode });
+
+      expect(known.status).toBe(200);
+      expect(unknown.status).toBe(200);
+      expect(unknown.body).toEqual(known.body);
+      expect(known.body.expires_in).toBe(600);
+    });
+
+    it('should refuse apps that have not enabled it', async () => {
+      const other = await createTestUser();
+      const code = `no-email-login-${Date.now()}`;
+      await api()
+        .post('/apps')
+        .set('Authorization', `Bearer ${other.token}`)
+        .send({ code, name: 'No Email Login' });
+
+      const res = await api()
+        .post('/auth/email-login/send')
+        .send({ email: other.email, app: code });
+
+      expect(res.status).toBe(403);
+      expect(res.body.error).toBe('email_login_disabled');
+    });
+  });
+
+  describe('POST /auth/email-login/verify', () => {
+    it('should reject a wrong code', async () => {
+      const res = await api()
+        .post('/auth/email-login/verify')
+        .send({ email: owner.email, app: appCode, code: '000000x' });
+
+      expect(res.status).toBe(401);
+    });
+  });
+});
@@ -20,4 +20,70 @@ describe('Health Check API', () => {
       expect(res.body).toHaveProperty('version');
     });
   });
+
+  describe('GET /.well-known/auth-server', () => {
+    it('should describe the server in a versioned schema', async () => {
+      const res = await api().get('/.well-known/auth-server');
+
+      expect(res.status).toBe(200);
+      expect(res.headers['cache-control']).toContain('public');
+      expect(res.body.schema_version).toBe(1);
+      expect(res.body.server).toBe('auth-server');
+      expect(res.body).toHaveProperty('version');
+      expect(res.body.jwks_uri).toMatch(/\/\.well-known\/jwks\.json$/);
+      expect(res.body.flows.sign_in).toEqual(expect.arrayContaining(['password', 'passkey']));
+      expect(res.body.flows.oauth_grant_types).toContain('authorization_code');
+      expect(res.body.token_formats.access_token).toEqual({ format: 'jwt', signing_alg_values: ['RS256'] });
+      expect(res.body.token_formats.refresh_token).toEqual({ format: 'jwt', signing_alg_values: ['RS256'] });
+      expect(res.body.token_formats.oauth_refresh_token).toEqual({ format: 'opaque' });
+      expect(typeof res.body.features.oauth_strict).toBe('boolean');
+      expect(res.body.features).not.toHaveProperty('metrics');
+      expect(res.body.features).not.toHaveProperty('chaos');
+    });
+  });
+
+  // Needs a server started with the same METRICS_TOKEN
+  const describeMetrics = process.env.METRICS_TOKEN ? describe : describe.skip;
+
+  describeMetrics('GET /metrics', () => {
+    const token = process.env.METRICS_TOKEN;
+
+    it('should require the metrics token', async () => {
+      const res = await api().get('/metrics').set('Authorization', 'Bearer wrong-token');
+
+      expect(res.status).toBe(401);
+    });
+
+    it('should count sign-ins per app in OpenMetrics format', async () => {
+      const app = `metrics-${Date.now()}`;
+      await api().post('/auth/login').send({ email: 'nobody@example.com', password: 'wrong', app });
+
+      const res = await api().get('/metrics').set('Authorization', `Bearer ${token}`);
+
+      expect(res.status).toBe(200);
+      expect(res.headers['content-type']).toContain('application/openmetrics-text');
+      expect(res.text).toContain('# TYPE auth_server_app_sign_ins counter');
+      expect(res.text.trimEnd().endsWith('# EOF')).toBe(true);
+    });
+  });
+
+  describe('X-Request-Id', () => {
+    it('should echo a supplied request id', async () => {
+      const res = await api().get('/health').set('X-Request-Id', 'health-trace-1');
+
+      expect(res.headers['x-request-id']).toBe('health-trace-1');
+    });
+
+    it('should generate a request id when none is supplied', async () => {
+      const res = await api().get('/health');
+
+      expect(res.headers['x-request-id']).toMatch(/^[0-9a-f-]{36}$/);
+    });
+
+    it('should replace a malformed request id', async () => {
+      const res = await api().get('/health').set('X-Request-Id', 'not valid!');
+
+      expect(res.headers['x-request-id']).not.toBe('not valid!');
+    });
+  });
 });
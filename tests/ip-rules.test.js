@@ -117,6 +117,42 @@ describe('IP Rules API', () => {
         expect(res.status).toBe(204);
       });
     });
+
+    describe('GET /admin/ip-rules/suggestions', () => {
+      it('should list pending suggestions', async () => {
+        const res = await api()
+          .get('/admin/ip-rules/suggestions')
+          .set('Authorization', `Bearer ${adminToken}`);
+
+        expect(res.status).toBe(200);
+        expect(Array.isArray(res.body)).toBe(true);
+        res.body.forEach((s) => expect(s.status).toBe('pending'));
+      });
+
+      it('should reject an unknown status filter', async () => {
+        const res = await api()
+          .get('/admin/ip-rules/suggestions?status=bogus')
+          .set('Authorization', `Bearer ${adminToken}`);
+
+        expect(res.status).toBe(400);
+      });
+
+      it('should reject non-admin users', async () => {
+        const res = await api()
+          .get('/admin/ip-rules/suggestions')
+          .set('Authorization', `Bearer ${userToken}`);
+
+        expect(res.status).toBe(403);
+      });
+
+      it('should return 404 when approving an unknown suggestion', async () => {
+        const res = await api()
+          .post('/admin/ip-rules/suggestions/00000000-0000-0000-0000-000000000000/approve')
+          .set('Authorization', `Bearer ${adminToken}`);
+
+        expect(res.status).toBe(404);
+      });
+    });
   });
 
   describe('App-level IP Rules', () => {
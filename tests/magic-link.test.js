@@ -0,0 +1,68 @@
+const { api, createTestUser, generateEmail } = require('./helpers');
+
+describe('Magic Link API', () => {
+  let owner;
+  let appCode;
+
+  beforeAll(async () => {
+    owner = await createTestUser();
+    appCode = `magic-link-${Date.now()}`;
+
+    const res = await api()
+      .post('/apps')
+      .set('Authorization', `Bearer ${owner.token}`)
+      .send({ code: appCode, name: 'Magic Link App' });
+
+    await api()
+      .put(`/apps/${res.body.id}/email-login`)
+      .set('Authorization', `Bearer ${owner.token}`)
+      .send({ enabled: true });
+  });
+
+  describe('POST /auth/magic-link', () => {
+    it('should answer the same for unknown addresses', async () => {
+      const known = await api()
+        .post('/auth/magic-link')
+        .send({ email: owner.email, app: appCode });
+      const unknown = await api()
+        .post('/auth/magic-link')
+        .send({ email: generateEmail(), app: appCode });
+
+      expect(known.status).toBe(200);
+      expect(unknown.status).toBe(200);
+      expect(unknown.body).toEqual(known.body);
+      expect(known.body.expires_in).toBe(900);
+    });
+
+    it('should refuse apps that have not enabled email sign-in', async () => {
+      const other = await createTestUser();
+      const code = `no-magic-link-${Date.now()}`;
+      await api()
+        .post('/apps')
+        .set('Authorization', `Bearer ${other.token}`)
+        .send({ code, name: 'No Magic Link' });
+
+      const res = await api()
+        .post('/auth/magic-link')
+        .send({ email: other.email, app: code });
+
+      expect(res.status).toBe(403);
+      expect(res.body.error).toBe('email_login_disabled');
+    });
+  });
+
+  describe('GET /auth/magic-link/verify', () => {
+    it('should reject an unknown token', async () => {
+      const res = await api().get('/auth/magic-link/verify?token=not-a-real-token');
+
+      expect(res.status).toBe(401);
+      expect(res.body.error).toBe('invalid_token');
+    });
+
+    it('should require a token', async () => {
+      const res = await api().get('/auth/magic-link/verify');
+
+      expect(res.status).toBe(400);
+    });
+  });
+});
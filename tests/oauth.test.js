@@ -0,0 +1,1548 @@
+const crypto = require('crypto');
+const { api, generateEmail, generatePassword, registerUser, login } = require('./helpers');
+
+describe('OAuth Authorization API', () => {
+  let accessToken;
+  let userId;
+
+  beforeAll(async () => {
+    const email = generateEmail();
+    const password = generatePassword();
+    await registerUser(email, password);
+    const res = await login(email, password);
+    accessToken = res.body.access_token;
+
+    const me = await api()
+      .get('/users/me')
+      .set('Authorization', `Bearer ${accessToken}`);
+    userId = me.body.id;
+  });
+
+  function consentParams(overrides = {}) {
+    return {
+      approved: true,
+      client_id: 'unknown-client',
+      user_id: userId,
+      redirect_uri: 'https://example.com/callback',
+      scopes: 'openid',
+      state: 'xyz',
+      code_challenge: 'E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM',
+      code_challenge_method: 'S256',
+      ...overrides,
+    };
+  }
+
+  describe('POST /oauth/authorize/callback', () => {
+    let clientId;
+    let clientUuid;
+    let clientSecret;
+
+    beforeAll(async () => {
+      const created = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ name: 'Callback Client', redirect_uris: ['https://example.com/callback'] });
+      clientId = created.body.client_id;
+      clientUuid = created.body.id;
+      clientSecret = created.body.client_secret;
+    });
+
+    function authorize(scope, extra = {}, token = accessToken) {
+      const req = api().get('/oauth/authorize');
+      if (token) req.set('Authorization', `Bearer ${token}`);
+      return req.query({
+        response_type: 'code',
+        client_id: clientId,
+        redirect_uri: 'https://example.com/callback',
+        scope,
+        state: 'xyz-state-value-123456',
+        code_challenge: 'E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM',
+        code_challenge_method: 'S256',
+        ...extra,
+      });
+    }
+
+    it('should not redirect when the client is unknown', async () => {
+      const res = await api()
+        .post('/oauth/authorize/callback')
+        .send(consentParams({ redirect_uri: 'https://evil.example.com/' }));
+
+      expect(res.status).toBe(401);
+      expect(res.body.error).toBe('invalid_client');
+      expect(res.body.redirect_url).toBeUndefined();
+    });
+
+    it('should not redirect to an unregistered redirect_uri', async () => {
+      const res = await api()
+        .post('/oauth/authorize/callback')
+        .send(consentParams({ client_id: clientId, redirect_uri: 'https://evil.example.com/' }));
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+      expect(res.body.redirect_url).toBeUndefined();
+    });
+
+    it('should require a session token', async () => {
+      const res = await api()
+        .post('/oauth/authorize/callback')
+        .send(consentParams({ client_id: clientId }));
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('login_required');
+      expect(res.body.redirect_url).toContain('state=xyz');
+    });
+
+    it('should reject a session belonging to another user', async () => {
+      const res = await api()
+        .post('/oauth/authorize/callback')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send(consentParams({ client_id: clientId, user_id: crypto.randomUUID() }));
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('login_required');
+    });
+
+    it('should reject approved scopes that were not requested', async () => {
+      const res = await api()
+        .post('/oauth/authorize/callback')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send(consentParams({ client_id: clientId, scopes: 'openid', approved_scopes: 'openid,email' }));
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_scope');
+    });
+
+    it('should store only the approved subset of scopes', async () => {
+      const res = await api()
+        .post('/oauth/authorize/callback')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send(consentParams({ client_id: clientId, scopes: 'openid,profile,email', approved_scopes: 'openid,email' }));
+
+      expect(res.status).toBe(200);
+      expect(res.body.redirect_url).toContain('code=');
+
+      const connected = await api()
+        .get('/account/connected-apps')
+        .set('Authorization', `Bearer ${accessToken}`);
+      const app = connected.body.apps.find((a) => a.client_id === clientId);
+      expect(app.scopes.sort()).toEqual(['email', 'openid']);
+    });
+
+    it('should not prompt again for scopes already granted', async () => {
+      const res = await authorize('openid email');
+
+      expect(res.status).toBe(200);
+      expect(res.body.new_scopes).toEqual([]);
+      expect(res.body.prompt_consent).toBe(false);
+      expect(res.body.consent_expired).toBe(false);
+    });
+
+    it('should prompt only for scopes not granted before', async () => {
+      const res = await authorize('openid email profile');
+
+      expect(res.status).toBe(200);
+      expect(res.body.new_scopes).toEqual(['profile']);
+      expect(res.body.prompt_consent).toBe(true);
+    });
+
+    it('should always prompt when the client asks to', async () => {
+      const updated = await api()
+        .put(`/oauth/clients/${clientUuid}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ always_prompt_consent: true });
+      expect(updated.status).toBe(200);
+      expect(updated.body.always_prompt_consent).toBe(true);
+
+      const res = await authorize('openid email');
+      expect(res.body.new_scopes).toEqual([]);
+      expect(res.body.prompt_consent).toBe(true);
+
+      await api()
+        .put(`/oauth/clients/${clientUuid}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ always_prompt_consent: false });
+    });
+
+    it('should prompt for consent when the request asks to', async () => {
+      const res = await authorize('openid email', { prompt: 'consent' });
+
+      expect(res.status).toBe(200);
+      expect(res.body.new_scopes).toEqual([]);
+      expect(res.body.prompt_consent).toBe(true);
+    });
+
+    it('should issue a code without any screen for prompt=none', async () => {
+      const res = await authorize('openid email', { prompt: 'none' });
+
+      expect(res.status).toBe(200);
+      expect(res.body.status).toBe('success');
+      expect(res.body.redirect_url).toContain('code=');
+      expect(res.body.redirect_url).toContain('state=xyz-state-value-123456');
+    });
+
+    it('should only redeem a code from the browser that approved it', async () => {
+      const res = await authorize('openid email', { prompt: 'none' });
+      const browserCookie = res.headers['set-cookie'].find((c) => c.startsWith('oauth_browser='));
+      expect(browserCookie).toContain('HttpOnly');
+      const code = new URL(res.body.redirect_url).searchParams.get('code');
+
+      function exchange(cookie) {
+        return api()
+          .post('/oauth/token')
+          .set('Cookie', cookie)
+          .send({
+            grant_type: 'authorization_code',
+            client_id: clientId,
+            client_secret: clientSecret,
+            code,
+            redirect_uri: 'https://example.com/callback',
+            code_verifier: 'dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk',
+          });
+      }
+
+      const injected = await exchange('oauth_browser=attacker-browser');
+      expect(injected.status).toBe(400);
+      expect(injected.body.error).toBe('invalid_grant');
+
+      const redeemed = await exchange(browserCookie.split(';')[0]);
+      expect(redeemed.status).toBe(200);
+      expect(redeemed.body.access_token).toBeDefined();
+    });
+
+    it('should require the browser cookie when a cookie-delivery client redeems a code', async () => {
+      await api()
+        .put(`/oauth/clients/${clientUuid}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ refresh_token_cookie: true });
+
+      const res = await authorize('openid email', { prompt: 'none' });
+      const code = new URL(res.body.redirect_url).searchParams.get('code');
+
+      const token = await api()
+        .post('/oauth/token')
+        .send({
+          grant_type: 'authorization_code',
+          client_id: clientId,
+          client_secret: clientSecret,
+          code,
+          redirect_uri: 'https://example.com/callback',
+          code_verifier: 'dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk',
+        });
+      expect(token.status).toBe(400);
+      expect(token.body.error).toBe('invalid_grant');
+
+      await api()
+        .put(`/oauth/clients/${clientUuid}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ refresh_token_cookie: false });
+    });
+
+    it('should put the sign-in posture in access tokens of clients that opt in', async () => {
+      const updated = await api()
+        .put(`/oauth/clients/${clientUuid}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ posture_claims: true });
+      expect(updated.status).toBe(200);
+      expect(updated.body.posture_claims).toBe(true);
+
+      const res = await authorize('openid email', { prompt: 'none' });
+      const browserCookie = res.headers['set-cookie'].find((c) => c.startsWith('oauth_browser='));
+      const code = new URL(res.body.redirect_url).searchParams.get('code');
+
+      const token = await api()
+        .post('/oauth/token')
+        .set('Cookie', browserCookie.split(';')[0])
+        .send({
+          grant_type: 'authorization_code',
+          client_id: clientId,
+          client_secret: clientSecret,
+          code,
+          redirect_uri: 'https://example.com/callback',
+          code_verifier: 'dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk',
+        });
+      expect(token.status).toBe(200);
+
+      const payload = JSON.parse(Buffer.from(token.body.access_token.split('.')[1], 'base64url').toString());
+      expect(payload.azp_ctx.device_trust).toBe('unknown');
+      expect(payload.azp_ctx.mfa_level).toBe('pwd');
+      expect(typeof payload.azp_ctx.network_zone).toBe('string');
+
+      await api()
+        .put(`/oauth/clients/${clientUuid}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ posture_claims: false });
+    });
+
+    it('should fail prompt=none without a session', async () => {
+      const res = await authorize('openid email', { prompt: 'none' }, null);
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('login_required');
+    });
+
+    it('should fail prompt=none when consent is needed', async () => {
+      const res = await authorize('openid email profile', { prompt: 'none' });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('consent_required');
+    });
+
+    it('should reject prompt=none combined with other values', async () => {
+      const res = await authorize('openid', { prompt: 'none login' });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
+
+    it('should ask for a new sign-in when the session is older than max_age', async () => {
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      const res = await authorize('openid', { max_age: 1, login_hint: 'someone@example.com' });
+
+      expect(res.status).toBe(200);
+      expect(res.body.status).toBe('login_required');
+      expect(res.body.reason).toBe('max_age');
+      expect(res.body.login_hint).toBe('someone@example.com');
+    });
+
+    it('should accept a session within max_age', async () => {
+      const res = await authorize('openid', { max_age: 3600 });
+
+      expect(res.status).toBe(200);
+      expect(res.body.status).toBe('consent_required');
+    });
+
+    it('should ask for a stronger sign-in when the client requires it', async () => {
+      const updated = await api()
+        .put(`/oauth/clients/${clientUuid}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ required_acr: 'mfa' });
+      expect(updated.status).toBe(200);
+      expect(updated.body.required_acr).toBe('mfa');
+
+      const res = await authorize('openid');
+
+      expect(res.status).toBe(200);
+      expect(res.body.status).toBe('login_required');
+      expect(res.body.reason).toBe('acr');
+      expect(res.body.required_acr).toBe('mfa');
+
+      const callback = await api()
+        .post('/oauth/authorize/callback')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send(consentParams({ client_id: clientId, approved_scopes: 'openid' }));
+      expect(callback.status).toBe(400);
+      expect(callback.body.error).toBe('login_required');
+
+      const cleared = await api()
+        .put(`/oauth/clients/${clientUuid}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ required_acr: '' });
+      expect(cleared.body.required_acr).toBeNull();
+    });
+
+    it('should reject an unknown required_acr', async () => {
+      const res = await api()
+        .put(`/oauth/clients/${clientUuid}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ required_acr: 'loa3' });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
+
+    it('should treat approving no scopes as a denial', async () => {
+      const res = await api()
+        .post('/oauth/authorize/callback')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send(consentParams({ client_id: clientId, approved_scopes: '' }));
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('access_denied');
+    });
+  });
+
+  describe('PUT /admin/oauth-clients/:client_id/skip-consent', () => {
+    it('should reject non-admin users', async () => {
+      const res = await api()
+        .put('/admin/oauth-clients/unknown-client/skip-consent')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ skip_consent: true });
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('PUT /admin/oauth-clients/:client_id/secret-policy', () => {
+    it('should reject non-admin users', async () => {
+      const res = await api()
+        .put('/admin/oauth-clients/unknown-client/secret-policy')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ max_age_days: 90 });
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('/oauth/clients/:id/secret', () => {
+    it('should record rotations in the secret history', async () => {
+      const created = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ name: 'Rotation Client', redirect_uris: ['https://example.com/callback'] });
+      const list = await api()
+        .get('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`);
+      const client = list.body.clients.find((c) => c.client_id === created.body.client_id);
+      expect(client.secret_created_at).toBeDefined();
+
+      const rotated = await api()
+        .post(`/oauth/clients/${client.id}/secret`)
+        .set('Authorization', `Bearer ${accessToken}`);
+      expect(rotated.status).toBe(200);
+
+      const res = await api()
+        .get(`/oauth/clients/${client.id}/secret/rotations`)
+        .set('Authorization', `Bearer ${accessToken}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.rotations.length).toBe(1);
+    });
+
+    describe('POST /oauth/clients/:id/secret/rotate', () => {
+      let created;
+      let client;
+
+      function introspectWith(secret) {
+        return api()
+          .post('/oauth/introspect')
+          .type('form')
+          .send({ token: 'whatever', client_id: created.client_id, client_secret: secret });
+      }
+
+      beforeAll(async () => {
+        const res = await api()
+          .post('/oauth/clients')
+          .set('Authorization', `Bearer ${accessToken}`)
+          .send({ name: 'Overlap Client', redirect_uris: ['https://example.com/callback'] });
+        created = res.body;
+        const list = await api()
+          .get('/oauth/clients')
+          .set('Authorization', `Bearer ${accessToken}`);
+        client = list.body.clients.find((c) => c.client_id === created.client_id);
+      });
+
+      it('should keep the old secret valid during the grace period', async () => {
+        const rotated = await api()
+          .post(`/oauth/clients/${client.id}/secret/rotate`)
+          .set('Authorization', `Bearer ${accessToken}`)
+          .send({ grace_period_secs: 3600 });
+
+        expect(rotated.status).toBe(200);
+        expect(rotated.body.client_secret).not.toBe(created.client_secret);
+        expect(new Date(rotated.body.previous_secret_expires_at).getTime()).toBeGreaterThan(Date.now());
+
+        expect((await introspectWith(created.client_secret)).status).toBe(200);
+        expect((await introspectWith(rotated.body.client_secret)).status).toBe(200);
+        expect((await introspectWith('wrong')).status).toBe(401);
+
+        const history = await api()
+          .get(`/oauth/clients/${client.id}/secret/rotations`)
+          .set('Authorization', `Bearer ${accessToken}`);
+        expect(history.body.rotations[0].previous_secret_valid_until).toBeTruthy();
+      });
+
+      it('should invalidate overlapping secrets on a plain regeneration', async () => {
+        const regenerated = await api()
+          .post(`/oauth/clients/${client.id}/secret`)
+          .set('Authorization', `Bearer ${accessToken}`);
+        expect(regenerated.status).toBe(200);
+
+        expect((await introspectWith(created.client_secret)).status).toBe(401);
+        expect((await introspectWith(regenerated.body.client_secret)).status).toBe(200);
+      });
+
+      it('should reject an out-of-range grace period', async () => {
+        const res = await api()
+          .post(`/oauth/clients/${client.id}/secret/rotate`)
+          .set('Authorization', `Bearer ${accessToken}`)
+          .send({ grace_period_secs: 0 });
+
+        expect(res.status).toBe(400);
+        expect(res.body.error).toBe('invalid_request');
+      });
+    });
+  });
+
+  describe('POST /oauth/clients', () => {
+    it.each([
+      ['a wildcard', 'https://*.example.com/callback'],
+      ['a fragment', 'https://example.com/callback#done'],
+      ['an open redirect parameter', 'https://example.com/callback?next=https://evil.com'],
+      ['a raw IP host', 'https://203.0.113.5/callback'],
+    ])('should reject a redirect URI with %s', async (_, uri) => {
+      const res = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ name: 'Policy Test Client', redirect_uris: [uri] });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
+  });
+
+  describe('POST /oauth/clients (grant_types)', () => {
+    let client;
+
+    beforeAll(async () => {
+      const res = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({
+          name: 'M2M Only Client',
+          redirect_uris: ['https://example.com/callback'],
+          grant_types: ['client_credentials'],
+        });
+      expect(res.status).toBe(201);
+      client = res.body;
+    });
+
+    it('should return the registered grant types', () => {
+      expect(client.grant_types).toEqual(['client_credentials']);
+    });
+
+    it('should allow a registered grant', async () => {
+      const res = await api()
+        .post('/oauth/token')
+        .send({ grant_type: 'client_credentials', client_id: client.client_id, client_secret: client.client_secret });
+
+      expect(res.status).toBe(200);
+    });
+
+    it('should reject a grant the client is not registered for', async () => {
+      const res = await api()
+        .post('/oauth/token')
+        .send({
+          grant_type: 'authorization_code',
+          client_id: client.client_id,
+          client_secret: client.client_secret,
+          code: 'unused',
+          redirect_uri: 'https://example.com/callback',
+          code_verifier: 'a'.repeat(43),
+        });
+
+      expect(res.status).toBe(401);
+      expect(res.body.error).toBe('unauthorized_client');
+    });
+
+    it('should reject an unknown grant type at registration', async () => {
+      const res = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ name: 'Bad Grants', redirect_uris: ['https://example.com/callback'], grant_types: ['password'] });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
+
+    it('should lift the restriction with an empty list', async () => {
+      const list = await api()
+        .get('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`);
+      const listed = list.body.clients.find((c) => c.client_id === client.client_id);
+      expect(listed.grant_types).toEqual(['client_credentials']);
+
+      const res = await api()
+        .put(`/oauth/clients/${listed.id}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ grant_types: [] });
+
+      expect(res.status).toBe(200);
+      expect(res.body.grant_types).toBeNull();
+    });
+  });
+
+  describe('PUT /oauth/clients/:id (access_token_ttl_secs)', () => {
+    let client;
+    let listed;
+
+    beforeAll(async () => {
+      const res = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({
+          name: 'Short Token Client',
+          redirect_uris: ['https://example.com/callback'],
+          grant_types: ['client_credentials'],
+        });
+      client = res.body;
+
+      const list = await api()
+        .get('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`);
+      listed = list.body.clients.find((c) => c.client_id === client.client_id);
+      expect(listed.access_token_ttl_secs).toBeNull();
+    });
+
+    it('should issue tokens with the client lifetime', async () => {
+      const update = await api()
+        .put(`/oauth/clients/${listed.id}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ access_token_ttl_secs: 120 });
+      expect(update.status).toBe(200);
+      expect(update.body.access_token_ttl_secs).toBe(120);
+
+      const res = await api()
+        .post('/oauth/token')
+        .send({ grant_type: 'client_credentials', client_id: client.client_id, client_secret: client.client_secret });
+
+      expect(res.status).toBe(200);
+      expect(res.body.expires_in).toBe(120);
+    });
+
+    it('should reject a lifetime above the server maximum', async () => {
+      const res = await api()
+        .put(`/oauth/clients/${listed.id}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ access_token_ttl_secs: 10 * 365 * 24 * 3600 });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
+
+    it('should restore the default with 0', async () => {
+      const res = await api()
+        .put(`/oauth/clients/${listed.id}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ access_token_ttl_secs: 0 });
+
+      expect(res.status).toBe(200);
+      expect(res.body.access_token_ttl_secs).toBeNull();
+    });
+  });
+
+  describe('POST /oauth/clients (userinfo_signed_response_alg)', () => {
+    it('should return the registered algorithm', async () => {
+      const res = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({
+          name: 'Signed Userinfo Client',
+          redirect_uris: ['https://example.com/callback'],
+          userinfo_signed_response_alg: 'RS256',
+        });
+
+      expect(res.status).toBe(201);
+      expect(res.body.userinfo_signed_response_alg).toBe('RS256');
+    });
+
+    it('should reject an unsupported algorithm', async () => {
+      const res = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({
+          name: 'Unsigned Userinfo Client',
+          redirect_uris: ['https://example.com/callback'],
+          userinfo_signed_response_alg: 'none',
+        });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
+  });
+
+  describe('/oauth/register/:client_id', () => {
+    let client;
+
+    beforeAll(async () => {
+      const res = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ name: 'Managed Client', redirect_uris: ['https://example.com/callback'] });
+      expect(res.status).toBe(201);
+      client = res.body;
+    });
+
+    it('should return a registration access token and client URI', () => {
+      expect(client.registration_access_token).toBeTruthy();
+      expect(client.registration_client_uri).toMatch(new RegExp(`/oauth/register/${client.client_id}$`));
+    });
+
+    it('should reject a missing or wrong registration access token', async () => {
+      const missing = await api().get(`/oauth/register/${client.client_id}`);
+      expect(missing.status).toBe(401);
+
+      const wrong = await api()
+        .get(`/oauth/register/${client.client_id}`)
+        .set('Authorization', `Bearer ${accessToken}`);
+      expect(wrong.status).toBe(401);
+      expect(wrong.body.error).toBe('invalid_client');
+    });
+
+    it('should read and update the client configuration', async () => {
+      const read = await api()
+        .get(`/oauth/register/${client.client_id}`)
+        .set('Authorization', `Bearer ${client.registration_access_token}`);
+      expect(read.status).toBe(200);
+      expect(read.body.client_name).toBe('Managed Client');
+
+      const updated = await api()
+        .put(`/oauth/register/${client.client_id}`)
+        .set('Authorization', `Bearer ${client.registration_access_token}`)
+        .send({
+          client_id: client.client_id,
+          client_name: 'Managed Client v2',
+          redirect_uris: ['https://example.com/callback2'],
+          scope: 'openid',
+          is_active: false,
+        });
+      expect(updated.status).toBe(200);
+      expect(updated.body.client_name).toBe('Managed Client v2');
+      expect(updated.body.redirect_uris).toEqual(['https://example.com/callback2']);
+      expect(updated.body.scope).toBe('openid');
+      expect(updated.body.is_active).toBe(false);
+    });
+
+    it('should reject a client_id that does not match the path', async () => {
+      const res = await api()
+        .put(`/oauth/register/${client.client_id}`)
+        .set('Authorization', `Bearer ${client.registration_access_token}`)
+        .send({ client_id: 'other', redirect_uris: ['https://example.com/callback'] });
+
+      expect(res.status).toBe(400);
+    });
+
+    it('should delete the client', async () => {
+      const res = await api()
+        .delete(`/oauth/register/${client.client_id}`)
+        .set('Authorization', `Bearer ${client.registration_access_token}`);
+      expect(res.status).toBe(204);
+
+      const after = await api()
+        .get(`/oauth/register/${client.client_id}`)
+        .set('Authorization', `Bearer ${client.registration_access_token}`);
+      expect(after.status).toBe(401);
+    });
+  });
+
+  describe('POST /oauth/clients (native)', () => {
+    it('should accept loopback and reverse-domain redirects for native apps', async () => {
+      const res = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({
+          name: 'Native Test Client',
+          client_type: 'native',
+          redirect_uris: ['http://127.0.0.1/callback', 'com.example.cli:/callback'],
+        });
+
+      expect(res.status).toBe(201);
+      expect(res.body.client_type).toBe('native');
+      expect(res.body.is_internal).toBe(false);
+    });
+
+    it('should reject a bare custom scheme for native apps', async () => {
+      const res = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({
+          name: 'Native Test Client',
+          client_type: 'native',
+          redirect_uris: ['myapp:/callback'],
+        });
+
+      expect(res.status).toBe(400);
+    });
+
+    it('should reject an unknown client_type', async () => {
+      const res = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({
+          name: 'Bad Type Client',
+          client_type: 'daemon',
+          redirect_uris: ['https://example.com/callback'],
+        });
+
+      expect(res.status).toBe(400);
+    });
+  });
+
+  describe('/oauth/clients/:id/scopes', () => {
+    const namespace = `ns${Date.now()}`;
+    let owner;
+    let other;
+    let scopeId;
+
+    async function registerClient(name) {
+      const res = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ name, redirect_uris: ['https://example.com/callback'] });
+      const list = await api()
+        .get('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`);
+      return list.body.clients.find((c) => c.client_id === res.body.client_id);
+    }
+
+    function authorize(clientId, scope) {
+      return api()
+        .get('/oauth/authorize')
+        .query({
+          response_type: 'code',
+          client_id: clientId,
+          redirect_uri: 'https://example.com/callback',
+          scope,
+          state: 'xyz-state-value-123456',
+          code_challenge: 'E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM',
+          code_challenge_method: 'S256',
+        });
+    }
+
+    beforeAll(async () => {
+      owner = await registerClient('Scope Owner Client');
+      other = await registerClient('Other Client');
+    });
+
+    it('should create a private namespaced scope', async () => {
+      const res = await api()
+        .post(`/oauth/clients/${owner.id}/scopes`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ code: `${namespace}:orders.read`, description: 'Read your orders' });
+
+      expect(res.status).toBe(201);
+      expect(res.body.visibility).toBe('private');
+      scopeId = res.body.id;
+    });
+
+    it('should map a custom scope to userinfo claims', async () => {
+      const res = await api()
+        .post(`/oauth/clients/${owner.id}/scopes`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({
+          code: `${namespace}:contact`,
+          description: 'See your name and phone number',
+          claims: ['name', 'phone_number'],
+        });
+
+      expect(res.status).toBe(201);
+      expect(res.body.claims).toEqual(['name', 'phone_number']);
+    });
+
+    it('should reject unsupported claims', async () => {
+      const res = await api()
+        .post(`/oauth/clients/${owner.id}/scopes`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({
+          code: `${namespace}:secrets`,
+          description: 'Read secrets',
+          claims: ['password_hash'],
+        });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_scope');
+    });
+
+    it('should ask for every scope when nothing was granted before', async () => {
+      const res = await authorize(owner.client_id, `openid ${namespace}:contact`)
+        .set('Authorization', `Bearer ${accessToken}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.granted_scopes).toEqual([]);
+      expect(res.body.new_scopes).toEqual(['openid', `${namespace}:contact`]);
+    });
+
+    it('should reject scopes without a namespace', async () => {
+      const res = await api()
+        .post(`/oauth/clients/${owner.id}/scopes`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ code: 'orders.read', description: 'Read your orders' });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_scope');
+    });
+
+    it('should reject a namespace owned by another client', async () => {
+      const res = await api()
+        .post(`/oauth/clients/${other.id}/scopes`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ code: `${namespace}:orders.write`, description: 'Change your orders' });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_scope');
+    });
+
+    it('should list the client scopes', async () => {
+      const res = await api()
+        .get(`/oauth/clients/${owner.id}/scopes`)
+        .set('Authorization', `Bearer ${accessToken}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.scopes.map((s) => s.code)).toContain(`${namespace}:orders.read`);
+    });
+
+    it('should show scope descriptions when the owner requests its scope', async () => {
+      const res = await authorize(owner.client_id, `openid ${namespace}:orders.read`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.scope_details).toContainEqual({
+        code: `${namespace}:orders.read`,
+        description: 'Read your orders',
+      });
+    });
+
+    it('should not let another client request a private scope', async () => {
+      const res = await authorize(other.client_id, `${namespace}:orders.read`);
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_scope');
+    });
+
+    it('should mark a global request as pending approval', async () => {
+      const res = await api()
+        .put(`/oauth/clients/${owner.id}/scopes/${scopeId}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ global: true });
+
+      expect(res.status).toBe(200);
+      expect(res.body.visibility).toBe('pending');
+    });
+
+    it('should not let non-admins approve global scopes', async () => {
+      const res = await api()
+        .post(`/admin/scopes/${scopeId}/approve`)
+        .set('Authorization', `Bearer ${accessToken}`);
+
+      expect(res.status).toBe(403);
+    });
+
+    it('should delete a client scope', async () => {
+      const res = await api()
+        .delete(`/oauth/clients/${owner.id}/scopes/${scopeId}`)
+        .set('Authorization', `Bearer ${accessToken}`);
+
+      expect(res.status).toBe(204);
+    });
+  });
+
+  describe('/admin/redirect-uri-blocklist', () => {
+    it('should reject non-admin users', async () => {
+      const res = await api()
+        .post('/admin/redirect-uri-blocklist')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ pattern: 'evil.example' });
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('GET /.well-known/openid-configuration', () => {
+    it('should advertise RS256 id_token signing', async () => {
+      const res = await api().get('/.well-known/openid-configuration');
+
+      expect(res.status).toBe(200);
+      expect(res.body.id_token_signing_alg_values_supported).toContain('RS256');
+      expect(res.body.jwks_uri).toMatch(/\/\.well-known\/jwks\.json$/);
+      expect(res.body.introspection_endpoint).toMatch(/\/oauth\/introspect$/);
+      expect(res.body.pushed_authorization_request_endpoint).toMatch(/\/oauth\/par$/);
+      expect(res.body.request_parameter_supported).toBe(true);
+      expect(res.body.request_uri_parameter_supported).toBe(true);
+      expect(res.body.require_request_uri_registration).toBe(true);
+      expect(res.body.request_object_signing_alg_values_supported).toContain('RS256');
+      expect(res.body.grant_types_supported).toContain('urn:ietf:params:oauth:grant-type:device_code');
+      expect(res.body.end_session_endpoint).toMatch(/\/oauth\/logout$/);
+      expect(res.body.backchannel_logout_supported).toBe(true);
+      expect(res.body.acr_values_supported).toEqual(['pwd', 'mfa', 'phr']);
+      expect(res.body.prompt_values_supported).toContain('none');
+      expect(res.body.userinfo_signing_alg_values_supported).toEqual(['RS256']);
+      expect(res.body.claims_supported).toEqual(expect.arrayContaining(['locale', 'updated_at']));
+    });
+  });
+
+  describe('GET /oauth/logout', () => {
+    let client;
+
+    beforeAll(async () => {
+      const res = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({
+          name: 'Logout Test Client',
+          redirect_uris: ['https://example.com/callback'],
+          post_logout_redirect_uris: ['https://example.com/signed-out'],
+          backchannel_logout_uri: 'https://example.com/backchannel-logout',
+        });
+      expect(res.status).toBe(201);
+      client = res.body;
+    });
+
+    it('should return the registered logout URIs', () => {
+      expect(client.post_logout_redirect_uris).toEqual(['https://example.com/signed-out']);
+      expect(client.backchannel_logout_uri).toBe('https://example.com/backchannel-logout');
+    });
+
+    it('should reject a backchannel_logout_uri with a fragment', async () => {
+      const res = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({
+          name: 'Logout Test Client',
+          redirect_uris: ['https://example.com/callback'],
+          backchannel_logout_uri: 'https://example.com/logout#frag',
+        });
+
+      expect(res.status).toBe(400);
+    });
+
+    it('should redirect to a registered post_logout_redirect_uri with state', async () => {
+      const res = await api()
+        .get('/oauth/logout')
+        .query({
+          client_id: client.client_id,
+          post_logout_redirect_uri: 'https://example.com/signed-out',
+          state: 'abc',
+        });
+
+      expect(res.status).toBe(303);
+      expect(res.headers.location).toBe('https://example.com/signed-out?state=abc');
+    });
+
+    it('should not redirect to an unregistered post_logout_redirect_uri', async () => {
+      const res = await api()
+        .get('/oauth/logout')
+        .query({ client_id: client.client_id, post_logout_redirect_uri: 'https://evil.com/' });
+
+      expect(res.status).toBe(400);
+      expect(res.headers.location).toBeUndefined();
+    });
+
+    it('should reject an invalid id_token_hint', async () => {
+      const res = await api().get('/oauth/logout').query({ id_token_hint: accessToken });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
+  });
+
+  describe('GET /.well-known/jwks.json', () => {
+    it('should publish the key that signs access tokens', async () => {
+      const res = await api().get('/.well-known/jwks.json');
+
+      expect(res.status).toBe(200);
+      expect(res.headers['cache-control']).toMatch(/max-age=\d+/);
+
+      const header = JSON.parse(Buffer.from(accessToken.split('.')[0], 'base64url').toString());
+      const key = res.body.keys.find((k) => k.kid === header.kid);
+      expect(key).toMatchObject({ kty: 'RSA', use: 'sig', alg: 'RS256' });
+    });
+  });
+
+  describe('GET /admin/oauth/stats', () => {
+    it('should reject non-admin users', async () => {
+      const res = await api()
+        .get('/admin/oauth/stats?window=7d')
+        .set('Authorization', `Bearer ${accessToken}`);
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('/admin/signing-keys', () => {
+    it('should reject non-admin users', async () => {
+      const res = await api()
+        .get('/admin/signing-keys')
+        .set('Authorization', `Bearer ${accessToken}`);
+
+      expect(res.status).toBe(403);
+    });
+
+    it('should reject key rotation by non-admin users', async () => {
+      const res = await api()
+        .post('/admin/keys/rotate')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ force: true });
+
+      expect(res.status).toBe(403);
+    });
+  });
+
+  describe('Device Authorization Grant', () => {
+    const DEVICE_GRANT = 'urn:ietf:params:oauth:grant-type:device_code';
+    let client;
+
+    beforeAll(async () => {
+      const created = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ name: 'Device Client', redirect_uris: ['https://example.com/callback'] });
+      client = created.body;
+    });
+
+    async function startDeviceFlow() {
+      const res = await api()
+        .post('/oauth/device_authorization')
+        .type('form')
+        .send({ client_id: client.client_id, scope: 'openid' });
+      expect(res.status).toBe(200);
+      return res.body;
+    }
+
+    function poll(deviceCode) {
+      return api()
+        .post('/oauth/token')
+        .type('form')
+        .send({ grant_type: DEVICE_GRANT, device_code: deviceCode, client_id: client.client_id });
+    }
+
+    it('should issue a user code and verification URI', async () => {
+      const body = await startDeviceFlow();
+
+      expect(body.user_code).toMatch(/^[BCDFGHJKLMNPQRSTVWXZ]{4}-[BCDFGHJKLMNPQRSTVWXZ]{4}$/);
+      expect(body.verification_uri).toMatch(/\/oauth\/device$/);
+      expect(body.verification_uri_complete).toContain(encodeURIComponent(body.user_code));
+      expect(body.interval).toBe(5);
+    });
+
+    it('should report authorization_pending, then slow_down when polling too fast', async () => {
+      const { device_code } = await startDeviceFlow();
+
+      const first = await poll(device_code);
+      expect(first.status).toBe(400);
+      expect(first.body.error).toBe('authorization_pending');
+
+      const second = await poll(device_code);
+      expect(second.body.error).toBe('slow_down');
+    });
+
+    it('should issue tokens once the user approves', async () => {
+      const { device_code, user_code } = await startDeviceFlow();
+
+      const info = await api().get('/oauth/device').query({ user_code: user_code.toLowerCase() });
+      expect(info.status).toBe(200);
+      expect(info.body.client_id).toBe(client.client_id);
+
+      const decision = await api()
+        .post('/oauth/device/verify')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ user_code, approved: true });
+      expect(decision.status).toBe(200);
+
+      const res = await poll(device_code);
+      expect(res.status).toBe(200);
+      expect(res.body.access_token).toBeDefined();
+      expect(res.body.id_token).toBeDefined();
+
+      const reused = await poll(device_code);
+      expect(reused.body.error).toBe('invalid_grant');
+    });
+
+    it('should report access_denied when the user denies', async () => {
+      const { device_code, user_code } = await startDeviceFlow();
+
+      await api()
+        .post('/oauth/device/verify')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ user_code, approved: false });
+
+      const res = await poll(device_code);
+      expect(res.body.error).toBe('access_denied');
+    });
+
+    it('should require a signed-in user to approve', async () => {
+      const { user_code } = await startDeviceFlow();
+
+      const res = await api()
+        .post('/oauth/device/verify')
+        .send({ user_code, approved: true });
+
+      expect(res.status).toBe(401);
+    });
+  });
+
+  describe('POST /oauth/introspect', () => {
+    let client;
+
+    beforeAll(async () => {
+      const created = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ name: 'Resource Server', redirect_uris: ['https://example.com/callback'] });
+      client = created.body;
+    });
+
+    it('should reject a wrong client secret', async () => {
+      const res = await api()
+        .post('/oauth/introspect')
+        .type('form')
+        .send({ token: 'whatever', client_id: client.client_id, client_secret: 'wrong' });
+
+      expect(res.status).toBe(401);
+      expect(res.body.error).toBe('invalid_client');
+    });
+
+    it('should report an unknown token as inactive', async () => {
+      const res = await api()
+        .post('/oauth/introspect')
+        .type('form')
+        .send({ token: 'not-a-real-token', client_id: client.client_id, client_secret: client.client_secret });
+
+      expect(res.status).toBe(200);
+      expect(res.body).toEqual({ active: false });
+    });
+
+    it('should describe an active client credentials token', async () => {
+      const issued = await api()
+        .post('/oauth/token')
+        .type('form')
+        .send({ grant_type: 'client_credentials', client_id: client.client_id, client_secret: client.client_secret });
+      expect(issued.status).toBe(200);
+
+      const res = await api()
+        .post('/oauth/introspect')
+        .type('form')
+        .send({
+          token: issued.body.access_token,
+          token_type_hint: 'access_token',
+          client_id: client.client_id,
+          client_secret: client.client_secret,
+        });
+
+      expect(res.status).toBe(200);
+      expect(res.body.active).toBe(true);
+      expect(res.body.client_id).toBe(client.client_id);
+      expect(res.body.sub).toBe(client.client_id);
+      expect(res.body.exp).toBeGreaterThan(Math.floor(Date.now() / 1000));
+    });
+  });
+
+  describe('GET /oauth/authorize', () => {
+    it('should not redirect errors for an unknown client', async () => {
+      const res = await api()
+        .get('/oauth/authorize')
+        .query({
+          response_type: 'token',
+          client_id: 'unknown-client',
+          redirect_uri: 'https://evil.example.com/',
+          state: 'xyz',
+        });
+
+      expect(res.status).toBe(401);
+      expect(res.body.error).toBe('invalid_client');
+      expect(res.body.redirect_url).toBeUndefined();
+    });
+  });
+
+  describe('Request objects (JAR)', () => {
+    let client;
+    let issuer;
+    const { privateKey, publicKey } = crypto.generateKeyPairSync('rsa', { modulusLength: 2048 });
+    const jwk = { ...publicKey.export({ format: 'jwk' }), kid: 'jar-key-1', alg: 'RS256', use: 'sig' };
+
+    function signRequestObject(claims) {
+      const encode = (value) => Buffer.from(JSON.stringify(value)).toString('base64url');
+      const input = `${encode({ alg: 'RS256', typ: 'oauth-authz-req+jwt', kid: jwk.kid })}.${encode(claims)}`;
+      const signature = crypto.sign('RSA-SHA256', Buffer.from(input), privateKey).toString('base64url');
+      return `${input}.${signature}`;
+    }
+
+    function requestClaims(overrides = {}) {
+      return {
+        iss: client.client_id,
+        aud: issuer,
+        exp: Math.floor(Date.now() / 1000) + 300,
+        client_id: client.client_id,
+        response_type: 'code',
+        redirect_uri: 'https://example.com/callback',
+        scope: 'openid',
+        state: 'jar-state-0123456789abcdef',
+        code_challenge: 'E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM',
+        ...overrides,
+      };
+    }
+
+    beforeAll(async () => {
+      const created = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ name: 'JAR Client', redirect_uris: ['https://example.com/callback'] });
+      const list = await api()
+        .get('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`);
+      const listed = list.body.clients.find((c) => c.client_id === created.body.client_id);
+      client = { ...created.body, id: listed.id };
+
+      const discovery = await api().get('/.well-known/openid-configuration');
+      issuer = discovery.body.issuer;
+    });
+
+    it('should reject private keys', async () => {
+      const res = await api()
+        .put(`/oauth/clients/${client.id}/jwks`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ keys: [{ ...privateKey.export({ format: 'jwk' }), kid: 'private' }] });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
+
+    it('should reject a request object before keys are registered', async () => {
+      const res = await api()
+        .get('/oauth/authorize')
+        .query({ client_id: client.client_id, request: signRequestObject(requestClaims()) });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request_object');
+    });
+
+    it('should register the client keys', async () => {
+      const res = await api()
+        .put(`/oauth/clients/${client.id}/jwks`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ keys: [jwk] });
+
+      expect(res.status).toBe(200);
+      expect(res.body.jwks.keys[0].kid).toBe('jar-key-1');
+    });
+
+    it('should take parameters from a signed request object', async () => {
+      const res = await api()
+        .get('/oauth/authorize')
+        .query({
+          client_id: client.client_id,
+          state: 'query-state-is-overridden',
+          request: signRequestObject(requestClaims()),
+        });
+
+      expect(res.status).toBe(200);
+      expect(res.body.status).toBe('consent_required');
+      expect(res.body.state).toBe('jar-state-0123456789abcdef');
+    });
+
+    it('should reject a request object for another audience', async () => {
+      const res = await api()
+        .get('/oauth/authorize')
+        .query({
+          client_id: client.client_id,
+          request: signRequestObject(requestClaims({ aud: 'https://evil.example.com' })),
+        });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request_object');
+    });
+
+    it('should accept a request object pushed to /oauth/par', async () => {
+      const pushed = await api()
+        .post('/oauth/par')
+        .type('form')
+        .send({
+          client_id: client.client_id,
+          client_secret: client.client_secret,
+          request: signRequestObject(requestClaims()),
+        });
+
+      expect(pushed.status).toBe(201);
+      expect(pushed.body.request_uri).toMatch(/^urn:ietf:params:oauth:request_uri:/);
+    });
+
+    it('should register URLs hosting request objects', async () => {
+      const res = await api()
+        .put(`/oauth/clients/${client.id}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ request_uris: ['https://example.com/requests/signin.jwt'] });
+
+      expect(res.status).toBe(200);
+      expect(res.body.request_uris).toEqual(['https://example.com/requests/signin.jwt']);
+    });
+
+    it('should reject a request_uri with a fragment', async () => {
+      const res = await api()
+        .put(`/oauth/clients/${client.id}`)
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ request_uris: ['https://example.com/requests/signin.jwt#v2'] });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
+
+    it('should not fetch a request_uri the client did not register', async () => {
+      const res = await api()
+        .get('/oauth/authorize')
+        .query({ client_id: client.client_id, request_uri: 'https://evil.example.com/request.jwt' });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request_uri');
+    });
+
+    it('should remove the client keys', async () => {
+      const res = await api()
+        .delete(`/oauth/clients/${client.id}/jwks`)
+        .set('Authorization', `Bearer ${accessToken}`);
+
+      expect(res.status).toBe(204);
+    });
+  });
+
+  describe('POST /oauth/par', () => {
+    let client;
+
+    beforeAll(async () => {
+      const created = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ name: 'PAR Client', redirect_uris: ['https://example.com/callback'] });
+      client = created.body;
+    });
+
+    function push(overrides = {}) {
+      return api()
+        .post('/oauth/par')
+        .type('form')
+        .send({
+          response_type: 'code',
+          client_id: client.client_id,
+          client_secret: client.client_secret,
+          redirect_uri: 'https://example.com/callback',
+          scope: 'openid',
+          state: 'par-state-0123456789abcdef',
+          code_challenge: 'E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM',
+          ...overrides,
+        });
+    }
+
+    it('should require client authentication', async () => {
+      const res = await push({ client_secret: undefined });
+
+      expect(res.status).toBe(401);
+      expect(res.body.error).toBe('invalid_client');
+    });
+
+    it('should validate the pushed parameters', async () => {
+      const res = await push({ redirect_uri: 'https://evil.example.com/callback' });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
+
+    it('should resolve the request_uri once at the authorization endpoint', async () => {
+      const pushed = await push();
+      expect(pushed.status).toBe(201);
+      expect(pushed.body.request_uri).toMatch(/^urn:ietf:params:oauth:request_uri:/);
+      expect(pushed.body.expires_in).toBeGreaterThan(0);
+
+      const query = { client_id: client.client_id, request_uri: pushed.body.request_uri };
+      const res = await api().get('/oauth/authorize').query(query);
+
+      expect(res.status).toBe(200);
+      expect(res.body.status).toBe('consent_required');
+      expect(res.body.redirect_uri).toBe('https://example.com/callback');
+      expect(res.body.state).toBe('par-state-0123456789abcdef');
+
+      const reused = await api().get('/oauth/authorize').query(query);
+      expect(reused.status).toBe(400);
+      expect(reused.body.error).toBe('invalid_request');
+    });
+
+    it('should not resolve a request_uri for another client', async () => {
+      const pushed = await push();
+      const other = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ name: 'Other Client', redirect_uris: ['https://example.com/callback'] });
+
+      const res = await api()
+        .get('/oauth/authorize')
+        .query({ client_id: other.body.client_id, request_uri: pushed.body.request_uri });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
+  });
+
+  describe('POST /oauth/token', () => {
+    let client;
+
+    beforeAll(async () => {
+      const created = await api()
+        .post('/oauth/clients')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ name: 'Token Client', redirect_uris: ['https://example.com/callback'] });
+      client = created.body;
+    });
+
+    function basic(id, secret) {
+      const encoded = Buffer.from(`${encodeURIComponent(id)}:${encodeURIComponent(secret)}`).toString('base64');
+      return `Basic ${encoded}`;
+    }
+
+    it('should accept a JSON body', async () => {
+      const res = await api()
+        .post('/oauth/token')
+        .send({ grant_type: 'client_credentials', client_id: client.client_id, client_secret: client.client_secret });
+
+      expect(res.status).toBe(200);
+      expect(res.body.access_token).toBeDefined();
+    });
+
+    it('should accept HTTP Basic client authentication', async () => {
+      const res = await api()
+        .post('/oauth/token')
+        .set('Authorization', basic(client.client_id, client.client_secret))
+        .type('form')
+        .send({ grant_type: 'client_credentials' });
+
+      expect(res.status).toBe(200);
+      expect(res.body.access_token).toBeDefined();
+    });
+
+    it('should reject a wrong Basic secret', async () => {
+      const res = await api()
+        .post('/oauth/token')
+        .set('Authorization', basic(client.client_id, 'wrong'))
+        .type('form')
+        .send({ grant_type: 'client_credentials' });
+
+      expect(res.status).toBe(401);
+      expect(res.body.error).toBe('invalid_client');
+    });
+
+    it('should reject more than one client authentication method', async () => {
+      const res = await api()
+        .post('/oauth/token')
+        .set('Authorization', basic(client.client_id, client.client_secret))
+        .type('form')
+        .send({ grant_type: 'client_credentials', client_secret: client.client_secret });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
+
+    it('should revoke with a JSON body and Basic authentication', async () => {
+      const issued = await api()
+        .post('/oauth/token')
+        .send({ grant_type: 'client_credentials', client_id: client.client_id, client_secret: client.client_secret });
+
+      const res = await api()
+        .post('/oauth/revoke')
+        .set('Authorization', basic(client.client_id, client.client_secret))
+        .send({ token: issued.body.access_token });
+
+      expect(res.status).toBe(200);
+    });
+
+    it('should reject an unknown authorization code', async () => {
+      const res = await api()
+        .post('/oauth/token')
+        .type('form')
+        .send({
+          grant_type: 'authorization_code',
+          code: 'not-a-real-code',
+          redirect_uri: 'https://example.com/callback',
+          client_id: 'unknown-client',
+          code_verifier: 'dBjftJeZ4CVP-mJ0kaS6oFE1_xAAVQgh3fi64tP1Ki3abc',
+        });
+
+      expect(res.status).toBeGreaterThanOrEqual(400);
+      expect(res.status).toBeLessThan(500);
+    });
+  });
+});
@@ -0,0 +1,81 @@
+const crypto = require('crypto');
+const { api, generateEmail, generatePassword, registerUser, login } = require('./helpers');
+
+describe('Push MFA API', () => {
+  let accessToken;
+  let refreshToken;
+
+  beforeAll(async () => {
+    const email = generateEmail();
+    const password = generatePassword();
+    await registerUser(email, password);
+    const res = await login(email, password);
+    accessToken = res.body.access_token;
+    refreshToken = res.body.refresh_token;
+  });
+
+  function sign(secret, challengeId, approve) {
+    return crypto
+      .createHmac('sha256', secret)
+      .update(`${challengeId}:${approve ? 'approve' : 'deny'}`)
+      .digest('hex');
+  }
+
+  describe('POST /auth/devices', () => {
+    it('should return the device signing secret only at registration', async () => {
+      const res = await api()
+        .post('/auth/devices')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ platform: 'ios', push_token: 'apns-test-token', refresh_token: refreshToken });
+
+      expect(res.status).toBe(201);
+      expect(res.body).toHaveProperty('signing_secret');
+
+      const list = await api()
+        .get('/auth/devices')
+        .set('Authorization', `Bearer ${accessToken}`);
+
+      expect(list.status).toBe(200);
+      list.body.devices.forEach((device) => {
+        expect(device).not.toHaveProperty('signing_secret');
+      });
+    });
+  });
+
+  describe('POST /auth/mfa/push', () => {
+    it('should reject an invalid mfa_token', async () => {
+      const res = await api()
+        .post('/auth/mfa/push')
+        .send({ mfa_token: 'invalid-token' });
+
+      expect(res.status).toBe(401);
+      expect(res.body.error).toBe('invalid_token');
+    });
+  });
+
+  describe('POST /auth/mfa/push/respond', () => {
+    it('should reject an unknown challenge', async () => {
+      const challengeId = crypto.randomUUID();
+      const res = await api()
+        .post('/auth/mfa/push/respond')
+        .send({
+          challenge_id: challengeId,
+          device_id: crypto.randomUUID(),
+          approve: true,
+          signature: sign('secret', challengeId, true),
+        });
+
+      expect(res.status).toBe(401);
+    });
+  });
+
+  describe('POST /auth/mfa/verify', () => {
+    it('should reject a push challenge with an invalid mfa_token', async () => {
+      const res = await api()
+        .post('/auth/mfa/verify')
+        .send({ mfa_token: 'invalid-token', push_challenge_id: crypto.randomUUID() });
+
+      expect(res.status).toBe(401);
+    });
+  });
+});
@@ -0,0 +1,120 @@
+const { api, createTestUser } = require('./helpers');
+
+describe('QR Login API', () => {
+  let userToken;
+
+  beforeAll(async () => {
+    const user = await createTestUser();
+    userToken = user.token;
+  });
+
+  async function startChannel() {
+    const res = await api()
+      .post('/auth/qr/start')
+      .send({});
+    return res.body;
+  }
+
+  describe('POST /auth/qr/start', () => {
+    it('should open a channel (public)', async () => {
+      const res = await api()
+        .post('/auth/qr/start')
+        .send({});
+
+      expect(res.status).toBe(201);
+      expect(res.body).toHaveProperty('channel_code');
+      expect(res.body).toHaveProperty('poll_secret');
+      expect(res.body).toHaveProperty('expires_in');
+      expect(res.body).toHaveProperty('interval');
+    });
+  });
+
+  describe('POST /auth/qr/token', () => {
+    it('should report pending before approval', async () => {
+      const channel = await startChannel();
+
+      const res = await api()
+        .post('/auth/qr/token')
+        .send({ channel_code: channel.channel_code, poll_secret: channel.poll_secret });
+
+      expect(res.status).toBe(200);
+      expect(res.body.status).toBe('pending');
+    });
+
+    it('should reject a wrong poll secret', async () => {
+      const channel = await startChannel();
+
+      const res = await api()
+        .post('/auth/qr/token')
+        .send({ channel_code: channel.channel_code, poll_secret: 'wrong-secret' });
+
+      expect(res.status).toBe(401);
+      expect(res.body.error).toBe('invalid_token');
+    });
+
+    it('should reject an unknown channel', async () => {
+      const res = await api()
+        .post('/auth/qr/token')
+        .send({ channel_code: 'unknown', poll_secret: 'unknown' });
+
+      expect(res.status).toBe(401);
+    });
+  });
+
+  describe('POST /auth/qr/approve', () => {
+    it('should require authentication', async () => {
+      const channel = await startChannel();
+
+      const res = await api()
+        .post('/auth/qr/approve')
+        .send({ channel_code: channel.channel_code });
+
+      expect(res.status).toBe(401);
+    });
+
+    it('should issue tokens exactly once after approval', async () => {
+      const channel = await startChannel();
+
+      const approveRes = await api()
+        .post('/auth/qr/approve')
+        .set('Authorization', `Bearer ${userToken}`)
+        .send({ channel_code: channel.channel_code });
+
+      expect(approveRes.status).toBe(200);
+      expect(approveRes.body.status).toBe('approved');
+
+      const tokenRes = await api()
+        .post('/auth/qr/token')
+        .send({ channel_code: channel.channel_code, poll_secret: channel.poll_secret });
+
+      expect(tokenRes.status).toBe(200);
+      expect(tokenRes.body).toHaveProperty('access_token');
+      expect(tokenRes.body).toHaveProperty('refresh_token');
+
+      const replayRes = await api()
+        .post('/auth/qr/token')
+        .send({ channel_code: channel.channel_code, poll_secret: channel.poll_secret });
+
+      expect(replayRes.status).toBe(401);
+    });
+
+    it('should deny a channel', async () => {
+      const channel = await startChannel();
+
+      const denyRes = await api()
+        .post('/auth/qr/approve')
+        .set('Authorization', `Bearer ${userToken}`)
+        .send({ channel_code: channel.channel_code, approve: false });
+
+      expect(denyRes.status).toBe(200);
+      expect(denyRes.body.status).toBe('denied');
+
+      const tokenRes = await api()
+        .post('/auth/qr/token')
+        .send({ channel_code: channel.channel_code, poll_secret: channel.poll_secret });
+
+      expect(tokenRes.status).toBe(401);
+      expect(tokenRes.body.error).toBe('invalid_credentials');
+    });
+  });
+});
@@ -0,0 +1,72 @@
+/**
+ * Fixtures for the OpenID Foundation conformance suite
+ * Run: node setup-conformance.js > ../conformance/oidcc-basic.json
+ *
+ * Registers a test user and the two confidential clients the suite needs,
+ * then prints the test plan configuration. The server should run with
+ * OAUTH_STRICT=true.
+ */
+require('dotenv').config();
+const request = require('supertest');
+
+const API_URL = process.env.API_URL || 'http://localhost:3000';
+const ISSUER_URL = process.env.CONFORMANCE_ISSUER_URL || API_URL;
+const SUITE_URL = process.env.CONFORMANCE_SUITE_URL || 'https://localhost.emobix.co.uk:8443';
+const ALIAS = process.env.CONFORMANCE_ALIAS || 'auth-server';
+const USER_EMAIL = process.env.CONFORMANCE_USER_EMAIL || 'conformance@example.com';
+const USER_PASSWORD = process.env.CONFORMANCE_USER_PASSWORD || 'Conformance123!@#';
+
+async function userToken() {
+  const registerRes = await request(API_URL)
+    .post('/auth/register')
+    .send({ email: USER_EMAIL, password: USER_PASSWORD });
+  if (registerRes.status !== 201 && registerRes.status !== 409) {
+    throw new Error(`Failed to register conformance user: ${JSON.stringify(registerRes.body)}`);
+  }
+
+  const loginRes = await request(API_URL)
+    .post('/auth/login')
+    .send({ email: USER_EMAIL, password: USER_PASSWORD });
+  if (loginRes.status !== 200 || !loginRes.body.access_token) {
+    throw new Error(`Failed to log in conformance user: ${JSON.stringify(loginRes.body)}`);
+  }
+  return loginRes.body.access_token;
+}
+
+async function createClient(token, name) {
+  const res = await request(API_URL)
+    .post('/oauth/clients')
+    .set('Authorization', `Bearer ${token}`)
+    .send({ name, redirect_uris: [`${SUITE_URL}/test/a/${ALIAS}/callback`] });
+  if (res.status !== 201) {
+    throw new Error(`Failed to create client ${name}: ${JSON.stringify(res.body)}`);
+  }
+  return { client_id: res.body.client_id, client_secret: res.body.client_secret };
+}
+
+async function setupConformance() {
+  const discovery = await request(API_URL).get('/.well-known/openid-configuration');
+  if (discovery.status !== 200) {
+    console.error('Discovery document is not available. Please start the server first.');
+    process.exit(1);
+  }
+
+  const token = await userToken();
+  const config = {
+    alias: ALIAS,
+    description: 'auth-server (OAUTH_STRICT=true)',
+    server: {
+      discoveryUrl: `${ISSUER_URL}/.well-known/openid-configuration`,
+    },
+    client: await createClient(token, 'OpenID Conformance Client'),
+    client2: await createClient(token, 'OpenID Conformance Client 2'),
+  };
+
+  console.error(`Log in as ${USER_EMAIL} when the suite opens the authorization endpoint.`);
+  console.log(JSON.stringify(config, null, 2));
+}
+
+setupConformance().catch((error) => {
+  console.error(error.message);
+  process.exit(1);
+});
@@ -0,0 +1,133 @@
+const crypto = require('crypto');
+const { api, generateEmail, generatePassword, registerUser, login } = require('./helpers');
+
+describe('SMS MFA API', () => {
+  let accessToken;
+
+  beforeAll(async () => {
+    const email = generateEmail();
+    const password = generatePassword();
+    await registerUser(email, password);
+    const res = await login(email, password);
+    accessToken = res.body.access_token;
+  });
+
+  function phoneNumber() {
+    return `+8490${crypto.randomInt(1000000, 9999999)}`;
+  }
+
+  describe('POST /auth/mfa/sms/setup', () => {
+    it('should require authentication', async () => {
+      const res = await api()
+        .post('/auth/mfa/sms/setup')
+        .send({ phone_number: phoneNumber() });
+
+      expect(res.status).toBe(401);
+    });
+
+    it('should reject a number without a country code', async () => {
+      const res = await api()
+        .post('/auth/mfa/sms/setup')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ phone_number: '0912345678' });
+
+      expect(res.status).toBe(400);
+      expect(res.body.error).toBe('invalid_request');
+    });
+
+    it('should text a code and only reveal the last digits of the number', async () => {
+      const phone = phoneNumber();
+      const res = await api()
+        .post('/auth/mfa/sms/setup')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ phone_number: phone });
+
+      expect(res.status).toBe(200);
+      expect(res.body).toHaveProperty('method_id');
+      expect(res.body.phone_hint).toBe(`+${'*'.repeat(phone.length - 4)}${phone.slice(-3)}`);
+      expect(res.body.expires_in).toBe(300);
+
+      const methods = await api()
+        .get('/auth/mfa/methods')
+        .set('Authorization', `Bearer ${accessToken}`);
+
+      const method = methods.body.methods.find((m) => m.id === res.body.method_id);
+      expect(method.method_type).toBe('sms');
+      expect(method.is_verified).toBe(false);
+      expect(method.phone_hint).toBe(res.body.phone_hint);
+      expect(method).not.toHaveProperty('phone_number');
+    });
+
+    it('should limit how often codes are sent', async () => {
+      const email = generateEmail();
+      const password = generatePassword();
+      await registerUser(email, password);
+      const token = (await login(email, password)).body.access_token;
+
+      const statuses = [];
+      for (let i = 0; i < 4; i++) {
+        const res = await api()
+          .post('/auth/mfa/sms/setup')
+          .set('Authorization', `Bearer ${token}`)
+          .send({ phone_number: phoneNumber() });
+        statuses.push(res.status);
+        if (res.status === 429) {
+          expect(res.body.error).toBe('rate_limit_exceeded');
+          expect(res.body.policy).toBe('sms_send');
+          expect(res.headers['retry-after']).toBeDefined();
+        }
+      }
+
+      expect(statuses.slice(0, 3)).toEqual([200, 200, 200]);
+      expect(statuses[3]).toBe(429);
+    });
+  });
+
+  describe('POST /auth/mfa/sms/verify', () => {
+    it('should reject a wrong code', async () => {
+      const setup = await api()
+        .post('/auth/mfa/sms/setup')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ phone_number: phoneNumber() });
+
+      const res = await api()
+        .post('/auth/mfa/sms/verify')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ method_id: setup.body.method_id, code: '000000x' });
+
+      expect(res.status).toBe(401);
+      expect(res.body.error).toBe('invalid_mfa_code');
+    });
+
+    it('should reject an unknown method', async () => {
+      const res = await api()
+        .post('/auth/mfa/sms/verify')
+        .set('Authorization', `Bearer ${accessToken}`)
+        .send({ method_id: crypto.randomUUID(), code: '123456' });
+
+      expect(res.status).toBe(401);
+      expect(res.body.error).toBe('invalid_mfa_code');
+    });
+  });
+
+  describe('POST /auth/mfa/sms/send', () => {
+    it('should reject an invalid mfa_token', async () => {
+      const res = await api()
+        .post('/auth/mfa/sms/send')
+        .send({ mfa_token: 'invalid-token' });
+
+      expect(res.status).toBe(401);
+      expect(res.body.error).toBe('invalid_token');
+    });
+  });
+
+  describe('POST /auth/mfa/verify', () => {
+    it('should reject an SMS code with an invalid mfa_token', async () => {
+      const res = await api()
+        .post('/auth/mfa/verify')
+        .send({ mfa_token: 'invalid-token', code: '123456', is_sms_code: true });
+
+      expect(res.status).toBe(401);
+    });
+  });
+});
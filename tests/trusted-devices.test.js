@@ -0,0 +1,60 @@
+const { api, createTestUser } = require('./helpers');
+
+describe('Trusted Devices API', () => {
+  let user;
+
+  beforeAll(async () => {
+    user = await createTestUser();
+  });
+
+  describe('GET /auth/trusted-devices', () => {
+    it('should require authentication', async () => {
+      const res = await api().get('/auth/trusted-devices');
+
+      expect(res.status).toBe(401);
+    });
+
+    it('should start empty', async () => {
+      const res = await api()
+        .get('/auth/trusted-devices')
+        .set('Authorization', `Bearer ${user.token}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.trusted_devices).toEqual([]);
+      expect(res.body.total).toBe(0);
+    });
+  });
+
+  describe('DELETE /auth/trusted-devices/:device_id', () => {
+    it('should return 404 for an unknown device', async () => {
+      const res = await api()
+        .delete('/auth/trusted-devices/00000000-0000-0000-0000-000000000000')
+        .set('Authorization', `Bearer ${user.token}`);
+
+      expect(res.status).toBe(404);
+    });
+  });
+
+  describe('DELETE /auth/trusted-devices', () => {
+    it('should revoke nothing for a user without trusted devices', async () => {
+      const res = await api()
+        .delete('/auth/trusted-devices')
+        .set('Authorization', `Bearer ${user.token}`);
+
+      expect(res.status).toBe(200);
+      expect(res.body.revoked_count).toBe(0);
+    });
+  });
+
+  describe('POST /auth/login', () => {
+    it('should ignore an unknown trusted device cookie', async () => {
+      const res = await api()
+        .post('/auth/login')
+        .set('Cookie', 'trusted_device=00000000-0000-0000-0000-000000000000.bogus')
+        .send({ email: user.email, password: user.password });
+
+      expect(res.status).toBe(200);
+      expect(res.body.access_token).toBeDefined();
+    });
+  });
+});
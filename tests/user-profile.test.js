@@ -21,6 +21,7 @@ describe('User Profile API', () => {
       expect(res.body.email).toBe(userEmail);
       expect(res.body).toHaveProperty('is_active');
       expect(res.body).toHaveProperty('created_at');
+      expect(res.body.email_undeliverable).toBe(false);
     });
 
     it('should reject without token', async () => {
@@ -52,6 +53,25 @@ describe('User Profile API', () => {
       expect(res.body.name).toBe('Test User');
       expect(res.body.phone).toBe('+1234567890');
     });
+
+    it('should store the locale as a normalized language tag', async () => {
+      const res = await api()
+        .put('/users/me')
+        .set('Authorization', `Bearer ${token}`)
+        .send({ locale: 'vi_vn' });
+
+      expect(res.status).toBe(200);
+      expect(res.body.locale).toBe('vi-VN');
+    });
+
+    it('should reject an invalid locale', async () => {
+      const res = await api()
+        .put('/users/me')
+        .set('Authorization', `Bearer ${token}`)
+        .send({ locale: 'not a locale' });
+
+      expect(res.status).toBe(400);
+    });
   });
 
   describe('POST /users/me/change-password', () => {
@@ -34,6 +34,7 @@ describe('Webhooks API', () => {
       expect(res.body.url).toBe('http://localhost:8080/webhook');
       expect(res.body.events).toContain('user.login');
       expect(res.body.is_active).toBe(true);
+      expect(res.body.payload_version).toBe(2);
       
       webhookId = res.body.id;
     });
@@ -90,6 +91,69 @@ describe('Webhooks API', () => {
     });
   });
 
+  describe('Payload versions', () => {
+    it('should pin a webhook to an older payload version', async () => {
+      const res = await api()
+        .put(`/apps/${appId}/webhooks/${webhookId}`)
+        .set('Authorization', `Bearer ${userToken}`)
+        .send({ payload_version: 1 });
+
+      expect(res.status).toBe(200);
+      expect(res.body.payload_version).toBe(1);
+    });
+
+    it('should reject an unknown payload version', async () => {
+      const res = await api()
+        .put(`/apps/${appId}/webhooks/${webhookId}`)
+        .set('Authorization', `Bearer ${userToken}`)
+        .send({ payload_version: 3 });
+
+      expect(res.status).toBe(400);
+    });
+  });
+
+  describe('POST /apps/:app_id/webhooks/:webhook_id/replay', () => {
+    it('should replay events in a window', async () => {
+      const from = new Date(Date.now() - 3600 * 1000).toISOString();
+      const res = await api()
+        .post(`/apps/${appId}/webhooks/${webhookId}/replay`)
+        .query({ from })
+        .set('Authorization', `Bearer ${userToken}`);
+
+      expect(res.status).toBe(200);
+      expect(typeof res.body.queued).toBe('number');
+      expect(res.body.truncated).toBe(false);
+      expect(res.body.next_from).toBeNull();
+    });
+
+    it('should require from', async () => {
+      const res = await api()
+        .post(`/apps/${appId}/webhooks/${webhookId}/replay`)
+        .set('Authorization', `Bearer ${userToken}`);
+
+      expect(res.status).toBe(400);
+    });
+
+    it('should reject a window that ends before it starts', async () => {
+      const res = await api()
+        .post(`/apps/${appId}/webhooks/${webhookId}/replay`)
+        .query({ from: '2025-01-02T00:00:00Z', to: '2025-01-01T00:00:00Z' })
+        .set('Authorization', `Bearer ${userToken}`);
+
+      expect(res.status).toBe(400);
+    });
+
+    it('should only allow the app owner', async () => {
+      const other = await createTestUser();
+      const res = await api()
+        .post(`/apps/${appId}/webhooks/${webhookId}/replay`)
+        .query({ from: new Date(Date.now() - 3600 * 1000).toISOString() })
+        .set('Authorization', `Bearer ${other.token}`);
+
+      expect(res.status).toBe(403);
+    });
+  });
+
   describe('DELETE /apps/:app_id/webhooks/:webhook_id', () => {
     it('should delete a webhook', async () => {
       const res = await api()